@@ -0,0 +1,19 @@
+use clap::Args;
+use clap_complete::engine::ArgValueCompleter;
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Examples:
+  mdv board                  # All tasks as a kanban board
+  mdv board --project MCP    # Only tasks in project MCP
+  mdv board --focus          # Only tasks in the focused project
+")]
+pub struct BoardArgs {
+    /// Only show tasks in this project (ID or folder name)
+    #[arg(long, short, add = ArgValueCompleter::new(crate::completions::complete_projects))]
+    pub project: Option<String>,
+
+    /// Only show tasks in the focused project (see 'mdv focus')
+    #[arg(long, conflicts_with = "project")]
+    pub focus: bool,
+}
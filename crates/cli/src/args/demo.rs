@@ -0,0 +1,47 @@
+use clap::{Args, ValueEnum};
+use std::path::PathBuf;
+
+/// Size of the generated demo vault.
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum DemoSizeArg {
+    /// 3 projects, 18 tasks, two weeks of dailies
+    #[default]
+    Small,
+    /// 25 projects, 1000 tasks, a year of dailies
+    Large,
+}
+
+impl From<DemoSizeArg> for mdvault_core::demo::DemoSize {
+    fn from(arg: DemoSizeArg) -> Self {
+        match arg {
+            DemoSizeArg::Small => Self::Small,
+            DemoSizeArg::Large => Self::Large,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Examples:
+  mdv demo                          # Small demo vault in a temp dir, opens a shell there
+  mdv demo --size large             # A year of history, for performance testing
+  mdv demo --seed 7 --no-shell      # Reproducible vault; print its location and exit
+  mdv demo --path /tmp/bug-1234     # Generate into a specific (empty) directory
+")]
+pub struct DemoArgs {
+    /// How much content to generate
+    #[arg(long, value_enum, default_value_t)]
+    pub size: DemoSizeArg,
+
+    /// Seed for the generator; the same seed gives the same vault
+    #[arg(long, default_value_t = 1)]
+    pub seed: u64,
+
+    /// Directory to create the demo in (default: a new temp directory)
+    #[arg(long)]
+    pub path: Option<PathBuf>,
+
+    /// Don't start a shell in the demo vault; just print how to use it
+    #[arg(long)]
+    pub no_shell: bool,
+}
@@ -0,0 +1,19 @@
+use clap::Args;
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Examples:
+  mdv doctor                  # Validate configuration
+  mdv doctor --index          # Also compare the index with the vault
+  mdv doctor --index --fix    # Repair what the index check finds
+")]
+pub struct DoctorArgs {
+    /// Check index integrity: unindexed or deleted notes, links that now
+    /// resolve, stale hashes, and full-text drift
+    #[arg(long)]
+    pub index: bool,
+
+    /// Repair index problems (requires --index)
+    #[arg(long, requires = "index")]
+    pub fix: bool,
+}
@@ -0,0 +1,24 @@
+use clap::Args;
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Policies:
+  expires: 2025-06-30          Note expires on that date
+  ttl: 90d                     Note expires 90 days after `created` (d, w, M, y)
+  on_expire: trash             Trash instead of archiving (default: archive)
+  retention = { ... }          Per-type rule in a type definition
+
+Examples:
+  mdv expire --dry-run        # Show what has expired
+  mdv expire                  # Archive or trash expired notes
+  mdv expire --undo           # Put back the notes moved by the last run
+")]
+pub struct ExpireArgs {
+    /// List expired notes without moving anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Restore the notes moved by the most recent run
+    #[arg(long, conflicts_with = "dry_run")]
+    pub undo: bool,
+}
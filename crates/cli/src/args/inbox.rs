@@ -0,0 +1,35 @@
+use clap::{Args, Subcommand};
+use std::path::PathBuf;
+
+/// Inbox subcommands.
+#[derive(Debug, Subcommand)]
+pub enum InboxCommands {
+    /// File text snippets from a drop folder as inbox notes
+    Ingest(InboxIngestArgs),
+}
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Examples:
+  mdv inbox ingest ~/Sync/phone-inbox
+  mdv inbox ingest ~/Sync/phone-inbox --capture inbox
+  mdv inbox ingest ./drop --keep
+
+Each .txt, .md, or .markdown file becomes an `Inbox/snippet-*.md` note titled
+by its first line, then is removed from the folder. A snippet with the same
+text as one filed before is reported as DUP and removed without a new note.
+Files that fail stay put. Hidden files and Syncthing partial downloads are
+ignored.
+")]
+pub struct InboxIngestArgs {
+    /// Folder to drain, e.g. one synced from your phone
+    pub dir: PathBuf,
+
+    /// Capture to run with a link to each new note (default: `[ingest] capture`)
+    #[arg(long)]
+    pub capture: Option<String>,
+
+    /// Leave snippet files in the folder after filing them
+    #[arg(long)]
+    pub keep: bool,
+}
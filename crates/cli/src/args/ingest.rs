@@ -0,0 +1,42 @@
+use clap::{Args, Subcommand};
+use std::path::PathBuf;
+
+/// Ingestion subcommands.
+#[derive(Debug, Subcommand)]
+pub enum IngestCommands {
+    /// Transcribe a voice memo into an inbox note
+    Audio(IngestAudioArgs),
+}
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Examples:
+  mdv ingest audio memo.m4a --transcriber 'whisper-cli -nt -f {file}'
+  mdv ingest audio memo.m4a --title \"Kitchen ideas\" --capture inbox
+  mdv ingest audio ~/Recordings/*.m4a --move
+
+The transcriber is any command that prints the transcript to stdout; `{file}`
+is replaced with the audio path (otherwise the path is appended). Set
+`[ingest] transcriber` and `capture` in the config to make them the default.
+")]
+pub struct IngestAudioArgs {
+    /// Audio files to ingest
+    #[arg(required = true)]
+    pub files: Vec<PathBuf>,
+
+    /// Transcription command (default: `[ingest] transcriber` from config)
+    #[arg(long)]
+    pub transcriber: Option<String>,
+
+    /// Note title (default: "Voice memo" and the time)
+    #[arg(long)]
+    pub title: Option<String>,
+
+    /// Capture to run with a link to the new note (default: `[ingest] capture`)
+    #[arg(long)]
+    pub capture: Option<String>,
+
+    /// Delete the original recording once it is archived in the vault
+    #[arg(long = "move")]
+    pub move_original: bool,
+}
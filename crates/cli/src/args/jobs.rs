@@ -0,0 +1,39 @@
+use clap::{Args, Subcommand};
+
+/// Scheduled job subcommands.
+#[derive(Debug, Subcommand)]
+pub enum JobsCommands {
+    /// List the jobs in .mdvault/jobs.lua with their last and next runs
+    List(JobsListArgs),
+
+    /// Run every job that is due (meant for cron)
+    #[command(after_help = "\
+Examples:
+  mdv jobs run-due --dry-run          # Show what would run
+  */15 * * * * mdv jobs run-due       # crontab entry: check every 15 minutes
+")]
+    RunDue(JobsRunDueArgs),
+
+    /// Run one job now, whether or not it is due
+    Run(JobsRunArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct JobsListArgs {
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct JobsRunDueArgs {
+    /// List the due jobs without running them
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct JobsRunArgs {
+    /// Job name, as shown by `mdv jobs list`
+    pub name: String,
+}
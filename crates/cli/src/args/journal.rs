@@ -0,0 +1,38 @@
+use clap::{Args, Subcommand};
+
+/// Journal subcommands.
+#[derive(Debug, Subcommand)]
+pub enum JournalCommands {
+    /// Open or create a daily note (default)
+    Day,
+    /// Open or create a weekly note
+    Week,
+}
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Examples:
+  mdv journal                           # Open (or create) today's daily note
+  mdv journal --date yesterday          # Yesterday's daily note
+  mdv journal --date \"today - 3d\"       # Date expression
+  mdv journal week                      # Open (or create) this week's note
+  mdv journal week --date \"today - 7d\"  # Last week's note
+  mdv journal week --date 2026-W04      # Specific ISO week
+  mdv journal --no-open                 # Create if missing, print the path
+")]
+pub struct JournalArgs {
+    /// Date (YYYY-MM-DD, YYYY-Wxx, "today", "yesterday", or date expression)
+    #[arg(long, short, global = true)]
+    pub date: Option<String>,
+
+    /// Don't open the note in $EDITOR, only print its path
+    #[arg(long, global = true)]
+    pub no_open: bool,
+
+    /// Non-interactive mode: fail if variables are missing instead of prompting
+    #[arg(long, global = true)]
+    pub batch: bool,
+
+    #[command(subcommand)]
+    pub command: Option<JournalCommands>,
+}
@@ -0,0 +1,19 @@
+use clap::Args;
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Examples:
+  mdv list-templates                 # List template names
+  mdv list-templates --check         # Lint every template
+  mdv list-templates --check --json  # Lint results as JSON, for CI
+")]
+pub struct ListTemplatesArgs {
+    /// Lint each template: frontmatter, filters, output path and undeclared
+    /// variables. Exits non-zero if any template has errors
+    #[arg(long)]
+    pub check: bool,
+
+    /// Print the lint results as JSON (with --check)
+    #[arg(long, requires = "check")]
+    pub json: bool,
+}
@@ -0,0 +1,14 @@
+use clap::Args;
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Examples:
+  mdv lua                     # Prototype hooks and queries interactively
+  mdv lua --no-history        # Don't read or save .mdvault/state/lua_history
+  echo 'mdv.date(\"today\")' | mdv lua   # Evaluate piped input
+")]
+pub struct LuaArgs {
+    /// Don't read or save the input history
+    #[arg(long)]
+    pub no_history: bool,
+}
@@ -0,0 +1,28 @@
+use clap::Args;
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Type definitions declare the current version and how to get there:
+  schema_version = 2
+  migrate = function(note, from_version) ... return note end
+
+Notes record their version in a `schema_version` field (none means 1).
+
+Examples:
+  mdv migrate --type task --dry-run   # Preview the frontmatter changes
+  mdv migrate --type task             # Rewrite notes behind the current version
+  mdv migrate --undo                  # Put back the notes the last run changed
+")]
+pub struct MigrateArgs {
+    /// Type whose notes to migrate
+    #[arg(long, required_unless_present = "undo")]
+    pub r#type: Option<String>,
+
+    /// Show the changes without writing anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Restore the notes changed by the most recent migration
+    #[arg(long, conflicts_with_all = ["type", "dry_run"])]
+    pub undo: bool,
+}
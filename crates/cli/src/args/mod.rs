@@ -1,48 +1,115 @@
 pub mod area;
+pub mod board;
 pub mod check;
 pub mod completions_args;
 pub mod context;
 pub mod dashboard;
+pub mod demo;
+pub mod doctor;
+pub mod expire;
 pub mod focus;
+pub mod inbox;
+pub mod ingest;
+pub mod jobs;
+pub mod journal;
+pub mod list_templates;
+pub mod lua;
+pub mod migrate;
 pub mod note;
+pub mod plugin;
+pub mod print;
 pub mod project;
+pub mod provenance;
+pub mod query;
 pub mod reindex;
 pub mod rename;
 pub mod report;
+pub mod review;
 pub mod search;
+pub mod serve;
+pub mod stats;
 pub mod task;
+pub mod types;
 pub mod validate;
 
 use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 pub use self::area::*;
+pub use self::board::*;
 pub use self::check::*;
 pub use self::completions_args::*;
 pub use self::context::*;
 pub use self::dashboard::*;
+pub use self::demo::*;
+pub use self::doctor::*;
+pub use self::expire::*;
 pub use self::focus::*;
+pub use self::inbox::*;
+pub use self::ingest::*;
+pub use self::jobs::*;
+pub use self::journal::*;
+pub use self::list_templates::*;
+pub use self::lua::*;
+pub use self::migrate::*;
 pub use self::note::*;
+pub use self::plugin::*;
+pub use self::print::*;
 pub use self::project::*;
+pub use self::provenance::*;
+pub use self::query::*;
 pub use self::reindex::*;
 pub use self::rename::*;
 pub use self::report::*;
+pub use self::review::*;
 pub use self::search::*;
+pub use self::serve::*;
+pub use self::stats::*;
 pub use self::task::*;
+pub use self::types::*;
 pub use self::validate::*;
 
 /// Output format for query commands.
-#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
 pub enum OutputFormat {
     /// Human-readable table format
     #[default]
     Table,
     /// JSON output
     Json,
+    /// JSON limited to stable fields, in a deterministic order
+    StableJson,
     /// Quiet mode - paths only
     Quiet,
 }
 
+/// Value of `--output`: a built-in format, or `lua:NAME` for a formatter
+/// from the formatters directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutputArg {
+    Format(OutputFormat),
+    Lua(String),
+}
+
+impl std::str::FromStr for OutputArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(name) = s.strip_prefix("lua:") {
+            if name.is_empty() {
+                return Err("lua: needs a formatter name, e.g. lua:task-brief".into());
+            }
+            return Ok(Self::Lua(name.to_string()));
+        }
+        OutputFormat::from_str(s, true).map(Self::Format).map_err(|_| {
+            format!(
+                "invalid output format '{s}' \
+                 (expected table, json, stable-json, quiet or lua:NAME)"
+            )
+        })
+    }
+}
+
 /// Task/project status filter.
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum StatusFilter {
@@ -123,12 +190,34 @@ impl From<NoteTypeArg> for mdvault_core::index::NoteType {
 #[derive(Debug, Parser)]
 #[command(name = "mdv", version, about = "Your markdown vault on the command line")]
 pub struct Cli {
-    #[arg(long, global = true)]
+    #[arg(long, global = true, env = "MDV_CONFIG")]
     pub config: Option<PathBuf>,
 
-    #[arg(long, global = true)]
+    #[arg(long, global = true, conflicts_with = "vault_root")]
     pub profile: Option<String>,
 
+    /// Use the profile whose vault is at this path
+    #[arg(long, global = true, value_name = "PATH")]
+    pub vault_root: Option<PathBuf>,
+
+    /// Run the command on another machine over SSH: [USER@]HOST[:VAULT_PATH]
+    #[arg(long, global = true, value_name = "TARGET")]
+    pub remote: Option<String>,
+
+    /// Plain output for screen readers: ASCII only, tables as one record per line
+    #[arg(long, global = true)]
+    pub accessible: bool,
+
+    /// Let Lua scripts use the shell and net permissions they declare, even
+    /// if the security policy does not allow them, and run `mdv.shell`
+    #[arg(long, global = true)]
+    pub trust_scripts: bool,
+
+    /// Open the TUI straight in the input of this capture, quitting once it
+    /// is written (for a global hotkey)
+    #[arg(short = 'c', long, value_name = "CAPTURE")]
+    pub quick_capture: Option<String>,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -136,10 +225,10 @@ pub struct Cli {
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     /// Validate configuration and print resolved paths
-    Doctor,
+    Doctor(DoctorArgs),
 
     /// List logical template names discovered under templates_dir
-    ListTemplates,
+    ListTemplates(ListTemplatesArgs),
 
     /// Render a template into a new file
     New(NewArgs),
@@ -156,6 +245,9 @@ pub enum Commands {
     /// List notes in the vault with optional filters
     List(ListArgs),
 
+    /// Query notes with a small query language (filters, SORT, LIMIT)
+    Query(QueryArgs),
+
     /// Show links for a note (backlinks and/or outgoing)
     Links(LinksArgs),
 
@@ -172,9 +264,28 @@ pub enum Commands {
     /// Find unused notes (stale or orphaned)
     Stale(StaleArgs),
 
+    /// Find near-duplicate notes and suggest merges
+    Duplicates(DuplicatesArgs),
+
+    /// Show the notes most related to a note
+    Related(RelatedArgs),
+
+    /// Suggest wikilinks a note is missing
+    SuggestLinks(SuggestLinksArgs),
+
+    /// Compute note embeddings for semantic search
+    #[cfg(feature = "embeddings")]
+    Embed(EmbedArgs),
+
+    /// Show vault size, quota usage, and the largest files
+    Stats(StatsArgs),
+
     /// Rename a note and update all references to it
     Rename(RenameArgs),
 
+    /// List, regenerate, or strip generated regions in a note
+    Provenance(ProvenanceArgs),
+
     /// Generate shell completion scripts
     Completions(CompletionsArgs),
 
@@ -196,9 +307,15 @@ pub enum Commands {
     /// Daily planning and review dashboard
     Today(TodayArgs),
 
+    /// Guided weekly review of stale notes, old tasks, and orphans
+    Review(ReviewArgs),
+
     /// Set or show active focus context
     Focus(FocusArgs),
 
+    /// Open or create periodic journal notes (daily, weekly)
+    Journal(JournalArgs),
+
     /// Query context for a day or week
     #[command(subcommand)]
     Context(ContextCommands),
@@ -206,8 +323,53 @@ pub enum Commands {
     /// Interactive dashboard TUI
     Dashboard(DashboardArgs),
 
+    /// Kanban board of tasks (TUI)
+    Board(BoardArgs),
+
     /// Check vault structural correctness (lint)
     Check(CheckArgs),
+
+    /// Generate a synthetic demo vault to try mdv safely
+    Demo(DemoArgs),
+
+    /// Print-friendly daily or weekly planning sheet
+    Print(PrintArgs),
+
+    /// Turn external material (voice memos) into notes
+    #[command(subcommand)]
+    Ingest(IngestCommands),
+
+    /// Quick-capture inbox: file snippets dropped in a folder
+    #[command(subcommand)]
+    Inbox(InboxCommands),
+
+    /// Archive or trash notes whose expiry or retention period has passed
+    Expire(ExpireArgs),
+
+    /// Rewrite notes written for an older schema version of their type
+    Migrate(MigrateArgs),
+
+    /// Work with type definitions
+    #[command(subcommand)]
+    Types(TypesCommands),
+
+    /// List and run compiled-in Rust plugins
+    #[command(subcommand)]
+    Plugin(PluginCommands),
+
+    /// Interactive Lua session with the vault bindings loaded
+    Lua(LuaArgs),
+
+    /// Scheduled Lua jobs from .mdvault/jobs.lua
+    #[command(subcommand)]
+    Jobs(JobsCommands),
+
+    /// Accept captures over HTTP, for browser extensions and web services
+    Serve(ServeArgs),
+
+    /// A user-defined Lua command from the commands directory
+    #[command(external_subcommand)]
+    User(Vec<String>),
 }
 
 pub(crate) fn parse_key_val(s: &str) -> Result<(String, String), String> {
@@ -246,6 +408,18 @@ mod tests {
         assert!(!StatusFilter::InProgress.matches("blocked"));
     }
 
+    #[test]
+    fn output_arg_parses_formats_and_formatters() {
+        assert_eq!("json".parse(), Ok(OutputArg::Format(OutputFormat::Json)));
+        assert_eq!(
+            "stable-json".parse(),
+            Ok(OutputArg::Format(OutputFormat::StableJson))
+        );
+        assert_eq!("lua:task-brief".parse(), Ok(OutputArg::Lua("task-brief".into())));
+        assert!("lua:".parse::<OutputArg>().is_err());
+        assert!("yaml".parse::<OutputArg>().is_err());
+    }
+
     #[test]
     fn kind_filter_as_str() {
         assert_eq!(KindFilter::Project.as_str(), "project");
@@ -1,8 +1,9 @@
 use clap::Args;
 use clap_complete::engine::ArgValueCompleter;
+use mdvault_core::index::FieldFilter;
 use std::path::PathBuf;
 
-use super::{NoteTypeArg, OutputFormat, parse_key_val};
+use super::{NoteTypeArg, OutputArg, parse_key_val};
 
 #[derive(Debug, Args)]
 #[command(after_help = "\
@@ -10,6 +11,7 @@ Examples:
   mdv new task \"My Task\" --var project=myproject
   mdv new --template daily
   mdv new project \"New Project\" --var status=active -o projects/new.md
+  mdv new task \"Try the hook\" --dry-run
 ")]
 pub struct NewArgs {
     /// Note type for scaffolding (e.g., "task", "project", "zettel")
@@ -35,6 +37,10 @@ pub struct NewArgs {
     /// Non-interactive mode: fail if variables are missing instead of prompting
     #[arg(long)]
     pub batch: bool,
+
+    /// Render the note and print it without writing anything
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 #[derive(Debug, Args)]
@@ -43,6 +49,7 @@ Examples:
   mdv capture --list
   mdv capture inbox --var text=\"Buy milk\"
   mdv capture todo --var task=\"Review PR\" --var priority=high
+  mdv capture inbox --var text=\"Buy milk\" --dry-run
 ")]
 pub struct CaptureArgs {
     /// Logical capture name (e.g. "inbox" or "todo")
@@ -60,6 +67,10 @@ pub struct CaptureArgs {
     /// Non-interactive mode: fail if variables are missing instead of prompting
     #[arg(long)]
     pub batch: bool,
+
+    /// Print the changes to the target note without writing anything
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 #[derive(Debug, Args)]
@@ -99,7 +110,13 @@ Examples:
   mdv list --type task                  # Filter by type
   mdv list --modified-after 2024-01-01  # Filter by date
   mdv list --modified-after \"today - 7d\" # Notes from last week
+  mdv list --where status=doing --where priority>=3
+  mdv list --outline                    # Word counts and heading outlines
+  mdv list --exclude 'Archive/'         # Skip archived notes
+  mdv list --vault work --type task     # Tasks in the 'work' vault
+  mdv list --all-vaults --json          # Every vault, tagged with its id
   mdv list --json                       # JSON output
+  mdv list --page-size 100 --json       # First page, with a next_cursor
   mdv list -q                           # Paths only
 ")]
 pub struct ListArgs {
@@ -115,18 +132,61 @@ pub struct ListArgs {
     #[arg(long)]
     pub modified_before: Option<String>,
 
+    /// Filter on a frontmatter field: KEY=VALUE, KEY!=VALUE, or KEY<, <=, >, >= VALUE
+    /// (repeatable; all must match)
+    #[arg(long = "where", value_name = "FILTER")]
+    pub filters: Vec<FieldFilter>,
+
+    /// Skip notes matching this gitignore-style pattern (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
     /// Maximum number of notes to return
     #[arg(long, short = 'n')]
     pub limit: Option<u32>,
 
-    /// Output format
-    #[arg(long, short, value_enum, default_value = "table")]
-    pub output: OutputFormat,
+    /// Return one page of this many results, with a cursor for the next
+    #[arg(long, value_name = "N")]
+    pub page_size: Option<usize>,
+
+    /// Continue from a cursor returned by the previous page
+    #[arg(long, value_name = "TOKEN")]
+    pub cursor: Option<String>,
+
+    /// Show each note's word count, reading time, and heading outline
+    #[arg(long)]
+    pub outline: bool,
+
+    /// List notes that vanished from the vault since they were indexed
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "type", "modified_after", "modified_before", "filters", "exclude",
+            "outline", "page_size", "cursor",
+        ]
+    )]
+    pub deleted: bool,
+
+    /// List notes in this vault (profile) instead of the active one (repeatable)
+    #[arg(long = "vault", value_name = "PROFILE", conflicts_with = "all_vaults")]
+    pub vaults: Vec<String>,
+
+    /// List notes in every configured vault
+    #[arg(long)]
+    pub all_vaults: bool,
+
+    /// Output format: table, json, stable-json, quiet, or lua:NAME for a Lua formatter
+    #[arg(long, short, default_value = "table")]
+    pub output: OutputArg,
 
     /// Output as JSON (shorthand for --output json)
     #[arg(long)]
     pub json: bool,
 
+    /// Output stable JSON: fixed fields sorted by path (shorthand for --output stable-json)
+    #[arg(long)]
+    pub stable_json: bool,
+
     /// Quiet mode - output paths only (shorthand for --output quiet)
     #[arg(long, short)]
     pub quiet: bool,
@@ -138,7 +198,9 @@ Examples:
   mdv links note.md                     # Show backlinks and outlinks
   mdv links note.md --backlinks         # Only backlinks
   mdv links note.md --outlinks          # Only outlinks
+  mdv links note.md --embeds            # Only embeds (![[note]])
   mdv links tasks/todo.md --json        # JSON output
+  mdv links note.md --depth 2 -o        # Everything within two outgoing hops
 ")]
 pub struct LinksArgs {
     /// Path to the note (relative to vault root)
@@ -153,14 +215,26 @@ pub struct LinksArgs {
     #[arg(long, short = 'o')]
     pub outlinks: bool,
 
-    /// Output format
-    #[arg(long, value_enum, default_value = "table")]
-    pub output: OutputFormat,
+    /// Show only embeds (`![[note]]`): notes embedding this one, and notes it embeds
+    #[arg(long, short = 'e')]
+    pub embeds: bool,
+
+    /// Follow links up to N hops, listing each note reached and the route to it
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    pub depth: Option<u32>,
+
+    /// Output format: table, json, stable-json, quiet, or lua:NAME for a Lua formatter
+    #[arg(long, default_value = "table")]
+    pub output: OutputArg,
 
     /// Output as JSON (shorthand for --output json)
     #[arg(long)]
     pub json: bool,
 
+    /// Output stable JSON: fixed fields sorted by path (shorthand for --output stable-json)
+    #[arg(long)]
+    pub stable_json: bool,
+
     /// Quiet mode - output paths only (shorthand for --output quiet)
     #[arg(long, short)]
     pub quiet: bool,
@@ -172,16 +246,25 @@ Examples:
   mdv orphans                           # Find orphan notes
   mdv orphans --json                    # JSON output
   mdv orphans -q                        # Paths only
+  mdv orphans --min-age 14              # Skip notes younger than two weeks
 ")]
 pub struct OrphansArgs {
-    /// Output format
-    #[arg(long, short, value_enum, default_value = "table")]
-    pub output: OutputFormat,
+    /// Skip notes created fewer than this many days ago
+    #[arg(long, value_name = "DAYS")]
+    pub min_age: Option<u32>,
+
+    /// Output format: table, json, stable-json, quiet, or lua:NAME for a Lua formatter
+    #[arg(long, short, default_value = "table")]
+    pub output: OutputArg,
 
     /// Output as JSON (shorthand for --output json)
     #[arg(long)]
     pub json: bool,
 
+    /// Output stable JSON: fixed fields sorted by path (shorthand for --output stable-json)
+    #[arg(long)]
+    pub stable_json: bool,
+
     /// Quiet mode - output paths only (shorthand for --output quiet)
     #[arg(long, short)]
     pub quiet: bool,
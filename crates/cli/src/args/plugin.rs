@@ -0,0 +1,57 @@
+use clap::{Args, Subcommand};
+use std::path::PathBuf;
+
+use super::NoteTypeArg;
+
+/// Plugin subcommands.
+#[derive(Debug, Subcommand)]
+pub enum PluginCommands {
+    /// List plugins compiled into this build and what they provide
+    List(PluginListArgs),
+
+    /// Run a command provided by a plugin
+    #[command(after_help = "\
+Examples:
+  mdv plugin run embed-notes --model small   # Arguments are passed to the plugin as-is
+")]
+    Run(PluginRunArgs),
+
+    /// Export notes with an exporter provided by a plugin
+    #[command(after_help = "\
+Examples:
+  mdv plugin export site -o public/index.html
+  mdv plugin export site --type zettel
+")]
+    Export(PluginExportArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct PluginListArgs {
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct PluginRunArgs {
+    /// Command name, as shown by `mdv plugin list`
+    pub command: String,
+
+    /// Arguments passed to the plugin command
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct PluginExportArgs {
+    /// Exporter name, as shown by `mdv plugin list`
+    pub exporter: String,
+
+    /// Only export notes of this type
+    #[arg(long, short = 't')]
+    pub r#type: Option<NoteTypeArg>,
+
+    /// Write to a file instead of stdout
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
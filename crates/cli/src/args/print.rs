@@ -0,0 +1,40 @@
+use clap::{Args, ValueEnum};
+use std::path::PathBuf;
+
+/// Which sheet to print.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PrintPeriod {
+    /// One day: focus, top tasks, meetings, habits, and space for notes
+    Today,
+    /// Monday to Sunday: tasks, meetings by day, and a habit grid
+    Week,
+}
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Examples:
+  mdv print today                      # Markdown sheet for today on stdout
+  mdv print week -o week.md            # This week's sheet as a markdown file
+  mdv print today -o today.pdf         # PDF (needs pandoc on PATH)
+  mdv print today --date 2025-01-15    # Sheet for another day
+
+Habits are the checkboxes under a `## Habits` heading in your most recent
+daily note.
+")]
+pub struct PrintArgs {
+    /// Sheet to produce
+    #[arg(value_enum)]
+    pub period: PrintPeriod,
+
+    /// Day to print (YYYY-MM-DD); for `week`, any day in that week
+    #[arg(long)]
+    pub date: Option<String>,
+
+    /// Write to a file instead of stdout (`.pdf` is converted with pandoc)
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+
+    /// Maximum number of tasks to list
+    #[arg(long, default_value = "8")]
+    pub tasks: usize,
+}
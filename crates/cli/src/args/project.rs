@@ -17,6 +17,9 @@ pub enum ProjectCommands {
 
     /// Archive a completed project
     Archive(ProjectArchiveArgs),
+
+    /// Move an archived project back out of the archive
+    Unarchive(ProjectUnarchiveArgs),
 }
 
 #[derive(Debug, Args)]
@@ -61,4 +64,45 @@ pub struct ProjectArchiveArgs {
     /// Skip confirmation prompts
     #[arg(long, short)]
     pub yes: bool,
+
+    /// Show what archiving would do, including links and tasks elsewhere, without changing anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Print the impact analysis as JSON (with --dry-run)
+    #[arg(long, requires = "dry_run")]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Examples:
+  mdv project unarchive my-proj                       # Restore as done
+  mdv project unarchive my-proj --status in-progress  # Resume work on it
+  mdv project unarchive MP --reopen MP-004 --focus    # Reopen a task and focus
+  mdv project unarchive MP --reopen-all               # Reopen tasks the archive cancelled
+")]
+pub struct ProjectUnarchiveArgs {
+    /// Project ID or folder name
+    pub project: String,
+
+    /// Status to restore the project to
+    #[arg(long, default_value = "done")]
+    pub status: String,
+
+    /// Reopen this cancelled task (repeatable)
+    #[arg(long, value_name = "TASK_ID")]
+    pub reopen: Vec<String>,
+
+    /// Reopen every task that archiving the project cancelled
+    #[arg(long, conflicts_with = "reopen")]
+    pub reopen_all: bool,
+
+    /// Focus on the project once it is restored
+    #[arg(long)]
+    pub focus: bool,
+
+    /// Skip confirmation prompts
+    #[arg(long, short)]
+    pub yes: bool,
 }
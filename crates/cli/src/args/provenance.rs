@@ -0,0 +1,28 @@
+use clap::Args;
+use clap_complete::engine::ArgValueCompleter;
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Examples:
+  mdv provenance Reports/2026-09.md               # List generated regions
+  mdv provenance Reports/2026-09.md --regenerate  # Regenerate every region that can be
+  mdv provenance Journal/2026-W13.md --strip 2    # Remove region 2 (markers and content)
+  mdv provenance Reports/2026-09.md --json        # JSON output
+")]
+pub struct ProvenanceArgs {
+    /// Path to the note (relative to the vault root or current directory)
+    #[arg(add = ArgValueCompleter::new(crate::completions::complete_notes))]
+    pub note: String,
+
+    /// Regenerate generated regions (all, or only region N)
+    #[arg(long, value_name = "N", conflicts_with = "strip")]
+    pub regenerate: Option<Option<usize>>,
+
+    /// Remove generated regions, markers and content (all, or only region N)
+    #[arg(long, value_name = "N")]
+    pub strip: Option<Option<usize>>,
+
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
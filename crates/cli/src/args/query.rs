@@ -0,0 +1,44 @@
+use clap::Args;
+
+use super::OutputArg;
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Syntax:
+  field:value, field!=value     Frontmatter equals / differs
+  field < value (<=, >, >=)     Compare dates, numbers, or text
+  NOT clause                    Negate a clause
+  path:Projects/                Notes under a folder
+  modified >= today-7d          File modification date
+  clause AND clause ...         All clauses must match
+  SORT field [ASC|DESC], ...    Order results (missing values last)
+  LIMIT n                       Return at most n notes
+
+Values such as today, today+7d, or 2025-01-15 are evaluated as dates;
+quote a value ('today') to match it literally.
+
+Examples:
+  mdv query \"type:task AND status:todo AND due < today+7d SORT due\"
+  mdv query \"type:meeting AND NOT project:ALP SORT date DESC LIMIT 5\"
+  mdv query \"path:Projects/ AND modified >= today-7d\" -q
+")]
+pub struct QueryArgs {
+    /// Query to run (empty matches every note)
+    pub query: String,
+
+    /// Output format: table, json, stable-json, quiet, or lua:NAME for a Lua formatter
+    #[arg(long, short, default_value = "table")]
+    pub output: OutputArg,
+
+    /// Output as JSON (shorthand for --output json)
+    #[arg(long)]
+    pub json: bool,
+
+    /// Output stable JSON: fixed fields sorted by path (shorthand for --output stable-json)
+    #[arg(long)]
+    pub stable_json: bool,
+
+    /// Quiet mode - output paths only (shorthand for --output quiet)
+    #[arg(long, short)]
+    pub quiet: bool,
+}
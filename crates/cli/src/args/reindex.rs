@@ -13,4 +13,14 @@ pub struct ReindexArgs {
     /// Explicitly request incremental update (default behavior)
     #[arg(long, conflicts_with = "force")]
     pub incremental: bool,
+
+    /// Compact the index afterwards: prune dangling rows, optimize full-text
+    /// search, and rebuild the database file
+    #[arg(long)]
+    pub vacuum: bool,
+
+    /// Leave notes matching this gitignore-style pattern out of the index, on
+    /// top of .mdvignore (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
 }
@@ -8,13 +8,14 @@ Examples:
   mdv rename old.md new.md              # Rename note and update references
   mdv rename old.md new.md --dry-run    # Preview changes without modifying files
   mdv rename old.md new.md --yes        # Skip confirmation prompt
+  mdv rename guide.md#Setup Install     # Rename a heading and update [[guide#Setup]] links
 ")]
 pub struct RenameArgs {
-    /// Source file path (relative to vault root)
+    /// Source file path (relative to vault root), or `note.md#Heading` to rename a heading
     #[arg(add = ArgValueCompleter::new(crate::completions::complete_notes))]
     pub source: PathBuf,
 
-    /// Destination file path (relative to vault root)
+    /// Destination file path (relative to vault root), or the new heading text
     pub dest: PathBuf,
 
     /// Preview changes without modifying files
@@ -0,0 +1,31 @@
+use clap::Args;
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Examples:
+  mdv review                          # Review last week, then fill this week's note
+  mdv review --days 30                # Only flag open tasks older than 30 days
+  mdv review --week 2026-W04          # Review a specific ISO week
+  mdv review --batch                  # Non-interactive: list items, write summary
+")]
+pub struct ReviewArgs {
+    /// Week to review ("last", "current", YYYY-Wxx, or date expression)
+    #[arg(long, default_value = "last")]
+    pub week: String,
+
+    /// Flag open tasks older than this many days
+    #[arg(long, default_value = "14")]
+    pub days: u32,
+
+    /// Minimum staleness score for stale notes (0.0-1.0)
+    #[arg(long, default_value = "0.8")]
+    pub threshold: f64,
+
+    /// Maximum number of items per review step
+    #[arg(long, short = 'n', default_value = "10")]
+    pub limit: usize,
+
+    /// Non-interactive mode: list items without prompting for actions
+    #[arg(long)]
+    pub batch: bool,
+}
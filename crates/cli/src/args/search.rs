@@ -1,6 +1,8 @@
 use clap::{Args, ValueEnum};
+use clap_complete::engine::ArgValueCompleter;
+use mdvault_core::index::FieldFilter;
 
-use super::{NoteTypeArg, OutputFormat};
+use super::{NoteTypeArg, OutputArg};
 
 /// Search mode for result expansion.
 #[derive(Debug, Clone, Copy, Default, ValueEnum)]
@@ -16,6 +18,9 @@ pub enum SearchModeArg {
     Cooccurrence,
     /// Full contextual search (all modes)
     Full,
+    /// Rank by meaning using note embeddings (run 'mdv embed' first)
+    #[cfg(feature = "embeddings")]
+    Semantic,
 }
 
 #[derive(Debug, Args)]
@@ -24,37 +29,96 @@ Examples:
   mdv search \"parser\"                     # Direct search for 'parser'
   mdv search \"parser\" --mode full         # Search with full context
   mdv search \"fix bug\" --type task        # Search only task notes
+  mdv search \"api\" --where status=doing    # Only notes with status: doing
   mdv search --type task --mode full       # All tasks with context
   mdv search \"ML\" --boost                 # Boost recently active notes
+  mdv search \"projct kickof\"              # Typos still find 'Project Kickoff'
+  mdv search \"api\" --no-fuzzy             # Exact title/path matches only
+  mdv search \"api\" --exclude Archive/     # Skip archived notes
+  mdv search \"parser AND (rust OR lua) -archived type:zettel\"
+  mdv search \"offsite\" --all-vaults       # Search every configured vault
+  mdv search --saved inbox-overdue         # Run a saved search
+  mdv search --list                        # List saved searches
+  mdv search \"api\" --page-size 20 --json   # First page, with a next_cursor
+
+Queries take AND (implied between terms), OR, NOT or a leading -, parentheses
+and \"quoted phrases\". type:NAME, path:PREFIX and title:WORD narrow by note
+metadata; any other key:value, key:>=value or key<value tests a frontmatter
+field, with date expressions such as today or today+7d evaluated.
 ")]
 pub struct SearchArgs {
-    /// Search query (matches title and path)
+    /// Search query (matches title, path, and note body; supports AND, OR, NOT, -, (), "phrases" and key:value)
     pub query: Option<String>,
 
+    /// Run a saved search from config or .mdvault/searches.lua
+    #[arg(long, value_name = "NAME", conflicts_with = "query")]
+    pub saved: Option<String>,
+
+    /// List saved searches
+    #[arg(long, conflicts_with_all = ["query", "saved", "page_size", "cursor"])]
+    pub list: bool,
+
     /// Filter by note type
     #[arg(long)]
     pub r#type: Option<NoteTypeArg>,
 
-    /// Search mode for context expansion
-    #[arg(long, value_enum, default_value = "direct")]
-    pub mode: SearchModeArg,
+    /// Filter on a frontmatter field: KEY=VALUE, KEY!=VALUE, or KEY<, <=, >, >= VALUE
+    /// (repeatable; all must match)
+    #[arg(long = "where", value_name = "FILTER")]
+    pub filters: Vec<FieldFilter>,
+
+    /// Skip notes matching this gitignore-style pattern (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    /// Search mode for context expansion [default: direct, or the saved search's]
+    #[arg(long, value_enum)]
+    pub mode: Option<SearchModeArg>,
 
     /// Boost recently active notes
     #[arg(long)]
     pub boost: bool,
 
+    /// Also match titles and paths approximately, tolerating typos (default)
+    #[arg(long, overrides_with = "no_fuzzy")]
+    pub fuzzy: bool,
+
+    /// Only match titles and paths containing the query exactly
+    #[arg(long, overrides_with = "fuzzy")]
+    pub no_fuzzy: bool,
+
     /// Maximum number of results
     #[arg(long, short = 'n')]
     pub limit: Option<u32>,
 
-    /// Output format
-    #[arg(long, short, value_enum, default_value = "table")]
-    pub output: OutputFormat,
+    /// Return one page of this many results, with a cursor for the next
+    #[arg(long, value_name = "N")]
+    pub page_size: Option<usize>,
+
+    /// Continue from a cursor returned by the previous page
+    #[arg(long, value_name = "TOKEN")]
+    pub cursor: Option<String>,
+
+    /// Search this vault (profile) instead of the active one (repeatable)
+    #[arg(long = "vault", value_name = "PROFILE", conflicts_with = "all_vaults")]
+    pub vaults: Vec<String>,
+
+    /// Search every configured vault, merging results by score
+    #[arg(long)]
+    pub all_vaults: bool,
+
+    /// Output format: table, json, stable-json, quiet, or lua:NAME for a Lua formatter
+    #[arg(long, short, default_value = "table")]
+    pub output: OutputArg,
 
     /// Output as JSON (shorthand for --output json)
     #[arg(long)]
     pub json: bool,
 
+    /// Output stable JSON: fixed fields sorted by path (shorthand for --output stable-json)
+    #[arg(long)]
+    pub stable_json: bool,
+
     /// Quiet mode - output paths only (shorthand for --output quiet)
     #[arg(long, short)]
     pub quiet: bool,
@@ -68,12 +132,22 @@ Examples:
   mdv stale --threshold 0.7              # Higher staleness threshold
   mdv stale --days 90                    # Notes not seen in 90 days
   mdv stale --orphans                    # Find notes with no incoming links
+  mdv stale --orphans --min-age 14       # ...created at least two weeks ago
+  mdv stale --explain                    # Show what each score is made of
 ")]
 pub struct StaleArgs {
     /// Find orphan notes (no incoming links) instead of stale notes
     #[arg(long)]
     pub orphans: bool,
 
+    /// With --orphans, skip notes created fewer than this many days ago
+    #[arg(long, value_name = "DAYS", requires = "orphans")]
+    pub min_age: Option<u32>,
+
+    /// Break each score down into its weighted signals
+    #[arg(long, conflicts_with = "orphans")]
+    pub explain: bool,
+
     /// Filter by note type
     #[arg(long)]
     pub r#type: Option<NoteTypeArg>,
@@ -90,9 +164,118 @@ pub struct StaleArgs {
     #[arg(long, short = 'n')]
     pub limit: Option<u32>,
 
-    /// Output format
-    #[arg(long, short, value_enum, default_value = "table")]
-    pub output: OutputFormat,
+    /// Output format: table, json, stable-json, quiet, or lua:NAME for a Lua formatter
+    #[arg(long, short, default_value = "table")]
+    pub output: OutputArg,
+
+    /// Output as JSON (shorthand for --output json)
+    #[arg(long)]
+    pub json: bool,
+
+    /// Output stable JSON: fixed fields sorted by path (shorthand for --output stable-json)
+    #[arg(long)]
+    pub stable_json: bool,
+
+    /// Quiet mode - output paths only (shorthand for --output quiet)
+    #[arg(long, short)]
+    pub quiet: bool,
+}
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Examples:
+  mdv duplicates                         # Pairs at least 60% similar
+  mdv duplicates --threshold 0.9         # Only very close copies
+  mdv duplicates --json                  # Pairs with merge suggestions as JSON
+
+Similarity is computed by `mdv reindex`; run it first to pick up new notes.
+")]
+pub struct DuplicatesArgs {
+    /// Minimum similarity (0.5-1.0, default 0.6)
+    #[arg(long, default_value = "0.6")]
+    pub threshold: f64,
+
+    /// Maximum number of pairs
+    #[arg(long, short = 'n')]
+    pub limit: Option<u32>,
+
+    /// Output format: table, json, stable-json, quiet, or lua:NAME for a Lua formatter
+    #[arg(long, short, default_value = "table")]
+    pub output: OutputArg,
+
+    /// Output as JSON (shorthand for --output json)
+    #[arg(long)]
+    pub json: bool,
+
+    /// Quiet mode - output "KEEP<TAB>MERGE" paths only (shorthand for --output quiet)
+    #[arg(long, short)]
+    pub quiet: bool,
+}
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Examples:
+  mdv related zettel/parsing.md          # Ten most related notes
+  mdv related zettel/parsing.md --type zettel -n 5
+  mdv related zettel/parsing.md --json   # Scores and signals as JSON
+
+Notes are scored on shared outgoing links, dailies mentioning both,
+frontmatter tags in common, and overlap of the words in their bodies.
+Co-occurrence comes from `mdv reindex`; run it first to pick up new dailies.
+")]
+pub struct RelatedArgs {
+    /// Path to the note (relative to vault root)
+    #[arg(add = ArgValueCompleter::new(crate::completions::complete_notes))]
+    pub note: String,
+
+    /// Only suggest notes of this type
+    #[arg(long)]
+    pub r#type: Option<NoteTypeArg>,
+
+    /// Maximum number of results
+    #[arg(long, short = 'n', default_value = "10")]
+    pub limit: usize,
+
+    /// Output format: table, json, stable-json, quiet, or lua:NAME for a Lua formatter
+    #[arg(long, short, default_value = "table")]
+    pub output: OutputArg,
+
+    /// Output as JSON (shorthand for --output json)
+    #[arg(long)]
+    pub json: bool,
+
+    /// Quiet mode - output paths only (shorthand for --output quiet)
+    #[arg(long, short)]
+    pub quiet: bool,
+}
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Examples:
+  mdv suggest-links zettel/parsing.md           # Five most likely links
+  mdv suggest-links zettel/parsing.md --apply   # Add them under ## Related
+  mdv suggest-links zettel/parsing.md --json    # Confidence and signals as JSON
+
+Suggests notes whose title, alias or file name appears in the note's body,
+and notes mentioned in the same dailies, leaving out notes it already links
+to. Co-occurrence comes from `mdv reindex`; run it first to pick up new dailies.
+")]
+pub struct SuggestLinksArgs {
+    /// Path to the note (relative to vault root)
+    #[arg(add = ArgValueCompleter::new(crate::completions::complete_notes))]
+    pub note: String,
+
+    /// Maximum number of suggestions
+    #[arg(long, short = 'n', default_value = "5")]
+    pub limit: usize,
+
+    /// Insert the suggestions as wikilinks in the note's Related section
+    #[arg(long)]
+    pub apply: bool,
+
+    /// Output format: table, json, stable-json, quiet, or lua:NAME for a Lua formatter
+    #[arg(long, short, default_value = "table")]
+    pub output: OutputArg,
 
     /// Output as JSON (shorthand for --output json)
     #[arg(long)]
@@ -102,3 +285,23 @@ pub struct StaleArgs {
     #[arg(long, short)]
     pub quiet: bool,
 }
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Examples:
+  mdv embed                              # Embed new and changed notes
+  mdv embed --force                      # Re-embed every note
+  mdv search \"keeping dough alive\" --mode semantic
+
+The backend, model and endpoint come from [embeddings] in the config. Vectors
+are stored in the index; run 'mdv reindex' first so new notes are picked up.
+")]
+pub struct EmbedArgs {
+    /// Re-embed every note, not just new and changed ones
+    #[arg(long)]
+    pub force: bool,
+
+    /// Output the counts as JSON
+    #[arg(long)]
+    pub json: bool,
+}
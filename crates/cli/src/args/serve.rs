@@ -0,0 +1,15 @@
+use clap::Args;
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Examples:
+  MDV_SERVE_TOKEN=secret mdv serve
+  mdv serve --bind 0.0.0.0:7171
+  curl -H 'Authorization: Bearer secret' -d '{\"text\": \"Call Ana\"}' \\
+      http://127.0.0.1:7171/capture
+")]
+pub struct ServeArgs {
+    /// Address to listen on [default: [serve] bind, else 127.0.0.1:7171]
+    #[arg(long, value_name = "ADDR")]
+    pub bind: Option<String>,
+}
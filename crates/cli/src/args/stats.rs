@@ -0,0 +1,18 @@
+use clap::Args;
+
+#[derive(Debug, Args)]
+#[command(after_help = "\
+Examples:
+  mdv stats                   # Vault size and quota usage
+  mdv stats --large           # Biggest files and folders
+  mdv stats --large -n 50     # Show the 50 biggest files
+")]
+pub struct StatsArgs {
+    /// List the biggest notes and attachments, and per-folder sizes
+    #[arg(long)]
+    pub large: bool,
+
+    /// Number of files to list with --large
+    #[arg(long, short = 'n', default_value_t = 20, requires = "large")]
+    pub limit: usize,
+}
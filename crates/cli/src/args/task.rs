@@ -29,6 +29,10 @@ pub struct TaskListArgs {
     /// Filter by status (todo, in-progress, done, blocked, cancelled)
     #[arg(long, short, value_enum)]
     pub status: Option<StatusFilter>,
+
+    /// List checkbox items (`- [ ]`) inside notes instead of task notes
+    #[arg(long)]
+    pub inline: bool,
 }
 
 #[derive(Debug, Args)]
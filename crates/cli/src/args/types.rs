@@ -0,0 +1,48 @@
+use clap::{Args, Subcommand};
+use std::path::PathBuf;
+
+/// Type definition subcommands.
+#[derive(Debug, Subcommand)]
+pub enum TypesCommands {
+    /// Export type schemas as JSON Schema documents
+    #[command(after_help = "\
+Examples:
+  mdv types export                    # Write every type to .mdvault/schemas/
+  mdv types export task --stdout      # Print the task schema
+  mdv types export --dir schemas/     # Write to another directory
+")]
+    Export(TypesExportArgs),
+
+    /// Run typedef fixtures from the `tests/` folder through validation
+    #[command(after_help = "\
+Examples:
+  mdv types test                      # Run every fixture in <typedefs_dir>/tests/
+  mdv types test task                 # Only fixtures for the task type
+  mdv types test --dir fixtures/      # Run fixtures from another directory
+")]
+    Test(TypesTestArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct TypesExportArgs {
+    /// Only export this type
+    pub r#type: Option<String>,
+
+    /// Print to stdout instead of writing files (all types: one object keyed by type)
+    #[arg(long, conflicts_with = "dir")]
+    pub stdout: bool,
+
+    /// Directory to write `<type>.schema.json` files to [default: .mdvault/schemas]
+    #[arg(long, value_name = "DIR")]
+    pub dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct TypesTestArgs {
+    /// Only run fixtures for this type
+    pub r#type: Option<String>,
+
+    /// Directory of fixture files [default: <typedefs_dir>/tests]
+    #[arg(long, value_name = "DIR")]
+    pub dir: Option<PathBuf>,
+}
@@ -1,6 +1,7 @@
 use clap::Args;
+use mdvault_core::index::FieldFilter;
 
-use super::OutputFormat;
+use super::OutputArg;
 
 #[derive(Debug, Args)]
 #[command(after_help = "\
@@ -8,6 +9,8 @@ Examples:
   mdv validate                          # Validate all notes
   mdv validate path/to/note.md          # Validate specific file
   mdv validate --type task              # Validate only task notes
+  mdv validate --where status=doing     # Validate notes by frontmatter field
+  mdv validate --exclude 'templates/**' # Skip generated notes
   mdv validate --fix                    # Auto-fix safe issues
   mdv validate --list-types             # Show available type definitions
   mdv validate --json                   # JSON output
@@ -20,6 +23,15 @@ pub struct ValidateArgs {
     #[arg(long)]
     pub r#type: Option<String>,
 
+    /// Only validate notes whose frontmatter matches: KEY=VALUE, KEY!=VALUE, or
+    /// KEY<, <=, >, >= VALUE (repeatable; all must match)
+    #[arg(long = "where", value_name = "FILTER", conflicts_with = "path")]
+    pub filters: Vec<FieldFilter>,
+
+    /// Skip notes matching this gitignore-style pattern (repeatable)
+    #[arg(long, value_name = "GLOB", conflicts_with = "path")]
+    pub exclude: Vec<String>,
+
     /// Maximum number of notes to validate
     #[arg(long, short = 'n')]
     pub limit: Option<u32>,
@@ -32,9 +44,9 @@ pub struct ValidateArgs {
     #[arg(long)]
     pub list_types: bool,
 
-    /// Output format
-    #[arg(long, short, value_enum, default_value = "table")]
-    pub output: OutputFormat,
+    /// Output format: table, json, stable-json, quiet, or lua:NAME for a Lua formatter
+    #[arg(long, short, default_value = "table")]
+    pub output: OutputArg,
 
     /// Output as JSON (shorthand for --output json)
     #[arg(long)]
@@ -5,7 +5,9 @@ use color_eyre::eyre::{Result, bail};
 use mdvault_core::index::{IndexedNote, NoteQuery, NoteType};
 use serde::Serialize;
 use std::path::Path;
-use tabled::{Table, Tabled, settings::Style};
+use tabled::Tabled;
+
+use crate::display::{Mark, mark};
 
 use super::common::{load_config, open_index};
 
@@ -313,11 +315,11 @@ pub fn report(
                 label: r.label.clone(),
                 actual: r.actual,
                 target: r.target,
-                met: if r.met { "✓".into() } else { "✗".into() },
+                met: mark(if r.met { Mark::Yes } else { Mark::No }).into(),
             })
             .collect();
 
-        let table = Table::new(&rows).with(Style::rounded()).to_string();
+        let table = crate::display::table(&rows);
         println!("{table}");
     }
     Ok(())
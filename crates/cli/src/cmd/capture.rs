@@ -1,11 +1,11 @@
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use color_eyre::eyre::{Result, WrapErr, bail};
 
-use super::common::load_config;
+use super::common::{load_config, run_on_save_hook};
 use crate::prompt::{PromptOptions, collect_variables, create_fuzzy_selector_callback};
 use mdvault_core::activity::ActivityLogService;
 use mdvault_core::captures::{
@@ -26,6 +26,7 @@ use mdvault_core::types::{TypeRegistry, TypedefRepository};
 
 use chrono::Local;
 use regex::Regex;
+use similar::TextDiff;
 
 /// Built-in variables that are automatically provided
 const BUILTIN_VARS: &[&str] = &[
@@ -112,13 +113,15 @@ fn extract_user_variables(spec: &CaptureSpec) -> Vec<String> {
     sorted
 }
 
+/// Run a capture; returns the file it writes (or would write, in a dry run).
 pub fn run(
     config: Option<&Path>,
     profile: Option<&str>,
     capture_name: &str,
     vars: &[(String, String)],
     batch: bool,
-) -> Result<()> {
+    dry_run: bool,
+) -> Result<PathBuf> {
     // 1. Load config
     let cfg = load_config(config, profile)?;
 
@@ -186,6 +189,7 @@ pub fn run(
     let target_file = resolve_target_path(&cfg.vault_root, &target_file_raw);
 
     // 6. Read existing file or create if missing
+    let mut created = false;
     let existing_content = match fs::read_to_string(&target_file) {
         Ok(content) => content,
         Err(e)
@@ -195,25 +199,33 @@ pub fn run(
             // Create the file with minimal structure
             let content =
                 create_minimal_note(&ctx, loaded.spec.target.section.as_deref());
+            created = true;
+            if dry_run {
+                // Nothing on disk yet; the preview diffs against an empty file
+                content
+            } else {
+                // Ensure parent directory exists
+                if let Some(parent) = target_file.parent() {
+                    fs::create_dir_all(parent).wrap_err_with(|| {
+                        format!("Failed to create directory {}", parent.display())
+                    })?;
+                }
 
-            // Ensure parent directory exists
-            if let Some(parent) = target_file.parent() {
-                fs::create_dir_all(parent).wrap_err_with(|| {
-                    format!("Failed to create directory {}", parent.display())
+                // Write the new file
+                fs::write(&target_file, &content).wrap_err_with(|| {
+                    format!("Failed to create target file {}", target_file.display())
                 })?;
-            }
 
-            // Write the new file
-            fs::write(&target_file, &content).wrap_err_with(|| {
-                format!("Failed to create target file {}", target_file.display())
-            })?;
+                if let Err(e) = set_updated_at(&target_file) {
+                    tracing::warn!(
+                        "Failed to set updated_at on new capture target: {}",
+                        e
+                    );
+                }
 
-            if let Err(e) = set_updated_at(&target_file) {
-                tracing::warn!("Failed to set updated_at on new capture target: {}", e);
+                println!("Created: {}", target_file.display());
+                content
             }
-
-            println!("Created: {}", target_file.display());
-            content
         }
         Err(e) => {
             bail!(
@@ -223,12 +235,39 @@ pub fn run(
         }
     };
 
-    // 7. Execute capture (frontmatter + content insertion)
+    // 7. Gather capture-time metadata; a missing value is not worth failing over.
+    // A dry run runs no commands, so it shows placeholders for their output.
+    let mut metadata = Vec::new();
+    for entry in &loaded.spec.metadata {
+        if dry_run {
+            metadata.push((entry.field.clone(), entry.kind.preview()));
+            continue;
+        }
+        match entry.kind.resolve(cfg.capture.location_command.as_deref()) {
+            Ok(value) => metadata.push((entry.field.clone(), value)),
+            Err(e) => eprintln!("Warning: skipping metadata '{}': {e}", entry.field),
+        }
+    }
+
+    // 8. Execute capture (frontmatter + content insertion)
     let (result_content, section_info): (String, Option<(String, u8)>) =
-        execute_capture_operations(&existing_content, &loaded.spec, &ctx)
+        execute_capture_operations(&existing_content, &loaded.spec, &ctx, &metadata)
             .map_err(|e| color_eyre::eyre::eyre!("{e}"))?;
 
-    // 8. Write back to file
+    if dry_run {
+        print_dry_run(
+            &cfg,
+            capture_name,
+            &target_file,
+            created,
+            section_info.as_ref(),
+            &existing_content,
+            &result_content,
+        );
+        return Ok(target_file);
+    }
+
+    // 9. Write back to file
     fs::write(&target_file, &result_content)
         .wrap_err_with(|| format!("Failed to write to {}", target_file.display()))?;
 
@@ -236,7 +275,7 @@ pub fn run(
         tracing::warn!("Failed to set updated_at on capture target: {}", e);
     }
 
-    // 8.5. Run after_insert hook if defined
+    // 9.5. Run after_insert hook if defined
     if loaded.spec.has_after_insert {
         let inserted_content =
             loaded.spec.content.as_ref().map(|t| render_string(t, &ctx));
@@ -254,16 +293,17 @@ pub fn run(
         }
     }
 
-    // 9. Run on_update hook if defined for this note type
+    // 10. Run on_update hook if defined for this note type
     run_on_update_hook_if_needed(&cfg, &target_file, &result_content);
+    run_on_save_hook(&cfg, &target_file);
 
-    // 10. Log to activity log
+    // 11. Log to activity log
     if let Some(activity) = ActivityLogService::try_from_config(&cfg) {
         let section_name = section_info.as_ref().map(|(title, _)| title.as_str());
         let _ = activity.log_capture(capture_name, &target_file, section_name);
     }
 
-    // 11. Reindex the target file so it appears in queries immediately
+    // 12. Reindex the target file so it appears in queries immediately
     let index_path = PathResolver::new(&cfg.vault_root).index_db();
     if let Ok(db) = IndexDb::open(&index_path) {
         let builder = IndexBuilder::new(&db, &cfg.vault_root);
@@ -282,7 +322,11 @@ pub fn run(
     if loaded.spec.frontmatter.is_some() {
         println!("frontmatter: modified");
     }
-    Ok(())
+    if !metadata.is_empty() {
+        let fields: Vec<&str> = metadata.iter().map(|(f, _)| f.as_str()).collect();
+        println!("metadata: {}", fields.join(", "));
+    }
+    Ok(target_file)
 }
 
 /// Run on_update hook for the target note if its type has one defined.
@@ -414,12 +458,48 @@ fn run_on_update_hook_if_needed(cfg: &ResolvedConfig, target_file: &Path, conten
     }
 }
 
+/// Show what a capture would do: its target and a unified diff of the note.
+fn print_dry_run(
+    cfg: &ResolvedConfig,
+    capture_name: &str,
+    target_file: &Path,
+    created: bool,
+    section_info: Option<&(String, u8)>,
+    before: &str,
+    after: &str,
+) {
+    println!("DRY RUN mdv capture");
+    println!("capture: {}", capture_name);
+    if created {
+        println!("target:  {} (would be created)", target_file.display());
+    } else {
+        println!("target:  {}", target_file.display());
+    }
+    if let Some((title, level)) = section_info {
+        println!("section: {} (level {})", title, level);
+    }
+    println!();
+
+    let label = target_file.strip_prefix(&cfg.vault_root).unwrap_or(target_file);
+    let label = label.to_string_lossy();
+    let original = if created { "" } else { before };
+    if original == after {
+        println!("(no changes)");
+    } else {
+        let diff = TextDiff::from_lines(original, after);
+        print!("{}", diff.unified_diff().header(&label, &label));
+    }
+    println!();
+    println!("Nothing was written. Run without --dry-run to capture.");
+}
+
 /// Execute capture operations: frontmatter modification and/or content insertion.
 /// Returns the modified content and optional section info (title, level).
 fn execute_capture_operations(
     existing_content: &str,
     spec: &CaptureSpec,
     ctx: &HashMap<String, String>,
+    metadata: &[(String, String)],
 ) -> Result<(String, Option<(String, u8)>), String> {
     // Parse frontmatter from existing content first
     let mut parsed = parse(existing_content)
@@ -432,6 +512,14 @@ fn execute_capture_operations(
             .map_err(|e| format!("Failed to apply frontmatter ops: {e}"))?;
     }
 
+    // Record metadata verbatim; values are not templates
+    if !metadata.is_empty() {
+        let fm = parsed.frontmatter.get_or_insert_with(Default::default);
+        for (field, value) in metadata {
+            fm.fields.insert(field.clone(), serde_yaml::Value::String(value.clone()));
+        }
+    }
+
     // Insert content if specified - operate on body only to preserve frontmatter
     if let Some(content_template) = &spec.content {
         let section = spec.target.section.as_ref().ok_or_else(|| {
@@ -2,7 +2,7 @@
 
 use std::path::Path;
 
-use super::common::{load_config, open_index};
+use super::common::{load_config, open_index_read_only};
 use color_eyre::eyre::{Result, bail};
 use mdvault_core::lint::{CategoryReport, LintReport, run_lint};
 use mdvault_core::types::{TypeRegistry, TypedefRepository};
@@ -12,7 +12,7 @@ use crate::CheckArgs;
 pub fn run(config: Option<&Path>, profile: Option<&str>, args: CheckArgs) -> Result<()> {
     // Load configuration
     let rc = load_config(config, profile)?;
-    let db = open_index(&rc.vault_root)?;
+    let db = open_index_read_only(&rc.vault_root)?;
 
     // Load type registry
     let typedef_repo = match &rc.typedefs_fallback_dir {
@@ -84,7 +84,7 @@ fn print_table(report: &LintReport) {
             let loc = format_location(&issue.path, issue.line);
             print!("  ERROR {}: {}", loc, issue.message);
             if let Some(ref sug) = issue.suggestion {
-                print!(" — {sug}");
+                print!(" {} {sug}", crate::display::glyphs("—"));
             }
             println!();
         }
@@ -93,7 +93,7 @@ fn print_table(report: &LintReport) {
             let loc = format_location(&issue.path, issue.line);
             print!("  WARN  {}: {}", loc, issue.message);
             if let Some(ref sug) = issue.suggestion {
-                print!(" — {sug}");
+                print!(" {} {sug}", crate::display::glyphs("—"));
             }
             println!();
         }
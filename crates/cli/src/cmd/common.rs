@@ -1,19 +1,60 @@
 //! Shared command utilities: config loading, index access, error helpers.
 
+use std::collections::BTreeMap;
 use std::path::Path;
+use std::sync::Arc;
 
-use color_eyre::eyre::{Result, WrapErr};
+use color_eyre::eyre::{Result, WrapErr, bail, eyre};
+use mdvault_core::captures::CaptureRepository;
 use mdvault_core::config::loader::ConfigLoader;
-use mdvault_core::config::types::ResolvedConfig;
-use mdvault_core::index::IndexDb;
+use mdvault_core::config::types::{ResolvedConfig, WebhookEvent};
+use mdvault_core::index::{FederatedIndex, IndexDb, RankHook};
+use mdvault_core::macros::MacroRepository;
 use mdvault_core::paths::PathResolver;
+use mdvault_core::plugins;
+use mdvault_core::saved_searches::{self, SavedSearch};
+use mdvault_core::scripting::template_filters::load_template_filters;
+use mdvault_core::scripting::{EventHooks, NoteHook, VaultContext, apply_note_hook};
+use mdvault_core::templates::repository::TemplateRepository;
+use mdvault_core::types::{TypeRegistry, TypedefRepository};
+use mdvault_core::vault::IgnoreRules;
+use mdvault_core::webhooks::WebhookService;
 
-/// Load configuration.
+/// Load configuration, initialise compiled-in plugins with it and register
+/// the vault's template filters.
+///
+/// A broken filters file only warns: commands that render nothing should
+/// not fail over it, and templates using its filters leave values as is.
 pub fn load_config(
     config: Option<&Path>,
     profile: Option<&str>,
 ) -> Result<ResolvedConfig> {
-    ConfigLoader::load(config, profile).wrap_err("Failed to load config")
+    let cfg = ConfigLoader::load(config, profile).wrap_err("Failed to load config")?;
+    plugins::activate(&cfg).wrap_err("Failed to load plugins")?;
+    let filters = PathResolver::new(&cfg.vault_root).template_filters();
+    if let Err(e) = load_template_filters(&filters) {
+        eprintln!("Warning: {e}; custom template filters are unavailable");
+    }
+    Ok(cfg)
+}
+
+/// Load the vault's saved searches from config and `.mdvault/searches.lua`.
+pub fn load_saved_searches(
+    cfg: &ResolvedConfig,
+) -> Result<BTreeMap<String, SavedSearch>> {
+    saved_searches::load(cfg).map_err(|e| color_eyre::eyre::eyre!("{e}"))
+}
+
+/// Rules for `--exclude` flags.
+///
+/// The index already leaves out what `.mdvignore` excludes, so only the
+/// flags' patterns are needed to filter notes read from it.
+pub fn exclude_rules(patterns: &[String]) -> Result<IgnoreRules> {
+    let mut rules = IgnoreRules::default();
+    for pattern in patterns {
+        rules.add(pattern)?;
+    }
+    Ok(rules)
 }
 
 /// Open the vault index database.
@@ -22,3 +63,138 @@ pub fn open_index(vault_root: &Path) -> Result<IndexDb> {
     IndexDb::open(&index_path)
         .wrap_err("Failed to open index. Run 'mdv reindex' to build it")
 }
+
+/// Open the vault index database for a command that only reads it.
+///
+/// Read-only connections never take the write lock, so they work while a
+/// reindex or the dashboard holds the index open.
+pub fn open_index_read_only(vault_root: &Path) -> Result<IndexDb> {
+    let index_path = PathResolver::new(vault_root).index_db();
+    IndexDb::open_read_only(&index_path)
+        .wrap_err("Failed to open index. Run 'mdv reindex' to build it")
+}
+
+/// Open the indexes of the vaults picked by `--vault` / `--all-vaults`.
+///
+/// With neither, this is the active profile's vault alone. Vault ids are
+/// profile names. Vaults with a `.mdvault/ranking.lua` rank their search
+/// results with it.
+pub fn open_vaults(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    vaults: &[String],
+    all_vaults: bool,
+) -> Result<FederatedIndex> {
+    let configs = if all_vaults {
+        ConfigLoader::load_all(config).wrap_err("Failed to load config")?
+    } else if vaults.is_empty() {
+        vec![load_config(config, profile)?]
+    } else {
+        vaults.iter().map(|v| load_config(config, Some(v))).collect::<Result<_>>()?
+    };
+
+    let mut indexes = Vec::new();
+    let mut hooks = Vec::new();
+    for rc in configs {
+        let db = open_index_read_only(&rc.vault_root)
+            .wrap_err_with(|| format!("Vault '{}'", rc.active_profile))?;
+        if let Some(hook) = RankHook::load(&rc.vault_root)
+            .map_err(|e| color_eyre::eyre::eyre!("Vault '{}': {e}", rc.active_profile))?
+        {
+            hooks.push((rc.active_profile.clone(), hook));
+        }
+        indexes.push((rc.active_profile, db));
+    }
+    let mut index = FederatedIndex::new(indexes);
+    for (vault, hook) in hooks {
+        index.set_rank_hook(vault, hook);
+    }
+    Ok(index)
+}
+
+/// Send an event to the configured webhooks.
+///
+/// Failed deliveries are warnings: the command that raised the event has
+/// already done its work.
+pub fn emit_webhook(cfg: &ResolvedConfig, kind: WebhookEvent, vars: &[(&str, &str)]) {
+    if let Some(webhooks) = WebhookService::try_from_config(cfg) {
+        for e in webhooks.emit(kind, vars) {
+            eprintln!("Warning: webhook {}: {e}", kind.as_str());
+        }
+    }
+}
+
+/// Load the type registry, falling back to built-ins when typedefs are unavailable.
+pub fn load_types(cfg: &ResolvedConfig) -> TypeRegistry {
+    let repo = match &cfg.typedefs_fallback_dir {
+        Some(fallback) => TypedefRepository::with_fallback(&cfg.typedefs_dir, fallback),
+        None => TypedefRepository::new(&cfg.typedefs_dir),
+    };
+    repo.ok()
+        .and_then(|r| TypeRegistry::from_repository(&r).ok())
+        .unwrap_or_else(TypeRegistry::new)
+}
+
+/// Run the note type's `on_save` hook on a note the command just wrote.
+///
+/// `path` may be absolute or relative to the vault root. Hook failures are
+/// warnings: the note itself has already been saved.
+pub fn run_on_save_hook(cfg: &ResolvedConfig, path: &Path) {
+    let file = cfg.vault_root.join(path);
+    let rel = file.strip_prefix(&cfg.vault_root).unwrap_or(&file);
+    let Ok(content) = std::fs::read_to_string(&file) else {
+        return;
+    };
+    match apply_note_hook(&load_types(cfg), NoteHook::Save, rel, &content) {
+        Ok(Some(updated)) => {
+            if let Err(e) = std::fs::write(&file, updated) {
+                eprintln!("Warning: failed to apply on_save hook changes: {e}");
+            }
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("Warning: on_save hook failed: {e}"),
+    }
+}
+
+/// Build the context Lua scripts run in: repositories, types and, once the
+/// vault has been indexed, the index.
+pub fn vault_context(cfg: &ResolvedConfig) -> Result<VaultContext> {
+    let templates = TemplateRepository::new(&cfg.templates_dir)
+        .wrap_err("Failed to load templates")?;
+    let captures =
+        CaptureRepository::new(&cfg.captures_dir).wrap_err("Failed to load captures")?;
+    let macros =
+        MacroRepository::new(&cfg.macros_dir).wrap_err("Failed to load macros")?;
+    let types = load_types(cfg);
+    let index =
+        IndexDb::open(&PathResolver::new(&cfg.vault_root).index_db()).ok().map(Arc::new);
+
+    let vault_ctx = VaultContext::new(cfg.clone(), templates, captures, macros, types);
+    Ok(match index {
+        Some(db) => vault_ctx.with_index(db),
+        None => vault_ctx,
+    })
+}
+
+/// Load the Lua event hooks in `hooks_dir`, or `None` if there are none.
+pub fn load_event_hooks(cfg: &ResolvedConfig) -> Result<Option<EventHooks>> {
+    // Hook errors carry Lua errors, which are not `Send`
+    let hooks = EventHooks::load(&cfg.hooks_dir).map_err(|e| eyre!("{e}"))?;
+    if hooks.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(hooks.with_context(vault_context(cfg)?)))
+}
+
+/// Open a file in the user's editor (`$EDITOR`, then `$VISUAL`, then `vim`).
+pub fn open_in_editor(path: &Path) -> Result<()> {
+    let editor = std::env::var("EDITOR")
+        .or_else(|_| std::env::var("VISUAL"))
+        .unwrap_or_else(|_| "vim".to_string());
+
+    match std::process::Command::new(&editor).arg(path).status() {
+        Ok(s) if s.success() => Ok(()),
+        Ok(s) => bail!("Editor exited with status: {}", s),
+        Err(e) => bail!("Failed to open editor '{}': {}", editor, e),
+    }
+}
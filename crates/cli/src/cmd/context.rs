@@ -5,7 +5,7 @@ use std::path::Path;
 use super::common::load_config;
 use chrono::{Datelike, Duration, Local, NaiveDate};
 use color_eyre::eyre::{Result, WrapErr};
-use mdvault_core::context::ContextQueryService;
+use mdvault_core::context::{ContextError, ContextQueryService};
 use mdvault_core::vars::datemath::{DateBase, parse_date_expr};
 
 /// Get context for a specific day.
@@ -191,7 +191,7 @@ fn parse_date_arg(arg: Option<&str>) -> Result<NaiveDate, String> {
 }
 
 /// Parse a week argument into a date within that week.
-fn parse_week_arg(arg: Option<&str>) -> Result<NaiveDate, String> {
+pub(super) fn parse_week_arg(arg: Option<&str>) -> Result<NaiveDate, String> {
     let arg = arg.unwrap_or("current");
 
     // Handle special keywords
@@ -295,14 +295,11 @@ pub fn focus(
     // Get focus context
     let context = match service.focus_context() {
         Ok(ctx) => ctx,
-        Err(e) => {
-            // Check if it's just "no focus set"
-            if e.to_string().contains("No focus set") {
-                println!("No focus set. Use `mdv focus <project>` to set focus.");
-                return Ok(());
-            }
-            return Err(e).wrap_err("Failed to get focus context");
+        Err(ContextError::NoFocus) => {
+            println!("No focus set. Use `mdv focus <project>` to set focus.");
+            return Ok(());
         }
+        Err(e) => return Err(e).wrap_err("Failed to get focus context"),
     };
 
     // Output based on format
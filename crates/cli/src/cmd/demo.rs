@@ -0,0 +1,97 @@
+//! Demo command implementation.
+
+use std::fmt::Write as _;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use color_eyre::eyre::{Result, WrapErr, bail};
+use mdvault_core::demo::{self, DemoOptions};
+use mdvault_core::index::{DerivedIndexBuilder, IndexBuilder, IndexDb};
+use mdvault_core::paths::PathResolver;
+
+use crate::DemoArgs;
+
+/// Run the demo command.
+///
+/// Layout of the demo directory:
+/// - `vault/`: the generated notes, with `.mdvault/` and its index
+/// - `config.toml`: a single `demo` profile pointing at `vault/`
+pub fn run(args: DemoArgs) -> Result<()> {
+    let root = match args.path {
+        Some(path) => path,
+        None => default_demo_dir(),
+    };
+    let vault = root.join("vault");
+    let options = DemoOptions {
+        size: args.size.into(),
+        seed: args.seed,
+        end_date: chrono::Local::now().date_naive(),
+    };
+
+    let stats =
+        demo::generate(&vault, &options).wrap_err("Failed to generate demo vault")?;
+    let config_path = write_config(&root, &vault)?;
+
+    let db = IndexDb::open(&PathResolver::new(&vault).index_db())
+        .wrap_err("Failed to create demo index")?;
+    IndexBuilder::new(&db, &vault)
+        .full_reindex(None)
+        .wrap_err("Failed to index demo vault")?;
+    DerivedIndexBuilder::new(&db)
+        .compute_all()
+        .wrap_err("Failed to compute derived indices")?;
+
+    println!("Demo vault created at {} (seed {})", vault.display(), args.seed);
+    println!(
+        "  {} projects, {} tasks, {} dailies, {} zettels",
+        stats.projects, stats.tasks, stats.dailies, stats.zettels
+    );
+    println!();
+
+    if args.no_shell || !io::stdin().is_terminal() {
+        println!("To use it, point mdv at its config:");
+        println!("  export MDV_CONFIG={}", config_path.display());
+        return Ok(());
+    }
+
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "sh".to_string());
+    println!(
+        "Starting {} in the demo vault (MDV_CONFIG is set). Type `exit` to leave.",
+        shell
+    );
+    let status = Command::new(&shell)
+        .current_dir(&vault)
+        .env("MDV_CONFIG", &config_path)
+        .status()
+        .wrap_err_with(|| format!("Failed to start shell '{}'", shell))?;
+
+    println!();
+    println!("Left the demo vault. It is still at {}", root.display());
+    if !status.success() {
+        bail!("Shell exited with status: {}", status);
+    }
+    Ok(())
+}
+
+/// A fresh directory under the system temp dir, e.g. `/tmp/mdv-demo-<pid>-<time>`.
+fn default_demo_dir() -> PathBuf {
+    let stamp = chrono::Local::now().format("%Y%m%d%H%M%S");
+    std::env::temp_dir().join(format!("mdv-demo-{}-{}", std::process::id(), stamp))
+}
+
+fn write_config(root: &Path, vault: &Path) -> Result<PathBuf> {
+    let mut toml = String::from("version = 1\nprofile = \"demo\"\n\n[profiles.demo]\n");
+    writeln!(toml, "vault_root = {:?}", vault.display().to_string())?;
+    for dir in ["typedefs", "templates", "captures", "macros"] {
+        let path = vault.join(".mdvault").join(dir);
+        std::fs::create_dir_all(&path)
+            .wrap_err_with(|| format!("Failed to create {}", path.display()))?;
+        writeln!(toml, "{dir}_dir = {:?}", path.display().to_string())?;
+    }
+
+    let config_path = root.join("config.toml");
+    std::fs::write(&config_path, toml)
+        .wrap_err_with(|| format!("Failed to write {}", config_path.display()))?;
+    Ok(config_path)
+}
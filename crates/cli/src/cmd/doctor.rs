@@ -1,8 +1,15 @@
-use color_eyre::eyre::{Result, bail};
+use chrono::{Duration, Utc};
+use color_eyre::eyre::{Result, WrapErr, bail};
 use mdvault_core::config::loader::{ConfigLoader, default_config_path};
+use mdvault_core::config::types::ResolvedConfig;
+use mdvault_core::index::{HealthReport, IndexDb, IndexHealth, IssueKind};
+use mdvault_core::paths::PathResolver;
+use mdvault_core::vault::{ByteSize, VaultUsage};
 use std::path::Path;
 
-pub fn run(config: Option<&Path>, profile: Option<&str>) -> Result<()> {
+use crate::DoctorArgs;
+
+pub fn run(config: Option<&Path>, profile: Option<&str>, args: DoctorArgs) -> Result<()> {
     match ConfigLoader::load(config, profile) {
         Ok(rc) => {
             println!("OK   mdv doctor");
@@ -19,6 +26,17 @@ pub fn run(config: Option<&Path>, profile: Option<&str>) -> Result<()> {
             println!("macros_dir: {}", rc.macros_dir.display());
             println!("security.allow_shell: {}", rc.security.allow_shell);
             println!("security.allow_http:  {}", rc.security.allow_http);
+            if !rc.quota.is_empty() {
+                println!();
+                check_quota(&rc)?;
+            }
+            if PathResolver::new(&rc.vault_root).index_db().exists() {
+                check_vanished(&rc)?;
+            }
+            if args.index {
+                println!();
+                check_index(&rc, args.fix)?;
+            }
             Ok(())
         }
         Err(e) => {
@@ -31,3 +49,119 @@ pub fn run(config: Option<&Path>, profile: Option<&str>) -> Result<()> {
         }
     }
 }
+
+/// Warn about folders, or the whole vault, that have outgrown their quota.
+/// Going over quota is reported but does not fail the check.
+fn check_quota(rc: &ResolvedConfig) -> Result<()> {
+    let usage = VaultUsage::scan(&rc.vault_root, rc.excluded_folders.clone())
+        .wrap_err("Failed to measure vault")?;
+    let exceeded = usage.check_quota(&rc.quota);
+    if exceeded.is_empty() {
+        println!("OK   quota");
+        println!("size: {}", ByteSize(usage.total));
+        return Ok(());
+    }
+    println!("WARN quota");
+    for e in &exceeded {
+        println!("  {e}");
+    }
+    println!("hint: run `mdv stats --large` to find the biggest files");
+    Ok(())
+}
+
+/// How far back `mdv doctor` looks for vanished notes.
+const VANISHED_WINDOW_DAYS: i64 = 30;
+
+/// Warn about notes deleted outside mdv in the last
+/// [`VANISHED_WINDOW_DAYS`] that other notes still link to.
+fn check_vanished(rc: &ResolvedConfig) -> Result<()> {
+    let db = IndexDb::open_read_only(&PathResolver::new(&rc.vault_root).index_db())
+        .wrap_err("Failed to open index")?;
+    let since = Utc::now() - Duration::days(VANISHED_WINDOW_DAYS);
+    let linked: Vec<_> = db
+        .get_tombstones(Some(since), None)
+        .wrap_err("Failed to read deleted notes")?
+        .into_iter()
+        .filter(|t| t.backlinks > 0)
+        .collect();
+    if linked.is_empty() {
+        return Ok(());
+    }
+
+    println!();
+    println!("WARN vanished notes");
+    for t in &linked {
+        println!(
+            "  {} (deleted {}, {} backlink(s))",
+            t.path.display(),
+            t.deleted_at.format("%Y-%m-%d"),
+            t.backlinks
+        );
+    }
+    println!("hint: restore them, or update the notes that link to them");
+    Ok(())
+}
+
+/// Compare the index with the vault, optionally repairing what is found.
+fn check_index(rc: &ResolvedConfig, fix: bool) -> Result<()> {
+    let index_path = PathResolver::new(&rc.vault_root).index_db();
+    if !index_path.exists() {
+        println!("FAIL mdv doctor --index");
+        println!("no index at {}", index_path.display());
+        bail!("index not built; run `mdv reindex` first");
+    }
+    let db = IndexDb::open(&index_path).wrap_err("Failed to open index")?;
+    let health = IndexHealth::new(&db, &rc.vault_root, rc.excluded_folders.clone());
+
+    let mut report = health.check().wrap_err("Index check failed")?;
+    if fix && !report.is_healthy() {
+        print_issues(&report);
+        let stats = health.fix(&report).wrap_err("Index repair failed")?;
+        let fixed: Vec<String> = IssueKind::ALL
+            .iter()
+            .filter_map(|k| stats.fixed.get(k).map(|n| format!("{} {}", n, k.as_str())))
+            .collect();
+        println!("fixed: {}", fixed.join(", "));
+        for (issue, reason) in &stats.failed {
+            println!("could not fix {}: {}", issue.path.display(), reason);
+        }
+        println!();
+        report = health.check().wrap_err("Index check failed")?;
+    }
+
+    if report.is_healthy() {
+        println!("OK   mdv doctor --index");
+        println!(
+            "notes: {} on disk, {} indexed",
+            report.notes_on_disk, report.notes_indexed
+        );
+        return Ok(());
+    }
+
+    println!("FAIL mdv doctor --index");
+    print_issues(&report);
+    if !fix {
+        println!("hint: run `mdv doctor --index --fix` to repair");
+    }
+    bail!("index check found {} problem(s)", report.issues.len());
+}
+
+fn print_issues(report: &HealthReport) {
+    println!("notes: {} on disk, {} indexed", report.notes_on_disk, report.notes_indexed);
+    for kind in IssueKind::ALL {
+        let issues: Vec<_> = report.of_kind(kind).collect();
+        if issues.is_empty() {
+            continue;
+        }
+        println!("{} ({}):", kind.description(), issues.len());
+        for issue in issues {
+            match (&issue.detail, issue.path.as_os_str().is_empty()) {
+                (Some(detail), true) => println!("  {}", detail),
+                (Some(detail), false) => {
+                    println!("  {} -> {}", issue.path.display(), detail)
+                }
+                (None, _) => println!("  {}", issue.path.display()),
+            }
+        }
+    }
+}
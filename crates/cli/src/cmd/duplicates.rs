@@ -0,0 +1,167 @@
+//! Near-duplicate notes command implementation.
+
+use std::path::Path;
+
+use color_eyre::eyre::{Result, WrapErr};
+use mdvault_core::index::{IndexDb, IndexedNote};
+use serde::Serialize;
+
+use super::common::{load_config, open_index_read_only};
+use super::output::{print_json, resolve_format, truncate};
+use crate::{DuplicatesArgs, OutputFormat};
+
+/// One side of a duplicate pair, for JSON.
+#[derive(Debug, Serialize)]
+struct PairNoteOutput {
+    path: String,
+    title: String,
+    word_count: u32,
+    backlinks: usize,
+}
+
+/// Duplicate pair output for JSON.
+#[derive(Debug, Serialize)]
+struct DuplicateOutput {
+    similarity: f64,
+    keep: PairNoteOutput,
+    merge: PairNoteOutput,
+    reason: &'static str,
+}
+
+/// A note of a duplicate pair with what the merge suggestion weighs.
+struct PairNote {
+    note: IndexedNote,
+    backlinks: usize,
+}
+
+/// A duplicate pair, ordered as a merge suggestion.
+struct Duplicate {
+    similarity: f64,
+    keep: PairNote,
+    merge: PairNote,
+    reason: &'static str,
+}
+
+pub fn run(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    args: DuplicatesArgs,
+) -> Result<()> {
+    let rc = load_config(config, profile)?;
+    let db = open_index_read_only(&rc.vault_root)?;
+    let format =
+        resolve_format(config, profile, args.output, false, args.json, args.quiet)?;
+
+    let pairs = db
+        .get_similar_pairs(args.threshold, args.limit)
+        .wrap_err("Error querying duplicate notes")?;
+
+    let mut duplicates = Vec::with_capacity(pairs.len());
+    for pair in pairs {
+        let (Some(a), Some(b)) =
+            (pair_note(&db, pair.note_a_id)?, pair_note(&db, pair.note_b_id)?)
+        else {
+            continue;
+        };
+        let (keep, merge, reason) = suggest_merge(a, b);
+        duplicates.push(Duplicate { similarity: pair.similarity, keep, merge, reason });
+    }
+
+    match format {
+        OutputFormat::Table => print_duplicates_table(&duplicates),
+        OutputFormat::Json | OutputFormat::StableJson => {
+            print_duplicates_json(&duplicates)
+        }
+        OutputFormat::Quiet => {
+            for dup in &duplicates {
+                println!(
+                    "{}\t{}",
+                    dup.keep.note.path.display(),
+                    dup.merge.note.path.display()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn pair_note(db: &IndexDb, id: i64) -> Result<Option<PairNote>> {
+    let Some(note) = db.get_note_by_id(id).wrap_err("Error reading note")? else {
+        return Ok(None);
+    };
+    let backlinks = db.get_backlinks(id).wrap_err("Error reading backlinks")?.len();
+    Ok(Some(PairNote { note, backlinks }))
+}
+
+/// Decide which note of a pair to keep, and why.
+///
+/// The note more of the vault links to wins, since merging into it leaves
+/// fewer links to update; then the longer note, which likely holds the
+/// fuller version of the idea; then the older one.
+fn suggest_merge(a: PairNote, b: PairNote) -> (PairNote, PairNote, &'static str) {
+    if a.backlinks != b.backlinks {
+        let reason = "more backlinks";
+        return if a.backlinks > b.backlinks { (a, b, reason) } else { (b, a, reason) };
+    }
+    if a.note.word_count != b.note.word_count {
+        let reason = "longer";
+        return if a.note.word_count > b.note.word_count {
+            (a, b, reason)
+        } else {
+            (b, a, reason)
+        };
+    }
+    if b.note.modified < a.note.modified { (b, a, "older") } else { (a, b, "older") }
+}
+
+fn print_duplicates_table(duplicates: &[Duplicate]) {
+    if duplicates.is_empty() {
+        println!("(no duplicate notes found)");
+        return;
+    }
+
+    let width = |f: fn(&Duplicate) -> usize| {
+        duplicates.iter().map(f).max().unwrap_or(4).clamp(4, 40)
+    };
+    let keep_width = width(|d| d.keep.note.path.to_string_lossy().len());
+    let merge_width = width(|d| d.merge.note.path.to_string_lossy().len());
+
+    println!("SIMILARITY  {:<keep_width$}  {:<merge_width$}  WHY", "KEEP", "MERGE");
+    println!("{:-<10}  {:-<keep_width$}  {:-<merge_width$}  {:-<14}", "", "", "", "");
+    for dup in duplicates {
+        println!(
+            "{:>9.0}%  {:<keep_width$}  {:<merge_width$}  {}",
+            dup.similarity * 100.0,
+            truncate(&dup.keep.note.path.to_string_lossy(), keep_width),
+            truncate(&dup.merge.note.path.to_string_lossy(), merge_width),
+            dup.reason,
+        );
+    }
+
+    println!();
+    println!("-- {} duplicate pairs --", duplicates.len());
+    println!(
+        "Fold each MERGE note into its KEEP note, point its backlinks at KEEP, \
+         then delete it."
+    );
+}
+
+fn print_duplicates_json(duplicates: &[Duplicate]) {
+    let side = |n: &PairNote| PairNoteOutput {
+        path: n.note.path.to_string_lossy().to_string(),
+        title: n.note.title.clone(),
+        word_count: n.note.word_count,
+        backlinks: n.backlinks,
+    };
+    let output: Vec<DuplicateOutput> = duplicates
+        .iter()
+        .map(|dup| DuplicateOutput {
+            similarity: dup.similarity,
+            keep: side(&dup.keep),
+            merge: side(&dup.merge),
+            reason: dup.reason,
+        })
+        .collect();
+    print_json(&output);
+}
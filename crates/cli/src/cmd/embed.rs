@@ -0,0 +1,45 @@
+//! Embed command implementation.
+
+use std::io::Write;
+use std::path::Path;
+
+use color_eyre::eyre::Result;
+use mdvault_core::embeddings;
+
+use super::common::{load_config, open_index};
+use super::output::print_json;
+use crate::EmbedArgs;
+
+pub fn run(config: Option<&Path>, profile: Option<&str>, args: EmbedArgs) -> Result<()> {
+    let rc = load_config(config, profile)?;
+    let db = open_index(&rc.vault_root)?;
+    let backend = embeddings::backend(&rc).map_err(|e| color_eyre::eyre::eyre!("{e}"))?;
+
+    if !args.json {
+        println!("Embedding notes with {}", backend.model_id());
+    }
+    let stats =
+        embeddings::embed_notes(&db, backend.as_ref(), args.force, |done, total| {
+            if !args.json {
+                print!("\rEmbedding... {}/{}", done, total);
+                std::io::stdout().flush().ok();
+            }
+        })
+        .map_err(|e| color_eyre::eyre::eyre!("{e}"))?;
+
+    if args.json {
+        print_json(&stats);
+        return Ok(());
+    }
+    if stats.embedded > 0 {
+        println!(); // Newline after progress
+    }
+    println!();
+    println!("Embedding complete:");
+    println!("  Embedded:   {}", stats.embedded);
+    println!("  Unchanged:  {}", stats.unchanged);
+    if stats.removed > 0 {
+        println!("  Removed:    {} (from another model)", stats.removed);
+    }
+    Ok(())
+}
@@ -0,0 +1,111 @@
+//! Expire command implementation.
+
+use std::path::Path;
+
+use chrono::Local;
+use color_eyre::eyre::{Result, WrapErr, bail, eyre};
+use mdvault_core::expire::{self, ExpireAction, ExpireError, MoveOutcome, find_expired};
+use mdvault_core::index::{IndexBuilder, IndexDb};
+use mdvault_core::types::TypedefRepository;
+
+use super::common::{load_config, open_index};
+use crate::ExpireArgs;
+
+/// Archive or trash expired notes, or undo the last run.
+pub fn run(config: Option<&Path>, profile: Option<&str>, args: ExpireArgs) -> Result<()> {
+    let cfg = load_config(config, profile)?;
+    let db = open_index(&cfg.vault_root)?;
+    let builder =
+        IndexBuilder::with_exclusions(&db, &cfg.vault_root, cfg.excluded_folders.clone());
+
+    if args.undo {
+        let outcome = match expire::undo_last_run(&cfg.vault_root) {
+            Err(ExpireError::NothingToUndo) => {
+                println!("Nothing to undo.");
+                return Ok(());
+            }
+            other => other.wrap_err("Failed to undo expiry")?,
+        };
+        for entry in &outcome.moved {
+            println!("restored  {}", entry.from.display());
+            // Reversed: the note now lives at `from` again
+            update_index(&db, &builder, &entry.to, Some(&entry.from));
+        }
+        return report_failures(&outcome, "restored");
+    }
+
+    // Pick up edits to expires/ttl made since the last index update
+    builder.incremental_reindex(None).wrap_err("Failed to update index")?;
+
+    let typedef_repo = match &cfg.typedefs_fallback_dir {
+        Some(fallback) => TypedefRepository::with_fallback(&cfg.typedefs_dir, fallback),
+        None => TypedefRepository::new(&cfg.typedefs_dir),
+    };
+    let typedefs = typedef_repo
+        .and_then(|repo| repo.load_all())
+        .map_err(|e| eyre!("Error loading type definitions: {e}"))?;
+
+    let scan = find_expired(&db, &typedefs, Local::now().date_naive())
+        .wrap_err("Failed to check note expiry")?;
+    for (path, problem) in &scan.invalid {
+        eprintln!("Warning: {}: {}", path.display(), problem);
+    }
+    if scan.expired.is_empty() {
+        println!("No expired notes.");
+        return Ok(());
+    }
+
+    if args.dry_run {
+        for note in &scan.expired {
+            println!(
+                "{:<8}  {}  (expired {}, {})",
+                note.action.as_str(),
+                note.path.display(),
+                note.expired_on,
+                note.reason
+            );
+        }
+        let count = scan.expired.len();
+        println!("\n{count} note(s) would be moved. Run without --dry-run to apply.");
+        return Ok(());
+    }
+
+    let run = Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let outcome = expire::expire_notes(&cfg.vault_root, &scan.expired, &run)
+        .wrap_err("Failed to expire notes")?;
+    for entry in &outcome.moved {
+        let (from, to) = (entry.from.display(), entry.to.display());
+        println!("{:<8}  {from} -> {to}", entry.action.as_str());
+        let archived =
+            (entry.action == ExpireAction::Archive).then_some(entry.to.as_path());
+        update_index(&db, &builder, &entry.from, archived);
+    }
+    if !outcome.moved.is_empty() {
+        println!("\nUndo with `mdv expire --undo`.");
+    }
+    report_failures(&outcome, "moved")
+}
+
+/// Drop `old` from the index and index the note at `new`, if it is still in
+/// the vault (trashed notes are not).
+fn update_index(db: &IndexDb, builder: &IndexBuilder, old: &Path, new: Option<&Path>) {
+    if let Err(e) = db.delete_note(old) {
+        eprintln!("Warning: failed to update index for {}: {e}", old.display());
+    }
+    if let Some(new) = new
+        && !new.starts_with(".mdvault")
+        && let Err(e) = builder.reindex_file(new)
+    {
+        eprintln!("Warning: failed to index {}: {e}", new.display());
+    }
+}
+
+fn report_failures(outcome: &MoveOutcome, verb: &str) -> Result<()> {
+    for (path, e) in &outcome.failed {
+        eprintln!("FAIL {}: {e}", path.display());
+    }
+    if !outcome.failed.is_empty() {
+        bail!("{} note(s) could not be {verb}", outcome.failed.len());
+    }
+    Ok(())
+}
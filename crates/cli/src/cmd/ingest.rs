@@ -0,0 +1,201 @@
+//! Ingest command implementation.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, NaiveDateTime};
+use color_eyre::eyre::{Result, WrapErr, bail};
+use mdvault_core::activity::ActivityLogService;
+use mdvault_core::config::types::ResolvedConfig;
+use mdvault_core::index::{IndexBuilder, IndexDb};
+use mdvault_core::ingest::{self, Snippet, SnippetLedger, SnippetOutcome, VoiceMemo};
+use mdvault_core::paths::PathResolver;
+
+use super::common::load_config;
+use crate::{InboxIngestArgs, IngestAudioArgs};
+
+/// Transcribe each audio file, archive it, and file the transcript in the inbox.
+pub fn audio(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    args: IngestAudioArgs,
+) -> Result<()> {
+    let cfg = load_config(config, profile)?;
+
+    let Some(transcriber) = args.transcriber.or_else(|| cfg.ingest.transcriber.clone())
+    else {
+        bail!(
+            "No transcriber configured.\n\
+             Pass --transcriber '<cmd> {{file}}' or set `transcriber` under [ingest] \
+             in your config."
+        );
+    };
+    let capture = args.capture.or_else(|| cfg.ingest.capture.clone());
+    if args.title.is_some() && args.files.len() > 1 {
+        bail!(
+            "--title applies to a single file; ingest files one at a time to title them"
+        );
+    }
+
+    for file in &args.files {
+        let transcript = ingest::transcribe(&transcriber, file)
+            .wrap_err_with(|| format!("Failed to transcribe {}", file.display()))?;
+
+        let memo = VoiceMemo {
+            audio: file,
+            transcript: &transcript,
+            title: args.title.as_deref(),
+            recorded: recorded_at(file),
+        };
+        let result = ingest::ingest_audio(&cfg.vault_root, &memo)
+            .wrap_err_with(|| format!("Failed to ingest {}", file.display()))?;
+
+        let rel = record_new_note(
+            &cfg,
+            "voice-memo",
+            &result.id,
+            &result.title,
+            &result.note_path,
+        );
+
+        println!("OK   mdv ingest audio");
+        println!("note:  {}", result.note_path.display());
+        println!("audio: {}", result.asset_path.display());
+
+        if args.move_original
+            && let Err(e) = std::fs::remove_file(file)
+        {
+            eprintln!("Warning: could not remove {}: {e}", file.display());
+        }
+
+        if let Some(name) = &capture {
+            capture_link(config, profile, name, &rel, &result.title).wrap_err_with(
+                || format!("Voice memo saved, but capture '{name}' failed"),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// File each text snippet in a drop folder as an inbox note and remove it.
+pub fn snippets(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    args: InboxIngestArgs,
+) -> Result<()> {
+    let cfg = load_config(config, profile)?;
+    let capture = args.capture.or_else(|| cfg.ingest.capture.clone());
+    let mut ledger =
+        SnippetLedger::open(&cfg.vault_root).wrap_err("Failed to read inbox ledger")?;
+
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&args.dir)
+        .wrap_err_with(|| format!("Failed to read {}", args.dir.display()))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && ingest::is_snippet_file(p))
+        .collect();
+    files.sort();
+
+    let (mut created, mut duplicates, mut failed) = (0, 0, 0);
+    for file in &files {
+        let name = file.file_name().unwrap_or_default().to_string_lossy();
+        let outcome = std::fs::read_to_string(file)
+            .wrap_err("not readable as UTF-8 text")
+            .and_then(|text| {
+                let snippet =
+                    Snippet { source: file, text: &text, received: recorded_at(file) };
+                Ok(ingest::ingest_snippet(&cfg.vault_root, &mut ledger, &snippet)?)
+            });
+
+        match outcome {
+            Ok(SnippetOutcome::Created(result)) => {
+                created += 1;
+                let rel = record_new_note(
+                    &cfg,
+                    "inbox",
+                    &result.id,
+                    &result.title,
+                    &result.note_path,
+                );
+                println!("OK    {name} -> {}", rel.display());
+                if let Some(capture) = &capture
+                    && let Err(e) =
+                        capture_link(config, profile, capture, &rel, &result.title)
+                {
+                    eprintln!("Warning: capture '{capture}' failed for {name}: {e:#}");
+                }
+            }
+            Ok(SnippetOutcome::Duplicate(note)) => {
+                duplicates += 1;
+                println!("DUP   {name} (already filed as {})", note.display());
+            }
+            Err(e) => {
+                failed += 1;
+                println!("FAIL  {name}: {e:#}");
+                // Leave it in the folder to retry or fix by hand
+                continue;
+            }
+        }
+
+        if !args.keep
+            && let Err(e) = std::fs::remove_file(file)
+        {
+            eprintln!("Warning: could not remove {}: {e}", file.display());
+        }
+    }
+
+    println!();
+    println!("-- {created} filed, {duplicates} duplicate, {failed} failed --");
+    if failed > 0 {
+        bail!("{failed} snippet(s) could not be filed");
+    }
+    Ok(())
+}
+
+/// Log and index a newly written note; returns its vault-relative path.
+pub(super) fn record_new_note(
+    cfg: &ResolvedConfig,
+    note_type: &str,
+    id: &str,
+    title: &str,
+    note_path: &Path,
+) -> PathBuf {
+    if let Some(activity) = ActivityLogService::try_from_config(cfg) {
+        let _ = activity.log_new(note_type, id, note_path, Some(title));
+    }
+
+    let rel = note_path.strip_prefix(&cfg.vault_root).unwrap_or(note_path).to_path_buf();
+    let index_path = PathResolver::new(&cfg.vault_root).index_db();
+    if let Ok(db) = IndexDb::open(&index_path) {
+        let builder = IndexBuilder::new(&db, &cfg.vault_root);
+        if let Err(e) = builder.reindex_file(&rel) {
+            eprintln!("Warning: failed to update index: {e}");
+        }
+    }
+    rel
+}
+
+/// Run `name` with a wikilink to the note at `rel` as its `text`.
+pub(super) fn capture_link(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    name: &str,
+    rel: &Path,
+    title: &str,
+) -> Result<()> {
+    let link = rel.with_extension("");
+    let vars = vec![
+        ("text".to_string(), format!("[[{}|{}]]", link.to_string_lossy(), title)),
+        ("title".to_string(), title.to_string()),
+        ("note".to_string(), rel.to_string_lossy().to_string()),
+    ];
+    super::capture::run(config, profile, name, &vars, true, false)?;
+    Ok(())
+}
+
+/// The file's modification time, which for most recorders (and sync tools)
+/// is when it was made; falls back to now.
+fn recorded_at(file: &Path) -> NaiveDateTime {
+    std::fs::metadata(file)
+        .and_then(|m| m.modified())
+        .map(|t| DateTime::<Local>::from(t).naive_local())
+        .unwrap_or_else(|_| Local::now().naive_local())
+}
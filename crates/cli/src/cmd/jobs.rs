@@ -0,0 +1,140 @@
+//! Jobs command: list and run the scheduled Lua jobs of `.mdvault/jobs.lua`.
+
+use std::path::Path;
+
+use chrono::{Local, NaiveDateTime};
+use color_eyre::eyre::{Report, Result, bail, eyre};
+use mdvault_core::jobs::{Job, JobError, JobState, Jobs};
+use serde::Serialize;
+
+use super::common::{load_config, vault_context};
+use super::output::print_json;
+use crate::JobsCommands;
+
+/// One job as shown by `mdv jobs list --json`.
+#[derive(Debug, Serialize)]
+struct JobSummary<'a> {
+    name: &'a str,
+    every: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    last_run: Option<NaiveDateTime>,
+    next_run: NaiveDateTime,
+    due: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_error: Option<&'a str>,
+}
+
+pub fn run(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    command: JobsCommands,
+) -> Result<()> {
+    let cfg = load_config(config, profile)?;
+    let jobs = Jobs::load(vault_context(&cfg)?).map_err(report)?;
+    let mut state = JobState::load(&cfg.vault_root).map_err(report)?;
+    let now = Local::now().naive_local();
+
+    match command {
+        JobsCommands::List(args) => list(&jobs, &state, now, args.json),
+        JobsCommands::RunDue(args) => {
+            let due = jobs.due(&state, now);
+            if due.is_empty() {
+                println!("No jobs due.");
+                return Ok(());
+            }
+            if args.dry_run {
+                for job in &due {
+                    println!("Would run {} ({})", job.name, job.every);
+                }
+                return Ok(());
+            }
+            run_jobs(&jobs, &due, &mut state, &cfg.vault_root)
+        }
+        JobsCommands::Run(args) => {
+            let job = jobs.get(&args.name).map_err(report)?;
+            run_jobs(&jobs, &[job], &mut state, &cfg.vault_root)
+        }
+    }
+}
+
+/// Run `due` in order, recording each run as it finishes.
+fn run_jobs(
+    jobs: &Jobs,
+    due: &[&Job],
+    state: &mut JobState,
+    vault_root: &Path,
+) -> Result<()> {
+    let mut failed = 0;
+    for job in due {
+        let started = Local::now().naive_local();
+        match jobs.run(job, state) {
+            Ok(output) => {
+                println!("OK   {}", job.name);
+                if let Some(output) = output {
+                    println!("{output}");
+                }
+                state.record(&job.name, started, None);
+            }
+            Err(e) => {
+                eprintln!("FAIL {}: {e}", job.name);
+                state.record(&job.name, started, Some(e.to_string()));
+                failed += 1;
+            }
+        }
+        state.save(vault_root).map_err(report)?;
+    }
+    if failed > 0 {
+        bail!("{failed} job(s) failed");
+    }
+    Ok(())
+}
+
+fn list(jobs: &Jobs, state: &JobState, now: NaiveDateTime, json: bool) -> Result<()> {
+    let summaries: Vec<JobSummary> = jobs
+        .jobs()
+        .iter()
+        .map(|job| {
+            let last_run = state.last_run(&job.name);
+            JobSummary {
+                name: &job.name,
+                every: &job.every,
+                description: job.description.as_deref(),
+                last_run,
+                next_run: job.schedule.next_run(last_run, now),
+                due: job.schedule.is_due(last_run, now),
+                last_error: state
+                    .jobs
+                    .get(&job.name)
+                    .and_then(|r| r.last_error.as_deref()),
+            }
+        })
+        .collect();
+
+    if json {
+        print_json(&summaries);
+        return Ok(());
+    }
+    if summaries.is_empty() {
+        println!("No jobs defined. Add them to .mdvault/jobs.lua.");
+        return Ok(());
+    }
+    let fmt = |t: NaiveDateTime| t.format("%Y-%m-%d %H:%M").to_string();
+    for job in &summaries {
+        let last = job.last_run.map(fmt).unwrap_or_else(|| "never".to_string());
+        let next = if job.due { "due now".to_string() } else { fmt(job.next_run) };
+        println!("{:<20} {:<18} last: {:<16}  next: {next}", job.name, job.every, last);
+        if let Some(description) = job.description {
+            println!("  {description}");
+        }
+        if let Some(error) = job.last_error {
+            println!("  last run failed: {error}");
+        }
+    }
+    Ok(())
+}
+
+/// Job errors carry Lua errors, which are not `Send`.
+fn report(e: JobError) -> Report {
+    eyre!("{e}")
+}
@@ -0,0 +1,162 @@
+//! Periodic journal notes: open or create daily and weekly notes.
+
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use color_eyre::eyre::{Result, eyre};
+use mdvault_core::config::types::ResolvedConfig;
+use mdvault_core::domain::{CreationContext, NoteType as DomainNoteType};
+use mdvault_core::vars::datemath::try_evaluate_date_expr;
+
+use super::common::{load_config, load_types, open_in_editor};
+use crate::{JournalArgs, JournalCommands, NewArgs};
+
+/// The journal period a note covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Period {
+    Day,
+    Week,
+}
+
+impl Period {
+    fn type_name(self) -> &'static str {
+        match self {
+            Self::Day => "daily",
+            Self::Week => "weekly",
+        }
+    }
+}
+
+/// Run the journal command.
+pub fn run(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    args: JournalArgs,
+) -> Result<()> {
+    let cfg = load_config(config, profile)?;
+
+    let period = match args.command {
+        Some(JournalCommands::Week) => Period::Week,
+        Some(JournalCommands::Day) | None => Period::Day,
+    };
+    let key = resolve_period_key(period, args.date.as_deref())?;
+    let path = ensure_note(config, profile, &cfg, period, &key, args.batch)?;
+
+    if args.no_open {
+        println!("{}", path.display());
+        Ok(())
+    } else {
+        open_in_editor(&path)
+    }
+}
+
+/// Return the path of the periodic note for `key`, creating it first if missing.
+///
+/// Creation goes through `mdv new` so templates, hooks, and reindexing apply.
+pub(crate) fn ensure_note(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    cfg: &ResolvedConfig,
+    period: Period,
+    key: &str,
+    batch: bool,
+) -> Result<PathBuf> {
+    let path = resolve_note_path(cfg, period, key)?;
+
+    if !path.exists() {
+        super::new::run(
+            config,
+            profile,
+            NewArgs {
+                note_type: Some(period.type_name().to_string()),
+                title: Some(key.to_string()),
+                template: None,
+                output: Some(path.clone()),
+                vars: Vec::new(),
+                batch,
+                dry_run: false,
+            },
+        )?;
+    }
+    Ok(path)
+}
+
+/// Resolve the user's date input into the note key for a period.
+///
+/// Daily notes are keyed by `YYYY-MM-DD`, weekly notes by `YYYY-Wxx`.
+/// Without input, the current day or week is used.
+fn resolve_period_key(period: Period, input: Option<&str>) -> Result<String> {
+    let Some(input) = input.map(str::trim).filter(|s| !s.is_empty()) else {
+        let fmt = match period {
+            Period::Day => "%Y-%m-%d",
+            Period::Week => "%G-W%V",
+        };
+        return Ok(Local::now().format(fmt).to_string());
+    };
+
+    let expr = match period {
+        Period::Day => input.to_string(),
+        Period::Week if input.contains('|') => input.to_string(),
+        Period::Week => format!("{input} | %G-W%V"),
+    };
+
+    try_evaluate_date_expr(&expr).ok_or_else(|| {
+        eyre!(
+            "Invalid date: '{}'. Use YYYY-MM-DD, YYYY-Wxx, 'today', 'yesterday', or a date expression",
+            input
+        )
+    })
+}
+
+/// Resolve where the periodic note lives, honouring typedef `output` overrides.
+fn resolve_note_path(cfg: &ResolvedConfig, period: Period, key: &str) -> Result<PathBuf> {
+    let registry = load_types(cfg);
+    let note_type = DomainNoteType::from_name(period.type_name(), &registry)
+        .map_err(|e| eyre!("{e}"))?;
+    let behavior = note_type.behavior();
+
+    let mut ctx = CreationContext::new(period.type_name(), key, cfg, &registry)
+        .with_batch_mode(true);
+    behavior.before_create(&mut ctx).map_err(|e| eyre!("{e}"))?;
+    behavior.output_path(&ctx).map_err(|e| eyre!("{e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day_key_from_literal_date() {
+        assert_eq!(
+            resolve_period_key(Period::Day, Some("2026-03-15")).unwrap(),
+            "2026-03-15"
+        );
+    }
+
+    #[test]
+    fn week_key_from_date() {
+        assert_eq!(
+            resolve_period_key(Period::Week, Some("2026-03-23")).unwrap(),
+            "2026-W13"
+        );
+    }
+
+    #[test]
+    fn week_key_from_iso_week() {
+        assert_eq!(
+            resolve_period_key(Period::Week, Some("2026-W05")).unwrap(),
+            "2026-W05"
+        );
+    }
+
+    #[test]
+    fn default_key_is_current_period() {
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        assert_eq!(resolve_period_key(Period::Day, None).unwrap(), today);
+    }
+
+    #[test]
+    fn invalid_date_is_rejected() {
+        assert!(resolve_period_key(Period::Day, Some("not a date")).is_err());
+    }
+}
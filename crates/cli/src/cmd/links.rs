@@ -2,19 +2,57 @@
 
 use std::path::Path;
 
-use super::common::{load_config, open_index};
+use super::common::{load_config, open_index_read_only};
 use super::output::{
-    LinkOutput, print_links_json, print_links_quiet, print_links_table, resolve_format,
+    LinkOutput, print_json, print_links_json, print_links_quiet, print_links_table,
+    resolve_format, sort_links_stable, truncate,
 };
 use crate::{LinksArgs, OutputFormat};
 use color_eyre::eyre::{Result, WrapErr};
+use mdvault_core::index::{
+    IndexDb, IndexedLink, LinkDirection, LinkType, ReachedNote, walk_links,
+};
+use serde::Serialize;
+
+/// Links covered by the `--stable-json` guarantee.
+///
+/// A single document with a key per requested direction, each sorted by
+/// source path, target path, and line.
+#[derive(Debug, Default, Serialize)]
+struct StableLinksOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backlinks: Option<Vec<LinkOutput>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    outgoing: Option<Vec<LinkOutput>>,
+}
+
+/// A note reached by `--depth`, for JSON.
+#[derive(Debug, Serialize)]
+struct ReachedOutput {
+    path: String,
+    title: String,
+    #[serde(rename = "type")]
+    note_type: String,
+    depth: usize,
+    /// Paths from the starting note to this one.
+    route: Vec<String>,
+}
+
+/// Notes reached by `--depth`, a key per requested direction.
+#[derive(Debug, Default, Serialize)]
+struct ReachedNotesOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backlinks: Option<Vec<ReachedOutput>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    outgoing: Option<Vec<ReachedOutput>>,
+}
 
 pub fn run(config: Option<&Path>, profile: Option<&str>, args: LinksArgs) -> Result<()> {
     // Load configuration
     let rc = load_config(config, profile)?;
 
     // Open database
-    let db = open_index(&rc.vault_root)?;
+    let db = open_index_read_only(&rc.vault_root)?;
 
     // Normalize the note path (strip leading ./)
     let note_path = normalize_path(&args.note);
@@ -37,13 +75,40 @@ pub fn run(config: Option<&Path>, profile: Option<&str>, args: LinksArgs) -> Res
     let show_outlinks = args.outlinks || !args.backlinks;
 
     // Resolve output format
-    let format = resolve_format(args.output, args.json, args.quiet);
+    let format = resolve_format(
+        config,
+        profile,
+        args.output,
+        args.stable_json,
+        args.json,
+        args.quiet,
+    )?;
+
+    if let Some(depth) = args.depth {
+        let directions = [
+            (show_backlinks, LinkDirection::Incoming),
+            (show_outlinks, LinkDirection::Outgoing),
+        ];
+        let link_type = args.embeds.then_some(LinkType::Embed);
+        return print_walks(
+            &db,
+            note_id,
+            &note_path,
+            depth,
+            &directions,
+            link_type,
+            format,
+        );
+    }
+
+    let mut stable = StableLinksOutput::default();
 
     // Get and display backlinks
     if show_backlinks {
         let links = db.get_backlinks(note_id).wrap_err("Error getting backlinks")?;
         let outputs: Vec<LinkOutput> = links
             .iter()
+            .filter(|l| !args.embeds || l.link_type == LinkType::Embed)
             .map(|l| {
                 // Look up source note path
                 let source_path = db
@@ -51,17 +116,26 @@ pub fn run(config: Option<&Path>, profile: Option<&str>, args: LinksArgs) -> Res
                     .ok()
                     .flatten()
                     .map(|n| n.path.to_string_lossy().to_string());
-                LinkOutput::from_link(l, source_path.as_deref())
+                with_anchor_status(
+                    &db,
+                    l,
+                    LinkOutput::from_link(l, source_path.as_deref()),
+                )
             })
             .collect();
 
-        if show_outlinks && !matches!(format, OutputFormat::Json) {
-            println!("=== Backlinks (notes linking to {}) ===", note_path);
+        if show_outlinks && matches!(format, OutputFormat::Table | OutputFormat::Quiet) {
+            if args.embeds {
+                println!("=== Embedded in (notes embedding {}) ===", note_path);
+            } else {
+                println!("=== Backlinks (notes linking to {}) ===", note_path);
+            }
             println!();
         }
         match format {
             OutputFormat::Table => print_links_table(&outputs, "backlinks"),
             OutputFormat::Json => print_links_json(&outputs),
+            OutputFormat::StableJson => stable.backlinks = Some(outputs),
             OutputFormat::Quiet => print_links_quiet(&outputs, true),
         }
     }
@@ -70,24 +144,155 @@ pub fn run(config: Option<&Path>, profile: Option<&str>, args: LinksArgs) -> Res
     if show_outlinks {
         let links =
             db.get_outgoing_links(note_id).wrap_err("Error getting outgoing links")?;
-        let outputs: Vec<LinkOutput> =
-            links.iter().map(|l| LinkOutput::from_link(l, Some(&note_path))).collect();
+        let outputs: Vec<LinkOutput> = links
+            .iter()
+            .filter(|l| !args.embeds || l.link_type == LinkType::Embed)
+            .map(|l| {
+                with_anchor_status(&db, l, LinkOutput::from_link(l, Some(&note_path)))
+            })
+            .collect();
 
-        if show_backlinks && !matches!(format, OutputFormat::Json) {
+        if show_backlinks && matches!(format, OutputFormat::Table | OutputFormat::Quiet) {
             println!();
-            println!("=== Outgoing links (notes {} links to) ===", note_path);
+            if args.embeds {
+                println!("=== Embeds (notes {} embeds) ===", note_path);
+            } else {
+                println!("=== Outgoing links (notes {} links to) ===", note_path);
+            }
             println!();
         }
         match format {
             OutputFormat::Table => print_links_table(&outputs, "outgoing links"),
             OutputFormat::Json => print_links_json(&outputs),
+            OutputFormat::StableJson => stable.outgoing = Some(outputs),
             OutputFormat::Quiet => print_links_quiet(&outputs, false),
         }
     }
 
+    if matches!(format, OutputFormat::StableJson) {
+        for links in [&mut stable.backlinks, &mut stable.outgoing].into_iter().flatten() {
+            sort_links_stable(links);
+        }
+        print_json(&stable);
+    }
+
+    Ok(())
+}
+
+/// `--depth`: the notes reached in each requested direction.
+fn print_walks(
+    db: &IndexDb,
+    note_id: i64,
+    note_path: &str,
+    depth: u32,
+    directions: &[(bool, LinkDirection)],
+    link_type: Option<LinkType>,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut output = ReachedNotesOutput::default();
+    let both = directions.iter().all(|(show, _)| *show);
+    for &(show, direction) in directions {
+        if !show {
+            continue;
+        }
+        let reached = walk_links(db, note_id, direction, depth as usize, link_type)
+            .wrap_err("Error walking links")?;
+        match format {
+            OutputFormat::Table | OutputFormat::Quiet => {
+                if both {
+                    if direction == LinkDirection::Outgoing {
+                        println!();
+                    }
+                    let what = match direction {
+                        LinkDirection::Incoming => "Backlinks",
+                        LinkDirection::Outgoing => "Outgoing links",
+                    };
+                    println!("=== {what} within {depth} hops of {note_path} ===");
+                    println!();
+                }
+                if matches!(format, OutputFormat::Quiet) {
+                    for r in &reached {
+                        println!("{}", r.note.path.display());
+                    }
+                } else {
+                    print_reached_table(&reached, direction);
+                }
+            }
+            OutputFormat::Json | OutputFormat::StableJson => {
+                let items = reached.into_iter().map(ReachedOutput::from).collect();
+                match direction {
+                    LinkDirection::Incoming => output.backlinks = Some(items),
+                    LinkDirection::Outgoing => output.outgoing = Some(items),
+                }
+            }
+        }
+    }
+    if matches!(format, OutputFormat::Json | OutputFormat::StableJson) {
+        print_json(&output);
+    }
     Ok(())
 }
 
+impl From<ReachedNote> for ReachedOutput {
+    fn from(r: ReachedNote) -> Self {
+        Self {
+            path: r.note.path.to_string_lossy().to_string(),
+            title: r.note.title,
+            note_type: r.note.note_type.as_str().to_string(),
+            depth: r.depth,
+            route: r.route.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+        }
+    }
+}
+
+/// Print notes reached by `--depth`, with the route that reached each.
+fn print_reached_table(reached: &[ReachedNote], direction: LinkDirection) {
+    if reached.is_empty() {
+        println!("(no linked notes found)");
+        return;
+    }
+
+    let path_width = reached
+        .iter()
+        .map(|r| r.note.path.to_string_lossy().len())
+        .max()
+        .unwrap_or(4)
+        .clamp(4, 40);
+    // Backlink routes read towards the starting note
+    let arrow = match direction {
+        LinkDirection::Outgoing => " -> ",
+        LinkDirection::Incoming => " <- ",
+    };
+
+    println!("DEPTH  {:<path_width$}  ROUTE", "PATH");
+    println!("{:-<5}  {:-<path_width$}  {:-<20}", "", "", "");
+    for r in reached {
+        let route: Vec<String> =
+            r.route.iter().map(|p| p.to_string_lossy().to_string()).collect();
+        println!(
+            "{:<5}  {:<path_width$}  {}",
+            r.depth,
+            truncate(&r.note.path.to_string_lossy(), path_width),
+            route.join(arrow),
+        );
+    }
+
+    println!();
+    println!("-- {} notes --", reached.len());
+}
+
+/// Record whether a link's `#heading` or `^block` exists in its (resolved) target.
+fn with_anchor_status(
+    db: &IndexDb,
+    link: &IndexedLink,
+    mut output: LinkOutput,
+) -> LinkOutput {
+    if link.target_anchor.is_some() && link.target_id.is_some() {
+        output.anchor_found = Some(matches!(db.resolve_anchor(link), Ok(Some(_))));
+    }
+    output
+}
+
 /// Normalize note path by removing leading ./.
 fn normalize_path(path: &str) -> String {
     path.strip_prefix("./").unwrap_or(path).to_string()
@@ -4,40 +4,181 @@ use std::path::Path;
 
 use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use color_eyre::eyre::{Result, WrapErr};
-use mdvault_core::index::NoteQuery;
+use mdvault_core::index::{
+    FederatedIndex, NoteQuery, NoteTombstone, VaultHit, query_fingerprint,
+};
 use mdvault_core::vars::try_evaluate_date_expr;
+use serde::Serialize;
 
-use super::common::{load_config, open_index};
+use super::common::{exclude_rules, open_vaults};
 use super::output::{
-    print_notes_json, print_notes_quiet, print_notes_table, resolve_format,
+    print_json, print_next_cursor, print_notes_outline, print_notes_quiet,
+    print_notes_table, print_vault_notes_json, print_vault_notes_stable_json,
+    resolve_format, take_page, truncate, vault_label,
 };
 use crate::{ListArgs, OutputFormat};
 
 pub fn run(config: Option<&Path>, profile: Option<&str>, args: ListArgs) -> Result<()> {
-    let rc = load_config(config, profile)?;
-    let db = open_index(&rc.vault_root)?;
+    let index = open_vaults(config, profile, &args.vaults, args.all_vaults)?;
+    let federated = args.all_vaults || !args.vaults.is_empty();
+    let format = resolve_format(
+        config,
+        profile,
+        args.output,
+        args.stable_json,
+        args.json,
+        args.quiet,
+    )?;
+
+    if args.deleted {
+        return list_deleted(&index, federated, args.limit, format);
+    }
+
+    let exclude = exclude_rules(&args.exclude)?;
 
-    // Build query
+    // Build query; with exclusions the limit applies after filtering
     let query = NoteQuery {
         note_type: args.r#type.map(|t| t.into()),
         path_prefix: None,
         modified_after: parse_date_arg(&args.modified_after, "modified-after"),
         modified_before: parse_date_arg(&args.modified_before, "modified-before"),
-        limit: args.limit,
+        limit: args.limit.filter(|_| exclude.is_empty()),
         offset: None,
+        fields: args.filters,
     };
 
     // Execute query
-    let notes = db.query_notes(&query).wrap_err("Error querying notes")?;
+    let mut hits = index.query_notes(&query).wrap_err("Error querying notes")?;
+    if !exclude.is_empty() {
+        hits.retain(|hit| !exclude.is_ignored(&hit.item.path, false));
+        if let Some(limit) = args.limit {
+            hits.truncate(limit as usize);
+        }
+    }
 
-    // Determine output format
-    let format = resolve_format(args.output, args.json, args.quiet);
+    // Page in the order the output uses
+    let stable = matches!(format, OutputFormat::StableJson);
+    if stable {
+        hits.sort_by(|a, b| (&a.vault, &a.item.path).cmp(&(&b.vault, &b.item.path)));
+    }
+    let fingerprint = query_fingerprint(&format!(
+        "list {query:?} {:?} {:?} {} {stable}",
+        args.exclude, args.vaults, args.all_vaults
+    ));
+    let (hits, paged) =
+        take_page(hits, args.cursor.as_deref(), args.page_size, fingerprint)?;
 
     // Output results
     match format {
-        OutputFormat::Table => print_notes_table(&notes),
-        OutputFormat::Json => print_notes_json(&notes),
-        OutputFormat::Quiet => print_notes_quiet(&notes),
+        OutputFormat::Json => print_vault_notes_json(&hits, paged.as_ref()),
+        OutputFormat::StableJson => {
+            print_vault_notes_stable_json(&hits, federated, paged.as_ref())
+        }
+        _ => {
+            let notes: Vec<_> = hits
+                .into_iter()
+                .map(|mut hit| {
+                    if federated {
+                        vault_label(&hit.vault, &mut hit.item);
+                    }
+                    hit.item
+                })
+                .collect();
+            match format {
+                OutputFormat::Table if args.outline => print_notes_outline(&notes),
+                OutputFormat::Quiet => print_notes_quiet(&notes),
+                _ => print_notes_table(&notes),
+            }
+            print_next_cursor(paged.as_ref());
+        }
+    }
+
+    Ok(())
+}
+
+/// Tombstone output for JSON.
+#[derive(Debug, Serialize)]
+struct DeletedNoteOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vault: Option<String>,
+    path: String,
+    #[serde(rename = "type")]
+    note_type: String,
+    title: String,
+    deleted_at: String,
+    backlinks: usize,
+}
+
+/// List tombstones of notes that vanished from the vault.
+fn list_deleted(
+    index: &FederatedIndex,
+    federated: bool,
+    limit: Option<u32>,
+    format: OutputFormat,
+) -> Result<()> {
+    let hits = index.tombstones(limit).wrap_err("Error querying deleted notes")?;
+    let label = |hit: &VaultHit<NoteTombstone>| {
+        if federated {
+            format!("{}:{}", hit.vault, hit.item.path.display())
+        } else {
+            hit.item.path.display().to_string()
+        }
+    };
+
+    match format {
+        OutputFormat::Json | OutputFormat::StableJson => {
+            let stable = matches!(format, OutputFormat::StableJson);
+            let mut output: Vec<DeletedNoteOutput> = hits
+                .iter()
+                .map(|hit| DeletedNoteOutput {
+                    vault: (federated || !stable).then(|| hit.vault.clone()),
+                    path: hit.item.path.to_string_lossy().to_string(),
+                    note_type: hit.item.note_type.as_str().to_string(),
+                    title: hit.item.title.clone(),
+                    deleted_at: hit.item.deleted_at.to_rfc3339(),
+                    backlinks: hit.item.backlinks,
+                })
+                .collect();
+            if stable {
+                output.sort_by(|a, b| (&a.vault, &a.path).cmp(&(&b.vault, &b.path)));
+            }
+            print_json(&output);
+        }
+        OutputFormat::Quiet => {
+            for hit in &hits {
+                println!("{}", label(hit));
+            }
+        }
+        OutputFormat::Table => {
+            if hits.is_empty() {
+                println!("(no deleted notes)");
+                return Ok(());
+            }
+            let path_width =
+                hits.iter().map(|h| label(h).len()).max().unwrap_or(4).clamp(4, 50);
+            let title_width =
+                hits.iter().map(|h| h.item.title.len()).max().unwrap_or(5).clamp(5, 40);
+            println!(
+                "{:<path_width$}  {:<8}  {:<title_width$}  {:<16}  BACKLINKS",
+                "PATH", "TYPE", "TITLE", "DELETED"
+            );
+            println!(
+                "{:-<path_width$}  {:-<8}  {:-<title_width$}  {:-<16}  {:-<9}",
+                "", "", "", "", ""
+            );
+            for hit in &hits {
+                println!(
+                    "{:<path_width$}  {:<8}  {:<title_width$}  {:<16}  {}",
+                    truncate(&label(hit), path_width),
+                    hit.item.note_type.as_str(),
+                    truncate(&hit.item.title, title_width),
+                    hit.item.deleted_at.format("%Y-%m-%d %H:%M"),
+                    hit.item.backlinks,
+                );
+            }
+            println!();
+            println!("-- {} deleted notes --", hits.len());
+        }
     }
 
     Ok(())
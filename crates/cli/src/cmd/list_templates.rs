@@ -1,11 +1,25 @@
-use super::common::load_config;
-use color_eyre::eyre::{Result, WrapErr};
+use super::common::{load_config, load_types};
+use crate::args::ListTemplatesArgs;
+use color_eyre::eyre::{Result, WrapErr, bail};
+use mdvault_core::config::types::ResolvedConfig;
 use mdvault_core::templates::discovery::discover_templates;
+use mdvault_core::templates::lint::{TemplateLint, lint_template};
+use mdvault_core::templates::repository::{LoadedTemplate, TemplateRepository};
+use mdvault_core::types::discovery::load_typedef_from_file;
+use mdvault_core::types::{TypeDefinition, TypeRegistry};
 use std::path::Path;
 
-pub fn run(config: Option<&Path>, profile: Option<&str>) -> Result<()> {
+pub fn run(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    args: ListTemplatesArgs,
+) -> Result<()> {
     let rc = load_config(config, profile)?;
 
+    if args.check {
+        return check(&rc, args.json);
+    }
+
     let list =
         discover_templates(&rc.templates_dir).wrap_err("Failed to discover templates")?;
 
@@ -20,3 +34,76 @@ pub fn run(config: Option<&Path>, profile: Option<&str>) -> Result<()> {
 
     Ok(())
 }
+
+/// Lint every template and report the problems.
+fn check(rc: &ResolvedConfig, json: bool) -> Result<()> {
+    let repo = TemplateRepository::new(&rc.templates_dir)
+        .wrap_err("Failed to discover templates")?;
+    let registry = load_types(rc);
+    let results: Vec<TemplateLint> = repo
+        .list_all()
+        .iter()
+        .map(|info| lint_template(&repo, info, |t| typedef_for(rc, &registry, t)))
+        .collect();
+    let failed = results.iter().filter(|r| !r.errors.is_empty()).count();
+
+    if json {
+        let output = serde_json::json!({
+            "templates": results,
+            "summary": {
+                "total": results.len(),
+                "failed": failed,
+                "warnings": results.iter().map(|r| r.warnings.len()).sum::<usize>(),
+            },
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else if results.is_empty() {
+        println!("(no templates found)");
+    } else {
+        for result in &results {
+            let status = match (result.errors.is_empty(), result.warnings.is_empty()) {
+                (true, true) => "ok",
+                (true, false) => "warn",
+                (false, _) => "FAILED",
+            };
+            println!("{status:<8}{}", result.name);
+            for error in &result.errors {
+                println!("    error: {error}");
+            }
+            for warning in &result.warnings {
+                println!("    warning: {warning}");
+            }
+        }
+        println!();
+        println!(
+            "{} template(s): {} ok, {} with errors",
+            results.len(),
+            results.len() - failed,
+            failed
+        );
+    }
+
+    if failed > 0 {
+        bail!("{failed} template(s) have errors");
+    }
+    Ok(())
+}
+
+/// The typedef a template uses: its `lua:` script, else the type named like
+/// the template, else a type that binds it with `template = "..."`.
+fn typedef_for(
+    rc: &ResolvedConfig,
+    registry: &TypeRegistry,
+    template: &LoadedTemplate,
+) -> Result<Option<TypeDefinition>, String> {
+    if let Some(lua) = template.frontmatter.as_ref().and_then(|fm| fm.lua.as_ref()) {
+        return load_typedef_from_file(&rc.resolve_lua_path(lua))
+            .map(Some)
+            .map_err(|e| format!("failed to load Lua script '{lua}': {e}"));
+    }
+    let name = &template.logical_name;
+    let bound = registry.list_all_types().into_iter().find_map(|t| {
+        registry.get(&t).filter(|td| td.template.as_deref() == Some(name.as_str()))
+    });
+    Ok(registry.get(name).or(bound).map(|td| (*td).clone()))
+}
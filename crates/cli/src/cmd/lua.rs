@@ -0,0 +1,74 @@
+//! `mdv lua`: an interactive Lua session with the vault bindings loaded.
+
+use std::io::IsTerminal;
+use std::path::Path;
+
+use color_eyre::eyre::{Result, WrapErr, eyre};
+use mdvault_core::paths::PathResolver;
+use mdvault_core::scripting::{LuaEngine, LuaRepl, Permissions, ReplStep, SandboxConfig};
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
+
+use super::common::{load_config, vault_context};
+use crate::LuaArgs;
+use crate::prompt::create_fuzzy_selector_callback;
+
+pub fn run(config: Option<&Path>, profile: Option<&str>, args: LuaArgs) -> Result<()> {
+    let cfg = load_config(config, profile)?;
+    let interactive = std::io::stdin().is_terminal();
+    // Piped input is a script, not something to recall later
+    let history = (interactive && !args.no_history)
+        .then(|| PathResolver::new(&cfg.vault_root).lua_history());
+
+    let vault_ctx = vault_context(&cfg)?.with_selector(create_fuzzy_selector_callback());
+    // Typed by the user, so only the security policy limits it
+    let engine = LuaEngine::with_vault_context(
+        SandboxConfig::restricted().with_permissions(Permissions::all()),
+        vault_ctx,
+    )
+    .map_err(|e| eyre!("{e}"))?;
+    let mut repl = LuaRepl::new(engine);
+
+    let mut editor = DefaultEditor::new().wrap_err("Failed to start line editor")?;
+    if let Some(path) = &history {
+        // No history yet on first use
+        let _ = editor.load_history(path);
+    }
+    if interactive {
+        println!("Lua session for {}. Ctrl-D to exit.", cfg.vault_root.display());
+    }
+
+    loop {
+        let prompt = if repl.is_pending() { ">> " } else { "> " };
+        match editor.readline(prompt) {
+            Ok(line) => {
+                if !line.trim().is_empty() {
+                    let _ = editor.add_history_entry(line.as_str());
+                }
+                match repl.eval_line(&line) {
+                    Ok(ReplStep::Done(values)) => {
+                        for value in values {
+                            println!("{value}");
+                        }
+                    }
+                    Ok(ReplStep::Continue) => {}
+                    Err(e) => eprintln!("{e}"),
+                }
+            }
+            // Ctrl-C abandons the chunk being typed, like in a shell
+            Err(ReadlineError::Interrupted) => repl.reset(),
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e).wrap_err("Failed to read input"),
+        }
+    }
+
+    if let Some(path) = &history {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Err(e) = editor.save_history(path) {
+            eprintln!("Warning: failed to save history: {e}");
+        }
+    }
+    Ok(())
+}
@@ -7,7 +7,7 @@ use std::process::Command;
 
 use color_eyre::eyre::{Result, WrapErr, bail};
 
-use super::common::load_config;
+use super::common::{load_config, load_types, run_on_save_hook};
 use crate::prompt::{PromptOptions, collect_variables};
 use mdvault_core::captures::CaptureRepository;
 use mdvault_core::config::types::ResolvedConfig;
@@ -170,11 +170,13 @@ pub fn run(
         }
         match IndexDb::open(&index_path) {
             Ok(db) => {
+                let types = load_types(&cfg);
                 let builder = IndexBuilder::with_exclusions(
                     &db,
                     &cfg.vault_root,
                     cfg.excluded_folders.clone(),
-                );
+                )
+                .with_types(&types);
                 if let Err(e) = builder.incremental_reindex(None) {
                     eprintln!("Warning: reindex failed: {e}");
                 }
@@ -337,6 +339,7 @@ impl StepExecutor for CliStepExecutor {
         // Write file
         fs::write(&output_path, &rendered)
             .map_err(|e| MacroRunError::TemplateError(e.to_string()))?;
+        run_on_save_hook(&self.config, &output_path);
 
         Ok(StepResult {
             step_index: 0, // Will be set by runner
@@ -414,6 +417,7 @@ impl StepExecutor for CliStepExecutor {
         let final_content = serialize(&parsed);
         fs::write(&target_file, &final_content)
             .map_err(|e| MacroRunError::CaptureError(e.to_string()))?;
+        run_on_save_hook(&self.config, &target_file);
 
         Ok(StepResult {
             step_index: 0,
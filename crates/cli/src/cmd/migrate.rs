@@ -0,0 +1,135 @@
+//! Migrate command implementation.
+
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{Result, WrapErr, bail, eyre};
+use mdvault_core::index::{IndexBuilder, IndexDb};
+use mdvault_core::migrate::{self, MigrationPlan};
+use mdvault_core::paths::PathResolver;
+use mdvault_core::undo;
+
+use super::common::{load_config, load_types};
+use crate::MigrateArgs;
+use mdvault_core::config::types::ResolvedConfig;
+
+/// Rewrite the notes of a type that are behind its schema version, or undo
+/// the last run.
+pub fn run(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    args: MigrateArgs,
+) -> Result<()> {
+    let cfg = load_config(config, profile)?;
+
+    if args.undo {
+        return undo_last_migration(&cfg);
+    }
+
+    let type_name = args.r#type.as_deref().unwrap_or_default();
+    let types = load_types(&cfg);
+    let typedef =
+        types.get(type_name).ok_or_else(|| eyre!("Unknown type: {type_name}"))?;
+    let plan =
+        migrate::plan_migrations(&cfg.vault_root, cfg.excluded_folders.clone(), &typedef)
+            .map_err(|e| eyre!("{e}"))?;
+
+    for (path, e) in &plan.failed {
+        eprintln!("FAIL {}: {e}", path.display());
+    }
+    if plan.notes.is_empty() {
+        println!(
+            "All {} '{}' note(s) are at schema version {}.",
+            plan.current, plan.type_name, plan.to_version
+        );
+        return report_failures(&plan);
+    }
+
+    print_plan(&plan);
+    let count = plan.notes.len();
+    if args.dry_run {
+        println!("\n{count} note(s) would be migrated. Run without --dry-run to apply.");
+        return report_failures(&plan);
+    }
+
+    let result = migrate::apply_migrations(&cfg.vault_root, &plan);
+    let paths: Vec<PathBuf> = plan.notes.iter().map(|n| n.path.clone()).collect();
+    reindex(&cfg, &paths);
+    result.wrap_err("Failed to migrate notes")?;
+    println!(
+        "\n{count} note(s) migrated to schema version {}. Undo with `mdv migrate --undo`.",
+        plan.to_version
+    );
+    report_failures(&plan)
+}
+
+/// One block per note: its path and versions, then its field changes.
+fn print_plan(plan: &MigrationPlan) {
+    for note in &plan.notes {
+        println!(
+            "{}  (v{} -> v{})",
+            note.path.display(),
+            note.from_version,
+            plan.to_version
+        );
+        for change in &note.changes {
+            println!("    {change}");
+        }
+    }
+}
+
+fn report_failures(plan: &MigrationPlan) -> Result<()> {
+    if !plan.failed.is_empty() {
+        bail!("migrate() failed on {} note(s)", plan.failed.len());
+    }
+    Ok(())
+}
+
+/// Undo the last journaled operation, provided it is a migration run.
+fn undo_last_migration(cfg: &ResolvedConfig) -> Result<()> {
+    let last =
+        undo::last_operation(&cfg.vault_root).wrap_err("Failed to read undo journal")?;
+    match last {
+        Some(record) if migrate::is_migration(&record) => {}
+        Some(record) => bail!(
+            "The last journaled change was not a migration ({}); nothing undone",
+            record.label
+        ),
+        None => {
+            println!("Nothing to undo.");
+            return Ok(());
+        }
+    }
+
+    let record = undo::undo_last(&cfg.vault_root).wrap_err("Failed to undo migration")?;
+    let paths: Vec<PathBuf> = record.files.iter().map(|f| f.path.clone()).collect();
+    for path in &paths {
+        println!("restored  {}", path.display());
+    }
+    reindex(cfg, &paths);
+    println!("\nUndid {}.", record.label);
+    Ok(())
+}
+
+/// Update the index entries of the rewritten notes, if the vault has an index.
+fn reindex(cfg: &ResolvedConfig, paths: &[PathBuf]) {
+    let index_path = PathResolver::new(&cfg.vault_root).index_db();
+    if !index_path.exists() {
+        return;
+    }
+    let db = match IndexDb::open(&index_path) {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("Warning: could not open index: {e}");
+            return;
+        }
+    };
+    let types = load_types(cfg);
+    let builder =
+        IndexBuilder::with_exclusions(&db, &cfg.vault_root, cfg.excluded_folders.clone())
+            .with_types(&types);
+    for path in paths {
+        if let Err(e) = builder.reindex_file(path) {
+            eprintln!("Warning: failed to index {}: {e}", path.display());
+        }
+    }
+}
@@ -4,20 +4,42 @@ pub mod charts;
 pub mod check;
 pub mod common;
 pub mod context;
+pub mod demo;
 pub mod doctor;
+pub mod duplicates;
+#[cfg(feature = "embeddings")]
+pub mod embed;
+pub mod expire;
 pub mod focus;
+pub mod ingest;
+pub mod jobs;
+pub mod journal;
 pub mod links;
 pub mod list;
 pub mod list_templates;
+pub mod lua;
 pub mod macro_cmd;
+pub mod migrate;
 pub mod new;
 pub mod output;
+pub mod plugin;
+pub mod print;
 pub mod project;
+pub mod provenance;
+pub mod query;
 pub mod reindex;
+pub mod related;
+pub mod remote;
 pub mod rename;
 pub mod report;
+pub mod review;
 pub mod search;
+pub mod serve;
 pub mod stale;
+pub mod stats;
+pub mod suggest_links;
 pub mod task;
 pub mod today;
+pub mod types;
+pub mod user_command;
 pub mod validate;
@@ -13,19 +13,20 @@ use mdvault_core::scripting::{
 use mdvault_core::templates::repository::TemplateRepository;
 use mdvault_core::types::{TypeDefinition, TypeRegistry, TypedefRepository};
 use std::collections::HashMap;
-use std::fs;
 use std::path::Path;
 
 use super::discovery::extract_note_type;
 
 /// Run on_create hook if the note type has one defined.
 /// Returns the HookResult which may contain modifications to apply.
+/// With `dry_run` the hook may read the vault but not change it or run programs.
 pub(super) fn run_on_create_hook_if_exists(
     cfg: &ResolvedConfig,
     output_path: &Path,
     content: &str,
     explicit_typedef: Option<&TypeDefinition>,
     variables: &HashMap<String, String>,
+    dry_run: bool,
 ) -> Result<HookResult, String> {
     let typedef_repo = match &cfg.typedefs_fallback_dir {
         Some(fallback) => TypedefRepository::with_fallback(&cfg.typedefs_dir, fallback),
@@ -35,7 +36,7 @@ pub(super) fn run_on_create_hook_if_exists(
     let type_registry =
         TypeRegistry::from_repository(&typedef_repo).map_err(|e| e.to_string())?;
 
-    let typedef = if let Some(td) = explicit_typedef {
+    let mut typedef = if let Some(td) = explicit_typedef {
         if !td.has_on_create_hook {
             return Ok(HookResult {
                 modified: false,
@@ -71,6 +72,10 @@ pub(super) fn run_on_create_hook_if_exists(
         }
     };
 
+    if dry_run {
+        typedef.permissions = typedef.permissions.read_only();
+    }
+
     let template_repo =
         TemplateRepository::new(&cfg.templates_dir).map_err(|e| e.to_string())?;
     let capture_repo =
@@ -127,15 +132,14 @@ pub(super) fn run_on_create_hook_if_exists(
     run_on_create_hook(&typedef, &note_ctx, vault_ctx).map_err(|e| e.to_string())
 }
 
-/// Apply hook modifications to the output file.
+/// The note content after applying hook modifications.
 pub(super) fn apply_hook_modifications(
-    output_path: &Path,
     original_content: &str,
     hook_result: &HookResult,
     order: Option<&[String]>,
-) -> Result<(), String> {
+) -> Result<String, String> {
     if !hook_result.modified {
-        return Ok(());
+        return Ok(original_content.to_string());
     }
 
     let original_parsed =
@@ -167,7 +171,5 @@ pub(super) fn apply_hook_modifications(
         body: final_body,
     };
 
-    let final_content = serialize_with_order(&doc, order);
-
-    fs::write(output_path, final_content).map_err(|e| e.to_string())
+    Ok(serialize_with_order(&doc, order))
 }
@@ -5,16 +5,18 @@ mod writer;
 
 use color_eyre::eyre::{Result, WrapErr, bail};
 
-use super::common::load_config;
+use super::common::{load_config, run_on_save_hook};
 use crate::NewArgs;
 use crate::prompt::{CollectedVars, PromptOptions};
 use mdvault_core::activity::ActivityLogService;
 use mdvault_core::config::types::ResolvedConfig;
 use mdvault_core::context::ContextManager;
 use mdvault_core::domain::{CreationContext, NoteType as DomainNoteType};
+use mdvault_core::index::IndexDb;
+use mdvault_core::paths::PathResolver;
 use mdvault_core::templates::discovery::TemplateInfo;
 use mdvault_core::templates::engine::{build_minimal_context, render_with_ref_date};
-use mdvault_core::templates::repository::TemplateRepository;
+use mdvault_core::templates::repository::{TemplateRepoError, TemplateRepository};
 use mdvault_core::types::{TypeRegistry, TypedefRepository};
 use std::collections::HashMap;
 use std::fs;
@@ -95,10 +97,42 @@ fn run_unified(cfg: &ResolvedConfig, effective_name: &str, args: &NewArgs) -> Re
     let type_registry =
         typedef_repo.as_ref().and_then(|repo| TypeRegistry::from_repository(repo).ok());
 
-    // 2. Try load template
+    // 2. Try load template: the one the type binds, else one named like the type
+    let bound_template = if args.template.is_none() {
+        type_registry
+            .as_ref()
+            .and_then(|reg| reg.get(effective_name))
+            .and_then(|td| td.template.clone())
+    } else {
+        None
+    };
     let template_repo = TemplateRepository::new(&cfg.templates_dir).ok();
-    let loaded_template =
-        template_repo.as_ref().and_then(|repo| repo.get_by_name(effective_name).ok());
+    let loaded_template = match &bound_template {
+        Some(name) => Some(
+            template_repo
+                .as_ref()
+                .ok_or_else(|| {
+                    color_eyre::eyre::eyre!(
+                        "Templates directory not found: {}",
+                        cfg.templates_dir.display()
+                    )
+                })
+                .and_then(|repo| repo.get_by_name(name).map_err(Into::into))
+                .wrap_err_with(|| {
+                    format!("Type '{effective_name}' uses template '{name}'")
+                })?,
+        ),
+        None => match template_repo.as_ref().map(|repo| repo.get_by_name(effective_name))
+        {
+            Some(Ok(template)) => Some(template),
+            Some(Err(TemplateRepoError::NotFound(_))) | None => None,
+            Some(Err(e)) => {
+                return Err(e).wrap_err_with(|| {
+                    format!("Failed to load template '{effective_name}'")
+                });
+            }
+        },
+    };
 
     // 3. Load Lua typedef
     let lua_typedef = discovery::resolve_lua_typedef(
@@ -127,7 +161,7 @@ fn run_unified(cfg: &ResolvedConfig, effective_name: &str, args: &NewArgs) -> Re
 
     // 6. Parse CLI vars and title
     let mut provided_vars: HashMap<String, String> = args.vars.iter().cloned().collect();
-    let needs_title = loaded_template.is_none();
+    let needs_title = loaded_template.is_none() || bound_template.is_some();
 
     let title = if let Some(ref t) = args.title {
         t.clone()
@@ -240,10 +274,11 @@ fn run_unified(cfg: &ResolvedConfig, effective_name: &str, args: &NewArgs) -> Re
         merge_context_to_render_vars(ctx, &mut render_ctx);
     }
 
-    // 15. Resolve output path
+    // 15. Resolve output path (a bound template only renders the body; the
+    // type still decides where its notes go)
     let output_path = discovery::resolve_output_path(
         args.output.as_ref(),
-        loaded_template.as_ref(),
+        loaded_template.as_ref().filter(|_| bound_template.is_none()),
         note_type.as_ref(),
         creation_ctx.as_ref(),
         &lua_typedef,
@@ -313,11 +348,17 @@ fn run_unified(cfg: &ResolvedConfig, effective_name: &str, args: &NewArgs) -> Re
         let note_type_name = discovery::extract_note_type(&rendered)
             .unwrap_or_else(|| typedef.name.clone());
 
+        let index_path = PathResolver::new(&cfg.vault_root).index_db();
+        let index = index_path
+            .exists()
+            .then(|| IndexDb::open_read_only(&index_path).ok())
+            .flatten();
         match writer::validate_before_write(
             registry,
             &note_type_name,
             &output_path,
             &rendered,
+            index.as_ref(),
         ) {
             Ok(Some(fixed)) => rendered = fixed,
             Ok(None) => {}
@@ -332,7 +373,33 @@ fn run_unified(cfg: &ResolvedConfig, effective_name: &str, args: &NewArgs) -> Re
         }
     }
 
-    // 20. Create dirs + write file
+    // 20. Dry run: show the note the hooks would leave, without writing
+    if args.dry_run {
+        let content = finalize_content(
+            cfg,
+            &output_path,
+            &rendered,
+            creation_ctx.as_ref(),
+            lua_typedef.as_ref(),
+            loaded_template.as_ref(),
+            &mut render_ctx,
+            ref_date,
+            true,
+        );
+        println!("DRY RUN mdv new");
+        println!("type: {}", effective_name);
+        println!("output: {}", output_path.display());
+        println!();
+        print!("{content}");
+        if !content.ends_with('\n') {
+            println!();
+        }
+        println!();
+        println!("Nothing was written. Run without --dry-run to create the note.");
+        return Ok(());
+    }
+
+    // 21. Create dirs + write file
     if let Some(parent) = output_path.parent() {
         fs::create_dir_all(parent).wrap_err_with(|| {
             format!("Failed to create parent directory {}", parent.display())
@@ -343,7 +410,7 @@ fn run_unified(cfg: &ResolvedConfig, effective_name: &str, args: &NewArgs) -> Re
         format!("Failed to write output file {}", output_path.display())
     })?;
 
-    // 21. Post-write pipeline
+    // 22. Post-write pipeline
     post_write_pipeline(
         cfg,
         &output_path,
@@ -357,7 +424,7 @@ fn run_unified(cfg: &ResolvedConfig, effective_name: &str, args: &NewArgs) -> Re
         effective_name,
     );
 
-    // 22. Print success
+    // 23. Print success
     println!("OK   mdv new");
     println!("type: {}", effective_name);
     if let Some(ref ctx) = creation_ctx
@@ -374,30 +441,35 @@ fn run_unified(cfg: &ResolvedConfig, effective_name: &str, args: &NewArgs) -> Re
     Ok(())
 }
 
-/// Post-write pipeline: hook execution, core_metadata protection, after_create, reindex, activity logging.
+/// Run the on_create hook on the rendered note and return the content it
+/// leaves, with core_metadata re-applied. With `dry_run` the hook may not
+/// change the vault or run programs.
 #[allow(clippy::too_many_arguments)]
-fn post_write_pipeline(
+fn finalize_content(
     cfg: &ResolvedConfig,
     output_path: &Path,
     rendered: &str,
-    note_type: Option<&DomainNoteType>,
     creation_ctx: Option<&CreationContext>,
     lua_typedef: Option<&mdvault_core::types::TypeDefinition>,
     loaded_template: Option<&mdvault_core::templates::repository::LoadedTemplate>,
     render_ctx: &mut HashMap<String, String>,
     ref_date: Option<chrono::NaiveDate>,
-    type_name: &str,
-) {
+    dry_run: bool,
+) -> String {
+    let order = lua_typedef.as_ref().and_then(|td| td.frontmatter_order.as_deref());
+    let mut content = rendered.to_string();
+
     match hooks::run_on_create_hook_if_exists(
         cfg,
         output_path,
         rendered,
         lua_typedef,
         render_ctx,
+        dry_run,
     ) {
         Ok(hook_result) => {
             if hook_result.modified {
-                let final_content = if let Some(ref new_vars) = hook_result.variables {
+                let rerendered = if let Some(ref new_vars) = hook_result.variables {
                     if let serde_yaml::Value::Mapping(map) = new_vars {
                         for (k, v) in map {
                             if let serde_yaml::Value::String(ks) = k {
@@ -427,18 +499,11 @@ fn post_write_pipeline(
                     rendered.to_string()
                 };
 
-                let order =
-                    lua_typedef.as_ref().and_then(|td| td.frontmatter_order.as_deref());
-
-                if let Err(e) = hooks::apply_hook_modifications(
-                    output_path,
-                    &final_content,
-                    &hook_result,
-                    order,
-                ) {
-                    eprintln!(
+                match hooks::apply_hook_modifications(&rerendered, &hook_result, order) {
+                    Ok(modified) => content = modified,
+                    Err(e) => eprintln!(
                         "Warning: failed to apply on_create hook modifications: {e}"
-                    );
+                    ),
                 }
             }
         }
@@ -449,23 +514,53 @@ fn post_write_pipeline(
 
     // Re-apply core_metadata after hooks
     if let Some(ctx) = creation_ctx {
-        let order = lua_typedef.as_ref().and_then(|td| td.frontmatter_order.as_deref());
-        if let Ok(current) = std::fs::read_to_string(output_path)
-            && let Ok(fixed) = ctx.core_metadata.apply_to_content(&current, order)
-            && let Err(e) = std::fs::write(output_path, fixed)
-        {
-            eprintln!("Warning: failed to re-apply core metadata: {e}");
+        match ctx.core_metadata.apply_to_content(&content, order) {
+            Ok(fixed) => content = fixed,
+            Err(e) => eprintln!("Warning: failed to re-apply core metadata: {e}"),
         }
     }
+    content
+}
+
+/// Post-write pipeline: hook execution, core_metadata protection, after_create, reindex, activity logging.
+#[allow(clippy::too_many_arguments)]
+fn post_write_pipeline(
+    cfg: &ResolvedConfig,
+    output_path: &Path,
+    rendered: &str,
+    note_type: Option<&DomainNoteType>,
+    creation_ctx: Option<&CreationContext>,
+    lua_typedef: Option<&mdvault_core::types::TypeDefinition>,
+    loaded_template: Option<&mdvault_core::templates::repository::LoadedTemplate>,
+    render_ctx: &mut HashMap<String, String>,
+    ref_date: Option<chrono::NaiveDate>,
+    type_name: &str,
+) {
+    let content = finalize_content(
+        cfg,
+        output_path,
+        rendered,
+        creation_ctx,
+        lua_typedef,
+        loaded_template,
+        render_ctx,
+        ref_date,
+        false,
+    );
+    if content != rendered
+        && let Err(e) = fs::write(output_path, &content)
+    {
+        eprintln!("Warning: failed to write on_create hook modifications: {e}");
+    }
 
     // Call after_create after hooks
-    if let (Some(nt), Some(ctx)) = (note_type, creation_ctx) {
-        let current = std::fs::read_to_string(output_path).unwrap_or_default();
-        if let Err(e) = nt.behavior().after_create(ctx, &current) {
-            eprintln!("Warning: after_create failed: {e}");
-        }
+    if let (Some(nt), Some(ctx)) = (note_type, creation_ctx)
+        && let Err(e) = nt.behavior().after_create(ctx, &content)
+    {
+        eprintln!("Warning: after_create failed: {e}");
     }
 
+    run_on_save_hook(cfg, output_path);
     writer::reindex_vault(cfg);
 
     // Activity logging
@@ -1,8 +1,11 @@
 use color_eyre::eyre::{Result, WrapErr, bail};
 
 use crate::prompt::{CollectedVars, PromptOptions, prompt_for_enum, prompt_for_field};
-use dialoguer::{Editor, FuzzySelect, Input, Select, theme::ColorfulTheme};
+use dialoguer::{
+    Confirm, Editor, FuzzySelect, Input, MultiSelect, Select, theme::ColorfulTheme,
+};
 use mdvault_core::config::types::ResolvedConfig;
+use mdvault_core::domain::{FieldPrompt, PromptType};
 use mdvault_core::index::{IndexDb, NoteQuery, NoteType};
 use mdvault_core::paths::PathResolver;
 use mdvault_core::types::{TypeDefinition, TypeRegistry};
@@ -10,7 +13,7 @@ use std::collections::HashMap;
 
 /// Dispatch type-specific prompts to interactive widgets and collect values.
 pub(super) fn dispatch_type_prompts(
-    prompts: Vec<mdvault_core::domain::FieldPrompt>,
+    prompts: Vec<FieldPrompt>,
     vars: &mut HashMap<String, String>,
     cfg: &ResolvedConfig,
     batch_mode: bool,
@@ -26,21 +29,19 @@ pub(super) fn dispatch_type_prompts(
             }
         } else {
             match &prompt.prompt_type {
-                mdvault_core::domain::PromptType::ProjectSelector => {
-                    match prompt_project_selection(cfg) {
-                        Some(project) => {
-                            vars.insert("project".to_string(), project);
-                        }
-                        None => {
-                            if let Some(default) = prompt.default_value {
-                                vars.insert(prompt.field_name, default);
-                            } else {
-                                bail!("No project selected");
-                            }
+                PromptType::ProjectSelector => match prompt_project_selection(cfg) {
+                    Some(project) => {
+                        vars.insert("project".to_string(), project);
+                    }
+                    None => {
+                        if let Some(default) = prompt.default_value {
+                            vars.insert(prompt.field_name, default);
+                        } else {
+                            bail!("No project selected");
                         }
                     }
-                }
-                mdvault_core::domain::PromptType::Text => {
+                },
+                PromptType::Text => {
                     match prompt_for_field(
                         &prompt.field_name,
                         &prompt.prompt_text,
@@ -58,12 +59,37 @@ pub(super) fn dispatch_type_prompts(
                         }
                     }
                 }
-                mdvault_core::domain::PromptType::Multiline => {
+                PromptType::Multiline => {
                     if let Some(text) = Editor::new().edit("").ok().flatten() {
                         vars.insert(prompt.field_name, text);
                     }
                 }
-                mdvault_core::domain::PromptType::Select(options) => {
+                PromptType::MultiSelect(_)
+                | PromptType::Date
+                | PromptType::Boolean
+                | PromptType::Reference(_) => {
+                    match prompt_for_schema_field(
+                        Some(cfg),
+                        &prompt.field_name,
+                        &prompt.prompt_text,
+                        &prompt.prompt_type,
+                        prompt.default_value.as_deref(),
+                        prompt.required,
+                    ) {
+                        Ok(value) if !value.is_empty() => {
+                            vars.insert(prompt.field_name, value);
+                        }
+                        Ok(_) => {
+                            if let Some(default) = prompt.default_value {
+                                vars.insert(prompt.field_name, default);
+                            }
+                        }
+                        Err(e) => {
+                            bail!("Error: {e}");
+                        }
+                    }
+                }
+                PromptType::Select(options) => {
                     match prompt_for_enum(
                         &prompt.field_name,
                         &prompt.prompt_text,
@@ -196,16 +222,15 @@ pub(super) fn collect_schema_variables(
                     bail!("Missing required field '{}' in batch mode", field_name);
                 }
             } else {
-                let enum_values = schema.enum_values.as_deref();
-                let default_str = schema.default.as_ref().map(yaml_value_to_string);
+                let prompt = FieldPrompt::from_schema(field_name, schema);
 
                 match prompt_for_schema_field(
+                    cfg,
                     field_name,
                     prompt_text,
-                    enum_values,
-                    default_str.as_deref(),
+                    &prompt.prompt_type,
+                    prompt.default_value.as_deref(),
                     schema.required,
-                    schema.multiline,
                 ) {
                     Ok(value) if !value.is_empty() => {
                         result.values.insert(field_name.clone(), value);
@@ -338,55 +363,104 @@ fn prompt_project_selection(cfg: &ResolvedConfig) -> Option<String> {
     })
 }
 
-/// Prompt for a single schema field value.
+/// Prompt for a single schema field value with the widget its prompt type
+/// calls for. Typed answers are checked with [`PromptType::parse_answer`]
+/// and asked again until they pass.
 fn prompt_for_schema_field(
+    cfg: Option<&ResolvedConfig>,
     field_name: &str,
     prompt_text: &str,
-    enum_values: Option<&[String]>,
+    prompt_type: &PromptType,
     default: Option<&str>,
     required: bool,
-    multiline: bool,
 ) -> Result<String, String> {
     let theme = ColorfulTheme::default();
 
-    if let Some(values) = enum_values {
-        let default_idx =
-            default.and_then(|d| values.iter().position(|v| v == d)).unwrap_or(0);
-
-        let selection = Select::with_theme(&theme)
-            .with_prompt(prompt_text)
-            .items(values)
-            .default(default_idx)
-            .interact_opt()
-            .map_err(|e| {
-                format!("Failed to read selection for '{}': {}", field_name, e)
-            })?;
-
-        return match selection {
-            Some(idx) => Ok(values[idx].clone()),
-            None => Ok(default.unwrap_or("").to_string()),
-        };
+    if let (PromptType::Reference(note_type), Some(cfg)) = (prompt_type, cfg) {
+        let selected = prompt_with_note_selector(cfg, note_type, prompt_text)?;
+        return Ok(selected.unwrap_or_else(|| default.unwrap_or("").to_string()));
     }
 
-    if multiline {
-        let initial = default.unwrap_or("");
-        let content = Editor::new()
-            .edit(initial)
-            .map_err(|e| format!("Editor error for '{}': {}", field_name, e))?;
-        return Ok(content.unwrap_or_else(|| initial.to_string()));
+    match prompt_type {
+        PromptType::Select(values) => {
+            let default_idx =
+                default.and_then(|d| values.iter().position(|v| v == d)).unwrap_or(0);
+
+            let selection = Select::with_theme(&theme)
+                .with_prompt(prompt_text)
+                .items(values)
+                .default(default_idx)
+                .interact_opt()
+                .map_err(|e| {
+                    format!("Failed to read selection for '{}': {}", field_name, e)
+                })?;
+
+            return match selection {
+                Some(idx) => Ok(values[idx].clone()),
+                None => Ok(default.unwrap_or("").to_string()),
+            };
+        }
+        PromptType::MultiSelect(values) => {
+            let chosen: Vec<&str> = default
+                .map(|d| d.split(',').map(str::trim).collect())
+                .unwrap_or_default();
+            let defaults: Vec<bool> =
+                values.iter().map(|v| chosen.contains(&v.as_str())).collect();
+
+            let selection = MultiSelect::with_theme(&theme)
+                .with_prompt(prompt_text)
+                .items(values)
+                .defaults(&defaults)
+                .interact_opt()
+                .map_err(|e| {
+                    format!("Failed to read selection for '{}': {}", field_name, e)
+                })?;
+
+            return match selection {
+                Some(indices) => Ok(indices
+                    .into_iter()
+                    .map(|idx| values[idx].as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")),
+                None => Ok(default.unwrap_or("").to_string()),
+            };
+        }
+        PromptType::Boolean => {
+            let default = default.and_then(|d| prompt_type.parse_answer(d).ok());
+            let answer = Confirm::with_theme(&theme)
+                .with_prompt(prompt_text)
+                .default(default.as_deref() == Some("true"))
+                .interact_opt()
+                .map_err(|e| {
+                    format!("Failed to read answer for '{}': {}", field_name, e)
+                })?;
+
+            return Ok(answer.map(|b| b.to_string()).or(default).unwrap_or_default());
+        }
+        PromptType::Multiline => {
+            let initial = default.unwrap_or("");
+            let content = Editor::new()
+                .edit(initial)
+                .map_err(|e| format!("Editor error for '{}': {}", field_name, e))?;
+            return Ok(content.unwrap_or_else(|| initial.to_string()));
+        }
+        _ => {}
     }
 
     let mut input = Input::<String>::with_theme(&theme);
     input = input.with_prompt(prompt_text);
     input = input.allow_empty(!required);
+    input =
+        input.validate_with(|value: &String| prompt_type.parse_answer(value).map(|_| ()));
 
     if let Some(def) = default {
         input = input.with_initial_text(def);
     }
 
-    input
+    let value = input
         .interact_text()
-        .map_err(|e| format!("Failed to read input for '{}': {}", field_name, e))
+        .map_err(|e| format!("Failed to read input for '{}': {}", field_name, e))?;
+    prompt_type.parse_answer(&value)
 }
 
 /// Prompt using a fuzzy note selector.
@@ -1,8 +1,11 @@
+use crate::cmd::common::load_types;
 use mdvault_core::config::types::ResolvedConfig;
 use mdvault_core::frontmatter::parse as parse_frontmatter;
 use mdvault_core::index::{IndexBuilder, IndexDb};
 use mdvault_core::paths::PathResolver;
-use mdvault_core::types::{TypeRegistry, try_fix_note, validate_note_for_creation};
+use mdvault_core::types::{
+    TypeRegistry, check_unique, try_fix_note, validate_note_for_creation,
+};
 use std::fs;
 use std::path::Path;
 
@@ -16,11 +19,13 @@ pub(super) fn reindex_vault(cfg: &ResolvedConfig) {
 
     match IndexDb::open(&index_path) {
         Ok(db) => {
+            let types = load_types(cfg);
             let builder = IndexBuilder::with_exclusions(
                 &db,
                 &cfg.vault_root,
                 cfg.excluded_folders.clone(),
-            );
+            )
+            .with_types(&types);
             if let Err(e) = builder.incremental_reindex(None) {
                 eprintln!("Warning: reindex failed: {e}");
             }
@@ -34,12 +39,14 @@ pub(super) fn reindex_vault(cfg: &ResolvedConfig) {
 /// Validate note content before writing.
 ///
 /// Returns Ok(None) if valid, Ok(Some(content)) if valid after auto-fixing,
-/// or Err with error messages if validation fails.
+/// or Err with error messages if validation fails. With the index, values of
+/// `unique` fields already used by another note fail validation too.
 pub(super) fn validate_before_write(
     registry: &TypeRegistry,
     note_type: &str,
     output_path: &Path,
     content: &str,
+    index: Option<&IndexDb>,
 ) -> Result<Option<String>, Vec<String>> {
     let parsed = match parse_frontmatter(content) {
         Ok(p) => p,
@@ -57,6 +64,13 @@ pub(super) fn validate_before_write(
         None => serde_yaml::Value::Mapping(serde_yaml::Mapping::new()),
     };
 
+    if let Some(db) = index {
+        let unique = check_unique(registry, note_type, output_path, &frontmatter, db);
+        if !unique.valid {
+            return Err(unique.errors.iter().map(|e| e.to_string()).collect());
+        }
+    }
+
     let path_str = output_path.to_string_lossy();
     let result = validate_note_for_creation(
         registry,
@@ -69,7 +83,7 @@ pub(super) fn validate_before_write(
     if result.valid {
         Ok(None)
     } else {
-        let fix_result = try_fix_note(registry, note_type, content, &result.errors);
+        let fix_result = try_fix_note(registry, note_type, content, &result);
         if fix_result.fixed {
             if let Some(new_content) = fix_result.content {
                 println!("Auto-fixed validation errors:");
@@ -99,7 +113,7 @@ mod tests {
         let registry = TypeRegistry::new();
         let path = Path::new("foo.md");
         let content = "---\n: invalid\n---\nbody";
-        let result = validate_before_write(&registry, "task", path, content);
+        let result = validate_before_write(&registry, "task", path, content, None);
         assert!(result.is_err());
         let errs = result.unwrap_err();
         assert!(!errs.is_empty());
@@ -1,40 +1,160 @@
 //! Shared output formatting for query commands.
 
-use mdvault_core::index::{IndexedLink, IndexedNote};
+use std::cell::RefCell;
+use std::path::Path;
+
+use color_eyre::eyre::{Result, eyre};
+use mdvault_core::index::{
+    DEFAULT_PAGE_SIZE, IndexedLink, IndexedNote, NoteHeading, VaultHit, paginate,
+};
+use mdvault_core::scripting::Formatter;
 use serde::Serialize;
 
-use crate::OutputFormat;
+use super::common::{load_config, vault_context};
+use crate::{OutputArg, OutputFormat};
+
+thread_local! {
+    /// The formatter picked with `--output lua:NAME`, which [`print_json`]
+    /// hands every payload to.
+    static FORMATTER: RefCell<Option<Formatter>> = const { RefCell::new(None) };
+}
 
-/// Resolve the output format from --output, --json, and --quiet flags.
+/// Resolve the output format from --output, --stable-json, --json, and --quiet flags.
 ///
-/// The --json and --quiet shorthand flags take precedence over --output.
-pub fn resolve_format(output: OutputFormat, json: bool, quiet: bool) -> OutputFormat {
-    if json {
-        OutputFormat::Json
+/// The shorthand flags take precedence over --output. `--output lua:NAME`
+/// loads the formatter from the formatters directory and resolves to JSON,
+/// so the command prints its JSON payload through the formatter.
+pub fn resolve_format(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    output: OutputArg,
+    stable_json: bool,
+    json: bool,
+    quiet: bool,
+) -> Result<OutputFormat> {
+    if stable_json {
+        Ok(OutputFormat::StableJson)
+    } else if json {
+        Ok(OutputFormat::Json)
     } else if quiet {
-        OutputFormat::Quiet
+        Ok(OutputFormat::Quiet)
     } else {
-        output
+        match output {
+            OutputArg::Format(format) => Ok(format),
+            OutputArg::Lua(name) => {
+                let cfg = load_config(config, profile)?;
+                let formatter =
+                    Formatter::load(&cfg.formatters_dir, &name, vault_context(&cfg)?)
+                        .map_err(|e| eyre!("{e}"))?;
+                FORMATTER.with_borrow_mut(|f| *f = Some(formatter));
+                Ok(OutputFormat::Json)
+            }
+        }
+    }
+}
+
+/// Where a paged command's output stopped.
+#[derive(Debug)]
+pub struct Paged {
+    /// Cursor for the following page; `None` on the last one.
+    pub next_cursor: Option<String>,
+}
+
+/// Cut one page out of `items` when `--cursor` or `--page-size` is given.
+///
+/// `fingerprint` identifies the query, so a cursor from another query is
+/// rejected. Without either flag all items are kept and no `Paged` is returned.
+pub fn take_page<T>(
+    items: Vec<T>,
+    cursor: Option<&str>,
+    page_size: Option<usize>,
+    fingerprint: u64,
+) -> Result<(Vec<T>, Option<Paged>)> {
+    if cursor.is_none() && page_size.is_none() {
+        return Ok((items, None));
+    }
+    let page =
+        paginate(items, cursor, page_size.unwrap_or(DEFAULT_PAGE_SIZE), fingerprint)?;
+    Ok((page.items, Some(Paged { next_cursor: page.next_cursor })))
+}
+
+/// A page of JSON output.
+#[derive(Serialize)]
+struct JsonPage<'a, T: Serialize + ?Sized> {
+    items: &'a T,
+    next_cursor: Option<&'a str>,
+}
+
+/// Print JSON, wrapped as `{"items": ..., "next_cursor": ...}` when paging.
+pub fn print_json_paged<T: Serialize + ?Sized>(items: &T, paged: Option<&Paged>) {
+    match paged {
+        Some(paged) => {
+            print_json(&JsonPage { items, next_cursor: paged.next_cursor.as_deref() })
+        }
+        None => print_json(items),
+    }
+}
+
+/// Tell the user how to fetch the next page (on stderr, so piped paths stay clean).
+pub fn print_next_cursor(paged: Option<&Paged>) {
+    if let Some(cursor) = paged.and_then(|p| p.next_cursor.as_deref()) {
+        eprintln!("More results: --cursor {cursor}");
     }
 }
 
 /// Formatted note for JSON output.
 #[derive(Debug, Serialize)]
 pub struct NoteOutput {
+    /// Vault (profile) the note belongs to, for commands that take `--vault`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vault: Option<String>,
     pub path: String,
     #[serde(rename = "type")]
     pub note_type: String,
     pub title: String,
     pub modified: String,
+    pub word_count: u32,
+    pub reading_minutes: u32,
+    pub headings: Vec<NoteHeading>,
 }
 
 impl From<&IndexedNote> for NoteOutput {
     fn from(note: &IndexedNote) -> Self {
         Self {
+            vault: None,
             path: note.path.to_string_lossy().to_string(),
             note_type: note.note_type.as_str().to_string(),
             title: note.title.clone(),
             modified: note.modified.format("%Y-%m-%d %H:%M").to_string(),
+            word_count: note.word_count,
+            reading_minutes: note.reading_minutes(),
+            headings: note.headings(),
+        }
+    }
+}
+
+/// Note fields covered by the `--stable-json` guarantee.
+///
+/// Fields may be added but never renamed or removed; values do not depend on
+/// file timestamps or ranking, so output is reproducible for a given vault.
+#[derive(Debug, Serialize)]
+pub struct StableNoteOutput {
+    /// Only present when notes were picked with `--vault` or `--all-vaults`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vault: Option<String>,
+    pub path: String,
+    #[serde(rename = "type")]
+    pub note_type: String,
+    pub title: String,
+}
+
+impl From<&IndexedNote> for StableNoteOutput {
+    fn from(note: &IndexedNote) -> Self {
+        Self {
+            vault: None,
+            path: note.path.to_string_lossy().to_string(),
+            note_type: note.note_type.as_str().to_string(),
+            title: note.title.clone(),
         }
     }
 }
@@ -45,11 +165,20 @@ pub struct LinkOutput {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source_path: Option<String>,
     pub target_path: String,
+    /// Heading text or `^block-id` the link points to within the target.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_anchor: Option<String>,
+    /// Whether `target_anchor` exists in the target note (absent without an anchor).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anchor_found: Option<bool>,
     pub link_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub link_text: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub line_number: Option<u32>,
+    /// The sentence, list item or frontmatter field containing the link.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
 }
 
 impl LinkOutput {
@@ -57,9 +186,12 @@ impl LinkOutput {
         Self {
             source_path: source_path.map(|s| s.to_string()),
             target_path: link.target_path.clone(),
+            target_anchor: link.target_anchor.clone(),
+            anchor_found: None,
             link_type: link.link_type.as_str().to_string(),
             link_text: link.link_text.clone(),
             line_number: link.line_number,
+            context: link.context.clone(),
         }
     }
 }
@@ -124,10 +256,109 @@ pub fn print_notes_table(notes: &[IndexedNote]) {
     println!("-- {} notes --", notes.len());
 }
 
+/// Print notes with their word counts and heading outlines.
+pub fn print_notes_outline(notes: &[IndexedNote]) {
+    if notes.is_empty() {
+        println!("(no notes found)");
+        return;
+    }
+
+    for note in notes {
+        println!(
+            "{}  ({} words, ~{} min)",
+            note.path.display(),
+            note.word_count,
+            note.reading_minutes()
+        );
+        for heading in note.headings() {
+            let indent = "  ".repeat(heading.level as usize);
+            println!("{indent}{}", heading.title);
+        }
+    }
+
+    println!();
+    println!("-- {} notes --", notes.len());
+}
+
 /// Print notes as JSON.
 pub fn print_notes_json(notes: &[IndexedNote]) {
     let output: Vec<NoteOutput> = notes.iter().map(NoteOutput::from).collect();
-    println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+    print_json(&output);
+}
+
+/// Print notes as stable JSON, sorted by path.
+pub fn print_notes_stable_json(notes: &[IndexedNote]) {
+    let mut output: Vec<StableNoteOutput> =
+        notes.iter().map(StableNoteOutput::from).collect();
+    output.sort_by(|a, b| a.path.cmp(&b.path));
+    print_json(&output);
+}
+
+/// Print notes from one or more vaults as JSON, each tagged with its vault.
+pub fn print_vault_notes_json(hits: &[VaultHit<IndexedNote>], paged: Option<&Paged>) {
+    let output: Vec<NoteOutput> = hits
+        .iter()
+        .map(|hit| NoteOutput { vault: Some(hit.vault.clone()), ..(&hit.item).into() })
+        .collect();
+    print_json_paged(&output, paged);
+}
+
+/// Print notes from one or more vaults as stable JSON, sorted by vault and path.
+///
+/// The vault is only included when `tag_vault` is set, so single-vault output
+/// is unchanged.
+pub fn print_vault_notes_stable_json(
+    hits: &[VaultHit<IndexedNote>],
+    tag_vault: bool,
+    paged: Option<&Paged>,
+) {
+    let mut output: Vec<StableNoteOutput> = hits
+        .iter()
+        .map(|hit| StableNoteOutput {
+            vault: tag_vault.then(|| hit.vault.clone()),
+            ..(&hit.item).into()
+        })
+        .collect();
+    output.sort_by(|a, b| (&a.vault, &a.path).cmp(&(&b.vault, &b.path)));
+    print_json_paged(&output, paged);
+}
+
+/// Show a note's path as `vault:path` in table and quiet output.
+pub fn vault_label(vault: &str, note: &mut IndexedNote) {
+    note.path = format!("{vault}:{}", note.path.display()).into();
+}
+
+/// Sort links into the deterministic order used by stable JSON.
+pub fn sort_links_stable(links: &mut [LinkOutput]) {
+    links.sort_by(|a, b| {
+        (&a.source_path, &a.target_path, a.line_number).cmp(&(
+            &b.source_path,
+            &b.target_path,
+            b.line_number,
+        ))
+    });
+}
+
+/// Pretty-print any serializable value as JSON.
+///
+/// With `--output lua:NAME` the formatter's text is printed instead. A
+/// formatter that fails ends the command, as nothing useful can be shown.
+pub fn print_json<T: Serialize + ?Sized>(value: &T) {
+    let formatted = FORMATTER.with_borrow(|formatter| {
+        formatter.as_ref().map(|formatter| {
+            let payload = serde_json::to_value(value).unwrap_or_default();
+            formatter.format(&payload)
+        })
+    });
+    match formatted {
+        None => println!("{}", serde_json::to_string_pretty(value).unwrap_or_default()),
+        Some(Ok(Some(text))) => println!("{}", text.trim_end_matches('\n')),
+        Some(Ok(None)) => {}
+        Some(Err(e)) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    }
 }
 
 /// Print notes as paths only (quiet mode).
@@ -144,25 +375,21 @@ pub fn print_links_table(links: &[LinkOutput], direction: &str) {
         return;
     }
 
-    let path_width = links
-        .iter()
-        .map(|l| {
-            l.target_path.len().max(l.source_path.as_ref().map(|s| s.len()).unwrap_or(0))
-        })
-        .max()
-        .unwrap_or(4)
-        .clamp(4, 50);
+    let paths: Vec<String> =
+        links.iter().map(|l| link_display_path(l, direction)).collect();
+    let path_width = paths.iter().map(|p| p.len()).max().unwrap_or(4).clamp(4, 50);
     let type_width = 10;
 
     println!(
-        "{:<path_width$}  {:<type_width$}  LINE",
+        "{:<path_width$}  {:<type_width$}  LINE    CONTEXT",
         "PATH",
         "LINK_TYPE",
         path_width = path_width,
         type_width = type_width
     );
     println!(
-        "{:-<path_width$}  {:-<type_width$}  {:-<6}",
+        "{:-<path_width$}  {:-<type_width$}  {:-<6}  {:-<7}",
+        "",
         "",
         "",
         "",
@@ -170,21 +397,23 @@ pub fn print_links_table(links: &[LinkOutput], direction: &str) {
         type_width = type_width
     );
 
-    for link in links {
-        let path = if direction == "backlinks" {
-            link.source_path.as_deref().unwrap_or(&link.target_path)
-        } else {
-            &link.target_path
-        };
+    for (link, path) in links.iter().zip(&paths) {
         let path = truncate(path, path_width);
         let line =
             link.line_number.map(|n| n.to_string()).unwrap_or_else(|| "-".to_string());
+        let missing =
+            if link.anchor_found == Some(false) { "  (anchor not found)" } else { "" };
+
+        let context =
+            link.context.as_deref().map(|c| truncate(c, 60)).unwrap_or_default();
 
         println!(
-            "{:<path_width$}  {:<type_width$}  {}",
+            "{:<path_width$}  {:<type_width$}  {:<6}  {}{}",
             path,
             link.link_type,
             line,
+            context,
+            missing,
             path_width = path_width,
             type_width = type_width,
         );
@@ -194,9 +423,23 @@ pub fn print_links_table(links: &[LinkOutput], direction: &str) {
     println!("-- {} {} --", links.len(), direction);
 }
 
+/// The path shown for a link: the other note, plus the anchor it points to.
+fn link_display_path(link: &LinkOutput, direction: &str) -> String {
+    let path = if direction == "backlinks" {
+        link.source_path.as_deref().unwrap_or(&link.target_path)
+    } else {
+        &link.target_path
+    };
+    match (&link.target_anchor, direction) {
+        (Some(anchor), "backlinks") => format!("{} -> #{}", path, anchor),
+        (Some(anchor), _) => format!("{}#{}", path, anchor),
+        (None, _) => path.to_string(),
+    }
+}
+
 /// Print links as JSON.
 pub fn print_links_json(links: &[LinkOutput]) {
-    println!("{}", serde_json::to_string_pretty(&links).unwrap_or_default());
+    print_json(links);
 }
 
 /// Print links as paths only (quiet mode).
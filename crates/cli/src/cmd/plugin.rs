@@ -0,0 +1,95 @@
+//! Plugin command: list compiled-in plugins and run their commands and
+//! exporters.
+
+use std::io::Write;
+use std::path::Path;
+
+use color_eyre::eyre::{Result, WrapErr, eyre};
+use mdvault_core::index::NoteQuery;
+use mdvault_core::plugins::{self, PluginRegistry};
+use serde::Serialize;
+
+use super::common::{load_config, open_index_read_only};
+use super::output::print_json;
+use crate::PluginCommands;
+
+/// One plugin as shown by `mdv plugin list`.
+#[derive(Debug, Serialize)]
+struct PluginSummary {
+    name: &'static str,
+    version: &'static str,
+    commands: Vec<&'static str>,
+    exporters: Vec<&'static str>,
+}
+
+pub fn run(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    command: PluginCommands,
+) -> Result<()> {
+    let cfg = load_config(config, profile)?;
+    let registry = plugins::active().ok_or_else(|| eyre!("Plugins were not loaded"))?;
+
+    match command {
+        PluginCommands::List(args) => list(registry, args.json),
+        PluginCommands::Run(args) => {
+            registry.run_command(&args.command, &args.args).map_err(Into::into)
+        }
+        PluginCommands::Export(args) => {
+            let db = open_index_read_only(&cfg.vault_root)?;
+            let query = NoteQuery {
+                note_type: args.r#type.map(Into::into),
+                ..Default::default()
+            };
+            let notes = db.query_notes(&query).wrap_err("Failed to query notes")?;
+
+            match args.output {
+                Some(path) => {
+                    let mut file = std::fs::File::create(&path).wrap_err_with(|| {
+                        format!("Failed to create {}", path.display())
+                    })?;
+                    registry.export(&args.exporter, &notes, &mut file)?;
+                    file.flush()?;
+                    println!("Exported {} note(s) to {}", notes.len(), path.display());
+                }
+                None => {
+                    let mut stdout = std::io::stdout().lock();
+                    registry.export(&args.exporter, &notes, &mut stdout)?;
+                    stdout.flush()?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+fn list(registry: &PluginRegistry, json: bool) -> Result<()> {
+    let summaries: Vec<PluginSummary> = registry
+        .plugins()
+        .map(|p| PluginSummary {
+            name: p.name(),
+            version: p.version(),
+            commands: p.commands().iter().map(|c| c.name).collect(),
+            exporters: p.exporters().iter().map(|e| e.name).collect(),
+        })
+        .collect();
+
+    if json {
+        print_json(&summaries);
+        return Ok(());
+    }
+    if summaries.is_empty() {
+        println!("No plugins in this build.");
+        return Ok(());
+    }
+    for plugin in registry.plugins() {
+        println!("{} {}", plugin.name(), plugin.version());
+        for c in plugin.commands() {
+            println!("  command   {:<16} {}", c.name, c.about);
+        }
+        for e in plugin.exporters() {
+            println!("  exporter  {:<16} {}", e.name, e.about);
+        }
+    }
+    Ok(())
+}
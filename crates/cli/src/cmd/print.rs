@@ -0,0 +1,355 @@
+//! Print-friendly daily and weekly planning sheets.
+//!
+//! Sheets are plain markdown so they render anywhere; `-o sheet.pdf` hands
+//! the markdown to pandoc for a one-page PDF.
+
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use color_eyre::eyre::{Result, WrapErr, bail};
+use mdvault_core::context::ContextManager;
+use mdvault_core::index::{CheckboxQuery, IndexDb, IndexedNote, NoteQuery};
+
+use super::common::{load_config, open_index_read_only};
+use super::today::{TaskInfo, extract_task_info, get_note_type, priority_order};
+use crate::{PrintArgs, PrintPeriod};
+
+/// Everything that goes on a sheet, for one day or one week.
+struct Sheet {
+    start: NaiveDate,
+    end: NaiveDate,
+    focus: Option<String>,
+    tasks: Vec<TaskInfo>,
+    meetings: Vec<Meeting>,
+    habits: Vec<String>,
+}
+
+struct Meeting {
+    date: NaiveDate,
+    time: Option<String>,
+    title: String,
+    attendees: Vec<String>,
+}
+
+/// Run the print command.
+pub fn run(config: Option<&Path>, profile: Option<&str>, args: PrintArgs) -> Result<()> {
+    let cfg = load_config(config, profile)?;
+    let db = open_index_read_only(&cfg.vault_root)?;
+
+    let date = match &args.date {
+        Some(d) => NaiveDate::parse_from_str(d, "%Y-%m-%d")
+            .wrap_err_with(|| format!("Invalid date '{}', expected YYYY-MM-DD", d))?,
+        None => Local::now().date_naive(),
+    };
+    let (start, end) = match args.period {
+        PrintPeriod::Today => (date, date),
+        PrintPeriod::Week => {
+            let monday =
+                date - Duration::days(date.weekday().num_days_from_monday() as i64);
+            (monday, monday + Duration::days(6))
+        }
+    };
+
+    let focus = ContextManager::load(&cfg.vault_root).ok().and_then(|ctx| {
+        ctx.focus().map(|f| match &f.note {
+            Some(note) => format!("{} ({})", f.project, note),
+            None => f.project.clone(),
+        })
+    });
+    let notes = db.query_notes(&NoteQuery::default()).unwrap_or_default();
+    let active_project = focus.as_deref().and_then(|f| f.split(' ').next());
+
+    let sheet = Sheet {
+        start,
+        end,
+        tasks: top_tasks(&notes, start, active_project, args.tasks),
+        meetings: meetings_between(&notes, start, end),
+        habits: latest_habits(&db, &notes, end),
+        focus,
+    };
+    let markdown = match args.period {
+        PrintPeriod::Today => render_day(&sheet),
+        PrintPeriod::Week => render_week(&sheet),
+    };
+
+    match args.output {
+        None => print!("{}", markdown),
+        Some(path) if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf")) => {
+            write_pdf(&markdown, &path)?;
+            println!("Wrote {}", path.display());
+        }
+        Some(path) => {
+            std::fs::write(&path, &markdown)
+                .wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+            println!("Wrote {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Open tasks, most pressing first: overdue, in progress, in the focused
+/// project, then by priority and due date.
+fn top_tasks(
+    notes: &[IndexedNote],
+    today: NaiveDate,
+    focus_project: Option<&str>,
+    limit: usize,
+) -> Vec<TaskInfo> {
+    let mut tasks: Vec<TaskInfo> = notes
+        .iter()
+        .filter(|n| get_note_type(n).as_deref() == Some("task"))
+        .map(extract_task_info)
+        .filter(|t| {
+            !matches!(t.status.as_str(), "done" | "completed" | "cancelled" | "canceled")
+        })
+        .collect();
+
+    tasks.sort_by_key(|t| {
+        (
+            !is_overdue(t, today),
+            t.status != "in-progress",
+            Some(t.project.as_str()) != focus_project,
+            priority_order(t),
+            t.due_date.clone().unwrap_or_else(|| "9999".to_string()),
+            t.id.clone(),
+        )
+    });
+    tasks.truncate(limit);
+    tasks
+}
+
+fn is_overdue(task: &TaskInfo, today: NaiveDate) -> bool {
+    task.due_date
+        .as_deref()
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .is_some_and(|due| due < today)
+}
+
+/// Meeting notes dated within `start..=end`, in time order.
+fn meetings_between(
+    notes: &[IndexedNote],
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Vec<Meeting> {
+    let mut meetings: Vec<Meeting> = notes
+        .iter()
+        .filter(|n| get_note_type(n).as_deref() == Some("meeting"))
+        .filter_map(|n| {
+            let fm: serde_json::Value =
+                serde_json::from_str(n.frontmatter_json.as_deref()?).ok()?;
+            let date = fm.get("date")?.as_str()?;
+            let date = NaiveDate::parse_from_str(date.get(..10)?, "%Y-%m-%d").ok()?;
+            if date < start || date > end {
+                return None;
+            }
+            let attendees = match fm.get("attendees") {
+                Some(serde_json::Value::Array(items)) => {
+                    items.iter().filter_map(|v| v.as_str().map(String::from)).collect()
+                }
+                Some(serde_json::Value::String(s)) if !s.is_empty() => {
+                    s.split(',').map(|a| a.trim().to_string()).collect()
+                }
+                _ => Vec::new(),
+            };
+            Some(Meeting {
+                date,
+                time: fm.get("time").and_then(|v| v.as_str()).map(String::from),
+                title: n.title.clone(),
+                attendees,
+            })
+        })
+        .collect();
+    meetings
+        .sort_by(|a, b| (a.date, &a.time, &a.title).cmp(&(b.date, &b.time, &b.title)));
+    meetings
+}
+
+/// Checkbox texts under `## Habits` in the latest daily note up to `date`.
+fn latest_habits(db: &IndexDb, notes: &[IndexedNote], date: NaiveDate) -> Vec<String> {
+    let cutoff = date.format("%Y-%m-%d").to_string();
+    let mut dailies: Vec<&IndexedNote> = notes
+        .iter()
+        .filter(|n| get_note_type(n).as_deref() == Some("daily"))
+        .filter(|n| {
+            n.path.file_stem().and_then(|s| s.to_str()).is_some_and(|s| *s <= *cutoff)
+        })
+        .collect();
+    dailies.sort_by(|a, b| b.path.file_stem().cmp(&a.path.file_stem()));
+
+    for daily in dailies {
+        let query = CheckboxQuery {
+            path_contains: Some(daily.path.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let habits: Vec<String> = db
+            .query_checkboxes(&query)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|c| {
+                c.heading.as_deref().is_some_and(|h| h.eq_ignore_ascii_case("habits"))
+            })
+            .map(|c| c.text)
+            .collect();
+        if !habits.is_empty() {
+            return habits;
+        }
+    }
+    Vec::new()
+}
+
+fn render_day(sheet: &Sheet) -> String {
+    let mut md = format!("# {}\n\n", sheet.start.format("%A, %-d %B %Y"));
+    render_focus_and_tasks(&mut md, sheet);
+
+    md.push_str("## Schedule\n\n");
+    if sheet.meetings.is_empty() {
+        md.push_str("_No meetings scheduled._\n\n");
+    } else {
+        md.push_str("| Time | Meeting | With |\n|------|---------|------|\n");
+        for m in &sheet.meetings {
+            writeln!(
+                md,
+                "| {} | {} | {} |",
+                m.time.as_deref().unwrap_or("-"),
+                m.title,
+                m.attendees.join(", ")
+            )
+            .unwrap();
+        }
+        md.push('\n');
+    }
+
+    md.push_str("## Habits\n\n");
+    if sheet.habits.is_empty() {
+        md.push_str(
+            "_Add a `## Habits` checklist to your daily note to track habits here._\n\n",
+        );
+    } else {
+        for habit in &sheet.habits {
+            writeln!(md, "- [ ] {}", habit).unwrap();
+        }
+        md.push('\n');
+    }
+
+    render_notes_space(&mut md);
+    md
+}
+
+fn render_week(sheet: &Sheet) -> String {
+    let mut md = format!(
+        "# Week {}: {} to {}\n\n",
+        sheet.start.format("%G-W%V"),
+        sheet.start.format("%-d %b"),
+        sheet.end.format("%-d %b %Y")
+    );
+    render_focus_and_tasks(&mut md, sheet);
+
+    md.push_str("## Schedule\n\n");
+    if sheet.meetings.is_empty() {
+        md.push_str("_No meetings scheduled._\n\n");
+    }
+    let mut day = sheet.start;
+    while day <= sheet.end {
+        let todays: Vec<&Meeting> =
+            sheet.meetings.iter().filter(|m| m.date == day).collect();
+        if !todays.is_empty() {
+            writeln!(md, "### {}\n", day.format("%A %-d")).unwrap();
+            for m in todays {
+                let time =
+                    m.time.as_deref().map(|t| format!("{} ", t)).unwrap_or_default();
+                let with = if m.attendees.is_empty() {
+                    String::new()
+                } else {
+                    format!(" (with {})", m.attendees.join(", "))
+                };
+                writeln!(md, "- {}{}{}", time, m.title, with).unwrap();
+            }
+            md.push('\n');
+        }
+        day += Duration::days(1);
+    }
+
+    md.push_str("## Habits\n\n");
+    if sheet.habits.is_empty() {
+        md.push_str(
+            "_Add a `## Habits` checklist to your daily note to track habits here._\n\n",
+        );
+    } else {
+        md.push_str("| Habit | Mon | Tue | Wed | Thu | Fri | Sat | Sun |\n");
+        md.push_str("|-------|-----|-----|-----|-----|-----|-----|-----|\n");
+        for habit in &sheet.habits {
+            writeln!(md, "| {} |{}", habit, " [ ] |".repeat(7)).unwrap();
+        }
+        md.push('\n');
+    }
+
+    render_notes_space(&mut md);
+    md
+}
+
+fn render_focus_and_tasks(md: &mut String, sheet: &Sheet) {
+    if let Some(focus) = &sheet.focus {
+        writeln!(md, "**Focus:** {}\n", focus).unwrap();
+    }
+
+    md.push_str("## Top tasks\n\n");
+    if sheet.tasks.is_empty() {
+        md.push_str("_Nothing open._\n\n");
+        return;
+    }
+    for task in &sheet.tasks {
+        let mut details = vec![task.project.clone()];
+        if task.status == "in-progress" {
+            details.push("in progress".to_string());
+        }
+        if let Some(priority) = &task.priority {
+            details.push(priority.clone());
+        }
+        if let Some(due) = &task.due_date {
+            let overdue = if is_overdue(task, sheet.start) { ", overdue" } else { "" };
+            details.push(format!("due {}{}", due, overdue));
+        }
+        writeln!(md, "- [ ] **{}** {} ({})", task.id, task.title, details.join(", "))
+            .unwrap();
+    }
+    md.push('\n');
+}
+
+fn render_notes_space(md: &mut String) {
+    md.push_str("## Notes\n\n");
+    // Non-breaking spaces keep blank writing lines from collapsing when rendered
+    md.push_str(&"&nbsp;\n\n".repeat(5));
+}
+
+/// Convert markdown to PDF with pandoc, the one external tool this needs.
+fn write_pdf(markdown: &str, path: &Path) -> Result<()> {
+    let child = Command::new("pandoc")
+        .args(["--from", "markdown", "--variable", "geometry:margin=2cm", "--output"])
+        .arg(path)
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => bail!(
+            "PDF output needs pandoc on PATH (https://pandoc.org). \
+             Use `-o sheet.md` for markdown instead."
+        ),
+        Err(e) => return Err(e).wrap_err("Failed to start pandoc"),
+    };
+
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(markdown.as_bytes())
+        .wrap_err("Failed to send sheet to pandoc")?;
+    let output = child.wait_with_output().wrap_err("pandoc did not finish")?;
+    if !output.status.success() {
+        bail!("pandoc failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
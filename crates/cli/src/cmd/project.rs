@@ -2,18 +2,23 @@
 
 use chrono::{DateTime, Duration, NaiveDate, Utc};
 use color_eyre::eyre::{Result, WrapErr, bail};
+use mdvault_core::config::types::WebhookEvent;
 use mdvault_core::context::ContextManager;
 use mdvault_core::domain::task_belongs_to_project;
 use mdvault_core::domain::{DailyLogService, services::ProjectLogService};
-use mdvault_core::index::{IndexDb, IndexedNote, NoteQuery, NoteType};
+use mdvault_core::index::{
+    ArchiveImpact, IndexDb, IndexedNote, LinkFate, NoteQuery, NoteType, archive_impact,
+};
 use serde::Serialize;
 use std::path::Path;
-use tabled::{Table, Tabled, settings::Style};
+use tabled::Tabled;
+
+use crate::display::{Mark, mark, progress_bar};
 
 use mdvault_core::paths::PathResolver;
 
-use super::common::{load_config, open_index};
-use crate::{KindFilter, StatusFilter};
+use super::common::{emit_webhook, load_config, open_index, run_on_save_hook};
+use crate::{KindFilter, ProjectUnarchiveArgs, StatusFilter};
 
 /// Row for project list table.
 #[derive(Tabled)]
@@ -150,7 +155,7 @@ pub fn list(
         return Ok(());
     }
 
-    let table = Table::new(&rows).with(Style::rounded()).to_string();
+    let table = crate::display::table(&rows);
 
     println!("{}", table);
     println!("\nTotal: {} projects", rows.len());
@@ -303,13 +308,13 @@ fn print_task_table(tasks: &[&IndexedNote]) {
         })
         .collect();
 
-    let table = Table::new(&rows).with(Style::rounded()).to_string();
+    let table = crate::display::table(&rows);
 
     println!("{}", table);
 }
 
 /// Extract project ID, status, and kind from frontmatter.
-fn extract_project_info(project: &IndexedNote) -> (String, String, String) {
+pub(crate) fn extract_project_info(project: &IndexedNote) -> (String, String, String) {
     let fm = project
         .frontmatter_json
         .as_ref()
@@ -383,42 +388,35 @@ struct ProgressRow {
     bar: String,
 }
 
-/// Progress data for JSON output.
+/// Progress data for JSON output (and the TUI project dashboard).
 #[derive(Serialize)]
-struct ProjectProgress {
-    id: String,
-    title: String,
-    kind: String,
-    status: String,
-    tasks: TaskCounts,
-    progress_percent: f64,
-    recent_completions: Vec<RecentCompletion>,
-    velocity: f64,
+pub(crate) struct ProjectProgress {
+    pub id: String,
+    pub title: String,
+    pub kind: String,
+    pub status: String,
+    pub tasks: TaskCounts,
+    pub progress_percent: f64,
+    pub recent_completions: Vec<RecentCompletion>,
+    pub velocity: f64,
 }
 
 #[derive(Serialize)]
-struct TaskCounts {
-    total: usize,
-    done: usize,
-    in_progress: usize,
-    todo: usize,
-    blocked: usize,
-    cancelled: usize,
+pub(crate) struct TaskCounts {
+    pub total: usize,
+    pub done: usize,
+    pub in_progress: usize,
+    pub todo: usize,
+    pub blocked: usize,
+    pub cancelled: usize,
 }
 
 #[derive(Serialize)]
-struct RecentCompletion {
-    id: String,
-    title: String,
-    completed_at: String,
-    days_ago: i64,
-}
-
-/// Generate a progress bar string.
-fn progress_bar(percent: f64, width: usize) -> String {
-    let filled = ((percent / 100.0) * width as f64).round() as usize;
-    let empty = width.saturating_sub(filled);
-    format!("{}{}", "█".repeat(filled), "░".repeat(empty))
+pub(crate) struct RecentCompletion {
+    pub id: String,
+    pub title: String,
+    pub completed_at: String,
+    pub days_ago: i64,
 }
 
 /// Show project progress with completion metrics and velocity.
@@ -500,7 +498,7 @@ pub fn progress(
 }
 
 /// Calculate progress data for a single project.
-fn calculate_project_progress(
+pub(crate) fn calculate_project_progress(
     project: &IndexedNote,
     all_tasks: &[IndexedNote],
 ) -> ProjectProgress {
@@ -577,7 +575,7 @@ fn calculate_project_progress(
     }
 
     // Sort by most recent first
-    recent_completions.sort_by(|a, b| a.days_ago.cmp(&b.days_ago));
+    recent_completions.sort_by_key(|c| c.days_ago);
 
     // Calculate velocity (tasks per week over last 4 weeks)
     let four_weeks_ago = now - Duration::weeks(4);
@@ -624,12 +622,12 @@ fn print_single_project_progress(data: &ProjectProgress) {
 
     // By status
     println!("By Status:");
-    println!("  ✓ Done:        {}", data.tasks.done);
-    println!("  → In Progress: {}", data.tasks.in_progress);
-    println!("  ○ Todo:        {}", data.tasks.todo);
-    println!("  ⊘ Blocked:     {}", data.tasks.blocked);
+    println!("  {} Done:        {}", mark(Mark::Done), data.tasks.done);
+    println!("  {} In Progress: {}", mark(Mark::InProgress), data.tasks.in_progress);
+    println!("  {} Todo:        {}", mark(Mark::Todo), data.tasks.todo);
+    println!("  {} Blocked:     {}", mark(Mark::Blocked), data.tasks.blocked);
     if data.tasks.cancelled > 0 {
-        println!("  ✗ Cancelled:   {}", data.tasks.cancelled);
+        println!("  {} Cancelled:   {}", mark(Mark::Cancelled), data.tasks.cancelled);
     }
     println!();
 
@@ -684,20 +682,70 @@ fn print_all_projects_progress(data: &[ProjectProgress]) {
         })
         .collect();
 
-    let table = Table::new(&rows).with(Style::rounded()).to_string();
+    let table = crate::display::table(&rows);
     println!("{}", table);
     println!("\nTotal: {} projects", data.len());
 }
 
+/// Log line appended to tasks cancelled by archiving their project.
+const ARCHIVE_CANCEL_NOTE: &str = "Cancelled - Project archived";
+
+/// Days back to look for dailies that mention a project being archived.
+const IMPACT_DAILY_DAYS: i64 = 30;
+
+/// Print what archiving does outside the project folder.
+fn print_archive_impact(impact: &ArchiveImpact) {
+    if impact.is_empty() {
+        println!("Nothing outside the project links to it.");
+        return;
+    }
+    println!("Outside the project:");
+    if !impact.inbound_links.is_empty() {
+        println!(
+            "  - {} inbound link(s): {} rewritten, {} unchanged (by name), {} will break",
+            impact.inbound_links.len(),
+            impact.count(LinkFate::Rewritten),
+            impact.count(LinkFate::Unchanged),
+            impact.count(LinkFate::Broken)
+        );
+        for link in impact.inbound_links.iter().filter(|l| l.fate == LinkFate::Broken) {
+            let line = link.line_number.map(|n| format!(":{n}")).unwrap_or_default();
+            println!("    - {}{} -> {}", link.source.display(), line, link.target);
+        }
+    }
+    if !impact.recent_dailies.is_empty() {
+        println!(
+            "  - Mentioned in {} daily note(s) in the last {} days",
+            impact.recent_dailies.len(),
+            IMPACT_DAILY_DAYS
+        );
+        for daily in &impact.recent_dailies {
+            println!("    - {}", daily.path.display());
+        }
+    }
+    if !impact.dependent_tasks.is_empty() {
+        println!(
+            "  - {} open task(s) in other projects link to it",
+            impact.dependent_tasks.len()
+        );
+        for task in &impact.dependent_tasks {
+            println!("    - {} [{}]: {}", task.path.display(), task.status, task.title);
+        }
+    }
+}
+
 /// Archive a completed project.
 ///
 /// Moves project files to Projects/_archive/{slug}/, cancels open tasks,
-/// clears focus if set, and logs the event.
+/// clears focus if set, and logs the event. The impact on the rest of the vault
+/// is shown first; with `dry_run` nothing else happens.
 pub fn archive(
     config: Option<&Path>,
     profile: Option<&str>,
     project_name: &str,
     skip_confirm: bool,
+    dry_run: bool,
+    json: bool,
 ) -> Result<()> {
     let cfg = load_config(config, profile)?;
     let db = open_index(&cfg.vault_root)?;
@@ -776,8 +824,23 @@ pub fn archive(
         .copied()
         .collect();
 
+    // What moving the folder does to the rest of the vault
+    let since = chrono::Local::now().date_naive() - Duration::days(IMPACT_DAILY_DAYS);
+    let impact = archive_impact(
+        &db,
+        &cfg.vault_root,
+        Path::new(&format!("Projects/{}", project_folder)),
+        since,
+    )
+    .wrap_err("Failed to analyse archive impact")?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&impact)?);
+        return Ok(());
+    }
+
     // Confirmation prompt
-    if !skip_confirm {
+    if dry_run || !skip_confirm {
         println!("Archive project: {} [{}]", project_title, project_id);
         println!();
         println!("This will:");
@@ -796,6 +859,12 @@ pub fn archive(
         println!("  - Set status to 'archived'");
         println!("  - Clear focus if set to this project");
         println!();
+        print_archive_impact(&impact);
+        println!();
+        if dry_run {
+            println!("(dry-run mode - no changes made)");
+            return Ok(());
+        }
         eprint!("Continue? [y/N] ");
 
         use std::io::Read;
@@ -822,6 +891,7 @@ pub fn archive(
 
     // 2. Update project frontmatter: status -> archived, add archived_at
     update_project_frontmatter_for_archive(&project_file_abs);
+    run_on_save_hook(&cfg, &project_file_abs);
 
     // 3. Log to project note (before move so path is valid)
     let archive_msg = format!("Archived project. {} task(s) cancelled.", tasks_cancelled);
@@ -843,54 +913,7 @@ pub fn archive(
         cfg.vault_root.join(format!("Projects/_archive/{}", project_folder));
 
     if source_dir.exists() {
-        // Move each .md file using execute_rename for reference updates
-        let md_files = collect_md_files(&source_dir);
-        let non_md_files = collect_non_md_files(&source_dir);
-
-        // Ensure archive directory structure exists
-        std::fs::create_dir_all(&archive_dir)
-            .wrap_err("Failed to create archive directory")?;
-
-        // Move .md files via execute_rename (updates backlinks and index)
-        for md_file in &md_files {
-            let rel_old = md_file.strip_prefix(&cfg.vault_root).unwrap_or(md_file);
-            let relative_to_source = md_file.strip_prefix(&source_dir).unwrap();
-            let new_abs = archive_dir.join(relative_to_source);
-
-            // Ensure parent dir exists
-            if let Some(parent) = new_abs.parent() {
-                let _ = std::fs::create_dir_all(parent);
-            }
-
-            let rel_new = new_abs.strip_prefix(&cfg.vault_root).unwrap_or(&new_abs);
-
-            match mdvault_core::rename::execute_rename(
-                &db,
-                &cfg.vault_root,
-                rel_old,
-                rel_new,
-            ) {
-                Ok(_) => {}
-                Err(e) => {
-                    eprintln!("Warning: failed to rename {}: {e}", rel_old.display());
-                    // Fall back to direct move
-                    let _ = std::fs::rename(md_file, &new_abs);
-                }
-            }
-        }
-
-        // Move non-.md files directly
-        for file in &non_md_files {
-            let relative_to_source = file.strip_prefix(&source_dir).unwrap();
-            let new_path = archive_dir.join(relative_to_source);
-            if let Some(parent) = new_path.parent() {
-                let _ = std::fs::create_dir_all(parent);
-            }
-            let _ = std::fs::rename(file, &new_path);
-        }
-
-        // Remove the now-empty source directory tree
-        let _ = std::fs::remove_dir_all(&source_dir);
+        move_project_dir(&cfg, &db, &source_dir, &archive_dir)?;
     }
 
     // 6. Log to daily note
@@ -904,6 +927,17 @@ pub fn archive(
         &archived_project_file,
     );
 
+    emit_webhook(
+        &cfg,
+        WebhookEvent::ProjectArchived,
+        &[
+            ("id", &project_id),
+            ("title", &project_title),
+            ("path", &format!("Projects/_archive/{0}/{0}.md", project_folder)),
+            ("tasks_cancelled", &tasks_cancelled.to_string()),
+        ],
+    );
+
     // Output
     println!("OK   mdv project archive");
     println!("project:  {} [{}]", project_title, project_id);
@@ -915,6 +949,263 @@ pub fn archive(
     Ok(())
 }
 
+/// Restore an archived project.
+///
+/// Moves the folder from Projects/_archive/{slug}/ back to Projects/{slug}/,
+/// sets the chosen status, reopens the selected cancelled tasks, optionally
+/// focuses the project, and logs the event.
+pub fn unarchive(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    args: ProjectUnarchiveArgs,
+) -> Result<()> {
+    let cfg = load_config(config, profile)?;
+    let db = open_index(&cfg.vault_root)?;
+
+    if args.status.eq_ignore_ascii_case("archived") {
+        bail!("Cannot restore a project to status 'archived'.");
+    }
+
+    // Find the archived project in the index
+    let project_query =
+        NoteQuery { note_type: Some(NoteType::Project), ..Default::default() };
+    let projects = db.query_notes(&project_query).unwrap_or_default();
+
+    let project = projects.iter().find(|p| {
+        let folder = p.path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        let (id, _, _) = extract_project_info(p);
+        p.path.starts_with("Projects/_archive")
+            && (folder.eq_ignore_ascii_case(&args.project)
+                || id.eq_ignore_ascii_case(&args.project))
+    });
+
+    let project = match project {
+        Some(p) => p,
+        None => {
+            eprintln!(
+                "Run 'mdv project list --include-archived' to see archived projects."
+            );
+            bail!("Archived project not found: {}", args.project);
+        }
+    };
+
+    let project_folder =
+        project.path.file_stem().and_then(|s| s.to_str()).unwrap_or("").to_string();
+    let (project_id, _, _) = extract_project_info(project);
+    let project_title = if project.title.is_empty() {
+        project_folder.clone()
+    } else {
+        project.title.clone()
+    };
+
+    let resolver = PathResolver::new(&cfg.vault_root);
+    let archive_dir =
+        cfg.vault_root.join(format!("Projects/_archive/{}", project_folder));
+    let project_dir = resolver.project_dir(&project_folder);
+    if project_dir.exists() {
+        bail!(
+            "Cannot unarchive '{}': Projects/{}/ already exists.",
+            project_title,
+            project_folder
+        );
+    }
+
+    // Cancelled tasks of this project, and which of them to reopen
+    let task_query = NoteQuery { note_type: Some(NoteType::Task), ..Default::default() };
+    let cancelled_tasks: Vec<IndexedNote> = db
+        .query_notes(&task_query)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|t| {
+            t.path.starts_with(format!("Projects/_archive/{}", project_folder))
+                && matches!(get_task_status(t).as_deref(), Some("cancelled" | "canceled"))
+        })
+        .collect();
+
+    let mut reopen: Vec<&IndexedNote> = Vec::new();
+    for wanted in &args.reopen {
+        let task = cancelled_tasks
+            .iter()
+            .find(|t| get_task_id(t).is_some_and(|id| id.eq_ignore_ascii_case(wanted)));
+        match task {
+            Some(task) => reopen.push(task),
+            None => {
+                bail!("No cancelled task '{}' in project '{}'.", wanted, project_title)
+            }
+        }
+    }
+    if args.reopen_all {
+        reopen.extend(cancelled_tasks.iter().filter(|t| {
+            std::fs::read_to_string(cfg.vault_root.join(&t.path))
+                .is_ok_and(|content| content.contains(ARCHIVE_CANCEL_NOTE))
+        }));
+    }
+
+    // Confirmation prompt
+    if !args.yes {
+        println!("Unarchive project: {} [{}]", project_title, project_id);
+        println!();
+        println!("This will:");
+        println!(
+            "  - Move Projects/_archive/{}/ back to Projects/{}/",
+            project_folder, project_folder
+        );
+        println!("  - Set status to '{}'", args.status);
+        if !reopen.is_empty() {
+            println!("  - Reopen {} cancelled task(s)", reopen.len());
+            for task in &reopen {
+                let tid = get_task_id(task).unwrap_or_else(|| "-".to_string());
+                println!("    - {}: {}", tid, task.title);
+            }
+        }
+        if args.focus {
+            println!("  - Focus on this project");
+        }
+        println!();
+        eprint!("Continue? [y/N] ");
+
+        use std::io::Read;
+        let mut input = [0u8; 1];
+        let _ = std::io::stdin().read(&mut input);
+        if input[0] != b'y' && input[0] != b'Y' {
+            println!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    // --- Execute the unarchive ---
+
+    // 1. Move files back, updating links to them
+    let reopen_paths: Vec<_> = reopen
+        .iter()
+        .filter_map(|t| t.path.strip_prefix("Projects/_archive").ok())
+        .map(|rel| Path::new("Projects").join(rel))
+        .collect();
+    move_project_dir(&cfg, &db, &archive_dir, &project_dir)?;
+
+    // 2. Restore project frontmatter: status, drop archived_at
+    let project_file_abs = resolver.project_note(&project_folder);
+    update_project_frontmatter_for_unarchive(&project_file_abs, &args.status);
+    run_on_save_hook(&cfg, &project_file_abs);
+
+    // 3. Reopen the selected tasks
+    let mut tasks_reopened = 0;
+    for task_rel in &reopen_paths {
+        let task_abs = cfg.vault_root.join(task_rel);
+        if reopen_task_for_unarchive(&cfg, &db, &task_abs, task_rel) {
+            tasks_reopened += 1;
+        }
+    }
+
+    // 4. Reindex the project note and log the event
+    let builder = mdvault_core::index::IndexBuilder::new(&db, &cfg.vault_root);
+    let project_rel = Path::new("Projects")
+        .join(&project_folder)
+        .join(format!("{}.md", project_folder));
+    let unarchive_msg =
+        format!("Unarchived project. {} task(s) reopened.", tasks_reopened);
+    let _ = ProjectLogService::log_entry(&project_file_abs, &unarchive_msg);
+    let _ = builder.reindex_file(&project_rel);
+    let _ = DailyLogService::log_event(
+        &cfg,
+        "Unarchived",
+        "project",
+        &project_title,
+        &project_id,
+        &project_file_abs,
+    );
+
+    // 5. Focus on it if asked
+    if args.focus {
+        ContextManager::load(&cfg.vault_root)
+            .and_then(|mut mgr| mgr.set_focus(&project_id))
+            .wrap_err("Failed to set focus")?;
+    }
+
+    // Output
+    println!("OK   mdv project unarchive");
+    println!("project:  {} [{}]", project_title, project_id);
+    println!("status:   {}", args.status);
+    println!("moved to: Projects/{}/", project_folder);
+    if tasks_reopened > 0 {
+        println!("tasks reopened: {}", tasks_reopened);
+    }
+    if args.focus {
+        println!("focus:    {}", project_id);
+    }
+    Ok(())
+}
+
+/// Move a project folder, updating links to its notes via the rename engine.
+///
+/// Notes go through `execute_rename` so backlinks and the index follow them;
+/// other files are moved as-is. The emptied source tree is removed.
+fn move_project_dir(
+    cfg: &mdvault_core::config::types::ResolvedConfig,
+    db: &IndexDb,
+    source_dir: &Path,
+    dest_dir: &Path,
+) -> Result<()> {
+    // Move each .md file using execute_rename for reference updates
+    let md_files = collect_md_files(source_dir);
+    let non_md_files = collect_non_md_files(source_dir);
+
+    // Ensure destination directory structure exists
+    std::fs::create_dir_all(dest_dir).wrap_err("Failed to create project directory")?;
+
+    let hooks = super::common::load_event_hooks(cfg).unwrap_or_else(|e| {
+        eprintln!("Warning: event hooks not loaded: {e}");
+        None
+    });
+
+    // Move .md files via execute_rename (updates backlinks and index)
+    for md_file in &md_files {
+        let rel_old = md_file.strip_prefix(&cfg.vault_root).unwrap_or(md_file);
+        let relative_to_source = md_file.strip_prefix(source_dir).unwrap();
+        let new_abs = dest_dir.join(relative_to_source);
+
+        // Ensure parent dir exists
+        if let Some(parent) = new_abs.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+
+        let rel_new = new_abs.strip_prefix(&cfg.vault_root).unwrap_or(&new_abs);
+
+        match mdvault_core::rename::execute_rename(
+            db,
+            &cfg.vault_root,
+            rel_old,
+            rel_new,
+            hooks.as_ref(),
+        ) {
+            Ok(result) => {
+                for warning in &result.warnings {
+                    eprintln!("Warning: {warning}");
+                }
+            }
+            Err(e) => {
+                eprintln!("Warning: failed to rename {}: {e}", rel_old.display());
+                // Fall back to direct move
+                let _ = std::fs::rename(md_file, &new_abs);
+            }
+        }
+    }
+
+    // Move non-.md files directly
+    for file in &non_md_files {
+        let relative_to_source = file.strip_prefix(source_dir).unwrap();
+        let new_path = dest_dir.join(relative_to_source);
+        if let Some(parent) = new_path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        let _ = std::fs::rename(file, &new_path);
+    }
+
+    // Remove the now-empty source directory tree
+    let _ = std::fs::remove_dir_all(source_dir);
+    Ok(())
+}
+
 /// Cancel a single task as part of project archival.
 ///
 /// Returns true if successfully cancelled.
@@ -964,10 +1255,11 @@ fn cancel_task_for_archive(
     let today = chrono::Local::now().format("%Y-%m-%d").to_string();
     let time = chrono::Local::now().format("%H:%M").to_string();
     let body = format!(
-        "{}\n- **[[{}]] {}** : Cancelled - Project archived\n",
+        "{}\n- **[[{}]] {}** : {}\n",
         parsed.body.trim_end(),
         today,
         time,
+        ARCHIVE_CANCEL_NOTE,
     );
 
     let final_content = format!("---\n{}---\n{}", yaml_str, body);
@@ -975,6 +1267,7 @@ fn cancel_task_for_archive(
     if std::fs::write(task_abs, final_content).is_err() {
         return false;
     }
+    run_on_save_hook(cfg, task_rel);
 
     // Update index
     let builder = mdvault_core::index::IndexBuilder::new(db, &cfg.vault_root);
@@ -1043,6 +1336,117 @@ fn update_project_frontmatter_for_archive(project_file: &std::path::Path) {
     }
 }
 
+/// Reopen a task cancelled when its project was archived.
+///
+/// Returns true if successfully reopened.
+fn reopen_task_for_unarchive(
+    cfg: &mdvault_core::config::types::ResolvedConfig,
+    db: &IndexDb,
+    task_abs: &std::path::Path,
+    task_rel: &std::path::Path,
+) -> bool {
+    let Ok(content) = std::fs::read_to_string(task_abs) else {
+        return false;
+    };
+    let Ok(parsed) = mdvault_core::frontmatter::parse(&content) else {
+        return false;
+    };
+    let Some(mut fm) = parsed.frontmatter else {
+        return false;
+    };
+
+    fm.fields.insert("status".to_string(), serde_yaml::Value::String("todo".to_string()));
+    fm.fields.remove("cancelled_at");
+
+    let task_id =
+        fm.fields.get("task-id").and_then(|v| v.as_str()).unwrap_or("").to_string();
+    let task_title =
+        fm.fields.get("title").and_then(|v| v.as_str()).unwrap_or("").to_string();
+
+    let mut mapping = serde_yaml::Mapping::new();
+    for (k, v) in fm.fields {
+        mapping.insert(serde_yaml::Value::String(k), v);
+    }
+    let Ok(yaml_str) = serde_yaml::to_string(&serde_yaml::Value::Mapping(mapping)) else {
+        return false;
+    };
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let time = chrono::Local::now().format("%H:%M").to_string();
+    let body = format!(
+        "{}\n- **[[{}]] {}** : Reopened - Project unarchived\n",
+        parsed.body.trim_end(),
+        today,
+        time,
+    );
+
+    if std::fs::write(task_abs, format!("---\n{}---\n{}", yaml_str, body)).is_err() {
+        return false;
+    }
+    run_on_save_hook(cfg, task_rel);
+
+    let builder = mdvault_core::index::IndexBuilder::new(db, &cfg.vault_root);
+    let _ = builder.reindex_file(task_rel);
+    let _ = DailyLogService::log_event(
+        cfg,
+        "Reopened",
+        "task",
+        &task_title,
+        &task_id,
+        task_abs,
+    );
+
+    true
+}
+
+/// Update project frontmatter to the restored status and drop archived_at.
+fn update_project_frontmatter_for_unarchive(
+    project_file: &std::path::Path,
+    status: &str,
+) {
+    let content = match std::fs::read_to_string(project_file) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Failed to read project file: {e}");
+            return;
+        }
+    };
+
+    let parsed = match mdvault_core::frontmatter::parse(&content) {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("Failed to parse project frontmatter: {e}");
+            return;
+        }
+    };
+
+    let Some(mut fm) = parsed.frontmatter else {
+        eprintln!("Project file has no frontmatter");
+        return;
+    };
+
+    fm.fields.insert("status".to_string(), serde_yaml::Value::String(status.to_string()));
+    fm.fields.remove("archived_at");
+
+    let mut mapping = serde_yaml::Mapping::new();
+    for (k, v) in fm.fields {
+        mapping.insert(serde_yaml::Value::String(k), v);
+    }
+    let yaml_str = match serde_yaml::to_string(&serde_yaml::Value::Mapping(mapping)) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Failed to serialize frontmatter: {e}");
+            return;
+        }
+    };
+
+    if let Err(e) =
+        std::fs::write(project_file, format!("---\n{}---\n{}", yaml_str, parsed.body))
+    {
+        eprintln!("Failed to write project file: {e}");
+    }
+}
+
 /// Recursively collect all .md files under a directory.
 fn collect_md_files(dir: &std::path::Path) -> Vec<std::path::PathBuf> {
     let mut result = Vec::new();
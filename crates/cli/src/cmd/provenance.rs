@@ -0,0 +1,155 @@
+//! Provenance command: inspect, regenerate, or strip the generated regions
+//! that mdv commands wrap in `<!-- mdv:generated -->` markers.
+
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use color_eyre::eyre::{Result, WrapErr, bail};
+use mdvault_core::index::IndexDb;
+use mdvault_core::provenance::{self, GeneratedRegion, Provenance};
+
+use super::common::{load_config, open_index_read_only};
+use super::output::print_json;
+use crate::ProvenanceArgs;
+
+pub fn run(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    args: ProvenanceArgs,
+) -> Result<()> {
+    let cfg = load_config(config, profile)?;
+    let path = resolve_note_path(&cfg.vault_root, &args.note);
+    let content = std::fs::read_to_string(&path)
+        .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+    let regions = provenance::find_regions(&content);
+
+    if let Some(which) = args.strip {
+        let selected = select(&regions, which)?;
+        let mut updated = content.clone();
+        // Work back to front so earlier spans stay valid.
+        for region in selected.iter().rev() {
+            updated = provenance::strip_region(&updated, region);
+        }
+        write_note(&path, &updated)?;
+        println!("Stripped {} region(s) from {}", selected.len(), path.display());
+    } else if let Some(which) = args.regenerate {
+        let selected = select(&regions, which)?;
+        let db = open_index_read_only(&cfg.vault_root)?;
+        let now = Utc::now().to_rfc3339();
+        let mut updated = content.clone();
+        let mut regenerated = 0;
+        for (number, region) in selected.iter().rev().map(|r| (number_of(&regions, r), r))
+        {
+            let p = &region.provenance;
+            match regenerate_body(&db, p)? {
+                Some(body) => {
+                    let stamp = Provenance::new(&p.generator, &p.source, &now);
+                    updated = provenance::replace_region(&updated, region, &stamp, &body);
+                    regenerated += 1;
+                }
+                None if which.is_some() => {
+                    bail!("Region {number} ({}) cannot be regenerated", p.generator)
+                }
+                None => println!(
+                    "Skipped region {number}: {} output cannot be regenerated",
+                    p.generator
+                ),
+            }
+        }
+        write_note(&path, &updated)?;
+        println!("Regenerated {regenerated} region(s) in {}", path.display());
+    } else if args.json {
+        print_json(&regions);
+    } else if regions.is_empty() {
+        println!("No generated regions in {}", path.display());
+    } else {
+        println!("Generated regions in {}:", path.display());
+        for (i, region) in regions.iter().enumerate() {
+            let p = &region.provenance;
+            println!(
+                "  {}. {:<8} lines {}-{}  {}  ({}, v{})",
+                i + 1,
+                p.generator,
+                region.start_line,
+                region.end_line,
+                p.source,
+                p.generated_at,
+                p.version
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolve a note argument against the vault root, falling back to the
+/// path as given (report notes may be written outside the vault).
+fn resolve_note_path(vault_root: &Path, note: &str) -> PathBuf {
+    let note = note.strip_prefix("./").unwrap_or(note);
+    let in_vault = vault_root.join(note);
+    if in_vault.exists() { in_vault } else { PathBuf::from(note) }
+}
+
+/// Pick all regions, or the 1-based region `which`.
+fn select(
+    regions: &[GeneratedRegion],
+    which: Option<usize>,
+) -> Result<Vec<GeneratedRegion>> {
+    if regions.is_empty() {
+        bail!("No generated regions found");
+    }
+    match which {
+        None => Ok(regions.to_vec()),
+        Some(n) if (1..=regions.len()).contains(&n) => Ok(vec![regions[n - 1].clone()]),
+        Some(n) => bail!("No region {n}: the note has {} region(s)", regions.len()),
+    }
+}
+
+/// 1-based position of `region` in `regions`.
+fn number_of(regions: &[GeneratedRegion], region: &GeneratedRegion) -> usize {
+    regions.iter().position(|r| r.span == region.span).map_or(0, |i| i + 1)
+}
+
+/// Re-run the generator recorded in `provenance`.
+///
+/// Returns `None` for generators whose output records a one-off session
+/// (such as a weekly review) and so cannot be reproduced.
+fn regenerate_body(db: &IndexDb, provenance: &Provenance) -> Result<Option<String>> {
+    match provenance.generator.as_str() {
+        "report" => super::report::regenerate_markdown(db, &provenance.source).map(Some),
+        _ => Ok(None),
+    }
+}
+
+fn write_note(path: &Path, content: &str) -> Result<()> {
+    std::fs::write(path, content)
+        .wrap_err_with(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regions() -> Vec<GeneratedRegion> {
+        let p = Provenance::new("review", "mdv review --week 2026-W13", "now");
+        let content =
+            format!("{}{}", provenance::wrap(&p, "a"), provenance::wrap(&p, "b"));
+        provenance::find_regions(&content)
+    }
+
+    #[test]
+    fn select_all_or_one() {
+        let regions = regions();
+        assert_eq!(select(&regions, None).unwrap().len(), 2);
+        let one = select(&regions, Some(2)).unwrap();
+        assert_eq!(one[0].body, "b\n");
+        assert_eq!(number_of(&regions, &one[0]), 2);
+    }
+
+    #[test]
+    fn select_rejects_out_of_range() {
+        assert!(select(&regions(), Some(0)).is_err());
+        assert!(select(&regions(), Some(3)).is_err());
+        assert!(select(&[], None).is_err());
+    }
+}
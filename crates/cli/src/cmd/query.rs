@@ -0,0 +1,37 @@
+//! Query command implementation.
+
+use std::path::Path;
+
+use color_eyre::eyre::{Result, WrapErr};
+use mdvault_core::query::Query;
+
+use super::common::{load_config, open_index_read_only};
+use super::output::{
+    print_notes_json, print_notes_quiet, print_notes_stable_json, print_notes_table,
+    resolve_format,
+};
+use crate::{OutputFormat, QueryArgs};
+
+pub fn run(config: Option<&Path>, profile: Option<&str>, args: QueryArgs) -> Result<()> {
+    let rc = load_config(config, profile)?;
+    let query = Query::parse(&args.query)?;
+    let db = open_index_read_only(&rc.vault_root)?;
+
+    let notes = query.run(&db).wrap_err("Error running query")?;
+
+    match resolve_format(
+        config,
+        profile,
+        args.output,
+        args.stable_json,
+        args.json,
+        args.quiet,
+    )? {
+        OutputFormat::Table => print_notes_table(&notes),
+        OutputFormat::Json => print_notes_json(&notes),
+        OutputFormat::StableJson => print_notes_stable_json(&notes),
+        OutputFormat::Quiet => print_notes_quiet(&notes),
+    }
+
+    Ok(())
+}
@@ -3,9 +3,10 @@
 use std::io::Write;
 use std::path::Path;
 
-use super::common::{load_config, open_index};
+use super::common::{load_config, load_types, open_index};
 use color_eyre::eyre::{Result, WrapErr};
-use mdvault_core::index::{DerivedIndexBuilder, IndexBuilder};
+use mdvault_core::index::{DerivedIndexBuilder, IndexBuilder, StalenessModel};
+use mdvault_core::vault::ByteSize;
 
 /// Run the reindex command.
 pub fn run(
@@ -13,6 +14,8 @@ pub fn run(
     profile: Option<&str>,
     verbose: bool,
     force: bool,
+    vacuum: bool,
+    exclude: Vec<String>,
 ) -> Result<()> {
     // Load configuration
     let rc = load_config(config, profile)?;
@@ -46,8 +49,11 @@ pub fn run(
     };
 
     // Build index with exclusions
+    let types = load_types(&rc);
     let builder =
-        IndexBuilder::with_exclusions(&db, &rc.vault_root, rc.excluded_folders.clone());
+        IndexBuilder::with_exclusions(&db, &rc.vault_root, rc.excluded_folders.clone())
+            .exclude(exclude)
+            .with_types(&types);
     let result = if force {
         builder.full_reindex(progress)
     } else {
@@ -86,7 +92,9 @@ pub fn run(
         println!();
         println!("Computing derived indices...");
     }
-    let derived_builder = DerivedIndexBuilder::new(&db);
+    let staleness = StalenessModel::load(&rc.vault_root, &rc.staleness)
+        .map_err(|e| color_eyre::eyre::eyre!("{e}"))?;
+    let derived_builder = DerivedIndexBuilder::new(&db).with_staleness(staleness);
     match derived_builder.compute_all() {
         Ok(derived_stats) => {
             println!();
@@ -95,6 +103,7 @@ pub fn run(
             println!("  Activity records:     {}", derived_stats.activity_records);
             println!("  Activity summaries:   {}", derived_stats.summaries_computed);
             println!("  Cooccurrence pairs:   {}", derived_stats.cooccurrence_pairs);
+            println!("  Duplicate pairs:      {}", derived_stats.duplicate_pairs);
             println!("  Duration:             {}ms", derived_stats.duration_ms);
         }
         Err(e) => {
@@ -102,6 +111,25 @@ pub fn run(
         }
     }
 
+    // Compact on request, or once the index has grown past the configured threshold
+    let auto_vacuum = match rc.index.auto_vacuum_growth {
+        Some(threshold) => {
+            db.growth_since_vacuum().wrap_err("Error sizing index")? >= threshold.0
+        }
+        None => false,
+    };
+    if vacuum || auto_vacuum {
+        let stats = db.vacuum().wrap_err("Error vacuuming index")?;
+        println!();
+        println!("Index maintenance{}:", if vacuum { "" } else { " (auto)" });
+        println!("  Rows pruned:    {}", stats.rows_pruned);
+        println!(
+            "  Size:           {} -> {}",
+            ByteSize(stats.size_before),
+            ByteSize(stats.size_after)
+        );
+    }
+
     println!();
     println!("Index stored at: {}", index_path.display());
 
@@ -0,0 +1,123 @@
+//! Related notes command implementation.
+
+use std::path::Path;
+
+use color_eyre::eyre::{Result, WrapErr};
+use mdvault_core::index::{NoteType, RelatedNote, RelatedSignals, related_notes};
+use serde::Serialize;
+
+use super::common::{load_config, open_index_read_only};
+use super::output::{print_json, resolve_format, truncate};
+use crate::{OutputFormat, RelatedArgs};
+
+/// Related note output for JSON.
+#[derive(Debug, Serialize)]
+struct RelatedOutput {
+    path: String,
+    title: String,
+    #[serde(rename = "type")]
+    note_type: String,
+    score: f64,
+    #[serde(flatten)]
+    signals: RelatedSignals,
+}
+
+pub fn run(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    args: RelatedArgs,
+) -> Result<()> {
+    let rc = load_config(config, profile)?;
+    let db = open_index_read_only(&rc.vault_root)?;
+
+    let note_path = args.note.strip_prefix("./").unwrap_or(&args.note);
+    let note = db
+        .get_note_by_path(Path::new(note_path))
+        .wrap_err("Error looking up note")?
+        .ok_or_else(|| {
+            color_eyre::eyre::eyre!(
+                "Note not found in index: {}\nHint: Check the path or run 'mdv reindex'.",
+                note_path
+            )
+        })?;
+    let note_id = note.id.expect("indexed note should have ID");
+
+    let note_type: Option<NoteType> = args.r#type.map(Into::into);
+    let mut related = related_notes(&db, note_id, usize::MAX)
+        .wrap_err("Error finding related notes")?;
+    related.retain(|r| note_type.is_none_or(|t| r.note.note_type == t));
+    related.truncate(args.limit);
+
+    match resolve_format(config, profile, args.output, false, args.json, args.quiet)? {
+        OutputFormat::Table => print_related_table(&related),
+        OutputFormat::Json | OutputFormat::StableJson => {
+            let output: Vec<RelatedOutput> = related
+                .into_iter()
+                .map(|r| RelatedOutput {
+                    path: r.note.path.to_string_lossy().to_string(),
+                    title: r.note.title,
+                    note_type: r.note.note_type.as_str().to_string(),
+                    score: r.score,
+                    signals: r.signals,
+                })
+                .collect();
+            print_json(&output);
+        }
+        OutputFormat::Quiet => {
+            for r in &related {
+                println!("{}", r.note.path.display());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Summarise why a note is related, strongest signals first.
+fn reasons(signals: &RelatedSignals) -> String {
+    let mut parts = Vec::new();
+    if signals.shared_links > 0 {
+        parts.push(format!("{} shared links", signals.shared_links));
+    }
+    if signals.cooccurrences > 0 {
+        parts.push(format!("{} dailies", signals.cooccurrences));
+    }
+    if !signals.shared_tags.is_empty() {
+        parts.push(format!("#{}", signals.shared_tags.join(" #")));
+    }
+    if signals.text_similarity > 0.0 {
+        parts.push(format!("text {:.0}%", signals.text_similarity * 100.0));
+    }
+    parts.join(", ")
+}
+
+fn print_related_table(related: &[RelatedNote]) {
+    if related.is_empty() {
+        println!("(no related notes found)");
+        return;
+    }
+
+    let path_width = related
+        .iter()
+        .map(|r| r.note.path.to_string_lossy().len())
+        .max()
+        .unwrap_or(4)
+        .clamp(4, 40);
+    let title_width =
+        related.iter().map(|r| r.note.title.len()).max().unwrap_or(5).clamp(5, 30);
+
+    println!("SCORE  {:<path_width$}  {:<title_width$}  WHY", "PATH", "TITLE");
+    println!("{:-<5}  {:-<path_width$}  {:-<title_width$}  {:-<20}", "", "", "", "");
+    for r in related {
+        println!(
+            "{:5.2}  {:<path_width$}  {:<title_width$}  {}",
+            r.score,
+            truncate(&r.note.path.to_string_lossy(), path_width),
+            truncate(&r.note.title, title_width),
+            reasons(&r.signals),
+        );
+    }
+
+    println!();
+    println!("-- {} related notes --", related.len());
+}
@@ -0,0 +1,133 @@
+//! Running mdv against a vault on another machine over SSH.
+//!
+//! `mdv --remote me@home:/srv/notes list --json` runs
+//! `mdv --vault-root /srv/notes list --json` on `home` through `ssh` and
+//! relays its output and exit status, so output is exactly what the remote
+//! mdv prints. The remote machine needs mdv installed and a config with a
+//! profile for that vault.
+
+use std::env;
+use std::ffi::OsString;
+use std::io::{self, IsTerminal};
+use std::process::Command;
+
+use color_eyre::eyre::{Result, WrapErr, bail};
+
+/// An SSH destination and, optionally, the vault path on it.
+#[derive(Debug, PartialEq)]
+pub struct RemoteTarget {
+    pub host: String,
+    pub vault: Option<String>,
+}
+
+impl RemoteTarget {
+    /// Parse `[USER@]HOST[:VAULT_PATH]`.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (host, vault) = match spec.split_once(':') {
+            Some((host, vault)) => (host, Some(vault)),
+            None => (spec, None),
+        };
+        if host.is_empty() || host.ends_with('@') {
+            bail!("Invalid --remote '{spec}': expected [USER@]HOST[:VAULT_PATH]");
+        }
+        Ok(Self {
+            host: host.to_string(),
+            vault: vault.filter(|v| !v.is_empty()).map(String::from),
+        })
+    }
+}
+
+/// Re-run this invocation (`args`, without the program name) on the remote
+/// host and exit with its status.
+///
+/// `MDV_SSH` overrides the ssh program and `MDV_REMOTE_BIN` the remote mdv.
+pub fn run(spec: &str, args: Vec<OsString>) -> Result<()> {
+    let target = RemoteTarget::parse(spec)?;
+    let ssh = env::var_os("MDV_SSH").unwrap_or_else(|| "ssh".into());
+    let mdv = env::var("MDV_REMOTE_BIN").unwrap_or_else(|_| "mdv".to_string());
+
+    let mut remote = vec![shell_quote(&mdv)];
+    if let Some(vault) = &target.vault {
+        remote.push("--vault-root".to_string());
+        remote.push(shell_quote(vault));
+    }
+    remote.extend(strip_remote(args).iter().map(|a| shell_quote(&a.to_string_lossy())));
+
+    let mut cmd = Command::new(&ssh);
+    // Only ask for a terminal when we have one: prompts and the TUI need it,
+    // but it would merge stderr into stdout and break piped JSON
+    if io::stdin().is_terminal() && io::stdout().is_terminal() {
+        cmd.arg("-t");
+    }
+    let status = cmd
+        .arg("--")
+        .arg(&target.host)
+        .arg(remote.join(" "))
+        .status()
+        .wrap_err_with(|| format!("Failed to run '{}'", ssh.to_string_lossy()))?;
+    std::process::exit(status.code().unwrap_or(1))
+}
+
+/// Drop `--remote TARGET` / `--remote=TARGET` from the arguments.
+///
+/// Anything after `--` is positional and kept as is.
+fn strip_remote(args: Vec<OsString>) -> Vec<OsString> {
+    let mut kept = Vec::with_capacity(args.len());
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            kept.push(arg);
+            kept.extend(args);
+            break;
+        } else if arg == "--remote" {
+            args.next();
+        } else if !arg.to_string_lossy().starts_with("--remote=") {
+            kept.push(arg);
+        }
+    }
+    kept
+}
+
+/// Quote a word for a POSIX shell, which is what ssh runs the command with.
+fn shell_quote(s: &str) -> String {
+    let plain = !s.is_empty()
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || "-_./=:@,+%".contains(c));
+    if plain { s.to_string() } else { format!("'{}'", s.replace('\'', r"'\''")) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn os(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn parses_targets() {
+        let target = RemoteTarget::parse("me@home:/srv/notes").unwrap();
+        assert_eq!(target.host, "me@home");
+        assert_eq!(target.vault.as_deref(), Some("/srv/notes"));
+        assert_eq!(RemoteTarget::parse("home:").unwrap().vault, None);
+        assert!(RemoteTarget::parse(":/srv").is_err());
+        assert!(RemoteTarget::parse("me@").is_err());
+    }
+
+    #[test]
+    fn strips_only_the_remote_flag() {
+        let args = os(&["--remote", "home", "list", "--remote=x", "--json"]);
+        assert_eq!(strip_remote(args), os(&["list", "--json"]));
+
+        let args = os(&["capture", "inbox", "--", "--remote", "home"]);
+        assert_eq!(strip_remote(args.clone()), args);
+    }
+
+    #[test]
+    fn quotes_for_the_remote_shell() {
+        assert_eq!(shell_quote("list"), "list");
+        assert_eq!(shell_quote("status=done"), "status=done");
+        assert_eq!(shell_quote("it's here"), r"'it'\''s here'");
+        assert_eq!(shell_quote("priority>=3"), "'priority>=3'");
+        assert_eq!(shell_quote(""), "''");
+    }
+}
@@ -3,11 +3,12 @@
 use std::io::{self, Write};
 use std::path::Path;
 
-use super::common::{load_config, open_index};
+use super::common::{load_config, load_event_hooks, open_index};
 use color_eyre::eyre::Result;
 use mdvault_core::activity::ActivityLogService;
 use mdvault_core::rename::{
-    FileChange, RenameError, RenamePreview, execute_rename, generate_preview,
+    FileChange, RenameError, RenamePreview, execute_heading_rename, execute_rename,
+    generate_heading_preview, generate_preview,
 };
 
 use crate::RenameArgs;
@@ -17,6 +18,19 @@ pub fn run(config: Option<&Path>, profile: Option<&str>, args: RenameArgs) -> Re
     let rc = load_config(config, profile)?;
     let db = open_index(&rc.vault_root)?;
 
+    // `note.md#Heading` renames a heading within the note
+    if let Some((note, heading)) = args.source.to_string_lossy().split_once('#') {
+        let new_heading = args.dest.to_string_lossy();
+        return run_heading_rename(
+            &db,
+            &rc.vault_root,
+            Path::new(note),
+            heading,
+            &new_heading,
+            &args,
+        );
+    }
+
     // Generate preview
     let preview = generate_preview(&db, &rc.vault_root, &args.source, &args.dest)
         .map_err(|e| format_rename_error(&e))?;
@@ -38,8 +52,13 @@ pub fn run(config: Option<&Path>, profile: Option<&str>, args: RenameArgs) -> Re
     }
 
     // Execute rename
-    let result = execute_rename(&db, &rc.vault_root, &args.source, &args.dest)
-        .map_err(|e| format_rename_error(&e))?;
+    let hooks = load_event_hooks(&rc).unwrap_or_else(|e| {
+        eprintln!("Warning: event hooks not loaded: {e}");
+        None
+    });
+    let result =
+        execute_rename(&db, &rc.vault_root, &args.source, &args.dest, hooks.as_ref())
+            .map_err(|e| format_rename_error(&e))?;
 
     // Log to activity log
     if let Some(activity) = ActivityLogService::try_from_config(&rc) {
@@ -88,6 +107,52 @@ pub fn run(config: Option<&Path>, profile: Option<&str>, args: RenameArgs) -> Re
     Ok(())
 }
 
+fn run_heading_rename(
+    db: &mdvault_core::index::IndexDb,
+    vault_root: &Path,
+    note: &Path,
+    heading: &str,
+    new_heading: &str,
+    args: &RenameArgs,
+) -> Result<()> {
+    let preview = generate_heading_preview(db, vault_root, note, heading, new_heading)
+        .map_err(|e| format_rename_error(&e))?;
+
+    println!("Renaming heading in {}: {} -> {}", note.display(), heading, new_heading);
+    println!();
+    println!(
+        "Found {} reference(s) in {} file(s):",
+        preview.total_references(),
+        preview.files_affected()
+    );
+    println!();
+    for change in &preview.changes {
+        print_file_change(change, vault_root);
+    }
+    for warning in &preview.warnings {
+        eprintln!("{}", warning);
+    }
+
+    if args.dry_run {
+        println!("(dry-run mode - no changes made)");
+        return Ok(());
+    }
+
+    if !args.yes && !confirm_rename() {
+        println!("Cancelled.");
+        return Ok(());
+    }
+
+    let result = execute_heading_rename(db, vault_root, note, heading, new_heading)
+        .map_err(|e| format_rename_error(&e))?;
+
+    println!("Renamed heading: {} -> {}", heading, new_heading);
+    println!("Files modified: {}", result.files_modified.len());
+    println!("References updated: {}", result.references_updated);
+
+    Ok(())
+}
+
 fn format_rename_error(e: &RenameError) -> color_eyre::eyre::Report {
     match e {
         RenameError::SourceNotFound(path) => {
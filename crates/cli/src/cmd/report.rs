@@ -1,14 +1,15 @@
 //! Activity report generation commands.
 
-use super::common::{load_config, open_index};
+use super::common::{load_config, load_saved_searches, open_index_read_only};
 use chrono::{Datelike, Duration, Local, NaiveDate, Utc};
 use color_eyre::eyre::{Result, WrapErr, bail};
 use mdvault_core::index::{IndexDb, IndexedNote, NoteQuery};
+use mdvault_core::provenance::{self, Provenance};
 use serde::Serialize;
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
-use tabled::{Table, Tabled, settings::Style};
+use tabled::Tabled;
 
 /// Report data for JSON output.
 #[derive(Serialize)]
@@ -100,9 +101,29 @@ pub fn run(
     json_output: bool,
 ) -> Result<()> {
     let cfg = load_config(config, profile)?;
-    let db = open_index(&cfg.vault_root)?;
+    let db = open_index_read_only(&cfg.vault_root)?;
+    let report = build_report(&db, month, week)?;
 
-    // Determine the time period
+    // Output the report
+    if let Some(path) = output {
+        let markdown = format_markdown_report(&report);
+        fs::write(path, &markdown)
+            .wrap_err_with(|| format!("Failed to write report to {}", path.display()))?;
+        println!("Report written to: {}", path.display());
+    } else if json_output {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        print_terminal_report(&report);
+    }
+    Ok(())
+}
+
+/// Generate report data for a month, a week, or (by default) the current month.
+fn build_report(
+    db: &IndexDb,
+    month: Option<&str>,
+    week: Option<&str>,
+) -> Result<ReportData> {
     let (start_date, end_date, period_str, period_type) = if let Some(m) = month {
         parse_month(m)?
     } else if let Some(w) = week {
@@ -120,21 +141,26 @@ pub fn run(
         (start, end, period, "month".to_string())
     };
 
-    // Generate report data
-    let report = generate_report(&db, start_date, end_date, &period_str, &period_type);
+    Ok(generate_report(db, start_date, end_date, &period_str, &period_type))
+}
 
-    // Output the report
-    if let Some(path) = output {
-        let markdown = format_markdown_report(&report);
-        fs::write(path, &markdown)
-            .wrap_err_with(|| format!("Failed to write report to {}", path.display()))?;
-        println!("Report written to: {}", path.display());
-    } else if json_output {
-        println!("{}", serde_json::to_string_pretty(&report).unwrap());
-    } else {
-        print_terminal_report(&report);
+/// Rebuild the body of a report note from the `source` recorded in its
+/// provenance marker (`mdv report --month YYYY-MM` or `--week YYYY-Wxx`).
+pub(crate) fn regenerate_markdown(db: &IndexDb, source: &str) -> Result<String> {
+    let mut words = source.split_whitespace();
+    let (mut month, mut week) = (None, None);
+    while let Some(word) = words.next() {
+        match word {
+            "--month" => month = words.next(),
+            "--week" => week = words.next(),
+            _ => {}
+        }
     }
-    Ok(())
+    if month.is_none() && week.is_none() {
+        bail!("Report source has no --month or --week: {source}");
+    }
+    let report = build_report(db, month, week)?;
+    Ok(format_markdown_body(&report))
 }
 
 /// Parse a month string (YYYY-MM) into date range.
@@ -376,7 +402,7 @@ fn generate_report(
             })
         })
         .collect();
-    overdue.sort_by(|a, b| b.days_overdue.cmp(&a.days_overdue));
+    overdue.sort_by_key(|t| std::cmp::Reverse(t.days_overdue));
 
     // High priority open tasks
     let mut high_priority: Vec<FlaggedTask> = tasks
@@ -505,9 +531,9 @@ fn print_terminal_report(report: &ReportData) {
     };
 
     println!();
-    println!("{}", "═".repeat(65));
+    println!("{}", crate::display::glyphs(&"═".repeat(65)));
     println!("{:^65}", title);
-    println!("{}", "═".repeat(65));
+    println!("{}", crate::display::glyphs(&"═".repeat(65)));
     println!();
 
     // Summary
@@ -540,7 +566,7 @@ fn print_terminal_report(report: &ReportData) {
                 completed: p.completed,
             })
             .collect();
-        let table = Table::new(&rows).with(Style::rounded()).to_string();
+        let table = crate::display::table(&rows);
         println!("{}", table);
         println!();
     }
@@ -611,6 +637,16 @@ fn print_activity_heatmap(heatmap: &[DayActivity]) {
         return;
     }
 
+    // A grid of shaded cells means nothing to a screen reader; list the days
+    if crate::display::accessible() {
+        let active: Vec<_> = heatmap.iter().filter(|d| d.completed > 0).collect();
+        for day in &active {
+            println!("  {} {}: {} completed", day.weekday, day.date, day.completed);
+        }
+        println!("  No completions on the other {} days", heatmap.len() - active.len());
+        return;
+    }
+
     // Group by week
     let mut weeks: Vec<Vec<&DayActivity>> = Vec::new();
     let mut current_week: Vec<&DayActivity> = Vec::new();
@@ -710,12 +746,6 @@ fn format_month_title(period: &str) -> String {
 
 /// Format report as markdown.
 fn format_markdown_report(report: &ReportData) -> String {
-    let title = if report.period_type == "month" {
-        format_month_title(&report.period)
-    } else {
-        format!("Weekly Report: {}", report.period)
-    };
-
     let mut md = String::new();
 
     // Frontmatter
@@ -726,6 +756,22 @@ fn format_markdown_report(report: &ReportData) -> String {
     md.push_str(&format!("generated: {}\n", report.generated_at));
     md.push_str("---\n\n");
 
+    let source = format!("mdv report --{} {}", report.period_type, report.period);
+    let provenance = Provenance::new("report", source, &report.generated_at);
+    md.push_str(&provenance::wrap(&provenance, &format_markdown_body(report)));
+    md
+}
+
+/// Format the report body (title onwards) as markdown.
+fn format_markdown_body(report: &ReportData) -> String {
+    let title = if report.period_type == "month" {
+        format_month_title(&report.period)
+    } else {
+        format!("Weekly Report: {}", report.period)
+    };
+
+    let mut md = String::new();
+
     // Title
     md.push_str(&format!("# {}\n\n", title));
 
@@ -825,11 +871,12 @@ pub fn run_dashboard(
     visual: bool,
 ) -> Result<()> {
     let cfg = load_config(config, profile)?;
-    let db = open_index(&cfg.vault_root)?;
+    let db = open_index_read_only(&cfg.vault_root)?;
 
     let options = mdvault_core::report::DashboardOptions {
         project: project.map(String::from),
         activity_days,
+        saved_searches: load_saved_searches(&cfg)?,
         ..Default::default()
     };
 
@@ -884,7 +931,7 @@ pub fn run_dashboard(
 /// Print dashboard report to terminal in human-readable format.
 fn print_dashboard_terminal(report: &mdvault_core::report::DashboardReport) {
     println!();
-    println!("{}", "═".repeat(65));
+    println!("{}", crate::display::glyphs(&"═".repeat(65)));
     match &report.scope {
         mdvault_core::report::ReportScope::Vault => {
             println!("{:^65}", "Vault Dashboard");
@@ -893,7 +940,7 @@ fn print_dashboard_terminal(report: &mdvault_core::report::DashboardReport) {
             println!("{:^65}", format!("Dashboard: {} [{}]", title, id));
         }
     }
-    println!("{}", "═".repeat(65));
+    println!("{}", crate::display::glyphs(&"═".repeat(65)));
     println!();
 
     // Summary
@@ -918,11 +965,7 @@ fn print_dashboard_terminal(report: &mdvault_core::report::DashboardReport) {
     if !report.projects.is_empty() {
         println!("PROJECTS");
         for p in &report.projects {
-            let bar_width: usize = 20;
-            let filled =
-                ((p.progress_percent / 100.0) * bar_width as f64).round() as usize;
-            let empty = bar_width.saturating_sub(filled);
-            let bar = format!("{}{}", "█".repeat(filled), "░".repeat(empty));
+            let bar = crate::display::progress_bar(p.progress_percent, 20);
 
             println!(
                 "  {} [{}]  {} {:.0}%  ({}/{} done)  vel: {:.1}/wk",
@@ -990,6 +1033,18 @@ fn print_dashboard_terminal(report: &mdvault_core::report::DashboardReport) {
         println!();
     }
 
+    // Saved searches
+    if !report.saved_searches.is_empty() {
+        println!("SAVED SEARCHES");
+        for search in &report.saved_searches {
+            println!("  {} ({})", search.name, search.count);
+            for note in &search.notes {
+                println!("    {} ({})", note.title, note.path);
+            }
+        }
+        println!();
+    }
+
     // Stale notes
     if !report.activity.stale_notes.is_empty() {
         println!("STALE NOTES (top {})", report.activity.stale_notes.len());
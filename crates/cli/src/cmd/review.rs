@@ -0,0 +1,403 @@
+//! Guided weekly review: walk through stale notes, old open tasks, and
+//! orphaned zettels, then write a summary into this week's weekly note.
+
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDate, Utc};
+use color_eyre::eyre::{Result, WrapErr, eyre};
+use dialoguer::{Select, theme::ColorfulTheme};
+use mdvault_core::context::{ContextQueryService, WeekContext};
+use mdvault_core::index::{IndexDb, IndexedNote, NoteQuery, NoteType};
+use mdvault_core::markdown_ast::{InsertPosition, MarkdownEditor, SectionMatch};
+use mdvault_core::provenance::{self, Provenance};
+
+use super::common::{load_config, open_in_editor, open_index_read_only};
+use super::journal::{Period, ensure_note};
+use crate::ReviewArgs;
+
+/// Heading of the section the review summary is written into.
+const REVIEW_SECTION: &str = "Review";
+
+/// An action chosen for a single review item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReviewAction {
+    Keep,
+    Open,
+    Done,
+    Cancel,
+    SkipRest,
+}
+
+impl ReviewAction {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Keep => "Keep",
+            Self::Open => "Open in editor",
+            Self::Done => "Mark done",
+            Self::Cancel => "Cancel task",
+            Self::SkipRest => "Skip the rest of this step",
+        }
+    }
+}
+
+/// Outcomes recorded while stepping through the review.
+#[derive(Debug, Default)]
+struct ReviewLog {
+    completed: Vec<String>,
+    cancelled: Vec<String>,
+    opened: Vec<String>,
+    stale_reviewed: usize,
+    tasks_reviewed: usize,
+    orphans_reviewed: usize,
+}
+
+/// Run the review command.
+pub fn run(config: Option<&Path>, profile: Option<&str>, args: ReviewArgs) -> Result<()> {
+    let cfg = load_config(config, profile)?;
+    let db = open_index_read_only(&cfg.vault_root)?;
+    let interactive = !args.batch && io::stdin().is_terminal();
+    let today = Local::now().date_naive();
+
+    let week_date = super::context::parse_week_arg(Some(&args.week))
+        .map_err(|e| eyre!("Invalid week: {e}"))?;
+    let week = ContextQueryService::new(&cfg)
+        .week_context(week_date)
+        .wrap_err("Failed to get week context")?;
+
+    println!("Weekly review: {}", week.to_summary());
+    println!();
+
+    let mut log = ReviewLog::default();
+
+    // Step 1: stale notes
+    let stale = find_stale_notes(&db, args.threshold, args.limit)?;
+    print_step_header(1, "Stale notes", stale.len());
+    for note in &stale {
+        log.stale_reviewed += 1;
+        let actions = [ReviewAction::Keep, ReviewAction::Open, ReviewAction::SkipRest];
+        match prompt_action(interactive, &describe_note(note), &actions)? {
+            ReviewAction::Open => open_note(&cfg.vault_root, note, &mut log)?,
+            ReviewAction::SkipRest => break,
+            _ => {}
+        }
+    }
+
+    // Step 2: old open tasks
+    let old_tasks = find_old_open_tasks(&db, today, args.days, args.limit)?;
+    print_step_header(
+        2,
+        &format!("Open tasks older than {} days", args.days),
+        old_tasks.len(),
+    );
+    for task in &old_tasks {
+        log.tasks_reviewed += 1;
+        let actions = [
+            ReviewAction::Keep,
+            ReviewAction::Done,
+            ReviewAction::Cancel,
+            ReviewAction::Open,
+            ReviewAction::SkipRest,
+        ];
+        match prompt_action(interactive, &describe_note(task), &actions)? {
+            ReviewAction::Done => {
+                super::task::done(config, profile, &task.path, None)?;
+                log.completed.push(note_ref(task));
+            }
+            ReviewAction::Cancel => {
+                super::task::cancel(config, profile, &task.path, Some("weekly review"))?;
+                log.cancelled.push(note_ref(task));
+            }
+            ReviewAction::Open => open_note(&cfg.vault_root, task, &mut log)?,
+            ReviewAction::SkipRest => break,
+            ReviewAction::Keep => {}
+        }
+    }
+
+    // Step 3: orphaned zettels
+    let orphans = find_orphaned_zettels(&db, args.limit)?;
+    print_step_header(3, "Orphaned zettels", orphans.len());
+    for note in &orphans {
+        log.orphans_reviewed += 1;
+        let actions = [ReviewAction::Keep, ReviewAction::Open, ReviewAction::SkipRest];
+        match prompt_action(interactive, &describe_note(note), &actions)? {
+            ReviewAction::Open => open_note(&cfg.vault_root, note, &mut log)?,
+            ReviewAction::SkipRest => break,
+            _ => {}
+        }
+    }
+
+    // Step 4: week context and weekly note
+    print_step_header(4, &format!("Week {}", week.week), week.days.len());
+    println!("{}", week.to_markdown());
+
+    let current_week = today.format("%G-W%V").to_string();
+    let weekly_path =
+        ensure_note(config, profile, &cfg, Period::Week, &current_week, args.batch)?;
+    let provenance = Provenance::new(
+        "review",
+        format!("mdv review --week {}", week.week),
+        Utc::now().to_rfc3339(),
+    );
+    let summary = provenance::wrap(&provenance, &render_summary(&week, &log));
+    write_summary(&weekly_path, &summary)?;
+
+    println!("OK   mdv review");
+    println!("weekly: {}", weekly_path.display());
+    Ok(())
+}
+
+/// Stale knowledge notes, excluding periodic notes and tasks (reviewed separately).
+fn find_stale_notes(
+    db: &IndexDb,
+    threshold: f64,
+    limit: usize,
+) -> Result<Vec<IndexedNote>> {
+    let notes = db
+        .get_stale_notes(threshold, None, None)
+        .wrap_err("Failed to query stale notes")?;
+    Ok(notes
+        .into_iter()
+        .map(|(note, _)| note)
+        .filter(|n| {
+            !matches!(n.note_type, NoteType::Daily | NoteType::Weekly | NoteType::Task)
+        })
+        .take(limit)
+        .collect())
+}
+
+/// Open tasks (not done or cancelled) created more than `days` days before `today`.
+fn find_old_open_tasks(
+    db: &IndexDb,
+    today: NaiveDate,
+    days: u32,
+    limit: usize,
+) -> Result<Vec<IndexedNote>> {
+    let query = NoteQuery { note_type: Some(NoteType::Task), ..Default::default() };
+    let tasks = db.query_notes(&query).wrap_err("Failed to query tasks")?;
+    let cutoff = today - chrono::Duration::days(days as i64);
+
+    let mut old: Vec<(NaiveDate, IndexedNote)> = tasks
+        .into_iter()
+        .filter(is_open_task)
+        .filter_map(|t| task_created_date(&t).map(|d| (d, t)))
+        .filter(|(created, _)| *created < cutoff)
+        .collect();
+    old.sort_by_key(|(created, _)| *created);
+
+    Ok(old.into_iter().map(|(_, t)| t).take(limit).collect())
+}
+
+/// Orphaned zettels (no incoming links).
+fn find_orphaned_zettels(db: &IndexDb, limit: usize) -> Result<Vec<IndexedNote>> {
+    let orphans = db.find_orphans().wrap_err("Failed to find orphans")?;
+    Ok(orphans
+        .into_iter()
+        .filter(|n| n.note_type == NoteType::Zettel)
+        .take(limit)
+        .collect())
+}
+
+fn frontmatter_str(note: &IndexedNote, key: &str) -> Option<String> {
+    note.frontmatter_json
+        .as_ref()
+        .and_then(|fm| serde_json::from_str::<serde_json::Value>(fm).ok())
+        .and_then(|fm| fm.get(key).and_then(|v| v.as_str()).map(String::from))
+}
+
+fn is_open_task(task: &IndexedNote) -> bool {
+    let status = frontmatter_str(task, "status").unwrap_or_else(|| "todo".to_string());
+    !matches!(status.as_str(), "done" | "completed" | "cancelled" | "canceled")
+}
+
+/// Creation date from `created`/`created_at` frontmatter, falling back to file times.
+fn task_created_date(task: &IndexedNote) -> Option<NaiveDate> {
+    ["created", "created_at"]
+        .iter()
+        .filter_map(|key| frontmatter_str(task, key))
+        .find_map(|s| NaiveDate::parse_from_str(s.get(..10)?, "%Y-%m-%d").ok())
+        .or_else(|| task.created.map(|c| c.date_naive()))
+        .or_else(|| Some(task.modified.date_naive()))
+}
+
+/// Wikilink-style reference to a note: task ID if present, else file stem.
+fn note_ref(note: &IndexedNote) -> String {
+    let id = frontmatter_str(note, "task-id").unwrap_or_else(|| {
+        note.path.file_stem().and_then(|s| s.to_str()).unwrap_or("note").to_string()
+    });
+    if note.title.is_empty() {
+        format!("[[{id}]]")
+    } else {
+        format!("[[{id}]] {}", note.title)
+    }
+}
+
+fn describe_note(note: &IndexedNote) -> String {
+    format!("{} ({})", note.title, note.path.display())
+}
+
+fn print_step_header(step: usize, title: &str, count: usize) {
+    println!("== Step {step}: {title} ({count}) ==");
+    if count == 0 {
+        println!("  (nothing to review)");
+    }
+}
+
+/// Ask for an action on an item; non-interactive runs list the item and keep it.
+fn prompt_action(
+    interactive: bool,
+    item: &str,
+    actions: &[ReviewAction],
+) -> Result<ReviewAction> {
+    if !interactive {
+        println!("  - {item}");
+        return Ok(ReviewAction::Keep);
+    }
+
+    let labels: Vec<&str> = actions.iter().map(|a| a.label()).collect();
+    let choice = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(item)
+        .items(&labels)
+        .default(0)
+        .interact_opt()
+        .wrap_err("Failed to read review action")?;
+
+    Ok(choice.map(|i| actions[i]).unwrap_or(ReviewAction::SkipRest))
+}
+
+fn open_note(vault_root: &Path, note: &IndexedNote, log: &mut ReviewLog) -> Result<()> {
+    let path: PathBuf = vault_root.join(&note.path);
+    open_in_editor(&path)?;
+    log.opened.push(note_ref(note));
+    Ok(())
+}
+
+/// Render the markdown summary written into the weekly note.
+fn render_summary(week: &WeekContext, log: &ReviewLog) -> String {
+    let mut out = String::new();
+    out.push_str(&format!(
+        "### Week {} ({} to {})\n",
+        week.week, week.start_date, week.end_date
+    ));
+    out.push_str(&format!("- {} tasks completed\n", week.summary.tasks_completed));
+    out.push_str(&format!("- {} tasks created\n", week.summary.tasks_created));
+    out.push_str(&format!("- {} notes modified\n", week.summary.notes_modified));
+    out.push_str(&format!("- {} active days\n", week.summary.active_days));
+    for proj in &week.projects {
+        out.push_str(&format!(
+            "- {}: {} done, {} active\n",
+            proj.name, proj.tasks_done, proj.tasks_active
+        ));
+    }
+
+    out.push_str("\n### Review decisions\n");
+    out.push_str(&format!(
+        "- Reviewed {} stale notes, {} old tasks, {} orphaned zettels\n",
+        log.stale_reviewed, log.tasks_reviewed, log.orphans_reviewed
+    ));
+    for item in &log.completed {
+        out.push_str(&format!("- Completed: {item}\n"));
+    }
+    for item in &log.cancelled {
+        out.push_str(&format!("- Cancelled: {item}\n"));
+    }
+    for item in &log.opened {
+        out.push_str(&format!("- Revisited: {item}\n"));
+    }
+    out
+}
+
+/// Insert the summary into the weekly note's review section, creating it if missing.
+fn write_summary(path: &Path, summary: &str) -> Result<()> {
+    let content = std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("Failed to read weekly note {}", path.display()))?;
+
+    let section = SectionMatch::new(REVIEW_SECTION);
+    let updated = if MarkdownEditor::section_exists(&content, &section) {
+        MarkdownEditor::insert_into_section(
+            &content,
+            &section,
+            summary,
+            InsertPosition::End,
+        )
+        .map_err(|e| eyre!("Failed to update weekly note: {e}"))?
+        .content
+    } else {
+        format!("{}\n\n## {REVIEW_SECTION}\n\n{summary}", content.trim_end())
+    };
+
+    std::fs::write(path, updated)
+        .wrap_err_with(|| format!("Failed to write weekly note {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn task(frontmatter: &str) -> IndexedNote {
+        IndexedNote {
+            id: None,
+            path: PathBuf::from("Projects/TST/Tasks/TST-001.md"),
+            note_type: NoteType::Task,
+            title: "Old task".into(),
+            created: None,
+            modified: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+            frontmatter_json: Some(frontmatter.into()),
+            content_hash: String::new(),
+            word_count: 0,
+            heading_json: None,
+        }
+    }
+
+    #[test]
+    fn created_date_prefers_frontmatter() {
+        let t = task(r#"{"created": "2025-06-01T10:00:00"}"#);
+        assert_eq!(task_created_date(&t), NaiveDate::from_ymd_opt(2025, 6, 1));
+    }
+
+    #[test]
+    fn created_date_falls_back_to_modified() {
+        let t = task("{}");
+        assert_eq!(task_created_date(&t), NaiveDate::from_ymd_opt(2026, 1, 1));
+    }
+
+    #[test]
+    fn done_tasks_are_not_open() {
+        assert!(!is_open_task(&task(r#"{"status": "done"}"#)));
+        assert!(is_open_task(&task(r#"{"status": "todo"}"#)));
+        assert!(is_open_task(&task("{}")));
+    }
+
+    #[test]
+    fn note_ref_uses_task_id() {
+        let t = task(r#"{"task-id": "TST-001"}"#);
+        assert_eq!(note_ref(&t), "[[TST-001]] Old task");
+    }
+
+    #[test]
+    fn write_summary_appends_section() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("week.md");
+        std::fs::write(&path, "---\ntype: weekly\n---\n# 2026-W13\n").unwrap();
+
+        write_summary(&path, "- 1 tasks completed\n").unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("## Review\n\n- 1 tasks completed"));
+    }
+
+    #[test]
+    fn write_summary_reuses_existing_section() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("week.md");
+        std::fs::write(&path, "# 2026-W13\n\n## Review\n\n## Next\n").unwrap();
+
+        write_summary(&path, "- summary line").unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.matches("## Review").count(), 1);
+        let review_pos = content.find("## Review").unwrap();
+        let line_pos = content.find("- summary line").unwrap();
+        let next_pos = content.find("## Next").unwrap();
+        assert!(review_pos < line_pos && line_pos < next_pos);
+    }
+}
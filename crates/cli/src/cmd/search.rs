@@ -1,20 +1,28 @@
 //! Search command implementation.
 
+use std::collections::BTreeMap;
+use std::io::{self, IsTerminal};
 use std::path::Path;
 
 use color_eyre::eyre::{Result, WrapErr};
 use mdvault_core::index::{
-    MatchSource, SearchEngine, SearchMode, SearchQuery, SearchResult,
+    BodyHit, MatchSource, SearchMode, SearchQuery, SearchResult, VaultHit,
+    query_fingerprint,
 };
+use mdvault_core::saved_searches::{self, SavedSearch};
 use serde::Serialize;
 
-use super::common::{load_config, open_index};
-use super::output::{resolve_format, truncate};
+use super::common::{exclude_rules, load_config, load_saved_searches, open_vaults};
+use super::output::{
+    Paged, print_json, print_json_paged, print_next_cursor, resolve_format, take_page,
+    truncate, vault_label,
+};
 use crate::{OutputFormat, SearchArgs, SearchModeArg};
 
 /// Search result for JSON output.
 #[derive(Debug, Serialize)]
 struct SearchResultOutput {
+    vault: String,
     path: String,
     #[serde(rename = "type")]
     note_type: String,
@@ -23,17 +31,61 @@ struct SearchResultOutput {
     match_source: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     staleness: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snippet: Option<String>,
+    /// Character ranges of the matched terms in `snippet`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    highlights: Vec<[usize; 2]>,
+    /// Every hit in the note body: line and character range.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    matches: Vec<BodyHit>,
 }
 
-impl From<&SearchResult> for SearchResultOutput {
-    fn from(result: &SearchResult) -> Self {
+impl From<&VaultHit<SearchResult>> for SearchResultOutput {
+    fn from(hit: &VaultHit<SearchResult>) -> Self {
+        let result = &hit.item;
         Self {
+            vault: hit.vault.clone(),
             path: result.note.path.to_string_lossy().to_string(),
             note_type: result.note.note_type.as_str().to_string(),
             title: result.note.title.clone(),
             score: result.score,
             match_source: format_match_source(&result.match_source),
             staleness: result.staleness,
+            snippet: result.snippet.as_ref().map(|s| s.text.clone()),
+            highlights: result
+                .snippet
+                .iter()
+                .flat_map(|s| &s.highlights)
+                .map(|h| [h.start, h.end])
+                .collect(),
+            matches: result.hits.clone(),
+        }
+    }
+}
+
+/// Search result fields covered by the `--stable-json` guarantee.
+#[derive(Debug, Serialize)]
+struct StableSearchResultOutput {
+    /// Only present with `--vault` or `--all-vaults`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vault: Option<String>,
+    path: String,
+    #[serde(rename = "type")]
+    note_type: String,
+    title: String,
+    match_source: String,
+}
+
+impl StableSearchResultOutput {
+    fn from_hit(hit: &VaultHit<SearchResult>, tag_vault: bool) -> Self {
+        let result = &hit.item;
+        Self {
+            vault: tag_vault.then(|| hit.vault.clone()),
+            path: result.note.path.to_string_lossy().to_string(),
+            note_type: result.note.note_type.as_str().to_string(),
+            title: result.note.title.clone(),
+            match_source: format_match_source(&result.match_source),
         }
     }
 }
@@ -50,48 +102,157 @@ fn format_match_source(source: &MatchSource) -> String {
 }
 
 pub fn run(config: Option<&Path>, profile: Option<&str>, args: SearchArgs) -> Result<()> {
-    // Load configuration
-    let rc = load_config(config, profile)?;
+    if args.list {
+        let cfg = load_config(config, profile)?;
+        let searches = load_saved_searches(&cfg)?;
+        print_saved_searches(&searches, args.json);
+        return Ok(());
+    }
+
+    // A saved search supplies the query; explicit flags still apply on top
+    let saved = match &args.saved {
+        Some(name) => {
+            let cfg = load_config(config, profile)?;
+            let searches = load_saved_searches(&cfg)?;
+            let query = saved_searches::get(&searches, name)
+                .and_then(|search| search.to_query(name))
+                .map_err(|e| color_eyre::eyre::eyre!("{e}"))?;
+            Some(query)
+        }
+        None => None,
+    };
 
-    // Open database
-    let db = open_index(&rc.vault_root)?;
+    let federated = args.all_vaults || !args.vaults.is_empty();
 
     // Convert search mode
     let mode = match args.mode {
-        SearchModeArg::Direct => SearchMode::Direct,
-        SearchModeArg::Neighbourhood => SearchMode::Neighbourhood { hops: 2 },
-        SearchModeArg::Temporal => SearchMode::Temporal { days: 30 },
-        SearchModeArg::Cooccurrence => SearchMode::Cooccurrence { min_shared: 2 },
-        SearchModeArg::Full => SearchMode::Full,
+        Some(SearchModeArg::Direct) => SearchMode::Direct,
+        Some(SearchModeArg::Neighbourhood) => SearchMode::Neighbourhood { hops: 2 },
+        Some(SearchModeArg::Temporal) => SearchMode::Temporal { days: 30 },
+        Some(SearchModeArg::Cooccurrence) => SearchMode::Cooccurrence { min_shared: 2 },
+        Some(SearchModeArg::Full) => SearchMode::Full,
+        // Semantic search blends in the direct matches
+        #[cfg(feature = "embeddings")]
+        Some(SearchModeArg::Semantic) => SearchMode::Direct,
+        None => saved.as_ref().map_or(SearchMode::Direct, |q| q.mode),
     };
 
-    // Build search query
+    let exclude = exclude_rules(&args.exclude)?;
+    let limit = args.limit.or_else(|| saved.as_ref().and_then(|q| q.limit));
+
+    // Build search query; with exclusions the limit applies after filtering
     let query = SearchQuery {
-        text: args.query,
+        text: args.query.or_else(|| saved.as_ref().and_then(|q| q.text.clone())),
         note_type: args.r#type.map(|t| t.into()),
         path_prefix: None,
+        fields: args.filters,
         mode,
-        limit: args.limit,
+        limit: limit.filter(|_| exclude.is_empty()),
         temporal_boost: args.boost,
+        fuzzy: !args.no_fuzzy,
     };
 
     // Execute search
-    let engine = SearchEngine::new(&db);
-    let results = engine.search(&query).wrap_err("Error searching")?;
+    let hits = match args.mode {
+        #[cfg(feature = "embeddings")]
+        Some(SearchModeArg::Semantic) => {
+            if federated {
+                color_eyre::eyre::bail!(
+                    "--mode semantic searches the active vault only; \
+                     drop --vault/--all-vaults"
+                );
+            }
+            semantic_hits(config, profile, &query)?
+        }
+        _ => open_vaults(config, profile, &args.vaults, args.all_vaults)?
+            .search(&query)
+            .wrap_err("Error searching")?,
+    };
 
     // Determine output format
-    let format = resolve_format(args.output, args.json, args.quiet);
+    let format = resolve_format(
+        config,
+        profile,
+        args.output,
+        args.stable_json,
+        args.json,
+        args.quiet,
+    )?;
+
+    let mut hits = hits;
+    if !exclude.is_empty() {
+        hits.retain(|hit| !exclude.is_ignored(&hit.item.note.path, false));
+        if let Some(limit) = limit {
+            hits.truncate(limit as usize);
+        }
+    }
+
+    // Page in the order the output uses
+    let stable = matches!(format, OutputFormat::StableJson);
+    if stable {
+        hits.sort_by(|a, b| {
+            (&a.vault, &a.item.note.path).cmp(&(&b.vault, &b.item.note.path))
+        });
+    }
+    let fingerprint = query_fingerprint(&format!(
+        "search {query:?} {:?} {:?} {:?} {} {stable}",
+        args.mode, args.exclude, args.vaults, args.all_vaults
+    ));
+    let (hits, paged) =
+        take_page(hits, args.cursor.as_deref(), args.page_size, fingerprint)?;
 
     // Output results
     match format {
-        OutputFormat::Table => print_results_table(&results),
-        OutputFormat::Json => print_results_json(&results),
-        OutputFormat::Quiet => print_results_quiet(&results),
+        OutputFormat::Json => print_results_json(&hits, paged.as_ref()),
+        OutputFormat::StableJson => {
+            print_results_stable_json(&hits, federated, paged.as_ref())
+        }
+        _ => {
+            let results: Vec<_> = hits
+                .into_iter()
+                .map(|mut hit| {
+                    if federated {
+                        vault_label(&hit.vault, &mut hit.item.note);
+                    }
+                    hit.item
+                })
+                .collect();
+            match format {
+                OutputFormat::Quiet => print_results_quiet(&results),
+                _ => print_results_table(&results),
+            }
+            print_next_cursor(paged.as_ref());
+        }
     }
 
     Ok(())
 }
 
+/// Run a semantic search over the active vault's embeddings.
+#[cfg(feature = "embeddings")]
+fn semantic_hits(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    query: &SearchQuery,
+) -> Result<Vec<VaultHit<SearchResult>>> {
+    use mdvault_core::embeddings;
+
+    let rc = load_config(config, profile)?;
+    let db = super::common::open_index_read_only(&rc.vault_root)?;
+    let backend = embeddings::backend(&rc).map_err(|e| color_eyre::eyre::eyre!("{e}"))?;
+    let results = embeddings::semantic_search(
+        &db,
+        backend.as_ref(),
+        query,
+        rc.embeddings.semantic_weight,
+    )
+    .wrap_err("Error searching")?;
+    Ok(results
+        .into_iter()
+        .map(|item| VaultHit { vault: rc.active_profile.clone(), item })
+        .collect())
+}
+
 /// Print search results as a table.
 fn print_results_table(results: &[SearchResult]) {
     if results.is_empty() {
@@ -131,6 +292,10 @@ fn print_results_table(results: &[SearchResult]) {
         source_width = source_width,
     );
 
+    // Matched terms are bold on a terminal, bracketed otherwise
+    let (open, close) =
+        if io::stdout().is_terminal() { ("\x1b[1;33m", "\x1b[0m") } else { ("[", "]") };
+
     // Rows
     for result in results {
         let path = truncate(&result.note.path.to_string_lossy(), path_width);
@@ -148,6 +313,11 @@ fn print_results_table(results: &[SearchResult]) {
             title_width = title_width,
             source_width = source_width,
         );
+
+        if let Some(snippet) = &result.snippet {
+            let excerpt = snippet.marked(open, close);
+            println!("    {}", excerpt.split_whitespace().collect::<Vec<_>>().join(" "));
+        }
     }
 
     println!();
@@ -155,10 +325,26 @@ fn print_results_table(results: &[SearchResult]) {
 }
 
 /// Print search results as JSON.
-fn print_results_json(results: &[SearchResult]) {
+fn print_results_json(hits: &[VaultHit<SearchResult>], paged: Option<&Paged>) {
     let output: Vec<SearchResultOutput> =
-        results.iter().map(SearchResultOutput::from).collect();
-    println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+        hits.iter().map(SearchResultOutput::from).collect();
+    print_json_paged(&output, paged);
+}
+
+/// Print search results as stable JSON, sorted by vault and path.
+///
+/// Scores and snippets are omitted: they shift as ranking evolves.
+fn print_results_stable_json(
+    hits: &[VaultHit<SearchResult>],
+    tag_vault: bool,
+    paged: Option<&Paged>,
+) {
+    let mut output: Vec<StableSearchResultOutput> = hits
+        .iter()
+        .map(|hit| StableSearchResultOutput::from_hit(hit, tag_vault))
+        .collect();
+    output.sort_by(|a, b| (&a.vault, &a.path).cmp(&(&b.vault, &b.path)));
+    print_json_paged(&output, paged);
 }
 
 /// Print search results as paths only.
@@ -167,3 +353,24 @@ fn print_results_quiet(results: &[SearchResult]) {
         println!("{}", result.note.path.display());
     }
 }
+
+/// Print saved searches as a table, or JSON.
+fn print_saved_searches(searches: &BTreeMap<String, SavedSearch>, json: bool) {
+    if json {
+        print_json(searches);
+        return;
+    }
+    if searches.is_empty() {
+        println!("(no saved searches)");
+        return;
+    }
+
+    let name_width = searches.keys().map(|n| n.len()).max().unwrap_or(4).max(4);
+    println!("{:<name_width$}  QUERY", "NAME");
+    for (name, search) in searches {
+        println!("{:<name_width$}  {}", name, search.query);
+        if let Some(description) = &search.description {
+            println!("{:<name_width$}  {}", "", description);
+        }
+    }
+}
@@ -0,0 +1,467 @@
+//! Serve command: accept captures over HTTP.
+//!
+//! A single endpoint, `POST /capture`, authenticated with a bearer token
+//! (a client's key from `[security.api.keys]`, or `[serve] token`):
+//!
+//! - `{"capture": "todo", "vars": {"task": "Review PR"}}` runs that capture
+//!   as `mdv capture todo --batch` would
+//! - `{"text": "Call Ana"}` files the text in the inbox as `mdv inbox ingest`
+//!   files a snippet, then runs the `[ingest] capture` if one is set
+//!
+//! Both go through the same pipeline and activity log as the commands.
+//! Each client is rate limited per capability (`capture` or `inbox`) by
+//! `[security.api.limits]`, and every attempt is appended to the audit log
+//! (see [`mdvault_core::api`]).
+//! Each connection is handled on its own thread with one deadline for the
+//! whole request, so a client trickling bytes cannot hold up the others.
+//! Writes still run one at a time, so captures never race each other.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{Local, Utc};
+use color_eyre::eyre::{Result, WrapErr, bail};
+use mdvault_core::api::{AuditLog, AuditRecord, RateLimiter};
+use mdvault_core::ingest::{self, Snippet, SnippetLedger, SnippetOutcome};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use super::common::load_config;
+use super::ingest::{capture_link, record_new_note};
+use crate::ServeArgs;
+
+const DEFAULT_BIND: &str = "127.0.0.1:7171";
+
+/// Client id of `[serve] token` (or `MDV_SERVE_TOKEN`) in the audit log.
+const DEFAULT_CLIENT: &str = "default";
+
+/// Largest request body accepted.
+const MAX_BODY: usize = 1024 * 1024;
+
+/// Most bytes read for the request line and headers.
+const MAX_HEAD: usize = 16 * 1024;
+
+/// How long a client may take to send its whole request.
+const REQUEST_DEADLINE: Duration = Duration::from_secs(10);
+
+/// Connections handled at once; more are answered with 503.
+const MAX_CONNECTIONS: usize = 32;
+
+pub fn run(config: Option<&Path>, profile: Option<&str>, args: ServeArgs) -> Result<()> {
+    let cfg = load_config(config, profile)?;
+    let token = std::env::var("MDV_SERVE_TOKEN")
+        .ok()
+        .or_else(|| cfg.serve.token.clone())
+        .filter(|t| !t.trim().is_empty());
+    let mut keys: Vec<(String, String)> = cfg
+        .security
+        .api
+        .keys
+        .iter()
+        .filter(|(_, key)| !key.trim().is_empty())
+        .map(|(client, key)| (client.clone(), key.clone()))
+        .collect();
+    if let Some(token) = token {
+        keys.push((DEFAULT_CLIENT.to_string(), token));
+    }
+    if keys.is_empty() {
+        bail!(
+            "mdv serve needs a token: set `token` under [serve] or client keys under \
+             [security.api.keys] in your config, or the MDV_SERVE_TOKEN environment variable"
+        );
+    }
+    let bind = args.bind.or_else(|| cfg.serve.bind.clone());
+    let bind = bind.as_deref().unwrap_or(DEFAULT_BIND);
+
+    let listener = TcpListener::bind(bind)
+        .wrap_err_with(|| format!("Failed to listen on {bind}"))?;
+    println!("Listening on http://{}", listener.local_addr()?);
+    std::io::stdout().flush()?;
+
+    let server = Server {
+        config,
+        profile,
+        keys,
+        vault_root: cfg.vault_root.clone(),
+        limiter: RateLimiter::new(cfg.security.api.limits.clone()),
+        audit: AuditLog::from_config(&cfg),
+        writes: Mutex::new(()),
+        connections: AtomicUsize::new(0),
+    };
+    thread::scope(|scope| {
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    eprintln!("Warning: failed to accept connection: {e}");
+                    continue;
+                }
+            };
+            if server.connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                server.connections.fetch_sub(1, Ordering::SeqCst);
+                let _ = stream.set_write_timeout(Some(Duration::from_secs(1)));
+                let _ = write_response(
+                    &mut stream,
+                    &Response::error(503, "too many connections"),
+                );
+                continue;
+            }
+            let server = &server;
+            scope.spawn(move || {
+                server.handle(stream);
+                server.connections.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+    });
+    Ok(())
+}
+
+struct Server<'a> {
+    config: Option<&'a Path>,
+    profile: Option<&'a str>,
+    /// API keys as (client id, key)
+    keys: Vec<(String, String)>,
+    vault_root: PathBuf,
+    limiter: RateLimiter,
+    audit: AuditLog,
+    /// Held while a request writes to the vault
+    writes: Mutex<()>,
+    /// Connections being handled
+    connections: AtomicUsize,
+}
+
+/// A parsed HTTP request.
+struct Request {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+/// The answer to a request.
+struct Response {
+    status: u16,
+    body: Value,
+    /// Seconds until a rate-limited client may try again
+    retry_after: Option<u64>,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Self { status: 200, body, retry_after: None }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        let message = message.into();
+        Self { status, body: json!({ "ok": false, "error": message }), retry_after: None }
+    }
+}
+
+/// The JSON body of `POST /capture`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CaptureRequest {
+    capture: Option<String>,
+    #[serde(default)]
+    vars: serde_json::Map<String, Value>,
+    text: Option<String>,
+}
+
+impl Server<'_> {
+    fn handle(&self, mut stream: TcpStream) {
+        let _ = stream.set_write_timeout(Some(REQUEST_DEADLINE));
+        let deadline = Instant::now() + REQUEST_DEADLINE;
+        let response = match read_request(&stream, deadline) {
+            Ok(request) => {
+                let response = self.respond(&request);
+                println!("{} {} {}", request.method, request.path, response.status);
+                response
+            }
+            Err((status, message)) => Response::error(status, message),
+        };
+        let _ = write_response(&mut stream, &response);
+    }
+
+    fn respond(&self, request: &Request) -> Response {
+        if request.path != "/capture" {
+            return Response::error(404, format!("no such endpoint: {}", request.path));
+        }
+        if request.method != "POST" {
+            return Response::error(405, "use POST");
+        }
+
+        // Every write attempt is audited, whatever becomes of it
+        let mut record = AuditRecord {
+            timestamp: Utc::now(),
+            client: None,
+            operation: None,
+            capture: None,
+            target: None,
+            accepted: false,
+            status: 0,
+            reason: None,
+        };
+        let response = self.write(request, &mut record);
+        record.accepted = response.status == 200;
+        record.status = response.status;
+        if !record.accepted {
+            record.reason = response.body["error"].as_str().map(String::from);
+        }
+        if let Err(e) = self.audit.append(&record) {
+            eprintln!(
+                "Warning: failed to append to {}: {e}",
+                self.audit.path().display()
+            );
+        }
+        response
+    }
+
+    /// Authenticate, rate limit and carry out a write, noting in `record`
+    /// who asked for what.
+    fn write(&self, request: &Request, record: &mut AuditRecord) -> Response {
+        let Some(client) = self.client(request.authorization.as_deref()) else {
+            return Response::error(401, "missing or wrong bearer token");
+        };
+        record.client = Some(client.to_string());
+
+        let request: CaptureRequest = match serde_json::from_slice(&request.body) {
+            Ok(request) => request,
+            Err(e) => return Response::error(400, format!("invalid JSON body: {e}")),
+        };
+        let operation = match (&request.capture, &request.text) {
+            (Some(_), None) => "capture",
+            (None, Some(_)) if request.vars.is_empty() => "inbox",
+            _ => {
+                return Response::error(
+                    400,
+                    "send either {\"capture\", \"vars\"} or {\"text\"}",
+                );
+            }
+        };
+        record.operation = Some(operation.to_string());
+        record.capture = request.capture.clone();
+
+        if let Err(wait) = self.limiter.acquire(client, operation, Instant::now()) {
+            let seconds = wait.as_secs_f64().ceil().max(1.0) as u64;
+            let message =
+                format!("rate limit for {operation} reached, retry in {seconds}s");
+            return Response {
+                retry_after: Some(seconds),
+                ..Response::error(429, message)
+            };
+        }
+
+        let _writing = self.writes.lock().unwrap_or_else(|e| e.into_inner());
+        let result = match request.capture {
+            Some(name) => self.run_capture(&name, request.vars),
+            None => self.file_text(&request.text.unwrap_or_default()),
+        };
+        match result {
+            Ok(body) => {
+                record.target = body["note"].as_str().map(String::from);
+                Response::ok(body)
+            }
+            Err(e) => Response::error(422, format!("{e:#}")),
+        }
+    }
+
+    /// The client whose key is the request's bearer token.
+    fn client(&self, authorization: Option<&str>) -> Option<&str> {
+        let token = authorization?.strip_prefix("Bearer ")?.trim();
+        self.keys
+            .iter()
+            .find(|(_, key)| constant_time_eq(token.as_bytes(), key.as_bytes()))
+            .map(|(client, _)| client.as_str())
+    }
+
+    /// Run a capture as `mdv capture --batch` does.
+    fn run_capture(
+        &self,
+        name: &str,
+        vars: serde_json::Map<String, Value>,
+    ) -> Result<Value> {
+        let vars: Vec<(String, String)> = vars
+            .into_iter()
+            .map(|(k, v)| match v {
+                Value::String(s) => (k, s),
+                other => (k, other.to_string()),
+            })
+            .collect();
+        let target =
+            super::capture::run(self.config, self.profile, name, &vars, true, false)?;
+        let note = target.strip_prefix(&self.vault_root).unwrap_or(&target);
+        Ok(json!({ "ok": true, "capture": name, "note": note }))
+    }
+
+    /// File `text` in the inbox, as `mdv inbox ingest` does with a snippet.
+    fn file_text(&self, text: &str) -> Result<Value> {
+        let cfg = load_config(self.config, self.profile)?;
+        let mut ledger = SnippetLedger::open(&cfg.vault_root)
+            .wrap_err("Failed to read inbox ledger")?;
+        let snippet = Snippet {
+            source: Path::new("mdv-serve"),
+            text,
+            received: Local::now().naive_local(),
+        };
+        match ingest::ingest_snippet(&cfg.vault_root, &mut ledger, &snippet)? {
+            SnippetOutcome::Created(result) => {
+                let rel = record_new_note(
+                    &cfg,
+                    "inbox",
+                    &result.id,
+                    &result.title,
+                    &result.note_path,
+                );
+                if let Some(capture) = &cfg.ingest.capture {
+                    capture_link(self.config, self.profile, capture, &rel, &result.title)
+                        .wrap_err_with(|| {
+                            format!("Text filed, but capture '{capture}' failed")
+                        })?;
+                }
+                Ok(json!({ "ok": true, "note": rel }))
+            }
+            SnippetOutcome::Duplicate(note) => {
+                Ok(json!({ "ok": true, "duplicate": true, "note": note }))
+            }
+        }
+    }
+}
+
+/// Read one request, which must arrive by `deadline`; an error carries the
+/// status to answer with.
+fn read_request(stream: &TcpStream, deadline: Instant) -> Result<Request, (u16, String)> {
+    let stream = DeadlineReader { stream, deadline };
+    let mut reader = BufReader::new(stream.take((MAX_HEAD + MAX_BODY) as u64));
+    let mut line = String::new();
+    let bad = |message: &str| (400, message.to_string());
+    let failed = |e: io::Error, message: &str| match e.kind() {
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => {
+            (408, "the request did not arrive in time".to_string())
+        }
+        _ => bad(message),
+    };
+    reader.read_line(&mut line).map_err(|e| failed(e, "could not read the request"))?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Err(bad("malformed request line"));
+    };
+    let request_path = path.split('?').next().unwrap_or(path).to_string();
+    let method = method.to_string();
+
+    let mut authorization = None;
+    let mut length = 0;
+    loop {
+        line.clear();
+        reader
+            .read_line(&mut line)
+            .map_err(|e| failed(e, "could not read the headers"))?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            return Err(bad("malformed header"));
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            length = value.parse().map_err(|_| bad("invalid Content-Length"))?;
+        } else if name.eq_ignore_ascii_case("authorization") {
+            authorization = Some(value.to_string());
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            return Err((411, "send the body with a Content-Length".into()));
+        }
+    }
+    if length > MAX_BODY {
+        return Err((413, format!("the body may be at most {MAX_BODY} bytes")));
+    }
+
+    let mut body = vec![0; length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|e| failed(e, "body shorter than Content-Length"))?;
+    Ok(Request { method, path: request_path, authorization, body })
+}
+
+/// Reads from a stream until a deadline, however slowly the bytes arrive.
+struct DeadlineReader<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+}
+
+impl Read for DeadlineReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let left = self.deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(left))?;
+        let mut stream = self.stream;
+        stream.read(buf)
+    }
+}
+
+fn write_response(stream: &mut TcpStream, response: &Response) -> std::io::Result<()> {
+    let status = response.status;
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        411 => "Length Required",
+        413 => "Content Too Large",
+        429 => "Too Many Requests",
+        503 => "Service Unavailable",
+        _ => "Unprocessable Content",
+    };
+    let body = response.body.to_string();
+    let mut head = format!(
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n",
+        body.len()
+    );
+    match status {
+        401 => head.push_str("WWW-Authenticate: Bearer\r\n"),
+        405 => head.push_str("Allow: POST\r\n"),
+        _ => {}
+    }
+    if let Some(seconds) = response.retry_after {
+        head.push_str(&format!("Retry-After: {seconds}\r\n"));
+    }
+    stream.write_all(format!("{head}\r\n{body}").as_bytes())?;
+    stream.flush()
+}
+
+/// Compare tokens without leaking how much of them matched through timing.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+
+    #[test]
+    fn test_capture_request_shapes() {
+        let parse = |s: &str| serde_json::from_str::<CaptureRequest>(s);
+        let r = parse(r#"{"capture": "todo", "vars": {"task": "x", "n": 2}}"#).unwrap();
+        assert_eq!(r.capture.as_deref(), Some("todo"));
+        assert_eq!(r.vars["n"], 2);
+        assert_eq!(parse(r#"{"text": "hi"}"#).unwrap().text.as_deref(), Some("hi"));
+        assert!(parse(r#"{"txt": "hi"}"#).is_err());
+    }
+}
@@ -3,12 +3,16 @@
 use std::path::Path;
 
 use color_eyre::eyre::{Result, WrapErr};
-use mdvault_core::index::IndexedNote;
+use mdvault_core::index::{
+    IndexedNote, OrphanFilter, StalenessBreakdown, StalenessModel, find_orphans,
+    staleness_signals,
+};
 use serde::Serialize;
 
-use super::common::{load_config, open_index};
+use super::common::{load_config, open_index_read_only};
 use super::output::{
-    print_notes_json, print_notes_quiet, print_notes_table, resolve_format, truncate,
+    print_json, print_notes_json, print_notes_quiet, print_notes_stable_json,
+    print_notes_table, resolve_format, truncate,
 };
 use crate::{OutputFormat, StaleArgs};
 
@@ -21,6 +25,8 @@ struct StaleNoteOutput {
     title: String,
     staleness: f64,
     last_seen: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    explain: Option<StalenessBreakdown>,
 }
 
 pub fn run(config: Option<&Path>, profile: Option<&str>, args: StaleArgs) -> Result<()> {
@@ -28,18 +34,31 @@ pub fn run(config: Option<&Path>, profile: Option<&str>, args: StaleArgs) -> Res
     let rc = load_config(config, profile)?;
 
     // Open database
-    let db = open_index(&rc.vault_root)?;
+    let db = open_index_read_only(&rc.vault_root)?;
 
     // Determine output format
-    let format = resolve_format(args.output, args.json, args.quiet);
+    let format = resolve_format(
+        config,
+        profile,
+        args.output,
+        args.stable_json,
+        args.json,
+        args.quiet,
+    )?;
 
     // --orphans mode: find notes with no incoming links
     if args.orphans {
-        let orphans = db.find_orphans().wrap_err("Error finding orphans")?;
+        let mut filter = OrphanFilter::from_config(&rc.orphans)
+            .wrap_err("Invalid [orphans] ignore_paths")?;
+        if let Some(days) = args.min_age {
+            filter = filter.min_age(days);
+        }
+        let orphans = find_orphans(&db, &filter).wrap_err("Error finding orphans")?;
 
         match format {
             OutputFormat::Table => print_notes_table(&orphans),
             OutputFormat::Json => print_notes_json(&orphans),
+            OutputFormat::StableJson => print_notes_stable_json(&orphans),
             OutputFormat::Quiet => print_notes_quiet(&orphans),
         }
         return Ok(());
@@ -53,7 +72,7 @@ pub fn run(config: Option<&Path>, profile: Option<&str>, args: StaleArgs) -> Res
     });
 
     // Query stale notes
-    let results: Vec<StaleNote> = if let Some(days) = args.days {
+    let mut results: Vec<StaleNote> = if let Some(days) = args.days {
         // Query by days not seen
         db.get_notes_not_seen_in_days(days, note_type_str.as_deref(), args.limit)
             .wrap_err("Error querying stale notes")?
@@ -62,6 +81,7 @@ pub fn run(config: Option<&Path>, profile: Option<&str>, args: StaleArgs) -> Res
                 note,
                 staleness: 1.0, // Max staleness for day-based query
                 last_seen,
+                explain: None,
             })
             .collect()
     } else {
@@ -73,14 +93,23 @@ pub fn run(config: Option<&Path>, profile: Option<&str>, args: StaleArgs) -> Res
                 note,
                 staleness,
                 last_seen: None, // Not available in staleness query
+                explain: None,
             })
             .collect()
     };
 
+    if args.explain {
+        explain(&rc, &db, &mut results)?;
+    }
+
     // Output results
     match format {
         OutputFormat::Table => print_stale_table(&results),
         OutputFormat::Json => print_stale_json(&results),
+        OutputFormat::StableJson => {
+            let notes: Vec<IndexedNote> = results.into_iter().map(|r| r.note).collect();
+            print_notes_stable_json(&notes)
+        }
         OutputFormat::Quiet => print_stale_quiet(&results),
     }
 
@@ -92,6 +121,29 @@ struct StaleNote {
     note: IndexedNote,
     staleness: f64,
     last_seen: Option<String>,
+    explain: Option<StalenessBreakdown>,
+}
+
+/// Recompute each note's score with the vault's staleness model, keeping
+/// the per-signal breakdown.
+fn explain(
+    rc: &mdvault_core::config::types::ResolvedConfig,
+    db: &mdvault_core::index::IndexDb,
+    notes: &mut [StaleNote],
+) -> Result<()> {
+    let model = StalenessModel::load(&rc.vault_root, &rc.staleness)
+        .map_err(|e| color_eyre::eyre::eyre!("{e}"))?;
+    let signals = staleness_signals(db).wrap_err("Error reading staleness signals")?;
+    for stale in notes {
+        let Some((note, s)) = stale.note.id.and_then(|id| signals.get(&id)) else {
+            continue;
+        };
+        let breakdown = model.breakdown(note, s).map_err(|e| {
+            color_eyre::eyre::eyre!("{}: staleness.lua: {e}", note.path.display())
+        })?;
+        stale.explain = Some(breakdown);
+    }
+    Ok(())
 }
 
 /// Print stale notes as a table.
@@ -144,12 +196,37 @@ fn print_stale_table(notes: &[StaleNote]) {
             path_width = path_width,
             title_width = title_width,
         );
+
+        if let Some(breakdown) = &stale.explain {
+            print_breakdown(breakdown);
+        }
     }
 
     println!();
     println!("-- {} stale notes --", notes.len());
 }
 
+/// Print one note's score breakdown below its row.
+fn print_breakdown(breakdown: &StalenessBreakdown) {
+    for c in &breakdown.components {
+        if c.weight == 0.0 {
+            continue;
+        }
+        println!(
+            "    {:<10}  {:.2} x {:.2} = {:.2}",
+            c.signal,
+            c.value,
+            c.weight,
+            c.contribution()
+        );
+    }
+    if breakdown.from_hook {
+        println!("    {:<10}  {:.2} (from staleness.lua)", "score", breakdown.score);
+    } else {
+        println!("    {:<10}  {:.2}", "score", breakdown.score);
+    }
+}
+
 /// Print stale notes as JSON.
 fn print_stale_json(notes: &[StaleNote]) {
     let output: Vec<StaleNoteOutput> = notes
@@ -160,9 +237,10 @@ fn print_stale_json(notes: &[StaleNote]) {
             title: stale.note.title.clone(),
             staleness: stale.staleness,
             last_seen: stale.last_seen.clone(),
+            explain: stale.explain.clone(),
         })
         .collect();
-    println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+    print_json(&output);
 }
 
 /// Print stale notes as paths only.
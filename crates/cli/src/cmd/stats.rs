@@ -0,0 +1,63 @@
+//! Stats command implementation.
+
+use std::path::Path;
+
+use color_eyre::eyre::{Result, WrapErr};
+use mdvault_core::index::READING_WPM;
+use mdvault_core::paths::PathResolver;
+use mdvault_core::vault::{ByteSize, VaultUsage};
+
+use super::common::{load_config, open_index_read_only};
+use crate::StatsArgs;
+
+/// Show vault size, quota usage, and optionally the largest files.
+pub fn run(config: Option<&Path>, profile: Option<&str>, args: StatsArgs) -> Result<()> {
+    let cfg = load_config(config, profile)?;
+    let usage = VaultUsage::scan(&cfg.vault_root, cfg.excluded_folders.clone())
+        .wrap_err("Failed to measure vault")?;
+
+    let (files, notes) = (usage.files.len(), usage.note_count());
+    println!("files: {files} ({notes} notes, {} attachments)", files - notes);
+    println!("size:  {}", ByteSize(usage.total));
+    // Word counts come from the index; skip them rather than build one here
+    if PathResolver::new(&cfg.vault_root).index_db().exists() {
+        let words = open_index_read_only(&cfg.vault_root)?.count_words()? as u64;
+        println!("words: {words} (~{} min read)", words.div_ceil(READING_WPM.into()));
+    }
+    if let Some(limit) = cfg.quota.vault {
+        print_quota("vault", usage.total, limit);
+    }
+    for (folder, limit) in &cfg.quota.folders {
+        print_quota(&format!("{folder}/"), usage.folder_size(Path::new(folder)), *limit);
+    }
+
+    if args.large {
+        println!("\nLargest files:");
+        for file in usage.largest(args.limit) {
+            println!(
+                "  {:>10}  {}",
+                ByteSize(file.size).to_string(),
+                file.relative_path.display()
+            );
+        }
+        println!("\nFolders:");
+        for (folder, size) in usage.top_folders() {
+            let name = if folder.as_os_str().is_empty() {
+                "(vault root)".to_string()
+            } else {
+                format!("{}/", folder.display())
+            };
+            println!("  {:>10}  {name}", ByteSize(size).to_string());
+        }
+    }
+
+    for exceeded in usage.check_quota(&cfg.quota) {
+        eprintln!("Warning: {exceeded}");
+    }
+    Ok(())
+}
+
+fn print_quota(scope: &str, used: u64, limit: ByteSize) {
+    let percent = (used * 100).checked_div(limit.0).unwrap_or(100);
+    println!("quota: {scope} {} of {limit} ({percent}%)", ByteSize(used));
+}
@@ -0,0 +1,182 @@
+//! Link suggestion command implementation.
+
+use std::path::Path;
+
+use color_eyre::eyre::{Result, WrapErr, eyre};
+use mdvault_core::index::{
+    IndexBuilder, LinkSuggestion, SuggestionSignals, suggest_links,
+};
+use mdvault_core::markdown_ast::{InsertPosition, MarkdownEditor, SectionMatch};
+use serde::Serialize;
+
+use super::common::{load_config, open_index, open_index_read_only};
+use super::output::{print_json, resolve_format, truncate};
+use crate::{OutputFormat, SuggestLinksArgs};
+
+/// Heading `--apply` adds the links under.
+const RELATED_SECTION: &str = "Related";
+
+/// Link suggestion output for JSON.
+#[derive(Debug, Serialize)]
+struct SuggestionOutput {
+    path: String,
+    title: String,
+    #[serde(rename = "type")]
+    note_type: String,
+    confidence: f64,
+    link: String,
+    #[serde(flatten)]
+    signals: SuggestionSignals,
+}
+
+pub fn run(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    args: SuggestLinksArgs,
+) -> Result<()> {
+    let rc = load_config(config, profile)?;
+    let db = if args.apply {
+        open_index(&rc.vault_root)?
+    } else {
+        open_index_read_only(&rc.vault_root)?
+    };
+
+    let note_path = args.note.strip_prefix("./").unwrap_or(&args.note);
+    let note = db
+        .get_note_by_path(Path::new(note_path))
+        .wrap_err("Error looking up note")?
+        .ok_or_else(|| {
+            eyre!(
+                "Note not found in index: {}\nHint: Check the path or run 'mdv reindex'.",
+                note_path
+            )
+        })?;
+    let note_id = note.id.expect("indexed note should have ID");
+
+    let suggestions =
+        suggest_links(&db, note_id, args.limit).wrap_err("Error suggesting links")?;
+
+    if args.apply && !suggestions.is_empty() {
+        let links: String =
+            suggestions.iter().map(|s| format!("- {}\n", wikilink(s))).collect();
+        write_related(&rc.vault_root.join(&note.path), &links)?;
+
+        let builder = IndexBuilder::with_exclusions(
+            &db,
+            &rc.vault_root,
+            rc.excluded_folders.clone(),
+        );
+        if let Err(e) = builder.incremental_reindex(None) {
+            eprintln!("Warning: reindex failed: {e}");
+        }
+    }
+
+    match resolve_format(config, profile, args.output, false, args.json, args.quiet)? {
+        OutputFormat::Table => {
+            print_suggestions_table(&suggestions);
+            if args.apply && !suggestions.is_empty() {
+                println!(
+                    "Added {} links to {} under '## {RELATED_SECTION}'",
+                    suggestions.len(),
+                    note.path.display()
+                );
+            }
+        }
+        OutputFormat::Json | OutputFormat::StableJson => {
+            let output: Vec<SuggestionOutput> = suggestions
+                .into_iter()
+                .map(|s| SuggestionOutput {
+                    link: wikilink(&s),
+                    path: s.note.path.to_string_lossy().to_string(),
+                    title: s.note.title,
+                    note_type: s.note.note_type.as_str().to_string(),
+                    confidence: s.confidence,
+                    signals: s.signals,
+                })
+                .collect();
+            print_json(&output);
+        }
+        OutputFormat::Quiet => {
+            for s in &suggestions {
+                println!("{}", s.note.path.display());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The wikilink to a suggested note: its path without `.md`, titled.
+fn wikilink(suggestion: &LinkSuggestion) -> String {
+    let target = suggestion.note.path.with_extension("");
+    format!("[[{}|{}]]", target.to_string_lossy(), suggestion.note.title)
+}
+
+/// Append the links to the note's Related section, creating it if missing.
+fn write_related(path: &Path, links: &str) -> Result<()> {
+    let content = std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("Failed to read note {}", path.display()))?;
+
+    let section = SectionMatch::new(RELATED_SECTION);
+    let updated = if MarkdownEditor::section_exists(&content, &section) {
+        MarkdownEditor::insert_into_section(
+            &content,
+            &section,
+            links,
+            InsertPosition::End,
+        )
+        .map_err(|e| eyre!("Failed to update note: {e}"))?
+        .content
+    } else {
+        format!("{}\n\n## {RELATED_SECTION}\n\n{links}", content.trim_end())
+    };
+
+    std::fs::write(path, updated)
+        .wrap_err_with(|| format!("Failed to write note {}", path.display()))
+}
+
+/// Summarise why a link is suggested.
+fn reasons(signals: &SuggestionSignals) -> String {
+    let mut parts = Vec::new();
+    if let Some(name) = &signals.matched {
+        match signals.mentions {
+            1 => parts.push(format!("mentions \"{name}\"")),
+            n => parts.push(format!("mentions \"{name}\" {n}x")),
+        }
+    }
+    if signals.cooccurrences > 0 {
+        parts.push(format!("{} dailies", signals.cooccurrences));
+    }
+    parts.join(", ")
+}
+
+fn print_suggestions_table(suggestions: &[LinkSuggestion]) {
+    if suggestions.is_empty() {
+        println!("(no link suggestions)");
+        return;
+    }
+
+    let path_width = suggestions
+        .iter()
+        .map(|s| s.note.path.to_string_lossy().len())
+        .max()
+        .unwrap_or(4)
+        .clamp(4, 40);
+    let title_width =
+        suggestions.iter().map(|s| s.note.title.len()).max().unwrap_or(5).clamp(5, 30);
+
+    println!("CONF   {:<path_width$}  {:<title_width$}  WHY", "PATH", "TITLE");
+    println!("{:-<5}  {:-<path_width$}  {:-<title_width$}  {:-<20}", "", "", "", "");
+    for s in suggestions {
+        println!(
+            "{:5.2}  {:<path_width$}  {:<title_width$}  {}",
+            s.confidence,
+            truncate(&s.note.path.to_string_lossy(), path_width),
+            truncate(&s.note.title, title_width),
+            reasons(&s.signals),
+        );
+    }
+
+    println!();
+    println!("-- {} link suggestions --", suggestions.len());
+}
@@ -2,15 +2,22 @@
 
 use color_eyre::eyre::{Result, WrapErr, bail};
 use mdvault_core::activity::ActivityLogService;
+use mdvault_core::config::types::{ResolvedConfig, WebhookEvent};
 use mdvault_core::domain::{
     DailyLogService, find_project_file, services::ProjectLogService,
 };
-use mdvault_core::index::{IndexBuilder, IndexDb, IndexedNote, NoteQuery, NoteType};
+use mdvault_core::index::{
+    CheckboxQuery, IndexBuilder, IndexDb, IndexedNote, NoteQuery, NoteType,
+};
 use mdvault_core::paths::PathResolver;
+use mdvault_core::types::check_transition;
+use std::collections::HashMap;
 use std::path::Path;
-use tabled::{Table, Tabled, settings::Style};
+use tabled::Tabled;
 
-use super::common::{load_config, open_index};
+use super::common::{
+    emit_webhook, load_config, load_types, open_index, run_on_save_hook,
+};
 use crate::StatusFilter;
 
 /// Row for task list table.
@@ -90,13 +97,65 @@ pub fn list(
     // Sort by project then ID
     rows.sort_by(|a, b| a.project.cmp(&b.project).then_with(|| a.id.cmp(&b.id)));
 
-    let table = Table::new(&rows).with(Style::rounded()).to_string();
+    let table = crate::display::table(&rows);
 
     println!("{}", table);
     println!("\nTotal: {} tasks", rows.len());
     Ok(())
 }
 
+/// Row for inline (checkbox) task list table.
+#[derive(Tabled)]
+struct InlineTaskRow {
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "Task")]
+    text: String,
+    #[tabled(rename = "Note")]
+    location: String,
+    #[tabled(rename = "Section")]
+    heading: String,
+}
+
+/// List checkbox items found inside notes.
+pub fn list_inline(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    project_filter: Option<&str>,
+    status_filter: Option<StatusFilter>,
+) -> Result<()> {
+    let cfg = load_config(config, profile)?;
+    let db = open_index(&cfg.vault_root)?;
+
+    let query = CheckboxQuery {
+        path_contains: project_filter.map(String::from),
+        ..Default::default()
+    };
+    let items = db.query_checkboxes(&query).wrap_err("Failed to query checkbox items")?;
+
+    let rows: Vec<InlineTaskRow> = items
+        .into_iter()
+        .filter(|item| status_filter.is_none_or(|f| f.matches(item.status.as_str())))
+        .map(|item| InlineTaskRow {
+            status: item.status.as_str().to_string(),
+            text: item.text,
+            location: format!("{}:{}", item.note_path.display(), item.line_number),
+            heading: item.heading.unwrap_or_else(|| "-".to_string()),
+        })
+        .collect();
+
+    if rows.is_empty() {
+        println!("No inline tasks found.");
+        return Ok(());
+    }
+
+    let table = crate::display::table(&rows);
+
+    println!("{}", table);
+    println!("\nTotal: {} inline tasks", rows.len());
+    Ok(())
+}
+
 /// Show detailed status for a specific task.
 pub fn status(config: Option<&Path>, profile: Option<&str>, task_id: &str) -> Result<()> {
     let cfg = load_config(config, profile)?;
@@ -200,6 +259,9 @@ pub fn done(
         }
     };
 
+    check_status_transition(&cfg, &fm.fields, "done")
+        .wrap_err("Cannot mark task done")?;
+
     // Update status to done
     fm.fields.insert("status".to_string(), serde_yaml::Value::String("done".to_string()));
 
@@ -266,6 +328,7 @@ pub fn done(
 
     // Write back
     std::fs::write(&full_path, final_content).wrap_err("Failed to write task")?;
+    run_on_save_hook(&cfg, task_path);
 
     // Update index for this file
     let index_path = PathResolver::new(&cfg.vault_root).index_db();
@@ -300,6 +363,19 @@ pub fn done(
         let _ = ProjectLogService::log_entry(&project_file, &msg);
     }
 
+    let rel_path = full_path.strip_prefix(&cfg.vault_root).unwrap_or(&full_path);
+    emit_webhook(
+        &cfg,
+        WebhookEvent::TaskCompleted,
+        &[
+            ("id", &task_id),
+            ("title", &safe_title),
+            ("path", &rel_path.to_string_lossy()),
+            ("project", project_name.as_deref().unwrap_or_default()),
+            ("summary", summary.unwrap_or_default()),
+        ],
+    );
+
     println!("OK   mdv task done");
     println!("task:   {}", task_id);
     println!("status: done");
@@ -343,6 +419,9 @@ pub fn cancel(
         }
     };
 
+    check_status_transition(&cfg, &fm.fields, "cancelled")
+        .wrap_err("Cannot cancel task")?;
+
     // Update status to cancelled
     fm.fields
         .insert("status".to_string(), serde_yaml::Value::String("cancelled".to_string()));
@@ -410,6 +489,7 @@ pub fn cancel(
 
     // Write back
     std::fs::write(&full_path, final_content).wrap_err("Failed to write task")?;
+    run_on_save_hook(&cfg, task_path);
 
     // Update index for this file
     let index_path = PathResolver::new(&cfg.vault_root).index_db();
@@ -493,6 +573,19 @@ fn strip_wikilinks(s: &str) -> String {
     result
 }
 
+/// Check that the task type's `transitions` allow its status to become `to`.
+fn check_status_transition(
+    cfg: &ResolvedConfig,
+    fields: &HashMap<String, serde_yaml::Value>,
+    to: &str,
+) -> Result<()> {
+    let Some(from) = fields.get("status").and_then(|v| v.as_str()) else {
+        return Ok(());
+    };
+    check_transition(&load_types(cfg), "task", "status", from, to)?;
+    Ok(())
+}
+
 /// Extract task ID, status, and project from frontmatter.
 fn extract_task_info(task: &IndexedNote) -> (String, String, String) {
     let fm = task
@@ -1,12 +1,12 @@
 //! Daily planning and review dashboard commands.
 
-use super::common::{load_config, open_index};
+use super::common::{load_config, open_in_editor, open_index_read_only};
 use chrono::{Local, NaiveDate, Timelike};
 use color_eyre::eyre::{Result, bail};
 use mdvault_core::index::{IndexDb, IndexedNote, NoteQuery};
 use serde::Serialize;
 use std::path::Path;
-use tabled::{Table, Tabled, settings::Style};
+use tabled::Tabled;
 
 use crate::TodayArgs;
 
@@ -25,13 +25,13 @@ struct DashboardData {
 }
 
 #[derive(Serialize, Clone)]
-struct TaskInfo {
-    id: String,
-    title: String,
-    project: String,
-    status: String,
-    priority: Option<String>,
-    due_date: Option<String>,
+pub(crate) struct TaskInfo {
+    pub(crate) id: String,
+    pub(crate) title: String,
+    pub(crate) project: String,
+    pub(crate) status: String,
+    pub(crate) priority: Option<String>,
+    pub(crate) due_date: Option<String>,
 }
 
 /// Row for pending tasks table.
@@ -56,7 +56,7 @@ pub fn run(config: Option<&Path>, profile: Option<&str>, args: TodayArgs) -> Res
     }
 
     let cfg = load_config(config, profile)?;
-    let db = open_index(&cfg.vault_root)?;
+    let db = open_index_read_only(&cfg.vault_root)?;
 
     // Determine mode based on flags or time of day
     let mode = if args.plan {
@@ -98,24 +98,7 @@ fn open_daily_note(config: Option<&Path>, profile: Option<&str>) -> Result<()> {
         );
     }
 
-    // Get editor from environment
-    let editor = std::env::var("EDITOR")
-        .or_else(|_| std::env::var("VISUAL"))
-        .unwrap_or_else(|_| "vim".to_string());
-
-    // Open the file in the editor
-    let status = std::process::Command::new(&editor).arg(&daily_path).status();
-
-    match status {
-        Ok(s) if s.success() => {}
-        Ok(s) => {
-            bail!("Editor exited with status: {}", s);
-        }
-        Err(e) => {
-            bail!("Failed to open editor '{}': {}", editor, e);
-        }
-    }
-    Ok(())
+    open_in_editor(&daily_path)
 }
 
 /// Gather all data for the dashboard.
@@ -350,7 +333,7 @@ fn print_plan_mode(data: &DashboardData) {
         );
         let rows: Vec<TaskRow> =
             data.overdue_tasks.iter().take(5).map(task_to_row).collect();
-        let table = Table::new(&rows).with(Style::rounded()).to_string();
+        let table = crate::display::table(&rows);
         println!("{}", table);
         println!();
     }
@@ -364,7 +347,7 @@ fn print_plan_mode(data: &DashboardData) {
         );
         let rows: Vec<TaskRow> =
             data.in_progress_tasks.iter().take(5).map(task_to_row).collect();
-        let table = Table::new(&rows).with(Style::rounded()).to_string();
+        let table = crate::display::table(&rows);
         println!("{}", table);
         println!();
     }
@@ -378,7 +361,7 @@ fn print_plan_mode(data: &DashboardData) {
         );
         let rows: Vec<TaskRow> =
             data.pending_tasks.iter().take(8).map(task_to_row).collect();
-        let table = Table::new(&rows).with(Style::rounded()).to_string();
+        let table = crate::display::table(&rows);
         println!("{}", table);
         println!();
     }
@@ -395,7 +378,7 @@ fn print_review_mode(data: &DashboardData) {
         );
         let rows: Vec<TaskRow> =
             data.completed_today.iter().take(10).map(task_to_row).collect();
-        let table = Table::new(&rows).with(Style::rounded()).to_string();
+        let table = crate::display::table(&rows);
         println!("{}", table);
         println!();
     } else {
@@ -412,7 +395,7 @@ fn print_review_mode(data: &DashboardData) {
         );
         let rows: Vec<TaskRow> =
             data.overdue_tasks.iter().take(5).map(task_to_row).collect();
-        let table = Table::new(&rows).with(Style::rounded()).to_string();
+        let table = crate::display::table(&rows);
         println!("{}", table);
         println!();
     }
@@ -426,7 +409,7 @@ fn print_review_mode(data: &DashboardData) {
         );
         let rows: Vec<TaskRow> =
             data.in_progress_tasks.iter().take(5).map(task_to_row).collect();
-        let table = Table::new(&rows).with(Style::rounded()).to_string();
+        let table = crate::display::table(&rows);
         println!("{}", table);
         println!();
     }
@@ -447,7 +430,7 @@ fn task_to_row(task: &TaskInfo) -> TaskRow {
 }
 
 /// Get priority order for sorting (lower = higher priority).
-fn priority_order(t: &TaskInfo) -> u8 {
+pub(crate) fn priority_order(t: &TaskInfo) -> u8 {
     match t.priority.as_deref() {
         Some("high") | Some("urgent") => 0,
         Some("medium") | Some("normal") => 1,
@@ -459,7 +442,7 @@ fn priority_order(t: &TaskInfo) -> u8 {
 // --- Helper functions ---
 
 /// Get note type from frontmatter.
-fn get_note_type(note: &IndexedNote) -> Option<String> {
+pub(crate) fn get_note_type(note: &IndexedNote) -> Option<String> {
     note.frontmatter_json
         .as_ref()
         .and_then(|fm| serde_json::from_str::<serde_json::Value>(fm).ok())
@@ -478,7 +461,7 @@ fn get_completed_at(note: &IndexedNote) -> Option<NaiveDate> {
 }
 
 /// Extract task info from indexed note.
-fn extract_task_info(note: &IndexedNote) -> TaskInfo {
+pub(crate) fn extract_task_info(note: &IndexedNote) -> TaskInfo {
     let fm = note
         .frontmatter_json
         .as_ref()
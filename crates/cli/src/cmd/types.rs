@@ -0,0 +1,110 @@
+//! Types command: export type schemas as JSON Schema and run typedef fixtures.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use color_eyre::eyre::{Result, WrapErr, bail, eyre};
+use mdvault_core::paths::PathResolver;
+use mdvault_core::types::{TypeRegistry, discover_fixtures, type_json_schema};
+use serde_json::{Map, Value};
+
+use super::common::{load_config, load_types};
+use crate::{TypesCommands, TypesExportArgs, TypesTestArgs};
+
+pub fn run(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    command: TypesCommands,
+) -> Result<()> {
+    let cfg = load_config(config, profile)?;
+    let registry = load_types(&cfg);
+
+    match command {
+        TypesCommands::Export(args) => export(&cfg.vault_root, &registry, args),
+        TypesCommands::Test(args) => test(&cfg.typedefs_dir, &registry, args),
+    }
+}
+
+fn export(
+    vault_root: &Path,
+    registry: &TypeRegistry,
+    args: TypesExportArgs,
+) -> Result<()> {
+    let names = match args.r#type {
+        Some(name) if registry.is_known_type(&name) => vec![name],
+        Some(name) => bail!("Unknown type: {name}"),
+        None => registry.list_all_types(),
+    };
+    let schemas: Vec<(String, Value)> = names
+        .into_iter()
+        .map(|name| (name.clone(), schema_for(registry, &name)))
+        .collect();
+
+    if args.stdout {
+        let out = match schemas.as_slice() {
+            [(_, schema)] => schema.clone(),
+            _ => Value::Object(schemas.into_iter().collect::<Map<_, _>>()),
+        };
+        println!("{}", serde_json::to_string_pretty(&out)?);
+        return Ok(());
+    }
+
+    let dir = args.dir.unwrap_or_else(|| PathResolver::new(vault_root).schemas_dir());
+    std::fs::create_dir_all(&dir)
+        .wrap_err_with(|| format!("Failed to create {}", dir.display()))?;
+    for (name, schema) in &schemas {
+        let path = dir.join(format!("{name}.schema.json"));
+        std::fs::write(&path, serde_json::to_string_pretty(schema)? + "\n")
+            .wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+        println!("wrote  {}", path.display());
+    }
+    Ok(())
+}
+
+fn test(typedefs_dir: &Path, registry: &TypeRegistry, args: TypesTestArgs) -> Result<()> {
+    let dir = args.dir.unwrap_or_else(|| typedefs_dir.join("tests"));
+    let fixtures =
+        discover_fixtures(&dir).map_err(|e| eyre!("Error loading fixtures: {e}"))?;
+    let fixtures: Vec<_> = fixtures
+        .into_iter()
+        .filter(|f| args.r#type.as_ref().is_none_or(|t| &f.note_type == t))
+        .collect();
+    if fixtures.is_empty() {
+        println!("No fixtures found in {}.", dir.display());
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for fixture in &fixtures {
+        let failures = fixture.run(registry);
+        if failures.is_empty() {
+            println!("ok      {}", fixture.name);
+        } else {
+            failed += 1;
+            println!("FAILED  {}", fixture.name);
+            for failure in failures {
+                println!("    {failure}");
+            }
+        }
+    }
+
+    println!();
+    println!(
+        "{} fixture(s): {} passed, {} failed",
+        fixtures.len(),
+        fixtures.len() - failed,
+        failed
+    );
+    if failed > 0 {
+        bail!("{failed} fixture(s) failed");
+    }
+    Ok(())
+}
+
+/// The schema of a type; built-in types without a Lua definition only pin `type`.
+fn schema_for(registry: &TypeRegistry, name: &str) -> Value {
+    match registry.get(name) {
+        Some(td) => type_json_schema(name, td.description.as_deref(), &td.schema),
+        None => type_json_schema(name, None, &HashMap::new()),
+    }
+}
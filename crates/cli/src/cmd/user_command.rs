@@ -0,0 +1,35 @@
+//! User-defined commands: Lua files in `commands_dir` run as `mdv <name>`.
+
+use std::path::Path;
+
+use color_eyre::eyre::{Report, Result, bail, eyre};
+use mdvault_core::scripting::commands::{self, CommandError};
+
+use super::common::{load_config, vault_context};
+use crate::prompt::create_fuzzy_selector_callback;
+
+/// Run `mdv <name> [args...]`; `argv` starts with the command name.
+pub fn run(config: Option<&Path>, profile: Option<&str>, argv: &[String]) -> Result<()> {
+    let Some((name, args)) = argv.split_first() else {
+        bail!("No command given");
+    };
+    let cfg = load_config(config, profile)?;
+    let command = commands::find(&cfg.commands_dir, name).map_err(report)?;
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        print!("{}", command.usage());
+        return Ok(());
+    }
+    let values = command.parse_args(args).map_err(report)?;
+
+    let vault_ctx = vault_context(&cfg)?.with_selector(create_fuzzy_selector_callback());
+
+    if let Some(output) = command.run(vault_ctx, &values).map_err(report)? {
+        println!("{output}");
+    }
+    Ok(())
+}
+
+/// Lua errors are not `Send`, so carry their message instead.
+fn report(e: CommandError) -> Report {
+    eyre!("{e}")
+}
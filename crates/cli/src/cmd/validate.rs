@@ -3,16 +3,18 @@
 use std::path::Path;
 
 use color_eyre::eyre::{Result, WrapErr, bail};
+use mdvault_core::config::types::WebhookEvent;
 use mdvault_core::frontmatter::parse as parse_frontmatter;
 use mdvault_core::index::IndexDb;
 use mdvault_core::paths::PathResolver;
 use mdvault_core::types::{
     TypeRegistry, TypedefRepository, ValidationResult, add_link_integrity_warnings,
-    apply_fixes, try_fix_note, validate_note,
+    apply_fixes, check_transitions, try_fix_note, validate_note,
+    validate_note_with_index,
 };
 
-use super::common::load_config;
-use super::output::resolve_format;
+use super::common::{emit_webhook, exclude_rules, load_config};
+use super::output::{print_json, resolve_format};
 use crate::{OutputFormat, ValidateArgs};
 
 pub fn run(
@@ -62,7 +64,8 @@ pub fn run(
             }
         }
     } else {
-        None
+        // Lets validate() functions use mdv.query() when the index exists
+        index_path.exists().then(|| IndexDb::open(&index_path).ok()).flatten()
     };
 
     // Check if we're validating a specific file or using the index
@@ -95,17 +98,26 @@ pub fn run(
         // Index-based mode - index_db is guaranteed to be Some here
         let db = index_db.as_ref().unwrap();
 
-        // Query notes to validate
+        // Query notes to validate; with exclusions the limit applies after
+        // filtering
+        let exclude = exclude_rules(&args.exclude)?;
         let query = mdvault_core::index::NoteQuery {
             note_type: args.r#type.as_ref().map(|s| s.parse().unwrap_or_default()),
             path_prefix: None,
             modified_after: None,
             modified_before: None,
-            limit: args.limit,
+            limit: args.limit.filter(|_| exclude.is_empty()),
             offset: None,
+            fields: args.filters.clone(),
         };
 
-        let notes = db.query_notes(&query).wrap_err("Error querying notes")?;
+        let mut notes = db.query_notes(&query).wrap_err("Error querying notes")?;
+        if !exclude.is_empty() {
+            notes.retain(|n| !exclude.is_ignored(&n.path, false));
+            if let Some(limit) = args.limit {
+                notes.truncate(limit as usize);
+            }
+        }
 
         // Convert to NoteInfo
         let note_infos: Vec<NoteInfo> = notes
@@ -129,7 +141,9 @@ pub fn run(
     let mut total = 0;
     let mut valid_count = 0;
     let mut error_count = 0;
+    let mut warning_count = 0;
     let mut fixed_count = 0;
+    let mut failed_paths: Vec<String> = Vec::new();
     let mut results: Vec<(
         std::path::PathBuf,
         String,
@@ -140,109 +154,105 @@ pub fn run(
     for note in &notes_to_validate {
         total += 1;
         let note_type = &note.note_type;
-
-        // Parse frontmatter
-        let frontmatter: serde_yaml::Value = parse_frontmatter(&note.content)
-            .ok()
-            .and_then(|p| p.frontmatter)
-            .map(|fm| {
-                let mut map = serde_yaml::Mapping::new();
-                for (k, v) in fm.fields {
-                    map.insert(serde_yaml::Value::String(k), v);
-                }
-                serde_yaml::Value::Mapping(map)
-            })
-            .unwrap_or(serde_yaml::Value::Mapping(serde_yaml::Mapping::new()));
-
-        // Run type-based validation (skip for untyped notes without custom definitions)
-        let mut result = if !registry.has_definition(note_type) && note_type == "none" {
-            ValidationResult::default()
-        } else {
-            validate_note(
+        let validate = |content: &str| {
+            let mut result = validate_content(
                 &registry,
+                index_db.as_ref(),
                 note_type,
-                &note.path.to_string_lossy(),
-                &frontmatter,
-                &note.content,
-            )
+                &note.path,
+                &note.relative_path,
+                content,
+            );
+            // Check link integrity if requested and index is available
+            if args.check_links
+                && let Some(ref db) = index_db
+            {
+                add_link_integrity_warnings(&mut result, db, &note.relative_path);
+            }
+            result
         };
-
-        // Check link integrity if requested and index is available
-        if args.check_links
-            && let Some(ref db) = index_db
-        {
-            add_link_integrity_warnings(&mut result, db, &note.relative_path);
-        }
-
-        // Determine if note is valid (errors only, warnings don't count)
-        let has_errors = !result.errors.is_empty();
-        let has_warnings = !result.warnings.is_empty();
-
-        if !has_errors && !has_warnings {
-            valid_count += 1;
-        } else if !has_errors {
-            // Only warnings, still valid but add to results for display
-            valid_count += 1;
-            results.push((note.path.clone(), note_type.clone(), result, None));
-        } else {
-            // Has errors - try to fix if --fix is set
-            let fixes = if args.fix {
-                let fix_result =
-                    try_fix_note(&registry, note_type, &note.content, &result.errors);
-                if fix_result.fixed {
-                    if let Some(new_content) = fix_result.content {
-                        if let Err(e) = apply_fixes(&note.path, &new_content) {
-                            eprintln!(
-                                "Warning: Failed to apply fixes to {}: {}",
-                                note.path.display(),
-                                e
-                            );
-                            None
-                        } else {
-                            fixed_count += 1;
-                            Some(fix_result.fixes)
-                        }
-                    } else {
-                        None
+        let mut result = validate(&note.content);
+
+        // Apply the safe fixes and any the type's validate() suggested, then
+        // check the note again so only what is left is reported
+        let mut fixes = None;
+        if args.fix && (!result.errors.is_empty() || !result.fixes.is_empty()) {
+            let fix_result = try_fix_note(&registry, note_type, &note.content, &result);
+            if let Some(new_content) = fix_result.content.filter(|_| fix_result.fixed) {
+                match apply_fixes(&note.path, &new_content) {
+                    Ok(()) => {
+                        fixed_count += 1;
+                        result = validate(&new_content);
+                        fixes = Some(fix_result.fixes);
                     }
-                } else {
-                    None
+                    Err(e) => eprintln!(
+                        "Warning: Failed to apply fixes to {}: {}",
+                        note.path.display(),
+                        e
+                    ),
                 }
-            } else {
-                None
-            };
+            }
+        }
 
-            // Only count as error if not fully fixed
-            if fixes.is_none()
-                || result.errors.len() > fixes.as_ref().map_or(0, |f| f.len())
-            {
-                error_count += 1;
+        // Determine if note is valid (errors only, warnings don't count);
+        // fixed notes are counted as fixed
+        if result.errors.is_empty() {
+            if fixes.is_none() {
+                valid_count += 1;
             }
+        } else {
+            error_count += 1;
+            failed_paths.push(note.relative_path.to_string_lossy().to_string());
+        }
+        if !result.warnings.is_empty() {
+            warning_count += 1;
+        }
+        if !result.errors.is_empty() || !result.warnings.is_empty() || fixes.is_some() {
             results.push((note.path.clone(), note_type.clone(), result, fixes));
         }
     }
 
     // Determine output format
-    let format = resolve_format(args.output, args.json, args.quiet);
+    let format =
+        resolve_format(config, profile, args.output, false, args.json, args.quiet)?;
 
     // Output results
     match format {
         OutputFormat::Table => print_results_table(
             &results,
-            total,
-            valid_count,
-            error_count,
-            fixed_count,
+            Counts {
+                total,
+                valid: valid_count,
+                errors: error_count,
+                warnings: warning_count,
+                fixed: fixed_count,
+            },
             args.fix,
         ),
-        OutputFormat::Json => {
-            print_results_json(&results, total, valid_count, error_count, fixed_count)
-        }
+        OutputFormat::Json | OutputFormat::StableJson => print_results_json(
+            &results,
+            Counts {
+                total,
+                valid: valid_count,
+                errors: error_count,
+                warnings: warning_count,
+                fixed: fixed_count,
+            },
+        ),
         OutputFormat::Quiet => print_results_quiet(&results),
     }
 
     // Exit with error code if any validation failures remain unfixed
     if error_count > 0 {
+        emit_webhook(
+            &rc,
+            WebhookEvent::ValidationFailed,
+            &[
+                ("failed", &error_count.to_string()),
+                ("checked", &total.to_string()),
+                ("paths", &failed_paths.join("\n")),
+            ],
+        );
         bail!("{} note(s) failed validation", error_count);
     }
     Ok(())
@@ -256,6 +266,60 @@ struct NoteInfo {
     content: String,
 }
 
+/// Validate a note's content against its type, with the index when there is one.
+///
+/// With the index, field transitions are also checked against the values
+/// last indexed for `relative_path`.
+fn validate_content(
+    registry: &TypeRegistry,
+    index_db: Option<&IndexDb>,
+    note_type: &str,
+    path: &Path,
+    relative_path: &Path,
+    content: &str,
+) -> ValidationResult {
+    // Skip untyped notes without custom definitions
+    if !registry.has_definition(note_type) && note_type == "none" {
+        return ValidationResult::default();
+    }
+
+    // Parse frontmatter
+    let frontmatter: serde_yaml::Value = parse_frontmatter(content)
+        .ok()
+        .and_then(|p| p.frontmatter)
+        .map(|fm| {
+            let mut map = serde_yaml::Mapping::new();
+            for (k, v) in fm.fields {
+                map.insert(serde_yaml::Value::String(k), v);
+            }
+            serde_yaml::Value::Mapping(map)
+        })
+        .unwrap_or(serde_yaml::Value::Mapping(serde_yaml::Mapping::new()));
+
+    let path = path.to_string_lossy();
+    match index_db {
+        Some(db) => {
+            let mut result = validate_note_with_index(
+                registry,
+                note_type,
+                &path,
+                &frontmatter,
+                content,
+                db,
+            );
+            result.merge(check_transitions(
+                registry,
+                note_type,
+                relative_path,
+                &frontmatter,
+                db,
+            ));
+            result
+        }
+        None => validate_note(registry, note_type, &path, &frontmatter, content),
+    }
+}
+
 /// Extract note type from content's frontmatter.
 fn extract_note_type(content: &str) -> String {
     parse_frontmatter(content)
@@ -301,77 +365,81 @@ fn print_types(registry: &TypeRegistry) {
     }
 }
 
-fn print_results_table(
-    results: &[(std::path::PathBuf, String, ValidationResult, Option<Vec<String>>)],
+/// How many notes ended up in each state.
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+struct Counts {
     total: usize,
     valid: usize,
     errors: usize,
+    warnings: usize,
     fixed: usize,
+}
+
+fn print_results_table(
+    results: &[(std::path::PathBuf, String, ValidationResult, Option<Vec<String>>)],
+    counts: Counts,
     fix_mode: bool,
 ) {
     if results.is_empty() {
-        println!("All {} notes validated successfully.", total);
+        println!("All {} notes validated successfully.", counts.total);
         return;
     }
 
+    let Counts { total, valid, errors, warnings, fixed } = counts;
     if fix_mode && fixed > 0 {
         println!(
-            "Validation Results: {} valid, {} fixed, {} with errors (of {} total)",
-            valid, fixed, errors, total
+            "Validation Results: {} valid, {} fixed, {} with errors, {} with warnings (of {} total)",
+            valid, fixed, errors, warnings, total
         );
     } else {
         println!(
-            "Validation Results: {} valid, {} with errors (of {} total)",
-            valid, errors, total
+            "Validation Results: {} valid, {} with errors, {} with warnings (of {} total)",
+            valid, errors, warnings, total
         );
     }
     println!();
 
+    let mut fixable = 0;
     for (path, note_type, result, fixes) in results {
         println!("{}  [type: {}]", path.display(), note_type);
 
         // Show fixes if any
         if let Some(applied_fixes) = fixes {
             for fix in applied_fixes {
-                println!("  + {}", fix);
+                println!("  + fixed: {}", fix);
             }
         }
 
-        // Show remaining errors
+        // Remaining errors, then warnings
         for error in &result.errors {
-            // Skip errors that were fixed
-            if let Some(applied) = &fixes {
-                let error_str = error.to_string();
-                if applied
-                    .iter()
-                    .any(|f| error_str.contains(f.split('\'').nth(1).unwrap_or("")))
-                {
-                    continue;
-                }
-            }
-            println!("  - {}", error);
+            println!("  - error: {}", error);
         }
-
         for warning in &result.warnings {
-            println!("  ~ {}", warning);
+            println!("  ~ warning: {}", warning);
+        }
+
+        // Fixes validate() suggested that --fix would apply
+        if !fix_mode {
+            for fix in &result.fixes {
+                println!("  ? fixable: {}", fix);
+            }
+            fixable += usize::from(!result.fixes.is_empty());
         }
         println!();
     }
+    if fixable > 0 {
+        println!("{} note(s) can be fixed with --fix", fixable);
+    }
 }
 
 fn print_results_json(
     results: &[(std::path::PathBuf, String, ValidationResult, Option<Vec<String>>)],
-    total: usize,
-    valid: usize,
-    errors: usize,
-    fixed: usize,
+    counts: Counts,
 ) {
     #[derive(serde::Serialize)]
     struct Output {
-        total: usize,
-        valid: usize,
-        errors: usize,
-        fixed: usize,
+        #[serde(flatten)]
+        counts: Counts,
         results: Vec<NoteResult>,
     }
 
@@ -384,13 +452,12 @@ fn print_results_json(
         warnings: Vec<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
         fixes_applied: Option<Vec<String>>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        fixes_available: Vec<String>,
     }
 
     let output = Output {
-        total,
-        valid,
-        errors,
-        fixed,
+        counts,
         results: results
             .iter()
             .map(|(path, note_type, result, fixes)| NoteResult {
@@ -400,11 +467,16 @@ fn print_results_json(
                 errors: result.errors.iter().map(|e| e.to_string()).collect(),
                 warnings: result.warnings.clone(),
                 fixes_applied: fixes.clone(),
+                fixes_available: if fixes.is_some() {
+                    Vec::new()
+                } else {
+                    result.fixes.iter().map(ToString::to_string).collect()
+                },
             })
             .collect(),
     };
 
-    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    print_json(&output);
 }
 
 fn print_results_quiet(
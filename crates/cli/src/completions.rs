@@ -125,7 +125,7 @@ pub fn complete_projects(current: &OsStr) -> Vec<CompletionCandidate> {
 
     if let Some(cfg) = load_config() {
         let index_path = PathResolver::new(&cfg.vault_root).index_db();
-        if let Ok(db) = IndexDb::open(&index_path) {
+        if let Ok(db) = IndexDb::open_read_only(&index_path) {
             let query =
                 NoteQuery { note_type: Some(NoteType::Project), ..Default::default() };
             if let Ok(projects) = db.query_notes(&query) {
@@ -0,0 +1,169 @@
+//! Terminal presentation shared by commands and the TUI.
+//!
+//! Accessible mode (`--accessible`, `MDV_ACCESSIBLE=1`, or `[display]
+//! accessible = true` in the config) swaps decorative output for plain text:
+//! ASCII progress bars and symbols, no box-drawing, no colour codes, and
+//! tables printed as one labelled record per line, which screen readers
+//! follow far better than a grid.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use mdvault_core::config::types::ResolvedConfig;
+use tabled::{Table, Tabled, settings::Style};
+
+static ACCESSIBLE: AtomicBool = AtomicBool::new(false);
+
+/// Decide the display mode once, before any command runs.
+pub fn init(flag: bool, config: Option<&ResolvedConfig>) {
+    let from_env =
+        std::env::var("MDV_ACCESSIBLE").is_ok_and(|v| !v.is_empty() && v != "0");
+    let from_config = config.is_some_and(|c| c.display.accessible);
+    ACCESSIBLE.store(flag || from_env || from_config, Ordering::Relaxed);
+}
+
+/// Whether accessible output is active.
+pub fn accessible() -> bool {
+    ACCESSIBLE.load(Ordering::Relaxed)
+}
+
+/// Render rows as a rounded table, or as `Header: value` records.
+pub fn table<T: Tabled>(rows: &[T]) -> String {
+    if accessible() {
+        linear_table(rows)
+    } else {
+        Table::new(rows).with(Style::rounded()).to_string()
+    }
+}
+
+fn linear_table<T: Tabled>(rows: &[T]) -> String {
+    let headers = T::headers();
+    rows.iter()
+        .map(|row| {
+            headers
+                .iter()
+                .zip(row.fields())
+                .filter(|(_, value)| !value.trim().is_empty())
+                .map(|(header, value)| format!("{}: {}", header, value))
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A `width`-cell bar for `percent` (0-100).
+pub fn progress_bar(percent: f64, width: usize) -> String {
+    let filled =
+        ((percent / 100.0) * width as f64).round().clamp(0.0, width as f64) as usize;
+    let empty = width - filled;
+    if accessible() {
+        format!("[{}{}]", "#".repeat(filled), "-".repeat(empty))
+    } else {
+        format!("{}{}", "█".repeat(filled), "░".repeat(empty))
+    }
+}
+
+/// Status markers used in summaries.
+#[derive(Debug, Clone, Copy)]
+pub enum Mark {
+    Done,
+    InProgress,
+    Todo,
+    Blocked,
+    Cancelled,
+    Yes,
+    No,
+}
+
+/// The symbol for a marker; a short word in accessible mode.
+pub fn mark(mark: Mark) -> &'static str {
+    match (mark, accessible()) {
+        (Mark::Done, false) => "✓",
+        (Mark::InProgress, false) => "→",
+        (Mark::Todo, false) => "○",
+        (Mark::Blocked, false) => "⊘",
+        (Mark::Cancelled, false) => "✗",
+        (Mark::Yes, false) => "✓",
+        (Mark::No, false) => "✗",
+        (Mark::Done, true) => "[x]",
+        (Mark::InProgress, true) => "[>]",
+        (Mark::Todo, true) => "[ ]",
+        (Mark::Blocked, true) => "[!]",
+        (Mark::Cancelled, true) => "[-]",
+        (Mark::Yes, true) => "yes",
+        (Mark::No, true) => "no",
+    }
+}
+
+/// `text` as-is, or with decorative glyphs replaced in accessible mode.
+pub fn glyphs(text: &str) -> String {
+    if !accessible() {
+        return text.to_string();
+    }
+    text.chars().map(|c| ascii_glyph(c).map_or(c.to_string(), str::to_string)).collect()
+}
+
+/// ASCII stand-in for a box-drawing, block, or symbol glyph.
+///
+/// Also used to rewrite the TUI's rendered buffer, so widgets need no changes.
+pub fn ascii_glyph(c: char) -> Option<&'static str> {
+    let ascii = match c {
+        '─' | '━' | '╌' | '┄' => "-",
+        '═' => "=",
+        '│' | '┃' | '║' | '╎' | '┆' => "|",
+        '┌' | '┐' | '└' | '┘' | '╭' | '╮' | '╯' | '╰' | '├' | '┤' | '┬' | '┴' | '┼'
+        | '╔' | '╗' | '╚' | '╝' | '┏' | '┓' | '┗' | '┛' => "+",
+        '█' | '▇' | '▆' | '▅' | '▓' | '▉' | '▊' | '▋' => "#",
+        '▄' | '▃' | '▒' | '▌' | '▍' => "=",
+        '▂' | '▁' | '░' | '▎' | '▏' => ".",
+        '·' | '•' => "*",
+        '…' => ".",
+        '—' | '–' => "-",
+        '→' | '▸' | '►' => ">",
+        '↻' => "*",
+        '✓' => "+",
+        '✗' => "x",
+        _ => return None,
+    };
+    Some(ascii)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Tabled)]
+    struct Row {
+        #[tabled(rename = "ID")]
+        id: &'static str,
+        #[tabled(rename = "Title")]
+        title: &'static str,
+        #[tabled(rename = "Due")]
+        due: &'static str,
+    }
+
+    fn rows() -> Vec<Row> {
+        vec![
+            Row { id: "WEB-001", title: "Fix login", due: "2025-01-06" },
+            Row { id: "WEB-002", title: "Ship it", due: "" },
+        ]
+    }
+
+    #[test]
+    fn linear_table_reads_row_by_row() {
+        assert_eq!(
+            linear_table(&rows()),
+            "ID: WEB-001, Title: Fix login, Due: 2025-01-06\nID: WEB-002, Title: Ship it"
+        );
+    }
+
+    #[test]
+    fn ascii_glyph_covers_borders_and_blocks() {
+        for glyph in ['─', '│', '╭', '┘', '█', '░', '▁', '…', '→'] {
+            let ascii = ascii_glyph(glyph).unwrap();
+            assert!(ascii.is_ascii(), "{glyph} -> {ascii}");
+        }
+        assert_eq!(ascii_glyph('a'), None);
+        assert_eq!(ascii_glyph(' '), None);
+    }
+}
@@ -0,0 +1,78 @@
+//! Reporting a failed command.
+//!
+//! Every failure ends with its stable error code (see
+//! [`mdvault_core::ErrorCode`]) and whether retrying may help, so scripts
+//! can tell "not found" from "index busy" without parsing messages. When
+//! the command was asked for JSON output, the failure is printed as JSON
+//! too:
+//!
+//! ```json
+//! {"error": {"code": "not_found", "retryable": false, "message": "...", "causes": ["..."]}}
+//! ```
+
+use color_eyre::eyre::Report;
+use mdvault_core::error::classify;
+use serde_json::json;
+
+/// Print `report` to stderr, as JSON if `json` is set.
+pub fn report(report: &Report, json: bool) {
+    let (code, retryable) = classify(report.as_ref());
+    if json {
+        let causes: Vec<String> = report.chain().skip(1).map(|e| e.to_string()).collect();
+        let error = json!({
+            "error": {
+                "code": code.as_str(),
+                "retryable": retryable,
+                "message": report.to_string(),
+                "causes": causes,
+            }
+        });
+        eprintln!("{error}");
+    } else {
+        eprintln!("Error: {report:?}");
+        let retry = if retryable { " (retryable)" } else { "" };
+        eprintln!("\nError code: {code}{retry}");
+    }
+}
+
+/// Whether the command line asks for JSON output (`--json`,
+/// `--stable-json` or `--output json`).
+pub fn wants_json<I: IntoIterator<Item = String>>(args: I) -> bool {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let format = match arg.as_str() {
+            "--json" | "--stable-json" => return true,
+            "--" => return false,
+            "--output" | "-o" => args.next(),
+            _ => arg
+                .strip_prefix("--output=")
+                .or_else(|| arg.strip_prefix("-o"))
+                .map(str::to_string),
+        };
+        if matches!(format.as_deref(), Some("json" | "stable-json")) {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wants(args: &str) -> bool {
+        wants_json(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn test_wants_json() {
+        assert!(wants("list --json"));
+        assert!(wants("context day --stable-json"));
+        assert!(wants("list --output json"));
+        assert!(wants("list --output=stable-json"));
+        assert!(wants("list -o json"));
+        assert!(!wants("list --output table"));
+        assert!(!wants("list"));
+        assert!(!wants("lua -- --json"));
+    }
+}
@@ -1,40 +1,78 @@
 mod args;
 mod cmd;
 mod completions;
+mod display;
+mod errors;
 mod logging;
 mod prompt;
 mod tui;
 
 pub use args::*;
 
+use std::process::ExitCode;
+
 use clap::{CommandFactory, Parser};
 use clap_complete::env::CompleteEnv;
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{Result, WrapErr};
 use mdvault_core::config::loader::ConfigLoader;
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
+    let json = errors::wants_json(std::env::args().skip(1));
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(report) => {
+            errors::report(&report, json);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<()> {
     color_eyre::install()?;
 
     // Enable dynamic shell completions
     // This intercepts completion requests before normal CLI parsing
     CompleteEnv::with_factory(Cli::command).complete();
 
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+
+    // Hand the whole invocation to the remote mdv before touching local config
+    if let Some(remote) = &cli.remote {
+        return cmd::remote::run(remote, std::env::args_os().skip(1).collect());
+    }
+    if let Some(vault_root) = cli.vault_root.take() {
+        let rc = ConfigLoader::load_for_vault(cli.config.as_deref(), &vault_root)
+            .wrap_err("Failed to load config")?;
+        cli.profile = Some(rc.active_profile);
+    }
 
     // Initialize logging if config is valid
     // We ignore errors here because individual commands will report them properly
-    if let Ok(cfg) = ConfigLoader::load(cli.config.as_deref(), cli.profile.as_deref()) {
-        logging::init(&cfg);
+    let cfg = ConfigLoader::load(cli.config.as_deref(), cli.profile.as_deref()).ok();
+    if let Some(cfg) = &cfg {
+        logging::init(cfg);
+    }
+    display::init(cli.accessible, cfg.as_ref());
+    mdvault_core::scripting::set_trust_scripts(cli.trust_scripts);
+
+    if cli.quick_capture.is_some() && cli.command.is_some() {
+        return Err(color_eyre::eyre::eyre!(
+            "--quick-capture opens the TUI and cannot be combined with a command"
+        ));
     }
 
     match cli.command {
         // No command provided - launch TUI
-        None => tui::run(cli.config.as_deref(), cli.profile.as_deref())?,
-        Some(Commands::Doctor) => {
-            cmd::doctor::run(cli.config.as_deref(), cli.profile.as_deref())?
+        None => tui::run(
+            cli.config.as_deref(),
+            cli.profile.as_deref(),
+            cli.quick_capture.as_deref(),
+        )?,
+        Some(Commands::Doctor(args)) => {
+            cmd::doctor::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
         }
-        Some(Commands::ListTemplates) => {
-            cmd::list_templates::run(cli.config.as_deref(), cli.profile.as_deref())?
+        Some(Commands::ListTemplates(args)) => {
+            cmd::list_templates::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
         }
         Some(Commands::New(args)) => {
             cmd::new::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
@@ -49,6 +87,7 @@ fn main() -> Result<()> {
                     args.name.as_ref().unwrap(),
                     &args.vars,
                     args.batch,
+                    args.dry_run,
                 )?;
             }
         }
@@ -71,22 +110,30 @@ fn main() -> Result<()> {
             cli.profile.as_deref(),
             args.verbose,
             args.force,
+            args.vacuum,
+            args.exclude,
         )?,
         Some(Commands::List(args)) => {
             cmd::list::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
         }
+        Some(Commands::Query(args)) => {
+            cmd::query::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
+        }
         Some(Commands::Links(args)) => {
             cmd::links::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
         }
         Some(Commands::Orphans(args)) => {
             let stale_args = StaleArgs {
                 orphans: true,
+                min_age: args.min_age,
+                explain: false,
                 r#type: None,
                 threshold: 0.5,
                 days: None,
                 limit: None,
                 output: args.output,
                 json: args.json,
+                stable_json: args.stable_json,
                 quiet: args.quiet,
             };
             cmd::stale::run(cli.config.as_deref(), cli.profile.as_deref(), stale_args)?
@@ -100,9 +147,28 @@ fn main() -> Result<()> {
         Some(Commands::Stale(args)) => {
             cmd::stale::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
         }
+        Some(Commands::Duplicates(args)) => {
+            cmd::duplicates::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
+        }
+        Some(Commands::Related(args)) => {
+            cmd::related::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
+        }
+        Some(Commands::SuggestLinks(args)) => {
+            cmd::suggest_links::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
+        }
+        #[cfg(feature = "embeddings")]
+        Some(Commands::Embed(args)) => {
+            cmd::embed::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
+        }
+        Some(Commands::Stats(args)) => {
+            cmd::stats::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
+        }
         Some(Commands::Rename(args)) => {
             cmd::rename::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
         }
+        Some(Commands::Provenance(args)) => {
+            cmd::provenance::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
+        }
         Some(Commands::Completions(args)) => {
             clap_complete::generate(
                 args.shell,
@@ -112,6 +178,12 @@ fn main() -> Result<()> {
             );
         }
         Some(Commands::Task(subcmd)) => match subcmd {
+            TaskCommands::List(args) if args.inline => cmd::task::list_inline(
+                cli.config.as_deref(),
+                cli.profile.as_deref(),
+                args.project.as_deref(),
+                args.status,
+            )?,
             TaskCommands::List(args) => cmd::task::list(
                 cli.config.as_deref(),
                 cli.profile.as_deref(),
@@ -160,6 +232,13 @@ fn main() -> Result<()> {
                 cli.profile.as_deref(),
                 &args.project,
                 args.yes,
+                args.dry_run,
+                args.json,
+            )?,
+            ProjectCommands::Unarchive(args) => cmd::project::unarchive(
+                cli.config.as_deref(),
+                cli.profile.as_deref(),
+                args,
             )?,
         },
         Some(Commands::Area(subcmd)) => match subcmd {
@@ -204,9 +283,15 @@ fn main() -> Result<()> {
         Some(Commands::Today(args)) => {
             cmd::today::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
         }
+        Some(Commands::Review(args)) => {
+            cmd::review::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
+        }
         Some(Commands::Focus(args)) => {
             cmd::focus::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
         }
+        Some(Commands::Journal(args)) => {
+            cmd::journal::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
+        }
         Some(Commands::Context(subcmd)) => match subcmd {
             ContextCommands::Day(args) => cmd::context::day(
                 cli.config.as_deref(),
@@ -238,12 +323,52 @@ fn main() -> Result<()> {
         Some(Commands::Check(args)) => {
             cmd::check::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
         }
+        Some(Commands::Demo(args)) => cmd::demo::run(args)?,
+        Some(Commands::Print(args)) => {
+            cmd::print::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
+        }
+        Some(Commands::Ingest(IngestCommands::Audio(args))) => {
+            cmd::ingest::audio(cli.config.as_deref(), cli.profile.as_deref(), args)?
+        }
+        Some(Commands::Inbox(InboxCommands::Ingest(args))) => {
+            cmd::ingest::snippets(cli.config.as_deref(), cli.profile.as_deref(), args)?
+        }
+        Some(Commands::Expire(args)) => {
+            cmd::expire::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
+        }
+        Some(Commands::Migrate(args)) => {
+            cmd::migrate::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
+        }
+        Some(Commands::Types(subcmd)) => {
+            cmd::types::run(cli.config.as_deref(), cli.profile.as_deref(), subcmd)?
+        }
+        Some(Commands::Plugin(subcmd)) => {
+            cmd::plugin::run(cli.config.as_deref(), cli.profile.as_deref(), subcmd)?
+        }
         Some(Commands::Dashboard(args)) => tui::dashboard::run(
             cli.config.as_deref(),
             cli.profile.as_deref(),
             args.project.as_deref(),
             args.activity_days,
         )?,
+        Some(Commands::Board(args)) => tui::board::run(
+            cli.config.as_deref(),
+            cli.profile.as_deref(),
+            args.project.as_deref(),
+            args.focus,
+        )?,
+        Some(Commands::Lua(args)) => {
+            cmd::lua::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
+        }
+        Some(Commands::Jobs(subcmd)) => {
+            cmd::jobs::run(cli.config.as_deref(), cli.profile.as_deref(), subcmd)?
+        }
+        Some(Commands::Serve(args)) => {
+            cmd::serve::run(cli.config.as_deref(), cli.profile.as_deref(), args)?
+        }
+        Some(Commands::User(argv)) => {
+            cmd::user_command::run(cli.config.as_deref(), cli.profile.as_deref(), &argv)?
+        }
     }
 
     Ok(())
@@ -1,22 +1,57 @@
-//! Execution logic for templates, captures, and macros.
+//! Execution logic for templates, captures, macros, and note actions.
 
+use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
 
-use chrono::Local;
+use chrono::{Local, NaiveDate};
 use regex::Regex;
 
-use mdvault_core::captures::{CaptureRepository, CaptureSpec};
+use super::app::PaletteItem;
+use super::graph::LinkGraph;
+use super::home::TodayStatus;
+use super::info::{LinkedNote, NoteInfo};
+use super::worker::JobContext;
+use crate::args::StatusFilter;
+use crate::cmd::project::{
+    ProjectProgress, calculate_project_progress, extract_project_info,
+};
+
+use mdvault_core::activity::{ActivityLogService, Operation};
+use mdvault_core::captures::{
+    CaptureRepository, CaptureSpec, capture_context, capture_target, run_capture,
+};
 use mdvault_core::config::types::ResolvedConfig;
-use mdvault_core::frontmatter::{apply_ops, parse, serialize};
+use mdvault_core::context::{ContextManager, ContextQueryService, FocusContext};
+use mdvault_core::domain::find_project_file;
+use mdvault_core::domain::services::{DailyLogService, ProjectLogService};
+use mdvault_core::expire::{ExpireAction, ExpiredNote, expire_notes};
+use mdvault_core::frontmatter::{Frontmatter, apply_ops, parse, serialize};
+use mdvault_core::index::{
+    IndexBuilder, IndexDb, IndexedNote, LinkDirection, NoteQuery, NoteType, SearchEngine,
+    SearchQuery, SearchResult, walk_links,
+};
 use mdvault_core::macros::{
     MacroRepository, RunContext, RunOptions, StepExecutor, run_macro,
 };
-use mdvault_core::markdown_ast::{MarkdownAstError, MarkdownEditor, SectionMatch};
+use mdvault_core::markdown_ast::{MarkdownEditor, SectionMatch};
+use mdvault_core::paths::PathResolver;
+use mdvault_core::rename::execute_rename;
 use mdvault_core::templates::discovery::TemplateInfo;
 use mdvault_core::templates::engine::{build_render_context, render};
-use mdvault_core::templates::repository::TemplateRepository;
+use mdvault_core::templates::repository::{LoadedTemplate, TemplateRepository};
+use mdvault_core::types::discovery::load_typedef_from_file;
+use mdvault_core::types::{
+    TypeDefinition, TypeRegistry, TypedefRepository, ValidationResult, apply_fixes,
+    try_fix_note, validate_note,
+};
+use mdvault_core::undo::{self, Recorder};
+use mdvault_core::vars::datemath::{evaluate_date_expr, parse_date_expr};
 
 /// Built-in variables that are automatically provided.
 const BUILTIN_VARS: &[&str] = &[
@@ -69,12 +104,13 @@ pub fn extract_user_variables(spec: &CaptureSpec) -> Vec<String> {
     sorted
 }
 
-/// Execute template creation.
+/// Execute template creation, recording the file for undo.
 pub fn execute_template(
     config: &ResolvedConfig,
     template_name: &str,
     output_path: &Path,
     vars: &HashMap<String, String>,
+    recorder: &mut Recorder,
 ) -> Result<String, String> {
     // Check output doesn't exist
     if output_path.exists() {
@@ -108,129 +144,707 @@ pub fn execute_template(
             .map_err(|e| format!("Failed to create directories: {e}"))?;
     }
 
+    recorder.before_write(output_path).map_err(|e| e.to_string())?;
     fs::write(output_path, rendered).map_err(|e| format!("Write failed: {e}"))?;
 
     Ok(format!("Created: {}", output_path.display()))
 }
 
-/// Execute capture insertion.
+/// Execute capture insertion, returning a message and the file written.
 pub fn execute_capture(
     config: &ResolvedConfig,
     capture_name: &str,
     vars: &HashMap<String, String>,
-) -> Result<String, String> {
+    target: Option<&Path>,
+    recorder: &mut Recorder,
+) -> Result<(String, PathBuf), String> {
     // Load capture
     let repo = CaptureRepository::new(&config.captures_dir)
         .map_err(|e| format!("Failed to load captures: {e}"))?;
 
-    let loaded =
+    let mut loaded =
         repo.get_by_name(capture_name).map_err(|e| format!("Capture error: {e}"))?;
+    if let Some(target) = target {
+        retarget_capture(&mut loaded.spec, config, target);
+    }
 
-    // Build full context (builtins + user vars)
-    let mut ctx = build_capture_context(config);
-    for (k, v) in vars {
-        ctx.insert(k.clone(), v.clone());
+    recorder
+        .before_write(&capture_target(config, &loaded.spec, vars))
+        .map_err(|e| e.to_string())?;
+    let outcome = run_capture(config, &loaded.spec, vars).map_err(|e| e.to_string())?;
+
+    let mut msg = format!("Captured to: {}", outcome.target.display());
+    if let Some((title, _level)) = outcome.section {
+        msg.push_str(&format!(" (section: {})", title));
+    }
+    if outcome.frontmatter_updated {
+        msg.push_str(" [frontmatter updated]");
     }
 
-    // Resolve target file
-    let target_path = render_string(&loaded.spec.target.file, &ctx);
-    let target_path = resolve_target_path(&config.vault_root, &target_path);
+    Ok((msg, outcome.target))
+}
 
-    // Read existing file
-    let existing = fs::read_to_string(&target_path)
-        .map_err(|e| format!("Failed to read {}: {e}", target_path.display()))?;
+/// Point a capture at a vault-relative note instead of its own target.
+pub fn retarget_capture(spec: &mut CaptureSpec, config: &ResolvedConfig, note: &Path) {
+    spec.target.file = config.vault_root.join(note).to_string_lossy().into_owned();
+}
 
-    // Execute capture operations
-    let (result_content, section_info) =
-        execute_capture_operations(&existing, &loaded.spec, &ctx)?;
+/// All indexed notes, for the notes browser.
+pub fn load_notes(config: &ResolvedConfig) -> Result<Vec<IndexedNote>, String> {
+    let index_path = PathResolver::new(&config.vault_root).index_db();
+    let db = IndexDb::open_read_only(&index_path)
+        .map_err(|e| format!("Failed to open index: {e}. Run 'mdv reindex' first."))?;
+    db.query_notes(&NoteQuery::default()).map_err(|e| format!("Index error: {e}"))
+}
 
-    // Write back
-    fs::write(&target_path, &result_content).map_err(|e| format!("Write failed: {e}"))?;
+/// File stems of the indexed notes of `note_type`, the values selector
+/// fields store; empty when the index is unavailable.
+pub fn note_choices(config: &ResolvedConfig, note_type: &str) -> Vec<String> {
+    let index_path = PathResolver::new(&config.vault_root).index_db();
+    let Ok(db) = IndexDb::open_read_only(&index_path) else {
+        return Vec::new();
+    };
+    let query = NoteQuery {
+        note_type: Some(note_type.parse().unwrap_or_default()),
+        ..Default::default()
+    };
+    let mut choices: Vec<String> = db
+        .query_notes(&query)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|n| n.path.file_stem().and_then(|s| s.to_str()).map(String::from))
+        .collect();
+    choices.sort();
+    choices
+}
 
-    let mut msg = format!("Captured to: {}", target_path.display());
-    if let Some((title, _level)) = section_info {
-        msg.push_str(&format!(" (section: {})", title));
+/// The type definition behind a template: its `lua:` script, or else the
+/// type it is named after.
+pub fn template_typedef(
+    config: &ResolvedConfig,
+    loaded: &LoadedTemplate,
+) -> Option<TypeDefinition> {
+    if let Some(lua_path) = loaded.frontmatter.as_ref().and_then(|fm| fm.lua.as_ref()) {
+        return load_typedef_from_file(&config.resolve_lua_path(lua_path)).ok();
     }
-    if loaded.spec.frontmatter.is_some() {
-        msg.push_str(" [frontmatter updated]");
+    let registry = type_registry(config).ok()?;
+    registry.get(&loaded.logical_name).map(|td| (*td).clone())
+}
+
+/// Type definitions from the typedefs directory (and its fallback).
+fn type_registry(config: &ResolvedConfig) -> Result<TypeRegistry, String> {
+    let repo = match &config.typedefs_fallback_dir {
+        Some(fallback) => {
+            TypedefRepository::with_fallback(&config.typedefs_dir, fallback)
+        }
+        None => TypedefRepository::new(&config.typedefs_dir),
     }
+    .map_err(|e| format!("Failed to load type definitions: {e}"))?;
+    TypeRegistry::from_repository(&repo)
+        .map_err(|e| format!("Failed to load type definitions: {e}"))
+}
 
-    Ok(msg)
+/// What validating a note against its type found.
+#[derive(Debug, Clone)]
+pub struct NoteValidation {
+    pub path: PathBuf,
+    pub note_type: String,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    /// Safe fixes (as `mdv validate --fix` makes) that `fix_note` applies.
+    pub fixes: Vec<String>,
 }
 
-/// Execute capture operations: frontmatter modification and/or content insertion.
-fn execute_capture_operations(
-    existing_content: &str,
-    spec: &CaptureSpec,
-    ctx: &HashMap<String, String>,
-) -> Result<(String, Option<(String, u8)>), String> {
-    // Parse frontmatter from existing content first
-    let mut parsed = parse(existing_content)
-        .map_err(|e| format!("Failed to parse frontmatter: {e}"))?;
-    let mut section_info = None;
+/// Validate the note at `path` against its type, as `mdv validate` does.
+/// `None` when there is nothing to report.
+pub fn validate_file(
+    config: &ResolvedConfig,
+    path: &Path,
+) -> Result<Option<NoteValidation>, String> {
+    let registry = type_registry(config)?;
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let (note_type, result) = validate_content(&registry, path, &content);
+    if result.errors.is_empty() && result.warnings.is_empty() {
+        return Ok(None);
+    }
+
+    let fixes = try_fix_note(&registry, &note_type, &content, &result).fixes;
+    Ok(Some(NoteValidation {
+        path: path.to_path_buf(),
+        note_type,
+        errors: result.errors.iter().map(ToString::to_string).collect(),
+        warnings: result.warnings,
+        fixes,
+    }))
+}
 
-    // Apply frontmatter operations if specified
-    if let Some(fm_ops) = &spec.frontmatter {
-        parsed = apply_ops(parsed, fm_ops, ctx)
-            .map_err(|e| format!("Frontmatter error: {e}"))?;
+/// Apply the safe fixes for the note at `path`.
+pub fn fix_note(config: &ResolvedConfig, path: &Path) -> Result<String, String> {
+    let registry = type_registry(config)?;
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let (note_type, result) = validate_content(&registry, path, &content);
+    let fixed = try_fix_note(&registry, &note_type, &content, &result);
+    let Some(new_content) = fixed.content.filter(|_| fixed.fixed) else {
+        return Err(format!("No safe fixes for {}", path.display()));
+    };
+    apply_fixes(path, &new_content)?;
+    Ok(format!("Fixed {}: {}", path.display(), fixed.fixes.join(", ")))
+}
+
+/// The note's type (`none` if it has none) and its validation result.
+fn validate_content(
+    registry: &TypeRegistry,
+    path: &Path,
+    content: &str,
+) -> (String, ValidationResult) {
+    let fields = parse(content).ok().and_then(|p| p.frontmatter).map(|fm| fm.fields);
+    let note_type = fields
+        .as_ref()
+        .and_then(|f| f.get("type"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("none")
+        .to_string();
+    let mut map = serde_yaml::Mapping::new();
+    for (k, v) in fields.unwrap_or_default() {
+        map.insert(serde_yaml::Value::String(k), v);
     }
+    let result = validate_note(
+        registry,
+        &note_type,
+        &path.to_string_lossy(),
+        &serde_yaml::Value::Mapping(map),
+        content,
+    );
+    (note_type, result)
+}
 
-    // Insert content if specified - operate on body only to preserve frontmatter
-    if let Some(content_template) = &spec.content {
-        let section = spec.target.section.as_ref().ok_or_else(|| {
-            "Capture has content but no target section specified".to_string()
-        })?;
-
-        let rendered_content = render_string(content_template, ctx);
-        let section_match = SectionMatch::new(section);
-        let position = spec.target.position.clone().into();
-
-        let result = MarkdownEditor::insert_into_section(
-            &parsed.body,
-            &section_match,
-            &rendered_content,
-            position,
-        )
-        .map_err(|e| match &e {
-            MarkdownAstError::SectionNotFound(s) => format!("Section not found: '{s}'"),
-            MarkdownAstError::EmptyDocument => "Target file is empty".to_string(),
-            MarkdownAstError::RenderError(msg) => format!("Render error: {msg}"),
-        })?;
-
-        section_info = Some((result.matched_heading.title, result.matched_heading.level));
-        parsed.body = result.content;
+/// Most results the live search shows.
+const SEARCH_LIMIT: u32 = 50;
+
+/// Search the index for `text`, best matches first.
+pub fn search_notes(
+    config: &ResolvedConfig,
+    text: &str,
+) -> Result<Vec<SearchResult>, String> {
+    if text.trim().is_empty() {
+        return Ok(Vec::new());
     }
+    let index_path = PathResolver::new(&config.vault_root).index_db();
+    let db = IndexDb::open_read_only(&index_path)
+        .map_err(|e| format!("Failed to open index: {e}. Run 'mdv reindex' first."))?;
+    let query = SearchQuery {
+        text: Some(text.to_string()),
+        limit: Some(SEARCH_LIMIT),
+        fuzzy: true,
+        ..Default::default()
+    };
+    SearchEngine::new(&db).search(&query).map_err(|e| format!("Search error: {e}"))
+}
 
-    // Serialize the document (frontmatter + body)
-    let final_content = serialize(&parsed);
-    Ok((final_content, section_info))
+/// Activity log entries per local day from `first` to `last`.
+pub fn load_activity(
+    config: &ResolvedConfig,
+    first: NaiveDate,
+    last: NaiveDate,
+) -> Result<HashMap<NaiveDate, usize>, String> {
+    let Some(activity) = ActivityLogService::try_from_config(config) else {
+        return Ok(HashMap::new());
+    };
+    // A day of slack either side covers every timezone offset
+    let since =
+        first.pred_opt().and_then(|d| d.and_hms_opt(0, 0, 0)).map(|t| t.and_utc());
+    let until =
+        last.succ_opt().and_then(|d| d.and_hms_opt(23, 59, 59)).map(|t| t.and_utc());
+    let entries = activity
+        .read_entries(since, until)
+        .map_err(|e| format!("Failed to read activity log: {e}"))?;
+
+    let mut counts = HashMap::new();
+    for entry in entries {
+        let day = entry.ts.with_timezone(&Local).date_naive();
+        if (first..=last).contains(&day) {
+            *counts.entry(day).or_insert(0) += 1;
+        }
+    }
+    Ok(counts)
 }
 
-fn build_capture_context(cfg: &ResolvedConfig) -> HashMap<String, String> {
-    let mut ctx = HashMap::new();
+/// The day's context (tasks, activity, modified notes) as markdown.
+pub fn day_summary(config: &ResolvedConfig, date: NaiveDate) -> Result<String, String> {
+    ContextQueryService::new(config)
+        .day_context(date)
+        .map(|context| context.to_markdown())
+        .map_err(|e| format!("Failed to load day context: {e}"))
+}
 
-    // Date/time
-    let now = Local::now();
-    ctx.insert("date".into(), now.format("%Y-%m-%d").to_string());
-    ctx.insert("time".into(), now.format("%H:%M").to_string());
-    ctx.insert("datetime".into(), now.to_rfc3339());
+/// The most recently modified notes and the tasks in progress, as many of
+/// each as `[tui.start_screen]` asks for.
+pub fn load_home(
+    config: &ResolvedConfig,
+) -> Result<(Vec<IndexedNote>, Vec<IndexedNote>), String> {
+    let limits = &config.tui.start_screen;
+    let index_path = PathResolver::new(&config.vault_root).index_db();
+    let db = IndexDb::open_read_only(&index_path)
+        .map_err(|e| format!("Failed to open index: {e}. Run 'mdv reindex' first."))?;
+    let recent = db
+        .query_notes(&NoteQuery {
+            limit: Some(limits.recent as u32),
+            ..Default::default()
+        })
+        .map_err(|e| format!("Index error: {e}"))?;
+    let tasks = db
+        .query_notes(&NoteQuery { note_type: Some(NoteType::Task), ..Default::default() })
+        .map_err(|e| format!("Index error: {e}"))?
+        .into_iter()
+        .filter(|task| {
+            task.frontmatter_json
+                .as_deref()
+                .and_then(|fm| serde_json::from_str::<serde_json::Value>(fm).ok())
+                .and_then(|fm| fm.get("status")?.as_str().map(str::to_lowercase))
+                .is_some_and(|status| StatusFilter::InProgress.matches(&status))
+        })
+        .take(limits.tasks)
+        .collect();
+    Ok((recent, tasks))
+}
 
-    // Config paths
-    ctx.insert("vault_root".into(), cfg.vault_root.to_string_lossy().to_string());
-    ctx.insert("templates_dir".into(), cfg.templates_dir.to_string_lossy().to_string());
-    ctx.insert("captures_dir".into(), cfg.captures_dir.to_string_lossy().to_string());
-    ctx.insert("macros_dir".into(), cfg.macros_dir.to_string_lossy().to_string());
+/// Whether `date`'s daily note exists and what the activity log recorded
+/// that day.
+pub fn today_status(config: &ResolvedConfig, date: NaiveDate) -> TodayStatus {
+    let daily = PathResolver::new(&config.vault_root)
+        .daily_note(&date.format("%Y-%m-%d").to_string());
+    let daily = daily
+        .is_file()
+        .then(|| daily.strip_prefix(&config.vault_root).unwrap_or(&daily).to_path_buf());
+
+    // A day of slack either side covers every timezone offset, as in
+    // `load_activity`
+    let entries = ActivityLogService::try_from_config(config)
+        .and_then(|activity| {
+            let since = date.pred_opt()?.and_hms_opt(0, 0, 0)?.and_utc();
+            let until = date.succ_opt()?.and_hms_opt(23, 59, 59)?.and_utc();
+            activity.read_entries(Some(since), Some(until)).ok()
+        })
+        .unwrap_or_default();
+    let today: Vec<_> = entries
+        .iter()
+        .filter(|e| e.ts.with_timezone(&Local).date_naive() == date)
+        .collect();
+    TodayStatus {
+        date,
+        daily,
+        activity: today.len(),
+        completed: today.iter().filter(|e| e.op == Operation::Complete).count(),
+    }
+}
 
-    ctx
+/// The link neighbourhood of the note at `path`, `depth` hops each way.
+pub fn load_graph(
+    config: &ResolvedConfig,
+    path: &Path,
+    depth: usize,
+) -> Result<LinkGraph, String> {
+    let index_path = PathResolver::new(&config.vault_root).index_db();
+    let db = IndexDb::open_read_only(&index_path)
+        .map_err(|e| format!("Failed to open index: {e}. Run 'mdv reindex' first."))?;
+    let center = db
+        .get_note_by_path(path)
+        .map_err(|e| format!("Index error: {e}"))?
+        .ok_or_else(|| format!("Not in the index: {}", path.display()))?;
+    let id = center.id.unwrap_or_default();
+    let walk = |direction| {
+        walk_links(&db, id, direction, depth, None)
+            .map_err(|e| format!("Index error: {e}"))
+    };
+    let incoming = walk(LinkDirection::Incoming)?;
+    let outgoing = walk(LinkDirection::Outgoing)?;
+    Ok(LinkGraph::new(center, incoming, outgoing, depth))
 }
 
-fn render_string(template: &str, ctx: &HashMap<String, String>) -> String {
-    let re = Regex::new(r"\{\{([a-zA-Z0-9_]+)\}\}").unwrap();
-    re.replace_all(template, |caps: &regex::Captures<'_>| {
-        let key = &caps[1];
-        ctx.get(key).cloned().unwrap_or_else(|| caps[0].to_string())
+/// Frontmatter, outline, and links of the note at `path`, for the details
+/// panel.
+pub fn note_info(config: &ResolvedConfig, path: &Path) -> Result<NoteInfo, String> {
+    let index_path = PathResolver::new(&config.vault_root).index_db();
+    let db = IndexDb::open_read_only(&index_path)
+        .map_err(|e| format!("Failed to open index: {e}. Run 'mdv reindex' first."))?;
+    let note = db
+        .get_note_by_path(path)
+        .map_err(|e| format!("Index error: {e}"))?
+        .ok_or_else(|| format!("Not in the index: {}", path.display()))?;
+    let id = note.id.unwrap_or_default();
+    let linked = |id: i64| {
+        db.get_note_by_id(id).ok().flatten().map(|n| LinkedNote {
+            label: if n.title.is_empty() {
+                n.path.display().to_string()
+            } else {
+                n.title
+            },
+            path: Some(n.path),
+        })
+    };
+
+    let backlinks = db
+        .get_backlinks(id)
+        .map_err(|e| format!("Index error: {e}"))?
+        .into_iter()
+        .filter_map(|link| linked(link.source_id))
+        .collect();
+    let outgoing = db
+        .get_outgoing_links(id)
+        .map_err(|e| format!("Index error: {e}"))?
+        .into_iter()
+        .map(|link| {
+            link.target_id
+                .and_then(linked)
+                .unwrap_or(LinkedNote { label: link.target_path, path: None })
+        })
+        .collect();
+    Ok(NoteInfo::new(&note, backlinks, outgoing))
+}
+
+/// Progress of every project that is not archived, and the focused
+/// project's ID.
+pub fn load_projects(
+    config: &ResolvedConfig,
+) -> Result<(Vec<ProjectProgress>, Option<String>), String> {
+    let index_path = PathResolver::new(&config.vault_root).index_db();
+    let db = IndexDb::open_read_only(&index_path)
+        .map_err(|e| format!("Failed to open index: {e}. Run 'mdv reindex' first."))?;
+    let query =
+        |note_type| NoteQuery { note_type: Some(note_type), ..Default::default() };
+    let projects = db
+        .query_notes(&query(NoteType::Project))
+        .map_err(|e| format!("Index error: {e}"))?;
+    let tasks = db
+        .query_notes(&query(NoteType::Task))
+        .map_err(|e| format!("Index error: {e}"))?;
+
+    let progress = projects
+        .iter()
+        .filter(|p| extract_project_info(p).1 != "archived")
+        .map(|p| calculate_project_progress(p, &tasks))
+        .collect();
+    let focused = ContextManager::load(&config.vault_root)
+        .ok()
+        .and_then(|context| context.active_project().map(String::from));
+    Ok((progress, focused))
+}
+
+/// The description of each palette item, empty when it has none or fails
+/// to load.
+pub fn palette_descriptions(
+    config: &ResolvedConfig,
+    items: &[PaletteItem],
+) -> Vec<String> {
+    let templates = TemplateRepository::new(&config.templates_dir).ok();
+    let captures = CaptureRepository::new(&config.captures_dir).ok();
+    let macros = MacroRepository::new(&config.macros_dir).ok();
+
+    items
+        .iter()
+        .map(|item| {
+            let description = match item {
+                PaletteItem::Template(info) => templates.as_ref().and_then(|repo| {
+                    let loaded = repo.get_by_name(&info.logical_name).ok()?;
+                    let value = loaded.frontmatter?.extra.remove("description")?;
+                    value.as_str().map(String::from)
+                }),
+                PaletteItem::Capture(info) => captures.as_ref().and_then(|repo| {
+                    repo.get_by_name(&info.logical_name).ok().map(|c| c.spec.description)
+                }),
+                PaletteItem::Macro(info) => macros.as_ref().and_then(|repo| {
+                    repo.get_by_name(&info.logical_name).ok().map(|m| m.spec.description)
+                }),
+            };
+            description.unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Focus `project`, as `mdv focus` does.
+pub fn focus_project(config: &ResolvedConfig, project: &str) -> Result<String, String> {
+    let mut context = ContextManager::load(&config.vault_root)
+        .map_err(|e| format!("Failed to load context state: {e}"))?;
+    context.set_focus(project).map_err(|e| format!("Failed to set focus: {e}"))?;
+    if let Some(activity) = ActivityLogService::try_from_config(config) {
+        let _ = activity.log_focus(project, None, "set");
+    }
+    Ok(format!("Focus set to: {project}"))
+}
+
+/// The active focus, if any.
+pub fn load_focus(config: &ResolvedConfig) -> Option<FocusContext> {
+    ContextManager::load(&config.vault_root).ok()?.focus().cloned()
+}
+
+/// Clear the focus, as `mdv focus --clear` does, returning the session
+/// that ended.
+pub fn stop_focus(config: &ResolvedConfig) -> Result<Option<FocusContext>, String> {
+    let mut context = ContextManager::load(&config.vault_root)
+        .map_err(|e| format!("Failed to load context state: {e}"))?;
+    let previous = context.focus().cloned();
+    context.clear_focus().map_err(|e| format!("Failed to clear focus: {e}"))?;
+    if let Some(activity) = ActivityLogService::try_from_config(config)
+        && let Some(focus) = &previous
+    {
+        let _ = activity.log_focus(&focus.project, None, "clear");
+    }
+    Ok(previous)
+}
+
+/// Append `message` to the Logs section of today's daily note.
+pub fn log_to_daily(config: &ResolvedConfig, message: &str) -> Result<String, String> {
+    DailyLogService::log_entry(config, message)?;
+    Ok("Logged to today's daily note".to_string())
+}
+
+/// Path of `project`'s note.
+pub fn project_file(config: &ResolvedConfig, project: &str) -> Result<PathBuf, String> {
+    find_project_file(config, project).map_err(|e| e.to_string())
+}
+
+/// Append `message` to the Logs section of `project`'s note.
+pub fn log_to_project(
+    config: &ResolvedConfig,
+    project: &str,
+    message: &str,
+) -> Result<String, String> {
+    let project_file = find_project_file(config, project).map_err(|e| e.to_string())?;
+    ProjectLogService::log_entry(&project_file, message)?;
+    Ok(format!("Logged to {project}"))
+}
+
+/// Rename a note and update the links to it.
+pub fn rename_note(
+    config: &ResolvedConfig,
+    from: &Path,
+    to: &Path,
+) -> Result<String, String> {
+    let index_path = PathResolver::new(&config.vault_root).index_db();
+    let db =
+        IndexDb::open(&index_path).map_err(|e| format!("Failed to open index: {e}"))?;
+    let note_type = db
+        .get_note_by_path(from)
+        .ok()
+        .flatten()
+        .map(|n| n.note_type.as_str().to_string())
+        .unwrap_or_else(|| "note".to_string());
+
+    let hooks = crate::cmd::common::load_event_hooks(config)
+        .map_err(|e| format!("Failed to load event hooks: {e}"))?;
+    let result = execute_rename(&db, &config.vault_root, from, to, hooks.as_ref())
+        .map_err(|e| format!("Rename failed: {e}"))?;
+
+    if let Some(activity) = ActivityLogService::try_from_config(config) {
+        let _ = activity.log_rename(
+            &note_type,
+            &result.old_path,
+            &result.new_path,
+            result.references_updated,
+        );
+    }
+
+    let mut message = format!(
+        "Renamed to {} ({} references updated)",
+        to.display(),
+        result.references_updated
+    );
+    for warning in &result.warnings {
+        message.push_str(&format!("; {warning}"));
+    }
+    Ok(message)
+}
+
+/// Notes waiting in `Inbox/`, relative to the vault, oldest name first.
+pub fn inbox_notes(config: &ResolvedConfig) -> Result<Vec<PathBuf>, String> {
+    let inbox = config.vault_root.join("Inbox");
+    if !inbox.is_dir() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(&inbox)
+        .map_err(|e| format!("Failed to read {}: {e}", inbox.display()))?;
+    let mut notes: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|path| {
+            path.strip_prefix(&config.vault_root).ok().map(Path::to_path_buf)
+        })
+        .collect();
+    notes.sort();
+    Ok(notes)
+}
+
+/// Set one frontmatter field of `note` and bring the index up to date.
+fn set_frontmatter_field(
+    config: &ResolvedConfig,
+    db: &IndexDb,
+    note: &Path,
+    field: &str,
+    value: &str,
+) -> Result<(), String> {
+    let path = config.vault_root.join(note);
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {e}", note.display()))?;
+    let mut parsed = parse(&content)
+        .map_err(|e| format!("Failed to parse {}: {e}", note.display()))?;
+    parsed
+        .frontmatter
+        .get_or_insert_with(Frontmatter::default)
+        .fields
+        .insert(field.to_string(), serde_yaml::Value::String(value.to_string()));
+    fs::write(&path, serialize(&parsed))
+        .map_err(|e| format!("Failed to write {}: {e}", note.display()))?;
+    IndexBuilder::with_exclusions(db, &config.vault_root, config.excluded_folders.clone())
+        .reindex_file(note)
+        .map_err(|e| format!("Failed to index {}: {e}", note.display()))
+}
+
+/// File an inbox note under `project`: set its `project` field and move it
+/// into the project's folder (tasks into its `Tasks/`), updating links.
+pub fn assign_note(
+    config: &ResolvedConfig,
+    note: &Path,
+    project: &str,
+) -> Result<String, String> {
+    let project_file = find_project_file(config, project).map_err(|e| e.to_string())?;
+    let index_path = PathResolver::new(&config.vault_root).index_db();
+    let db =
+        IndexDb::open(&index_path).map_err(|e| format!("Failed to open index: {e}"))?;
+    set_frontmatter_field(config, &db, note, "project", project)?;
+
+    let is_task = db
+        .get_note_by_path(note)
+        .ok()
+        .flatten()
+        .is_some_and(|n| n.note_type == NoteType::Task);
+    let project_dir = project_file.parent().unwrap_or(&config.vault_root);
+    let project_dir = project_dir.strip_prefix(&config.vault_root).unwrap_or(project_dir);
+    let folder =
+        if is_task { project_dir.join("Tasks") } else { project_dir.to_path_buf() };
+    let Some(name) = note.file_name() else {
+        return Err(format!("Not a note: {}", note.display()));
+    };
+    rename_note(config, note, &folder.join(name))?;
+    Ok(format!("Moved to {}", folder.join(name).display()))
+}
+
+/// Set the `due_date` of `note` from a date or date expression
+/// (`2025-03-01`, `tomorrow`, `friday`, `today + 3d`).
+pub fn schedule_note(
+    config: &ResolvedConfig,
+    note: &Path,
+    when: &str,
+) -> Result<String, String> {
+    let date = NaiveDate::parse_from_str(when.trim(), "%Y-%m-%d")
+        .ok()
+        .or_else(|| {
+            let expr = parse_date_expr(when).ok()?;
+            NaiveDate::parse_from_str(&evaluate_date_expr(&expr), "%Y-%m-%d").ok()
+        })
+        .ok_or_else(|| format!("Not a date: {when}"))?;
+    let index_path = PathResolver::new(&config.vault_root).index_db();
+    let db =
+        IndexDb::open(&index_path).map_err(|e| format!("Failed to open index: {e}"))?;
+    set_frontmatter_field(config, &db, note, "due_date", &date.to_string())?;
+    Ok(format!("Due {date}"))
+}
+
+/// Archive or trash `note` the way `mdv expire` does, journaled so
+/// `mdv expire --undo` brings it back.
+pub fn file_away(
+    config: &ResolvedConfig,
+    note: &Path,
+    action: ExpireAction,
+) -> Result<String, String> {
+    let index_path = PathResolver::new(&config.vault_root).index_db();
+    let db =
+        IndexDb::open(&index_path).map_err(|e| format!("Failed to open index: {e}"))?;
+    let title = note.file_stem().map(|s| s.to_string_lossy().into_owned());
+    let expired = ExpiredNote {
+        path: note.to_path_buf(),
+        title: title.unwrap_or_default(),
+        expired_on: Local::now().date_naive(),
+        action,
+        reason: "inbox triage".to_string(),
+    };
+    let run = Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let outcome = expire_notes(&config.vault_root, &[expired], &run)
+        .map_err(|e| format!("Failed to move {}: {e}", note.display()))?;
+    if let Some((_, e)) = outcome.failed.first() {
+        return Err(format!("Failed to move {}: {e}", note.display()));
+    }
+    let Some(entry) = outcome.moved.first() else {
+        return Err(format!("Failed to move {}", note.display()));
+    };
+
+    // Trashed notes leave the index; archived ones stay searchable
+    let _ = db.delete_note(&entry.from);
+    if action == ExpireAction::Archive {
+        let builder = IndexBuilder::with_exclusions(
+            &db,
+            &config.vault_root,
+            config.excluded_folders.clone(),
+        );
+        let _ = builder.reindex_file(&entry.to);
+    }
+    Ok(match action {
+        ExpireAction::Archive => format!("Archived to {}", entry.to.display()),
+        ExpireAction::Trash => {
+            "Moved to the trash (undo with `mdv expire --undo`)".to_string()
+        }
     })
-    .into_owned()
+}
+
+/// Journal the files a run wrote so it can be undone. The run itself
+/// already happened, so a journal that cannot be written only costs the
+/// undo.
+pub fn commit_undo(recorder: Recorder, label: &str) {
+    let _ = recorder.commit(label);
+}
+
+/// Whether operation `id` is still the last one journaled, i.e. undoable.
+pub fn can_undo(config: &ResolvedConfig, id: &str) -> bool {
+    undo::last_operation(&config.vault_root)
+        .ok()
+        .flatten()
+        .is_some_and(|record| record.id == id)
+}
+
+/// Revert the files operation `id` wrote, returning what was rolled back.
+pub fn undo_operation(config: &ResolvedConfig, id: &str) -> Result<String, String> {
+    if !can_undo(config, id) {
+        return Err("Something was written since; nothing undone".to_string());
+    }
+    let record = undo::undo_last(&config.vault_root).map_err(|e| e.to_string())?;
+
+    // Keep the index in step; a stale entry is fixed by the next reindex
+    let index_path = PathResolver::new(&config.vault_root).index_db();
+    if let Ok(db) = IndexDb::open(&index_path) {
+        let builder = IndexBuilder::with_exclusions(
+            &db,
+            &config.vault_root,
+            config.excluded_folders.clone(),
+        );
+        for file in &record.files {
+            if file.backup.is_some() {
+                let _ = builder.reindex_file(&file.path);
+            } else {
+                let _ = db.delete_note(&file.path);
+            }
+        }
+    }
+
+    let files: Vec<String> = record
+        .files
+        .iter()
+        .map(|file| match file.backup {
+            Some(_) => format!("restored {}", file.path.display()),
+            None => format!("removed {}", file.path.display()),
+        })
+        .collect();
+    Ok(format!("Undid {}: {}", record.label, files.join(", ")))
 }
 
 fn resolve_target_path(vault_root: &Path, target: &str) -> std::path::PathBuf {
@@ -239,10 +853,17 @@ fn resolve_target_path(vault_root: &Path, target: &str) -> std::path::PathBuf {
 }
 
 /// Execute a macro workflow.
+///
+/// Shell steps run only when `trust` is set, i.e. the user confirmed them.
+/// Files written by template and capture steps are recorded for undo; what
+/// shell steps do is not.
 pub fn execute_macro(
     config: &ResolvedConfig,
     macro_name: &str,
     vars: &HashMap<String, String>,
+    trust: bool,
+    job: &JobContext,
+    recorder: &mut Recorder,
 ) -> Result<String, String> {
     use mdvault_core::macros::{
         CaptureStep, MacroRunError, ShellStep, StepResult, TemplateStep,
@@ -255,14 +876,34 @@ pub fn execute_macro(
     let loaded = repo.get_by_name(macro_name).map_err(|e| format!("Macro error: {e}"))?;
 
     // Build context with provided vars
-    let mut ctx_vars = build_capture_context(config);
+    let mut ctx_vars = capture_context(config);
     for (k, v) in vars {
         ctx_vars.insert(k.clone(), v.clone());
     }
 
-    // TUI executor (no shell support)
+    // TUI executor (shell output is captured, never written to the terminal).
+    // It reports each step as progress and stops once the job is cancelled.
     struct TuiStepExecutor<'a> {
         config: &'a ResolvedConfig,
+        job: &'a JobContext,
+        recorder: RefCell<&'a mut Recorder>,
+        total: usize,
+        started: Cell<usize>,
+    }
+
+    impl TuiStepExecutor<'_> {
+        fn begin(&self, what: &str) -> Result<(), String> {
+            if self.job.is_cancelled() {
+                return Err("cancelled".to_string());
+            }
+            self.started.set(self.started.get() + 1);
+            self.job.progress(format!(
+                "step {}/{}: {what}",
+                self.started.get(),
+                self.total
+            ));
+            Ok(())
+        }
     }
 
     impl<'a> StepExecutor for TuiStepExecutor<'a> {
@@ -276,6 +917,7 @@ pub fn execute_macro(
                 resolve_template_output_path,
             };
 
+            self.begin(&step.template).map_err(MacroRunError::TemplateError)?;
             let step_vars = ctx.with_step_vars(&step.vars_with);
 
             // Load template
@@ -331,6 +973,10 @@ pub fn execute_macro(
             }
 
             // Write file
+            self.recorder
+                .borrow_mut()
+                .before_write(&output_path)
+                .map_err(|e| MacroRunError::TemplateError(e.to_string()))?;
             fs::write(&output_path, &rendered)
                 .map_err(|e| MacroRunError::TemplateError(e.to_string()))?;
 
@@ -349,6 +995,7 @@ pub fn execute_macro(
         ) -> Result<StepResult, MacroRunError> {
             use mdvault_core::templates::engine::render_string;
 
+            self.begin(&step.capture).map_err(MacroRunError::CaptureError)?;
             let step_vars = ctx.with_step_vars(&step.vars_with);
 
             // Load capture
@@ -409,6 +1056,10 @@ pub fn execute_macro(
 
             // Serialize and write
             let final_content = serialize(&parsed);
+            self.recorder
+                .borrow_mut()
+                .before_write(&target_file)
+                .map_err(|e| MacroRunError::CaptureError(e.to_string()))?;
             fs::write(&target_file, &final_content)
                 .map_err(|e| MacroRunError::CaptureError(e.to_string()))?;
 
@@ -422,18 +1073,82 @@ pub fn execute_macro(
 
         fn execute_shell(
             &self,
-            _step: &ShellStep,
-            _ctx: &RunContext,
+            step: &ShellStep,
+            ctx: &RunContext,
         ) -> Result<StepResult, MacroRunError> {
-            // Shell not supported in TUI
-            Err(MacroRunError::TrustRequired)
+            use mdvault_core::templates::engine::render_string;
+
+            let rendered_cmd = render_string(&step.shell, &ctx.vars)
+                .map_err(|e| MacroRunError::ShellError(e.to_string()))?;
+            self.begin(&rendered_cmd).map_err(MacroRunError::ShellError)?;
+
+            let mut child = Command::new("sh")
+                .arg("-c")
+                .arg(&rendered_cmd)
+                .current_dir(&self.config.vault_root)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .spawn()
+                .map_err(|e| MacroRunError::ShellError(e.to_string()))?;
+
+            // Read stderr on the side so a chatty command cannot fill the
+            // pipe while we wait, and kill the command if the job is cancelled
+            let mut stderr_pipe = child.stderr.take();
+            let reader = thread::spawn(move || {
+                let mut stderr = String::new();
+                if let Some(pipe) = &mut stderr_pipe {
+                    let _ = pipe.read_to_string(&mut stderr);
+                }
+                stderr
+            });
+            let status = loop {
+                if let Some(status) = child
+                    .try_wait()
+                    .map_err(|e| MacroRunError::ShellError(e.to_string()))?
+                {
+                    break status;
+                }
+                if self.job.is_cancelled() {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(MacroRunError::ShellError(format!(
+                        "Cancelled: {rendered_cmd}"
+                    )));
+                }
+                thread::sleep(Duration::from_millis(50));
+            };
+            let stderr = reader.join().unwrap_or_default();
+
+            if status.success() {
+                Ok(StepResult {
+                    step_index: 0,
+                    success: true,
+                    message: format!("Executed: {rendered_cmd}"),
+                    output_path: None,
+                })
+            } else {
+                Err(MacroRunError::ShellError(format!(
+                    "Command failed: {rendered_cmd}\n{stderr}"
+                )))
+            }
         }
     }
 
-    let executor = TuiStepExecutor { config };
+    let executor = TuiStepExecutor {
+        config,
+        job,
+        recorder: RefCell::new(recorder),
+        total: loaded.spec.steps.len(),
+        started: Cell::new(0),
+    };
 
-    // Run with no trust (shell commands will fail)
-    let run_options = RunOptions { trust: false, allow_shell: false, dry_run: false };
+    // Confirming in the TUI stands in for `--trust`, which also allows shell
+    let run_options = RunOptions {
+        trust,
+        allow_shell: config.security.allow_shell || trust,
+        dry_run: false,
+    };
 
     let run_ctx = RunContext::new(ctx_vars, run_options);
     let result = run_macro(&loaded, &executor, run_ctx);
@@ -446,6 +1161,9 @@ pub fn execute_macro(
             msg.push_str(&format!(" → {}", path.display()));
         }
         Ok(msg)
+    } else if job.is_cancelled() {
+        let done = result.step_results.iter().filter(|r| r.success).count();
+        Err(format!("Macro cancelled after {done} of {} steps", loaded.spec.steps.len()))
     } else {
         let failed = result
             .step_results
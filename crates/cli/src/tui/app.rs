@@ -1,15 +1,39 @@
 //! Application state and update logic following The Elm Architecture.
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
 
 use mdvault_core::captures::CaptureInfo;
 use mdvault_core::config::types::ResolvedConfig;
-use mdvault_core::macros::{MacroInfo, requires_trust};
+use mdvault_core::context::FocusContext;
+use mdvault_core::domain::PromptType;
+use mdvault_core::expire::ExpireAction;
+use mdvault_core::index::IndexedNote;
+use mdvault_core::macros::{MacroInfo, get_shell_commands, requires_trust};
 use mdvault_core::templates::discovery::TemplateInfo;
 use mdvault_core::templates::engine::build_minimal_context;
 use mdvault_core::templates::repository::TemplateRepository;
-use mdvault_core::vars::collect_all_variables;
+use mdvault_core::types::FieldSchema;
+use mdvault_core::undo::Recorder;
+use mdvault_core::vars::{VarSpec, collect_all_variables};
+
+use super::actions::NoteValidation;
+use super::browser::NoteBrowser;
+use super::calendar::Calendar;
+use super::graph::{DEFAULT_DEPTH, LinkGraph, Side};
+use super::home::HomeScreen;
+use super::inbox::InboxTriage;
+use super::info::NoteInfo;
+use super::keys::KeyMap;
+use super::palette::PaletteFilter;
+use super::picker::Picker;
+use super::project::ProjectDashboard;
+use super::search::NoteSearch;
+use super::session::{self, LIST_WIDTH_STEP, SessionState};
+use super::worker::{Job, JobContext, JobResult};
 
 /// Unified item that can be either a template, capture, or macro.
 #[derive(Debug, Clone)]
@@ -27,6 +51,14 @@ impl PaletteItem {
             PaletteItem::Macro(m) => &m.logical_name,
         }
     }
+
+    pub fn path(&self) -> &Path {
+        match self {
+            PaletteItem::Template(t) => &t.path,
+            PaletteItem::Capture(c) => &c.path,
+            PaletteItem::Macro(m) => &m.path,
+        }
+    }
 }
 
 /// Variable info with display metadata.
@@ -40,6 +72,48 @@ pub struct VarInfo {
     pub description: Option<String>,
     /// Default value (pre-fills input).
     pub default: Option<String>,
+    /// How the value is asked for: typed (one line or several), picked
+    /// from options or indexed notes, a date, or yes/no.
+    pub prompt_type: PromptType,
+}
+
+/// Which list the left pane shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Pane {
+    /// Start screen: recent notes, today, and tasks in progress.
+    Home,
+    /// Templates, captures, and macros.
+    Palette,
+    /// The vault's indexed notes.
+    Notes,
+    /// Full-text search over the index.
+    Search,
+    /// Month calendar of activity and daily notes.
+    Calendar,
+    /// Progress of each project, starting on the focused one.
+    Project,
+    /// Notes in `Inbox/`, triaged one at a time.
+    Inbox,
+}
+
+/// A prompt about the selected note or project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotePrompt {
+    /// Name of the capture to run into the note.
+    CaptureInto,
+    /// New vault-relative path for the note.
+    Rename,
+    /// Title of a new task in the project.
+    NewTask,
+    /// Entry for the project's Logs section.
+    ProjectLog,
+    /// Entry for today's daily note about a focus session that ended.
+    FocusLog,
+    /// Project to file the inbox note under.
+    AssignProject,
+    /// Due date (or date expression) for the inbox note.
+    Schedule,
 }
 
 /// Current operating mode.
@@ -47,10 +121,22 @@ pub struct VarInfo {
 pub enum Mode {
     /// Browsing palette, selecting item.
     Browse,
+    /// Typing into the notes filter box.
+    Filter,
+    /// Typing a search query.
+    Search,
+    /// Exploring the link graph around a note.
+    Graph,
     /// Entering output path for template.
     OutputPath,
     /// Entering variables for selected item.
     Input { var_index: usize },
+    /// Answering a prompt about the selected note.
+    Prompt(NotePrompt),
+    /// Typing `yes` to let the selected macro run its shell commands.
+    ConfirmTrust,
+    /// Waiting on a template, capture, or macro running in the background.
+    Running,
     /// Showing result (success/error).
     Result,
 }
@@ -62,9 +148,31 @@ pub enum Preview {
     Template { content: String },
     Capture { content: String },
     Macro { content: String, requires_trust: bool },
+    Note { path: PathBuf, content: String },
+    Day { date: NaiveDate, content: String },
     Error(String),
 }
 
+impl Preview {
+    /// Whether this preview is a note's markdown, which renders styled
+    /// unless raw mode is on.
+    pub fn is_markdown(&self) -> bool {
+        matches!(self, Preview::Note { .. } | Preview::Day { .. })
+    }
+
+    pub fn content(&self) -> &str {
+        match self {
+            Preview::None => "",
+            Preview::Template { content }
+            | Preview::Capture { content }
+            | Preview::Macro { content, .. }
+            | Preview::Note { content, .. }
+            | Preview::Day { content, .. } => content,
+            Preview::Error(e) => e,
+        }
+    }
+}
+
 /// Feedback message to display in status bar.
 #[derive(Debug, Clone)]
 pub struct StatusMessage {
@@ -78,6 +186,69 @@ pub enum Message {
     // Navigation
     SelectNext,
     SelectPrev,
+    SwitchPane,
+
+    // Notes browser
+    StartFilter,
+    FilterChar(char),
+    FilterBackspace,
+    FilterDone,
+    FilterCancel,
+    ToggleView,
+    CycleType,
+    OpenNote,
+    CaptureInto,
+    RenameNote,
+
+    // Search
+    StartSearch,
+    SearchChar(char),
+    SearchBackspace,
+    SearchDone,
+
+    // Link graph
+    ShowGraph,
+    GraphSide(Side),
+    GraphFollow,
+    GraphBack,
+    GraphDepth,
+    GraphClose,
+
+    // Calendar
+    MoveDay(i64),
+    MoveMonth(i32),
+    Today,
+    OpenDaily,
+    OpenToday,
+
+    // Project dashboard
+    NewTask,
+    LogProject,
+    FocusProject,
+    StopFocus,
+
+    // Inbox triage
+    AssignNote,
+    ScheduleNote,
+    ArchiveNote,
+    DeleteNote,
+    SkipNote,
+
+    // Layout
+    ResizeList(i16),
+
+    // Preview
+    ScrollPreview(i32),
+    TogglePreviewRaw,
+    ToggleInfo,
+
+    // Help
+    ToggleHelp,
+
+    // Editor
+    Edit,
+    Fix,
+    Undo,
 
     // Mode transitions
     Execute,
@@ -85,8 +256,15 @@ pub enum Message {
 
     // Input handling
     InputChar(char),
+    InputNewline,
     InputBackspace,
     InputSubmit,
+    ToggleOption,
+
+    // Background job
+    JobProgress(String),
+    JobDone(JobResult),
+    CancelJob,
 
     // System
     Quit,
@@ -98,9 +276,76 @@ pub struct App {
     /// Operating mode.
     pub mode: Mode,
 
+    /// Which list the left pane shows.
+    pub pane: Pane,
+
+    /// Width of the list pane, in percent of the body.
+    pub list_width: u16,
+
+    /// Start screen.
+    pub home: HomeScreen,
+
+    /// Notes browser over the vault index.
+    pub browser: NoteBrowser,
+
+    /// Why the notes could not be loaded, if they could not.
+    pub browser_error: Option<String>,
+
+    /// Live search over the index.
+    pub search: NoteSearch,
+
+    /// Calendar of daily activity.
+    pub calendar: Calendar,
+
+    /// Project dashboard.
+    pub projects: ProjectDashboard,
+
+    /// Inbox triage queue.
+    pub inbox: InboxTriage,
+
+    /// Active focus (`mdv focus`), whose session timer the header shows.
+    pub focus: Option<FocusContext>,
+
+    /// Link graph being explored, while in graph mode.
+    pub graph: Option<LinkGraph>,
+
+    /// Note the next capture writes into instead of its own target.
+    pub capture_target: Option<PathBuf>,
+
+    /// Note to open in the editor once the terminal is released.
+    pub open_request: Option<PathBuf>,
+
+    /// File the last template or capture wrote, which `edit` opens from
+    /// the result.
+    pub last_written: Option<PathBuf>,
+
+    /// Validation of the file just written, shown with the result.
+    pub validation: Option<NoteValidation>,
+
+    /// Journaled operation of the last run, which `undo` reverts from the
+    /// result.
+    pub undo_id: Option<String>,
+
+    /// Template, capture, or macro running on a worker thread.
+    pub job: Option<Job>,
+
+    /// Day whose daily note to open (creating it if needed) once the
+    /// terminal is released.
+    pub daily_request: Option<NaiveDate>,
+
+    /// Project and title of a task to create (as `mdv new task` does) once
+    /// the terminal is released.
+    pub task_request: Option<(String, String)>,
+
     /// Resolved configuration.
     pub config: ResolvedConfig,
 
+    /// Active key bindings (defaults plus `[tui.keys]`).
+    pub keys: KeyMap,
+
+    /// Whether the key binding help overlay is shown.
+    pub show_help: bool,
+
     /// All palette items (templates + captures + macros).
     pub items: Vec<PaletteItem>,
 
@@ -113,9 +358,24 @@ pub struct App {
     /// Currently selected index in palette.
     pub selected: usize,
 
+    /// Fuzzy filter over the palette items.
+    pub palette: PaletteFilter,
+
     /// Preview of currently selected item.
     pub preview: Preview,
 
+    /// Lines the preview is scrolled down by.
+    pub preview_scroll: u16,
+
+    /// Show note previews as plain text instead of rendered markdown.
+    pub preview_raw: bool,
+
+    /// Show the details panel beside note previews.
+    pub show_info: bool,
+
+    /// Details of the previewed note, for the panel.
+    pub note_info: Option<Result<NoteInfo, String>>,
+
     /// Variables required by current item (with metadata for prompts).
     pub required_var_infos: Vec<VarInfo>,
 
@@ -125,23 +385,42 @@ pub struct App {
     /// Current input buffer (for variable/path entry).
     pub input_buffer: String,
 
+    /// Picker for the variable being entered, if it has fixed values.
+    pub picker: Option<Picker>,
+
+    /// Whether the variable being entered is multi-line text, where Enter
+    /// adds a line break and `submit` (Ctrl-d) submits.
+    pub multiline: bool,
+
     /// Status bar message.
     pub status: Option<StatusMessage>,
 
     /// Should quit.
     pub should_quit: bool,
 
+    /// Running a single capture for `mdv -c`: quit once it is written or
+    /// cancelled.
+    pub quick_capture: bool,
+
     /// Resolved output path for template (from frontmatter or user input).
     pub resolved_output_path: Option<PathBuf>,
+
+    /// Shell commands of the macro awaiting trust confirmation.
+    pub trust_commands: Vec<String>,
+
+    /// Whether the user confirmed the selected macro's shell commands.
+    pub macro_trusted: bool,
 }
 
 impl App {
     /// Create a new App with loaded config and discovered items.
     pub fn new(
         config: ResolvedConfig,
+        keys: KeyMap,
         templates: Vec<TemplateInfo>,
         captures: Vec<CaptureInfo>,
         macros: Vec<MacroInfo>,
+        notes: Result<Vec<IndexedNote>, String>,
     ) -> Self {
         let captures_start_index = templates.len();
         let macros_start_index = templates.len() + captures.len();
@@ -151,22 +430,62 @@ impl App {
         items.extend(captures.into_iter().map(PaletteItem::Capture));
         items.extend(macros.into_iter().map(PaletteItem::Macro));
 
+        let (notes, browser_error) = match notes {
+            Ok(notes) => (notes, None),
+            Err(e) => (Vec::new(), Some(e)),
+        };
+
         let mut app = App {
             mode: Mode::Browse,
+            pane: Pane::Palette,
+            list_width: SessionState::default().list_width,
+            home: HomeScreen::default(),
+            browser: NoteBrowser::new(notes),
+            browser_error,
+            search: NoteSearch::default(),
+            calendar: Calendar::new(Local::now().date_naive()),
+            projects: ProjectDashboard::default(),
+            inbox: InboxTriage::default(),
+            focus: None,
+            graph: None,
+            capture_target: None,
+            open_request: None,
+            last_written: None,
+            undo_id: None,
+            validation: None,
+            job: None,
+            daily_request: None,
+            task_request: None,
             config,
+            keys,
+            show_help: false,
             items,
             captures_start_index,
             macros_start_index,
             selected: 0,
+            palette: PaletteFilter::default(),
             preview: Preview::None,
+            preview_scroll: 0,
+            preview_raw: false,
+            show_info: true,
+            note_info: None,
             required_var_infos: Vec::new(),
             var_values: HashMap::new(),
             input_buffer: String::new(),
+            picker: None,
+            multiline: false,
             status: None,
             should_quit: false,
+            quick_capture: false,
             resolved_output_path: None,
+            trust_commands: Vec::new(),
+            macro_trusted: false,
         };
 
+        let descriptions = super::actions::palette_descriptions(&app.config, &app.items);
+        app.palette = PaletteFilter::new(&app.items, descriptions);
+        app.focus = super::actions::load_focus(&app.config);
+
         // Load preview for first item if any
         app.load_preview();
         app
@@ -175,37 +494,430 @@ impl App {
     /// Process a message and update state.
     pub fn update(&mut self, msg: Message) {
         match msg {
-            Message::SelectNext => {
-                if self.selected < self.items.len().saturating_sub(1) {
-                    self.selected += 1;
-                    self.load_preview();
+            Message::SelectNext if self.picker.is_some() => {
+                if let Some(picker) = &mut self.picker {
+                    picker.select_next();
+                }
+            }
+            Message::SelectPrev if self.picker.is_some() => {
+                if let Some(picker) = &mut self.picker {
+                    picker.select_prev();
+                }
+            }
+            Message::SelectNext if self.mode == Mode::Graph => {
+                if let Some(graph) = &mut self.graph {
+                    graph.select_next();
+                }
+            }
+            Message::SelectPrev if self.mode == Mode::Graph => {
+                if let Some(graph) = &mut self.graph {
+                    graph.select_prev();
                 }
             }
-            Message::SelectPrev => {
-                if self.selected > 0 {
-                    self.selected -= 1;
+            Message::SelectNext => match self.pane {
+                Pane::Home => {
+                    self.home.select_next();
+                    self.load_preview();
+                }
+                Pane::Palette => {
+                    if let Some(next) = self.palette.next(self.selected) {
+                        self.selected = next;
+                        self.load_preview();
+                    }
+                }
+                Pane::Notes => {
+                    self.browser.select_next();
+                    self.load_preview();
+                }
+                Pane::Search => {
+                    self.search.select_next();
+                    self.load_preview();
+                }
+                Pane::Calendar => self.update(Message::MoveDay(7)),
+                Pane::Project => self.projects.select_next(),
+                Pane::Inbox => {
+                    self.inbox.select_next();
+                    self.load_preview();
+                }
+            },
+            Message::SelectPrev => match self.pane {
+                Pane::Home => {
+                    self.home.select_prev();
+                    self.load_preview();
+                }
+                Pane::Palette => {
+                    if let Some(prev) = self.palette.prev(self.selected) {
+                        self.selected = prev;
+                        self.load_preview();
+                    }
+                }
+                Pane::Notes => {
+                    self.browser.select_prev();
+                    self.load_preview();
+                }
+                Pane::Search => {
+                    self.search.select_prev();
+                    self.load_preview();
+                }
+                Pane::Calendar => self.update(Message::MoveDay(-7)),
+                Pane::Project => self.projects.select_prev(),
+                Pane::Inbox => {
+                    self.inbox.select_prev();
                     self.load_preview();
                 }
+            },
+            Message::SwitchPane => {
+                self.pane = match self.pane {
+                    Pane::Home => Pane::Palette,
+                    Pane::Palette => Pane::Notes,
+                    Pane::Notes => Pane::Search,
+                    Pane::Search => Pane::Calendar,
+                    Pane::Calendar => Pane::Project,
+                    Pane::Project => Pane::Inbox,
+                    Pane::Inbox => Pane::Home,
+                };
+                self.status = None;
+                // Pick up notes written since the last visit
+                match self.pane {
+                    Pane::Home => self.home.invalidate(),
+                    Pane::Inbox => self.inbox.invalidate(),
+                    _ => {}
+                }
+                self.load_preview();
+            }
+            Message::StartFilter => {
+                self.mode = Mode::Filter;
+            }
+            Message::FilterChar(c) => {
+                if self.pane == Pane::Palette {
+                    self.palette.push_query(c, &self.items);
+                    self.select_first_match();
+                } else {
+                    self.browser.push_query(c);
+                }
+                self.load_preview();
+            }
+            Message::FilterBackspace => {
+                if self.pane == Pane::Palette {
+                    self.palette.pop_query(&self.items);
+                    self.select_first_match();
+                } else {
+                    self.browser.pop_query();
+                }
+                self.load_preview();
+            }
+            Message::FilterDone => {
+                self.mode = Mode::Browse;
+            }
+            Message::FilterCancel => {
+                if self.pane == Pane::Palette {
+                    self.palette.clear_query(&self.items);
+                } else {
+                    self.browser.clear_query();
+                }
+                self.mode = Mode::Browse;
+                self.load_preview();
+            }
+            Message::ToggleView => {
+                self.browser.toggle_view();
+                self.load_preview();
+            }
+            Message::CycleType => {
+                self.browser.cycle_type();
+                self.load_preview();
+            }
+            Message::StartSearch => {
+                self.pane = Pane::Search;
+                self.mode = Mode::Search;
+                self.load_preview();
+            }
+            Message::SearchChar(c) => {
+                self.search.query.push(c);
+                self.run_search();
+            }
+            Message::SearchBackspace => {
+                self.search.query.pop();
+                self.run_search();
+            }
+            Message::SearchDone => {
+                self.mode = Mode::Browse;
+            }
+            Message::ShowGraph => {
+                if let Some(path) = self.selected_note_path().map(Path::to_path_buf) {
+                    match super::actions::load_graph(&self.config, &path, DEFAULT_DEPTH) {
+                        Ok(graph) => {
+                            self.graph = Some(graph);
+                            self.mode = Mode::Graph;
+                        }
+                        Err(e) => self.finish_note_action(Err(e)),
+                    }
+                }
+            }
+            Message::GraphSide(side) => {
+                if let Some(graph) = &mut self.graph {
+                    graph.focus(side);
+                }
+            }
+            Message::GraphFollow => {
+                if let Some(node) = self.graph.as_ref().and_then(|g| g.selected_node()) {
+                    let path = node.path.clone();
+                    self.regraph(&path, |graph, next| graph.recenter(next));
+                }
+            }
+            Message::GraphBack => {
+                if let Some(path) = self.graph.as_mut().and_then(|g| g.back()) {
+                    self.regraph(&path, |graph, mut next| {
+                        next.history = std::mem::take(&mut graph.history);
+                        *graph = next;
+                    });
+                }
+            }
+            Message::GraphDepth => {
+                if let Some(graph) = &self.graph {
+                    let path = graph.center.path.clone();
+                    let depth = if graph.depth == 1 { 2 } else { 1 };
+                    self.regraph_at(&path, depth, |graph, mut next| {
+                        next.history = std::mem::take(&mut graph.history);
+                        *graph = next;
+                    });
+                }
+            }
+            Message::GraphClose => {
+                self.graph = None;
+                self.mode = Mode::Browse;
+            }
+            Message::MoveDay(days) => {
+                self.calendar.move_days(days);
+                self.load_preview();
+            }
+            Message::MoveMonth(months) => {
+                self.calendar.move_months(months);
+                self.load_preview();
+            }
+            Message::Today => {
+                self.calendar.select_today();
+                self.load_preview();
+            }
+            Message::OpenDaily => {
+                self.daily_request = Some(self.calendar.selected);
+            }
+            Message::OpenToday => {
+                self.daily_request = Some(Local::now().date_naive());
+            }
+            Message::Edit => {
+                if self.mode == Mode::Result {
+                    if let Some(written) = self.last_written.take() {
+                        self.update(Message::ClearStatus);
+                        self.open_request = Some(written);
+                    }
+                    return;
+                }
+                let path = if let Some(note) = self.selected_note_path() {
+                    Ok(Some(self.config.vault_root.join(note)))
+                } else {
+                    match self.pane {
+                        Pane::Palette if self.palette.is_visible(self.selected) => {
+                            Ok(self
+                                .items
+                                .get(self.selected)
+                                .map(|i| i.path().to_path_buf()))
+                        }
+                        Pane::Project => match self.projects.selected_project() {
+                            Some(project) => {
+                                super::actions::project_file(&self.config, &project.id)
+                                    .map(Some)
+                            }
+                            None => Ok(None),
+                        },
+                        _ => Ok(None),
+                    }
+                };
+                match path {
+                    Ok(path) => self.open_request = path,
+                    Err(e) => {
+                        self.status = Some(StatusMessage { text: e, is_error: true });
+                        self.mode = Mode::Result;
+                    }
+                }
+            }
+            Message::ScrollPreview(lines) => {
+                let last = self.preview_lines().saturating_sub(1);
+                let scroll = (self.preview_scroll as i64 + lines as i64).max(0);
+                self.preview_scroll = (scroll as usize).min(last) as u16;
+            }
+            Message::TogglePreviewRaw => {
+                self.preview_raw = !self.preview_raw;
+                self.preview_scroll = 0;
+            }
+            Message::ToggleInfo => {
+                self.show_info = !self.show_info;
+                self.load_preview();
+            }
+            Message::ToggleHelp => {
+                self.show_help = !self.show_help;
+            }
+            Message::OpenNote => {
+                if let Some(path) = self.selected_note_path() {
+                    self.open_request = Some(self.config.vault_root.join(path));
+                }
+            }
+            Message::CaptureInto => {
+                if self.selected_note_path().is_some() {
+                    self.input_buffer = self
+                        .items
+                        .get(self.captures_start_index..self.macros_start_index)
+                        .and_then(|captures| captures.first())
+                        .map(|c| c.name().to_string())
+                        .unwrap_or_default();
+                    self.mode = Mode::Prompt(NotePrompt::CaptureInto);
+                }
+            }
+            Message::RenameNote => {
+                if let Some(note) = self.browser.selected_note() {
+                    self.input_buffer = note.path.to_string_lossy().into_owned();
+                    self.mode = Mode::Prompt(NotePrompt::Rename);
+                }
+            }
+            Message::NewTask => {
+                if self.projects.selected_project().is_some() {
+                    self.input_buffer.clear();
+                    self.mode = Mode::Prompt(NotePrompt::NewTask);
+                }
+            }
+            Message::LogProject => {
+                if self.projects.selected_project().is_some() {
+                    self.input_buffer.clear();
+                    self.mode = Mode::Prompt(NotePrompt::ProjectLog);
+                }
+            }
+            Message::FocusProject => {
+                if let Some(project) = self.projects.selected_project() {
+                    let result = super::actions::focus_project(&self.config, &project.id);
+                    self.focus = super::actions::load_focus(&self.config);
+                    self.projects.invalidate();
+                    self.finish_note_action(result);
+                }
+            }
+            Message::StopFocus => self.stop_focus(),
+            Message::AssignNote => {
+                if self.inbox.current_note().is_some() {
+                    let projects = super::actions::note_choices(&self.config, "project");
+                    if projects.is_empty() {
+                        self.finish_note_action(Err(
+                            "No projects to file into".to_string()
+                        ));
+                        return;
+                    }
+                    self.input_buffer.clear();
+                    self.picker = Some(Picker::new(projects, None));
+                    self.mode = Mode::Prompt(NotePrompt::AssignProject);
+                }
+            }
+            Message::ScheduleNote => {
+                if self.inbox.current_note().is_some() {
+                    self.input_buffer.clear();
+                    self.mode = Mode::Prompt(NotePrompt::Schedule);
+                }
+            }
+            Message::ArchiveNote => self.file_away(ExpireAction::Archive),
+            Message::DeleteNote => self.file_away(ExpireAction::Trash),
+            Message::SkipNote => {
+                self.inbox.skip();
+                self.status = None;
+                self.load_preview();
+            }
+            Message::ResizeList(steps) => {
+                self.list_width =
+                    session::resize(self.list_width, steps * LIST_WIDTH_STEP);
             }
             Message::Execute => {
-                self.start_execution();
+                // A filter that hides every item leaves nothing to run
+                if self.palette.is_visible(self.selected) {
+                    self.start_execution();
+                }
             }
             Message::Cancel => {
                 self.mode = Mode::Browse;
                 self.input_buffer.clear();
+                self.picker = None;
+                self.multiline = false;
                 self.required_var_infos.clear();
                 self.var_values.clear();
                 self.resolved_output_path = None;
+                self.capture_target = None;
+                self.trust_commands.clear();
+                self.macro_trusted = false;
+                if self.quick_capture {
+                    self.should_quit = true;
+                }
             }
             Message::InputChar(c) => {
                 self.input_buffer.push(c);
+                if let Some(picker) = &mut self.picker {
+                    picker.set_query(&self.input_buffer);
+                }
+            }
+            Message::InputNewline => {
+                self.input_buffer.push('\n');
+                self.multiline = true;
             }
             Message::InputBackspace => {
                 self.input_buffer.pop();
+                if let Some(picker) = &mut self.picker {
+                    picker.set_query(&self.input_buffer);
+                }
             }
             Message::InputSubmit => {
                 self.submit_input();
             }
+            Message::ToggleOption => {
+                if let Some(picker) = &mut self.picker {
+                    picker.toggle();
+                }
+            }
+            Message::Fix => {
+                let Some(validation) = &self.validation else {
+                    return;
+                };
+                let path = validation.path.clone();
+                let (text, is_error) = match super::actions::fix_note(&self.config, &path)
+                {
+                    Ok(msg) => (msg, false),
+                    Err(msg) => (msg, true),
+                };
+                self.status = Some(StatusMessage { text, is_error });
+                self.validation =
+                    super::actions::validate_file(&self.config, &path).ok().flatten();
+                self.load_preview();
+            }
+            Message::Undo => {
+                let Some(id) = self.undo_id.take() else {
+                    return;
+                };
+                let (text, is_error) =
+                    match super::actions::undo_operation(&self.config, &id) {
+                        Ok(msg) => (msg, false),
+                        Err(msg) => (msg, true),
+                    };
+                self.status = Some(StatusMessage { text, is_error });
+                self.last_written = None;
+                self.validation = None;
+                self.reload_notes();
+                self.load_preview();
+            }
+            Message::JobProgress(text) => {
+                if let Some(job) = &mut self.job {
+                    job.progress = Some(text);
+                }
+            }
+            Message::JobDone(result) => {
+                self.finish_job(result);
+            }
+            Message::CancelJob => {
+                if let Some(job) = &self.job {
+                    job.cancel();
+                }
+            }
             Message::ClearStatus => {
                 self.status = None;
                 self.mode = Mode::Browse;
@@ -213,6 +925,15 @@ impl App {
                 self.required_var_infos.clear();
                 self.var_values.clear();
                 self.resolved_output_path = None;
+                self.capture_target = None;
+                self.trust_commands.clear();
+                self.macro_trusted = false;
+                self.last_written = None;
+                self.validation = None;
+                self.undo_id = None;
+                if self.quick_capture {
+                    self.should_quit = true;
+                }
             }
             Message::Quit => {
                 self.should_quit = true;
@@ -224,7 +945,71 @@ impl App {
     pub fn load_preview(&mut self) {
         use mdvault_core::macros::MacroRepository;
 
-        if self.items.is_empty() {
+        self.preview_scroll = 0;
+        self.note_info = None;
+
+        if self.pane == Pane::Calendar {
+            if self.calendar.needs_activity() {
+                let (first, last) = self.calendar.month_bounds();
+                let activity = super::actions::load_activity(&self.config, first, last);
+                self.calendar.set_activity(activity);
+            }
+            let date = self.calendar.selected;
+            self.preview = match super::actions::day_summary(&self.config, date) {
+                Ok(content) => Preview::Day { date, content },
+                Err(e) => Preview::Error(e),
+            };
+            return;
+        }
+
+        if self.pane == Pane::Project {
+            if self.projects.needs_load() {
+                match super::actions::load_projects(&self.config) {
+                    Ok((projects, focused)) => {
+                        self.projects.set_projects(projects, focused)
+                    }
+                    Err(e) => {
+                        self.status = Some(StatusMessage { text: e, is_error: true })
+                    }
+                }
+            }
+            self.preview = Preview::None;
+            return;
+        }
+
+        if self.pane == Pane::Home && self.home.needs_load() {
+            let today = Local::now().date_naive();
+            self.home.today = Some(super::actions::today_status(&self.config, today));
+            self.home.set_notes(super::actions::load_home(&self.config));
+        }
+
+        if self.pane == Pane::Inbox && self.inbox.needs_load() {
+            let notes = super::actions::inbox_notes(&self.config);
+            self.inbox.set_notes(notes);
+        }
+
+        if self.show_info && matches!(self.pane, Pane::Notes | Pane::Search) {
+            self.note_info = self
+                .selected_note_path()
+                .map(|path| super::actions::note_info(&self.config, path));
+        }
+
+        if self.pane != Pane::Palette {
+            self.preview = match self.selected_note_path() {
+                Some(path) => {
+                    match std::fs::read_to_string(self.config.vault_root.join(path)) {
+                        Ok(content) => {
+                            Preview::Note { path: path.to_path_buf(), content }
+                        }
+                        Err(e) => Preview::Error(format!("Failed to read: {e}")),
+                    }
+                }
+                None => Preview::None,
+            };
+            return;
+        }
+
+        if !self.palette.is_visible(self.selected) {
             self.preview = Preview::None;
             return;
         }
@@ -261,6 +1046,62 @@ impl App {
         }
     }
 
+    /// Go straight to the input of capture `name`, for `mdv -c`.
+    pub fn start_quick_capture(&mut self, name: &str) -> Result<(), String> {
+        let index = (self.captures_start_index..self.macros_start_index)
+            .find(|&i| self.items[i].name() == name)
+            .ok_or_else(|| format!("Unknown capture: {name}"))?;
+        self.quick_capture = true;
+        self.selected = index;
+        self.start_execution();
+        Ok(())
+    }
+
+    /// Reopen on the pane, filters, and layout of a previous session.
+    pub fn restore_session(&mut self, state: SessionState) {
+        self.pane =
+            if self.config.tui.start_screen.enabled { Pane::Home } else { state.pane };
+        self.list_width = state.list_width;
+        self.show_info = state.show_info;
+        self.palette.set_query(&state.palette_query, &self.items);
+        self.select_first_match();
+        self.browser.set_filters(state.notes_view, state.notes_type, &state.notes_query);
+        self.search.query = state.search_query;
+        if !self.search.query.trim().is_empty() {
+            let results = super::actions::search_notes(&self.config, &self.search.query);
+            self.search.set_results(results);
+        }
+        self.load_preview();
+    }
+
+    /// What to restore next time.
+    pub fn session_state(&self) -> SessionState {
+        SessionState {
+            pane: self.pane,
+            palette_query: self.palette.query.clone(),
+            notes_query: self.browser.query.clone(),
+            notes_type: self.browser.type_filter,
+            notes_view: self.browser.view,
+            search_query: self.search.query.clone(),
+            list_width: self.list_width,
+            show_info: self.show_info,
+        }
+    }
+
+    /// Select the first palette item the filter shows.
+    fn select_first_match(&mut self) {
+        self.selected = self.palette.first().unwrap_or(0);
+    }
+
+    /// Lines in the preview as drawn, before wrapping.
+    fn preview_lines(&self) -> usize {
+        if self.preview.is_markdown() && !self.preview_raw {
+            super::markdown::render(self.preview.content()).len()
+        } else {
+            self.preview.content().lines().count()
+        }
+    }
+
     /// Start execution workflow for selected item.
     fn start_execution(&mut self) {
         if self.items.is_empty() {
@@ -279,13 +1120,7 @@ impl App {
                             // No vars needed, proceed to output path resolution
                             self.proceed_to_template_output();
                         } else {
-                            // Pre-fill with default if available
-                            if let Some(default) = &self.required_var_infos[0].default {
-                                self.input_buffer = default.clone();
-                            } else {
-                                self.input_buffer.clear();
-                            }
-                            self.mode = Mode::Input { var_index: 0 };
+                            self.prompt_var(0);
                         }
                     }
                     Err(e) => {
@@ -304,13 +1139,7 @@ impl App {
                             // No vars needed, execute immediately
                             self.execute_capture();
                         } else {
-                            // Pre-fill with default if available
-                            if let Some(default) = &self.required_var_infos[0].default {
-                                self.input_buffer = default.clone();
-                            } else {
-                                self.input_buffer.clear();
-                            }
-                            self.mode = Mode::Input { var_index: 0 };
+                            self.prompt_var(0);
                         }
                     }
                     Err(e) => {
@@ -322,30 +1151,19 @@ impl App {
             PaletteItem::Macro(info) => {
                 // Load macro to extract required variables with metadata
                 match self.load_macro_var_infos(&info.logical_name) {
-                    Ok((var_infos, needs_trust)) => {
-                        if needs_trust {
-                            // Macros with shell commands aren't supported in TUI yet
-                            self.status = Some(StatusMessage {
-                                text: "Macro requires --trust flag. Use CLI: mdv macro --trust".to_string(),
-                                is_error: true,
-                            });
-                            self.mode = Mode::Result;
-                            return;
-                        }
+                    Ok((var_infos, shell_commands)) => {
                         self.required_var_infos = var_infos;
                         self.var_values.clear();
-                        if self.required_var_infos.is_empty() {
-                            // No vars needed, execute immediately
-                            self.execute_macro();
-                        } else {
-                            // Pre-fill with default if available
-                            if let Some(default) = &self.required_var_infos[0].default {
-                                self.input_buffer = default.clone();
-                            } else {
-                                self.input_buffer.clear();
-                            }
-                            self.mode = Mode::Input { var_index: 0 };
+                        self.macro_trusted = false;
+                        if !shell_commands.is_empty() {
+                            // Shell steps run only after the user has seen
+                            // them and typed `yes`, the TUI's `--trust`
+                            self.trust_commands = shell_commands;
+                            self.input_buffer.clear();
+                            self.mode = Mode::ConfirmTrust;
+                            return;
                         }
+                        self.collect_macro_vars();
                     }
                     Err(e) => {
                         self.status = Some(StatusMessage { text: e, is_error: true });
@@ -356,6 +1174,34 @@ impl App {
         }
     }
 
+    /// Prompt for the selected macro's variables, or run it if it has none.
+    fn collect_macro_vars(&mut self) {
+        if self.required_var_infos.is_empty() {
+            // No vars needed, execute immediately
+            self.execute_macro();
+        } else {
+            self.prompt_var(0);
+        }
+    }
+
+    /// Run the macro awaiting confirmation if the user typed `yes`.
+    fn confirm_trust(&mut self) {
+        let confirmed = self.input_buffer.trim() == "yes";
+        self.input_buffer.clear();
+        self.trust_commands.clear();
+        if !confirmed {
+            self.status = Some(StatusMessage {
+                text: "Macro not run: shell commands were not confirmed".to_string(),
+                is_error: true,
+            });
+            self.required_var_infos.clear();
+            self.mode = Mode::Result;
+            return;
+        }
+        self.macro_trusted = true;
+        self.collect_macro_vars();
+    }
+
     /// Try to resolve template output path from frontmatter.
     fn resolve_template_output(&self, name: &str) -> Result<Option<PathBuf>, String> {
         use mdvault_core::templates::engine::render_string;
@@ -436,23 +1282,37 @@ impl App {
             Mode::Input { var_index } => {
                 let var_index = *var_index;
                 if var_index < self.required_var_infos.len() {
-                    let var_name = self.required_var_infos[var_index].name.clone();
-                    self.var_values.insert(var_name, self.input_buffer.clone());
+                    let value = match &self.picker {
+                        Some(picker) if picker.is_multi() => picker.chosen_value(),
+                        Some(picker) => match picker.value() {
+                            Some(value) => value.to_string(),
+                            // Nothing matches the typed filter
+                            None => return,
+                        },
+                        None => self.input_buffer.clone(),
+                    };
+                    let info = &self.required_var_infos[var_index];
+                    let value = match info.prompt_type.parse_answer(&value) {
+                        // Keep asking until the answer fits
+                        Err(e) => {
+                            self.status = Some(StatusMessage { text: e, is_error: true });
+                            return;
+                        }
+                        Ok(parsed) if parsed.is_empty() => value,
+                        Ok(parsed) => parsed,
+                    };
+                    self.status = None;
+                    let var_name = info.name.clone();
+                    self.var_values.insert(var_name, value);
 
                     if var_index + 1 < self.required_var_infos.len() {
-                        // Pre-fill next input with default if available
-                        if let Some(default) =
-                            &self.required_var_infos[var_index + 1].default
-                        {
-                            self.input_buffer = default.clone();
-                        } else {
-                            self.input_buffer.clear();
-                        }
                         // More vars to collect
-                        self.mode = Mode::Input { var_index: var_index + 1 };
+                        self.prompt_var(var_index + 1);
                     } else {
                         // All vars collected, execute based on item type
                         self.input_buffer.clear();
+                        self.picker = None;
+                        self.multiline = false;
                         match &self.items[self.selected] {
                             // Templates need output path resolution after vars
                             PaletteItem::Template(_) => self.proceed_to_template_output(),
@@ -462,10 +1322,338 @@ impl App {
                     }
                 }
             }
+            Mode::Prompt(NotePrompt::CaptureInto) => self.start_capture_into(),
+            Mode::Prompt(NotePrompt::Rename) => self.rename_selected_note(),
+            Mode::Prompt(NotePrompt::NewTask) => self.request_task(),
+            Mode::Prompt(NotePrompt::ProjectLog) => self.log_to_project(),
+            Mode::Prompt(NotePrompt::FocusLog) => self.log_focus_session(),
+            Mode::Prompt(NotePrompt::AssignProject) => self.assign_inbox_note(),
+            Mode::Prompt(NotePrompt::Schedule) => self.schedule_inbox_note(),
+            Mode::ConfirmTrust => self.confirm_trust(),
             _ => {}
         }
     }
 
+    /// Prompt for the variable at `var_index`: a picker over its options,
+    /// the indexed notes it references, or yes/no (several options at once
+    /// for a multi-select), else a text field pre-filled with its default
+    /// (multi-line if the schema says so or the default has line breaks).
+    fn prompt_var(&mut self, var_index: usize) {
+        let info = &self.required_var_infos[var_index];
+        let default = info.default.as_deref();
+        let options = match &info.prompt_type {
+            PromptType::Select(options) => options.clone(),
+            PromptType::Boolean => vec!["true".to_string(), "false".to_string()],
+            PromptType::Reference(note_type) => {
+                super::actions::note_choices(&self.config, note_type)
+            }
+            PromptType::ProjectSelector => {
+                super::actions::note_choices(&self.config, "project")
+            }
+            _ => Vec::new(),
+        };
+        self.input_buffer.clear();
+        self.picker = match &info.prompt_type {
+            PromptType::MultiSelect(options) => {
+                let defaults: Vec<&str> = default
+                    .map(|d| d.split(',').map(str::trim).collect())
+                    .unwrap_or_default();
+                Some(Picker::multi(options.clone(), &defaults))
+            }
+            _ if !options.is_empty() => {
+                let default = default.and_then(|d| info.prompt_type.parse_answer(d).ok());
+                Some(Picker::new(options, default.as_deref()))
+            }
+            _ => {
+                self.input_buffer = default.unwrap_or_default().to_string();
+                None
+            }
+        };
+        self.multiline = self.picker.is_none()
+            && (info.prompt_type == PromptType::Multiline
+                || self.input_buffer.contains('\n'));
+        self.mode = Mode::Input { var_index };
+    }
+
+    /// How to ask for a variable: from its own `options`, else from its
+    /// field in the typedef schema (see [`PromptType::for_field`]). A
+    /// `project` variable picks from the indexed projects.
+    fn var_prompt_type(
+        name: &str,
+        spec: Option<&VarSpec>,
+        field: Option<&FieldSchema>,
+    ) -> PromptType {
+        if let Some(options) = spec.and_then(VarSpec::options) {
+            return PromptType::Select(options.to_vec());
+        }
+        match field.map(PromptType::for_field) {
+            Some(PromptType::Text) | None if name == "project" => {
+                PromptType::ProjectSelector
+            }
+            Some(prompt_type) => prompt_type,
+            None => PromptType::Text,
+        }
+    }
+
+    /// Run the capture named in the input buffer into the selected note.
+    fn start_capture_into(&mut self) {
+        let Some(note) = self.selected_note_path().map(Path::to_path_buf) else {
+            return;
+        };
+        let name = self.input_buffer.trim();
+        let Some(index) = (self.captures_start_index..self.macros_start_index)
+            .find(|&i| self.items[i].name() == name)
+        else {
+            self.status = Some(StatusMessage {
+                text: format!("Unknown capture: {name}"),
+                is_error: true,
+            });
+            self.mode = Mode::Result;
+            return;
+        };
+        self.capture_target = Some(note);
+        self.selected = index;
+        self.input_buffer.clear();
+        self.start_execution();
+    }
+
+    /// Rename the selected note to the path in the input buffer.
+    fn rename_selected_note(&mut self) {
+        let Some(note) = self.browser.selected_note() else {
+            return;
+        };
+        let from = note.path.clone();
+        let to = PathBuf::from(self.input_buffer.trim());
+        self.input_buffer.clear();
+        if to.as_os_str().is_empty() || to == from {
+            self.mode = Mode::Browse;
+            return;
+        }
+
+        let result = super::actions::rename_note(&self.config, &from, &to);
+        if result.is_ok() {
+            self.reload_notes();
+            self.browser.select_path(&to);
+        }
+        self.finish_note_action(result);
+    }
+
+    /// Create a task titled after the input buffer in the selected project,
+    /// once the terminal is released.
+    fn request_task(&mut self) {
+        let title = self.input_buffer.trim().to_string();
+        self.input_buffer.clear();
+        self.mode = Mode::Browse;
+        if let Some(project) = self.projects.selected_project()
+            && !title.is_empty()
+        {
+            self.task_request = Some((project.id.clone(), title));
+        }
+    }
+
+    /// Append the input buffer to the selected project's log.
+    fn log_to_project(&mut self) {
+        let message = self.input_buffer.trim().to_string();
+        self.input_buffer.clear();
+        let Some(project) = self.projects.selected_project() else {
+            return;
+        };
+        if message.is_empty() {
+            self.mode = Mode::Browse;
+            return;
+        }
+        let result = super::actions::log_to_project(&self.config, &project.id, &message);
+        self.finish_note_action(result);
+    }
+
+    /// End the focus session and offer to log it, pre-filled with its
+    /// length, to today's daily note.
+    fn stop_focus(&mut self) {
+        let stopped = match super::actions::stop_focus(&self.config) {
+            Ok(Some(focus)) => focus,
+            Ok(None) => {
+                self.finish_note_action(Err("No active focus".to_string()));
+                return;
+            }
+            Err(e) => {
+                self.finish_note_action(Err(e));
+                return;
+            }
+        };
+        self.focus = None;
+        self.projects.invalidate();
+        let spent = super::focus::elapsed(&stopped, Local::now());
+        self.input_buffer = format!(
+            "Focused on [[{}]] for {}",
+            stopped.project,
+            super::focus::duration(spent)
+        );
+        self.mode = Mode::Prompt(NotePrompt::FocusLog);
+    }
+
+    /// Log the entry for a finished focus session to today's daily note.
+    fn log_focus_session(&mut self) {
+        let message = self.input_buffer.trim().to_string();
+        self.input_buffer.clear();
+        if message.is_empty() {
+            self.mode = Mode::Browse;
+            return;
+        }
+        let result = super::actions::log_to_daily(&self.config, &message);
+        self.calendar.invalidate();
+        self.finish_note_action(result);
+    }
+
+    /// File the inbox note under the project chosen in the picker.
+    fn assign_inbox_note(&mut self) {
+        let Some(project) =
+            self.picker.as_ref().and_then(|p| p.value()).map(str::to_string)
+        else {
+            // Nothing matches the typed filter
+            return;
+        };
+        self.input_buffer.clear();
+        self.picker = None;
+        let Some(note) = self.inbox.current_note().map(Path::to_path_buf) else {
+            self.mode = Mode::Browse;
+            return;
+        };
+        let result = super::actions::assign_note(&self.config, &note, &project);
+        self.finish_triage(result);
+    }
+
+    /// Give the inbox note the due date in the input buffer.
+    fn schedule_inbox_note(&mut self) {
+        let when = self.input_buffer.trim().to_string();
+        self.input_buffer.clear();
+        let Some(note) = self.inbox.current_note().map(Path::to_path_buf) else {
+            self.mode = Mode::Browse;
+            return;
+        };
+        if when.is_empty() {
+            self.mode = Mode::Browse;
+            return;
+        }
+        let result = super::actions::schedule_note(&self.config, &note, &when);
+        self.finish_triage(result);
+    }
+
+    /// Archive or trash the inbox note.
+    fn file_away(&mut self, action: ExpireAction) {
+        if let Some(note) = self.inbox.current_note().map(Path::to_path_buf) {
+            let result = super::actions::file_away(&self.config, &note, action);
+            self.finish_triage(result);
+        }
+    }
+
+    /// After a triage action, move on to the next inbox note on success
+    /// (reporting in the status bar) or show the error.
+    fn finish_triage(&mut self, result: Result<String, String>) {
+        match result {
+            Ok(text) => {
+                self.inbox.filed_current();
+                self.reload_notes();
+                self.projects.invalidate();
+                self.status = Some(StatusMessage { text, is_error: false });
+                self.mode = Mode::Browse;
+                self.load_preview();
+            }
+            Err(e) => self.finish_note_action(Err(e)),
+        }
+    }
+
+    /// Show the outcome of a note action and refresh its preview.
+    fn finish_note_action(&mut self, result: Result<String, String>) {
+        let (text, is_error) = match result {
+            Ok(msg) => (msg, false),
+            Err(msg) => (msg, true),
+        };
+        self.status = Some(StatusMessage { text, is_error });
+        self.mode = Mode::Result;
+        self.load_preview();
+    }
+
+    /// Re-run the live search for the current query.
+    fn run_search(&mut self) {
+        let results = super::actions::search_notes(&self.config, &self.search.query);
+        self.search.set_results(results);
+        self.load_preview();
+    }
+
+    /// Load the graph around `path` at the current depth and apply it.
+    fn regraph(&mut self, path: &Path, apply: impl FnOnce(&mut LinkGraph, LinkGraph)) {
+        let depth = self.graph.as_ref().map_or(DEFAULT_DEPTH, |g| g.depth);
+        self.regraph_at(path, depth, apply);
+    }
+
+    fn regraph_at(
+        &mut self,
+        path: &Path,
+        depth: usize,
+        apply: impl FnOnce(&mut LinkGraph, LinkGraph),
+    ) {
+        match super::actions::load_graph(&self.config, path, depth) {
+            Ok(next) => {
+                if let Some(graph) = &mut self.graph {
+                    apply(graph, next);
+                }
+                self.status = None;
+            }
+            Err(e) => {
+                self.status = Some(StatusMessage { text: e, is_error: true });
+            }
+        }
+    }
+
+    /// The note selected on the start screen or in the notes or search pane,
+    /// the inbox note being triaged (or the graph, while exploring it),
+    /// relative to the vault.
+    pub fn selected_note_path(&self) -> Option<&Path> {
+        if let Some(graph) = self.graph.as_ref().filter(|_| self.mode == Mode::Graph) {
+            return Some(graph.selected_path());
+        }
+        match self.pane {
+            Pane::Palette | Pane::Calendar | Pane::Project => None,
+            Pane::Home => self.home.selected_path(),
+            Pane::Notes => self.browser.selected_note().map(|n| n.path.as_path()),
+            Pane::Search => self.search.selected_path(),
+            Pane::Inbox => self.inbox.current_note(),
+        }
+    }
+
+    /// Reload the notes browser from the index (and the start screen, next
+    /// time it is shown).
+    pub fn reload_notes(&mut self) {
+        self.home.invalidate();
+        match super::actions::load_notes(&self.config) {
+            Ok(notes) => {
+                self.browser.set_notes(notes);
+                self.browser_error = None;
+            }
+            Err(e) => self.browser_error = Some(e),
+        }
+    }
+
+    /// The note waiting to be opened in the editor, if any.
+    pub fn take_open_request(&mut self) -> Option<PathBuf> {
+        self.open_request.take()
+    }
+
+    /// The project and title of a task to create, if any.
+    pub fn take_task_request(&mut self) -> Option<(String, String)> {
+        self.task_request.take()
+    }
+
+    /// The day whose daily note should be opened, if any.
+    pub fn take_daily_request(&mut self) -> Option<NaiveDate> {
+        self.daily_request.take()
+    }
+
+    /// Path of the note a capture will write into, relative to the vault.
+    pub fn capture_target(&self) -> Option<&Path> {
+        self.capture_target.as_deref()
+    }
+
     /// Load capture and extract user-defined variables with metadata.
     fn load_capture_var_infos(&self, name: &str) -> Result<Vec<VarInfo>, String> {
         use mdvault_core::captures::CaptureRepository;
@@ -473,8 +1661,11 @@ impl App {
         let repo = CaptureRepository::new(&self.config.captures_dir)
             .map_err(|e| format!("Failed to load captures: {e}"))?;
 
-        let loaded =
+        let mut loaded =
             repo.get_by_name(name).map_err(|e| format!("Failed to load capture: {e}"))?;
+        if let Some(target) = &self.capture_target {
+            super::actions::retarget_capture(&mut loaded.spec, &self.config, target);
+        }
 
         // Get variable names from content/target/section
         let var_names = super::actions::extract_user_variables(&loaded.spec);
@@ -483,28 +1674,25 @@ impl App {
         let var_infos: Vec<VarInfo> = var_names
             .into_iter()
             .map(|name| {
-                let (prompt, description, default) =
-                    if let Some(vars_map) = &loaded.spec.vars {
-                        if let Some(var_spec) = vars_map.get(&name) {
-                            let prompt_text = var_spec.prompt();
-                            // Only use prompt if non-empty (simple form has prompt, full form might have empty)
-                            let prompt_opt = if prompt_text.is_empty() {
-                                None
-                            } else {
-                                Some(prompt_text.to_string())
-                            };
-                            (
-                                prompt_opt,
-                                var_spec.description().map(|s| s.to_string()),
-                                var_spec.default().map(|s| s.to_string()),
-                            )
-                        } else {
-                            (None, None, None)
-                        }
+                let var_spec = loaded.spec.vars.as_ref().and_then(|vars| vars.get(&name));
+                let (prompt, description, default) = if let Some(var_spec) = var_spec {
+                    let prompt_text = var_spec.prompt();
+                    // Only use prompt if non-empty (simple form has prompt, full form might have empty)
+                    let prompt_opt = if prompt_text.is_empty() {
+                        None
                     } else {
-                        (None, None, None)
+                        Some(prompt_text.to_string())
                     };
-                VarInfo { name, prompt, description, default }
+                    (
+                        prompt_opt,
+                        var_spec.description().map(|s| s.to_string()),
+                        var_spec.default().map(|s| s.to_string()),
+                    )
+                } else {
+                    (None, None, None)
+                };
+                let prompt_type = Self::var_prompt_type(&name, var_spec, None);
+                VarInfo { name, prompt, description, default, prompt_type }
             })
             .collect();
 
@@ -512,8 +1700,11 @@ impl App {
     }
 
     /// Load macro and extract user-defined variables with metadata.
-    /// Returns (var_infos, needs_trust).
-    fn load_macro_var_infos(&self, name: &str) -> Result<(Vec<VarInfo>, bool), String> {
+    /// Returns (var_infos, shell_commands).
+    fn load_macro_var_infos(
+        &self,
+        name: &str,
+    ) -> Result<(Vec<VarInfo>, Vec<String>), String> {
         use mdvault_core::macros::MacroRepository;
 
         let repo = MacroRepository::new(&self.config.macros_dir)
@@ -522,7 +1713,7 @@ impl App {
         let loaded =
             repo.get_by_name(name).map_err(|e| format!("Failed to load macro: {e}"))?;
 
-        let needs_trust = requires_trust(&loaded.spec);
+        let shell_commands = get_shell_commands(&loaded.spec);
 
         // Get variables from macro spec
         let var_infos: Vec<VarInfo> = if let Some(vars_map) = &loaded.spec.vars {
@@ -540,6 +1731,7 @@ impl App {
                         prompt: prompt_opt,
                         description: spec.description().map(|s| s.to_string()),
                         default: spec.default().map(|s| s.to_string()),
+                        prompt_type: Self::var_prompt_type(name, Some(spec), None),
                     }
                 })
                 .collect()
@@ -547,7 +1739,7 @@ impl App {
             Vec::new()
         };
 
-        Ok((var_infos, needs_trust))
+        Ok((var_infos, shell_commands))
     }
 
     /// Load template and extract user-defined variables with metadata.
@@ -563,11 +1755,18 @@ impl App {
         // Note: Template vars DSL was removed in v0.2.0. For variable metadata
         // (prompts, defaults), templates should use lua: field to reference a Lua typedef.
         let all_vars = collect_all_variables(None, &loaded.body);
+        // The typedef's variables and schema describe the body's variables
+        let typedef = super::actions::template_typedef(&self.config, &loaded);
 
         // Convert to VarInfo with metadata
         let var_infos: Vec<VarInfo> = all_vars
             .into_iter()
             .map(|(name, spec_opt)| {
+                let spec_opt = spec_opt.or_else(|| {
+                    typedef.as_ref().and_then(|td| td.variables.get(&name).cloned())
+                });
+                let field = typedef.as_ref().and_then(|td| td.schema.get(&name));
+                let prompt_type = Self::var_prompt_type(&name, spec_opt.as_ref(), field);
                 if let Some(spec) = spec_opt {
                     let prompt_text = spec.prompt();
                     let prompt_opt = if prompt_text.is_empty() {
@@ -580,10 +1779,17 @@ impl App {
                         prompt: prompt_opt,
                         description: spec.description().map(|s| s.to_string()),
                         default: spec.default().map(|s| s.to_string()),
+                        prompt_type: prompt_type.clone(),
                     }
                 } else {
                     // Variable found in content but not declared in frontmatter
-                    VarInfo { name, prompt: None, description: None, default: None }
+                    VarInfo {
+                        name,
+                        prompt: field.and_then(|f| f.prompt.clone()),
+                        description: field.and_then(|f| f.description.clone()),
+                        default: None,
+                        prompt_type,
+                    }
                 }
             })
             .collect();
@@ -606,21 +1812,20 @@ impl App {
             return;
         };
 
-        match super::actions::execute_template(
-            &self.config,
-            &info.logical_name,
-            &output_path,
-            &self.var_values,
-        ) {
-            Ok(msg) => {
-                self.status = Some(StatusMessage { text: msg, is_error: false });
-            }
-            Err(msg) => {
-                self.status = Some(StatusMessage { text: msg, is_error: true });
-            }
-        }
-        self.mode = Mode::Result;
-        self.input_buffer.clear();
+        let config = self.config.clone();
+        let name = info.logical_name.clone();
+        let vars = self.var_values.clone();
+        let what = format!("template '{name}'");
+        self.start_recorded_job(format!("Creating {name}"), what, move |_, recorder| {
+            super::actions::execute_template(
+                &config,
+                &name,
+                &output_path,
+                &vars,
+                recorder,
+            )
+            .map(|msg| (msg, Some(output_path)))
+        });
     }
 
     /// Execute capture insertion.
@@ -629,19 +1834,21 @@ impl App {
             return;
         };
 
-        match super::actions::execute_capture(
-            &self.config,
-            &info.logical_name,
-            &self.var_values,
-        ) {
-            Ok(msg) => {
-                self.status = Some(StatusMessage { text: msg, is_error: false });
-            }
-            Err(msg) => {
-                self.status = Some(StatusMessage { text: msg, is_error: true });
-            }
-        }
-        self.mode = Mode::Result;
+        let config = self.config.clone();
+        let name = info.logical_name.clone();
+        let vars = self.var_values.clone();
+        let target = self.capture_target.clone();
+        let what = format!("capture '{name}'");
+        self.start_recorded_job(format!("Capturing {name}"), what, move |_, recorder| {
+            super::actions::execute_capture(
+                &config,
+                &name,
+                &vars,
+                target.as_deref(),
+                recorder,
+            )
+            .map(|(msg, written)| (msg, Some(written)))
+        });
     }
 
     /// Execute macro workflow.
@@ -650,18 +1857,79 @@ impl App {
             return;
         };
 
-        match super::actions::execute_macro(
-            &self.config,
-            &info.logical_name,
-            &self.var_values,
-        ) {
-            Ok(msg) => {
-                self.status = Some(StatusMessage { text: msg, is_error: false });
-            }
-            Err(msg) => {
-                self.status = Some(StatusMessage { text: msg, is_error: true });
-            }
+        let trust = std::mem::take(&mut self.macro_trusted);
+        let config = self.config.clone();
+        let name = info.logical_name.clone();
+        let vars = self.var_values.clone();
+        let what = format!("macro '{name}'");
+        self.start_recorded_job(format!("Running {name}"), what, move |job, recorder| {
+            super::actions::execute_macro(&config, &name, &vars, trust, job, recorder)
+                .map(|msg| (msg, None))
+        });
+    }
+
+    /// Run `work` as a job whose writes are journaled as `what`, so they
+    /// can be undone from the result.
+    fn start_recorded_job(
+        &mut self,
+        label: String,
+        what: String,
+        work: impl FnOnce(&JobContext, &mut Recorder) -> JobResult + Send + 'static,
+    ) {
+        let mut recorder = Recorder::new(&self.config.vault_root);
+        self.undo_id = Some(recorder.id().to_string());
+        self.start_job(label, move |job| {
+            let result = work(job, &mut recorder);
+            super::actions::commit_undo(recorder, &what);
+            result
+        });
+    }
+
+    /// Run `work` on a worker thread; its result arrives as `JobDone`.
+    fn start_job(
+        &mut self,
+        label: String,
+        work: impl FnOnce(&JobContext) -> JobResult + Send + 'static,
+    ) {
+        self.input_buffer.clear();
+        self.job = Some(Job::spawn(label, work));
+        self.mode = Mode::Running;
+    }
+
+    /// The next message from the running job, if any.
+    pub fn poll_job(&self) -> Option<Message> {
+        self.job.as_ref().and_then(Job::poll)
+    }
+
+    /// Show the outcome of a finished job.
+    fn finish_job(&mut self, result: JobResult) {
+        self.job = None;
+        // Even a failed run may have written files before it stopped
+        self.undo_id =
+            self.undo_id.take().filter(|id| super::actions::can_undo(&self.config, id));
+        // A quick capture is done once written; errors stay up to be read
+        if self.quick_capture && result.is_ok() {
+            self.should_quit = true;
+            return;
         }
+        let result = result.map(|(msg, written)| {
+            // Report problems with the note right away, not at the next
+            // `mdv validate`
+            self.validation = written.as_deref().and_then(|path| {
+                super::actions::validate_file(&self.config, path).ok().flatten()
+            });
+            self.last_written = written;
+            msg
+        });
+        if self.capture_target.is_some() {
+            self.finish_note_action(result);
+            return;
+        }
+        let (text, is_error) = match result {
+            Ok(msg) => (msg, false),
+            Err(msg) => (msg, true),
+        };
+        self.status = Some(StatusMessage { text, is_error });
         self.mode = Mode::Result;
     }
 
@@ -669,6 +1937,17 @@ impl App {
     pub fn current_input_label(&self) -> Option<String> {
         match &self.mode {
             Mode::OutputPath => Some("Output path".to_string()),
+            Mode::Prompt(NotePrompt::CaptureInto) => Some("Capture name".to_string()),
+            Mode::Prompt(NotePrompt::Rename) => Some("New path".to_string()),
+            Mode::Prompt(NotePrompt::NewTask) => Some("Task title".to_string()),
+            Mode::Prompt(NotePrompt::ProjectLog) => Some("Log entry".to_string()),
+            Mode::Prompt(NotePrompt::FocusLog) => {
+                Some("Log entry (Esc to skip)".to_string())
+            }
+            Mode::Prompt(NotePrompt::AssignProject) => Some("Project".to_string()),
+            Mode::Prompt(NotePrompt::Schedule) => {
+                Some("Due (date or expression, e.g. friday)".to_string())
+            }
             Mode::Input { var_index } => {
                 self.required_var_infos.get(*var_index).map(|info| {
                     // Use prompt if available, otherwise variable name
@@ -0,0 +1,527 @@
+//! Board application state and update logic (Elm Architecture).
+
+use std::path::{Path, PathBuf};
+
+use mdvault_core::activity::ActivityLogService;
+use mdvault_core::config::types::ResolvedConfig;
+use mdvault_core::frontmatter::{parse, serialize};
+use mdvault_core::index::{IndexBuilder, IndexDb, IndexedNote, NoteQuery, NoteType};
+use mdvault_core::paths::PathResolver;
+use mdvault_core::types::{TypeRegistry, check_transition};
+
+use crate::cmd::common::load_types;
+
+/// A board column, in display order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Todo,
+    InProgress,
+    Blocked,
+    Done,
+}
+
+impl Column {
+    pub const ALL: [Column; 4] =
+        [Column::Todo, Column::InProgress, Column::Blocked, Column::Done];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Column::Todo => "Todo",
+            Column::InProgress => "In Progress",
+            Column::Blocked => "Blocked",
+            Column::Done => "Done",
+        }
+    }
+
+    /// The `status` value written when a task moves here.
+    pub fn status(self) -> &'static str {
+        match self {
+            Column::Todo => "todo",
+            Column::InProgress => "in-progress",
+            Column::Blocked => "blocked",
+            Column::Done => "done",
+        }
+    }
+
+    /// The column for a task `status`; `None` for cancelled tasks, which
+    /// the board leaves out. Unknown or missing statuses count as todo.
+    pub fn from_status(status: Option<&str>) -> Option<Column> {
+        let status = status.unwrap_or("todo").to_lowercase();
+        match status.as_str() {
+            "cancelled" | "canceled" => None,
+            "in-progress" | "in_progress" | "inprogress" | "doing" => {
+                Some(Column::InProgress)
+            }
+            "blocked" | "waiting" => Some(Column::Blocked),
+            "done" | "completed" => Some(Column::Done),
+            _ => Some(Column::Todo),
+        }
+    }
+
+    fn index(self) -> usize {
+        Column::ALL.iter().position(|c| *c == self).unwrap_or(0)
+    }
+}
+
+/// A task on the board.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Card {
+    /// Vault-relative path of the task note.
+    pub path: PathBuf,
+    pub id: String,
+    pub title: String,
+    /// Frontmatter `project`, or the folder under `Projects/`.
+    pub project: String,
+    pub column: Column,
+}
+
+impl Card {
+    /// A card for an indexed task; `None` if the task is cancelled.
+    pub fn from_note(note: &IndexedNote) -> Option<Card> {
+        let fm = note
+            .frontmatter_json
+            .as_deref()
+            .and_then(|fm| serde_json::from_str::<serde_json::Value>(fm).ok());
+        let field = |key: &str| {
+            fm.as_ref()
+                .and_then(|fm| fm.get(key))
+                .and_then(|v| v.as_str())
+                .map(String::from)
+        };
+        let column = Column::from_status(field("status").as_deref())?;
+        let stem = note.path.file_stem().map(|s| s.to_string_lossy().into_owned());
+
+        Some(Card {
+            id: field("task-id").or_else(|| stem.clone()).unwrap_or_default(),
+            title: if note.title.is_empty() {
+                stem.unwrap_or_default()
+            } else {
+                note.title.clone()
+            },
+            project: field("project")
+                .or_else(|| project_folder(&note.path))
+                .unwrap_or_else(|| "inbox".to_string()),
+            path: note.path.clone(),
+            column,
+        })
+    }
+
+    /// Whether the card belongs to `project` (an ID or folder name).
+    fn in_project(&self, project: &str) -> bool {
+        self.project.eq_ignore_ascii_case(project)
+            || mdvault_core::domain::task_belongs_to_project(
+                &self.path.to_string_lossy(),
+                project,
+            )
+    }
+}
+
+/// The folder under `Projects/` (or `Projects/_archive/`) holding a task.
+fn project_folder(path: &Path) -> Option<String> {
+    let parts: Vec<String> =
+        path.iter().map(|s| s.to_string_lossy().into_owned()).collect();
+    match parts.as_slice() {
+        [projects, archive, name, _, ..]
+            if projects == "Projects" && archive == "_archive" =>
+        {
+            Some(name.clone())
+        }
+        [projects, name, _, ..] if projects == "Projects" => Some(name.clone()),
+        _ => None,
+    }
+}
+
+/// Current operating mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// Normal browsing.
+    Browse,
+    /// Showing a status message (press any key to dismiss).
+    Status,
+}
+
+/// Messages that drive state updates.
+#[derive(Debug, Clone)]
+pub enum Message {
+    // Navigation
+    SelectNext,
+    SelectPrev,
+    ColumnNext,
+    ColumnPrev,
+
+    // Actions
+    MoveRight,
+    MoveLeft,
+    CycleProject,
+    Refresh,
+
+    // System
+    Quit,
+    DismissStatus,
+}
+
+/// Feedback message.
+pub struct StatusMessage {
+    pub text: String,
+    pub is_error: bool,
+}
+
+/// Main board application state.
+pub struct BoardApp {
+    /// Every task on the board, by ID.
+    pub cards: Vec<Card>,
+    /// Only show this project's tasks.
+    pub project: Option<String>,
+    /// Focused column.
+    pub column: Column,
+    /// Selected card within each column.
+    pub rows: [usize; 4],
+    pub mode: Mode,
+    pub status: Option<StatusMessage>,
+    pub should_quit: bool,
+
+    pub config: ResolvedConfig,
+}
+
+impl BoardApp {
+    pub fn new(
+        config: ResolvedConfig,
+        notes: &[IndexedNote],
+        project: Option<String>,
+    ) -> Self {
+        let mut app = Self {
+            cards: Vec::new(),
+            project,
+            column: Column::Todo,
+            rows: [0; 4],
+            mode: Mode::Browse,
+            status: None,
+            should_quit: false,
+            config,
+        };
+        app.set_cards(notes);
+        app
+    }
+
+    fn set_cards(&mut self, notes: &[IndexedNote]) {
+        self.cards = notes.iter().filter_map(Card::from_note).collect();
+        self.cards.sort_by(|a, b| a.id.cmp(&b.id).then(a.path.cmp(&b.path)));
+        for column in Column::ALL {
+            let len = self.column_cards(column).len();
+            let row = &mut self.rows[column.index()];
+            *row = (*row).min(len.saturating_sub(1));
+        }
+    }
+
+    /// The cards shown in `column`, after the project filter.
+    pub fn column_cards(&self, column: Column) -> Vec<&Card> {
+        self.cards
+            .iter()
+            .filter(|c| c.column == column)
+            .filter(|c| self.project.as_deref().is_none_or(|p| c.in_project(p)))
+            .collect()
+    }
+
+    /// Projects with tasks on the board, sorted.
+    pub fn projects(&self) -> Vec<String> {
+        let mut projects: Vec<String> =
+            self.cards.iter().map(|c| c.project.clone()).collect();
+        projects.sort_by_key(|p| p.to_lowercase());
+        projects.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        projects
+    }
+
+    /// The selected card in the focused column.
+    pub fn selected_card(&self) -> Option<&Card> {
+        self.column_cards(self.column).get(self.rows[self.column.index()]).copied()
+    }
+
+    /// Process a message and update state.
+    pub fn update(&mut self, msg: Message) {
+        match msg {
+            Message::SelectNext => {
+                let len = self.column_cards(self.column).len();
+                let row = &mut self.rows[self.column.index()];
+                if *row + 1 < len {
+                    *row += 1;
+                }
+            }
+            Message::SelectPrev => {
+                let row = &mut self.rows[self.column.index()];
+                *row = row.saturating_sub(1);
+            }
+            Message::ColumnNext => {
+                self.column = Column::ALL[(self.column.index() + 1).min(3)];
+            }
+            Message::ColumnPrev => {
+                self.column = Column::ALL[self.column.index().saturating_sub(1)];
+            }
+            Message::MoveRight => {
+                if self.column != Column::Done {
+                    self.move_selected(Column::ALL[self.column.index() + 1]);
+                }
+            }
+            Message::MoveLeft => {
+                if self.column != Column::Todo {
+                    self.move_selected(Column::ALL[self.column.index() - 1]);
+                }
+            }
+            Message::CycleProject => {
+                let projects = self.projects();
+                self.project = match &self.project {
+                    None => projects.first().cloned(),
+                    Some(current) => projects
+                        .iter()
+                        .position(|p| p.eq_ignore_ascii_case(current))
+                        .and_then(|i| projects.get(i + 1).cloned()),
+                };
+                self.rows = [0; 4];
+            }
+            Message::Refresh => match self.reload() {
+                Ok(()) => self.show("Refreshed".to_string(), false),
+                Err(e) => self.show(e, true),
+            },
+            Message::Quit => {
+                self.should_quit = true;
+            }
+            Message::DismissStatus => {
+                self.status = None;
+                self.mode = Mode::Browse;
+            }
+        }
+    }
+
+    fn show(&mut self, text: String, is_error: bool) {
+        self.status = Some(StatusMessage { text, is_error });
+        self.mode = Mode::Status;
+    }
+
+    /// Move the selected card to `target`, following it there.
+    fn move_selected(&mut self, target: Column) {
+        let Some(card) = self.selected_card().cloned() else {
+            return;
+        };
+        if let Err(e) = move_task(&self.config, &card, target) {
+            self.show(e, true);
+            return;
+        }
+        if let Err(e) = self.reload() {
+            self.show(e, true);
+            return;
+        }
+
+        self.column = target;
+        if let Some(row) =
+            self.column_cards(target).iter().position(|c| c.path == card.path)
+        {
+            self.rows[target.index()] = row;
+        }
+    }
+
+    /// Reload the tasks from the index.
+    fn reload(&mut self) -> Result<(), String> {
+        let index_path = PathResolver::new(&self.config.vault_root).index_db();
+        let db = IndexDb::open_read_only(&index_path)
+            .map_err(|e| format!("Index error: {e}"))?;
+        let notes = load_tasks(&db)?;
+        self.set_cards(&notes);
+        Ok(())
+    }
+}
+
+/// Every indexed task note.
+pub fn load_tasks(db: &IndexDb) -> Result<Vec<IndexedNote>, String> {
+    let query = NoteQuery { note_type: Some(NoteType::Task), ..Default::default() };
+    db.query_notes(&query).map_err(|e| format!("Failed to query tasks: {e}"))
+}
+
+/// Set a task's status for `column` in its frontmatter and reindex it.
+///
+/// Moving to done stamps `completed_at` and logs the completion; moving
+/// out of done clears the stamp. Moves the task type's `transitions` do not
+/// allow are refused.
+fn move_task(config: &ResolvedConfig, card: &Card, column: Column) -> Result<(), String> {
+    let full_path = config.vault_root.join(&card.path);
+    let types = load_types(config);
+    write_status(&full_path, column, &types)?;
+
+    let index_path = PathResolver::new(&config.vault_root).index_db();
+    let db = IndexDb::open(&index_path).map_err(|e| format!("Index error: {e}"))?;
+    IndexBuilder::new(&db, &config.vault_root)
+        .reindex_file(&card.path)
+        .map_err(|e| format!("Failed to update index: {e}"))?;
+
+    if column == Column::Done
+        && let Some(activity) = ActivityLogService::try_from_config(config)
+    {
+        let _ = activity.log_complete("task", &card.id, &full_path, None);
+    }
+    Ok(())
+}
+
+/// Rewrite the `status` of the task note at `path`.
+fn write_status(path: &Path, column: Column, types: &TypeRegistry) -> Result<(), String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let mut doc = parse(&content).map_err(|e| format!("Failed to parse task: {e}"))?;
+    let Some(fm) = doc.frontmatter.as_mut() else {
+        return Err(format!("Task has no frontmatter: {}", path.display()));
+    };
+    if let Some(from) = fm.fields.get("status").and_then(|v| v.as_str()) {
+        check_transition(types, "task", "status", from, column.status())
+            .map_err(|e| format!("Cannot move task: {e}"))?;
+    }
+
+    let string = |s: String| serde_yaml::Value::String(s);
+    fm.fields.insert("status".to_string(), string(column.status().to_string()));
+    let now = chrono::Local::now();
+    fm.fields.insert(
+        "updated_at".to_string(),
+        string(now.format("%Y-%m-%d %H:%M:%S").to_string()),
+    );
+    if column == Column::Done {
+        fm.fields.insert(
+            "completed_at".to_string(),
+            string(chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S").to_string()),
+        );
+    } else {
+        fm.fields.remove("completed_at");
+    }
+
+    std::fs::write(path, serialize(&doc))
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn task(path: &str, frontmatter: &str) -> IndexedNote {
+        IndexedNote {
+            id: None,
+            path: PathBuf::from(path),
+            note_type: NoteType::Task,
+            title: String::new(),
+            created: None,
+            modified: Utc::now(),
+            frontmatter_json: Some(frontmatter.to_string()),
+            content_hash: String::new(),
+            word_count: 0,
+            heading_json: None,
+        }
+    }
+
+    fn config(dir: &Path) -> ResolvedConfig {
+        let path = dir.join("config.toml");
+        std::fs::write(
+            &path,
+            format!(
+                "version = 1\nprofile = \"t\"\n\n[profiles.t]\nvault_root = \"{0}\"\n\
+                 templates_dir = \"{0}\"\ncaptures_dir = \"{0}\"\nmacros_dir = \"{0}\"\n",
+                dir.display()
+            ),
+        )
+        .unwrap();
+        mdvault_core::config::loader::ConfigLoader::load(Some(&path), None).unwrap()
+    }
+
+    fn board() -> BoardApp {
+        let dir = tempfile::tempdir().unwrap();
+        let notes = [
+            task(
+                "Projects/MCP/Tasks/MCP-002.md",
+                r#"{"task-id": "MCP-002", "project": "MCP"}"#,
+            ),
+            task(
+                "Projects/MCP/Tasks/MCP-001.md",
+                r#"{"task-id": "MCP-001", "status": "in_progress"}"#,
+            ),
+            task("Projects/web/Tasks/WEB-001.md", r#"{"status": "waiting"}"#),
+            task("Inbox/call.md", r#"{"status": "done"}"#),
+            task("Inbox/old.md", r#"{"status": "cancelled"}"#),
+        ];
+        BoardApp::new(config(dir.path()), &notes, None)
+    }
+
+    fn ids(app: &BoardApp, column: Column) -> Vec<&str> {
+        app.column_cards(column).iter().map(|c| c.id.as_str()).collect()
+    }
+
+    #[test]
+    fn statuses_map_to_columns() {
+        let app = board();
+        assert_eq!(ids(&app, Column::Todo), ["MCP-002"]);
+        assert_eq!(ids(&app, Column::InProgress), ["MCP-001"]);
+        assert_eq!(ids(&app, Column::Blocked), ["WEB-001"]);
+        assert_eq!(ids(&app, Column::Done), ["call"]);
+        assert_eq!(app.cards.len(), 4, "cancelled tasks are left out");
+        assert_eq!(app.cards[1].project, "MCP");
+        assert_eq!(app.projects(), ["inbox", "MCP", "web"]);
+    }
+
+    #[test]
+    fn project_filter_and_navigation() {
+        let mut app = board();
+        app.update(Message::CycleProject);
+        assert_eq!(app.project.as_deref(), Some("inbox"));
+        assert_eq!(ids(&app, Column::Done), ["call"]);
+        app.update(Message::CycleProject);
+        assert_eq!(ids(&app, Column::Todo), ["MCP-002"]);
+        assert!(ids(&app, Column::Blocked).is_empty());
+        app.update(Message::CycleProject);
+        app.update(Message::CycleProject);
+        assert_eq!(app.project, None);
+
+        app.update(Message::ColumnNext);
+        app.update(Message::ColumnNext);
+        assert_eq!(app.selected_card().unwrap().id, "WEB-001");
+        app.update(Message::ColumnPrev);
+        app.update(Message::ColumnPrev);
+        app.update(Message::ColumnPrev);
+        assert_eq!(app.column, Column::Todo);
+    }
+
+    #[test]
+    fn write_status_updates_frontmatter() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("task.md");
+        std::fs::write(&path, "---\ntype: task\nstatus: todo\n---\n# Task\n").unwrap();
+
+        write_status(&path, Column::Done, &TypeRegistry::new()).unwrap();
+        let doc = parse(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let fm = doc.frontmatter.unwrap().fields;
+        assert_eq!(fm["status"], "done");
+        assert!(fm.contains_key("completed_at"));
+        assert!(doc.body.contains("# Task"));
+
+        write_status(&path, Column::Blocked, &TypeRegistry::new()).unwrap();
+        let doc = parse(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let fm = doc.frontmatter.unwrap().fields;
+        assert_eq!(fm["status"], "blocked");
+        assert!(!fm.contains_key("completed_at"));
+    }
+
+    #[test]
+    fn write_status_refuses_illegal_transition() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("task.md");
+        std::fs::write(&path, "---\ntype: task\nstatus: todo\n---\n# Task\n").unwrap();
+
+        let mut task = mdvault_core::types::TypeDefinition::empty("task");
+        task.schema.insert(
+            "status".to_string(),
+            mdvault_core::types::FieldSchema {
+                transitions: Some(vec!["todo -> in-progress -> done".to_string()]),
+                ..Default::default()
+            },
+        );
+        let mut types = TypeRegistry::new();
+        types.register(task).unwrap();
+
+        let err = write_status(&path, Column::Done, &types).unwrap_err();
+        assert!(err.contains("'todo' -> 'done'"), "{err}");
+        assert!(std::fs::read_to_string(&path).unwrap().contains("status: todo"));
+        write_status(&path, Column::InProgress, &types).unwrap();
+    }
+}
@@ -0,0 +1,55 @@
+//! Key event mapping for the task board.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::app::{BoardApp, Message, Mode};
+
+/// Map a key event to an optional message based on current mode.
+pub fn map_key_event(app: &BoardApp, key: KeyEvent) -> Option<Message> {
+    // Global: Ctrl+C always quits
+    if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+        return Some(Message::Quit);
+    }
+
+    match &app.mode {
+        Mode::Browse => map_browse_keys(key),
+        Mode::Status => map_status_keys(key),
+    }
+}
+
+fn map_browse_keys(key: KeyEvent) -> Option<Message> {
+    let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+    match key.code {
+        // Moving cards
+        KeyCode::Char('L') | KeyCode::Char('>') => Some(Message::MoveRight),
+        KeyCode::Char('H') | KeyCode::Char('<') => Some(Message::MoveLeft),
+        KeyCode::Right if shift => Some(Message::MoveRight),
+        KeyCode::Left if shift => Some(Message::MoveLeft),
+
+        // Navigation
+        KeyCode::Char('j') | KeyCode::Down => Some(Message::SelectNext),
+        KeyCode::Char('k') | KeyCode::Up => Some(Message::SelectPrev),
+        KeyCode::Char('l') | KeyCode::Right | KeyCode::Tab => Some(Message::ColumnNext),
+        KeyCode::Char('h') | KeyCode::Left | KeyCode::BackTab => {
+            Some(Message::ColumnPrev)
+        }
+
+        // Actions
+        KeyCode::Char('p') => Some(Message::CycleProject),
+        KeyCode::Char('r') => Some(Message::Refresh),
+
+        // Quit
+        KeyCode::Char('q') | KeyCode::Esc => Some(Message::Quit),
+
+        _ => None,
+    }
+}
+
+fn map_status_keys(key: KeyEvent) -> Option<Message> {
+    match key.code {
+        KeyCode::Enter | KeyCode::Esc | KeyCode::Char(' ') | KeyCode::Char('q') => {
+            Some(Message::DismissStatus)
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,122 @@
+//! Kanban board TUI for tasks.
+//!
+//! Launched via `mdv board`. Lays indexed tasks out in todo, in-progress,
+//! blocked and done columns; moving a card rewrites the task's `status`
+//! and reindexes the file.
+
+mod app;
+mod event;
+mod ui;
+
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use color_eyre::eyre::Result;
+use crossterm::{
+    event::{Event, poll, read},
+    execute,
+    terminal::{
+        EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+    },
+};
+use ratatui::prelude::*;
+
+use mdvault_core::config::loader::ConfigLoader;
+use mdvault_core::context::ContextManager;
+use mdvault_core::index::IndexDb;
+use mdvault_core::paths::PathResolver;
+
+use app::{BoardApp, load_tasks};
+use event::map_key_event;
+
+/// Run the interactive task board.
+///
+/// With `focus`, the board starts on the focused project (see `mdv focus`).
+pub fn run(
+    config_path: Option<&Path>,
+    profile: Option<&str>,
+    project: Option<&str>,
+    focus: bool,
+) -> Result<()> {
+    let cfg = ConfigLoader::load(config_path, profile).map_err(|e| {
+        color_eyre::eyre::eyre!("Configuration error: {e}\nRun 'mdv doctor' to diagnose.")
+    })?;
+
+    let project = if focus {
+        let context = ContextManager::load(&cfg.vault_root)
+            .map_err(|e| color_eyre::eyre::eyre!("Failed to load focus: {e}"))?;
+        let Some(active) = context.active_project() else {
+            return Err(color_eyre::eyre::eyre!(
+                "No active focus. Set one with 'mdv focus <project>'."
+            ));
+        };
+        Some(active.to_string())
+    } else {
+        project.map(String::from)
+    };
+
+    let index_path = PathResolver::new(&cfg.vault_root).index_db();
+    let db = IndexDb::open_read_only(&index_path).map_err(|e| {
+        color_eyre::eyre::eyre!("Failed to open index: {e}\nRun 'mdv reindex' first.")
+    })?;
+    let notes = load_tasks(&db).map_err(|e| color_eyre::eyre::eyre!("{e}"))?;
+    drop(db);
+
+    let app = BoardApp::new(cfg, &notes, project);
+
+    let mut terminal = setup_terminal()?;
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        run_app(&mut terminal, app)
+    }));
+
+    restore_terminal(&mut terminal)?;
+
+    match result {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(e),
+        Err(_) => Err(color_eyre::eyre::eyre!("Board panicked")),
+    }
+}
+
+fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let terminal = Terminal::new(backend)?;
+    Ok(terminal)
+}
+
+fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    Ok(())
+}
+
+fn run_app(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    mut app: BoardApp,
+) -> Result<()> {
+    loop {
+        terminal.draw(|frame| {
+            ui::draw(frame, &app);
+            if crate::display::accessible() {
+                super::make_accessible(frame.buffer_mut());
+            }
+        })?;
+
+        if poll(Duration::from_millis(100))?
+            && let Event::Key(key) = read()?
+            && let Some(msg) = map_key_event(&app, key)
+        {
+            app.update(msg);
+        }
+
+        if app.should_quit {
+            return Ok(());
+        }
+    }
+}
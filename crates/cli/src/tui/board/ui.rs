@@ -0,0 +1,140 @@
+//! Task board rendering.
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, ListItem, Paragraph},
+};
+
+use super::app::{BoardApp, Column};
+use crate::tui::ui::list;
+
+/// Draw the entire board.
+pub fn draw(frame: &mut Frame, app: &BoardApp) {
+    let main_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Header
+            Constraint::Min(5),    // Columns
+            Constraint::Length(2), // Status / keybindings
+        ])
+        .split(frame.area());
+
+    draw_header(frame, main_chunks[0], app);
+    draw_columns(frame, main_chunks[1], app);
+    draw_status(frame, main_chunks[2], app);
+}
+
+fn draw_header(frame: &mut Frame, area: Rect, app: &BoardApp) {
+    let scope = match &app.project {
+        Some(project) => format!("Project {project}"),
+        None => "All projects".to_string(),
+    };
+
+    let line = Line::from(vec![
+        Span::styled(" mdv board ", Style::default().fg(Color::Cyan).bold()),
+        Span::styled("| ", Style::default().fg(Color::DarkGray)),
+        Span::styled(scope, Style::default().fg(Color::White)),
+    ]);
+
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+fn column_color(column: Column) -> Color {
+    match column {
+        Column::Todo => Color::Blue,
+        Column::InProgress => Color::Yellow,
+        Column::Blocked => Color::Red,
+        Column::Done => Color::Green,
+    }
+}
+
+fn draw_columns(frame: &mut Frame, area: Rect, app: &BoardApp) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 4); 4])
+        .split(area);
+
+    for (i, column) in Column::ALL.into_iter().enumerate() {
+        draw_column(frame, chunks[i], app, column, i);
+    }
+}
+
+fn draw_column(frame: &mut Frame, area: Rect, app: &BoardApp, column: Column, i: usize) {
+    let cards = app.column_cards(column);
+    let focused = app.column == column;
+    let selected = focused.then_some(app.rows[i]);
+
+    let items: Vec<ListItem> = cards
+        .iter()
+        .enumerate()
+        .map(|(row, card)| {
+            let is_selected = selected == Some(row);
+            let style = if is_selected {
+                Style::default().bg(Color::DarkGray).fg(Color::White)
+            } else {
+                Style::default()
+            };
+            let prefix = if is_selected { " > " } else { "   " };
+            let mut lines = vec![Line::from(vec![
+                Span::raw(prefix),
+                Span::styled(&card.id, Style::default().fg(Color::Cyan)),
+            ])];
+            lines.push(Line::from(format!("   {}", card.title)));
+            if app.project.is_none() {
+                lines.push(Line::from(Span::styled(
+                    format!("   {}", card.project),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+            ListItem::new(lines).style(style)
+        })
+        .collect();
+
+    let border = if focused { column_color(column) } else { Color::DarkGray };
+    let title = Line::from(vec![
+        Span::styled(
+            format!(" {} ", column.title()),
+            Style::default().fg(column_color(column)).bold(),
+        ),
+        Span::styled(format!("{} ", cards.len()), Style::default().fg(Color::DarkGray)),
+    ]);
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(border));
+    list::draw(frame, area, block, items, selected, " (empty)");
+}
+
+fn draw_status(frame: &mut Frame, area: Rect, app: &BoardApp) {
+    let (left_text, right_content) = if let Some(status) = &app.status {
+        let style = if status.is_error {
+            Style::default().fg(Color::Red)
+        } else {
+            Style::default().fg(Color::Green)
+        };
+        (" [Enter] dismiss", Span::styled(&status.text, style))
+    } else {
+        (
+            " [h/l] column  [j/k] task  [H/L] move  [p] project  [r] refresh  [q] quit",
+            Span::styled("Ready", Style::default().fg(Color::DarkGray)),
+        )
+    };
+
+    let left = Span::styled(left_text, Style::default().fg(Color::DarkGray));
+    let right_len = match &app.status {
+        Some(s) => s.text.len(),
+        None => 5, // "Ready"
+    };
+    let padding =
+        area.width.saturating_sub(left_text.len() as u16 + right_len as u16 + 2) as usize;
+
+    let line = Line::from(vec![left, Span::raw(" ".repeat(padding)), right_content]);
+
+    let paragraph = Paragraph::new(line).block(
+        Block::default()
+            .borders(Borders::TOP)
+            .border_style(Style::default().fg(Color::DarkGray)),
+    );
+
+    frame.render_widget(paragraph, area);
+}
@@ -0,0 +1,321 @@
+//! Notes browser state: the indexed notes, narrowed by type and a fuzzy
+//! filter, laid out as a directory tree or a flat list.
+
+use std::path::Path;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use mdvault_core::index::{IndexedNote, NoteType};
+use serde::{Deserialize, Serialize};
+
+/// Type filter choices, in cycling order.
+const NOTE_TYPES: [NoteType; 6] = [
+    NoteType::Daily,
+    NoteType::Weekly,
+    NoteType::Task,
+    NoteType::Project,
+    NoteType::Zettel,
+    NoteType::None,
+];
+
+/// How the browser lays out notes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BrowserView {
+    /// Grouped under their folders, by path.
+    Tree,
+    /// One line per note; best fuzzy matches first while filtering.
+    Flat,
+}
+
+/// A line of the browser list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BrowserRow {
+    /// A folder heading (tree view only).
+    Dir { name: String, depth: usize },
+    /// The `index`-th visible note.
+    Note { index: usize, depth: usize },
+}
+
+/// Notes browser state.
+pub struct NoteBrowser {
+    notes: Vec<IndexedNote>,
+    /// Indices into `notes` that pass the filters, in display order.
+    visible: Vec<usize>,
+    pub view: BrowserView,
+    pub type_filter: Option<NoteType>,
+    pub query: String,
+    /// Index into the visible notes.
+    pub selected: usize,
+}
+
+impl NoteBrowser {
+    pub fn new(notes: Vec<IndexedNote>) -> Self {
+        let mut browser = Self {
+            notes: Vec::new(),
+            visible: Vec::new(),
+            view: BrowserView::Tree,
+            type_filter: None,
+            query: String::new(),
+            selected: 0,
+        };
+        browser.set_notes(notes);
+        browser
+    }
+
+    /// Replace the notes, keeping the selection on the same path if it
+    /// is still there.
+    pub fn set_notes(&mut self, mut notes: Vec<IndexedNote>) {
+        let current = self.selected_note().map(|n| n.path.clone());
+        notes.sort_by(|a, b| a.path.cmp(&b.path));
+        self.notes = notes;
+        self.refilter();
+        if let Some(path) = current {
+            self.select_path(&path);
+        }
+    }
+
+    /// Total number of notes, before filtering.
+    pub fn total(&self) -> usize {
+        self.notes.len()
+    }
+
+    /// Notes passing the filters, in display order.
+    pub fn visible_notes(&self) -> impl Iterator<Item = &IndexedNote> {
+        self.visible.iter().map(|&i| &self.notes[i])
+    }
+
+    pub fn visible_len(&self) -> usize {
+        self.visible.len()
+    }
+
+    pub fn selected_note(&self) -> Option<&IndexedNote> {
+        self.visible.get(self.selected).map(|&i| &self.notes[i])
+    }
+
+    /// Select the note at `path`; returns whether it is visible.
+    pub fn select_path(&mut self, path: &Path) -> bool {
+        match self.visible.iter().position(|&i| self.notes[i].path == path) {
+            Some(pos) => {
+                self.selected = pos;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.visible.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn toggle_view(&mut self) {
+        self.view = match self.view {
+            BrowserView::Tree => BrowserView::Flat,
+            BrowserView::Flat => BrowserView::Tree,
+        };
+        self.refilter();
+    }
+
+    /// Step the type filter through the types present, then back to all.
+    pub fn cycle_type(&mut self) {
+        let present: Vec<NoteType> = NOTE_TYPES
+            .into_iter()
+            .filter(|t| self.notes.iter().any(|n| n.note_type == *t))
+            .collect();
+        self.type_filter = match self.type_filter {
+            None => present.first().copied(),
+            Some(current) => present
+                .iter()
+                .position(|t| *t == current)
+                .and_then(|i| present.get(i + 1).copied()),
+        };
+        self.selected = 0;
+        self.refilter();
+    }
+
+    pub fn push_query(&mut self, c: char) {
+        self.query.push(c);
+        self.selected = 0;
+        self.refilter();
+    }
+
+    pub fn pop_query(&mut self) {
+        self.query.pop();
+        self.selected = 0;
+        self.refilter();
+    }
+
+    pub fn clear_query(&mut self) {
+        self.query.clear();
+        self.refilter();
+    }
+
+    /// Apply a layout and filters at once (restoring a session).
+    pub fn set_filters(
+        &mut self,
+        view: BrowserView,
+        type_filter: Option<NoteType>,
+        query: &str,
+    ) {
+        self.view = view;
+        self.type_filter = type_filter;
+        self.query = query.to_string();
+        self.selected = 0;
+        self.refilter();
+    }
+
+    /// Recompute the visible notes from the filters.
+    fn refilter(&mut self) {
+        let current = self.visible.get(self.selected).copied();
+        let matcher = SkimMatcherV2::default().ignore_case();
+        let query = self.query.trim();
+
+        let mut scored: Vec<(usize, i64)> = self
+            .notes
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| self.type_filter.is_none_or(|t| n.note_type == t))
+            .filter_map(|(i, n)| {
+                if query.is_empty() {
+                    return Some((i, 0));
+                }
+                let haystack = format!("{} {}", n.path.display(), n.title);
+                matcher.fuzzy_match(&haystack, query).map(|score| (i, score))
+            })
+            .collect();
+        if self.view == BrowserView::Flat && !query.is_empty() {
+            scored.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        }
+        self.visible = scored.into_iter().map(|(i, _)| i).collect();
+
+        if let Some(pos) = current.and_then(|c| self.visible.iter().position(|&i| i == c))
+        {
+            self.selected = pos;
+        }
+        self.selected = self.selected.min(self.visible.len().saturating_sub(1));
+    }
+
+    /// The list lines: in tree view, a heading for each folder before its
+    /// first visible note.
+    pub fn rows(&self) -> Vec<BrowserRow> {
+        if self.view == BrowserView::Flat {
+            return (0..self.visible.len())
+                .map(|index| BrowserRow::Note { index, depth: 0 })
+                .collect();
+        }
+
+        let mut rows = Vec::new();
+        let mut open: Vec<String> = Vec::new();
+        for (index, note) in self.visible_notes().enumerate() {
+            let dirs: Vec<String> = note
+                .path
+                .parent()
+                .map(|p| p.iter().map(|s| s.to_string_lossy().into_owned()).collect())
+                .unwrap_or_default();
+            let common = open.iter().zip(&dirs).take_while(|(a, b)| a == b).count();
+            for (depth, name) in dirs.iter().enumerate().skip(common) {
+                rows.push(BrowserRow::Dir { name: name.clone(), depth });
+            }
+            open = dirs;
+            rows.push(BrowserRow::Note { index, depth: open.len() });
+        }
+        rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::path::PathBuf;
+
+    fn note(path: &str, note_type: NoteType) -> IndexedNote {
+        IndexedNote {
+            id: None,
+            path: PathBuf::from(path),
+            note_type,
+            title: Path::new(path).file_stem().unwrap().to_string_lossy().into(),
+            created: None,
+            modified: Utc::now(),
+            frontmatter_json: None,
+            content_hash: String::new(),
+            word_count: 0,
+            heading_json: None,
+        }
+    }
+
+    fn browser() -> NoteBrowser {
+        NoteBrowser::new(vec![
+            note("zettel/rust/lifetimes.md", NoteType::Zettel),
+            note("daily/2024-05-01.md", NoteType::Daily),
+            note("zettel/parsing.md", NoteType::Zettel),
+            note("inbox.md", NoteType::None),
+        ])
+    }
+
+    fn visible(b: &NoteBrowser) -> Vec<String> {
+        b.visible_notes().map(|n| n.path.display().to_string()).collect()
+    }
+
+    #[test]
+    fn tree_rows_head_each_folder_once() {
+        let b = browser();
+        let dir = |name: &str, depth| BrowserRow::Dir { name: name.into(), depth };
+        assert_eq!(
+            b.rows(),
+            [
+                dir("daily", 0),
+                BrowserRow::Note { index: 0, depth: 1 },
+                BrowserRow::Note { index: 1, depth: 0 },
+                dir("zettel", 0),
+                BrowserRow::Note { index: 2, depth: 1 },
+                dir("rust", 1),
+                BrowserRow::Note { index: 3, depth: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn fuzzy_and_type_filters() {
+        let mut b = browser();
+        for c in "zpars".chars() {
+            b.push_query(c);
+        }
+        assert_eq!(visible(&b), ["zettel/parsing.md"]);
+        b.clear_query();
+
+        b.cycle_type();
+        assert_eq!(b.type_filter, Some(NoteType::Daily));
+        assert_eq!(visible(&b), ["daily/2024-05-01.md"]);
+        b.cycle_type();
+        assert_eq!(b.type_filter, Some(NoteType::Zettel));
+        b.cycle_type();
+        b.cycle_type();
+        assert_eq!(b.type_filter, None);
+        assert_eq!(b.visible_len(), 4);
+
+        b.set_filters(BrowserView::Flat, Some(NoteType::Zettel), "pars");
+        assert_eq!(visible(&b), ["zettel/parsing.md"]);
+    }
+
+    #[test]
+    fn selection_follows_the_note() {
+        let mut b = browser();
+        assert!(b.select_path(Path::new("zettel/parsing.md")));
+        b.toggle_view();
+        b.set_notes(vec![
+            note("a.md", NoteType::None),
+            note("zettel/parsing.md", NoteType::Zettel),
+        ]);
+        assert_eq!(b.selected_note().unwrap().path, Path::new("zettel/parsing.md"));
+        b.select_next();
+        b.select_next();
+        assert_eq!(b.selected, 1);
+    }
+}
@@ -0,0 +1,185 @@
+//! Calendar state: the selected day and activity counts for its month.
+
+use std::collections::HashMap;
+
+use chrono::{Datelike, Days, Months, NaiveDate};
+
+/// Activity counts at or above these mark a day as busier.
+const DENSITY_STEPS: [usize; 3] = [1, 4, 10];
+
+/// Calendar pane state.
+pub struct Calendar {
+    pub selected: NaiveDate,
+    pub today: NaiveDate,
+    /// Logged activity entries per day of the selected month.
+    pub activity: HashMap<NaiveDate, usize>,
+    /// Why the activity log could not be read, if it could not.
+    pub error: Option<String>,
+    /// Month the activity counts are for, as (year, month).
+    loaded: Option<(i32, u32)>,
+}
+
+impl Calendar {
+    pub fn new(today: NaiveDate) -> Self {
+        Self {
+            selected: today,
+            today,
+            activity: HashMap::new(),
+            error: None,
+            loaded: None,
+        }
+    }
+
+    /// Whether the activity counts are for another month than the selection.
+    pub fn needs_activity(&self) -> bool {
+        self.loaded != Some((self.selected.year(), self.selected.month()))
+    }
+
+    /// Store the activity counts for the selected month.
+    pub fn set_activity(&mut self, activity: Result<HashMap<NaiveDate, usize>, String>) {
+        self.loaded = Some((self.selected.year(), self.selected.month()));
+        match activity {
+            Ok(activity) => {
+                self.activity = activity;
+                self.error = None;
+            }
+            Err(e) => {
+                self.activity.clear();
+                self.error = Some(e);
+            }
+        }
+    }
+
+    /// Drop the activity counts so they are read again, e.g. after a
+    /// daily note was created.
+    pub fn invalidate(&mut self) {
+        self.loaded = None;
+    }
+
+    /// Move the selection by `days` (negative for earlier).
+    pub fn move_days(&mut self, days: i64) {
+        let moved = if days < 0 {
+            self.selected.checked_sub_days(Days::new(days.unsigned_abs()))
+        } else {
+            self.selected.checked_add_days(Days::new(days as u64))
+        };
+        if let Some(date) = moved {
+            self.selected = date;
+        }
+    }
+
+    /// Move the selection by `months`, clamping to the end of shorter months.
+    pub fn move_months(&mut self, months: i32) {
+        let moved = if months < 0 {
+            self.selected.checked_sub_months(Months::new(months.unsigned_abs()))
+        } else {
+            self.selected.checked_add_months(Months::new(months as u32))
+        };
+        if let Some(date) = moved {
+            self.selected = date;
+        }
+    }
+
+    pub fn select_today(&mut self) {
+        self.selected = self.today;
+    }
+
+    /// First and last day of the selected month.
+    pub fn month_bounds(&self) -> (NaiveDate, NaiveDate) {
+        let first = self.selected.with_day(1).unwrap_or(self.selected);
+        let last = first
+            .checked_add_months(Months::new(1))
+            .and_then(|next| next.pred_opt())
+            .unwrap_or(first);
+        (first, last)
+    }
+
+    /// The selected month as weeks from Monday to Sunday; days outside the
+    /// month are `None`.
+    pub fn weeks(&self) -> Vec<[Option<NaiveDate>; 7]> {
+        let (first, last) = self.month_bounds();
+        let mut weeks = Vec::new();
+        let mut week = [None; 7];
+        let mut day = first;
+        loop {
+            let weekday = day.weekday().num_days_from_monday() as usize;
+            week[weekday] = Some(day);
+            if weekday == 6 || day == last {
+                weeks.push(week);
+                week = [None; 7];
+            }
+            match day.succ_opt() {
+                Some(next) if day < last => day = next,
+                _ => break,
+            }
+        }
+        weeks
+    }
+
+    /// Activity entries logged on `date`.
+    pub fn count(&self, date: NaiveDate) -> usize {
+        self.activity.get(&date).copied().unwrap_or(0)
+    }
+
+    /// How busy `date` was, from 0 (no activity) to 3.
+    pub fn density(&self, date: NaiveDate) -> usize {
+        let count = self.count(date);
+        DENSITY_STEPS.iter().filter(|&&step| count >= step).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        // October 2026 starts on a Thursday and has 31 days
+        let calendar = Calendar::new(date("2026-10-16"));
+        let weeks = calendar.weeks();
+        assert_eq!(weeks.len(), 5);
+        assert_eq!(weeks[0][..3], [None, None, None]);
+        assert_eq!(weeks[0][3], Some(date("2026-10-01")));
+        assert_eq!(weeks[4][5], Some(date("2026-10-31")));
+        assert_eq!(weeks[4][6], None);
+        assert_eq!(calendar.month_bounds(), (date("2026-10-01"), date("2026-10-31")));
+    }
+
+    #[test]
+    fn navigation_crosses_months_and_reloads() {
+        let mut calendar = Calendar::new(date("2026-01-31"));
+        calendar.set_activity(Ok(HashMap::new()));
+        assert!(!calendar.needs_activity());
+
+        calendar.move_months(1);
+        assert_eq!(calendar.selected, date("2026-02-28"));
+        assert!(calendar.needs_activity());
+        calendar.move_days(-28);
+        assert_eq!(calendar.selected, date("2026-01-31"));
+        calendar.move_days(7);
+        assert_eq!(calendar.selected, date("2026-02-07"));
+        calendar.move_months(-2);
+        assert_eq!(calendar.selected, date("2025-12-07"));
+        calendar.select_today();
+        assert_eq!(calendar.selected, date("2026-01-31"));
+    }
+
+    #[test]
+    fn density_levels() {
+        let mut calendar = Calendar::new(date("2026-10-16"));
+        calendar.set_activity(Ok(HashMap::from([
+            (date("2026-10-01"), 1),
+            (date("2026-10-02"), 5),
+            (date("2026-10-03"), 12),
+        ])));
+        assert_eq!(calendar.density(date("2026-10-04")), 0);
+        assert_eq!(calendar.density(date("2026-10-01")), 1);
+        assert_eq!(calendar.density(date("2026-10-02")), 2);
+        assert_eq!(calendar.density(date("2026-10-03")), 3);
+        assert_eq!(calendar.count(date("2026-10-03")), 12);
+    }
+}
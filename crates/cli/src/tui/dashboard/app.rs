@@ -253,7 +253,7 @@ impl DashboardApp {
         };
 
         let index_path = PathResolver::new(&cfg.vault_root).index_db();
-        let db = match IndexDb::open(&index_path) {
+        let db = match IndexDb::open_read_only(&index_path) {
             Ok(db) => db,
             Err(e) => {
                 self.status = Some(StatusMessage {
@@ -270,9 +270,22 @@ impl DashboardApp {
             mdvault_core::report::ReportScope::Vault => None,
         };
 
+        let saved_searches = match mdvault_core::saved_searches::load(&cfg) {
+            Ok(s) => s,
+            Err(e) => {
+                self.status = Some(StatusMessage {
+                    text: format!("Saved searches error: {e}"),
+                    is_error: true,
+                });
+                self.mode = Mode::Status;
+                return;
+            }
+        };
+
         let options = mdvault_core::report::DashboardOptions {
             project,
             activity_days: self.report.activity.period_days,
+            saved_searches,
             ..Default::default()
         };
 
@@ -42,13 +42,17 @@ pub fn run(
     })?;
 
     let index_path = PathResolver::new(&cfg.vault_root).index_db();
-    let db = IndexDb::open(&index_path).map_err(|e| {
+    let db = IndexDb::open_read_only(&index_path).map_err(|e| {
         color_eyre::eyre::eyre!("Failed to open index: {e}\nRun 'mdv reindex' first.")
     })?;
 
+    let saved_searches = mdvault_core::saved_searches::load(&cfg)
+        .map_err(|e| color_eyre::eyre::eyre!("Failed to load saved searches: {e}"))?;
+
     let options = DashboardOptions {
         project: project.map(String::from),
         activity_days,
+        saved_searches,
         ..Default::default()
     };
 
@@ -98,7 +102,12 @@ fn run_app(
     mut app: DashboardApp,
 ) -> Result<()> {
     loop {
-        terminal.draw(|frame| ui::draw(frame, &app))?;
+        terminal.draw(|frame| {
+            ui::draw(frame, &app);
+            if crate::display::accessible() {
+                super::make_accessible(frame.buffer_mut());
+            }
+        })?;
 
         if poll(Duration::from_millis(100))?
             && let Event::Key(key) = read()?
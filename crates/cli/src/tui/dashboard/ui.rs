@@ -612,6 +612,33 @@ fn draw_vault_alerts(
         }
     }
 
+    if !app.report.saved_searches.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "  Saved Searches",
+            Style::default().fg(Color::Cyan).bold(),
+        )));
+        for search in &app.report.saved_searches {
+            lines.push(Line::from(vec![
+                Span::styled("    / ", Style::default().fg(Color::Cyan)),
+                Span::styled(
+                    truncate_str(&search.name, 30),
+                    Style::default().fg(Color::White),
+                ),
+                Span::styled(
+                    format!(" ({})", search.count),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+            for note in search.notes.iter().take(3) {
+                lines.push(Line::from(Span::styled(
+                    format!("        {}", truncate_str(&note.title, 35)),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+        }
+    }
+
     // Apply scroll
     let visible: Vec<Line> = lines.into_iter().skip(app.detail_scroll).collect();
 
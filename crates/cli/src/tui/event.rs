@@ -2,7 +2,9 @@
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
-use super::app::{App, Message, Mode};
+use super::app::{App, Message, Mode, Pane};
+use super::graph::Side;
+use super::keys::{Action, KeyMap};
 
 /// Map a key event to an optional message based on current app mode.
 pub fn map_key_event(app: &App, key: KeyEvent) -> Option<Message> {
@@ -11,43 +13,296 @@ pub fn map_key_event(app: &App, key: KeyEvent) -> Option<Message> {
         return Some(Message::Quit);
     }
 
-    // Mode-specific bindings
+    // Any key closes the help overlay
+    if app.show_help {
+        return Some(Message::ToggleHelp);
+    }
+
+    let keys = &app.keys;
+
+    // Modes that take text handle keys of their own first
     match &app.mode {
-        Mode::Browse => map_browse_keys(key),
-        Mode::OutputPath | Mode::Input { .. } => map_input_keys(key),
-        Mode::Result => map_result_keys(key),
+        Mode::Filter => map_filter_keys(keys, key),
+        Mode::Search => map_search_input_keys(keys, key),
+        Mode::Input { .. } | Mode::Prompt(_) if app.picker.is_some() => {
+            map_picker_keys(keys, key)
+        }
+        Mode::Input { .. } => map_var_input_keys(keys, key, app.multiline),
+        Mode::OutputPath | Mode::Prompt(_) | Mode::ConfirmTrust => {
+            map_input_keys(keys, key)
+        }
+        Mode::Result if key.code == KeyCode::Char(' ') => Some(Message::ClearStatus),
+        _ => tables(app).iter().find_map(|table| keys.lookup(key, table)),
     }
 }
 
-fn map_browse_keys(key: KeyEvent) -> Option<Message> {
-    match key.code {
-        // Vim-style navigation
-        KeyCode::Char('j') | KeyCode::Down => Some(Message::SelectNext),
-        KeyCode::Char('k') | KeyCode::Up => Some(Message::SelectPrev),
+/// Binding tables of the current mode and pane, tried in order.
+fn tables(app: &App) -> &'static [&'static [(Action, Message)]] {
+    match &app.mode {
+        Mode::Browse => match app.pane {
+            Pane::Home => &[HOME_KEYS, PREVIEW_KEYS, SHARED_KEYS],
+            Pane::Palette => &[BROWSE_KEYS, PREVIEW_KEYS, SHARED_KEYS],
+            Pane::Notes => &[NOTES_KEYS, PREVIEW_KEYS, SHARED_KEYS],
+            Pane::Search => &[SEARCH_KEYS, PREVIEW_KEYS, SHARED_KEYS],
+            Pane::Calendar => &[CALENDAR_KEYS, PREVIEW_KEYS, SHARED_KEYS],
+            Pane::Project => &[PROJECT_KEYS, SHARED_KEYS],
+            Pane::Inbox => &[INBOX_KEYS, PREVIEW_KEYS, SHARED_KEYS],
+        },
+        Mode::Graph => &[GRAPH_KEYS],
+        Mode::Running => &[RUNNING_KEYS],
+        Mode::Result => &[RESULT_KEYS],
+        _ => &[],
+    }
+}
+
+/// Actions bound in the current mode and pane, in the order keys are
+/// tried, for the help overlay.
+pub fn active_actions(app: &App) -> Vec<Action> {
+    let mut actions = Vec::new();
+    for (action, _) in tables(app).iter().copied().flatten() {
+        if !actions.contains(action) {
+            actions.push(*action);
+        }
+    }
+    actions
+}
+
+/// Bindings every browse pane shares, tried after the pane's own.
+const SHARED_KEYS: &[(Action, Message)] = &[
+    (Action::StopFocus, Message::StopFocus),
+    (Action::ShrinkList, Message::ResizeList(-1)),
+    (Action::GrowList, Message::ResizeList(1)),
+];
+
+const BROWSE_KEYS: &[(Action, Message)] = &[
+    // Navigation
+    (Action::SelectNext, Message::SelectNext),
+    (Action::SelectPrev, Message::SelectPrev),
+    (Action::SwitchPane, Message::SwitchPane),
+    // Actions
+    (Action::Execute, Message::Execute),
+    (Action::Search, Message::StartFilter),
+    (Action::Edit, Message::Edit),
+    (Action::Help, Message::ToggleHelp),
+    (Action::Quit, Message::Quit),
+    (Action::Cancel, Message::Quit),
+];
+
+const HOME_KEYS: &[(Action, Message)] = &[
+    (Action::SelectNext, Message::SelectNext),
+    (Action::SelectPrev, Message::SelectPrev),
+    (Action::SwitchPane, Message::SwitchPane),
+    // Quick actions
+    (Action::Today, Message::OpenToday),
+    (Action::Search, Message::StartSearch),
+    (Action::Execute, Message::OpenNote),
+    (Action::Open, Message::OpenNote),
+    (Action::Capture, Message::CaptureInto),
+    (Action::Graph, Message::ShowGraph),
+    (Action::Edit, Message::Edit),
+    (Action::Help, Message::ToggleHelp),
+    (Action::Quit, Message::Quit),
+    (Action::Cancel, Message::Quit),
+];
+
+const NOTES_KEYS: &[(Action, Message)] = &[
+    (Action::SelectNext, Message::SelectNext),
+    (Action::SelectPrev, Message::SelectPrev),
+    (Action::SwitchPane, Message::SwitchPane),
+    // Filtering and layout
+    (Action::Search, Message::StartFilter),
+    (Action::ToggleView, Message::ToggleView),
+    (Action::CycleType, Message::CycleType),
+    // Note actions
+    (Action::Execute, Message::OpenNote),
+    (Action::Open, Message::OpenNote),
+    (Action::Capture, Message::CaptureInto),
+    (Action::Rename, Message::RenameNote),
+    (Action::Graph, Message::ShowGraph),
+    (Action::Edit, Message::Edit),
+    (Action::Help, Message::ToggleHelp),
+    (Action::Quit, Message::Quit),
+    (Action::Cancel, Message::Quit),
+];
+
+const SEARCH_KEYS: &[(Action, Message)] = &[
+    (Action::SelectNext, Message::SelectNext),
+    (Action::SelectPrev, Message::SelectPrev),
+    (Action::SwitchPane, Message::SwitchPane),
+    (Action::Search, Message::StartSearch),
+    (Action::Execute, Message::OpenNote),
+    (Action::Open, Message::OpenNote),
+    (Action::Capture, Message::CaptureInto),
+    (Action::Graph, Message::ShowGraph),
+    (Action::Edit, Message::Edit),
+    (Action::Help, Message::ToggleHelp),
+    (Action::Quit, Message::Quit),
+    (Action::Cancel, Message::Quit),
+];
 
-        // Actions
-        KeyCode::Enter => Some(Message::Execute),
-        KeyCode::Char('q') | KeyCode::Esc => Some(Message::Quit),
+const CALENDAR_KEYS: &[(Action, Message)] = &[
+    (Action::Left, Message::MoveDay(-1)),
+    (Action::Right, Message::MoveDay(1)),
+    (Action::SelectPrev, Message::MoveDay(-7)),
+    (Action::SelectNext, Message::MoveDay(7)),
+    (Action::PrevMonth, Message::MoveMonth(-1)),
+    (Action::NextMonth, Message::MoveMonth(1)),
+    (Action::Today, Message::Today),
+    (Action::SwitchPane, Message::SwitchPane),
+    (Action::Execute, Message::OpenDaily),
+    (Action::Open, Message::OpenDaily),
+    (Action::Edit, Message::OpenDaily),
+    (Action::Help, Message::ToggleHelp),
+    (Action::Quit, Message::Quit),
+    (Action::Cancel, Message::Quit),
+];
 
-        _ => None,
+const PROJECT_KEYS: &[(Action, Message)] = &[
+    (Action::SelectNext, Message::SelectNext),
+    (Action::SelectPrev, Message::SelectPrev),
+    (Action::SwitchPane, Message::SwitchPane),
+    // Quick actions
+    (Action::NewTask, Message::NewTask),
+    (Action::LogProject, Message::LogProject),
+    (Action::FocusProject, Message::FocusProject),
+    (Action::Edit, Message::Edit),
+    (Action::Help, Message::ToggleHelp),
+    (Action::Quit, Message::Quit),
+    (Action::Cancel, Message::Quit),
+];
+
+const INBOX_KEYS: &[(Action, Message)] = &[
+    (Action::SelectNext, Message::SelectNext),
+    (Action::SelectPrev, Message::SelectPrev),
+    (Action::SwitchPane, Message::SwitchPane),
+    // Triage: each files the note and moves on to the next
+    (Action::Assign, Message::AssignNote),
+    (Action::Schedule, Message::ScheduleNote),
+    (Action::Archive, Message::ArchiveNote),
+    (Action::Delete, Message::DeleteNote),
+    (Action::Skip, Message::SkipNote),
+    (Action::Execute, Message::OpenNote),
+    (Action::Open, Message::OpenNote),
+    (Action::Edit, Message::Edit),
+    (Action::Help, Message::ToggleHelp),
+    (Action::Quit, Message::Quit),
+    (Action::Cancel, Message::Quit),
+];
+
+/// Preview bindings shared by the browse panes, tried after the pane's own.
+const PREVIEW_KEYS: &[(Action, Message)] = &[
+    (Action::ScrollDown, Message::ScrollPreview(1)),
+    (Action::ScrollUp, Message::ScrollPreview(-1)),
+    (Action::PageDown, Message::ScrollPreview(10)),
+    (Action::PageUp, Message::ScrollPreview(-10)),
+    (Action::ToggleRaw, Message::TogglePreviewRaw),
+    (Action::ToggleInfo, Message::ToggleInfo),
+];
+
+const GRAPH_KEYS: &[(Action, Message)] = &[
+    (Action::SelectNext, Message::SelectNext),
+    (Action::SelectPrev, Message::SelectPrev),
+    (Action::Left, Message::GraphSide(Side::Incoming)),
+    (Action::Right, Message::GraphSide(Side::Outgoing)),
+    (Action::Execute, Message::GraphFollow),
+    (Action::GraphBack, Message::GraphBack),
+    (Action::GraphDepth, Message::GraphDepth),
+    (Action::Open, Message::OpenNote),
+    (Action::Edit, Message::Edit),
+    (Action::Help, Message::ToggleHelp),
+    (Action::Quit, Message::GraphClose),
+    (Action::Cancel, Message::GraphClose),
+    (Action::Graph, Message::GraphClose),
+];
+
+/// Whether `key` types a character in a text field rather than acting.
+fn is_text(key: KeyEvent) -> bool {
+    matches!(key.code, KeyCode::Char(_))
+        && !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+}
+
+fn map_search_input_keys(keys: &KeyMap, key: KeyEvent) -> Option<Message> {
+    match key.code {
+        KeyCode::Down => Some(Message::SelectNext),
+        KeyCode::Up => Some(Message::SelectPrev),
+        KeyCode::Char(c) if is_text(key) => Some(Message::SearchChar(c)),
+        KeyCode::Backspace => Some(Message::SearchBackspace),
+        _ => keys.lookup(
+            key,
+            &[
+                (Action::Execute, Message::OpenNote),
+                (Action::Cancel, Message::SearchDone),
+            ],
+        ),
+    }
+}
+
+fn map_filter_keys(keys: &KeyMap, key: KeyEvent) -> Option<Message> {
+    match key.code {
+        KeyCode::Down => Some(Message::SelectNext),
+        KeyCode::Up => Some(Message::SelectPrev),
+        KeyCode::Char(c) if is_text(key) => Some(Message::FilterChar(c)),
+        KeyCode::Backspace => Some(Message::FilterBackspace),
+        _ => keys.lookup(
+            key,
+            &[
+                (Action::Execute, Message::FilterDone),
+                (Action::Cancel, Message::FilterCancel),
+            ],
+        ),
     }
 }
 
-fn map_input_keys(key: KeyEvent) -> Option<Message> {
+fn map_input_keys(keys: &KeyMap, key: KeyEvent) -> Option<Message> {
     match key.code {
-        KeyCode::Char(c) => Some(Message::InputChar(c)),
+        KeyCode::Char(c) if is_text(key) => Some(Message::InputChar(c)),
         KeyCode::Backspace => Some(Message::InputBackspace),
-        KeyCode::Enter => Some(Message::InputSubmit),
-        KeyCode::Esc => Some(Message::Cancel),
-        _ => None,
+        _ => keys.lookup(
+            key,
+            &[(Action::Execute, Message::InputSubmit), (Action::Cancel, Message::Cancel)],
+        ),
     }
 }
 
-fn map_result_keys(key: KeyEvent) -> Option<Message> {
+/// Variables can span lines: once one has a line break, Enter adds more
+/// and only `submit` submits.
+fn map_var_input_keys(keys: &KeyMap, key: KeyEvent, multiline: bool) -> Option<Message> {
+    let msg = keys.lookup(
+        key,
+        &[
+            (Action::Newline, Message::InputNewline),
+            (Action::Submit, Message::InputSubmit),
+        ],
+    );
+    if msg.is_some() {
+        return msg;
+    }
+    if multiline && keys.is(Action::Execute, key) {
+        return Some(Message::InputNewline);
+    }
+    map_input_keys(keys, key)
+}
+
+/// Typing narrows a picker's options and the arrows choose among them;
+/// Tab chooses several in a multi-select.
+fn map_picker_keys(keys: &KeyMap, key: KeyEvent) -> Option<Message> {
     match key.code {
-        KeyCode::Enter | KeyCode::Esc | KeyCode::Char(' ') | KeyCode::Char('q') => {
-            Some(Message::ClearStatus)
-        }
-        _ => None,
+        KeyCode::Down => Some(Message::SelectNext),
+        KeyCode::Up => Some(Message::SelectPrev),
+        KeyCode::Tab => Some(Message::ToggleOption),
+        _ => map_input_keys(keys, key),
     }
 }
+
+/// Other keys wait until the job is done.
+const RUNNING_KEYS: &[(Action, Message)] = &[(Action::Cancel, Message::CancelJob)];
+
+const RESULT_KEYS: &[(Action, Message)] = &[
+    (Action::Edit, Message::Edit),
+    (Action::Fix, Message::Fix),
+    (Action::Undo, Message::Undo),
+    (Action::Help, Message::ToggleHelp),
+    (Action::Execute, Message::ClearStatus),
+    (Action::Cancel, Message::ClearStatus),
+    (Action::Quit, Message::ClearStatus),
+];
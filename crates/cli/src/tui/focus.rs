@@ -0,0 +1,51 @@
+//! Focus session timer: how long the project set with `mdv focus` has been
+//! active, for the header and the daily log entry when it stops.
+
+use chrono::{DateTime, Local, TimeDelta};
+use mdvault_core::context::FocusContext;
+
+/// Time since `focus` started (zero if it does not record a start).
+pub fn elapsed(focus: &FocusContext, now: DateTime<Local>) -> TimeDelta {
+    focus.started_at.map(|start| (now - start).max(TimeDelta::zero())).unwrap_or_default()
+}
+
+/// A running timer: `12:05`, or `1:02:05` past the hour.
+pub fn timer(elapsed: TimeDelta) -> String {
+    let secs = elapsed.num_seconds();
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if h > 0 { format!("{h}:{m:02}:{s:02}") } else { format!("{m:02}:{s:02}") }
+}
+
+/// A session length for the daily log: `45m`, or `1h 05m`.
+pub fn duration(elapsed: TimeDelta) -> String {
+    let mins = elapsed.num_minutes();
+    if mins >= 60 {
+        format!("{}h {:02}m", mins / 60, mins % 60)
+    } else {
+        format!("{mins}m")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_timer_and_duration() {
+        let now = Local::now();
+        let mut focus = FocusContext::new("MCP");
+        focus.started_at = Some(now - TimeDelta::seconds(3725));
+        let spent = elapsed(&focus, now);
+
+        assert_eq!(timer(spent), "1:02:05");
+        assert_eq!(duration(spent), "1h 02m");
+        assert_eq!(timer(TimeDelta::seconds(65)), "01:05");
+        assert_eq!(duration(TimeDelta::seconds(65)), "1m");
+
+        // A start in the future (clock skew) and no start count as zero
+        focus.started_at = Some(now + TimeDelta::seconds(10));
+        assert_eq!(elapsed(&focus, now), TimeDelta::zero());
+        focus.started_at = None;
+        assert_eq!(timer(elapsed(&focus, now)), "00:00");
+    }
+}
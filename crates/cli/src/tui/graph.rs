@@ -0,0 +1,228 @@
+//! Link graph state: a note's neighbourhood as backlink and outgoing
+//! columns, each listing direct neighbours with the notes a hop beyond
+//! them underneath.
+
+use std::path::{Path, PathBuf};
+
+use mdvault_core::index::{IndexedNote, ReachedNote};
+
+/// Hops shown when the graph opens.
+pub const DEFAULT_DEPTH: usize = 2;
+
+/// A column of the graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// Notes linking to the centre.
+    Incoming,
+    /// Notes the centre links to.
+    Outgoing,
+}
+
+impl Side {
+    fn index(self) -> usize {
+        match self {
+            Side::Incoming => 0,
+            Side::Outgoing => 1,
+        }
+    }
+}
+
+/// A note in a graph column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphNode {
+    pub path: PathBuf,
+    pub title: String,
+    /// Hops from the centre.
+    pub depth: usize,
+}
+
+/// Graph view state.
+pub struct LinkGraph {
+    pub center: IndexedNote,
+    pub incoming: Vec<GraphNode>,
+    pub outgoing: Vec<GraphNode>,
+    /// Hops walked in each direction.
+    pub depth: usize,
+    /// Focused column.
+    pub side: Side,
+    /// Selected row in each column.
+    pub rows: [usize; 2],
+    /// Earlier centres, most recent last.
+    pub history: Vec<PathBuf>,
+}
+
+impl LinkGraph {
+    /// Lay out the walks from `center` in each direction.
+    pub fn new(
+        center: IndexedNote,
+        incoming: Vec<ReachedNote>,
+        outgoing: Vec<ReachedNote>,
+        depth: usize,
+    ) -> Self {
+        let side = if incoming.is_empty() && !outgoing.is_empty() {
+            Side::Outgoing
+        } else {
+            Side::Incoming
+        };
+        Self {
+            center,
+            incoming: column(incoming),
+            outgoing: column(outgoing),
+            depth,
+            side,
+            rows: [0; 2],
+            history: Vec::new(),
+        }
+    }
+
+    pub fn nodes(&self, side: Side) -> &[GraphNode] {
+        match side {
+            Side::Incoming => &self.incoming,
+            Side::Outgoing => &self.outgoing,
+        }
+    }
+
+    /// Selected row in `side`.
+    pub fn row(&self, side: Side) -> usize {
+        self.rows[side.index()]
+    }
+
+    pub fn selected_node(&self) -> Option<&GraphNode> {
+        self.nodes(self.side).get(self.row(self.side))
+    }
+
+    /// Path of the selected node, or of the centre if its column is empty.
+    pub fn selected_path(&self) -> &Path {
+        self.selected_node().map_or(self.center.path.as_path(), |n| n.path.as_path())
+    }
+
+    pub fn select_next(&mut self) {
+        let len = self.nodes(self.side).len();
+        let row = &mut self.rows[self.side.index()];
+        if *row + 1 < len {
+            *row += 1;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        let row = &mut self.rows[self.side.index()];
+        *row = row.saturating_sub(1);
+    }
+
+    pub fn focus(&mut self, side: Side) {
+        self.side = side;
+    }
+
+    /// Replace the neighbourhood with `next`'s, remembering this centre
+    /// for [`LinkGraph::back`].
+    pub fn recenter(&mut self, mut next: LinkGraph) {
+        next.history = std::mem::take(&mut self.history);
+        next.history.push(self.center.path.clone());
+        *self = next;
+    }
+
+    /// The centre to return to, dropping it from the history.
+    pub fn back(&mut self) -> Option<PathBuf> {
+        self.history.pop()
+    }
+}
+
+/// Direct neighbours in path order, each followed by the notes reached
+/// through it.
+fn column(reached: Vec<ReachedNote>) -> Vec<GraphNode> {
+    let node = |r: &ReachedNote| GraphNode {
+        path: r.note.path.clone(),
+        title: r.note.title.clone(),
+        depth: r.depth,
+    };
+    let mut nodes = Vec::new();
+    for near in reached.iter().filter(|r| r.depth == 1) {
+        nodes.push(node(near));
+        nodes.extend(
+            reached
+                .iter()
+                .filter(|r| r.depth == 2 && r.route.get(1) == Some(&near.note.path))
+                .map(node),
+        );
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use mdvault_core::index::NoteType;
+
+    fn note(path: &str) -> IndexedNote {
+        IndexedNote {
+            id: None,
+            path: PathBuf::from(path),
+            note_type: NoteType::Zettel,
+            title: path.trim_end_matches(".md").to_string(),
+            created: None,
+            modified: Utc::now(),
+            frontmatter_json: None,
+            content_hash: String::new(),
+            word_count: 0,
+            heading_json: None,
+        }
+    }
+
+    fn reached(route: &[&str]) -> ReachedNote {
+        ReachedNote {
+            note: note(route.last().unwrap()),
+            depth: route.len() - 1,
+            route: route.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    fn paths(nodes: &[GraphNode]) -> Vec<String> {
+        nodes.iter().map(|n| format!("{}{}", n.depth, n.path.display())).collect()
+    }
+
+    #[test]
+    fn second_hops_sit_under_their_neighbour() {
+        let graph = LinkGraph::new(
+            note("me.md"),
+            vec![],
+            vec![
+                reached(&["me.md", "a.md"]),
+                reached(&["me.md", "b.md"]),
+                reached(&["me.md", "a.md", "a2.md"]),
+                reached(&["me.md", "b.md", "b2.md"]),
+                reached(&["me.md", "a.md", "a3.md"]),
+            ],
+            2,
+        );
+        assert_eq!(
+            paths(&graph.outgoing),
+            ["1a.md", "2a2.md", "2a3.md", "1b.md", "2b2.md"]
+        );
+        // Nothing links here, so the outgoing column has focus
+        assert_eq!(graph.side, Side::Outgoing);
+        assert_eq!(graph.selected_path(), Path::new("a.md"));
+    }
+
+    #[test]
+    fn navigation_and_history() {
+        let mut graph = LinkGraph::new(
+            note("me.md"),
+            vec![reached(&["me.md", "in.md"])],
+            vec![reached(&["me.md", "a.md"]), reached(&["me.md", "b.md"])],
+            1,
+        );
+        assert_eq!(graph.selected_path(), Path::new("in.md"));
+        graph.select_next();
+        assert_eq!(graph.row(Side::Incoming), 0);
+        graph.focus(Side::Outgoing);
+        graph.select_next();
+        graph.select_next();
+        assert_eq!(graph.selected_path(), Path::new("b.md"));
+
+        graph.recenter(LinkGraph::new(note("b.md"), vec![], vec![], 1));
+        assert_eq!(graph.selected_path(), Path::new("b.md"));
+        assert_eq!(graph.back().as_deref(), Some(Path::new("me.md")));
+        assert_eq!(graph.back(), None);
+    }
+}
@@ -0,0 +1,156 @@
+//! Start screen state: recently modified notes and in-progress tasks to
+//! pick up where you left off, with a summary of today.
+
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use mdvault_core::index::IndexedNote;
+
+/// How today is going so far.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TodayStatus {
+    pub date: NaiveDate,
+    /// Today's daily note, relative to the vault, once it exists.
+    pub daily: Option<PathBuf>,
+    /// Activity log entries recorded today.
+    pub activity: usize,
+    /// Tasks completed today.
+    pub completed: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct HomeScreen {
+    /// Recently modified notes, newest first.
+    pub recent: Vec<IndexedNote>,
+    /// Tasks in progress.
+    pub tasks: Vec<IndexedNote>,
+    pub today: Option<TodayStatus>,
+    /// Why the notes could not be loaded, if they could not.
+    pub error: Option<String>,
+    /// Selected entry, counting the recent notes first and then the tasks.
+    pub selected: usize,
+    loaded: bool,
+}
+
+impl HomeScreen {
+    /// Whether the screen needs (re)loading.
+    pub fn needs_load(&self) -> bool {
+        !self.loaded
+    }
+
+    pub fn invalidate(&mut self) {
+        self.loaded = false;
+    }
+
+    /// Apply freshly loaded recent notes and tasks, keeping the selected
+    /// note selected if it is still listed.
+    pub fn set_notes(
+        &mut self,
+        notes: Result<(Vec<IndexedNote>, Vec<IndexedNote>), String>,
+    ) {
+        let selected = self.selected_note().map(|n| n.path.clone());
+        self.loaded = true;
+        match notes {
+            Ok((recent, tasks)) => {
+                self.recent = recent;
+                self.tasks = tasks;
+                self.error = None;
+            }
+            Err(e) => {
+                self.recent.clear();
+                self.tasks.clear();
+                self.error = Some(e);
+            }
+        }
+        self.selected = selected
+            .and_then(|path| self.entries().position(|n| n.path == path))
+            .unwrap_or(self.selected)
+            .min(self.len().saturating_sub(1));
+    }
+
+    /// Recent notes, then tasks.
+    pub fn entries(&self) -> impl Iterator<Item = &IndexedNote> {
+        self.recent.iter().chain(&self.tasks)
+    }
+
+    pub fn len(&self) -> usize {
+        self.recent.len() + self.tasks.len()
+    }
+
+    pub fn selected_note(&self) -> Option<&IndexedNote> {
+        self.entries().nth(self.selected)
+    }
+
+    pub fn selected_path(&self) -> Option<&Path> {
+        self.selected_note().map(|n| n.path.as_path())
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use mdvault_core::index::NoteType;
+
+    use super::*;
+
+    fn note(path: &str, note_type: NoteType) -> IndexedNote {
+        IndexedNote {
+            id: None,
+            path: PathBuf::from(path),
+            note_type,
+            title: String::new(),
+            created: None,
+            modified: Utc::now(),
+            frontmatter_json: None,
+            content_hash: String::new(),
+            word_count: 0,
+            heading_json: None,
+        }
+    }
+
+    #[test]
+    fn selects_across_recent_notes_and_tasks() {
+        let mut home = HomeScreen::default();
+        assert!(home.needs_load());
+        home.set_notes(Ok((
+            vec![note("a.md", NoteType::Zettel)],
+            vec![
+                note("Tasks/t1.md", NoteType::Task),
+                note("Tasks/t2.md", NoteType::Task),
+            ],
+        )));
+        assert_eq!(home.selected_path(), Some(Path::new("a.md")));
+
+        home.select_next();
+        assert_eq!(home.selected_path(), Some(Path::new("Tasks/t1.md")));
+        home.select_next();
+        home.select_next();
+        assert_eq!(home.selected_path(), Some(Path::new("Tasks/t2.md")));
+
+        // A note edited since moves up; the selection follows it
+        home.invalidate();
+        home.set_notes(Ok((
+            vec![note("Tasks/t2.md", NoteType::Task), note("a.md", NoteType::Zettel)],
+            vec![
+                note("Tasks/t1.md", NoteType::Task),
+                note("Tasks/t2.md", NoteType::Task),
+            ],
+        )));
+        assert!(!home.needs_load());
+        assert_eq!(home.selected, 0);
+
+        home.set_notes(Err("no index".to_string()));
+        assert_eq!(home.selected_note().map(|n| &n.path), None);
+        assert_eq!(home.error.as_deref(), Some("no index"));
+    }
+}
@@ -0,0 +1,133 @@
+//! Inbox triage state: the notes in `Inbox/`, one at a time. Filing a note
+//! (assigning, scheduling, archiving, or deleting it) drops it from the
+//! queue and moves on to the next; skipping leaves it for later.
+
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default)]
+pub struct InboxTriage {
+    /// Vault-relative paths of the notes still to triage.
+    notes: Vec<PathBuf>,
+    loaded: bool,
+    /// Why the inbox could not be read, if it could not.
+    pub error: Option<String>,
+    /// Index of the note being triaged.
+    pub current: usize,
+    /// Notes filed since the pane was loaded.
+    pub filed: usize,
+}
+
+impl InboxTriage {
+    /// Whether the notes need (re)loading.
+    pub fn needs_load(&self) -> bool {
+        !self.loaded
+    }
+
+    pub fn invalidate(&mut self) {
+        self.loaded = false;
+    }
+
+    /// Apply freshly loaded notes, staying on the current note if it is
+    /// still there.
+    pub fn set_notes(&mut self, notes: Result<Vec<PathBuf>, String>) {
+        let current = self.current_note().map(Path::to_path_buf);
+        self.loaded = true;
+        match notes {
+            Ok(notes) => {
+                self.notes = notes;
+                self.error = None;
+            }
+            Err(e) => {
+                self.notes.clear();
+                self.error = Some(e);
+            }
+        }
+        self.current = current
+            .and_then(|c| self.notes.iter().position(|n| *n == c))
+            .unwrap_or(self.current)
+            .min(self.notes.len().saturating_sub(1));
+    }
+
+    pub fn notes(&self) -> &[PathBuf] {
+        &self.notes
+    }
+
+    pub fn current_note(&self) -> Option<&Path> {
+        self.notes.get(self.current).map(PathBuf::as_path)
+    }
+
+    pub fn select_next(&mut self) {
+        if self.current + 1 < self.notes.len() {
+            self.current += 1;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.current = self.current.saturating_sub(1);
+    }
+
+    /// Leave the current note for later, wrapping around at the end.
+    pub fn skip(&mut self) {
+        if !self.notes.is_empty() {
+            self.current = (self.current + 1) % self.notes.len();
+        }
+    }
+
+    /// Drop the current note once it is filed; the next one takes its place.
+    pub fn filed_current(&mut self) {
+        if self.current < self.notes.len() {
+            self.notes.remove(self.current);
+            self.filed += 1;
+        }
+        if self.current >= self.notes.len() {
+            self.current = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(names: &[&str]) -> Vec<PathBuf> {
+        names.iter().map(|n| PathBuf::from(format!("Inbox/{n}.md"))).collect()
+    }
+
+    #[test]
+    fn skips_and_files_through_the_queue() {
+        let mut inbox = InboxTriage::default();
+        assert!(inbox.needs_load());
+        inbox.set_notes(Ok(paths(&["a", "b", "c"])));
+        assert_eq!(inbox.current_note(), Some(Path::new("Inbox/a.md")));
+
+        inbox.skip();
+        inbox.filed_current();
+        assert_eq!(inbox.current_note(), Some(Path::new("Inbox/c.md")));
+        assert_eq!(inbox.filed, 1);
+
+        // Skipping past the end wraps to the notes left behind
+        inbox.skip();
+        assert_eq!(inbox.current_note(), Some(Path::new("Inbox/a.md")));
+
+        // Filing the last note in the list goes back to the start
+        inbox.select_next();
+        inbox.filed_current();
+        assert_eq!(inbox.current_note(), Some(Path::new("Inbox/a.md")));
+        inbox.filed_current();
+        assert_eq!(inbox.current_note(), None);
+        assert_eq!(inbox.filed, 3);
+    }
+
+    #[test]
+    fn reload_keeps_the_current_note() {
+        let mut inbox = InboxTriage::default();
+        inbox.set_notes(Ok(paths(&["a", "b", "c"])));
+        inbox.select_next();
+        inbox.set_notes(Ok(paths(&["0", "a", "b", "c"])));
+        assert_eq!(inbox.current_note(), Some(Path::new("Inbox/b.md")));
+
+        inbox.set_notes(Err("unreadable".to_string()));
+        assert_eq!(inbox.current_note(), None);
+        assert_eq!(inbox.error.as_deref(), Some("unreadable"));
+    }
+}
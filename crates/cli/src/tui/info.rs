@@ -0,0 +1,121 @@
+//! Details of the selected note from the index: frontmatter, heading
+//! outline, and the notes linking to it and linked from it.
+
+use std::path::PathBuf;
+
+use mdvault_core::index::{IndexedNote, NoteHeading};
+use serde_json::Value;
+
+/// A note at the other end of a link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkedNote {
+    /// Title of the note, or the link target when it is not in the index.
+    pub label: String,
+    /// `None` when the link does not resolve to an indexed note.
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteInfo {
+    pub path: PathBuf,
+    /// Frontmatter fields as displayed, by name.
+    pub fields: Vec<(String, String)>,
+    pub headings: Vec<NoteHeading>,
+    pub backlinks: Vec<LinkedNote>,
+    pub outgoing: Vec<LinkedNote>,
+}
+
+impl NoteInfo {
+    /// Details of `note`; a note linked more than once is listed once.
+    pub fn new(
+        note: &IndexedNote,
+        backlinks: Vec<LinkedNote>,
+        outgoing: Vec<LinkedNote>,
+    ) -> Self {
+        Self {
+            path: note.path.clone(),
+            fields: fields(note.frontmatter_json.as_deref()),
+            headings: note.headings(),
+            backlinks: dedup(backlinks),
+            outgoing: dedup(outgoing),
+        }
+    }
+}
+
+fn fields(json: Option<&str>) -> Vec<(String, String)> {
+    let Some(Value::Object(map)) = json.and_then(|j| serde_json::from_str(j).ok()) else {
+        return Vec::new();
+    };
+    map.into_iter().map(|(name, value)| (name, display(&value))).collect()
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(display).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+fn dedup(links: Vec<LinkedNote>) -> Vec<LinkedNote> {
+    let mut seen = Vec::new();
+    for link in links {
+        if !seen.contains(&link) {
+            seen.push(link);
+        }
+    }
+    seen
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use mdvault_core::index::NoteType;
+
+    use super::*;
+
+    #[test]
+    fn lists_fields_headings_and_links() {
+        let note = IndexedNote {
+            id: Some(1),
+            path: PathBuf::from("Projects/mcp.md"),
+            note_type: NoteType::Project,
+            title: "MCP".to_string(),
+            created: None,
+            modified: Utc::now(),
+            frontmatter_json: Some(
+                r#"{"status":"open","tags":["rust","tui"],"priority":2,"due":null}"#
+                    .to_string(),
+            ),
+            content_hash: String::new(),
+            word_count: 0,
+            heading_json: Some(r#"[{"level":1,"title":"MCP"}]"#.to_string()),
+        };
+        let linked = |label: &str, path: Option<&str>| LinkedNote {
+            label: label.to_string(),
+            path: path.map(PathBuf::from),
+        };
+        let info = NoteInfo::new(
+            &note,
+            vec![
+                linked("Daily", Some("daily/a.md")),
+                linked("Daily", Some("daily/a.md")),
+            ],
+            vec![linked("missing", None)],
+        );
+
+        assert_eq!(
+            info.fields,
+            [
+                ("due".to_string(), String::new()),
+                ("priority".to_string(), "2".to_string()),
+                ("status".to_string(), "open".to_string()),
+                ("tags".to_string(), "rust, tui".to_string()),
+            ]
+        );
+        assert_eq!(info.headings.len(), 1);
+        assert_eq!(info.backlinks, [linked("Daily", Some("daily/a.md"))]);
+        assert_eq!(info.outgoing, [linked("missing", None)]);
+    }
+}
@@ -0,0 +1,484 @@
+//! Configurable key bindings.
+//!
+//! Every rebindable key press is an [`Action`]; which message an action
+//! sends depends on the pane or mode (`execute` runs a palette item but
+//! opens a note). `[tui.keys]` in the config replaces the default keys of
+//! the actions it lists.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use mdvault_core::config::types::KeyBinding;
+
+/// Something a key can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Action {
+    SelectNext,
+    SelectPrev,
+    Left,
+    Right,
+    SwitchPane,
+    Execute,
+    Cancel,
+    Quit,
+    Search,
+    Open,
+    Capture,
+    Rename,
+    Graph,
+    ToggleView,
+    CycleType,
+    Today,
+    PrevMonth,
+    NextMonth,
+    GraphBack,
+    GraphDepth,
+    NewTask,
+    LogProject,
+    FocusProject,
+    StopFocus,
+    Assign,
+    Schedule,
+    Archive,
+    Delete,
+    Skip,
+    ScrollDown,
+    ScrollUp,
+    PageDown,
+    PageUp,
+    ToggleRaw,
+    ToggleInfo,
+    ShrinkList,
+    GrowList,
+    Edit,
+    Fix,
+    Undo,
+    Newline,
+    Submit,
+    Help,
+}
+
+impl Action {
+    pub const ALL: [Action; 43] = [
+        Action::SelectNext,
+        Action::SelectPrev,
+        Action::Left,
+        Action::Right,
+        Action::SwitchPane,
+        Action::Execute,
+        Action::Cancel,
+        Action::Quit,
+        Action::Search,
+        Action::Open,
+        Action::Capture,
+        Action::Rename,
+        Action::Graph,
+        Action::ToggleView,
+        Action::CycleType,
+        Action::Today,
+        Action::PrevMonth,
+        Action::NextMonth,
+        Action::GraphBack,
+        Action::GraphDepth,
+        Action::NewTask,
+        Action::LogProject,
+        Action::FocusProject,
+        Action::StopFocus,
+        Action::Assign,
+        Action::Schedule,
+        Action::Archive,
+        Action::Delete,
+        Action::Skip,
+        Action::ScrollDown,
+        Action::ScrollUp,
+        Action::PageDown,
+        Action::PageUp,
+        Action::ToggleRaw,
+        Action::ToggleInfo,
+        Action::ShrinkList,
+        Action::GrowList,
+        Action::Edit,
+        Action::Fix,
+        Action::Undo,
+        Action::Newline,
+        Action::Submit,
+        Action::Help,
+    ];
+
+    /// Name used in `[tui.keys]`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::SelectNext => "select_next",
+            Action::SelectPrev => "select_prev",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::SwitchPane => "switch_pane",
+            Action::Execute => "execute",
+            Action::Cancel => "cancel",
+            Action::Quit => "quit",
+            Action::Search => "search",
+            Action::Open => "open",
+            Action::Capture => "capture",
+            Action::Rename => "rename",
+            Action::Graph => "graph",
+            Action::ToggleView => "toggle_view",
+            Action::CycleType => "cycle_type",
+            Action::Today => "today",
+            Action::PrevMonth => "prev_month",
+            Action::NextMonth => "next_month",
+            Action::GraphBack => "graph_back",
+            Action::GraphDepth => "graph_depth",
+            Action::NewTask => "new_task",
+            Action::LogProject => "log_project",
+            Action::FocusProject => "focus_project",
+            Action::StopFocus => "stop_focus",
+            Action::Assign => "assign",
+            Action::Schedule => "schedule",
+            Action::Archive => "archive",
+            Action::Delete => "delete",
+            Action::Skip => "skip",
+            Action::ScrollDown => "scroll_down",
+            Action::ScrollUp => "scroll_up",
+            Action::PageDown => "page_down",
+            Action::PageUp => "page_up",
+            Action::ToggleRaw => "toggle_raw",
+            Action::ToggleInfo => "toggle_info",
+            Action::ShrinkList => "shrink_list",
+            Action::GrowList => "grow_list",
+            Action::Edit => "edit",
+            Action::Fix => "fix",
+            Action::Undo => "undo",
+            Action::Newline => "newline",
+            Action::Submit => "submit",
+            Action::Help => "help",
+        }
+    }
+
+    /// What the action does, for the help overlay.
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::SelectNext => "Next item (a week later in the calendar)",
+            Action::SelectPrev => "Previous item (a week earlier in the calendar)",
+            Action::Left => "Previous day / incoming links",
+            Action::Right => "Next day / outgoing links",
+            Action::SwitchPane => {
+                "Cycle home, palette, notes, search, calendar, projects and inbox"
+            }
+            Action::Execute => "Run the item, open the note, submit input",
+            Action::Cancel => "Cancel input, close the graph",
+            Action::Quit => "Quit",
+            Action::Search => {
+                "Search the index (filter in the palette and notes browser)"
+            }
+            Action::Open => "Open the note in $EDITOR",
+            Action::Capture => "Capture into the note",
+            Action::Rename => "Rename the note",
+            Action::Graph => "Explore the note's link graph",
+            Action::ToggleView => "Toggle tree and flat note list",
+            Action::CycleType => "Cycle the note type filter",
+            Action::Today => "Jump to today in the calendar, or open today's daily note",
+            Action::PrevMonth => "Previous month",
+            Action::NextMonth => "Next month",
+            Action::GraphBack => "Back to the previous note in the graph",
+            Action::GraphDepth => "Toggle one or two graph hops",
+            Action::NewTask => "Create a task in the project",
+            Action::LogProject => "Add an entry to the project's log",
+            Action::FocusProject => "Focus the project (mdv focus)",
+            Action::StopFocus => "Stop the focus session and log it to the daily note",
+            Action::Assign => "Move the inbox note into a project",
+            Action::Schedule => "Set the inbox note's due date",
+            Action::Archive => "Archive the inbox note",
+            Action::Delete => "Move the inbox note to the trash",
+            Action::Skip => "Leave the inbox note for later",
+            Action::ScrollDown => "Scroll the preview down",
+            Action::ScrollUp => "Scroll the preview up",
+            Action::PageDown => "Scroll the preview a page down",
+            Action::PageUp => "Scroll the preview a page up",
+            Action::ToggleRaw => "Toggle rendered and raw preview",
+            Action::ToggleInfo => "Show or hide the note details panel",
+            Action::ShrinkList => "Narrow the list, widening the preview",
+            Action::GrowList => "Widen the list, narrowing the preview",
+            Action::Edit => "Edit the selection or the file just written in $EDITOR",
+            Action::Fix => "Apply safe fixes to the note just written",
+            Action::Undo => "Revert the files the last run wrote",
+            Action::Newline => "Line break in a variable (then Enter adds them too)",
+            Action::Submit => "Submit a multi-line variable",
+            Action::Help => "Show this help",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|a| a.name() == name)
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::SelectNext => &["j", "Down"],
+            Action::SelectPrev => &["k", "Up"],
+            Action::Left => &["h", "Left"],
+            Action::Right => &["l", "Right"],
+            Action::SwitchPane => &["Tab"],
+            Action::Execute => &["Enter"],
+            Action::Cancel => &["Esc"],
+            Action::Quit => &["q"],
+            Action::Search => &["/"],
+            Action::Open => &["o"],
+            Action::Capture => &["c"],
+            Action::Rename => &["r"],
+            Action::Graph => &["g"],
+            Action::ToggleView => &["v"],
+            Action::CycleType => &["t"],
+            Action::Today => &["t"],
+            Action::PrevMonth => &["[", "PageUp"],
+            Action::NextMonth => &["]", "PageDown"],
+            Action::GraphBack => &["b", "Backspace"],
+            Action::GraphDepth => &["d"],
+            Action::NewTask => &["n"],
+            Action::LogProject => &["L"],
+            Action::FocusProject => &["f"],
+            Action::StopFocus => &["F"],
+            Action::Assign => &["p"],
+            Action::Schedule => &["s"],
+            Action::Archive => &["a"],
+            Action::Delete => &["d"],
+            Action::Skip => &["Space"],
+            Action::ScrollDown => &["J"],
+            Action::ScrollUp => &["K"],
+            Action::PageDown => &["PageDown"],
+            Action::PageUp => &["PageUp"],
+            Action::ToggleRaw => &["m"],
+            Action::ToggleInfo => &["i"],
+            Action::ShrinkList => &["<"],
+            Action::GrowList => &[">"],
+            Action::Edit => &["e"],
+            Action::Fix => &["f"],
+            Action::Undo => &["u"],
+            Action::Newline => &["Shift-Enter", "Alt-Enter"],
+            Action::Submit => &["Ctrl-d"],
+            Action::Help => &["?"],
+        }
+    }
+}
+
+/// A key with its Ctrl/Alt/Shift modifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    /// Parse a key as written in the config: a single character (`j`, `?`),
+    /// a named key (`Enter`, `PageDown`, `F5`), optionally prefixed with
+    /// `Ctrl-`, `Alt-` or `Shift-` (`Ctrl-n`, `C-n`, `M-x`, `Shift-Enter`).
+    pub fn parse(text: &str) -> Option<Key> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = text;
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            let prefix = ["ctrl-", "ctrl+", "c-"]
+                .into_iter()
+                .map(|p| (p, KeyModifiers::CONTROL))
+                .chain(["alt-", "alt+", "m-", "a-"].map(|p| (p, KeyModifiers::ALT)))
+                .chain(["shift-", "shift+"].map(|p| (p, KeyModifiers::SHIFT)))
+                .find(|(p, _)| lower.starts_with(p) && rest.len() > p.len());
+            let Some((p, modifier)) = prefix else {
+                break;
+            };
+            modifiers |= modifier;
+            rest = &rest[p.len()..];
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                f => KeyCode::F(f.strip_prefix('f')?.parse().ok()?),
+            },
+        };
+        Some(Key { code, modifiers })
+    }
+
+    fn matches(&self, event: KeyEvent) -> bool {
+        // Shift is part of a character itself (`J`) and of BackTab, so
+        // there only Ctrl and Alt have to agree; it tells Shift-Enter from
+        // Enter.
+        let mut relevant = KeyModifiers::CONTROL | KeyModifiers::ALT;
+        if !matches!(self.code, KeyCode::Char(_) | KeyCode::BackTab) {
+            relevant |= KeyModifiers::SHIFT;
+        }
+        self.code == event.code && self.modifiers & relevant == event.modifiers & relevant
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("Ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("Alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            f.write_str("Shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::PageUp => f.write_str("PgUp"),
+            KeyCode::PageDown => f.write_str("PgDn"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+
+/// Active key for every action.
+#[derive(Debug, Clone)]
+pub struct KeyMap {
+    bindings: BTreeMap<Action, Vec<Key>>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .into_iter()
+            .map(|action| {
+                let keys = action
+                    .default_keys()
+                    .iter()
+                    .map(|k| Key::parse(k).expect("default keys parse"))
+                    .collect();
+                (action, keys)
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl KeyMap {
+    /// Defaults with `[tui.keys]` applied on top.
+    pub fn from_config(keys: &BTreeMap<String, KeyBinding>) -> Result<Self, String> {
+        let mut map = Self::default();
+        for (name, binding) in keys {
+            let action = Action::from_name(name)
+                .ok_or_else(|| format!("tui.keys: unknown action '{name}'"))?;
+            let keys = binding
+                .keys()
+                .iter()
+                .map(|k| {
+                    Key::parse(k)
+                        .ok_or_else(|| format!("tui.keys.{name}: unrecognised key '{k}'"))
+                })
+                .collect::<Result<_, _>>()?;
+            map.bindings.insert(action, keys);
+        }
+        Ok(map)
+    }
+
+    /// Whether `event` is bound to `action`.
+    pub fn is(&self, action: Action, event: KeyEvent) -> bool {
+        self.bindings
+            .get(&action)
+            .is_some_and(|keys| keys.iter().any(|k| k.matches(event)))
+    }
+
+    /// Message of the first of `actions` bound to `event`.
+    pub fn lookup<M: Clone>(
+        &self,
+        event: KeyEvent,
+        actions: &[(Action, M)],
+    ) -> Option<M> {
+        actions.iter().find(|(action, _)| self.is(*action, event)).map(|(_, m)| m.clone())
+    }
+
+    /// All keys of `action`, e.g. `j, Down`.
+    pub fn keys(&self, action: Action) -> String {
+        let keys = self.bindings.get(&action).map(Vec::as_slice).unwrap_or_default();
+        keys.iter().map(Key::to_string).collect::<Vec<_>>().join(", ")
+    }
+
+    /// First key of `action`, for status bar hints.
+    pub fn key(&self, action: Action) -> String {
+        self.bindings
+            .get(&action)
+            .and_then(|keys| keys.first())
+            .map_or_else(|| "-".to_string(), Key::to_string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn parses_keys() {
+        let ctrl_n = Key::parse("Ctrl-n").unwrap();
+        assert!(ctrl_n.matches(press(KeyCode::Char('n'), KeyModifiers::CONTROL)));
+        assert!(!ctrl_n.matches(press(KeyCode::Char('n'), KeyModifiers::NONE)));
+        assert_eq!(Key::parse("C-n"), Some(ctrl_n));
+        assert_eq!(ctrl_n.to_string(), "Ctrl-n");
+
+        let shift_j = Key::parse("J").unwrap();
+        assert!(shift_j.matches(press(KeyCode::Char('J'), KeyModifiers::SHIFT)));
+        assert_eq!(Key::parse("pagedown").unwrap().to_string(), "PgDn");
+        assert_eq!(Key::parse("F5").unwrap().code, KeyCode::F(5));
+        assert_eq!(Key::parse("-").unwrap().code, KeyCode::Char('-'));
+        assert_eq!(Key::parse("Hyper"), None);
+
+        let shift_enter = Key::parse("Shift-Enter").unwrap();
+        assert!(shift_enter.matches(press(KeyCode::Enter, KeyModifiers::SHIFT)));
+        assert!(!shift_enter.matches(press(KeyCode::Enter, KeyModifiers::NONE)));
+        let enter = Key::parse("Enter").unwrap();
+        assert!(!enter.matches(press(KeyCode::Enter, KeyModifiers::SHIFT)));
+        assert_eq!(shift_enter.to_string(), "Shift-Enter");
+    }
+
+    #[test]
+    fn config_replaces_defaults() {
+        let keys = BTreeMap::from([
+            ("select_next".to_string(), KeyBinding::One("n".to_string())),
+            (
+                "quit".to_string(),
+                KeyBinding::Many(vec!["Q".to_string(), "Ctrl-q".to_string()]),
+            ),
+        ]);
+        let map = KeyMap::from_config(&keys).unwrap();
+        assert!(
+            map.is(Action::SelectNext, press(KeyCode::Char('n'), KeyModifiers::NONE))
+        );
+        assert!(
+            !map.is(Action::SelectNext, press(KeyCode::Char('j'), KeyModifiers::NONE))
+        );
+        assert!(
+            map.is(Action::SelectPrev, press(KeyCode::Char('k'), KeyModifiers::NONE))
+        );
+        assert_eq!(map.keys(Action::Quit), "Q, Ctrl-q");
+
+        let unknown = BTreeMap::from([("jump".to_string(), KeyBinding::One("x".into()))]);
+        assert!(KeyMap::from_config(&unknown).unwrap_err().contains("unknown action"));
+        let bad = BTreeMap::from([("quit".to_string(), KeyBinding::One("Hyper".into()))]);
+        assert!(KeyMap::from_config(&bad).unwrap_err().contains("'Hyper'"));
+    }
+}
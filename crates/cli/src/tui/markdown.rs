@@ -0,0 +1,329 @@
+//! Markdown to styled ratatui lines for the preview pane.
+//!
+//! Notes are parsed with comrak and each block becomes one or more lines:
+//! headings are coloured by level, lists keep their markers and nesting,
+//! code blocks and quotes are indented, and inline emphasis, code, links
+//! and wikilinks are styled. Wrapping is left to the `Paragraph` drawing
+//! the lines.
+
+use comrak::nodes::{AstNode, ListType, NodeValue};
+use comrak::{Arena, Options, parse_document};
+use ratatui::prelude::*;
+
+/// Render markdown into styled lines.
+pub fn render(content: &str) -> Vec<Line<'static>> {
+    let arena = Arena::new();
+    let root = parse_document(&arena, content, &options());
+    let mut renderer = Renderer::default();
+    renderer.block(root);
+    renderer.flush();
+    while renderer.lines.last().is_some_and(|l| l.width() == 0) {
+        renderer.lines.pop();
+    }
+    renderer.lines
+}
+
+fn options() -> Options<'static> {
+    let mut options = Options::default();
+    options.extension.strikethrough = true;
+    options.extension.table = true;
+    options.extension.autolink = true;
+    options.extension.tasklist = true;
+    options.extension.footnotes = true;
+    options.extension.wikilinks_title_after_pipe = true;
+    options.extension.front_matter_delimiter = Some("---".to_string());
+    options
+}
+
+fn heading_style(level: u8) -> Style {
+    match level {
+        1 => Style::default().fg(Color::Cyan).bold().underlined(),
+        2 => Style::default().fg(Color::Cyan).bold(),
+        _ => Style::default().fg(Color::Blue).bold(),
+    }
+}
+
+#[derive(Default)]
+struct Renderer {
+    lines: Vec<Line<'static>>,
+    /// Spans of the line being built.
+    spans: Vec<Span<'static>>,
+    /// Written before every line of the current block (quotes, list
+    /// continuation indent).
+    prefix: String,
+    /// Written instead of `prefix` before the next line (a list marker).
+    marker: Option<String>,
+}
+
+impl Renderer {
+    /// Finish the line being built, if any.
+    fn flush(&mut self) {
+        if self.spans.is_empty() && self.marker.is_none() {
+            return;
+        }
+        let lead = self.marker.take().unwrap_or_else(|| self.prefix.clone());
+        let mut spans = vec![Span::styled(lead, Style::default().fg(Color::DarkGray))];
+        spans.append(&mut self.spans);
+        self.lines.push(Line::from(spans));
+    }
+
+    /// Push a full line with the current prefix.
+    fn line(&mut self, text: String, style: Style) {
+        self.flush();
+        self.spans.push(Span::styled(text, style));
+        let lead = self.marker.take().unwrap_or_else(|| self.prefix.clone());
+        let mut spans = vec![Span::styled(lead, Style::default().fg(Color::DarkGray))];
+        spans.append(&mut self.spans);
+        self.lines.push(Line::from(spans));
+    }
+
+    /// Separate blocks with one empty line.
+    fn blank(&mut self) {
+        self.flush();
+        if self.lines.last().is_some_and(|l| l.width() > self.prefix.len()) {
+            self.lines.push(Line::from(Span::styled(
+                self.prefix.trim_end().to_string(),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+    }
+
+    fn block<'a>(&mut self, node: &'a AstNode<'a>) {
+        let value = node.data.borrow().value.clone();
+        match value {
+            NodeValue::FrontMatter(text) => {
+                let dim = Style::default().fg(Color::DarkGray);
+                for line in text.trim_end().lines() {
+                    self.line(line.to_string(), dim);
+                }
+                self.blank();
+            }
+            NodeValue::Heading(heading) => {
+                let style = heading_style(heading.level);
+                self.flush();
+                self.spans.push(Span::styled(
+                    format!("{} ", "#".repeat(heading.level as usize)),
+                    style.remove_modifier(Modifier::UNDERLINED),
+                ));
+                self.inlines(node, style);
+                self.blank();
+            }
+            NodeValue::Paragraph => {
+                self.inlines(node, Style::default());
+                if in_tight_list(node) {
+                    self.flush();
+                } else {
+                    self.blank();
+                }
+            }
+            NodeValue::List(list) => {
+                self.flush();
+                let outer = self.prefix.clone();
+                for (i, item) in node.children().enumerate() {
+                    let bullet = match list.list_type {
+                        ListType::Bullet => "- ".to_string(),
+                        ListType::Ordered => format!("{}. ", list.start + i),
+                    };
+                    let task = match &item.data.borrow().value {
+                        NodeValue::TaskItem(task) if task.symbol.is_some() => "[x] ",
+                        NodeValue::TaskItem(_) => "[ ] ",
+                        _ => "",
+                    };
+                    let marker = format!("{bullet}{task}");
+                    self.marker = Some(format!("{outer}{marker}"));
+                    self.prefix =
+                        format!("{outer}{}", " ".repeat(marker.chars().count()));
+                    for child in item.children() {
+                        self.block(child);
+                    }
+                    self.flush();
+                    self.prefix = outer.clone();
+                }
+                self.marker = None;
+                if !in_tight_list(node) {
+                    self.blank();
+                }
+            }
+            NodeValue::CodeBlock(code) => {
+                self.flush();
+                let style = Style::default().fg(Color::Yellow);
+                if !code.info.is_empty() {
+                    self.line(code.info.clone(), Style::default().fg(Color::DarkGray));
+                }
+                for line in code.literal.trim_end_matches('\n').lines() {
+                    self.line(format!("    {line}"), style);
+                }
+                self.blank();
+            }
+            NodeValue::BlockQuote
+            | NodeValue::MultilineBlockQuote(_)
+            | NodeValue::Alert(_) => {
+                self.flush();
+                let outer = self.prefix.clone();
+                self.prefix = format!("{outer}> ");
+                for child in node.children() {
+                    self.block(child);
+                }
+                self.flush();
+                while self.lines.last().is_some_and(|l| l.width() <= self.prefix.len()) {
+                    self.lines.pop();
+                }
+                self.prefix = outer;
+                self.blank();
+            }
+            NodeValue::ThematicBreak => {
+                self.line("─".repeat(24), Style::default().fg(Color::DarkGray));
+                self.blank();
+            }
+            NodeValue::Table(_) => {
+                for (i, row) in node.children().enumerate() {
+                    let style =
+                        if i == 0 { Style::default().bold() } else { Style::default() };
+                    for (j, cell) in row.children().enumerate() {
+                        let sep = if j == 0 { "| " } else { " | " };
+                        self.spans.push(Span::styled(
+                            sep,
+                            Style::default().fg(Color::DarkGray),
+                        ));
+                        self.inlines(cell, style);
+                    }
+                    self.spans
+                        .push(Span::styled(" |", Style::default().fg(Color::DarkGray)));
+                    self.flush();
+                }
+                self.blank();
+            }
+            NodeValue::HtmlBlock(html) => {
+                let dim = Style::default().fg(Color::DarkGray);
+                for line in html.literal.trim_end().lines() {
+                    self.line(line.to_string(), dim);
+                }
+                self.blank();
+            }
+            NodeValue::FootnoteDefinition(footnote) => {
+                self.flush();
+                self.marker = Some(format!("{}[^{}]: ", self.prefix, footnote.name));
+                for child in node.children() {
+                    self.block(child);
+                }
+                self.flush();
+            }
+            _ => {
+                for child in node.children() {
+                    self.block(child);
+                }
+            }
+        }
+    }
+
+    /// Render the inline children of `node` onto the current line.
+    fn inlines<'a>(&mut self, node: &'a AstNode<'a>, style: Style) {
+        for child in node.children() {
+            self.inline(child, style);
+        }
+    }
+
+    fn inline<'a>(&mut self, node: &'a AstNode<'a>, style: Style) {
+        let value = node.data.borrow().value.clone();
+        match value {
+            NodeValue::Text(text) => {
+                self.spans.push(Span::styled(text.into_owned(), style))
+            }
+            NodeValue::SoftBreak => self.spans.push(Span::styled(" ", style)),
+            NodeValue::LineBreak => self.flush(),
+            NodeValue::Code(code) => {
+                self.spans.push(Span::styled(code.literal, style.fg(Color::Yellow)));
+            }
+            NodeValue::Emph => self.inlines(node, style.italic()),
+            NodeValue::Strong => self.inlines(node, style.bold()),
+            NodeValue::Strikethrough => self.inlines(node, style.crossed_out()),
+            NodeValue::Underline => self.inlines(node, style.underlined()),
+            NodeValue::Link(_) => self.inlines(node, style.fg(Color::Blue).underlined()),
+            NodeValue::WikiLink(_) => self.inlines(node, style.fg(Color::Magenta).bold()),
+            NodeValue::Image(_) => {
+                self.spans.push(Span::styled("[image: ", style.fg(Color::DarkGray)));
+                self.inlines(node, style);
+                self.spans.push(Span::styled("]", style.fg(Color::DarkGray)));
+            }
+            NodeValue::HtmlInline(html) => {
+                self.spans.push(Span::styled(html, style.fg(Color::DarkGray)));
+            }
+            NodeValue::FootnoteReference(footnote) => {
+                self.spans.push(Span::styled(
+                    format!("[^{}]", footnote.name),
+                    style.fg(Color::DarkGray),
+                ));
+            }
+            NodeValue::Math(math) => self.spans.push(Span::styled(math.literal, style)),
+            _ => self.inlines(node, style),
+        }
+    }
+}
+
+/// Whether `node` sits directly in an item of a tight list, which has no
+/// blank lines between its items.
+fn in_tight_list<'a>(node: &'a AstNode<'a>) -> bool {
+    let Some(item) = node.parent() else {
+        return false;
+    };
+    if !matches!(item.data.borrow().value, NodeValue::Item(_) | NodeValue::TaskItem(_)) {
+        return false;
+    }
+    item.parent().is_some_and(
+        |list| matches!(list.data.borrow().value, NodeValue::List(l) if l.tight),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(lines: &[Line]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn blocks_become_lines() {
+        let lines = render(
+            "---\ntype: zettel\n---\n# Title\n\nSome **bold** and `code`.\n\n\
+             - one\n- [x] two\n  1. nested\n\n> quoted\n\n```rust\nfn main() {}\n```\n",
+        );
+        assert_eq!(
+            text(&lines),
+            [
+                "---",
+                "type: zettel",
+                "---",
+                "",
+                "# Title",
+                "",
+                "Some bold and code.",
+                "",
+                "- one",
+                "- [x] two",
+                "      1. nested",
+                "",
+                "> quoted",
+                "",
+                "rust",
+                "    fn main() {}",
+            ]
+        );
+    }
+
+    #[test]
+    fn inline_styles() {
+        let lines = render("A [[target|Wiki]] and *em* text\n");
+        let spans = &lines[0].spans;
+        let wiki = spans.iter().find(|s| s.content == "Wiki").unwrap();
+        assert_eq!(wiki.style.fg, Some(Color::Magenta));
+        let em = spans.iter().find(|s| s.content == "em").unwrap();
+        assert!(em.style.add_modifier.contains(Modifier::ITALIC));
+
+        let heading = render("## Section\n");
+        assert!(
+            heading[0].spans.iter().any(|s| s.content == "Section"
+                && s.style.add_modifier.contains(Modifier::BOLD))
+        );
+    }
+}
@@ -2,9 +2,25 @@
 
 mod actions;
 mod app;
+pub mod board;
+mod browser;
+mod calendar;
 pub mod dashboard;
 mod event;
+mod focus;
+mod graph;
+mod home;
+mod inbox;
+mod info;
+mod keys;
+mod markdown;
+mod palette;
+mod picker;
+mod project;
+mod search;
+mod session;
 mod ui;
+mod worker;
 
 use std::io;
 use std::path::Path;
@@ -12,10 +28,14 @@ use std::time::Duration;
 
 use color_eyre::eyre::Result;
 use crossterm::{
-    event::{Event, poll, read},
+    event::{
+        Event, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
+        PushKeyboardEnhancementFlags, poll, read,
+    },
     execute,
     terminal::{
         EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+        supports_keyboard_enhancement,
     },
 };
 use ratatui::prelude::*;
@@ -23,18 +43,32 @@ use ratatui::prelude::*;
 use mdvault_core::captures::CaptureRepository;
 use mdvault_core::config::loader::ConfigLoader;
 use mdvault_core::macros::MacroRepository;
+use mdvault_core::paths::PathResolver;
 use mdvault_core::templates::repository::TemplateRepository;
 
 use app::App;
 use event::map_key_event;
+use keys::KeyMap;
+use session::SessionState;
+
+use crate::display;
 
-/// Run the TUI application.
-pub fn run(config_path: Option<&Path>, profile: Option<&str>) -> Result<()> {
+/// Run the TUI application, or with `quick_capture` only the input of that
+/// capture (`mdv -c`).
+pub fn run(
+    config_path: Option<&Path>,
+    profile: Option<&str>,
+    quick_capture: Option<&str>,
+) -> Result<()> {
     // Load config (fail fast if config broken)
     let config = ConfigLoader::load(config_path, profile).map_err(|e| {
         color_eyre::eyre::eyre!("Configuration error: {e}\nRun 'mdv doctor' to diagnose.")
     })?;
 
+    // Key bindings are config too: a typo should not silently keep defaults
+    let keys = KeyMap::from_config(&config.tui.keys)
+        .map_err(|e| color_eyre::eyre::eyre!("Configuration error: {e}"))?;
+
     // Discover templates
     let templates = match TemplateRepository::new(&config.templates_dir) {
         Ok(repo) => repo.list_all().to_vec(),
@@ -62,15 +96,29 @@ pub fn run(config_path: Option<&Path>, profile: Option<&str>) -> Result<()> {
         }
     };
 
-    // Initialize app
-    let app = App::new(config, templates, captures, macros);
+    // Load indexed notes for the browser (an unbuilt index only empties it);
+    // a quick capture never shows them, so it skips the query
+    let notes = match quick_capture {
+        Some(_) => Ok(Vec::new()),
+        None => actions::load_notes(&config),
+    };
+
+    // Initialize app, back where the last session left off
+    let session_path = PathResolver::new(&config.vault_root).tui_state();
+    let mut app = App::new(config, keys, templates, captures, macros, notes);
+    match quick_capture {
+        Some(name) => app.start_quick_capture(name).map_err(|e| {
+            color_eyre::eyre::eyre!("{e}\nRun 'mdv capture --list' to see them.")
+        })?,
+        None => app.restore_session(SessionState::load(&session_path)),
+    }
 
     // Setup terminal
     let mut terminal = setup_terminal()?;
 
     // Run with cleanup on panic
     let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-        run_app(&mut terminal, app)
+        run_app(&mut terminal, app, config_path, profile, &session_path)
     }));
 
     // Always restore terminal
@@ -84,28 +132,72 @@ pub fn run(config_path: Option<&Path>, profile: Option<&str>) -> Result<()> {
 }
 
 fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let terminal = Terminal::new(backend)?;
+    let backend = CrosstermBackend::new(io::stdout());
+    let mut terminal = Terminal::new(backend)?;
+    resume_terminal(&mut terminal)?;
     Ok(terminal)
 }
 
+/// Raw mode and the alternate screen, also after handing the terminal to
+/// an editor. Terminals that support it report Shift-Enter apart from
+/// Enter, for line breaks in multi-line input.
+fn resume_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+    enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+    if supports_keyboard_enhancement().unwrap_or(false) {
+        execute!(
+            terminal.backend_mut(),
+            PushKeyboardEnhancementFlags(
+                KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+            )
+        )?;
+    }
+    Ok(())
+}
+
 fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+    if supports_keyboard_enhancement().unwrap_or(false) {
+        execute!(terminal.backend_mut(), PopKeyboardEnhancementFlags)?;
+    }
     disable_raw_mode()?;
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
     terminal.show_cursor()?;
     Ok(())
 }
 
+/// Rewrite a rendered frame for accessible mode: ASCII borders, bars, and
+/// symbols, and no dim grey text. Selection highlights become reverse video
+/// so they stay visible without relying on colour.
+pub(crate) fn make_accessible(buf: &mut Buffer) {
+    for cell in buf.content.iter_mut() {
+        if let Some(ascii) = cell.symbol().chars().next().and_then(display::ascii_glyph) {
+            cell.set_symbol(ascii);
+        }
+        if cell.fg == Color::DarkGray {
+            cell.fg = Color::Reset;
+        }
+        if cell.bg == Color::DarkGray {
+            cell.bg = Color::Reset;
+            cell.modifier |= Modifier::REVERSED;
+        }
+    }
+}
+
 fn run_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     mut app: App,
+    config_path: Option<&Path>,
+    profile: Option<&str>,
+    session_path: &Path,
 ) -> Result<()> {
     loop {
         // 1. Render current state
-        terminal.draw(|frame| ui::draw(frame, &app))?;
+        terminal.draw(|frame| {
+            ui::draw(frame, &app);
+            if display::accessible() {
+                make_accessible(frame.buffer_mut());
+            }
+        })?;
 
         // 2. Poll for events (with timeout for responsiveness)
         if poll(Duration::from_millis(100))?
@@ -118,8 +210,86 @@ fn run_app(
             }
         }
 
-        // 5. Check quit condition
+        // 5. Apply whatever the background job reported meanwhile
+        while let Some(msg) = app.poll_job() {
+            app.update(msg);
+        }
+
+        // 6. Hand the terminal to the editor if a note should be opened
+        if let Some(path) = app.take_open_request() {
+            restore_terminal(terminal)?;
+            let opened = crate::cmd::common::open_in_editor(&path);
+            resume_terminal(terminal)?;
+            terminal.clear()?;
+            if let Err(e) = opened {
+                app.status =
+                    Some(app::StatusMessage { text: e.to_string(), is_error: true });
+                app.mode = app::Mode::Result;
+            }
+            app.load_preview();
+        }
+
+        // 7. Same for a daily note, which is created first if missing
+        if let Some(date) = app.take_daily_request() {
+            restore_terminal(terminal)?;
+            let opened = crate::cmd::journal::ensure_note(
+                config_path,
+                profile,
+                &app.config,
+                crate::cmd::journal::Period::Day,
+                &date.format("%Y-%m-%d").to_string(),
+                true,
+            )
+            .and_then(|path| crate::cmd::common::open_in_editor(&path));
+            resume_terminal(terminal)?;
+            terminal.clear()?;
+            if let Err(e) = opened {
+                app.status =
+                    Some(app::StatusMessage { text: e.to_string(), is_error: true });
+                app.mode = app::Mode::Result;
+            }
+            app.reload_notes();
+            app.calendar.invalidate();
+            app.load_preview();
+        }
+
+        // 8. And for a task created from the project dashboard, so that
+        //    `mdv new task` can prompt for any other required fields
+        if let Some((project, title)) = app.take_task_request() {
+            restore_terminal(terminal)?;
+            let created = crate::cmd::new::run(
+                config_path,
+                profile,
+                crate::NewArgs {
+                    note_type: Some("task".to_string()),
+                    title: Some(title.clone()),
+                    template: None,
+                    output: None,
+                    vars: vec![("project".to_string(), project.clone())],
+                    batch: false,
+                    dry_run: false,
+                },
+            );
+            resume_terminal(terminal)?;
+            terminal.clear()?;
+            let (text, is_error) = match created {
+                Ok(()) => (format!("Created task in {project}: {title}"), false),
+                Err(e) => (e.to_string(), true),
+            };
+            app.status = Some(app::StatusMessage { text, is_error });
+            app.mode = app::Mode::Result;
+            app.reload_notes();
+            app.projects.invalidate();
+            app.load_preview();
+        }
+
+        // 9. Check quit condition
         if app.should_quit {
+            // Best effort: a read-only vault only costs the restored layout.
+            // A quick capture leaves the layout of the last session alone.
+            if !app.quick_capture {
+                let _ = app.session_state().save(session_path);
+            }
             return Ok(());
         }
     }
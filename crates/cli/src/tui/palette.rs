@@ -0,0 +1,188 @@
+//! Palette filter: fuzzy-matches item names and descriptions, keeping the
+//! templates, captures and macros groups.
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+
+use super::app::PaletteItem;
+
+/// A palette item that passes the filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaletteMatch {
+    /// Index into the palette items.
+    pub index: usize,
+    /// Character positions in the item name that matched the query.
+    pub highlights: Vec<usize>,
+}
+
+#[derive(Debug, Default)]
+pub struct PaletteFilter {
+    pub query: String,
+    /// Description of each item, matched along with its name.
+    descriptions: Vec<String>,
+    /// Items passing the filter: grouped by kind, best matches first
+    /// within a group.
+    visible: Vec<PaletteMatch>,
+}
+
+impl PaletteFilter {
+    pub fn new(items: &[PaletteItem], descriptions: Vec<String>) -> Self {
+        let mut filter = Self { descriptions, ..Default::default() };
+        filter.refilter(items);
+        filter
+    }
+
+    pub fn visible(&self) -> &[PaletteMatch] {
+        &self.visible
+    }
+
+    pub fn description(&self, index: usize) -> &str {
+        self.descriptions.get(index).map_or("", String::as_str)
+    }
+
+    pub fn is_visible(&self, index: usize) -> bool {
+        self.position(index).is_some()
+    }
+
+    /// The visible item after `index`, in display order.
+    pub fn next(&self, index: usize) -> Option<usize> {
+        let pos = self.position(index).map_or(0, |p| p + 1);
+        self.visible.get(pos).map(|m| m.index)
+    }
+
+    /// The visible item before `index`, in display order.
+    pub fn prev(&self, index: usize) -> Option<usize> {
+        let pos = self.position(index)?.checked_sub(1)?;
+        self.visible.get(pos).map(|m| m.index)
+    }
+
+    /// The first visible item, if any.
+    pub fn first(&self) -> Option<usize> {
+        self.visible.first().map(|m| m.index)
+    }
+
+    pub fn push_query(&mut self, c: char, items: &[PaletteItem]) {
+        self.query.push(c);
+        self.refilter(items);
+    }
+
+    pub fn pop_query(&mut self, items: &[PaletteItem]) {
+        self.query.pop();
+        self.refilter(items);
+    }
+
+    pub fn clear_query(&mut self, items: &[PaletteItem]) {
+        self.query.clear();
+        self.refilter(items);
+    }
+
+    pub fn set_query(&mut self, query: &str, items: &[PaletteItem]) {
+        self.query = query.to_string();
+        self.refilter(items);
+    }
+
+    fn position(&self, index: usize) -> Option<usize> {
+        self.visible.iter().position(|m| m.index == index)
+    }
+
+    /// Recompute the visible items from the query.
+    fn refilter(&mut self, items: &[PaletteItem]) {
+        let matcher = SkimMatcherV2::default().ignore_case();
+        let query = self.query.trim();
+
+        let mut scored: Vec<(usize, i64, PaletteMatch)> = items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| {
+                if query.is_empty() {
+                    return Some((
+                        index,
+                        0,
+                        PaletteMatch { index, highlights: Vec::new() },
+                    ));
+                }
+                let name = item.name();
+                let haystack = format!("{name} {}", self.description(index));
+                let (score, indices) = matcher.fuzzy_indices(&haystack, query)?;
+                let name_len = name.chars().count();
+                let highlights = indices.into_iter().filter(|&i| i < name_len).collect();
+                Some((index, score, PaletteMatch { index, highlights }))
+            })
+            .collect();
+        // Items are stored grouped by kind, so sorting on the kind keeps
+        // the groups
+        scored.sort_by(|a, b| {
+            kind_rank(&items[a.0])
+                .cmp(&kind_rank(&items[b.0]))
+                .then(b.1.cmp(&a.1))
+                .then(a.0.cmp(&b.0))
+        });
+        self.visible = scored.into_iter().map(|(.., m)| m).collect();
+    }
+}
+
+fn kind_rank(item: &PaletteItem) -> u8 {
+    match item {
+        PaletteItem::Template(_) => 0,
+        PaletteItem::Capture(_) => 1,
+        PaletteItem::Macro(_) => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use mdvault_core::captures::{CaptureFormat, CaptureInfo};
+    use mdvault_core::templates::discovery::TemplateInfo;
+
+    use super::*;
+
+    fn items() -> Vec<PaletteItem> {
+        let template = |name: &str| {
+            PaletteItem::Template(TemplateInfo {
+                logical_name: name.to_string(),
+                path: PathBuf::from(name),
+            })
+        };
+        let capture = |name: &str| {
+            PaletteItem::Capture(CaptureInfo {
+                logical_name: name.to_string(),
+                path: PathBuf::from(name),
+                format: CaptureFormat::Lua,
+            })
+        };
+        vec![template("daily"), template("meeting"), capture("inbox"), capture("todo")]
+    }
+
+    #[test]
+    fn filters_names_and_descriptions_by_group() {
+        let items = items();
+        let descriptions = vec![
+            String::new(),
+            "Meeting notes with attendees".to_string(),
+            "Quick thought into the inbox".to_string(),
+            "Add a todo to today's daily note".to_string(),
+        ];
+        let mut filter = PaletteFilter::new(&items, descriptions);
+        assert_eq!(filter.visible().len(), 4);
+
+        for c in "daily".chars() {
+            filter.push_query(c, &items);
+        }
+        // The template matches by name, the capture by description only
+        let indices: Vec<usize> = filter.visible().iter().map(|m| m.index).collect();
+        assert_eq!(indices, [0, 3]);
+        assert_eq!(filter.visible()[0].highlights, [0, 1, 2, 3, 4]);
+        assert!(filter.visible()[1].highlights.is_empty());
+
+        assert_eq!(filter.first(), Some(0));
+        assert_eq!(filter.next(0), Some(3));
+        assert_eq!(filter.next(3), None);
+        assert_eq!(filter.prev(3), Some(0));
+        assert!(!filter.is_visible(1));
+
+        filter.clear_query(&items);
+        assert_eq!(filter.visible().len(), 4);
+    }
+}
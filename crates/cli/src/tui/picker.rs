@@ -0,0 +1,170 @@
+//! Picker for variables with a fixed set of values: a list narrowed by
+//! typing, so the value cannot be mistyped.
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+
+#[derive(Debug)]
+pub struct Picker {
+    options: Vec<String>,
+    /// Indices of the options matching the query, best first.
+    visible: Vec<usize>,
+    /// Position of the selected option in `visible`.
+    pub selected: usize,
+    /// For a multi-select picker, whether each option is chosen.
+    chosen: Option<Vec<bool>>,
+}
+
+impl Picker {
+    /// A picker over `options`, starting on `default` when it is one of them.
+    pub fn new(options: Vec<String>, default: Option<&str>) -> Self {
+        let selected =
+            default.and_then(|d| options.iter().position(|o| o == d)).unwrap_or(0);
+        let visible = (0..options.len()).collect();
+        Self { options, visible, selected, chosen: None }
+    }
+
+    /// A picker where any number of `options` can be chosen, starting with
+    /// those in `defaults`.
+    pub fn multi(options: Vec<String>, defaults: &[&str]) -> Self {
+        let chosen = options.iter().map(|o| defaults.contains(&o.as_str())).collect();
+        Self { chosen: Some(chosen), ..Self::new(options, None) }
+    }
+
+    /// Whether any number of options can be chosen.
+    pub fn is_multi(&self) -> bool {
+        self.chosen.is_some()
+    }
+
+    /// Whether the option is chosen in a multi-select picker.
+    pub fn is_chosen(&self, option: &str) -> bool {
+        let index = self.options.iter().position(|o| o == option);
+        match (&self.chosen, index) {
+            (Some(chosen), Some(i)) => chosen[i],
+            _ => false,
+        }
+    }
+
+    /// Choose the selected option of a multi-select picker, or unchoose it.
+    pub fn toggle(&mut self) {
+        if let (Some(chosen), Some(&i)) =
+            (&mut self.chosen, self.visible.get(self.selected))
+        {
+            chosen[i] = !chosen[i];
+        }
+    }
+
+    /// The chosen options of a multi-select picker, comma-separated in
+    /// their listed order.
+    pub fn chosen_value(&self) -> String {
+        let chosen = self.chosen.as_deref().unwrap_or_default();
+        self.options
+            .iter()
+            .zip(chosen)
+            .filter(|(_, chosen)| **chosen)
+            .map(|(option, _)| option.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Options matching the query, in display order.
+    pub fn visible(&self) -> impl Iterator<Item = &str> {
+        self.visible.iter().map(|&i| self.options[i].as_str())
+    }
+
+    pub fn visible_len(&self) -> usize {
+        self.visible.len()
+    }
+
+    pub fn total(&self) -> usize {
+        self.options.len()
+    }
+
+    /// The selected option, if any matches the query.
+    pub fn value(&self) -> Option<&str> {
+        self.visible.get(self.selected).map(|&i| self.options[i].as_str())
+    }
+
+    /// Narrow the options to those fuzzy-matching `query`.
+    pub fn set_query(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() {
+            self.visible = (0..self.options.len()).collect();
+        } else {
+            let matcher = SkimMatcherV2::default().ignore_case();
+            let mut scored: Vec<(i64, usize)> = self
+                .options
+                .iter()
+                .enumerate()
+                .filter_map(|(i, option)| {
+                    matcher.fuzzy_match(option, query).map(|s| (s, i))
+                })
+                .collect();
+            scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+            self.visible = scored.into_iter().map(|(_, i)| i).collect();
+        }
+        self.selected = 0;
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.visible.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> Vec<String> {
+        ["todo", "doing", "blocked", "done"].map(String::from).to_vec()
+    }
+
+    #[test]
+    fn starts_on_default_and_narrows_by_query() {
+        let mut picker = Picker::new(options(), Some("blocked"));
+        assert_eq!(picker.value(), Some("blocked"));
+
+        // A default that is not an option falls back to the first
+        assert_eq!(Picker::new(options(), Some("later")).value(), Some("todo"));
+
+        picker.set_query("do");
+        assert_eq!(picker.visible_len(), 3);
+        assert_eq!(picker.value(), picker.visible().next());
+        picker.select_next();
+        picker.select_next();
+        picker.select_next();
+        assert_eq!(picker.selected, 2);
+        picker.select_prev();
+        assert_eq!(picker.selected, 1);
+
+        picker.set_query("xyz");
+        assert_eq!(picker.value(), None);
+
+        picker.set_query("");
+        assert_eq!(picker.visible_len(), picker.total());
+    }
+
+    #[test]
+    fn multi_select_toggles_options() {
+        let mut picker = Picker::multi(options(), &["done"]);
+        assert!(picker.is_multi());
+        assert!(!Picker::new(options(), None).is_multi());
+        assert_eq!(picker.chosen_value(), "done");
+
+        picker.toggle();
+        picker.set_query("block");
+        picker.toggle();
+        assert!(picker.is_chosen("blocked"));
+        assert_eq!(picker.chosen_value(), "todo, blocked, done");
+
+        picker.set_query("");
+        picker.toggle();
+        assert_eq!(picker.chosen_value(), "blocked, done");
+    }
+}
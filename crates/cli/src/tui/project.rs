@@ -0,0 +1,119 @@
+//! Project dashboard state: every active project's progress, with the
+//! focused one (see `mdv focus`) selected when the pane opens.
+
+use crate::cmd::project::ProjectProgress;
+
+#[derive(Default)]
+pub struct ProjectDashboard {
+    /// Progress of each project, as in `mdv project progress`.
+    pub projects: Vec<ProjectProgress>,
+    /// Index of the selected project.
+    pub selected: usize,
+    /// Project ID of the active focus, if any.
+    pub focused: Option<String>,
+    loaded: bool,
+}
+
+impl ProjectDashboard {
+    /// Whether projects have to be (re)loaded before showing the pane.
+    pub fn needs_load(&self) -> bool {
+        !self.loaded
+    }
+
+    /// Reload projects next time the pane is shown.
+    pub fn invalidate(&mut self) {
+        self.loaded = false;
+    }
+
+    /// Replace the projects, keeping the selected one if it still exists
+    /// and otherwise selecting the focused project.
+    pub fn set_projects(
+        &mut self,
+        projects: Vec<ProjectProgress>,
+        focused: Option<String>,
+    ) {
+        let previous = self.selected_project().map(|p| p.id.clone());
+        self.projects = projects;
+        self.focused = focused;
+        self.loaded = true;
+        self.selected = previous
+            .as_deref()
+            .or(self.focused.as_deref())
+            .and_then(|id| {
+                self.projects.iter().position(|p| p.id.eq_ignore_ascii_case(id))
+            })
+            .unwrap_or(0);
+    }
+
+    pub fn selected_project(&self) -> Option<&ProjectProgress> {
+        self.projects.get(self.selected)
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.projects.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Whether `project` is the active focus.
+    pub fn is_focused(&self, project: &ProjectProgress) -> bool {
+        self.focused.as_deref().is_some_and(|f| project.id.eq_ignore_ascii_case(f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::project::TaskCounts;
+
+    fn project(id: &str) -> ProjectProgress {
+        ProjectProgress {
+            id: id.to_string(),
+            title: id.to_string(),
+            kind: "project".to_string(),
+            status: "open".to_string(),
+            tasks: TaskCounts {
+                total: 0,
+                done: 0,
+                in_progress: 0,
+                todo: 0,
+                blocked: 0,
+                cancelled: 0,
+            },
+            progress_percent: 0.0,
+            recent_completions: Vec::new(),
+            velocity: 0.0,
+        }
+    }
+
+    #[test]
+    fn selects_focus_then_keeps_selection() {
+        let mut dashboard = ProjectDashboard::default();
+        assert!(dashboard.needs_load());
+
+        dashboard.set_projects(
+            vec![project("ABC"), project("MCP"), project("XYZ")],
+            Some("mcp".to_string()),
+        );
+        assert_eq!(dashboard.selected_project().unwrap().id, "MCP");
+        assert!(dashboard.is_focused(&dashboard.projects[1]));
+
+        dashboard.select_next();
+        dashboard.select_next();
+        assert_eq!(dashboard.selected, 2);
+
+        // A reload keeps the selected project, even after focus moved
+        dashboard
+            .set_projects(vec![project("ABC"), project("XYZ")], Some("ABC".to_string()));
+        assert_eq!(dashboard.selected_project().unwrap().id, "XYZ");
+
+        dashboard.set_projects(vec![project("ABC")], None);
+        assert_eq!(dashboard.selected, 0);
+        dashboard.select_prev();
+        assert_eq!(dashboard.selected, 0);
+    }
+}
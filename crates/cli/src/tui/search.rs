@@ -0,0 +1,107 @@
+//! Live search state: the query being typed and its ranked results.
+
+use std::path::Path;
+
+use mdvault_core::index::SearchResult;
+
+/// Search pane state.
+#[derive(Default)]
+pub struct NoteSearch {
+    pub query: String,
+    /// Results for `query`, best first.
+    pub results: Vec<SearchResult>,
+    /// Why the last search failed, if it did.
+    pub error: Option<String>,
+    /// Index into `results`.
+    pub selected: usize,
+}
+
+impl NoteSearch {
+    /// Replace the results, keeping the selection on the same note if it
+    /// is still among them.
+    pub fn set_results(&mut self, results: Result<Vec<SearchResult>, String>) {
+        let current = self.selected_path().map(Path::to_path_buf);
+        match results {
+            Ok(results) => {
+                self.results = results;
+                self.error = None;
+            }
+            Err(e) => {
+                self.results.clear();
+                self.error = Some(e);
+            }
+        }
+        self.selected = current
+            .and_then(|path| self.results.iter().position(|r| r.note.path == path))
+            .unwrap_or(0);
+    }
+
+    pub fn selected_result(&self) -> Option<&SearchResult> {
+        self.results.get(self.selected)
+    }
+
+    /// Vault-relative path of the selected result.
+    pub fn selected_path(&self) -> Option<&Path> {
+        self.selected_result().map(|r| r.note.path.as_path())
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.results.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use mdvault_core::index::{IndexedNote, MatchSource, NoteType};
+    use std::path::PathBuf;
+
+    fn result(path: &str) -> SearchResult {
+        SearchResult {
+            note: IndexedNote {
+                id: None,
+                path: PathBuf::from(path),
+                note_type: NoteType::None,
+                title: String::new(),
+                created: None,
+                modified: Utc::now(),
+                frontmatter_json: None,
+                content_hash: String::new(),
+                word_count: 0,
+                heading_json: None,
+            },
+            score: 1.0,
+            match_source: MatchSource::Direct,
+            staleness: None,
+            snippet: None,
+            hits: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn selection_follows_the_note_across_searches() {
+        let mut search = NoteSearch::default();
+        search.set_results(Ok(vec![result("a.md"), result("b.md"), result("c.md")]));
+        search.select_next();
+        search.select_next();
+        search.select_next();
+        assert_eq!(search.selected_path(), Some(Path::new("c.md")));
+
+        // Typing another letter reorders the results
+        search.set_results(Ok(vec![result("c.md"), result("a.md")]));
+        assert_eq!(search.selected_path(), Some(Path::new("c.md")));
+        search.set_results(Ok(vec![result("a.md")]));
+        assert_eq!(search.selected, 0);
+
+        search.set_results(Err("bad query".to_string()));
+        assert!(search.selected_result().is_none());
+        assert_eq!(search.error.as_deref(), Some("bad query"));
+    }
+}
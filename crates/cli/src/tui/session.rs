@@ -0,0 +1,119 @@
+//! Layout and session state remembered across runs: the pane, filters,
+//! list width, and details panel, kept in `.mdvault/state/tui.toml`.
+
+use std::fs;
+use std::path::Path;
+
+use mdvault_core::index::NoteType;
+use serde::{Deserialize, Serialize};
+
+use super::app::Pane;
+use super::browser::BrowserView;
+
+/// Narrowest and widest the list pane can be, in percent of the body.
+pub const MIN_LIST_WIDTH: u16 = 15;
+pub const MAX_LIST_WIDTH: u16 = 70;
+
+/// How much one resize key press moves the split, in percent.
+pub const LIST_WIDTH_STEP: i16 = 5;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionState {
+    /// Pane shown when the TUI opens.
+    pub pane: Pane,
+    /// Palette filter query.
+    pub palette_query: String,
+    /// Notes browser filter query, type filter, and layout.
+    pub notes_query: String,
+    pub notes_type: Option<NoteType>,
+    pub notes_view: BrowserView,
+    /// Live search query.
+    pub search_query: String,
+    /// Width of the list pane, in percent of the body.
+    pub list_width: u16,
+    /// Details panel beside note previews.
+    pub show_info: bool,
+}
+
+impl Default for SessionState {
+    fn default() -> Self {
+        Self {
+            pane: Pane::Palette,
+            palette_query: String::new(),
+            notes_query: String::new(),
+            notes_type: None,
+            notes_view: BrowserView::Tree,
+            search_query: String::new(),
+            list_width: 30,
+            show_info: true,
+        }
+    }
+}
+
+impl SessionState {
+    /// The state saved at `path`; defaults when there is none or it cannot
+    /// be read, since losing it should never stop the TUI from starting.
+    pub fn load(path: &Path) -> Self {
+        let state: Self = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| toml::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { list_width: resize(state.list_width, 0), ..state }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize TUI state: {e}"))?;
+        fs::write(path, content)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    }
+}
+
+/// `width` moved by `delta` percent, kept within the allowed range.
+pub fn resize(width: u16, delta: i16) -> u16 {
+    width.saturating_add_signed(delta).clamp(MIN_LIST_WIDTH, MAX_LIST_WIDTH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_and_restores_state() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("state/tui.toml");
+
+        // Nothing saved yet
+        assert_eq!(SessionState::load(&path), SessionState::default());
+
+        let state = SessionState {
+            pane: Pane::Notes,
+            notes_query: "meeting".to_string(),
+            notes_type: Some(NoteType::Task),
+            notes_view: BrowserView::Flat,
+            list_width: 45,
+            show_info: false,
+            ..SessionState::default()
+        };
+        state.save(&path).unwrap();
+        assert_eq!(SessionState::load(&path), state);
+
+        // Unknown or out-of-range values fall back rather than fail
+        fs::write(&path, "pane = \"elsewhere\"\n").unwrap();
+        assert_eq!(SessionState::load(&path), SessionState::default());
+        fs::write(&path, "list_width = 95\n").unwrap();
+        assert_eq!(SessionState::load(&path).list_width, MAX_LIST_WIDTH);
+    }
+
+    #[test]
+    fn resize_stays_in_range() {
+        assert_eq!(resize(30, LIST_WIDTH_STEP), 35);
+        assert_eq!(resize(MIN_LIST_WIDTH, -LIST_WIDTH_STEP), MIN_LIST_WIDTH);
+        assert_eq!(resize(MAX_LIST_WIDTH, LIST_WIDTH_STEP), MAX_LIST_WIDTH);
+    }
+}
@@ -0,0 +1,83 @@
+//! Calendar pane rendering (month grid shaded by activity).
+
+use chrono::Datelike;
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::tui::app::App;
+
+/// Day colours from no activity to the busiest.
+const DENSITY_COLORS: [Color; 4] = [Color::Gray, Color::Green, Color::Yellow, Color::Red];
+
+pub fn draw(frame: &mut Frame, area: Rect, app: &App) {
+    let calendar = &app.calendar;
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "  Mo Tu We Th Fr Sa Su",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    for week in calendar.weeks() {
+        let mut spans = vec![Span::raw("  ")];
+        for day in week {
+            let Some(day) = day else {
+                spans.push(Span::raw("   "));
+                continue;
+            };
+            let mut style = Style::default().fg(DENSITY_COLORS[calendar.density(day)]);
+            if calendar.density(day) > 0 {
+                style = style.bold();
+            }
+            if day == calendar.today {
+                style = style.underlined();
+            }
+            if day == calendar.selected {
+                style = style.bg(Color::DarkGray).fg(Color::White);
+            }
+            spans.push(Span::styled(format!("{:>2}", day.day()), style));
+            spans.push(Span::raw(" "));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    lines.push(Line::from(""));
+    let count = calendar.count(calendar.selected);
+    lines.push(Line::from(vec![
+        Span::raw("  "),
+        Span::styled(
+            calendar.selected.format("%a %d %b").to_string(),
+            Style::default().fg(Color::Cyan).bold(),
+        ),
+        Span::styled(
+            format!("  {count} logged {}", if count == 1 { "entry" } else { "entries" }),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]));
+
+    let mut legend = vec![Span::styled("  less ", Style::default().fg(Color::DarkGray))];
+    for color in DENSITY_COLORS {
+        legend.push(Span::styled("# ", Style::default().fg(color).bold()));
+    }
+    legend.push(Span::styled("more", Style::default().fg(Color::DarkGray)));
+    lines.push(Line::from(legend));
+
+    if let Some(e) = &calendar.error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("  {e}"),
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .title(calendar.selected.format("%B %Y").to_string())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::DarkGray)),
+    );
+    frame.render_widget(paragraph, area);
+}
@@ -0,0 +1,129 @@
+//! Link graph rendering: backlinks, the centre note and outgoing links
+//! side by side.
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, ListItem, Paragraph, Wrap},
+};
+
+use super::list;
+use crate::tui::app::App;
+use crate::tui::graph::{LinkGraph, Side};
+
+pub fn draw(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(graph) = &app.graph else {
+        return;
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(35), // Links here
+            Constraint::Percentage(30), // Centre
+            Constraint::Percentage(35), // Links from here
+        ])
+        .split(area);
+
+    draw_side(frame, chunks[0], graph, Side::Incoming);
+    draw_center(frame, chunks[1], graph);
+    draw_side(frame, chunks[2], graph, Side::Outgoing);
+}
+
+fn draw_center(frame: &mut Frame, area: Rect, graph: &LinkGraph) {
+    let note = &graph.center;
+    let title = if note.title.is_empty() {
+        note.path.display().to_string()
+    } else {
+        note.title.clone()
+    };
+    let direct = |side| graph.nodes(side).iter().filter(|n| n.depth == 1).count();
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("<-- {} link here", direct(Side::Incoming)),
+            Style::default().fg(Color::Magenta),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(title, Style::default().fg(Color::White).bold())),
+        Line::from(Span::styled(
+            note.path.display().to_string(),
+            Style::default().fg(Color::DarkGray),
+        )),
+        Line::from(Span::styled(
+            note.note_type.as_str().to_string(),
+            Style::default().fg(Color::DarkGray),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("links to {} -->", direct(Side::Outgoing)),
+            Style::default().fg(Color::Cyan),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("{} hop{}", graph.depth, if graph.depth == 1 { "" } else { "s" }),
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    if !graph.history.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!("{} back", graph.history.len()),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let paragraph = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .title("Note")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::White)),
+        );
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_side(frame: &mut Frame, area: Rect, graph: &LinkGraph, side: Side) {
+    let nodes = graph.nodes(side);
+    let focused = graph.side == side;
+    let selected = focused.then_some(graph.row(side)).filter(|_| !nodes.is_empty());
+    let color = match side {
+        Side::Incoming => Color::Magenta,
+        Side::Outgoing => Color::Cyan,
+    };
+
+    let items: Vec<ListItem> = nodes
+        .iter()
+        .enumerate()
+        .map(|(row, node)| {
+            let is_selected = selected == Some(row);
+            let style = if is_selected {
+                Style::default().bg(Color::DarkGray).fg(Color::White)
+            } else if node.depth > 1 {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default()
+            };
+            let prefix = if is_selected { " > " } else { "   " };
+            let indent = if node.depth > 1 { "  + " } else { "" };
+            let label = if node.title.is_empty() {
+                node.path.display().to_string()
+            } else {
+                node.title.clone()
+            };
+            ListItem::new(format!("{prefix}{indent}{label}")).style(style)
+        })
+        .collect();
+
+    let title = match side {
+        Side::Incoming => format!("Links here {}", nodes.len()),
+        Side::Outgoing => format!("Links from here {}", nodes.len()),
+    };
+    let border = if focused { color } else { Color::DarkGray };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(border));
+    list::draw(frame, area, block, items, selected, " (none)");
+}
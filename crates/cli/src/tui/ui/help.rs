@@ -0,0 +1,80 @@
+//! Key binding help overlay for the current mode and pane.
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::tui::app::{App, Mode, Pane};
+use crate::tui::event;
+
+/// Draw the bindings of the actions that work here over the centre of
+/// `area`.
+pub fn draw(frame: &mut Frame, area: Rect, app: &App) {
+    let rows: Vec<(String, &str, &str)> = event::active_actions(app)
+        .into_iter()
+        .map(|action| (app.keys.keys(action), action.name(), action.description()))
+        .collect();
+    let keys_width = rows.iter().map(|(keys, ..)| keys.len()).max().unwrap_or(0);
+    let name_width = rows.iter().map(|(_, name, _)| name.len()).max().unwrap_or(0);
+
+    let mut lines: Vec<Line> = rows
+        .into_iter()
+        .map(|(keys, name, description)| {
+            Line::from(vec![
+                Span::styled(
+                    format!(" {keys:<keys_width$}  "),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(
+                    format!("{name:<name_width$}  "),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::raw(description),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        " Any key closes this help",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let width =
+        (lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 3).min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .title(format!(" Keys: {} ([tui.keys] in config) ", context(app)))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(paragraph, popup);
+}
+
+/// Where the keys apply, for the title.
+fn context(app: &App) -> &'static str {
+    match app.mode {
+        Mode::Graph => "graph",
+        Mode::Running => "running",
+        Mode::Result => "result",
+        _ => match app.pane {
+            Pane::Home => "home",
+            Pane::Palette => "palette",
+            Pane::Notes => "notes",
+            Pane::Search => "search",
+            Pane::Calendar => "calendar",
+            Pane::Project => "projects",
+            Pane::Inbox => "inbox",
+        },
+    }
+}
@@ -0,0 +1,160 @@
+//! Start screen rendering (today's status, recent notes, tasks in
+//! progress, and the keys to get going).
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, ListItem, Paragraph},
+};
+
+use mdvault_core::index::IndexedNote;
+
+use super::list;
+use crate::tui::app::App;
+use crate::tui::keys::Action;
+
+pub fn draw(frame: &mut Frame, area: Rect, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(4), Constraint::Min(3), Constraint::Length(4)])
+        .split(area);
+
+    draw_today(frame, chunks[0], app);
+    draw_notes(frame, chunks[1], app);
+    draw_quick_actions(frame, chunks[2], app);
+}
+
+fn draw_today(frame: &mut Frame, area: Rect, app: &App) {
+    let dim = Style::default().fg(Color::DarkGray);
+    let lines = match &app.home.today {
+        Some(today) => {
+            let daily = match &today.daily {
+                Some(_) => {
+                    Span::styled("daily note written", Style::default().fg(Color::Green))
+                }
+                None => {
+                    Span::styled("no daily note yet", Style::default().fg(Color::Yellow))
+                }
+            };
+            vec![
+                Line::from(vec![
+                    Span::styled(
+                        today.date.format("%a %-d %b").to_string(),
+                        Style::default().bold(),
+                    ),
+                    Span::styled(" · ", dim),
+                    daily,
+                ]),
+                Line::from(Span::styled(
+                    format!(
+                        "{} activity entries · {} tasks completed",
+                        today.activity, today.completed
+                    ),
+                    dim,
+                )),
+            ]
+        }
+        None => Vec::new(),
+    };
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .title("Today")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::DarkGray)),
+    );
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_notes(frame: &mut Frame, area: Rect, app: &App) {
+    let home = &app.home;
+    let heading = |text: &'static str| {
+        ListItem::new(Span::styled(text, Style::default().fg(Color::Cyan).bold()))
+    };
+    // Headings are not selectable, so track which row the selection is on
+    let mut items = vec![heading(" Recent")];
+    let mut selected_row = None;
+    let mut row = |items: &mut Vec<ListItem>, i: usize, note: &IndexedNote| {
+        if i == home.selected {
+            selected_row = Some(items.len());
+        }
+        items.push(note_item(note, i == home.selected));
+    };
+
+    if let Some(e) = &home.error {
+        items.push(list::placeholder(format!(" {e}")));
+    } else {
+        if home.recent.is_empty() {
+            items.push(list::placeholder("   (no notes yet)"));
+        }
+        for (i, note) in home.recent.iter().enumerate() {
+            row(&mut items, i, note);
+        }
+        items.push(ListItem::new(""));
+        items.push(heading(" In progress"));
+        if home.tasks.is_empty() {
+            items.push(list::placeholder("   (no tasks in progress)"));
+        }
+        for (i, note) in home.tasks.iter().enumerate() {
+            row(&mut items, home.recent.len() + i, note);
+        }
+    }
+
+    // Never empty: the headings are always there
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray));
+    list::draw(frame, area, block, items, selected_row, "");
+}
+
+fn note_item(note: &IndexedNote, is_selected: bool) -> ListItem<'static> {
+    let style = if is_selected {
+        Style::default().bg(Color::DarkGray).fg(Color::White)
+    } else {
+        Style::default()
+    };
+    let prefix = if is_selected { " > " } else { "   " };
+    let name = if note.title.is_empty() {
+        note.path.display().to_string()
+    } else {
+        note.title.clone()
+    };
+    ListItem::new(Line::from(vec![
+        Span::raw(prefix),
+        Span::raw(name),
+        Span::styled(
+            format!("  {}", note.note_type.as_str()),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]))
+    .style(style)
+}
+
+fn draw_quick_actions(frame: &mut Frame, area: Rect, app: &App) {
+    let hint = |action: Action, label: &'static str| {
+        vec![
+            Span::styled(
+                format!(" [{}]", app.keys.key(action)),
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::raw(format!(" {label} ")),
+        ]
+    };
+    let lines = vec![
+        Line::from(
+            [hint(Action::Today, "today's daily"), hint(Action::Search, "search")]
+                .concat(),
+        ),
+        Line::from(
+            [hint(Action::Execute, "open"), hint(Action::SwitchPane, "palette and more")]
+                .concat(),
+        ),
+    ];
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .title("Quick actions")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::DarkGray)),
+    );
+    frame.render_widget(paragraph, area);
+}
@@ -0,0 +1,45 @@
+//! Inbox triage pane rendering (the notes left to file, current one first).
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, ListItem},
+};
+
+use super::list;
+use crate::tui::app::App;
+
+pub fn draw(frame: &mut Frame, area: Rect, app: &App) {
+    let inbox = &app.inbox;
+    let notes = inbox.notes();
+
+    let items: Vec<ListItem> = notes
+        .iter()
+        .enumerate()
+        .map(|(i, path)| {
+            let is_current = i == inbox.current;
+            let style = if is_current {
+                Style::default().bg(Color::DarkGray).fg(Color::White)
+            } else {
+                Style::default()
+            };
+            let prefix = if is_current { " > " } else { "   " };
+            let name = path.file_stem().unwrap_or_default().to_string_lossy();
+            ListItem::new(Line::from(vec![Span::raw(prefix), Span::raw(name)]))
+                .style(style)
+        })
+        .collect();
+
+    let empty = match &inbox.error {
+        Some(e) => format!(" {e}"),
+        None if inbox.filed > 0 => " (inbox zero)".to_string(),
+        None => " (Inbox/ is empty)".to_string(),
+    };
+    let position = if notes.is_empty() { 0 } else { inbox.current + 1 };
+    let title = format!("Inbox {position}/{} · {} filed", notes.len(), inbox.filed);
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray));
+    let selected = (!notes.is_empty()).then_some(inbox.current);
+    list::draw(frame, area, block, items, selected, empty);
+}
@@ -0,0 +1,76 @@
+//! Note details panel rendering (frontmatter, outline, backlinks, and
+//! outgoing links of the previewed note).
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::tui::info::{LinkedNote, NoteInfo};
+
+pub fn draw(frame: &mut Frame, area: Rect, info: &Result<NoteInfo, String>) {
+    let dim = Style::default().fg(Color::DarkGray);
+    let lines = match info {
+        Ok(info) => lines(info),
+        Err(e) => vec![Line::from(Span::styled(format!(" {e}"), dim.italic()))],
+    };
+
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().title("Details").borders(Borders::ALL).border_style(dim))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
+
+fn lines(info: &NoteInfo) -> Vec<Line<'static>> {
+    let heading = |text: String| {
+        Line::from(Span::styled(
+            format!(" {text}"),
+            Style::default().fg(Color::Cyan).bold(),
+        ))
+    };
+    let none = |text: &'static str| {
+        Line::from(Span::styled(text, Style::default().fg(Color::DarkGray).italic()))
+    };
+
+    let mut lines = vec![heading("Frontmatter".to_string())];
+    if info.fields.is_empty() {
+        lines.push(none("   (none)"));
+    }
+    for (name, value) in &info.fields {
+        lines.push(Line::from(vec![
+            Span::styled(format!("   {name}: "), Style::default().fg(Color::DarkGray)),
+            Span::raw(value.clone()),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(heading("Outline".to_string()));
+    if info.headings.is_empty() {
+        lines.push(none("   (no headings)"));
+    }
+    for h in &info.headings {
+        let indent = "  ".repeat(usize::from(h.level.saturating_sub(1)));
+        lines.push(Line::from(format!("   {indent}{}", h.title)));
+    }
+
+    for (title, links) in [("Backlinks", &info.backlinks), ("Links", &info.outgoing)] {
+        lines.push(Line::from(""));
+        lines.push(heading(format!("{title} ({})", links.len())));
+        if links.is_empty() {
+            lines.push(none("   (none)"));
+        }
+        lines.extend(links.iter().map(link_line));
+    }
+    lines
+}
+
+fn link_line(link: &LinkedNote) -> Line<'static> {
+    match &link.path {
+        Some(_) => Line::from(format!("   {}", link.label)),
+        // Links to notes that do not exist stand out
+        None => Line::from(Span::styled(
+            format!("   {} (missing)", link.label),
+            Style::default().fg(Color::Red),
+        )),
+    }
+}
@@ -1,9 +1,14 @@
 //! Main layout and frame composition.
 
+use chrono::Local;
 use ratatui::{prelude::*, widgets::Paragraph};
 
-use super::{palette, preview, status};
-use crate::tui::app::App;
+use super::{
+    calendar, graph, help, home, inbox, info, notes, palette, preview, project, search,
+    status,
+};
+use crate::tui::app::{App, Mode, Pane};
+use crate::tui::focus;
 
 /// Draw the entire application UI.
 pub fn draw(frame: &mut Frame, app: &App) {
@@ -20,37 +25,118 @@ pub fn draw(frame: &mut Frame, app: &App) {
     // Header
     draw_header(frame, main_chunks[0], app);
 
+    // Body: the graph takes it all while exploring links
+    if app.mode == Mode::Graph {
+        graph::draw(frame, main_chunks[1], app);
+        status::draw(frame, main_chunks[2], app);
+        if app.show_help {
+            help::draw(frame, main_chunks[1], app);
+        }
+        return;
+    }
+
     // Body: palette | preview
     let body_chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
-            Constraint::Percentage(30), // Palette
-            Constraint::Percentage(70), // Preview
+            Constraint::Percentage(app.list_width), // Palette or notes
+            Constraint::Percentage(100 - app.list_width), // Preview
         ])
         .split(main_chunks[1]);
 
-    palette::draw(frame, body_chunks[0], app);
-    preview::draw(frame, body_chunks[1], app);
+    match app.pane {
+        Pane::Home => home::draw(frame, body_chunks[0], app),
+        Pane::Palette => palette::draw(frame, body_chunks[0], app),
+        Pane::Notes => notes::draw(frame, body_chunks[0], app),
+        Pane::Search => search::draw(frame, body_chunks[0], app),
+        Pane::Calendar => calendar::draw(frame, body_chunks[0], app),
+        Pane::Project => project::draw(frame, body_chunks[0], app),
+        Pane::Inbox => inbox::draw(frame, body_chunks[0], app),
+    }
+    if app.pane == Pane::Project && app.mode == Mode::Browse {
+        project::draw_dashboard(frame, body_chunks[1], app);
+    } else if matches!(app.mode, Mode::Browse | Mode::Filter | Mode::Search)
+        && let Some(note_info) = &app.note_info
+    {
+        // Preview | details of the note
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(body_chunks[1]);
+        preview::draw(frame, chunks[0], app);
+        info::draw(frame, chunks[1], note_info);
+    } else {
+        preview::draw(frame, body_chunks[1], app);
+    }
 
     // Status bar
     status::draw(frame, main_chunks[2], app);
+
+    if app.show_help {
+        help::draw(frame, main_chunks[1], app);
+    }
 }
 
 fn draw_header(frame: &mut Frame, area: Rect, app: &App) {
     let profile_text = format!("[{}]", app.config.active_profile);
     let title = "mdvault";
 
+    // Pane tabs, the active one highlighted
+    let tab = |label: &'static str, pane: Pane| {
+        if app.pane == pane {
+            Span::styled(label, Style::default().fg(Color::White).bold().underlined())
+        } else {
+            Span::styled(label, Style::default().fg(Color::DarkGray))
+        }
+    };
+    let tabs = [
+        tab("Home", Pane::Home),
+        Span::raw("  "),
+        tab("Palette", Pane::Palette),
+        Span::raw("  "),
+        tab("Notes", Pane::Notes),
+        Span::raw("  "),
+        tab("Search", Pane::Search),
+        Span::raw("  "),
+        tab("Calendar", Pane::Calendar),
+        Span::raw("  "),
+        tab("Projects", Pane::Project),
+        Span::raw("  "),
+        tab("Inbox", Pane::Inbox),
+    ];
+    let tabs_len: usize = tabs.iter().map(|s| s.width()).sum();
+
+    // The focused project and how long its session has run
+    let focus = app.focus.as_ref().map(|focus| {
+        let spent = focus::elapsed(focus, Local::now());
+        Span::styled(
+            format!("● {} {}  ", focus.project, focus::timer(spent)),
+            Style::default().fg(Color::Green),
+        )
+    });
+    let focus_len = focus.as_ref().map_or(0, |s| s.width());
+
     // Calculate padding for right-alignment
-    let padding =
-        area.width.saturating_sub(title.len() as u16 + profile_text.len() as u16 + 2)
-            as usize;
+    let padding = area.width.saturating_sub(
+        title.len() as u16
+            + tabs_len as u16
+            + focus_len as u16
+            + profile_text.len() as u16
+            + 5,
+    ) as usize;
 
-    let line = Line::from(vec![
+    let mut spans = vec![
         Span::styled(format!(" {}", title), Style::default().fg(Color::Cyan).bold()),
-        Span::raw(" ".repeat(padding)),
+        Span::raw("   "),
+    ];
+    spans.extend(tabs);
+    spans.push(Span::raw(" ".repeat(padding)));
+    spans.extend(focus);
+    spans.extend([
         Span::styled(profile_text, Style::default().fg(Color::DarkGray)),
         Span::raw(" "),
     ]);
+    let line = Line::from(spans);
 
     let paragraph = Paragraph::new(line);
     frame.render_widget(paragraph, area);
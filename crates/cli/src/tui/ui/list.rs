@@ -0,0 +1,35 @@
+//! The scrolling list every pane draws its rows in.
+//!
+//! Panes style the selected row themselves (highlight and `>` marker)
+//! because a row can span several lines or sit among headings that cannot
+//! be selected. The [`ListState`] passed to ratatui only scrolls that row
+//! into view; its own highlighting is never used.
+
+use std::borrow::Cow;
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, List, ListItem, ListState},
+};
+
+/// A dimmed, italic row for messages such as "(no matches)".
+pub fn placeholder<'a>(text: impl Into<Cow<'a, str>>) -> ListItem<'a> {
+    ListItem::new(Span::styled(text, Style::default().fg(Color::DarkGray).italic()))
+}
+
+/// Draw `items` in `block`, scrolled so row `selected` is visible, with
+/// `empty` as a [`placeholder`] when there are no rows.
+pub fn draw<'a>(
+    frame: &mut Frame,
+    area: Rect,
+    block: Block<'a>,
+    mut items: Vec<ListItem<'a>>,
+    selected: Option<usize>,
+    empty: impl Into<Cow<'a, str>>,
+) {
+    if items.is_empty() {
+        items.push(placeholder(empty));
+    }
+    let mut state = ListState::default().with_selected(selected);
+    frame.render_stateful_widget(List::new(items).block(block), area, &mut state);
+}
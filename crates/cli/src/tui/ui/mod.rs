@@ -1,8 +1,18 @@
 //! UI rendering modules.
 
+mod calendar;
+mod graph;
+mod help;
+mod home;
+mod inbox;
+mod info;
 mod layout;
+pub mod list;
+mod notes;
 mod palette;
 mod preview;
+mod project;
+mod search;
 mod status;
 
 pub use layout::draw;
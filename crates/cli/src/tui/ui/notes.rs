@@ -0,0 +1,109 @@
+//! Notes browser rendering (filter box and note list).
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, ListItem, Paragraph},
+};
+
+use super::list;
+use crate::tui::app::{App, Mode};
+use crate::tui::browser::{BrowserRow, BrowserView};
+
+pub fn draw(frame: &mut Frame, area: Rect, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)])
+        .split(area);
+
+    draw_filter(frame, chunks[0], app);
+    draw_list(frame, chunks[1], app);
+}
+
+fn draw_filter(frame: &mut Frame, area: Rect, app: &App) {
+    let browser = &app.browser;
+    let editing = app.mode == Mode::Filter;
+
+    let mut spans = vec![Span::styled(" / ", Style::default().fg(Color::DarkGray))];
+    if browser.query.is_empty() && !editing {
+        spans.push(Span::styled("filter", Style::default().fg(Color::DarkGray).italic()));
+    } else {
+        spans.push(Span::raw(&browser.query));
+    }
+    if editing {
+        spans.push(Span::styled("_", Style::default().fg(Color::Gray).rapid_blink()));
+    }
+
+    let view = match browser.view {
+        BrowserView::Tree => "tree",
+        BrowserView::Flat => "flat",
+    };
+    let note_type = browser.type_filter.map(|t| t.as_str()).unwrap_or("all");
+    let title = format!(
+        "Notes {}/{} [{note_type}] [{view}]",
+        browser.visible_len(),
+        browser.total()
+    );
+
+    let border = if editing { Color::Cyan } else { Color::DarkGray };
+    let paragraph = Paragraph::new(Line::from(spans)).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(border)),
+    );
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_list(frame: &mut Frame, area: Rect, app: &App) {
+    let browser = &app.browser;
+    let notes: Vec<_> = browser.visible_notes().collect();
+    let rows = browser.rows();
+
+    let mut items: Vec<ListItem> = Vec::new();
+    let mut selected_row = None;
+    for row in &rows {
+        match row {
+            BrowserRow::Dir { name, depth } => {
+                items.push(ListItem::new(Line::from(Span::styled(
+                    format!(" {}{name}/", "  ".repeat(*depth)),
+                    Style::default().fg(Color::Blue).bold(),
+                ))));
+            }
+            BrowserRow::Note { index, depth } => {
+                let note = notes[*index];
+                let label = match browser.view {
+                    BrowserView::Tree => note
+                        .path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                    BrowserView::Flat => note.path.display().to_string(),
+                };
+                let is_selected = *index == browser.selected;
+                if is_selected {
+                    selected_row = Some(items.len());
+                }
+                let style = if is_selected {
+                    Style::default().bg(Color::DarkGray).fg(Color::White)
+                } else {
+                    Style::default()
+                };
+                let prefix = if is_selected { " > " } else { "   " };
+                items.push(
+                    ListItem::new(format!("{prefix}{}{label}", "  ".repeat(*depth)))
+                        .style(style),
+                );
+            }
+        }
+    }
+
+    let empty = match &app.browser_error {
+        Some(e) => format!(" {e}"),
+        None if browser.total() == 0 => " (no indexed notes)".to_string(),
+        None => " (no matching notes)".to_string(),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray));
+    list::draw(frame, area, block, items, selected_row, empty);
+}
@@ -1,100 +1,125 @@
-//! Palette list rendering (templates, captures, and macros).
+//! Palette rendering (filter box and the templates, captures, and macros
+//! list).
 
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, List, ListItem},
+    widgets::{Block, Borders, ListItem, Paragraph},
 };
 
-use crate::tui::app::App;
+use super::list;
+use crate::tui::app::{App, Mode, PaletteItem};
+use crate::tui::palette::PaletteMatch;
 
 pub fn draw(frame: &mut Frame, area: Rect, app: &App) {
-    let mut items: Vec<ListItem> = Vec::new();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)])
+        .split(area);
 
-    // Templates section
-    if app.captures_start_index > 0 {
-        items.push(ListItem::new(Line::from(vec![Span::styled(
-            " TEMPLATES",
-            Style::default().fg(Color::Cyan).bold(),
-        )])));
+    draw_filter(frame, chunks[0], app);
+    draw_list(frame, chunks[1], app);
+}
 
-        for (i, item) in app.items.iter().enumerate().take(app.captures_start_index) {
-            let style = if i == app.selected {
-                Style::default().bg(Color::DarkGray).fg(Color::White)
-            } else {
-                Style::default()
-            };
+fn draw_filter(frame: &mut Frame, area: Rect, app: &App) {
+    let palette = &app.palette;
+    let editing = app.mode == Mode::Filter;
 
-            let prefix = if i == app.selected { " > " } else { "   " };
-            items.push(ListItem::new(format!("{}{}", prefix, item.name())).style(style));
-        }
+    let mut spans = vec![Span::styled(" / ", Style::default().fg(Color::DarkGray))];
+    if palette.query.is_empty() && !editing {
+        spans.push(Span::styled("filter", Style::default().fg(Color::DarkGray).italic()));
+    } else {
+        spans.push(Span::raw(&palette.query));
+    }
+    if editing {
+        spans.push(Span::styled("_", Style::default().fg(Color::Gray).rapid_blink()));
     }
 
-    // Captures section
-    let has_captures = app.macros_start_index > app.captures_start_index;
-    if has_captures {
-        // Add spacing if we had templates
-        if app.captures_start_index > 0 {
-            items.push(ListItem::new(""));
-        }
-
-        items.push(ListItem::new(Line::from(vec![Span::styled(
-            " CAPTURES",
-            Style::default().fg(Color::Magenta).bold(),
-        )])));
+    let title = format!("Palette {}/{}", palette.visible().len(), app.items.len());
+    let border = if editing { Color::Cyan } else { Color::DarkGray };
+    let paragraph = Paragraph::new(Line::from(spans)).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(border)),
+    );
+    frame.render_widget(paragraph, area);
+}
 
-        for i in app.captures_start_index..app.macros_start_index {
-            let item = &app.items[i];
-            let style = if i == app.selected {
-                Style::default().bg(Color::DarkGray).fg(Color::White)
-            } else {
-                Style::default()
-            };
+fn draw_list(frame: &mut Frame, area: Rect, app: &App) {
+    let palette = &app.palette;
+    let filtering = !palette.query.trim().is_empty();
 
-            let prefix = if i == app.selected { " > " } else { "   " };
-            items.push(ListItem::new(format!("{}{}", prefix, item.name())).style(style));
+    let mut items: Vec<ListItem> = Vec::new();
+    let mut selected_row = None;
+    let mut heading = None;
+    for m in palette.visible() {
+        let item = &app.items[m.index];
+
+        // Matches are grouped by kind, so a heading starts each group
+        let (title, color) = match item {
+            PaletteItem::Template(_) => (" TEMPLATES", Color::Cyan),
+            PaletteItem::Capture(_) => (" CAPTURES", Color::Magenta),
+            PaletteItem::Macro(_) => (" MACROS", Color::Yellow),
+        };
+        if heading != Some(title) {
+            // Add spacing between groups
+            if heading.is_some() {
+                items.push(ListItem::new(""));
+            }
+            items.push(ListItem::new(Line::from(vec![Span::styled(
+                title,
+                Style::default().fg(color).bold(),
+            )])));
+            heading = Some(title);
         }
-    }
 
-    // Macros section
-    let has_macros = app.macros_start_index < app.items.len();
-    if has_macros {
-        // Add spacing if we had captures or templates
-        if app.macros_start_index > 0 {
-            items.push(ListItem::new(""));
+        let is_selected = m.index == app.selected;
+        if is_selected {
+            selected_row = Some(items.len());
         }
+        let style = if is_selected {
+            Style::default().bg(Color::DarkGray).fg(Color::White)
+        } else {
+            Style::default()
+        };
+        let description = palette.description(m.index);
+        items.push(
+            ListItem::new(item_line(item.name(), m, is_selected, filtering, description))
+                .style(style),
+        );
+    }
 
-        items.push(ListItem::new(Line::from(vec![Span::styled(
-            " MACROS",
-            Style::default().fg(Color::Yellow).bold(),
-        )])));
-
-        for i in app.macros_start_index..app.items.len() {
-            let item = &app.items[i];
-            let style = if i == app.selected {
-                Style::default().bg(Color::DarkGray).fg(Color::White)
-            } else {
-                Style::default()
-            };
+    let empty = if app.items.is_empty() { " (no items found)" } else { " (no matches)" };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray));
+    list::draw(frame, area, block, items, selected_row, empty);
+}
 
-            let prefix = if i == app.selected { " > " } else { "   " };
-            items.push(ListItem::new(format!("{}{}", prefix, item.name())).style(style));
+/// An item's name with matched letters highlighted, followed by its
+/// description while filtering (it may be what matched).
+fn item_line<'a>(
+    name: &'a str,
+    m: &PaletteMatch,
+    is_selected: bool,
+    filtering: bool,
+    description: &'a str,
+) -> Line<'a> {
+    let prefix = if is_selected { " > " } else { "   " };
+    let mut spans = vec![Span::raw(prefix)];
+    let highlight = Style::default().fg(Color::Yellow).bold();
+    spans.extend(name.chars().enumerate().map(|(i, c)| {
+        if m.highlights.contains(&i) {
+            Span::styled(c.to_string(), highlight)
+        } else {
+            Span::raw(c.to_string())
         }
+    }));
+    if filtering && !description.is_empty() {
+        spans.push(Span::styled(
+            format!("  {description}"),
+            Style::default().fg(Color::DarkGray),
+        ));
     }
-
-    // Empty state
-    if app.items.is_empty() {
-        items.push(ListItem::new(Span::styled(
-            " (no items found)",
-            Style::default().fg(Color::DarkGray).italic(),
-        )));
-    }
-
-    let list = List::new(items).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::DarkGray)),
-    );
-
-    // We don't use ListState for selection since we manually handle the styling
-    frame.render_widget(list, area);
+    Line::from(spans)
 }
@@ -5,16 +5,30 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Wrap},
 };
 
-use crate::tui::app::{App, Mode, Preview};
+use crate::tui::actions::NoteValidation;
+use crate::tui::app::{App, Mode, NotePrompt, Preview};
+use crate::tui::keys::Action;
+use crate::tui::markdown;
+use crate::tui::picker::Picker;
 
 pub fn draw(frame: &mut Frame, area: Rect, app: &App) {
     // In input modes, show the input form instead of preview
-    if matches!(app.mode, Mode::OutputPath | Mode::Input { .. }) {
+    if matches!(app.mode, Mode::OutputPath | Mode::Input { .. } | Mode::Prompt(_)) {
         draw_input_form(frame, area, app);
         return;
     }
+    if app.mode == Mode::ConfirmTrust {
+        draw_trust_confirm(frame, area, app);
+        return;
+    }
+    if app.mode == Mode::Result
+        && let Some(validation) = &app.validation
+    {
+        draw_validation(frame, area, app, validation);
+        return;
+    }
 
-    let (title, content, style) = match &app.preview {
+    let (mut title, content, style) = match &app.preview {
         Preview::None => (
             "Preview".to_string(),
             String::from("Select an item to preview"),
@@ -28,18 +42,33 @@ pub fn draw(frame: &mut Frame, area: Rect, app: &App) {
         }
         Preview::Macro { content, requires_trust } => {
             let title = if *requires_trust {
-                "Macro Preview [requires --trust]".to_string()
+                "Macro Preview [runs shell commands]".to_string()
             } else {
                 "Macro Preview".to_string()
             };
             (title, content.clone(), Style::default())
         }
+        Preview::Note { path, content } => {
+            (path.display().to_string(), content.clone(), Style::default())
+        }
+        Preview::Day { date, content } => {
+            (date.format("%A %Y-%m-%d").to_string(), content.clone(), Style::default())
+        }
         Preview::Error(e) => {
             ("Error".to_string(), e.clone(), Style::default().fg(Color::Red))
         }
     };
 
-    let paragraph = Paragraph::new(content)
+    let text = if app.preview.is_markdown() && !app.preview_raw {
+        Text::from(markdown::render(&content))
+    } else {
+        Text::from(content)
+    };
+    if app.preview.is_markdown() && app.preview_raw {
+        title.push_str(" [raw]");
+    }
+
+    let paragraph = Paragraph::new(text)
         .style(style)
         .block(
             Block::default()
@@ -47,7 +76,8 @@ pub fn draw(frame: &mut Frame, area: Rect, app: &App) {
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(Color::DarkGray)),
         )
-        .wrap(Wrap { trim: false });
+        .wrap(Wrap { trim: false })
+        .scroll((app.preview_scroll, 0));
 
     frame.render_widget(paragraph, area);
 }
@@ -55,8 +85,13 @@ pub fn draw(frame: &mut Frame, area: Rect, app: &App) {
 fn draw_input_form(frame: &mut Frame, area: Rect, app: &App) {
     let label = app.current_input_label().unwrap_or_else(|| "Input".to_string());
 
-    let title = match &app.mode {
+    let mut title = match &app.mode {
         Mode::OutputPath => "Enter Output Path".to_string(),
+        Mode::Prompt(NotePrompt::CaptureInto) => "Capture Into Note".to_string(),
+        Mode::Prompt(NotePrompt::Rename) => "Rename Note".to_string(),
+        Mode::Prompt(NotePrompt::NewTask) => "New Task".to_string(),
+        Mode::Prompt(NotePrompt::ProjectLog) => "Log to Project".to_string(),
+        Mode::Prompt(NotePrompt::FocusLog) => "Log Focus Session".to_string(),
         Mode::Input { var_index } => {
             if app.required_var_infos.len() > 1 {
                 // Show progress
@@ -67,6 +102,17 @@ fn draw_input_form(frame: &mut Frame, area: Rect, app: &App) {
         }
         _ => "Input".to_string(),
     };
+    let note = match app.mode {
+        Mode::Prompt(NotePrompt::NewTask | NotePrompt::ProjectLog) => {
+            app.projects.selected_project().map(|p| p.id.clone())
+        }
+        Mode::Prompt(NotePrompt::FocusLog) => Some("today's daily note".to_string()),
+        Mode::Prompt(_) => app.selected_note_path().map(|p| p.display().to_string()),
+        _ => app.capture_target().map(|p| p.display().to_string()),
+    };
+    if let Some(note) = note {
+        title.push_str(&format!(": {note}"));
+    }
 
     let mut content = vec![
         Line::from(""),
@@ -74,12 +120,18 @@ fn draw_input_form(frame: &mut Frame, area: Rect, app: &App) {
             format!("  {}: ", label),
             Style::default().fg(Color::Cyan).bold(),
         )]),
-        Line::from(vec![
-            Span::raw("  "),
-            Span::styled(&app.input_buffer, Style::default().fg(Color::White)),
-            Span::styled("_", Style::default().fg(Color::Gray).rapid_blink()),
-        ]),
     ];
+    // Multi-line input keeps its line breaks, with the cursor after the
+    // last line
+    for line in app.input_buffer.split('\n') {
+        content.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled(line, Style::default().fg(Color::White)),
+        ]));
+    }
+    if let Some(last) = content.last_mut() {
+        last.push_span(Span::styled("_", Style::default().fg(Color::Gray).rapid_blink()));
+    }
 
     // Show description if available
     if let Some(description) = app.current_input_description() {
@@ -90,18 +142,184 @@ fn draw_input_form(frame: &mut Frame, area: Rect, app: &App) {
         )]));
     }
 
+    if let Some(picker) = &app.picker {
+        content.push(Line::from(""));
+        draw_picker_options(&mut content, picker, area.height);
+    }
+
+    let hint = if app.multiline {
+        format!(
+            "  [{}] submit  [{}] newline  [{}] cancel",
+            app.keys.key(Action::Submit),
+            app.keys.key(Action::Execute),
+            app.keys.key(Action::Cancel)
+        )
+    } else if app.picker.as_ref().is_some_and(Picker::is_multi) {
+        "  [Tab] choose  [Enter] submit  [Esc] cancel".to_string()
+    } else {
+        "  [Enter] submit  [Esc] cancel".to_string()
+    };
     content.push(Line::from(""));
-    content.push(Line::from(vec![Span::styled(
-        "  [Enter] submit  [Esc] cancel",
+    content
+        .push(Line::from(vec![Span::styled(hint, Style::default().fg(Color::DarkGray))]));
+
+    let paragraph = Paragraph::new(content)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+/// The picker's matching options, scrolled to keep the selected one in the
+/// rows left by the rest of the form.
+fn draw_picker_options(content: &mut Vec<Line>, picker: &Picker, height: u16) {
+    // Less the count line, the hint line and its spacing, and the borders
+    let rows = (height as usize).saturating_sub(content.len() + 5).max(1);
+    let start = (picker.selected + 1).saturating_sub(rows);
+
+    content.push(Line::from(Span::styled(
+        format!("  {}/{} (type to filter)", picker.visible_len(), picker.total()),
         Style::default().fg(Color::DarkGray),
-    )]));
-
-    let paragraph = Paragraph::new(content).block(
-        Block::default()
-            .title(title)
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan)),
-    );
+    )));
+    if picker.visible_len() == 0 {
+        content.push(Line::from(Span::styled(
+            "    (no matches)",
+            Style::default().fg(Color::DarkGray).italic(),
+        )));
+    }
+    for (i, option) in picker.visible().enumerate().skip(start).take(rows) {
+        // A multi-select marks the chosen options
+        let option = match (picker.is_multi(), picker.is_chosen(option)) {
+            (false, _) => option.to_string(),
+            (true, true) => format!("[x] {option}"),
+            (true, false) => format!("[ ] {option}"),
+        };
+        let line = if i == picker.selected {
+            Line::from(Span::styled(
+                format!("  > {option}"),
+                Style::default().bg(Color::DarkGray).fg(Color::White),
+            ))
+        } else {
+            Line::from(format!("    {option}"))
+        };
+        content.push(line);
+    }
+}
+
+fn draw_trust_confirm(frame: &mut Frame, area: Rect, app: &App) {
+    let mut content = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "  This macro runs the following shell commands:",
+            Style::default().fg(Color::Yellow).bold(),
+        )),
+        Line::from(""),
+    ];
+    for command in &app.trust_commands {
+        content.push(Line::from(vec![
+            Span::styled("  $ ", Style::default().fg(Color::DarkGray)),
+            Span::styled(command.as_str(), Style::default().fg(Color::White)),
+        ]));
+    }
+    content.extend([
+        Line::from(""),
+        Line::from(Span::styled(
+            "  Type 'yes' to run them (same as mdv macro --trust):",
+            Style::default().fg(Color::Cyan).bold(),
+        )),
+        Line::from(vec![
+            Span::raw("  "),
+            Span::styled(&app.input_buffer, Style::default().fg(Color::White)),
+            Span::styled("_", Style::default().fg(Color::Gray).rapid_blink()),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "  [Enter] confirm  [Esc] cancel",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ]);
+
+    let paragraph = Paragraph::new(content)
+        .block(
+            Block::default()
+                .title("Confirm Shell Commands")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow)),
+        )
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Errors and warnings found in the file just written, with its fixes.
+fn draw_validation(
+    frame: &mut Frame,
+    area: Rect,
+    app: &App,
+    validation: &NoteValidation,
+) {
+    let mut content = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("  "),
+            Span::styled(
+                validation.path.display().to_string(),
+                Style::default().fg(Color::White).bold(),
+            ),
+            Span::styled(
+                format!(" ({})", validation.note_type),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]),
+    ];
+
+    let sections = [
+        ("Errors", "✗", Color::Red, &validation.errors),
+        ("Warnings", "!", Color::Yellow, &validation.warnings),
+        ("Safe fixes", "+", Color::Green, &validation.fixes),
+    ];
+    for (heading, marker, color, items) in sections {
+        if items.is_empty() {
+            continue;
+        }
+        content.push(Line::from(""));
+        content.push(Line::from(Span::styled(
+            format!("  {heading}"),
+            Style::default().fg(color).bold(),
+        )));
+        for item in items {
+            content.push(Line::from(vec![
+                Span::styled(format!("  {marker} "), Style::default().fg(color)),
+                Span::raw(item.as_str()),
+            ]));
+        }
+    }
+
+    let mut hints = vec![format!("[{}] open file", app.keys.key(Action::Edit))];
+    if !validation.fixes.is_empty() {
+        hints.push(format!("[{}] apply fixes", app.keys.key(Action::Fix)));
+    }
+    hints.push(format!("[{}] continue", app.keys.key(Action::Execute)));
+    content.push(Line::from(""));
+    content.push(Line::from(Span::styled(
+        format!("  {}", hints.join("  ")),
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let border = if validation.errors.is_empty() { Color::Yellow } else { Color::Red };
+    let paragraph = Paragraph::new(content)
+        .block(
+            Block::default()
+                .title("Validation")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(border)),
+        )
+        .wrap(Wrap { trim: false });
 
     frame.render_widget(paragraph, area);
 }
@@ -0,0 +1,157 @@
+//! Project pane rendering (project list and the selected project's
+//! dashboard).
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Gauge, ListItem, Paragraph},
+};
+
+use super::list;
+use crate::tui::app::App;
+
+pub fn draw(frame: &mut Frame, area: Rect, app: &App) {
+    let dashboard = &app.projects;
+
+    let items: Vec<ListItem> = dashboard
+        .projects
+        .iter()
+        .enumerate()
+        .map(|(i, project)| {
+            let is_selected = i == dashboard.selected;
+            let style = if is_selected {
+                Style::default().bg(Color::DarkGray).fg(Color::White)
+            } else {
+                Style::default()
+            };
+            let prefix = if is_selected { " > " } else { "   " };
+            let marker = if dashboard.is_focused(project) {
+                Span::styled("● ", Style::default().fg(Color::Yellow))
+            } else {
+                Span::raw("  ")
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(prefix),
+                marker,
+                Span::styled(project.id.clone(), Style::default().bold()),
+                Span::styled(
+                    format!("  {:.0}%", project.progress_percent),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+            .style(style)
+        })
+        .collect();
+
+    let block = Block::default()
+        .title(format!("Projects {}", dashboard.projects.len()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray));
+    let selected = (!dashboard.projects.is_empty()).then_some(dashboard.selected);
+    list::draw(
+        frame,
+        area,
+        block,
+        items,
+        selected,
+        " (no projects; create one with mdv new project)",
+    );
+}
+
+/// The selected project's task counts, recent completions and velocity.
+pub fn draw_dashboard(frame: &mut Frame, area: Rect, app: &App) {
+    let dashboard = &app.projects;
+    let Some(project) = dashboard.selected_project() else {
+        let paragraph = Paragraph::new("Select a project")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(
+                Block::default()
+                    .title("Project")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::DarkGray)),
+            );
+        frame.render_widget(paragraph, area);
+        return;
+    };
+
+    let mut title = format!("{} ({}, {})", project.title, project.kind, project.status);
+    if dashboard.is_focused(project) {
+        title.push_str(" [focus]");
+    }
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Progress gauge
+            Constraint::Length(1),
+            Constraint::Length(8), // Task counts and velocity
+            Constraint::Min(2),    // Recent completions
+        ])
+        .split(inner);
+
+    let gauge = Gauge::default()
+        .gauge_style(Style::default().fg(Color::Green).bg(Color::DarkGray))
+        .ratio((project.progress_percent / 100.0).clamp(0.0, 1.0))
+        .label(format!(
+            "{:.0}% ({}/{} done)",
+            project.progress_percent,
+            project.tasks.done,
+            project.tasks.total - project.tasks.cancelled
+        ));
+    frame.render_widget(gauge, chunks[0].inner(Margin::new(2, 0)));
+
+    let count = |label: &'static str, n: usize, color: Color| {
+        Line::from(vec![
+            Span::styled(format!("  {label:<13}"), Style::default().fg(Color::DarkGray)),
+            Span::styled(n.to_string(), Style::default().fg(color)),
+        ])
+    };
+    let tasks = &project.tasks;
+    let counts = vec![
+        count("Todo", tasks.todo, Color::White),
+        count("In progress", tasks.in_progress, Color::Yellow),
+        count("Blocked", tasks.blocked, Color::Red),
+        count("Done", tasks.done, Color::Green),
+        count("Cancelled", tasks.cancelled, Color::DarkGray),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  Velocity     ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("{:.1} tasks/week", project.velocity),
+                Style::default().fg(Color::Cyan).bold(),
+            ),
+            Span::styled(" (last 4 weeks)", Style::default().fg(Color::DarkGray)),
+        ]),
+    ];
+    frame.render_widget(Paragraph::new(counts), chunks[2]);
+
+    let mut recent = vec![Line::from(Span::styled(
+        "  Completed in the last 7 days",
+        Style::default().bold(),
+    ))];
+    if project.recent_completions.is_empty() {
+        recent.push(Line::from(Span::styled(
+            "  (none)",
+            Style::default().fg(Color::DarkGray).italic(),
+        )));
+    }
+    for completion in &project.recent_completions {
+        recent.push(Line::from(vec![
+            Span::styled(
+                format!("  {}  ", completion.completed_at),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(
+                format!("{:<10}", completion.id),
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::raw(completion.title.clone()),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(recent), chunks[3]);
+}
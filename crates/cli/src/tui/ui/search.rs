@@ -0,0 +1,118 @@
+//! Search pane rendering (query box and ranked results with snippets).
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, ListItem, Paragraph},
+};
+
+use mdvault_core::index::Excerpt;
+
+use super::list;
+use crate::tui::app::{App, Mode};
+
+pub fn draw(frame: &mut Frame, area: Rect, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)])
+        .split(area);
+
+    draw_query(frame, chunks[0], app);
+    draw_results(frame, chunks[1], app);
+}
+
+fn draw_query(frame: &mut Frame, area: Rect, app: &App) {
+    let search = &app.search;
+    let editing = app.mode == Mode::Search;
+
+    let mut spans = vec![Span::styled(" / ", Style::default().fg(Color::DarkGray))];
+    if search.query.is_empty() && !editing {
+        spans.push(Span::styled("search", Style::default().fg(Color::DarkGray).italic()));
+    } else {
+        spans.push(Span::raw(&search.query));
+    }
+    if editing {
+        spans.push(Span::styled("_", Style::default().fg(Color::Gray).rapid_blink()));
+    }
+
+    let title = format!("Search {}", search.results.len());
+    let border = if editing { Color::Cyan } else { Color::DarkGray };
+    let paragraph = Paragraph::new(Line::from(spans)).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(border)),
+    );
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_results(frame: &mut Frame, area: Rect, app: &App) {
+    let search = &app.search;
+
+    let items: Vec<ListItem> = search
+        .results
+        .iter()
+        .enumerate()
+        .map(|(i, result)| {
+            let is_selected = i == search.selected;
+            let style = if is_selected {
+                Style::default().bg(Color::DarkGray).fg(Color::White)
+            } else {
+                Style::default()
+            };
+            let prefix = if is_selected { " > " } else { "   " };
+            let name = if result.note.title.is_empty() {
+                result.note.path.display().to_string()
+            } else {
+                result.note.title.clone()
+            };
+
+            let mut lines = vec![Line::from(vec![
+                Span::raw(prefix),
+                Span::styled(name, Style::default().bold()),
+                Span::styled(
+                    format!("  {:.2}", result.score),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])];
+            lines.push(Line::from(Span::styled(
+                format!("   {}", result.note.path.display()),
+                Style::default().fg(Color::DarkGray),
+            )));
+            if let Some(snippet) = &result.snippet {
+                lines.push(snippet_line(snippet));
+            }
+            ListItem::new(lines).style(style)
+        })
+        .collect();
+
+    let empty = match &search.error {
+        Some(e) => format!(" {e}"),
+        None if search.query.trim().is_empty() => " (type to search)".to_string(),
+        None => " (no matches)".to_string(),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray));
+    let selected = (!search.results.is_empty()).then_some(search.selected);
+    list::draw(frame, area, block, items, selected, empty);
+}
+
+/// The excerpt on one line, with the matched terms highlighted.
+fn snippet_line(snippet: &Excerpt) -> Line<'static> {
+    let chars: Vec<char> =
+        snippet.text.chars().map(|c| if c.is_whitespace() { ' ' } else { c }).collect();
+    let mut spans = vec![Span::raw("   ")];
+    let mut at = 0;
+    for range in &snippet.highlights {
+        let start = range.start.clamp(at, chars.len());
+        let end = range.end.clamp(start, chars.len());
+        spans.push(Span::raw(chars[at..start].iter().collect::<String>()));
+        spans.push(Span::styled(
+            chars[start..end].iter().collect::<String>(),
+            Style::default().fg(Color::Yellow).bold(),
+        ));
+        at = end;
+    }
+    spans.push(Span::raw(chars[at..].iter().collect::<String>()));
+    Line::from(spans)
+}
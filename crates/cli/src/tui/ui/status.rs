@@ -5,25 +5,177 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph},
 };
 
-use crate::tui::app::{App, Mode};
+use crate::tui::app::{App, Mode, Pane};
+use crate::tui::keys::Action;
 
 pub fn draw(frame: &mut Frame, area: Rect, app: &App) {
-    let (left_text, right_text) = match &app.mode {
-        Mode::Browse => (" [j/k] navigate  [Enter] execute  [q] quit", "Ready"),
-        Mode::OutputPath | Mode::Input { .. } => {
-            (" [Enter] submit  [Esc] cancel", "Input Mode")
+    use Action::*;
+
+    let (hints, right_text): (&[(&[Action], &str)], &str) = match &app.mode {
+        Mode::Browse if app.pane == Pane::Home => (
+            &[
+                (&[SelectNext, SelectPrev], "navigate"),
+                (&[Execute], "open"),
+                (&[Today], "daily note"),
+                (&[Search], "search"),
+                (&[Capture], "capture"),
+                (&[Graph], "graph"),
+                (&[SwitchPane], "palette"),
+                (&[Help], "help"),
+            ],
+            "Home",
+        ),
+        Mode::Browse if app.pane == Pane::Notes => (
+            &[
+                (&[Search], "filter"),
+                (&[ToggleView], "tree/flat"),
+                (&[CycleType], "type"),
+                (&[Execute], "open"),
+                (&[Edit], "edit"),
+                (&[Capture], "capture"),
+                (&[Rename], "rename"),
+                (&[Graph], "graph"),
+                (&[ToggleInfo], "details"),
+                (&[SwitchPane], "search"),
+                (&[Help], "help"),
+            ],
+            "Notes",
+        ),
+        Mode::Browse if app.pane == Pane::Search => (
+            &[
+                (&[Search], "edit query"),
+                (&[SelectNext, SelectPrev], "navigate"),
+                (&[Execute], "open"),
+                (&[Capture], "capture"),
+                (&[Graph], "graph"),
+                (&[ToggleInfo], "details"),
+                (&[SwitchPane], "calendar"),
+                (&[Help], "help"),
+            ],
+            "Search",
+        ),
+        Mode::Browse if app.pane == Pane::Calendar => (
+            &[
+                (&[Left, SelectNext, SelectPrev, Right], "day"),
+                (&[PrevMonth, NextMonth], "month"),
+                (&[Today], "today"),
+                (&[Execute], "daily note"),
+                (&[SwitchPane], "projects"),
+                (&[Help], "help"),
+            ],
+            "Calendar",
+        ),
+        Mode::Browse if app.pane == Pane::Project => (
+            &[
+                (&[SelectNext, SelectPrev], "navigate"),
+                (&[NewTask], "new task"),
+                (&[LogProject], "log"),
+                (&[FocusProject], "focus"),
+                (&[Edit], "edit"),
+                (&[SwitchPane], "inbox"),
+                (&[Help], "help"),
+            ],
+            "Projects",
+        ),
+        Mode::Browse if app.pane == Pane::Inbox => (
+            &[
+                (&[Assign], "project"),
+                (&[Schedule], "schedule"),
+                (&[Archive], "archive"),
+                (&[Delete], "delete"),
+                (&[Skip], "skip"),
+                (&[Execute], "open"),
+                (&[SwitchPane], "home"),
+                (&[Help], "help"),
+            ],
+            "Inbox",
+        ),
+        Mode::Browse => (
+            &[
+                (&[SelectNext, SelectPrev], "navigate"),
+                (&[Execute], "execute"),
+                (&[Edit], "edit"),
+                (&[Search], "filter"),
+                (&[ScrollDown, ScrollUp], "scroll"),
+                (&[ToggleRaw], "raw"),
+                (&[SwitchPane], "notes"),
+                (&[Help], "help"),
+                (&[Quit], "quit"),
+            ],
+            "Ready",
+        ),
+        Mode::Filter => (&[(&[Execute], "keep filter"), (&[Cancel], "clear")], "Filter"),
+        Mode::Graph => (
+            &[
+                (&[Left, Right], "side"),
+                (&[SelectNext, SelectPrev], "navigate"),
+                (&[Execute], "follow"),
+                (&[GraphBack], "back"),
+                (&[GraphDepth], "hops"),
+                (&[Open], "open"),
+                (&[Help], "help"),
+                (&[Cancel], "close"),
+            ],
+            "Graph",
+        ),
+        Mode::Search => (&[(&[Execute], "open"), (&[Cancel], "done typing")], "Search"),
+        Mode::Input { .. } if app.multiline => (
+            &[(&[Submit], "submit"), (&[Execute], "newline"), (&[Cancel], "cancel")],
+            "Input Mode",
+        ),
+        Mode::Input { .. } if app.picker.is_none() => (
+            &[(&[Execute], "submit"), (&[Newline], "newline"), (&[Cancel], "cancel")],
+            "Input Mode",
+        ),
+        Mode::OutputPath | Mode::Input { .. } | Mode::Prompt(_) => {
+            (&[(&[Execute], "submit"), (&[Cancel], "cancel")], "Input Mode")
+        }
+        Mode::ConfirmTrust => {
+            (&[(&[Execute], "confirm"), (&[Cancel], "cancel")], "Confirm Trust")
+        }
+        Mode::Running => (&[(&[Cancel], "cancel")], "Running"),
+        Mode::Result if app.validation.as_ref().is_some_and(|v| !v.fixes.is_empty()) => {
+            (&[(&[Execute], "continue"), (&[Edit], "edit file"), (&[Fix], "fix")], "Done")
         }
-        Mode::Result => (" [Enter] continue", "Done"),
+        Mode::Result if app.last_written.is_some() => {
+            (&[(&[Execute], "continue"), (&[Edit], "edit file")], "Done")
+        }
+        Mode::Result => (&[(&[Execute], "continue")], "Done"),
     };
 
-    // If there's a status message, show it on the right
+    let mut left_text = String::new();
+    for (actions, label) in hints {
+        let keys: Vec<String> = actions.iter().map(|a| app.keys.key(*a)).collect();
+        left_text.push_str(&format!(" [{}] {label} ", keys.join("/")));
+    }
+    if app.mode == Mode::Result && app.undo_id.is_some() {
+        left_text.push_str(&format!(" [{}] undo ", app.keys.key(Undo)));
+    }
+    if matches!(app.mode, Mode::Filter | Mode::Search) || app.picker.is_some() {
+        left_text.push_str(" [Up/Down] navigate");
+    }
+    if app.picker.as_ref().is_some_and(|p| p.is_multi()) {
+        left_text.push_str("  [Tab] choose");
+    }
+    let left_text = left_text.trim_end();
+
+    // If there's a status message, show it on the right; a running job
+    // shows a spinner and its latest progress instead
     let right_content = if let Some(status) = &app.status {
         let style = if status.is_error {
             Style::default().fg(Color::Red)
         } else {
             Style::default().fg(Color::Green)
         };
-        Span::styled(&status.text, style)
+        Span::styled(status.text.as_str(), style)
+    } else if let Some(job) = &app.job {
+        let mut text = format!("{} {}", job.spinner(), job.label);
+        if job.is_cancelling() {
+            text.push_str(" (cancelling)");
+        } else if let Some(progress) = &job.progress {
+            text.push_str(&format!(": {progress}"));
+        }
+        Span::styled(text, Style::default().fg(Color::Cyan))
     } else {
         Span::styled(right_text, Style::default().fg(Color::DarkGray))
     };
@@ -32,8 +184,7 @@ pub fn draw(frame: &mut Frame, area: Rect, app: &App) {
 
     // Calculate padding for right-alignment
     let left_len = left_text.len();
-    let right_len =
-        if let Some(status) = &app.status { status.text.len() } else { right_text.len() };
+    let right_len = right_content.content.chars().count();
     let padding =
         area.width.saturating_sub(left_len as u16 + right_len as u16 + 2) as usize;
 
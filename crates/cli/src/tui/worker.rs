@@ -0,0 +1,143 @@
+//! Background jobs: templates, captures and macros run on a worker thread
+//! so the TUI keeps drawing, and report back as messages for
+//! `App::update`.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+use std::time::Instant;
+
+use super::app::Message;
+
+/// What a finished job reports: a message and the file it wrote, if any.
+pub type JobResult = Result<(String, Option<PathBuf>), String>;
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Handle the job uses to report progress and notice cancellation.
+pub struct JobContext {
+    sender: Sender<Message>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl JobContext {
+    pub fn progress(&self, text: impl Into<String>) {
+        let _ = self.sender.send(Message::JobProgress(text.into()));
+    }
+
+    /// Whether the user asked the job to stop.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+}
+
+/// A running job, polled by the UI loop.
+pub struct Job {
+    /// What is running, for the status bar.
+    pub label: String,
+    /// Latest progress the job reported.
+    pub progress: Option<String>,
+    receiver: Receiver<Message>,
+    cancel: Arc<AtomicBool>,
+    started: Instant,
+}
+
+impl Job {
+    pub fn spawn(
+        label: impl Into<String>,
+        work: impl FnOnce(&JobContext) -> JobResult + Send + 'static,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let ctx = JobContext { sender, cancel: Arc::clone(&cancel) };
+        thread::spawn(move || {
+            let result = work(&ctx);
+            let _ = ctx.sender.send(Message::JobDone(result));
+        });
+        Self {
+            label: label.into(),
+            progress: None,
+            receiver,
+            cancel,
+            started: Instant::now(),
+        }
+    }
+
+    /// Ask the job to stop at its next checkpoint.
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelling(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    /// The next message from the job, if one is waiting.
+    pub fn poll(&self) -> Option<Message> {
+        match self.receiver.try_recv() {
+            Ok(msg) => Some(msg),
+            Err(TryRecvError::Empty) => None,
+            // The worker died (panicked) without reporting
+            Err(TryRecvError::Disconnected) => Some(Message::JobDone(Err(format!(
+                "{} stopped unexpectedly",
+                self.label
+            )))),
+        }
+    }
+
+    /// Spinner frame for the time the job has been running.
+    pub fn spinner(&self) -> char {
+        let frame = self.started.elapsed().as_millis() / 100;
+        SPINNER[frame as usize % SPINNER.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// Messages until the job is done, the last being its result.
+    fn drain(job: &Job) -> Vec<Message> {
+        let mut messages = Vec::new();
+        loop {
+            match job.poll() {
+                Some(msg @ Message::JobDone(_)) => {
+                    messages.push(msg);
+                    return messages;
+                }
+                Some(msg) => messages.push(msg),
+                None => thread::sleep(Duration::from_millis(5)),
+            }
+        }
+    }
+
+    #[test]
+    fn reports_progress_then_result() {
+        let job = Job::spawn("test", |ctx| {
+            ctx.progress("halfway");
+            Ok(("done".to_string(), None))
+        });
+        let messages = drain(&job);
+        assert!(matches!(&messages[0], Message::JobProgress(p) if p == "halfway"));
+        assert!(matches!(&messages[1], Message::JobDone(Ok((m, None))) if m == "done"));
+    }
+
+    #[test]
+    fn cancel_reaches_the_job() {
+        let job = Job::spawn("test", |ctx| {
+            while !ctx.is_cancelled() {
+                thread::sleep(Duration::from_millis(5));
+            }
+            Err("cancelled".to_string())
+        });
+        assert!(!job.is_cancelling());
+        job.cancel();
+        assert!(job.is_cancelling());
+        let messages = drain(&job);
+        assert!(matches!(messages.last(), Some(Message::JobDone(Err(_)))));
+    }
+}
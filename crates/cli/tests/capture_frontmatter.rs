@@ -364,3 +364,49 @@ This file has no frontmatter.
     assert!(content.contains("status: draft"), "status should be draft");
     assert!(content.contains("# Plain Document"), "content should be preserved");
 }
+
+#[cfg(unix)]
+#[test]
+fn capture_records_metadata() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    let vault = root.join("vault");
+
+    let config = make_config(&vault.to_string_lossy())
+        + "\n[capture]\nlocation_command = \"echo 'Lisbon, PT'\"\n";
+    write(root, "config.toml", config);
+
+    write(
+        root,
+        "vault/captures/field-note.lua",
+        r#"
+return {
+    name = "field-note",
+    target = {
+        file = "journal.md",
+        section = "Notes",
+        position = "end",
+    },
+    content = "- {{text}}",
+    metadata = { "os", "location", hostname = "machine" },
+}
+"#,
+    );
+    write(root, "vault/journal.md", "# Journal\n\n## Notes\n");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args([
+        "capture",
+        "field-note",
+        "--var",
+        "text=Saw a heron",
+    ]);
+
+    cmd.assert().success().stdout(predicate::str::contains("metadata: "));
+
+    let content = fs::read_to_string(root.join("vault/journal.md")).unwrap();
+    assert!(content.contains(&format!("os: {}", std::env::consts::OS)), "{content}");
+    assert!(content.contains("location: Lisbon, PT"), "{content}");
+    assert!(content.contains("machine: "), "{content}");
+    assert!(content.contains("- Saw a heron"), "{content}");
+}
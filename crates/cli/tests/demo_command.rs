@@ -0,0 +1,53 @@
+//! Integration tests for the `mdv demo` command.
+
+use std::fs;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn mdv_cmd() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_mdv"))
+}
+
+#[test]
+fn test_demo_creates_indexed_vault() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path().join("demo");
+
+    let output = mdv_cmd()
+        .args(["demo", "--no-shell", "--seed", "3", "--path"])
+        .arg(&root)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("3 projects, 18 tasks"), "{stdout}");
+    assert!(stdout.contains("export MDV_CONFIG="), "{stdout}");
+    assert!(root.join("vault/.mdvault/index.db").exists());
+
+    // The printed config works through MDV_CONFIG, without --config
+    let output = mdv_cmd()
+        .env("MDV_CONFIG", root.join("config.toml"))
+        .args(["list", "--type", "task", "--stable-json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let tasks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(tasks.as_array().unwrap().len(), 18);
+}
+
+#[test]
+fn test_demo_refuses_non_empty_directory() {
+    let tmp = tempdir().unwrap();
+    let vault = tmp.path().join("vault");
+    fs::create_dir_all(&vault).unwrap();
+    fs::write(vault.join("mine.md"), "# Mine\n").unwrap();
+
+    let output = mdv_cmd()
+        .args(["demo", "--no-shell", "--path"])
+        .arg(tmp.path())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not empty"));
+    assert_eq!(fs::read_to_string(vault.join("mine.md")).unwrap(), "# Mine\n");
+}
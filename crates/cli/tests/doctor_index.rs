@@ -0,0 +1,96 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(config: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(config).args(args);
+    cmd
+}
+
+fn setup() -> (tempfile::TempDir, std::path::PathBuf) {
+    let tmp = tempdir().unwrap();
+    let vault = tmp.path().join("vault");
+    let config = tmp.path().join("config.toml");
+    write(
+        tmp.path(),
+        "config.toml",
+        &format!(
+            r#"
+version = 1
+profile = "test"
+
+[profiles.test]
+vault_root = "{}"
+templates_dir = "{{{{vault_root}}}}/templates"
+captures_dir = "{{{{vault_root}}}}/captures"
+macros_dir = "{{{{vault_root}}}}/macros"
+"#,
+            vault.display()
+        ),
+    );
+    write(&vault, "a.md", "# A\n\nSee [[b]].\n");
+    write(&vault, "gone.md", "# Gone\n");
+    mdv(&config, &["reindex"]).assert().success();
+    (tmp, config)
+}
+
+#[test]
+fn doctor_index_reports_healthy_index() {
+    let (_tmp, config) = setup();
+
+    mdv(&config, &["doctor", "--index"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("OK   mdv doctor --index"))
+        .stdout(predicate::str::contains("notes: 2 on disk, 2 indexed"));
+}
+
+#[test]
+fn doctor_index_finds_and_fixes_drift() {
+    let (tmp, config) = setup();
+    let vault = tmp.path().join("vault");
+    write(&vault, "b.md", "# B\n");
+    fs::remove_file(vault.join("gone.md")).unwrap();
+    write(&vault, "a.md", "# A\n\nSee [[b]], edited.\n");
+
+    mdv(&config, &["doctor", "--index"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("FAIL mdv doctor --index"))
+        .stdout(predicate::str::contains(
+            "notes on disk missing from the index (1):\n  b.md",
+        ))
+        .stdout(predicate::str::contains(
+            "index entries whose file was deleted (1):\n  gone.md",
+        ))
+        .stdout(predicate::str::contains(
+            "notes changed since they were indexed (1):\n  a.md",
+        ))
+        .stdout(predicate::str::contains("mdv doctor --index --fix"));
+
+    mdv(&config, &["doctor", "--index", "--fix"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "fixed: 1 not_indexed, 1 file_missing, 1 hash_mismatch",
+        ))
+        .stdout(predicate::str::contains("OK   mdv doctor --index"));
+
+    mdv(&config, &["doctor", "--index"]).assert().success();
+}
+
+#[test]
+fn doctor_fix_requires_index() {
+    let (_tmp, config) = setup();
+    mdv(&config, &["doctor", "--fix"]).assert().failure();
+}
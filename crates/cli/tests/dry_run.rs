@@ -0,0 +1,180 @@
+//! Integration tests for `mdv new --dry-run` and `mdv capture --dry-run`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: impl AsRef<str>) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content.as_ref()).unwrap();
+}
+
+fn make_config(vault_root: &str) -> String {
+    format!(
+        r#"
+version = 1
+profile = "test"
+
+[profiles.test]
+vault_root = "{vault_root}"
+templates_dir = "{{{{vault_root}}}}/templates"
+captures_dir = "{{{{vault_root}}}}/captures"
+macros_dir = "{{{{vault_root}}}}/macros"
+"#
+    )
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+#[test]
+fn new_dry_run_prints_hooked_note_without_writing() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    let vault = root.join("vault");
+    write(root, "config.toml", make_config(&vault.to_string_lossy()));
+    fs::create_dir_all(vault.join("captures")).unwrap();
+    fs::create_dir_all(vault.join("macros")).unwrap();
+    write(
+        root,
+        "types/ticket.lua",
+        r#"
+return {
+    output = "tickets/{{title | slug}}.md",
+    permissions = { net = true },
+    on_create = function(note)
+        pcall(mdv.write_note, "side-effect.md", "should not exist\n")
+        local fetched = pcall(mdv.fetch, "http://127.0.0.1:9/")
+        note.frontmatter.reviewed = "yes"
+        note.frontmatter.fetch = fetched and "allowed" or "denied"
+        return note
+    end,
+}
+"#,
+    );
+    write(root, "vault/templates/ticket.md", "---\ntype: ticket\n---\n# {{title}}\n");
+
+    // Trusted, so only the dry run keeps the hook off the network
+    mdv(
+        root,
+        &["--trust-scripts", "new", "ticket", "Broken Login", "--batch", "--dry-run"],
+    )
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("DRY RUN mdv new"))
+    .stdout(predicate::str::contains("fetch: denied"))
+    .stdout(predicate::str::contains("tickets/broken-login.md"))
+    .stdout(predicate::str::contains("reviewed: yes"))
+    .stdout(predicate::str::contains("# Broken Login"))
+    .stdout(predicate::str::contains("Nothing was written."));
+
+    assert!(!vault.join("tickets/broken-login.md").exists());
+    assert!(!vault.join("side-effect.md").exists());
+}
+
+#[test]
+fn capture_dry_run_prints_diff_without_writing() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    let vault = root.join("vault");
+    write(root, "config.toml", make_config(&vault.to_string_lossy()));
+    write(
+        root,
+        "vault/captures/inbox.lua",
+        r#"
+return {
+    name = "inbox",
+    target = { file = "notes.md", section = "Inbox", position = "end" },
+    content = "- {{text}}",
+}
+"#,
+    );
+    let original = "# Notes\n\n## Inbox\n\n- Existing item\n";
+    write(root, "vault/notes.md", original);
+
+    mdv(root, &["capture", "inbox", "--var", "text=New item", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DRY RUN mdv capture"))
+        .stdout(predicate::str::contains("section: Inbox (level 2)"))
+        .stdout(predicate::str::contains("--- notes.md\n+++ notes.md"))
+        .stdout(predicate::str::contains("+- New item"))
+        .stdout(predicate::str::contains("OK   mdv capture").not());
+
+    assert_eq!(fs::read_to_string(vault.join("notes.md")).unwrap(), original);
+}
+
+#[test]
+fn capture_dry_run_does_not_create_missing_target() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    let vault = root.join("vault");
+    write(root, "config.toml", make_config(&vault.to_string_lossy()));
+    write(
+        root,
+        "vault/captures/log.lua",
+        r#"
+return {
+    name = "log",
+    target = { file = "log/today.md", section = "Log", create_if_missing = true },
+    content = "- {{text}}",
+}
+"#,
+    );
+
+    mdv(root, &["capture", "log", "--var", "text=Started", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(would be created)"))
+        .stdout(predicate::str::contains("+## Log"))
+        .stdout(predicate::str::contains("+- Started"));
+
+    assert!(!vault.join("log").exists());
+}
+
+#[test]
+fn capture_dry_run_does_not_run_the_location_command() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    let vault = root.join("vault");
+    let marker = root.join("location-ran");
+    let config = make_config(&vault.to_string_lossy())
+        + &format!(
+            "\n[capture]\nlocation_command = \"touch '{}'; echo Lisbon\"\n",
+            marker.display()
+        );
+    write(root, "config.toml", config);
+    write(
+        root,
+        "vault/captures/field-note.lua",
+        r#"
+return {
+    name = "field-note",
+    target = { file = "journal.md", section = "Notes", position = "end" },
+    content = "- {{text}}",
+    metadata = { "location" },
+}
+"#,
+    );
+    write(root, "vault/journal.md", "# Journal\n\n## Notes\n");
+
+    mdv(root, &["capture", "field-note", "--var", "text=Harbour", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("+location: <location>"))
+        .stdout(predicate::str::contains("Lisbon").not());
+    assert!(!marker.exists());
+
+    // Without --dry-run the command does run
+    mdv(root, &["capture", "field-note", "--var", "text=Harbour"]).assert().success();
+    assert!(marker.exists());
+}
@@ -0,0 +1,87 @@
+//! Integration tests for `mdv duplicates`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+const IDEA: &str = "Cache rendered previews on the CDN so the editor loads \
+                    instantly for readers on slow connections.";
+
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            root.join("vault").display()
+        ),
+    );
+    let vault = root.join("vault");
+    write(&vault, "ideas/previews.md", &format!("# Previews\n\n{IDEA}\n"));
+    write(
+        &vault,
+        "Inbox/snippet.md",
+        &format!("# Previews\n\n{IDEA} Also for mobile.\n"),
+    );
+    write(&vault, "roadmap.md", "# Roadmap\n\nShip [[ideas/previews]] next quarter.\n");
+    write(&vault, "budget.md", "# Budget\n\nQuarterly budget review with finance.\n");
+    mdv(root, &["reindex"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Duplicate pairs:      1"));
+    tmp
+}
+
+#[test]
+fn duplicates_suggests_keeping_the_linked_note() {
+    let tmp = setup();
+    mdv(tmp.path(), &["duplicates"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ideas/previews.md  Inbox/snippet.md"))
+        .stdout(predicate::str::contains("more backlinks"))
+        .stdout(predicate::str::contains("-- 1 duplicate pairs --"));
+
+    mdv(tmp.path(), &["duplicates", "-q"])
+        .assert()
+        .success()
+        .stdout("ideas/previews.md\tInbox/snippet.md\n");
+
+    let output = mdv(tmp.path(), &["duplicates", "--json"]).output().unwrap();
+    let pairs: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(pairs[0]["keep"]["backlinks"], 1);
+    assert_eq!(pairs[0]["merge"]["path"], "Inbox/snippet.md");
+    assert!(pairs[0]["similarity"].as_f64().unwrap() < 1.0);
+}
+
+#[test]
+fn duplicates_respects_threshold() {
+    let tmp = setup();
+    mdv(tmp.path(), &["duplicates", "--threshold", "0.99"])
+        .assert()
+        .success()
+        .stdout("(no duplicate notes found)\n");
+}
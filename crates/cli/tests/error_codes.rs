@@ -0,0 +1,34 @@
+//! Integration tests for the error code reported when a command fails.
+
+use std::process::Command;
+
+fn mdv(args: &[&str]) -> std::process::Output {
+    Command::new(assert_cmd::cargo::cargo_bin!("mdv"))
+        .arg("--config")
+        .arg("/nonexistent/mdvault/config.toml")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn failures_end_with_their_error_code() {
+    let output = mdv(&["list"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Failed to load config"), "{stderr}");
+    assert!(stderr.trim_end().ends_with("Error code: config"), "{stderr}");
+}
+
+#[test]
+fn json_commands_report_failures_as_json() {
+    let output = mdv(&["list", "--json"]);
+    assert!(!output.status.success());
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"]["code"], "config");
+    assert_eq!(error["error"]["retryable"], false);
+    assert_eq!(error["error"]["message"], "Failed to load config");
+    assert!(
+        error["error"]["causes"][0].as_str().unwrap().contains("config file not found")
+    );
+}
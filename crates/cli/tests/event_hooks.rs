@@ -0,0 +1,91 @@
+//! Integration tests for Lua event hooks in `hooks_dir`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            root.join("vault").display()
+        ),
+    );
+    for dir in ["templates", "captures", "macros"] {
+        fs::create_dir_all(root.join("vault").join(dir)).unwrap();
+    }
+    write(root, "vault/old.md", "# Old\n");
+    write(root, "vault/source.md", "See [[old]].\n");
+    // Hooks live next to the config file by default
+    write(
+        root,
+        "hooks/log.lua",
+        r#"
+return {
+    on_rename = function(old_path, new_path)
+        local ok, err = mdv.write_note("renames.md", old_path .. " -> " .. new_path .. "\n")
+        assert(ok, err)
+    end,
+}
+"#,
+    );
+    mdv(root, &["reindex"]).assert().success();
+    tmp
+}
+
+#[test]
+fn rename_runs_on_rename_hooks() {
+    let tmp = setup();
+    let root = tmp.path();
+    mdv(root, &["rename", "old.md", "Archive/new.md", "--yes"]).assert().success();
+
+    let vault = root.join("vault");
+    assert_eq!(
+        fs::read_to_string(vault.join("renames.md")).unwrap(),
+        "old.md -> Archive/new.md\n"
+    );
+    assert_eq!(fs::read_to_string(vault.join("source.md")).unwrap(), "See [[new]].\n");
+}
+
+#[test]
+fn failing_hook_does_not_undo_the_rename() {
+    let tmp = setup();
+    let root = tmp.path();
+    write(
+        root,
+        "hooks/broken.lua",
+        r#"return { on_rename = function() error("boom") end }"#,
+    );
+
+    mdv(root, &["rename", "old.md", "new.md", "--yes"]).assert().success().stderr(
+        predicate::str::contains("broken.lua").and(predicate::str::contains("boom")),
+    );
+
+    let vault = root.join("vault");
+    assert!(vault.join("new.md").exists());
+    assert!(vault.join("renames.md").exists());
+}
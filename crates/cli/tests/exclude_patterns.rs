@@ -0,0 +1,102 @@
+//! Integration tests for `.mdvignore` and `--exclude`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            root.join("vault").display()
+        ),
+    );
+    let vault = root.join("vault");
+    write(&vault, ".mdvignore", "# build output\ngenerated/\n");
+    write(&vault, "ideas.md", "# Ideas\n\nRoadmap thoughts.\n");
+    write(&vault, "Archive/2023/old.md", "# Old\n\nRoadmap from last year.\n");
+    write(&vault, "generated/summary.md", "# Summary\n\nRoadmap digest.\n");
+    tmp
+}
+
+#[test]
+fn reindex_skips_mdvignore_matches() {
+    let tmp = setup();
+    mdv(tmp.path(), &["reindex"]).assert().success();
+    mdv(tmp.path(), &["list", "-q"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ideas.md"))
+        .stdout(predicate::str::contains("Archive/2023/old.md"))
+        .stdout(predicate::str::contains("generated").not());
+}
+
+#[test]
+fn reindex_exclude_leaves_notes_out_without_tombstones() {
+    let tmp = setup();
+    mdv(tmp.path(), &["reindex"]).assert().success();
+    mdv(tmp.path(), &["reindex", "--exclude", "Archive/"]).assert().success();
+
+    mdv(tmp.path(), &["list", "-q"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Archive").not());
+    mdv(tmp.path(), &["list", "--deleted", "-q"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn list_search_and_validate_take_exclude() {
+    let tmp = setup();
+    mdv(tmp.path(), &["reindex"]).assert().success();
+
+    mdv(tmp.path(), &["list", "-q", "--exclude", "Archive/**", "-n", "1"])
+        .assert()
+        .success()
+        .stdout("ideas.md\n");
+    mdv(tmp.path(), &["search", "roadmap", "-q", "--exclude", "/Archive"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ideas.md"))
+        .stdout(predicate::str::contains("Archive").not());
+    mdv(tmp.path(), &["validate", "--exclude", "*.md", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"total\": 0"));
+}
+
+#[test]
+fn invalid_patterns_are_reported() {
+    let tmp = setup();
+    mdv(tmp.path(), &["reindex"]).assert().success();
+    mdv(tmp.path(), &["list", "--exclude", "[abc"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid exclude pattern"));
+}
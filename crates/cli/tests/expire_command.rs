@@ -0,0 +1,122 @@
+//! Integration tests for `mdv expire`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: impl AsRef<str>) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content.as_ref()).unwrap();
+}
+
+fn make_config(vault_root: &str) -> String {
+    format!(
+        r#"
+version = 1
+profile = "test"
+
+[profiles.test]
+vault_root = "{vault_root}"
+templates_dir = "{{{{vault_root}}}}/templates"
+captures_dir = "{{{{vault_root}}}}/captures"
+macros_dir = "{{{{vault_root}}}}/macros"
+"#
+    )
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(root, "config.toml", make_config(&root.join("vault").to_string_lossy()));
+    write(
+        root,
+        "types/meeting.lua",
+        r#"return { retention = { after = "1y", action = "archive" } }"#,
+    );
+    write(root, "vault/keep.md", "---\nexpires: 2999-01-01\n---\n# Keep\n");
+    write(root, "vault/scratch.md", "---\nexpires: 2020-01-01\non_expire: trash\n---\n");
+    write(
+        root,
+        "vault/Meetings/2020/standup.md",
+        "---\ntype: meeting\ndate: 2020-03-02\n---\n# Standup\n",
+    );
+    mdv(root, &["reindex"]).assert().success();
+    tmp
+}
+
+#[test]
+fn expire_dry_run_moves_nothing() {
+    let tmp = setup();
+    let root = tmp.path();
+
+    mdv(root, &["expire", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "archive   Meetings/2020/standup.md  (expired 2021-03-02, meeting retention 1y)",
+        ))
+        .stdout(predicate::str::contains("trash     scratch.md"))
+        .stdout(predicate::str::contains("keep.md").not())
+        .stdout(predicate::str::contains("2 note(s) would be moved"));
+
+    assert!(root.join("vault/scratch.md").exists());
+    assert!(root.join("vault/Meetings/2020/standup.md").exists());
+}
+
+#[test]
+fn expire_moves_notes_and_undo_restores_them() {
+    let tmp = setup();
+    let root = tmp.path();
+    let vault = root.join("vault");
+
+    mdv(root, &["expire"]).assert().success().stdout(predicate::str::contains(
+        "archive   Meetings/2020/standup.md -> Archive/Meetings/2020/standup.md",
+    ));
+    assert!(vault.join("Archive/Meetings/2020/standup.md").exists());
+    assert!(!vault.join("scratch.md").exists());
+    assert!(vault.join("keep.md").exists());
+    let trashed: Vec<_> = fs::read_dir(vault.join(".mdvault/trash")).unwrap().collect();
+    assert_eq!(trashed.len(), 1);
+
+    // Archived notes stay searchable under their new path; trashed ones are gone
+    mdv(root, &["list", "--quiet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Archive/Meetings/2020/standup.md"))
+        .stdout(predicate::str::contains("scratch.md").not());
+
+    // A second run finds nothing new
+    mdv(root, &["expire"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No expired notes."));
+
+    mdv(root, &["expire", "--undo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("restored  scratch.md"));
+    assert!(vault.join("scratch.md").exists());
+    assert!(vault.join("Meetings/2020/standup.md").exists());
+    assert!(!vault.join("Archive/Meetings/2020/standup.md").exists());
+
+    mdv(root, &["list", "--quiet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Archive/").not());
+    mdv(root, &["expire", "--undo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Nothing to undo."));
+}
@@ -0,0 +1,249 @@
+//! Golden-file tests for query command output.
+//!
+//! Each test copies the canned vault in `fixtures/golden_vault/` into a temp
+//! directory, indexes it, runs one `mdv` subcommand, and snapshots stdout.
+//! Table output is normalized for timestamps; `--stable-json` output must
+//! match byte for byte. When an output change is intended, review it with
+//! `cargo insta review`.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use regex::Regex;
+use tempfile::TempDir;
+
+struct GoldenVault {
+    _tmp: TempDir,
+    vault: PathBuf,
+    cfg_path: PathBuf,
+}
+
+impl GoldenVault {
+    fn new() -> Self {
+        let tmp = tempfile::tempdir().unwrap();
+        let vault = tmp.path().join("vault");
+        let fixture =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/golden_vault");
+        copy_dir(&fixture, &vault);
+
+        for dir in ["typedefs", "templates", "captures", "macros"] {
+            fs::create_dir_all(vault.join(".mdvault").join(dir)).unwrap();
+        }
+
+        let cfg_dir = tmp.path().join("xdg/mdvault");
+        fs::create_dir_all(&cfg_dir).unwrap();
+        let cfg_path = cfg_dir.join("config.toml");
+
+        let mut toml = String::new();
+        writeln!(toml, "version = 1").unwrap();
+        writeln!(toml, "profile = \"default\"").unwrap();
+        writeln!(toml, "[profiles.default]").unwrap();
+        writeln!(toml, "vault_root = \"{}\"", vault.display()).unwrap();
+        for dir in ["typedefs", "templates", "captures", "macros"] {
+            writeln!(toml, "{dir}_dir = \"{}/.mdvault/{dir}\"", vault.display()).unwrap();
+        }
+        fs::write(&cfg_path, toml).unwrap();
+
+        let golden = Self { _tmp: tmp, vault, cfg_path };
+        golden.run(&["reindex"]);
+        golden
+    }
+
+    /// Run `mdv` with the vault's config and return normalized stdout.
+    fn run(&self, args: &[&str]) -> String {
+        let output = Command::new(assert_cmd::cargo::cargo_bin!("mdv"))
+            .env("NO_COLOR", "1")
+            .current_dir(&self.vault)
+            .arg("--config")
+            .arg(&self.cfg_path)
+            .args(args)
+            .output()
+            .expect("failed to run mdv");
+        assert!(
+            output.status.success(),
+            "mdv {:?} failed:\n{}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let stdout = stdout.replace(&self.vault.display().to_string(), "<VAULT>");
+        Regex::new(r"\d{4}-\d{2}-\d{2} \d{2}:\d{2}")
+            .unwrap()
+            .replace_all(&stdout, "<DATETIME>")
+            .to_string()
+    }
+}
+
+fn copy_dir(src: &Path, dst: &Path) {
+    fs::create_dir_all(dst).unwrap();
+    for entry in fs::read_dir(src).unwrap() {
+        let entry = entry.unwrap();
+        let target = dst.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            fs::copy(entry.path(), &target).unwrap();
+        }
+    }
+}
+
+// --- list ---
+
+#[test]
+fn list_table() {
+    let vault = GoldenVault::new();
+    // Table rows are ordered by mtime, which the copy does not preserve
+    let mut lines: Vec<String> = vault.run(&["list"]).lines().map(String::from).collect();
+    lines[2..9].sort();
+    insta::assert_snapshot!(lines.join("\n"));
+}
+
+#[test]
+fn list_stable_json() {
+    let vault = GoldenVault::new();
+    insta::assert_snapshot!(vault.run(&["list", "--stable-json"]));
+}
+
+#[test]
+fn list_stable_json_by_type() {
+    let vault = GoldenVault::new();
+    insta::assert_snapshot!(vault.run(&["list", "--type", "task", "--stable-json"]));
+}
+
+#[test]
+fn list_where_stable_json() {
+    let vault = GoldenVault::new();
+    insta::assert_snapshot!(vault.run(&[
+        "list",
+        "--where",
+        "status!=done",
+        "--where",
+        "project=ALP",
+        "--stable-json"
+    ]));
+}
+
+#[test]
+fn stable_json_flag_matches_output_option() {
+    let vault = GoldenVault::new();
+    assert_eq!(
+        vault.run(&["list", "--stable-json"]),
+        vault.run(&["list", "--output", "stable-json"])
+    );
+}
+
+#[test]
+fn stable_json_note_fields_are_fixed() {
+    let vault = GoldenVault::new();
+    let json: serde_json::Value =
+        serde_json::from_str(&vault.run(&["list", "--stable-json"])).unwrap();
+
+    for note in json.as_array().unwrap() {
+        let mut keys: Vec<&str> =
+            note.as_object().unwrap().keys().map(|k| k.as_str()).collect();
+        keys.sort();
+        assert_eq!(keys, ["path", "title", "type"]);
+    }
+}
+
+// --- search ---
+
+#[test]
+fn search_stable_json() {
+    let vault = GoldenVault::new();
+    insta::assert_snapshot!(vault.run(&["search", "compiler", "--stable-json"]));
+}
+
+#[test]
+fn search_quiet_body_only_match() {
+    let vault = GoldenVault::new();
+    insta::assert_snapshot!(vault.run(&["search", "gardening", "--quiet"]));
+}
+
+// --- links ---
+
+#[test]
+fn links_table() {
+    let vault = GoldenVault::new();
+    insta::assert_snapshot!(vault.run(&["links", "Projects/ALP/Tasks/ALP-001.md"]));
+}
+
+#[test]
+fn links_stable_json() {
+    let vault = GoldenVault::new();
+    insta::assert_snapshot!(vault.run(&[
+        "links",
+        "Projects/ALP/Tasks/ALP-001.md",
+        "--stable-json"
+    ]));
+}
+
+#[test]
+fn links_stable_json_outgoing_only() {
+    let vault = GoldenVault::new();
+    insta::assert_snapshot!(vault.run(&[
+        "links",
+        "zettel/compiler-notes.md",
+        "--outlinks",
+        "--stable-json"
+    ]));
+}
+
+#[test]
+fn links_table_with_anchors() {
+    let vault = GoldenVault::new();
+    insta::assert_snapshot!(vault.run(&[
+        "links",
+        "zettel/compiler-notes.md",
+        "--outlinks"
+    ]));
+}
+
+// --- task ---
+
+#[test]
+fn task_list_inline() {
+    let vault = GoldenVault::new();
+    insta::assert_snapshot!(vault.run(&["task", "list", "--inline"]));
+}
+
+#[test]
+fn task_list_inline_open_in_project() {
+    let vault = GoldenVault::new();
+    insta::assert_snapshot!(vault.run(&[
+        "task",
+        "list",
+        "--inline",
+        "--project",
+        "ALP",
+        "--status",
+        "todo"
+    ]));
+}
+
+// --- orphans ---
+
+#[test]
+fn orphans_stable_json() {
+    let vault = GoldenVault::new();
+    insta::assert_snapshot!(vault.run(&["orphans", "--stable-json"]));
+}
+
+// --- accessible ---
+
+#[test]
+fn task_list_accessible() {
+    let vault = GoldenVault::new();
+    insta::assert_snapshot!(vault.run(&["task", "list", "--accessible"]));
+}
+
+#[test]
+fn project_progress_accessible() {
+    let vault = GoldenVault::new();
+    let out = vault.run(&["project", "progress", "ALP", "--accessible"]);
+    assert!(out.is_ascii(), "non-ASCII output:\n{out}");
+    insta::assert_snapshot!(out);
+}
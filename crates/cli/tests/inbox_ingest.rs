@@ -0,0 +1,109 @@
+//! Integration tests for `mdv inbox ingest`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            root.join("vault").display()
+        ),
+    );
+    tmp
+}
+
+fn snippet_notes(vault: &Path) -> Vec<PathBuf> {
+    let mut notes: Vec<_> = fs::read_dir(vault.join("Inbox"))
+        .map(|dir| dir.map(|e| e.unwrap().path()).collect())
+        .unwrap_or_default();
+    notes.sort();
+    notes
+}
+
+#[test]
+fn ingest_drains_snippets_and_reports_each_file() {
+    let tmp = setup();
+    let root = tmp.path();
+    let drop = root.join("drop");
+    write(&drop, "a-share.txt", "Book the venue\nbefore Friday\n");
+    write(&drop, "b-share.md", "Book the venue\nbefore Friday\n");
+    write(&drop, "c-empty.txt", "  \n");
+    write(&drop, "photo.jpg", "binary");
+    write(&drop, ".syncthing.d-share.txt.tmp", "partial");
+
+    mdv(root, &["inbox", "ingest", &drop.to_string_lossy()])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("OK    a-share.txt -> Inbox/snippet-"))
+        .stdout(predicate::str::contains("DUP   b-share.md (already filed as Inbox/"))
+        .stdout(predicate::str::contains("FAIL  c-empty.txt: snippet is empty"))
+        .stdout(predicate::str::contains("-- 1 filed, 1 duplicate, 1 failed --"))
+        .stderr(predicate::str::contains("1 snippet(s) could not be filed"));
+
+    let notes = snippet_notes(&root.join("vault"));
+    assert_eq!(notes.len(), 1);
+    let note = fs::read_to_string(&notes[0]).unwrap();
+    assert!(note.contains("type: inbox\n"));
+    assert!(note.contains("title: Book the venue\n"));
+    assert!(note.ends_with("---\n\nBook the venue\nbefore Friday\n"));
+
+    // Filed and duplicate snippets are drained; the rest stay
+    let mut left: Vec<_> = fs::read_dir(&drop)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    left.sort();
+    assert_eq!(left, [".syncthing.d-share.txt.tmp", "c-empty.txt", "photo.jpg"]);
+
+    // A re-share after triage is still recognised
+    fs::remove_file(&notes[0]).unwrap();
+    fs::remove_file(drop.join("c-empty.txt")).unwrap();
+    write(&drop, "again.txt", "Book the venue\nbefore Friday");
+    mdv(root, &["inbox", "ingest", &drop.to_string_lossy(), "--keep"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DUP   again.txt"));
+    assert!(drop.join("again.txt").exists());
+    assert!(snippet_notes(&root.join("vault")).is_empty());
+}
+
+#[test]
+fn ingest_indexes_new_notes() {
+    let tmp = setup();
+    let root = tmp.path();
+    let drop = root.join("drop");
+    mdv(root, &["reindex"]).assert().success();
+    write(&drop, "idea.txt", "# Cheaper caching\nUse the CDN.\n");
+
+    mdv(root, &["inbox", "ingest", &drop.to_string_lossy()]).assert().success();
+    mdv(root, &["list", "-q"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Inbox/snippet-"));
+}
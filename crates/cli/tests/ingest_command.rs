@@ -0,0 +1,155 @@
+#![cfg(unix)]
+//! Integration tests for `mdv ingest audio`, using `cat` as the transcriber.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: impl AsRef<str>) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content.as_ref()).unwrap();
+}
+
+fn make_config(vault_root: &str, ingest: &str) -> String {
+    format!(
+        r#"
+version = 1
+profile = "test"
+
+[profiles.test]
+vault_root = "{vault_root}"
+templates_dir = "{{{{vault_root}}}}/templates"
+captures_dir = "{{{{vault_root}}}}/captures"
+macros_dir = "{{{{vault_root}}}}/macros"
+
+{ingest}
+"#
+    )
+}
+
+fn inbox_notes(vault: &Path) -> Vec<PathBuf> {
+    let mut notes: Vec<_> = fs::read_dir(vault.join("Inbox"))
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.file_name().unwrap().to_string_lossy().starts_with("voice-"))
+        .collect();
+    notes.sort();
+    notes
+}
+
+#[test]
+fn ingest_audio_creates_note_and_archives_recording() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    let vault = root.join("vault");
+    write(root, "config.toml", make_config(&vault.to_string_lossy(), ""));
+    write(root, "memo.m4a", "Remember to book the venue.");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config")
+        .arg(root.join("config.toml"))
+        .args(["ingest", "audio", "--transcriber", "cat {file}", "--title", "Venue"])
+        .arg(root.join("memo.m4a"));
+    cmd.assert().success().stdout(predicate::str::contains("OK   mdv ingest audio"));
+
+    let notes = inbox_notes(&vault);
+    assert_eq!(notes.len(), 1);
+    let note = fs::read_to_string(&notes[0]).unwrap();
+    assert!(note.contains("type: voice-memo\n"));
+    assert!(note.contains("title: Venue\n"));
+    assert!(note.contains("## Transcript\n\nRemember to book the venue.\n"));
+
+    let audio_dir = vault.join("assets/audio");
+    let archived: Vec<_> = walk(&audio_dir);
+    assert_eq!(archived.len(), 1);
+    let rel = archived[0].strip_prefix(&vault).unwrap().to_string_lossy().to_string();
+    assert!(note.contains(&format!("![Recording](../{rel})")));
+    assert!(root.join("memo.m4a").exists(), "original kept without --move");
+
+    let activity = fs::read_to_string(vault.join(".mdvault/activity.jsonl")).unwrap();
+    assert!(activity.contains("\"type\":\"voice-memo\""), "{activity}");
+}
+
+#[test]
+fn ingest_audio_uses_config_and_runs_capture() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    let vault = root.join("vault");
+    write(
+        root,
+        "config.toml",
+        make_config(
+            &vault.to_string_lossy(),
+            "[ingest]\ntranscriber = \"tr a-z A-Z <\"\ncapture = \"inbox\"",
+        ),
+    );
+    write(
+        root,
+        "vault/captures/inbox.lua",
+        r#"
+return {
+    name = "inbox",
+    description = "Add to inbox",
+    target = {
+        file = "inbox.md",
+        section = "Inbox",
+        position = "end",
+    },
+    content = "- {{text}}",
+}
+"#,
+    );
+    write(root, "vault/inbox.md", "# Inbox\n\n## Inbox\n\n- Existing item\n");
+    write(root, "memo.ogg", "call mum");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config")
+        .arg(root.join("config.toml"))
+        .args(["ingest", "audio", "--move"])
+        .arg(root.join("memo.ogg"));
+    cmd.assert().success().stdout(predicate::str::contains("OK   mdv capture"));
+
+    let notes = inbox_notes(&vault);
+    let note = fs::read_to_string(&notes[0]).unwrap();
+    assert!(note.contains("CALL MUM"));
+    assert!(!root.join("memo.ogg").exists(), "--move removes the original");
+
+    let stem = notes[0].file_stem().unwrap().to_string_lossy().to_string();
+    let inbox = fs::read_to_string(vault.join("inbox.md")).unwrap();
+    assert!(inbox.contains(&format!("- [[Inbox/{stem}|Voice memo ")), "{inbox}");
+}
+
+#[test]
+fn ingest_audio_without_transcriber_fails() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    let vault = root.join("vault");
+    write(root, "config.toml", make_config(&vault.to_string_lossy(), ""));
+    write(root, "memo.m4a", "words");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config")
+        .arg(root.join("config.toml"))
+        .args(["ingest", "audio"])
+        .arg(root.join("memo.m4a"));
+    cmd.assert().failure().stderr(predicate::str::contains("No transcriber configured"));
+}
+
+fn walk(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(walk(&path));
+        } else {
+            files.push(path);
+        }
+    }
+    files
+}
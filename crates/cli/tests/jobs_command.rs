@@ -0,0 +1,118 @@
+//! Integration tests for scheduled Lua jobs (`mdv jobs`).
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup(jobs: &str) -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            root.join("vault").display()
+        ),
+    );
+    for dir in ["templates", "captures", "macros"] {
+        fs::create_dir_all(root.join("vault").join(dir)).unwrap();
+    }
+    write(root, "vault/.mdvault/jobs.lua", jobs);
+    tmp
+}
+
+const JOBS: &str = r#"
+job "stamp" {
+    every = "6h",
+    description = "Write a stamp note",
+    run = function(job)
+        local ok, err = mdv.write_note("stamp.md", "ran " .. job.name .. "\n")
+        assert(ok, err)
+        return "stamped"
+    end,
+}
+
+job "broken" {
+    every = "daily 07:00",
+    run = function() error("boom") end,
+}
+"#;
+
+#[test]
+fn run_due_runs_jobs_and_records_last_runs() {
+    let tmp = setup(JOBS);
+    let root = tmp.path();
+    let vault = root.join("vault");
+
+    mdv(root, &["jobs", "run-due", "--dry-run"]).assert().success().stdout(
+        predicate::str::contains("Would run stamp")
+            .and(predicate::str::contains("Would run broken")),
+    );
+    assert!(!vault.join("stamp.md").exists());
+
+    mdv(root, &["jobs", "run-due"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("OK   stamp\nstamped"))
+        .stderr(
+            predicate::str::contains("FAIL broken")
+                .and(predicate::str::contains("1 job(s) failed")),
+        );
+    assert_eq!(fs::read_to_string(vault.join("stamp.md")).unwrap(), "ran stamp\n");
+
+    let state = fs::read_to_string(vault.join(".mdvault/state/jobs.toml")).unwrap();
+    assert!(state.contains("[jobs.stamp]") && state.contains("last_run"), "{state}");
+    assert!(state.contains("boom"), "{state}");
+
+    // The successful job is not due again; the failed one is retried
+    mdv(root, &["jobs", "run-due", "--dry-run"]).assert().success().stdout(
+        predicate::str::contains("Would run broken")
+            .and(predicate::str::contains("stamp").not()),
+    );
+
+    mdv(root, &["jobs", "list", "--json"]).assert().success().stdout(
+        predicate::str::contains("\"last_error\"")
+            .and(predicate::str::contains("\"due\": false")),
+    );
+
+    // `run` ignores the schedule
+    fs::remove_file(vault.join("stamp.md")).unwrap();
+    mdv(root, &["jobs", "run", "stamp"]).assert().success();
+    assert!(vault.join("stamp.md").exists());
+}
+
+#[test]
+fn rejects_invalid_jobs() {
+    let tmp = setup(r#"job "bad" { every = "sometimes", run = function() end }"#);
+    mdv(tmp.path(), &["jobs", "list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("'bad': invalid schedule 'sometimes'"));
+
+    let tmp = setup("");
+    mdv(tmp.path(), &["jobs", "run", "nosuch"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown job 'nosuch'"));
+}
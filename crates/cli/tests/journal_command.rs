@@ -0,0 +1,123 @@
+//! Integration tests for the `mdv journal` command.
+
+use std::fs;
+use std::io::Write;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn mdv_cmd() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_mdv"))
+}
+
+fn create_test_config(vault_path: &std::path::Path, config_path: &std::path::Path) {
+    let config_content = format!(
+        r#"
+version = 1
+profile = "test"
+
+[profiles.test]
+vault_root = "{}"
+templates_dir = "{}/templates"
+captures_dir = "{}/captures"
+macros_dir = "{}/macros"
+"#,
+        vault_path.display(),
+        vault_path.display(),
+        vault_path.display(),
+        vault_path.display()
+    );
+
+    fs::create_dir_all(config_path.parent().unwrap()).unwrap();
+    let mut file = fs::File::create(config_path).unwrap();
+    file.write_all(config_content.as_bytes()).unwrap();
+}
+
+#[test]
+fn test_journal_creates_daily_for_date() {
+    let tmp = tempdir().unwrap();
+    let vault = tmp.path().join("vault");
+    let config = tmp.path().join("config.toml");
+
+    fs::create_dir_all(&vault).unwrap();
+    create_test_config(&vault, &config);
+
+    let output = mdv_cmd()
+        .args(["--config", config.to_str().unwrap()])
+        .args(["journal", "--date", "2026-03-15", "--no-open", "--batch"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "journal failed: {:?}", output);
+    let daily = vault.join("Journal/2026/Daily/2026-03-15.md");
+    assert!(daily.exists(), "daily note not created at {}", daily.display());
+
+    let content = fs::read_to_string(&daily).unwrap();
+    assert!(content.contains("type: daily"));
+    assert!(content.contains("date: 2026-03-15"));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("2026-03-15.md"));
+}
+
+#[test]
+fn test_journal_reuses_existing_daily() {
+    let tmp = tempdir().unwrap();
+    let vault = tmp.path().join("vault");
+    let config = tmp.path().join("config.toml");
+
+    let daily = vault.join("Journal/2026/Daily/2026-03-15.md");
+    fs::create_dir_all(daily.parent().unwrap()).unwrap();
+    fs::write(&daily, "---\ntype: daily\ndate: 2026-03-15\n---\n\nexisting\n").unwrap();
+    create_test_config(&vault, &config);
+
+    let output = mdv_cmd()
+        .args(["--config", config.to_str().unwrap()])
+        .args(["journal", "--date", "2026-03-15", "--no-open"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "journal failed: {:?}", output);
+    let content = fs::read_to_string(&daily).unwrap();
+    assert!(content.contains("existing"), "existing note was overwritten");
+}
+
+#[test]
+fn test_journal_week_creates_weekly() {
+    let tmp = tempdir().unwrap();
+    let vault = tmp.path().join("vault");
+    let config = tmp.path().join("config.toml");
+
+    fs::create_dir_all(&vault).unwrap();
+    create_test_config(&vault, &config);
+
+    let output = mdv_cmd()
+        .args(["--config", config.to_str().unwrap()])
+        .args(["journal", "week", "--date", "2026-03-23", "--no-open", "--batch"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "journal week failed: {:?}", output);
+    let weekly = vault.join("Journal/2026/Weekly/2026-W13.md");
+    assert!(weekly.exists(), "weekly note not created at {}", weekly.display());
+    assert!(fs::read_to_string(&weekly).unwrap().contains("type: weekly"));
+}
+
+#[test]
+fn test_journal_rejects_invalid_date() {
+    let tmp = tempdir().unwrap();
+    let vault = tmp.path().join("vault");
+    let config = tmp.path().join("config.toml");
+
+    fs::create_dir_all(&vault).unwrap();
+    create_test_config(&vault, &config);
+
+    let output = mdv_cmd()
+        .args(["--config", config.to_str().unwrap()])
+        .args(["journal", "--date", "not a date", "--no-open"])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Invalid date"));
+}
@@ -0,0 +1,96 @@
+//! Integration tests for `mdv links --depth`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+/// hub -> a -> b -> hub (a cycle), a -> c, intro -> hub
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            root.join("vault").display()
+        ),
+    );
+    let vault = root.join("vault");
+    write(&vault, "hub.md", "# Hub\n\nSee [[a]].\n");
+    write(&vault, "a.md", "# A\n\nOn to [[b]] and [[c]].\n");
+    write(&vault, "b.md", "# B\n\nBack to [[hub]].\n");
+    write(&vault, "c.md", "# C\n");
+    write(&vault, "intro.md", "# Intro\n\nStart at [[hub]].\n");
+    mdv(root, &["reindex"]).assert().success();
+    tmp
+}
+
+#[test]
+fn walks_outgoing_links_with_routes() {
+    let tmp = setup();
+    mdv(tmp.path(), &["links", "hub.md", "--outlinks", "--depth", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1      a.md  hub.md -> a.md"))
+        .stdout(predicate::str::contains("2      b.md  hub.md -> a.md -> b.md"))
+        .stdout(predicate::str::contains("2      c.md  hub.md -> a.md -> c.md"))
+        .stdout(predicate::str::contains("-- 3 notes --"));
+}
+
+#[test]
+fn cycles_do_not_repeat_notes() {
+    let tmp = setup();
+    mdv(tmp.path(), &["links", "hub.md", "-o", "--depth", "10", "-q"])
+        .assert()
+        .success()
+        .stdout("a.md\nb.md\nc.md\n");
+}
+
+#[test]
+fn json_has_both_directions() {
+    let tmp = setup();
+    let output =
+        mdv(tmp.path(), &["links", "hub.md", "--depth", "2", "--json"]).output().unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let backlinks: Vec<(&str, u64)> = json["backlinks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| (r["path"].as_str().unwrap(), r["depth"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(backlinks, [("b.md", 1), ("intro.md", 1), ("a.md", 2)]);
+    assert_eq!(
+        json["backlinks"][2]["route"],
+        serde_json::json!(["hub.md", "b.md", "a.md"])
+    );
+    assert_eq!(json["outgoing"].as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn depth_must_be_positive() {
+    let tmp = setup();
+    mdv(tmp.path(), &["links", "hub.md", "--depth", "0"]).assert().failure();
+}
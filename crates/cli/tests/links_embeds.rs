@@ -0,0 +1,62 @@
+//! Integration tests for embeds in `mdv links`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+#[test]
+fn links_embeds_shows_only_embeds() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    let vault = root.join("vault");
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            vault.display()
+        ),
+    );
+    write(&vault, "summary.md", "# Summary\n");
+    write(&vault, "meeting.md", "# Meeting\n\n![[summary]]\n");
+    write(&vault, "index.md", "# Index\n\nSee [[summary]].\n");
+    mdv(root, &["reindex"]).assert().success();
+
+    mdv(root, &["links", "summary.md", "--backlinks"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("meeting.md"))
+        .stdout(predicate::str::contains("embed"))
+        .stdout(predicate::str::contains("index.md"));
+
+    mdv(root, &["links", "summary.md", "--embeds", "-q"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("meeting.md"))
+        .stdout(predicate::str::contains("index.md").not());
+
+    mdv(root, &["links", "meeting.md", "--embeds", "--outlinks", "-q"])
+        .assert()
+        .success()
+        .stdout("summary\n");
+}
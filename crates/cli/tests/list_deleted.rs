@@ -0,0 +1,103 @@
+//! Integration tests for `mdv list --deleted` and vanished notes in `mdv doctor`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            root.join("vault").display()
+        ),
+    );
+    let vault = root.join("vault");
+    write(&vault, "ideas/caching.md", "---\ntitle: Caching\n---\nUse the CDN.\n");
+    write(&vault, "ideas/scratch.md", "---\ntitle: Scratch\n---\nNothing here.\n");
+    write(&vault, "plan.md", "# Plan\n\nSee [[ideas/caching]].\n");
+    mdv(root, &["reindex"]).assert().success();
+    tmp
+}
+
+#[test]
+fn deleted_notes_are_listed_with_backlinks() {
+    let tmp = setup();
+    let root = tmp.path();
+    fs::remove_file(root.join("vault/ideas/caching.md")).unwrap();
+    fs::remove_file(root.join("vault/ideas/scratch.md")).unwrap();
+    mdv(root, &["reindex"]).assert().success();
+
+    mdv(root, &["list", "-q"]).assert().success().stdout("plan.md\n");
+    mdv(root, &["list", "--deleted"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ideas/caching.md"))
+        .stdout(predicate::str::contains("-- 2 deleted notes --"));
+
+    let output = mdv(root, &["list", "--deleted", "--json"]).output().unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let caching = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|t| t["path"] == "ideas/caching.md")
+        .unwrap();
+    assert_eq!(caching["title"], "Caching");
+    assert_eq!(caching["backlinks"], 1);
+
+    mdv(root, &["list", "--deleted", "--type", "task"]).assert().failure();
+}
+
+#[test]
+fn doctor_warns_about_vanished_notes_still_linked() {
+    let tmp = setup();
+    let root = tmp.path();
+    mdv(root, &["doctor"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("vanished").not());
+
+    fs::remove_file(root.join("vault/ideas/caching.md")).unwrap();
+    fs::remove_file(root.join("vault/ideas/scratch.md")).unwrap();
+    mdv(root, &["reindex"]).assert().success();
+
+    mdv(root, &["doctor"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("WARN vanished notes"))
+        .stdout(predicate::str::contains("ideas/caching.md (deleted "))
+        .stdout(predicate::str::contains("1 backlink(s)"))
+        .stdout(predicate::str::contains("ideas/scratch.md").not());
+
+    // Restoring the note clears its tombstone
+    write(&root.join("vault"), "ideas/caching.md", "---\ntitle: Caching\n---\n");
+    mdv(root, &["reindex"]).assert().success();
+    mdv(root, &["doctor"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("vanished").not());
+}
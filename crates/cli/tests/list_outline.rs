@@ -0,0 +1,67 @@
+//! Integration tests for word counts and outlines in `mdv list`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    let vault = root.join("vault");
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            vault.display()
+        ),
+    );
+    write(
+        &vault,
+        "plan.md",
+        "# Plan\n\nShip the draft.\n\n```sh\nmake release\n```\n\n## Steps\n",
+    );
+    mdv(root, &["reindex"]).assert().success();
+    tmp
+}
+
+#[test]
+fn list_outline_shows_words_and_headings() {
+    let tmp = setup();
+    mdv(tmp.path(), &["list", "--outline"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("plan.md  (5 words, ~1 min)"))
+        .stdout(predicate::str::contains("\n  Plan\n    Steps\n"));
+}
+
+#[test]
+fn list_json_includes_outline() {
+    let tmp = setup();
+    let output = mdv(tmp.path(), &["list", "--json"]).output().unwrap();
+    let notes: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(notes[0]["word_count"], 5);
+    assert_eq!(notes[0]["reading_minutes"], 1);
+    assert_eq!(notes[0]["headings"][1]["title"], "Steps");
+    assert_eq!(notes[0]["headings"][1]["level"], 2);
+}
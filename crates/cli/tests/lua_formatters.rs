@@ -0,0 +1,109 @@
+//! Integration tests for Lua output formatters (`--output lua:NAME`).
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            root.join("vault").display()
+        ),
+    );
+    for dir in ["templates", "captures", "macros"] {
+        fs::create_dir_all(root.join("vault").join(dir)).unwrap();
+    }
+    write(
+        root,
+        "vault/Tasks/a.md",
+        "---\ntype: task\ntitle: Ship it\nstatus: todo\n---\n",
+    );
+    write(
+        root,
+        "vault/Tasks/b.md",
+        "---\ntype: task\ntitle: Test it\nstatus: todo\n---\n",
+    );
+    // Formatters live next to the config file by default
+    write(
+        root,
+        "formatters/task-brief.lua",
+        r#"
+return function(notes)
+    table.sort(notes, function(a, b) return a.path < b.path end)
+    local lines = {}
+    for _, note in ipairs(notes) do
+        lines[#lines + 1] = "* " .. note.title .. " [" .. note.type .. "]"
+    end
+    return table.concat(lines, "\n")
+end
+"#,
+    );
+    mdv(root, &["reindex"]).assert().success();
+    tmp
+}
+
+#[test]
+fn formats_query_output_with_lua() {
+    let tmp = setup();
+    let root = tmp.path();
+
+    mdv(root, &["list", "--output", "lua:task-brief"])
+        .assert()
+        .success()
+        .stdout("* Ship it [task]\n* Test it [task]\n");
+    mdv(root, &["query", "type:task", "-o", "lua:task-brief"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("* Test it [task]"));
+
+    // The shorthand flags still win
+    mdv(root, &["list", "--output", "lua:task-brief", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"title\": \"Ship it\""));
+}
+
+#[test]
+fn reports_missing_and_failing_formatters() {
+    let tmp = setup();
+    let root = tmp.path();
+    write(root, "formatters/broken.lua", "return function() error('boom') end");
+
+    mdv(root, &["list", "--output", "lua:nosuch"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown formatter 'nosuch'"));
+    mdv(root, &["list", "--output", "lua:broken"]).assert().failure().stderr(
+        predicate::str::contains("Formatter 'broken' failed")
+            .and(predicate::str::contains("boom")),
+    );
+    mdv(root, &["list", "--output", "yaml"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid output format 'yaml'"));
+}
@@ -0,0 +1,63 @@
+//! Integration tests for the interactive Lua session (`mdv lua`).
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+#[test]
+fn evaluates_piped_lua_with_vault_bindings() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            root.join("vault").display()
+        ),
+    );
+    for dir in ["templates", "captures", "macros"] {
+        fs::create_dir_all(root.join("vault").join(dir)).unwrap();
+    }
+    write(root, "vault/Tasks/a.md", "---\ntype: task\ntitle: Ship it\n---\n");
+    mdv(root, &["reindex"]).assert().success();
+
+    let input = "\
+#mdv.query({ type = 'task' })
+function title(path)
+  return mdv.read_note(path).frontmatter.title
+end
+title('Tasks/a')
+nosuch()
+1 + 1
+";
+    assert_cmd::Command::from_std(mdv(root, &["lua"]))
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout("1\nShip it\n2\n")
+        .stderr(predicate::str::contains("nosuch"));
+
+    // Piped sessions leave no history behind
+    assert!(!root.join("vault/.mdvault/state/lua_history").exists());
+}
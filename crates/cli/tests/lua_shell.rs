@@ -0,0 +1,118 @@
+//! Integration tests for `mdv.shell` in Lua scripts.
+
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn setup(root: &Path, allow_shell: bool) {
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n\n\
+             [security]\nallow_shell = {allow_shell}\n",
+            root.join("vault").display()
+        ),
+    );
+    for dir in ["templates", "captures", "macros", "Projects"] {
+        fs::create_dir_all(root.join("vault").join(dir)).unwrap();
+    }
+}
+
+fn lua(root: &Path, flags: &[&str], input: &str) -> assert_cmd::assert::Assert {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(flags).arg("lua");
+    assert_cmd::Command::from_std(cmd).write_stdin(input).assert()
+}
+
+#[test]
+fn shell_runs_commands_and_logs_them_with_trust() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    setup(root, false);
+
+    let input = "\
+res = mdv.shell('cat; pwd; echo oops >&2; exit 3', { cwd = 'Projects', stdin = 'hi ' })
+res.code
+res.stdout
+res.stderr
+";
+    lua(root, &["--trust-scripts"], input)
+        .success()
+        .stdout(predicate::str::contains("3\nhi "))
+        .stdout(predicate::str::contains("vault/Projects\n"))
+        .stdout(predicate::str::contains("oops"));
+
+    let log = fs::read_to_string(root.join("vault/.mdvault/activity.jsonl")).unwrap();
+    assert!(log.contains(r#""op":"shell""#), "{log}");
+    assert!(log.contains(r#""path":"Projects""#), "{log}");
+    assert!(log.contains(r#""code":3"#), "{log}");
+}
+
+#[test]
+fn shell_needs_trust_even_when_the_policy_allows_it() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    setup(root, true);
+
+    lua(root, &[], "mdv.shell('touch ran')\n")
+        .success()
+        .stderr(predicate::str::contains("mdv.shell requires --trust-scripts"));
+    assert!(!root.join("vault/ran").exists());
+
+    // Without the policy or the flag, the shell permission itself is missing
+    setup(root, false);
+    lua(root, &[], "mdv.shell('touch ran')\n")
+        .success()
+        .stderr(predicate::str::contains("mdv.shell is not permitted"));
+    assert!(!root.join("vault/ran").exists());
+}
+
+#[test]
+fn shell_policy_without_trust_cannot_start_programs() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    setup(root, true);
+
+    // The policy gives scripts os and io, minus anything that runs a program
+    let input = "\
+tostring(os.execute) .. ' ' .. tostring(io.popen) .. ' ' .. tostring(os.exit)
+type(os.date) .. ' ' .. type(io.open)
+";
+    lua(root, &[], input)
+        .success()
+        .stdout(predicate::str::contains("nil nil nil\nfunction function"));
+    lua(root, &["--trust-scripts"], "type(os.execute) .. ' ' .. type(io.popen)\n")
+        .success()
+        .stdout(predicate::str::contains("function function"));
+}
+
+#[test]
+fn shell_cwd_must_stay_in_the_vault() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    setup(root, false);
+
+    let input = "\
+select(2, mdv.shell('touch ran', { cwd = '..' }))
+select(2, mdv.shell('touch ran', { cwd = '/tmp' }))
+";
+    lua(root, &["--trust-scripts"], input)
+        .success()
+        .stdout(predicate::str::contains("cwd '..' is outside the vault"))
+        .stdout(predicate::str::contains("cwd '/tmp' is outside the vault"));
+    assert!(!root.join("ran").exists());
+}
@@ -0,0 +1,136 @@
+//! Integration tests for `mdv migrate`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: impl AsRef<str>) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content.as_ref()).unwrap();
+}
+
+fn make_config(vault_root: &str) -> String {
+    format!(
+        r#"
+version = 1
+profile = "test"
+
+[profiles.test]
+vault_root = "{vault_root}"
+templates_dir = "{{{{vault_root}}}}/templates"
+captures_dir = "{{{{vault_root}}}}/captures"
+macros_dir = "{{{{vault_root}}}}/macros"
+"#
+    )
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+/// A `book` type at schema version 2, which renamed `author` to `authors`.
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(root, "config.toml", make_config(&root.join("vault").to_string_lossy()));
+    write(
+        root,
+        "types/book.lua",
+        r#"return {
+    schema_version = 2,
+    migrate = function(note, from_version)
+        local fm = note.frontmatter
+        if from_version < 2 then
+            fm.authors = { fm.author }
+            fm.author = nil
+        end
+        return note
+    end,
+}"#,
+    );
+    write(root, "vault/books/dune.md", "---\ntype: book\nauthor: Herbert\n---\n# Dune\n");
+    write(
+        root,
+        "vault/books/emma.md",
+        "---\ntype: book\nauthors:\n- Austen\nschema_version: 2\n---\n# Emma\n",
+    );
+    tmp
+}
+
+#[test]
+fn migrate_dry_run_previews_without_writing() {
+    let tmp = setup();
+    let root = tmp.path();
+    let before = fs::read_to_string(root.join("vault/books/dune.md")).unwrap();
+
+    mdv(root, &["migrate", "--type", "book", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("books/dune.md  (v1 -> v2)"))
+        .stdout(predicate::str::contains("- author: Herbert"))
+        .stdout(predicate::str::contains("+ authors:"))
+        .stdout(predicate::str::contains("emma").not())
+        .stdout(predicate::str::contains("1 note(s) would be migrated"));
+
+    assert_eq!(fs::read_to_string(root.join("vault/books/dune.md")).unwrap(), before);
+}
+
+#[test]
+fn migrate_rewrites_outdated_notes_and_undoes() {
+    let tmp = setup();
+    let root = tmp.path();
+    let before = fs::read_to_string(root.join("vault/books/dune.md")).unwrap();
+
+    mdv(root, &["migrate", "--type", "book"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 note(s) migrated to schema version 2"));
+
+    let migrated = fs::read_to_string(root.join("vault/books/dune.md")).unwrap();
+    assert!(migrated.contains("schema_version: 2"), "{migrated}");
+    assert!(migrated.contains("- Herbert"), "{migrated}");
+    assert!(!migrated.contains("author: Herbert"), "{migrated}");
+    assert!(migrated.contains("# Dune"), "{migrated}");
+
+    mdv(root, &["migrate", "--type", "book"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("All 2 'book' note(s) are at schema version 2"));
+
+    mdv(root, &["migrate", "--undo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("restored  books/dune.md"));
+    assert_eq!(fs::read_to_string(root.join("vault/books/dune.md")).unwrap(), before);
+}
+
+#[test]
+fn migrate_undo_with_empty_journal() {
+    let tmp = setup();
+    let root = tmp.path();
+
+    mdv(root, &["migrate", "--undo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Nothing to undo."));
+}
+
+#[test]
+fn migrate_requires_a_migrate_function() {
+    let tmp = setup();
+    let root = tmp.path();
+    write(root, "types/film.lua", "return { schema_version = 3 }");
+
+    mdv(root, &["migrate", "--type", "film"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("migrate"));
+}
@@ -737,3 +737,48 @@ fn weekly_var_week_overrides_path_and_frontmatter() {
     assert!(content.contains(&format!("week: {}", target_week)));
     assert!(content.contains(&format!("title: {}", target_week)));
 }
+
+#[test]
+fn typedef_bound_template_renders_body_but_behaviour_keeps_path() {
+    let (_tmp, vault, cfg_path) = setup_vault();
+
+    write(
+        &vault.join(".mdvault/typedefs/task.lua"),
+        r#"return { template = "work-item" }"#,
+    );
+    write(
+        &vault.join(".mdvault/templates/work-item.md"),
+        "---\noutput: ignored/{{title}}.md\ntags: [work]\n---\n# {{title}}\n\nChecklist for {{task-id}}\n",
+    );
+    write(
+        &vault.join("Projects/TST/TST.md"),
+        "---\ntype: project\ntitle: Test Project\nproject-id: TST\ntask_counter: 0\n---\n",
+    );
+
+    let output = run_mdv(
+        &cfg_path,
+        &["new", "task", "Bound Task", "--var", "project=TST", "--batch"],
+    );
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let task_path = vault.join("Projects/TST/Tasks/TST-001.md");
+    assert!(task_path.exists());
+    assert!(!vault.join("ignored").exists());
+    let content = fs::read_to_string(&task_path).unwrap();
+    assert!(content.contains("# Bound Task"));
+    assert!(content.contains("Checklist for TST-001"));
+    assert!(content.contains("type: task"));
+    assert!(content.contains("task-id: TST-001"));
+}
+
+#[test]
+fn typedef_bound_template_must_exist() {
+    let (_tmp, vault, cfg_path) = setup_vault();
+
+    write(&vault.join(".mdvault/typedefs/task.lua"), r#"return { template = "nope" }"#);
+
+    let output = run_mdv(&cfg_path, &["new", "task", "Lost", "--batch"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Type 'task' uses template 'nope'"), "{stderr}");
+}
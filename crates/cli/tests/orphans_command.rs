@@ -0,0 +1,110 @@
+//! Integration tests for the `mdv orphans` whitelist and `--min-age`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(&path, content).unwrap();
+    // A month old, so --min-age has something to compare against
+    let old = SystemTime::now() - Duration::from_secs(30 * 24 * 3600);
+    fs::File::options().write(true).open(&path).unwrap().set_modified(old).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup(extra_config: &str) -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(
+        root.join("config.toml"),
+        format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n\n{extra_config}",
+            root.join("vault").display()
+        ),
+    )
+    .unwrap();
+    let vault = root.join("vault");
+    write(
+        &vault,
+        "Projects/garden/garden.md",
+        "---\ntype: project\ntitle: Garden\n---\nTend it.\n",
+    );
+    write(
+        &vault,
+        "Projects/garden/Tasks/GAR-001.md",
+        "---\ntype: task\ntitle: Water\nproject: garden\n---\nDaily.\n",
+    );
+    write(&vault, "MOCs/index.md", "# Index\n\n[[Projects/garden/Tasks/GAR-001]]\n");
+    write(&vault, "daily/2024-01-01.md", "---\ntype: daily\n---\nQuiet day.\n");
+    write(&vault, "lonely.md", "# Lonely\n");
+    tmp
+}
+
+fn orphans(root: &Path, args: &[&str]) -> String {
+    let output = mdv(root, &[&["orphans", "-q"], args].concat()).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn frontmatter_references_are_incoming_links() {
+    let tmp = setup("");
+    mdv(tmp.path(), &["reindex"]).assert().success();
+    assert_eq!(
+        orphans(tmp.path(), &[]),
+        "MOCs/index.md\ndaily/2024-01-01.md\nlonely.md\n"
+    );
+}
+
+#[test]
+fn config_ignores_types_and_paths() {
+    let tmp =
+        setup("[orphans]\nignore_types = [\"daily\"]\nignore_paths = [\"MOCs/\"]\n");
+    mdv(tmp.path(), &["reindex"]).assert().success();
+    assert_eq!(orphans(tmp.path(), &[]), "lonely.md\n");
+
+    // The same rules apply to `mdv stale --orphans`
+    mdv(tmp.path(), &["stale", "--orphans", "-q"])
+        .assert()
+        .success()
+        .stdout("lonely.md\n");
+}
+
+#[test]
+fn min_age_skips_new_notes() {
+    let tmp = setup("");
+    fs::write(tmp.path().join("vault/fresh.md"), "# Fresh\n").unwrap();
+    mdv(tmp.path(), &["reindex"]).assert().success();
+
+    assert!(orphans(tmp.path(), &[]).contains("fresh.md"));
+    let old_only = orphans(tmp.path(), &["--min-age", "7"]);
+    assert!(!old_only.contains("fresh.md"));
+    assert!(old_only.contains("lonely.md"));
+    assert_eq!(orphans(tmp.path(), &["--min-age", "60"]), "");
+}
+
+#[test]
+fn invalid_ignore_paths_are_errors() {
+    let tmp = setup("[orphans]\nignore_paths = [\"[abc\"]\n");
+    mdv(tmp.path(), &["reindex"]).assert().success();
+    mdv(tmp.path(), &["orphans"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid exclude pattern '[abc'"));
+}
@@ -0,0 +1,124 @@
+//! Integration tests for `--page-size` / `--cursor` on `list` and `search`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            root.join("vault").display()
+        ),
+    );
+    for i in 0..5 {
+        write(
+            &root.join("vault"),
+            &format!("note-{i}.md"),
+            &format!("# Note {i}\n\nPaging test body.\n"),
+        );
+    }
+    mdv(root, &["reindex"]).assert().success();
+    tmp
+}
+
+fn json(cmd: &mut Command) -> serde_json::Value {
+    let output = cmd.output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+/// Follow cursors from the first page to the last, collecting paths.
+fn walk(root: &Path, args: &[&str]) -> Vec<String> {
+    let mut paths = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let mut all: Vec<&str> = args.to_vec();
+        all.extend(["--page-size", "2", "--json"]);
+        if let Some(cursor) = &cursor {
+            all.extend(["--cursor", cursor]);
+        }
+        let page = json(&mut mdv(root, &all));
+        let items = page["items"].as_array().unwrap();
+        assert!(items.len() <= 2);
+        paths.extend(items.iter().map(|i| i["path"].as_str().unwrap().to_string()));
+        match page["next_cursor"].as_str() {
+            Some(next) => cursor = Some(next.to_string()),
+            None => return paths,
+        }
+    }
+}
+
+#[test]
+fn list_pages_cover_every_note_once() {
+    let tmp = setup();
+    let everything = json(&mut mdv(tmp.path(), &["list", "--json"]));
+    let expected: Vec<String> = everything
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|n| n["path"].as_str().unwrap().to_string())
+        .collect();
+
+    assert_eq!(walk(tmp.path(), &["list"]), expected);
+}
+
+#[test]
+fn search_pages_cover_every_match_once() {
+    let tmp = setup();
+    let mut paths = walk(tmp.path(), &["search", "paging"]);
+    assert_eq!(paths.len(), 5);
+    paths.sort();
+    paths.dedup();
+    assert_eq!(paths.len(), 5);
+}
+
+#[test]
+fn cursors_are_tied_to_their_query() {
+    let tmp = setup();
+    let page = json(&mut mdv(tmp.path(), &["list", "--page-size", "2", "--json"]));
+    let cursor = page["next_cursor"].as_str().unwrap();
+
+    mdv(tmp.path(), &["list", "--type", "task", "--cursor", cursor])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("different query"));
+    mdv(tmp.path(), &["list", "--cursor", "bogus"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid cursor"));
+}
+
+#[test]
+fn table_output_points_to_the_next_page() {
+    let tmp = setup();
+    mdv(tmp.path(), &["list", "--page-size", "3", "-q"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--cursor").not())
+        .stderr(predicate::str::contains("More results: --cursor c1"));
+}
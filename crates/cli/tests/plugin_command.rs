@@ -0,0 +1,87 @@
+//! Integration tests for `mdv plugin` in a build without plugins.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+fn setup_vault() -> (tempfile::TempDir, PathBuf, PathBuf) {
+    let tmp = tempdir().unwrap();
+    let vault = tmp.path().join("vault");
+    let cfg_path = setup_config(&tmp, &vault);
+    (tmp, vault, cfg_path)
+}
+
+fn setup_config(tmp: &tempfile::TempDir, vault: &Path) -> PathBuf {
+    let xdg = tmp.path().join("xdg");
+    let cfg_dir = xdg.join("mdvault");
+    let cfg_path = cfg_dir.join("config.toml");
+    fs::create_dir_all(&cfg_dir).unwrap();
+
+    fs::create_dir_all(vault.join(".mdvault/typedefs")).unwrap();
+    fs::create_dir_all(vault.join(".mdvault/templates")).unwrap();
+    fs::create_dir_all(vault.join(".mdvault/captures")).unwrap();
+    fs::create_dir_all(vault.join(".mdvault/macros")).unwrap();
+
+    let mut toml = String::new();
+    writeln!(&mut toml, "version = 1").unwrap();
+    writeln!(&mut toml, "profile = \"default\"").unwrap();
+    writeln!(&mut toml).unwrap();
+    writeln!(&mut toml, "[profiles.default]").unwrap();
+    writeln!(&mut toml, "vault_root = \"{}\"", vault.display()).unwrap();
+    writeln!(&mut toml, "typedefs_dir = \"{}/.mdvault/typedefs\"", vault.display())
+        .unwrap();
+    writeln!(&mut toml, "templates_dir = \"{}/.mdvault/templates\"", vault.display())
+        .unwrap();
+    writeln!(&mut toml, "captures_dir = \"{}/.mdvault/captures\"", vault.display())
+        .unwrap();
+    writeln!(&mut toml, "macros_dir = \"{}/.mdvault/macros\"", vault.display()).unwrap();
+
+    fs::write(&cfg_path, toml).unwrap();
+    cfg_path
+}
+
+fn run_mdv(cfg_path: &Path, args: &[&str]) -> std::process::Output {
+    let mut cmd = std::process::Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.env("NO_COLOR", "1");
+    let vault_root =
+        cfg_path.parent().unwrap().parent().unwrap().parent().unwrap().join("vault");
+    cmd.current_dir(&vault_root);
+    cmd.args(["--config", cfg_path.to_str().unwrap()]);
+    cmd.args(args);
+    cmd.output().expect("Failed to run mdv")
+}
+
+// --- Tests ---
+
+#[test]
+fn plugin_list_is_empty_without_plugins() {
+    let (_tmp, _vault, cfg_path) = setup_vault();
+
+    let output = run_mdv(&cfg_path, &["plugin", "list"]);
+    assert!(output.status.success(), "plugin list failed: {:?}", output);
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("No plugins in this build.")
+    );
+
+    let output = run_mdv(&cfg_path, &["plugin", "list", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json, serde_json::json!([]));
+}
+
+#[test]
+fn unknown_plugin_command_and_exporter_fail() {
+    let (_tmp, _vault, cfg_path) = setup_vault();
+
+    let output = run_mdv(&cfg_path, &["plugin", "run", "embed", "--model", "small"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("No plugin provides command 'embed'"), "{stderr}");
+
+    let output = run_mdv(&cfg_path, &["reindex"]);
+    assert!(output.status.success(), "reindex failed: {:?}", output);
+    let output = run_mdv(&cfg_path, &["plugin", "export", "site"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("No plugin provides exporter 'site'"), "{stderr}");
+}
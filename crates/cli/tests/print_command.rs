@@ -0,0 +1,147 @@
+//! Integration tests for the `mdv print` command.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn mdv_cmd() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_mdv"))
+}
+
+fn write(vault: &Path, rel: &str, content: &str) {
+    let path = vault.join(rel);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+/// A vault with two open tasks, a done task, two meetings and a daily
+/// note carrying a habits checklist; returns the config path.
+fn setup_vault(root: &Path) -> std::path::PathBuf {
+    let vault = root.join("vault");
+    let config = root.join("config.toml");
+    fs::create_dir_all(&vault).unwrap();
+    fs::write(
+        &config,
+        format!(
+            r#"
+version = 1
+profile = "test"
+
+[profiles.test]
+vault_root = "{0}"
+templates_dir = "{0}/templates"
+captures_dir = "{0}/captures"
+macros_dir = "{0}/macros"
+"#,
+            vault.display()
+        ),
+    )
+    .unwrap();
+
+    write(
+        &vault,
+        "Projects/WEB/Tasks/WEB-001.md",
+        "---\ntype: task\ntask-id: WEB-001\ntitle: Fix login\nproject: WEB\n\
+         status: todo\npriority: medium\ndue_date: 2025-01-10\n---\n# Fix login\n",
+    );
+    write(
+        &vault,
+        "Projects/WEB/Tasks/WEB-002.md",
+        "---\ntype: task\ntask-id: WEB-002\ntitle: Write docs\nproject: WEB\n\
+         status: todo\npriority: high\n---\n# Write docs\n",
+    );
+    write(
+        &vault,
+        "Projects/WEB/Tasks/WEB-003.md",
+        "---\ntype: task\ntask-id: WEB-003\ntitle: Old work\nproject: WEB\n\
+         status: done\n---\n# Old work\n",
+    );
+    write(
+        &vault,
+        "Meetings/2025/MTG-2025-01-15-001.md",
+        "---\ntype: meeting\ntitle: Standup\ndate: 2025-01-15\ntime: \"09:30\"\n\
+         attendees: [Ana, Bo]\n---\n# Standup\n",
+    );
+    write(
+        &vault,
+        "Meetings/2025/MTG-2025-01-17-001.md",
+        "---\ntype: meeting\ntitle: Retro\ndate: 2025-01-17\n---\n# Retro\n",
+    );
+    write(
+        &vault,
+        "Journal/Daily/2025-01-14.md",
+        "---\ntype: daily\ndate: 2025-01-14\n---\n# 2025-01-14\n\n\
+         ## Habits\n\n- [x] Exercise\n- [ ] Read 20 pages\n\n## Log\n\n- [ ] Not a habit\n",
+    );
+
+    let output = mdv_cmd()
+        .args(["--config", config.to_str().unwrap(), "reindex"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "reindex failed: {:?}", output);
+    config
+}
+
+fn print(config: &Path, args: &[&str]) -> String {
+    let output = mdv_cmd()
+        .args(["--config", config.to_str().unwrap(), "print"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "print failed: {:?}", output);
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn print_today_has_tasks_schedule_and_habits() {
+    let tmp = tempdir().unwrap();
+    let config = setup_vault(tmp.path());
+
+    let sheet = print(&config, &["today", "--date", "2025-01-15"]);
+
+    assert!(sheet.starts_with("# Wednesday, 15 January 2025"));
+    // Overdue task first, done tasks left out
+    let overdue = sheet.find("**WEB-001** Fix login").expect("overdue task listed");
+    let other = sheet.find("**WEB-002** Write docs").expect("open task listed");
+    assert!(overdue < other);
+    assert!(sheet.contains("due 2025-01-10, overdue"));
+    assert!(!sheet.contains("WEB-003"));
+
+    assert!(sheet.contains("| 09:30 | Standup | Ana, Bo |"));
+    assert!(!sheet.contains("Retro"));
+
+    assert!(sheet.contains("- [ ] Exercise\n- [ ] Read 20 pages\n"));
+    assert!(!sheet.contains("Not a habit"));
+    assert!(sheet.contains("## Notes"));
+}
+
+#[test]
+fn print_week_groups_meetings_by_day() {
+    let tmp = tempdir().unwrap();
+    let config = setup_vault(tmp.path());
+
+    let sheet = print(&config, &["week", "--date", "2025-01-15"]);
+
+    assert!(sheet.starts_with("# Week 2025-W03: 13 Jan to 19 Jan 2025"));
+    assert!(sheet.contains("### Wednesday 15\n\n- 09:30 Standup (with Ana, Bo)"));
+    assert!(sheet.contains("### Friday 17\n\n- Retro"));
+    assert!(sheet.contains("| Exercise | [ ] | [ ] | [ ] | [ ] | [ ] | [ ] | [ ] |"));
+}
+
+#[test]
+fn print_limits_tasks_and_writes_file() {
+    let tmp = tempdir().unwrap();
+    let config = setup_vault(tmp.path());
+    let out = tmp.path().join("sheet.md");
+
+    let stdout = print(
+        &config,
+        &["today", "--date", "2025-01-15", "--tasks", "1", "-o", out.to_str().unwrap()],
+    );
+
+    assert!(stdout.contains("Wrote"));
+    let sheet = fs::read_to_string(&out).unwrap();
+    assert!(sheet.contains("WEB-001"));
+    assert!(!sheet.contains("WEB-002"));
+}
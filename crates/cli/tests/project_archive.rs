@@ -230,3 +230,161 @@ fn archive_rejects_non_done_project() {
         "Project should not have been moved"
     );
 }
+
+#[test]
+fn archive_dry_run_reports_impact_outside_project() {
+    let tmp = tempdir().unwrap();
+    let vault = tmp.path().join("vault");
+    let config = tmp.path().join("config.toml");
+
+    fs::create_dir_all(&vault).unwrap();
+    create_test_config(&vault, &config);
+
+    scaffold_project(&vault, "app", "APP", "done", &[("APP-001", "done")]);
+    fs::write(vault.join("Projects/app/diagram.png"), "png").unwrap();
+    scaffold_project(&vault, "web", "WEB", "open", &[]);
+    fs::write(
+        vault.join("Projects/web/Tasks/WEB-001.md"),
+        "---\ntype: task\ntitle: Port login\nstatus: in-progress\n---\n\
+         Needs [[app]].\n![[Projects/app/diagram.png]]\n",
+    )
+    .unwrap();
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    fs::create_dir_all(vault.join("Journal")).unwrap();
+    fs::write(
+        vault.join(format!("Journal/{today}.md")),
+        format!(
+            "---\ntype: daily\ndate: {today}\n---\nWrapped up [[Projects/app/app]].\n"
+        ),
+    )
+    .unwrap();
+
+    mdv_cmd()
+        .args(["--config", config.to_str().unwrap(), "reindex"])
+        .output()
+        .expect("Failed to reindex");
+
+    let output = mdv_cmd()
+        .args(["--config", config.to_str().unwrap(), "project", "archive", "app"])
+        .arg("--dry-run")
+        .output()
+        .expect("Failed to execute archive");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {stdout}");
+    assert!(
+        stdout.contains(
+            "3 inbound link(s): 1 rewritten, 1 unchanged (by name), 1 will break"
+        ),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("Projects/web/Tasks/WEB-001.md:2 -> Projects/app/diagram.png"),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains(&format!("Journal/{today}.md")));
+    assert!(stdout.contains("1 open task(s) in other projects link to it"));
+    assert!(stdout.contains("no changes made"));
+    assert!(vault.join("Projects/app/app.md").exists(), "dry run must not move files");
+
+    let output = mdv_cmd()
+        .args(["--config", config.to_str().unwrap(), "project", "archive", "app"])
+        .args(["--dry-run", "--json"])
+        .output()
+        .expect("Failed to execute archive");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let fates: Vec<_> = json["inbound_links"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|l| l["fate"].as_str().unwrap())
+        .collect();
+    assert_eq!(fates.iter().filter(|f| **f == "broken").count(), 1);
+    assert_eq!(json["dependent_tasks"][0]["status"], "in-progress");
+    assert_eq!(json["recent_dailies"][0]["date"], today);
+}
+
+#[test]
+fn unarchive_restores_project_and_reopens_tasks() {
+    let tmp = tempdir().unwrap();
+    let vault = tmp.path().join("vault");
+    let config = tmp.path().join("config.toml");
+    let config_arg = config.to_str().unwrap();
+
+    fs::create_dir_all(&vault).unwrap();
+    create_test_config(&vault, &config);
+
+    scaffold_project(
+        &vault,
+        "test-proj",
+        "TST",
+        "done",
+        &[("TST-001", "done"), ("TST-002", "todo"), ("TST-003", "todo")],
+    );
+    fs::write(vault.join("notes.md"), "See [[Projects/test-proj/test-proj]].\n").unwrap();
+
+    let run = |args: &[&str]| {
+        let output =
+            mdv_cmd().args(["--config", config_arg]).args(args).output().unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        (output.status.success(), stdout, stderr)
+    };
+    run(&["reindex"]);
+    let (ok, _, stderr) = run(&["project", "archive", "test-proj", "--yes"]);
+    assert!(ok, "archive failed: {stderr}");
+    assert!(
+        fs::read_to_string(vault.join("notes.md"))
+            .unwrap()
+            .contains("Projects/_archive/test-proj/test-proj")
+    );
+
+    // Unknown or non-cancelled tasks are rejected before anything moves
+    let (ok, _, stderr) =
+        run(&["project", "unarchive", "TST", "--reopen", "TST-001", "--yes"]);
+    assert!(!ok);
+    assert!(stderr.contains("No cancelled task 'TST-001'"), "stderr: {stderr}");
+    assert!(vault.join("Projects/_archive/test-proj/test-proj.md").exists());
+
+    let (ok, stdout, stderr) = run(&[
+        "project",
+        "unarchive",
+        "TST",
+        "--status",
+        "in-progress",
+        "--reopen",
+        "TST-002",
+        "--focus",
+        "--yes",
+    ]);
+    assert!(ok, "unarchive failed: {stderr}");
+    assert!(stdout.contains("OK   mdv project unarchive"), "stdout: {stdout}");
+    assert!(stdout.contains("tasks reopened: 1"), "stdout: {stdout}");
+
+    assert!(!vault.join("Projects/_archive/test-proj").exists());
+    let project =
+        fs::read_to_string(vault.join("Projects/test-proj/test-proj.md")).unwrap();
+    assert!(project.contains("status: in-progress"), "project:\n{project}");
+    assert!(!project.contains("archived_at"), "project:\n{project}");
+
+    let reopened =
+        fs::read_to_string(vault.join("Projects/test-proj/Tasks/TST-002.md")).unwrap();
+    assert!(reopened.contains("status: todo"), "task:\n{reopened}");
+    assert!(!reopened.contains("cancelled_at"), "task:\n{reopened}");
+    let still_cancelled =
+        fs::read_to_string(vault.join("Projects/test-proj/Tasks/TST-003.md")).unwrap();
+    assert!(still_cancelled.contains("status: cancelled"));
+
+    // Links follow the project back out of the archive
+    assert_eq!(
+        fs::read_to_string(vault.join("notes.md")).unwrap(),
+        "See [[Projects/test-proj/test-proj]].\n"
+    );
+
+    let (_, stdout, _) = run(&["focus"]);
+    assert!(stdout.contains("Active focus: TST"), "stdout: {stdout}");
+
+    // New tasks can be created in the project again
+    let (ok, _, stderr) =
+        run(&["new", "task", "Follow-up", "--var", "project=test-proj"]);
+    assert!(ok, "task creation failed: {stderr}");
+}
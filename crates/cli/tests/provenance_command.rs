@@ -0,0 +1,129 @@
+//! Integration tests for `mdv provenance` and generated-region markers.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+fn write(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, contents).unwrap();
+}
+
+fn setup_vault() -> (tempfile::TempDir, PathBuf, PathBuf) {
+    let tmp = tempdir().unwrap();
+    let vault = tmp.path().join("vault");
+    let cfg_path = setup_config(&tmp, &vault);
+    (tmp, vault, cfg_path)
+}
+
+fn setup_config(tmp: &tempfile::TempDir, vault: &Path) -> PathBuf {
+    let xdg = tmp.path().join("xdg");
+    let cfg_dir = xdg.join("mdvault");
+    let cfg_path = cfg_dir.join("config.toml");
+    fs::create_dir_all(&cfg_dir).unwrap();
+
+    fs::create_dir_all(vault.join(".mdvault/typedefs")).unwrap();
+    fs::create_dir_all(vault.join(".mdvault/templates")).unwrap();
+    fs::create_dir_all(vault.join(".mdvault/captures")).unwrap();
+    fs::create_dir_all(vault.join(".mdvault/macros")).unwrap();
+
+    let mut toml = String::new();
+    writeln!(&mut toml, "version = 1").unwrap();
+    writeln!(&mut toml, "profile = \"default\"").unwrap();
+    writeln!(&mut toml).unwrap();
+    writeln!(&mut toml, "[profiles.default]").unwrap();
+    writeln!(&mut toml, "vault_root = \"{}\"", vault.display()).unwrap();
+    writeln!(&mut toml, "typedefs_dir = \"{}/.mdvault/typedefs\"", vault.display())
+        .unwrap();
+    writeln!(&mut toml, "templates_dir = \"{}/.mdvault/templates\"", vault.display())
+        .unwrap();
+    writeln!(&mut toml, "captures_dir = \"{}/.mdvault/captures\"", vault.display())
+        .unwrap();
+    writeln!(&mut toml, "macros_dir = \"{}/.mdvault/macros\"", vault.display()).unwrap();
+
+    fs::write(&cfg_path, toml).unwrap();
+    cfg_path
+}
+
+fn run_mdv(cfg_path: &Path, args: &[&str]) -> std::process::Output {
+    let mut cmd = std::process::Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.env("NO_COLOR", "1");
+    let vault_root =
+        cfg_path.parent().unwrap().parent().unwrap().parent().unwrap().join("vault");
+    cmd.current_dir(&vault_root);
+    cmd.args(["--config", cfg_path.to_str().unwrap()]);
+    cmd.args(args);
+    cmd.output().expect("Failed to run mdv")
+}
+
+// --- Tests ---
+
+#[test]
+fn report_regions_can_be_listed_regenerated_and_stripped() {
+    let (_tmp, vault, cfg_path) = setup_vault();
+
+    write(
+        &vault.join("Projects/TST/Tasks/TST-001.md"),
+        "---\ntype: task\ntitle: Shipped\ntask-id: TST-001\nproject: TST\nstatus: done\ncompleted_at: 2026-09-10\n---\n",
+    );
+    let output = run_mdv(&cfg_path, &["reindex"]);
+    assert!(output.status.success(), "reindex failed: {:?}", output);
+
+    fs::create_dir_all(vault.join("Reports")).unwrap();
+    let output =
+        run_mdv(&cfg_path, &["report", "--month", "2026-09", "-o", "Reports/2026-09.md"]);
+    assert!(output.status.success(), "report failed: {:?}", output);
+    let report = vault.join("Reports/2026-09.md");
+    let original = fs::read_to_string(&report).unwrap();
+    assert!(original.starts_with("---\ntype: report\n"));
+
+    let output = run_mdv(&cfg_path, &["provenance", "Reports/2026-09.md"]);
+    assert!(output.status.success(), "provenance failed: {:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("1. report"), "region not listed: {stdout}");
+    assert!(stdout.contains("mdv report --month 2026-09"), "source missing: {stdout}");
+
+    let output = run_mdv(&cfg_path, &["provenance", "Reports/2026-09.md", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["generator"], "report");
+    assert_eq!(json[0]["start_line"], 8);
+
+    // Hand edits inside the region are replaced on regeneration.
+    let tampered = original.replace("# Monthly Report", "# Edited Report");
+    fs::write(&report, &tampered).unwrap();
+    let output =
+        run_mdv(&cfg_path, &["provenance", "Reports/2026-09.md", "--regenerate"]);
+    assert!(output.status.success(), "regenerate failed: {:?}", output);
+    let regenerated = fs::read_to_string(&report).unwrap();
+    assert!(regenerated.contains("# Monthly Report: September 2026"));
+    assert!(!regenerated.contains("# Edited Report"));
+
+    let output =
+        run_mdv(&cfg_path, &["provenance", "Reports/2026-09.md", "--strip", "1"]);
+    assert!(output.status.success(), "strip failed: {:?}", output);
+    let stripped = fs::read_to_string(&report).unwrap();
+    assert!(stripped.starts_with("---\ntype: report\n"));
+    assert!(!stripped.contains("mdv:generated"));
+    assert!(!stripped.contains("Monthly Report"));
+}
+
+#[test]
+fn review_regions_are_not_regenerated() {
+    let (_tmp, vault, cfg_path) = setup_vault();
+    write(
+        &vault.join("week.md"),
+        "# Week\n\n<!-- mdv:generated generator=\"review\" version=\"0.1.0\" source=\"mdv review --week 2026-W13\" at=\"now\" -->\n- summary\n<!-- /mdv:generated -->\n",
+    );
+
+    let output = run_mdv(&cfg_path, &["provenance", "week.md", "--regenerate", "1"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("cannot be regenerated"), "unexpected error: {stderr}");
+
+    let output = run_mdv(&cfg_path, &["provenance", "week.md", "--strip", "2"]);
+    assert!(!output.status.success());
+    assert!(fs::read_to_string(vault.join("week.md")).unwrap().contains("- summary"));
+}
@@ -0,0 +1,81 @@
+//! Integration tests for `mdv query`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: impl AsRef<str>) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content.as_ref()).unwrap();
+}
+
+fn make_config(vault_root: &str) -> String {
+    format!(
+        r#"
+version = 1
+profile = "test"
+
+[profiles.test]
+vault_root = "{vault_root}"
+templates_dir = "{{{{vault_root}}}}/templates"
+captures_dir = "{{{{vault_root}}}}/captures"
+macros_dir = "{{{{vault_root}}}}/macros"
+"#
+    )
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(root, "config.toml", make_config(&root.join("vault").to_string_lossy()));
+    write(root, "vault/Tasks/a.md", "---\ntype: task\nstatus: todo\npriority: 2\n---\n");
+    write(root, "vault/Tasks/b.md", "---\ntype: task\nstatus: todo\npriority: 10\n---\n");
+    write(root, "vault/Tasks/c.md", "---\ntype: task\nstatus: done\npriority: 5\n---\n");
+    write(root, "vault/Notes/d.md", "---\nstatus: todo\n---\n");
+    mdv(root, &["reindex"]).assert().success();
+    tmp
+}
+
+#[test]
+fn query_filters_sorts_and_limits() {
+    let tmp = setup();
+    let output = mdv(
+        tmp.path(),
+        &["query", "type:task AND NOT status:done SORT priority DESC", "-q"],
+    )
+    .output()
+    .unwrap();
+    assert!(output.status.success());
+    let paths: Vec<_> =
+        String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).collect();
+    assert_eq!(paths, ["Tasks/b.md", "Tasks/a.md"]);
+
+    mdv(tmp.path(), &["query", "path:Notes/ AND status:todo", "-q"])
+        .assert()
+        .success()
+        .stdout("Notes/d.md\n");
+    mdv(tmp.path(), &["query", "SORT priority LIMIT 1", "-q"])
+        .assert()
+        .success()
+        .stdout("Tasks/a.md\n");
+}
+
+#[test]
+fn query_reports_syntax_errors() {
+    let tmp = setup();
+    mdv(tmp.path(), &["query", "status:todo OR status:done"]).assert().failure().stderr(
+        predicate::str::contains("position 12").and(predicate::str::contains("OR")),
+    );
+}
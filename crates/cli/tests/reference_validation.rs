@@ -0,0 +1,72 @@
+//! Integration tests for reference fields with a `target_type`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: impl AsRef<str>) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content.as_ref()).unwrap();
+}
+
+fn make_config(vault_root: &str) -> String {
+    format!(
+        r#"
+version = 1
+profile = "test"
+
+[profiles.test]
+vault_root = "{vault_root}"
+templates_dir = "{{{{vault_root}}}}/templates"
+captures_dir = "{{{{vault_root}}}}/captures"
+macros_dir = "{{{{vault_root}}}}/macros"
+"#
+    )
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+#[test]
+fn validate_reports_dangling_and_mistyped_references() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(root, "config.toml", make_config(&root.join("vault").to_string_lossy()));
+    write(
+        root,
+        "types/task.lua",
+        r#"return { schema = {
+    project = { type = "reference", target_type = "project" },
+} }"#,
+    );
+    write(root, "vault/Projects/MCP/MCP.md", "---\ntype: project\ntitle: MCP\n---\n");
+    write(root, "vault/zettel/idea.md", "---\ntype: zettel\ntitle: Idea\n---\n");
+    write(
+        root,
+        "vault/Projects/MCP/Tasks/MCP-001.md",
+        "---\ntype: task\nproject: MCP\n---\n",
+    );
+    write(root, "vault/Inbox/INB-001.md", "---\ntype: task\nproject: GONE\n---\n");
+    write(
+        root,
+        "vault/Inbox/INB-002.md",
+        "---\ntype: task\nproject: \"[[idea]]\"\n---\n",
+    );
+    mdv(root, &["reindex"]).assert().success();
+
+    mdv(root, &["validate", "--type", "task"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("no project note 'GONE'"))
+        .stdout(predicate::str::contains("'[[idea]]' is a zettel note, expected project"))
+        .stdout(predicate::str::contains("MCP-001").not());
+}
@@ -0,0 +1,81 @@
+//! Integration tests for `mdv reindex --vacuum` and `[index] auto_vacuum_growth`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup(extra: &str) -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n{extra}",
+            root.join("vault").display()
+        ),
+    );
+    for i in 0..20 {
+        write(&root.join("vault"), &format!("note-{i}.md"), &"Some words. ".repeat(200));
+    }
+    tmp
+}
+
+#[test]
+fn vacuum_reports_size_and_keeps_the_index_usable() {
+    let tmp = setup("");
+    let root = tmp.path();
+    mdv(root, &["reindex"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Index maintenance").not());
+
+    for i in 0..15 {
+        fs::remove_file(root.join(format!("vault/note-{i}.md"))).unwrap();
+    }
+    mdv(root, &["reindex", "--vacuum"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Index maintenance:\n  Rows pruned:    0\n"))
+        .stdout(predicate::str::is_match(r"Size: +[\d.]+ KB -> [\d.]+ KB").unwrap());
+
+    mdv(root, &["search", "words", "-q"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("note-19.md"));
+}
+
+#[test]
+fn index_is_vacuumed_automatically_past_the_growth_threshold() {
+    let tmp = setup("\n[index]\nauto_vacuum_growth = \"4KB\"\n");
+    mdv(tmp.path(), &["reindex"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Index maintenance (auto):"));
+
+    let tmp = setup("\n[index]\nauto_vacuum_growth = \"1GB\"\n");
+    mdv(tmp.path(), &["reindex"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Index maintenance").not());
+}
@@ -0,0 +1,101 @@
+//! Integration tests for `mdv related`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            root.join("vault").display()
+        ),
+    );
+    let vault = root.join("vault");
+    write(
+        &vault,
+        "zettel/parsing.md",
+        "---\ntype: zettel\ntitle: Parsing\ntags: [rust, parsing]\n---\n\
+         Parser combinators build grammars from small functions. See [[grammar]].\n",
+    );
+    write(
+        &vault,
+        "zettel/lexing.md",
+        "---\ntype: zettel\ntitle: Lexing\n---\nTokens feed the [[grammar]].\n",
+    );
+    write(
+        &vault,
+        "zettel/macros.md",
+        "---\ntype: zettel\ntitle: Macros\ntags: [rust]\n---\nDeclarative expansion.\n",
+    );
+    write(&vault, "grammar.md", "# Grammar\n\nProductions.\n");
+    write(&vault, "garden.md", "# Garden\n\nTomatoes.\n");
+    mdv(root, &["reindex"]).assert().success();
+    tmp
+}
+
+#[test]
+fn lists_related_notes_with_reasons() {
+    let tmp = setup();
+    mdv(tmp.path(), &["related", "zettel/parsing.md"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("zettel/lexing.md"))
+        .stdout(predicate::str::contains("1 shared links"))
+        .stdout(predicate::str::contains("#rust"))
+        .stdout(predicate::str::contains("garden.md").not());
+}
+
+#[test]
+fn json_includes_signals_and_type_filter_applies() {
+    let tmp = setup();
+    let output = mdv(
+        tmp.path(),
+        &["related", "./zettel/parsing.md", "--type", "zettel", "--json"],
+    )
+    .output()
+    .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let items = json.as_array().unwrap();
+    assert!(items.iter().all(|r| r["type"] == "zettel"));
+
+    let macros = items.iter().find(|r| r["path"] == "zettel/macros.md").unwrap();
+    assert_eq!(macros["shared_tags"], serde_json::json!(["rust"]));
+    let lexing = items.iter().find(|r| r["path"] == "zettel/lexing.md").unwrap();
+    assert_eq!(lexing["shared_links"], 1);
+    assert!(lexing["score"].as_f64().unwrap() > 0.0);
+}
+
+#[test]
+fn unknown_note_fails() {
+    let tmp = setup();
+    mdv(tmp.path(), &["related", "nope.md"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Note not found in index: nope.md"));
+}
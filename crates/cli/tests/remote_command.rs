@@ -0,0 +1,112 @@
+//! Integration tests for `mdv --remote` and `--vault-root`.
+//!
+//! `MDV_SSH` points at a stand-in for ssh that runs the command locally, so
+//! the "remote" mdv is the binary under test.
+#![cfg(unix)]
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.args(args);
+    cmd
+}
+
+/// A config with `home` (default) and `server` profiles, the server vault
+/// indexed, and a fake ssh that records its destination.
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    let mut config = "version = 1\nprofile = \"home\"\n".to_string();
+    for name in ["home", "server"] {
+        config.push_str(&format!(
+            "\n[profiles.{name}]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            root.join(name).display()
+        ));
+    }
+    write(root, "config.toml", &config);
+    write(root, "home/local.md", "# Local\n");
+    write(root, "server/it's remote.md", "# Remote\n");
+    let config = root.join("config.toml");
+    mdv(&["--config", &config.to_string_lossy(), "--profile", "server", "reindex"])
+        .assert()
+        .success();
+
+    let ssh = root.join("fake-ssh");
+    write(
+        root,
+        "fake-ssh",
+        &format!(
+            "#!/bin/sh\nwhile [ \"$1\" != \"--\" ]; do shift; done\n\
+             echo \"$2\" > {}\nexec sh -c \"$3\"\n",
+            root.join("host").display()
+        ),
+    );
+    fs::set_permissions(&ssh, fs::Permissions::from_mode(0o755)).unwrap();
+    tmp
+}
+
+fn remote(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = mdv(args);
+    cmd.env("MDV_CONFIG", root.join("config.toml"))
+        .env("MDV_SSH", root.join("fake-ssh"))
+        .env("MDV_REMOTE_BIN", assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd
+}
+
+#[test]
+fn remote_runs_against_the_remote_vault() {
+    let tmp = setup();
+    let root = tmp.path();
+    let target = format!("me@box:{}", root.join("server").display());
+
+    remote(root, &["--remote", &target, "list", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("it's remote.md"))
+        .stdout(predicate::str::contains("\"vault\": \"server\""))
+        .stdout(predicate::str::contains("local.md").not());
+    assert_eq!(fs::read_to_string(root.join("host")).unwrap().trim(), "me@box");
+
+    // The remote exit status is ours
+    remote(root, &["list", "--remote", &target, "--vault", "nope"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("profile 'nope' not found"));
+}
+
+#[test]
+fn vault_root_picks_the_matching_profile() {
+    let tmp = setup();
+    let root = tmp.path();
+    let config = root.join("config.toml");
+    let server = root.join("server");
+
+    mdv(&["--config", &config.to_string_lossy()])
+        .args(["--vault-root", &server.to_string_lossy(), "list", "-q"])
+        .assert()
+        .success()
+        .stdout("it's remote.md\n");
+
+    mdv(&["--config", &config.to_string_lossy(), "--vault-root", "/nowhere", "list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no profile has its vault at '/nowhere'"));
+}
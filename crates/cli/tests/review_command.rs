@@ -0,0 +1,105 @@
+//! Integration tests for the `mdv review` weekly review workflow.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+// --- Test Harness (same pattern as new_builtin_types.rs) ---
+
+fn write(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, contents).unwrap();
+}
+
+fn setup_vault() -> (tempfile::TempDir, PathBuf, PathBuf) {
+    let tmp = tempdir().unwrap();
+    let vault = tmp.path().join("vault");
+    let cfg_path = setup_config(&tmp, &vault);
+    (tmp, vault, cfg_path)
+}
+
+fn setup_config(tmp: &tempfile::TempDir, vault: &Path) -> PathBuf {
+    let xdg = tmp.path().join("xdg");
+    let cfg_dir = xdg.join("mdvault");
+    let cfg_path = cfg_dir.join("config.toml");
+    fs::create_dir_all(&cfg_dir).unwrap();
+
+    fs::create_dir_all(vault.join(".mdvault/typedefs")).unwrap();
+    fs::create_dir_all(vault.join(".mdvault/templates")).unwrap();
+    fs::create_dir_all(vault.join(".mdvault/captures")).unwrap();
+    fs::create_dir_all(vault.join(".mdvault/macros")).unwrap();
+
+    let mut toml = String::new();
+    writeln!(&mut toml, "version = 1").unwrap();
+    writeln!(&mut toml, "profile = \"default\"").unwrap();
+    writeln!(&mut toml).unwrap();
+    writeln!(&mut toml, "[profiles.default]").unwrap();
+    writeln!(&mut toml, "vault_root = \"{}\"", vault.display()).unwrap();
+    writeln!(&mut toml, "typedefs_dir = \"{}/.mdvault/typedefs\"", vault.display())
+        .unwrap();
+    writeln!(&mut toml, "templates_dir = \"{}/.mdvault/templates\"", vault.display())
+        .unwrap();
+    writeln!(&mut toml, "captures_dir = \"{}/.mdvault/captures\"", vault.display())
+        .unwrap();
+    writeln!(&mut toml, "macros_dir = \"{}/.mdvault/macros\"", vault.display()).unwrap();
+
+    fs::write(&cfg_path, toml).unwrap();
+    cfg_path
+}
+
+fn run_mdv(cfg_path: &Path, args: &[&str]) -> std::process::Output {
+    let mut cmd = std::process::Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.env("NO_COLOR", "1");
+    let vault_root =
+        cfg_path.parent().unwrap().parent().unwrap().parent().unwrap().join("vault");
+    cmd.current_dir(&vault_root);
+    cmd.args(["--config", cfg_path.to_str().unwrap()]);
+    cmd.args(args);
+    cmd.output().expect("Failed to run mdv")
+}
+
+// --- Tests ---
+
+#[test]
+fn review_batch_lists_items_and_writes_weekly_summary() {
+    let (_tmp, vault, cfg_path) = setup_vault();
+
+    write(
+        &vault.join("Projects/TST/Tasks/TST-001.md"),
+        "---\ntype: task\ntitle: Ancient task\ntask-id: TST-001\nstatus: todo\ncreated: 2020-01-01\n---\n",
+    );
+    write(
+        &vault.join("Projects/TST/Tasks/TST-002.md"),
+        "---\ntype: task\ntitle: Finished task\ntask-id: TST-002\nstatus: done\ncreated: 2020-01-01\n---\n",
+    );
+    write(
+        &vault.join("zettels/lonely.md"),
+        "---\ntype: zettel\ntitle: Lonely idea\n---\n# Lonely idea\n",
+    );
+
+    let output = run_mdv(&cfg_path, &["reindex"]);
+    assert!(output.status.success(), "reindex failed: {:?}", output);
+
+    let output = run_mdv(&cfg_path, &["review", "--batch"]);
+    assert!(output.status.success(), "review failed: {:?}", output);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Ancient task"), "old task not listed: {stdout}");
+    assert!(!stdout.contains("Finished task"), "done task listed: {stdout}");
+    assert!(stdout.contains("Lonely idea"), "orphan zettel not listed: {stdout}");
+    assert!(stdout.contains("OK   mdv review"));
+
+    let week = chrono::Local::now().format("%G-W%V").to_string();
+    let weekly = vault.join(format!("Journal/{}/Weekly/{}.md", &week[..4], week));
+    assert!(weekly.exists(), "weekly note not created at {}", weekly.display());
+
+    let content = fs::read_to_string(&weekly).unwrap();
+    assert!(content.contains("## Review"));
+    assert!(content.contains("### Review decisions"));
+    assert!(content.contains("1 old tasks"));
+    assert!(content.contains("<!-- mdv:generated generator=\"review\""));
+    assert!(content.contains("<!-- /mdv:generated -->"));
+}
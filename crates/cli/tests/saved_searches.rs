@@ -0,0 +1,108 @@
+//! Integration tests for saved searches: `mdv search --saved`, `--list` and the dashboard.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n\n\
+             [searches.bread]\nquery = \"starter\"\ndescription = \"Baking notes\"\n",
+            root.join("vault").display()
+        ),
+    );
+    let vault = root.join("vault");
+    write(
+        &vault,
+        ".mdvault/searches.lua",
+        "saved_search \"inbox-overdue\" {\n    query = \"type:task status:todo due<today\",\n}\n",
+    );
+    write(
+        &vault,
+        "Tasks/late.md",
+        "---\ntype: task\ntitle: Late task\nstatus: todo\ndue: 2020-01-01\n---\n",
+    );
+    write(
+        &vault,
+        "Tasks/later.md",
+        "---\ntype: task\ntitle: Later task\nstatus: todo\ndue: 2999-01-01\n---\n",
+    );
+    write(&vault, "bread.md", "---\ntitle: Bread\n---\nFeed the starter daily.\n");
+    mdv(root, &["reindex"]).assert().success();
+    tmp
+}
+
+#[test]
+fn list_shows_config_and_lua_searches() {
+    let tmp = setup();
+    mdv(tmp.path(), &["search", "--list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("bread"))
+        .stdout(predicate::str::contains("Baking notes"))
+        .stdout(predicate::str::contains("type:task status:todo due<today"));
+
+    let output = mdv(tmp.path(), &["search", "--list", "--json"]).output().unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["bread"]["query"], "starter");
+    assert_eq!(json["inbox-overdue"]["query"], "type:task status:todo due<today");
+}
+
+#[test]
+fn saved_runs_the_named_query() {
+    let tmp = setup();
+    mdv(tmp.path(), &["search", "--saved", "inbox-overdue", "-q"])
+        .assert()
+        .success()
+        .stdout("Tasks/late.md\n");
+    mdv(tmp.path(), &["search", "--saved", "bread", "-q"])
+        .assert()
+        .success()
+        .stdout("bread.md\n");
+}
+
+#[test]
+fn unknown_saved_search_fails() {
+    let tmp = setup();
+    mdv(tmp.path(), &["search", "--saved", "nope"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown saved search 'nope'"));
+}
+
+#[test]
+fn dashboard_reports_saved_search_counts() {
+    let tmp = setup();
+    let output = mdv(tmp.path(), &["report", "--dashboard", "--json"]).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let searches = json["saved_searches"].as_array().unwrap();
+    let overdue = searches.iter().find(|s| s["name"] == "inbox-overdue").unwrap();
+    assert_eq!(overdue["count"], 1);
+    assert_eq!(overdue["notes"][0]["title"], "Late task");
+}
@@ -0,0 +1,90 @@
+//! Integration tests for `.mdvault/ranking.lua` reordering `mdv search` results.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            root.join("vault").display()
+        ),
+    );
+    let vault = root.join("vault");
+    write(&vault, "alpha-one.md", "---\ntitle: Alpha One\n---\nFirst.\n");
+    write(&vault, "alpha-two.md", "---\ntitle: Alpha Two\npinned: true\n---\nSecond.\n");
+    write(&vault, "hub.md", "# Hub\n\nSee [[alpha-two]].\n");
+    mdv(root, &["reindex"]).assert().success();
+    tmp
+}
+
+#[test]
+fn rank_hook_reorders_results() {
+    let tmp = setup();
+    write(
+        &tmp.path().join("vault"),
+        ".mdvault/ranking.lua",
+        "function rank(note, base, signals)\n\
+         \x20   if note.frontmatter.pinned then base = base + 1 end\n\
+         \x20   return base + signals.backlinks\n\
+         end\n",
+    );
+    let output = mdv(tmp.path(), &["search", "alpha", "--json"]).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["path"], "alpha-two.md");
+    // Both titles match equally; pinned and the backlink from hub add 2
+    assert_eq!(json[1]["path"], "alpha-one.md");
+    let score = |i: usize| json[i]["score"].as_f64().unwrap();
+    assert_eq!(score(0), score(1) + 2.0);
+}
+
+#[test]
+fn rank_hook_errors_name_the_note() {
+    let tmp = setup();
+    write(
+        &tmp.path().join("vault"),
+        ".mdvault/ranking.lua",
+        "function rank() return \"high\" end\n",
+    );
+    mdv(tmp.path(), &["search", "alpha"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Ranking hook failed for "))
+        .stderr(predicate::str::contains("must return a number or nil"));
+}
+
+#[test]
+fn ranking_file_without_rank_function_is_rejected() {
+    let tmp = setup();
+    write(&tmp.path().join("vault"), ".mdvault/ranking.lua", "weight = 2\n");
+    mdv(tmp.path(), &["search", "alpha"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("does not define a rank"));
+}
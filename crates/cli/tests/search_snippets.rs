@@ -0,0 +1,118 @@
+//! Integration tests for `mdv search` matching: highlighted snippets and fuzzy titles.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            root.join("vault").display()
+        ),
+    );
+    write(
+        &root.join("vault"),
+        "bread.md",
+        "---\ntitle: Bread\n---\nFeed the starter daily.\n\nA cold starter rises slowly.\n",
+    );
+    mdv(root, &["reindex"]).assert().success();
+    tmp
+}
+
+#[test]
+fn table_marks_matched_terms() {
+    let tmp = setup();
+    mdv(tmp.path(), &["search", "starter"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Feed the [starter] daily"));
+}
+
+#[test]
+fn json_includes_highlights_and_body_hits() {
+    let tmp = setup();
+    let output = mdv(tmp.path(), &["search", "starter", "--json"]).output().unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let hit = &json[0];
+
+    let snippet = hit["snippet"].as_str().unwrap();
+    assert!(!snippet.contains('['));
+    let [start, end] = [&hit["highlights"][0][0], &hit["highlights"][0][1]]
+        .map(|v| v.as_u64().unwrap() as usize);
+    let matched: String = snippet.chars().skip(start).take(end - start).collect();
+    assert_eq!(matched, "starter");
+
+    let lines: Vec<_> =
+        hit["matches"].as_array().unwrap().iter().map(|m| m["line"].clone()).collect();
+    assert_eq!(lines, [1, 3]);
+    assert_eq!(hit["matches"][0]["start"], 9);
+    assert_eq!(hit["matches"][0]["end"], 16);
+}
+
+#[test]
+fn typos_match_titles_unless_fuzzy_is_off() {
+    let tmp = setup();
+    write(&tmp.path().join("vault"), "kickoff.md", "# Project Kickoff\n\nAgenda.\n");
+    mdv(tmp.path(), &["reindex"]).assert().success();
+
+    mdv(tmp.path(), &["search", "projct kickof", "-q"])
+        .assert()
+        .success()
+        .stdout("kickoff.md\n");
+    mdv(tmp.path(), &["search", "projct kickof", "--no-fuzzy", "-q"])
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[test]
+fn boolean_queries_and_prefixes() {
+    let tmp = setup();
+    let vault = tmp.path().join("vault");
+    write(
+        &vault,
+        "rye.md",
+        "---\ntype: zettel\nstatus: draft\n---\n# Rye\n\nA sour starter.\n",
+    );
+    write(&vault, "old.md", "---\ntype: zettel\n---\n# Old\n\nAn archived starter.\n");
+    mdv(tmp.path(), &["reindex"]).assert().success();
+
+    mdv(tmp.path(), &["search", "starter -archived type:zettel", "-q"])
+        .assert()
+        .success()
+        .stdout("rye.md\n");
+    mdv(tmp.path(), &["search", "(sour OR cold) status:draft", "-q"])
+        .assert()
+        .success()
+        .stdout("rye.md\n");
+    mdv(tmp.path(), &["search", "starter AND (rye"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unbalanced parenthesis"));
+}
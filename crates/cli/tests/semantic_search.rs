@@ -0,0 +1,85 @@
+//! Integration tests for `mdv embed` and `mdv search --mode semantic`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup(embeddings: &str) -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n{embeddings}\n\n[profiles.test]\n\
+             vault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            root.join("vault").display()
+        ),
+    );
+    let vault = root.join("vault");
+    write(&vault, "bread.md", "# Bread\n\nSourdough starter needs flour and feeding.\n");
+    write(&vault, "baking.md", "# Baking\n\nKneading the dough develops gluten.\n");
+    write(&vault, "garden.md", "# Garden\n\nTomatoes want sun and water.\n");
+    mdv(root, &["reindex"]).assert().success();
+    tmp
+}
+
+#[test]
+fn embeds_once_then_searches_by_similarity() {
+    let tmp = setup("[embeddings]\nbackend = \"hashing\"\ndimensions = 512");
+
+    mdv(tmp.path(), &["embed"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("hashing:512"))
+        .stdout(predicate::str::contains("Embedded:   3"));
+    mdv(tmp.path(), &["embed", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"unchanged\": 3"));
+
+    // "kneaded" appears nowhere, but shares stems with "Kneading"
+    mdv(tmp.path(), &["search", "kneaded doughs", "--mode", "semantic", "--quiet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("baking.md"))
+        .stdout(predicate::str::contains("garden.md").not());
+}
+
+#[test]
+fn semantic_search_needs_embeddings() {
+    let tmp = setup("[embeddings]\nbackend = \"hashing\"");
+    mdv(tmp.path(), &["search", "dough", "--mode", "semantic"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("mdv embed"));
+}
+
+#[test]
+fn ollama_needs_http_allowed() {
+    let tmp = setup("");
+    mdv(tmp.path(), &["embed"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("allow_http"));
+}
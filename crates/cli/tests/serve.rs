@@ -0,0 +1,303 @@
+//! Integration tests for `mdv serve`.
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: impl AsRef<str>) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content.as_ref()).unwrap();
+}
+
+fn make_config(vault_root: &str) -> String {
+    format!(
+        r#"
+version = 1
+profile = "test"
+
+[profiles.test]
+vault_root = "{vault_root}"
+templates_dir = "{{{{vault_root}}}}/templates"
+captures_dir = "{{{{vault_root}}}}/captures"
+macros_dir = "{{{{vault_root}}}}/macros"
+"#
+    )
+}
+
+/// A running `mdv serve`, killed when dropped.
+struct Server {
+    child: Child,
+    addr: String,
+}
+
+impl Server {
+    fn start(root: &Path) -> Self {
+        let mut child = Command::new(assert_cmd::cargo::cargo_bin!("mdv"))
+            .arg("--config")
+            .arg(root.join("config.toml"))
+            .args(["serve", "--bind", "127.0.0.1:0"])
+            .env("MDV_SERVE_TOKEN", "s3cret")
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut line = String::new();
+        BufReader::new(child.stdout.as_mut().unwrap()).read_line(&mut line).unwrap();
+        let addr = line.trim().strip_prefix("Listening on http://").unwrap().to_string();
+        Self { child, addr }
+    }
+
+    /// Send a request and return the status code and body.
+    fn request(
+        &self,
+        method: &str,
+        path: &str,
+        token: Option<&str>,
+        body: &str,
+    ) -> (u16, String) {
+        let response = self.send(method, path, token, body);
+        let status = response[9..12].parse().unwrap();
+        let body = response.split_once("\r\n\r\n").unwrap().1.to_string();
+        (status, body)
+    }
+
+    /// Send a request and return the whole response.
+    fn send(&self, method: &str, path: &str, token: Option<&str>, body: &str) -> String {
+        let mut stream = TcpStream::connect(&self.addr).unwrap();
+        let mut head = format!("{method} {path} HTTP/1.1\r\nHost: localhost\r\n");
+        if let Some(token) = token {
+            head.push_str(&format!("Authorization: Bearer {token}\r\n"));
+        }
+        head.push_str(&format!("Content-Length: {}\r\n\r\n", body.len()));
+        stream.write_all(format!("{head}{body}").as_bytes()).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn setup(root: &Path) {
+    write(root, "config.toml", make_config(&root.join("vault").to_string_lossy()));
+    write(
+        root,
+        "vault/captures/inbox.lua",
+        r#"
+return {
+    name = "inbox",
+    target = { file = "notes.md", section = "Inbox", position = "end" },
+    content = "- {{text}}",
+}
+"#,
+    );
+    write(root, "vault/notes.md", "# Notes\n\n## Inbox\n\n- Existing item\n");
+}
+
+#[test]
+fn capture_endpoint_runs_captures_and_files_text() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    setup(root);
+    let server = Server::start(root);
+
+    let (status, body) = server.request(
+        "POST",
+        "/capture",
+        Some("s3cret"),
+        r#"{"capture": "inbox", "vars": {"text": "From the browser"}}"#,
+    );
+    assert_eq!(status, 200, "{body}");
+    let notes = fs::read_to_string(root.join("vault/notes.md")).unwrap();
+    assert!(notes.contains("- Existing item\n- From the browser"), "{notes}");
+
+    let (status, body) =
+        server.request("POST", "/capture", Some("s3cret"), r#"{"text": "Call Ana"}"#);
+    assert_eq!(status, 200, "{body}");
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let note = root.join("vault").join(json["note"].as_str().unwrap());
+    assert!(fs::read_to_string(note).unwrap().contains("Call Ana"));
+
+    let log = fs::read_to_string(root.join("vault/.mdvault/activity.jsonl")).unwrap();
+    assert!(log.contains(r#""op":"capture""#), "{log}");
+    assert!(log.contains(r#""op":"new""#), "{log}");
+}
+
+#[test]
+fn capture_endpoint_rejects_bad_requests() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    setup(root);
+    let server = Server::start(root);
+    let body = r#"{"capture": "inbox", "vars": {"text": "nope"}}"#;
+
+    assert_eq!(server.request("POST", "/capture", None, body).0, 401);
+    assert_eq!(server.request("POST", "/capture", Some("wrong"), body).0, 401);
+    assert_eq!(server.request("GET", "/capture", Some("s3cret"), "").0, 405);
+    assert_eq!(server.request("POST", "/other", Some("s3cret"), body).0, 404);
+    assert_eq!(server.request("POST", "/capture", Some("s3cret"), "{").0, 400);
+    assert_eq!(
+        server.request("POST", "/capture", Some("s3cret"), r#"{"capture": "missing"}"#).0,
+        422
+    );
+    let notes = fs::read_to_string(root.join("vault/notes.md")).unwrap();
+    assert!(!notes.contains("nope"));
+}
+
+#[test]
+fn writes_are_rate_limited_per_client() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    setup(root);
+    let config = make_config(&root.join("vault").to_string_lossy());
+    write(
+        root,
+        "config.toml",
+        format!(
+            "{config}\n[security.api.keys]\nphone = \"k-phone\"\nlaptop = \"k-laptop\"\n\n\
+             [security.api.limits]\ninbox = \"1/min\"\n"
+        ),
+    );
+    let server = Server::start(root);
+
+    let (status, body) =
+        server.request("POST", "/capture", Some("k-phone"), r#"{"text": "First"}"#);
+    assert_eq!(status, 200, "{body}");
+    let response =
+        server.send("POST", "/capture", Some("k-phone"), r#"{"text": "Second"}"#);
+    assert!(response.starts_with("HTTP/1.1 429 Too Many Requests"), "{response}");
+    let retry_after = response
+        .lines()
+        .find_map(|l| l.strip_prefix("Retry-After: "))
+        .and_then(|s| s.trim().parse::<u64>().ok());
+    assert!(matches!(retry_after, Some(1..=60)), "{response}");
+
+    // Another client has its own bucket, and captures are not limited
+    let (status, body) =
+        server.request("POST", "/capture", Some("k-laptop"), r#"{"text": "Third"}"#);
+    assert_eq!(status, 200, "{body}");
+    let (status, body) = server.request(
+        "POST",
+        "/capture",
+        Some("k-phone"),
+        r#"{"capture": "inbox", "vars": {"text": "Fourth"}}"#,
+    );
+    assert_eq!(status, 200, "{body}");
+    // MDV_SERVE_TOKEN still works alongside the keys
+    let (status, body) =
+        server.request("POST", "/capture", Some("s3cret"), r#"{"text": "Fifth"}"#);
+    assert_eq!(status, 200, "{body}");
+}
+
+#[test]
+fn write_attempts_are_audited() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    setup(root);
+    let server = Server::start(root);
+
+    let (status, body) = server.request(
+        "POST",
+        "/capture",
+        Some("s3cret"),
+        r#"{"capture": "inbox", "vars": {"text": "Logged"}}"#,
+    );
+    assert_eq!(status, 200, "{body}");
+    let (status, body) =
+        server.request("POST", "/capture", Some("s3cret"), r#"{"text": "Call Ana"}"#);
+    assert_eq!(status, 200, "{body}");
+    let filed: serde_json::Value = serde_json::from_str(&body).unwrap();
+    server.request("POST", "/capture", Some("wrong"), r#"{"text": "Nope"}"#);
+    server.request("POST", "/capture", Some("s3cret"), r#"{"capture": "missing"}"#);
+    // Not a write, so not audited
+    server.request("GET", "/capture", Some("s3cret"), "");
+
+    let log = fs::read_to_string(root.join("vault/.mdvault/api-audit.jsonl")).unwrap();
+    let records: Vec<serde_json::Value> =
+        log.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(records.len(), 4, "{log}");
+    for record in &records {
+        assert!(record["timestamp"].is_string(), "{record}");
+    }
+
+    assert_eq!(records[0]["client"], "default");
+    assert_eq!(records[0]["operation"], "capture");
+    assert_eq!(records[0]["capture"], "inbox");
+    assert_eq!(records[0]["target"], "notes.md");
+    assert_eq!(records[0]["accepted"], true);
+
+    assert_eq!(records[1]["operation"], "inbox");
+    assert_eq!(records[1]["target"], filed["note"]);
+    assert_eq!(records[1]["accepted"], true);
+
+    assert!(records[2]["client"].is_null());
+    assert_eq!(records[2]["accepted"], false);
+    assert_eq!(records[2]["status"], 401);
+
+    assert_eq!(records[3]["capture"], "missing");
+    assert!(records[3]["target"].is_null());
+    assert_eq!(records[3]["accepted"], false);
+    assert_eq!(records[3]["status"], 422);
+    assert!(records[3]["reason"].as_str().unwrap().contains("missing"));
+}
+
+#[test]
+fn slow_clients_do_not_hold_up_others() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    setup(root);
+    let server = Server::start(root);
+
+    // Send half a request line and stall
+    let mut slow = TcpStream::connect(&server.addr).unwrap();
+    slow.write_all(b"POST /capt").unwrap();
+    thread::sleep(Duration::from_millis(100));
+
+    let started = Instant::now();
+    let (status, body) =
+        server.request("POST", "/capture", Some("s3cret"), r#"{"text": "Call Ana"}"#);
+    assert_eq!(status, 200, "{body}");
+    assert!(started.elapsed() < Duration::from_secs(5), "{:?}", started.elapsed());
+
+    // Trickling a byte at a time does not extend the 10s deadline
+    slow.set_read_timeout(Some(Duration::from_millis(500))).unwrap();
+    let mut response = Vec::new();
+    while started.elapsed() < Duration::from_secs(20) {
+        let _ = slow.write_all(b"u");
+        if slow.read_to_end(&mut response).is_ok() || !response.is_empty() {
+            break;
+        }
+    }
+    let response = String::from_utf8_lossy(&response);
+    assert!(response.starts_with("HTTP/1.1 408"), "{response}");
+    assert!(started.elapsed() < Duration::from_secs(15), "{:?}", started.elapsed());
+}
+
+#[test]
+fn serve_refuses_to_start_without_a_token() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    setup(root);
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("mdv"))
+        .arg("--config")
+        .arg(root.join("config.toml"))
+        .args(["serve", "--bind", "127.0.0.1:0"])
+        .env_remove("MDV_SERVE_TOKEN")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("mdv serve needs a token"));
+}
@@ -0,0 +1,147 @@
+//! Integration tests for configurable staleness scoring in `mdv stale`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+/// A vault where today's daily links to `active.md` and nothing links to
+/// `forgotten.md`. `extra_config` is appended to the config file.
+fn setup(extra_config: &str) -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n\n{extra_config}",
+            root.join("vault").display()
+        ),
+    );
+    let vault = root.join("vault");
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    write(
+        &vault,
+        &format!("daily/{today}.md"),
+        &format!("---\ntype: daily\ndate: {today}\n---\nWorked on [[active]].\n"),
+    );
+    write(&vault, "active.md", "---\ntitle: Active\n---\nIn use.\n");
+    write(
+        &vault,
+        "forgotten.md",
+        "---\ntitle: Forgotten\nevergreen: true\n---\nUntouched.\n",
+    );
+    tmp
+}
+
+fn stale_json(root: &Path, args: &[&str]) -> Vec<serde_json::Value> {
+    let output = mdv(root, &[&["stale", "--json"], args].concat()).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+fn find<'a>(notes: &'a [serde_json::Value], path: &str) -> Option<&'a serde_json::Value> {
+    notes.iter().find(|n| n["path"] == path)
+}
+
+#[test]
+fn default_weights_score_daily_references() {
+    let tmp = setup("");
+    mdv(tmp.path(), &["reindex"]).assert().success();
+
+    let notes = stale_json(tmp.path(), &[]);
+    let forgotten = find(&notes, "forgotten.md").expect("forgotten note is stale");
+    assert!((forgotten["staleness"].as_f64().unwrap() - 0.84).abs() < 1e-6);
+    assert!(find(&notes, "active.md").is_none());
+}
+
+#[test]
+fn weights_come_from_config() {
+    let tmp = setup(
+        "[staleness]\nreferenced_weight = 0.0\nactivity_weight = 0.0\n\
+         modified_weight = 0.0\nbacklinks_weight = 1.0\n",
+    );
+    mdv(tmp.path(), &["reindex"]).assert().success();
+
+    // Only backlinks count: the daily's fresh link halves active.md's score
+    let notes = stale_json(tmp.path(), &["--threshold", "0.4"]);
+    let score = |path| find(&notes, path).unwrap()["staleness"].as_f64().unwrap();
+    assert!((score("active.md") - 0.5).abs() < 1e-6);
+    assert!((score("forgotten.md") - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn explain_breaks_scores_down() {
+    let tmp = setup("");
+    mdv(tmp.path(), &["reindex"]).assert().success();
+
+    mdv(tmp.path(), &["stale", "--explain"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("referenced  1.00 x 0.60 = 0.60"))
+        .stdout(predicate::str::contains("activity    1.00 x 0.24 = 0.24"))
+        .stdout(predicate::str::contains("modified").not());
+
+    let notes = stale_json(tmp.path(), &["--explain"]);
+    let explain = &find(&notes, "forgotten.md").unwrap()["explain"];
+    assert_eq!(explain["components"].as_array().unwrap().len(), 4);
+    assert_eq!(explain["components"][0]["signal"], "referenced");
+    assert_eq!(explain["from_hook"], false);
+
+    let plain = stale_json(tmp.path(), &[]);
+    assert!(find(&plain, "forgotten.md").unwrap().get("explain").is_none());
+}
+
+#[test]
+fn staleness_lua_overrides_scores() {
+    let tmp = setup("");
+    write(
+        &tmp.path().join("vault"),
+        ".mdvault/staleness.lua",
+        "function staleness(note, signals, score)\n\
+             if note.frontmatter.evergreen then return 0 end\n\
+         end\n",
+    );
+    mdv(tmp.path(), &["reindex"]).assert().success();
+    assert!(find(&stale_json(tmp.path(), &[]), "forgotten.md").is_none());
+
+    let notes = stale_json(tmp.path(), &["--threshold", "0", "--explain"]);
+    let explain = &find(&notes, "forgotten.md").unwrap()["explain"];
+    assert_eq!(explain["score"], 0.0);
+    assert_eq!(explain["from_hook"], true);
+}
+
+#[test]
+fn invalid_models_fail_reindex() {
+    let tmp = setup("[staleness]\nhorizon_days = 0\n");
+    mdv(tmp.path(), &["reindex"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("horizon_days must be positive"));
+
+    let tmp = setup("");
+    write(&tmp.path().join("vault"), ".mdvault/staleness.lua", "x = 1\n");
+    mdv(tmp.path(), &["reindex"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("does not define a staleness"));
+}
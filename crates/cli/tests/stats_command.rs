@@ -0,0 +1,92 @@
+//! Integration tests for `mdv stats` and quota warnings in `mdv doctor`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: impl AsRef<[u8]>) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn make_config(vault_root: &str, quota: &str) -> String {
+    format!(
+        r#"
+version = 1
+profile = "test"
+
+[profiles.test]
+vault_root = "{vault_root}"
+templates_dir = "{{{{vault_root}}}}/templates"
+captures_dir = "{{{{vault_root}}}}/captures"
+macros_dir = "{{{{vault_root}}}}/macros"
+{quota}
+"#
+    )
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup(quota: &str) -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(root, "config.toml", make_config(&root.join("vault").to_string_lossy(), quota));
+    write(root, "vault/Logs/server.md", vec![b'x'; 4096]);
+    write(root, "vault/Projects/plan.md", "# Plan\n");
+    write(root, "vault/Projects/diagram.png", vec![0u8; 2048]);
+    tmp
+}
+
+#[test]
+fn stats_lists_large_files_and_folders() {
+    let tmp = setup("");
+    mdv(tmp.path(), &["stats", "--large", "-n", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("files: 3 (2 notes, 1 attachments)"))
+        .stdout(predicate::str::contains("4.0 KB  Logs/server.md"))
+        .stdout(predicate::str::contains("diagram.png").not())
+        .stdout(predicate::str::contains("Projects/"));
+}
+
+#[test]
+fn quota_warnings_in_stats_and_doctor() {
+    let tmp = setup("\n[quota]\nvault = \"1MB\"\nfolders = { Logs = \"2KB\" }\n");
+    mdv(tmp.path(), &["stats"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("quota: Logs/ 4.0 KB of 2.0 KB (200%)"))
+        .stderr(predicate::str::contains(
+            "Warning: Logs/ is 4.0 KB, over its 2.0 KB quota",
+        ));
+    mdv(tmp.path(), &["doctor"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("WARN quota"))
+        .stdout(predicate::str::contains("Logs/ is 4.0 KB"))
+        .stdout(predicate::str::contains("vault is").not());
+}
+
+#[test]
+fn stats_reports_words_once_indexed() {
+    let tmp = setup("");
+    mdv(tmp.path(), &["stats"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("words:").not());
+    mdv(tmp.path(), &["reindex"]).assert().success();
+    mdv(tmp.path(), &["stats"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("words: 2 (~1 min read)"));
+}
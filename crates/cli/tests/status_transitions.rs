@@ -0,0 +1,100 @@
+//! Integration tests for status transitions declared in typedefs.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: impl AsRef<str>) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content.as_ref()).unwrap();
+}
+
+fn make_config(vault_root: &str) -> String {
+    format!(
+        r#"
+version = 1
+profile = "test"
+
+[profiles.test]
+vault_root = "{vault_root}"
+templates_dir = "{{{{vault_root}}}}/templates"
+captures_dir = "{{{{vault_root}}}}/captures"
+macros_dir = "{{{{vault_root}}}}/macros"
+"#
+    )
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(root, "config.toml", make_config(&root.join("vault").to_string_lossy()));
+    write(
+        root,
+        "types/task.lua",
+        r#"return { schema = { status = {
+    type = "string",
+    enum = { "todo", "doing", "blocked", "done", "cancelled" },
+    transitions = { "todo -> doing -> done", "doing -> blocked -> doing", "todo -> cancelled" },
+} } }"#,
+    );
+    write(
+        root,
+        "vault/Inbox/INB-001.md",
+        "---\ntype: task\nstatus: todo\n---\n# Write docs\n",
+    );
+    tmp
+}
+
+#[test]
+fn task_done_refuses_illegal_transition() {
+    let tmp = setup();
+    let root = tmp.path();
+
+    mdv(root, &["task", "done", "Inbox/INB-001.md"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Cannot mark task done"))
+        .stderr(predicate::str::contains(
+            "illegal transition for 'status': 'todo' -> 'done' (from 'todo' only: doing, cancelled)",
+        ));
+    let content = fs::read_to_string(root.join("vault/Inbox/INB-001.md")).unwrap();
+    assert!(content.contains("status: todo"), "{content}");
+
+    mdv(root, &["task", "cancel", "Inbox/INB-001.md"]).assert().success();
+}
+
+#[test]
+fn validate_checks_transition_from_indexed_status() {
+    let tmp = setup();
+    let root = tmp.path();
+    mdv(root, &["reindex"]).assert().success();
+
+    write(
+        root,
+        "vault/Inbox/INB-001.md",
+        "---\ntype: task\nstatus: done\n---\n# Write docs\n",
+    );
+    mdv(root, &["validate", "Inbox/INB-001.md"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("'todo' -> 'done'"));
+
+    write(
+        root,
+        "vault/Inbox/INB-001.md",
+        "---\ntype: task\nstatus: doing\n---\n# Write docs\n",
+    );
+    mdv(root, &["validate", "Inbox/INB-001.md"]).assert().success();
+}
@@ -0,0 +1,118 @@
+//! Integration tests for `mdv suggest-links`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+/// `zettel/bread.md` mentions "Sourdough" and the alias "Starter", and
+/// already links to the oven note.
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            root.join("vault").display()
+        ),
+    );
+    let vault = root.join("vault");
+    write(
+        &vault,
+        "zettel/bread.md",
+        "---\ntype: zettel\ntitle: Bread\n---\n\
+         Sourdough needs a lively starter and a hot [[oven]].\n",
+    );
+    write(&vault, "zettel/sourdough.md", "---\ntitle: Sourdough\n---\nSlow bread.\n");
+    write(
+        &vault,
+        "zettel/culture.md",
+        "---\ntitle: Culture\naliases: [Starter]\n---\nFlour and water.\n",
+    );
+    write(&vault, "oven.md", "# Oven\n\nHot.\n");
+    write(&vault, "garden.md", "# Garden\n\nTomatoes.\n");
+    mdv(root, &["reindex"]).assert().success();
+    tmp
+}
+
+#[test]
+fn lists_mentioned_notes_not_yet_linked() {
+    let tmp = setup();
+    mdv(tmp.path(), &["suggest-links", "zettel/bread.md"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("zettel/sourdough.md"))
+        .stdout(predicate::str::contains("mentions \"Starter\""))
+        .stdout(predicate::str::contains("oven.md").not())
+        .stdout(predicate::str::contains("garden.md").not());
+}
+
+#[test]
+fn json_includes_links_and_signals() {
+    let tmp = setup();
+    let output = mdv(tmp.path(), &["suggest-links", "./zettel/bread.md", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let items = json.as_array().unwrap();
+    assert_eq!(items.len(), 2);
+
+    let culture = items.iter().find(|s| s["path"] == "zettel/culture.md").unwrap();
+    assert_eq!(culture["matched"], "Starter");
+    assert_eq!(culture["mentions"], 1);
+    assert_eq!(culture["link"], "[[zettel/culture|Culture]]");
+    assert!(culture["confidence"].as_f64().unwrap() > 0.0);
+}
+
+#[test]
+fn apply_adds_a_related_section() {
+    let tmp = setup();
+    let note = tmp.path().join("vault/zettel/bread.md");
+    mdv(tmp.path(), &["suggest-links", "zettel/bread.md", "--apply", "-n", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added 1 links"));
+
+    let content = fs::read_to_string(&note).unwrap();
+    assert!(content.ends_with("\n\n## Related\n\n- [[zettel/culture|Culture]]\n"));
+
+    // The index picked up the new link, so it is not suggested again
+    mdv(tmp.path(), &["suggest-links", "zettel/bread.md", "--apply"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("zettel/culture.md").not());
+    let content = fs::read_to_string(&note).unwrap();
+    assert_eq!(content.matches("## Related").count(), 1);
+    assert!(content.contains("- [[zettel/sourdough|Sourdough]]"));
+}
+
+#[test]
+fn unknown_note_fails() {
+    let tmp = setup();
+    mdv(tmp.path(), &["suggest-links", "nope.md"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Note not found in index: nope.md"));
+}
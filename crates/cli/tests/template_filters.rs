@@ -0,0 +1,89 @@
+//! Integration tests for template filters, built-in and from `.mdvault/filters.lua`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: impl AsRef<str>) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content.as_ref()).unwrap();
+}
+
+fn make_config(vault_root: &str) -> String {
+    format!(
+        r#"
+version = 1
+profile = "test"
+
+[profiles.test]
+vault_root = "{vault_root}"
+templates_dir = "{{{{vault_root}}}}/templates"
+captures_dir = "{{{{vault_root}}}}/captures"
+macros_dir = "{{{{vault_root}}}}/macros"
+"#
+    )
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup(root: &Path) {
+    write(root, "config.toml", make_config(&root.join("vault").to_string_lossy()));
+    write(
+        root,
+        "types/zettel.lua",
+        r#"return {
+    output = "zettel/{{zettel-id | default:\"draft\" | shout}}-{{title | slug}}.md",
+}"#,
+    );
+}
+
+#[test]
+fn output_path_uses_lua_and_builtin_filters() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    setup(root);
+    write(
+        root,
+        "vault/.mdvault/filters.lua",
+        "return { shout = function(value) return value:upper() .. '!' end }",
+    );
+
+    mdv(root, &["new", "zettel", "Team Sync", "--batch"]).assert().success();
+    assert!(root.join("vault/zettel/DRAFT!-team-sync.md").exists());
+}
+
+#[test]
+fn invalid_filters_file_is_reported_without_failing() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    setup(root);
+    write(
+        root,
+        "vault/.mdvault/filters.lua",
+        "return { upper = function(v) return v end }",
+    );
+
+    mdv(root, &["new", "zettel", "Team Sync", "--batch"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("'upper' is a built-in filter"));
+    // The unknown filter leaves the value unchanged
+    assert!(root.join("vault/zettel/draft-team-sync.md").exists());
+
+    write(root, "vault/.mdvault/filters.lua", "return {");
+    fs::create_dir_all(root.join("vault/templates")).unwrap();
+    mdv(root, &["list-templates"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("custom template filters are unavailable"));
+}
@@ -0,0 +1,103 @@
+//! Integration tests for templates that `{% extends %}` a base template.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: impl AsRef<str>) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content.as_ref()).unwrap();
+}
+
+fn make_config(vault_root: &str) -> String {
+    format!(
+        r#"
+version = 1
+profile = "test"
+
+[profiles.test]
+vault_root = "{vault_root}"
+templates_dir = "{{{{vault_root}}}}/templates"
+captures_dir = "{{{{vault_root}}}}/captures"
+macros_dir = "{{{{vault_root}}}}/macros"
+"#
+    )
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup(root: &Path) {
+    write(root, "config.toml", make_config(&root.join("vault").to_string_lossy()));
+    write(
+        root,
+        "vault/templates/base-note.md",
+        "# {{title}}\n\n{% block body %}\nWrite here.\n{% endblock %}\n\n## Links\n",
+    );
+}
+
+#[test]
+fn new_renders_child_template_into_base() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    setup(root);
+    write(
+        root,
+        "vault/templates/retro.md",
+        "{% extends \"base-note\" %}\n{% block body %}\n## Attendees\n{% endblock %}\n",
+    );
+    let output = root.join("vault/standup.md");
+
+    mdv(
+        root,
+        &[
+            "new",
+            "--template",
+            "retro",
+            "--output",
+            output.to_str().unwrap(),
+            "--var",
+            "title=Standup",
+        ],
+    )
+    .assert()
+    .success();
+
+    let rendered = fs::read_to_string(&output).unwrap();
+    assert!(rendered.contains("# Standup\n\n## Attendees\n\n## Links\n"), "{rendered}");
+    assert!(!rendered.contains("{%"));
+}
+
+#[test]
+fn new_reports_unknown_parent_template() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    setup(root);
+    write(root, "vault/templates/retro.md", "{% extends \"nope\" %}\n");
+    let output = root.join("vault/standup.md");
+
+    mdv(
+        root,
+        &[
+            "new",
+            "--template",
+            "retro",
+            "--output",
+            output.to_str().unwrap(),
+            "--var",
+            "title=X",
+        ],
+    )
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("template 'retro' extends unknown template 'nope'"));
+}
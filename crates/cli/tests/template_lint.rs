@@ -0,0 +1,95 @@
+//! Integration tests for `mdv list-templates --check`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: impl AsRef<str>) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content.as_ref()).unwrap();
+}
+
+fn make_config(vault_root: &str) -> String {
+    format!(
+        r#"
+version = 1
+profile = "test"
+
+[profiles.test]
+vault_root = "{vault_root}"
+templates_dir = "{{{{vault_root}}}}/templates"
+captures_dir = "{{{{vault_root}}}}/captures"
+macros_dir = "{{{{vault_root}}}}/macros"
+"#
+    )
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup(root: &Path) {
+    write(root, "config.toml", make_config(&root.join("vault").to_string_lossy()));
+    write(
+        root,
+        "types/ticket.lua",
+        r#"return { output = "tickets/{{ref}}.md", schema = { ref = { type = "string" } } }"#,
+    );
+    write(root, "vault/templates/ticket.md", "# {{ref}}: {{title}}\n");
+    write(
+        root,
+        "vault/templates/note.md",
+        "---\noutput: \"notes/{{title | slug}}.md\"\n---\n# {{title}}\n{{mood}}\n",
+    );
+}
+
+#[test]
+fn check_passes_with_only_warnings() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    setup(root);
+
+    mdv(root, &["list-templates", "--check"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("warn    note"))
+        .stdout(predicate::str::contains("    warning: undeclared variable 'mood'"))
+        .stdout(predicate::str::contains("ok      ticket"))
+        .stdout(predicate::str::contains("2 template(s): 2 ok, 0 with errors"));
+}
+
+#[test]
+fn check_fails_on_errors_and_reports_json() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    setup(root);
+    write(
+        root,
+        "vault/templates/broken.md",
+        "---\noutput: \"x/{{missing}}.md\"\n---\n{{title | shout}}\n",
+    );
+
+    mdv(root, &["list-templates", "--check"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("FAILED  broken"))
+        .stdout(predicate::str::contains("    error: unknown filter 'shout'"))
+        .stderr(predicate::str::contains("1 template(s) have errors"));
+
+    let output = mdv(root, &["list-templates", "--check", "--json"]).output().unwrap();
+    assert!(!output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["summary"]["total"], 3);
+    assert_eq!(json["summary"]["failed"], 1);
+    let broken = &json["templates"][0];
+    assert_eq!(broken["name"], "broken");
+    assert_eq!(broken["errors"][1], "output path 'x/{{missing}}.md' cannot be rendered");
+}
@@ -0,0 +1,107 @@
+//! Integration tests for `mdv types export`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: impl AsRef<str>) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content.as_ref()).unwrap();
+}
+
+fn make_config(vault_root: &str) -> String {
+    format!(
+        r#"
+version = 1
+profile = "test"
+
+[profiles.test]
+vault_root = "{vault_root}"
+templates_dir = "{{{{vault_root}}}}/templates"
+captures_dir = "{{{{vault_root}}}}/captures"
+macros_dir = "{{{{vault_root}}}}/macros"
+"#
+    )
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(root, "config.toml", make_config(&root.join("vault").to_string_lossy()));
+    write(
+        root,
+        "types/book.lua",
+        r#"return {
+    description = "Books I have read",
+    schema = {
+        author = { type = "string", required = true, min_length = 2 },
+        rating = { type = "number", integer = true, min = 1, max = 5 },
+        state = { type = "string", enum = { "reading", "read" }, default = "reading" },
+    },
+}"#,
+    );
+    fs::create_dir_all(root.join("vault")).unwrap();
+    tmp
+}
+
+fn json(bytes: &[u8]) -> serde_json::Value {
+    serde_json::from_slice(bytes).expect("valid JSON")
+}
+
+#[test]
+fn types_export_prints_one_type_to_stdout() {
+    let tmp = setup();
+    let out = mdv(tmp.path(), &["types", "export", "book", "--stdout"]).output().unwrap();
+    assert!(out.status.success());
+
+    let schema = json(&out.stdout);
+    assert_eq!(schema["title"], "book");
+    assert_eq!(schema["description"], "Books I have read");
+    assert_eq!(schema["properties"]["type"]["const"], "book");
+    assert_eq!(schema["properties"]["author"]["minLength"], 2);
+    assert_eq!(schema["properties"]["rating"]["type"], "integer");
+    assert_eq!(schema["properties"]["rating"]["maximum"], 5.0);
+    assert_eq!(
+        schema["properties"]["state"]["enum"],
+        serde_json::json!(["reading", "read"])
+    );
+    assert_eq!(schema["properties"]["state"]["default"], "reading");
+    assert_eq!(schema["required"], serde_json::json!(["author", "type"]));
+}
+
+#[test]
+fn types_export_writes_every_type_to_schemas_dir() {
+    let tmp = setup();
+    let root = tmp.path();
+    mdv(root, &["types", "export"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("book.schema.json"));
+
+    let dir = root.join("vault/.mdvault/schemas");
+    let book = json(&fs::read(dir.join("book.schema.json")).unwrap());
+    assert_eq!(book["properties"]["author"]["type"], "string");
+    let task = json(&fs::read(dir.join("task.schema.json")).unwrap());
+    assert_eq!(task["properties"]["type"]["const"], "task");
+}
+
+#[test]
+fn types_export_rejects_unknown_type() {
+    let tmp = setup();
+    mdv(tmp.path(), &["types", "export", "nope", "--stdout"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown type: nope"));
+}
@@ -0,0 +1,108 @@
+//! Integration tests for `mdv types test`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: impl AsRef<str>) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content.as_ref()).unwrap();
+}
+
+fn make_config(vault_root: &str) -> String {
+    format!(
+        r#"
+version = 1
+profile = "test"
+
+[profiles.test]
+vault_root = "{vault_root}"
+templates_dir = "{{{{vault_root}}}}/templates"
+captures_dir = "{{{{vault_root}}}}/captures"
+macros_dir = "{{{{vault_root}}}}/macros"
+"#
+    )
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(root, "config.toml", make_config(&root.join("vault").to_string_lossy()));
+    write(
+        root,
+        "types/book.lua",
+        r#"return {
+    schema = {
+        author = { type = "string", required = true },
+        state = { type = "string", enum = { "reading", "read" } },
+    },
+    validate = function(note)
+        if note.frontmatter.state == "read" and not note.frontmatter.rating then
+            return false, "read books need a rating"
+        end
+        return true
+    end,
+}"#,
+    );
+    write(root, "types/tests/book/reading.yaml", "frontmatter:\n  author: Le Guin\n");
+    write(
+        root,
+        "types/tests/book/read-without-rating.yaml",
+        "frontmatter:\n  author: Le Guin\n  state: read\nexpect:\n  errors: [\"need a rating\"]\n",
+    );
+    fs::create_dir_all(root.join("vault")).unwrap();
+    tmp
+}
+
+#[test]
+fn types_test_runs_fixtures_through_validation() {
+    let tmp = setup();
+    mdv(tmp.path(), &["types", "test"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ok      book/read-without-rating"))
+        .stdout(predicate::str::contains("ok      book/reading"))
+        .stdout(predicate::str::contains("2 fixture(s): 2 passed, 0 failed"));
+}
+
+#[test]
+fn types_test_reports_fixtures_that_no_longer_hold() {
+    let tmp = setup();
+    let root = tmp.path();
+    // The validator stops requiring a rating
+    write(
+        root,
+        "types/book.lua",
+        r#"return { schema = { author = { type = "string", required = true } } }"#,
+    );
+
+    mdv(root, &["types", "test", "book"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("FAILED  book/read-without-rating"))
+        .stdout(predicate::str::contains("expected the note to be invalid"))
+        .stdout(predicate::str::contains("no error containing 'need a rating'"))
+        .stdout(predicate::str::contains("2 fixture(s): 1 passed, 1 failed"))
+        .stderr(predicate::str::contains("1 fixture(s) failed"));
+}
+
+#[test]
+fn types_test_without_fixtures() {
+    let tmp = setup();
+    mdv(tmp.path(), &["types", "test", "task"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No fixtures found"));
+}
@@ -0,0 +1,101 @@
+//! Integration tests for schema fields declared `unique = true`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: impl AsRef<str>) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content.as_ref()).unwrap();
+}
+
+fn make_config(vault_root: &str) -> String {
+    format!(
+        r#"
+version = 1
+profile = "test"
+
+[profiles.test]
+vault_root = "{vault_root}"
+templates_dir = "{{{{vault_root}}}}/templates"
+captures_dir = "{{{{vault_root}}}}/captures"
+macros_dir = "{{{{vault_root}}}}/macros"
+"#
+    )
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup(root: &Path) {
+    write(root, "config.toml", make_config(&root.join("vault").to_string_lossy()));
+    write(
+        root,
+        "types/zettel.lua",
+        r#"return {
+    output = "zettel/{{title | slugify}}.md",
+    schema = {
+        ["zettel-id"] = { type = "string", required = true, unique = true },
+    },
+}"#,
+    );
+    write(
+        root,
+        "vault/zettel/first.md",
+        "---\ntype: zettel\ntitle: First\nzettel-id: C-1\n---\n",
+    );
+}
+
+#[test]
+fn validate_flags_duplicate_unique_values() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    setup(root);
+    write(
+        root,
+        "vault/zettel/second.md",
+        "---\ntype: zettel\ntitle: Second\nzettel-id: C-1\n---\n",
+    );
+    write(
+        root,
+        "vault/zettel/third.md",
+        "---\ntype: zettel\ntitle: Third\nzettel-id: C-3\n---\n",
+    );
+    mdv(root, &["reindex"]).assert().success();
+
+    mdv(root, &["validate", "--type", "zettel"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "duplicate value for unique field 'zettel-id': 'C-1' is also used by zettel/second.md",
+        ))
+        .stdout(predicate::str::contains("third.md").not());
+}
+
+#[test]
+fn new_refuses_duplicate_unique_value() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    setup(root);
+    mdv(root, &["reindex"]).assert().success();
+
+    mdv(root, &["new", "zettel", "Clash", "--batch", "--var", "zettel-id=C-1"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("duplicate value for unique field 'zettel-id'"));
+    assert!(!root.join("vault/zettel/clash.md").exists());
+
+    mdv(root, &["new", "zettel", "Fresh", "--batch", "--var", "zettel-id=C-2"])
+        .assert()
+        .success();
+    assert!(root.join("vault/zettel/fresh.md").exists());
+}
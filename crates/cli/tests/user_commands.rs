@@ -0,0 +1,274 @@
+//! Integration tests for user-defined Lua commands (`mdv <name>`).
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+/// Answer one HTTP request with `body` and return the URL to fetch it from.
+fn serve_once(body: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/note.md", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = [0; 4096];
+        let _ = stream.read(&mut request);
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+    url
+}
+
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            root.join("vault").display()
+        ),
+    );
+    for dir in ["templates", "captures", "macros"] {
+        fs::create_dir_all(root.join("vault").join(dir)).unwrap();
+    }
+    write(
+        root,
+        "vault/Tasks/a.md",
+        "---\ntype: task\ntitle: Ship it\nstatus: done\n---\n",
+    );
+    write(
+        root,
+        "vault/Tasks/b.md",
+        "---\ntype: task\ntitle: Test it\nstatus: todo\n---\n",
+    );
+    // Commands live next to the config file by default
+    write(
+        root,
+        "commands/standup.lua",
+        r#"
+return {
+    name = "standup",
+    description = "Count tasks for the standup",
+    args = {
+        { name = "team", help = "Team name", required = true },
+        { name = "greeting", default = "Morning" },
+    },
+    run = function(ctx)
+        local tasks = mdv.query({ type = "task" })
+        return ctx.args.greeting .. " " .. ctx.args.team .. ": " .. #tasks .. " tasks"
+    end,
+}
+"#,
+    );
+    mdv(root, &["reindex"]).assert().success();
+    tmp
+}
+
+#[test]
+fn runs_a_user_command_with_vault_bindings() {
+    let tmp = setup();
+    mdv(tmp.path(), &["standup", "core"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Morning core: 2 tasks"));
+    mdv(tmp.path(), &["standup", "--greeting=Hi", "--team", "web"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Hi web: 2 tasks"));
+}
+
+#[test]
+fn shows_usage_and_rejects_bad_arguments() {
+    let tmp = setup();
+    mdv(tmp.path(), &["standup", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Usage: mdv standup <team> [greeting]"));
+    mdv(tmp.path(), &["standup"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("missing 'team'"));
+    mdv(tmp.path(), &["nosuch"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown command 'nosuch'"));
+}
+
+#[test]
+fn writes_notes_and_frontmatter_from_lua() {
+    let tmp = setup();
+    let root = tmp.path();
+    write(
+        root,
+        "vault/Projects/mcp.md",
+        "---\ntype: project\ntitle: MCP\ntask_counter: 1\n---\n# MCP\n",
+    );
+    write(
+        root,
+        "commands/bump.lua",
+        r##"
+return {
+    run = function(ctx)
+        local project = mdv.read_note("Projects/mcp")
+        local ok, err = mdv.set_frontmatter(project.path, {
+            task_counter = project.frontmatter.task_counter + 1,
+        })
+        assert(ok, err)
+        ok, err = mdv.write_note("Inbox/idea", {
+            frontmatter = { title = "Idea", tags = { "lua" } },
+            body = "# Idea\n",
+        })
+        assert(ok, err)
+        local _, escaped = mdv.write_note("../outside.md", "# nope")
+        local _, internal = mdv.write_note(".mdvault/x.md", "# nope")
+        return escaped .. "\n" .. internal
+    end,
+}
+"##,
+    );
+
+    mdv(root, &["bump"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("outside the vault").count(2));
+
+    let vault = root.join("vault");
+    let project = fs::read_to_string(vault.join("Projects/mcp.md")).unwrap();
+    assert!(project.contains("task_counter: 2"), "{project}");
+    assert!(project.contains("# MCP"));
+    let idea = fs::read_to_string(vault.join("Inbox/idea.md")).unwrap();
+    assert!(idea.contains("title: Idea") && idea.ends_with("# Idea\n"), "{idea}");
+    assert!(!root.join("outside.md").exists());
+    assert!(!vault.join(".mdvault/x.md").exists());
+
+    // Both notes were reindexed and logged without a separate `mdv reindex`
+    mdv(root, &["list", "--where", "task_counter=2", "-q"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Projects/mcp.md"));
+    let log = fs::read_to_string(vault.join(".mdvault/activity.jsonl")).unwrap();
+    assert!(log.contains("Inbox/idea.md") && log.contains("\"source\":\"lua\""), "{log}");
+}
+
+#[test]
+fn enforces_declared_permissions() {
+    let tmp = setup();
+    let root = tmp.path();
+    write(
+        root,
+        "commands/readonly.lua",
+        r#"
+return {
+    permissions = { write_vault = false },
+    run = function() mdv.write_note("out.md", "nope") end,
+}
+"#,
+    );
+    let url = serve_once("title: Ship it\n");
+    write(
+        root,
+        "commands/fetch.lua",
+        &format!(
+            r#"
+return {{
+    permissions = {{ net = true, shell = true }},
+    run = function(ctx)
+        local res = assert(mdv.fetch("{url}"))
+        local _, err = mdv.fetch("file://" .. ctx.vault_root .. "/Tasks/a.md")
+        local shell = os and "shell" or "no shell"
+        return res.body:match("title: ([^\n]+)") .. ", " .. shell .. ", " .. err
+    end,
+}}
+"#
+        ),
+    );
+
+    mdv(root, &["readonly"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("mdv.write_note is not permitted"));
+    assert!(!root.join("vault/out.md").exists());
+
+    // net and shell also need the security policy or --trust-scripts
+    mdv(root, &["fetch"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("mdv.fetch is not permitted"));
+    mdv(root, &["--trust-scripts", "fetch"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Ship it, shell"))
+        .stdout(predicate::str::contains("only http and https URLs can be fetched"));
+
+    write(
+        root,
+        "commands/bad.lua",
+        "return { permissions = { disk = true }, run = print }",
+    );
+    mdv(root, &["bad"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown permission 'disk'"));
+}
+
+#[test]
+fn logs_and_reads_activity_from_lua() {
+    let tmp = setup();
+    let root = tmp.path();
+    write(
+        root,
+        "commands/workout.lua",
+        r#"
+return {
+    args = { { name = "minutes", required = true } },
+    run = function(ctx)
+        assert(mdv.log_activity("workout", { minutes = tonumber(ctx.args.minutes) }))
+        mdv.log_activity("reviewed", { path = "Tasks/a.md", type = "task" })
+
+        local total = 0
+        for _, entry in ipairs(mdv.activity_since("today - 1d", { event = "workout" })) do
+            total = total + entry.meta.minutes
+        end
+        local reviewed = mdv.activity_since("today - 1d", { op = "custom", type = "task" })
+        return total .. " minutes, reviewed " .. reviewed[1].path
+    end,
+}
+"#,
+    );
+
+    mdv(root, &["workout", "30"]).assert().success();
+    mdv(root, &["workout", "15"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("45 minutes, reviewed Tasks/a.md"));
+
+    let log = fs::read_to_string(root.join("vault/.mdvault/activity.jsonl")).unwrap();
+    assert!(log.contains(r#""op":"custom""#), "{log}");
+    assert!(log.contains(r#""event":"workout""#), "{log}");
+}
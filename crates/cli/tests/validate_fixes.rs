@@ -0,0 +1,128 @@
+//! Integration tests for issues and fixes returned by Lua `validate()` functions.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            root.join("vault").display()
+        ),
+    );
+    for dir in ["templates", "captures", "macros"] {
+        fs::create_dir_all(root.join("vault").join(dir)).unwrap();
+    }
+    // Type definitions live next to the config file by default
+    write(
+        root,
+        "types/recipe.lua",
+        r#"
+return {
+    name = "recipe",
+    schema = { title = { type = "string" } },
+    validate = function(note)
+        local fm = note.frontmatter
+        local issues = {}
+        if not fm.tags then
+            issues[#issues + 1] = { severity = "warning", field = "tags",
+                message = "untagged recipe", fix = { op = "append", value = "recipe" } }
+        end
+        if fm.servings and fm.servings < 1 then
+            issues[#issues + 1] = { severity = "error", field = "servings",
+                message = "must be at least 1", fix = { op = "set", value = 1 } }
+        end
+        if fm.legacy_id then
+            issues[#issues + 1] = { severity = "error", field = "legacy_id",
+                message = "no longer used", fix = { op = "remove" } }
+        end
+        if not fm.source then
+            issues[#issues + 1] = { severity = "error", message = "cite a source" }
+        end
+        return issues
+    end,
+}
+"#,
+    );
+    write(
+        root,
+        "vault/soup.md",
+        "---\ntype: recipe\ntitle: Soup\nservings: 0\nlegacy_id: 7\n---\n# Soup\n",
+    );
+    tmp
+}
+
+#[test]
+fn reports_issue_severities_and_available_fixes() {
+    let tmp = setup();
+    mdv(tmp.path(), &["validate", "soup.md"]).assert().failure().stdout(
+        predicate::str::contains(
+            "- error: invalid value for field 'servings': must be at least 1",
+        )
+        .and(predicate::str::contains("- error: custom validation failed: cite a source"))
+        .and(predicate::str::contains("~ warning: tags: untagged recipe"))
+        .and(predicate::str::contains("? fixable: Set 'servings' to '1'"))
+        .and(predicate::str::contains("1 note(s) can be fixed with --fix")),
+    );
+
+    mdv(tmp.path(), &["validate", "soup.md", "--json"]).assert().failure().stdout(
+        predicate::str::contains("\"warnings\": 1")
+            .and(predicate::str::contains("\"fixes_available\""))
+            .and(predicate::str::contains("Removed 'legacy_id'")),
+    );
+}
+
+#[test]
+fn applies_fixes_and_reports_what_is_left() {
+    let tmp = setup();
+    let root = tmp.path();
+    mdv(root, &["validate", "soup.md", "--fix"]).assert().failure().stdout(
+        predicate::str::contains("+ fixed: Appended 'recipe' to 'tags'")
+            .and(predicate::str::contains("+ fixed: Removed 'legacy_id'"))
+            .and(predicate::str::contains(
+                "- error: custom validation failed: cite a source",
+            ))
+            .and(predicate::str::contains("servings").count(1)),
+    );
+
+    let content = fs::read_to_string(root.join("vault/soup.md")).unwrap();
+    assert!(content.contains("servings: 1"), "{content}");
+    assert!(content.contains("- recipe"), "{content}");
+    assert!(!content.contains("legacy_id"), "{content}");
+
+    // Once the error is gone the note passes
+    fs::write(
+        root.join("vault/soup.md"),
+        content.replace("title: Soup", "title: Soup\nsource: Gran"),
+    )
+    .unwrap();
+    mdv(root, &["validate", "soup.md"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("All 1 notes validated successfully."));
+}
@@ -0,0 +1,106 @@
+//! Integration tests for `--vault` and `--all-vaults` on `mdv list` and `mdv search`.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+fn json(root: &Path, args: &[&str]) -> Value {
+    let output = mdv(root, args).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+/// Two vaults, `personal` (the default profile) and `work`, both indexed.
+fn setup() -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    let mut config = "version = 1\nprofile = \"personal\"\n".to_string();
+    for name in ["personal", "work"] {
+        config.push_str(&format!(
+            "\n[profiles.{name}]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n",
+            root.join(name).display()
+        ));
+    }
+    write(root, "config.toml", &config);
+    write(root, "personal/garden.md", "# Garden\n\nPlan the offsite picnic.\n");
+    write(root, "work/offsite.md", "# Offsite\n\nAgenda for the offsite.\n");
+    write(root, "work/budget.md", "# Budget\n");
+    mdv(root, &["reindex"]).assert().success();
+    mdv(root, &["--profile", "work", "reindex"]).assert().success();
+    tmp
+}
+
+#[test]
+fn list_reads_another_vault() {
+    let tmp = setup();
+    let notes = json(tmp.path(), &["list", "--vault", "work", "--json"]);
+    let notes = notes.as_array().unwrap();
+    assert_eq!(notes.len(), 2);
+    assert!(notes.iter().all(|n| n["vault"] == "work"));
+
+    // The active vault is tagged too
+    let notes = json(tmp.path(), &["list", "--json"]);
+    assert_eq!(notes[0]["vault"], "personal");
+    assert_eq!(notes[0]["path"], "garden.md");
+
+    mdv(tmp.path(), &["list", "--vault", "work", "-q"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("work:budget.md"));
+}
+
+#[test]
+fn search_all_vaults_merges_results() {
+    let tmp = setup();
+    let results = json(tmp.path(), &["search", "offsite", "--all-vaults", "--json"]);
+    let mut found: Vec<String> = results
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            format!("{}:{}", r["vault"].as_str().unwrap(), r["path"].as_str().unwrap())
+        })
+        .collect();
+    found.sort();
+    assert_eq!(found, ["personal:garden.md", "work:offsite.md"]);
+
+    let stable =
+        json(tmp.path(), &["search", "offsite", "--all-vaults", "--stable-json"]);
+    assert_eq!(stable[0]["vault"], "personal");
+    assert_eq!(stable[1]["vault"], "work");
+
+    // Stable JSON for the active vault alone stays untagged
+    let stable = json(tmp.path(), &["search", "offsite", "--stable-json"]);
+    assert!(stable[0].get("vault").is_none());
+}
+
+#[test]
+fn unknown_vault_is_an_error() {
+    let tmp = setup();
+    mdv(tmp.path(), &["list", "--vault", "home"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("profile 'home' not found"));
+    mdv(tmp.path(), &["list", "--vault", "work", "--all-vaults"]).assert().failure();
+}
@@ -0,0 +1,180 @@
+//! Integration tests for `[webhooks]`, delivered to a local HTTP listener.
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use tempfile::tempdir;
+
+fn write(dir: &Path, rel: &str, content: &str) {
+    let path = dir.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+fn mdv(root: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("mdv"));
+    cmd.arg("--config").arg(root.join("config.toml")).args(args);
+    cmd
+}
+
+/// A received request: path, headers (lowercased names) and body.
+struct Request {
+    path: String,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+/// Serve one request per entry of `statuses`, answering with that status,
+/// and pass each request on. Returns the base URL.
+fn serve(statuses: Vec<u16>) -> (String, Receiver<Request>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for status in statuses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let path = line.split_whitespace().nth(1).unwrap().to_string();
+            let mut headers = Vec::new();
+            loop {
+                line.clear();
+                reader.read_line(&mut line).unwrap();
+                let Some((name, value)) = line.trim_end().split_once(": ") else {
+                    break;
+                };
+                headers.push((name.to_lowercase(), value.to_string()));
+            }
+            let length = headers
+                .iter()
+                .find(|(name, _)| name == "content-length")
+                .map_or(0, |(_, v)| v.parse().unwrap());
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 {status} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            )
+            .unwrap();
+            let body = String::from_utf8(body).unwrap();
+            let _ = tx.send(Request { path, headers, body });
+        }
+    });
+    (url, rx)
+}
+
+fn setup(allow_http: bool, hooks: &str) -> tempfile::TempDir {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path();
+    write(
+        root,
+        "config.toml",
+        &format!(
+            "version = 1\nprofile = \"test\"\n\n[profiles.test]\nvault_root = \"{}\"\n\
+             templates_dir = \"{{{{vault_root}}}}/templates\"\n\
+             captures_dir = \"{{{{vault_root}}}}/captures\"\n\
+             macros_dir = \"{{{{vault_root}}}}/macros\"\n\n\
+             [security]\nallow_http = {allow_http}\n\n\
+             [webhooks]\nretries = 2\nbackoff_ms = 0\n{hooks}",
+            root.join("vault").display()
+        ),
+    );
+    write(
+        &root.join("vault"),
+        "Projects/app/Tasks/APP-001.md",
+        "---\ntype: task\ntitle: Ship \"beta\"\ntask-id: APP-001\nproject: app\n\
+         status: todo\n---\n",
+    );
+    tmp
+}
+
+#[test]
+fn task_done_posts_templated_payload() {
+    let (url, requests) = serve(vec![200, 503, 200]);
+    let hooks = format!(
+        "\n[[webhooks.hook]]\nevent = \"task.completed\"\nurl = \"{url}/done\"\n\
+         payload = '{{\"text\": \"Done: {{{{title}}}} ({{{{vault}}}})\"}}'\n\
+         headers = {{ Authorization = \"Bearer s3cret\" }}\n\n\
+         [[webhooks.hook]]\nevent = \"task.completed\"\nurl = \"{url}/flaky\"\n\n\
+         [[webhooks.hook]]\nevent = \"project.archived\"\nurl = \"{url}/archived\"\n"
+    );
+    let tmp = setup(true, &hooks);
+
+    mdv(tmp.path(), &["task", "done", "Projects/app/Tasks/APP-001.md"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("webhook").not());
+
+    let templated = requests.recv().unwrap();
+    assert_eq!(templated.path, "/done");
+    assert_eq!(templated.body, r#"{"text": "Done: Ship \"beta\" (test)"}"#);
+    assert!(
+        templated
+            .headers
+            .contains(&("authorization".to_string(), "Bearer s3cret".to_string()))
+    );
+    assert!(
+        templated
+            .headers
+            .contains(&("content-type".to_string(), "application/json".to_string()))
+    );
+
+    // The second target fails once and is retried with the default payload
+    let first = requests.recv().unwrap();
+    let retry = requests.recv().unwrap();
+    assert_eq!((first.path.as_str(), retry.path.as_str()), ("/flaky", "/flaky"));
+    let event: serde_json::Value = serde_json::from_str(&retry.body).unwrap();
+    assert_eq!(event["event"], "task.completed");
+    assert_eq!(event["id"], "APP-001");
+    assert_eq!(event["path"], "Projects/app/Tasks/APP-001.md");
+    assert_eq!(event["project"], "app");
+}
+
+#[test]
+fn failed_delivery_warns_without_failing_the_command() {
+    let (url, requests) = serve(vec![500, 500, 500]);
+    let hooks = format!(
+        "\n[[webhooks.hook]]\nevent = \"task.completed\"\nurl = \"{url}/down\"\n"
+    );
+    let tmp = setup(true, &hooks);
+
+    mdv(tmp.path(), &["task", "done", "Projects/app/Tasks/APP-001.md"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(format!(
+            "Warning: webhook task.completed: delivery to {url}/down failed after 3 attempt(s)"
+        )));
+    assert_eq!(requests.iter().count(), 3);
+}
+
+#[test]
+fn webhooks_need_allow_http() {
+    let hooks = "\n[[webhooks.hook]]\nevent = \"task.completed\"\nurl = \"http://127.0.0.1:9/\"\n";
+    let tmp = setup(false, hooks);
+
+    mdv(tmp.path(), &["task", "done", "Projects/app/Tasks/APP-001.md"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("security.allow_http is false"));
+}
+
+#[test]
+fn unknown_event_is_a_config_error() {
+    let hooks =
+        "\n[[webhooks.hook]]\nevent = \"task.done\"\nurl = \"http://127.0.0.1:9/\"\n";
+    let tmp = setup(true, hooks);
+
+    mdv(tmp.path(), &["task", "done", "Projects/app/Tasks/APP-001.md"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("task.done"));
+}
@@ -0,0 +1,74 @@
+//! Time running a type definition's `validate()` over many notes, with a
+//! new engine per note versus the per-thread pool.
+//!
+//! Run with `cargo bench -p mdvault-core --bench typedef_pool`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use mdvault_core::scripting::{LuaEngine, SandboxConfig, pool};
+use mdvault_core::types::TypeDefinition;
+use mlua::{Function, Table};
+
+const NOTES: usize = 5_000;
+
+const TASK_TYPEDEF: &str = r#"
+local statuses = { todo = true, doing = true, done = true }
+return {
+    name = "task",
+    schema = {
+        title = { type = "string", required = true },
+        status = { type = "string", enum = { "todo", "doing", "done" } },
+    },
+    validate = function(note)
+        if not statuses[note.frontmatter.status] then
+            return false, "unknown status"
+        end
+        return #note.frontmatter.title > 0
+    end,
+}
+"#;
+
+fn note(lua: &mlua::Lua, i: usize) -> Table {
+    let frontmatter = lua.create_table().unwrap();
+    frontmatter.set("title", format!("Task {i}")).unwrap();
+    frontmatter.set("status", if i.is_multiple_of(3) { "done" } else { "todo" }).unwrap();
+    let note = lua.create_table().unwrap();
+    note.set("path", format!("Tasks/task-{i}.md")).unwrap();
+    note.set("frontmatter", frontmatter).unwrap();
+    note
+}
+
+fn fresh_engine_per_note(typedef: &TypeDefinition) -> Duration {
+    let start = Instant::now();
+    for i in 0..NOTES {
+        let engine = LuaEngine::new(SandboxConfig::restricted()).unwrap();
+        let lua = engine.lua();
+        let table: Table = lua.load(&typedef.lua_source).eval().unwrap();
+        let validate: Function = table.get("validate").unwrap();
+        black_box(validate.call::<bool>(note(lua, i)).unwrap());
+    }
+    start.elapsed()
+}
+
+fn pooled(typedef: &TypeDefinition) -> Duration {
+    let start = Instant::now();
+    for i in 0..NOTES {
+        let pooled = pool::checkout(typedef).unwrap();
+        let validate: Function = pooled.table().get("validate").unwrap();
+        black_box(validate.call::<bool>(note(pooled.lua(), i)).unwrap());
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let typedef = TypeDefinition {
+        lua_source: TASK_TYPEDEF.to_string(),
+        ..TypeDefinition::empty("task")
+    };
+    let fresh = fresh_engine_per_note(&typedef);
+    let pooled = pooled(&typedef);
+    println!("validate() over {NOTES} notes:");
+    println!("  new engine per note  {fresh:>10.2?}");
+    println!("  pooled engine        {pooled:>10.2?}");
+}
@@ -190,6 +190,30 @@ impl ActivityLogService {
         self.log(entry)
     }
 
+    /// Log a note written by a Lua script (`mdv.write_note`,
+    /// `mdv.set_frontmatter`).
+    pub fn log_script_write(
+        &self,
+        note_type: &str,
+        path: &Path,
+        created: bool,
+    ) -> Result<()> {
+        let op = if created { Operation::New } else { Operation::Update };
+        let rel_path = self.relativize(path);
+        self.log(ActivityEntry::new(op, note_type, rel_path).with_meta("source", "lua"))
+    }
+
+    /// Log a command run by a Lua script (`mdv.shell`); `code` is `None`
+    /// if the command was killed by a signal.
+    pub fn log_shell(&self, command: &str, cwd: &Path, code: Option<i32>) -> Result<()> {
+        let rel_path = self.relativize(cwd);
+        self.log(
+            ActivityEntry::new(Operation::Shell, "shell", rel_path)
+                .with_meta("command", command)
+                .with_meta("code", code),
+        )
+    }
+
     /// Log a "focus" operation.
     pub fn log_focus(
         &self,
@@ -19,6 +19,10 @@ pub enum Operation {
     Rename,
     Delete,
     Focus,
+    /// An entry a script logged with `mdv.log_activity`; `meta.event` names it.
+    Custom,
+    /// A command a script ran with `mdv.shell`.
+    Shell,
 }
 
 impl std::fmt::Display for Operation {
@@ -33,6 +37,8 @@ impl std::fmt::Display for Operation {
             Operation::Rename => write!(f, "rename"),
             Operation::Delete => write!(f, "delete"),
             Operation::Focus => write!(f, "focus"),
+            Operation::Custom => write!(f, "custom"),
+            Operation::Shell => write!(f, "shell"),
         }
     }
 }
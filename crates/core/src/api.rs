@@ -0,0 +1,193 @@
+//! Guards for writes made over HTTP (`mdv serve`).
+//!
+//! `[security.api]` gives each client its own key, limits each capability
+//! with a token bucket per client, and names the audit log that every
+//! write attempt is appended to, accepted or not:
+//!
+//! ```json
+//! {"timestamp": "...", "client": "firefox", "operation": "capture", "capture": "todo",
+//!  "target": "Inbox/todo.md", "accepted": true, "status": 200}
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::config::types::{RateLimit, ResolvedConfig};
+use crate::paths::PathResolver;
+
+/// Token buckets for every client and capability with a limit.
+pub struct RateLimiter {
+    limits: BTreeMap<String, RateLimit>,
+    buckets: Mutex<HashMap<(String, String), Bucket>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    /// Limits keyed by capability; capabilities without one are unlimited.
+    pub fn new(limits: BTreeMap<String, RateLimit>) -> Self {
+        Self { limits, buckets: Mutex::new(HashMap::new()) }
+    }
+
+    /// Take one request from `client`'s bucket for `capability`.
+    ///
+    /// When the bucket is empty, returns how long until it holds a request
+    /// again.
+    pub fn acquire(
+        &self,
+        client: &str,
+        capability: &str,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let Some(limit) = self.limits.get(capability) else {
+            return Ok(());
+        };
+        let interval = limit.refill_interval().as_secs_f64();
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = buckets
+            .entry((client.to_string(), capability.to_string()))
+            .or_insert(Bucket { tokens: f64::from(limit.burst), updated: now });
+
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed / interval).min(f64::from(limit.burst));
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) * interval))
+        }
+    }
+}
+
+/// One write attempt, as recorded in the audit log.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditRecord {
+    pub timestamp: DateTime<Utc>,
+    /// Client id from the API key; `None` when no key matched
+    pub client: Option<String>,
+    /// Capability used (`capture` or `inbox`); `None` for unreadable requests
+    pub operation: Option<String>,
+    /// Capture that was asked for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capture: Option<String>,
+    /// Note written, relative to the vault root
+    pub target: Option<String>,
+    pub accepted: bool,
+    /// HTTP status of the answer
+    pub status: u16,
+    /// Why the attempt was rejected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// The JSONL audit log of API writes.
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    /// The log named by `[security.api] audit_log`, or
+    /// `.mdvault/api-audit.jsonl` in the vault.
+    pub fn from_config(config: &ResolvedConfig) -> Self {
+        let path = match &config.security.api.audit_log {
+            Some(path) => config.vault_root.join(path),
+            None => PathResolver::new(&config.vault_root).api_audit_log(),
+        };
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append `record` as one line.
+    pub fn append(&self, record: &AuditRecord) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        // One write per record, so concurrent appends do not interleave
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        file.write_all(line.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(rate: &str) -> RateLimiter {
+        RateLimiter::new(BTreeMap::from([("capture".to_string(), rate.parse().unwrap())]))
+    }
+
+    #[test]
+    fn buckets_allow_a_burst_then_refill() {
+        let limiter = limiter("2/min");
+        let start = Instant::now();
+        assert!(limiter.acquire("a", "capture", start).is_ok());
+        assert!(limiter.acquire("a", "capture", start).is_ok());
+        let wait = limiter.acquire("a", "capture", start).unwrap_err();
+        assert_eq!(wait.as_secs(), 30);
+
+        // Each client and capability has its own bucket
+        assert!(limiter.acquire("b", "capture", start).is_ok());
+        assert!(limiter.acquire("a", "inbox", start).is_ok());
+
+        let later = start + Duration::from_secs(30);
+        assert!(limiter.acquire("a", "capture", later).is_ok());
+        assert!(limiter.acquire("a", "capture", later).is_err());
+    }
+
+    #[test]
+    fn buckets_hold_at_most_a_burst() {
+        let limiter = limiter("60/min");
+        let start = Instant::now();
+        for _ in 0..60 {
+            assert!(limiter.acquire("a", "capture", start).is_ok());
+        }
+        let later = start + Duration::from_secs(3600);
+        for _ in 0..60 {
+            assert!(limiter.acquire("a", "capture", later).is_ok());
+        }
+        assert!(limiter.acquire("a", "capture", later).is_err());
+    }
+
+    #[test]
+    fn audit_log_appends_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog { path: dir.path().join(".mdvault/api-audit.jsonl") };
+        let record = AuditRecord {
+            timestamp: Utc::now(),
+            client: Some("firefox".into()),
+            operation: Some("inbox".into()),
+            capture: None,
+            target: Some("Inbox/call-ana.md".into()),
+            accepted: true,
+            status: 200,
+            reason: None,
+        };
+        log.append(&record).unwrap();
+        log.append(&AuditRecord { accepted: false, status: 429, ..record }).unwrap();
+
+        let text = fs::read_to_string(log.path()).unwrap();
+        let lines: Vec<serde_json::Value> =
+            text.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["client"], "firefox");
+        assert_eq!(lines[0]["target"], "Inbox/call-ana.md");
+        assert_eq!(lines[1]["accepted"], false);
+        assert!(lines[1].get("capture").is_none());
+    }
+}
@@ -0,0 +1,229 @@
+//! Non-interactive capture execution.
+//!
+//! Renders a capture against its target note and writes the result, without
+//! prompting or running Lua hooks. Used by the TUI and by embedders that
+//! drive the engine through `mdvault-ffi`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use chrono::Local;
+use regex::Regex;
+use thiserror::Error;
+
+use super::CaptureSpec;
+use crate::config::types::ResolvedConfig;
+use crate::frontmatter::modifier::FrontmatterModifyError;
+use crate::frontmatter::{FrontmatterParseError, apply_ops, parse, serialize};
+use crate::markdown_ast::{MarkdownAstError, MarkdownEditor, SectionMatch};
+
+static PLACEHOLDER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{([a-zA-Z0-9_]+)\}\}").unwrap());
+
+#[derive(Debug, Error)]
+pub enum CaptureApplyError {
+    #[error("failed to read {path}: {source}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("failed to write {path}: {source}")]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("failed to parse frontmatter: {0}")]
+    Frontmatter(#[from] FrontmatterParseError),
+
+    #[error("frontmatter error: {0}")]
+    FrontmatterOps(#[from] FrontmatterModifyError),
+
+    #[error("capture has content but no target section specified")]
+    NoSection,
+
+    #[error(transparent)]
+    Section(#[from] MarkdownAstError),
+}
+
+/// What a capture changed.
+#[derive(Debug, Clone)]
+pub struct CaptureOutcome {
+    /// Absolute path of the note that was written.
+    pub target: PathBuf,
+    /// Heading the content went under, and its level.
+    pub section: Option<(String, u8)>,
+    pub frontmatter_updated: bool,
+}
+
+/// Built-in variables available to every capture.
+pub fn capture_context(cfg: &ResolvedConfig) -> HashMap<String, String> {
+    let mut ctx = HashMap::new();
+
+    let now = Local::now();
+    ctx.insert("date".into(), now.format("%Y-%m-%d").to_string());
+    ctx.insert("time".into(), now.format("%H:%M").to_string());
+    ctx.insert("datetime".into(), now.to_rfc3339());
+
+    ctx.insert("vault_root".into(), cfg.vault_root.to_string_lossy().to_string());
+    ctx.insert("templates_dir".into(), cfg.templates_dir.to_string_lossy().to_string());
+    ctx.insert("captures_dir".into(), cfg.captures_dir.to_string_lossy().to_string());
+    ctx.insert("macros_dir".into(), cfg.macros_dir.to_string_lossy().to_string());
+
+    ctx
+}
+
+/// The note `spec` writes to when run with `vars`.
+pub fn capture_target(
+    cfg: &ResolvedConfig,
+    spec: &CaptureSpec,
+    vars: &HashMap<String, String>,
+) -> PathBuf {
+    let mut ctx = capture_context(cfg);
+    ctx.extend(vars.iter().map(|(k, v)| (k.clone(), v.clone())));
+    resolve_target_path(&cfg.vault_root, &render_placeholders(&spec.target.file, &ctx))
+}
+
+/// Run `spec` with `vars` on top of the built-in context and write the target.
+///
+/// Metadata that cannot be resolved (e.g. no location command) is skipped.
+pub fn run_capture(
+    cfg: &ResolvedConfig,
+    spec: &CaptureSpec,
+    vars: &HashMap<String, String>,
+) -> Result<CaptureOutcome, CaptureApplyError> {
+    let mut ctx = capture_context(cfg);
+    ctx.extend(vars.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+    let target = capture_target(cfg, spec, vars);
+    let existing = fs::read_to_string(&target)
+        .map_err(|source| CaptureApplyError::Read { path: target.clone(), source })?;
+
+    let location_command = cfg.capture.location_command.as_deref();
+    let metadata: Vec<(String, String)> = spec
+        .metadata
+        .iter()
+        .filter_map(|m| {
+            m.kind.resolve(location_command).ok().map(|v| (m.field.clone(), v))
+        })
+        .collect();
+
+    let (content, section) = apply_capture(&existing, spec, &ctx, &metadata)?;
+    fs::write(&target, content)
+        .map_err(|source| CaptureApplyError::Write { path: target.clone(), source })?;
+
+    Ok(CaptureOutcome {
+        target,
+        section,
+        frontmatter_updated: spec.frontmatter.is_some() || !metadata.is_empty(),
+    })
+}
+
+/// Apply a capture to the text of its target note: frontmatter operations,
+/// metadata fields, then content insertion. Returns the new text and the
+/// heading the content went under.
+pub fn apply_capture(
+    existing: &str,
+    spec: &CaptureSpec,
+    ctx: &HashMap<String, String>,
+    metadata: &[(String, String)],
+) -> Result<(String, Option<(String, u8)>), CaptureApplyError> {
+    let mut parsed = parse(existing)?;
+    let mut section_info = None;
+
+    if let Some(fm_ops) = &spec.frontmatter {
+        parsed = apply_ops(parsed, fm_ops, ctx)?;
+    }
+
+    // Metadata values are recorded verbatim; they are not templates
+    if !metadata.is_empty() {
+        let fm = parsed.frontmatter.get_or_insert_with(Default::default);
+        for (field, value) in metadata {
+            fm.fields.insert(field.clone(), serde_yaml::Value::String(value.clone()));
+        }
+    }
+
+    // Insert into the body only, so frontmatter formatting is preserved
+    if let Some(content_template) = &spec.content {
+        let section = spec.target.section.as_ref().ok_or(CaptureApplyError::NoSection)?;
+        let rendered = render_placeholders(content_template, ctx);
+        let result = MarkdownEditor::insert_into_section(
+            &parsed.body,
+            &SectionMatch::new(section),
+            &rendered,
+            spec.target.position.clone().into(),
+        )?;
+
+        section_info = Some((result.matched_heading.title, result.matched_heading.level));
+        parsed.body = result.content;
+    }
+
+    Ok((serialize(&parsed), section_info))
+}
+
+/// Replace `{{name}}` placeholders, leaving unknown ones as written.
+fn render_placeholders(template: &str, ctx: &HashMap<String, String>) -> String {
+    PLACEHOLDER_RE
+        .replace_all(template, |caps: &regex::Captures<'_>| {
+            ctx.get(&caps[1]).cloned().unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+}
+
+fn resolve_target_path(vault_root: &Path, target: &str) -> PathBuf {
+    let path = Path::new(target);
+    if path.is_absolute() { path.to_path_buf() } else { vault_root.join(path) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::captures::{CapturePosition, CaptureTarget};
+
+    fn spec(section: Option<&str>) -> CaptureSpec {
+        CaptureSpec {
+            name: "inbox".into(),
+            description: String::new(),
+            vars: Default::default(),
+            target: CaptureTarget {
+                file: "Inbox.md".into(),
+                section: section.map(String::from),
+                position: CapturePosition::End,
+                create_if_missing: false,
+            },
+            content: Some("- {{text}} ({{missing}})".into()),
+            frontmatter: None,
+            metadata: Vec::new(),
+            before_insert_source: None,
+            after_insert_source: None,
+            lua_source: None,
+            has_before_insert: false,
+            has_after_insert: false,
+        }
+    }
+
+    #[test]
+    fn inserts_content_and_metadata() {
+        let ctx = HashMap::from([("text".to_string(), "Buy milk".to_string())]);
+        let metadata = [("device".to_string(), "laptop".to_string())];
+        let (content, section) =
+            apply_capture("# Inbox\n\n## Items\n", &spec(Some("Items")), &ctx, &metadata)
+                .unwrap();
+
+        assert!(content.starts_with("---\ndevice: laptop\n---\n"));
+        assert!(content.contains("- Buy milk ({{missing}})"));
+        assert_eq!(section, Some(("Items".to_string(), 2)));
+    }
+
+    #[test]
+    fn content_needs_a_section() {
+        let err =
+            apply_capture("# Inbox\n", &spec(None), &HashMap::new(), &[]).unwrap_err();
+        assert!(matches!(err, CaptureApplyError::NoSection));
+    }
+}
@@ -9,6 +9,7 @@ use crate::frontmatter::{FrontmatterOp, FrontmatterOpType, FrontmatterOps};
 use crate::scripting::{LuaEngine, ScriptingError};
 use crate::vars::{VarMetadata, VarSpec, VarsMap};
 
+use super::metadata::{MetadataField, MetadataKind};
 use super::types::{CapturePosition, CaptureRepoError, CaptureSpec, CaptureTarget};
 
 /// Load and parse a capture specification from a Lua file.
@@ -69,6 +70,9 @@ fn parse_capture_lua(
     // Extract frontmatter operations (optional)
     let frontmatter = extract_frontmatter(&table, path)?;
 
+    // Extract capture-time metadata (optional)
+    let metadata = extract_metadata(&table, path)?;
+
     // Check for lifecycle hooks
     let has_before_insert: bool = table.get::<mlua::Function>("before_insert").is_ok();
     let has_after_insert: bool = table.get::<mlua::Function>("after_insert").is_ok();
@@ -80,6 +84,7 @@ fn parse_capture_lua(
         target,
         content,
         frontmatter,
+        metadata,
         before_insert_source: None, // Set when needed for execution
         after_insert_source: None,
         lua_source: Some(source.to_string()),
@@ -122,8 +127,15 @@ fn extract_vars(table: &mlua::Table, path: &Path) -> Result<VarsMap, CaptureRepo
                 let default: Option<String> = t.get("default").ok();
                 let required: Option<bool> = t.get("required").ok();
                 let description: Option<String> = t.get("description").ok();
-
-                VarSpec::Full(VarMetadata { prompt, description, required, default })
+                let options: Option<Vec<String>> = t.get("options").ok();
+
+                VarSpec::Full(VarMetadata {
+                    prompt,
+                    description,
+                    required,
+                    default,
+                    options,
+                })
             }
             _ => continue, // Skip invalid values
         };
@@ -259,6 +271,70 @@ fn extract_frontmatter(
     }
 }
 
+/// Extract capture-time metadata from Lua table.
+///
+/// Supports two formats:
+/// - List: `{ "hostname", "os", "location" }` (default field names)
+/// - Map: `{ hostname = "device", location = "place" }` (custom field names)
+fn extract_metadata(
+    table: &mlua::Table,
+    path: &Path,
+) -> Result<Vec<MetadataField>, CaptureRepoError> {
+    let meta_table: mlua::Table = match table.get::<mlua::Value>("metadata") {
+        Ok(mlua::Value::Table(t)) => t,
+        Ok(mlua::Value::Nil) | Err(_) => return Ok(Vec::new()),
+        Ok(_) => {
+            return Err(CaptureRepoError::LuaInvalid {
+                path: path.to_path_buf(),
+                message: "metadata must be a table".to_string(),
+            });
+        }
+    };
+
+    let invalid = |message: String| CaptureRepoError::LuaInvalid {
+        path: path.to_path_buf(),
+        message,
+    };
+    let kind_of = |name: &str| {
+        MetadataKind::from_name(name).ok_or_else(|| {
+            invalid(format!(
+                "Unknown metadata '{}' (expected hostname, os, or location)",
+                name
+            ))
+        })
+    };
+
+    let mut fields = Vec::new();
+    for pair in meta_table.pairs::<mlua::Value, mlua::Value>() {
+        let (key, value) = pair.map_err(|e| CaptureRepoError::LuaParse {
+            path: path.to_path_buf(),
+            source: ScriptingError::Lua(e),
+        })?;
+        let (name, field) = match (key, value) {
+            (mlua::Value::Integer(_), mlua::Value::String(name)) => {
+                (name.to_string_lossy(), None)
+            }
+            (mlua::Value::String(name), mlua::Value::String(field)) => {
+                (name.to_string_lossy(), Some(field.to_string_lossy()))
+            }
+            (mlua::Value::String(name), mlua::Value::Boolean(true)) => {
+                (name.to_string_lossy(), None)
+            }
+            (mlua::Value::String(_), mlua::Value::Boolean(false)) => continue,
+            _ => {
+                return Err(invalid(
+                    "metadata entries must be names or name = \"field\" pairs"
+                        .to_string(),
+                ));
+            }
+        };
+        let kind = kind_of(&name)?;
+        let field = field.unwrap_or_else(|| kind.default_field().to_string());
+        fields.push(MetadataField { kind, field });
+    }
+    Ok(fields)
+}
+
 /// Extract a simple key-value map from Lua table.
 fn extract_simple_map(
     table: &mlua::Table,
@@ -533,6 +609,52 @@ return {
         }
     }
 
+    #[test]
+    fn test_load_capture_with_metadata() {
+        let temp = TempDir::new().unwrap();
+        let list = write_lua_capture(
+            temp.path(),
+            "field",
+            r#"
+return {
+    name = "field",
+    target = { file = "field.md" },
+    metadata = { "hostname", "os", "location" },
+}
+"#,
+        );
+        let spec = load_capture_from_lua(&list).unwrap();
+        let fields: Vec<_> = spec.metadata.iter().map(|m| m.field.as_str()).collect();
+        assert_eq!(fields, vec!["device", "os", "location"]);
+
+        let map = write_lua_capture(
+            temp.path(),
+            "travel",
+            r#"
+return {
+    name = "travel",
+    target = { file = "travel.md" },
+    metadata = { location = "place" },
+}
+"#,
+        );
+        let spec = load_capture_from_lua(&map).unwrap();
+        assert_eq!(
+            spec.metadata,
+            vec![MetadataField { kind: MetadataKind::Location, field: "place".into() }]
+        );
+
+        let bad = write_lua_capture(
+            temp.path(),
+            "bad",
+            r#"return { target = { file = "x.md" }, metadata = { "weather" } }"#,
+        );
+        assert!(matches!(
+            load_capture_from_lua(&bad),
+            Err(CaptureRepoError::LuaInvalid { .. })
+        ));
+    }
+
     #[test]
     fn test_load_capture_with_create_if_missing() {
         let temp = TempDir::new().unwrap();
@@ -0,0 +1,180 @@
+//! Capture-time context recorded as frontmatter.
+//!
+//! A capture spec can ask for the device hostname, the operating system, and a
+//! location string to be written into the target note, so field notes and
+//! travel journals carry that context without typing it. Location comes from
+//! a user-configured command (`[capture] location_command`), since there is no
+//! portable way to ask the system for it.
+
+use std::process::Command;
+
+use thiserror::Error;
+
+/// A piece of context that can be recorded at capture time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataKind {
+    /// The machine's hostname.
+    Hostname,
+    /// The operating system, e.g. `macos` or `linux`.
+    Os,
+    /// Output of the configured location command.
+    Location,
+}
+
+impl MetadataKind {
+    /// Parse the name used in capture specs.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "hostname" => Some(Self::Hostname),
+            "os" => Some(Self::Os),
+            "location" => Some(Self::Location),
+            _ => None,
+        }
+    }
+
+    /// Frontmatter field written when the spec does not name one.
+    pub fn default_field(&self) -> &'static str {
+        match self {
+            Self::Hostname => "device",
+            Self::Os => "os",
+            Self::Location => "location",
+        }
+    }
+
+    /// Look up the current value.
+    ///
+    /// `location_command` is only consulted for [`MetadataKind::Location`].
+    pub fn resolve(
+        &self,
+        location_command: Option<&str>,
+    ) -> Result<String, MetadataError> {
+        match self {
+            Self::Hostname => hostname(),
+            Self::Os => Ok(std::env::consts::OS.to_string()),
+            Self::Location => {
+                let command = location_command.ok_or(MetadataError::NoLocationCommand)?;
+                run_command(command)
+            }
+        }
+    }
+
+    /// The value a dry run shows: like [`resolve`](Self::resolve) but
+    /// without running any command, so the location is a placeholder.
+    pub fn preview(&self) -> String {
+        match self {
+            Self::Hostname => system_hostname().unwrap_or_else(|| "<hostname>".into()),
+            Self::Os => std::env::consts::OS.to_string(),
+            Self::Location => "<location>".into(),
+        }
+    }
+}
+
+/// A metadata entry requested by a capture spec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataField {
+    pub kind: MetadataKind,
+    /// Frontmatter field to write.
+    pub field: String,
+}
+
+#[derive(Debug, Error)]
+pub enum MetadataError {
+    #[error("no location command configured ([capture] location_command)")]
+    NoLocationCommand,
+
+    #[error("failed to run `{command}`: {source}")]
+    Spawn {
+        command: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("`{command}` exited with {status}")]
+    Failed { command: String, status: String },
+
+    #[error("`{command}` printed nothing")]
+    Empty { command: String },
+}
+
+fn hostname() -> Result<String, MetadataError> {
+    match system_hostname() {
+        Some(name) => Ok(name),
+        None => run_command("hostname"),
+    }
+}
+
+/// The hostname from the environment or `/etc/hostname`.
+fn system_hostname() -> Option<String> {
+    for var in ["HOSTNAME", "COMPUTERNAME"] {
+        if let Ok(name) = std::env::var(var)
+            && !name.trim().is_empty()
+        {
+            return Some(name.trim().to_string());
+        }
+    }
+    #[cfg(unix)]
+    if let Ok(name) = std::fs::read_to_string("/etc/hostname")
+        && !name.trim().is_empty()
+    {
+        return Some(name.trim().to_string());
+    }
+    None
+}
+
+/// Run `command` through the shell and return its trimmed stdout.
+fn run_command(command: &str) -> Result<String, MetadataError> {
+    #[cfg(unix)]
+    let output = Command::new("sh").arg("-c").arg(command).output();
+    #[cfg(not(unix))]
+    let output = Command::new("cmd").arg("/C").arg(command).output();
+
+    let output = output.map_err(|source| MetadataError::Spawn {
+        command: command.to_string(),
+        source,
+    })?;
+    if !output.status.success() {
+        return Err(MetadataError::Failed {
+            command: command.to_string(),
+            status: output.status.to_string(),
+        });
+    }
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if value.is_empty() {
+        return Err(MetadataError::Empty { command: command.to_string() });
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn os_and_hostname_resolve() {
+        assert_eq!(MetadataKind::Os.resolve(None).unwrap(), std::env::consts::OS);
+        assert!(!MetadataKind::Hostname.resolve(None).unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn preview_runs_no_commands() {
+        assert_eq!(MetadataKind::Location.preview(), "<location>");
+        assert_eq!(MetadataKind::Os.preview(), std::env::consts::OS);
+        assert!(!MetadataKind::Hostname.preview().is_empty());
+    }
+
+    #[test]
+    fn location_runs_the_configured_command() {
+        let location = MetadataKind::Location;
+        assert_eq!(location.resolve(Some("echo '  Lisbon, PT '")).unwrap(), "Lisbon, PT");
+        assert!(matches!(location.resolve(None), Err(MetadataError::NoLocationCommand)));
+        assert!(matches!(
+            location.resolve(Some("false")),
+            Err(MetadataError::Failed { .. })
+        ));
+        assert!(matches!(
+            location.resolve(Some("true")),
+            Err(MetadataError::Empty { .. })
+        ));
+    }
+}
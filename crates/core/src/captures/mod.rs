@@ -1,14 +1,21 @@
+pub mod apply;
 pub mod discovery;
 pub mod hooks;
 pub mod lua_loader;
+pub mod metadata;
 pub mod repository;
 pub mod types;
 
 // Re-export primary API
+pub use apply::{
+    CaptureApplyError, CaptureOutcome, apply_capture, capture_context, capture_target,
+    run_capture,
+};
 pub use hooks::{
     AfterInsertResult, BeforeInsertResult, run_after_insert_hook, run_before_insert_hook,
 };
 pub use lua_loader::load_capture_from_lua;
+pub use metadata::{MetadataError, MetadataField, MetadataKind};
 pub use repository::CaptureRepository;
 pub use types::{
     CaptureDiscoveryError, CaptureFormat, CaptureInfo, CapturePosition, CaptureRepoError,
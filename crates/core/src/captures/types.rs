@@ -2,6 +2,7 @@ use serde::Deserialize;
 use std::path::PathBuf;
 use thiserror::Error;
 
+use super::metadata::MetadataField;
 use crate::frontmatter::FrontmatterOps;
 use crate::markdown_ast::InsertPosition;
 use crate::vars::VarsMap;
@@ -32,6 +33,10 @@ pub struct CaptureSpec {
     #[serde(default)]
     pub frontmatter: Option<FrontmatterOps>,
 
+    /// Capture-time context (hostname, OS, location) to record in the target's frontmatter
+    #[serde(skip)]
+    pub metadata: Vec<MetadataField>,
+
     /// Lua source code for before_insert hook (receives content, vars, target; returns modified content)
     #[serde(skip)]
     pub before_insert_source: Option<String>,
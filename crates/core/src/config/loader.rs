@@ -1,5 +1,5 @@
 use crate::config::types::{
-    ActivityConfig, ConfigFile, LoggingConfig, Profile, ResolvedConfig, SecurityPolicy,
+    ApiConfig, ConfigFile, LoggingConfig, Profile, ResolvedConfig,
 };
 use shellexpand::full;
 use std::path::{Path, PathBuf};
@@ -25,11 +25,20 @@ pub enum ConfigError {
     #[error("no profiles defined in config")]
     NoProfiles,
 
+    #[error("no profile has its vault at '{0}'")]
+    NoProfileForVault(String),
+
     #[error("version {0} is unsupported (expected 1)")]
     BadVersion(u32),
 
     #[error("home directory not available to expand '~'")]
     NoHome,
+
+    #[error("webhook URL must start with http:// or https://: {0}")]
+    BadWebhookUrl(String),
+
+    #[error("unknown capability in [security.api.limits]: {0} (expected one of: {list})", list = ApiConfig::CAPABILITIES.join(", "))]
+    UnknownCapability(String),
 }
 
 pub struct ConfigLoader;
@@ -39,6 +48,57 @@ impl ConfigLoader {
         config_path: Option<&Path>,
         profile_override: Option<&str>,
     ) -> Result<ResolvedConfig, ConfigError> {
+        let (cf, config_dir) = Self::read(config_path)?;
+
+        let active = profile_override
+            .map(ToOwned::to_owned)
+            .or(cf.profile.clone())
+            .unwrap_or_else(|| "default".to_string());
+
+        let prof = cf
+            .profiles
+            .get(&active)
+            .ok_or_else(|| ConfigError::ProfileNotFound(active.clone()))?;
+
+        let resolved = Self::resolve_profile(&active, prof, &cf, &config_dir)?;
+        Ok(resolved)
+    }
+
+    /// Resolve every profile in the config, sorted by name.
+    ///
+    /// Each profile is one vault; used to query several vaults at once.
+    pub fn load_all(
+        config_path: Option<&Path>,
+    ) -> Result<Vec<ResolvedConfig>, ConfigError> {
+        let (cf, config_dir) = Self::read(config_path)?;
+
+        let mut names: Vec<&String> = cf.profiles.keys().collect();
+        names.sort();
+        names
+            .into_iter()
+            .map(|name| Self::resolve_profile(name, &cf.profiles[name], &cf, &config_dir))
+            .collect()
+    }
+
+    /// Resolve the profile whose vault is at `vault_root`.
+    pub fn load_for_vault(
+        config_path: Option<&Path>,
+        vault_root: &Path,
+    ) -> Result<ResolvedConfig, ConfigError> {
+        let target = expand_path(&vault_root.to_string_lossy())?;
+        let target = target.canonicalize().unwrap_or(target);
+        Self::load_all(config_path)?
+            .into_iter()
+            .find(|rc| {
+                rc.vault_root.canonicalize().unwrap_or(rc.vault_root.clone()) == target
+            })
+            .ok_or_else(|| {
+                ConfigError::NoProfileForVault(vault_root.display().to_string())
+            })
+    }
+
+    /// Read and check the config file, returning it with its directory.
+    fn read(config_path: Option<&Path>) -> Result<(ConfigFile, PathBuf), ConfigError> {
         let path = match config_path {
             Some(p) => p.to_path_buf(),
             None => default_config_path(),
@@ -60,38 +120,27 @@ impl ConfigLoader {
         if cf.profiles.is_empty() {
             return Err(ConfigError::NoProfiles);
         }
-
-        let active = profile_override
-            .map(ToOwned::to_owned)
-            .or(cf.profile.clone())
-            .unwrap_or_else(|| "default".to_string());
-
-        let prof = cf
-            .profiles
-            .get(&active)
-            .ok_or_else(|| ConfigError::ProfileNotFound(active.clone()))?;
+        if let Some(hook) = cf.webhooks.hooks.iter().find(|h| !is_http_url(&h.url)) {
+            return Err(ConfigError::BadWebhookUrl(hook.url.clone()));
+        }
+        let capabilities = ApiConfig::CAPABILITIES;
+        if let Some(name) =
+            cf.security.api.limits.keys().find(|c| !capabilities.contains(&c.as_str()))
+        {
+            return Err(ConfigError::UnknownCapability(name.clone()));
+        }
 
         // Compute config directory for typedefs fallback resolution
         let config_dir =
             path.parent().map(|p| p.to_path_buf()).unwrap_or_else(default_config_dir);
 
-        let resolved = Self::resolve_profile(
-            &active,
-            prof,
-            &cf.security,
-            &cf.logging,
-            &cf.activity,
-            &config_dir,
-        )?;
-        Ok(resolved)
+        Ok((cf, config_dir))
     }
 
     fn resolve_profile(
         active: &str,
         prof: &Profile,
-        sec: &SecurityPolicy,
-        log_cfg: &LoggingConfig,
-        activity_cfg: &ActivityConfig,
+        cf: &ConfigFile,
         config_dir: &Path,
     ) -> Result<ResolvedConfig, ConfigError> {
         let vault_root = expand_path(&prof.vault_root)?;
@@ -116,6 +165,18 @@ impl ConfigLoader {
             }
             None => (default_td_dir, None),
         };
+        let commands_dir = match &prof.commands_dir {
+            Some(dir) => expand_path(&sub(dir))?,
+            None => config_dir.join("commands"),
+        };
+        let hooks_dir = match &prof.hooks_dir {
+            Some(dir) => expand_path(&sub(dir))?,
+            None => config_dir.join("hooks"),
+        };
+        let formatters_dir = match &prof.formatters_dir {
+            Some(dir) => expand_path(&sub(dir))?,
+            None => config_dir.join("formatters"),
+        };
 
         // Resolve excluded folders
         let excluded_folders: Vec<PathBuf> = prof
@@ -128,6 +189,7 @@ impl ConfigLoader {
             .collect();
 
         // Resolve log file path if present
+        let log_cfg = &cf.logging;
         let logging = if let Some(ref file) = log_cfg.file {
             let expanded_file = expand_path(&sub(&file.to_string_lossy()))?;
             LoggingConfig {
@@ -147,10 +209,26 @@ impl ConfigLoader {
             macros_dir,
             typedefs_dir,
             typedefs_fallback_dir,
+            commands_dir,
+            hooks_dir,
+            formatters_dir,
             excluded_folders,
-            security: sec.clone(),
+            security: cf.security.clone(),
             logging,
-            activity: activity_cfg.clone(),
+            activity: cf.activity.clone(),
+            display: cf.display.clone(),
+            ingest: cf.ingest.clone(),
+            capture: cf.capture.clone(),
+            quota: cf.quota.clone(),
+            webhooks: cf.webhooks.clone(),
+            index: cf.index.clone(),
+            plugins: cf.plugins.clone(),
+            searches: cf.searches.clone(),
+            embeddings: cf.embeddings.clone(),
+            staleness: cf.staleness.clone(),
+            orphans: cf.orphans.clone(),
+            tui: cf.tui.clone(),
+            serve: cf.serve.clone(),
         })
     }
 }
@@ -171,6 +249,12 @@ pub fn default_config_path() -> PathBuf {
     home.join(".config").join("mdvault").join("config.toml")
 }
 
+/// Whether `url` uses the http or https scheme.
+pub(crate) fn is_http_url(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
 fn expand_path(input: &str) -> Result<PathBuf, ConfigError> {
     let expanded = full(input).map_err(|_| ConfigError::NoHome)?;
     Ok(PathBuf::from(expanded.to_string()))
@@ -180,6 +264,7 @@ fn expand_path(input: &str) -> Result<PathBuf, ConfigError> {
 mod tests {
     use super::*;
     use std::io::Write;
+    use std::time::Duration;
     use tempfile::NamedTempFile;
 
     #[test]
@@ -224,6 +309,64 @@ allow_http = false
         assert!(matches!(result, Err(ConfigError::ParseError(_, _))));
     }
 
+    #[test]
+    fn test_webhook_urls_must_be_http() {
+        for url in ["file:///etc/passwd", "scp://host/x", "-o/tmp/x"] {
+            let mut file = NamedTempFile::new().unwrap();
+            write!(
+                file,
+                "version = 1\n[profiles.default]\nvault_root = \"/tmp/v\"\n\
+                 templates_dir = \"/tmp/v/t\"\ncaptures_dir = \"/tmp/v/c\"\n\
+                 macros_dir = \"/tmp/v/m\"\n[[webhooks.hook]]\nevent = \"task.completed\"\nurl = \"{url}\"\n"
+            )
+            .unwrap();
+            let result = ConfigLoader::load(Some(file.path()), None);
+            assert!(
+                matches!(&result, Err(ConfigError::BadWebhookUrl(u)) if u == url),
+                "{url}: {result:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_security_api() {
+        let load = |api: &str| {
+            let mut file = NamedTempFile::new().unwrap();
+            write!(
+                file,
+                "version = 1\n[profiles.default]\nvault_root = \"/tmp/v\"\n\
+                 templates_dir = \"/tmp/v/t\"\ncaptures_dir = \"/tmp/v/c\"\n\
+                 macros_dir = \"/tmp/v/m\"\n{api}"
+            )
+            .unwrap();
+            ConfigLoader::load(Some(file.path()), None)
+        };
+
+        let loaded = load(
+            "[security.api.keys]\nfirefox = \"k1\"\n\
+             [security.api.limits]\ncapture = \"30/min\"\n\
+             inbox = { rate = \"10/hour\", burst = 2 }\n",
+        )
+        .unwrap();
+        let api = &loaded.security.api;
+        assert_eq!(api.keys["firefox"], "k1");
+        let capture = api.limits["capture"];
+        assert_eq!((capture.burst, capture.count), (30, 30));
+        assert_eq!(capture.refill_interval(), Duration::from_secs(2));
+        let inbox = api.limits["inbox"];
+        assert_eq!((inbox.burst, inbox.count), (2, 10));
+        assert_eq!(inbox.per, Duration::from_secs(3600));
+
+        assert!(matches!(
+            load("[security.api.limits]\nshell = \"1/min\"\n"),
+            Err(ConfigError::UnknownCapability(c)) if c == "shell"
+        ));
+        for bad in ["\"30\"", "\"0/min\"", "\"3/fortnight\""] {
+            let result = load(&format!("[security.api.limits]\ncapture = {bad}\n"));
+            assert!(matches!(result, Err(ConfigError::ParseError(..))), "{bad}");
+        }
+    }
+
     #[test]
     fn test_profile_override() {
         let mut file = NamedTempFile::new().unwrap();
@@ -252,6 +395,19 @@ allow_http = false
         let loaded = ConfigLoader::load(Some(file.path()), Some("work")).unwrap();
         assert_eq!(loaded.active_profile, "work");
         assert_eq!(loaded.vault_root.to_str().unwrap(), "/tmp/work");
+
+        let all = ConfigLoader::load_all(Some(file.path())).unwrap();
+        let names: Vec<_> = all.iter().map(|c| c.active_profile.as_str()).collect();
+        assert_eq!(names, ["default", "work"]);
+        assert_eq!(all[1].vault_root.to_str().unwrap(), "/tmp/work");
+
+        let by_vault =
+            ConfigLoader::load_for_vault(Some(file.path()), Path::new("/tmp/work/"))
+                .unwrap();
+        assert_eq!(by_vault.active_profile, "work");
+        let missing =
+            ConfigLoader::load_for_vault(Some(file.path()), Path::new("/tmp/x"));
+        assert!(matches!(missing, Err(ConfigError::NoProfileForVault(_))));
     }
 
     #[test]
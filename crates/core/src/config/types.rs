@@ -1,7 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use crate::vault::{ByteSize, QuotaConfig};
 
 #[derive(Debug, Deserialize)]
 pub struct ConfigFile {
@@ -14,6 +18,34 @@ pub struct ConfigFile {
     pub logging: LoggingConfig,
     #[serde(default)]
     pub activity: ActivityConfig,
+    #[serde(default)]
+    pub display: DisplayConfig,
+    #[serde(default)]
+    pub ingest: IngestConfig,
+    #[serde(default)]
+    pub capture: CaptureConfig,
+    #[serde(default)]
+    pub quota: QuotaConfig,
+    #[serde(default)]
+    pub webhooks: WebhooksConfig,
+    #[serde(default)]
+    pub index: IndexConfig,
+    /// Per-plugin settings, `[plugins.<name>]`.
+    #[serde(default)]
+    pub plugins: BTreeMap<String, toml::Table>,
+    /// Saved searches, `[searches.<name>]`.
+    #[serde(default)]
+    pub searches: BTreeMap<String, SavedSearch>,
+    #[serde(default)]
+    pub embeddings: EmbeddingsConfig,
+    #[serde(default)]
+    pub staleness: StalenessConfig,
+    #[serde(default)]
+    pub orphans: OrphansConfig,
+    #[serde(default)]
+    pub tui: TuiConfig,
+    #[serde(default)]
+    pub serve: ServeConfig,
 }
 
 #[derive(Debug, Deserialize)]
@@ -24,6 +56,12 @@ pub struct Profile {
     pub macros_dir: String,
     /// Optional override for typedefs directory (defaults to global ~/.config/mdvault/types/)
     pub typedefs_dir: Option<String>,
+    /// Optional override for user commands directory (defaults to global ~/.config/mdvault/commands/)
+    pub commands_dir: Option<String>,
+    /// Optional override for event hooks directory (defaults to global ~/.config/mdvault/hooks/)
+    pub hooks_dir: Option<String>,
+    /// Optional override for output formatters directory (defaults to global ~/.config/mdvault/formatters/)
+    pub formatters_dir: Option<String>,
     /// Folders to exclude from vault operations (relative to vault_root).
     /// These folders and their contents will be ignored by indexing, validation, etc.
     #[serde(default)]
@@ -36,6 +74,89 @@ pub struct SecurityPolicy {
     pub allow_shell: bool,
     #[serde(default)]
     pub allow_http: bool,
+    #[serde(default)]
+    pub api: ApiConfig,
+}
+
+/// Who may write through `mdv serve` and how often, `[security.api]`.
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct ApiConfig {
+    /// API keys by client id; the id is what the audit log records
+    pub keys: BTreeMap<String, String>,
+    /// Rate limits per capability (see [`ApiConfig::CAPABILITIES`]), e.g.
+    /// `capture = "30/min"`; a capability without one is not limited
+    pub limits: BTreeMap<String, RateLimit>,
+    /// Audit log of every write attempt (default `.mdvault/api-audit.jsonl`;
+    /// relative paths are taken from the vault root)
+    pub audit_log: Option<PathBuf>,
+}
+
+impl ApiConfig {
+    /// What a client can do: run a capture, or file text in the inbox.
+    pub const CAPABILITIES: &[&str] = &["capture", "inbox"];
+}
+
+/// A token bucket: up to `burst` requests at once, refilled at `count`
+/// per `per`. Written as `"30/min"` (a burst of 30) or
+/// `{ rate = "30/min", burst = 5 }`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub burst: u32,
+    pub count: u32,
+    pub per: Duration,
+}
+
+impl RateLimit {
+    /// How long the bucket takes to regain one request.
+    pub fn refill_interval(&self) -> Duration {
+        self.per / self.count
+    }
+}
+
+impl FromStr for RateLimit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid rate '{s}' (e.g. 30/min)");
+        let (count, unit) = s.split_once('/').ok_or_else(invalid)?;
+        let count: u32 = count.trim().parse().map_err(|_| invalid())?;
+        let per = match unit.trim() {
+            "s" | "sec" | "second" => Duration::from_secs(1),
+            "m" | "min" | "minute" => Duration::from_secs(60),
+            "h" | "hour" => Duration::from_secs(60 * 60),
+            "d" | "day" => Duration::from_secs(24 * 60 * 60),
+            other => return Err(format!("unknown rate unit '{other}' in '{s}'")),
+        };
+        if count == 0 {
+            return Err(format!("rate '{s}' allows nothing; remove the limit instead"));
+        }
+        Ok(Self { burst: count, count, per })
+    }
+}
+
+impl<'de> Deserialize<'de> for RateLimit {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Text(String),
+            Table { rate: String, burst: Option<u32> },
+        }
+        let (rate, burst) = match Raw::deserialize(deserializer)? {
+            Raw::Text(rate) => (rate, None),
+            Raw::Table { rate, burst } => (rate, burst),
+        };
+        let mut limit: Self = rate.parse().map_err(serde::de::Error::custom)?;
+        match burst {
+            Some(0) => return Err(serde::de::Error::custom("burst must be at least 1")),
+            Some(burst) => limit.burst = burst,
+            None => {}
+        }
+        Ok(limit)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -90,6 +211,321 @@ fn default_retention_days() -> u32 {
     90
 }
 
+/// Output presentation settings shared by the CLI and TUI.
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct DisplayConfig {
+    /// High-contrast, ASCII-only output with tables read row by row
+    /// (same as `--accessible`)
+    #[serde(default)]
+    pub accessible: bool,
+}
+
+/// Settings for `mdv ingest`.
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct IngestConfig {
+    /// Command that prints a transcript for an audio file, e.g.
+    /// `whisper-cli -nt -f {file}` (used when `--transcriber` is not given)
+    #[serde(default)]
+    pub transcriber: Option<String>,
+    /// Capture to run with a link to each new voice memo or snippet, e.g. `inbox`
+    #[serde(default)]
+    pub capture: Option<String>,
+}
+
+/// Settings for `mdv capture`.
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct CaptureConfig {
+    /// Command that prints the current location, e.g. `CoreLocationCLI -format "%address"`
+    /// (used by captures that request `location` metadata)
+    #[serde(default)]
+    pub location_command: Option<String>,
+}
+
+/// A named search, run with `mdv search --saved <name>`.
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq)]
+pub struct SavedSearch {
+    /// Query in `mdv search` syntax, e.g. `type:task status:todo due<today`
+    pub query: String,
+    /// Shown by `mdv search --list`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Context expansion: direct (default), neighbourhood, temporal,
+    /// cooccurrence or full
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    /// Maximum number of results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
+/// Settings for index maintenance.
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct IndexConfig {
+    /// Vacuum the index after `mdv reindex` once it has grown by this much
+    /// since the last vacuum, e.g. `50MB` (default: never)
+    #[serde(default)]
+    pub auto_vacuum_growth: Option<ByteSize>,
+}
+
+/// Settings for webhooks fired on vault events.
+///
+/// Deliveries need `security.allow_http = true`.
+#[derive(Debug, Deserialize, Clone)]
+pub struct WebhooksConfig {
+    /// Extra attempts after a failed delivery (default: 2)
+    #[serde(default = "default_webhook_retries")]
+    pub retries: u32,
+    /// Delay before the first retry, doubled for each one after (default: 500)
+    #[serde(default = "default_webhook_backoff_ms")]
+    pub backoff_ms: u64,
+    /// Timeout for a single delivery attempt (default: 10)
+    #[serde(default = "default_webhook_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Targets, one `[[webhooks.hook]]` table each
+    #[serde(default, rename = "hook")]
+    pub hooks: Vec<WebhookTarget>,
+}
+
+impl Default for WebhooksConfig {
+    fn default() -> Self {
+        Self {
+            retries: default_webhook_retries(),
+            backoff_ms: default_webhook_backoff_ms(),
+            timeout_secs: default_webhook_timeout_secs(),
+            hooks: Vec::new(),
+        }
+    }
+}
+
+fn default_webhook_retries() -> u32 {
+    2
+}
+
+fn default_webhook_backoff_ms() -> u64 {
+    500
+}
+
+fn default_webhook_timeout_secs() -> u64 {
+    10
+}
+
+/// A URL to POST to when an event happens.
+#[derive(Debug, Deserialize, Clone)]
+pub struct WebhookTarget {
+    pub event: WebhookEvent,
+    pub url: String,
+    /// JSON body with `{{var}}` placeholders; defaults to the event as JSON
+    #[serde(default)]
+    pub payload: Option<String>,
+    /// Extra request headers, e.g. `{ Authorization = "Bearer ..." }`
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+}
+
+/// Settings for semantic search (`mdv embed`, `mdv search --mode semantic`).
+///
+/// Only used by builds with the `embeddings` feature.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct EmbeddingsConfig {
+    /// Where vectors come from (default: ollama)
+    #[serde(default)]
+    pub backend: EmbeddingBackendKind,
+    /// Embedding model served by the backend (default: nomic-embed-text)
+    #[serde(default = "default_embeddings_model")]
+    pub model: String,
+    /// Ollama server URL (default: http://localhost:11434)
+    #[serde(default = "default_embeddings_url")]
+    pub url: String,
+    /// Vector size of the hashing backend (default: 256)
+    #[serde(default = "default_embeddings_dimensions")]
+    pub dimensions: usize,
+    /// Share of a semantic search score from vector similarity; the rest is
+    /// the lexical score (default: 0.6)
+    #[serde(default = "default_semantic_weight")]
+    pub semantic_weight: f64,
+    /// Timeout for a single backend request (default: 60)
+    #[serde(default = "default_embeddings_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for EmbeddingsConfig {
+    fn default() -> Self {
+        Self {
+            backend: EmbeddingBackendKind::default(),
+            model: default_embeddings_model(),
+            url: default_embeddings_url(),
+            dimensions: default_embeddings_dimensions(),
+            semantic_weight: default_semantic_weight(),
+            timeout_secs: default_embeddings_timeout_secs(),
+        }
+    }
+}
+
+/// Embedding backends.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingBackendKind {
+    /// A local Ollama server's `/api/embed`; needs `security.allow_http`
+    #[default]
+    Ollama,
+    /// Built in: hashed word and character features, no model needed
+    Hashing,
+}
+
+fn default_embeddings_model() -> String {
+    "nomic-embed-text".to_string()
+}
+
+fn default_embeddings_url() -> String {
+    "http://localhost:11434".to_string()
+}
+
+fn default_embeddings_dimensions() -> usize {
+    256
+}
+
+fn default_semantic_weight() -> f64 {
+    0.6
+}
+
+fn default_embeddings_timeout_secs() -> u64 {
+    60
+}
+
+/// How `mdv reindex` scores staleness (0 = active, 1 = forgotten).
+///
+/// Each signal is scaled to 0–1 and the score is their weighted sum, capped
+/// at 1. The defaults weigh daily references only.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct StalenessConfig {
+    /// Days after which a signal counts as fully stale (default: 90)
+    pub horizon_days: f64,
+    /// Weight of days since the note was last linked from a daily
+    /// (default: 0.6)
+    pub referenced_weight: f64,
+    /// Weight of how rarely dailies referenced it in the last 30 and 90 days
+    /// (default: 0.24)
+    pub activity_weight: f64,
+    /// Weight of days since the file was modified (default: 0)
+    pub modified_weight: f64,
+    /// Weight of having few recently edited notes linking here (default: 0)
+    pub backlinks_weight: f64,
+    /// A backlink counts half as much once its source note is this many days
+    /// old (default: 30)
+    pub backlink_half_life_days: f64,
+}
+
+impl Default for StalenessConfig {
+    fn default() -> Self {
+        Self {
+            horizon_days: 90.0,
+            referenced_weight: 0.6,
+            activity_weight: 0.24,
+            modified_weight: 0.0,
+            backlinks_weight: 0.0,
+            backlink_half_life_days: 30.0,
+        }
+    }
+}
+
+/// Settings for `mdv serve`.
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct ServeConfig {
+    /// Address to listen on (default `127.0.0.1:7171`)
+    pub bind: Option<String>,
+    /// Bearer token clients must send; the `MDV_SERVE_TOKEN` environment
+    /// variable takes precedence
+    pub token: Option<String>,
+}
+
+/// Notes `mdv orphans` should not report.
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct OrphansConfig {
+    /// Note types that are standalone by design (e.g. `["daily", "moc"]`)
+    pub ignore_types: Vec<String>,
+    /// Gitignore-style patterns for paths that are standalone by design
+    /// (e.g. `["Templates/", "Journal/2019/**"]`)
+    pub ignore_paths: Vec<String>,
+    /// Skip notes created fewer than this many days ago
+    pub min_age_days: Option<u32>,
+}
+
+/// Settings for the terminal UI.
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct TuiConfig {
+    /// Key bindings by action, e.g. `select_next = ["j", "Ctrl-n"]`; an
+    /// action listed here replaces its default keys
+    pub keys: BTreeMap<String, KeyBinding>,
+    /// What the TUI opens on
+    pub start_screen: StartScreenConfig,
+}
+
+/// The TUI start screen: recent notes, today's status, and open work.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct StartScreenConfig {
+    /// Open on the start screen rather than the pane of the last session
+    /// (default: true)
+    pub enabled: bool,
+    /// Recently modified notes to list (default: 10)
+    pub recent: usize,
+    /// In-progress tasks to list (default: 10)
+    pub tasks: usize,
+}
+
+impl Default for StartScreenConfig {
+    fn default() -> Self {
+        Self { enabled: true, recent: 10, tasks: 10 }
+    }
+}
+
+/// One key or several for a TUI action.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum KeyBinding {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyBinding {
+    /// The keys as written, e.g. `["j", "Down"]`.
+    pub fn keys(&self) -> &[String] {
+        match self {
+            KeyBinding::One(key) => std::slice::from_ref(key),
+            KeyBinding::Many(keys) => keys,
+        }
+    }
+}
+
+/// Vault events a webhook can subscribe to.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEvent {
+    /// `mdv task done`
+    #[serde(rename = "task.completed")]
+    TaskCompleted,
+    /// `mdv project archive`
+    #[serde(rename = "project.archived")]
+    ProjectArchived,
+    /// `mdv validate` found notes that fail validation
+    #[serde(rename = "validation.failed")]
+    ValidationFailed,
+}
+
+impl WebhookEvent {
+    /// Event name as written in the config and sent in payloads.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TaskCompleted => "task.completed",
+            Self::ProjectArchived => "project.archived",
+            Self::ValidationFailed => "validation.failed",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ResolvedConfig {
     pub active_profile: String,
@@ -101,11 +537,32 @@ pub struct ResolvedConfig {
     pub typedefs_dir: PathBuf,
     /// Fallback directory for type definitions (the global default when typedefs_dir is overridden).
     pub typedefs_fallback_dir: Option<PathBuf>,
+    /// Directory for user-defined Lua commands, run as `mdv <name>`.
+    pub commands_dir: PathBuf,
+    /// Directory for Lua event hooks such as `on_rename`.
+    pub hooks_dir: PathBuf,
+    /// Directory for Lua output formatters, used as `--output lua:<name>`.
+    pub formatters_dir: PathBuf,
     /// Folders to exclude from vault operations (resolved to absolute paths).
     pub excluded_folders: Vec<PathBuf>,
     pub security: SecurityPolicy,
     pub logging: LoggingConfig,
     pub activity: ActivityConfig,
+    pub display: DisplayConfig,
+    pub ingest: IngestConfig,
+    pub capture: CaptureConfig,
+    pub quota: QuotaConfig,
+    pub webhooks: WebhooksConfig,
+    pub index: IndexConfig,
+    /// Per-plugin settings, keyed by plugin name.
+    pub plugins: BTreeMap<String, toml::Table>,
+    /// Saved searches from config, keyed by name.
+    pub searches: BTreeMap<String, SavedSearch>,
+    pub embeddings: EmbeddingsConfig,
+    pub staleness: StalenessConfig,
+    pub orphans: OrphansConfig,
+    pub tui: TuiConfig,
+    pub serve: ServeConfig,
 }
 
 impl ResolvedConfig {
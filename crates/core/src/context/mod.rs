@@ -10,7 +10,7 @@ mod query;
 mod query_types;
 mod types;
 
-pub use manager::ContextManager;
+pub use manager::{ContextError as ContextStateError, ContextManager};
 pub use query::ContextQueryService;
 pub use query_types::{
     ActivityItem, ContextError, DailyNoteInfo, DayContext, DaySummary,
@@ -10,7 +10,7 @@ use crate::activity::{ActivityEntry, ActivityLogService, Operation};
 use crate::config::types::ResolvedConfig;
 use crate::context::ContextManager;
 use crate::frontmatter::parse as parse_frontmatter;
-use crate::index::IndexDb;
+use crate::index::{IndexDb, IndexError};
 use crate::markdown_ast::MarkdownEditor;
 
 use crate::paths::PathResolver;
@@ -200,7 +200,7 @@ impl ContextQueryService {
 
         // Convert project map to vec
         context.projects = project_map.into_values().collect();
-        context.projects.sort_by(|a, b| b.tasks_done.cmp(&a.tasks_done));
+        context.projects.sort_by_key(|p| std::cmp::Reverse(p.tasks_done));
 
         Ok(context)
     }
@@ -474,7 +474,7 @@ impl ContextQueryService {
         }
 
         let mut result: Vec<ProjectActivity> = project_map.into_values().collect();
-        result.sort_by(|a, b| b.tasks_done.cmp(&a.tasks_done));
+        result.sort_by_key(|p| std::cmp::Reverse(p.tasks_done));
         result
     }
 
@@ -501,22 +501,17 @@ impl ContextQueryService {
         activity_days: u32,
     ) -> Result<NoteContext, ContextError> {
         let Some(ref db) = self.index_db else {
-            return Err(ContextError::IndexError("Index database not available".into()));
+            return Err(ContextError::IndexUnavailable);
         };
 
         // Get the note from the index
         let note = db
-            .get_note_by_path(note_path)
-            .map_err(|e| ContextError::IndexError(e.to_string()))?
-            .ok_or_else(|| {
-                ContextError::IndexError(format!(
-                    "Note not found: {}",
-                    note_path.display()
-                ))
-            })?;
-
-        let note_id =
-            note.id.ok_or_else(|| ContextError::IndexError("Note has no ID".into()))?;
+            .get_note_by_path(note_path)?
+            .ok_or_else(|| ContextError::NoteNotFound(note_path.to_path_buf()))?;
+
+        let note_id = note.id.ok_or_else(|| {
+            IndexError::InvalidData(format!("note has no ID: {}", note_path.display()))
+        })?;
 
         // Parse frontmatter
         let metadata: serde_json::Value = note
@@ -561,11 +556,9 @@ impl ContextQueryService {
 
     /// Get context for the focused project.
     pub fn focus_context(&self) -> Result<FocusContextOutput, ContextError> {
-        let mgr = ContextManager::load(&self.vault_root)
-            .map_err(|e| ContextError::IoError(std::io::Error::other(e.to_string())))?;
+        let mgr = ContextManager::load(&self.vault_root)?;
 
-        let focus =
-            mgr.focus().ok_or_else(|| ContextError::IndexError("No focus set".into()))?;
+        let focus = mgr.focus().ok_or(ContextError::NoFocus)?;
 
         let project = focus.project.clone();
         let note = focus.note.clone();
@@ -749,10 +742,8 @@ impl ContextQueryService {
             };
 
             match status {
-                "done" | "completed" => {
-                    if completed.len() < 5 {
-                        completed.push(task_info);
-                    }
+                "done" | "completed" if completed.len() < 5 => {
+                    completed.push(task_info);
                 }
                 "doing" | "in-progress" | "in_progress" => {
                     active.push(task_info);
@@ -1080,10 +1071,26 @@ mod tests {
             macros_dir: vault_root.join(".mdvault/macros"),
             typedefs_dir: vault_root.join(".mdvault/typedefs"),
             typedefs_fallback_dir: None,
+            commands_dir: PathBuf::new(),
+            hooks_dir: PathBuf::new(),
+            formatters_dir: PathBuf::new(),
             excluded_folders: vec![],
             security: Default::default(),
             logging: Default::default(),
             activity: Default::default(),
+            display: Default::default(),
+            ingest: Default::default(),
+            capture: Default::default(),
+            quota: Default::default(),
+            webhooks: Default::default(),
+            index: Default::default(),
+            plugins: Default::default(),
+            searches: Default::default(),
+            embeddings: Default::default(),
+            staleness: Default::default(),
+            orphans: Default::default(),
+            tui: Default::default(),
+            serve: Default::default(),
         }
     }
 }
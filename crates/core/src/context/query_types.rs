@@ -5,14 +5,28 @@ use std::path::PathBuf;
 use serde::Serialize;
 use thiserror::Error;
 
+use crate::index::IndexError;
+
 /// Error type for context queries.
 #[derive(Debug, Error)]
 pub enum ContextError {
     #[error("Failed to read activity log: {0}")]
     ActivityError(String),
 
-    #[error("Failed to query index: {0}")]
-    IndexError(String),
+    #[error("Failed to query index")]
+    Index(#[from] IndexError),
+
+    #[error("Index database not available")]
+    IndexUnavailable,
+
+    #[error("Note not found: {0}")]
+    NoteNotFound(PathBuf),
+
+    #[error("No focus set")]
+    NoFocus,
+
+    #[error("Failed to load context state: {0}")]
+    State(#[from] super::manager::ContextError),
 
     #[error("Invalid date: {0}")]
     InvalidDate(String),
@@ -1149,8 +1163,13 @@ mod tests {
         let e = ContextError::ActivityError("test".into());
         assert_eq!(e.to_string(), "Failed to read activity log: test");
 
-        let e = ContextError::IndexError("db gone".into());
-        assert_eq!(e.to_string(), "Failed to query index: db gone");
+        let e = ContextError::Index(IndexError::InvalidData("db gone".into()));
+        assert_eq!(e.to_string(), "Failed to query index");
+        let source = std::error::Error::source(&e).unwrap();
+        assert_eq!(source.to_string(), "Invalid data: db gone");
+
+        let e = ContextError::NoteNotFound(PathBuf::from("a.md"));
+        assert_eq!(e.to_string(), "Note not found: a.md");
 
         let e = ContextError::InvalidDate("bad".into());
         assert_eq!(e.to_string(), "Invalid date: bad");
@@ -0,0 +1,530 @@
+//! Synthetic demo vault generation.
+//!
+//! Builds a realistic-looking vault (projects, tasks with status histories,
+//! daily notes, and linked zettels) from a seed. The same seed and end date
+//! always produce the same files, so a demo vault can stand in for a real one
+//! when trying features, reproducing bugs, or benchmarking the indexer.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use thiserror::Error;
+
+use crate::paths::PathResolver;
+
+#[derive(Debug, Error)]
+pub enum DemoError {
+    #[error("demo target is not empty: {0}")]
+    NotEmpty(PathBuf),
+
+    #[error("failed to write {path}: {source}")]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// How much content to generate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DemoSize {
+    /// A handful of projects over two weeks; quick to browse.
+    #[default]
+    Small,
+    /// A year of history with ~1000 tasks; useful for performance work.
+    Large,
+}
+
+impl DemoSize {
+    fn projects(self) -> usize {
+        match self {
+            Self::Small => 3,
+            Self::Large => 25,
+        }
+    }
+
+    fn tasks_per_project(self) -> usize {
+        match self {
+            Self::Small => 6,
+            Self::Large => 40,
+        }
+    }
+
+    fn days(self) -> i64 {
+        match self {
+            Self::Small => 14,
+            Self::Large => 365,
+        }
+    }
+
+    fn zettels(self) -> usize {
+        match self {
+            Self::Small => 10,
+            Self::Large => 400,
+        }
+    }
+}
+
+/// Options for [`generate`].
+#[derive(Debug, Clone)]
+pub struct DemoOptions {
+    pub size: DemoSize,
+    /// Seed for all random choices.
+    pub seed: u64,
+    /// Last day of simulated history (usually today).
+    pub end_date: NaiveDate,
+}
+
+/// Counts of what was written.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DemoStats {
+    pub projects: usize,
+    pub tasks: usize,
+    pub dailies: usize,
+    pub zettels: usize,
+}
+
+impl DemoStats {
+    pub fn total_notes(&self) -> usize {
+        self.projects + self.tasks + self.dailies + self.zettels
+    }
+}
+
+const PROJECT_NAMES: &[&str] = &[
+    "Website Relaunch",
+    "Home Renovation",
+    "Thesis Draft",
+    "Mobile App",
+    "Garden Plan",
+    "Conference Talk",
+    "Data Pipeline",
+    "Book Club",
+    "Marathon Training",
+    "Tax Return",
+    "Podcast Launch",
+    "Kitchen Remodel",
+];
+
+const VERBS: &[&str] = &[
+    "Draft",
+    "Review",
+    "Fix",
+    "Plan",
+    "Research",
+    "Write",
+    "Test",
+    "Refactor",
+    "Design",
+    "Schedule",
+    "Document",
+    "Prototype",
+];
+
+const OBJECTS: &[&str] = &[
+    "landing page",
+    "budget",
+    "outline",
+    "login flow",
+    "supplier quotes",
+    "slides",
+    "error handling",
+    "interview notes",
+    "release checklist",
+    "onboarding guide",
+    "cost estimate",
+    "test plan",
+    "feedback survey",
+    "migration script",
+];
+
+const TOPICS: &[&str] = &[
+    "spaced repetition",
+    "deep work",
+    "event sourcing",
+    "composting",
+    "habit loops",
+    "type inference",
+    "interval training",
+    "second-order effects",
+    "technical debt",
+    "active recall",
+    "incremental reading",
+    "feedback loops",
+];
+
+const STATUSES: &[(&str, u64)] =
+    &[("done", 45), ("in-progress", 20), ("todo", 25), ("blocked", 5), ("cancelled", 5)];
+
+/// Small deterministic PRNG (SplitMix64); no need for a `rand` dependency.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform integer in `0..n`.
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n.max(1)
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len() as u64) as usize]
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.below(100) < percent
+    }
+
+    fn weighted<'a>(&mut self, items: &[(&'a str, u64)]) -> &'a str {
+        let total: u64 = items.iter().map(|(_, w)| w).sum();
+        let mut roll = self.below(total);
+        for (item, weight) in items {
+            if roll < *weight {
+                return item;
+            }
+            roll -= weight;
+        }
+        items[items.len() - 1].0
+    }
+
+    /// `HH:MM` within `from_hour..to_hour`, so same-day events stay in order.
+    fn time(&mut self, from_hour: u64, to_hour: u64) -> String {
+        format!(
+            "{:02}:{:02}",
+            from_hour + self.below(to_hour - from_hour),
+            self.below(12) * 5
+        )
+    }
+}
+
+/// Something that happened on a given day, logged in that day's daily note.
+struct DayEvent {
+    time: String,
+    text: String,
+}
+
+/// Generate a demo vault into `root`, which must be missing or empty.
+pub fn generate(root: &Path, options: &DemoOptions) -> Result<DemoStats, DemoError> {
+    if root.read_dir().is_ok_and(|mut entries| entries.next().is_some()) {
+        return Err(DemoError::NotEmpty(root.to_path_buf()));
+    }
+
+    let mut rng = Rng(options.seed);
+    let size = options.size;
+    let paths = PathResolver::new(root);
+    let start = options.end_date - Duration::days(size.days() - 1);
+    let day = |offset: u64| start + Duration::days(offset as i64);
+    // Links use vault-relative paths, which resolve without basename lookup
+    let target = |path: PathBuf| {
+        path.strip_prefix(root).unwrap_or(&path).with_extension("").display().to_string()
+    };
+    let daily = |date: NaiveDate| target(paths.daily_note(&date.to_string()));
+    let mut stats = DemoStats::default();
+    let mut events: BTreeMap<NaiveDate, Vec<DayEvent>> = BTreeMap::new();
+
+    // Zettels first, so tasks can link to them
+    let zettels: Vec<(String, String)> = (0..size.zettels())
+        .map(|i| {
+            let topic = TOPICS[i % TOPICS.len()];
+            let title = if i < TOPICS.len() {
+                capitalize(topic)
+            } else {
+                format!("{} ({})", capitalize(topic), i / TOPICS.len() + 1)
+            };
+            (slug(&title), title)
+        })
+        .collect();
+    let zettel_links: Vec<String> =
+        zettels.iter().map(|(name, _)| target(paths.zettel(name))).collect();
+
+    for (i, (slug_name, title)) in zettels.iter().enumerate() {
+        let mut body = format!(
+            "---\ntype: zettel\ntitle: {title}\ntags: [{}]\n---\n# {title}\n\n",
+            slug(TOPICS[i % TOPICS.len()])
+        );
+        writeln!(body, "## Summary\n\nNotes on {}.\n", title.to_lowercase()).unwrap();
+        writeln!(body, "## Connections\n").unwrap();
+        // Leave some zettels without connections, like a real slip box
+        if i % 7 != 6 {
+            for _ in 0..1 + rng.below(3) {
+                let j = rng.below(zettels.len() as u64) as usize;
+                if j != i {
+                    let anchor = if rng.chance(30) { "#Summary" } else { "" };
+                    writeln!(body, "- [[{}{anchor}|{}]]", zettel_links[j], zettels[j].1)
+                        .unwrap();
+                }
+            }
+        }
+        write_note(&paths.zettel(slug_name), &body)?;
+        stats.zettels += 1;
+    }
+
+    for p in 0..size.projects() {
+        let name = match PROJECT_NAMES.get(p) {
+            Some(name) => name.to_string(),
+            None => format!("{} {}", PROJECT_NAMES[p % PROJECT_NAMES.len()], p),
+        };
+        let id = project_id(p);
+        let project_link = target(paths.project_note(&id));
+        let created = day(rng.below(size.days() as u64 / 4 + 1));
+        let mut task_lines = String::new();
+        let mut project_log = String::new();
+        let mut open_tasks = 0;
+
+        for t in 0..size.tasks_per_project() {
+            let task_id = format!("{id}-{:03}", t + 1);
+            let title = format!("{} {}", rng.pick(VERBS), rng.pick(OBJECTS));
+            let status = rng.weighted(STATUSES);
+            let offset = (created - start).num_days() as u64;
+            let created_at = day(offset + rng.below(size.days() as u64 - offset));
+            let remaining = (options.end_date - created_at).num_days() as u64 + 1;
+            let mut history = vec![(created_at, rng.time(8, 11), "Created")];
+            let mut extra = String::new();
+
+            if status != "todo" {
+                let started = created_at + Duration::days(rng.below(remaining) as i64);
+                history.push((started, rng.time(11, 14), "Started"));
+                let left = (options.end_date - started).num_days() as u64 + 1;
+                let finished = started + Duration::days(rng.below(left) as i64);
+                match status {
+                    "done" => {
+                        history.push((finished, rng.time(14, 18), "Completed"));
+                        writeln!(extra, "completed_at: {}T17:00:00", finished).unwrap();
+                    }
+                    "cancelled" => {
+                        history.push((finished, rng.time(14, 18), "Cancelled"));
+                        writeln!(extra, "cancelled_at: {}T17:00:00", finished).unwrap();
+                    }
+                    "blocked" => {
+                        history.push((finished, rng.time(14, 18), "Blocked"));
+                    }
+                    _ => {}
+                }
+            }
+            if !matches!(status, "done" | "cancelled") {
+                open_tasks += 1;
+            }
+
+            let z = rng.below(zettels.len() as u64) as usize;
+            let task_link = target(paths.project_task(&id, &task_id));
+            let mut body = format!(
+                "---\ntype: task\ntitle: {title}\ntask-id: {task_id}\nproject: {id}\n\
+                 status: {status}\ncreated: {created_at}\n{extra}---\n# {title}\n\n\
+                 Part of [[{project_link}|{name}]]. Background: [[{}|{}]].\n\n## Logs\n",
+                zettel_links[z], zettels[z].1
+            );
+            for (date, time, action) in history {
+                writeln!(body, "- [[{}]] - {time}: {action}", daily(date)).unwrap();
+                if action != "Started" {
+                    writeln!(
+                        project_log,
+                        "- [[{}]] - {time}: {action} task [[{task_link}|{task_id}]]",
+                        daily(date)
+                    )
+                    .unwrap();
+                }
+                events.entry(date).or_default().push(DayEvent {
+                    time,
+                    text: format!("{action} task {task_id}: [[{task_link}|{title}]]"),
+                });
+            }
+            write_note(&paths.project_task(&id, &task_id), &body)?;
+            writeln!(task_lines, "- [[{task_link}|{title}]]").unwrap();
+            stats.tasks += 1;
+        }
+
+        let status = if open_tasks == 0 { "done" } else { "open" };
+        let mut body = format!(
+            "---\ntype: project\ntitle: {name}\nproject-id: {id}\nkind: project\n\
+             status: {status}\ncreated: {created}\ntask_counter: {}\n---\n# {name}\n\n\
+             ## Tasks\n\n{task_lines}\n## Milestones\n\n",
+            size.tasks_per_project()
+        );
+        let total = size.tasks_per_project();
+        let milestones_done = 3 * (total - open_tasks) / total;
+        for m in 1..=3 {
+            let mark = if m <= milestones_done { 'x' } else { ' ' };
+            writeln!(body, "- [{mark}] Milestone {m}").unwrap();
+        }
+        writeln!(body, "\n## Logs\n\n- [[{}]] - 08:00: Created project", daily(created))
+            .unwrap();
+        body.push_str(&project_log);
+        write_note(&paths.project_note(&id), &body)?;
+        events.entry(created).or_default().push(DayEvent {
+            time: "08:00".to_string(),
+            text: format!("Created project {id}: [[{project_link}|{name}]]"),
+        });
+        stats.projects += 1;
+    }
+
+    // One daily per weekday, plus any weekend day that has activity
+    for offset in 0..size.days() as u64 {
+        let date = day(offset);
+        let mut day_events = events.remove(&date).unwrap_or_default();
+        let weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        if weekend && day_events.is_empty() {
+            continue;
+        }
+        day_events.sort_by(|a, b| a.time.cmp(&b.time));
+
+        let mut body =
+            format!("---\ntype: daily\ndate: {date}\n---\n# {date}\n\n## Logs\n");
+        for event in &day_events {
+            writeln!(body, "- **{}**: {}", event.time, event.text).unwrap();
+        }
+        let todos = rng.below(4);
+        if todos > 0 {
+            writeln!(body, "\n## Todo\n").unwrap();
+        }
+        for _ in 0..todos {
+            let mark = if date < options.end_date && rng.chance(70) { 'x' } else { ' ' };
+            writeln!(body, "- [{mark}] {} {}", rng.pick(VERBS), rng.pick(OBJECTS))
+                .unwrap();
+        }
+        if rng.chance(40) {
+            let z = rng.below(zettels.len() as u64) as usize;
+            writeln!(
+                body,
+                "\n## Notes\n\nRead more about [[{}|{}]].",
+                zettel_links[z], zettels[z].1
+            )
+            .unwrap();
+        }
+        write_note(&paths.daily_note(&date.format("%Y-%m-%d").to_string()), &body)?;
+        stats.dailies += 1;
+    }
+
+    Ok(stats)
+}
+
+/// Three-character project ID: `WEB`, `HOM`, ... then `WE1`, `HO1` once the
+/// name list wraps around.
+fn project_id(index: usize) -> String {
+    let name = PROJECT_NAMES[index % PROJECT_NAMES.len()].to_uppercase();
+    let round = index / PROJECT_NAMES.len();
+    if round == 0 { name[..3].to_string() } else { format!("{}{}", &name[..2], round) }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn slug(s: &str) -> String {
+    s.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn write_note(path: &Path, content: &str) -> Result<(), DemoError> {
+    let write = || {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)
+    };
+    write().map_err(|source| DemoError::Write { path: path.to_path_buf(), source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn options(size: DemoSize, seed: u64) -> DemoOptions {
+        DemoOptions {
+            size,
+            seed,
+            end_date: NaiveDate::from_ymd_opt(2025, 3, 14).unwrap(),
+        }
+    }
+
+    fn read_all(root: &Path) -> Vec<(PathBuf, String)> {
+        let mut files: Vec<_> = walkdir::WalkDir::new(root)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| {
+                let rel = e.path().strip_prefix(root).unwrap().to_path_buf();
+                (rel, fs::read_to_string(e.path()).unwrap())
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_small_vault_counts() {
+        let dir = TempDir::new().unwrap();
+        let stats = generate(dir.path(), &options(DemoSize::Small, 1)).unwrap();
+
+        assert_eq!(stats.projects, 3);
+        assert_eq!(stats.tasks, 18);
+        assert_eq!(stats.zettels, 10);
+        assert!(stats.dailies >= 10 && stats.dailies <= 14);
+        assert_eq!(read_all(dir.path()).len(), stats.total_notes());
+    }
+
+    #[test]
+    fn test_same_seed_same_vault() {
+        let a = TempDir::new().unwrap();
+        let b = TempDir::new().unwrap();
+        let c = TempDir::new().unwrap();
+        generate(a.path(), &options(DemoSize::Small, 7)).unwrap();
+        generate(b.path(), &options(DemoSize::Small, 7)).unwrap();
+        generate(c.path(), &options(DemoSize::Small, 8)).unwrap();
+
+        assert_eq!(read_all(a.path()), read_all(b.path()));
+        assert_ne!(read_all(a.path()), read_all(c.path()));
+    }
+
+    #[test]
+    fn test_tasks_have_history() {
+        let dir = TempDir::new().unwrap();
+        generate(dir.path(), &options(DemoSize::Small, 3)).unwrap();
+
+        for (path, content) in read_all(dir.path()) {
+            if !path.to_string_lossy().contains("/Tasks/") {
+                continue;
+            }
+            assert!(content.starts_with("---\ntype: task\n"), "{}", path.display());
+            assert!(content.contains(": Created"), "{}", path.display());
+            if content.contains("status: done") {
+                assert!(content.contains("completed_at:"));
+                assert!(content.contains(": Completed"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_refuses_non_empty_target() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("existing.md"), "keep me").unwrap();
+
+        let err = generate(dir.path(), &options(DemoSize::Small, 1)).unwrap_err();
+        assert!(matches!(err, DemoError::NotEmpty(_)));
+    }
+
+    #[test]
+    fn test_project_ids_are_unique() {
+        let ids: std::collections::HashSet<_> = (0..100).map(project_id).collect();
+        assert_eq!(ids.len(), 100);
+        assert!(ids.iter().all(|id| id.len() >= 3));
+    }
+}
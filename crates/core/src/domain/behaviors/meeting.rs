@@ -201,10 +201,26 @@ mod tests {
             macros_dir: vault_root.join(".mdvault/macros"),
             typedefs_dir: vault_root.join(".mdvault/typedefs"),
             typedefs_fallback_dir: None,
+            commands_dir: PathBuf::new(),
+            hooks_dir: PathBuf::new(),
+            formatters_dir: PathBuf::new(),
             excluded_folders: vec![],
             security: Default::default(),
             logging: Default::default(),
             activity: Default::default(),
+            display: Default::default(),
+            ingest: Default::default(),
+            capture: Default::default(),
+            quota: Default::default(),
+            webhooks: Default::default(),
+            index: Default::default(),
+            plugins: Default::default(),
+            searches: Default::default(),
+            embeddings: Default::default(),
+            staleness: Default::default(),
+            orphans: Default::default(),
+            tui: Default::default(),
+            serve: Default::default(),
         }
     }
 
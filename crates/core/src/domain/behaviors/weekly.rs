@@ -174,10 +174,26 @@ mod tests {
             macros_dir: PathBuf::from("/tmp/test-vault/.mdvault/macros"),
             typedefs_dir: PathBuf::from("/tmp/test-vault/.mdvault/types"),
             typedefs_fallback_dir: None,
+            commands_dir: PathBuf::new(),
+            hooks_dir: PathBuf::new(),
+            formatters_dir: PathBuf::new(),
             excluded_folders: vec![],
             security: SecurityPolicy::default(),
             logging: LoggingConfig::default(),
             activity: ActivityConfig::default(),
+            display: Default::default(),
+            ingest: Default::default(),
+            capture: Default::default(),
+            quota: Default::default(),
+            webhooks: Default::default(),
+            index: Default::default(),
+            plugins: Default::default(),
+            searches: Default::default(),
+            embeddings: Default::default(),
+            staleness: Default::default(),
+            orphans: Default::default(),
+            tui: Default::default(),
+            serve: Default::default(),
         }
     }
 
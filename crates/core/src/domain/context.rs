@@ -11,7 +11,8 @@ use chrono::NaiveDate;
 use crate::config::types::ResolvedConfig;
 use crate::frontmatter::{Frontmatter, ParsedDocument, parse, serialize_with_order};
 use crate::templates::repository::LoadedTemplate;
-use crate::types::{TypeDefinition, TypeRegistry};
+use crate::types::{FieldSchema, FieldType, TypeDefinition, TypeRegistry};
+use crate::vars::try_evaluate_date_expr;
 
 /// Core metadata fields managed by Rust.
 /// These fields are authoritative and survive template/hook modifications.
@@ -23,9 +24,10 @@ pub struct CoreMetadata {
     pub task_id: Option<String>,
     pub meeting_id: Option<String>,
     pub task_counter: Option<u32>,
-    pub project: Option<String>, // Parent project for tasks
-    pub date: Option<String>,    // For daily/meeting notes
-    pub week: Option<String>,    // For weekly notes
+    pub project: Option<String>,     // Parent project for tasks
+    pub date: Option<String>,        // For daily/meeting notes
+    pub week: Option<String>,        // For weekly notes
+    pub schema_version: Option<u32>, // Version of the type's schema
 }
 
 impl CoreMetadata {
@@ -59,6 +61,12 @@ impl CoreMetadata {
         if let Some(ref w) = self.week {
             map.insert("week".into(), serde_yaml::Value::String(w.clone()));
         }
+        if let Some(version) = self.schema_version {
+            map.insert(
+                crate::migrate::VERSION_FIELD.into(),
+                serde_yaml::Value::Number(version.into()),
+            );
+        }
         map
     }
 
@@ -142,6 +150,7 @@ impl<'a> CreationContext<'a> {
         let core_metadata = CoreMetadata {
             note_type: Some(type_name.to_string()),
             title: Some(title.to_string()),
+            schema_version: typedef.as_ref().and_then(|td| td.schema_version),
             ..Default::default()
         };
 
@@ -231,8 +240,34 @@ pub struct FieldPrompt {
     pub default_value: Option<String>,
 }
 
+impl FieldPrompt {
+    /// The prompt for a schema field, asked with the field's `prompt` text
+    /// (or its name) and pre-filled with its default.
+    pub fn from_schema(field_name: &str, field: &FieldSchema) -> Self {
+        Self {
+            field_name: field_name.to_string(),
+            prompt_text: field.prompt.clone().unwrap_or_else(|| field_name.to_string()),
+            prompt_type: PromptType::for_field(field),
+            required: field.required,
+            default_value: field.default.as_ref().and_then(|v| match v {
+                serde_yaml::Value::String(s) => Some(s.clone()),
+                serde_yaml::Value::Number(n) => Some(n.to_string()),
+                serde_yaml::Value::Bool(b) => Some(b.to_string()),
+                serde_yaml::Value::Sequence(items) => Some(
+                    items
+                        .iter()
+                        .filter_map(|item| item.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ),
+                _ => None,
+            }),
+        }
+    }
+}
+
 /// Type of prompt to display.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PromptType {
     /// Single-line text input.
     Text,
@@ -240,6 +275,186 @@ pub enum PromptType {
     Multiline,
     /// Selection from a list of options.
     Select(Vec<String>),
+    /// Any number of options from a list, answered comma-separated.
+    MultiSelect(Vec<String>),
+    /// A date, given as `YYYY-MM-DD` or a date expression like `today + 3d`.
+    Date,
+    /// Yes or no, answered `true` or `false`.
+    Boolean,
+    /// Pick a note of the given type from the index; answered with its
+    /// file stem.
+    Reference(String),
     /// Special: pick from indexed projects.
     ProjectSelector,
 }
+
+impl PromptType {
+    /// The prompt a schema field calls for:
+    /// - a `selector`, or a `reference` field with a `note_type`, picks a note
+    /// - a `list` field with `enum` values (on the field or its `items`)
+    ///   picks several of them
+    /// - an `enum` picks one value
+    /// - `boolean` and `date` fields ask yes/no and for a date
+    /// - `multiline` string fields open an editor
+    pub fn for_field(field: &FieldSchema) -> Self {
+        if let Some(note_type) = &field.selector {
+            return Self::Reference(note_type.clone());
+        }
+        match field.effective_type() {
+            FieldType::Reference => match &field.note_type {
+                Some(note_type) => Self::Reference(note_type.clone()),
+                None => Self::Text,
+            },
+            FieldType::List => {
+                let values = field.enum_values.as_ref().or_else(|| {
+                    field.items.as_ref().and_then(|i| i.enum_values.as_ref())
+                });
+                match values {
+                    Some(values) => Self::MultiSelect(values.clone()),
+                    None => Self::Text,
+                }
+            }
+            _ if field.enum_values.is_some() => {
+                Self::Select(field.enum_values.clone().unwrap_or_default())
+            }
+            FieldType::Boolean => Self::Boolean,
+            FieldType::Date => Self::Date,
+            _ if field.multiline => Self::Multiline,
+            _ => Self::Text,
+        }
+    }
+
+    /// Check an answer to this prompt and normalize it to the value stored
+    /// in the note's variables: options in their declared spelling, dates
+    /// evaluated to `YYYY-MM-DD`, booleans as `true`/`false`. An empty
+    /// answer stays empty, leaving defaults and required checks to the
+    /// caller.
+    pub fn parse_answer(&self, input: &str) -> Result<String, String> {
+        let trimmed = input.trim();
+        if trimmed.is_empty() {
+            return Ok(String::new());
+        }
+        let option = |options: &[String], value: &str| {
+            options.iter().find(|o| o.eq_ignore_ascii_case(value)).cloned().ok_or_else(
+                || format!("'{}' is not one of: {}", value, options.join(", ")),
+            )
+        };
+        match self {
+            Self::Text | Self::Multiline => Ok(input.to_string()),
+            Self::Reference(_) | Self::ProjectSelector => Ok(trimmed.to_string()),
+            Self::Select(options) => option(options, trimmed),
+            Self::MultiSelect(options) => Ok(trimmed
+                .split(',')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(|v| option(options, v))
+                .collect::<Result<Vec<_>, _>>()?
+                .join(", ")),
+            Self::Date => {
+                let date = try_evaluate_date_expr(trimmed)
+                    .unwrap_or_else(|| trimmed.to_string());
+                NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                    .map(|d| d.format("%Y-%m-%d").to_string())
+                    .map_err(|_| {
+                        format!(
+                            "'{trimmed}' is not a date (YYYY-MM-DD or e.g. today + 3d)"
+                        )
+                    })
+            }
+            Self::Boolean => match trimmed.to_lowercase().as_str() {
+                "y" | "yes" | "true" | "1" | "on" => Ok("true".to_string()),
+                "n" | "no" | "false" | "0" | "off" => Ok("false".to_string()),
+                _ => Err(format!("'{trimmed}' is not yes or no")),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(field_type: FieldType) -> FieldSchema {
+        FieldSchema { field_type: Some(field_type), ..Default::default() }
+    }
+
+    #[test]
+    fn test_prompt_type_for_field() {
+        let options = vec!["a".to_string(), "b".to_string()];
+        let selector =
+            FieldSchema { selector: Some("project".into()), ..Default::default() };
+        let reference = FieldSchema {
+            note_type: Some("person".into()),
+            ..field(FieldType::Reference)
+        };
+        let list_items = FieldSchema {
+            items: Some(Box::new(FieldSchema {
+                enum_values: Some(options.clone()),
+                ..Default::default()
+            })),
+            ..field(FieldType::List)
+        };
+        let status = FieldSchema::required_enum(options.clone());
+        let notes = FieldSchema { multiline: true, ..Default::default() };
+
+        assert_eq!(
+            PromptType::for_field(&selector),
+            PromptType::Reference("project".into())
+        );
+        assert_eq!(
+            PromptType::for_field(&reference),
+            PromptType::Reference("person".into())
+        );
+        assert_eq!(
+            PromptType::for_field(&list_items),
+            PromptType::MultiSelect(options.clone())
+        );
+        assert_eq!(PromptType::for_field(&field(FieldType::List)), PromptType::Text);
+        assert_eq!(PromptType::for_field(&status), PromptType::Select(options));
+        assert_eq!(
+            PromptType::for_field(&field(FieldType::Boolean)),
+            PromptType::Boolean
+        );
+        assert_eq!(PromptType::for_field(&field(FieldType::Date)), PromptType::Date);
+        assert_eq!(PromptType::for_field(&notes), PromptType::Multiline);
+        assert_eq!(PromptType::for_field(&FieldSchema::default()), PromptType::Text);
+    }
+
+    #[test]
+    fn test_parse_answer() {
+        let options = vec!["Todo".to_string(), "Done".to_string()];
+        let select = PromptType::Select(options.clone());
+        assert_eq!(select.parse_answer("done").unwrap(), "Done");
+        assert!(select.parse_answer("later").is_err());
+
+        let multi = PromptType::MultiSelect(options);
+        assert_eq!(multi.parse_answer("todo, DONE,").unwrap(), "Todo, Done");
+        assert!(multi.parse_answer("todo, later").is_err());
+
+        assert_eq!(PromptType::Date.parse_answer("2025-01-15").unwrap(), "2025-01-15");
+        assert_eq!(
+            PromptType::Date.parse_answer("2025-01-15 + 3d").unwrap(),
+            "2025-01-18"
+        );
+        assert!(PromptType::Date.parse_answer("soon").is_err());
+
+        assert_eq!(PromptType::Boolean.parse_answer("Yes").unwrap(), "true");
+        assert_eq!(PromptType::Boolean.parse_answer("n").unwrap(), "false");
+        assert!(PromptType::Boolean.parse_answer("maybe").is_err());
+
+        assert_eq!(PromptType::Date.parse_answer("  ").unwrap(), "");
+    }
+
+    #[test]
+    fn test_field_prompt_from_schema() {
+        let field = FieldSchema {
+            prompt: Some("Tags?".into()),
+            default: Some(serde_yaml::from_str("[a, b]").unwrap()),
+            ..field(FieldType::List)
+        };
+        let prompt = FieldPrompt::from_schema("tags", &field);
+        assert_eq!(prompt.prompt_text, "Tags?");
+        assert_eq!(prompt.default_value.as_deref(), Some("a, b"));
+        assert!(!prompt.required);
+    }
+}
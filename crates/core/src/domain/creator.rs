@@ -13,6 +13,7 @@ use super::NoteType;
 use super::context::CreationContext;
 use super::traits::{DomainError, DomainResult, NoteBehavior};
 use crate::templates::engine::render_with_ref_date as render_template;
+use crate::templates::repository::{LoadedTemplate, TemplateRepository};
 use crate::types::scaffolding::generate_scaffolding;
 
 /// Result of a successful note creation.
@@ -50,7 +51,7 @@ impl NoteCreator {
     /// 1. Collect type-specific prompts
     /// 2. Run before_create (sets IDs, counters, etc.)
     /// 3. Resolve output path
-    /// 4. Generate content (template or scaffolding)
+    /// 4. Generate content (template, the type's bound template, or scaffolding)
     /// 5. Ensure core metadata is present
     /// 6. Validate content
     /// 7. Write to disk
@@ -82,6 +83,9 @@ impl NoteCreator {
         }
 
         // Step 4: Generate content
+        if ctx.template.is_none() {
+            ctx.template = self.bound_template(ctx)?;
+        }
         let content = self.generate_content(ctx)?;
 
         // Step 5: Ensure core metadata is preserved
@@ -122,6 +126,27 @@ impl NoteCreator {
         })
     }
 
+    /// Load the template the type definition binds to its notes, if any.
+    fn bound_template(
+        &self,
+        ctx: &CreationContext,
+    ) -> DomainResult<Option<LoadedTemplate>> {
+        let Some(name) = ctx.typedef.as_ref().and_then(|td| td.template.as_deref())
+        else {
+            return Ok(None);
+        };
+        TemplateRepository::new(&ctx.config.templates_dir)
+            .map_err(|e| e.to_string())
+            .and_then(|repo| repo.get_by_name(name).map_err(|e| e.to_string()))
+            .map(Some)
+            .map_err(|e| {
+                DomainError::Other(format!(
+                    "Type '{}' uses template '{}': {}",
+                    ctx.type_name, name, e
+                ))
+            })
+    }
+
     /// Generate the note content.
     ///
     /// If a template is provided in the context, renders it with variable substitution.
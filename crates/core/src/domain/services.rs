@@ -211,6 +211,25 @@ impl DailyLogService {
         note_id: &str,
         output_path: &Path,
     ) -> Result<(), String> {
+        let rel_path =
+            output_path.strip_prefix(&config.vault_root).unwrap_or(output_path);
+        let link = rel_path.file_stem().and_then(|s| s.to_str()).unwrap_or("note");
+
+        let id_display =
+            if note_id.is_empty() { String::new() } else { format!(" {}", note_id) };
+
+        Self::log_entry(
+            config,
+            &format!("{} {}{}: [[{}|{}]]", action, note_type, id_display, link, title),
+        )
+    }
+
+    /// Log a free-form entry to today's daily note, as
+    /// `- **HH:MM**: <message>`.
+    ///
+    /// Creates the daily note if it doesn't exist. Used for entries that are
+    /// not about a single note, such as a finished focus session.
+    pub fn log_entry(config: &ResolvedConfig, message: &str) -> Result<(), String> {
         let today = Local::now().format("%Y-%m-%d").to_string();
         let time = Local::now().format("%H:%M").to_string();
 
@@ -221,17 +240,7 @@ impl DailyLogService {
         let mut content = fs::read_to_string(&daily_path)
             .map_err(|e| format!("Could not read daily note: {e}"))?;
 
-        let rel_path =
-            output_path.strip_prefix(&config.vault_root).unwrap_or(output_path);
-        let link = rel_path.file_stem().and_then(|s| s.to_str()).unwrap_or("note");
-
-        let id_display =
-            if note_id.is_empty() { String::new() } else { format!(" {}", note_id) };
-
-        let log_entry = format!(
-            "- **{}**: {} {}{}: [[{}|{}]]\n",
-            time, action, note_type, id_display, link, title
-        );
+        let log_entry = format!("- **{}**: {}\n", time, message);
 
         if let Some(log_pos) = content.find("## Logs") {
             let after_log = &content[log_pos + 7..];
@@ -321,10 +330,26 @@ mod tests {
             macros_dir: vault_root.join(".mdvault/macros"),
             typedefs_dir: vault_root.join(".mdvault/typedefs"),
             typedefs_fallback_dir: None,
+            commands_dir: PathBuf::new(),
+            hooks_dir: PathBuf::new(),
+            formatters_dir: PathBuf::new(),
             excluded_folders: vec![],
             security: Default::default(),
             logging: Default::default(),
             activity: Default::default(),
+            display: Default::default(),
+            ingest: Default::default(),
+            capture: Default::default(),
+            quota: Default::default(),
+            webhooks: Default::default(),
+            index: Default::default(),
+            plugins: Default::default(),
+            searches: Default::default(),
+            embeddings: Default::default(),
+            staleness: Default::default(),
+            orphans: Default::default(),
+            tui: Default::default(),
+            serve: Default::default(),
         }
     }
 
@@ -396,6 +421,29 @@ mod tests {
         assert!(content.contains("Created project NEW"));
     }
 
+    #[test]
+    fn test_log_entry_appends_message() {
+        let tmp = tempdir().unwrap();
+        let config = make_test_config(tmp.path().to_path_buf());
+
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        let year = &today[..4];
+        let daily_path = tmp.path().join(format!("Journal/{}/Daily/{}.md", year, today));
+        fs::create_dir_all(daily_path.parent().unwrap()).unwrap();
+        fs::write(
+            &daily_path,
+            "---\ntype: daily\n---\n\n## Logs\n- Existing entry\n\n## Notes\n",
+        )
+        .unwrap();
+
+        DailyLogService::log_entry(&config, "Focused on MCP for 45m").unwrap();
+
+        let content = fs::read_to_string(&daily_path).unwrap();
+        let entry = content.find(": Focused on MCP for 45m\n").unwrap();
+        assert!(entry > content.find("- Existing entry").unwrap());
+        assert!(entry < content.find("## Notes").unwrap());
+    }
+
     #[test]
     fn test_project_log_appends_to_existing_logs_section() {
         let tmp = tempdir().unwrap();
@@ -0,0 +1,499 @@
+//! Note embeddings for semantic search.
+//!
+//! `mdv embed` turns each note's title and body into a vector through an
+//! [`EmbeddingBackend`] and stores it in the index (`note_embeddings`),
+//! tagged with the backend's model id and the note's content hash so only
+//! changed notes are recomputed. `mdv search --mode semantic` embeds the
+//! query the same way and ranks notes by cosine similarity, blended with
+//! the lexical score from [`SearchEngine`].
+//!
+//! Two backends ship:
+//! - `ollama`: a local Ollama server's `/api/embed`, called through `curl`
+//!   like webhooks, so it needs `security.allow_http = true`
+//! - `hashing`: hashed word and character-trigram features. No model and no
+//!   network; it finds notes sharing vocabulary and word stems rather than
+//!   meaning, which makes it a reasonable offline fallback.
+//!
+//! Other backends (an in-process model, a hosted API) only need to
+//! implement [`EmbeddingBackend`].
+//!
+//! Everything here is behind the `embeddings` cargo feature.
+
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::config::types::{EmbeddingBackendKind, EmbeddingsConfig, ResolvedConfig};
+use crate::index::{
+    IndexDb, IndexError, MatchSource, NoteQuery, SearchEngine, SearchMode, SearchQuery,
+    SearchResult,
+};
+
+/// Notes are embedded in requests of this many.
+const BATCH_SIZE: usize = 16;
+
+/// Text beyond this many characters is not embedded; most embedding models
+/// truncate well before it anyway.
+const MAX_EMBED_CHARS: usize = 8000;
+
+/// Notes without a lexical match need at least this cosine similarity to be
+/// returned by a semantic search.
+pub const MIN_SEMANTIC_SIMILARITY: f64 = 0.3;
+
+#[derive(Debug, Error)]
+pub enum EmbeddingError {
+    #[error("the ollama embeddings backend needs security.allow_http = true")]
+    HttpNotAllowed,
+
+    #[error("failed to run curl: {0}")]
+    Spawn(#[source] std::io::Error),
+
+    #[error("embedding request to {url} failed: {message}")]
+    Request { url: String, message: String },
+
+    #[error("unexpected response from the embeddings backend: {0}")]
+    Response(String),
+
+    #[error("semantic search needs query text")]
+    NoQuery,
+
+    #[error("no notes are embedded with {model}. Run 'mdv embed' first")]
+    NotEmbedded { model: String },
+
+    #[error(transparent)]
+    Index(#[from] IndexError),
+}
+
+/// Something that turns text into vectors.
+pub trait EmbeddingBackend {
+    /// Identifies the vector space, e.g. `ollama:nomic-embed-text`. Vectors
+    /// are only compared with others carrying the same id.
+    fn model_id(&self) -> String;
+
+    /// One vector per text, in order.
+    fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError>;
+}
+
+/// The backend selected by `[embeddings]` in the config.
+pub fn backend(
+    config: &ResolvedConfig,
+) -> Result<Box<dyn EmbeddingBackend>, EmbeddingError> {
+    let settings = &config.embeddings;
+    match settings.backend {
+        EmbeddingBackendKind::Hashing => {
+            Ok(Box::new(HashingBackend::new(settings.dimensions)))
+        }
+        EmbeddingBackendKind::Ollama => {
+            if !config.security.allow_http {
+                return Err(EmbeddingError::HttpNotAllowed);
+            }
+            Ok(Box::new(OllamaBackend::new(settings)))
+        }
+    }
+}
+
+/// Embeddings from hashed word and character-trigram counts.
+pub struct HashingBackend {
+    dimensions: usize,
+}
+
+impl HashingBackend {
+    pub fn new(dimensions: usize) -> Self {
+        Self { dimensions: dimensions.max(1) }
+    }
+
+    fn embed_one(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0f32; self.dimensions];
+        let mut add = |feature: &str, weight: f32| {
+            let hash = fnv1a(feature.as_bytes());
+            let slot = (hash % self.dimensions as u64) as usize;
+            // The top bit picks a sign so collisions tend to cancel out
+            vector[slot] += if hash >> 63 == 0 { weight } else { -weight };
+        };
+
+        for word in text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+            let word = word.to_lowercase();
+            add(&word, 1.0);
+            let chars: Vec<char> = format!("^{word}$").chars().collect();
+            for gram in chars.windows(3) {
+                add(&gram.iter().collect::<String>(), 0.5);
+            }
+        }
+
+        normalize(&mut vector);
+        vector
+    }
+}
+
+impl EmbeddingBackend for HashingBackend {
+    fn model_id(&self) -> String {
+        format!("hashing:{}", self.dimensions)
+    }
+
+    fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        Ok(texts.iter().map(|t| self.embed_one(t)).collect())
+    }
+}
+
+/// Embeddings from an Ollama server.
+pub struct OllamaBackend {
+    url: String,
+    model: String,
+    timeout_secs: u64,
+}
+
+impl OllamaBackend {
+    pub fn new(settings: &EmbeddingsConfig) -> Self {
+        Self {
+            url: format!("{}/api/embed", settings.url.trim_end_matches('/')),
+            model: settings.model.clone(),
+            timeout_secs: settings.timeout_secs,
+        }
+    }
+}
+
+impl EmbeddingBackend for OllamaBackend {
+    fn model_id(&self) -> String {
+        format!("ollama:{}", self.model)
+    }
+
+    fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        let body = serde_json::json!({ "model": self.model, "input": texts }).to_string();
+
+        let mut child = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--request", "POST"])
+            .arg("--max-time")
+            .arg(self.timeout_secs.to_string())
+            .args(["--header", "Content-Type: application/json"])
+            .args(["--data-binary", "@-"])
+            .arg(&self.url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(EmbeddingError::Spawn)?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // A failed write shows up as a curl error below
+            let _ = stdin.write_all(body.as_bytes());
+        }
+        let output = child.wait_with_output().map_err(EmbeddingError::Spawn)?;
+        if !output.status.success() {
+            return Err(EmbeddingError::Request {
+                url: self.url.clone(),
+                message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+
+        #[derive(serde::Deserialize)]
+        struct Response {
+            embeddings: Vec<Vec<f32>>,
+        }
+        let response: Response = serde_json::from_slice(&output.stdout)
+            .map_err(|e| EmbeddingError::Response(e.to_string()))?;
+        if response.embeddings.len() != texts.len() {
+            return Err(EmbeddingError::Response(format!(
+                "asked for {} vectors, got {}",
+                texts.len(),
+                response.embeddings.len()
+            )));
+        }
+        Ok(response.embeddings)
+    }
+}
+
+/// What `embed_notes` did.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EmbedStats {
+    /// Notes (re)embedded.
+    pub embedded: usize,
+    /// Notes whose stored vector was still current.
+    pub unchanged: usize,
+    /// Vectors dropped because they came from another model.
+    pub removed: usize,
+}
+
+/// Embed every note whose content changed since it was last embedded, or
+/// all of them with `force`.
+///
+/// `progress` is called with (done, total) after each batch.
+pub fn embed_notes(
+    db: &IndexDb,
+    backend: &dyn EmbeddingBackend,
+    force: bool,
+    mut progress: impl FnMut(usize, usize),
+) -> Result<EmbedStats, EmbeddingError> {
+    let model = backend.model_id();
+    let mut stats =
+        EmbedStats { removed: db.delete_other_embeddings(&model)?, ..Default::default() };
+
+    let current = if force { HashMap::new() } else { db.get_embedding_hashes(&model)? };
+    let bodies: HashMap<i64, String> = db.get_note_bodies()?.into_iter().collect();
+
+    let mut pending = Vec::new();
+    for note in db.query_notes(&NoteQuery::default())? {
+        let Some(id) = note.id else { continue };
+        if current.get(&id) == Some(&note.content_hash) {
+            stats.unchanged += 1;
+            continue;
+        }
+        let body = bodies.get(&id).map(String::as_str).unwrap_or("");
+        let text: String =
+            format!("{}\n\n{}", note.title, body).chars().take(MAX_EMBED_CHARS).collect();
+        pending.push((id, note.content_hash, text));
+    }
+
+    let total = pending.len();
+    for batch in pending.chunks(BATCH_SIZE) {
+        let texts: Vec<String> = batch.iter().map(|(_, _, text)| text.clone()).collect();
+        let vectors = backend.embed(&texts)?;
+        for ((id, hash, _), vector) in batch.iter().zip(vectors) {
+            db.upsert_embedding(*id, &model, hash, &vector)?;
+        }
+        stats.embedded += batch.len();
+        progress(stats.embedded, total);
+    }
+
+    Ok(stats)
+}
+
+/// Search by meaning: cosine similarity to the query's embedding, blended
+/// with the lexical score.
+///
+/// Each result scores `w * similarity + (1 - w) * lexical`, where `w` is
+/// `semantic_weight` and the lexical score is scaled so the best lexical
+/// match is 1.
+/// Notes the query does not match lexically need a similarity of at least
+/// [`MIN_SEMANTIC_SIMILARITY`]. The query's type, path and field filters
+/// apply to every result.
+pub fn semantic_search(
+    db: &IndexDb,
+    backend: &dyn EmbeddingBackend,
+    query: &SearchQuery,
+    semantic_weight: f64,
+) -> Result<Vec<SearchResult>, EmbeddingError> {
+    let text = query
+        .text
+        .as_deref()
+        .filter(|t| !t.trim().is_empty())
+        .ok_or(EmbeddingError::NoQuery)?;
+    let model = backend.model_id();
+    let vectors = db.get_embeddings(&model)?;
+    if vectors.is_empty() {
+        return Err(EmbeddingError::NotEmbedded { model });
+    }
+    let query_vector = backend
+        .embed(&[text.to_string()])?
+        .pop()
+        .ok_or_else(|| EmbeddingError::Response("no vector for the query".into()))?;
+    let weight = semantic_weight.clamp(0.0, 1.0);
+
+    let lexical_query =
+        SearchQuery { mode: SearchMode::Direct, limit: None, ..query.clone() };
+    let mut lexical: HashMap<i64, SearchResult> = SearchEngine::new(db)
+        .search(&lexical_query)?
+        .into_iter()
+        .filter_map(|r| Some((r.note.id?, r)))
+        .collect();
+    let best_lexical = lexical.values().map(|r| r.score).fold(f64::EPSILON, f64::max);
+
+    let mut allowed: HashMap<i64, _> = db
+        .query_notes(&NoteQuery {
+            note_type: query.note_type,
+            path_prefix: query.path_prefix.as_ref().map(Into::into),
+            fields: query.fields.clone(),
+            ..Default::default()
+        })?
+        .into_iter()
+        .filter_map(|n| Some((n.id?, n)))
+        .collect();
+
+    let mut results = Vec::new();
+    let mut seen = HashSet::new();
+    for (id, vector) in &vectors {
+        let Some(note) = allowed.remove(id) else { continue };
+        seen.insert(*id);
+        let similarity = cosine(&query_vector, vector).max(0.0);
+        let lexical_hit = lexical.remove(id);
+        if lexical_hit.is_none() && similarity < MIN_SEMANTIC_SIMILARITY {
+            continue;
+        }
+        let lexical_score = lexical_hit.as_ref().map_or(0.0, |r| r.score / best_lexical);
+        let score = weight * similarity + (1.0 - weight) * lexical_score;
+        results.push(match lexical_hit {
+            Some(hit) => SearchResult { score, ..hit },
+            None => SearchResult {
+                staleness: db
+                    .get_activity_summary(*id)?
+                    .map(|s| s.staleness_score as f64),
+                note,
+                score,
+                match_source: MatchSource::Direct,
+                snippet: None,
+                hits: Vec::new(),
+            },
+        });
+    }
+
+    // Lexical matches that have not been embedded yet keep their share
+    for (id, hit) in lexical {
+        if !seen.contains(&id) {
+            let score = (1.0 - weight) * hit.score / best_lexical;
+            results.push(SearchResult { score, ..hit });
+        }
+    }
+
+    results.sort_by(|a, b| {
+        b.score.total_cmp(&a.score).then_with(|| a.note.path.cmp(&b.note.path))
+    });
+    if let Some(limit) = query.limit {
+        results.truncate(limit as usize);
+    }
+    Ok(results)
+}
+
+/// Cosine similarity of two vectors; 0 when either is all zeros or their
+/// lengths differ.
+pub fn cosine(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f64, 0.0f64, 0.0f64);
+    for (x, y) in a.iter().zip(b) {
+        let (x, y) = (*x as f64, *y as f64);
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a.sqrt() * norm_b.sqrt())
+    }
+}
+
+/// Scale a vector to unit length, leaving zero vectors alone.
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+}
+
+/// 64-bit FNV-1a. Stable across builds, unlike `DefaultHasher`, so stored
+/// hashing vectors stay comparable with new queries.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::{IndexedNote, NoteType};
+    use chrono::Utc;
+    use std::path::PathBuf;
+
+    fn add(db: &IndexDb, path: &str, title: &str, body: &str) -> i64 {
+        let hash = format!("hash-{body}");
+        let id = db
+            .insert_note(&IndexedNote {
+                id: None,
+                path: PathBuf::from(path),
+                note_type: NoteType::Zettel,
+                title: title.to_string(),
+                created: None,
+                modified: Utc::now(),
+                frontmatter_json: None,
+                content_hash: hash.clone(),
+                word_count: 0,
+                heading_json: None,
+            })
+            .unwrap();
+        db.upsert_note_text(id, title, body, &hash).unwrap();
+        id
+    }
+
+    fn vault() -> IndexDb {
+        let db = IndexDb::open_in_memory().unwrap();
+        add(&db, "bread.md", "Bread", "Sourdough starter needs feeding and flour");
+        add(&db, "garden.md", "Garden", "Tomatoes want sun and water");
+        add(&db, "baking.md", "Baking", "Kneading dough builds gluten in the flour");
+        db
+    }
+
+    #[test]
+    fn hashing_vectors_are_unit_length_and_stable() {
+        let backend = HashingBackend::new(64);
+        let [a, b] = [
+            backend.embed_one("Parsing grammars"),
+            backend.embed_one("parsing, GRAMMARS!"),
+        ];
+        assert_eq!(a, b);
+        let norm: f32 = a.iter().map(|v| v * v).sum();
+        assert!((norm - 1.0).abs() < 1e-5);
+        assert!(backend.embed_one("").iter().all(|v| *v == 0.0));
+        // Published FNV-1a test vector
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn cosine_handles_edge_cases() {
+        assert!((cosine(&[1.0, 0.0], &[1.0, 0.0]) - 1.0).abs() < 1e-9);
+        assert_eq!(cosine(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+        assert_eq!(cosine(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine(&[1.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn embed_notes_skips_unchanged_notes() {
+        let db = vault();
+        let backend = HashingBackend::new(128);
+
+        let first = embed_notes(&db, &backend, false, |_, _| {}).unwrap();
+        assert_eq!((first.embedded, first.unchanged), (3, 0));
+
+        let again = embed_notes(&db, &backend, false, |_, _| {}).unwrap();
+        assert_eq!((again.embedded, again.unchanged), (0, 3));
+
+        let forced = embed_notes(&db, &backend, true, |_, _| {}).unwrap();
+        assert_eq!(forced.embedded, 3);
+
+        // Another model replaces the old vectors
+        let other = embed_notes(&db, &HashingBackend::new(64), false, |_, _| {}).unwrap();
+        assert_eq!((other.embedded, other.removed), (3, 3));
+    }
+
+    #[test]
+    fn semantic_search_finds_notes_without_the_exact_words() {
+        let db = vault();
+        let backend = HashingBackend::new(512);
+        embed_notes(&db, &backend, false, |_, _| {}).unwrap();
+
+        let query =
+            SearchQuery { text: Some("flour dough".into()), ..Default::default() };
+        let results = semantic_search(&db, &backend, &query, 0.6).unwrap();
+        let paths: Vec<_> =
+            results.iter().map(|r| r.note.path.to_string_lossy().to_string()).collect();
+        assert_eq!(paths.first().map(String::as_str), Some("baking.md"));
+        assert!(!paths.contains(&"garden.md".to_string()));
+    }
+
+    #[test]
+    fn semantic_search_needs_text_and_vectors() {
+        let db = vault();
+        let backend = HashingBackend::new(32);
+        let query = SearchQuery { text: Some("flour".into()), ..Default::default() };
+        assert!(matches!(
+            semantic_search(&db, &backend, &query, 0.5),
+            Err(EmbeddingError::NotEmbedded { .. })
+        ));
+        assert!(matches!(
+            semantic_search(&db, &backend, &SearchQuery::default(), 0.5),
+            Err(EmbeddingError::NoQuery)
+        ));
+    }
+}
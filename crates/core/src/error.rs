@@ -0,0 +1,696 @@
+//! Unified error type for the core crate.
+//!
+//! Each module keeps its own error enum for precise handling close to the
+//! failure. `Error` wraps them all without flattening to strings, so callers
+//! (CLI, MCP) can walk the source chain, map failures to a stable
+//! [`ErrorCode`], and decide whether an operation is worth retrying.
+
+use thiserror::Error;
+
+#[cfg(feature = "native")]
+use crate::activity::ActivityError;
+#[cfg(feature = "native")]
+use crate::captures::{CaptureApplyError, CaptureRepoError};
+use crate::config::loader::ConfigError;
+#[cfg(feature = "native")]
+use crate::context::ContextError;
+#[cfg(feature = "native")]
+use crate::context::ContextStateError;
+#[cfg(feature = "native")]
+use crate::demo::DemoError;
+#[cfg(feature = "native")]
+use crate::domain::DomainError;
+#[cfg(feature = "native")]
+use crate::expire::ExpireError;
+use crate::frontmatter::FrontmatterParseError;
+use crate::frontmatter::modifier::FrontmatterModifyError;
+#[cfg(feature = "native")]
+use crate::index::{BuilderError, DerivedError, IndexError, SchemaError};
+#[cfg(feature = "native")]
+use crate::ingest::IngestError;
+#[cfg(feature = "native")]
+use crate::macros::{MacroRepoError, MacroRunError};
+use crate::markdown_ast::MarkdownAstError;
+#[cfg(feature = "native")]
+use crate::query::QueryError;
+#[cfg(feature = "native")]
+use crate::rename::RenameError;
+#[cfg(feature = "native")]
+use crate::scripting::{HookError, ScriptingError};
+use crate::templates::engine::TemplateRenderError;
+use crate::templates::repository::TemplateRepoError;
+use crate::types::{TypedefError, ValidationError};
+use crate::vars::DateMathError;
+use crate::vault::{IgnoreError, VaultWalkerError};
+#[cfg(feature = "native")]
+use crate::webhooks::WebhookError;
+
+/// Result alias using the unified core error.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Stable, user-facing failure category.
+///
+/// The string form (`as_str`) is part of the public contract: it is safe to
+/// match on in scripts and MCP clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// Configuration is missing or malformed.
+    Config,
+    /// A note, template, type, or other resource does not exist.
+    NotFound,
+    /// The target of a write already exists.
+    AlreadyExists,
+    /// User input (date expression, path, argument) could not be understood.
+    InvalidInput,
+    /// A note failed schema or structural validation.
+    Validation,
+    /// The vault index is unavailable, corrupt, or busy.
+    Index,
+    /// A Lua script, hook, or macro failed.
+    Scripting,
+    /// A template could not be loaded or rendered.
+    Template,
+    /// Filesystem access failed.
+    Io,
+    /// An unexpected internal failure.
+    Internal,
+}
+
+impl ErrorCode {
+    /// Stable identifier, e.g. `"not_found"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Config => "config",
+            Self::NotFound => "not_found",
+            Self::AlreadyExists => "already_exists",
+            Self::InvalidInput => "invalid_input",
+            Self::Validation => "validation",
+            Self::Index => "index",
+            Self::Scripting => "scripting",
+            Self::Template => "template",
+            Self::Io => "io",
+            Self::Internal => "internal",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Any error produced by the core crate.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    Index(#[from] IndexError),
+
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    IndexBuild(#[from] BuilderError),
+
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    IndexDerived(#[from] DerivedError),
+
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    Rename(#[from] RenameError),
+
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    Context(#[from] ContextError),
+
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    ContextState(#[from] ContextStateError),
+
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    Domain(#[from] DomainError),
+
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    Demo(#[from] DemoError),
+
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    Ingest(#[from] IngestError),
+
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    Expire(#[from] ExpireError),
+
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    Query(#[from] QueryError),
+
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    Webhook(#[from] WebhookError),
+
+    #[error(transparent)]
+    Typedef(#[from] TypedefError),
+
+    #[error(transparent)]
+    Validation(#[from] ValidationError),
+
+    #[error(transparent)]
+    Frontmatter(#[from] FrontmatterParseError),
+
+    #[error(transparent)]
+    FrontmatterModify(#[from] FrontmatterModifyError),
+
+    #[error(transparent)]
+    TemplateRender(#[from] TemplateRenderError),
+
+    #[error(transparent)]
+    TemplateRepo(#[from] TemplateRepoError),
+
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    CaptureRepo(#[from] CaptureRepoError),
+
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    CaptureApply(#[from] CaptureApplyError),
+
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    MacroRepo(#[from] MacroRepoError),
+
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    MacroRun(#[from] MacroRunError),
+
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    Scripting(#[from] ScriptingError),
+
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    Hook(#[from] HookError),
+
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    Activity(#[from] ActivityError),
+
+    #[error(transparent)]
+    DateMath(#[from] DateMathError),
+
+    #[error(transparent)]
+    Markdown(#[from] MarkdownAstError),
+
+    #[error(transparent)]
+    Walker(#[from] VaultWalkerError),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+impl Error {
+    /// Stable category for this failure.
+    pub fn code(&self) -> ErrorCode {
+        code_of(self.inner()).unwrap_or(ErrorCode::Internal)
+    }
+
+    /// The wrapped module error.
+    fn inner(&self) -> &(dyn std::error::Error + 'static) {
+        match self {
+            Self::Config(e) => e,
+            #[cfg(feature = "native")]
+            Self::Index(e) => e,
+            #[cfg(feature = "native")]
+            Self::IndexBuild(e) => e,
+            #[cfg(feature = "native")]
+            Self::IndexDerived(e) => e,
+            #[cfg(feature = "native")]
+            Self::Rename(e) => e,
+            #[cfg(feature = "native")]
+            Self::Context(e) => e,
+            #[cfg(feature = "native")]
+            Self::ContextState(e) => e,
+            #[cfg(feature = "native")]
+            Self::Domain(e) => e,
+            #[cfg(feature = "native")]
+            Self::Demo(e) => e,
+            #[cfg(feature = "native")]
+            Self::Ingest(e) => e,
+            #[cfg(feature = "native")]
+            Self::Expire(e) => e,
+            #[cfg(feature = "native")]
+            Self::Query(e) => e,
+            #[cfg(feature = "native")]
+            Self::Webhook(e) => e,
+            Self::Typedef(e) => e,
+            Self::Validation(e) => e,
+            Self::Frontmatter(e) => e,
+            Self::FrontmatterModify(e) => e,
+            Self::TemplateRender(e) => e,
+            Self::TemplateRepo(e) => e,
+            #[cfg(feature = "native")]
+            Self::CaptureRepo(e) => e,
+            #[cfg(feature = "native")]
+            Self::CaptureApply(e) => e,
+            #[cfg(feature = "native")]
+            Self::MacroRepo(e) => e,
+            #[cfg(feature = "native")]
+            Self::MacroRun(e) => e,
+            #[cfg(feature = "native")]
+            Self::Scripting(e) => e,
+            #[cfg(feature = "native")]
+            Self::Hook(e) => e,
+            #[cfg(feature = "native")]
+            Self::Activity(e) => e,
+            Self::DateMath(e) => e,
+            Self::Markdown(e) => e,
+            Self::Walker(e) => e,
+            Self::Io(e) => e,
+        }
+    }
+
+    /// Whether retrying the same operation may succeed.
+    ///
+    /// True for transient conditions only: a busy or locked index database,
+    /// or interrupted/timed-out I/O. Everything else needs user action.
+    pub fn is_retryable(&self) -> bool {
+        // Transparent variants forward `source()` to the wrapped error, so a
+        // bare I/O error would be skipped by the chain walk below.
+        if let Self::Io(e) = self {
+            return is_transient_io(e);
+        }
+
+        let mut source: Option<&(dyn std::error::Error + 'static)> = Some(self);
+        while let Some(err) = source {
+            if is_transient(err) {
+                return true;
+            }
+            source = err.source();
+        }
+        false
+    }
+}
+
+/// The category of `err` if it is an [`Error`] or one of the module errors
+/// it wraps. Only `err` itself is looked at, not its sources.
+pub fn code_of(err: &(dyn std::error::Error + 'static)) -> Option<ErrorCode> {
+    if let Some(e) = err.downcast_ref::<Error>() {
+        return Some(e.code());
+    }
+    if err.is::<ConfigError>() {
+        return Some(ErrorCode::Config);
+    }
+    #[cfg(feature = "native")]
+    if let Some(e) = err.downcast_ref::<IndexError>() {
+        return Some(index_code(e));
+    }
+    #[cfg(feature = "native")]
+    if let Some(e) = err.downcast_ref::<BuilderError>() {
+        return Some(match e {
+            BuilderError::Index(e) => index_code(e),
+            BuilderError::Walker(_) | BuilderError::FileRead { .. } => ErrorCode::Io,
+        });
+    }
+    #[cfg(feature = "native")]
+    if let Some(e) = err.downcast_ref::<DerivedError>() {
+        return Some(match e {
+            DerivedError::Index(e) => index_code(e),
+            DerivedError::DateParse(_) => ErrorCode::Index,
+            DerivedError::Staleness { .. } => ErrorCode::Scripting,
+        });
+    }
+    #[cfg(feature = "native")]
+    if let Some(e) = err.downcast_ref::<RenameError>() {
+        return Some(match e {
+            RenameError::SourceNotFound(_)
+            | RenameError::NoteNotInIndex(_)
+            | RenameError::HeadingNotFound { .. } => ErrorCode::NotFound,
+            RenameError::TargetExists(_) => ErrorCode::AlreadyExists,
+            RenameError::Index(e) => index_code(e),
+            RenameError::ReadError { .. }
+            | RenameError::WriteError { .. }
+            | RenameError::RenameError(_) => ErrorCode::Io,
+        });
+    }
+    #[cfg(feature = "native")]
+    if let Some(e) = err.downcast_ref::<ContextError>() {
+        return Some(match e {
+            ContextError::Index(e) => index_code(e),
+            ContextError::IndexUnavailable => ErrorCode::Index,
+            ContextError::NoteNotFound(_) | ContextError::NoFocus => ErrorCode::NotFound,
+            ContextError::InvalidDate(_) => ErrorCode::InvalidInput,
+            ContextError::ActivityError(_) | ContextError::IoError(_) => ErrorCode::Io,
+            ContextError::State(_) => ErrorCode::Config,
+        });
+    }
+    #[cfg(feature = "native")]
+    if let Some(e) = err.downcast_ref::<ContextStateError>() {
+        return Some(match e {
+            ContextStateError::Read(_) => ErrorCode::Io,
+            ContextStateError::Parse(_) | ContextStateError::Serialize(_) => {
+                ErrorCode::Config
+            }
+        });
+    }
+    #[cfg(feature = "native")]
+    if let Some(e) = err.downcast_ref::<DomainError>() {
+        return Some(match e {
+            DomainError::Validation(_) | DomainError::MissingField(_) => {
+                ErrorCode::Validation
+            }
+            DomainError::Io(_) => ErrorCode::Io,
+            DomainError::LifecycleHook(_) => ErrorCode::Scripting,
+            DomainError::IdGeneration(_)
+            | DomainError::PathResolution(_)
+            | DomainError::Other(_) => ErrorCode::Internal,
+        });
+    }
+    #[cfg(feature = "native")]
+    if let Some(e) = err.downcast_ref::<DemoError>() {
+        return Some(match e {
+            DemoError::NotEmpty(_) => ErrorCode::AlreadyExists,
+            DemoError::Write { .. } => ErrorCode::Io,
+        });
+    }
+    #[cfg(feature = "native")]
+    if let Some(e) = err.downcast_ref::<IngestError>() {
+        return Some(match e {
+            IngestError::MissingFile(_) => ErrorCode::NotFound,
+            IngestError::Exists(_) => ErrorCode::AlreadyExists,
+            IngestError::Spawn { .. }
+            | IngestError::TranscriberFailed { .. }
+            | IngestError::EmptyTranscript(_) => ErrorCode::Scripting,
+            IngestError::Write { .. } | IngestError::Ledger { .. } => ErrorCode::Io,
+            IngestError::EmptySnippet(_) => ErrorCode::InvalidInput,
+        });
+    }
+    #[cfg(feature = "native")]
+    if let Some(e) = err.downcast_ref::<ExpireError>() {
+        return Some(match e {
+            ExpireError::InvalidTtl(_)
+            | ExpireError::InvalidAction(_)
+            | ExpireError::InvalidFilter(_) => ErrorCode::InvalidInput,
+            ExpireError::Index(e) => index_code(e),
+            ExpireError::Exists(_) => ErrorCode::AlreadyExists,
+            ExpireError::Io { .. } | ExpireError::Journal { .. } => ErrorCode::Io,
+            ExpireError::NothingToUndo => ErrorCode::NotFound,
+        });
+    }
+    #[cfg(feature = "native")]
+    if let Some(e) = err.downcast_ref::<QueryError>() {
+        return Some(match e {
+            QueryError::Syntax { .. } | QueryError::Invalid(_) => ErrorCode::InvalidInput,
+            QueryError::Index(e) => index_code(e),
+        });
+    }
+    #[cfg(feature = "native")]
+    if let Some(e) = err.downcast_ref::<WebhookError>() {
+        return Some(match e {
+            WebhookError::HttpNotAllowed => ErrorCode::Config,
+            WebhookError::Payload { .. } => ErrorCode::Template,
+            WebhookError::Spawn(_) | WebhookError::Delivery { .. } => ErrorCode::Io,
+        });
+    }
+    #[cfg(feature = "native")]
+    if let Some(e) = err.downcast_ref::<TypedefError>() {
+        return Some(match e {
+            TypedefError::NotFound(_) | TypedefError::MissingDir(_) => {
+                ErrorCode::NotFound
+            }
+            TypedefError::WalkError(..) | TypedefError::Io { .. } => ErrorCode::Io,
+            TypedefError::LuaParse { .. } => ErrorCode::Scripting,
+            TypedefError::InvalidDefinition { .. } | TypedefError::Duplicate(_) => {
+                ErrorCode::Config
+            }
+        });
+    }
+    if err.is::<ValidationError>() {
+        return Some(ErrorCode::Validation);
+    }
+    if err.is::<FrontmatterParseError>() || err.is::<FrontmatterModifyError>() {
+        return Some(ErrorCode::Validation);
+    }
+    if err.is::<TemplateRenderError>() {
+        return Some(ErrorCode::Template);
+    }
+    if let Some(e) = err.downcast_ref::<TemplateRepoError>() {
+        return Some(match e {
+            TemplateRepoError::NotFound(_) => ErrorCode::NotFound,
+            TemplateRepoError::Io { .. } => ErrorCode::Io,
+            TemplateRepoError::Discovery(_)
+            | TemplateRepoError::FrontmatterParse { .. }
+            | TemplateRepoError::UnknownParent { .. }
+            | TemplateRepoError::InheritanceCycle(_) => ErrorCode::Template,
+        });
+    }
+    #[cfg(feature = "native")]
+    if let Some(e) = err.downcast_ref::<CaptureRepoError>() {
+        return Some(match e {
+            CaptureRepoError::NotFound(_) => ErrorCode::NotFound,
+            CaptureRepoError::Io { .. } => ErrorCode::Io,
+            _ => ErrorCode::Scripting,
+        });
+    }
+    #[cfg(feature = "native")]
+    if let Some(e) = err.downcast_ref::<CaptureApplyError>() {
+        return Some(match e {
+            CaptureApplyError::Read { source, .. }
+            | CaptureApplyError::Write { source, .. } => io_code(source),
+            CaptureApplyError::Frontmatter(_) | CaptureApplyError::FrontmatterOps(_) => {
+                ErrorCode::Validation
+            }
+            CaptureApplyError::NoSection => ErrorCode::Config,
+            CaptureApplyError::Section(MarkdownAstError::SectionNotFound(_)) => {
+                ErrorCode::NotFound
+            }
+            CaptureApplyError::Section(_) => ErrorCode::InvalidInput,
+        });
+    }
+    #[cfg(feature = "native")]
+    if let Some(e) = err.downcast_ref::<MacroRepoError>() {
+        return Some(match e {
+            MacroRepoError::NotFound(_) => ErrorCode::NotFound,
+            MacroRepoError::Io { .. } => ErrorCode::Io,
+            _ => ErrorCode::Scripting,
+        });
+    }
+    #[cfg(feature = "native")]
+    if let Some(e) = err.downcast_ref::<MacroRunError>() {
+        return Some(match e {
+            MacroRunError::TrustRequired | MacroRunError::ShellDisabled => {
+                ErrorCode::Config
+            }
+            _ => ErrorCode::Scripting,
+        });
+    }
+    #[cfg(feature = "native")]
+    if err.is::<ScriptingError>() {
+        return Some(ErrorCode::Scripting);
+    }
+    #[cfg(feature = "native")]
+    if let Some(e) = err.downcast_ref::<HookError>() {
+        return Some(match e {
+            HookError::TemplateNotFound(_)
+            | HookError::CaptureNotFound(_)
+            | HookError::MacroNotFound(_) => ErrorCode::NotFound,
+            HookError::Io(_) => ErrorCode::Io,
+            _ => ErrorCode::Scripting,
+        });
+    }
+    #[cfg(feature = "native")]
+    if err.is::<ActivityError>() {
+        return Some(ErrorCode::Io);
+    }
+    if err.is::<DateMathError>() {
+        return Some(ErrorCode::InvalidInput);
+    }
+    if let Some(e) = err.downcast_ref::<MarkdownAstError>() {
+        return Some(match e {
+            MarkdownAstError::SectionNotFound(_) => ErrorCode::NotFound,
+            _ => ErrorCode::InvalidInput,
+        });
+    }
+    if let Some(e) = err.downcast_ref::<VaultWalkerError>() {
+        return Some(match e {
+            VaultWalkerError::MissingRoot(_) => ErrorCode::Config,
+            VaultWalkerError::Ignore(IgnoreError::InvalidPattern { .. }) => {
+                ErrorCode::InvalidInput
+            }
+            _ => ErrorCode::Io,
+        });
+    }
+    if let Some(e) = err.downcast_ref::<std::io::Error>() {
+        return Some(io_code(e));
+    }
+    None
+}
+
+/// Classify a failure reported through another error type, such as the
+/// CLI's `eyre` reports that wrap core errors in context messages.
+///
+/// The code comes from the outermost core error in the source chain
+/// ([`ErrorCode::Internal`] if there is none); the failure is retryable if
+/// any error in the chain is transient, as for [`Error::is_retryable`].
+pub fn classify(err: &(dyn std::error::Error + 'static)) -> (ErrorCode, bool) {
+    let mut code = None;
+    let mut retryable = false;
+    let mut source = Some(err);
+    while let Some(err) = source {
+        code = code.or_else(|| code_of(err));
+        retryable |= is_transient(err);
+        source = err.source();
+    }
+    (code.unwrap_or(ErrorCode::Internal), retryable)
+}
+
+#[cfg(feature = "native")]
+fn index_code(e: &IndexError) -> ErrorCode {
+    match e {
+        IndexError::NoteNotFound(_) => ErrorCode::NotFound,
+        IndexError::InvalidQuery(_) => ErrorCode::InvalidInput,
+        IndexError::Ranking { .. } => ErrorCode::Scripting,
+        IndexError::Schema(SchemaError::VersionTooNew { .. }) => ErrorCode::Config,
+        IndexError::Database(_) | IndexError::Schema(_) | IndexError::InvalidData(_) => {
+            ErrorCode::Index
+        }
+    }
+}
+
+fn io_code(e: &std::io::Error) -> ErrorCode {
+    match e.kind() {
+        std::io::ErrorKind::NotFound => ErrorCode::NotFound,
+        std::io::ErrorKind::AlreadyExists => ErrorCode::AlreadyExists,
+        std::io::ErrorKind::InvalidInput => ErrorCode::InvalidInput,
+        _ => ErrorCode::Io,
+    }
+}
+
+/// Whether `err` itself is a busy database or interrupted/timed-out I/O.
+fn is_transient(err: &(dyn std::error::Error + 'static)) -> bool {
+    #[cfg(feature = "native")]
+    if let Some(e) = err.downcast_ref::<rusqlite::Error>()
+        && is_transient_sqlite(e)
+    {
+        return true;
+    }
+    err.downcast_ref::<std::io::Error>().is_some_and(is_transient_io)
+}
+
+#[cfg(feature = "native")]
+fn is_transient_sqlite(e: &rusqlite::Error) -> bool {
+    matches!(
+        e.sqlite_error_code(),
+        Some(rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked)
+    )
+}
+
+fn is_transient_io(e: &std::io::Error) -> bool {
+    matches!(
+        e.kind(),
+        std::io::ErrorKind::Interrupted
+            | std::io::ErrorKind::WouldBlock
+            | std::io::ErrorKind::TimedOut
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "native")]
+    use std::path::PathBuf;
+
+    #[test]
+    fn error_code_strings_are_stable() {
+        assert_eq!(ErrorCode::NotFound.as_str(), "not_found");
+        assert_eq!(ErrorCode::AlreadyExists.to_string(), "already_exists");
+        assert_eq!(ErrorCode::InvalidInput.as_str(), "invalid_input");
+    }
+
+    #[test]
+    #[cfg(feature = "native")]
+    fn rename_errors_map_to_codes() {
+        let e: Error = RenameError::SourceNotFound(PathBuf::from("a.md")).into();
+        assert_eq!(e.code(), ErrorCode::NotFound);
+
+        let e: Error = RenameError::TargetExists(PathBuf::from("b.md")).into();
+        assert_eq!(e.code(), ErrorCode::AlreadyExists);
+    }
+
+    #[test]
+    #[cfg(feature = "native")]
+    fn index_source_is_preserved_through_rename() {
+        let inner = IndexError::NoteNotFound("x.md".into());
+        let e: Error = RenameError::Index(inner).into();
+
+        assert_eq!(e.code(), ErrorCode::NotFound);
+        let source = std::error::Error::source(&e).expect("rename error has a source");
+        assert!(source.downcast_ref::<IndexError>().is_some());
+    }
+
+    #[test]
+    #[cfg(feature = "native")]
+    fn busy_database_is_retryable() {
+        let busy = rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+            None,
+        );
+        let e: Error = ContextError::Index(IndexError::Database(busy)).into();
+        assert_eq!(e.code(), ErrorCode::Index);
+        assert!(e.is_retryable());
+    }
+
+    #[test]
+    #[cfg(feature = "native")]
+    fn missing_note_is_not_retryable() {
+        let e: Error = ContextError::NoteNotFound(PathBuf::from("gone.md")).into();
+        assert_eq!(e.code(), ErrorCode::NotFound);
+        assert!(!e.is_retryable());
+    }
+
+    /// A context message around a source, as `eyre`'s `wrap_err` adds.
+    #[derive(Debug, thiserror::Error)]
+    #[error("Failed to do the thing")]
+    struct Wrapped(#[source] Box<dyn std::error::Error + Send + Sync>);
+
+    #[test]
+    fn classify_walks_the_source_chain() {
+        let e = Wrapped(Box::new(ConfigError::NoProfiles));
+        assert_eq!(classify(&e), (ErrorCode::Config, false));
+
+        let e = Wrapped(std::io::Error::from(std::io::ErrorKind::TimedOut).into());
+        assert_eq!(classify(&e), (ErrorCode::Io, true));
+
+        let e = Wrapped("no typed error inside".into());
+        assert_eq!(classify(&e), (ErrorCode::Internal, false));
+    }
+
+    #[test]
+    #[cfg(feature = "native")]
+    fn classify_uses_the_outermost_core_error() {
+        let busy = rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+            None,
+        );
+        let e = Wrapped(Box::new(RenameError::Index(IndexError::Database(busy))));
+        assert_eq!(classify(&e), (ErrorCode::Index, true));
+
+        let e = Wrapped(Box::new(ContextError::NoteNotFound(PathBuf::from("a.md"))));
+        assert_eq!(classify(&e), (ErrorCode::NotFound, false));
+    }
+
+    #[test]
+    fn io_errors_classified_by_kind() {
+        let e: Error = std::io::Error::from(std::io::ErrorKind::NotFound).into();
+        assert_eq!(e.code(), ErrorCode::NotFound);
+
+        let e: Error = std::io::Error::from(std::io::ErrorKind::TimedOut).into();
+        assert_eq!(e.code(), ErrorCode::Io);
+        assert!(e.is_retryable());
+    }
+}
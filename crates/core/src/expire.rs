@@ -0,0 +1,520 @@
+//! Note expiry and retention.
+//!
+//! A note expires when its frontmatter says so (`expires: 2025-06-30`, or
+//! `ttl: 90d` counted from its creation date) or when a `retention` rule on its
+//! type definition matches. Expired notes are archived under `Archive/` or
+//! moved to `.mdvault/trash/`. Every move is recorded in a journal, so a
+//! run can be undone with [`undo_last_run`].
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::index::{
+    FieldFilter, FieldFilterError, IndexDb, IndexError, IndexedNote, NoteQuery,
+};
+use crate::paths::PathResolver;
+use crate::types::TypeDefinition;
+use crate::vars::datemath::{evaluate_date_expr, parse_date_expr};
+
+#[derive(Debug, Error)]
+pub enum ExpireError {
+    #[error("invalid ttl '{0}' (expected e.g. 30d, 6w, 3M, 1y)")]
+    InvalidTtl(String),
+
+    #[error("invalid expiry action '{0}' (expected archive or trash)")]
+    InvalidAction(String),
+
+    #[error(transparent)]
+    InvalidFilter(#[from] FieldFilterError),
+
+    #[error(transparent)]
+    Index(#[from] IndexError),
+
+    #[error("destination already exists: {0}")]
+    Exists(PathBuf),
+
+    #[error("failed to move {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("expire journal is corrupt at line {line}: {message}")]
+    Journal { line: usize, message: String },
+
+    #[error("nothing to undo")]
+    NothingToUndo,
+}
+
+/// What happens to a note once it expires.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExpireAction {
+    /// Move under `Archive/`, keeping it searchable.
+    #[default]
+    Archive,
+    /// Move to `.mdvault/trash/`, out of the index.
+    Trash,
+}
+
+impl ExpireAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Archive => "archive",
+            Self::Trash => "trash",
+        }
+    }
+}
+
+impl FromStr for ExpireAction {
+    type Err = ExpireError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "archive" => Ok(Self::Archive),
+            "trash" => Ok(Self::Trash),
+            _ => Err(ExpireError::InvalidAction(s.to_string())),
+        }
+    }
+}
+
+/// A type-level retention policy, declared as `retention = { ... }` in a
+/// type definition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetentionRule {
+    /// How long after creation a note of this type expires, e.g. `1y`.
+    pub after: String,
+    pub action: ExpireAction,
+    /// Only notes matching every filter expire, e.g. `status=resolved`.
+    pub filters: Vec<FieldFilter>,
+}
+
+impl RetentionRule {
+    /// Build a rule, checking the duration, action, and filters up front.
+    pub fn parse(
+        after: &str,
+        action: Option<&str>,
+        filters: &[String],
+    ) -> Result<Self, ExpireError> {
+        validate_ttl(after)?;
+        Ok(Self {
+            after: after.trim().to_string(),
+            action: action.map(str::parse).transpose()?.unwrap_or_default(),
+            filters: filters.iter().map(|f| f.parse()).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// Add a ttl such as `90d` or `1y` to `base`.
+pub fn add_ttl(base: NaiveDate, ttl: &str) -> Result<NaiveDate, ExpireError> {
+    validate_ttl(ttl)?;
+    let invalid = || ExpireError::InvalidTtl(ttl.to_string());
+    let expr =
+        parse_date_expr(&format!("{} + {}", base, ttl.trim())).map_err(|_| invalid())?;
+    NaiveDate::parse_from_str(&evaluate_date_expr(&expr), "%Y-%m-%d")
+        .map_err(|_| invalid())
+}
+
+/// Accept days, weeks, months, and years only; hours and minutes make no
+/// sense for retention.
+fn validate_ttl(ttl: &str) -> Result<(), ExpireError> {
+    let ttl = ttl.trim();
+    let valid = ttl.len() > 1
+        && ttl.ends_with(['d', 'w', 'M', 'y', 'Y'])
+        && ttl[..ttl.len() - 1].chars().all(|c| c.is_ascii_digit());
+    if valid { Ok(()) } else { Err(ExpireError::InvalidTtl(ttl.to_string())) }
+}
+
+/// A note whose expiry date has passed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpiredNote {
+    pub path: PathBuf,
+    pub title: String,
+    pub expired_on: NaiveDate,
+    pub action: ExpireAction,
+    /// Which policy applied, e.g. `ttl 90d` or `meeting retention 1y`.
+    pub reason: String,
+}
+
+/// Result of [`find_expired`].
+#[derive(Debug, Clone, Default)]
+pub struct ExpiryScan {
+    pub expired: Vec<ExpiredNote>,
+    /// Notes with an unusable `expires`, `ttl`, or `on_expire` value.
+    pub invalid: Vec<(PathBuf, String)>,
+}
+
+/// Find every indexed note that has expired as of `today`.
+///
+/// A note's own `expires`/`ttl` takes precedence over its type's retention
+/// rule. Notes already under `Archive/` are left alone.
+pub fn find_expired(
+    db: &IndexDb,
+    typedefs: &[TypeDefinition],
+    today: NaiveDate,
+) -> Result<ExpiryScan, ExpireError> {
+    let mut scan = ExpiryScan::default();
+    let mut has_own_policy = HashSet::new();
+
+    let notes = db.query_notes(&NoteQuery::default())?;
+    for note in notes.iter().filter(|n| !n.path.starts_with("Archive")) {
+        let fm = frontmatter(note);
+        let field = |key: &str| fm.get(key).and_then(value_string);
+        let (expires, ttl) = (field("expires"), field("ttl"));
+        if expires.is_none() && ttl.is_none() {
+            continue;
+        }
+        has_own_policy.insert(note.path.clone());
+
+        let policy = (|| {
+            let action =
+                field("on_expire").map(|a| a.parse()).transpose()?.unwrap_or_default();
+            let (expired_on, reason) = match (expires, ttl) {
+                (Some(date), _) => (
+                    parse_date(&date)
+                        .ok_or_else(|| format!("invalid expires '{date}'"))?,
+                    format!("expires {date}"),
+                ),
+                (None, Some(ttl)) => {
+                    (add_ttl(base_date(note, &fm), &ttl)?, format!("ttl {ttl}"))
+                }
+                (None, None) => unreachable!(),
+            };
+            Ok::<_, Box<dyn std::error::Error>>((expired_on, action, reason))
+        })();
+        match policy {
+            Ok((expired_on, action, reason)) if expired_on <= today => {
+                scan.expired.push(expired(note, expired_on, action, reason))
+            }
+            Ok(_) => {}
+            Err(e) => scan.invalid.push((note.path.clone(), e.to_string())),
+        }
+    }
+
+    for typedef in typedefs {
+        let Some(rule) = &typedef.retention else { continue };
+        let mut fields = vec![FieldFilter::from_str(&format!("type={}", typedef.name))?];
+        fields.extend(rule.filters.iter().cloned());
+        let matching = db.query_notes(&NoteQuery { fields, ..Default::default() })?;
+
+        for note in matching {
+            if has_own_policy.contains(&note.path) || note.path.starts_with("Archive") {
+                continue;
+            }
+            let expired_on = add_ttl(base_date(&note, &frontmatter(&note)), &rule.after)?;
+            if expired_on <= today {
+                let reason = format!("{} retention {}", typedef.name, rule.after);
+                scan.expired.push(expired(&note, expired_on, rule.action, reason));
+            }
+        }
+    }
+
+    scan.expired.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(scan)
+}
+
+fn expired(
+    note: &IndexedNote,
+    expired_on: NaiveDate,
+    action: ExpireAction,
+    reason: String,
+) -> ExpiredNote {
+    ExpiredNote {
+        path: note.path.clone(),
+        title: note.title.clone(),
+        expired_on,
+        action,
+        reason,
+    }
+}
+
+fn frontmatter(note: &IndexedNote) -> serde_json::Map<String, serde_json::Value> {
+    note.frontmatter_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default()
+}
+
+fn value_string(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) if !s.trim().is_empty() => {
+            Some(s.trim().to_string())
+        }
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Parse `YYYY-MM-DD`, ignoring any time part.
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// When the note came into being: its `created` or `date` field, else the
+/// file's creation or modification time.
+fn base_date(
+    note: &IndexedNote,
+    fm: &serde_json::Map<String, serde_json::Value>,
+) -> NaiveDate {
+    ["created", "date"]
+        .iter()
+        .filter_map(|key| fm.get(*key).and_then(value_string))
+        .find_map(|v| parse_date(&v))
+        .unwrap_or_else(|| note.created.unwrap_or(note.modified).date_naive())
+}
+
+/// One journaled move. Paths are vault-relative.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Identifier shared by all moves of one run.
+    pub run: String,
+    pub at: DateTime<Utc>,
+    pub action: ExpireAction,
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// Moves made (or undone) and the notes that could not be moved.
+#[derive(Debug, Default)]
+pub struct MoveOutcome {
+    pub moved: Vec<JournalEntry>,
+    pub failed: Vec<(PathBuf, ExpireError)>,
+}
+
+/// Archive or trash `notes`, journaling each move under `run`.
+///
+/// A note whose destination already exists is skipped and reported.
+pub fn expire_notes(
+    vault_root: &Path,
+    notes: &[ExpiredNote],
+    run: &str,
+) -> Result<MoveOutcome, ExpireError> {
+    let resolver = PathResolver::new(vault_root);
+    let mut outcome = MoveOutcome::default();
+
+    for note in notes {
+        let dest = match note.action {
+            ExpireAction::Archive => resolver.archived_note(&note.path),
+            ExpireAction::Trash => resolver.trash_dir(run).join(&note.path),
+        };
+        let entry = JournalEntry {
+            run: run.to_string(),
+            at: Utc::now(),
+            action: note.action,
+            from: note.path.clone(),
+            to: dest.strip_prefix(vault_root).unwrap_or(&dest).to_path_buf(),
+        };
+        match move_file(&vault_root.join(&note.path), &dest) {
+            Ok(()) => {
+                append_journal(&resolver.expire_journal(), &entry)?;
+                outcome.moved.push(entry);
+            }
+            Err(e) => outcome.failed.push((note.path.clone(), e)),
+        }
+    }
+    Ok(outcome)
+}
+
+/// Move the notes of the most recent run back to where they were.
+///
+/// Restored moves are dropped from the journal; moves that could not be
+/// undone stay so the undo can be retried.
+pub fn undo_last_run(vault_root: &Path) -> Result<MoveOutcome, ExpireError> {
+    let journal_path = PathResolver::new(vault_root).expire_journal();
+    let entries = read_journal(&journal_path)?;
+    let run = entries.last().map(|e| e.run.clone()).ok_or(ExpireError::NothingToUndo)?;
+
+    let mut outcome = MoveOutcome::default();
+    let mut kept = Vec::new();
+    for entry in entries.into_iter().rev() {
+        if entry.run != run {
+            kept.push(entry);
+            continue;
+        }
+        match move_file(&vault_root.join(&entry.to), &vault_root.join(&entry.from)) {
+            Ok(()) => outcome.moved.push(entry),
+            Err(e) => {
+                outcome.failed.push((entry.from.clone(), e));
+                kept.push(entry);
+            }
+        }
+    }
+    kept.reverse();
+
+    let mut content = String::new();
+    for entry in &kept {
+        content
+            .push_str(&serde_json::to_string(entry).expect("journal entry serializes"));
+        content.push('\n');
+    }
+    fs::write(&journal_path, content)
+        .map_err(|source| ExpireError::Io { path: journal_path.clone(), source })?;
+    Ok(outcome)
+}
+
+/// Read every entry of the expire journal (empty if there is none).
+pub fn read_journal(path: &Path) -> Result<Vec<JournalEntry>, ExpireError> {
+    let content = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(source) => return Err(ExpireError::Io { path: path.to_path_buf(), source }),
+    };
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| ExpireError::Journal { line: i + 1, message: e.to_string() })
+        })
+        .collect()
+}
+
+fn append_journal(path: &Path, entry: &JournalEntry) -> Result<(), ExpireError> {
+    let io_err = |source| ExpireError::Io { path: path.to_path_buf(), source };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(io_err)?;
+    }
+    let mut file =
+        fs::OpenOptions::new().create(true).append(true).open(path).map_err(io_err)?;
+    let line = serde_json::to_string(entry).expect("journal entry serializes");
+    writeln!(file, "{line}").map_err(io_err)
+}
+
+fn move_file(from: &Path, to: &Path) -> Result<(), ExpireError> {
+    if to.exists() {
+        return Err(ExpireError::Exists(to.to_path_buf()));
+    }
+    let io_err = |source| ExpireError::Io { path: from.to_path_buf(), source };
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent).map_err(io_err)?;
+    }
+    fs::rename(from, to).map_err(io_err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::IndexBuilder;
+    use tempfile::TempDir;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn vault(notes: &[(&str, &str)]) -> (TempDir, IndexDb) {
+        let dir = TempDir::new().unwrap();
+        for (path, content) in notes {
+            let full = dir.path().join(path);
+            fs::create_dir_all(full.parent().unwrap()).unwrap();
+            fs::write(full, content).unwrap();
+        }
+        let db = IndexDb::open_in_memory().unwrap();
+        IndexBuilder::new(&db, dir.path()).full_reindex(None).unwrap();
+        (dir, db)
+    }
+
+    #[test]
+    fn ttl_arithmetic() {
+        assert_eq!(add_ttl(date("2025-01-31"), "30d").unwrap(), date("2025-03-02"));
+        assert_eq!(add_ttl(date("2025-01-15"), "2w").unwrap(), date("2025-01-29"));
+        assert_eq!(add_ttl(date("2025-01-15"), "3M").unwrap(), date("2025-04-15"));
+        assert_eq!(add_ttl(date("2024-02-29"), "1y").unwrap(), date("2025-02-28"));
+        for bad in ["", "d", "12h", "5m", "1 week", "-3d"] {
+            assert!(add_ttl(date("2025-01-15"), bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn frontmatter_and_type_policies() {
+        let (_dir, db) = vault(&[
+            ("a.md", "---\nexpires: 2025-03-01\n---\n# A\n"),
+            ("b.md", "---\ncreated: 2025-01-01\nttl: 30d\non_expire: trash\n---\n# B\n"),
+            ("c.md", "---\ncreated: 2025-02-20\nttl: 30d\n---\n# C\n"),
+            ("d.md", "---\nttl: soon\n---\n# D\n"),
+            ("m1.md", "---\ntype: meeting\ndate: 2024-01-10\n---\n# M1\n"),
+            ("m2.md", "---\ntype: meeting\ndate: 2025-01-10\n---\n# M2\n"),
+            // Its own expiry date wins over the type rule
+            ("m3.md", "---\ntype: meeting\ndate: 2020-01-01\nexpires: 2026-01-01\n---\n"),
+            ("i1.md", "---\ntype: inbox\ncreated: 2025-01-01\nstatus: resolved\n---\n"),
+            ("i2.md", "---\ntype: inbox\ncreated: 2025-01-01\nstatus: open\n---\n"),
+            ("Archive/old.md", "---\nexpires: 2020-01-01\n---\n# Old\n"),
+        ]);
+        let mut meeting = TypeDefinition::empty("meeting");
+        meeting.retention = Some(RetentionRule::parse("1y", None, &[]).unwrap());
+        let mut inbox = TypeDefinition::empty("inbox");
+        inbox.retention = Some(
+            RetentionRule::parse("1w", Some("trash"), &["status=resolved".into()])
+                .unwrap(),
+        );
+
+        let scan = find_expired(&db, &[meeting, inbox], date("2025-03-10")).unwrap();
+        let found: Vec<_> = scan
+            .expired
+            .iter()
+            .map(|n| (n.path.to_str().unwrap(), n.action, n.reason.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("a.md", ExpireAction::Archive, "expires 2025-03-01"),
+                ("b.md", ExpireAction::Trash, "ttl 30d"),
+                ("i1.md", ExpireAction::Trash, "inbox retention 1w"),
+                ("m1.md", ExpireAction::Archive, "meeting retention 1y"),
+            ]
+        );
+        assert_eq!(scan.invalid.len(), 1);
+        assert_eq!(scan.invalid[0].0, Path::new("d.md"));
+    }
+
+    #[test]
+    fn moves_are_journaled_and_undoable() {
+        let (dir, db) = vault(&[
+            ("Meetings/old.md", "---\nexpires: 2025-01-01\n---\n"),
+            ("scratch.md", "---\nexpires: 2025-01-01\non_expire: trash\n---\n"),
+        ]);
+        let scan = find_expired(&db, &[], date("2025-02-01")).unwrap();
+
+        let outcome = expire_notes(dir.path(), &scan.expired, "run-1").unwrap();
+        assert!(outcome.failed.is_empty());
+        assert!(dir.path().join("Archive/Meetings/old.md").exists());
+        assert!(dir.path().join(".mdvault/trash/run-1/scratch.md").exists());
+        assert!(!dir.path().join("scratch.md").exists());
+
+        let journal =
+            read_journal(&PathResolver::new(dir.path()).expire_journal()).unwrap();
+        assert_eq!(journal.len(), 2);
+        assert_eq!(journal[0].to, Path::new("Archive/Meetings/old.md"));
+
+        let undone = undo_last_run(dir.path()).unwrap();
+        assert_eq!(undone.moved.len(), 2);
+        assert!(dir.path().join("Meetings/old.md").exists());
+        assert!(dir.path().join("scratch.md").exists());
+        assert!(matches!(undo_last_run(dir.path()), Err(ExpireError::NothingToUndo)));
+    }
+
+    #[test]
+    fn existing_destination_is_not_overwritten() {
+        let (dir, db) = vault(&[
+            ("note.md", "---\nexpires: 2025-01-01\n---\nnew\n"),
+            ("Archive/note.md", "old\n"),
+        ]);
+        let scan = find_expired(&db, &[], date("2025-02-01")).unwrap();
+        let outcome = expire_notes(dir.path(), &scan.expired, "run-1").unwrap();
+
+        assert!(matches!(outcome.failed[..], [(_, ExpireError::Exists(_))]));
+        assert_eq!(
+            fs::read_to_string(dir.path().join("Archive/note.md")).unwrap(),
+            "old\n"
+        );
+        assert!(dir.path().join("note.md").exists());
+    }
+}
@@ -52,7 +52,8 @@ pub fn parse(content: &str) -> Result<ParsedDocument, FrontmatterParseError> {
         let frontmatter: Frontmatter = if yaml_content.trim().is_empty() {
             Frontmatter::default()
         } else {
-            serde_yaml::from_str(yaml_content.trim())?
+            // Untrimmed: trailing newlines belong to block scalars like `key: |`
+            serde_yaml::from_str(yaml_content)?
         };
 
         Ok(ParsedDocument { frontmatter: Some(frontmatter), body })
@@ -64,17 +65,14 @@ pub fn parse(content: &str) -> Result<ParsedDocument, FrontmatterParseError> {
 
 /// Find the position of closing `---` delimiter.
 fn find_closing_delimiter(content: &str) -> Option<usize> {
-    // Look for --- at the start of a line
-    for (i, line) in content.lines().enumerate() {
+    // Look for --- at the start of a line, tracking byte offsets including
+    // the line terminator so CRLF documents resolve to the right position
+    let mut pos = 0;
+    for line in content.split_inclusive('\n') {
         if line.trim() == "---" {
-            // Calculate byte position
-            let pos: usize = content
-                .lines()
-                .take(i)
-                .map(|l| l.len() + 1) // +1 for newline
-                .sum();
             return Some(pos);
         }
+        pos += line.len();
     }
     None
 }
@@ -251,4 +249,20 @@ mod tests {
         assert!(raw.contains("tags: [daily]"), "raw frontmatter should contain tags");
         assert_eq!(body, "# Daily");
     }
+
+    #[test]
+    fn parse_crlf_frontmatter() {
+        let content = "---\r\ntitle: Hello\r\ntype: task\r\n---\r\n# Content\r\n";
+        let result = parse(content).unwrap();
+        let fm = result.frontmatter.unwrap();
+        assert_eq!(fm.fields.get("type").and_then(|v| v.as_str()), Some("task"));
+        assert_eq!(result.body, "# Content\r\n");
+    }
+
+    #[test]
+    fn parse_keeps_block_scalar_trailing_newline() {
+        let content = "---\nnote: |\n  line\n---\nbody";
+        let fm = parse(content).unwrap().frontmatter.unwrap();
+        assert_eq!(fm.fields.get("note").and_then(|v| v.as_str()), Some("line\n"));
+    }
 }
@@ -15,7 +15,10 @@ pub fn serialize_with_order(doc: &ParsedDocument, order: Option<&[String]>) -> S
         && !fm.fields.is_empty()
     {
         let yaml = serialize_frontmatter(&fm.fields, order);
-        return format!("---\n{}---\n\n{}", yaml, doc.body);
+        // Exactly one blank line separates frontmatter and body, so parsing
+        // and serializing again does not keep adding blank lines
+        let body = doc.body.trim_start_matches(['\r', '\n']);
+        return format!("---\n{}---\n\n{}", yaml, body);
     }
     doc.body.clone()
 }
@@ -101,4 +104,12 @@ mod tests {
         assert_eq!(fm.fields.get("count").and_then(|v| v.as_i64()), Some(42));
         assert!(reparsed.body.contains("# Body"));
     }
+
+    #[test]
+    fn serialize_round_trip_does_not_add_blank_lines() {
+        let once = serialize(&parse("---\ntitle: Hello\n---\n\n# Body\n").unwrap());
+        let twice = serialize(&parse(&once).unwrap());
+        assert_eq!(once, "---\ntitle: Hello\n---\n\n# Body\n");
+        assert_eq!(once, twice);
+    }
 }
@@ -6,7 +6,9 @@ use chrono::{DateTime, Utc};
 use thiserror::Error;
 
 use super::db::{IndexDb, IndexError};
-use super::types::{IndexedLink, IndexedNote};
+use super::types::{IndexedCheckbox, IndexedLink, IndexedNote, NoteAnchor};
+use crate::scripting::{NoteHook, apply_note_hook};
+use crate::types::TypeRegistry;
 use crate::vault::{
     VaultWalker, VaultWalkerError, WalkedFile, content_hash, extract_note,
 };
@@ -72,12 +74,16 @@ pub struct IndexBuilder<'a> {
     db: &'a IndexDb,
     vault_root: &'a Path,
     excluded_folders: Vec<std::path::PathBuf>,
+    /// Gitignore-style patterns skipped on top of `.mdvignore`.
+    exclude_patterns: Vec<String>,
+    /// Type definitions whose `on_index` hooks run on each indexed note.
+    types: Option<&'a TypeRegistry>,
 }
 
 impl<'a> IndexBuilder<'a> {
     /// Create a new index builder.
     pub fn new(db: &'a IndexDb, vault_root: &'a Path) -> Self {
-        Self { db, vault_root, excluded_folders: Vec::new() }
+        Self::with_exclusions(db, vault_root, Vec::new())
     }
 
     /// Create a new index builder with folder exclusions.
@@ -86,7 +92,41 @@ impl<'a> IndexBuilder<'a> {
         vault_root: &'a Path,
         excluded_folders: Vec<std::path::PathBuf>,
     ) -> Self {
-        Self { db, vault_root, excluded_folders }
+        Self {
+            db,
+            vault_root,
+            excluded_folders,
+            exclude_patterns: Vec::new(),
+            types: None,
+        }
+    }
+
+    /// Also skip files matching these gitignore-style patterns.
+    pub fn exclude(mut self, patterns: Vec<String>) -> Self {
+        self.exclude_patterns = patterns;
+        self
+    }
+
+    /// Run the `on_index` hooks of these types on the notes indexed.
+    pub fn with_types(mut self, types: &'a TypeRegistry) -> Self {
+        self.types = Some(types);
+        self
+    }
+
+    /// Walker over the vault with every exclusion applied.
+    fn walker(&self) -> Result<VaultWalker, BuilderError> {
+        Ok(VaultWalker::with_exclusions(self.vault_root, self.excluded_folders.clone())?
+            .exclude(&self.exclude_patterns)?)
+    }
+
+    /// Drop an indexed note the walk did not find. Notes whose file is gone
+    /// leave a tombstone; notes that are merely excluded do not.
+    fn drop_unwalked(&self, path: &Path) -> Result<bool, BuilderError> {
+        if self.vault_root.join(path).exists() {
+            Ok(self.db.delete_note(path)?)
+        } else {
+            Ok(self.db.bury_note(path)?)
+        }
     }
 
     /// Perform a full reindex of the vault.
@@ -99,40 +139,52 @@ impl<'a> IndexBuilder<'a> {
         let mut stats = IndexStats::default();
 
         // Walk the vault with exclusions
-        let walker =
-            VaultWalker::with_exclusions(self.vault_root, self.excluded_folders.clone())?;
-        let files = walker.walk()?;
+        let files = self.walker()?.walk()?;
         stats.files_found = files.len();
 
-        // Clear existing index
-        self.db.clear_all()?;
-
-        // Phase 1: Index all notes
-        for (i, file) in files.iter().enumerate() {
-            if let Some(ref cb) = progress {
-                cb(i + 1, files.len(), &file.relative_path.to_string_lossy());
+        // Rebuild in one write transaction: readers keep the old index until
+        // the new one is complete, and other writers wait their turn
+        self.db.write_transaction(|| {
+            // Leave tombstones for notes gone since the last index
+            let walked: std::collections::HashSet<&Path> =
+                files.iter().map(|f| f.relative_path.as_path()).collect();
+            for path in self.db.get_all_paths()? {
+                if !walked.contains(path.as_path()) {
+                    self.drop_unwalked(&path)?;
+                }
             }
 
-            match self.index_note(file) {
-                Ok(link_count) => {
-                    stats.notes_indexed += 1;
-                    stats.links_indexed += link_count;
+            // Clear existing index
+            self.db.clear_all()?;
+
+            // Phase 1: Index all notes
+            for (i, file) in files.iter().enumerate() {
+                if let Some(ref cb) = progress {
+                    cb(i + 1, files.len(), &file.relative_path.to_string_lossy());
                 }
-                Err(e) => {
-                    // Log error but continue indexing
-                    tracing::warn!(
-                        "Failed to index {}: {}",
-                        file.relative_path.display(),
-                        e
-                    );
-                    stats.notes_skipped += 1;
+
+                match self.index_note(file) {
+                    Ok(link_count) => {
+                        stats.notes_indexed += 1;
+                        stats.links_indexed += link_count;
+                    }
+                    Err(e) => {
+                        // Log error but continue indexing
+                        tracing::warn!(
+                            "Failed to index {}: {}",
+                            file.relative_path.display(),
+                            e
+                        );
+                        stats.notes_skipped += 1;
+                    }
                 }
             }
-        }
 
-        // Phase 2: Resolve link targets
-        self.db.resolve_link_targets()?;
-        stats.broken_links = self.db.count_broken_links()? as usize;
+            // Phase 2: Resolve link targets
+            self.db.resolve_link_targets()?;
+            stats.broken_links = self.db.count_broken_links()? as usize;
+            Ok::<_, BuilderError>(())
+        })?;
 
         stats.duration_ms = start.elapsed().as_millis() as u64;
         Ok(stats)
@@ -148,67 +200,84 @@ impl<'a> IndexBuilder<'a> {
         let mut stats = IndexStats::default();
 
         // Phase 1: Walk the vault and collect all current files (with exclusions)
-        let walker =
-            VaultWalker::with_exclusions(self.vault_root, self.excluded_folders.clone())?;
-        let files = walker.walk()?;
+        let files = self.walker()?.walk()?;
         stats.files_found = files.len();
 
-        // Phase 2: Get all currently indexed paths for deletion detection
-        let indexed_paths: std::collections::HashSet<std::path::PathBuf> =
-            self.db.get_all_paths()?.into_iter().collect();
+        self.db.write_transaction(|| {
+            // Phase 2: Get all currently indexed paths for deletion detection
+            let indexed_paths: std::collections::HashSet<std::path::PathBuf> =
+                self.db.get_all_paths()?.into_iter().collect();
 
-        // Track which paths we've seen in the vault
-        let mut seen_paths: std::collections::HashSet<std::path::PathBuf> =
-            std::collections::HashSet::with_capacity(files.len());
+            // Track which paths we've seen in the vault
+            let mut seen_paths: std::collections::HashSet<std::path::PathBuf> =
+                std::collections::HashSet::with_capacity(files.len());
 
-        // Phase 3: Classify and process each file
-        for (i, file) in files.iter().enumerate() {
-            if let Some(ref cb) = progress {
-                cb(i + 1, files.len(), &file.relative_path.to_string_lossy());
-            }
+            // Phase 3: Classify and process each file
+            for (i, file) in files.iter().enumerate() {
+                if let Some(ref cb) = progress {
+                    cb(i + 1, files.len(), &file.relative_path.to_string_lossy());
+                }
 
-            seen_paths.insert(file.relative_path.clone());
+                seen_paths.insert(file.relative_path.clone());
 
-            // Classify the change
-            let change = self.classify_change(file)?;
+                // Classify the change
+                let change = self.classify_change(file)?;
 
-            match change {
-                FileChange::Unchanged => {
-                    stats.files_unchanged += 1;
-                }
-                FileChange::Added | FileChange::Modified => match self.index_note(file) {
-                    Ok(link_count) => {
-                        stats.notes_indexed += 1;
-                        stats.links_indexed += link_count;
-                        if change == FileChange::Added {
-                            stats.files_added += 1;
-                        } else {
-                            stats.files_updated += 1;
-                        }
+                match change {
+                    FileChange::Unchanged => {
+                        stats.files_unchanged += 1;
                     }
-                    Err(e) => {
-                        tracing::warn!(
-                            "Failed to index {}: {}",
-                            file.relative_path.display(),
-                            e
-                        );
-                        stats.notes_skipped += 1;
+                    FileChange::Added | FileChange::Modified => {
+                        match self.index_note(file) {
+                            Ok(link_count) => {
+                                stats.notes_indexed += 1;
+                                stats.links_indexed += link_count;
+                                if change == FileChange::Added {
+                                    stats.files_added += 1;
+                                } else {
+                                    stats.files_updated += 1;
+                                }
+                            }
+                            Err(e) => {
+                                tracing::warn!(
+                                    "Failed to index {}: {}",
+                                    file.relative_path.display(),
+                                    e
+                                );
+                                stats.notes_skipped += 1;
+                            }
+                        }
                     }
-                },
+                }
             }
-        }
 
-        // Phase 4: Detect and delete removed files
-        for indexed_path in &indexed_paths {
-            if !seen_paths.contains(indexed_path) && self.db.delete_note(indexed_path)? {
-                stats.files_deleted += 1;
-                tracing::debug!("Deleted from index: {}", indexed_path.display());
+            // Phase 4: Detect removed files, leaving tombstones
+            for indexed_path in &indexed_paths {
+                if !seen_paths.contains(indexed_path)
+                    && self.drop_unwalked(indexed_path)?
+                {
+                    stats.files_deleted += 1;
+                    tracing::debug!("Deleted from index: {}", indexed_path.display());
+                }
+            }
+
+            // Phase 5: Backfill full-text rows for notes that were skipped as
+            // unchanged but have no (or outdated) searchable text
+            for note in self.db.get_notes_with_stale_text()? {
+                if let Err(e) = self.index_note_text(&note) {
+                    tracing::warn!(
+                        "Failed to index text of {}: {}",
+                        note.path.display(),
+                        e
+                    );
+                }
             }
-        }
 
-        // Phase 5: Resolve link targets (handles newly valid links)
-        self.db.resolve_link_targets()?;
-        stats.broken_links = self.db.count_broken_links()? as usize;
+            // Phase 6: Resolve link targets (handles newly valid links)
+            self.db.resolve_link_targets()?;
+            stats.broken_links = self.db.count_broken_links()? as usize;
+            Ok::<_, BuilderError>(())
+        })?;
 
         stats.duration_ms = start.elapsed().as_millis() as u64;
         Ok(stats)
@@ -249,6 +318,7 @@ impl<'a> IndexBuilder<'a> {
                 source: e,
             }
         })?;
+        let content = self.apply_index_hook(file, content);
 
         // Compute content hash
         let hash =
@@ -273,11 +343,21 @@ impl<'a> IndexBuilder<'a> {
             modified,
             frontmatter_json: extracted.frontmatter_json,
             content_hash: hash,
+            word_count: extracted.word_count,
+            heading_json: serde_json::to_string(&extracted.headings).ok(),
         };
 
         // Insert note and get ID
         let note_id = self.db.upsert_note(&note)?;
 
+        // Update the full-text row (no-op when the hash is unchanged)
+        self.db.upsert_note_text(
+            note_id,
+            &note.title,
+            &extracted.body,
+            &note.content_hash,
+        )?;
+
         // Delete existing links for this note (in case of update)
         self.db.delete_links_from(note_id)?;
 
@@ -289,6 +369,7 @@ impl<'a> IndexBuilder<'a> {
                 source_id: note_id,
                 target_id: None, // Resolved in phase 2
                 target_path: link.target,
+                target_anchor: link.anchor,
                 link_text: link.text,
                 link_type: link.link_type,
                 context: link.context,
@@ -297,9 +378,88 @@ impl<'a> IndexBuilder<'a> {
             self.db.insert_link(&indexed_link)?;
         }
 
+        // Replace heading and block anchors
+        self.db.delete_anchors_from(note_id)?;
+        for anchor in extracted.anchors {
+            self.db.insert_anchor(&NoteAnchor {
+                note_id,
+                kind: anchor.kind,
+                name: anchor.name,
+                line_number: anchor.line_number,
+            })?;
+        }
+
+        // Replace queryable frontmatter fields, plus any fields plugins derive
+        self.db.replace_note_fields(note_id, note.frontmatter_json.as_deref())?;
+        if let Some(plugins) = crate::plugins::active() {
+            for (name, fields) in plugins.enrich(&note, &extracted.body) {
+                self.db.add_note_fields(note_id, name, &fields)?;
+            }
+        }
+
+        // Replace checkbox items
+        self.db.delete_checkboxes_from(note_id)?;
+        for checkbox in extracted.checkboxes {
+            self.db.insert_checkbox(&IndexedCheckbox {
+                id: None,
+                note_id,
+                note_path: note.path.clone(),
+                line_number: checkbox.line_number,
+                text: checkbox.text,
+                status: checkbox.status,
+                heading: checkbox.heading,
+            })?;
+        }
+
         Ok(link_count)
     }
 
+    /// Let the note's type update its frontmatter through `on_index`,
+    /// writing back what changed. A failing hook is logged and the note
+    /// indexed as it is.
+    fn apply_index_hook(&self, file: &WalkedFile, content: String) -> String {
+        let Some(types) = self.types else {
+            return content;
+        };
+        let path = &file.relative_path;
+        match apply_note_hook(types, NoteHook::Index, path, &content) {
+            Ok(Some(updated)) => match std::fs::write(&file.absolute_path, &updated) {
+                Ok(()) => updated,
+                Err(e) => {
+                    tracing::warn!("Failed to write {}: {}", path.display(), e);
+                    content
+                }
+            },
+            Ok(None) => content,
+            Err(e) => {
+                tracing::warn!("on_index hook failed for {}: {}", path.display(), e);
+                content
+            }
+        }
+    }
+
+    /// Refresh the full-text row of an already indexed note from disk.
+    pub(super) fn index_note_text(&self, note: &IndexedNote) -> Result<(), BuilderError> {
+        let Some(note_id) = note.id else {
+            return Ok(());
+        };
+        let absolute_path = self.vault_root.join(&note.path);
+        let content = std::fs::read_to_string(&absolute_path).map_err(|e| {
+            BuilderError::FileRead {
+                path: absolute_path.display().to_string(),
+                source: e,
+            }
+        })?;
+        let extracted = extract_note(&content, &note.path);
+        self.db.upsert_note_text(
+            note_id,
+            &note.title,
+            &extracted.body,
+            &note.content_hash,
+        )?;
+        Ok(())
+    }
+
     /// Reindex a single file by its path relative to the vault root.
     pub fn reindex_file(&self, relative_path: &Path) -> Result<(), BuilderError> {
         let absolute_path = self.vault_root.join(relative_path);
@@ -314,7 +474,7 @@ impl<'a> IndexBuilder<'a> {
             modified: metadata.modified().unwrap_or(std::time::SystemTime::now()),
             size: metadata.len(),
         };
-        self.index_note(&file)?;
+        self.db.write_transaction(|| self.index_note(&file))?;
         Ok(())
     }
 }
@@ -322,6 +482,7 @@ impl<'a> IndexBuilder<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::TypeDefinition;
     use std::fs;
     use tempfile::TempDir;
 
@@ -521,6 +682,42 @@ Links to [Note One](../note1.md).
         assert_eq!(stats.notes_indexed, 1);
     }
 
+    #[test]
+    fn test_on_index_hook_updates_frontmatter() {
+        let vault = TempDir::new().unwrap();
+        fs::write(
+            vault.path().join("standup.md"),
+            "---\ntype: meeting\ntitle: Standup\n---\n\nThree short words.\n",
+        )
+        .unwrap();
+        let mut typedef = TypeDefinition::empty("meeting");
+        typedef.has_on_index_hook = true;
+        typedef.lua_source = r#"
+            return {
+                on_index = function(note)
+                    note.frontmatter.slug = string.lower(note.frontmatter.title)
+                    return note
+                end
+            }
+        "#
+        .to_string();
+        let mut types = TypeRegistry::new();
+        types.register(typedef).unwrap();
+
+        let db = IndexDb::open_in_memory().unwrap();
+        let builder = IndexBuilder::new(&db, vault.path()).with_types(&types);
+        builder.incremental_reindex(None).unwrap();
+
+        let content = fs::read_to_string(vault.path().join("standup.md")).unwrap();
+        assert!(content.contains("slug: standup"), "{content}");
+        let note = db.get_note_by_path(Path::new("standup.md")).unwrap().unwrap();
+        assert!(note.frontmatter_json.unwrap().contains("\"slug\":\"standup\""));
+
+        // Once applied the hook changes nothing, so the note stays unchanged
+        let stats = builder.incremental_reindex(None).unwrap();
+        assert_eq!(stats.files_unchanged, 1);
+    }
+
     #[test]
     fn test_incremental_file_added() {
         let vault = create_test_vault();
@@ -563,6 +760,42 @@ Links to [Note One](../note1.md).
         assert_eq!(db.count_notes().unwrap(), 2);
     }
 
+    #[test]
+    fn test_vanished_notes_leave_tombstones() {
+        let vault = create_test_vault();
+        let db = IndexDb::open_in_memory().unwrap();
+        let builder = IndexBuilder::new(&db, vault.path());
+        builder.full_reindex(None).unwrap();
+
+        let note2 = fs::read_to_string(vault.path().join("note2.md")).unwrap();
+        fs::remove_file(vault.path().join("note2.md")).unwrap();
+        builder.incremental_reindex(None).unwrap();
+
+        let tombstones = db.get_tombstones(None, None).unwrap();
+        assert_eq!(tombstones.len(), 1);
+        assert_eq!(tombstones[0].path, Path::new("note2.md"));
+        assert_eq!(tombstones[0].title, "Note Two");
+        // note1 still links to it
+        assert_eq!(tombstones[0].backlinks, 1);
+
+        // Tombstones survive a full rebuild, which also notices deletions
+        fs::remove_file(vault.path().join("note1.md")).unwrap();
+        builder.full_reindex(None).unwrap();
+        let paths: Vec<_> =
+            db.get_tombstones(None, None).unwrap().into_iter().map(|t| t.path).collect();
+        assert_eq!(paths.len(), 2);
+        assert!(paths.contains(&"note1.md".into()));
+
+        // A note that comes back is no longer a tombstone
+        fs::write(vault.path().join("note2.md"), note2).unwrap();
+        builder.incremental_reindex(None).unwrap();
+        let tombstones = db.get_tombstones(None, None).unwrap();
+        assert_eq!(tombstones.len(), 1);
+        assert_eq!(tombstones[0].path, Path::new("note1.md"));
+        // note2 and note3 both link to it
+        assert_eq!(tombstones[0].backlinks, 2);
+    }
+
     #[test]
     fn test_incremental_links_updated_on_change() {
         let vault = create_test_vault();
@@ -608,4 +841,135 @@ Links to [Note One](../note1.md).
         let backlinks = db.get_backlinks(missing.id.unwrap()).unwrap();
         assert!(!backlinks.is_empty());
     }
+
+    #[test]
+    fn test_body_text_is_searchable() {
+        let vault = create_test_vault();
+        let db = IndexDb::open_in_memory().unwrap();
+        let builder = IndexBuilder::new(&db, vault.path());
+
+        builder.incremental_reindex(None).unwrap();
+
+        let query = crate::index::types::NoteQuery::default();
+        let matches = db.search_fulltext("links", &query).unwrap();
+        assert_eq!(matches.len(), 2); // note1 and note3 bodies
+
+        // Frontmatter is not part of the indexed body
+        assert!(db.search_fulltext("zettel", &query).unwrap().is_empty());
+
+        // Modified content replaces the old text
+        fs::write(vault.path().join("note1.md"), "# Note 1\n\nAbout orchids.").unwrap();
+        builder.incremental_reindex(None).unwrap();
+        assert_eq!(db.search_fulltext("orchids", &query).unwrap().len(), 1);
+        assert_eq!(db.search_fulltext("links", &query).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_incremental_backfills_missing_text() {
+        let vault = create_test_vault();
+        let db = IndexDb::open_in_memory().unwrap();
+        let builder = IndexBuilder::new(&db, vault.path());
+
+        builder.incremental_reindex(None).unwrap();
+        // Simulate an index built before full-text search existed
+        db.connection().execute("DELETE FROM notes_fts", []).unwrap();
+
+        let stats = builder.incremental_reindex(None).unwrap();
+        assert_eq!(stats.files_unchanged, 3);
+
+        let query = crate::index::types::NoteQuery::default();
+        assert_eq!(db.search_fulltext("links", &query).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_checkboxes_are_indexed() {
+        let vault = create_test_vault();
+        fs::write(
+            vault.path().join("daily.md"),
+            "# Today\n\n- [ ] Call [[note1]]\n- [x] Water plants\n",
+        )
+        .unwrap();
+        let db = IndexDb::open_in_memory().unwrap();
+        let builder = IndexBuilder::new(&db, vault.path());
+
+        builder.incremental_reindex(None).unwrap();
+
+        let query = crate::index::types::CheckboxQuery::default();
+        let items = db.query_checkboxes(&query).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].text, "Call [[note1]]");
+        assert_eq!(items[0].heading.as_deref(), Some("Today"));
+        assert_eq!(items[1].status, crate::index::types::TaskStatus::Done);
+
+        // Edits replace the note's items rather than appending
+        fs::write(vault.path().join("daily.md"), "- [ ] Only one\n").unwrap();
+        builder.incremental_reindex(None).unwrap();
+        let items = db.query_checkboxes(&query).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].text, "Only one");
+    }
+
+    #[test]
+    fn test_outline_is_indexed() {
+        let vault = create_test_vault();
+        fs::write(vault.path().join("plan.md"), "# Plan\n\nOne two three.\n").unwrap();
+        let db = IndexDb::open_in_memory().unwrap();
+        let builder = IndexBuilder::new(&db, vault.path());
+
+        builder.incremental_reindex(None).unwrap();
+        let note = db.get_note_by_path(Path::new("plan.md")).unwrap().unwrap();
+        assert_eq!(note.word_count, 4);
+        assert_eq!(note.headings().len(), 1);
+
+        fs::write(vault.path().join("plan.md"), "# Plan\n\n## Done\n").unwrap();
+        builder.incremental_reindex(None).unwrap();
+        let note = db.get_note_by_path(Path::new("plan.md")).unwrap().unwrap();
+        assert_eq!(note.word_count, 2);
+        let titles: Vec<_> = note.headings().into_iter().map(|h| h.title).collect();
+        assert_eq!(titles, ["Plan", "Done"]);
+    }
+
+    #[test]
+    fn test_fragment_links_resolve_to_anchors() {
+        let vault = create_test_vault();
+        fs::write(
+            vault.path().join("guide.md"),
+            "# Guide\n\n## Next Steps\n\nDo it. ^do-it\n\nSee [[#Next Steps]].\n",
+        )
+        .unwrap();
+        fs::write(
+            vault.path().join("reader.md"),
+            "[[guide#Next Steps]] [[guide^do-it]] [[guide#Missing]] \
+             [slug](guide.md#next-steps)\n",
+        )
+        .unwrap();
+        let db = IndexDb::open_in_memory().unwrap();
+        let builder = IndexBuilder::new(&db, vault.path());
+
+        builder.incremental_reindex(None).unwrap();
+
+        let guide = db.get_note_by_path(Path::new("guide.md")).unwrap().unwrap();
+        let reader = db.get_note_by_path(Path::new("reader.md")).unwrap().unwrap();
+        let links = db.get_outgoing_links(reader.id.unwrap()).unwrap();
+        assert_eq!(links.len(), 4);
+        assert!(links.iter().all(|l| l.target_id == guide.id));
+
+        let resolved: Vec<Option<String>> =
+            links.iter().map(|l| db.resolve_anchor(l).unwrap().map(|a| a.name)).collect();
+        assert_eq!(
+            resolved,
+            vec![
+                Some("Next Steps".to_string()),
+                Some("do-it".to_string()),
+                None,
+                Some("Next Steps".to_string()),
+            ]
+        );
+        assert_eq!(db.count_broken_anchors().unwrap(), 1);
+
+        // A fragment-only link points into its own note, without un-orphaning it
+        let own = db.get_outgoing_links(guide.id.unwrap()).unwrap();
+        assert_eq!(own[0].target_id, guide.id);
+        assert!(db.resolve_anchor(&own[0]).unwrap().is_some());
+    }
 }
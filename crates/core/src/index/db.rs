@@ -1,12 +1,18 @@
 //! Database connection and operations.
 
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
-use rusqlite::{Connection, OptionalExtension, params};
+use rusqlite::{Connection, OpenFlags, OptionalExtension, params};
 use thiserror::Error;
 
-use super::schema::{SchemaError, init_schema};
-use super::types::{IndexedLink, IndexedNote, LinkType, NoteQuery, NoteType};
+use super::schema::{SchemaError, init_schema, schema_is_current};
+use super::search::QueryParseError;
+use super::types::{
+    AnchorKind, BodyHit, CheckboxQuery, Excerpt, FieldFilter, FieldOp, FullTextMatch,
+    IndexedCheckbox, IndexedLink, IndexedNote, LinkType, NoteAnchor, NoteQuery, NoteType,
+    TaskStatus,
+};
 
 #[derive(Debug, Error)]
 pub enum IndexError {
@@ -21,9 +27,18 @@ pub enum IndexError {
 
     #[error("Invalid data: {0}")]
     InvalidData(String),
+
+    #[error("Invalid search query: {0}")]
+    InvalidQuery(#[from] QueryParseError),
+
+    #[error("Ranking hook failed for {path}: {message}")]
+    Ranking { path: String, message: String },
 }
 
 /// Vault index database handle.
+/// `index_meta` key holding the database size after the last vacuum.
+const VACUUMED_SIZE_KEY: &str = "vacuumed_size";
+
 pub struct IndexDb {
     conn: Connection,
 }
@@ -41,6 +56,32 @@ impl IndexDb {
         Ok(Self { conn })
     }
 
+    /// Open the index at the given path for reading only.
+    ///
+    /// Query commands use this so they never contend with a writer: in WAL
+    /// mode they keep reading the last committed index while a reindex runs.
+    /// A missing or outdated index is first created or migrated through a
+    /// short read-write open.
+    pub fn open_read_only(path: &Path) -> Result<Self, IndexError> {
+        if !path.exists() || !schema_is_current(&Self::connect_read_only(path)?)? {
+            drop(Self::open(path)?);
+        }
+        let conn = Self::connect_read_only(path)?;
+        Ok(Self { conn })
+    }
+
+    fn connect_read_only(path: &Path) -> Result<Connection, IndexError> {
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        conn.execute_batch(
+            "PRAGMA busy_timeout = 5000;
+             PRAGMA query_only = ON;",
+        )?;
+        Ok(conn)
+    }
+
     /// Create an in-memory database (for testing).
     pub fn open_in_memory() -> Result<Self, IndexError> {
         let conn = Connection::open_in_memory()?;
@@ -54,6 +95,36 @@ impl IndexDb {
         &self.conn
     }
 
+    /// Run `f` inside a write transaction.
+    ///
+    /// `BEGIN IMMEDIATE` takes the write lock up front, so writers run one at
+    /// a time: a second one waits out the busy timeout instead of failing
+    /// halfway through its changes. Readers see the index as it was until
+    /// the commit. An error rolls everything back. Called inside another
+    /// write transaction, `f` simply joins it.
+    pub fn write_transaction<T, E>(
+        &self,
+        f: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, E>
+    where
+        E: From<IndexError>,
+    {
+        if !self.conn.is_autocommit() {
+            return f();
+        }
+        self.conn.execute_batch("BEGIN IMMEDIATE").map_err(IndexError::from)?;
+        match f() {
+            Ok(value) => {
+                self.conn.execute_batch("COMMIT").map_err(IndexError::from)?;
+                Ok(value)
+            }
+            Err(e) => {
+                let _ = self.conn.execute_batch("ROLLBACK");
+                Err(e)
+            }
+        }
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // Notes CRUD
     // ─────────────────────────────────────────────────────────────────────────
@@ -61,8 +132,8 @@ impl IndexDb {
     /// Insert a new note into the index.
     pub fn insert_note(&self, note: &IndexedNote) -> Result<i64, IndexError> {
         self.conn.execute(
-            "INSERT INTO notes (path, note_type, title, created_at, modified_at, frontmatter_json, content_hash)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO notes (path, note_type, title, created_at, modified_at, frontmatter_json, content_hash, word_count, heading_json)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             params![
                 note.path.to_string_lossy(),
                 note.note_type.as_str(),
@@ -71,6 +142,8 @@ impl IndexDb {
                 note.modified.to_rfc3339(),
                 note.frontmatter_json,
                 note.content_hash,
+                note.word_count,
+                note.heading_json,
             ],
         )?;
         Ok(self.conn.last_insert_rowid())
@@ -86,8 +159,9 @@ impl IndexDb {
             "UPDATE notes SET
                 path = ?1, note_type = ?2, title = ?3,
                 created_at = ?4, modified_at = ?5,
-                frontmatter_json = ?6, content_hash = ?7
-             WHERE id = ?8",
+                frontmatter_json = ?6, content_hash = ?7,
+                word_count = ?8, heading_json = ?9
+             WHERE id = ?10",
             params![
                 note.path.to_string_lossy(),
                 note.note_type.as_str(),
@@ -96,6 +170,8 @@ impl IndexDb {
                 note.modified.to_rfc3339(),
                 note.frontmatter_json,
                 note.content_hash,
+                note.word_count,
+                note.heading_json,
                 id,
             ],
         )?;
@@ -109,15 +185,17 @@ impl IndexDb {
     /// Upsert a note (insert or update based on path).
     pub fn upsert_note(&self, note: &IndexedNote) -> Result<i64, IndexError> {
         self.conn.execute(
-            "INSERT INTO notes (path, note_type, title, created_at, modified_at, frontmatter_json, content_hash)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "INSERT INTO notes (path, note_type, title, created_at, modified_at, frontmatter_json, content_hash, word_count, heading_json)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
              ON CONFLICT(path) DO UPDATE SET
                 note_type = excluded.note_type,
                 title = excluded.title,
                 created_at = excluded.created_at,
                 modified_at = excluded.modified_at,
                 frontmatter_json = excluded.frontmatter_json,
-                content_hash = excluded.content_hash",
+                content_hash = excluded.content_hash,
+                word_count = excluded.word_count,
+                heading_json = excluded.heading_json",
             params![
                 note.path.to_string_lossy(),
                 note.note_type.as_str(),
@@ -126,6 +204,8 @@ impl IndexDb {
                 note.modified.to_rfc3339(),
                 note.frontmatter_json,
                 note.content_hash,
+                note.word_count,
+                note.heading_json,
             ],
         )?;
 
@@ -146,7 +226,8 @@ impl IndexDb {
     ) -> Result<Option<IndexedNote>, IndexError> {
         self.conn
             .query_row(
-                "SELECT id, path, note_type, title, created_at, modified_at, frontmatter_json, content_hash
+                "SELECT id, path, note_type, title, created_at, modified_at, frontmatter_json, content_hash,
+                    word_count, heading_json
                  FROM notes WHERE path = ?1",
                 [path.to_string_lossy()],
                 Self::row_to_note,
@@ -155,11 +236,58 @@ impl IndexDb {
             .map_err(Into::into)
     }
 
+    /// Find the notes a frontmatter reference like `MCP`, `Projects/MCP/MCP`
+    /// or `[[MCP|alias]]` points to: by path, path without `.md`, or file name.
+    pub fn find_notes_by_reference(
+        &self,
+        reference: &str,
+    ) -> Result<Vec<IndexedNote>, IndexError> {
+        let target = reference.trim().trim_start_matches("[[").trim_end_matches("]]");
+        let target = target.split(['|', '#']).next().unwrap_or_default().trim();
+        if target.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut stmt = self.conn.prepare(
+            "SELECT id, path, note_type, title, created_at, modified_at, frontmatter_json, content_hash,
+                word_count, heading_json
+             FROM notes
+             WHERE path = ?1 OR path = ?1 || '.md' OR path = ?2
+                OR substr(path, -length(?2) - 1) = '/' || ?2
+             ORDER BY path",
+        )?;
+        let file_name = format!("{}.md", target.trim_end_matches(".md"));
+        let notes = stmt
+            .query_map([target, file_name.as_str()], Self::row_to_note)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(notes)
+    }
+
+    /// Find the notes whose frontmatter `key` holds `value` exactly (or
+    /// lists it).
+    pub fn find_notes_by_field(
+        &self,
+        key: &str,
+        value: &str,
+    ) -> Result<Vec<IndexedNote>, IndexError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, path, note_type, title, created_at, modified_at, frontmatter_json, content_hash,
+                word_count, heading_json
+             FROM notes
+             WHERE id IN (SELECT note_id FROM note_fields WHERE key = ?1 AND value_text = ?2)
+             ORDER BY path",
+        )?;
+        let notes = stmt
+            .query_map([key, value], Self::row_to_note)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(notes)
+    }
+
     /// Get a note by its ID.
     pub fn get_note_by_id(&self, id: i64) -> Result<Option<IndexedNote>, IndexError> {
         self.conn
             .query_row(
-                "SELECT id, path, note_type, title, created_at, modified_at, frontmatter_json, content_hash
+                "SELECT id, path, note_type, title, created_at, modified_at, frontmatter_json, content_hash,
+                    word_count, heading_json
                  FROM notes WHERE id = ?1",
                 [id],
                 Self::row_to_note,
@@ -171,7 +299,8 @@ impl IndexDb {
     /// Query notes with filters.
     pub fn query_notes(&self, query: &NoteQuery) -> Result<Vec<IndexedNote>, IndexError> {
         let mut sql = String::from(
-            "SELECT id, path, note_type, title, created_at, modified_at, frontmatter_json, content_hash
+            "SELECT id, path, note_type, title, created_at, modified_at, frontmatter_json, content_hash,
+                    word_count, heading_json
              FROM notes WHERE 1=1",
         );
         let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
@@ -196,7 +325,10 @@ impl IndexDb {
             params_vec.push(Box::new(before.to_rfc3339()));
         }
 
-        sql.push_str(" ORDER BY modified_at DESC");
+        push_field_filters(&mut sql, &mut params_vec, "notes.id", &query.fields);
+
+        // Path breaks ties so paging sees the same order every time
+        sql.push_str(" ORDER BY modified_at DESC, path ASC");
 
         if let Some(limit) = query.limit {
             sql.push_str(&format!(" LIMIT {}", limit));
@@ -226,6 +358,64 @@ impl IndexDb {
         Ok(rows > 0)
     }
 
+    /// Remove a note that vanished from the vault, leaving a tombstone.
+    ///
+    /// Use [`delete_note`](Self::delete_note) when mdv itself moves or
+    /// removes the note.
+    pub fn bury_note(&self, path: &Path) -> Result<bool, IndexError> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO note_tombstones (path, note_type, title, deleted_at)
+             SELECT path, note_type, title, ?2 FROM notes WHERE path = ?1",
+            params![path.to_string_lossy(), chrono::Utc::now().to_rfc3339()],
+        )?;
+        self.delete_note(path)
+    }
+
+    /// Tombstones of vanished notes, most recent first, with the number of
+    /// links that still point at each.
+    pub fn get_tombstones(
+        &self,
+        deleted_after: Option<chrono::DateTime<chrono::Utc>>,
+        limit: Option<u32>,
+    ) -> Result<Vec<super::types::NoteTombstone>, IndexError> {
+        let mut sql = String::from(
+            "SELECT t.path, t.note_type, t.title, t.deleted_at,
+                    (SELECT COUNT(*) FROM links l
+                     WHERE l.target_id IS NULL
+                       AND (l.target_path = t.path
+                            OR l.target_path || '.md' = t.path
+                            OR l.target_path = REPLACE(t.path, '.md', '')))
+             FROM note_tombstones t
+             WHERE ?1 IS NULL OR t.deleted_at >= ?1
+             ORDER BY t.deleted_at DESC, t.path",
+        );
+        if let Some(limit) = limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let tombstones = stmt
+            .query_map([deleted_after.map(|d| d.to_rfc3339())], |row| {
+                let path: String = row.get(0)?;
+                let type_str: String = row.get(1)?;
+                let deleted_str: String = row.get(3)?;
+                let backlinks: i64 = row.get(4)?;
+                Ok(super::types::NoteTombstone {
+                    path: path.into(),
+                    note_type: type_str.parse().unwrap(),
+                    title: row.get(2)?,
+                    deleted_at: chrono::DateTime::parse_from_rfc3339(&deleted_str)
+                        .map(|d| d.with_timezone(&chrono::Utc))
+                        .unwrap_or_else(|_| chrono::Utc::now()),
+                    backlinks: backlinks as usize,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(tombstones)
+    }
+
     /// Get content hash for a note path (for change detection).
     pub fn get_content_hash(&self, path: &Path) -> Result<Option<String>, IndexError> {
         self.conn
@@ -272,6 +462,8 @@ impl IndexDb {
                 .unwrap_or_else(|_| chrono::Utc::now()),
             frontmatter_json: row.get(6)?,
             content_hash: row.get(7)?,
+            word_count: row.get(8)?,
+            heading_json: row.get(9)?,
         })
     }
 
@@ -282,12 +474,13 @@ impl IndexDb {
     /// Insert a link between notes.
     pub fn insert_link(&self, link: &IndexedLink) -> Result<i64, IndexError> {
         self.conn.execute(
-            "INSERT INTO links (source_id, target_id, target_path, link_text, link_type, context, line_number)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO links (source_id, target_id, target_path, target_anchor, link_text, link_type, context, line_number)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
             params![
                 link.source_id,
                 link.target_id,
                 link.target_path,
+                link.target_anchor,
                 link.link_text,
                 link.link_type.as_str(),
                 link.context,
@@ -310,7 +503,8 @@ impl IndexDb {
         source_id: i64,
     ) -> Result<Vec<IndexedLink>, IndexError> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, source_id, target_id, target_path, link_text, link_type, context, line_number
+            "SELECT id, source_id, target_id, target_path, link_text, link_type, context, line_number,
+                    target_anchor
              FROM links WHERE source_id = ?1",
         )?;
 
@@ -325,7 +519,8 @@ impl IndexDb {
     /// Get incoming links (backlinks) to a note.
     pub fn get_backlinks(&self, target_id: i64) -> Result<Vec<IndexedLink>, IndexError> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, source_id, target_id, target_path, link_text, link_type, context, line_number
+            "SELECT id, source_id, target_id, target_path, link_text, link_type, context, line_number,
+                    target_anchor
              FROM links WHERE target_id = ?1",
         )?;
 
@@ -337,12 +532,32 @@ impl IndexDb {
         Ok(links)
     }
 
+    /// Links of one kind whose target is not an indexed note.
+    pub fn get_unresolved_links(
+        &self,
+        link_type: LinkType,
+    ) -> Result<Vec<IndexedLink>, IndexError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, source_id, target_id, target_path, link_text, link_type, context, line_number,
+                    target_anchor
+             FROM links WHERE target_id IS NULL AND link_type = ?1",
+        )?;
+
+        let links = stmt
+            .query_map([link_type.as_str()], Self::row_to_link)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(links)
+    }
+
     /// Find orphan notes (no incoming links).
     pub fn find_orphans(&self) -> Result<Vec<IndexedNote>, IndexError> {
         let mut stmt = self.conn.prepare(
-            "SELECT n.id, n.path, n.note_type, n.title, n.created_at, n.modified_at, n.frontmatter_json, n.content_hash
+            "SELECT n.id, n.path, n.note_type, n.title, n.created_at, n.modified_at,
+                    n.frontmatter_json, n.content_hash, n.word_count, n.heading_json
              FROM notes n
-             LEFT JOIN links l ON l.target_id = n.id
+             LEFT JOIN links l ON l.target_id = n.id AND l.source_id != n.id
              WHERE l.id IS NULL",
         )?;
 
@@ -366,6 +581,13 @@ impl IndexDb {
             [],
         )?;
 
+        // Fragment-only links ([[#Section]]) point into their own note
+        self.conn.execute(
+            "UPDATE links SET target_id = source_id
+             WHERE target_id IS NULL AND target_path = '' AND target_anchor IS NOT NULL",
+            [],
+        )?;
+
         // Count how many links now have a resolved target
         let resolved: i64 = self.conn.query_row(
             "SELECT COUNT(*) FROM links WHERE target_id IS NOT NULL",
@@ -385,6 +607,36 @@ impl IndexDb {
         Ok(count)
     }
 
+    /// Find unresolved links whose target note is now in the index.
+    ///
+    /// Returns `(source path, target path)` pairs; these resolve on the next
+    /// [`resolve_link_targets`](Self::resolve_link_targets).
+    pub fn find_resolvable_links(&self) -> Result<Vec<(PathBuf, String)>, IndexError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT s.path, l.target_path
+             FROM links l
+             JOIN notes s ON s.id = l.source_id
+             WHERE l.target_id IS NULL
+               AND EXISTS (
+                   SELECT 1 FROM notes n
+                   WHERE l.target_path = n.path
+                      OR l.target_path || '.md' = n.path
+                      OR l.target_path = REPLACE(n.path, '.md', '')
+               )
+             ORDER BY s.path, l.line_number",
+        )?;
+
+        let links = stmt
+            .query_map([], |row| {
+                let source: String = row.get(0)?;
+                Ok((PathBuf::from(source), row.get(1)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(links)
+    }
+
     fn row_to_link(row: &rusqlite::Row) -> Result<IndexedLink, rusqlite::Error> {
         let type_str: String = row.get(5)?;
         Ok(IndexedLink {
@@ -396,9 +648,395 @@ impl IndexDb {
             link_type: LinkType::parse(&type_str).unwrap_or(LinkType::Wikilink),
             context: row.get(6)?,
             line_number: row.get(7)?,
+            target_anchor: row.get(8)?,
         })
     }
 
+    // ─────────────────────────────────────────────────────────────────────────
+    // Anchors (headings and block IDs)
+    // ─────────────────────────────────────────────────────────────────────────
+
+    /// Insert a heading or block anchor.
+    pub fn insert_anchor(&self, anchor: &NoteAnchor) -> Result<i64, IndexError> {
+        self.conn.execute(
+            "INSERT INTO note_anchors (note_id, kind, name, line_number)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                anchor.note_id,
+                anchor.kind.as_str(),
+                anchor.name,
+                anchor.line_number
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Delete all anchors of a note.
+    pub fn delete_anchors_from(&self, note_id: i64) -> Result<usize, IndexError> {
+        let rows = self
+            .conn
+            .execute("DELETE FROM note_anchors WHERE note_id = ?1", [note_id])?;
+        Ok(rows)
+    }
+
+    /// Get the anchors of a note in document order.
+    pub fn get_anchors(&self, note_id: i64) -> Result<Vec<NoteAnchor>, IndexError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT note_id, kind, name, line_number FROM note_anchors
+             WHERE note_id = ?1 ORDER BY line_number",
+        )?;
+
+        let anchors = stmt
+            .query_map([note_id], |row| {
+                let kind: String = row.get(1)?;
+                Ok(NoteAnchor {
+                    note_id: row.get(0)?,
+                    kind: AnchorKind::parse(&kind).unwrap_or(AnchorKind::Heading),
+                    name: row.get(2)?,
+                    line_number: row.get(3)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(anchors)
+    }
+
+    /// Find the anchor a link's fragment points to.
+    ///
+    /// Returns `None` when the link has no fragment, its target note is not
+    /// resolved, or the target has no matching heading or block.
+    pub fn resolve_anchor(
+        &self,
+        link: &IndexedLink,
+    ) -> Result<Option<NoteAnchor>, IndexError> {
+        let (Some(target_id), Some(fragment)) = (link.target_id, &link.target_anchor)
+        else {
+            return Ok(None);
+        };
+        Ok(self.get_anchors(target_id)?.into_iter().find(|a| a.matches(fragment)))
+    }
+
+    /// Count links whose note resolves but whose fragment does not.
+    pub fn count_broken_anchors(&self) -> Result<i64, IndexError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, source_id, target_id, target_path, link_text, link_type, context, line_number,
+                    target_anchor
+             FROM links WHERE target_id IS NOT NULL AND target_anchor IS NOT NULL",
+        )?;
+        let links: Vec<IndexedLink> =
+            stmt.query_map([], Self::row_to_link)?.filter_map(|r| r.ok()).collect();
+
+        let mut broken = 0;
+        for link in &links {
+            if self.resolve_anchor(link)?.is_none() {
+                broken += 1;
+            }
+        }
+        Ok(broken)
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // Frontmatter fields
+    // ─────────────────────────────────────────────────────────────────────────
+
+    /// Replace the queryable frontmatter fields of a note.
+    pub fn replace_note_fields(
+        &self,
+        note_id: i64,
+        frontmatter_json: Option<&str>,
+    ) -> Result<usize, IndexError> {
+        self.conn.execute("DELETE FROM note_fields WHERE note_id = ?1", [note_id])?;
+
+        let Some(value) = frontmatter_json
+            .and_then(|j| serde_json::from_str::<serde_json::Value>(j).ok())
+        else {
+            return Ok(0);
+        };
+        self.add_note_fields(note_id, "", &value)
+    }
+
+    /// Add queryable fields for a note under `prefix` (dotted, like nested
+    /// frontmatter), without clearing existing ones. Used for fields that
+    /// plugins derive from a note.
+    pub fn add_note_fields(
+        &self,
+        note_id: i64,
+        prefix: &str,
+        value: &serde_json::Value,
+    ) -> Result<usize, IndexError> {
+        let mut fields = Vec::new();
+        flatten_fields(prefix, value, &mut fields);
+
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO note_fields (note_id, key, value_text, value_num) VALUES (?1, ?2, ?3, ?4)",
+        )?;
+        for (key, text, num) in &fields {
+            stmt.execute(params![note_id, key, text, num])?;
+        }
+        Ok(fields.len())
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // Checkbox items
+    // ─────────────────────────────────────────────────────────────────────────
+
+    /// Insert a checkbox item. `note_path` is ignored; the owner is `note_id`.
+    pub fn insert_checkbox(&self, item: &IndexedCheckbox) -> Result<i64, IndexError> {
+        self.conn.execute(
+            "INSERT INTO checkbox_items (note_id, line_number, text, status, heading)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                item.note_id,
+                item.line_number,
+                item.text,
+                item.status.as_str(),
+                item.heading,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Delete all checkbox items of a note.
+    pub fn delete_checkboxes_from(&self, note_id: i64) -> Result<usize, IndexError> {
+        let rows = self
+            .conn
+            .execute("DELETE FROM checkbox_items WHERE note_id = ?1", [note_id])?;
+        Ok(rows)
+    }
+
+    /// Query checkbox items, ordered by note path then line.
+    pub fn query_checkboxes(
+        &self,
+        query: &CheckboxQuery,
+    ) -> Result<Vec<IndexedCheckbox>, IndexError> {
+        let mut sql = String::from(
+            "SELECT c.id, c.note_id, n.path, c.line_number, c.text, c.status, c.heading
+             FROM checkbox_items c
+             JOIN notes n ON n.id = c.note_id
+             WHERE 1=1",
+        );
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if let Some(status) = &query.status {
+            sql.push_str(" AND c.status = ?");
+            params_vec.push(Box::new(status.as_str().to_string()));
+        }
+
+        if let Some(fragment) = &query.path_contains {
+            sql.push_str(" AND instr(n.path, ?) > 0");
+            params_vec.push(Box::new(fragment.clone()));
+        }
+
+        sql.push_str(" ORDER BY n.path, c.line_number");
+
+        if let Some(limit) = query.limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let items = stmt
+            .query_map(params_refs.as_slice(), |row| {
+                let path_str: String = row.get(2)?;
+                let status_str: String = row.get(5)?;
+                Ok(IndexedCheckbox {
+                    id: Some(row.get(0)?),
+                    note_id: row.get(1)?,
+                    note_path: path_str.into(),
+                    line_number: row.get(3)?,
+                    text: row.get(4)?,
+                    status: TaskStatus::parse(&status_str).unwrap_or_default(),
+                    heading: row.get(6)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(items)
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // Full-text search
+    // ─────────────────────────────────────────────────────────────────────────
+
+    /// Store the searchable text of a note.
+    ///
+    /// Skips the write when the stored row was built from the same content
+    /// hash. Returns whether the row was (re)written.
+    pub fn upsert_note_text(
+        &self,
+        note_id: i64,
+        title: &str,
+        body: &str,
+        content_hash: &str,
+    ) -> Result<bool, IndexError> {
+        if self.get_note_text_hash(note_id)?.as_deref() == Some(content_hash) {
+            return Ok(false);
+        }
+
+        self.conn.execute("DELETE FROM notes_fts WHERE rowid = ?1", [note_id])?;
+        self.conn.execute(
+            "INSERT INTO notes_fts (rowid, title, body, content_hash) VALUES (?1, ?2, ?3, ?4)",
+            params![note_id, title, body, content_hash],
+        )?;
+        Ok(true)
+    }
+
+    /// Get the content hash the full-text row for a note was built from.
+    pub fn get_note_text_hash(&self, note_id: i64) -> Result<Option<String>, IndexError> {
+        self.conn
+            .query_row(
+                "SELECT content_hash FROM notes_fts WHERE rowid = ?1",
+                [note_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Get notes whose full-text row is missing or built from older content.
+    ///
+    /// Used to backfill the text index for notes that are otherwise unchanged,
+    /// e.g. after upgrading from a schema without full-text search.
+    pub fn get_notes_with_stale_text(&self) -> Result<Vec<IndexedNote>, IndexError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT n.id, n.path, n.note_type, n.title, n.created_at, n.modified_at,
+                    n.frontmatter_json, n.content_hash, n.word_count, n.heading_json
+             FROM notes n
+             LEFT JOIN notes_fts f ON f.rowid = n.id
+             WHERE f.content_hash IS NULL OR f.content_hash != n.content_hash",
+        )?;
+
+        let notes =
+            stmt.query_map([], Self::row_to_note)?.filter_map(|r| r.ok()).collect();
+
+        Ok(notes)
+    }
+
+    /// Count full-text rows left behind by notes that no longer exist.
+    pub fn count_orphan_note_text(&self) -> Result<i64, IndexError> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM notes_fts WHERE rowid NOT IN (SELECT id FROM notes)",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    /// Delete full-text rows left behind by notes that no longer exist.
+    pub fn delete_orphan_note_text(&self) -> Result<usize, IndexError> {
+        let rows = self.conn.execute(
+            "DELETE FROM notes_fts WHERE rowid NOT IN (SELECT id FROM notes)",
+            [],
+        )?;
+        Ok(rows)
+    }
+
+    /// Search note titles and bodies, best matches first.
+    ///
+    /// `text` is treated as plain words (all must match, in any order);
+    /// FTS5 operators are not interpreted. The type, path prefix, and
+    /// field filters of `filter` are honoured, as are `limit` and `offset`.
+    pub fn search_fulltext(
+        &self,
+        text: &str,
+        filter: &NoteQuery,
+    ) -> Result<Vec<FullTextMatch>, IndexError> {
+        match fts_match_expr(text) {
+            Some(expr) => self.fulltext_matches(expr, filter),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Like [`search_fulltext`](Self::search_fulltext), but a note matches
+    /// when it contains any of `phrases`; each phrase matches as written.
+    pub fn search_fulltext_any(
+        &self,
+        phrases: &[String],
+        filter: &NoteQuery,
+    ) -> Result<Vec<FullTextMatch>, IndexError> {
+        let terms: Vec<String> = phrases.iter().filter_map(|p| fts_phrase(p)).collect();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.fulltext_matches(terms.join(" OR "), filter)
+    }
+
+    /// Ids of notes whose title or body contain `phrase`.
+    pub fn fulltext_ids(&self, phrase: &str) -> Result<HashSet<i64>, IndexError> {
+        let Some(expr) = fts_phrase(phrase) else {
+            return Ok(HashSet::new());
+        };
+        let mut stmt =
+            self.conn.prepare("SELECT rowid FROM notes_fts WHERE notes_fts MATCH ?1")?;
+        let ids =
+            stmt.query_map([expr], |row| row.get(0))?.filter_map(|r| r.ok()).collect();
+        Ok(ids)
+    }
+
+    /// Run an FTS5 MATCH expression, best matches first.
+    fn fulltext_matches(
+        &self,
+        expr: String,
+        filter: &NoteQuery,
+    ) -> Result<Vec<FullTextMatch>, IndexError> {
+        let mut sql = String::from(
+            "SELECT n.id, n.path, n.note_type, n.title, n.created_at, n.modified_at,
+                    n.frontmatter_json, n.content_hash, n.word_count, n.heading_json,
+                    snippet(notes_fts, -1, char(2), char(3), '…', 12), bm25(notes_fts),
+                    highlight(notes_fts, 1, char(2), char(3))
+             FROM notes_fts
+             JOIN notes n ON n.id = notes_fts.rowid
+             WHERE notes_fts MATCH ?",
+        );
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(expr)];
+
+        if let Some(note_type) = &filter.note_type {
+            sql.push_str(" AND n.note_type = ?");
+            params_vec.push(Box::new(note_type.as_str().to_string()));
+        }
+
+        if let Some(prefix) = &filter.path_prefix {
+            sql.push_str(" AND n.path LIKE ?");
+            params_vec.push(Box::new(format!("{}%", prefix.to_string_lossy())));
+        }
+
+        push_field_filters(&mut sql, &mut params_vec, "n.id", &filter.fields);
+
+        sql.push_str(" ORDER BY bm25(notes_fts)");
+
+        if let Some(limit) = filter.limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+
+        if let Some(offset) = filter.offset {
+            sql.push_str(&format!(" OFFSET {}", offset));
+        }
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let matches = stmt
+            .query_map(params_refs.as_slice(), |row| {
+                let snippet: String = row.get(10)?;
+                let body: String = row.get(12)?;
+                Ok(FullTextMatch {
+                    note: Self::row_to_note(row)?,
+                    snippet: Excerpt::from_marked(&snippet),
+                    hits: BodyHit::from_marked(&body),
+                    rank: row.get(11)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(matches)
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // Statistics
     // ─────────────────────────────────────────────────────────────────────────
@@ -428,6 +1066,16 @@ impl IndexDb {
         Ok(count)
     }
 
+    /// Get total word count across all notes.
+    pub fn count_words(&self) -> Result<i64, IndexError> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COALESCE(SUM(word_count), 0) FROM notes",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
     /// Get total link count.
     pub fn count_links(&self) -> Result<i64, IndexError> {
         let count: i64 =
@@ -436,27 +1084,132 @@ impl IndexDb {
     }
 
     /// Clear all data from the index (for full reindex).
+    ///
+    /// Tombstones survive: they record what the vault used to hold.
     pub fn clear_all(&self) -> Result<(), IndexError> {
         self.conn.execute_batch(
             "DELETE FROM links;
+             DELETE FROM note_anchors;
+             DELETE FROM checkbox_items;
+             DELETE FROM note_fields;
+             DELETE FROM notes_fts;
              DELETE FROM temporal_activity;
              DELETE FROM activity_summary;
              DELETE FROM note_cooccurrence;
+             DELETE FROM note_similarity;
              DELETE FROM notes;",
         )?;
         Ok(())
     }
 
+    // ─────────────────────────────────────────────────────────────────────────
+    // Maintenance
+    // ─────────────────────────────────────────────────────────────────────────
+
+    /// Size of the database in bytes, free pages included.
+    pub fn size_bytes(&self) -> Result<u64, IndexError> {
+        let pages: i64 =
+            self.conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+        let page_size: i64 =
+            self.conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+        Ok((pages * page_size) as u64)
+    }
+
+    /// Get a bookkeeping value from `index_meta`.
+    pub fn get_meta(&self, key: &str) -> Result<Option<String>, IndexError> {
+        self.conn
+            .query_row("SELECT value FROM index_meta WHERE key = ?1", [key], |row| {
+                row.get(0)
+            })
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Set a bookkeeping value in `index_meta`.
+    pub fn set_meta(&self, key: &str, value: &str) -> Result<(), IndexError> {
+        self.conn.execute(
+            "INSERT INTO index_meta (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    /// Bytes the database has grown since the last [`vacuum`](Self::vacuum)
+    /// (since it was created, if never vacuumed).
+    pub fn growth_since_vacuum(&self) -> Result<u64, IndexError> {
+        let last = self
+            .get_meta(VACUUMED_SIZE_KEY)?
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(0);
+        Ok(self.size_bytes()?.saturating_sub(last))
+    }
+
+    /// Delete rows that still point at notes which no longer exist.
+    ///
+    /// Foreign keys normally cascade, but full-text rows are not covered
+    /// and indexes written with foreign keys off keep their tombstones.
+    pub fn prune_dangling_rows(&self) -> Result<usize, IndexError> {
+        let mut pruned = self.delete_orphan_note_text()?;
+        for (table, columns) in [
+            ("links", &["source_id"][..]),
+            ("note_anchors", &["note_id"]),
+            ("checkbox_items", &["note_id"]),
+            ("note_fields", &["note_id"]),
+            ("temporal_activity", &["note_id", "daily_id"]),
+            ("activity_summary", &["note_id"]),
+            ("note_cooccurrence", &["note_a_id", "note_b_id"]),
+            ("note_similarity", &["note_a_id", "note_b_id"]),
+        ] {
+            let dangling: Vec<String> = columns
+                .iter()
+                .map(|c| format!("{c} NOT IN (SELECT id FROM notes)"))
+                .collect();
+            pruned += self.conn.execute(
+                &format!("DELETE FROM {table} WHERE {}", dangling.join(" OR ")),
+                [],
+            )?;
+        }
+        pruned += self.conn.execute(
+            "UPDATE links SET target_id = NULL
+             WHERE target_id IS NOT NULL AND target_id NOT IN (SELECT id FROM notes)",
+            [],
+        )?;
+        Ok(pruned)
+    }
+
+    /// Compact the database: prune dangling rows, merge the full-text index
+    /// segments, refresh query planner statistics, and rebuild the file.
+    ///
+    /// Records the resulting size for [`growth_since_vacuum`](Self::growth_since_vacuum).
+    pub fn vacuum(&self) -> Result<super::types::VacuumStats, IndexError> {
+        let size_before = self.size_bytes()?;
+        let rows_pruned = self.prune_dangling_rows()?;
+        self.conn.execute_batch(
+            "INSERT INTO notes_fts (notes_fts) VALUES ('optimize');
+             ANALYZE;
+             VACUUM;",
+        )?;
+        // Fold the WAL back in so the file on disk shrinks too; a no-op
+        // for in-memory databases
+        self.conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        let size_after = self.size_bytes()?;
+        self.set_meta(VACUUMED_SIZE_KEY, &size_after.to_string())?;
+        Ok(super::types::VacuumStats { rows_pruned, size_before, size_after })
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // Derived Index Operations
     // ─────────────────────────────────────────────────────────────────────────
 
-    /// Clear derived tables (temporal_activity, activity_summary, note_cooccurrence).
+    /// Clear derived tables (temporal_activity, activity_summary, note_cooccurrence,
+    /// note_similarity).
     pub fn clear_derived_tables(&self) -> Result<(), IndexError> {
         self.conn.execute_batch(
             "DELETE FROM temporal_activity;
              DELETE FROM activity_summary;
-             DELETE FROM note_cooccurrence;",
+             DELETE FROM note_cooccurrence;
+             DELETE FROM note_similarity;",
         )?;
         Ok(())
     }
@@ -467,7 +1220,8 @@ impl IndexDb {
         type_str: &str,
     ) -> Result<Vec<IndexedNote>, IndexError> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, path, note_type, title, created_at, modified_at, frontmatter_json, content_hash
+            "SELECT id, path, note_type, title, created_at, modified_at, frontmatter_json, content_hash,
+                    word_count, heading_json
              FROM notes WHERE note_type = ?1",
         )?;
 
@@ -612,6 +1366,125 @@ impl IndexDb {
         Ok(())
     }
 
+    /// Store a note's embedding, replacing any previous one.
+    pub fn upsert_embedding(
+        &self,
+        note_id: i64,
+        model: &str,
+        content_hash: &str,
+        vector: &[f32],
+    ) -> Result<(), IndexError> {
+        let blob: Vec<u8> = vector.iter().flat_map(|v| v.to_le_bytes()).collect();
+        self.conn.execute(
+            "INSERT OR REPLACE INTO note_embeddings (note_id, model, content_hash, vector)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![note_id, model, content_hash, blob],
+        )?;
+        Ok(())
+    }
+
+    /// Content hash each note was embedded from, for notes embedded by `model`.
+    pub fn get_embedding_hashes(
+        &self,
+        model: &str,
+    ) -> Result<HashMap<i64, String>, IndexError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT note_id, content_hash FROM note_embeddings WHERE model = ?1",
+        )?;
+        let hashes = stmt
+            .query_map([model], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(hashes)
+    }
+
+    /// Every vector computed by `model`, keyed by note id.
+    pub fn get_embeddings(
+        &self,
+        model: &str,
+    ) -> Result<Vec<(i64, Vec<f32>)>, IndexError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT note_id, vector FROM note_embeddings WHERE model = ?1")?;
+        let vectors = stmt
+            .query_map([model], |row| {
+                let blob: Vec<u8> = row.get(1)?;
+                let vector = blob
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect();
+                Ok((row.get(0)?, vector))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(vectors)
+    }
+
+    /// Drop embeddings made by any model other than `model`.
+    pub fn delete_other_embeddings(&self, model: &str) -> Result<usize, IndexError> {
+        let rows = self
+            .conn
+            .execute("DELETE FROM note_embeddings WHERE model != ?1", [model])?;
+        Ok(rows)
+    }
+
+    /// Get the indexed body text of every note, keyed by note id.
+    pub fn get_note_bodies(&self) -> Result<Vec<(i64, String)>, IndexError> {
+        let mut stmt =
+            self.conn.prepare("SELECT rowid, body FROM notes_fts ORDER BY rowid")?;
+        let bodies = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(bodies)
+    }
+
+    /// Insert a near-duplicate pair, stored with the lower note id first.
+    pub fn insert_similarity(
+        &self,
+        note_a: i64,
+        note_b: i64,
+        similarity: f64,
+    ) -> Result<(), IndexError> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO note_similarity (note_a_id, note_b_id, similarity)
+             VALUES (?1, ?2, ?3)",
+            params![note_a.min(note_b), note_a.max(note_b), similarity],
+        )?;
+        Ok(())
+    }
+
+    /// Get near-duplicate pairs at or above `min_similarity`, most similar first.
+    pub fn get_similar_pairs(
+        &self,
+        min_similarity: f64,
+        limit: Option<u32>,
+    ) -> Result<Vec<super::types::SimilarityPair>, IndexError> {
+        let mut sql = String::from(
+            "SELECT note_a_id, note_b_id, similarity
+             FROM note_similarity
+             WHERE similarity >= ?1
+             ORDER BY similarity DESC, note_a_id, note_b_id",
+        );
+        if let Some(limit) = limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let pairs = stmt
+            .query_map([min_similarity], |row| {
+                Ok(super::types::SimilarityPair {
+                    note_a_id: row.get(0)?,
+                    note_b_id: row.get(1)?,
+                    similarity: row.get(2)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(pairs)
+    }
+
     /// Get activity summary for a note.
     pub fn get_activity_summary(
         &self,
@@ -649,7 +1522,8 @@ impl IndexDb {
     ) -> Result<Vec<(IndexedNote, i32)>, IndexError> {
         let mut stmt = self.conn.prepare(
             "SELECT n.id, n.path, n.note_type, n.title, n.created_at, n.modified_at,
-                    n.frontmatter_json, n.content_hash, c.shared_daily_count
+                    n.frontmatter_json, n.content_hash, n.word_count, n.heading_json,
+                    c.shared_daily_count
              FROM note_cooccurrence c
              JOIN notes n ON (
                  CASE WHEN c.note_a_id = ?1 THEN c.note_b_id ELSE c.note_a_id END = n.id
@@ -662,7 +1536,7 @@ impl IndexDb {
         let results = stmt
             .query_map(params![note_id, limit], |row| {
                 let note = Self::row_to_note(row)?;
-                let count: i32 = row.get(8)?;
+                let count: i32 = row.get(10)?;
                 Ok((note, count))
             })?
             .filter_map(|r| r.ok())
@@ -682,7 +1556,8 @@ impl IndexDb {
     ) -> Result<Vec<(IndexedNote, f64)>, IndexError> {
         let mut sql = String::from(
             "SELECT n.id, n.path, n.note_type, n.title, n.created_at, n.modified_at,
-                    n.frontmatter_json, n.content_hash, s.staleness_score
+                    n.frontmatter_json, n.content_hash, n.word_count, n.heading_json,
+                    s.staleness_score
              FROM notes n
              LEFT JOIN activity_summary s ON n.id = s.note_id
              WHERE COALESCE(s.staleness_score, 1.0) >= ?1",
@@ -703,7 +1578,7 @@ impl IndexDb {
         let results = if let Some(nt) = note_type {
             stmt.query_map(params![min_staleness, nt], |row| {
                 let note = Self::row_to_note(row)?;
-                let staleness: Option<f64> = row.get(8)?;
+                let staleness: Option<f64> = row.get(10)?;
                 Ok((note, staleness.unwrap_or(1.0)))
             })?
             .filter_map(|r| r.ok())
@@ -711,7 +1586,7 @@ impl IndexDb {
         } else {
             stmt.query_map([min_staleness], |row| {
                 let note = Self::row_to_note(row)?;
-                let staleness: Option<f64> = row.get(8)?;
+                let staleness: Option<f64> = row.get(10)?;
                 Ok((note, staleness.unwrap_or(1.0)))
             })?
             .filter_map(|r| r.ok())
@@ -734,7 +1609,8 @@ impl IndexDb {
 
         let mut sql = String::from(
             "SELECT n.id, n.path, n.note_type, n.title, n.created_at, n.modified_at,
-                    n.frontmatter_json, n.content_hash, s.last_seen
+                    n.frontmatter_json, n.content_hash, n.word_count, n.heading_json,
+                    s.last_seen
              FROM notes n
              LEFT JOIN activity_summary s ON n.id = s.note_id
              WHERE s.last_seen IS NULL OR s.last_seen < ?1",
@@ -755,7 +1631,7 @@ impl IndexDb {
         let results = if let Some(nt) = note_type {
             stmt.query_map(params![&cutoff_date, nt], |row| {
                 let note = Self::row_to_note(row)?;
-                let last_seen: Option<String> = row.get(8)?;
+                let last_seen: Option<String> = row.get(10)?;
                 Ok((note, last_seen))
             })?
             .filter_map(|r| r.ok())
@@ -763,7 +1639,7 @@ impl IndexDb {
         } else {
             stmt.query_map([&cutoff_date], |row| {
                 let note = Self::row_to_note(row)?;
-                let last_seen: Option<String> = row.get(8)?;
+                let last_seen: Option<String> = row.get(10)?;
                 Ok((note, last_seen))
             })?
             .filter_map(|r| r.ok())
@@ -774,6 +1650,101 @@ impl IndexDb {
     }
 }
 
+/// Build an FTS5 MATCH expression that requires every word in `text`.
+///
+/// Each word is quoted so punctuation and FTS5 keywords (`AND`, `NEAR`, `-`)
+/// in user input are matched literally instead of parsed as syntax.
+fn fts_match_expr(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect();
+
+    if terms.is_empty() { None } else { Some(terms.join(" ")) }
+}
+
+/// Quote `phrase` as one FTS5 phrase: its words must appear in order.
+fn fts_phrase(phrase: &str) -> Option<String> {
+    let words: Vec<&str> = phrase.split_whitespace().collect();
+    if words.is_empty() {
+        return None;
+    }
+    Some(format!("\"{}\"", words.join(" ").replace('"', "\"\"")))
+}
+
+/// Append one `EXISTS` clause per field filter, matching against the note
+/// id column `id_col`.
+fn push_field_filters(
+    sql: &mut String,
+    params: &mut Vec<Box<dyn rusqlite::ToSql>>,
+    id_col: &str,
+    filters: &[FieldFilter],
+) {
+    for filter in filters {
+        params.push(Box::new(filter.key.clone()));
+        let comparison = match (filter.op, filter.numeric_value()) {
+            (FieldOp::Eq | FieldOp::Ne, Some(num)) => {
+                params.push(Box::new(num));
+                params.push(Box::new(filter.value.clone()));
+                "(f.value_num = ? OR f.value_text = ? COLLATE NOCASE)".to_string()
+            }
+            (FieldOp::Eq | FieldOp::Ne, None) => {
+                params.push(Box::new(filter.value.clone()));
+                "f.value_text = ? COLLATE NOCASE".to_string()
+            }
+            (op, Some(num)) => {
+                params.push(Box::new(num));
+                format!("f.value_num {} ?", op.as_str())
+            }
+            (op, None) => {
+                params.push(Box::new(filter.value.clone()));
+                format!("f.value_text {} ?", op.as_str())
+            }
+        };
+
+        sql.push_str(&format!(
+            " AND {}EXISTS (SELECT 1 FROM note_fields f WHERE f.note_id = {} AND f.key = ? AND {})",
+            if filter.op == FieldOp::Ne { "NOT " } else { "" },
+            id_col,
+            comparison
+        ));
+    }
+}
+
+/// Flatten frontmatter into `(key, text, number)` rows: nested mappings
+/// become dotted keys, list elements share their key, nulls are skipped.
+fn flatten_fields(
+    prefix: &str,
+    value: &serde_json::Value,
+    out: &mut Vec<(String, String, Option<f64>)>,
+) {
+    use serde_json::Value;
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten_fields(&key, child, out);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                flatten_fields(prefix, item, out);
+            }
+        }
+        Value::Null => {}
+        Value::Bool(b) => out.push((prefix.to_string(), b.to_string(), None)),
+        Value::Number(n) => out.push((prefix.to_string(), n.to_string(), n.as_f64())),
+        Value::String(text) => {
+            let num = text.trim().parse::<f64>().ok().filter(|n| n.is_finite());
+            out.push((prefix.to_string(), text.clone(), num));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -790,6 +1761,8 @@ mod tests {
             modified: Utc::now(),
             frontmatter_json: Some(r#"{"tags": ["test"]}"#.to_string()),
             content_hash: "abc123".to_string(),
+            word_count: 0,
+            heading_json: None,
         }
     }
 
@@ -855,6 +1828,7 @@ mod tests {
             source_id: id1,
             target_id: Some(id2),
             target_path: "note2.md".to_string(),
+            target_anchor: None,
             link_text: Some("Note 2".to_string()),
             link_type: LinkType::Wikilink,
             context: None,
@@ -884,6 +1858,7 @@ mod tests {
             source_id: id1,
             target_id: Some(id2),
             target_path: "note2.md".to_string(),
+            target_anchor: None,
             link_text: None,
             link_type: LinkType::Wikilink,
             context: None,
@@ -895,4 +1870,314 @@ mod tests {
         assert_eq!(orphans.len(), 1);
         assert_eq!(orphans[0].path, PathBuf::from("note1.md"));
     }
+
+    #[test]
+    fn test_readers_see_committed_index_during_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.db");
+
+        // A missing index is created, and a closed one reopens
+        drop(IndexDb::open_read_only(&path).unwrap());
+        let writer = IndexDb::open(&path).unwrap();
+        let reader = IndexDb::open_read_only(&path).unwrap();
+        assert!(reader.insert_note(&sample_note("nope.md")).is_err());
+
+        writer
+            .write_transaction(|| {
+                writer.insert_note(&sample_note("new.md"))?;
+                // Readers are not blocked and see the last commit
+                assert_eq!(reader.count_notes()?, 0);
+
+                // A second writer cannot start meanwhile
+                let other = IndexDb::open(&path)?;
+                other.connection().execute_batch("PRAGMA busy_timeout = 0;")?;
+                assert!(other.connection().execute_batch("BEGIN IMMEDIATE").is_err());
+                Ok::<_, IndexError>(())
+            })
+            .unwrap();
+        assert_eq!(reader.count_notes().unwrap(), 1);
+
+        // A failed transaction leaves nothing behind
+        let failed: Result<(), IndexError> = writer.write_transaction(|| {
+            writer.insert_note(&sample_note("rolled-back.md"))?;
+            Err(IndexError::InvalidData("stop".to_string()))
+        });
+        assert!(failed.is_err());
+        assert_eq!(reader.count_notes().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_vacuum_prunes_tombstones() {
+        let db = IndexDb::open_in_memory().unwrap();
+        let id1 = db.insert_note(&sample_note("keep.md")).unwrap();
+        let id2 = db.insert_note(&sample_note("gone.md")).unwrap();
+        db.upsert_note_text(id2 + 1, "Never indexed", "stray body", "abc123").unwrap();
+        db.insert_link(&IndexedLink {
+            id: None,
+            source_id: id2,
+            target_id: Some(id1),
+            target_path: "keep.md".to_string(),
+            target_anchor: None,
+            link_text: None,
+            link_type: LinkType::Wikilink,
+            context: None,
+            line_number: None,
+        })
+        .unwrap();
+
+        // Delete without cascades, as an index written with foreign keys off
+        db.conn.execute_batch("PRAGMA foreign_keys = OFF;").unwrap();
+        db.conn.execute("DELETE FROM notes WHERE id = ?1", [id2]).unwrap();
+        db.conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        assert!(db.growth_since_vacuum().unwrap() > 0);
+
+        let stats = db.vacuum().unwrap();
+        assert_eq!(stats.rows_pruned, 2);
+        assert!(stats.size_after > 0);
+        assert_eq!(db.count_links().unwrap(), 0);
+        assert_eq!(db.count_orphan_note_text().unwrap(), 0);
+        assert_eq!(db.growth_since_vacuum().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_fulltext_search_body() {
+        let db = IndexDb::open_in_memory().unwrap();
+        let id1 = db.insert_note(&sample_note("a.md")).unwrap();
+        let id2 = db.insert_note(&sample_note("b.md")).unwrap();
+        db.upsert_note_text(
+            id1,
+            "A",
+            "Notes about [[sourdough]]\nfeed the Sourdough",
+            "h1",
+        )
+        .unwrap();
+        db.upsert_note_text(id2, "B", "Unrelated gardening log", "h2").unwrap();
+
+        let matches = db.search_fulltext("sourdough", &NoteQuery::default()).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].note.path, PathBuf::from("a.md"));
+        assert!(matches[0].snippet.marked("<", ">").contains("[[<sourdough>]]"));
+        let hits: Vec<_> =
+            matches[0].hits.iter().map(|h| (h.line, h.start, h.end)).collect();
+        assert_eq!(hits, [(1, 14, 23), (2, 35, 44)]);
+    }
+
+    #[test]
+    fn test_fulltext_skips_unchanged_hash() {
+        let db = IndexDb::open_in_memory().unwrap();
+        let id = db.insert_note(&sample_note("a.md")).unwrap();
+
+        assert!(db.upsert_note_text(id, "A", "first", "h1").unwrap());
+        assert!(!db.upsert_note_text(id, "A", "first", "h1").unwrap());
+        assert!(db.upsert_note_text(id, "A", "second", "h2").unwrap());
+
+        assert!(db.search_fulltext("first", &NoteQuery::default()).unwrap().is_empty());
+        assert_eq!(db.search_fulltext("second", &NoteQuery::default()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_fulltext_row_removed_with_note() {
+        let db = IndexDb::open_in_memory().unwrap();
+        let id = db.insert_note(&sample_note("a.md")).unwrap();
+        db.upsert_note_text(id, "A", "ephemeral words", "h1").unwrap();
+
+        db.delete_note(Path::new("a.md")).unwrap();
+
+        assert!(db.get_note_text_hash(id).unwrap().is_none());
+        assert!(
+            db.search_fulltext("ephemeral", &NoteQuery::default()).unwrap().is_empty()
+        );
+    }
+
+    #[test]
+    fn test_fulltext_query_syntax_is_literal() {
+        let db = IndexDb::open_in_memory().unwrap();
+        let id = db.insert_note(&sample_note("a.md")).unwrap();
+        db.upsert_note_text(id, "A", "rock AND roll \"quoted\"", "h1").unwrap();
+
+        // Would be a syntax error if passed to MATCH unquoted
+        let matches = db.search_fulltext("AND \"quoted", &NoteQuery::default()).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert!(db.search_fulltext("   ", &NoteQuery::default()).unwrap().is_empty());
+    }
+
+    fn sample_checkbox(note_id: i64, line: u32, status: TaskStatus) -> IndexedCheckbox {
+        IndexedCheckbox {
+            id: None,
+            note_id,
+            note_path: PathBuf::new(),
+            line_number: line,
+            text: format!("item on line {line}"),
+            status,
+            heading: Some("Tasks".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_checkboxes() {
+        let db = IndexDb::open_in_memory().unwrap();
+        let daily = db.insert_note(&sample_note("Journal/2025-01-06.md")).unwrap();
+        let project = db.insert_note(&sample_note("Projects/ALP/ALP.md")).unwrap();
+
+        db.insert_checkbox(&sample_checkbox(project, 3, TaskStatus::Open)).unwrap();
+        db.insert_checkbox(&sample_checkbox(daily, 7, TaskStatus::Done)).unwrap();
+        db.insert_checkbox(&sample_checkbox(daily, 2, TaskStatus::Open)).unwrap();
+
+        let all = db.query_checkboxes(&CheckboxQuery::default()).unwrap();
+        let order: Vec<(String, u32)> = all
+            .iter()
+            .map(|c| (c.note_path.to_string_lossy().to_string(), c.line_number))
+            .collect();
+        assert_eq!(
+            order,
+            [
+                ("Journal/2025-01-06.md".to_string(), 2),
+                ("Journal/2025-01-06.md".to_string(), 7),
+                ("Projects/ALP/ALP.md".to_string(), 3),
+            ]
+        );
+        assert_eq!(all[0].heading.as_deref(), Some("Tasks"));
+
+        let open = db
+            .query_checkboxes(&CheckboxQuery {
+                status: Some(TaskStatus::Open),
+                path_contains: Some("ALP".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].note_id, project);
+
+        // Removed with their note
+        db.delete_note(Path::new("Journal/2025-01-06.md")).unwrap();
+        assert_eq!(db.query_checkboxes(&CheckboxQuery::default()).unwrap().len(), 1);
+        assert_eq!(db.delete_checkboxes_from(project).unwrap(), 1);
+    }
+
+    fn note_with_fields(db: &IndexDb, path: &str, frontmatter: &str) -> i64 {
+        let mut note = sample_note(path);
+        note.frontmatter_json = Some(frontmatter.to_string());
+        let id = db.insert_note(&note).unwrap();
+        db.replace_note_fields(id, note.frontmatter_json.as_deref()).unwrap();
+        id
+    }
+
+    fn paths_where(db: &IndexDb, filters: &[&str]) -> Vec<String> {
+        let query = NoteQuery {
+            fields: filters.iter().map(|f| f.parse().unwrap()).collect(),
+            ..Default::default()
+        };
+        let mut paths: Vec<String> = db
+            .query_notes(&query)
+            .unwrap()
+            .into_iter()
+            .map(|n| n.path.to_string_lossy().into_owned())
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn test_query_notes_by_frontmatter_fields() {
+        let db = IndexDb::open_in_memory().unwrap();
+        note_with_fields(
+            &db,
+            "a.md",
+            r#"{"status": "doing", "priority": 3, "tags": ["rust", "cli"], "due": "2025-01-10"}"#,
+        );
+        note_with_fields(
+            &db,
+            "b.md",
+            r#"{"status": "Doing", "priority": "5", "review": {"next": "2025-03-01"}}"#,
+        );
+        note_with_fields(
+            &db,
+            "c.md",
+            r#"{"status": "done", "priority": 1, "archived": true}"#,
+        );
+
+        assert_eq!(paths_where(&db, &["status=doing"]), ["a.md", "b.md"]);
+        assert_eq!(paths_where(&db, &["status=doing", "priority>=4"]), ["b.md"]);
+        // Numeric, not lexicographic: "10" > "3"
+        assert_eq!(paths_where(&db, &["priority<10"]), ["a.md", "b.md", "c.md"]);
+        assert_eq!(paths_where(&db, &["priority=3.0"]), ["a.md"]);
+        assert_eq!(paths_where(&db, &["tags=cli"]), ["a.md"]);
+        assert_eq!(paths_where(&db, &["tags!=cli"]), ["b.md", "c.md"]);
+        assert_eq!(paths_where(&db, &["due<2025-02-01"]), ["a.md"]);
+        assert_eq!(paths_where(&db, &["review.next>2025-02-01"]), ["b.md"]);
+        assert_eq!(paths_where(&db, &["archived=true"]), ["c.md"]);
+        assert!(paths_where(&db, &["missing=x"]).is_empty());
+    }
+
+    #[test]
+    fn test_note_fields_replaced_and_removed_with_note() {
+        let db = IndexDb::open_in_memory().unwrap();
+        let id = note_with_fields(&db, "a.md", r#"{"status": "todo"}"#);
+        db.replace_note_fields(id, Some(r#"{"status": "done"}"#)).unwrap();
+        assert!(paths_where(&db, &["status=todo"]).is_empty());
+        assert_eq!(paths_where(&db, &["status=done"]), ["a.md"]);
+
+        db.delete_note(Path::new("a.md")).unwrap();
+        let rows: i64 = db
+            .connection()
+            .query_row("SELECT COUNT(*) FROM note_fields", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(rows, 0);
+    }
+
+    #[test]
+    fn test_plugin_fields_are_prefixed_and_queryable() {
+        let db = IndexDb::open_in_memory().unwrap();
+        let id = note_with_fields(&db, "a.md", r#"{"status": "todo"}"#);
+        let fields = serde_json::json!({"chars": 120, "lang": "en"});
+        assert_eq!(db.add_note_fields(id, "wordstats", &fields).unwrap(), 2);
+
+        assert_eq!(paths_where(&db, &["wordstats.chars>100"]), ["a.md"]);
+        assert_eq!(paths_where(&db, &["status=todo", "wordstats.lang=en"]), ["a.md"]);
+
+        // Reindexing the frontmatter clears them until plugins run again
+        db.replace_note_fields(id, Some(r#"{"status": "todo"}"#)).unwrap();
+        assert!(paths_where(&db, &["wordstats.lang=en"]).is_empty());
+    }
+
+    #[test]
+    fn test_find_notes_by_reference() {
+        let db = IndexDb::open_in_memory().unwrap();
+        db.insert_note(&sample_note("Projects/MCP/MCP.md")).unwrap();
+        db.insert_note(&sample_note("Projects/MCP/Tasks/MCP-001.md")).unwrap();
+        db.insert_note(&sample_note("Archive/NOTMCP.md")).unwrap();
+
+        let found = |reference: &str| -> Vec<String> {
+            db.find_notes_by_reference(reference)
+                .unwrap()
+                .into_iter()
+                .map(|n| n.path.to_string_lossy().into_owned())
+                .collect()
+        };
+        assert_eq!(found("MCP"), ["Projects/MCP/MCP.md"]);
+        assert_eq!(found("[[MCP|the project]]"), ["Projects/MCP/MCP.md"]);
+        assert_eq!(found("Projects/MCP/MCP"), ["Projects/MCP/MCP.md"]);
+        assert_eq!(found("Projects/MCP/MCP.md"), ["Projects/MCP/MCP.md"]);
+        assert!(found("MCP-002").is_empty());
+        assert!(found("").is_empty());
+    }
+
+    #[test]
+    fn test_find_notes_by_field() {
+        let db = IndexDb::open_in_memory().unwrap();
+        note_with_fields(&db, "a.md", r#"{"zettel-id": "202401011200", "tags": ["x"]}"#);
+        note_with_fields(&db, "b.md", r#"{"zettel-id": "202401011200"}"#);
+        note_with_fields(&db, "c.md", r#"{"zettel-id": "202401011201", "tags": ["X"]}"#);
+
+        let paths = |key: &str, value: &str| -> Vec<String> {
+            db.find_notes_by_field(key, value)
+                .unwrap()
+                .into_iter()
+                .map(|n| n.path.to_string_lossy().into_owned())
+                .collect()
+        };
+        assert_eq!(paths("zettel-id", "202401011200"), ["a.md", "b.md"]);
+        assert_eq!(paths("tags", "x"), ["a.md"]);
+        assert!(paths("zettel-id", "2024").is_empty());
+    }
 }
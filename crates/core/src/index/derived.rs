@@ -4,12 +4,17 @@
 //! - `temporal_activity`: When notes are referenced in daily notes
 //! - `activity_summary`: Aggregated activity metrics per note
 //! - `note_cooccurrence`: Notes that appear together in daily notes
+//! - `note_similarity`: Pairs of notes with near-identical body text
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 
-use chrono::{Duration, NaiveDate, Utc};
 use thiserror::Error;
 
 use super::IndexError;
 use super::db::IndexDb;
+use super::staleness::{StalenessModel, staleness_signals};
 
 /// Errors that can occur during derived index computation.
 #[derive(Debug, Error)]
@@ -19,8 +24,20 @@ pub enum DerivedError {
 
     #[error("Failed to parse date: {0}")]
     DateParse(String),
+
+    #[error("Failed to score staleness of {note}: {message}")]
+    Staleness { note: String, message: String },
 }
 
+/// Number of consecutive words in a content shingle.
+pub const SHINGLE_WORDS: usize = 5;
+
+/// Lowest similarity stored as a near-duplicate pair.
+///
+/// Queries can ask for a higher threshold; anything below this is dropped
+/// at build time to keep the table small.
+pub const MIN_DUPLICATE_SIMILARITY: f64 = 0.5;
+
 /// Statistics from derived index computation.
 #[derive(Debug, Clone, Default)]
 pub struct DerivedStats {
@@ -32,6 +49,8 @@ pub struct DerivedStats {
     pub summaries_computed: usize,
     /// Number of cooccurrence pairs found.
     pub cooccurrence_pairs: usize,
+    /// Number of near-duplicate pairs found.
+    pub duplicate_pairs: usize,
     /// Duration in milliseconds.
     pub duration_ms: u64,
 }
@@ -39,12 +58,19 @@ pub struct DerivedStats {
 /// Builder for computing derived indices.
 pub struct DerivedIndexBuilder<'a> {
     db: &'a IndexDb,
+    staleness: StalenessModel,
 }
 
 impl<'a> DerivedIndexBuilder<'a> {
     /// Create a new derived index builder.
     pub fn new(db: &'a IndexDb) -> Self {
-        Self { db }
+        Self { db, staleness: StalenessModel::default() }
+    }
+
+    /// Score staleness with `model` instead of the default weights.
+    pub fn with_staleness(mut self, model: StalenessModel) -> Self {
+        self.staleness = model;
+        self
     }
 
     /// Compute all derived indices.
@@ -54,20 +80,26 @@ impl<'a> DerivedIndexBuilder<'a> {
         let start = std::time::Instant::now();
         let mut stats = DerivedStats::default();
 
-        // Clear existing derived data
-        self.db.clear_derived_tables()?;
+        self.db.write_transaction(|| {
+            // Clear existing derived data
+            self.db.clear_derived_tables()?;
+
+            // Step 1: Build temporal activity from daily notes
+            stats.dailies_processed = self.build_temporal_activity()?;
 
-        // Step 1: Build temporal activity from daily notes
-        stats.dailies_processed = self.build_temporal_activity()?;
+            // Step 2: Count activity records
+            stats.activity_records = self.db.count_temporal_activity()? as usize;
 
-        // Step 2: Count activity records
-        stats.activity_records = self.db.count_temporal_activity()? as usize;
+            // Step 3: Compute activity summaries
+            stats.summaries_computed = self.compute_activity_summaries()?;
 
-        // Step 3: Compute activity summaries
-        stats.summaries_computed = self.compute_activity_summaries()?;
+            // Step 4: Compute cooccurrence matrix
+            stats.cooccurrence_pairs = self.compute_cooccurrence()?;
 
-        // Step 4: Compute cooccurrence matrix
-        stats.cooccurrence_pairs = self.compute_cooccurrence()?;
+            // Step 5: Find near-duplicate notes
+            stats.duplicate_pairs = self.compute_similarity()?;
+            Ok::<_, DerivedError>(())
+        })?;
 
         stats.duration_ms = start.elapsed().as_millis() as u64;
         Ok(stats)
@@ -144,32 +176,25 @@ impl<'a> DerivedIndexBuilder<'a> {
         Ok(daily.modified.format("%Y-%m-%d").to_string())
     }
 
-    /// Compute activity summaries for all notes.
+    /// Compute activity summaries and staleness scores for all notes.
     fn compute_activity_summaries(&self) -> Result<usize, DerivedError> {
-        let today = Utc::now().date_naive();
-        let thirty_days_ago = today - Duration::days(30);
-        let ninety_days_ago = today - Duration::days(90);
-
-        // Get aggregated activity data
-        let summaries = self.db.aggregate_activity(
-            &thirty_days_ago.to_string(),
-            &ninety_days_ago.to_string(),
-        )?;
+        let mut signals: Vec<_> = staleness_signals(self.db)?.into_iter().collect();
+        signals.sort_by_key(|(id, _)| *id);
 
         let mut count = 0;
-        for summary in summaries {
-            // Compute staleness score
-            let staleness = self.compute_staleness_score(
-                summary.last_seen.as_deref(),
-                summary.access_count_30d,
-                summary.access_count_90d,
-            );
+        for (note_id, (note, s)) in &signals {
+            let staleness =
+                self.staleness.score(note, s).map_err(|e| DerivedError::Staleness {
+                    note: note.path.display().to_string(),
+                    message: e.to_string(),
+                })?;
+            let last_seen = s.last_referenced.map(|d| d.to_string());
 
             self.db.upsert_activity_summary(
-                summary.note_id,
-                summary.last_seen.as_deref(),
-                summary.access_count_30d,
-                summary.access_count_90d,
+                *note_id,
+                last_seen.as_deref(),
+                s.references_30d as i32,
+                s.references_90d as i32,
                 staleness,
             )?;
             count += 1;
@@ -178,39 +203,6 @@ impl<'a> DerivedIndexBuilder<'a> {
         Ok(count)
     }
 
-    /// Compute staleness score based on activity patterns.
-    ///
-    /// Score ranges from 0.0 (very active) to 1.0 (very stale).
-    fn compute_staleness_score(
-        &self,
-        last_seen: Option<&str>,
-        count_30d: i32,
-        count_90d: i32,
-    ) -> f64 {
-        let today = Utc::now().date_naive();
-
-        // Days since last seen (default to 365 if never seen)
-        let days_since = last_seen
-            .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
-            .map(|d| (today - d).num_days() as f64)
-            .unwrap_or(365.0);
-
-        // Base staleness from recency (0.0 = today, 1.0 = 90+ days)
-        let recency_score = (days_since / 90.0).min(1.0);
-
-        // Activity factor (more activity = less stale)
-        let activity_factor = if count_30d > 0 {
-            0.0 // Active in last 30 days - not stale
-        } else if count_90d > 0 {
-            0.3 // Active in last 90 days - slightly stale
-        } else {
-            0.6 // No recent activity - more stale
-        };
-
-        // Combined score
-        (recency_score * 0.6 + activity_factor * 0.4).min(1.0)
-    }
-
     /// Compute note cooccurrence matrix.
     ///
     /// Finds pairs of notes that are referenced together in daily notes.
@@ -231,6 +223,79 @@ impl<'a> DerivedIndexBuilder<'a> {
 
         Ok(count)
     }
+
+    /// Find pairs of notes whose bodies are near duplicates.
+    ///
+    /// Each body is reduced to a set of word shingles and pairs are scored
+    /// by Jaccard similarity. Only notes sharing at least one shingle are
+    /// compared, so unrelated notes cost nothing.
+    fn compute_similarity(&self) -> Result<usize, DerivedError> {
+        let signatures: Vec<(i64, HashSet<u64>)> = self
+            .db
+            .get_note_bodies()?
+            .into_iter()
+            .map(|(id, body)| (id, shingles(&body)))
+            .filter(|(_, set)| !set.is_empty())
+            .collect();
+
+        let mut postings: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (i, (_, set)) in signatures.iter().enumerate() {
+            for shingle in set {
+                postings.entry(*shingle).or_default().push(i);
+            }
+        }
+
+        let mut shared: HashMap<(usize, usize), usize> = HashMap::new();
+        for notes in postings.values() {
+            for (n, &a) in notes.iter().enumerate() {
+                for &b in &notes[n + 1..] {
+                    *shared.entry((a, b)).or_default() += 1;
+                }
+            }
+        }
+
+        let mut count = 0;
+        for ((a, b), common) in shared {
+            let (id_a, set_a) = &signatures[a];
+            let (id_b, set_b) = &signatures[b];
+            let similarity = jaccard(common, set_a.len(), set_b.len());
+            if similarity >= MIN_DUPLICATE_SIMILARITY {
+                self.db.insert_similarity(*id_a, *id_b, similarity)?;
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+}
+
+/// Hash every run of [`SHINGLE_WORDS`] consecutive words in `text`.
+///
+/// Words are lowercased and split on anything that is not alphanumeric, so
+/// formatting and punctuation changes do not hide a duplicate. Text shorter
+/// than one shingle has no signature.
+fn shingles(text: &str) -> HashSet<u64> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    words
+        .windows(SHINGLE_WORDS)
+        .map(|window| {
+            let mut hasher = DefaultHasher::new();
+            window.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect()
+}
+
+/// Jaccard similarity of two sets from their sizes and overlap, 0 when
+/// both are empty.
+pub(crate) fn jaccard(common: usize, len_a: usize, len_b: usize) -> f64 {
+    let union = len_a + len_b - common;
+    if union == 0 { 0.0 } else { common as f64 / union as f64 }
 }
 
 /// Extract a date string (YYYY-MM-DD) from a file path.
@@ -243,6 +308,7 @@ fn extract_date_from_path(path: &str) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Utc;
 
     #[test]
     fn test_extract_date_from_path() {
@@ -258,20 +324,58 @@ mod tests {
     }
 
     #[test]
-    fn test_staleness_score() {
-        let builder = DerivedIndexBuilder { db: &IndexDb::open_in_memory().unwrap() };
-
-        // Very active (accessed today, high count)
-        let score = builder.compute_staleness_score(
-            Some(&Utc::now().format("%Y-%m-%d").to_string()),
-            5,
-            10,
-        );
-        assert!(score < 0.1, "Active notes should have low staleness");
+    fn test_jaccard() {
+        assert_eq!(jaccard(2, 4, 2), 0.5);
+        assert_eq!(jaccard(0, 3, 3), 0.0);
+        assert_eq!(jaccard(0, 0, 0), 0.0);
+    }
+
+    #[test]
+    fn test_shingles_ignore_formatting() {
+        let a = shingles("Ship the **beta** to five design partners, then iterate.");
+        let b = shingles("ship the beta to five\ndesign partners -- then iterate");
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 5);
+        assert!(shingles("too short to sign").is_empty());
+    }
+
+    #[test]
+    fn test_compute_similarity_flags_near_duplicates() {
+        use super::super::types::{IndexedNote, NoteType};
+        use std::path::PathBuf;
+
+        let db = IndexDb::open_in_memory().unwrap();
+        let idea = "Cache rendered previews on the CDN so the editor loads instantly \
+                    for readers on slow connections";
+        let bodies = [
+            ("idea.md", idea.to_string()),
+            ("idea-again.md", format!("{idea} and mobile")),
+            ("other.md", "Quarterly budget review with finance and the leads".into()),
+        ];
+        for (path, body) in &bodies {
+            let id = db
+                .insert_note(&IndexedNote {
+                    id: None,
+                    path: PathBuf::from(path),
+                    note_type: NoteType::Zettel,
+                    title: path.to_string(),
+                    created: None,
+                    modified: Utc::now(),
+                    frontmatter_json: None,
+                    content_hash: format!("hash-{path}"),
+                    word_count: 0,
+                    heading_json: None,
+                })
+                .unwrap();
+            db.upsert_note_text(id, path, body, &format!("hash-{path}")).unwrap();
+        }
+
+        let stats = DerivedIndexBuilder::new(&db).compute_all().unwrap();
+        assert_eq!(stats.duplicate_pairs, 1);
 
-        // Never seen: days_since=365, recency_score=1.0, activity_factor=0.6
-        // Combined: 1.0*0.6 + 0.6*0.4 = 0.84
-        let score = builder.compute_staleness_score(None, 0, 0);
-        assert!(score > 0.8, "Never-seen notes should be stale (score: {})", score);
+        let pairs = db.get_similar_pairs(0.0, None).unwrap();
+        assert_eq!((pairs[0].note_a_id, pairs[0].note_b_id), (1, 2));
+        assert!(pairs[0].similarity > 0.8 && pairs[0].similarity < 1.0);
+        assert!(db.get_similar_pairs(0.95, None).unwrap().is_empty());
     }
 }
@@ -0,0 +1,165 @@
+//! Queries across several vault indexes at once.
+//!
+//! Each vault keeps its own database; a [`FederatedIndex`] runs the same
+//! query against each and merges the results, tagging every hit with the
+//! vault (profile name) it came from. Paths stay relative to their own vault.
+
+use std::collections::HashMap;
+
+use super::db::{IndexDb, IndexError};
+use super::ranking::RankHook;
+use super::search::{SearchEngine, SearchQuery, SearchResult};
+use super::types::{IndexedNote, NoteQuery, NoteTombstone};
+
+/// A result tagged with the vault it came from.
+#[derive(Debug, Clone)]
+pub struct VaultHit<T> {
+    /// Vault id (the profile name).
+    pub vault: String,
+    pub item: T,
+}
+
+/// A set of vault indexes queried together.
+pub struct FederatedIndex {
+    vaults: Vec<(String, IndexDb)>,
+    rank_hooks: HashMap<String, RankHook>,
+}
+
+impl FederatedIndex {
+    /// Federate the given `(vault id, index)` pairs, in the order given.
+    pub fn new(vaults: Vec<(String, IndexDb)>) -> Self {
+        Self { vaults, rank_hooks: HashMap::new() }
+    }
+
+    /// Rank `vault`'s search results with its own `rank` function.
+    pub fn set_rank_hook(&mut self, vault: impl Into<String>, hook: RankHook) {
+        self.rank_hooks.insert(vault.into(), hook);
+    }
+
+    /// Vault ids, in query order.
+    pub fn vault_ids(&self) -> impl Iterator<Item = &str> {
+        self.vaults.iter().map(|(id, _)| id.as_str())
+    }
+
+    /// Notes matching `query` in every vault, grouped by vault.
+    ///
+    /// `query.limit` caps the combined result, not each vault.
+    pub fn query_notes(
+        &self,
+        query: &NoteQuery,
+    ) -> Result<Vec<VaultHit<IndexedNote>>, IndexError> {
+        let mut hits = Vec::new();
+        for (vault, db) in &self.vaults {
+            for note in db.query_notes(query)? {
+                hits.push(VaultHit { vault: vault.clone(), item: note });
+            }
+        }
+        if let Some(limit) = query.limit {
+            hits.truncate(limit as usize);
+        }
+        Ok(hits)
+    }
+
+    /// Tombstones of vanished notes in every vault, grouped by vault.
+    ///
+    /// `limit` caps the combined result, not each vault.
+    pub fn tombstones(
+        &self,
+        limit: Option<u32>,
+    ) -> Result<Vec<VaultHit<NoteTombstone>>, IndexError> {
+        let mut hits = Vec::new();
+        for (vault, db) in &self.vaults {
+            for tombstone in db.get_tombstones(None, limit)? {
+                hits.push(VaultHit { vault: vault.clone(), item: tombstone });
+            }
+        }
+        if let Some(limit) = limit {
+            hits.truncate(limit as usize);
+        }
+        Ok(hits)
+    }
+
+    /// Search every vault, merging results by score (highest first).
+    ///
+    /// Scores come from the same ranking in each vault, so they are
+    /// comparable; `query.limit` caps the merged result.
+    pub fn search(
+        &self,
+        query: &SearchQuery,
+    ) -> Result<Vec<VaultHit<SearchResult>>, IndexError> {
+        let mut hits = Vec::new();
+        for (vault, db) in &self.vaults {
+            let engine = SearchEngine::new(db).with_rank_hook(self.rank_hooks.get(vault));
+            for result in engine.search(query)? {
+                hits.push(VaultHit { vault: vault.clone(), item: result });
+            }
+        }
+        // Stable sort keeps vault order for equal scores
+        hits.sort_by(|a, b| b.item.score.total_cmp(&a.item.score));
+        if let Some(limit) = query.limit {
+            hits.truncate(limit as usize);
+        }
+        Ok(hits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::types::NoteType;
+    use chrono::Utc;
+    use std::path::PathBuf;
+
+    fn index_with(paths: &[&str]) -> IndexDb {
+        let db = IndexDb::open_in_memory().unwrap();
+        for path in paths {
+            db.insert_note(&IndexedNote {
+                id: None,
+                path: PathBuf::from(path),
+                note_type: NoteType::Zettel,
+                title: format!("Launch {path}"),
+                created: None,
+                modified: Utc::now(),
+                frontmatter_json: None,
+                content_hash: format!("hash-{path}"),
+                word_count: 0,
+                heading_json: None,
+            })
+            .unwrap();
+        }
+        db
+    }
+
+    fn federation() -> FederatedIndex {
+        FederatedIndex::new(vec![
+            ("personal".to_string(), index_with(&["garden.md"])),
+            ("work".to_string(), index_with(&["plan.md", "retro.md"])),
+        ])
+    }
+
+    #[test]
+    fn test_query_notes_tags_each_vault() {
+        let index = federation();
+        let hits = index.query_notes(&NoteQuery::default()).unwrap();
+        let vaults: Vec<_> = hits.iter().map(|h| h.vault.as_str()).collect();
+        assert_eq!(vaults, ["personal", "work", "work"]);
+        assert_eq!(hits[0].item.path, PathBuf::from("garden.md"));
+
+        let limited = NoteQuery { limit: Some(2), ..Default::default() };
+        assert_eq!(index.query_notes(&limited).unwrap().len(), 2);
+        assert_eq!(index.vault_ids().collect::<Vec<_>>(), ["personal", "work"]);
+    }
+
+    #[test]
+    fn test_search_merges_vaults() {
+        let query = SearchQuery {
+            text: Some("launch".to_string()),
+            limit: Some(10),
+            ..Default::default()
+        };
+        let hits = federation().search(&query).unwrap();
+        assert_eq!(hits.len(), 3);
+        assert!(hits.iter().any(|h| h.vault == "personal"));
+        assert!(hits.windows(2).all(|w| w[0].item.score >= w[1].item.score));
+    }
+}
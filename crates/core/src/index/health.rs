@@ -0,0 +1,332 @@
+//! Index integrity checks.
+//!
+//! Compares the index with the vault on disk and with itself: notes that were
+//! never indexed, rows for deleted files, links that would now resolve,
+//! content that changed since it was indexed, and full-text rows that no
+//! longer match their note. Every problem class can be repaired in place
+//! without a full reindex.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::builder::{BuilderError, IndexBuilder};
+use super::db::IndexDb;
+use super::types::NoteQuery;
+use crate::vault::{VaultWalker, content_hash};
+
+/// A class of index problem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum IssueKind {
+    /// A note on disk has no index row.
+    NotIndexed,
+    /// An index row points at a file that no longer exists.
+    FileMissing,
+    /// An unresolved link whose target note is now indexed.
+    UnresolvedLink,
+    /// The file changed since it was indexed.
+    HashMismatch,
+    /// The full-text row is missing, outdated, or has no note.
+    FtsDesync,
+}
+
+impl IssueKind {
+    /// All kinds, in report order.
+    pub const ALL: [IssueKind; 5] = [
+        Self::NotIndexed,
+        Self::FileMissing,
+        Self::UnresolvedLink,
+        Self::HashMismatch,
+        Self::FtsDesync,
+    ];
+
+    /// Stable identifier, e.g. `"not_indexed"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NotIndexed => "not_indexed",
+            Self::FileMissing => "file_missing",
+            Self::UnresolvedLink => "unresolved_link",
+            Self::HashMismatch => "hash_mismatch",
+            Self::FtsDesync => "fts_desync",
+        }
+    }
+
+    /// Human-readable heading for reports.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::NotIndexed => "notes on disk missing from the index",
+            Self::FileMissing => "index entries whose file was deleted",
+            Self::UnresolvedLink => "unresolved links whose target now exists",
+            Self::HashMismatch => "notes changed since they were indexed",
+            Self::FtsDesync => "full-text entries out of sync",
+        }
+    }
+}
+
+/// One problem found by [`IndexHealth::check`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexIssue {
+    pub kind: IssueKind,
+    /// Vault-relative path of the affected note (the link source for
+    /// [`IssueKind::UnresolvedLink`]).
+    pub path: PathBuf,
+    /// Extra context, e.g. the link target.
+    pub detail: Option<String>,
+}
+
+/// Result of an integrity check.
+#[derive(Debug, Clone, Default)]
+pub struct HealthReport {
+    /// Markdown files found in the vault.
+    pub notes_on_disk: usize,
+    /// Notes with an index row.
+    pub notes_indexed: usize,
+    pub issues: Vec<IndexIssue>,
+}
+
+impl HealthReport {
+    /// Whether no problems were found.
+    pub fn is_healthy(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Issues of one kind.
+    pub fn of_kind(&self, kind: IssueKind) -> impl Iterator<Item = &IndexIssue> {
+        self.issues.iter().filter(move |i| i.kind == kind)
+    }
+}
+
+/// How many issues of each kind [`IndexHealth::fix`] repaired.
+#[derive(Debug, Clone, Default)]
+pub struct FixStats {
+    pub fixed: HashMap<IssueKind, usize>,
+    /// Issues that could not be repaired, with the reason.
+    pub failed: Vec<(IndexIssue, String)>,
+}
+
+/// Checks and repairs the index of one vault.
+pub struct IndexHealth<'a> {
+    db: &'a IndexDb,
+    vault_root: &'a Path,
+    excluded_folders: Vec<PathBuf>,
+}
+
+impl<'a> IndexHealth<'a> {
+    pub fn new(
+        db: &'a IndexDb,
+        vault_root: &'a Path,
+        excluded_folders: Vec<PathBuf>,
+    ) -> Self {
+        Self { db, vault_root, excluded_folders }
+    }
+
+    /// Compare the index with the vault and report every problem found.
+    pub fn check(&self) -> Result<HealthReport, BuilderError> {
+        let walker =
+            VaultWalker::with_exclusions(self.vault_root, self.excluded_folders.clone())?;
+        let files = walker.walk()?;
+        let notes = self.db.query_notes(&NoteQuery::default())?;
+
+        let mut report = HealthReport {
+            notes_on_disk: files.len(),
+            notes_indexed: notes.len(),
+            issues: Vec::new(),
+        };
+        let issue = |kind, path: &Path, detail: Option<String>| IndexIssue {
+            kind,
+            path: path.to_path_buf(),
+            detail,
+        };
+
+        let indexed: HashMap<&Path, &str> =
+            notes.iter().map(|n| (n.path.as_path(), n.content_hash.as_str())).collect();
+        let on_disk: HashSet<&Path> =
+            files.iter().map(|f| f.relative_path.as_path()).collect();
+
+        for file in &files {
+            match indexed.get(file.relative_path.as_path()) {
+                None => report.issues.push(issue(
+                    IssueKind::NotIndexed,
+                    &file.relative_path,
+                    None,
+                )),
+                Some(stored) => {
+                    // Unreadable files surface as hash mismatches; reindexing
+                    // reports the underlying error
+                    let current = content_hash(&file.absolute_path).unwrap_or_default();
+                    if current != *stored {
+                        report.issues.push(issue(
+                            IssueKind::HashMismatch,
+                            &file.relative_path,
+                            None,
+                        ));
+                    }
+                }
+            }
+        }
+
+        for note in &notes {
+            if !on_disk.contains(note.path.as_path()) {
+                report.issues.push(issue(IssueKind::FileMissing, &note.path, None));
+            }
+        }
+
+        for (source, target) in self.db.find_resolvable_links()? {
+            report.issues.push(issue(IssueKind::UnresolvedLink, &source, Some(target)));
+        }
+
+        for note in self.db.get_notes_with_stale_text()? {
+            report.issues.push(issue(IssueKind::FtsDesync, &note.path, None));
+        }
+        let orphans = self.db.count_orphan_note_text()?;
+        if orphans > 0 {
+            report.issues.push(issue(
+                IssueKind::FtsDesync,
+                Path::new(""),
+                Some(format!("{orphans} entries without a note")),
+            ));
+        }
+
+        report.issues.sort_by(|a, b| (a.kind, &a.path).cmp(&(b.kind, &b.path)));
+        Ok(report)
+    }
+
+    /// Repair the issues in `report`.
+    ///
+    /// Deleted files are dropped from the index, new and changed notes are
+    /// reindexed, full-text rows are rebuilt, and links are re-resolved last
+    /// so they can point at notes added in the same pass.
+    pub fn fix(&self, report: &HealthReport) -> Result<FixStats, BuilderError> {
+        let builder = IndexBuilder::with_exclusions(
+            self.db,
+            self.vault_root,
+            self.excluded_folders.clone(),
+        );
+        let mut stats = FixStats::default();
+        let mut record = |issue: &IndexIssue, result: Result<(), String>| match result {
+            Ok(()) => *stats.fixed.entry(issue.kind).or_default() += 1,
+            Err(e) => stats.failed.push((issue.clone(), e)),
+        };
+
+        for issue in report.of_kind(IssueKind::FileMissing) {
+            record(
+                issue,
+                self.db.bury_note(&issue.path).map(|_| ()).map_err(|e| e.to_string()),
+            );
+        }
+        let reindexed: HashSet<&Path> = report
+            .issues
+            .iter()
+            .filter(|i| matches!(i.kind, IssueKind::NotIndexed | IssueKind::HashMismatch))
+            .map(|i| {
+                record(i, builder.reindex_file(&i.path).map_err(|e| e.to_string()));
+                i.path.as_path()
+            })
+            .collect();
+
+        for issue in report.of_kind(IssueKind::FtsDesync) {
+            let result = if issue.path.as_os_str().is_empty() {
+                self.db.delete_orphan_note_text().map(|_| ()).map_err(|e| e.to_string())
+            } else if reindexed.contains(issue.path.as_path()) {
+                Ok(())
+            } else {
+                match self.db.get_note_by_path(&issue.path) {
+                    Ok(Some(note)) => {
+                        builder.index_note_text(&note).map_err(|e| e.to_string())
+                    }
+                    Ok(None) => Ok(()),
+                    Err(e) => Err(e.to_string()),
+                }
+            };
+            record(issue, result);
+        }
+
+        self.db.resolve_link_targets()?;
+        for issue in report.of_kind(IssueKind::UnresolvedLink) {
+            record(issue, Ok(()));
+        }
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn indexed_vault() -> (TempDir, IndexDb) {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.md"), "# A\n\nSee [[b]] and [[c]].\n").unwrap();
+        fs::write(dir.path().join("b.md"), "# B\n").unwrap();
+        let db = IndexDb::open_in_memory().unwrap();
+        IndexBuilder::new(&db, dir.path()).full_reindex(None).unwrap();
+        (dir, db)
+    }
+
+    fn kinds(report: &HealthReport) -> Vec<(IssueKind, String)> {
+        report
+            .issues
+            .iter()
+            .map(|i| (i.kind, i.path.to_string_lossy().into_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn fresh_index_is_healthy() {
+        let (dir, db) = indexed_vault();
+        let report = IndexHealth::new(&db, dir.path(), Vec::new()).check().unwrap();
+        assert!(report.is_healthy(), "{:?}", report.issues);
+        assert_eq!((report.notes_on_disk, report.notes_indexed), (2, 2));
+    }
+
+    #[test]
+    fn detects_and_fixes_every_kind() {
+        let (dir, db) = indexed_vault();
+        // c.md appears on disk without being indexed; a link to it is pending
+        fs::write(dir.path().join("c.md"), "# C\n").unwrap();
+        IndexBuilder::new(&db, dir.path()).reindex_file(Path::new("c.md")).unwrap();
+        fs::write(dir.path().join("d.md"), "# D\n").unwrap();
+        fs::write(dir.path().join("b.md"), "# B changed\n").unwrap();
+        fs::remove_file(dir.path().join("a.md")).unwrap();
+        fs::write(dir.path().join("e.md"), "# E\n\n[[c]]\n").unwrap();
+        IndexBuilder::new(&db, dir.path()).reindex_file(Path::new("e.md")).unwrap();
+        db.connection().execute("DELETE FROM notes_fts WHERE title = 'E'", []).unwrap();
+
+        let health = IndexHealth::new(&db, dir.path(), Vec::new());
+        let report = health.check().unwrap();
+        assert_eq!(
+            kinds(&report),
+            vec![
+                (IssueKind::NotIndexed, "d.md".to_string()),
+                (IssueKind::FileMissing, "a.md".to_string()),
+                (IssueKind::UnresolvedLink, "a.md".to_string()),
+                (IssueKind::UnresolvedLink, "e.md".to_string()),
+                (IssueKind::HashMismatch, "b.md".to_string()),
+                (IssueKind::FtsDesync, "e.md".to_string()),
+            ]
+        );
+
+        let stats = health.fix(&report).unwrap();
+        assert!(stats.failed.is_empty(), "{:?}", stats.failed);
+        assert_eq!(stats.fixed.values().sum::<usize>(), report.issues.len());
+        assert!(health.check().unwrap().is_healthy());
+    }
+
+    #[test]
+    fn orphan_fulltext_rows_are_removed() {
+        let (dir, db) = indexed_vault();
+        db.connection()
+            .execute(
+                "INSERT INTO notes_fts (rowid, title, body, content_hash) VALUES (999, 'x', 'y', 'z')",
+                [],
+            )
+            .unwrap();
+
+        let health = IndexHealth::new(&db, dir.path(), Vec::new());
+        let report = health.check().unwrap();
+        assert_eq!(kinds(&report), vec![(IssueKind::FtsDesync, String::new())]);
+
+        health.fix(&report).unwrap();
+        assert!(health.check().unwrap().is_healthy());
+    }
+}
@@ -0,0 +1,283 @@
+//! Graph impact of moving a folder of notes out of the way.
+//!
+//! Archiving moves a project folder under `_archive/`. Before that happens
+//! this answers, from the links table: which links from outside the folder
+//! are rewritten by the move and which will break, which dailies mentioned
+//! the folder's notes recently, and which open tasks elsewhere link into it.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use super::db::{IndexDb, IndexError};
+use super::types::{LinkType, NoteType, TaskStatus};
+
+/// What happens to an inbound link when its target moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkFate {
+    /// Names the target by path; the move rewrites it.
+    Rewritten,
+    /// Names the target by basename; it keeps resolving untouched.
+    Unchanged,
+    /// Points at a non-note file by path, which moves without link updates.
+    Broken,
+}
+
+/// A link from outside the folder to something inside it.
+#[derive(Debug, Clone, Serialize)]
+pub struct InboundLink {
+    /// Note containing the link.
+    pub source: PathBuf,
+    /// Target as written in the link.
+    pub target: String,
+    pub link_type: LinkType,
+    pub line_number: Option<u32>,
+    pub fate: LinkFate,
+}
+
+/// A daily note that mentioned the folder's notes.
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyMention {
+    pub path: PathBuf,
+    /// The daily's date.
+    pub date: NaiveDate,
+}
+
+/// An unfinished task outside the folder that links into it.
+#[derive(Debug, Clone, Serialize)]
+pub struct DependentTask {
+    pub path: PathBuf,
+    pub title: String,
+    /// Status as written in the task's frontmatter.
+    pub status: String,
+}
+
+/// Impact of archiving a folder, computed from the index.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ArchiveImpact {
+    pub inbound_links: Vec<InboundLink>,
+    /// Dailies since the cut-off date, most recent first.
+    pub recent_dailies: Vec<DailyMention>,
+    pub dependent_tasks: Vec<DependentTask>,
+}
+
+impl ArchiveImpact {
+    /// Number of inbound links with the given fate.
+    pub fn count(&self, fate: LinkFate) -> usize {
+        self.inbound_links.iter().filter(|l| l.fate == fate).count()
+    }
+
+    /// Whether archiving touches anything outside the folder.
+    pub fn is_empty(&self) -> bool {
+        self.inbound_links.is_empty()
+            && self.recent_dailies.is_empty()
+            && self.dependent_tasks.is_empty()
+    }
+}
+
+/// Analyse moving `folder` (relative to `vault_root`) out of the way.
+///
+/// Dailies count as recent from `since` on; daily dates come from the derived
+/// temporal index, so they are as fresh as the last `mdv reindex`.
+pub fn archive_impact(
+    db: &IndexDb,
+    vault_root: &Path,
+    folder: &Path,
+    since: NaiveDate,
+) -> Result<ArchiveImpact, IndexError> {
+    let prefix = format!("{}/", folder.to_string_lossy().trim_end_matches('/'));
+    let prefix_len = prefix.chars().count() as i64;
+    let conn = db.connection();
+
+    // Links from outside the folder to notes in it, to other files under it
+    // by path, or (unresolved, as the index only resolves paths) to one of
+    // its notes by name
+    let mut stmt =
+        conn.prepare("SELECT path FROM notes WHERE substr(path, 1, ?2) = ?1")?;
+    let names: HashSet<String> = stmt
+        .query_map(rusqlite::params![prefix, prefix_len], |row| row.get::<_, String>(0))?
+        .filter_map(|r| r.ok())
+        .filter_map(|path| {
+            Some(Path::new(&path).file_stem()?.to_string_lossy().to_lowercase())
+        })
+        .collect();
+
+    let mut stmt = conn.prepare(
+        "SELECT s.path, l.target_path, l.link_type, l.line_number, l.target_id
+         FROM links l
+         JOIN notes s ON s.id = l.source_id
+         LEFT JOIN notes t ON t.id = l.target_id
+         WHERE substr(s.path, 1, ?2) != ?1
+           AND (substr(t.path, 1, ?2) = ?1
+                OR (l.target_id IS NULL AND l.target_path != ''))
+         ORDER BY s.path, l.line_number",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![prefix, prefix_len], |row| {
+        let link_type: String = row.get(2)?;
+        Ok((
+            PathBuf::from(row.get::<_, String>(0)?),
+            row.get::<_, String>(1)?,
+            LinkType::parse(&link_type).unwrap_or(LinkType::Wikilink),
+            row.get::<_, Option<u32>>(3)?,
+            row.get::<_, Option<i64>>(4)?.is_some(),
+        ))
+    })?;
+
+    let mut inbound_links = Vec::new();
+    for row in rows {
+        let (source, target, link_type, line_number, resolved) = row?;
+        let fate = if resolved {
+            LinkFate::Rewritten
+        } else if target.starts_with(&prefix) {
+            // Already broken unless the file exists; then the move breaks it
+            if !vault_root.join(&target).is_file() {
+                continue;
+            }
+            LinkFate::Broken
+        } else if !target.contains('/')
+            && names.contains(&target.trim_end_matches(".md").to_lowercase())
+        {
+            LinkFate::Unchanged
+        } else {
+            continue;
+        };
+        inbound_links.push(InboundLink { source, target, link_type, line_number, fate });
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT d.path, MAX(ta.activity_date)
+         FROM temporal_activity ta
+         JOIN notes d ON d.id = ta.daily_id
+         JOIN notes n ON n.id = ta.note_id
+         WHERE substr(n.path, 1, ?2) = ?1 AND substr(d.path, 1, ?2) != ?1
+           AND ta.activity_date >= ?3
+         GROUP BY d.path
+         ORDER BY 2 DESC, d.path",
+    )?;
+    let recent_dailies = stmt
+        .query_map(
+            rusqlite::params![prefix, prefix_len, since.format("%Y-%m-%d").to_string()],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+        )?
+        .filter_map(|r| r.ok())
+        .filter_map(|(path, date)| {
+            let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok()?;
+            Some(DailyMention { path: path.into(), date })
+        })
+        .collect();
+
+    // Tasks elsewhere that link into the folder, whichever way the link is written
+    let mut sources: Vec<&Path> = inbound_links
+        .iter()
+        .filter(|l| l.fate != LinkFate::Broken)
+        .map(|l| l.source.as_path())
+        .collect();
+    sources.dedup();
+    let mut dependent_tasks = Vec::new();
+    for source in sources {
+        let Some(note) = db.get_note_by_path(source)? else { continue };
+        if note.note_type != NoteType::Task {
+            continue;
+        }
+        let status = note
+            .frontmatter_json
+            .and_then(|fm| serde_json::from_str::<serde_json::Value>(&fm).ok())
+            .and_then(|fm| fm.get("status")?.as_str().map(String::from))
+            .unwrap_or_else(|| "todo".to_string());
+        if matches!(
+            TaskStatus::parse(&status),
+            Some(TaskStatus::Done | TaskStatus::Cancelled)
+        ) {
+            continue;
+        }
+        dependent_tasks.push(DependentTask {
+            path: note.path,
+            title: note.title,
+            status,
+        });
+    }
+
+    Ok(ArchiveImpact { inbound_links, recent_dailies, dependent_tasks })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::{DerivedIndexBuilder, IndexBuilder};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, rel: &str, content: &str) {
+        let path = root.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_archive_impact() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "Projects/app/app.md", "---\ntype: project\n---\n# App\n");
+        write(
+            root,
+            "Projects/app/Tasks/APP-001.md",
+            "---\ntype: task\nstatus: todo\n---\nSee [[app]].\n",
+        );
+        write(root, "Projects/app/diagram.png", "png");
+        write(
+            root,
+            "Journal/2026-10-14.md",
+            "---\ntype: daily\ndate: 2026-10-14\n---\nWorked on [[Projects/app/app]].\n",
+        );
+        write(
+            root,
+            "Journal/2025-01-02.md",
+            "---\ntype: daily\ndate: 2025-01-02\n---\nKickoff for [[app]].\n",
+        );
+        write(
+            root,
+            "Projects/web/Tasks/WEB-001.md",
+            "---\ntype: task\nstatus: in-progress\n---\nNeeds [[app]].\n\
+             ![[Projects/app/diagram.png]]\n![[Projects/app/gone.png]]\n",
+        );
+        write(
+            root,
+            "Projects/web/Tasks/WEB-002.md",
+            "---\ntype: task\nstatus: done\n---\nUsed [[app]].\n",
+        );
+
+        let db = IndexDb::open_in_memory().unwrap();
+        IndexBuilder::new(&db, root).full_reindex(None).unwrap();
+        DerivedIndexBuilder::new(&db).compute_all().unwrap();
+
+        let since = NaiveDate::from_ymd_opt(2026, 9, 16).unwrap();
+        let impact = archive_impact(&db, root, Path::new("Projects/app"), since).unwrap();
+
+        // Links inside the folder are not counted
+        assert!(
+            impact.inbound_links.iter().all(|l| !l.source.starts_with("Projects/app"))
+        );
+        assert_eq!(impact.count(LinkFate::Rewritten), 1);
+        assert_eq!(impact.count(LinkFate::Unchanged), 3);
+        let broken: Vec<_> = impact
+            .inbound_links
+            .iter()
+            .filter(|l| l.fate == LinkFate::Broken)
+            .map(|l| l.target.as_str())
+            .collect();
+        assert_eq!(broken, ["Projects/app/diagram.png"]);
+
+        let dailies: Vec<_> = impact.recent_dailies.iter().map(|d| &d.path).collect();
+        assert_eq!(dailies, [Path::new("Journal/2026-10-14.md")]);
+
+        assert_eq!(impact.dependent_tasks.len(), 1);
+        assert_eq!(
+            impact.dependent_tasks[0].path,
+            Path::new("Projects/web/Tasks/WEB-001.md")
+        );
+        assert_eq!(impact.dependent_tasks[0].status, "in-progress");
+    }
+}
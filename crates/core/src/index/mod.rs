@@ -4,6 +4,19 @@
 //! - Note metadata (path, type, title, frontmatter)
 //! - Links between notes (wikilinks, markdown links, frontmatter refs)
 //! - Temporal activity (when notes are referenced in dailies)
+//! - Full-text search over note bodies (SQLite FTS5)
+//! - Checkbox list items (`- [ ]`) as lightweight tasks
+//! - Headings and block IDs, so `[[note#Section]]` and `[[note#^id]]` resolve
+//! - Frontmatter fields as key/value rows, for `priority>=3`-style filters
+//!
+//! The note and link types are plain data and are always built; the
+//! database itself needs the `native` feature.
+//!
+//! [`IndexHealth`] checks the index against the vault and repairs drift,
+//! [`FederatedIndex`] queries several vaults' indexes together, and
+//! [`RankHook`] lets a vault reorder search results from Lua,
+//! [`StalenessModel`] scores how forgotten each note is, and
+//! [`suggest_links`] finds wikilinks a note is missing.
 //!
 //! # Example
 //!
@@ -21,19 +34,81 @@
 //! let tasks = db.query_notes(&query).unwrap();
 //! ```
 
+#[cfg(feature = "native")]
 pub mod builder;
+#[cfg(feature = "native")]
 pub mod db;
+#[cfg(feature = "native")]
 pub mod derived;
+#[cfg(feature = "native")]
+pub mod federation;
+#[cfg(feature = "native")]
+pub mod health;
+#[cfg(feature = "native")]
+pub mod impact;
+#[cfg(feature = "native")]
+pub mod orphans;
+pub mod page;
+#[cfg(feature = "native")]
+pub mod ranking;
+#[cfg(feature = "native")]
+pub mod related;
+#[cfg(feature = "native")]
 pub mod schema;
+#[cfg(feature = "native")]
 pub mod search;
+#[cfg(feature = "native")]
+pub mod staleness;
+#[cfg(feature = "native")]
+pub mod suggest;
+#[cfg(feature = "native")]
+pub mod traversal;
 pub mod types;
 
+#[cfg(feature = "native")]
 pub use builder::{BuilderError, FileChange, IndexBuilder, IndexStats, ProgressCallback};
+#[cfg(feature = "native")]
 pub use db::{IndexDb, IndexError};
-pub use derived::{DerivedError, DerivedIndexBuilder, DerivedStats};
+#[cfg(feature = "native")]
+pub use derived::{
+    DerivedError, DerivedIndexBuilder, DerivedStats, MIN_DUPLICATE_SIMILARITY,
+    SHINGLE_WORDS,
+};
+#[cfg(feature = "native")]
+pub use federation::{FederatedIndex, VaultHit};
+#[cfg(feature = "native")]
+pub use health::{FixStats, HealthReport, IndexHealth, IndexIssue, IssueKind};
+#[cfg(feature = "native")]
+pub use impact::{
+    ArchiveImpact, DailyMention, DependentTask, InboundLink, LinkFate, archive_impact,
+};
+#[cfg(feature = "native")]
+pub use orphans::{OrphanFilter, find_orphans};
+pub use page::{CursorError, DEFAULT_PAGE_SIZE, Page, paginate, query_fingerprint};
+#[cfg(feature = "native")]
+pub use ranking::{RankHook, RankHookError, RankSignals};
+#[cfg(feature = "native")]
+pub use related::{RelatedNote, RelatedSignals, related_notes};
+#[cfg(feature = "native")]
 pub use schema::{SCHEMA_VERSION, SchemaError};
-pub use search::{MatchSource, SearchEngine, SearchMode, SearchQuery, SearchResult};
+#[cfg(feature = "native")]
+pub use search::{
+    MatchSource, QueryExpr, QueryParseError, SearchEngine, SearchMode, SearchQuery,
+    SearchResult,
+};
+#[cfg(feature = "native")]
+pub use staleness::{
+    StalenessBreakdown, StalenessComponent, StalenessError, StalenessModel,
+    StalenessSignals, staleness_signals,
+};
+#[cfg(feature = "native")]
+pub use suggest::{LinkSuggestion, SuggestionSignals, suggest_links};
+#[cfg(feature = "native")]
+pub use traversal::{LinkDirection, ReachedNote, walk_links};
 pub use types::{
-    ActivitySummary, AggregateActivity, CooccurrencePair, IndexedLink, IndexedNote,
-    LinkType, NoteQuery, NoteType, ProjectStatus, TaskStatus, TemporalActivity,
+    ActivitySummary, AggregateActivity, AnchorKind, BodyHit, CheckboxQuery,
+    CooccurrencePair, Excerpt, FieldFilter, FieldFilterError, FieldOp, FullTextMatch,
+    IndexedCheckbox, IndexedLink, IndexedNote, LinkType, NoteAnchor, NoteHeading,
+    NoteQuery, NoteTombstone, NoteType, ProjectStatus, READING_WPM, SimilarityPair,
+    TaskStatus, TemporalActivity, VacuumStats, slugify,
 };
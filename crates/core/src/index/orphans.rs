@@ -0,0 +1,193 @@
+//! Orphan detection with the vault's whitelist.
+//!
+//! A note is an orphan when nothing links to it. Beyond the resolved links
+//! in the index, a frontmatter reference by name (`project: my-project`,
+//! `parent: "[[Roadmap]]"`) counts as an incoming link to the note with that
+//! file name, since such fields name notes rather than give their paths.
+//! Notes whose type or path the `[orphans]` config ignores, and notes
+//! younger than the minimum age, are never reported.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+
+use super::db::{IndexDb, IndexError};
+use super::types::{IndexedNote, LinkType};
+use crate::config::types::OrphansConfig;
+use crate::vault::ignore::{IgnoreError, IgnoreRules};
+
+/// Which orphans to leave out.
+#[derive(Debug, Clone, Default)]
+pub struct OrphanFilter {
+    ignore_types: HashSet<String>,
+    ignore_paths: IgnoreRules,
+    min_age_days: Option<u32>,
+}
+
+impl OrphanFilter {
+    /// The whitelist from an `[orphans]` config table.
+    pub fn from_config(config: &OrphansConfig) -> Result<Self, IgnoreError> {
+        let mut ignore_paths = IgnoreRules::default();
+        for pattern in &config.ignore_paths {
+            ignore_paths.add(pattern)?;
+        }
+        Ok(Self {
+            ignore_types: config.ignore_types.iter().map(|t| t.to_lowercase()).collect(),
+            ignore_paths,
+            min_age_days: config.min_age_days,
+        })
+    }
+
+    /// Skip notes created fewer than `days` days ago.
+    pub fn min_age(mut self, days: u32) -> Self {
+        self.min_age_days = Some(days);
+        self
+    }
+
+    /// Whether `note` may be reported.
+    fn allows(&self, note: &IndexedNote) -> bool {
+        if self.ignore_types.contains(note.note_type.as_str()) {
+            return false;
+        }
+        if self.ignore_paths.is_ignored(&note.path, false) {
+            return false;
+        }
+        match self.min_age_days {
+            Some(days) => {
+                let born = note.created.unwrap_or(note.modified);
+                (Utc::now() - born).num_days() >= days as i64
+            }
+            None => true,
+        }
+    }
+}
+
+/// Notes nothing links to, by path, minus those `filter` leaves out.
+pub fn find_orphans(
+    db: &IndexDb,
+    filter: &OrphanFilter,
+) -> Result<Vec<IndexedNote>, IndexError> {
+    let mut referrers: HashMap<String, HashSet<i64>> = HashMap::new();
+    for link in db.get_unresolved_links(LinkType::Frontmatter)? {
+        if let Some(name) = reference_name(&link.target_path) {
+            referrers.entry(name).or_default().insert(link.source_id);
+        }
+    }
+
+    let mut orphans: Vec<IndexedNote> = db
+        .find_orphans()?
+        .into_iter()
+        .filter(|note| filter.allows(note))
+        .filter(|note| {
+            let Some(stem) = note.path.file_stem() else {
+                return true;
+            };
+            let referenced = referrers
+                .get(&stem.to_string_lossy().to_lowercase())
+                .is_some_and(|sources| sources.iter().any(|&s| Some(s) != note.id));
+            !referenced
+        })
+        .collect();
+    orphans.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(orphans)
+}
+
+/// The note name a frontmatter value refers to: the last path segment,
+/// without `[[ ]]`, alias, anchor or `.md`, lowercased.
+fn reference_name(value: &str) -> Option<String> {
+    let value = value.trim();
+    let value =
+        value.strip_prefix("[[").and_then(|v| v.strip_suffix("]]")).unwrap_or(value);
+    let value = value.split(['|', '#']).next().unwrap_or(value).trim();
+    let name = value.rsplit('/').next().unwrap_or(value);
+    let name = name.strip_suffix(".md").unwrap_or(name);
+    (!name.is_empty()).then(|| name.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::types::{IndexedLink, NoteType};
+    use std::path::{Path, PathBuf};
+
+    fn add(db: &IndexDb, path: &str, note_type: NoteType, age_days: i64) -> i64 {
+        db.insert_note(&IndexedNote {
+            id: None,
+            path: PathBuf::from(path),
+            note_type,
+            title: path.to_string(),
+            created: None,
+            modified: Utc::now() - chrono::Duration::days(age_days),
+            frontmatter_json: None,
+            content_hash: format!("hash-{path}"),
+            word_count: 0,
+            heading_json: None,
+        })
+        .unwrap()
+    }
+
+    fn reference(db: &IndexDb, from: i64, target: &str) {
+        db.insert_link(&IndexedLink {
+            id: None,
+            source_id: from,
+            target_id: None,
+            target_path: target.to_string(),
+            link_text: None,
+            link_type: LinkType::Frontmatter,
+            context: None,
+            line_number: None,
+            target_anchor: None,
+        })
+        .unwrap();
+    }
+
+    fn paths(notes: &[IndexedNote]) -> Vec<&Path> {
+        notes.iter().map(|n| n.path.as_path()).collect()
+    }
+
+    #[test]
+    fn frontmatter_references_count_as_links() {
+        let db = IndexDb::open_in_memory().unwrap();
+        add(&db, "Projects/garden/garden.md", NoteType::Project, 100);
+        add(&db, "Roadmap.md", NoteType::Zettel, 100);
+        let task = add(&db, "Projects/garden/Tasks/GAR-001.md", NoteType::Task, 100);
+        reference(&db, task, "garden");
+        reference(&db, task, "[[Roadmap|the plan]]");
+        let lonely = add(&db, "lonely.md", NoteType::Zettel, 100);
+        reference(&db, lonely, "lonely");
+
+        let orphans = find_orphans(&db, &OrphanFilter::default()).unwrap();
+        assert_eq!(
+            paths(&orphans),
+            [Path::new("Projects/garden/Tasks/GAR-001.md"), Path::new("lonely.md")]
+        );
+    }
+
+    #[test]
+    fn whitelist_and_min_age() {
+        let db = IndexDb::open_in_memory().unwrap();
+        add(&db, "daily/2024-01-01.md", NoteType::Daily, 100);
+        add(&db, "MOCs/index.md", NoteType::Zettel, 100);
+        add(&db, "fresh.md", NoteType::Zettel, 1);
+        add(&db, "old.md", NoteType::Zettel, 100);
+
+        let config = OrphansConfig {
+            ignore_types: vec!["Daily".into()],
+            ignore_paths: vec!["MOCs/".into()],
+            min_age_days: None,
+        };
+        let filter = OrphanFilter::from_config(&config).unwrap();
+        let orphans = find_orphans(&db, &filter).unwrap();
+        assert_eq!(paths(&orphans), [Path::new("fresh.md"), Path::new("old.md")]);
+
+        let orphans = find_orphans(&db, &filter.min_age(7)).unwrap();
+        assert_eq!(paths(&orphans), [Path::new("old.md")]);
+    }
+
+    #[test]
+    fn reference_names() {
+        assert_eq!(reference_name("my-project").as_deref(), Some("my-project"));
+        assert_eq!(reference_name("[[Notes/Idea.md#Part]]").as_deref(), Some("idea"));
+        assert_eq!(reference_name("[[]]"), None);
+    }
+}
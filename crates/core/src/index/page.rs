@@ -0,0 +1,129 @@
+//! Cursor-based paging over ordered results.
+//!
+//! A cursor is an opaque token naming where the next page starts. It also
+//! carries a fingerprint of the query that produced it, so handing it to a
+//! different query is an error rather than a silently shifted page. Pages
+//! only line up if the results come in the same order every time, which is
+//! why note listings and search results break ties by path.
+
+use thiserror::Error;
+
+/// Page size when `--cursor` is given without `--page-size`.
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Prefix identifying the cursor format.
+const CURSOR_PREFIX: &str = "c1";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CursorError {
+    #[error("invalid cursor '{0}'")]
+    Invalid(String),
+
+    #[error("cursor '{0}' belongs to a different query")]
+    Mismatch(String),
+
+    #[error("page size must be at least 1")]
+    ZeroPageSize,
+}
+
+/// One page of results.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Token for the following page; `None` on the last one.
+    pub next_cursor: Option<String>,
+}
+
+/// Fingerprint of a query, from a description of everything that shapes its
+/// results (filters, mode, limit, vaults).
+pub fn query_fingerprint(description: &str) -> u64 {
+    // FNV-1a: stable across builds, so cursors survive an upgrade
+    description.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Cut the page starting at `cursor` (or the first page) out of `items`.
+pub fn paginate<T>(
+    items: Vec<T>,
+    cursor: Option<&str>,
+    page_size: usize,
+    fingerprint: u64,
+) -> Result<Page<T>, CursorError> {
+    if page_size == 0 {
+        return Err(CursorError::ZeroPageSize);
+    }
+    let offset = match cursor {
+        Some(token) => decode(token, fingerprint)?,
+        None => 0,
+    };
+    let end = offset.saturating_add(page_size);
+    let next_cursor = (end < items.len()).then(|| encode(end, fingerprint));
+    let items = items.into_iter().skip(offset).take(page_size).collect();
+    Ok(Page { items, next_cursor })
+}
+
+fn encode(offset: usize, fingerprint: u64) -> String {
+    format!("{CURSOR_PREFIX}{:016x}{fingerprint:016x}", offset as u64)
+}
+
+fn decode(token: &str, fingerprint: u64) -> Result<usize, CursorError> {
+    let invalid = || CursorError::Invalid(token.to_string());
+    let body = token.strip_prefix(CURSOR_PREFIX).ok_or_else(invalid)?;
+    if body.len() != 32 || !body.is_ascii() {
+        return Err(invalid());
+    }
+    let offset = u64::from_str_radix(&body[..16], 16).map_err(|_| invalid())?;
+    let theirs = u64::from_str_radix(&body[16..], 16).map_err(|_| invalid())?;
+    if theirs != fingerprint {
+        return Err(CursorError::Mismatch(token.to_string()));
+    }
+    usize::try_from(offset).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn walks_pages_to_the_end() {
+        let fp = query_fingerprint("list");
+        let items: Vec<u32> = (0..5).collect();
+
+        let first = paginate(items.clone(), None, 2, fp).unwrap();
+        assert_eq!(first.items, [0, 1]);
+        let second =
+            paginate(items.clone(), first.next_cursor.as_deref(), 2, fp).unwrap();
+        assert_eq!(second.items, [2, 3]);
+        let last = paginate(items.clone(), second.next_cursor.as_deref(), 2, fp).unwrap();
+        assert_eq!(last.items, [4]);
+        assert_eq!(last.next_cursor, None);
+
+        let exact = paginate(items, None, 5, fp).unwrap();
+        assert_eq!(exact.next_cursor, None);
+    }
+
+    #[test]
+    fn rejects_foreign_and_malformed_cursors() {
+        let page = paginate(vec![1, 2, 3], None, 1, query_fingerprint("a")).unwrap();
+        let cursor = page.next_cursor.unwrap();
+        assert!(matches!(
+            paginate(vec![1, 2, 3], Some(&cursor), 1, query_fingerprint("b")),
+            Err(CursorError::Mismatch(_))
+        ));
+        assert!(matches!(
+            paginate(vec![1], Some("c1zz"), 1, 0),
+            Err(CursorError::Invalid(_))
+        ));
+        assert_eq!(paginate(vec![1], None, 0, 0), Err(CursorError::ZeroPageSize));
+    }
+
+    #[test]
+    fn cursors_past_the_end_give_an_empty_page() {
+        let fp = query_fingerprint("q");
+        let page =
+            paginate((0..3).collect::<Vec<_>>(), Some(&encode(10, fp)), 2, fp).unwrap();
+        assert!(page.items.is_empty());
+        assert_eq!(page.next_cursor, None);
+    }
+}
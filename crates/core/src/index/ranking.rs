@@ -0,0 +1,229 @@
+//! User-defined search ranking from `.mdvault/ranking.lua`.
+//!
+//! The file defines a global `rank(note, base_score, signals)` function.
+//! [`SearchEngine`](super::SearchEngine) calls it once per candidate after
+//! the built-in scoring, and orders results by what it returns:
+//!
+//! ```lua
+//! function rank(note, base_score, signals)
+//!     -- Favour well-linked notes, fade out ones untouched for months
+//!     local score = base_score + 0.1 * signals.backlinks
+//!     if signals.days_since_modified > 90 then
+//!         score = score * 0.5
+//!     end
+//!     return score
+//! end
+//! ```
+//!
+//! `note` has `path`, `title`, `type` and `frontmatter`; `signals` has
+//! `backlinks`, `outlinks`, `days_since_modified`, `type`, `match` (`direct`,
+//! `linked`, `temporal` or `cooccurrence`) and `staleness` when known.
+//! Returning `nil` keeps `base_score`.
+
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use mlua::{Function, LuaSerdeExt, Value};
+use thiserror::Error;
+
+use super::search::{MatchSource, SearchResult};
+use crate::paths::PathResolver;
+use crate::scripting::{LuaEngine, ScriptingError};
+
+/// Errors loading a ranking script.
+#[derive(Debug, Error)]
+pub enum RankHookError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to evaluate {path}: {source}")]
+    Lua {
+        path: PathBuf,
+        #[source]
+        source: ScriptingError,
+    },
+
+    #[error("{path} does not define a rank(note, base_score, signals) function")]
+    MissingFunction { path: PathBuf },
+}
+
+/// What the index knows about a candidate, beyond its score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankSignals {
+    /// Links from other notes to this one.
+    pub backlinks: usize,
+    /// Links from this note to others.
+    pub outlinks: usize,
+    /// Whole days since the note file was last modified.
+    pub days_since_modified: i64,
+    /// Note type name.
+    pub note_type: String,
+    /// How the search reached the note.
+    pub match_source: &'static str,
+    /// Staleness score, when activity has been recorded.
+    pub staleness: Option<f64>,
+}
+
+/// A loaded `rank` function.
+pub struct RankHook {
+    engine: LuaEngine,
+}
+
+impl RankHook {
+    /// Load the vault's `.mdvault/ranking.lua`, if it exists.
+    pub fn load(vault_root: &Path) -> Result<Option<Self>, RankHookError> {
+        let path = PathResolver::new(vault_root).ranking();
+        if !path.exists() {
+            return Ok(None);
+        }
+        let source = std::fs::read_to_string(&path)
+            .map_err(|e| RankHookError::Io { path: path.clone(), source: e })?;
+        Self::from_source(&source, &path).map(Some)
+    }
+
+    /// Evaluate a ranking script; `path` is only used in errors.
+    pub fn from_source(source: &str, path: &Path) -> Result<Self, RankHookError> {
+        let lua_err = |e: ScriptingError| RankHookError::Lua {
+            path: path.to_path_buf(),
+            source: e,
+        };
+        let engine = LuaEngine::sandboxed().map_err(lua_err)?;
+        engine.lua().load(source).exec().map_err(|e| lua_err(e.into()))?;
+        let rank: Value =
+            engine.lua().globals().get("rank").map_err(|e| lua_err(e.into()))?;
+        if !matches!(rank, Value::Function(_)) {
+            return Err(RankHookError::MissingFunction { path: path.to_path_buf() });
+        }
+        Ok(Self { engine })
+    }
+
+    /// Score `result` with the user's function.
+    pub fn rank(
+        &self,
+        result: &SearchResult,
+        signals: &RankSignals,
+    ) -> Result<f64, ScriptingError> {
+        let lua = self.engine.lua();
+        let rank: Function = lua.globals().get("rank")?;
+
+        let note = lua.create_table()?;
+        note.set("path", result.note.path.to_string_lossy().to_string())?;
+        note.set("title", result.note.title.as_str())?;
+        note.set("type", result.note.note_type.as_str())?;
+        let frontmatter: serde_json::Value = result
+            .note
+            .frontmatter_json
+            .as_deref()
+            .and_then(|fm| serde_json::from_str(fm).ok())
+            .unwrap_or_else(|| serde_json::Value::Object(Default::default()));
+        note.set("frontmatter", lua.to_value(&frontmatter)?)?;
+
+        let table = lua.create_table()?;
+        table.set("backlinks", signals.backlinks)?;
+        table.set("outlinks", signals.outlinks)?;
+        table.set("days_since_modified", signals.days_since_modified)?;
+        table.set("type", signals.note_type.as_str())?;
+        table.set("match", signals.match_source)?;
+        table.set("staleness", signals.staleness)?;
+
+        match rank.call::<Value>((note, result.score, table))? {
+            Value::Nil => Ok(result.score),
+            Value::Integer(n) => Ok(n as f64),
+            Value::Number(n) => Ok(n),
+            other => Err(ScriptingError::Other(format!(
+                "rank() must return a number or nil, got {}",
+                other.type_name()
+            ))),
+        }
+    }
+}
+
+impl RankSignals {
+    /// Signals for a result, given its link counts.
+    pub fn new(result: &SearchResult, backlinks: usize, outlinks: usize) -> Self {
+        Self {
+            backlinks,
+            outlinks,
+            days_since_modified: (Utc::now() - result.note.modified).num_days().max(0),
+            note_type: result.note.note_type.as_str().to_string(),
+            match_source: match result.match_source {
+                MatchSource::Direct => "direct",
+                MatchSource::Linked { .. } => "linked",
+                MatchSource::Temporal { .. } => "temporal",
+                MatchSource::Cooccurrence { .. } => "cooccurrence",
+            },
+            staleness: result.staleness,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::types::{IndexedNote, NoteType};
+
+    fn result(score: f64) -> SearchResult {
+        SearchResult {
+            note: IndexedNote {
+                id: Some(1),
+                path: PathBuf::from("zettel/idea.md"),
+                note_type: NoteType::Zettel,
+                title: "Idea".into(),
+                created: None,
+                modified: Utc::now() - chrono::Duration::days(10),
+                frontmatter_json: Some(r#"{"priority": 3}"#.into()),
+                content_hash: "h".into(),
+                word_count: 0,
+                heading_json: None,
+            },
+            score,
+            match_source: MatchSource::Direct,
+            staleness: None,
+            snippet: None,
+            hits: Vec::new(),
+        }
+    }
+
+    fn hook(source: &str) -> RankHook {
+        RankHook::from_source(source, Path::new("ranking.lua")).unwrap()
+    }
+
+    #[test]
+    fn rank_sees_note_and_signals() {
+        let hook = hook(
+            r#"function rank(note, base, s)
+                return base + s.backlinks + note.frontmatter.priority
+                    + (s.days_since_modified >= 10 and 100 or 0)
+            end"#,
+        );
+        let r = result(0.5);
+        let signals = RankSignals::new(&r, 2, 0);
+        assert_eq!(signals.match_source, "direct");
+        assert_eq!(hook.rank(&r, &signals).unwrap(), 105.5);
+    }
+
+    #[test]
+    fn nil_keeps_base_score() {
+        let hook = hook("function rank() return nil end");
+        let r = result(0.7);
+        assert_eq!(hook.rank(&r, &RankSignals::new(&r, 0, 0)).unwrap(), 0.7);
+    }
+
+    #[test]
+    fn non_numbers_are_errors() {
+        let hook = hook(r#"function rank() return "high" end"#);
+        let r = result(0.7);
+        let err = hook.rank(&r, &RankSignals::new(&r, 0, 0)).unwrap_err();
+        assert!(err.to_string().contains("got string"), "{err}");
+    }
+
+    #[test]
+    fn scripts_must_define_rank() {
+        let err = RankHook::from_source("x = 1", Path::new("ranking.lua")).err().unwrap();
+        assert!(matches!(err, RankHookError::MissingFunction { .. }));
+    }
+}
@@ -0,0 +1,276 @@
+//! Notes related to a given note.
+//!
+//! Four signals are combined, each scaled to 0–1 and weighted:
+//! - shared outgoing links: Jaccard overlap of the notes each links to
+//! - co-occurrence: how many dailies mention both (from `note_cooccurrence`)
+//! - tags: Jaccard overlap of frontmatter `tags`
+//! - text: Jaccard overlap of the distinct words in each body
+//!
+//! Notes scoring zero on every signal are left out.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use super::db::{IndexDb, IndexError};
+use super::derived::jaccard;
+use super::types::{IndexedNote, NoteQuery};
+
+/// Weight of shared outgoing links in the combined score.
+const LINK_WEIGHT: f64 = 0.3;
+/// Weight of daily co-occurrence in the combined score.
+const COOCCURRENCE_WEIGHT: f64 = 0.2;
+/// Weight of tag overlap in the combined score.
+const TAG_WEIGHT: f64 = 0.2;
+/// Weight of body text overlap in the combined score.
+const TEXT_WEIGHT: f64 = 0.3;
+
+/// Words shorter than this are too common to say much about a note.
+const MIN_WORD_LEN: usize = 4;
+
+/// Why a note is related.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RelatedSignals {
+    /// Notes both link to.
+    pub shared_links: usize,
+    /// Dailies mentioning both.
+    pub cooccurrences: u32,
+    /// Frontmatter tags in common.
+    pub shared_tags: Vec<String>,
+    /// Jaccard similarity of the bodies' words (0–1).
+    pub text_similarity: f64,
+}
+
+/// A note related to the one asked about.
+#[derive(Debug, Clone)]
+pub struct RelatedNote {
+    pub note: IndexedNote,
+    /// Combined score (0–1, higher = more related).
+    pub score: f64,
+    pub signals: RelatedSignals,
+}
+
+/// The notes most related to `note_id`, best first, at most `limit`.
+pub fn related_notes(
+    db: &IndexDb,
+    note_id: i64,
+    limit: usize,
+) -> Result<Vec<RelatedNote>, IndexError> {
+    let notes: HashMap<i64, IndexedNote> = db
+        .query_notes(&NoteQuery::default())?
+        .into_iter()
+        .filter_map(|n| Some((n.id?, n)))
+        .collect();
+    let Some(target) = notes.get(&note_id) else {
+        return Err(IndexError::NoteNotFound(format!("id {note_id}")));
+    };
+
+    let mut signals: HashMap<i64, RelatedSignals> = HashMap::new();
+
+    // Shared outgoing links: every other source of a link into one of our
+    // targets shares that target
+    let targets = link_targets(db, note_id)?;
+    let mut shared_links: HashMap<i64, usize> = HashMap::new();
+    for &target_id in &targets {
+        let sources: HashSet<i64> = db
+            .get_backlinks(target_id)?
+            .into_iter()
+            .map(|l| l.source_id)
+            .filter(|&id| id != note_id && id != target_id)
+            .collect();
+        for source in sources {
+            *shared_links.entry(source).or_default() += 1;
+        }
+    }
+    let mut link_scores = HashMap::new();
+    for (id, common) in shared_links {
+        let theirs = link_targets(db, id)?.len();
+        link_scores.insert(id, jaccard(common, targets.len(), theirs));
+        signals.entry(id).or_default().shared_links = common;
+    }
+
+    for (note, count) in db.get_cooccurrent_notes(note_id, u32::MAX)? {
+        if let Some(id) = note.id {
+            signals.entry(id).or_default().cooccurrences = count.max(0) as u32;
+        }
+    }
+
+    let tags = note_tags(target);
+    if !tags.is_empty() {
+        for (&id, note) in &notes {
+            let common: Vec<String> =
+                note_tags(note).into_iter().filter(|t| tags.contains(t)).collect();
+            if !common.is_empty() {
+                signals.entry(id).or_default().shared_tags = common;
+            }
+        }
+    }
+    let mut tag_scores = HashMap::new();
+    for (&id, s) in &signals {
+        if !s.shared_tags.is_empty() {
+            let theirs = notes.get(&id).map(|n| note_tags(n).len()).unwrap_or(0);
+            tag_scores.insert(id, jaccard(s.shared_tags.len(), tags.len(), theirs));
+        }
+    }
+
+    let bodies = db.get_note_bodies()?;
+    let words = bodies.iter().find(|(id, _)| *id == note_id).map(|(_, b)| word_set(b));
+    if let Some(words) = words.filter(|w| !w.is_empty()) {
+        for (id, body) in &bodies {
+            if *id == note_id {
+                continue;
+            }
+            let theirs = word_set(body);
+            let common = words.intersection(&theirs).count();
+            if common > 0 {
+                signals.entry(*id).or_default().text_similarity =
+                    jaccard(common, words.len(), theirs.len());
+            }
+        }
+    }
+
+    let mut related: Vec<RelatedNote> = signals
+        .into_iter()
+        .filter(|(id, _)| *id != note_id)
+        .filter_map(|(id, signals)| {
+            let note = notes.get(&id)?.clone();
+            let score = LINK_WEIGHT * link_scores.get(&id).copied().unwrap_or(0.0)
+                + COOCCURRENCE_WEIGHT * saturate(signals.cooccurrences)
+                + TAG_WEIGHT * tag_scores.get(&id).copied().unwrap_or(0.0)
+                + TEXT_WEIGHT * signals.text_similarity;
+            (score > 0.0).then_some(RelatedNote { note, score, signals })
+        })
+        .collect();
+
+    related
+        .sort_by(|a, b| b.score.total_cmp(&a.score).then(a.note.path.cmp(&b.note.path)));
+    related.truncate(limit);
+    Ok(related)
+}
+
+/// Resolved link targets of a note, excluding itself.
+fn link_targets(db: &IndexDb, note_id: i64) -> Result<HashSet<i64>, IndexError> {
+    Ok(db
+        .get_outgoing_links(note_id)?
+        .into_iter()
+        .filter_map(|l| l.target_id)
+        .filter(|&id| id != note_id)
+        .collect())
+}
+
+/// Lowercased frontmatter `tags`, as a list or a comma-separated string.
+fn note_tags(note: &IndexedNote) -> Vec<String> {
+    let Some(fm) = note
+        .frontmatter_json
+        .as_deref()
+        .and_then(|fm| serde_json::from_str::<serde_json::Value>(fm).ok())
+    else {
+        return Vec::new();
+    };
+    let mut tags: Vec<String> = match fm.get("tags") {
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .filter_map(|t| t.as_str())
+            .map(|t| t.trim().trim_start_matches('#').to_lowercase())
+            .collect(),
+        Some(serde_json::Value::String(s)) => s
+            .split(',')
+            .map(|t| t.trim().trim_start_matches('#').to_lowercase())
+            .collect(),
+        _ => Vec::new(),
+    };
+    tags.retain(|t| !t.is_empty());
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Distinct lowercased words of at least [`MIN_WORD_LEN`] characters.
+fn word_set(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= MIN_WORD_LEN)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Map a count onto 0–1: one shared daily is 0.5, more approach 1.
+pub(crate) fn saturate(count: u32) -> f64 {
+    count as f64 / (count as f64 + 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::types::{IndexedLink, LinkType, NoteType};
+    use chrono::Utc;
+    use std::path::{Path, PathBuf};
+
+    fn add(db: &IndexDb, path: &str, frontmatter: Option<&str>, body: &str) -> i64 {
+        let id = db
+            .insert_note(&IndexedNote {
+                id: None,
+                path: PathBuf::from(path),
+                note_type: NoteType::Zettel,
+                title: path.to_string(),
+                created: None,
+                modified: Utc::now(),
+                frontmatter_json: frontmatter.map(String::from),
+                content_hash: format!("hash-{path}"),
+                word_count: 0,
+                heading_json: None,
+            })
+            .unwrap();
+        db.upsert_note_text(id, path, body, &format!("hash-{path}")).unwrap();
+        id
+    }
+
+    fn link(db: &IndexDb, from: i64, to: i64) {
+        db.insert_link(&IndexedLink {
+            id: None,
+            source_id: from,
+            target_id: Some(to),
+            target_path: format!("{to}"),
+            link_text: None,
+            link_type: LinkType::Wikilink,
+            context: None,
+            line_number: None,
+            target_anchor: None,
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn combines_links_tags_and_text() {
+        let db = IndexDb::open_in_memory().unwrap();
+        let rust = r#"{"tags": ["rust", "parsing"]}"#;
+        let me = add(&db, "me.md", Some(rust), "Parser combinators for config files");
+        let hub = add(&db, "hub.md", None, "Index");
+        let sibling = add(&db, "sibling.md", None, "Unrelated gardening");
+        let tagged = add(&db, "tagged.md", Some(r#"{"tags": "Rust"}"#), "Lifetimes");
+        let wordy = add(&db, "wordy.md", None, "Writing parser combinators by hand");
+        add(&db, "lonely.md", None, "Nothing here");
+        link(&db, me, hub);
+        link(&db, sibling, hub);
+
+        let related = related_notes(&db, me, 10).unwrap();
+        let by_id = |id| related.iter().find(|r| r.note.id == Some(id)).unwrap();
+
+        assert_eq!(by_id(sibling).signals.shared_links, 1);
+        assert_eq!(by_id(tagged).signals.shared_tags, ["rust"]);
+        assert!(by_id(wordy).signals.text_similarity > 0.0);
+        assert!(related.iter().all(|r| r.note.id != Some(me)));
+        assert!(related.iter().all(|r| r.note.path != Path::new("lonely.md")));
+        // A full link overlap (1.0 * 0.3) beats half the tags (0.5 * 0.2)
+        assert_eq!(related[0].note.id, Some(sibling));
+    }
+
+    #[test]
+    fn limit_and_unknown_notes() {
+        let db = IndexDb::open_in_memory().unwrap();
+        let a = add(&db, "a.md", None, "shared words here");
+        add(&db, "b.md", None, "shared words there");
+        add(&db, "c.md", None, "shared words everywhere");
+        assert_eq!(related_notes(&db, a, 1).unwrap().len(), 1);
+        assert!(matches!(related_notes(&db, 99, 5), Err(IndexError::NoteNotFound(_))));
+    }
+}
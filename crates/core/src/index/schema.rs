@@ -4,7 +4,7 @@ use rusqlite::Connection;
 use thiserror::Error;
 
 /// Current schema version.
-pub const SCHEMA_VERSION: i32 = 1;
+pub const SCHEMA_VERSION: i32 = 11;
 
 #[derive(Debug, Error)]
 pub enum SchemaError {
@@ -20,13 +20,16 @@ pub enum SchemaError {
 
 /// Initialize or migrate the database schema.
 pub fn init_schema(conn: &Connection) -> Result<(), SchemaError> {
-    let version = get_schema_version(conn)?;
+    let mut version = get_schema_version(conn)?;
 
     if version == 0 {
-        // Fresh database - create all tables
+        // Fresh database - create the base tables, then migrate forward
         create_schema_v1(conn)?;
-        set_schema_version(conn, SCHEMA_VERSION)?;
-    } else if version < SCHEMA_VERSION {
+        set_schema_version(conn, 1)?;
+        version = 1;
+    }
+
+    if version < SCHEMA_VERSION {
         // Run migrations
         migrate(conn, version)?;
     } else if version > SCHEMA_VERSION {
@@ -39,6 +42,21 @@ pub fn init_schema(conn: &Connection) -> Result<(), SchemaError> {
     Ok(())
 }
 
+/// Whether the schema is at the current version, without migrating.
+///
+/// For read-only connections, which cannot migrate: `false` means the
+/// database must first be opened read-write.
+pub fn schema_is_current(conn: &Connection) -> Result<bool, SchemaError> {
+    let version = get_schema_version(conn)?;
+    if version > SCHEMA_VERSION {
+        return Err(SchemaError::VersionTooNew {
+            found: version,
+            supported: SCHEMA_VERSION,
+        });
+    }
+    Ok(version == SCHEMA_VERSION)
+}
+
 fn get_schema_version(conn: &Connection) -> Result<i32, SchemaError> {
     // Check if schema_version table exists
     let exists: bool = conn.query_row(
@@ -154,20 +172,245 @@ fn create_schema_v1(conn: &Connection) -> Result<(), SchemaError> {
     Ok(())
 }
 
-fn migrate(_conn: &Connection, from_version: i32) -> Result<(), SchemaError> {
-    // Add migration steps here as schema evolves
-    // Example:
-    // match from_version {
-    //     1 => migrate_v1_to_v2(conn)?,
-    //     2 => migrate_v2_to_v3(conn)?,
-    //     _ => {}
-    // }
+/// Apply migrations one version at a time until the schema is current.
+fn migrate(conn: &Connection, from_version: i32) -> Result<(), SchemaError> {
+    let mut version = from_version;
+    while version < SCHEMA_VERSION {
+        match version {
+            1 => migrate_v1_to_v2(conn)?,
+            2 => migrate_v2_to_v3(conn)?,
+            3 => migrate_v3_to_v4(conn)?,
+            4 => migrate_v4_to_v5(conn)?,
+            5 => migrate_v5_to_v6(conn)?,
+            6 => migrate_v6_to_v7(conn)?,
+            7 => migrate_v7_to_v8(conn)?,
+            8 => migrate_v8_to_v9(conn)?,
+            9 => migrate_v9_to_v10(conn)?,
+            10 => migrate_v10_to_v11(conn)?,
+            _ => {
+                return Err(SchemaError::MigrationFailed(format!(
+                    "No migration path from version {} to {}",
+                    version, SCHEMA_VERSION
+                )));
+            }
+        }
+        version += 1;
+        set_schema_version(conn, version)?;
+    }
+    Ok(())
+}
+
+/// v2: full-text index over note bodies.
+///
+/// The v1 `notes_fts` table was declared but never populated, so it is
+/// replaced outright. Rows are keyed by note id (`rowid`) and carry the
+/// content hash they were built from, so unchanged notes are not rewritten.
+fn migrate_v1_to_v2(conn: &Connection) -> Result<(), SchemaError> {
+    conn.execute_batch(
+        r#"
+        DROP TABLE IF EXISTS notes_fts;
+
+        CREATE VIRTUAL TABLE notes_fts USING fts5(
+            title,
+            body,
+            content_hash UNINDEXED,
+            tokenize = 'porter unicode61'
+        );
+
+        -- Virtual tables do not take part in foreign keys; mirror the cascade
+        CREATE TRIGGER notes_fts_delete AFTER DELETE ON notes BEGIN
+            DELETE FROM notes_fts WHERE rowid = old.id;
+        END;
+        "#,
+    )?;
+
+    Ok(())
+}
+
+/// v3: checkbox list items as first-class task entries.
+///
+/// Existing notes have no rows yet; clearing their content hashes makes the
+/// next incremental reindex re-extract every note instead of skipping it.
+fn migrate_v2_to_v3(conn: &Connection) -> Result<(), SchemaError> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE checkbox_items (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            note_id INTEGER NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+            line_number INTEGER NOT NULL,
+            text TEXT NOT NULL,
+            status TEXT NOT NULL,
+            heading TEXT
+        );
+
+        CREATE INDEX idx_checkbox_note ON checkbox_items(note_id);
+        CREATE INDEX idx_checkbox_status ON checkbox_items(status);
+
+        UPDATE notes SET content_hash = '';
+        "#,
+    )?;
+
+    Ok(())
+}
+
+/// v4: heading and block anchors as link targets.
+///
+/// Links gain the fragment that used to be left in `target_path`, which kept
+/// `[[note#Section]]` from resolving at all. Hashes are cleared as in v3 so
+/// existing links are re-extracted.
+fn migrate_v3_to_v4(conn: &Connection) -> Result<(), SchemaError> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE links ADD COLUMN target_anchor TEXT;
+
+        CREATE TABLE note_anchors (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            note_id INTEGER NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+            kind TEXT NOT NULL,
+            name TEXT NOT NULL,
+            line_number INTEGER NOT NULL
+        );
+
+        CREATE INDEX idx_anchors_note ON note_anchors(note_id);
+
+        UPDATE notes SET content_hash = '';
+        "#,
+    )?;
+
+    Ok(())
+}
+
+/// v5: frontmatter fields as key/value rows, for `--where` filters.
+///
+/// Each scalar (and each element of a list) gets a row with its text and,
+/// when numeric, its number. Hashes are cleared as in v3 to populate it.
+fn migrate_v4_to_v5(conn: &Connection) -> Result<(), SchemaError> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE note_fields (
+            note_id INTEGER NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+            key TEXT NOT NULL,
+            value_text TEXT NOT NULL,
+            value_num REAL
+        );
+
+        CREATE INDEX idx_fields_note ON note_fields(note_id);
+        CREATE INDEX idx_fields_key_text ON note_fields(key, value_text);
+        CREATE INDEX idx_fields_key_num ON note_fields(key, value_num);
+
+        UPDATE notes SET content_hash = '';
+        "#,
+    )?;
+
+    Ok(())
+}
+
+/// v6: embeds (`![[note]]`) get their own link type.
+///
+/// They used to be stored as plain wikilinks or markdown links; hashes are
+/// cleared as in v3 so existing embeds are re-extracted.
+fn migrate_v5_to_v6(conn: &Connection) -> Result<(), SchemaError> {
+    conn.execute_batch("UPDATE notes SET content_hash = '';")?;
+    Ok(())
+}
+
+/// v7: word count and heading outline per note.
+///
+/// `heading_json` is a JSON array of `{level, title}`. Hashes are cleared
+/// as in v3 to populate both columns.
+fn migrate_v6_to_v7(conn: &Connection) -> Result<(), SchemaError> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE notes ADD COLUMN word_count INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE notes ADD COLUMN heading_json TEXT;
+
+        UPDATE notes SET content_hash = '';
+        "#,
+    )?;
+
+    Ok(())
+}
+
+/// v8: near-duplicate note pairs.
+///
+/// A derived table like `note_cooccurrence`: pairs are stored once with
+/// `note_a_id < note_b_id` and rebuilt by the derived index step.
+fn migrate_v7_to_v8(conn: &Connection) -> Result<(), SchemaError> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE note_similarity (
+            note_a_id INTEGER NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+            note_b_id INTEGER NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+            similarity REAL NOT NULL,
+            PRIMARY KEY (note_a_id, note_b_id)
+        );
+
+        CREATE INDEX idx_similarity_score ON note_similarity(similarity);
+        "#,
+    )?;
+
+    Ok(())
+}
+
+/// v9: key/value bookkeeping about the index itself, such as its size after
+/// the last vacuum.
+fn migrate_v8_to_v9(conn: &Connection) -> Result<(), SchemaError> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE index_meta (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+        "#,
+    )?;
 
-    // For now, no migrations exist - we only have v1
-    Err(SchemaError::MigrationFailed(format!(
-        "No migration path from version {} to {}",
-        from_version, SCHEMA_VERSION
-    )))
+    Ok(())
+}
+
+/// v10: tombstones for notes that vanished from the vault.
+///
+/// Kept apart from `notes` so normal queries never see them. Links keep
+/// their `target_path`, which is how a tombstone finds its old backlinks.
+fn migrate_v9_to_v10(conn: &Connection) -> Result<(), SchemaError> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE note_tombstones (
+            path TEXT PRIMARY KEY,
+            note_type TEXT NOT NULL,
+            title TEXT NOT NULL,
+            deleted_at TEXT NOT NULL
+        );
+
+        CREATE INDEX idx_tombstones_deleted ON note_tombstones(deleted_at);
+
+        -- A note back at a vanished path is no longer deleted
+        CREATE TRIGGER notes_tombstone_clear AFTER INSERT ON notes BEGIN
+            DELETE FROM note_tombstones WHERE path = new.path;
+        END;
+        "#,
+    )?;
+
+    Ok(())
+}
+
+/// v11: note embeddings for semantic search.
+///
+/// One vector per note, tagged with the model that produced it and the
+/// content hash it was computed from, so `mdv embed` only redoes notes that
+/// changed. Vectors are little-endian `f32`s.
+fn migrate_v10_to_v11(conn: &Connection) -> Result<(), SchemaError> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE note_embeddings (
+            note_id INTEGER PRIMARY KEY REFERENCES notes(id) ON DELETE CASCADE,
+            model TEXT NOT NULL,
+            content_hash TEXT NOT NULL,
+            vector BLOB NOT NULL
+        );
+        "#,
+    )?;
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -198,6 +441,54 @@ mod tests {
         assert!(tables.contains(&"notes".to_string()));
         assert!(tables.contains(&"links".to_string()));
         assert!(tables.contains(&"temporal_activity".to_string()));
+        assert!(tables.contains(&"notes_fts".to_string()));
+        assert!(tables.contains(&"checkbox_items".to_string()));
+        assert!(tables.contains(&"note_anchors".to_string()));
+        assert!(tables.contains(&"note_fields".to_string()));
+        assert!(tables.contains(&"note_similarity".to_string()));
+        assert!(tables.contains(&"index_meta".to_string()));
+        assert!(tables.contains(&"note_tombstones".to_string()));
+        assert!(tables.contains(&"note_embeddings".to_string()));
+    }
+
+    #[test]
+    fn test_migrate_v1_database() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema_v1(&conn).unwrap();
+        set_schema_version(&conn, 1).unwrap();
+
+        init_schema(&conn).unwrap();
+
+        assert_eq!(get_schema_version(&conn).unwrap(), SCHEMA_VERSION);
+        conn.execute(
+            "INSERT INTO notes_fts (rowid, title, body, content_hash) VALUES (1, 't', 'b', 'h')",
+            [],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_migrate_v2_forces_reindex() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema_v1(&conn).unwrap();
+        migrate_v1_to_v2(&conn).unwrap();
+        set_schema_version(&conn, 2).unwrap();
+        conn.execute(
+            "INSERT INTO notes (path, title, modified_at, content_hash)
+             VALUES ('a.md', 'A', '2025-01-01T00:00:00Z', 'abc')",
+            [],
+        )
+        .unwrap();
+
+        init_schema(&conn).unwrap();
+
+        assert_eq!(get_schema_version(&conn).unwrap(), SCHEMA_VERSION);
+        let hash: String = conn
+            .query_row("SELECT content_hash FROM notes WHERE path = 'a.md'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(hash, "");
     }
 
     #[test]
@@ -1,19 +1,29 @@
 //! Contextual search beyond keyword matching.
 //!
 //! This module provides multi-modal search capabilities:
-//! - Direct match: Notes matching a query string
+//! - Direct match: Notes whose title, path, or body match a query string,
+//!   optionally with fuzzy title and path matching to tolerate typos
 //! - Graph neighbourhood: Linked notes within N hops
 //! - Temporal context: Recent dailies referencing matches
 //! - Cooccurrence: Notes that appeared together in dailies
+//!
+//! Query text may use `AND`, `OR`, `NOT`/`-`, parentheses, quoted phrases and
+//! `key:value` prefixes; see [`QueryExpr`].
 
 use std::collections::{HashMap, HashSet};
 
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+
 use super::IndexError;
 use super::db::IndexDb;
-use super::types::{IndexedNote, NoteType};
+use super::ranking::{RankHook, RankSignals};
+use super::types::{
+    BodyHit, Excerpt, FieldFilter, FieldOp, IndexedNote, NoteQuery, NoteType,
+};
 
 /// Search mode determining how results are expanded.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum SearchMode {
     /// Only return notes directly matching the query.
     #[default]
@@ -37,14 +47,24 @@ pub struct SearchQuery {
     pub note_type: Option<NoteType>,
     /// Path prefix filter.
     pub path_prefix: Option<String>,
+    /// Frontmatter predicates for direct matches.
+    pub fields: Vec<FieldFilter>,
     /// Search mode for result expansion.
     pub mode: SearchMode,
     /// Maximum results to return.
     pub limit: Option<u32>,
     /// Favour recently active notes.
     pub temporal_boost: bool,
+    /// Also match titles and paths approximately (e.g. "projct kickof").
+    pub fuzzy: bool,
 }
 
+/// Fuzzy matches below this fraction of a perfect match are dropped.
+const FUZZY_THRESHOLD: f64 = 0.5;
+
+/// Score of a perfect fuzzy match, kept below an exact substring match.
+const FUZZY_WEIGHT: f64 = 0.8;
+
 /// A search result with relevance information.
 #[derive(Debug, Clone)]
 pub struct SearchResult {
@@ -56,6 +76,10 @@ pub struct SearchResult {
     pub match_source: MatchSource,
     /// Staleness score if available (lower = more active).
     pub staleness: Option<f64>,
+    /// Excerpt around the match (full-text matches only).
+    pub snippet: Option<Excerpt>,
+    /// Where the query matched in the body (full-text matches only).
+    pub hits: Vec<BodyHit>,
 }
 
 /// How a search result was matched.
@@ -74,30 +98,26 @@ pub enum MatchSource {
 /// Search engine using the vault index.
 pub struct SearchEngine<'a> {
     db: &'a IndexDb,
+    rank_hook: Option<&'a RankHook>,
 }
 
 impl<'a> SearchEngine<'a> {
     /// Create a new search engine.
     pub fn new(db: &'a IndexDb) -> Self {
-        Self { db }
+        Self { db, rank_hook: None }
+    }
+
+    /// Re-score every candidate with a user `rank` function before sorting.
+    pub fn with_rank_hook(mut self, hook: Option<&'a RankHook>) -> Self {
+        self.rank_hook = hook;
+        self
     }
 
     /// Execute a search query.
     pub fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>, IndexError> {
         // Step 1: Find direct matches
-        let direct_matches = self.find_direct_matches(query)?;
-        let direct_ids: HashSet<i64> =
-            direct_matches.iter().filter_map(|n| n.id).collect();
-
-        let mut results: Vec<SearchResult> = direct_matches
-            .into_iter()
-            .map(|note| SearchResult {
-                staleness: self.get_staleness(note.id),
-                note,
-                score: 1.0,
-                match_source: MatchSource::Direct,
-            })
-            .collect();
+        let mut results = self.find_direct_matches(query)?;
+        let direct_ids: HashSet<i64> = results.iter().filter_map(|r| r.note.id).collect();
 
         // Step 2: Expand based on mode
         match query.mode {
@@ -135,10 +155,16 @@ impl<'a> SearchEngine<'a> {
             }
         }
 
-        // Step 4: Deduplicate and sort by score
+        // Step 4: Deduplicate, apply the user's ranking, and sort by score
         results = self.deduplicate_results(results);
+        if let Some(hook) = self.rank_hook {
+            self.apply_rank_hook(hook, &mut results)?;
+        }
         results.sort_by(|a, b| {
-            b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.note.path.cmp(&b.note.path))
         });
 
         // Step 5: Apply limit
@@ -150,32 +176,179 @@ impl<'a> SearchEngine<'a> {
     }
 
     /// Find notes directly matching the query.
+    ///
+    /// Title and path matches score 1.0. With `fuzzy`, titles and paths that
+    /// contain the query's characters in order score up to 0.8, by how tight
+    /// the match is. Body matches from the full-text index add up to 1.0
+    /// more, scaled by bm25 rank relative to the best hit, so a note matching
+    /// in both title and body outranks either alone.
     fn find_direct_matches(
         &self,
         query: &SearchQuery,
-    ) -> Result<Vec<IndexedNote>, IndexError> {
-        // Build a NoteQuery from SearchQuery
-        let note_query = super::types::NoteQuery {
+    ) -> Result<Vec<SearchResult>, IndexError> {
+        let mut note_query = NoteQuery {
             note_type: query.note_type,
             path_prefix: query.path_prefix.as_ref().map(Into::into),
-            limit: query.limit,
+            fields: query.fields.clone(),
             ..Default::default()
         };
 
-        let notes = self.db.query_notes(&note_query)?;
-
-        // Filter by text if provided
-        if let Some(text) = &query.text {
-            let text_lower = text.to_lowercase();
-            Ok(notes
+        let Some(text) = query.text.as_deref().filter(|t| !t.trim().is_empty()) else {
+            note_query.limit = query.limit;
+            let notes = self.db.query_notes(&note_query)?;
+            return Ok(notes
                 .into_iter()
-                .filter(|n| {
-                    n.title.to_lowercase().contains(&text_lower)
-                        || n.path.to_string_lossy().to_lowercase().contains(&text_lower)
-                })
-                .collect())
-        } else {
-            Ok(notes)
+                .map(|note| self.direct_result(note, 1.0))
+                .collect());
+        };
+
+        if let Some(expr) = QueryExpr::parse(text)?.filter(|e| !e.is_plain()) {
+            return self.find_expr_matches(&expr, &note_query);
+        }
+
+        let text_lower = text.to_lowercase();
+        let matcher = SkimMatcherV2::default().ignore_case();
+        let perfect = matcher.fuzzy_match(text, text).unwrap_or(1).max(1) as f64;
+        let mut results: Vec<SearchResult> = self
+            .db
+            .query_notes(&note_query)?
+            .into_iter()
+            .filter_map(|note| {
+                let path = note.path.to_string_lossy().to_lowercase();
+                let score = if note.title.to_lowercase().contains(&text_lower)
+                    || path.contains(&text_lower)
+                {
+                    1.0
+                } else if query.fuzzy {
+                    let best = [&note.title, &path]
+                        .into_iter()
+                        .filter_map(|candidate| matcher.fuzzy_match(candidate, text))
+                        .max()?;
+                    let closeness = (best as f64 / perfect).min(1.0);
+                    if closeness < FUZZY_THRESHOLD {
+                        return None;
+                    }
+                    FUZZY_WEIGHT * closeness
+                } else {
+                    return None;
+                };
+                Some(self.direct_result(note, score))
+            })
+            .collect();
+
+        let body_matches = self.db.search_fulltext(text, &note_query)?;
+        // bm25 is negative; the first row is the most relevant
+        let best_rank =
+            body_matches.first().map(|m| m.rank).unwrap_or(-1.0).min(-f64::EPSILON);
+
+        for m in body_matches {
+            let relevance = (m.rank / best_rank).clamp(0.0, 1.0);
+            match results.iter_mut().find(|r| r.note.id == m.note.id) {
+                Some(existing) => {
+                    existing.score += relevance;
+                    existing.snippet = Some(m.snippet);
+                    existing.hits = m.hits;
+                }
+                None => {
+                    let mut result = self.direct_result(m.note, relevance);
+                    result.snippet = Some(m.snippet);
+                    result.hits = m.hits;
+                    results.push(result);
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Find notes matching a structured query.
+    ///
+    /// Every note satisfying the expression is a direct match. Scoring mirrors
+    /// plain queries over the terms not under a `NOT`: 1.0 when one is in the
+    /// title or path, plus body relevance; notes matched only through
+    /// metadata or exclusions score 0.5.
+    fn find_expr_matches(
+        &self,
+        expr: &QueryExpr,
+        note_query: &NoteQuery,
+    ) -> Result<Vec<SearchResult>, IndexError> {
+        let hits = self.leaf_hits(expr)?;
+        let mut terms = Vec::new();
+        expr.positive_terms(false, &mut terms);
+        let terms_lower: Vec<String> = terms.iter().map(|t| t.to_lowercase()).collect();
+
+        let mut results: Vec<SearchResult> = self
+            .db
+            .query_notes(note_query)?
+            .into_iter()
+            .filter(|note| expr.matches(note, &hits))
+            .map(|note| {
+                let title = note.title.to_lowercase();
+                let path = note.path.to_string_lossy().to_lowercase();
+                let named =
+                    terms_lower.iter().any(|t| title.contains(t) || path.contains(t));
+                self.direct_result(note, if named { 1.0 } else { 0.0 })
+            })
+            .collect();
+
+        let body_matches = self.db.search_fulltext_any(&terms, note_query)?;
+        let best_rank =
+            body_matches.first().map(|m| m.rank).unwrap_or(-1.0).min(-f64::EPSILON);
+        for m in body_matches {
+            if let Some(result) = results.iter_mut().find(|r| r.note.id == m.note.id) {
+                result.score += (m.rank / best_rank).clamp(0.0, 1.0);
+                result.snippet = Some(m.snippet);
+                result.hits = m.hits;
+            }
+        }
+
+        for result in &mut results {
+            if result.score <= 0.0 {
+                result.score = 0.5;
+            }
+        }
+        Ok(results)
+    }
+
+    /// Look up the notes matching each full-text term and field predicate.
+    fn leaf_hits(&self, expr: &QueryExpr) -> Result<LeafHits, IndexError> {
+        let mut hits = LeafHits::default();
+        let mut pending = vec![expr];
+        while let Some(expr) = pending.pop() {
+            match expr {
+                QueryExpr::Term(text) if !hits.terms.contains_key(text) => {
+                    hits.terms.insert(text.clone(), self.db.fulltext_ids(text)?);
+                }
+                QueryExpr::Field(filter)
+                    if !hits.fields.iter().any(|(f, _)| f == filter) =>
+                {
+                    let query =
+                        NoteQuery { fields: vec![filter.clone()], ..Default::default() };
+                    let ids = self
+                        .db
+                        .query_notes(&query)?
+                        .iter()
+                        .filter_map(|n| n.id)
+                        .collect();
+                    hits.fields.push((filter.clone(), ids));
+                }
+                QueryExpr::Not(inner) => pending.push(inner),
+                QueryExpr::And(parts) | QueryExpr::Or(parts) => pending.extend(parts),
+                _ => {}
+            }
+        }
+        Ok(hits)
+    }
+
+    /// Build a direct-match result for a note.
+    fn direct_result(&self, note: IndexedNote, score: f64) -> SearchResult {
+        SearchResult {
+            staleness: self.get_staleness(note.id),
+            note,
+            score,
+            match_source: MatchSource::Direct,
+            snippet: None,
+            hits: Vec::new(),
         }
     }
 
@@ -208,6 +381,8 @@ impl<'a> SearchEngine<'a> {
                                 note,
                                 score: 0.5 / (hop as f64), // Decay by distance
                                 match_source: MatchSource::Linked { hops: hop },
+                                snippet: None,
+                                hits: Vec::new(),
                             });
                         }
                     }
@@ -226,6 +401,8 @@ impl<'a> SearchEngine<'a> {
                                 note,
                                 score: 0.5 / (hop as f64),
                                 match_source: MatchSource::Linked { hops: hop },
+                                snippet: None,
+                                hits: Vec::new(),
                             });
                         }
                     }
@@ -266,6 +443,8 @@ impl<'a> SearchEngine<'a> {
                         note: source_note,
                         score: 0.4,
                         match_source: MatchSource::Temporal { daily_path: path },
+                        snippet: None,
+                        hits: Vec::new(),
                     });
                 }
             }
@@ -298,6 +477,8 @@ impl<'a> SearchEngine<'a> {
                         match_source: MatchSource::Cooccurrence {
                             shared_dailies: shared_count as u32,
                         },
+                        snippet: None,
+                        hits: Vec::new(),
                     });
                 }
             }
@@ -317,6 +498,30 @@ impl<'a> SearchEngine<'a> {
         })
     }
 
+    /// Replace each result's score with the rank hook's.
+    fn apply_rank_hook(
+        &self,
+        hook: &RankHook,
+        results: &mut [SearchResult],
+    ) -> Result<(), IndexError> {
+        for result in results {
+            let (backlinks, outlinks) = match result.note.id {
+                Some(id) => (
+                    self.db.get_backlinks(id)?.len(),
+                    self.db.get_outgoing_links(id)?.len(),
+                ),
+                None => (0, 0),
+            };
+            let signals = RankSignals::new(result, backlinks, outlinks);
+            result.score =
+                hook.rank(result, &signals).map_err(|e| IndexError::Ranking {
+                    path: result.note.path.to_string_lossy().to_string(),
+                    message: e.to_string(),
+                })?;
+        }
+        Ok(())
+    }
+
     /// Deduplicate results, keeping highest score for each note.
     fn deduplicate_results(&self, results: Vec<SearchResult>) -> Vec<SearchResult> {
         let mut best: HashMap<i64, SearchResult> = HashMap::new();
@@ -337,11 +542,318 @@ impl<'a> SearchEngine<'a> {
     }
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+// Query language
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// A search query parsed into boolean structure.
+///
+/// Words and `"quoted phrases"` match a note's title, path, or body, and
+/// adjacent terms must all match (`AND` may be written out). `OR` joins
+/// alternatives and binds looser than `AND`; `NOT` or a leading `-` excludes;
+/// parentheses group. `type:NAME`, `path:PREFIX` and `title:WORD` narrow by
+/// note metadata, and any other `key:value` compares a frontmatter field,
+/// with `key:>=value` style comparisons as in `--where`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryExpr {
+    /// Word or phrase in the title, path, or body.
+    Term(String),
+    /// Word or phrase in the title.
+    Title(String),
+    /// Notes of this type.
+    Type(NoteType),
+    /// Notes under this path prefix (case-insensitive).
+    Path(String),
+    /// Frontmatter field predicate.
+    Field(FieldFilter),
+    Not(Box<QueryExpr>),
+    And(Vec<QueryExpr>),
+    Or(Vec<QueryExpr>),
+}
+
+/// A search query that could not be parsed.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum QueryParseError {
+    #[error("unbalanced parenthesis")]
+    UnbalancedParen,
+    #[error("unterminated quote")]
+    UnterminatedQuote,
+    #[error("expected a search term {0}")]
+    MissingTerm(String),
+}
+
+/// Notes matching each leaf of a query, looked up once per search.
+#[derive(Default)]
+struct LeafHits {
+    terms: HashMap<String, HashSet<i64>>,
+    fields: Vec<(FieldFilter, HashSet<i64>)>,
+}
+
+impl QueryExpr {
+    /// Parse query text; `None` when it holds no terms.
+    pub fn parse(text: &str) -> Result<Option<Self>, QueryParseError> {
+        let tokens = tokenize(text)?;
+        if tokens.is_empty() {
+            return Ok(None);
+        }
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.parse_or("at the start")?;
+        if parser.pos < parser.tokens.len() {
+            return Err(QueryParseError::UnbalancedParen);
+        }
+        Ok(Some(expr))
+    }
+
+    /// Whether this is just words, which plain search handles (with fuzzy
+    /// title matching) on its own.
+    fn is_plain(&self) -> bool {
+        let word = |e: &QueryExpr| matches!(e, QueryExpr::Term(t) if !t.contains(' '));
+        match self {
+            QueryExpr::And(parts) => parts.iter().all(word),
+            other => word(other),
+        }
+    }
+
+    /// Collect the terms a match must or may contain, skipping excluded ones.
+    fn positive_terms(&self, negated: bool, out: &mut Vec<String>) {
+        match self {
+            QueryExpr::Term(t) | QueryExpr::Title(t) if !negated => out.push(t.clone()),
+            QueryExpr::Not(inner) => inner.positive_terms(!negated, out),
+            QueryExpr::And(parts) | QueryExpr::Or(parts) => {
+                parts.iter().for_each(|p| p.positive_terms(negated, out))
+            }
+            _ => {}
+        }
+    }
+
+    /// Evaluate against a note.
+    fn matches(&self, note: &IndexedNote, hits: &LeafHits) -> bool {
+        let contains = |haystack: &str, needle: &str| {
+            haystack.to_lowercase().contains(&needle.to_lowercase())
+        };
+        match self {
+            QueryExpr::Term(t) => {
+                contains(&note.title, t)
+                    || contains(&note.path.to_string_lossy(), t)
+                    || note.id.is_some_and(|id| {
+                        hits.terms.get(t).is_some_and(|s| s.contains(&id))
+                    })
+            }
+            QueryExpr::Title(t) => contains(&note.title, t),
+            QueryExpr::Type(note_type) => note.note_type == *note_type,
+            QueryExpr::Path(prefix) => note
+                .path
+                .to_string_lossy()
+                .to_lowercase()
+                .starts_with(&prefix.to_lowercase()),
+            QueryExpr::Field(filter) => note.id.is_some_and(|id| {
+                hits.fields.iter().any(|(f, ids)| f == filter && ids.contains(&id))
+            }),
+            QueryExpr::Not(inner) => !inner.matches(note, hits),
+            QueryExpr::And(parts) => parts.iter().all(|p| p.matches(note, hits)),
+            QueryExpr::Or(parts) => parts.iter().any(|p| p.matches(note, hits)),
+        }
+    }
+
+    /// A word or phrase, a `key:value` prefix, or a bare `key<value`
+    /// comparison. Date expressions in field values (`today`, `today+7d`)
+    /// are evaluated.
+    fn leaf(text: String, quoted: bool) -> Self {
+        let is_key = |key: &str| {
+            !key.is_empty()
+                && key
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        };
+        let prefixed = text.split_once(':').filter(|(key, value)| {
+            !quoted && is_key(key) && !value.is_empty() && !value.starts_with("//")
+        });
+        let Some((key, value)) = prefixed else {
+            let compared = text
+                .find(['<', '>', '!'])
+                .filter(|&pos| !quoted && is_key(&text[..pos]))
+                .and_then(|_| text.parse::<FieldFilter>().ok())
+                .filter(|filter| !filter.value.is_empty());
+            return match compared {
+                Some(filter) => QueryExpr::field(filter),
+                None => QueryExpr::Term(text),
+            };
+        };
+
+        match key.to_lowercase().as_str() {
+            "path" => QueryExpr::Path(value.to_string()),
+            "title" => QueryExpr::Title(value.to_string()),
+            "type" => {
+                let Ok(note_type) = value.parse::<NoteType>();
+                if note_type == NoteType::None {
+                    QueryExpr::Field(FieldFilter {
+                        key: "type".to_string(),
+                        op: FieldOp::Eq,
+                        value: value.to_string(),
+                    })
+                } else {
+                    QueryExpr::Type(note_type)
+                }
+            }
+            _ if value.starts_with(['=', '!', '<', '>']) => {
+                match format!("{key}{value}").parse::<FieldFilter>() {
+                    Ok(filter) => QueryExpr::field(filter),
+                    Err(_) => QueryExpr::Term(text.clone()),
+                }
+            }
+            _ => QueryExpr::field(FieldFilter {
+                key: key.to_string(),
+                op: FieldOp::Eq,
+                value: value.to_string(),
+            }),
+        }
+    }
+
+    /// A field test, with date expressions in the value evaluated.
+    fn field(mut filter: FieldFilter) -> Self {
+        let today = chrono::Local::now().date_naive();
+        filter.value = crate::query::evaluate_value(&filter.value, today);
+        QueryExpr::Field(filter)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    Word { text: String, quoted: bool },
+}
+
+/// Split query text into tokens. `AND`, `OR` and `NOT` are operators only in
+/// capitals; `-` negates only at the start of a term.
+fn tokenize(text: &str) -> Result<Vec<Token>, QueryParseError> {
+    fn quoted(
+        chars: &mut std::iter::Peekable<std::str::Chars>,
+    ) -> Result<String, QueryParseError> {
+        let mut text = String::new();
+        for c in chars.by_ref() {
+            if c == '"' {
+                return Ok(text);
+            }
+            text.push(c);
+        }
+        Err(QueryParseError::UnterminatedQuote)
+    }
+
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '-' => {
+                chars.next();
+                if chars.peek().is_some_and(|c| !c.is_whitespace()) {
+                    tokens.push(Token::Not);
+                }
+            }
+            '"' => {
+                chars.next();
+                let text = quoted(&mut chars)?;
+                tokens.push(Token::Word { text, quoted: true });
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    chars.next();
+                    if c == '"' {
+                        word.push_str(&quoted(&mut chars)?);
+                    } else {
+                        word.push(c);
+                    }
+                }
+                tokens.push(match word.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Word { text: word, quoted: false },
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive-descent parser: `or := and (OR and)*`, `and := unary (AND? unary)*`,
+/// `unary := (NOT | -) unary | ( or ) | term`.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn parse_or(&mut self, context: &str) -> Result<QueryExpr, QueryParseError> {
+        let mut parts = vec![self.parse_and(context)?];
+        while self.tokens.get(self.pos) == Some(&Token::Or) {
+            self.pos += 1;
+            parts.push(self.parse_and("after 'OR'")?);
+        }
+        Ok(if parts.len() == 1 { parts.remove(0) } else { QueryExpr::Or(parts) })
+    }
+
+    fn parse_and(&mut self, context: &str) -> Result<QueryExpr, QueryParseError> {
+        let mut parts = vec![self.parse_unary(context)?];
+        loop {
+            match self.tokens.get(self.pos) {
+                Some(Token::And) => {
+                    self.pos += 1;
+                    parts.push(self.parse_unary("after 'AND'")?);
+                }
+                Some(Token::Open | Token::Not | Token::Word { .. }) => {
+                    parts.push(self.parse_unary(context)?);
+                }
+                _ => break,
+            }
+        }
+        Ok(if parts.len() == 1 { parts.remove(0) } else { QueryExpr::And(parts) })
+    }
+
+    fn parse_unary(&mut self, context: &str) -> Result<QueryExpr, QueryParseError> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        match token {
+            Some(Token::Not) => {
+                Ok(QueryExpr::Not(Box::new(self.parse_unary("after 'NOT' or '-'")?)))
+            }
+            Some(Token::Open) => {
+                let expr = self.parse_or("after '('")?;
+                if self.tokens.get(self.pos) != Some(&Token::Close) {
+                    return Err(QueryParseError::UnbalancedParen);
+                }
+                self.pos += 1;
+                Ok(expr)
+            }
+            Some(Token::Word { text, quoted }) => Ok(QueryExpr::leaf(text, quoted)),
+            _ => Err(QueryParseError::MissingTerm(context.to_string())),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use chrono::Utc;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
     fn sample_note(path: &str, title: &str, note_type: NoteType) -> IndexedNote {
         IndexedNote {
@@ -353,6 +865,8 @@ mod tests {
             modified: Utc::now(),
             frontmatter_json: None,
             content_hash: format!("hash-{}", path),
+            word_count: 0,
+            heading_json: None,
         }
     }
 
@@ -415,4 +929,189 @@ mod tests {
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].note.note_type, NoteType::Task);
     }
+
+    #[test]
+    fn test_fuzzy_title_match() {
+        let db = IndexDb::open_in_memory().unwrap();
+        for (path, title) in [
+            ("projects/kickoff.md", "Project Kickoff"),
+            ("projects/project-kickoff-notes.md", "Project kickoff notes"),
+            ("packing.md", "Packing list"),
+        ] {
+            db.insert_note(&sample_note(path, title, NoteType::Zettel)).unwrap();
+        }
+        let engine = SearchEngine::new(&db);
+
+        let mut query =
+            SearchQuery { text: Some("projct kickof".to_string()), ..Default::default() };
+        assert!(engine.search(&query).unwrap().is_empty());
+
+        query.fuzzy = true;
+        let results = engine.search(&query).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.score < 1.0));
+        assert!(results.iter().all(|r| r.note.path.starts_with("projects")));
+
+        // Exact substring matches still outrank fuzzy ones
+        query.text = Some("kickoff notes".to_string());
+        let results = engine.search(&query).unwrap();
+        assert_eq!(results[0].note.title, "Project kickoff notes");
+        assert_eq!(results[0].score, 1.0);
+    }
+
+    #[test]
+    fn test_body_search_with_snippet() {
+        let db = IndexDb::open_in_memory().unwrap();
+
+        let title_id = db
+            .insert_note(&sample_note("a.md", "Compiler notes", NoteType::Zettel))
+            .unwrap();
+        let body_id =
+            db.insert_note(&sample_note("b.md", "Misc", NoteType::Zettel)).unwrap();
+        let both_id = db
+            .insert_note(&sample_note("c.md", "Compiler design", NoteType::Zettel))
+            .unwrap();
+        db.upsert_note_text(title_id, "Compiler notes", "nothing relevant", "h1")
+            .unwrap();
+        db.upsert_note_text(body_id, "Misc", "a tiny compiler in rust", "h2").unwrap();
+        db.upsert_note_text(both_id, "Compiler design", "the compiler pipeline", "h3")
+            .unwrap();
+
+        let engine = SearchEngine::new(&db);
+        let query =
+            SearchQuery { text: Some("compiler".to_string()), ..Default::default() };
+        let results = engine.search(&query).unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].note.path, PathBuf::from("c.md"));
+        let body_hit = results.iter().find(|r| r.note.path == Path::new("b.md")).unwrap();
+        let snippet = body_hit.snippet.as_ref().unwrap();
+        assert_eq!(snippet.text, "a tiny compiler in rust");
+        assert_eq!(snippet.highlights.len(), 1);
+        assert_eq!(snippet.highlights[0], 7..15);
+        assert_eq!(body_hit.hits.len(), 1);
+    }
+
+    fn term(t: &str) -> QueryExpr {
+        QueryExpr::Term(t.to_string())
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let expr =
+            QueryExpr::parse("parser AND (rust OR lua) -archived type:zettel").unwrap();
+        assert_eq!(
+            expr,
+            Some(QueryExpr::And(vec![
+                term("parser"),
+                QueryExpr::Or(vec![term("rust"), term("lua")]),
+                QueryExpr::Not(Box::new(term("archived"))),
+                QueryExpr::Type(NoteType::Zettel),
+            ]))
+        );
+
+        // OR binds looser than the implicit AND
+        assert_eq!(
+            QueryExpr::parse("a b OR c").unwrap(),
+            Some(QueryExpr::Or(vec![
+                QueryExpr::And(vec![term("a"), term("b")]),
+                term("c")
+            ]))
+        );
+
+        assert_eq!(
+            QueryExpr::parse("\"rust book\" status:\"in progress\" priority:>=2")
+                .unwrap(),
+            Some(QueryExpr::And(vec![
+                term("rust book"),
+                QueryExpr::Field("status=in progress".parse().unwrap()),
+                QueryExpr::Field("priority>=2".parse().unwrap()),
+            ]))
+        );
+
+        // Lowercase operators, inner dashes and URLs are plain words
+        assert_eq!(
+            QueryExpr::parse("rock and roll well-known https://example.com").unwrap(),
+            Some(QueryExpr::And(vec![
+                term("rock"),
+                term("and"),
+                term("roll"),
+                term("well-known"),
+                term("https://example.com"),
+            ]))
+        );
+        assert_eq!(QueryExpr::parse("  ").unwrap(), None);
+
+        // Bare comparisons test fields; date expressions are evaluated
+        let today = chrono::Local::now().date_naive().format("%Y-%m-%d").to_string();
+        assert_eq!(
+            QueryExpr::parse("due<today status:todo hello!").unwrap(),
+            Some(QueryExpr::And(vec![
+                QueryExpr::Field(format!("due<{today}").parse().unwrap()),
+                QueryExpr::Field("status=todo".parse().unwrap()),
+                term("hello!"),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_parse_query_errors() {
+        assert_eq!(QueryExpr::parse("(a OR b"), Err(QueryParseError::UnbalancedParen));
+        assert_eq!(QueryExpr::parse("a)"), Err(QueryParseError::UnbalancedParen));
+        assert_eq!(QueryExpr::parse("\"open"), Err(QueryParseError::UnterminatedQuote));
+        assert_eq!(
+            QueryExpr::parse("a OR"),
+            Err(QueryParseError::MissingTerm("after 'OR'".to_string()))
+        );
+        assert_eq!(
+            QueryExpr::parse("AND a"),
+            Err(QueryParseError::MissingTerm("at the start".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_boolean_search() {
+        let db = IndexDb::open_in_memory().unwrap();
+        let notes = [
+            ("z/rust.md", "Parser in Rust", NoteType::Zettel, "a parser written in rust"),
+            ("z/lua.md", "Lua notes", NoteType::Zettel, "embedding a parser for lua"),
+            ("z/old.md", "Old parser", NoteType::Zettel, "archived rust parser"),
+            ("t/task.md", "Parser task", NoteType::Task, "port the parser to rust"),
+            ("z/go.md", "Go parser", NoteType::Zettel, "a parser in go"),
+        ];
+        for (path, title, note_type, body) in notes {
+            let id = db.insert_note(&sample_note(path, title, note_type)).unwrap();
+            db.upsert_note_text(id, title, body, path).unwrap();
+        }
+        let engine = SearchEngine::new(&db);
+        let paths = |text: &str| {
+            let query =
+                SearchQuery { text: Some(text.to_string()), ..Default::default() };
+            let mut paths: Vec<_> = engine
+                .search(&query)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.note.path.to_string_lossy().to_string())
+                .collect();
+            paths.sort();
+            paths
+        };
+
+        assert_eq!(
+            paths("parser AND (rust OR lua) -archived type:zettel"),
+            ["z/lua.md", "z/rust.md"]
+        );
+        assert_eq!(paths("parser NOT rust path:z/"), ["z/go.md", "z/lua.md"]);
+        assert_eq!(paths("\"parser in\""), ["z/go.md", "z/rust.md"]);
+        assert_eq!(paths("title:old OR type:task"), ["t/task.md", "z/old.md"]);
+
+        // Body hits still carry excerpts
+        let query = SearchQuery {
+            text: Some("lua OR go -title:go".to_string()),
+            ..Default::default()
+        };
+        let results = engine.search(&query).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].snippet.is_some());
+    }
 }
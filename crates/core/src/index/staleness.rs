@@ -0,0 +1,462 @@
+//! Staleness scoring for `mdv stale`.
+//!
+//! Each note gets a score from 0 (active) to 1 (forgotten), computed at
+//! reindex time from these signals, each scaled to 0–1:
+//!
+//! - `referenced`: days since a daily last linked to the note, over the
+//!   horizon (1 when no daily ever has)
+//! - `activity`: 0 with daily references in the last 30 days, 0.5 with some
+//!   in the last 90, 1 otherwise
+//! - `modified`: days since the file changed, over the horizon
+//! - `backlinks`: how few notes link here, each link decaying with the age
+//!   of its source note
+//!
+//! The score is the weighted sum, capped at 1; the weights come from the
+//! `[staleness]` config table. A vault can replace the result by defining
+//! `staleness(note, signals, score)` in `.mdvault/staleness.lua`:
+//!
+//! ```lua
+//! function staleness(note, signals, score)
+//!     -- Reference notes never go stale
+//!     if note.frontmatter.evergreen then
+//!         return 0
+//!     end
+//!     return score
+//! end
+//! ```
+//!
+//! `note` has `path`, `title`, `type` and `frontmatter`; `signals` has
+//! `days_since_modified`, `days_since_referenced` (nil when never),
+//! `references_30d`, `references_90d`, `backlinks` and `backlink_ages`.
+//! Returning `nil` keeps `score`.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use chrono::{NaiveDate, Utc};
+use mlua::{Function, LuaSerdeExt, Value};
+use serde::Serialize;
+use thiserror::Error;
+
+use super::db::{IndexDb, IndexError};
+use super::types::{IndexedNote, NoteQuery};
+use crate::config::types::StalenessConfig;
+use crate::paths::PathResolver;
+use crate::scripting::{LuaEngine, ScriptingError};
+
+/// Errors setting up a staleness model.
+#[derive(Debug, Error)]
+pub enum StalenessError {
+    #[error("invalid [staleness] config: {0}")]
+    InvalidConfig(String),
+
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to evaluate {path}: {source}")]
+    Lua {
+        path: PathBuf,
+        #[source]
+        source: ScriptingError,
+    },
+
+    #[error("{path} does not define a staleness(note, signals, score) function")]
+    MissingFunction { path: PathBuf },
+}
+
+/// The raw facts a staleness score is computed from.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StalenessSignals {
+    /// Whole days since the note file was last modified.
+    pub days_since_modified: i64,
+    /// The latest daily linking here; `None` if none ever has.
+    pub last_referenced: Option<NaiveDate>,
+    /// Whole days since `last_referenced`.
+    pub days_since_referenced: Option<i64>,
+    /// Daily references in the last 30 days.
+    pub references_30d: u32,
+    /// Daily references in the last 90 days.
+    pub references_90d: u32,
+    /// Age in days of each note linking here.
+    pub backlink_ages: Vec<i64>,
+}
+
+/// One signal's share of a score.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StalenessComponent {
+    pub signal: &'static str,
+    /// The signal scaled to 0–1.
+    pub value: f64,
+    pub weight: f64,
+}
+
+impl StalenessComponent {
+    /// What this signal adds to the score.
+    pub fn contribution(&self) -> f64 {
+        self.value * self.weight
+    }
+}
+
+/// A score and how it was reached.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StalenessBreakdown {
+    pub score: f64,
+    pub components: Vec<StalenessComponent>,
+    /// Whether `staleness.lua` changed the weighted score.
+    pub from_hook: bool,
+}
+
+/// Configured weights plus the vault's optional Lua override.
+#[derive(Default)]
+pub struct StalenessModel {
+    config: StalenessConfig,
+    hook: Option<LuaEngine>,
+}
+
+impl StalenessModel {
+    /// A model using `config` alone.
+    pub fn new(config: StalenessConfig) -> Result<Self, StalenessError> {
+        let invalid = |m: &str| Err(StalenessError::InvalidConfig(m.to_string()));
+        if config.horizon_days.is_nan() || config.horizon_days <= 0.0 {
+            return invalid("horizon_days must be positive");
+        }
+        let half_life = config.backlink_half_life_days;
+        if half_life.is_nan() || half_life <= 0.0 {
+            return invalid("backlink_half_life_days must be positive");
+        }
+        let weights = [
+            config.referenced_weight,
+            config.activity_weight,
+            config.modified_weight,
+            config.backlinks_weight,
+        ];
+        if weights.iter().any(|w| w.is_nan() || *w < 0.0) {
+            return invalid("weights must not be negative");
+        }
+        Ok(Self { config, hook: None })
+    }
+
+    /// The model for a vault: `config` plus `.mdvault/staleness.lua` if it
+    /// exists.
+    pub fn load(
+        vault_root: &Path,
+        config: &StalenessConfig,
+    ) -> Result<Self, StalenessError> {
+        let model = Self::new(config.clone())?;
+        let path = PathResolver::new(vault_root).staleness();
+        if !path.exists() {
+            return Ok(model);
+        }
+        let source = std::fs::read_to_string(&path)
+            .map_err(|e| StalenessError::Io { path: path.clone(), source: e })?;
+        model.with_hook(&source, &path)
+    }
+
+    /// Add a Lua override from source; `path` is only used in errors.
+    pub fn with_hook(
+        mut self,
+        source: &str,
+        path: &Path,
+    ) -> Result<Self, StalenessError> {
+        let lua_err = |e: ScriptingError| StalenessError::Lua {
+            path: path.to_path_buf(),
+            source: e,
+        };
+        let engine = LuaEngine::sandboxed().map_err(lua_err)?;
+        engine.lua().load(source).exec().map_err(|e| lua_err(e.into()))?;
+        let hook: Value =
+            engine.lua().globals().get("staleness").map_err(|e| lua_err(e.into()))?;
+        if !matches!(hook, Value::Function(_)) {
+            return Err(StalenessError::MissingFunction { path: path.to_path_buf() });
+        }
+        self.hook = Some(engine);
+        Ok(self)
+    }
+
+    /// Score a note.
+    pub fn score(
+        &self,
+        note: &IndexedNote,
+        signals: &StalenessSignals,
+    ) -> Result<f64, ScriptingError> {
+        self.breakdown(note, signals).map(|b| b.score)
+    }
+
+    /// Score a note, keeping each signal's share.
+    pub fn breakdown(
+        &self,
+        note: &IndexedNote,
+        signals: &StalenessSignals,
+    ) -> Result<StalenessBreakdown, ScriptingError> {
+        let c = &self.config;
+        let horizon = |days: i64| (days.max(0) as f64 / c.horizon_days).min(1.0);
+        let activity = if signals.references_30d > 0 {
+            0.0
+        } else if signals.references_90d > 0 {
+            0.5
+        } else {
+            1.0
+        };
+        let backlink_weight: f64 = signals
+            .backlink_ages
+            .iter()
+            .map(|&age| 0.5f64.powf(age.max(0) as f64 / c.backlink_half_life_days))
+            .sum();
+
+        let components = vec![
+            StalenessComponent {
+                signal: "referenced",
+                value: signals.days_since_referenced.map(horizon).unwrap_or(1.0),
+                weight: c.referenced_weight,
+            },
+            StalenessComponent {
+                signal: "activity",
+                value: activity,
+                weight: c.activity_weight,
+            },
+            StalenessComponent {
+                signal: "modified",
+                value: horizon(signals.days_since_modified),
+                weight: c.modified_weight,
+            },
+            StalenessComponent {
+                signal: "backlinks",
+                value: 1.0 / (1.0 + backlink_weight),
+                weight: c.backlinks_weight,
+            },
+        ];
+        let weighted =
+            components.iter().map(StalenessComponent::contribution).sum::<f64>().min(1.0);
+
+        let score = match &self.hook {
+            Some(engine) => call_hook(engine, note, signals, weighted)?,
+            None => weighted,
+        };
+        Ok(StalenessBreakdown { score, components, from_hook: score != weighted })
+    }
+}
+
+/// Run the vault's `staleness` function; its result is capped to 0–1.
+fn call_hook(
+    engine: &LuaEngine,
+    note: &IndexedNote,
+    signals: &StalenessSignals,
+    score: f64,
+) -> Result<f64, ScriptingError> {
+    let lua = engine.lua();
+    let hook: Function = lua.globals().get("staleness")?;
+
+    let table = lua.create_table()?;
+    table.set("path", note.path.to_string_lossy().to_string())?;
+    table.set("title", note.title.as_str())?;
+    table.set("type", note.note_type.as_str())?;
+    let frontmatter: serde_json::Value = note
+        .frontmatter_json
+        .as_deref()
+        .and_then(|fm| serde_json::from_str(fm).ok())
+        .unwrap_or_else(|| serde_json::Value::Object(Default::default()));
+    table.set("frontmatter", lua.to_value(&frontmatter)?)?;
+
+    let s = lua.create_table()?;
+    s.set("days_since_modified", signals.days_since_modified)?;
+    s.set("days_since_referenced", signals.days_since_referenced)?;
+    s.set("references_30d", signals.references_30d)?;
+    s.set("references_90d", signals.references_90d)?;
+    s.set("backlinks", signals.backlink_ages.len())?;
+    s.set("backlink_ages", signals.backlink_ages.clone())?;
+
+    let value = match hook.call::<Value>((table, s, score))? {
+        Value::Nil => score,
+        Value::Integer(n) => n as f64,
+        Value::Number(n) => n,
+        other => {
+            return Err(ScriptingError::Other(format!(
+                "staleness() must return a number or nil, got {}",
+                other.type_name()
+            )));
+        }
+    };
+    Ok(value.clamp(0.0, 1.0))
+}
+
+/// Signals for every indexed note, keyed by note id.
+///
+/// Daily references come from the derived `temporal_activity` table, so
+/// they reflect the last reindex.
+pub fn staleness_signals(
+    db: &IndexDb,
+) -> Result<HashMap<i64, (IndexedNote, StalenessSignals)>, IndexError> {
+    let now = Utc::now();
+    let today = now.date_naive();
+    let notes: HashMap<i64, IndexedNote> = db
+        .query_notes(&NoteQuery::default())?
+        .into_iter()
+        .filter_map(|n| Some((n.id?, n)))
+        .collect();
+    let activity: HashMap<i64, _> = db
+        .aggregate_activity(
+            &(today - chrono::Duration::days(30)).to_string(),
+            &(today - chrono::Duration::days(90)).to_string(),
+        )?
+        .into_iter()
+        .map(|a| (a.note_id, a))
+        .collect();
+
+    let mut signals = HashMap::new();
+    for (&id, note) in &notes {
+        let mut s = StalenessSignals {
+            days_since_modified: (now - note.modified).num_days().max(0),
+            ..Default::default()
+        };
+        if let Some(a) = activity.get(&id) {
+            s.last_referenced = a
+                .last_seen
+                .as_deref()
+                .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+            s.days_since_referenced =
+                s.last_referenced.map(|d| (today - d).num_days().max(0));
+            s.references_30d = a.access_count_30d.max(0) as u32;
+            s.references_90d = a.access_count_90d.max(0) as u32;
+        }
+        let sources: HashSet<i64> = db
+            .get_backlinks(id)?
+            .into_iter()
+            .map(|l| l.source_id)
+            .filter(|&source| source != id)
+            .collect();
+        s.backlink_ages = sources
+            .iter()
+            .filter_map(|source| notes.get(source))
+            .map(|source| (now - source.modified).num_days().max(0))
+            .collect();
+        s.backlink_ages.sort_unstable();
+        signals.insert(id, (note.clone(), s));
+    }
+    Ok(signals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::types::NoteType;
+
+    fn note() -> IndexedNote {
+        IndexedNote {
+            id: Some(1),
+            path: PathBuf::from("zettel/idea.md"),
+            note_type: NoteType::Zettel,
+            title: "Idea".into(),
+            created: None,
+            modified: Utc::now(),
+            frontmatter_json: Some(r#"{"evergreen": true}"#.into()),
+            content_hash: "h".into(),
+            word_count: 0,
+            heading_json: None,
+        }
+    }
+
+    fn score(model: &StalenessModel, signals: &StalenessSignals) -> f64 {
+        model.score(&note(), signals).unwrap()
+    }
+
+    #[test]
+    fn default_weights_score_daily_references() {
+        let model = StalenessModel::default();
+
+        let active = StalenessSignals {
+            days_since_referenced: Some(0),
+            references_30d: 5,
+            references_90d: 10,
+            ..Default::default()
+        };
+        assert!(score(&model, &active) < 0.1, "Active notes should have low staleness");
+
+        // Never referenced: 1.0 * 0.6 + 1.0 * 0.24
+        let never = StalenessSignals { days_since_modified: 400, ..Default::default() };
+        assert!((score(&model, &never) - 0.84).abs() < 1e-9);
+
+        let fading = StalenessSignals {
+            days_since_referenced: Some(45),
+            references_90d: 1,
+            ..Default::default()
+        };
+        assert!((score(&model, &fading) - (0.5 * 0.6 + 0.5 * 0.24)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn configured_weights_and_backlink_decay() {
+        let model = StalenessModel::new(StalenessConfig {
+            referenced_weight: 0.0,
+            activity_weight: 0.0,
+            modified_weight: 0.5,
+            backlinks_weight: 0.5,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let fresh_links =
+            StalenessSignals { backlink_ages: vec![0, 0, 0], ..Default::default() };
+        let old_links =
+            StalenessSignals { backlink_ages: vec![300, 300, 300], ..Default::default() };
+        // Three fresh backlinks: 1 / (1 + 3)
+        assert!((score(&model, &fresh_links) - 0.125).abs() < 1e-9);
+        assert!(score(&model, &old_links) > 0.49);
+
+        let untouched =
+            StalenessSignals { days_since_modified: 45, ..Default::default() };
+        let b = model.breakdown(&note(), &untouched).unwrap();
+        assert_eq!(b.components.len(), 4);
+        assert_eq!(b.components[2].signal, "modified");
+        assert!((b.components[2].value - 0.5).abs() < 1e-9);
+        assert!(!b.from_hook);
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        let bad = |config| StalenessModel::new(config).err().unwrap().to_string();
+        let zero = StalenessConfig { horizon_days: 0.0, ..Default::default() };
+        assert!(bad(zero).contains("horizon_days"));
+        let negative = StalenessConfig { modified_weight: -1.0, ..Default::default() };
+        assert!(bad(negative).contains("negative"));
+    }
+
+    #[test]
+    fn hook_overrides_the_score() {
+        let model = StalenessModel::default()
+            .with_hook(
+                r#"function staleness(note, s, score)
+                    if note.frontmatter.evergreen then return 0 end
+                    if s.days_since_referenced == nil then return 7 end
+                end"#,
+                Path::new("staleness.lua"),
+            )
+            .unwrap();
+        let b = model.breakdown(&note(), &StalenessSignals::default()).unwrap();
+        assert_eq!(b.score, 0.0);
+        assert!(b.from_hook);
+
+        let mut plain = note();
+        plain.frontmatter_json = None;
+        // Out-of-range results are capped
+        assert_eq!(model.score(&plain, &StalenessSignals::default()).unwrap(), 1.0);
+        let seen =
+            StalenessSignals { days_since_referenced: Some(0), ..Default::default() };
+        assert!(model.score(&plain, &seen).unwrap() > 0.0);
+    }
+
+    #[test]
+    fn hook_errors() {
+        let missing = StalenessModel::default().with_hook("x = 1", Path::new("s.lua"));
+        assert!(matches!(missing, Err(StalenessError::MissingFunction { .. })));
+
+        let model = StalenessModel::default()
+            .with_hook(r#"function staleness() return "old" end"#, Path::new("s.lua"))
+            .unwrap();
+        let err = model.score(&note(), &StalenessSignals::default()).unwrap_err();
+        assert!(err.to_string().contains("got string"), "{err}");
+    }
+}
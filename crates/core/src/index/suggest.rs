@@ -0,0 +1,276 @@
+//! Wikilinks a note is missing.
+//!
+//! [`suggest_links`] finds notes a note talks about but does not link to.
+//! Two signals are combined, each scaled to 0–1 and weighted:
+//! - mentions: the note's body names another note by title, alias or file
+//!   name, as whole words and outside existing `[[links]]`
+//! - co-occurrence: how many dailies mention both (from `note_cooccurrence`)
+//!
+//! Notes the note already links to, and the note itself, are never
+//! suggested.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use super::db::{IndexDb, IndexError};
+use super::related::saturate;
+use super::types::{IndexedNote, NoteQuery};
+
+/// Weight of a mention in the body in the confidence.
+const MENTION_WEIGHT: f64 = 0.7;
+/// Weight of daily co-occurrence in the confidence.
+const COOCCURRENCE_WEIGHT: f64 = 0.3;
+/// A mention by file name only counts this much of one by title or alias,
+/// since file names are often slugs that happen to be common words.
+const STEM_MENTION: f64 = 0.8;
+
+/// Names shorter than this match too much ordinary text.
+const MIN_NAME_LEN: usize = 3;
+
+/// A note worth linking to.
+#[derive(Debug, Clone)]
+pub struct LinkSuggestion {
+    pub note: IndexedNote,
+    /// Combined score (0–1, higher = more likely wanted).
+    pub confidence: f64,
+    pub signals: SuggestionSignals,
+}
+
+/// Why a link is suggested.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SuggestionSignals {
+    /// The title, alias or file name found in the body.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched: Option<String>,
+    /// How often `matched` occurs in the body.
+    pub mentions: usize,
+    /// Dailies mentioning both notes.
+    pub cooccurrences: u32,
+}
+
+/// Links `note_id` is missing, most confident first, at most `limit`.
+pub fn suggest_links(
+    db: &IndexDb,
+    note_id: i64,
+    limit: usize,
+) -> Result<Vec<LinkSuggestion>, IndexError> {
+    let notes: HashMap<i64, IndexedNote> = db
+        .query_notes(&NoteQuery::default())?
+        .into_iter()
+        .filter_map(|n| Some((n.id?, n)))
+        .collect();
+    if !notes.contains_key(&note_id) {
+        return Err(IndexError::NoteNotFound(format!("id {note_id}")));
+    }
+    let mut linked: HashSet<i64> =
+        db.get_outgoing_links(note_id)?.into_iter().filter_map(|l| l.target_id).collect();
+    linked.insert(note_id);
+
+    let mut signals: HashMap<i64, SuggestionSignals> = HashMap::new();
+    let mut mention_scores: HashMap<i64, f64> = HashMap::new();
+
+    let body = db
+        .get_note_bodies()?
+        .into_iter()
+        .find(|(id, _)| *id == note_id)
+        .map(|(_, body)| unlinked_text(&body).to_lowercase())
+        .unwrap_or_default();
+    if !body.is_empty() {
+        for (&id, note) in notes.iter().filter(|(id, _)| !linked.contains(id)) {
+            // The best-scoring name wins; a longer one breaks ties
+            let best = candidate_names(note)
+                .into_iter()
+                .filter_map(|(name, weight)| {
+                    let count = count_mentions(&body, &name.to_lowercase());
+                    (count > 0).then_some((name, weight, count))
+                })
+                .max_by(|a, b| {
+                    a.1.total_cmp(&b.1)
+                        .then(a.0.chars().count().cmp(&b.0.chars().count()))
+                });
+            if let Some((name, weight, count)) = best {
+                mention_scores.insert(id, weight);
+                let s = signals.entry(id).or_default();
+                s.matched = Some(name);
+                s.mentions = count;
+            }
+        }
+    }
+
+    for (note, count) in db.get_cooccurrent_notes(note_id, u32::MAX)? {
+        if let Some(id) = note.id.filter(|id| !linked.contains(id)) {
+            signals.entry(id).or_default().cooccurrences = count.max(0) as u32;
+        }
+    }
+
+    let mut suggestions: Vec<LinkSuggestion> = signals
+        .into_iter()
+        .filter_map(|(id, signals)| {
+            let note = notes.get(&id)?.clone();
+            let confidence = MENTION_WEIGHT
+                * mention_scores.get(&id).copied().unwrap_or(0.0)
+                + COOCCURRENCE_WEIGHT * saturate(signals.cooccurrences);
+            (confidence > 0.0).then_some(LinkSuggestion { note, confidence, signals })
+        })
+        .collect();
+
+    suggestions.sort_by(|a, b| {
+        b.confidence.total_cmp(&a.confidence).then(a.note.path.cmp(&b.note.path))
+    });
+    suggestions.truncate(limit);
+    Ok(suggestions)
+}
+
+/// Names a note can be mentioned by, with how much a mention counts: its
+/// title and frontmatter `aliases` fully, its file name less.
+fn candidate_names(note: &IndexedNote) -> Vec<(String, f64)> {
+    let mut names = vec![(note.title.trim().to_string(), 1.0)];
+    let fm = note
+        .frontmatter_json
+        .as_deref()
+        .and_then(|fm| serde_json::from_str::<serde_json::Value>(fm).ok());
+    match fm.as_ref().and_then(|fm| fm.get("aliases")) {
+        Some(serde_json::Value::Array(items)) => names.extend(
+            items.iter().filter_map(|a| a.as_str()).map(|a| (a.trim().to_string(), 1.0)),
+        ),
+        Some(serde_json::Value::String(s)) => {
+            names.extend(s.split(',').map(|a| (a.trim().to_string(), 1.0)))
+        }
+        _ => {}
+    }
+    if let Some(stem) = note.path.file_stem() {
+        names.push((stem.to_string_lossy().to_string(), STEM_MENTION));
+    }
+    names.retain(|(name, _)| name.chars().count() >= MIN_NAME_LEN);
+    names
+}
+
+/// `text` with every `[[...]]` span blanked out, so names inside links to
+/// other notes are not mistaken for mentions.
+fn unlinked_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("[[") {
+        let Some(len) = rest[start..].find("]]") else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        out.push(' ');
+        rest = &rest[start + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Occurrences of `name` in `text` as whole words. Both are lowercase.
+fn count_mentions(text: &str, name: &str) -> usize {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    text.match_indices(name)
+        .filter(|(at, _)| {
+            !is_word(text[..*at].chars().next_back())
+                && !is_word(text[at + name.len()..].chars().next())
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::types::{IndexedLink, LinkType, NoteType};
+    use chrono::Utc;
+    use std::path::PathBuf;
+
+    fn add(db: &IndexDb, path: &str, title: &str, fm: Option<&str>, body: &str) -> i64 {
+        let id = db
+            .insert_note(&IndexedNote {
+                id: None,
+                path: PathBuf::from(path),
+                note_type: NoteType::Zettel,
+                title: title.to_string(),
+                created: None,
+                modified: Utc::now(),
+                frontmatter_json: fm.map(String::from),
+                content_hash: format!("hash-{path}"),
+                word_count: 0,
+                heading_json: None,
+            })
+            .unwrap();
+        db.upsert_note_text(id, title, body, &format!("hash-{path}")).unwrap();
+        id
+    }
+
+    fn link(db: &IndexDb, from: i64, to: i64) {
+        db.insert_link(&IndexedLink {
+            id: None,
+            source_id: from,
+            target_id: Some(to),
+            target_path: format!("{to}"),
+            link_text: None,
+            link_type: LinkType::Wikilink,
+            context: None,
+            line_number: None,
+            target_anchor: None,
+        })
+        .unwrap();
+    }
+
+    fn matched(suggestions: &[LinkSuggestion], id: i64) -> Option<&str> {
+        suggestions.iter().find(|s| s.note.id == Some(id))?.signals.matched.as_deref()
+    }
+
+    #[test]
+    fn mentions_by_title_alias_and_file_name() {
+        let db = IndexDb::open_in_memory().unwrap();
+        let me = add(
+            &db,
+            "me.md",
+            "Me",
+            None,
+            "Sourdough needs a starter. The levain-schedule matters, \
+             and so does the [[Bread Oven]]. Ovens vary. Rye too.",
+        );
+        let dough = add(&db, "sourdough.md", "Sourdough", None, "");
+        let starter =
+            add(&db, "culture.md", "Culture", Some(r#"{"aliases": ["Starter"]}"#), "");
+        let schedule = add(&db, "levain-schedule.md", "Feeding plan", None, "");
+        let oven = add(&db, "oven.md", "Oven", None, "");
+        let bread = add(&db, "bread-oven.md", "Bread Oven", None, "");
+        let rye = add(&db, "rye.md", "Rye", None, "");
+        link(&db, me, rye);
+
+        let suggestions = suggest_links(&db, me, 10).unwrap();
+        assert_eq!(matched(&suggestions, dough), Some("Sourdough"));
+        assert_eq!(matched(&suggestions, starter), Some("Starter"));
+        assert_eq!(matched(&suggestions, schedule), Some("levain-schedule"));
+        // "Ovens" is not the word "oven", and linked text is not a mention
+        assert!(matched(&suggestions, oven).is_none());
+        assert!(matched(&suggestions, bread).is_none());
+        // Already linked
+        assert!(suggestions.iter().all(|s| s.note.id != Some(rye)));
+        assert!(suggestions.iter().all(|s| s.note.id != Some(me)));
+
+        let top = &suggestions[0];
+        assert!((top.confidence - MENTION_WEIGHT).abs() < 1e-9);
+        let by_stem = suggestions.iter().find(|s| s.note.id == Some(schedule)).unwrap();
+        assert!((by_stem.confidence - MENTION_WEIGHT * STEM_MENTION).abs() < 1e-9);
+    }
+
+    #[test]
+    fn limit_and_unknown_notes() {
+        let db = IndexDb::open_in_memory().unwrap();
+        let me = add(&db, "me.md", "Me", None, "alpha and bravo");
+        add(&db, "a.md", "Alpha", None, "");
+        add(&db, "b.md", "Bravo", None, "");
+        assert_eq!(suggest_links(&db, me, 1).unwrap().len(), 1);
+        assert!(matches!(suggest_links(&db, 99, 5), Err(IndexError::NoteNotFound(_))));
+    }
+
+    #[test]
+    fn whole_word_mentions() {
+        assert_eq!(count_mentions("rust, rusty and rust.", "rust"), 2);
+        assert_eq!(count_mentions("the bread oven is hot", "bread oven"), 1);
+        assert_eq!(count_mentions("nothing", "rust"), 0);
+        assert_eq!(unlinked_text("see [[Rust]] and rust"), "see   and rust");
+    }
+}
@@ -0,0 +1,198 @@
+//! Multi-hop walks over the link graph.
+//!
+//! [`walk_links`] visits notes breadth-first from a starting note, following
+//! either outgoing links or backlinks, up to a number of hops. Each note is
+//! reached once, by a shortest route, so cycles end the walk instead of
+//! looping.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+
+use super::db::{IndexDb, IndexError};
+use super::types::{IndexedNote, LinkType};
+
+/// Which links a walk follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkDirection {
+    /// From a note to the notes it links to.
+    Outgoing,
+    /// From a note to the notes linking to it.
+    Incoming,
+}
+
+/// A note reached by a walk.
+#[derive(Debug, Clone)]
+pub struct ReachedNote {
+    pub note: IndexedNote,
+    /// Hops from the starting note (1 = direct neighbour).
+    pub depth: usize,
+    /// Paths of the notes on the route, from the starting note to this one.
+    pub route: Vec<PathBuf>,
+}
+
+/// Notes within `max_depth` hops of `start`, nearest first and by path
+/// within a depth. With `link_type`, only links of that type are followed.
+pub fn walk_links(
+    db: &IndexDb,
+    start: i64,
+    direction: LinkDirection,
+    max_depth: usize,
+    link_type: Option<LinkType>,
+) -> Result<Vec<ReachedNote>, IndexError> {
+    let Some(first) = db.get_note_by_id(start)? else {
+        return Err(IndexError::NoteNotFound(format!("id {start}")));
+    };
+    let mut parent: HashMap<i64, i64> = HashMap::new();
+    let mut paths = HashMap::from([(start, first.path)]);
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([(start, 0)]);
+    let mut reached = Vec::new();
+
+    while let Some((id, depth)) = queue.pop_front() {
+        if depth == max_depth {
+            continue;
+        }
+        let links = match direction {
+            LinkDirection::Outgoing => db.get_outgoing_links(id)?,
+            LinkDirection::Incoming => db.get_backlinks(id)?,
+        };
+        let mut neighbours: Vec<i64> = links
+            .into_iter()
+            .filter(|l| link_type.is_none_or(|t| l.link_type == t))
+            .filter_map(|l| match direction {
+                LinkDirection::Outgoing => l.target_id,
+                LinkDirection::Incoming => Some(l.source_id),
+            })
+            .collect();
+        neighbours.sort_unstable();
+        neighbours.dedup();
+
+        for next in neighbours {
+            if !visited.insert(next) {
+                continue;
+            }
+            let Some(note) = db.get_note_by_id(next)? else {
+                continue;
+            };
+            parent.insert(next, id);
+            paths.insert(next, note.path.clone());
+            queue.push_back((next, depth + 1));
+            reached.push(ReachedNote { note, depth: depth + 1, route: Vec::new() });
+        }
+    }
+
+    for r in &mut reached {
+        let mut ids = vec![r.note.id.unwrap_or_default()];
+        while let Some(&prev) = ids.last().and_then(|id| parent.get(id)) {
+            ids.push(prev);
+        }
+        r.route = ids.iter().rev().filter_map(|id| paths.get(id).cloned()).collect();
+    }
+    reached.sort_by(|a, b| a.depth.cmp(&b.depth).then(a.note.path.cmp(&b.note.path)));
+    Ok(reached)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::types::{IndexedLink, NoteType};
+    use chrono::Utc;
+    use std::path::Path;
+
+    fn add(db: &IndexDb, path: &str) -> i64 {
+        db.insert_note(&IndexedNote {
+            id: None,
+            path: PathBuf::from(path),
+            note_type: NoteType::Zettel,
+            title: path.to_string(),
+            created: None,
+            modified: Utc::now(),
+            frontmatter_json: None,
+            content_hash: format!("hash-{path}"),
+            word_count: 0,
+            heading_json: None,
+        })
+        .unwrap()
+    }
+
+    fn link(db: &IndexDb, from: i64, to: i64, link_type: LinkType) {
+        db.insert_link(&IndexedLink {
+            id: None,
+            source_id: from,
+            target_id: Some(to),
+            target_path: format!("{to}"),
+            link_text: None,
+            link_type,
+            context: None,
+            line_number: None,
+            target_anchor: None,
+        })
+        .unwrap();
+    }
+
+    fn summary(reached: &[ReachedNote]) -> Vec<(String, usize, usize)> {
+        reached
+            .iter()
+            .map(|r| (r.note.path.display().to_string(), r.depth, r.route.len()))
+            .collect()
+    }
+
+    /// a -> b -> c -> a (cycle), b -> d, e -> a
+    fn graph() -> (IndexDb, [i64; 5]) {
+        let db = IndexDb::open_in_memory().unwrap();
+        let ids = ["a.md", "b.md", "c.md", "d.md", "e.md"].map(|p| add(&db, p));
+        let [a, b, c, d, e] = ids;
+        link(&db, a, b, LinkType::Wikilink);
+        link(&db, b, c, LinkType::Wikilink);
+        link(&db, c, a, LinkType::Wikilink);
+        link(&db, b, d, LinkType::Embed);
+        link(&db, e, a, LinkType::Wikilink);
+        (db, ids)
+    }
+
+    #[test]
+    fn walks_outgoing_links_by_depth() {
+        let (db, [a, ..]) = graph();
+        let reached = walk_links(&db, a, LinkDirection::Outgoing, 2, None).unwrap();
+        assert_eq!(
+            summary(&reached),
+            [("b.md".into(), 1, 2), ("c.md".into(), 2, 3), ("d.md".into(), 2, 3)]
+        );
+        assert_eq!(
+            reached[1].route,
+            [Path::new("a.md"), Path::new("b.md"), Path::new("c.md")]
+        );
+
+        let one = walk_links(&db, a, LinkDirection::Outgoing, 1, None).unwrap();
+        assert_eq!(summary(&one), [("b.md".into(), 1, 2)]);
+    }
+
+    #[test]
+    fn cycles_end_the_walk() {
+        let (db, [a, ..]) = graph();
+        let reached = walk_links(&db, a, LinkDirection::Outgoing, 10, None).unwrap();
+        assert_eq!(reached.len(), 3);
+        assert!(reached.iter().all(|r| r.note.id != Some(a)));
+    }
+
+    #[test]
+    fn walks_backlinks_and_filters_types() {
+        let (db, [a, _, _, d, _]) = graph();
+        let reached = walk_links(&db, a, LinkDirection::Incoming, 2, None).unwrap();
+        assert_eq!(
+            summary(&reached),
+            [("c.md".into(), 1, 2), ("e.md".into(), 1, 2), ("b.md".into(), 2, 3)]
+        );
+        assert_eq!(reached[1].route, [Path::new("a.md"), Path::new("e.md")]);
+
+        let embeds =
+            walk_links(&db, d, LinkDirection::Incoming, 3, Some(LinkType::Embed))
+                .unwrap();
+        assert_eq!(summary(&embeds), [("b.md".into(), 1, 2)]);
+
+        assert!(matches!(
+            walk_links(&db, 99, LinkDirection::Outgoing, 1, None),
+            Err(IndexError::NoteNotFound(_))
+        ));
+    }
+}
@@ -2,6 +2,7 @@
 
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::ops::Range;
 use std::path::PathBuf;
 use std::str::FromStr;
 
@@ -133,6 +134,8 @@ pub enum LinkType {
     Markdown,
     /// Frontmatter reference: project: note-name
     Frontmatter,
+    /// Embed (transclusion): ![[note]] or ![text](path.md)
+    Embed,
 }
 
 impl LinkType {
@@ -141,6 +144,7 @@ impl LinkType {
             Self::Wikilink => "wikilink",
             Self::Markdown => "markdown",
             Self::Frontmatter => "frontmatter",
+            Self::Embed => "embed",
         }
     }
 
@@ -149,6 +153,7 @@ impl LinkType {
             "wikilink" => Some(Self::Wikilink),
             "markdown" => Some(Self::Markdown),
             "frontmatter" => Some(Self::Frontmatter),
+            "embed" => Some(Self::Embed),
             _ => None,
         }
     }
@@ -173,6 +178,38 @@ pub struct IndexedNote {
     pub frontmatter_json: Option<String>,
     /// Content hash for change detection.
     pub content_hash: String,
+    /// Words of prose in the body (code blocks excluded).
+    #[serde(default)]
+    pub word_count: u32,
+    /// Heading outline as a JSON array of [`NoteHeading`].
+    #[serde(default)]
+    pub heading_json: Option<String>,
+}
+
+/// Words per minute assumed for reading time.
+pub const READING_WPM: u32 = 200;
+
+impl IndexedNote {
+    /// Heading outline, in document order.
+    pub fn headings(&self) -> Vec<NoteHeading> {
+        self.heading_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str(json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Estimated reading time in whole minutes (at least 1 for any text).
+    pub fn reading_minutes(&self) -> u32 {
+        self.word_count.div_ceil(READING_WPM)
+    }
+}
+
+/// A heading in a note's outline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteHeading {
+    /// 1 for `#`, 2 for `##`, and so on.
+    pub level: u8,
+    pub title: String,
 }
 
 /// A link between two notes.
@@ -184,8 +221,10 @@ pub struct IndexedLink {
     pub source_id: i64,
     /// Target note ID (None if broken link).
     pub target_id: Option<i64>,
-    /// Raw target path from the link.
+    /// Raw target path from the link, without any `#heading` or `^block` fragment.
     pub target_path: String,
+    /// Fragment addressing part of the target: heading text, or `^id` for a block.
+    pub target_anchor: Option<String>,
     /// Link display text (content within [[brackets]] or [text]).
     pub link_text: Option<String>,
     /// Type of link.
@@ -196,6 +235,75 @@ pub struct IndexedLink {
     pub line_number: Option<u32>,
 }
 
+/// Kind of linkable location inside a note.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnchorKind {
+    /// A markdown heading (`## Section`).
+    Heading,
+    /// A block ID at the end of a line (`text ^block-id`).
+    Block,
+}
+
+impl AnchorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Heading => "heading",
+            Self::Block => "block",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "heading" => Some(Self::Heading),
+            "block" => Some(Self::Block),
+            _ => None,
+        }
+    }
+}
+
+/// A heading or block ID that links can point to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteAnchor {
+    /// Owning note ID.
+    pub note_id: i64,
+    /// Heading or block.
+    pub kind: AnchorKind,
+    /// Heading text, or block ID without the `^`.
+    pub name: String,
+    /// Line number in the note body (1-based).
+    pub line_number: u32,
+}
+
+impl NoteAnchor {
+    /// Whether a link fragment (`Heading text`, `heading-text`, or `^id`) refers
+    /// to this anchor.
+    ///
+    /// Headings match by slug, so `[[note#Next Steps]]` and
+    /// `[text](note.md#next-steps)` both resolve to `## Next steps`.
+    pub fn matches(&self, fragment: &str) -> bool {
+        match (self.kind, fragment.strip_prefix('^')) {
+            (AnchorKind::Block, Some(id)) => self.name == id,
+            (AnchorKind::Heading, None) => slugify(&self.name) == slugify(fragment),
+            _ => false,
+        }
+    }
+}
+
+/// GitHub-style heading slug: lowercase, spaces to hyphens, punctuation dropped.
+pub fn slugify(text: &str) -> String {
+    text.trim()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' | '-' => Some('-'),
+            '_' => Some('_'),
+            c if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
 /// Temporal activity record - when a note was referenced in a daily.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemporalActivity {
@@ -241,6 +349,229 @@ pub struct NoteQuery {
     pub limit: Option<u32>,
     /// Offset for pagination.
     pub offset: Option<u32>,
+    /// Frontmatter predicates; a note must satisfy all of them.
+    pub fields: Vec<FieldFilter>,
+}
+
+/// Comparison operator in a [`FieldFilter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl FieldOp {
+    /// The operator as written in a filter, e.g. `">="`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Eq => "=",
+            Self::Ne => "!=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+        }
+    }
+}
+
+/// A predicate on a frontmatter field, such as `status=doing` or
+/// `priority>=3`.
+///
+/// Values that parse as numbers compare numerically; anything else compares
+/// as text, which orders ISO dates correctly. `=` ignores case and matches
+/// any element of a list field; `!=` also matches notes without the field.
+/// Nested mappings are addressed with dots (`review.next`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldFilter {
+    pub key: String,
+    pub op: FieldOp,
+    pub value: String,
+}
+
+impl FieldFilter {
+    /// The value as a number, when it is one.
+    pub fn numeric_value(&self) -> Option<f64> {
+        self.value.trim().parse::<f64>().ok().filter(|n| n.is_finite())
+    }
+}
+
+/// A `--where` expression that could not be parsed.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error(
+    "invalid field filter '{0}': expected KEY=VALUE, KEY!=VALUE, KEY<VALUE, \
+     KEY<=VALUE, KEY>VALUE, or KEY>=VALUE"
+)]
+pub struct FieldFilterError(pub String);
+
+impl FromStr for FieldFilter {
+    type Err = FieldFilterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || FieldFilterError(s.to_string());
+        let pos = s.find(['=', '!', '<', '>']).ok_or_else(err)?;
+        let (key, rest) = s.split_at(pos);
+        // Longest operators first so `>=` is not read as `>` with value `=3`
+        let (op, value) = [
+            ("!=", FieldOp::Ne),
+            ("<=", FieldOp::Le),
+            (">=", FieldOp::Ge),
+            ("=", FieldOp::Eq),
+            ("<", FieldOp::Lt),
+            (">", FieldOp::Gt),
+        ]
+        .into_iter()
+        .find_map(|(token, op)| rest.strip_prefix(token).map(|v| (op, v)))
+        .ok_or_else(err)?;
+
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(err());
+        }
+        Ok(Self { key: key.to_string(), op, value: value.trim().to_string() })
+    }
+}
+
+impl std::fmt::Display for FieldFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}{}", self.key, self.op.as_str(), self.value)
+    }
+}
+
+/// Marks the start of a hit in full-text index output.
+#[cfg(feature = "native")]
+pub(crate) const HIT_START: char = '\u{2}';
+/// Marks the end of a hit in full-text index output.
+#[cfg(feature = "native")]
+pub(crate) const HIT_END: char = '\u{3}';
+
+/// Text around a search hit, with the matched terms located.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Excerpt {
+    /// The excerpt, with `…` where it was cut from a longer text.
+    pub text: String,
+    /// Character ranges of the matched terms in `text`.
+    pub highlights: Vec<Range<usize>>,
+}
+
+impl Excerpt {
+    /// Parse text with hits wrapped in [`HIT_START`] and [`HIT_END`].
+    #[cfg(feature = "native")]
+    pub(crate) fn from_marked(marked: &str) -> Self {
+        let mut text = String::with_capacity(marked.len());
+        let mut highlights = Vec::new();
+        let mut chars = 0;
+        let mut start = None;
+        for c in marked.chars() {
+            match c {
+                HIT_START => start = Some(chars),
+                HIT_END => {
+                    if let Some(start) = start.take() {
+                        highlights.push(start..chars);
+                    }
+                }
+                _ => {
+                    text.push(c);
+                    chars += 1;
+                }
+            }
+        }
+        Self { text, highlights }
+    }
+
+    /// The text with each highlight wrapped in `open` and `close`.
+    pub fn marked(&self, open: &str, close: &str) -> String {
+        let mut out = String::with_capacity(self.text.len());
+        let mut highlights = self.highlights.iter().peekable();
+        for (i, c) in self.text.chars().enumerate() {
+            if highlights.peek().is_some_and(|h| h.start == i && h.end > i) {
+                out.push_str(open);
+            }
+            out.push(c);
+            if highlights.peek().is_some_and(|h| h.end == i + 1) {
+                out.push_str(close);
+                highlights.next();
+            }
+        }
+        out
+    }
+}
+
+/// Where a search term matched in a note's body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct BodyHit {
+    /// Line in the body (1-based), counted like link line numbers.
+    pub line: u32,
+    /// Character offset of the hit in the body.
+    pub start: usize,
+    /// Character offset just past the hit.
+    pub end: usize,
+}
+
+impl BodyHit {
+    /// Locate the hits of a body returned with [`HIT_START`]/[`HIT_END`] marks.
+    #[cfg(feature = "native")]
+    pub(crate) fn from_marked(marked: &str) -> Vec<Self> {
+        let body = Excerpt::from_marked(marked);
+        let mut hits = Vec::with_capacity(body.highlights.len());
+        let mut highlights = body.highlights.into_iter().peekable();
+        let mut line = 1;
+        for (i, c) in body.text.chars().enumerate() {
+            while let Some(h) = highlights.next_if(|h| h.start == i) {
+                hits.push(Self { line, start: h.start, end: h.end });
+            }
+            if c == '\n' {
+                line += 1;
+            }
+        }
+        hits
+    }
+}
+
+/// A note matched by the full-text index.
+#[derive(Debug, Clone)]
+pub struct FullTextMatch {
+    /// The matching note.
+    pub note: IndexedNote,
+    /// Excerpt from the best-matching column (title or body).
+    pub snippet: Excerpt,
+    /// Every hit in the note's body, in order.
+    pub hits: Vec<BodyHit>,
+    /// FTS5 bm25 rank (more negative = more relevant).
+    pub rank: f64,
+}
+
+/// A checkbox list item (`- [ ] ...`) inside a note.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedCheckbox {
+    /// Database ID (None if not yet inserted).
+    pub id: Option<i64>,
+    /// Owning note ID.
+    pub note_id: i64,
+    /// Path of the owning note (populated by queries).
+    pub note_path: PathBuf,
+    /// Line number in the note body (1-based).
+    pub line_number: u32,
+    /// Item text after the checkbox.
+    pub text: String,
+    /// Status derived from the box marker.
+    pub status: TaskStatus,
+    /// Nearest heading above the item.
+    pub heading: Option<String>,
+}
+
+/// Query filter for listing checkbox items.
+#[derive(Debug, Clone, Default)]
+pub struct CheckboxQuery {
+    /// Filter by status.
+    pub status: Option<TaskStatus>,
+    /// Filter by owning note path substring.
+    pub path_contains: Option<String>,
+    /// Maximum number of results.
+    pub limit: Option<u32>,
 }
 
 /// Aggregate activity data for computing summaries.
@@ -268,3 +599,85 @@ pub struct CooccurrencePair {
     /// Most recent shared daily date.
     pub most_recent: Option<String>,
 }
+
+/// Result of [`IndexDb::vacuum`](super::IndexDb::vacuum).
+#[derive(Debug, Clone, Default)]
+pub struct VacuumStats {
+    /// Rows removed because the note they belong to no longer exists.
+    pub rows_pruned: usize,
+    /// Database size in bytes before maintenance.
+    pub size_before: u64,
+    /// Database size in bytes after maintenance.
+    pub size_after: u64,
+}
+
+/// A near-duplicate pair: two notes with largely the same body text.
+#[derive(Debug, Clone)]
+pub struct SimilarityPair {
+    /// First note ID (the lower of the two).
+    pub note_a_id: i64,
+    /// Second note ID.
+    pub note_b_id: i64,
+    /// Jaccard similarity of the notes' shingle sets (0.0-1.0).
+    pub similarity: f64,
+}
+
+/// A note that disappeared from the vault, kept as a tombstone.
+#[derive(Debug, Clone, Serialize)]
+pub struct NoteTombstone {
+    /// Path the note had, relative to the vault root.
+    pub path: PathBuf,
+    pub note_type: NoteType,
+    pub title: String,
+    /// When the index noticed the note was gone.
+    pub deleted_at: DateTime<Utc>,
+    /// Links in remaining notes that still point at it.
+    pub backlinks: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "native")]
+    fn test_excerpt_from_marked() {
+        let excerpt = Excerpt::from_marked("…the \u{2}café\u{3} on \u{2}main\u{3} st…");
+        assert_eq!(excerpt.text, "…the café on main st…");
+        assert_eq!(excerpt.highlights, [5..9, 13..17]);
+        assert_eq!(excerpt.marked("*", "*"), "…the *café* on *main* st…");
+
+        let hits = BodyHit::from_marked("intro\n\u{2}café\u{3}\nand \u{2}café\u{3}");
+        let hits: Vec<_> = hits.iter().map(|h| (h.line, h.start, h.end)).collect();
+        assert_eq!(hits, [(2, 6, 10), (3, 15, 19)]);
+    }
+
+    #[test]
+    fn field_filter_parses_each_operator() {
+        let cases = [
+            ("status=doing", FieldOp::Eq, "doing"),
+            ("status!=done", FieldOp::Ne, "done"),
+            ("priority>=3", FieldOp::Ge, "3"),
+            ("priority<=3", FieldOp::Le, "3"),
+            ("priority>3", FieldOp::Gt, "3"),
+            ("due<2025-02-01", FieldOp::Lt, "2025-02-01"),
+            ("title=a=b", FieldOp::Eq, "a=b"),
+            (" owner = Ana ", FieldOp::Eq, "Ana"),
+        ];
+        for (input, op, value) in cases {
+            let filter: FieldFilter = input.parse().unwrap();
+            assert_eq!((filter.op, filter.value.as_str()), (op, value), "{input}");
+        }
+        assert_eq!(
+            "priority>=3".parse::<FieldFilter>().unwrap().to_string(),
+            "priority>=3"
+        );
+    }
+
+    #[test]
+    fn field_filter_rejects_malformed_input() {
+        for input in ["status", "=doing", "status!doing", ""] {
+            assert!(input.parse::<FieldFilter>().is_err(), "{input}");
+        }
+    }
+}
@@ -0,0 +1,519 @@
+//! Ingestion of external material into the vault.
+//!
+//! Voice memos go through a user-supplied transcription command (whisper.cpp,
+//! a cloud CLI, anything that prints text to stdout). The recording is
+//! archived under `assets/audio/` and the transcript becomes an inbox note
+//! that links back to it.
+//!
+//! Text snippets (say, shared from a phone into a Syncthing folder) become
+//! inbox notes as they are. A ledger of their hashes keeps a snippet that
+//! arrives twice from being filed twice.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::paths::PathResolver;
+use crate::vault::content_hash_str;
+
+#[derive(Debug, Error)]
+pub enum IngestError {
+    #[error("audio file not found: {0}")]
+    MissingFile(PathBuf),
+
+    #[error("note already exists: {0}")]
+    Exists(PathBuf),
+
+    #[error("failed to run transcriber `{command}`: {source}")]
+    Spawn {
+        command: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("transcriber exited with {status}: {stderr}")]
+    TranscriberFailed { status: String, stderr: String },
+
+    #[error("transcriber produced no text for {0}")]
+    EmptyTranscript(PathBuf),
+
+    #[error("failed to write {path}: {source}")]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("snippet is empty: {0}")]
+    EmptySnippet(PathBuf),
+
+    #[error("invalid entry in {path} at line {line}: {message}")]
+    Ledger { path: PathBuf, line: usize, message: String },
+}
+
+/// Run `command` on `audio` and return the trimmed transcript from stdout.
+///
+/// `{file}` in the command is replaced with the audio path; without it, the
+/// path is appended as the last argument. The command runs through the shell,
+/// so pipes and redirects work.
+pub fn transcribe(command: &str, audio: &Path) -> Result<String, IngestError> {
+    if !audio.is_file() {
+        return Err(IngestError::MissingFile(audio.to_path_buf()));
+    }
+
+    let output = shell_command(command, audio)
+        .output()
+        .map_err(|source| IngestError::Spawn { command: command.to_string(), source })?;
+    if !output.status.success() {
+        return Err(IngestError::TranscriberFailed {
+            status: output.status.to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+
+    let transcript = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if transcript.is_empty() {
+        return Err(IngestError::EmptyTranscript(audio.to_path_buf()));
+    }
+    Ok(transcript)
+}
+
+#[cfg(unix)]
+fn shell_command(command: &str, audio: &Path) -> Command {
+    // The path is passed as $1 rather than spliced in, so no quoting is needed
+    let script = if command.contains("{file}") {
+        command.replace("{file}", "\"$1\"")
+    } else {
+        format!("{command} \"$1\"")
+    };
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(script).arg("mdv-transcribe").arg(audio);
+    cmd
+}
+
+#[cfg(not(unix))]
+fn shell_command(command: &str, audio: &Path) -> Command {
+    let quoted = format!("\"{}\"", audio.display());
+    let script = if command.contains("{file}") {
+        command.replace("{file}", &quoted)
+    } else {
+        format!("{command} {quoted}")
+    };
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(script);
+    cmd
+}
+
+/// A recording and its transcript, ready to become a note.
+#[derive(Debug, Clone)]
+pub struct VoiceMemo<'a> {
+    pub audio: &'a Path,
+    pub transcript: &'a str,
+    /// Note title; defaults to "Voice memo" plus the recording time.
+    pub title: Option<&'a str>,
+    /// When the memo was recorded (usually the ingestion time).
+    pub recorded: NaiveDateTime,
+}
+
+/// Where [`ingest_audio`] put things.
+#[derive(Debug, Clone)]
+pub struct IngestedAudio {
+    /// Stable identifier, `voice-<timestamp>`.
+    pub id: String,
+    pub title: String,
+    /// The new inbox note.
+    pub note_path: PathBuf,
+    /// The archived recording.
+    pub asset_path: PathBuf,
+}
+
+/// Archive the recording and write the transcript note.
+///
+/// The recording is copied, not moved; the caller decides whether the
+/// original can go.
+pub fn ingest_audio(
+    vault_root: &Path,
+    memo: &VoiceMemo<'_>,
+) -> Result<IngestedAudio, IngestError> {
+    if !memo.audio.is_file() {
+        return Err(IngestError::MissingFile(memo.audio.to_path_buf()));
+    }
+
+    let stamp = memo.recorded.format("%Y-%m-%d-%H%M%S").to_string();
+    let id = format!("voice-{stamp}");
+    let resolver = PathResolver::new(vault_root);
+    let note_path = resolver.voice_memo(&id);
+    if note_path.exists() {
+        return Err(IngestError::Exists(note_path));
+    }
+
+    let ext = memo.audio.extension().and_then(|e| e.to_str()).unwrap_or("audio");
+    let asset_path = resolver
+        .audio_dir(&memo.recorded.format("%Y").to_string())
+        .join(format!("{stamp}.{}", ext.to_lowercase()));
+    write_with_parents(&asset_path, |path| fs::copy(memo.audio, path).map(|_| ()))?;
+
+    let title = match memo.title {
+        Some(t) if !t.trim().is_empty() => t.trim().to_string(),
+        _ => format!("Voice memo {}", memo.recorded.format("%Y-%m-%d %H:%M")),
+    };
+    let content = render_note(vault_root, memo, &id, &title, &note_path, &asset_path);
+    write_with_parents(&note_path, |path| fs::write(path, &content))?;
+
+    Ok(IngestedAudio { id, title, note_path, asset_path })
+}
+
+fn render_note(
+    vault_root: &Path,
+    memo: &VoiceMemo<'_>,
+    id: &str,
+    title: &str,
+    note_path: &Path,
+    asset_path: &Path,
+) -> String {
+    let asset_rel = asset_path.strip_prefix(vault_root).unwrap_or(asset_path);
+    // Markdown link relative to the note, so it works in any markdown viewer
+    let depth = note_path
+        .strip_prefix(vault_root)
+        .map(|p| p.components().count().saturating_sub(1))
+        .unwrap_or(0);
+    let link = format!("{}{}", "../".repeat(depth), asset_rel.to_string_lossy());
+    let source = memo.audio.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+
+    format!(
+        "---\ntype: voice-memo\nmemo-id: {id}\ntitle: {title}\ncreated: {created}\n\
+         audio: {audio}\nsource: {source}\n---\n\n# {title}\n\n\
+         ![Recording]({link})\n\n## Transcript\n\n{transcript}\n",
+        id = id,
+        title = yaml_string(title),
+        created = memo.recorded.format("%Y-%m-%dT%H:%M:%S"),
+        audio = yaml_string(&asset_rel.to_string_lossy()),
+        source = yaml_string(&source),
+        link = link,
+        transcript = memo.transcript.trim(),
+    )
+}
+
+/// Quote a frontmatter value when plain YAML would misread it.
+fn yaml_string(value: &str) -> String {
+    let plain = !value.is_empty()
+        && !value.contains(": ")
+        && !value.contains(" #")
+        && !value.starts_with(|c: char| "!&*[]{}|>'\"%@`#,?-".contains(c));
+    if plain { value.to_string() } else { format!("{value:?}") }
+}
+
+/// A text snippet to file in the inbox.
+#[derive(Debug, Clone)]
+pub struct Snippet<'a> {
+    /// The file it came from; only its name is recorded.
+    pub source: &'a Path,
+    pub text: &'a str,
+    /// When the snippet arrived (usually the file's modification time).
+    pub received: NaiveDateTime,
+}
+
+/// Where [`ingest_snippet`] put a new snippet.
+#[derive(Debug, Clone)]
+pub struct IngestedSnippet {
+    /// Stable identifier, `snippet-<timestamp>-<hash>`.
+    pub id: String,
+    pub title: String,
+    pub note_path: PathBuf,
+}
+
+/// What happened to a snippet.
+#[derive(Debug, Clone)]
+pub enum SnippetOutcome {
+    Created(IngestedSnippet),
+    /// Same text as an earlier snippet; holds the note it was filed as
+    /// (relative to the vault, and possibly since moved or deleted).
+    Duplicate(PathBuf),
+}
+
+/// Snippets filed so far, by content hash.
+///
+/// Kept in `.mdvault/inbox.jsonl` rather than looked up in the vault, so a
+/// duplicate is recognised even after its note has been triaged.
+#[derive(Debug)]
+pub struct SnippetLedger {
+    path: PathBuf,
+    seen: HashMap<String, PathBuf>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LedgerEntry {
+    hash: String,
+    note: PathBuf,
+    source: String,
+    received: NaiveDateTime,
+}
+
+impl SnippetLedger {
+    /// Load the vault's ledger (empty if there is none yet).
+    pub fn open(vault_root: &Path) -> Result<Self, IngestError> {
+        let path = PathResolver::new(vault_root).inbox_ledger();
+        let content = match fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(source) => return Err(IngestError::Write { path, source }),
+        };
+
+        let mut seen = HashMap::new();
+        for (i, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: LedgerEntry =
+                serde_json::from_str(line).map_err(|e| IngestError::Ledger {
+                    path: path.clone(),
+                    line: i + 1,
+                    message: e.to_string(),
+                })?;
+            seen.insert(entry.hash, entry.note);
+        }
+        Ok(Self { path, seen })
+    }
+
+    fn record(&mut self, entry: LedgerEntry) -> Result<(), IngestError> {
+        let line = serde_json::to_string(&entry).expect("ledger entry serializes");
+        write_with_parents(&self.path, |path| {
+            let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{line}")
+        })?;
+        self.seen.insert(entry.hash, entry.note);
+        Ok(())
+    }
+}
+
+/// Whether a file in a drop folder is a snippet: `.txt`, `.md` or
+/// `.markdown`, and not hidden or a sync tool's partial download.
+pub fn is_snippet_file(path: &Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    if name.starts_with('.') || name.starts_with("~syncthing~") {
+        return false;
+    }
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    ["txt", "md", "markdown"].iter().any(|e| ext.eq_ignore_ascii_case(e))
+}
+
+/// File a snippet as an inbox note, unless the same text was filed before.
+pub fn ingest_snippet(
+    vault_root: &Path,
+    ledger: &mut SnippetLedger,
+    snippet: &Snippet<'_>,
+) -> Result<SnippetOutcome, IngestError> {
+    let text = snippet.text.trim();
+    if text.is_empty() {
+        return Err(IngestError::EmptySnippet(snippet.source.to_path_buf()));
+    }
+    let hash = content_hash_str(text);
+    if let Some(note) = ledger.seen.get(&hash) {
+        return Ok(SnippetOutcome::Duplicate(note.clone()));
+    }
+
+    let stamp = snippet.received.format("%Y-%m-%d-%H%M%S");
+    let id = format!("snippet-{stamp}-{}", &hash[..6]);
+    let note_path = PathResolver::new(vault_root).inbox_snippet(&id);
+    if note_path.exists() {
+        return Err(IngestError::Exists(note_path));
+    }
+
+    let title = snippet_title(text);
+    let source =
+        snippet.source.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    let content = format!(
+        "---\ntype: inbox\ninbox-id: {id}\ntitle: {title}\ncreated: {created}\n\
+         source: {source}\n---\n\n{text}\n",
+        title = yaml_string(&title),
+        created = snippet.received.format("%Y-%m-%dT%H:%M:%S"),
+        source = yaml_string(&source),
+    );
+    write_with_parents(&note_path, |path| fs::write(path, &content))?;
+
+    let rel = note_path.strip_prefix(vault_root).unwrap_or(&note_path).to_path_buf();
+    ledger.record(LedgerEntry {
+        hash,
+        note: rel,
+        source: source.to_string(),
+        received: snippet.received,
+    })?;
+    Ok(SnippetOutcome::Created(IngestedSnippet { id, title, note_path }))
+}
+
+/// The first line, without heading marks, cut to 60 characters.
+fn snippet_title(text: &str) -> String {
+    let first = text.lines().next().unwrap_or("").trim_start_matches('#').trim();
+    match first.char_indices().nth(60) {
+        Some((end, _)) => format!("{}…", first[..end].trim_end()),
+        None => first.to_string(),
+    }
+}
+
+fn write_with_parents(
+    path: &Path,
+    write: impl FnOnce(&Path) -> std::io::Result<()>,
+) -> Result<(), IngestError> {
+    let to_err = |source| IngestError::Write { path: path.to_path_buf(), source };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(to_err)?;
+    }
+    write(path).map_err(to_err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use tempfile::tempdir;
+
+    fn recorded() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 1, 15).unwrap().and_hms_opt(9, 30, 5).unwrap()
+    }
+
+    #[test]
+    fn ingest_archives_audio_and_links_it() {
+        let vault = tempdir().unwrap();
+        let audio = vault.path().join("Memo 12.M4A");
+        fs::write(&audio, b"fake audio").unwrap();
+
+        let memo = VoiceMemo {
+            audio: &audio,
+            transcript: "Call the plumber about the leak.\n",
+            title: None,
+            recorded: recorded(),
+        };
+        let result = ingest_audio(vault.path(), &memo).unwrap();
+
+        assert_eq!(result.id, "voice-2025-01-15-093005");
+        assert_eq!(result.title, "Voice memo 2025-01-15 09:30");
+        assert_eq!(
+            result.asset_path,
+            vault.path().join("assets/audio/2025/2025-01-15-093005.m4a")
+        );
+        assert_eq!(fs::read(&result.asset_path).unwrap(), b"fake audio");
+        assert!(audio.exists(), "original is kept");
+
+        let note = fs::read_to_string(&result.note_path).unwrap();
+        assert!(
+            note.starts_with("---\ntype: voice-memo\nmemo-id: voice-2025-01-15-093005\n")
+        );
+        assert!(note.contains("source: Memo 12.M4A\n"));
+        assert!(
+            note.contains("![Recording](../assets/audio/2025/2025-01-15-093005.m4a)")
+        );
+        assert!(note.ends_with("## Transcript\n\nCall the plumber about the leak.\n"));
+    }
+
+    #[test]
+    fn ingest_refuses_to_overwrite() {
+        let vault = tempdir().unwrap();
+        let audio = vault.path().join("memo.wav");
+        fs::write(&audio, b"x").unwrap();
+        let memo = VoiceMemo {
+            audio: &audio,
+            transcript: "hi",
+            title: Some("Idea: caching"),
+            recorded: recorded(),
+        };
+
+        let first = ingest_audio(vault.path(), &memo).unwrap();
+        let note = fs::read_to_string(&first.note_path).unwrap();
+        assert!(note.contains("title: \"Idea: caching\"\n"));
+        assert!(matches!(ingest_audio(vault.path(), &memo), Err(IngestError::Exists(_))));
+    }
+
+    #[test]
+    fn snippets_become_inbox_notes_once() {
+        let vault = tempdir().unwrap();
+        let mut ledger = SnippetLedger::open(vault.path()).unwrap();
+        let snippet = Snippet {
+            source: Path::new("/drop/share-1.txt"),
+            text: "\n# Idea: cheaper caching\nUse the CDN for thumbnails.\n",
+            received: recorded(),
+        };
+
+        let SnippetOutcome::Created(created) =
+            ingest_snippet(vault.path(), &mut ledger, &snippet).unwrap()
+        else {
+            panic!("expected a new note");
+        };
+        assert!(created.id.starts_with("snippet-2025-01-15-093005-"));
+        assert_eq!(created.title, "Idea: cheaper caching");
+        let note = fs::read_to_string(&created.note_path).unwrap();
+        assert!(note.starts_with("---\ntype: inbox\n"));
+        assert!(note.contains("title: \"Idea: cheaper caching\"\nc"));
+        assert!(note.contains("source: share-1.txt\n"));
+        assert!(
+            note.ends_with(
+                "---\n\n# Idea: cheaper caching\nUse the CDN for thumbnails.\n"
+            )
+        );
+
+        // The same text again, even from a reloaded ledger, is a duplicate
+        let mut reloaded = SnippetLedger::open(vault.path()).unwrap();
+        let again = Snippet { source: Path::new("share-2.md"), ..snippet.clone() };
+        let rel = created.note_path.strip_prefix(vault.path()).unwrap();
+        assert!(matches!(
+            ingest_snippet(vault.path(), &mut reloaded, &again).unwrap(),
+            SnippetOutcome::Duplicate(ref note) if note == rel
+        ));
+
+        let empty = Snippet { text: "  \n", ..snippet };
+        assert!(matches!(
+            ingest_snippet(vault.path(), &mut reloaded, &empty),
+            Err(IngestError::EmptySnippet(_))
+        ));
+    }
+
+    #[test]
+    fn snippet_files_and_titles() {
+        assert!(is_snippet_file(Path::new("drop/note.TXT")));
+        assert!(is_snippet_file(Path::new("drop/clip.markdown")));
+        assert!(!is_snippet_file(Path::new("drop/photo.jpg")));
+        assert!(!is_snippet_file(Path::new("drop/.syncthing.note.txt.tmp")));
+        assert!(!is_snippet_file(Path::new("drop/~syncthing~note.txt.tmp")));
+
+        let long = "word ".repeat(20);
+        assert_eq!(snippet_title(&long).chars().count(), 60);
+        assert!(snippet_title(&long).ends_with("word…"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn transcribe_substitutes_file_and_captures_stdout() {
+        let dir = tempdir().unwrap();
+        let audio = dir.path().join("it's a memo.ogg");
+        fs::write(&audio, "spoken words").unwrap();
+
+        assert_eq!(transcribe("cat {file}", &audio).unwrap(), "spoken words");
+        assert_eq!(transcribe("cat", &audio).unwrap(), "spoken words");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn transcribe_reports_failures() {
+        let dir = tempdir().unwrap();
+        let audio = dir.path().join("memo.ogg");
+        fs::write(&audio, "").unwrap();
+
+        let err = transcribe("echo broken >&2; false", &audio).unwrap_err();
+        assert!(
+            matches!(err, IngestError::TranscriberFailed { ref stderr, .. } if stderr == "broken")
+        );
+        assert!(matches!(
+            transcribe("cat", &audio),
+            Err(IngestError::EmptyTranscript(_))
+        ));
+        assert!(matches!(
+            transcribe("cat", &dir.path().join("nope.ogg")),
+            Err(IngestError::MissingFile(_))
+        ));
+    }
+}
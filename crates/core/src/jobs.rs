@@ -0,0 +1,469 @@
+//! Scheduled jobs: Lua functions that run periodically.
+//!
+//! Jobs are defined in `.mdvault/jobs.lua` in the vault:
+//!
+//! ```lua
+//! job "tomorrow-daily" {
+//!     every = "daily 21:00",
+//!     description = "Create tomorrow's daily note",
+//!     run = function(job)
+//!         mdv.macro("plan-tomorrow")
+//!     end,
+//! }
+//! ```
+//!
+//! `run` gets the vault bindings (`mdv.capture`, `mdv.macro`, `mdv.query`,
+//! `mdv.write_note`, ...), limited by the job's `permissions` if it declares
+//! any. `mdv jobs run-due`, typically from cron, runs every
+//! job whose time has come since it last ran; last runs are kept in
+//! `.mdvault/state/jobs.toml`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime, Timelike, Weekday};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::paths::PathResolver;
+use crate::scripting::{
+    LuaEngine, Permissions, SandboxConfig, ScriptingError, VaultContext,
+};
+
+/// Errors loading, running or recording jobs.
+#[derive(Debug, Error)]
+pub enum JobError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to evaluate {path}: {source}")]
+    Lua {
+        path: PathBuf,
+        #[source]
+        source: ScriptingError,
+    },
+
+    #[error("Invalid job in {path}: {message}")]
+    Invalid { path: PathBuf, message: String },
+
+    #[error("Unknown job '{0}'. Run 'mdv jobs list' to see them")]
+    Unknown(String),
+
+    #[error("Job '{name}' failed: {source}")]
+    Failed {
+        name: String,
+        #[source]
+        source: ScriptingError,
+    },
+
+    #[error("Failed to parse {path}: {message}")]
+    State { path: PathBuf, message: String },
+}
+
+/// When a job runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Schedule {
+    /// At the start of every hour (`hourly`).
+    Hourly,
+    /// Every day at a time (`daily 07:00`; midnight without a time).
+    Daily(NaiveTime),
+    /// Every week on a day, at a time (`weekly mon 09:00`).
+    Weekly(Weekday, NaiveTime),
+    /// A fixed interval after the last run (`30m`, `6h`, `2d`).
+    Every(Duration),
+}
+
+impl FromStr for Schedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_lowercase();
+        let words: Vec<&str> = lower.split_whitespace().collect();
+        let time = |word: Option<&&str>| match word {
+            None => Ok(NaiveTime::MIN),
+            Some(word) => NaiveTime::parse_from_str(word, "%H:%M")
+                .map_err(|_| format!("invalid time '{word}' (expected HH:MM)")),
+        };
+        match words.as_slice() {
+            ["hourly"] => Ok(Self::Hourly),
+            ["daily"] | ["daily", _] => Ok(Self::Daily(time(words.get(1))?)),
+            ["weekly", day] | ["weekly", day, _] => {
+                let day = day
+                    .parse::<Weekday>()
+                    .map_err(|_| format!("invalid weekday '{day}'"))?;
+                Ok(Self::Weekly(day, time(words.get(2))?))
+            }
+            [interval] => parse_interval(interval).map(Self::Every),
+            _ => Err(format!(
+                "invalid schedule '{s}' (expected hourly, daily HH:MM, \
+                 weekly DAY HH:MM, or an interval like 30m, 6h, 2d)"
+            )),
+        }
+    }
+}
+
+/// `30m`, `6h`, `2d`.
+fn parse_interval(s: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid schedule '{s}'");
+    let unit = s.chars().last().ok_or_else(invalid)?;
+    let n: i64 = s[..s.len() - unit.len_utf8()].parse().map_err(|_| invalid())?;
+    if n <= 0 {
+        return Err(invalid());
+    }
+    match unit {
+        'm' => Ok(Duration::minutes(n)),
+        'h' => Ok(Duration::hours(n)),
+        'd' => Ok(Duration::days(n)),
+        _ => Err(invalid()),
+    }
+}
+
+impl Schedule {
+    /// The latest scheduled time at or before `now`; `None` for intervals.
+    fn previous(&self, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        match *self {
+            Self::Hourly => now.with_minute(0).and_then(|t| t.with_second(0)),
+            Self::Daily(time) => {
+                let at = now.date().and_time(time);
+                Some(if at > now { at - Duration::days(1) } else { at })
+            }
+            Self::Weekly(day, time) => {
+                let back = (7 + now.weekday().num_days_from_monday()
+                    - day.num_days_from_monday())
+                    % 7;
+                let at = (now.date() - Duration::days(back as i64)).and_time(time);
+                Some(if at > now { at - Duration::days(7) } else { at })
+            }
+            Self::Every(_) => None,
+        }
+    }
+
+    fn period(&self) -> Duration {
+        match *self {
+            Self::Hourly => Duration::hours(1),
+            Self::Daily(_) => Duration::days(1),
+            Self::Weekly(..) => Duration::days(7),
+            Self::Every(interval) => interval,
+        }
+    }
+
+    /// Whether a job last run at `last_run` should run at `now`.
+    ///
+    /// A job that never ran is due. Missed runs are caught up once, not once
+    /// per missed slot.
+    pub fn is_due(&self, last_run: Option<NaiveDateTime>, now: NaiveDateTime) -> bool {
+        let Some(last) = last_run else {
+            return true;
+        };
+        match self.previous(now) {
+            Some(slot) => last < slot,
+            None => now - last >= self.period(),
+        }
+    }
+
+    /// When the job runs next: `now` if it is due.
+    pub fn next_run(
+        &self,
+        last_run: Option<NaiveDateTime>,
+        now: NaiveDateTime,
+    ) -> NaiveDateTime {
+        if self.is_due(last_run, now) {
+            return now;
+        }
+        match (self.previous(now), last_run) {
+            (Some(slot), _) => slot + self.period(),
+            (None, Some(last)) => last + self.period(),
+            (None, None) => now,
+        }
+    }
+}
+
+/// A job defined in `jobs.lua`.
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub name: String,
+    /// The schedule as written, e.g. `daily 07:00`.
+    pub every: String,
+    pub schedule: Schedule,
+    pub description: Option<String>,
+    /// What `run` may do.
+    pub permissions: Permissions,
+    /// Position in the Lua `__jobs` table, to find `run` again.
+    slot: usize,
+}
+
+/// Defines `job "name" { ... }` as a curried call collecting into `__jobs`.
+const LUA_PRELUDE: &str = r#"
+__jobs = {}
+function job(name)
+    return function(def)
+        def.name = name
+        __jobs[#__jobs + 1] = def
+    end
+end
+"#;
+
+/// The jobs of a vault, loaded into a Lua engine that can run them.
+pub struct Jobs {
+    engine: Option<LuaEngine>,
+    jobs: Vec<Job>,
+}
+
+impl Jobs {
+    /// Evaluate `.mdvault/jobs.lua`. A vault without one has no jobs.
+    pub fn load(vault_ctx: VaultContext) -> Result<Self, JobError> {
+        let path = PathResolver::new(&vault_ctx.vault_root).jobs();
+        if !path.exists() {
+            return Ok(Self { engine: None, jobs: Vec::new() });
+        }
+        let source = std::fs::read_to_string(&path)
+            .map_err(|e| JobError::Io { path: path.clone(), source: e })?;
+        let engine =
+            LuaEngine::with_vault_context(SandboxConfig::restricted(), vault_ctx)
+                .map_err(|e| JobError::Lua { path: path.clone(), source: e })?;
+        let jobs = parse_lua(&engine, &source, &path)?;
+        Ok(Self { engine: Some(engine), jobs })
+    }
+
+    pub fn jobs(&self) -> &[Job] {
+        &self.jobs
+    }
+
+    /// Look up a job by name.
+    pub fn get(&self, name: &str) -> Result<&Job, JobError> {
+        self.jobs
+            .iter()
+            .find(|job| job.name == name)
+            .ok_or_else(|| JobError::Unknown(name.to_string()))
+    }
+
+    /// Jobs due at `now` according to `state`, in definition order.
+    pub fn due(&self, state: &JobState, now: NaiveDateTime) -> Vec<&Job> {
+        self.jobs
+            .iter()
+            .filter(|job| job.schedule.is_due(state.last_run(&job.name), now))
+            .collect()
+    }
+
+    /// Call a job's `run(job)`; a string it returns is passed back.
+    pub fn run(&self, job: &Job, state: &JobState) -> Result<Option<String>, JobError> {
+        let failed = |e: mlua::Error| JobError::Failed {
+            name: job.name.clone(),
+            source: e.into(),
+        };
+        let Some(engine) = &self.engine else {
+            return Err(JobError::Unknown(job.name.clone()));
+        };
+        engine
+            .set_permissions(job.permissions)
+            .map_err(|e| JobError::Failed { name: job.name.clone(), source: e })?;
+        let lua = engine.lua();
+        let defs: mlua::Table = lua.globals().get("__jobs").map_err(failed)?;
+        let def: mlua::Table = defs.get(job.slot).map_err(failed)?;
+        let run: mlua::Function = def.get("run").map_err(failed)?;
+
+        let arg = lua.create_table().map_err(failed)?;
+        arg.set("name", job.name.as_str()).map_err(failed)?;
+        arg.set("every", job.every.as_str()).map_err(failed)?;
+        if let Some(last) = state.last_run(&job.name) {
+            arg.set("last_run", last.format(TIMESTAMP_FORMAT).to_string())
+                .map_err(failed)?;
+        }
+        match run.call::<mlua::Value>(arg).map_err(failed)? {
+            mlua::Value::String(s) => Ok(Some(s.to_string_lossy())),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Collect the `job` definitions of a jobs file.
+fn parse_lua(
+    engine: &LuaEngine,
+    source: &str,
+    path: &Path,
+) -> Result<Vec<Job>, JobError> {
+    let lua_err =
+        |e: mlua::Error| JobError::Lua { path: path.to_path_buf(), source: e.into() };
+    let invalid =
+        |message: String| JobError::Invalid { path: path.to_path_buf(), message };
+    let lua = engine.lua();
+    lua.load(LUA_PRELUDE).exec().map_err(lua_err)?;
+    lua.load(source).set_name(path.to_string_lossy()).exec().map_err(lua_err)?;
+
+    let defs: mlua::Table = lua.globals().get("__jobs").map_err(lua_err)?;
+    let mut jobs: Vec<Job> = Vec::new();
+    for (i, def) in defs.sequence_values::<mlua::Table>().enumerate() {
+        let def = def.map_err(lua_err)?;
+        let name: String =
+            def.get("name").map_err(|_| invalid("job needs a name".into()))?;
+        if jobs.iter().any(|job| job.name == name) {
+            return Err(invalid(format!("'{name}' is defined twice")));
+        }
+        let every: Option<String> = def
+            .get("every")
+            .map_err(|_| invalid(format!("'{name}': every must be a string")))?;
+        let Some(every) = every else {
+            return Err(invalid(format!("'{name}' has no every")));
+        };
+        let schedule = every.parse().map_err(|e| invalid(format!("'{name}': {e}")))?;
+        if !matches!(def.get::<mlua::Value>("run"), Ok(mlua::Value::Function(_))) {
+            return Err(invalid(format!("'{name}' has no run function")));
+        }
+        jobs.push(Job {
+            description: def.get("description").map_err(|_| {
+                invalid(format!("'{name}': description must be a string"))
+            })?,
+            permissions: Permissions::from_lua(def.get("permissions").map_err(lua_err)?)
+                .map_err(|e| invalid(format!("'{name}': {e}")))?,
+            name,
+            every,
+            schedule,
+            slot: i + 1,
+        });
+    }
+    Ok(jobs)
+}
+
+/// How last runs are written in `jobs.toml` and passed to `run`.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// What happened the last time a job ran.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JobRecord {
+    /// Local time of the last successful run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_run: Option<NaiveDateTime>,
+    /// The error of the last run, if it failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// Last runs of every job, kept in `.mdvault/state/jobs.toml`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JobState {
+    #[serde(default)]
+    pub jobs: BTreeMap<String, JobRecord>,
+}
+
+impl JobState {
+    /// Read the state file; a vault whose jobs never ran has an empty state.
+    pub fn load(vault_root: &Path) -> Result<Self, JobError> {
+        let path = PathResolver::new(vault_root).jobs_state();
+        match std::fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content)
+                .map_err(|e| JobError::State { path, message: e.to_string() }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(JobError::Io { path, source: e }),
+        }
+    }
+
+    pub fn save(&self, vault_root: &Path) -> Result<(), JobError> {
+        let path = PathResolver::new(vault_root).jobs_state();
+        let io_err = |e| JobError::Io { path: path.clone(), source: e };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(io_err)?;
+        }
+        let content = toml::to_string_pretty(self).map_err(|e| JobError::State {
+            path: path.clone(),
+            message: e.to_string(),
+        })?;
+        std::fs::write(&path, content).map_err(io_err)
+    }
+
+    pub fn last_run(&self, name: &str) -> Option<NaiveDateTime> {
+        self.jobs.get(name).and_then(|record| record.last_run)
+    }
+
+    /// Record a run at `at`. A failed run keeps the previous last run, so
+    /// the job is retried next time.
+    pub fn record(&mut self, name: &str, at: NaiveDateTime, error: Option<String>) {
+        let record = self.jobs.entry(name.to_string()).or_default();
+        if error.is_none() {
+            record.last_run = Some(at.with_nanosecond(0).unwrap_or(at));
+        }
+        record.last_error = error;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap()
+            .and_time(NaiveTime::parse_from_str(time, "%H:%M").unwrap())
+    }
+
+    #[test]
+    fn parses_schedules() {
+        let seven = NaiveTime::from_hms_opt(7, 0, 0).unwrap();
+        assert_eq!("hourly".parse(), Ok(Schedule::Hourly));
+        assert_eq!("daily 07:00".parse(), Ok(Schedule::Daily(seven)));
+        assert_eq!("Daily".parse(), Ok(Schedule::Daily(NaiveTime::MIN)));
+        assert_eq!("weekly mon 07:00".parse(), Ok(Schedule::Weekly(Weekday::Mon, seven)));
+        assert_eq!("30m".parse(), Ok(Schedule::Every(Duration::minutes(30))));
+        assert_eq!("2d".parse(), Ok(Schedule::Every(Duration::days(2))));
+        for bad in ["", "daily 7am", "weekly someday", "0h", "5y", "every day"] {
+            assert!(bad.parse::<Schedule>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn daily_jobs_catch_up_once() {
+        let daily: Schedule = "daily 07:00".parse().unwrap();
+        let now = at("2026-03-18", "09:00");
+        assert!(daily.is_due(None, now));
+        assert!(daily.is_due(Some(at("2026-03-15", "07:00")), now));
+        assert!(daily.is_due(Some(at("2026-03-18", "06:59")), now));
+        assert!(!daily.is_due(Some(at("2026-03-18", "07:00")), now));
+        // Before today's slot, yesterday's run counts
+        assert!(
+            !daily.is_due(Some(at("2026-03-17", "07:30")), at("2026-03-18", "06:00"))
+        );
+        assert_eq!(
+            daily.next_run(Some(at("2026-03-18", "07:00")), now),
+            at("2026-03-19", "07:00")
+        );
+    }
+
+    #[test]
+    fn weekly_and_interval_jobs() {
+        // 2026-03-18 is a Wednesday
+        let weekly: Schedule = "weekly mon 09:00".parse().unwrap();
+        let now = at("2026-03-18", "12:00");
+        assert!(weekly.is_due(Some(at("2026-03-15", "09:00")), now));
+        assert!(!weekly.is_due(Some(at("2026-03-16", "09:00")), now));
+        assert_eq!(
+            weekly.next_run(Some(at("2026-03-16", "09:00")), now),
+            at("2026-03-23", "09:00")
+        );
+
+        let every: Schedule = "6h".parse().unwrap();
+        assert!(every.is_due(Some(at("2026-03-18", "06:00")), now));
+        assert!(!every.is_due(Some(at("2026-03-18", "06:01")), now));
+        assert_eq!(
+            every.next_run(Some(at("2026-03-18", "08:00")), now),
+            at("2026-03-18", "14:00")
+        );
+    }
+
+    #[test]
+    fn failed_runs_are_retried() {
+        let mut state = JobState::default();
+        state.record("roll", at("2026-03-18", "07:00"), None);
+        state.record("roll", at("2026-03-19", "07:00"), Some("boom".into()));
+        assert_eq!(state.last_run("roll"), Some(at("2026-03-18", "07:00")));
+        assert_eq!(state.jobs["roll"].last_error.as_deref(), Some("boom"));
+
+        let text = toml::to_string_pretty(&state).unwrap();
+        let back: JobState = toml::from_str(&text).unwrap();
+        assert_eq!(back.last_run("roll"), state.last_run("roll"));
+    }
+}
@@ -15,22 +15,59 @@ pub fn doctor_stub() -> String {
     )
 }
 
+#[cfg(feature = "native")]
 pub mod activity;
+#[cfg(feature = "native")]
+pub mod api;
+#[cfg(feature = "native")]
 pub mod captures;
 pub mod config;
+#[cfg(feature = "native")]
 pub mod context;
+#[cfg(feature = "native")]
+pub mod demo;
+#[cfg(feature = "native")]
 pub mod domain;
+#[cfg(feature = "embeddings")]
+pub mod embeddings;
+pub mod error;
+#[cfg(feature = "native")]
+pub mod expire;
 pub mod frontmatter;
 pub mod ids;
 pub mod index;
+#[cfg(feature = "native")]
+pub mod ingest;
+#[cfg(feature = "native")]
+pub mod jobs;
+#[cfg(feature = "native")]
 pub mod lint;
+#[cfg(feature = "native")]
 pub mod macros;
 pub mod markdown_ast;
+#[cfg(feature = "native")]
+pub mod migrate;
 pub mod paths;
+#[cfg(feature = "native")]
+pub mod plugins;
+pub mod provenance;
+#[cfg(feature = "native")]
+pub mod query;
+#[cfg(feature = "native")]
 pub mod rename;
+#[cfg(feature = "native")]
 pub mod report;
+#[cfg(feature = "native")]
+pub mod saved_searches;
+#[cfg(feature = "native")]
 pub mod scripting;
 pub mod templates;
 pub mod types;
+#[cfg(feature = "native")]
+pub mod undo;
 pub mod vars;
 pub mod vault;
+#[cfg(feature = "native")]
+pub mod webhooks;
+
+pub use error::{Error, ErrorCode, Result};
@@ -5,7 +5,7 @@ use std::path::Path;
 
 use crate::frontmatter::parse as parse_frontmatter;
 use crate::index::{IndexBuilder, IndexDb, NoteQuery, NoteType};
-use crate::types::{TypeRegistry, validate_note};
+use crate::types::{TypeRegistry, validate_note_with_index};
 
 use super::result::{CategoryReport, LintIssue};
 
@@ -167,12 +167,13 @@ pub fn check_schema_violations(
             })
             .unwrap_or(serde_yaml::Value::Mapping(serde_yaml::Mapping::new()));
 
-        let result = validate_note(
+        let result = validate_note_with_index(
             registry,
             note_type,
             &full_path.to_string_lossy(),
             &frontmatter,
             &content,
+            db,
         );
 
         let path_str = note.path.to_string_lossy().to_string();
@@ -417,6 +418,8 @@ mod tests {
             modified: Utc::now(),
             frontmatter_json: fm_json.map(String::from),
             content_hash: format!("hash-{path}"),
+            word_count: 0,
+            heading_json: None,
         };
         db.insert_note(&note).unwrap()
     }
@@ -434,6 +437,7 @@ mod tests {
             source_id,
             target_id,
             target_path: target_path.to_string(),
+            target_anchor: None,
             link_text: link_text.map(String::from),
             link_type: LinkType::Wikilink,
             context: None,
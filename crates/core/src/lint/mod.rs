@@ -67,6 +67,16 @@ pub fn run_lint(
         categories.push(report);
     }
 
+    // Categories contributed by plugins
+    if let Some(plugins) = crate::plugins::active() {
+        categories.extend(
+            plugins
+                .lint(db)
+                .into_iter()
+                .filter(|r| category_filter.is_none_or(|cat| cat == r.name)),
+        );
+    }
+
     // Compute summary
     let total_errors: usize = categories.iter().map(|c| c.errors.len()).sum();
     let total_warnings: usize = categories.iter().map(|c| c.warnings.len()).sum();
@@ -128,6 +138,8 @@ mod tests {
             modified: Utc::now(),
             frontmatter_json: None,
             content_hash: format!("hash-{path}"),
+            word_count: 0,
+            heading_json: None,
         };
         db.insert_note(&note).unwrap()
     }
@@ -143,6 +155,7 @@ mod tests {
             source_id,
             target_id,
             target_path: target_path.to_string(),
+            target_anchor: None,
             link_text: None,
             link_type: LinkType::Wikilink,
             context: None,
@@ -106,8 +106,15 @@ fn extract_vars(table: &mlua::Table, path: &Path) -> Result<VarsMap, MacroRepoEr
                 let default: Option<String> = t.get("default").ok();
                 let required: Option<bool> = t.get("required").ok();
                 let description: Option<String> = t.get("description").ok();
-
-                VarSpec::Full(VarMetadata { prompt, description, required, default })
+                let options: Option<Vec<String>> = t.get("options").ok();
+
+                VarSpec::Full(VarMetadata {
+                    prompt,
+                    description,
+                    required,
+                    default,
+                    options,
+                })
             }
             _ => continue, // Skip invalid values
         };
@@ -0,0 +1,357 @@
+//! Schema migrations for type definitions.
+//!
+//! A type definition that changes its schema bumps `schema_version` and
+//! gives a `migrate(note, from_version)` function that brings a note's
+//! frontmatter up to date. Notes record the version they were written for
+//! in a `schema_version` field (new notes get the type's current version;
+//! notes without one are at version 1). [`plan_migrations`] runs `migrate`
+//! on every note of a type that is behind, and [`apply_migrations`] writes
+//! the results as one journaled operation, so the run can be undone (see
+//! [`crate::undo`]).
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::frontmatter::{Frontmatter, ParsedDocument, parse, serialize_with_order};
+use crate::scripting::run_migrate_hook;
+use crate::types::TypeDefinition;
+use crate::undo::{OperationRecord, Recorder, UndoError};
+use crate::vault::{VaultWalker, VaultWalkerError};
+
+/// Frontmatter field holding the schema version a note was written for.
+pub const VERSION_FIELD: &str = "schema_version";
+
+#[derive(Debug, Error)]
+pub enum MigrateError {
+    #[error("type '{0}' does not declare a schema_version")]
+    NoVersion(String),
+
+    #[error("type '{0}' has no migrate() function")]
+    NoMigrateFn(String),
+
+    #[error(transparent)]
+    Walk(#[from] VaultWalkerError),
+
+    #[error("failed to write {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error(transparent)]
+    Undo(#[from] UndoError),
+}
+
+/// A frontmatter field a migration adds, removes, or changes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldChange {
+    Added { field: String, value: serde_yaml::Value },
+    Removed { field: String, value: serde_yaml::Value },
+    Changed { field: String, from: serde_yaml::Value, to: serde_yaml::Value },
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Added { field, value } => write!(f, "+ {field}: {}", show(value)),
+            Self::Removed { field, value } => write!(f, "- {field}: {}", show(value)),
+            Self::Changed { field, from, to } => {
+                write!(f, "~ {field}: {} -> {}", show(from), show(to))
+            }
+        }
+    }
+}
+
+/// A value as it reads in frontmatter, on one line.
+fn show(value: &serde_yaml::Value) -> String {
+    match value {
+        serde_yaml::Value::String(s) => s.clone(),
+        other => serde_json::to_string(other).unwrap_or_default(),
+    }
+}
+
+/// A note brought up to the type's current schema version.
+#[derive(Debug, Clone)]
+pub struct NoteMigration {
+    /// Vault-relative path of the note.
+    pub path: PathBuf,
+    /// Version the note was written for.
+    pub from_version: u32,
+    /// What changed in its frontmatter, by field name.
+    pub changes: Vec<FieldChange>,
+    /// The note's new text.
+    pub content: String,
+}
+
+/// What migrating the notes of a type would do.
+#[derive(Debug, Clone)]
+pub struct MigrationPlan {
+    pub type_name: String,
+    /// The type's current schema version.
+    pub to_version: u32,
+    /// Notes behind the current version, in path order.
+    pub notes: Vec<NoteMigration>,
+    /// Notes of the type already at the current version.
+    pub current: usize,
+    /// Notes `migrate` failed on, with the error.
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// The schema version a note's frontmatter says it was written for.
+pub fn note_version(fields: &HashMap<String, serde_yaml::Value>) -> u32 {
+    match fields.get(VERSION_FIELD) {
+        Some(serde_yaml::Value::Number(n)) => n.as_u64().map_or(1, |v| v as u32),
+        Some(serde_yaml::Value::String(s)) => s.trim().parse().unwrap_or(1),
+        _ => 1,
+    }
+}
+
+/// Run `migrate` on every note of `typedef`'s type in the vault that is
+/// behind its schema version, without writing anything.
+pub fn plan_migrations(
+    vault_root: &Path,
+    excluded_folders: Vec<PathBuf>,
+    typedef: &TypeDefinition,
+) -> Result<MigrationPlan, MigrateError> {
+    let to_version = typedef
+        .schema_version
+        .ok_or_else(|| MigrateError::NoVersion(typedef.name.clone()))?;
+    if !typedef.has_migrate_fn {
+        return Err(MigrateError::NoMigrateFn(typedef.name.clone()));
+    }
+
+    let mut plan = MigrationPlan {
+        type_name: typedef.name.clone(),
+        to_version,
+        notes: Vec::new(),
+        current: 0,
+        failed: Vec::new(),
+    };
+    let mut files = VaultWalker::with_exclusions(vault_root, excluded_folders)?.walk()?;
+    files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+
+    for file in files {
+        let Ok(content) = fs::read_to_string(&file.absolute_path) else {
+            continue;
+        };
+        let Ok(parsed) = parse(&content) else {
+            continue;
+        };
+        let Some(fields) = parsed.frontmatter.map(|fm| fm.fields) else {
+            continue;
+        };
+        if fields.get("type").and_then(|t| t.as_str()) != Some(typedef.name.as_str()) {
+            continue;
+        }
+        let from_version = note_version(&fields);
+        if from_version >= to_version {
+            plan.current += 1;
+            continue;
+        }
+
+        let mut new_fields = match run_migrate_hook(
+            typedef,
+            &file.absolute_path,
+            &content,
+            &fields,
+            from_version,
+        ) {
+            Ok(new_fields) => new_fields,
+            Err(e) => {
+                plan.failed.push((file.relative_path, e.to_string()));
+                continue;
+            }
+        };
+        new_fields.insert(VERSION_FIELD.to_string(), to_version.into());
+
+        let changes = diff_fields(&fields, &new_fields);
+        let doc = ParsedDocument {
+            frontmatter: Some(Frontmatter { fields: new_fields }),
+            body: parsed.body,
+        };
+        plan.notes.push(NoteMigration {
+            path: file.relative_path,
+            from_version,
+            changes,
+            content: serialize_with_order(&doc, typedef.frontmatter_order.as_deref()),
+        });
+    }
+    Ok(plan)
+}
+
+/// The fields `migrate` added, removed, or changed, leaving out the
+/// version stamp.
+fn diff_fields(
+    old: &HashMap<String, serde_yaml::Value>,
+    new: &HashMap<String, serde_yaml::Value>,
+) -> Vec<FieldChange> {
+    let mut fields: Vec<&String> = old.keys().chain(new.keys()).collect();
+    fields.sort();
+    fields.dedup();
+    fields
+        .into_iter()
+        .filter(|field| *field != VERSION_FIELD)
+        .filter_map(|field| match (old.get(field), new.get(field)) {
+            (None, Some(value)) => {
+                Some(FieldChange::Added { field: field.clone(), value: value.clone() })
+            }
+            (Some(value), None) => {
+                Some(FieldChange::Removed { field: field.clone(), value: value.clone() })
+            }
+            (Some(from), Some(to)) if from != to => Some(FieldChange::Changed {
+                field: field.clone(),
+                from: from.clone(),
+                to: to.clone(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Write the migrated notes of `plan` as one journaled operation, which
+/// [`crate::undo::undo_last`] can revert. Returns the journal record, if
+/// anything was written.
+pub fn apply_migrations(
+    vault_root: &Path,
+    plan: &MigrationPlan,
+) -> Result<Option<OperationRecord>, MigrateError> {
+    let mut recorder = Recorder::new(vault_root);
+    let mut result = Ok(());
+    for note in &plan.notes {
+        let path = vault_root.join(&note.path);
+        recorder.before_write(&path)?;
+        if let Err(source) = fs::write(&path, &note.content) {
+            result = Err(MigrateError::Io { path, source });
+            break;
+        }
+    }
+    // Journal what was written even if a write failed, so it can be undone
+    let record = recorder.commit(&migration_label(&plan.type_name, plan.to_version))?;
+    result.map(|()| record)
+}
+
+/// Journal label of a migration run, e.g. `migrate 'task' to v2`.
+pub fn migration_label(type_name: &str, to_version: u32) -> String {
+    format!("migrate '{type_name}' to v{to_version}")
+}
+
+/// Whether a journaled operation is a migration run.
+pub fn is_migration(record: &OperationRecord) -> bool {
+    record.label.starts_with("migrate '")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::undo::undo_last;
+    use tempfile::TempDir;
+
+    const TASK: &str = r#"
+return {
+    schema_version = 3,
+    migrate = function(note, from)
+        local fm = note.frontmatter
+        if from < 2 then
+            fm.status = fm.state
+            fm.state = nil
+        end
+        if from < 3 and fm.priority == 1 then fm.priority = "high" end
+        return note
+    end,
+}
+"#;
+
+    fn typedef(source: &str) -> TypeDefinition {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("task.lua"), source).unwrap();
+        crate::types::discovery::load_typedef_from_file(&dir.path().join("task.lua"))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_note_version() {
+        let fields = |yaml: &str| -> HashMap<String, serde_yaml::Value> {
+            serde_yaml::from_str(yaml).unwrap()
+        };
+        assert_eq!(note_version(&fields("type: task")), 1);
+        assert_eq!(note_version(&fields("schema_version: 3")), 3);
+        assert_eq!(note_version(&fields("schema_version: '2'")), 2);
+    }
+
+    #[test]
+    fn migrates_notes_behind_the_schema_version() {
+        let vault = TempDir::new().unwrap();
+        let write = |name: &str, content: &str| {
+            std::fs::write(vault.path().join(name), content).unwrap();
+        };
+        write("a.md", "---\ntype: task\nstate: doing\npriority: 1\n---\n# A\n");
+        write(
+            "b.md",
+            "---\ntype: task\nschema_version: 2\nstatus: todo\npriority: 1\n---\n",
+        );
+        write("c.md", "---\ntype: task\nschema_version: 3\nstatus: done\n---\n");
+        write("d.md", "---\ntype: zettel\nstate: doing\n---\n");
+
+        let td = typedef(TASK);
+        let plan = plan_migrations(vault.path(), Vec::new(), &td).unwrap();
+        assert_eq!(plan.to_version, 3);
+        assert_eq!(plan.current, 1);
+        assert!(plan.failed.is_empty());
+        assert_eq!(plan.notes.len(), 2);
+
+        let a = &plan.notes[0];
+        assert_eq!(a.path, Path::new("a.md"));
+        assert_eq!(a.from_version, 1);
+        let changes: Vec<String> = a.changes.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            changes,
+            ["~ priority: 1 -> high", "- state: doing", "+ status: doing"]
+        );
+        assert!(a.content.contains("schema_version: 3"));
+        assert!(a.content.ends_with("# A\n"));
+        assert_eq!(plan.notes[1].changes.len(), 1);
+
+        let original = std::fs::read_to_string(vault.path().join("a.md")).unwrap();
+        let record = apply_migrations(vault.path(), &plan).unwrap().unwrap();
+        assert!(is_migration(&record));
+        assert_eq!(record.files.len(), 2);
+        let migrated = std::fs::read_to_string(vault.path().join("a.md")).unwrap();
+        assert!(migrated.contains("status: doing"));
+
+        // Nothing is left to do, and the run can be undone
+        let again = plan_migrations(vault.path(), Vec::new(), &td).unwrap();
+        assert!(again.notes.is_empty());
+        assert_eq!(again.current, 3);
+        undo_last(vault.path()).unwrap();
+        assert_eq!(std::fs::read_to_string(vault.path().join("a.md")).unwrap(), original);
+    }
+
+    #[test]
+    fn reports_failures_and_missing_setup() {
+        let vault = TempDir::new().unwrap();
+        std::fs::write(vault.path().join("a.md"), "---\ntype: task\n---\n").unwrap();
+
+        let broken = typedef(
+            r#"return { schema_version = 2, migrate = function(note, from) error("boom") end }"#,
+        );
+        let plan = plan_migrations(vault.path(), Vec::new(), &broken).unwrap();
+        assert!(plan.notes.is_empty());
+        assert!(plan.failed[0].1.contains("boom"));
+
+        let unversioned = typedef("return { migrate = function(note) return note end }");
+        assert!(matches!(
+            plan_migrations(vault.path(), Vec::new(), &unversioned),
+            Err(MigrateError::NoVersion(_))
+        ));
+        let no_fn = typedef("return { schema_version = 2 }");
+        assert!(matches!(
+            plan_migrations(vault.path(), Vec::new(), &no_fn),
+            Err(MigrateError::NoMigrateFn(_))
+        ));
+    }
+}
@@ -24,6 +24,16 @@ impl<'a> PathResolver<'a> {
         self.vault_root.join(format!("Inbox/{id}.md"))
     }
 
+    /// `Inbox/{id}.md` — transcribed voice memo awaiting triage.
+    pub fn voice_memo(&self, id: &str) -> PathBuf {
+        self.vault_root.join(format!("Inbox/{id}.md"))
+    }
+
+    /// `Inbox/{id}.md` — text snippet awaiting triage.
+    pub fn inbox_snippet(&self, id: &str) -> PathBuf {
+        self.vault_root.join(format!("Inbox/{id}.md"))
+    }
+
     /// `Projects/{project}/Tasks/{id}.md`
     pub fn project_task(&self, project: &str, id: &str) -> PathBuf {
         self.vault_root.join(format!("Projects/{project}/Tasks/{id}.md"))
@@ -72,6 +82,11 @@ impl<'a> PathResolver<'a> {
         self.vault_root.join(format!("{type_name}s/{slug}.md"))
     }
 
+    /// `Archive/{rel}` — expired notes keep their vault-relative layout.
+    pub fn archived_note(&self, rel: &Path) -> PathBuf {
+        self.vault_root.join("Archive").join(rel)
+    }
+
     // ── Meetings directory ───────────────────────────────────────────────
 
     /// `Meetings/{year}` — for scanning existing meeting IDs.
@@ -79,6 +94,13 @@ impl<'a> PathResolver<'a> {
         self.vault_root.join(format!("Meetings/{year}"))
     }
 
+    // ── Assets ───────────────────────────────────────────────────────────
+
+    /// `assets/audio/{year}` — archived voice memo recordings.
+    pub fn audio_dir(&self, year: &str) -> PathBuf {
+        self.vault_root.join(format!("assets/audio/{year}"))
+    }
+
     // ── System paths ─────────────────────────────────────────────────────
 
     /// `.mdvault/index.db`
@@ -86,6 +108,36 @@ impl<'a> PathResolver<'a> {
         self.vault_root.join(".mdvault/index.db")
     }
 
+    /// `.mdvault/schemas` — JSON Schemas written by `mdv types export`.
+    pub fn schemas_dir(&self) -> PathBuf {
+        self.vault_root.join(".mdvault/schemas")
+    }
+
+    /// `.mdvault/searches.lua`
+    pub fn saved_searches(&self) -> PathBuf {
+        self.vault_root.join(".mdvault/searches.lua")
+    }
+
+    /// `.mdvault/filters.lua` — template filters written in Lua.
+    pub fn template_filters(&self) -> PathBuf {
+        self.vault_root.join(".mdvault/filters.lua")
+    }
+
+    /// `.mdvignore` — gitignore-style patterns the vault walker skips.
+    pub fn ignore_file(&self) -> PathBuf {
+        self.vault_root.join(".mdvignore")
+    }
+
+    /// `.mdvault/ranking.lua`
+    pub fn ranking(&self) -> PathBuf {
+        self.vault_root.join(".mdvault/ranking.lua")
+    }
+
+    /// `.mdvault/staleness.lua`
+    pub fn staleness(&self) -> PathBuf {
+        self.vault_root.join(".mdvault/staleness.lua")
+    }
+
     /// `.mdvault/state`
     pub fn state_dir(&self) -> PathBuf {
         self.vault_root.join(".mdvault/state")
@@ -96,16 +148,68 @@ impl<'a> PathResolver<'a> {
         self.vault_root.join(".mdvault/state/context.toml")
     }
 
+    /// `.mdvault/state/tui.toml` — layout and filters the TUI restores.
+    pub fn tui_state(&self) -> PathBuf {
+        self.vault_root.join(".mdvault/state/tui.toml")
+    }
+
     /// `.mdvault/activity.jsonl`
     pub fn activity_log(&self) -> PathBuf {
         self.vault_root.join(".mdvault/activity.jsonl")
     }
 
+    /// `.mdvault/api-audit.jsonl` — write attempts made through `mdv serve`.
+    pub fn api_audit_log(&self) -> PathBuf {
+        self.vault_root.join(".mdvault/api-audit.jsonl")
+    }
+
     /// `.mdvault/activity_archive`
     pub fn activity_archive_dir(&self) -> PathBuf {
         self.vault_root.join(".mdvault/activity_archive")
     }
 
+    /// `.mdvault/trash/{run}` — notes trashed by one `mdv expire` run.
+    pub fn trash_dir(&self, run: &str) -> PathBuf {
+        self.vault_root.join(format!(".mdvault/trash/{run}"))
+    }
+
+    /// `.mdvault/expire.jsonl` — journal of moves made by `mdv expire`.
+    pub fn expire_journal(&self) -> PathBuf {
+        self.vault_root.join(".mdvault/expire.jsonl")
+    }
+
+    /// `.mdvault/undo.jsonl` — journal of files written by recent
+    /// operations, for undoing the last one.
+    pub fn undo_journal(&self) -> PathBuf {
+        self.vault_root.join(".mdvault/undo.jsonl")
+    }
+
+    /// `.mdvault/undo/{id}` — what the files of one operation held before.
+    pub fn undo_dir(&self, id: &str) -> PathBuf {
+        self.vault_root.join(format!(".mdvault/undo/{id}"))
+    }
+
+    /// `.mdvault/inbox.jsonl` — hashes of snippets already filed by
+    /// `mdv inbox ingest`.
+    pub fn inbox_ledger(&self) -> PathBuf {
+        self.vault_root.join(".mdvault/inbox.jsonl")
+    }
+
+    /// `.mdvault/jobs.lua` — scheduled jobs for `mdv jobs`.
+    pub fn jobs(&self) -> PathBuf {
+        self.vault_root.join(".mdvault/jobs.lua")
+    }
+
+    /// `.mdvault/state/jobs.toml` — when each job last ran.
+    pub fn jobs_state(&self) -> PathBuf {
+        self.vault_root.join(".mdvault/state/jobs.toml")
+    }
+
+    /// `.mdvault/state/lua_history` — input history of `mdv lua`.
+    pub fn lua_history(&self) -> PathBuf {
+        self.vault_root.join(".mdvault/state/lua_history")
+    }
+
     // ── Path predicates ──────────────────────────────────────────────────
 
     /// Check whether a task path belongs to a given project folder.
@@ -219,6 +323,7 @@ mod tests {
             resolver().state_file(),
             Path::new("/vault/.mdvault/state/context.toml")
         );
+        assert_eq!(resolver().tui_state(), Path::new("/vault/.mdvault/state/tui.toml"));
     }
 
     #[test]
@@ -231,6 +336,35 @@ mod tests {
             resolver().activity_archive_dir(),
             Path::new("/vault/.mdvault/activity_archive")
         );
+        assert_eq!(
+            resolver().api_audit_log(),
+            Path::new("/vault/.mdvault/api-audit.jsonl")
+        );
+    }
+
+    #[test]
+    fn expire_paths() {
+        assert_eq!(
+            resolver().archived_note(Path::new("Meetings/2024/MTG-1.md")),
+            Path::new("/vault/Archive/Meetings/2024/MTG-1.md")
+        );
+        assert_eq!(
+            resolver().trash_dir("20250115-093000"),
+            Path::new("/vault/.mdvault/trash/20250115-093000")
+        );
+        assert_eq!(
+            resolver().expire_journal(),
+            Path::new("/vault/.mdvault/expire.jsonl")
+        );
+    }
+
+    #[test]
+    fn undo_paths() {
+        assert_eq!(resolver().undo_journal(), Path::new("/vault/.mdvault/undo.jsonl"));
+        assert_eq!(
+            resolver().undo_dir("20250115-093000.123456"),
+            Path::new("/vault/.mdvault/undo/20250115-093000.123456")
+        );
     }
 
     #[test]
@@ -0,0 +1,524 @@
+//! Rust-native plugins.
+//!
+//! Lua covers small per-vault customisation. Heavier extensions (semantic
+//! search, an LSP, publishers) live in their own crates, implement
+//! [`VaultPlugin`], and register themselves with [`register_plugin!`]:
+//!
+//! ```ignore
+//! use mdvault_core::index::IndexedNote;
+//! use mdvault_core::plugins::VaultPlugin;
+//! use serde_json::{Map, Value};
+//!
+//! #[derive(Default)]
+//! struct WordStats;
+//!
+//! impl VaultPlugin for WordStats {
+//!     fn name(&self) -> &'static str {
+//!         "wordstats"
+//!     }
+//!
+//!     fn enrich(&self, _note: &IndexedNote, body: &str) -> Map<String, Value> {
+//!         let mut fields = Map::new();
+//!         fields.insert("chars".into(), body.chars().count().into());
+//!         fields
+//!     }
+//! }
+//!
+//! mdvault_core::register_plugin!(WordStats::default());
+//! ```
+//!
+//! A plugin is part of a build when the binary links its crate, which the
+//! CLI does behind a cargo feature per plugin. At startup the registry
+//! constructs every registered plugin, skips those disabled in config, and
+//! hands the rest their `[plugins.<name>]` settings through
+//! [`VaultPlugin::init`].
+//!
+//! Plugins can contribute:
+//! - commands, run with `mdv plugin run <command>`
+//! - index enrichers, whose fields are stored as `<plugin>.<key>` and can be
+//!   queried like frontmatter
+//! - lint rules, reported by `mdv check` as extra categories
+//! - exporters, run with `mdv plugin export <exporter>`
+
+use std::collections::HashSet;
+use std::io::Write;
+use std::sync::OnceLock;
+
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::config::types::ResolvedConfig;
+use crate::index::{IndexDb, IndexedNote};
+use crate::lint::CategoryReport;
+
+#[doc(hidden)]
+pub use inventory as __inventory;
+
+/// Result type for plugin hooks.
+pub type PluginResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Errors raised while loading or running plugins.
+#[derive(Debug, Error)]
+pub enum PluginError {
+    #[error("Plugin '{plugin}' failed to initialise: {message}")]
+    Init { plugin: String, message: String },
+
+    #[error("Two plugins are named '{0}'")]
+    DuplicateName(String),
+
+    #[error("No plugin provides command '{0}'")]
+    UnknownCommand(String),
+
+    #[error("No plugin provides exporter '{0}'")]
+    UnknownExporter(String),
+
+    #[error("Plugin '{plugin}' failed: {message}")]
+    Failed { plugin: String, message: String },
+}
+
+/// What a plugin sees of the host: the resolved config and its own settings.
+pub struct PluginContext<'a> {
+    pub config: &'a ResolvedConfig,
+    /// The `[plugins.<name>]` table, empty when absent.
+    pub settings: &'a toml::Table,
+}
+
+/// A subcommand or exporter offered by a plugin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginItem {
+    pub name: &'static str,
+    /// One-line description shown by `mdv plugin list`.
+    pub about: &'static str,
+}
+
+/// A Rust-native extension. Every hook has a no-op default, so a plugin
+/// only implements what it contributes.
+pub trait VaultPlugin: Send + Sync {
+    /// Unique name; also the config table and enriched-field prefix.
+    fn name(&self) -> &'static str;
+
+    /// Plugin version, usually `env!("CARGO_PKG_VERSION")`.
+    fn version(&self) -> &'static str {
+        "0.0.0"
+    }
+
+    /// Called once before any other hook.
+    fn init(&mut self, _ctx: &PluginContext<'_>) -> PluginResult<()> {
+        Ok(())
+    }
+
+    /// Commands this plugin provides.
+    fn commands(&self) -> Vec<PluginItem> {
+        Vec::new()
+    }
+
+    /// Run one of [`commands`](Self::commands).
+    fn run_command(
+        &self,
+        _ctx: &PluginContext<'_>,
+        command: &str,
+        _args: &[String],
+    ) -> PluginResult<()> {
+        Err(format!("unknown command '{command}'").into())
+    }
+
+    /// Extra fields for a note, computed while it is indexed.
+    fn enrich(&self, _note: &IndexedNote, _body: &str) -> Map<String, Value> {
+        Map::new()
+    }
+
+    /// Lint categories to add to `mdv check`.
+    fn lint(
+        &self,
+        _ctx: &PluginContext<'_>,
+        _db: &IndexDb,
+    ) -> PluginResult<Vec<CategoryReport>> {
+        Ok(Vec::new())
+    }
+
+    /// Exporters this plugin provides.
+    fn exporters(&self) -> Vec<PluginItem> {
+        Vec::new()
+    }
+
+    /// Run one of [`exporters`](Self::exporters) over `notes`.
+    fn export(
+        &self,
+        _ctx: &PluginContext<'_>,
+        exporter: &str,
+        _notes: &[IndexedNote],
+        _out: &mut dyn Write,
+    ) -> PluginResult<()> {
+        Err(format!("unknown exporter '{exporter}'").into())
+    }
+}
+
+/// A compiled-in plugin, collected at link time. Created by
+/// [`register_plugin!`].
+pub struct PluginRegistration {
+    constructor: fn() -> Box<dyn VaultPlugin>,
+}
+
+impl PluginRegistration {
+    pub const fn new(constructor: fn() -> Box<dyn VaultPlugin>) -> Self {
+        Self { constructor }
+    }
+}
+
+inventory::collect!(PluginRegistration);
+
+/// Register a plugin so the host picks it up when the crate is linked.
+///
+/// Takes an expression constructing the plugin, evaluated once per
+/// registry load.
+#[macro_export]
+macro_rules! register_plugin {
+    ($constructor:expr) => {
+        $crate::plugins::__inventory::submit! {
+            $crate::plugins::PluginRegistration::new(|| ::std::boxed::Box::new($constructor))
+        }
+    };
+}
+
+/// The initialised plugins for a vault.
+pub struct PluginRegistry {
+    config: ResolvedConfig,
+    plugins: Vec<Box<dyn VaultPlugin>>,
+    empty: toml::Table,
+}
+
+impl PluginRegistry {
+    /// Construct and initialise every registered plugin that isn't disabled
+    /// with `enabled = false` in its config table.
+    pub fn load(config: &ResolvedConfig) -> Result<Self, PluginError> {
+        let plugins = inventory::iter::<PluginRegistration>
+            .into_iter()
+            .map(|r| (r.constructor)())
+            .collect();
+        Self::with_plugins(config, plugins)
+    }
+
+    /// Initialise the given plugins (used by [`load`](Self::load) and tests).
+    pub fn with_plugins(
+        config: &ResolvedConfig,
+        plugins: Vec<Box<dyn VaultPlugin>>,
+    ) -> Result<Self, PluginError> {
+        let mut registry = Self {
+            config: config.clone(),
+            plugins: Vec::new(),
+            empty: toml::Table::new(),
+        };
+        let mut seen = HashSet::new();
+        for mut plugin in plugins {
+            let name = plugin.name();
+            if !seen.insert(name) {
+                return Err(PluginError::DuplicateName(name.to_string()));
+            }
+            let settings = registry.config.plugins.get(name).unwrap_or(&registry.empty);
+            if settings.get("enabled").and_then(toml::Value::as_bool) == Some(false) {
+                continue;
+            }
+            let ctx = PluginContext { config: &registry.config, settings };
+            plugin.init(&ctx).map_err(|e| PluginError::Init {
+                plugin: name.to_string(),
+                message: e.to_string(),
+            })?;
+            registry.plugins.push(plugin);
+        }
+        Ok(registry)
+    }
+
+    /// Loaded plugins, in registration order.
+    pub fn plugins(&self) -> impl Iterator<Item = &dyn VaultPlugin> {
+        self.plugins.iter().map(|p| p.as_ref())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    /// Run a plugin command by name.
+    pub fn run_command(&self, command: &str, args: &[String]) -> Result<(), PluginError> {
+        let plugin = self
+            .find(|p| p.commands().iter().any(|c| c.name == command))
+            .ok_or_else(|| PluginError::UnknownCommand(command.to_string()))?;
+        plugin
+            .run_command(&self.context(plugin), command, args)
+            .map_err(|e| failed(plugin, e))
+    }
+
+    /// Run a plugin exporter by name.
+    pub fn export(
+        &self,
+        exporter: &str,
+        notes: &[IndexedNote],
+        out: &mut dyn Write,
+    ) -> Result<(), PluginError> {
+        let plugin = self
+            .find(|p| p.exporters().iter().any(|e| e.name == exporter))
+            .ok_or_else(|| PluginError::UnknownExporter(exporter.to_string()))?;
+        plugin
+            .export(&self.context(plugin), exporter, notes, out)
+            .map_err(|e| failed(plugin, e))
+    }
+
+    /// Fields from every enricher, keyed by plugin name. Plugins that add
+    /// nothing are left out.
+    pub fn enrich(&self, note: &IndexedNote, body: &str) -> Vec<(&'static str, Value)> {
+        self.plugins
+            .iter()
+            .filter_map(|p| {
+                let fields = p.enrich(note, body);
+                (!fields.is_empty()).then(|| (p.name(), Value::Object(fields)))
+            })
+            .collect()
+    }
+
+    /// Lint categories from every plugin. A failing rule is reported as an
+    /// error in a category named after the plugin rather than aborting the
+    /// whole check.
+    pub fn lint(&self, db: &IndexDb) -> Vec<CategoryReport> {
+        let mut reports = Vec::new();
+        for plugin in &self.plugins {
+            match plugin.lint(&self.context(plugin.as_ref()), db) {
+                Ok(found) => reports.extend(found),
+                Err(e) => {
+                    let mut report = CategoryReport::new(
+                        plugin.name(),
+                        format!("Plugin: {}", plugin.name()),
+                    );
+                    report.errors.push(crate::lint::LintIssue {
+                        path: String::new(),
+                        line: None,
+                        message: format!("lint rule failed: {e}"),
+                        suggestion: None,
+                        fixable: false,
+                    });
+                    reports.push(report);
+                }
+            }
+        }
+        reports
+    }
+
+    fn find(&self, pred: impl Fn(&dyn VaultPlugin) -> bool) -> Option<&dyn VaultPlugin> {
+        self.plugins.iter().map(|p| p.as_ref()).find(|p| pred(*p))
+    }
+
+    fn context<'a>(&'a self, plugin: &dyn VaultPlugin) -> PluginContext<'a> {
+        let settings = self.config.plugins.get(plugin.name()).unwrap_or(&self.empty);
+        PluginContext { config: &self.config, settings }
+    }
+}
+
+fn failed(
+    plugin: &dyn VaultPlugin,
+    e: Box<dyn std::error::Error + Send + Sync>,
+) -> PluginError {
+    PluginError::Failed { plugin: plugin.name().to_string(), message: e.to_string() }
+}
+
+static ACTIVE: OnceLock<PluginRegistry> = OnceLock::new();
+
+/// Load the registered plugins for this process. The first successful call
+/// wins; later calls return the same registry.
+pub fn activate(config: &ResolvedConfig) -> Result<&'static PluginRegistry, PluginError> {
+    if let Some(registry) = ACTIVE.get() {
+        return Ok(registry);
+    }
+    let registry = PluginRegistry::load(config)?;
+    Ok(ACTIVE.get_or_init(|| registry))
+}
+
+/// The process-wide registry, once [`activate`] has run. Indexing and lint
+/// consult it so every command sees the same plugins.
+pub fn active() -> Option<&'static PluginRegistry> {
+    ACTIVE.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn config(plugins: &str) -> ResolvedConfig {
+        let mut cfg = ResolvedConfig {
+            active_profile: "test".into(),
+            vault_root: PathBuf::from("/vault"),
+            templates_dir: PathBuf::new(),
+            captures_dir: PathBuf::new(),
+            macros_dir: PathBuf::new(),
+            typedefs_dir: PathBuf::new(),
+            typedefs_fallback_dir: None,
+            commands_dir: PathBuf::new(),
+            hooks_dir: PathBuf::new(),
+            formatters_dir: PathBuf::new(),
+            excluded_folders: Vec::new(),
+            security: Default::default(),
+            logging: Default::default(),
+            activity: Default::default(),
+            display: Default::default(),
+            ingest: Default::default(),
+            capture: Default::default(),
+            quota: Default::default(),
+            webhooks: Default::default(),
+            index: Default::default(),
+            plugins: Default::default(),
+            searches: Default::default(),
+            embeddings: Default::default(),
+            staleness: Default::default(),
+            orphans: Default::default(),
+            tui: Default::default(),
+            serve: Default::default(),
+        };
+        let table: toml::Table = toml::from_str(plugins).unwrap();
+        for (name, value) in table {
+            cfg.plugins.insert(name, value.as_table().unwrap().clone());
+        }
+        cfg
+    }
+
+    #[derive(Default)]
+    struct Echo {
+        greeting: String,
+    }
+
+    impl VaultPlugin for Echo {
+        fn name(&self) -> &'static str {
+            "echo"
+        }
+
+        fn init(&mut self, ctx: &PluginContext<'_>) -> PluginResult<()> {
+            match ctx.settings.get("greeting") {
+                Some(toml::Value::String(g)) => self.greeting = g.clone(),
+                Some(_) => return Err("greeting must be a string".into()),
+                None => self.greeting = "hello".into(),
+            }
+            Ok(())
+        }
+
+        fn commands(&self) -> Vec<PluginItem> {
+            vec![PluginItem { name: "echo", about: "Print arguments" }]
+        }
+
+        fn run_command(
+            &self,
+            _ctx: &PluginContext<'_>,
+            _command: &str,
+            args: &[String],
+        ) -> PluginResult<()> {
+            if args.is_empty() { Err("nothing to echo".into()) } else { Ok(()) }
+        }
+
+        fn enrich(&self, note: &IndexedNote, body: &str) -> Map<String, Value> {
+            let mut fields = Map::new();
+            fields.insert("greeting".into(), self.greeting.clone().into());
+            fields.insert("title_len".into(), note.title.len().into());
+            fields.insert("lines".into(), body.lines().count().into());
+            fields
+        }
+
+        fn exporters(&self) -> Vec<PluginItem> {
+            vec![PluginItem { name: "titles", about: "One title per line" }]
+        }
+
+        fn export(
+            &self,
+            _ctx: &PluginContext<'_>,
+            _exporter: &str,
+            notes: &[IndexedNote],
+            out: &mut dyn Write,
+        ) -> PluginResult<()> {
+            for note in notes {
+                writeln!(out, "{}", note.title)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn note(title: &str) -> IndexedNote {
+        IndexedNote {
+            id: None,
+            path: PathBuf::from("a.md"),
+            note_type: crate::index::NoteType::Zettel,
+            title: title.into(),
+            created: None,
+            modified: chrono::Utc::now(),
+            frontmatter_json: None,
+            content_hash: String::new(),
+            word_count: 0,
+            heading_json: None,
+        }
+    }
+
+    #[test]
+    fn init_receives_plugin_settings() {
+        let registry = PluginRegistry::with_plugins(
+            &config("[echo]\ngreeting = \"hi\"\n"),
+            vec![Box::new(Echo::default())],
+        )
+        .unwrap();
+        let fields = registry.enrich(&note("Title"), "one\ntwo\n");
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].0, "echo");
+        assert_eq!(fields[0].1["greeting"], "hi");
+        assert_eq!(fields[0].1["lines"], 2);
+    }
+
+    #[test]
+    fn disabled_plugins_are_skipped() {
+        let registry = PluginRegistry::with_plugins(
+            &config("[echo]\nenabled = false\n"),
+            vec![Box::new(Echo::default())],
+        )
+        .unwrap();
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn init_errors_name_the_plugin() {
+        let err = PluginRegistry::with_plugins(
+            &config("[echo]\ngreeting = 3\n"),
+            vec![Box::new(Echo::default())],
+        )
+        .err()
+        .unwrap();
+        assert!(matches!(err, PluginError::Init { ref plugin, .. } if plugin == "echo"));
+    }
+
+    #[test]
+    fn duplicate_names_are_rejected() {
+        let err = PluginRegistry::with_plugins(
+            &config(""),
+            vec![Box::new(Echo::default()), Box::new(Echo::default())],
+        )
+        .err()
+        .unwrap();
+        assert!(matches!(err, PluginError::DuplicateName(_)));
+    }
+
+    #[test]
+    fn commands_and_exporters_dispatch_by_name() {
+        let registry =
+            PluginRegistry::with_plugins(&config(""), vec![Box::new(Echo::default())])
+                .unwrap();
+
+        assert!(registry.run_command("echo", &["x".into()]).is_ok());
+        assert!(matches!(
+            registry.run_command("echo", &[]),
+            Err(PluginError::Failed { .. })
+        ));
+        assert!(matches!(
+            registry.run_command("nope", &[]),
+            Err(PluginError::UnknownCommand(_))
+        ));
+
+        let mut out = Vec::new();
+        registry.export("titles", &[note("A"), note("B")], &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "A\nB\n");
+        assert!(matches!(
+            registry.export("pdf", &[], &mut Vec::new()),
+            Err(PluginError::UnknownExporter(_))
+        ));
+    }
+}
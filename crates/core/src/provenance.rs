@@ -0,0 +1,293 @@
+//! Provenance markers for generated note content.
+//!
+//! Commands that write generated blocks into notes (report notes, weekly
+//! review summaries) wrap each block in a pair of HTML comments recording
+//! which generator produced it, from what source, and when:
+//!
+//! ```text
+//! <!-- mdv:generated generator="report" version="0.3.0" source="mdv report --month 2026-09" at="2026-10-01T09:00:00Z" -->
+//! # Monthly Report: September 2026
+//! ...
+//! <!-- /mdv:generated -->
+//! ```
+//!
+//! HTML comments do not render in markdown previews, so the markers stay out
+//! of the way while letting `mdv provenance` find, regenerate or strip the
+//! generated regions later. Markers inside fenced code blocks are ignored.
+
+use std::ops::Range;
+
+use serde::Serialize;
+
+/// Prefix of the opening marker line.
+pub const START_MARKER: &str = "<!-- mdv:generated";
+
+/// The closing marker line.
+pub const END_MARKER: &str = "<!-- /mdv:generated -->";
+
+/// Who generated a block, from what, and when.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Provenance {
+    /// Generator name, e.g. `report` or `review`.
+    pub generator: String,
+    /// mdvault version that wrote the block.
+    pub version: String,
+    /// The source query or command the block was generated from.
+    pub source: String,
+    /// RFC 3339 timestamp of generation.
+    pub generated_at: String,
+}
+
+impl Provenance {
+    /// Provenance stamped with the running mdvault version.
+    pub fn new(
+        generator: impl Into<String>,
+        source: impl Into<String>,
+        generated_at: impl Into<String>,
+    ) -> Self {
+        Self {
+            generator: generator.into(),
+            version: crate::version().to_string(),
+            source: source.into(),
+            generated_at: generated_at.into(),
+        }
+    }
+
+    /// Render the opening marker line (without trailing newline).
+    pub fn marker(&self) -> String {
+        format!(
+            "{START_MARKER} generator=\"{}\" version=\"{}\" source=\"{}\" at=\"{}\" -->",
+            sanitize(&self.generator),
+            sanitize(&self.version),
+            sanitize(&self.source),
+            sanitize(&self.generated_at),
+        )
+    }
+
+    /// Parse an opening marker line. Returns `None` for anything else.
+    pub fn parse_marker(line: &str) -> Option<Self> {
+        let rest = line.trim().strip_prefix(START_MARKER)?.strip_suffix("-->")?;
+        if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let attrs = parse_attrs(rest);
+        let get = |key: &str| {
+            attrs
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.clone())
+                .unwrap_or_default()
+        };
+        let generator = get("generator");
+        if generator.is_empty() {
+            return None;
+        }
+        Some(Self {
+            generator,
+            version: get("version"),
+            source: get("source"),
+            generated_at: get("at"),
+        })
+    }
+}
+
+/// A generated block found in a note.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GeneratedRegion {
+    /// Provenance from the opening marker.
+    #[serde(flatten)]
+    pub provenance: Provenance,
+    /// 1-based line of the opening marker.
+    pub start_line: usize,
+    /// 1-based line of the closing marker.
+    pub end_line: usize,
+    /// Content between the markers.
+    #[serde(skip)]
+    pub body: String,
+    /// Byte range of the whole block, markers included.
+    #[serde(skip)]
+    pub span: Range<usize>,
+}
+
+/// Wrap `body` in provenance markers.
+pub fn wrap(provenance: &Provenance, body: &str) -> String {
+    let body = body.trim_end_matches('\n');
+    if body.is_empty() {
+        format!("{}\n{END_MARKER}\n", provenance.marker())
+    } else {
+        format!("{}\n{body}\n{END_MARKER}\n", provenance.marker())
+    }
+}
+
+/// Find all generated regions in `content`, in document order.
+///
+/// An opening marker without a matching close is ignored, as is an opening
+/// marker encountered while another region is still open.
+pub fn find_regions(content: &str) -> Vec<GeneratedRegion> {
+    let mut regions = Vec::new();
+    let mut open: Option<(Provenance, usize, usize, usize)> = None;
+    let mut in_fence = false;
+    let mut offset = 0;
+
+    for (idx, line) in content.split_inclusive('\n').enumerate() {
+        let line_start = offset;
+        offset += line.len();
+        let trimmed = line.trim();
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        match &open {
+            None => {
+                if let Some(p) = Provenance::parse_marker(trimmed) {
+                    open = Some((p, idx + 1, line_start, offset));
+                }
+            }
+            Some(_) if trimmed == END_MARKER => {
+                let (provenance, start_line, start, body_start) = open.take().unwrap();
+                regions.push(GeneratedRegion {
+                    provenance,
+                    start_line,
+                    end_line: idx + 1,
+                    body: content[body_start..line_start].to_string(),
+                    span: start..offset,
+                });
+            }
+            Some(_) => {}
+        }
+    }
+
+    regions
+}
+
+/// Remove a region, markers and content, from `content`.
+pub fn strip_region(content: &str, region: &GeneratedRegion) -> String {
+    format!("{}{}", &content[..region.span.start], &content[region.span.end..])
+}
+
+/// Replace a region's content and provenance, keeping its position.
+pub fn replace_region(
+    content: &str,
+    region: &GeneratedRegion,
+    provenance: &Provenance,
+    body: &str,
+) -> String {
+    let mut block = wrap(provenance, body);
+    if !content[..region.span.end].ends_with('\n') {
+        block.pop();
+    }
+    format!("{}{}{}", &content[..region.span.start], block, &content[region.span.end..])
+}
+
+/// Keep attribute values on one line and inside their quotes.
+fn sanitize(value: &str) -> String {
+    value.replace('"', "'").replace(['\n', '\r'], " ").replace("-->", "->")
+}
+
+/// Parse `key="value"` / `key=value` pairs.
+fn parse_attrs(s: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut rest = s.trim_start();
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim().to_string();
+        let after = &rest[eq + 1..];
+        let (value, remaining) = if let Some(quoted) = after.strip_prefix('"') {
+            match quoted.find('"') {
+                Some(end) => (&quoted[..end], &quoted[end + 1..]),
+                None => (quoted, ""),
+            }
+        } else {
+            let end = after.find(char::is_whitespace).unwrap_or(after.len());
+            (&after[..end], &after[end..])
+        };
+        attrs.push((key, value.to_string()));
+        rest = remaining.trim_start();
+    }
+    attrs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prov(source: &str) -> Provenance {
+        Provenance {
+            generator: "report".into(),
+            version: "1.2.3".into(),
+            source: source.into(),
+            generated_at: "2026-10-01T09:00:00Z".into(),
+        }
+    }
+
+    #[test]
+    fn marker_round_trips() {
+        let p = prov("mdv report --month 2026-09");
+        assert_eq!(Provenance::parse_marker(&p.marker()), Some(p));
+    }
+
+    #[test]
+    fn marker_sanitizes_quotes() {
+        let p = prov(r#"title = "x" -->"#);
+        let parsed = Provenance::parse_marker(&p.marker()).unwrap();
+        assert_eq!(parsed.source, "title = 'x' ->");
+    }
+
+    #[test]
+    fn non_markers_are_rejected() {
+        assert!(Provenance::parse_marker("<!-- comment -->").is_none());
+        assert!(
+            Provenance::parse_marker("<!-- mdv:generatedx generator=a -->").is_none()
+        );
+        assert!(Provenance::parse_marker("<!-- mdv:generated -->").is_none());
+    }
+
+    #[test]
+    fn finds_regions_with_lines_and_bodies() {
+        let content = format!(
+            "# Note\n\n{}\nhand written\n{}",
+            wrap(&prov("a"), "- one\n- two\n"),
+            wrap(&prov("b"), "- three")
+        );
+        let regions = find_regions(&content);
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0].provenance.source, "a");
+        assert_eq!((regions[0].start_line, regions[0].end_line), (3, 6));
+        assert_eq!(regions[0].body, "- one\n- two\n");
+        assert_eq!(regions[1].start_line, 9);
+        assert_eq!(regions[1].body, "- three\n");
+    }
+
+    #[test]
+    fn ignores_unterminated_and_fenced_markers() {
+        let fenced = format!("```\n{}```\n", wrap(&prov("x"), "body"));
+        assert!(find_regions(&fenced).is_empty());
+        let open = format!("{}\nbody\n", prov("x").marker());
+        assert!(find_regions(&open).is_empty());
+    }
+
+    #[test]
+    fn strip_and_replace_keep_surrounding_text() {
+        let content = format!("before\n{}after\n", wrap(&prov("a"), "old"));
+        let region = &find_regions(&content)[0];
+
+        assert_eq!(strip_region(&content, region), "before\nafter\n");
+
+        let replaced = replace_region(&content, region, &prov("b"), "new\n");
+        assert!(replaced.starts_with("before\n"));
+        assert!(replaced.ends_with("new\n<!-- /mdv:generated -->\nafter\n"));
+        assert_eq!(find_regions(&replaced)[0].provenance.source, "b");
+    }
+
+    #[test]
+    fn replace_region_at_end_without_newline() {
+        let content = format!("x\n{}\n{END_MARKER}", prov("a").marker());
+        let region = &find_regions(&content)[0];
+        let replaced = replace_region(&content, region, &prov("a"), "body");
+        assert!(replaced.ends_with("body\n<!-- /mdv:generated -->"));
+    }
+}
@@ -0,0 +1,333 @@
+//! A small query language over the note index.
+//!
+//! ```text
+//! type:task AND status:todo AND due < today+7d SORT due, priority DESC LIMIT 20
+//! ```
+//!
+//! Clauses are `field op value` joined by `AND`, where `op` is one of `:`
+//! (same as `=`), `!=`, `<`, `<=`, `>`, `>=`, and `NOT` negates a clause.
+//! Unquoted values that are date expressions (`today`, `today+7d`,
+//! `2025-01-15`) are evaluated first; quote a value to take it literally.
+//! `path:` filters by path prefix and `modified` compares file modification
+//! dates; every other field is matched against frontmatter.
+//!
+//! Filters compile onto a [`NoteQuery`], so they run in SQL against the
+//! index. `SORT` and `LIMIT` are applied to the results.
+
+mod parser;
+
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use chrono::{DateTime, Days, Local, NaiveDate, Utc};
+use thiserror::Error;
+
+use crate::index::{FieldFilter, FieldOp, IndexDb, IndexError, IndexedNote, NoteQuery};
+use crate::vars::datemath::{evaluate_date_expr_with_ref, is_date_expr, parse_date_expr};
+
+#[derive(Debug, Error)]
+pub enum QueryError {
+    #[error("query syntax error at position {position}: {message}")]
+    Syntax { position: usize, message: String },
+
+    #[error("invalid query: {0}")]
+    Invalid(String),
+
+    #[error(transparent)]
+    Index(#[from] IndexError),
+}
+
+impl QueryError {
+    fn syntax(position: usize, message: impl Into<String>) -> Self {
+        Self::Syntax { position, message: message.into() }
+    }
+}
+
+/// One `field op value` condition, as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clause {
+    pub field: String,
+    pub op: FieldOp,
+    pub value: String,
+    /// Quoted values are never evaluated as date expressions.
+    pub quoted: bool,
+}
+
+/// A `SORT` key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortKey {
+    /// `title`, `path`, `modified`, or a frontmatter field.
+    pub field: String,
+    pub descending: bool,
+}
+
+/// A parsed query, ready to run against an index.
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    pub filter: NoteQuery,
+    pub sort: Vec<SortKey>,
+    pub limit: Option<u32>,
+}
+
+impl Query {
+    /// Parse `input`, evaluating date expressions relative to today.
+    pub fn parse(input: &str) -> Result<Self, QueryError> {
+        Self::parse_at(input, Local::now().date_naive())
+    }
+
+    /// Parse `input`, evaluating date expressions relative to `today`.
+    pub fn parse_at(input: &str, today: NaiveDate) -> Result<Self, QueryError> {
+        let parsed = parser::parse(input)?;
+        let mut filter = NoteQuery::default();
+
+        for clause in parsed.clauses {
+            let value = if clause.quoted {
+                clause.value.clone()
+            } else {
+                evaluate_value(&clause.value, today)
+            };
+            match clause.field.to_lowercase().as_str() {
+                "path" => {
+                    if clause.op != FieldOp::Eq {
+                        return Err(QueryError::Invalid(
+                            "path only supports path:PREFIX".to_string(),
+                        ));
+                    }
+                    filter.path_prefix = Some(value.into());
+                }
+                "modified" => apply_modified(&mut filter, clause.op, &value)?,
+                _ => filter.fields.push(FieldFilter {
+                    key: clause.field,
+                    op: clause.op,
+                    value,
+                }),
+            }
+        }
+
+        Ok(Self { filter, sort: parsed.sort, limit: parsed.limit })
+    }
+
+    /// Run the query against `db`.
+    pub fn run(&self, db: &IndexDb) -> Result<Vec<IndexedNote>, QueryError> {
+        let mut filter = self.filter.clone();
+        // Without a sort the index order stands and the limit can go to SQL
+        if self.sort.is_empty() {
+            filter.limit = self.limit;
+        }
+        let mut notes = db.query_notes(&filter)?;
+
+        if !self.sort.is_empty() {
+            let mut keyed: Vec<(Vec<Option<String>>, IndexedNote)> = notes
+                .into_iter()
+                .map(|note| {
+                    let values = self.sort.iter().map(|k| sort_value(&note, &k.field));
+                    (values.collect(), note)
+                })
+                .collect();
+            keyed.sort_by(|(a, _), (b, _)| {
+                self.sort
+                    .iter()
+                    .zip(a.iter().zip(b))
+                    .map(|(key, (a, b))| {
+                        compare_values(a.as_deref(), b.as_deref(), key.descending)
+                    })
+                    .find(|o| o.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            });
+            notes = keyed.into_iter().map(|(_, note)| note).collect();
+            if let Some(limit) = self.limit {
+                notes.truncate(limit as usize);
+            }
+        }
+        Ok(notes)
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// The opposite comparison, for `NOT`.
+pub(crate) fn negate(op: FieldOp) -> FieldOp {
+    match op {
+        FieldOp::Eq => FieldOp::Ne,
+        FieldOp::Ne => FieldOp::Eq,
+        FieldOp::Lt => FieldOp::Ge,
+        FieldOp::Le => FieldOp::Gt,
+        FieldOp::Gt => FieldOp::Le,
+        FieldOp::Ge => FieldOp::Lt,
+    }
+}
+
+/// Evaluate date expressions, leaving anything else as written.
+pub(crate) fn evaluate_value(value: &str, today: NaiveDate) -> String {
+    if !is_date_expr(value) {
+        return value.to_string();
+    }
+    // `today-7d` reads naturally in a query; date math wants `today - 7d`
+    let spaced = match value.split_once('-') {
+        Some((base, offset)) if base.chars().all(|c| c.is_ascii_alphabetic()) => {
+            format!("{base} - {offset}")
+        }
+        _ => value.to_string(),
+    };
+    parse_date_expr(&spaced)
+        .map(|expr| evaluate_date_expr_with_ref(&expr, Some(today)))
+        .unwrap_or_else(|_| value.to_string())
+}
+
+fn apply_modified(
+    filter: &mut NoteQuery,
+    op: FieldOp,
+    value: &str,
+) -> Result<(), QueryError> {
+    let date = NaiveDate::parse_from_str(value.get(..10).unwrap_or(value), "%Y-%m-%d")
+        .map_err(|_| {
+            QueryError::Invalid(format!("modified expects a date, got '{value}'"))
+        })?;
+    let start = |d: NaiveDate| DateTime::<Utc>::from_naive_utc_and_offset(d.into(), Utc);
+    let next = date.checked_add_days(Days::new(1)).unwrap_or(date);
+    match op {
+        FieldOp::Eq => {
+            filter.modified_after = Some(start(date));
+            filter.modified_before = Some(start(next));
+        }
+        FieldOp::Ge => filter.modified_after = Some(start(date)),
+        FieldOp::Gt => filter.modified_after = Some(start(next)),
+        FieldOp::Lt => filter.modified_before = Some(start(date)),
+        FieldOp::Le => filter.modified_before = Some(start(next)),
+        FieldOp::Ne => {
+            return Err(QueryError::Invalid(
+                "modified does not support != (use < or >)".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The value a note sorts by: built-in attributes, else its frontmatter.
+fn sort_value(note: &IndexedNote, field: &str) -> Option<String> {
+    match field.to_lowercase().as_str() {
+        "title" => return Some(note.title.clone()),
+        "path" => return Some(note.path.to_string_lossy().into_owned()),
+        "modified" => return Some(note.modified.to_rfc3339()),
+        _ => {}
+    }
+    let fm: serde_json::Value =
+        serde_json::from_str(note.frontmatter_json.as_deref()?).ok()?;
+    match fm.get(field)? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Numbers compare numerically, everything else case-insensitively. Notes
+/// without a value sort last in either direction.
+fn compare_values(a: Option<&str>, b: Option<&str>, descending: bool) -> Ordering {
+    let (a, b) = match (a, b) {
+        (None, None) => return Ordering::Equal,
+        (None, Some(_)) => return Ordering::Greater,
+        (Some(_), None) => return Ordering::Less,
+        (Some(a), Some(b)) => (a, b),
+    };
+    let ordering = match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(x), Ok(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+        _ => a.to_lowercase().cmp(&b.to_lowercase()),
+    };
+    if descending { ordering.reverse() } else { ordering }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::IndexBuilder;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, 15).unwrap()
+    }
+
+    fn paths(notes: &[IndexedNote]) -> Vec<String> {
+        notes.iter().map(|n| n.path.to_string_lossy().into_owned()).collect()
+    }
+
+    #[test]
+    fn compiles_onto_note_query() {
+        let query = Query::parse_at(
+            "type:task AND due < today+7d AND NOT status:done AND path:Projects/ \
+             AND modified >= 2025-01-01 AND title:'today'",
+            today(),
+        )
+        .unwrap();
+        let filters: Vec<String> =
+            query.filter.fields.iter().map(|f| f.to_string()).collect();
+        assert_eq!(
+            filters,
+            vec!["type=task", "due<2025-01-22", "status!=done", "title=today"]
+        );
+        assert_eq!(query.filter.path_prefix.as_deref(), Some("Projects/".as_ref()));
+        assert_eq!(
+            query.filter.modified_after.unwrap().to_rfc3339(),
+            "2025-01-01T00:00:00+00:00"
+        );
+
+        let query = Query::parse_at("due >= today-7d", today()).unwrap();
+        assert_eq!(query.filter.fields[0].to_string(), "due>=2025-01-08");
+    }
+
+    #[test]
+    fn rejects_unsupported_fields() {
+        assert!(matches!(
+            Query::parse_at("path > a", today()),
+            Err(QueryError::Invalid(_))
+        ));
+        assert!(matches!(
+            Query::parse_at("modified:soon", today()),
+            Err(QueryError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn runs_with_sort_and_limit() {
+        let dir = TempDir::new().unwrap();
+        let notes = [
+            (
+                "a.md",
+                "---\ntype: task\nstatus: todo\ndue: 2025-01-20\npriority: 2\n---\n",
+            ),
+            (
+                "b.md",
+                "---\ntype: task\nstatus: todo\ndue: 2025-01-17\npriority: 10\n---\n",
+            ),
+            ("c.md", "---\ntype: task\nstatus: todo\npriority: 10\n---\n"),
+            ("d.md", "---\ntype: task\nstatus: done\ndue: 2025-01-16\n---\n"),
+            ("e.md", "---\ntype: task\nstatus: todo\ndue: 2025-03-01\n---\n"),
+        ];
+        for (path, content) in notes {
+            fs::write(dir.path().join(path), content).unwrap();
+        }
+        let db = IndexDb::open_in_memory().unwrap();
+        IndexBuilder::new(&db, dir.path()).full_reindex(None).unwrap();
+
+        let run =
+            |q: &str| paths(&Query::parse_at(q, today()).unwrap().run(&db).unwrap());
+        assert_eq!(
+            run("type:task AND status:todo AND due < today+7d SORT due"),
+            ["b.md", "a.md"]
+        );
+        // Numeric sort, then missing values last
+        assert_eq!(
+            run("status:todo SORT priority DESC, path"),
+            ["b.md", "c.md", "a.md", "e.md"]
+        );
+        assert_eq!(run("SORT due DESC LIMIT 2"), ["e.md", "a.md"]);
+        assert_eq!(run("status:done").len(), 1);
+    }
+}
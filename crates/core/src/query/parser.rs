@@ -0,0 +1,292 @@
+//! Tokenizer and parser for the query language.
+
+use super::{Clause, QueryError, SortKey, negate};
+use crate::index::FieldOp;
+
+/// A lexical token with its byte offset in the input.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Token {
+    /// Unquoted word: keyword, field name, or value.
+    Word(String),
+    /// Quoted value, taken literally.
+    Quoted(String),
+    Op(FieldOp),
+}
+
+pub(super) fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(pos, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '"' || c == '\'' {
+            chars.next();
+            let mut value = String::new();
+            let mut closed = false;
+            for (_, ch) in chars.by_ref() {
+                if ch == c {
+                    closed = true;
+                    break;
+                }
+                value.push(ch);
+            }
+            if !closed {
+                return Err(QueryError::syntax(pos, "unterminated quote"));
+            }
+            tokens.push((pos, Token::Quoted(value)));
+            continue;
+        }
+
+        let rest = &input[pos..];
+        if let Some((op, len)) = match_op(rest) {
+            for _ in 0..len {
+                chars.next();
+            }
+            tokens.push((pos, Token::Op(op)));
+            continue;
+        }
+
+        let mut word = String::new();
+        while let Some(&(i, ch)) = chars.peek() {
+            if ch.is_whitespace()
+                || ch == '"'
+                || ch == '\''
+                || match_op(&input[i..]).is_some()
+            {
+                break;
+            }
+            word.push(ch);
+            chars.next();
+        }
+        tokens.push((pos, Token::Word(word)));
+    }
+    Ok(tokens)
+}
+
+fn match_op(s: &str) -> Option<(FieldOp, usize)> {
+    // Longest operators first so `<=` is not read as `<` followed by `=`
+    [
+        ("!=", FieldOp::Ne),
+        ("<=", FieldOp::Le),
+        (">=", FieldOp::Ge),
+        (":", FieldOp::Eq),
+        ("=", FieldOp::Eq),
+        ("<", FieldOp::Lt),
+        (">", FieldOp::Gt),
+    ]
+    .into_iter()
+    .find(|(text, _)| s.starts_with(text))
+    .map(|(text, op)| (op, text.len()))
+}
+
+fn is_keyword(token: &Token, keyword: &str) -> bool {
+    matches!(token, Token::Word(w) if w.eq_ignore_ascii_case(keyword))
+}
+
+/// Parsed query before it is compiled onto a `NoteQuery`.
+#[derive(Debug, Default)]
+pub(super) struct Parsed {
+    pub clauses: Vec<Clause>,
+    pub sort: Vec<SortKey>,
+    pub limit: Option<u32>,
+}
+
+pub(super) fn parse(input: &str) -> Result<Parsed, QueryError> {
+    let tokens = tokenize(input)?;
+    let mut parsed = Parsed::default();
+    let mut i = 0;
+    let end = input.len();
+    let pos_at = |i: usize| tokens.get(i).map(|(p, _)| *p).unwrap_or(end);
+
+    // Clauses, joined by AND
+    while i < tokens.len()
+        && !is_keyword(&tokens[i].1, "SORT")
+        && !is_keyword(&tokens[i].1, "LIMIT")
+    {
+        if !parsed.clauses.is_empty() {
+            if is_keyword(&tokens[i].1, "AND") {
+                i += 1;
+            } else if is_keyword(&tokens[i].1, "OR") {
+                return Err(QueryError::syntax(
+                    pos_at(i),
+                    "OR is not supported; run separate queries",
+                ));
+            } else {
+                return Err(QueryError::syntax(
+                    pos_at(i),
+                    "expected AND, SORT, or LIMIT",
+                ));
+            }
+        }
+
+        let negated = tokens.get(i).is_some_and(|(_, t)| is_keyword(t, "NOT"));
+        if negated {
+            i += 1;
+        }
+
+        let field = match tokens.get(i) {
+            Some((_, Token::Word(w))) if !w.is_empty() => w.clone(),
+            _ => return Err(QueryError::syntax(pos_at(i), "expected a field name")),
+        };
+        let op = match tokens.get(i + 1) {
+            Some((_, Token::Op(op))) => *op,
+            _ => {
+                return Err(QueryError::syntax(
+                    pos_at(i + 1),
+                    format!("expected an operator after '{field}' (e.g. {field}:value)"),
+                ));
+            }
+        };
+        let (value, quoted) = match tokens.get(i + 2) {
+            Some((_, Token::Word(w))) => (w.clone(), false),
+            Some((_, Token::Quoted(q))) => (q.clone(), true),
+            _ => return Err(QueryError::syntax(pos_at(i + 2), "expected a value")),
+        };
+        i += 3;
+
+        let op = if negated { negate(op) } else { op };
+        parsed.clauses.push(Clause { field, op, value, quoted });
+    }
+
+    // SORT key [ASC|DESC] [, key ...]
+    if tokens.get(i).is_some_and(|(_, t)| is_keyword(t, "SORT")) {
+        i += 1;
+        let mut words = Vec::new();
+        while let Some((pos, token)) = tokens.get(i) {
+            match token {
+                t if is_keyword(t, "LIMIT") => break,
+                Token::Word(w) => words
+                    .extend(w.split(',').filter(|p| !p.is_empty()).map(|p| (*pos, p))),
+                _ => return Err(QueryError::syntax(*pos, "expected a sort field")),
+            }
+            i += 1;
+        }
+
+        let mut words = words.into_iter().peekable();
+        while let Some((pos, field)) = words.next() {
+            if field.eq_ignore_ascii_case("ASC") || field.eq_ignore_ascii_case("DESC") {
+                return Err(QueryError::syntax(pos, "expected a sort field"));
+            }
+            let direction = words.next_if(|(_, w)| {
+                w.eq_ignore_ascii_case("ASC") || w.eq_ignore_ascii_case("DESC")
+            });
+            let descending =
+                direction.is_some_and(|(_, w)| w.eq_ignore_ascii_case("DESC"));
+            parsed.sort.push(SortKey { field: field.to_string(), descending });
+        }
+        if parsed.sort.is_empty() {
+            return Err(QueryError::syntax(
+                pos_at(i),
+                "expected a sort field after SORT",
+            ));
+        }
+    }
+
+    // LIMIT n
+    if tokens.get(i).is_some_and(|(_, t)| is_keyword(t, "LIMIT")) {
+        i += 1;
+        parsed.limit = match tokens.get(i) {
+            Some((_, Token::Word(n))) => Some(n.parse().map_err(|_| {
+                QueryError::syntax(
+                    pos_at(i),
+                    format!("LIMIT expects a number, got '{n}'"),
+                )
+            })?),
+            _ => {
+                return Err(QueryError::syntax(
+                    pos_at(i),
+                    "expected a number after LIMIT",
+                ));
+            }
+        };
+        i += 1;
+    }
+
+    if i < tokens.len() {
+        return Err(QueryError::syntax(pos_at(i), "unexpected input"));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clause(field: &str, op: FieldOp, value: &str) -> Clause {
+        Clause { field: field.into(), op, value: value.into(), quoted: false }
+    }
+
+    #[test]
+    fn tokenizes_glued_operators() {
+        let tokens: Vec<_> = tokenize("due<=today+7d title:'a b'")
+            .unwrap()
+            .into_iter()
+            .map(|t| t.1)
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Word("due".into()),
+                Token::Op(FieldOp::Le),
+                Token::Word("today+7d".into()),
+                Token::Word("title".into()),
+                Token::Op(FieldOp::Eq),
+                Token::Quoted("a b".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_clauses_sort_and_limit() {
+        let parsed = parse(
+            "type:task AND status:todo and NOT project:ALP AND due < today+7d \
+             SORT due, priority DESC LIMIT 5",
+        )
+        .unwrap();
+        assert_eq!(
+            parsed.clauses,
+            vec![
+                clause("type", FieldOp::Eq, "task"),
+                clause("status", FieldOp::Eq, "todo"),
+                clause("project", FieldOp::Ne, "ALP"),
+                clause("due", FieldOp::Lt, "today+7d"),
+            ]
+        );
+        assert_eq!(
+            parsed.sort,
+            vec![
+                SortKey { field: "due".into(), descending: false },
+                SortKey { field: "priority".into(), descending: true },
+            ]
+        );
+        assert_eq!(parsed.limit, Some(5));
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        let parsed = parse("  ").unwrap();
+        assert!(parsed.clauses.is_empty() && parsed.sort.is_empty());
+        assert_eq!(parse("SORT title").unwrap().sort.len(), 1);
+    }
+
+    #[test]
+    fn reports_syntax_errors_with_position() {
+        let cases = [
+            ("status", "expected an operator"),
+            ("status:", "expected a value"),
+            ("a:1 b:2", "expected AND"),
+            ("a:1 OR b:2", "OR is not supported"),
+            ("a:'open", "unterminated quote"),
+            ("a:1 LIMIT x", "LIMIT expects a number"),
+            ("a:1 SORT", "expected a sort field"),
+        ];
+        for (input, expected) in cases {
+            let err = parse(input).unwrap_err().to_string();
+            assert!(err.contains(expected), "{input}: {err}");
+        }
+        assert!(matches!(parse("a:1 b:2"), Err(QueryError::Syntax { position: 4, .. })));
+    }
+}
@@ -9,15 +9,18 @@ use std::sync::LazyLock;
 use regex::Regex;
 
 use crate::frontmatter;
+use crate::index::slugify;
 use crate::rename::types::{Reference, ReferenceType};
+use crate::vault::{is_embed, split_link_target};
 
 // Regex patterns for reference detection
 static WIKILINK_RE: LazyLock<Regex> = LazyLock::new(|| {
     // Matches [[target]] or [[target|alias]] or [[target#section]] or [[target#section|alias]]
+    // The target may be empty for same-note links: [[#section]]
     // Captures:
-    // 1: target (note name/path, may include #section)
+    // 1: target (note name/path, may include #section or ^block)
     // 2: alias (if present)
-    Regex::new(r"\[\[([^\]|#]+(?:#[^\]|]+)?)(?:\|([^\]]+))?\]\]").unwrap()
+    Regex::new(r"\[\[([^\]|#]*(?:#[^\]|]+)?)(?:\|([^\]]+))?\]\]").unwrap()
 });
 
 static MARKDOWN_LINK_RE: LazyLock<Regex> = LazyLock::new(|| {
@@ -25,7 +28,7 @@ static MARKDOWN_LINK_RE: LazyLock<Regex> = LazyLock::new(|| {
     // Captures:
     // 1: text
     // 2: url
-    Regex::new(r"\[([^\]]+)\]\(([^)]+)\)").unwrap()
+    Regex::new(r"\[([^\]]*)\]\(([^)]+)\)").unwrap()
 });
 
 /// Find all references to a target note in a file's content.
@@ -46,7 +49,7 @@ pub fn find_references_in_content(
 
     // Find references in body content
     let body_refs =
-        find_body_references(content, source_path, target_basename, target_rel);
+        find_body_references(content, source_path, target_basename, target_rel, false);
     references.extend(body_refs);
 
     // Find references in frontmatter
@@ -56,11 +59,39 @@ pub fn find_references_in_content(
     references
 }
 
+/// Find all references to a heading of the target note.
+///
+/// Matches `[[note#Heading]]` and `[text](note.md#heading)` by heading slug.
+/// When `source_path` is the target note itself, same-note links such as
+/// `[[#Heading]]` are included too.
+pub fn find_section_references_in_content(
+    content: &str,
+    source_path: &Path,
+    target_path: &Path,
+    vault_root: &Path,
+    heading: &str,
+) -> Vec<Reference> {
+    let target_basename = target_path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    let target_rel = target_path.strip_prefix(vault_root).unwrap_or(target_path);
+    let include_self = source_path == target_path;
+    let heading_slug = slugify(heading);
+
+    find_body_references(content, source_path, target_basename, target_rel, include_self)
+        .into_iter()
+        .filter(|r| {
+            r.section
+                .as_deref()
+                .is_some_and(|s| !s.starts_with('^') && slugify(s) == heading_slug)
+        })
+        .collect()
+}
+
 fn find_body_references(
     content: &str,
     source_path: &Path,
     target_basename: &str,
     target_rel: &Path,
+    include_self: bool,
 ) -> Vec<Reference> {
     let mut references = Vec::new();
 
@@ -76,13 +107,22 @@ fn find_body_references(
             let target_text = cap.get(1).map(|m| m.as_str()).unwrap_or("");
             let alias = cap.get(2).map(|m| m.as_str().to_string());
 
-            // Parse target (may include #section)
-            let (note_ref, section) = parse_wikilink_target(target_text);
+            // Parse target (may include #section or ^block)
+            let (note_ref, section) = split_link_target(target_text);
+            let section = section.map(String::from);
+
+            let is_match = if note_ref.is_empty() {
+                include_self && section.is_some()
+            } else {
+                matches_target(note_ref, target_basename, target_rel)
+            };
 
-            if matches_target(note_ref, target_basename, target_rel) {
-                let start = line_start_offset + full_match.start();
+            if is_match {
+                let embed = is_embed(line, full_match.start());
+                let match_start = full_match.start() - usize::from(embed);
+                let start = line_start_offset + match_start;
                 let end = line_start_offset + full_match.end();
-                let column = (full_match.start() + 1) as u32;
+                let column = (match_start + 1) as u32;
 
                 let ref_type = match (&section, &alias) {
                     (Some(_), Some(_)) => ReferenceType::WikilinkWithSectionAndAlias,
@@ -97,11 +137,12 @@ fn find_body_references(
                     column,
                     start,
                     end,
-                    original: full_match.as_str().to_string(),
+                    original: line[match_start..full_match.end()].to_string(),
                     ref_type,
                     alias,
                     section,
                     target_as_written: note_ref.to_string(),
+                    embed,
                 });
             }
         }
@@ -110,17 +151,31 @@ fn find_body_references(
         for cap in MARKDOWN_LINK_RE.captures_iter(line) {
             let full_match = cap.get(0).unwrap();
             let link_text = cap.get(1).map(|m| m.as_str()).unwrap_or("");
-            let url = cap.get(2).map(|m| m.as_str()).unwrap_or("");
+            let raw_url = cap.get(2).map(|m| m.as_str()).unwrap_or("");
 
             // Skip external URLs
-            if url.starts_with("http://") || url.starts_with("https://") {
+            if raw_url.starts_with("http://") || raw_url.starts_with("https://") {
                 continue;
             }
 
-            if matches_markdown_target(url, target_basename, target_rel) {
-                let start = line_start_offset + full_match.start();
+            // Separate any #fragment from the path
+            let (url, section) = match raw_url.split_once('#') {
+                Some((url, fragment)) => (url, Some(fragment.to_string())),
+                None => (raw_url, None),
+            };
+
+            let is_match = if url.is_empty() {
+                include_self && section.is_some()
+            } else {
+                matches_markdown_target(url, target_basename, target_rel)
+            };
+
+            if is_match {
+                let embed = is_embed(line, full_match.start());
+                let match_start = full_match.start() - usize::from(embed);
+                let start = line_start_offset + match_start;
                 let end = line_start_offset + full_match.end();
-                let column = (full_match.start() + 1) as u32;
+                let column = (match_start + 1) as u32;
 
                 references.push(Reference {
                     source_path: source_path.to_path_buf(),
@@ -128,11 +183,12 @@ fn find_body_references(
                     column,
                     start,
                     end,
-                    original: full_match.as_str().to_string(),
+                    original: line[match_start..full_match.end()].to_string(),
                     ref_type: ReferenceType::MarkdownLink,
                     alias: Some(link_text.to_string()),
-                    section: None,
+                    section,
                     target_as_written: url.to_string(),
+                    embed,
                 });
             }
         }
@@ -189,6 +245,7 @@ fn find_frontmatter_references(
                     alias: None,
                     section: None,
                     target_as_written: s.to_string(),
+                    embed: false,
                 });
             }
 
@@ -214,6 +271,7 @@ fn find_frontmatter_references(
                             alias: None,
                             section: None,
                             target_as_written: s.to_string(),
+                            embed: false,
                         });
                     }
                 }
@@ -224,17 +282,6 @@ fn find_frontmatter_references(
     references
 }
 
-/// Parse a wikilink target, separating the note reference from the section anchor.
-fn parse_wikilink_target(target: &str) -> (&str, Option<String>) {
-    if let Some(hash_pos) = target.find('#') {
-        let note = &target[..hash_pos];
-        let section = &target[hash_pos + 1..];
-        (note, Some(section.to_string()))
-    } else {
-        (target, None)
-    }
-}
-
 /// Check if a wikilink reference matches the target note.
 fn matches_target(reference: &str, target_basename: &str, target_rel: &Path) -> bool {
     let ref_lower = reference.to_lowercase();
@@ -481,6 +528,82 @@ mod tests {
         assert_eq!(refs[0].section, Some("section".to_string()));
     }
 
+    #[test]
+    fn test_find_block_references() {
+        let content = "See [[my-note^intro]] and [[my-note#^intro|the intro]].";
+        let refs = find_references_in_content(
+            content,
+            Path::new("source.md"),
+            Path::new("/vault/my-note.md"),
+            Path::new("/vault"),
+        );
+
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].section.as_deref(), Some("^intro"));
+        assert_eq!(refs[0].target_as_written, "my-note");
+        assert_eq!(refs[1].ref_type, ReferenceType::WikilinkWithSectionAndAlias);
+    }
+
+    #[test]
+    fn test_find_markdown_link_with_fragment() {
+        let content = "Check [this](./my-note.md#next-steps) out.";
+        let refs = find_references_in_content(
+            content,
+            Path::new("source.md"),
+            Path::new("/vault/my-note.md"),
+            Path::new("/vault"),
+        );
+
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].target_as_written, "./my-note.md");
+        assert_eq!(refs[0].section.as_deref(), Some("next-steps"));
+    }
+
+    #[test]
+    fn test_find_embeds() {
+        let content = "Above\n![[my-note#Summary]] and ![](./my-note.md)";
+        let refs = find_references_in_content(
+            content,
+            Path::new("source.md"),
+            Path::new("/vault/my-note.md"),
+            Path::new("/vault"),
+        );
+
+        assert_eq!(refs.len(), 2);
+        assert!(refs.iter().all(|r| r.embed));
+        assert_eq!(refs[0].original, "![[my-note#Summary]]");
+        assert_eq!((refs[0].start, refs[0].column), (6, 1));
+        assert_eq!(refs[1].original, "![](./my-note.md)");
+    }
+
+    #[test]
+    fn test_find_section_references() {
+        let content = "[[my-note#Next Steps]] [[my-note#Other]] [[#Next Steps]] \
+                       [md](my-note.md#next-steps) [[my-note]] [[my-note^next-steps]]";
+
+        let refs = find_section_references_in_content(
+            content,
+            Path::new("/vault/source.md"),
+            Path::new("/vault/my-note.md"),
+            Path::new("/vault"),
+            "Next steps",
+        );
+        let found: Vec<&str> = refs.iter().map(|r| r.original.as_str()).collect();
+        assert_eq!(found, vec!["[[my-note#Next Steps]]", "[md](my-note.md#next-steps)"]);
+
+        // Same-note links only count inside the note itself
+        let own = find_section_references_in_content(
+            content,
+            Path::new("/vault/my-note.md"),
+            Path::new("/vault/my-note.md"),
+            Path::new("/vault"),
+            "Next steps",
+        );
+        assert_eq!(own.len(), 3);
+        assert_eq!(own[1].original, "[[#Next Steps]]");
+        assert_eq!(own[1].target_as_written, "");
+    }
+
     #[test]
     fn test_find_markdown_link() {
         let content = "Check out [this note](./my-note.md) for more.";
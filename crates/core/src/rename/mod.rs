@@ -1,7 +1,9 @@
 //! Rename and reference management for mdvault.
 //!
 //! This module provides safe note renaming with automatic reference updates.
-//! It handles wikilinks, markdown links, and frontmatter references.
+//! It handles wikilinks, markdown links, and frontmatter references, keeping
+//! any `#section` or `^block` fragment intact. Headings can be renamed too,
+//! updating every link that points at them.
 
 mod detector;
 mod types;
@@ -13,10 +15,13 @@ use std::path::{Path, PathBuf};
 
 pub use types::*;
 
-use crate::index::IndexDb;
+use crate::index::{AnchorKind, IndexDb, IndexError, slugify};
+use crate::scripting::EventHooks;
 
-use detector::find_references_in_content;
-use updater::apply_updates;
+use detector::{find_references_in_content, find_section_references_in_content};
+use updater::{
+    apply_section_updates, apply_updates, rebase_reference, rename_heading_line,
+};
 
 /// Generate a preview of what a rename operation would do.
 ///
@@ -51,26 +56,22 @@ pub fn generate_preview(
     // Find the note in the index
     let old_rel = old_abs.strip_prefix(vault_root).unwrap_or(&old_abs);
     let note = db
-        .get_note_by_path(old_rel)
-        .map_err(|e| RenameError::IndexError(e.to_string()))?
+        .get_note_by_path(old_rel)?
         .ok_or_else(|| RenameError::NoteNotInIndex(old_abs.clone()))?;
 
-    let note_id =
-        note.id.ok_or_else(|| RenameError::IndexError("Note has no ID".to_string()))?;
+    let note_id = note.id.ok_or_else(|| {
+        IndexError::InvalidData(format!("note has no ID: {}", old_rel.display()))
+    })?;
 
     // Get backlinks from index to find files that reference this note
-    let backlinks =
-        db.get_backlinks(note_id).map_err(|e| RenameError::IndexError(e.to_string()))?;
+    let backlinks = db.get_backlinks(note_id)?;
 
     // Find all references by parsing the source files
     let mut all_references = Vec::new();
     let mut files_to_scan: HashMap<PathBuf, ()> = HashMap::new();
 
     for link in &backlinks {
-        if let Some(source_note) = db
-            .get_note_by_id(link.source_id)
-            .map_err(|e| RenameError::IndexError(e.to_string()))?
-        {
+        if let Some(source_note) = db.get_note_by_id(link.source_id)? {
             let source_path = vault_root.join(&source_note.path);
             files_to_scan.insert(source_path, ());
         }
@@ -82,8 +83,12 @@ pub fn generate_preview(
             RenameError::ReadError { path: source_path.clone(), source: e }
         })?;
 
-        let refs =
+        let mut refs =
             find_references_in_content(&content, source_path, &old_abs, vault_root);
+        let new_rel = new_abs.strip_prefix(vault_root).unwrap_or(&new_abs);
+        for reference in &mut refs {
+            rebase_reference(reference, old_rel, new_rel, vault_root);
+        }
         all_references.extend(refs);
     }
 
@@ -151,12 +156,14 @@ pub fn generate_preview(
 
 /// Execute a rename operation.
 ///
-/// This modifies files on disk and updates the index.
+/// This modifies files on disk and updates the index, then runs any
+/// `on_rename` event hooks. A failing hook becomes a warning.
 pub fn execute_rename(
     db: &IndexDb,
     vault_root: &Path,
     old_path: &Path,
     new_path: &Path,
+    hooks: Option<&EventHooks>,
 ) -> Result<RenameResult, RenameError> {
     // Generate preview first to get all the info
     let preview = generate_preview(db, vault_root, old_path, new_path)?;
@@ -191,11 +198,166 @@ pub fn execute_rename(
     let old_rel = preview.old_path.strip_prefix(vault_root).unwrap_or(&preview.old_path);
     let new_rel = preview.new_path.strip_prefix(vault_root).unwrap_or(&preview.new_path);
 
-    update_note_path(db, old_rel, new_rel)
-        .map_err(|e| RenameError::IndexError(e.to_string()))?;
+    update_note_path(db, old_rel, new_rel)?;
 
     // Re-resolve link targets after the rename
-    db.resolve_link_targets().map_err(|e| RenameError::IndexError(e.to_string()))?;
+    db.resolve_link_targets()?;
+
+    let mut warnings = preview.warnings;
+    if let Some(hooks) = hooks {
+        warnings.extend(hooks.on_rename(old_rel, new_rel).iter().map(|e| e.to_string()));
+    }
+
+    Ok(RenameResult {
+        old_path: preview.old_path,
+        new_path: preview.new_path,
+        files_modified,
+        references_updated,
+        warnings,
+    })
+}
+
+/// Generate a preview of renaming a heading inside a note.
+///
+/// Finds `[[note#Heading]]`, `[text](note.md#heading)`, and same-note
+/// `[[#Heading]]` references. Headings are matched by slug, so case and
+/// punctuation differences in the links do not matter. The heading line
+/// itself is part of the note's [`FileChange`]; `old_path` and `new_path`
+/// of the preview are both the note.
+pub fn generate_heading_preview(
+    db: &IndexDb,
+    vault_root: &Path,
+    note_path: &Path,
+    old_heading: &str,
+    new_heading: &str,
+) -> Result<RenamePreview, RenameError> {
+    let note_abs = if note_path.is_absolute() {
+        note_path.to_path_buf()
+    } else {
+        vault_root.join(note_path)
+    };
+
+    if !note_abs.exists() {
+        return Err(RenameError::SourceNotFound(note_abs));
+    }
+
+    let note_rel = note_abs.strip_prefix(vault_root).unwrap_or(&note_abs);
+    let note = db
+        .get_note_by_path(note_rel)?
+        .ok_or_else(|| RenameError::NoteNotInIndex(note_abs.clone()))?;
+    let note_id = note.id.ok_or_else(|| {
+        IndexError::InvalidData(format!("note has no ID: {}", note_rel.display()))
+    })?;
+
+    let note_content = fs::read_to_string(&note_abs)
+        .map_err(|e| RenameError::ReadError { path: note_abs.clone(), source: e })?;
+    if rename_heading_line(&note_content, old_heading, new_heading).is_none() {
+        return Err(RenameError::HeadingNotFound {
+            path: note_abs,
+            heading: old_heading.to_string(),
+        });
+    }
+
+    // The note itself always changes; add every file that links to it
+    let mut files_to_scan = vec![note_abs.clone()];
+    for link in db.get_backlinks(note_id)? {
+        if link.target_anchor.is_none() {
+            continue;
+        }
+        if let Some(source_note) = db.get_note_by_id(link.source_id)? {
+            let source_path = vault_root.join(&source_note.path);
+            if !files_to_scan.contains(&source_path) {
+                files_to_scan.push(source_path);
+            }
+        }
+    }
+
+    let mut all_references = Vec::new();
+    let mut changes = Vec::new();
+
+    for source_path in files_to_scan {
+        let content = fs::read_to_string(&source_path).map_err(|e| {
+            RenameError::ReadError { path: source_path.clone(), source: e }
+        })?;
+
+        let refs = find_section_references_in_content(
+            &content,
+            &source_path,
+            &note_abs,
+            vault_root,
+            old_heading,
+        );
+        let mut new_content = apply_section_updates(&content, &refs, new_heading);
+        if source_path == note_abs
+            && let Some((renamed, _)) =
+                rename_heading_line(&new_content, old_heading, new_heading)
+        {
+            new_content = renamed;
+        }
+
+        if new_content != content {
+            all_references.extend(refs.iter().cloned());
+            changes.push(FileChange {
+                path: source_path,
+                original_content: content,
+                new_content,
+                references: refs,
+            });
+        }
+    }
+
+    // Two headings with the same slug make fragment links ambiguous
+    let mut warnings = Vec::new();
+    let new_slug = slugify(new_heading);
+    let clashes = db
+        .get_anchors(note_id)?
+        .iter()
+        .filter(|a| a.kind == AnchorKind::Heading && slugify(&a.name) == new_slug)
+        .count();
+    if clashes > 0 && new_slug != slugify(old_heading) {
+        warnings.push(format!(
+            "Warning: {} already has a heading '{}'. Links to it will become ambiguous.",
+            note_rel.display(),
+            new_heading
+        ));
+    }
+
+    Ok(RenamePreview {
+        old_path: note_abs.clone(),
+        new_path: note_abs,
+        references: all_references,
+        changes,
+        warnings,
+    })
+}
+
+/// Rename a heading inside a note and update every link that points at it.
+///
+/// This modifies files on disk and updates the index.
+pub fn execute_heading_rename(
+    db: &IndexDb,
+    vault_root: &Path,
+    note_path: &Path,
+    old_heading: &str,
+    new_heading: &str,
+) -> Result<RenameResult, RenameError> {
+    let preview =
+        generate_heading_preview(db, vault_root, note_path, old_heading, new_heading)?;
+
+    let mut files_modified = Vec::new();
+    let mut references_updated = 0;
+
+    for change in &preview.changes {
+        fs::write(&change.path, &change.new_content).map_err(|e| {
+            RenameError::WriteError { path: change.path.clone(), source: e }
+        })?;
+
+        files_modified.push(change.path.clone());
+        references_updated += change.references.len();
+    }
+
+    let note_rel = preview.old_path.strip_prefix(vault_root).unwrap_or(&preview.old_path);
+    update_heading_anchor(db, note_rel, &preview.references, old_heading, new_heading)?;
 
     Ok(RenameResult {
         old_path: preview.old_path,
@@ -206,12 +368,55 @@ pub fn execute_rename(
     })
 }
 
+/// Update a renamed heading and the fragments of links to it in the index.
+fn update_heading_anchor(
+    db: &IndexDb,
+    note_path: &Path,
+    references: &[Reference],
+    old_heading: &str,
+    new_heading: &str,
+) -> Result<(), IndexError> {
+    let Some(note_id) = db.get_note_by_path(note_path)?.and_then(|n| n.id) else {
+        return Ok(());
+    };
+    let conn = db.connection();
+
+    let old_slug = slugify(old_heading);
+    if let Some(anchor) = db
+        .get_anchors(note_id)?
+        .into_iter()
+        .find(|a| a.kind == AnchorKind::Heading && slugify(&a.name) == old_slug)
+    {
+        conn.execute(
+            "UPDATE note_anchors SET name = ?1 WHERE note_id = ?2 AND kind = 'heading' AND name = ?3",
+            rusqlite::params![new_heading, note_id, anchor.name],
+        )?;
+    }
+
+    for reference in references {
+        let Some(old_fragment) = &reference.section else {
+            continue;
+        };
+        let new_fragment = if reference.is_markdown_link() {
+            slugify(new_heading)
+        } else {
+            new_heading.to_string()
+        };
+        conn.execute(
+            "UPDATE links SET target_anchor = ?1 WHERE target_id = ?2 AND target_anchor = ?3",
+            rusqlite::params![new_fragment, note_id, old_fragment],
+        )?;
+    }
+
+    Ok(())
+}
+
 /// Update a note's path in the index.
 fn update_note_path(
     db: &IndexDb,
     old_path: &Path,
     new_path: &Path,
-) -> Result<(), crate::index::IndexError> {
+) -> Result<(), IndexError> {
     let conn = db.connection();
 
     // Update the notes table
@@ -230,6 +435,16 @@ fn update_note_path(
         rusqlite::params![new_path.to_string_lossy(), old_path.to_string_lossy(),],
     )?;
 
+    // Update path matches written without the extension
+    let strip_ext = |p: &Path| {
+        let p = p.to_string_lossy();
+        p.strip_suffix(".md").map(String::from).unwrap_or_else(|| p.to_string())
+    };
+    conn.execute(
+        "UPDATE links SET target_path = ?1 WHERE target_path = ?2",
+        rusqlite::params![strip_ext(new_path), strip_ext(old_path)],
+    )?;
+
     // Update basename-only matches
     conn.execute(
         "UPDATE links SET target_path = ?1 WHERE target_path = ?2",
@@ -272,6 +487,8 @@ mod tests {
             modified: Utc::now(),
             frontmatter_json: None,
             content_hash: "hash".to_string(),
+            word_count: 0,
+            heading_json: None,
         }
     }
 
@@ -338,6 +555,7 @@ mod tests {
             temp_dir.path(),
             Path::new("old.md"),
             Path::new("new.md"),
+            None,
         )
         .unwrap();
 
@@ -372,6 +590,7 @@ mod tests {
             source_id,
             target_id: Some(old_id),
             target_path: "old".to_string(),
+            target_anchor: None,
             link_text: None,
             link_type: crate::index::types::LinkType::Wikilink,
             context: None,
@@ -385,6 +604,7 @@ mod tests {
             temp_dir.path(),
             Path::new("old.md"),
             Path::new("new.md"),
+            None,
         )
         .unwrap();
 
@@ -398,4 +618,187 @@ mod tests {
         assert!(source_content.contains("[[new]]"));
         assert!(!source_content.contains("[[old]]"));
     }
+
+    #[test]
+    fn test_execute_rename_runs_hooks() {
+        let (temp_dir, db) = setup_test_vault();
+        create_note(temp_dir.path(), "old.md", "# Old Note\n");
+        db.insert_note(&sample_note("old.md")).unwrap();
+
+        let hooks_dir = TempDir::new().unwrap();
+        fs::write(
+            hooks_dir.path().join("log.lua"),
+            r#"return { on_rename = function(old, new) error(old .. " -> " .. new) end }"#,
+        )
+        .unwrap();
+        let hooks = EventHooks::load(hooks_dir.path()).unwrap();
+
+        let result = execute_rename(
+            &db,
+            temp_dir.path(),
+            Path::new("old.md"),
+            Path::new("new.md"),
+            Some(&hooks),
+        )
+        .unwrap();
+
+        // The rename stands; the failing hook is reported as a warning
+        assert!(temp_dir.path().join("new.md").exists());
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].contains("old.md -> new.md"), "{:?}", result.warnings);
+    }
+
+    fn index_vault(db: &IndexDb, root: &Path) {
+        crate::index::IndexBuilder::new(db, root).full_reindex(None).unwrap();
+    }
+
+    #[test]
+    fn test_rename_keeps_fragments() {
+        let (temp_dir, db) = setup_test_vault();
+        create_note(temp_dir.path(), "old.md", "# Old\n\n## Setup\n\nStep one. ^step\n");
+        create_note(
+            temp_dir.path(),
+            "source.md",
+            "[[old#Setup]] [[old^step]] [guide](./old.md#setup)\n",
+        );
+        index_vault(&db, temp_dir.path());
+
+        execute_rename(
+            &db,
+            temp_dir.path(),
+            Path::new("old.md"),
+            Path::new("new.md"),
+            None,
+        )
+        .unwrap();
+
+        let source = fs::read_to_string(temp_dir.path().join("source.md")).unwrap();
+        assert_eq!(source, "[[new#Setup]] [[new^step]] [guide](./new.md#setup)\n");
+    }
+
+    #[test]
+    fn test_rename_across_folders_rewrites_paths() {
+        let (temp_dir, db) = setup_test_vault();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("a")).unwrap();
+        fs::create_dir_all(root.join("docs")).unwrap();
+        create_note(root, "a/x.md", "# X\n");
+        create_note(root, "source.md", "[[a/x]] [[x]] [[a/x|X]] [l](a/x.md)\n");
+        create_note(root, "docs/guide.md", "[[a/x]] [up](../a/x.md#top)\n");
+        index_vault(&db, root);
+
+        execute_rename(&db, root, Path::new("a/x.md"), Path::new("b/c/x.md"), None)
+            .unwrap();
+
+        let source = fs::read_to_string(root.join("source.md")).unwrap();
+        assert_eq!(source, "[[b/c/x]] [[x]] [[b/c/x|X]] [l](b/c/x.md)\n");
+        let guide = fs::read_to_string(root.join("docs/guide.md")).unwrap();
+        assert_eq!(guide, "[[b/c/x]] [up](../b/c/x.md#top)\n");
+
+        // The index follows the links to the new path
+        let note = db.get_note_by_path(Path::new("b/c/x.md")).unwrap().unwrap();
+        assert_eq!(db.get_backlinks(note.id.unwrap()).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_rename_rewrites_embeds() {
+        let (temp_dir, db) = setup_test_vault();
+        create_note(temp_dir.path(), "old.md", "# Old\n\n## Setup\n");
+        create_note(
+            temp_dir.path(),
+            "source.md",
+            "![[old]]\n![[old#Setup|Steps]] ![](old.md)\n",
+        );
+        index_vault(&db, temp_dir.path());
+
+        execute_rename(
+            &db,
+            temp_dir.path(),
+            Path::new("old.md"),
+            Path::new("new.md"),
+            None,
+        )
+        .unwrap();
+
+        let source = fs::read_to_string(temp_dir.path().join("source.md")).unwrap();
+        assert_eq!(source, "![[new]]\n![[new#Setup|Steps]] ![](new.md)\n");
+
+        execute_heading_rename(
+            &db,
+            temp_dir.path(),
+            Path::new("new.md"),
+            "Setup",
+            "Install",
+        )
+        .unwrap();
+        let source = fs::read_to_string(temp_dir.path().join("source.md")).unwrap();
+        assert_eq!(source, "![[new]]\n![[new#Install|Steps]] ![](new.md)\n");
+    }
+
+    #[test]
+    fn test_heading_rename_updates_references() {
+        let (temp_dir, db) = setup_test_vault();
+        create_note(
+            temp_dir.path(),
+            "guide.md",
+            "# Guide\n\nSee [[#Setup]].\n\n## Setup\n\nSteps.\n",
+        );
+        create_note(
+            temp_dir.path(),
+            "source.md",
+            "[[guide#setup|how]] [[guide#Other]] [md](guide.md#setup) [[guide]]\n",
+        );
+        index_vault(&db, temp_dir.path());
+
+        let preview = generate_heading_preview(
+            &db,
+            temp_dir.path(),
+            Path::new("guide.md"),
+            "Setup",
+            "Getting Started",
+        )
+        .unwrap();
+        assert_eq!(preview.total_references(), 3);
+        assert_eq!(preview.files_affected(), 2);
+
+        let result = execute_heading_rename(
+            &db,
+            temp_dir.path(),
+            Path::new("guide.md"),
+            "Setup",
+            "Getting Started",
+        )
+        .unwrap();
+        assert_eq!(result.references_updated, 3);
+
+        let guide = fs::read_to_string(temp_dir.path().join("guide.md")).unwrap();
+        assert_eq!(
+            guide,
+            "# Guide\n\nSee [[#Getting Started]].\n\n## Getting Started\n\nSteps.\n"
+        );
+        let source = fs::read_to_string(temp_dir.path().join("source.md")).unwrap();
+        assert_eq!(
+            source,
+            "[[guide#Getting Started|how]] [[guide#Other]] [md](guide.md#getting-started) [[guide]]\n"
+        );
+
+        // The index follows without a reindex
+        assert_eq!(db.count_broken_anchors().unwrap(), 1); // guide#Other
+    }
+
+    #[test]
+    fn test_heading_rename_missing_heading() {
+        let (temp_dir, db) = setup_test_vault();
+        create_note(temp_dir.path(), "guide.md", "# Guide\n");
+        index_vault(&db, temp_dir.path());
+
+        let result = generate_heading_preview(
+            &db,
+            temp_dir.path(),
+            Path::new("guide.md"),
+            "Setup",
+            "Install",
+        );
+        assert!(matches!(result, Err(RenameError::HeadingNotFound { .. })));
+    }
 }
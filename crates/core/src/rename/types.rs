@@ -3,6 +3,8 @@
 use std::path::PathBuf;
 use thiserror::Error;
 
+use crate::index::IndexError;
+
 /// Errors that can occur during rename operations
 #[derive(Debug, Error)]
 pub enum RenameError {
@@ -30,10 +32,13 @@ pub enum RenameError {
     RenameError(#[source] std::io::Error),
 
     #[error("index error: {0}")]
-    IndexError(String),
+    Index(#[from] IndexError),
 
     #[error("note not found in index: {0}")]
     NoteNotInIndex(PathBuf),
+
+    #[error("heading '{heading}' not found in {path}")]
+    HeadingNotFound { path: PathBuf, heading: String },
 }
 
 /// Type of reference found in a file
@@ -43,11 +48,11 @@ pub enum ReferenceType {
     Wikilink,
     /// Wikilink with display alias: [[note|Display Text]]
     WikilinkWithAlias,
-    /// Wikilink with section anchor: [[note#section]]
+    /// Wikilink with section or block anchor: [[note#section]], [[note^block-id]]
     WikilinkWithSection,
     /// Wikilink with both alias and section: [[note#section|Display Text]]
     WikilinkWithSectionAndAlias,
-    /// Standard markdown link: [text](path.md)
+    /// Standard markdown link: [text](path.md) or [text](path.md#section)
     MarkdownLink,
     /// Frontmatter scalar field: project: note-name
     FrontmatterField { field: String },
@@ -74,10 +79,14 @@ pub struct Reference {
     pub ref_type: ReferenceType,
     /// For wikilinks with aliases, the alias text
     pub alias: Option<String>,
-    /// For wikilinks with sections, the section anchor
+    /// The fragment after the target: section text, or `^id` for a block
     pub section: Option<String>,
-    /// The link target as written (may be basename or full path)
+    /// The link target as written, without the fragment (may be basename,
+    /// full path, or empty for same-note links like `[[#section]]`)
     pub target_as_written: String,
+    /// Whether this is an embed (`![[note]]`, `![text](note.md)`); the `!`
+    /// is part of `original` and the byte range
+    pub embed: bool,
 }
 
 impl Reference {
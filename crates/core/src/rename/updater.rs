@@ -2,8 +2,9 @@
 //!
 //! Updates references in files while preserving their original format.
 
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 
+use crate::index::slugify;
 use crate::rename::types::{Reference, ReferenceType};
 
 /// Generate the updated text for a reference.
@@ -11,6 +12,15 @@ use crate::rename::types::{Reference, ReferenceType};
 /// Preserves the original format (wikilink style, alias, section, etc.)
 /// while updating the target path.
 pub fn update_reference(reference: &Reference, new_basename: &str) -> String {
+    embed_prefix(reference, updated_link(reference, new_basename))
+}
+
+/// Keep the `!` of an embed in front of the rewritten link.
+fn embed_prefix(reference: &Reference, link: String) -> String {
+    if reference.embed { format!("!{link}") } else { link }
+}
+
+fn updated_link(reference: &Reference, new_basename: &str) -> String {
     match &reference.ref_type {
         ReferenceType::Wikilink => {
             if reference.uses_full_path() {
@@ -35,32 +45,32 @@ pub fn update_reference(reference: &Reference, new_basename: &str) -> String {
         }
 
         ReferenceType::WikilinkWithSection => {
-            let section = reference.section.as_deref().unwrap_or("");
+            let fragment = fragment_suffix(reference);
             if reference.uses_full_path() {
                 let new_path =
                     update_path_in_reference(&reference.target_as_written, new_basename);
-                format!("[[{}#{}]]", new_path, section)
+                format!("[[{}{}]]", new_path, fragment)
             } else {
-                format!("[[{}#{}]]", new_basename, section)
+                format!("[[{}{}]]", new_basename, fragment)
             }
         }
 
         ReferenceType::WikilinkWithSectionAndAlias => {
-            let section = reference.section.as_deref().unwrap_or("");
+            let fragment = fragment_suffix(reference);
             let alias = reference.alias.as_deref().unwrap_or("");
             if reference.uses_full_path() {
                 let new_path =
                     update_path_in_reference(&reference.target_as_written, new_basename);
-                format!("[[{}#{}|{}]]", new_path, section, alias)
+                format!("[[{}{}|{}]]", new_path, fragment, alias)
             } else {
-                format!("[[{}#{}|{}]]", new_basename, section, alias)
+                format!("[[{}{}|{}]]", new_basename, fragment, alias)
             }
         }
 
         ReferenceType::MarkdownLink => {
             let link_text = reference.alias.as_deref().unwrap_or("");
             let new_url = update_markdown_url(&reference.target_as_written, new_basename);
-            format!("[{}]({})", link_text, new_url)
+            format!("[{}]({}{})", link_text, new_url, fragment_suffix(reference))
         }
 
         ReferenceType::FrontmatterField { .. }
@@ -71,6 +81,93 @@ pub fn update_reference(reference: &Reference, new_basename: &str) -> String {
     }
 }
 
+/// Generate the updated text for a reference when the heading it points to is renamed.
+///
+/// The note part is kept as written; only the fragment changes. Markdown links
+/// get the new heading's slug, since URLs cannot contain spaces.
+pub fn update_reference_section(reference: &Reference, new_heading: &str) -> String {
+    let target = &reference.target_as_written;
+    let link = match &reference.ref_type {
+        ReferenceType::MarkdownLink => {
+            let link_text = reference.alias.as_deref().unwrap_or("");
+            format!("[{}]({}#{})", link_text, target, slugify(new_heading))
+        }
+        _ => match &reference.alias {
+            Some(alias) => format!("[[{}#{}|{}]]", target, new_heading, alias),
+            None => format!("[[{}#{}]]", target, new_heading),
+        },
+    };
+    embed_prefix(reference, link)
+}
+
+/// The `#section` or `^block` suffix of a reference, as originally written.
+fn fragment_suffix(reference: &Reference) -> String {
+    match reference.section.as_deref() {
+        // Block shorthand: [[note^id]]
+        Some(s) if s.starts_with('^') && !reference.original.contains('#') => {
+            s.to_string()
+        }
+        Some(s) => format!("#{}", s),
+        None => String::new(),
+    }
+}
+
+/// Replace the text of the first ATX heading whose slug matches `old_heading`.
+///
+/// Headings inside fenced code blocks are skipped. Returns the new content and
+/// the 1-based line of the heading, or `None` if no heading matches.
+pub fn rename_heading_line(
+    content: &str,
+    old_heading: &str,
+    new_heading: &str,
+) -> Option<(String, u32)> {
+    let old_slug = slugify(old_heading);
+    let mut fence: Option<&str> = None;
+    let mut offset = 0;
+
+    for (idx, line) in content.split_inclusive('\n').enumerate() {
+        let start = offset;
+        offset += line.len();
+        let trimmed = line.trim_start();
+
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            continue;
+        }
+
+        let hashes = trimmed.len() - trimmed.trim_start_matches('#').len();
+        let text = &trimmed[hashes..];
+        if !(1..=6).contains(&hashes) || !text.starts_with(' ') {
+            continue;
+        }
+        if slugify(text.trim().trim_end_matches('#')) != old_slug {
+            continue;
+        }
+
+        let indent = &line[..line.len() - trimmed.len()];
+        let ending = &line[line.trim_end_matches(['\r', '\n']).len()..];
+        let mut result = String::with_capacity(content.len());
+        result.push_str(&content[..start]);
+        result.push_str(&format!(
+            "{}{} {}{}",
+            indent,
+            &trimmed[..hashes],
+            new_heading,
+            ending
+        ));
+        result.push_str(&content[offset..]);
+        return Some((result, (idx + 1) as u32));
+    }
+
+    None
+}
+
 /// Update a path-style reference, preserving the directory structure.
 fn update_path_in_reference(original: &str, new_basename: &str) -> String {
     if let Some(slash_pos) = original.rfind('/') {
@@ -119,6 +216,78 @@ fn update_path_portion(path: &str, new_basename: &str) -> String {
     }
 }
 
+/// Point a path-style reference at the folder its target moves to.
+///
+/// Only the folder changes here; [`update_reference`] then swaps the basename.
+/// Wikilinks with a path and markdown URLs written from the vault root get the
+/// new folder, markdown URLs relative to the linking note are recomputed from
+/// it. Basename-only references resolve wherever the note lives and are kept.
+pub fn rebase_reference(
+    reference: &mut Reference,
+    old_rel: &Path,
+    new_rel: &Path,
+    vault_root: &Path,
+) {
+    let old_dir = old_rel.parent().unwrap_or(Path::new(""));
+    let new_dir = new_rel.parent().unwrap_or(Path::new(""));
+    if old_dir == new_dir || reference.is_frontmatter() {
+        return;
+    }
+
+    let written = reference.target_as_written.as_str();
+    let rebased = if reference.is_markdown_link() {
+        let source_rel = reference
+            .source_path
+            .strip_prefix(vault_root)
+            .unwrap_or(&reference.source_path);
+        let source_dir = source_rel.parent().unwrap_or(Path::new(""));
+        let from_source = normalize(&source_dir.join(written));
+        if written.starts_with("./")
+            || written.starts_with("../")
+            || !written.contains('/')
+        {
+            if !same_path(&from_source, old_rel) {
+                return;
+            }
+            compute_relative_path(
+                &reference.source_path,
+                old_rel,
+                &vault_root.join(new_rel),
+                vault_root,
+            )
+        } else if same_path(Path::new(written), old_rel) {
+            new_rel.to_string_lossy().to_string()
+        } else {
+            return;
+        }
+    } else if reference.uses_full_path() {
+        new_rel.to_string_lossy().to_string()
+    } else {
+        return;
+    };
+    reference.target_as_written = rebased;
+}
+
+/// Resolve `.` and `..` components without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                result.pop();
+            }
+            other => result.push(other),
+        }
+    }
+    result
+}
+
+/// Compare vault paths the way the link detector does: ignoring case.
+fn same_path(a: &Path, b: &Path) -> bool {
+    a.to_string_lossy().to_lowercase() == b.to_string_lossy().to_lowercase()
+}
+
 /// Apply reference updates to file content.
 ///
 /// References must be sorted by start position (will be processed in reverse order).
@@ -129,7 +298,7 @@ pub fn apply_updates(
 ) -> String {
     // Sort references by start position (descending) to apply from end to start
     let mut sorted_refs: Vec<_> = references.iter().collect();
-    sorted_refs.sort_by(|a, b| b.start.cmp(&a.start));
+    sorted_refs.sort_by_key(|r| std::cmp::Reverse(r.start));
 
     let mut result = content.to_string();
 
@@ -145,10 +314,30 @@ pub fn apply_updates(
     result
 }
 
+/// Apply heading-rename updates to file content.
+pub fn apply_section_updates(
+    content: &str,
+    references: &[Reference],
+    new_heading: &str,
+) -> String {
+    let mut sorted_refs: Vec<_> = references.iter().collect();
+    sorted_refs.sort_by_key(|r| std::cmp::Reverse(r.start));
+
+    let mut result = content.to_string();
+
+    for reference in sorted_refs {
+        let replacement = update_reference_section(reference, new_heading);
+        if reference.start <= result.len() && reference.end <= result.len() {
+            result.replace_range(reference.start..reference.end, &replacement);
+        }
+    }
+
+    result
+}
+
 /// Compute the new relative path for a markdown link when the target moves.
 ///
 /// This handles the case where we need to recalculate relative paths.
-#[allow(dead_code)]
 pub fn compute_relative_path(
     source_path: &Path,
     _old_target: &Path,
@@ -225,6 +414,7 @@ mod tests {
             alias: None,
             section: None,
             target_as_written: extract_target(original),
+            embed: false,
         }
     }
 
@@ -277,6 +467,84 @@ mod tests {
         assert_eq!(result, "[[new-note#section]]");
     }
 
+    #[test]
+    fn test_update_block_reference_keeps_style() {
+        let mut shorthand = make_reference(
+            "[[old-note^intro]]",
+            ReferenceType::WikilinkWithSection,
+            0,
+            18,
+        );
+        shorthand.target_as_written = "old-note".to_string();
+        shorthand.section = Some("^intro".to_string());
+        assert_eq!(update_reference(&shorthand, "new-note"), "[[new-note^intro]]");
+
+        let mut hashed = make_reference(
+            "[[old-note#^intro]]",
+            ReferenceType::WikilinkWithSection,
+            0,
+            19,
+        );
+        hashed.section = Some("^intro".to_string());
+        assert_eq!(update_reference(&hashed, "new-note"), "[[new-note#^intro]]");
+    }
+
+    #[test]
+    fn test_update_markdown_link_keeps_fragment() {
+        let mut reference = make_reference(
+            "[t](./old-note.md#setup)",
+            ReferenceType::MarkdownLink,
+            0,
+            24,
+        );
+        reference.alias = Some("t".to_string());
+        reference.target_as_written = "./old-note.md".to_string();
+        reference.section = Some("setup".to_string());
+
+        assert_eq!(update_reference(&reference, "new-note"), "[t](./new-note.md#setup)");
+    }
+
+    #[test]
+    fn test_update_reference_section() {
+        let mut wikilink = make_reference(
+            "[[tasks/note#Old|see]]",
+            ReferenceType::WikilinkWithSectionAndAlias,
+            0,
+            22,
+        );
+        wikilink.target_as_written = "tasks/note".to_string();
+        wikilink.alias = Some("see".to_string());
+        assert_eq!(
+            update_reference_section(&wikilink, "New Name"),
+            "[[tasks/note#New Name|see]]"
+        );
+
+        let mut same_note =
+            make_reference("[[#Old]]", ReferenceType::WikilinkWithSection, 0, 8);
+        same_note.target_as_written = String::new();
+        assert_eq!(update_reference_section(&same_note, "New Name"), "[[#New Name]]");
+
+        let mut markdown =
+            make_reference("[x](note.md#old)", ReferenceType::MarkdownLink, 0, 16);
+        markdown.alias = Some("x".to_string());
+        markdown.target_as_written = "note.md".to_string();
+        assert_eq!(
+            update_reference_section(&markdown, "New Name"),
+            "[x](note.md#new-name)"
+        );
+    }
+
+    #[test]
+    fn test_rename_heading_line() {
+        let content = "# Title\r\n\n```\n## Setup\n```\n## Setup ##\nbody\n";
+        let (result, line) =
+            rename_heading_line(content, "setup", "Installation").unwrap();
+
+        assert_eq!(line, 6);
+        assert_eq!(result, "# Title\r\n\n```\n## Setup\n```\n## Installation\nbody\n");
+        assert!(rename_heading_line(content, "Missing", "X").is_none());
+    }
+
     #[test]
     fn test_update_wikilink_preserves_path() {
         let mut reference =
@@ -330,6 +598,7 @@ mod tests {
             alias: None,
             section: None,
             target_as_written: "old-note".to_string(),
+            embed: false,
         };
 
         let result = update_reference(&reference, "new-note");
@@ -294,7 +294,7 @@ pub(super) fn build_flagged_tasks(
             })
         })
         .collect();
-    overdue.sort_by(|a, b| b.days_overdue.cmp(&a.days_overdue));
+    overdue.sort_by_key(|t| std::cmp::Reverse(t.days_overdue));
 
     // High priority
     let mut high_priority: Vec<FlaggedTask> = tasks
@@ -373,7 +373,7 @@ pub(super) fn build_flagged_tasks(
             })
         })
         .collect();
-    zombie.sort_by(|a, b| b.days_overdue.cmp(&a.days_overdue));
+    zombie.sort_by_key(|t| std::cmp::Reverse(t.days_overdue));
 
     (overdue, high_priority, upcoming_deadlines, zombie)
 }
@@ -414,6 +414,6 @@ pub(super) fn build_review_due(
         })
         .collect();
 
-    due.sort_by(|a, b| b.days_overdue.cmp(&a.days_overdue));
+    due.sort_by_key(|t| std::cmp::Reverse(t.days_overdue));
     due
 }
@@ -13,9 +13,10 @@ mod tests;
 
 use chrono::Utc;
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::index::{IndexDb, IndexedNote, NoteQuery, NoteType};
+use crate::saved_searches::{self, SavedSearch, SavedSearchSummary};
 
 use aggregation::{
     build_activity_report, build_flagged_tasks, build_project_report, build_review_due,
@@ -23,6 +24,9 @@ use aggregation::{
 };
 use helpers::extract_project_info;
 
+/// Notes listed per saved search in a dashboard.
+const SAVED_SEARCH_TOP: usize = 5;
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Schema types
 // ─────────────────────────────────────────────────────────────────────────────
@@ -40,6 +44,9 @@ pub struct DashboardReport {
     pub upcoming_deadlines: Vec<FlaggedTask>,
     pub zombie: Vec<FlaggedTask>,
     pub review_due: Vec<ReviewDueProject>,
+    /// Results of the vault's saved searches.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub saved_searches: Vec<SavedSearchSummary>,
 }
 
 /// A project or area that hasn't been reviewed within its review_interval.
@@ -173,6 +180,8 @@ pub struct DashboardOptions {
     pub stale_threshold: f64,
     /// Minimum days in "todo" status before a task is flagged as zombie (default: 30).
     pub zombie_days: u32,
+    /// Saved searches to run and include (default: none).
+    pub saved_searches: BTreeMap<String, SavedSearch>,
 }
 
 impl Default for DashboardOptions {
@@ -183,6 +192,7 @@ impl Default for DashboardOptions {
             stale_limit: 10,
             stale_threshold: 0.5,
             zombie_days: 30,
+            saved_searches: BTreeMap::new(),
         }
     }
 }
@@ -243,6 +253,10 @@ pub fn build_dashboard(
 
     let review_due = build_review_due(&target_projects, today);
 
+    let saved_searches =
+        saved_searches::summarize(db, &options.saved_searches, SAVED_SEARCH_TOP)
+            .map_err(|e| e.to_string())?;
+
     Ok(DashboardReport {
         generated_at: Utc::now().to_rfc3339(),
         scope,
@@ -254,5 +268,6 @@ pub fn build_dashboard(
         upcoming_deadlines,
         zombie,
         review_due,
+        saved_searches,
     })
 }
@@ -26,6 +26,8 @@ fn make_note(
         modified: Utc::now(),
         frontmatter_json: frontmatter.map(String::from),
         content_hash: "test".to_string(),
+        word_count: 0,
+        heading_json: None,
     }
 }
 
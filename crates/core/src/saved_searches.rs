@@ -0,0 +1,282 @@
+//! Saved searches: named `mdv search` queries.
+//!
+//! Searches are defined in config:
+//!
+//! ```toml
+//! [searches.inbox-overdue]
+//! query = "type:task status:todo due<today"
+//! description = "Open tasks past their due date"
+//! ```
+//!
+//! or in `.mdvault/searches.lua` in the vault:
+//!
+//! ```lua
+//! saved_search "inbox-overdue" {
+//!     query = "type:task status:todo due<today",
+//!     description = "Open tasks past their due date",
+//!     limit = 20,
+//! }
+//! ```
+//!
+//! A Lua definition replaces a config one with the same name. Searches run
+//! with `mdv search --saved <name>` and feed the dashboard report.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::config::types::ResolvedConfig;
+use crate::index::{IndexDb, IndexError, SearchEngine, SearchMode, SearchQuery};
+use crate::paths::PathResolver;
+use crate::scripting::{LuaEngine, ScriptingError};
+
+pub use crate::config::types::SavedSearch;
+
+/// Errors loading or running saved searches.
+#[derive(Debug, Error)]
+pub enum SavedSearchError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to evaluate {path}: {source}")]
+    Lua {
+        path: PathBuf,
+        #[source]
+        source: ScriptingError,
+    },
+
+    #[error("Invalid saved search in {path}: {message}")]
+    Invalid { path: PathBuf, message: String },
+
+    #[error("Unknown saved search '{0}'. Run 'mdv search --list' to see them")]
+    Unknown(String),
+
+    #[error(
+        "Saved search '{name}' has unknown mode '{mode}' \
+         (expected direct, neighbourhood, temporal, cooccurrence or full)"
+    )]
+    InvalidMode { name: String, mode: String },
+
+    #[error("Saved search '{name}' failed: {source}")]
+    Search {
+        name: String,
+        #[source]
+        source: IndexError,
+    },
+}
+
+/// Defines `saved_search "name" { ... }` as a curried call collecting into
+/// `__saved_searches`.
+const LUA_PRELUDE: &str = r#"
+__saved_searches = {}
+function saved_search(name)
+    return function(def)
+        def.name = name
+        __saved_searches[#__saved_searches + 1] = def
+    end
+end
+"#;
+
+/// All saved searches for a vault: config entries, then `.mdvault/searches.lua`.
+pub fn load(
+    config: &ResolvedConfig,
+) -> Result<BTreeMap<String, SavedSearch>, SavedSearchError> {
+    let mut searches = config.searches.clone();
+    let path = PathResolver::new(&config.vault_root).saved_searches();
+    if path.exists() {
+        let source = std::fs::read_to_string(&path)
+            .map_err(|e| SavedSearchError::Io { path: path.clone(), source: e })?;
+        searches.extend(parse_lua(&source, &path)?);
+    }
+    Ok(searches)
+}
+
+/// Look up a saved search by name.
+pub fn get<'a>(
+    searches: &'a BTreeMap<String, SavedSearch>,
+    name: &str,
+) -> Result<&'a SavedSearch, SavedSearchError> {
+    searches.get(name).ok_or_else(|| SavedSearchError::Unknown(name.to_string()))
+}
+
+/// Evaluate a searches file and collect its `saved_search` definitions.
+fn parse_lua(
+    source: &str,
+    path: &Path,
+) -> Result<Vec<(String, SavedSearch)>, SavedSearchError> {
+    let lua_err =
+        |e: ScriptingError| SavedSearchError::Lua { path: path.to_path_buf(), source: e };
+    let engine = LuaEngine::sandboxed().map_err(lua_err)?;
+    let lua = engine.lua();
+    lua.load(LUA_PRELUDE).exec().map_err(|e| lua_err(e.into()))?;
+    lua.load(source).exec().map_err(|e| lua_err(e.into()))?;
+
+    let defs: mlua::Table =
+        lua.globals().get("__saved_searches").map_err(|e| lua_err(e.into()))?;
+    let invalid =
+        |message: String| SavedSearchError::Invalid { path: path.to_path_buf(), message };
+
+    let mut searches = Vec::new();
+    for def in defs.sequence_values::<mlua::Table>() {
+        let def = def.map_err(|e| lua_err(e.into()))?;
+        let name: String =
+            def.get("name").map_err(|_| invalid("saved_search needs a name".into()))?;
+        let query: Option<String> = def
+            .get("query")
+            .map_err(|_| invalid(format!("'{name}': query must be a string")))?;
+        let Some(query) = query else {
+            return Err(invalid(format!("'{name}' has no query")));
+        };
+        let search = SavedSearch {
+            query,
+            description: def.get("description").map_err(|_| {
+                invalid(format!("'{name}': description must be a string"))
+            })?,
+            mode: def
+                .get("mode")
+                .map_err(|_| invalid(format!("'{name}': mode must be a string")))?,
+            limit: def
+                .get("limit")
+                .map_err(|_| invalid(format!("'{name}': limit must be a number")))?,
+        };
+        searches.push((name, search));
+    }
+    Ok(searches)
+}
+
+impl SavedSearch {
+    /// The search to run for this definition.
+    pub fn to_query(&self, name: &str) -> Result<SearchQuery, SavedSearchError> {
+        let mode = match self.mode.as_deref().map(str::to_lowercase).as_deref() {
+            None | Some("direct") => SearchMode::Direct,
+            Some("neighbourhood" | "neighborhood") => {
+                SearchMode::Neighbourhood { hops: 2 }
+            }
+            Some("temporal") => SearchMode::Temporal { days: 30 },
+            Some("cooccurrence") => SearchMode::Cooccurrence { min_shared: 2 },
+            Some("full") => SearchMode::Full,
+            Some(other) => {
+                return Err(SavedSearchError::InvalidMode {
+                    name: name.to_string(),
+                    mode: other.to_string(),
+                });
+            }
+        };
+        Ok(SearchQuery {
+            text: Some(self.query.clone()),
+            mode,
+            limit: self.limit,
+            fuzzy: true,
+            ..Default::default()
+        })
+    }
+}
+
+/// A saved search's result count and first few notes, for reports.
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedSearchSummary {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub count: usize,
+    pub notes: Vec<SavedSearchNote>,
+}
+
+/// A note matched by a saved search.
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedSearchNote {
+    pub path: String,
+    pub title: String,
+}
+
+/// Run every saved search, keeping up to `top` notes of each.
+pub fn summarize(
+    db: &IndexDb,
+    searches: &BTreeMap<String, SavedSearch>,
+    top: usize,
+) -> Result<Vec<SavedSearchSummary>, SavedSearchError> {
+    let engine = SearchEngine::new(db);
+    let mut summaries = Vec::new();
+    for (name, search) in searches {
+        let query = search.to_query(name)?;
+        let results = engine
+            .search(&query)
+            .map_err(|e| SavedSearchError::Search { name: name.clone(), source: e })?;
+        summaries.push(SavedSearchSummary {
+            name: name.clone(),
+            description: search.description.clone(),
+            count: results.len(),
+            notes: results
+                .iter()
+                .take(top)
+                .map(|r| SavedSearchNote {
+                    path: r.note.path.to_string_lossy().to_string(),
+                    title: r.note.title.clone(),
+                })
+                .collect(),
+        });
+    }
+    Ok(summaries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lua_definitions() {
+        let source = r#"
+            saved_search "inbox-overdue" {
+                query = "type:task status:todo due<today",
+                description = "Overdue",
+                limit = 5,
+            }
+            saved_search "ideas" { query = "type:zettel", mode = "full" }
+        "#;
+        let searches = parse_lua(source, Path::new("searches.lua")).unwrap();
+        assert_eq!(searches.len(), 2);
+        assert_eq!(searches[0].0, "inbox-overdue");
+        assert_eq!(searches[0].1.query, "type:task status:todo due<today");
+        assert_eq!(searches[0].1.description.as_deref(), Some("Overdue"));
+        assert_eq!(searches[0].1.limit, Some(5));
+        assert_eq!(searches[1].1.mode.as_deref(), Some("full"));
+    }
+
+    #[test]
+    fn lua_definitions_need_a_query() {
+        let err = parse_lua(r#"saved_search "x" { limit = 3 }"#, Path::new("s.lua"))
+            .unwrap_err();
+        assert!(err.to_string().contains("'x' has no query"), "{err}");
+
+        let err = parse_lua("saved_search(", Path::new("s.lua")).unwrap_err();
+        assert!(matches!(err, SavedSearchError::Lua { .. }));
+    }
+
+    #[test]
+    fn modes_map_to_search_modes() {
+        let mut search = SavedSearch { query: "x".into(), ..Default::default() };
+        assert_eq!(search.to_query("s").unwrap().mode, SearchMode::Direct);
+        search.mode = Some("Neighbourhood".into());
+        assert_eq!(
+            search.to_query("s").unwrap().mode,
+            SearchMode::Neighbourhood { hops: 2 }
+        );
+        search.mode = Some("sideways".into());
+        assert!(matches!(
+            search.to_query("s"),
+            Err(SavedSearchError::InvalidMode { .. })
+        ));
+    }
+
+    #[test]
+    fn unknown_names_are_errors() {
+        let searches = BTreeMap::new();
+        assert!(matches!(get(&searches, "nope"), Err(SavedSearchError::Unknown(_))));
+    }
+}
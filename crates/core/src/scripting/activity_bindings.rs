@@ -0,0 +1,242 @@
+//! Activity log bindings for Lua.
+//!
+//! This module provides Lua bindings for the vault's activity log
+//! (`.mdvault/activity.jsonl`):
+//! - `mdv.log_activity(event, details?)` - Append an entry
+//! - `mdv.activity_since(since, filter?)` - Read the entries logged since a date
+
+use std::path::{Path, PathBuf};
+
+use mlua::{Function, Lua, Result as LuaResult, Table, Value};
+
+use super::hook_runner::lua_value_to_yaml;
+use super::index_bindings::parse_query_date;
+use super::vault_context::VaultContext;
+use crate::activity::{ActivityEntry, ActivityLogService, Operation};
+use crate::types::validation::yaml_to_lua_table;
+
+/// Register activity log bindings on an existing mdv table.
+///
+/// This adds `mdv.log_activity()` and `mdv.activity_since()`, which use the
+/// vault and activity settings of the [`VaultContext`] in app data.
+pub fn register_activity_bindings(lua: &Lua) -> LuaResult<()> {
+    let mdv: Table = lua.globals().get("mdv")?;
+
+    mdv.set("log_activity", create_log_activity_fn(lua)?)?;
+    mdv.set("activity_since", create_activity_since_fn(lua)?)?;
+
+    Ok(())
+}
+
+/// Create the `mdv.log_activity(event, details?)` function.
+///
+/// Appends a `custom` entry named `event`. `details` may set the `path`,
+/// `type` and `id` of the note the entry is about; its other fields become
+/// the entry's metadata. Returns true if the entry was written, false if
+/// activity logging is off or leaves out `custom` entries.
+///
+/// # Examples (in Lua)
+///
+/// ```lua
+/// mdv.log_activity("workout", { minutes = 30, kind = "run" })
+/// mdv.log_activity("reviewed", { path = note.path, type = note.type })
+/// ```
+fn create_log_activity_fn(lua: &Lua) -> LuaResult<Function> {
+    lua.create_function(|lua, (event, details): (String, Option<Table>)| {
+        let ctx = lua
+            .app_data_ref::<VaultContext>()
+            .ok_or_else(|| mlua::Error::runtime("VaultContext not available"))?;
+
+        let entry = entry_from_lua(&event, details.as_ref(), &ctx.vault_root)?;
+        let Some(service) = ActivityLogService::try_from_config(&ctx.config) else {
+            return Ok(false);
+        };
+        if !service.should_log(entry.op) {
+            return Ok(false);
+        }
+        service.log(entry).map_err(|e| mlua::Error::runtime(e.to_string()))?;
+        Ok(true)
+    })
+}
+
+/// Create the `mdv.activity_since(since, filter?)` function.
+///
+/// Returns the entries logged at or after `since` (`YYYY-MM-DD`, RFC 3339,
+/// or a date expression like `today - 7d`), oldest first. `filter` may
+/// narrow them by `op`, `type` and `event`. Entries older than the log's
+/// retention period have been rotated out and are not returned.
+///
+/// Each entry has `ts`, `op`, `type` and `meta`, plus `path`, `id` and
+/// `event` when it has them.
+///
+/// # Examples (in Lua)
+///
+/// ```lua
+/// local done = mdv.activity_since("today - 7d", { op = "complete", type = "task" })
+/// print(#done .. " tasks done this week")
+/// ```
+fn create_activity_since_fn(lua: &Lua) -> LuaResult<Function> {
+    lua.create_function(|lua, (since, filter): (String, Option<Table>)| {
+        let ctx = lua
+            .app_data_ref::<VaultContext>()
+            .ok_or_else(|| mlua::Error::runtime("VaultContext not available"))?;
+
+        let since = parse_query_date("since", &since)?;
+        let field = |key: &str| -> LuaResult<Option<String>> {
+            match &filter {
+                Some(filter) => filter.get(key).map_err(|_| {
+                    mlua::Error::runtime(format!("filter.{key} must be a string"))
+                }),
+                None => Ok(None),
+            }
+        };
+        let (op, note_type, event) = (field("op")?, field("type")?, field("event")?);
+
+        let service =
+            ActivityLogService::new(&ctx.vault_root, ctx.config.activity.clone());
+        let entries = service
+            .read_entries(Some(since), None)
+            .map_err(|e| mlua::Error::runtime(e.to_string()))?;
+
+        let result = lua.create_table()?;
+        for entry in entries.iter().filter(|entry| {
+            op.as_ref().is_none_or(|op| entry.op.to_string() == *op)
+                && note_type.as_ref().is_none_or(|t| entry.note_type == *t)
+                && event.as_ref().is_none_or(|e| entry_event(entry) == Some(e.as_str()))
+        }) {
+            result.push(entry_to_lua(lua, entry)?)?;
+        }
+        Ok(result)
+    })
+}
+
+/// The name a script gave a `custom` entry.
+fn entry_event(entry: &ActivityEntry) -> Option<&str> {
+    match entry.op {
+        Operation::Custom => entry.meta.get("event").and_then(|e| e.as_str()),
+        _ => None,
+    }
+}
+
+/// Build the entry for `mdv.log_activity(event, details)`.
+fn entry_from_lua(
+    event: &str,
+    details: Option<&Table>,
+    vault_root: &Path,
+) -> LuaResult<ActivityEntry> {
+    if event.trim().is_empty() {
+        return Err(mlua::Error::runtime("mdv.log_activity needs an event name"));
+    }
+
+    let mut path = PathBuf::new();
+    let mut note_type = "custom".to_string();
+    let mut id = String::new();
+    let mut meta = Vec::new();
+    for pair in details.iter().flat_map(|t| t.pairs::<String, Value>()) {
+        let (key, value) = pair?;
+        let text = || -> LuaResult<String> {
+            match &value {
+                Value::String(s) => Ok(s.to_str()?.to_string()),
+                _ => Err(mlua::Error::runtime(format!("{key} must be a string"))),
+            }
+        };
+        match key.as_str() {
+            "path" => {
+                let full = PathBuf::from(text()?);
+                path =
+                    full.strip_prefix(vault_root).map(Path::to_path_buf).unwrap_or(full);
+            }
+            "type" => note_type = text()?,
+            "id" => id = text()?,
+            _ => {
+                let yaml = lua_value_to_yaml(value)
+                    .map_err(|e| mlua::Error::runtime(e.to_string()))?;
+                let json = serde_json::to_value(yaml)
+                    .map_err(|e| mlua::Error::runtime(format!("{key}: {e}")))?;
+                meta.push((key, json));
+            }
+        }
+    }
+
+    let mut entry = ActivityEntry::new(Operation::Custom, note_type, path).with_id(id);
+    entry.meta.extend(meta);
+    Ok(entry.with_meta("event", event).with_meta("source", "lua"))
+}
+
+/// Convert an entry to the table `mdv.activity_since` returns.
+fn entry_to_lua(lua: &Lua, entry: &ActivityEntry) -> LuaResult<Table> {
+    let table = lua.create_table()?;
+    table.set("ts", entry.ts.to_rfc3339())?;
+    table.set("op", entry.op.to_string())?;
+    table.set("type", entry.note_type.as_str())?;
+    if !entry.id.is_empty() {
+        table.set("id", entry.id.as_str())?;
+    }
+    if !entry.path.as_os_str().is_empty() {
+        table.set("path", entry.path.to_string_lossy().to_string())?;
+    }
+    if let Some(event) = entry_event(entry) {
+        table.set("event", event)?;
+    }
+
+    let meta = serde_yaml::to_value(&entry.meta)
+        .map_err(|e| mlua::Error::runtime(e.to_string()))?;
+    let meta = match yaml_to_lua_table(lua, &meta)? {
+        Value::Nil => Value::Table(lua.create_table()?),
+        meta => meta,
+    };
+    table.set("meta", meta)?;
+    Ok(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scripting::LuaEngine;
+
+    #[test]
+    fn test_entry_from_lua() {
+        let engine = LuaEngine::sandboxed().unwrap();
+        let details: Table = engine
+            .lua()
+            .load(r#"{ path = "/vault/Notes/a.md", type = "zettel", minutes = 30, tags = { "x" } }"#)
+            .eval()
+            .unwrap();
+        let entry =
+            entry_from_lua("workout", Some(&details), Path::new("/vault")).unwrap();
+
+        assert_eq!(entry.op, Operation::Custom);
+        assert_eq!(entry.note_type, "zettel");
+        assert_eq!(entry.path, PathBuf::from("Notes/a.md"));
+        assert_eq!(entry_event(&entry), Some("workout"));
+        assert_eq!(entry.meta["minutes"], serde_json::json!(30));
+        assert_eq!(entry.meta["tags"], serde_json::json!(["x"]));
+        assert!(!entry.meta.contains_key("path"));
+
+        assert!(entry_from_lua(" ", None, Path::new("/vault")).is_err());
+        let bad: Table = engine.lua().load("{ type = 3 }").eval().unwrap();
+        assert!(entry_from_lua("x", Some(&bad), Path::new("/vault")).is_err());
+    }
+
+    #[test]
+    fn test_entry_to_lua() {
+        let engine = LuaEngine::sandboxed().unwrap();
+        let lua = engine.lua();
+        let entry = entry_from_lua("streak", None, Path::new("/vault")).unwrap();
+        let table = entry_to_lua(lua, &entry).unwrap();
+
+        assert_eq!(table.get::<String>("op").unwrap(), "custom");
+        assert_eq!(table.get::<String>("type").unwrap(), "custom");
+        assert_eq!(table.get::<String>("event").unwrap(), "streak");
+        assert!(table.get::<Option<String>>("path").unwrap().is_none());
+        let meta: Table = table.get("meta").unwrap();
+        assert_eq!(meta.get::<String>("source").unwrap(), "lua");
+
+        let done = ActivityEntry::new(Operation::Complete, "task", "Tasks/t.md")
+            .with_id("TST-001");
+        let table = entry_to_lua(lua, &done).unwrap();
+        assert_eq!(table.get::<String>("id").unwrap(), "TST-001");
+        assert!(table.get::<Option<String>>("event").unwrap().is_none());
+        assert_eq!(table.get::<Table>("meta").unwrap().len().unwrap(), 0);
+    }
+}
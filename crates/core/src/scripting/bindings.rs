@@ -3,9 +3,17 @@
 //! This module provides the `mdv` global table with bindings to
 //! mdvault's date math and template rendering engines.
 
-use mlua::{Function, Lua, Result as LuaResult, Table, Value};
+use mlua::{Function, Lua, MultiValue, Result as LuaResult, Table, Value};
 use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
+use super::stdlib_bindings::register_stdlib_bindings;
+use super::types::trust_scripts;
+use super::vault_context::VaultContext;
+use crate::activity::ActivityLogService;
+use crate::config::loader::is_http_url;
 use crate::templates::engine::{RenderContext, render_string};
 use crate::vars::datemath::{evaluate_date_expr, is_date_expr, parse_date_expr};
 
@@ -15,15 +23,23 @@ use crate::vars::datemath::{evaluate_date_expr, is_date_expr, parse_date_expr};
 /// - `mdv.date(expr, format?)` - Evaluate date math expressions
 /// - `mdv.render(template, context)` - Render templates with variables
 /// - `mdv.is_date_expr(str)` - Check if string is a date expression
+/// - `mdv.fetch(url, opts?)` - Make an HTTP request (needs the `net` permission)
+/// - `mdv.shell(cmd, opts?)` - Run a shell command (needs the `shell` permission
+///   and `--trust-scripts`)
+///
+/// along with the `mdv.slug()`, `mdv.path`, `mdv.yaml` and `mdv.string`
+/// helpers (see [`register_stdlib_bindings`]).
 pub fn register_mdv_table(lua: &Lua) -> LuaResult<()> {
     let mdv = lua.create_table()?;
 
     mdv.set("date", create_date_fn(lua)?)?;
     mdv.set("render", create_render_fn(lua)?)?;
     mdv.set("is_date_expr", create_is_date_expr_fn(lua)?)?;
+    mdv.set("fetch", create_fetch_fn(lua)?)?;
+    mdv.set("shell", create_shell_fn(lua)?)?;
 
     lua.globals().set("mdv", mdv)?;
-    Ok(())
+    register_stdlib_bindings(lua)
 }
 
 /// Create the `mdv.date(expr, format?)` function.
@@ -90,6 +106,204 @@ fn create_is_date_expr_fn(lua: &Lua) -> LuaResult<Function> {
     lua.create_function(|_, s: String| Ok(is_date_expr(&s)))
 }
 
+/// Create the `mdv.fetch(url, opts?)` function.
+///
+/// Requests go through `curl`. `opts` may set `method`, `headers` (a table),
+/// `body` and `timeout` (seconds, default 30). Returns `{ status, body }`,
+/// or `nil, error` if the request could not be made. Only `http://` and
+/// `https://` URLs are fetched, redirects included. Only scripts with the
+/// `net` permission may call it.
+///
+/// # Examples (in Lua)
+///
+/// ```lua
+/// local res, err = mdv.fetch("https://example.com/api", {
+///     method = "POST",
+///     headers = { ["Content-Type"] = "application/json" },
+///     body = '{"ok": true}',
+/// })
+/// if res then print(res.status, res.body) end
+/// ```
+fn create_fetch_fn(lua: &Lua) -> LuaResult<Function> {
+    lua.create_function(|lua, (url, opts): (String, Option<Table>)| {
+        let failed = |message: String| {
+            Ok(MultiValue::from_vec(vec![
+                Value::Nil,
+                Value::String(lua.create_string(message)?),
+            ]))
+        };
+        if !is_http_url(&url) {
+            return failed(format!("only http and https URLs can be fetched: {url}"));
+        }
+
+        let mut cmd = Command::new("curl");
+        cmd.args(["--silent", "--show-error", "--location"]).args([
+            "--proto",
+            "=http,https",
+            "--proto-redir",
+            "=http,https",
+        ]);
+        let mut body = None;
+        let mut timeout = 30;
+        if let Some(opts) = &opts {
+            if let Some(method) = opts.get::<Option<String>>("method")? {
+                cmd.arg("--request").arg(method);
+            }
+            if let Some(headers) = opts.get::<Option<Table>>("headers")? {
+                for pair in headers.pairs::<String, String>() {
+                    let (name, value) = pair?;
+                    cmd.arg("--header").arg(format!("{name}: {value}"));
+                }
+            }
+            body = opts.get::<Option<String>>("body")?;
+            timeout = opts.get::<Option<u64>>("timeout")?.unwrap_or(timeout);
+        }
+        if body.is_some() {
+            cmd.args(["--data-binary", "@-"]);
+        }
+        cmd.arg("--max-time")
+            .arg(timeout.to_string())
+            .args(["--write-out", "\n%{http_code}"])
+            .arg("--url")
+            .arg(&url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => return failed(format!("failed to run curl: {e}")),
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            // A failed write shows up as a curl error below
+            let _ = stdin.write_all(body.unwrap_or_default().as_bytes());
+        }
+        let output = match child.wait_with_output() {
+            Ok(output) => output,
+            Err(e) => return failed(format!("failed to run curl: {e}")),
+        };
+        if !output.status.success() {
+            return failed(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+
+        // The status code is the last line, after the body
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let (body, status) = stdout.rsplit_once('\n').unwrap_or(("", &stdout));
+        let response = lua.create_table()?;
+        response.set("status", status.trim().parse::<u16>().unwrap_or(0))?;
+        response.set("body", body)?;
+        Ok(MultiValue::from_vec(vec![Value::Table(response)]))
+    })
+}
+
+/// Create the `mdv.shell(cmd, opts?)` function.
+///
+/// Runs `cmd` with `sh -c` in the vault root, like a macro's shell step.
+/// `opts` may set `cwd` (relative to the vault root), `env` (a table) and
+/// `stdin`. Returns `{ code, stdout, stderr }` whatever the exit status, or
+/// `nil, error` if the command could not be started. Every run is written to
+/// the activity log.
+///
+/// Besides the `shell` permission, the invocation must carry
+/// `--trust-scripts`: a vault's security policy alone does not let scripts
+/// run commands, just as macro shell steps need `--trust`.
+///
+/// # Examples (in Lua)
+///
+/// ```lua
+/// local res = mdv.shell("git add -A && git commit -m 'notes'")
+/// if res and res.code ~= 0 then print(res.stderr) end
+/// ```
+fn create_shell_fn(lua: &Lua) -> LuaResult<Function> {
+    lua.create_function(|lua, (command, opts): (String, Option<Table>)| {
+        if !trust_scripts() {
+            return Err(mlua::Error::runtime("mdv.shell requires --trust-scripts"));
+        }
+        let root = lua
+            .app_data_ref::<VaultContext>()
+            .map(|ctx| ctx.vault_root.clone())
+            .unwrap_or_default();
+        let mut cwd = root.clone();
+        let mut stdin = None;
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(&command);
+        if let Some(opts) = &opts {
+            if let Some(dir) = opts.get::<Option<String>>("cwd")? {
+                cwd = match resolve_cwd(&root, &dir) {
+                    Ok(cwd) => cwd,
+                    Err(e) => {
+                        return Ok(MultiValue::from_vec(vec![
+                            Value::Nil,
+                            Value::String(lua.create_string(e)?),
+                        ]));
+                    }
+                };
+            }
+            if let Some(env) = opts.get::<Option<Table>>("env")? {
+                for pair in env.pairs::<String, String>() {
+                    let (name, value) = pair?;
+                    cmd.env(name, value);
+                }
+            }
+            stdin = opts.get::<Option<String>>("stdin")?;
+        }
+        if !cwd.as_os_str().is_empty() {
+            cmd.current_dir(&cwd);
+        }
+        cmd.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped());
+
+        let output = cmd.spawn().and_then(|mut child| {
+            if let Some(mut pipe) = child.stdin.take() {
+                // A command that does not read its input closes the pipe early
+                let _ = pipe.write_all(stdin.unwrap_or_default().as_bytes());
+            }
+            child.wait_with_output()
+        });
+        let output = match output {
+            Ok(output) => output,
+            Err(e) => {
+                return Ok(MultiValue::from_vec(vec![
+                    Value::Nil,
+                    Value::String(lua.create_string(format!("failed to run sh: {e}"))?),
+                ]));
+            }
+        };
+
+        if let Some(ctx) = lua.app_data_ref::<VaultContext>()
+            && let Some(activity) = ActivityLogService::try_from_config(&ctx.config)
+        {
+            let _ = activity.log_shell(&command, &cwd, output.status.code());
+        }
+
+        let result = lua.create_table()?;
+        result.set("code", output.status.code())?;
+        result.set("stdout", String::from_utf8_lossy(&output.stdout).into_owned())?;
+        result.set("stderr", String::from_utf8_lossy(&output.stderr).into_owned())?;
+        Ok(MultiValue::from_vec(vec![Value::Table(result)]))
+    })
+}
+
+/// Resolve `mdv.shell`'s `cwd` option against the vault root, refusing
+/// directories outside it (including through `..` or symlinks).
+fn resolve_cwd(root: &Path, dir: &str) -> Result<PathBuf, String> {
+    let base = if root.as_os_str().is_empty() {
+        std::env::current_dir().map_err(|e| e.to_string())?
+    } else {
+        root.to_path_buf()
+    };
+    let canonical_base =
+        base.canonicalize().map_err(|e| format!("{}: {e}", base.display()))?;
+    let cwd = base
+        .join(dir)
+        .canonicalize()
+        .map_err(|e| format!("cwd '{dir}' is not a directory in the vault: {e}"))?;
+    match cwd.strip_prefix(&canonical_base) {
+        // Keep the vault root as given so the activity log can relativize it
+        Ok(rel) => Ok(base.join(rel)),
+        Err(_) => Err(format!("cwd '{dir}' is outside the vault")),
+    }
+}
+
 /// Convert a Lua value to a string for use in template context.
 fn lua_value_to_string(key: &str, value: Value) -> LuaResult<String> {
     match value {
@@ -104,3 +318,44 @@ fn lua_value_to_string(key: &str, value: Value) -> LuaResult<String> {
         ))),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fetch_rejects_other_protocols_and_options() {
+        let lua = Lua::new();
+        let fetch = create_fetch_fn(&lua).unwrap();
+        for url in
+            ["file:///etc/passwd", "-o/tmp/x", "--config=/etc/passwd", "ftp://host/"]
+        {
+            let (res, err): (Value, String) = fetch.call(url).unwrap();
+            assert!(res.is_nil(), "{url}");
+            assert!(err.contains("only http and https URLs"), "{url}: {err}");
+        }
+        assert!(is_http_url("HTTPS://example.com"));
+    }
+
+    #[test]
+    fn test_shell_cwd_stays_in_the_vault() {
+        let tmp = tempfile::tempdir().unwrap();
+        let vault = tmp.path().join("vault");
+        std::fs::create_dir_all(vault.join("Projects")).unwrap();
+
+        assert_eq!(resolve_cwd(&vault, "Projects").unwrap(), vault.join("Projects"));
+        assert_eq!(resolve_cwd(&vault, ".").unwrap(), vault);
+        for dir in ["..", "Projects/../..", "/tmp"] {
+            let err = resolve_cwd(&vault, dir).unwrap_err();
+            assert!(err.contains("outside the vault"), "{dir}: {err}");
+        }
+        assert!(resolve_cwd(&vault, "missing").is_err());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(tmp.path(), vault.join("escape")).unwrap();
+            let err = resolve_cwd(&vault, "escape").unwrap_err();
+            assert!(err.contains("outside the vault"), "{err}");
+        }
+    }
+}
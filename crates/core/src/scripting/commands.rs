@@ -0,0 +1,413 @@
+//! User-defined CLI commands written in Lua.
+//!
+//! Each `.lua` file in the commands directory returns a table:
+//!
+//! ```lua
+//! return {
+//!     name = "standup",
+//!     description = "Summarise yesterday's done tasks",
+//!     args = {
+//!         { name = "since", help = "Start date", default = "yesterday" },
+//!     },
+//!     run = function(ctx)
+//!         local done = mdv.query({ type = "task", limit = 50 })
+//!         return #done .. " tasks since " .. ctx.args.since
+//!     end,
+//! }
+//! ```
+//!
+//! and runs as `mdv standup [--since DATE]`. `run` gets the vault bindings
+//! (`mdv.template`, `mdv.capture`, `mdv.query`, `mdv.read_note`, ...); a
+//! string it returns is printed. A `permissions` table limits or extends
+//! what `run` may do (see [`Permissions`]).
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use mlua::{Function, Table, Value};
+use thiserror::Error;
+
+use super::engine::LuaEngine;
+use super::types::{Permissions, SandboxConfig, ScriptingError};
+use super::vault_context::VaultContext;
+
+/// Errors loading or running user commands.
+#[derive(Debug, Error)]
+pub enum CommandError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to evaluate {path}: {source}")]
+    Lua {
+        path: PathBuf,
+        #[source]
+        source: ScriptingError,
+    },
+
+    #[error("Invalid command in {path}: {message}")]
+    Invalid { path: PathBuf, message: String },
+
+    #[error("Unknown command '{0}'. Run 'mdv --help' to see the built-in commands")]
+    Unknown(String),
+
+    #[error("{0}")]
+    Usage(String),
+
+    #[error("Command '{name}' failed: {source}")]
+    Failed {
+        name: String,
+        #[source]
+        source: ScriptingError,
+    },
+}
+
+/// An argument declared by a user command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandArg {
+    pub name: String,
+    pub help: Option<String>,
+    pub default: Option<String>,
+    pub required: bool,
+}
+
+/// A command defined by a Lua file in the commands directory.
+#[derive(Debug, Clone)]
+pub struct UserCommand {
+    pub name: String,
+    pub description: Option<String>,
+    pub args: Vec<CommandArg>,
+    pub permissions: Permissions,
+    pub path: PathBuf,
+}
+
+/// All commands in `dir`, sorted by name. A missing directory has none.
+pub fn discover(dir: &Path) -> Result<Vec<UserCommand>, CommandError> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(CommandError::Io { path: dir.to_path_buf(), source: e }),
+    };
+
+    let mut commands = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|e| CommandError::Io { path: dir.to_path_buf(), source: e })?
+            .path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "lua") {
+            commands.push(UserCommand::load(&path)?);
+        }
+    }
+    commands.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(commands)
+}
+
+/// Look up a command by name.
+pub fn find(dir: &Path, name: &str) -> Result<UserCommand, CommandError> {
+    discover(dir)?
+        .into_iter()
+        .find(|c| c.name == name)
+        .ok_or_else(|| CommandError::Unknown(name.to_string()))
+}
+
+impl UserCommand {
+    /// Read a command's name, description and arguments from its file.
+    ///
+    /// The name defaults to the file stem.
+    pub fn load(path: &Path) -> Result<Self, CommandError> {
+        let invalid =
+            |message: String| CommandError::Invalid { path: path.to_path_buf(), message };
+        let engine = LuaEngine::sandboxed()
+            .map_err(|e| CommandError::Lua { path: path.to_path_buf(), source: e })?;
+        let def = eval(&engine, path)?;
+
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let name: Option<String> =
+            def.get("name").map_err(|_| invalid("name must be a string".into()))?;
+        let name = name.unwrap_or(stem);
+        if name.is_empty() || name.starts_with('-') || name.contains(char::is_whitespace)
+        {
+            return Err(invalid(format!("'{name}' is not a valid command name")));
+        }
+        if !matches!(def.get::<Value>("run"), Ok(Value::Function(_))) {
+            return Err(invalid(format!("'{name}' has no run function")));
+        }
+
+        let mut args = Vec::new();
+        let decls: Option<Table> = def
+            .get("args")
+            .map_err(|_| invalid(format!("'{name}': args must be a list")))?;
+        for decl in decls.iter().flat_map(|t| t.sequence_values::<Table>()) {
+            let decl =
+                decl.map_err(|_| invalid(format!("'{name}': each arg must be a table")))?;
+            let arg_name: String = decl
+                .get("name")
+                .map_err(|_| invalid(format!("'{name}': every arg needs a name")))?;
+            let field = |key: &str| -> Result<Option<String>, CommandError> {
+                decl.get(key).map_err(|_| {
+                    invalid(format!("'{name}': {key} of '{arg_name}' must be a string"))
+                })
+            };
+            args.push(CommandArg {
+                help: field("help")?,
+                default: field("default")?,
+                required: decl
+                    .get::<Option<bool>>("required")
+                    .ok()
+                    .flatten()
+                    .unwrap_or(false),
+                name: arg_name,
+            });
+        }
+
+        let description = def
+            .get("description")
+            .map_err(|_| invalid(format!("'{name}': description must be a string")))?;
+        let permissions = def.get("permissions").map_err(|e| CommandError::Lua {
+            path: path.to_path_buf(),
+            source: e.into(),
+        })?;
+        let permissions = Permissions::from_lua(permissions)
+            .map_err(|e| invalid(format!("'{name}': {e}")))?;
+        Ok(Self { name, description, args, permissions, path: path.to_path_buf() })
+    }
+
+    /// Match command-line arguments to the declared ones.
+    ///
+    /// Values are given as `--name value`, `--name=value` or positionally in
+    /// declaration order; missing ones take their default.
+    pub fn parse_args(
+        &self,
+        argv: &[String],
+    ) -> Result<BTreeMap<String, String>, CommandError> {
+        let mut values = BTreeMap::new();
+        let mut positional = self.args.iter();
+        let mut argv = argv.iter();
+        while let Some(token) = argv.next() {
+            let (arg, value) = match token.strip_prefix("--") {
+                Some(flag) => {
+                    let (flag, inline) = match flag.split_once('=') {
+                        Some((flag, value)) => (flag, Some(value.to_string())),
+                        None => (flag, None),
+                    };
+                    let arg =
+                        self.args.iter().find(|a| a.name == flag).ok_or_else(|| {
+                            self.usage_error(format!("unknown option '--{flag}'"))
+                        })?;
+                    let value = match inline {
+                        Some(value) => value,
+                        None => argv.next().cloned().ok_or_else(|| {
+                            self.usage_error(format!("'--{flag}' needs a value"))
+                        })?,
+                    };
+                    (arg, value)
+                }
+                None => {
+                    let arg =
+                        positional.find(|a| !values.contains_key(&a.name)).ok_or_else(
+                            || self.usage_error(format!("unexpected argument '{token}'")),
+                        )?;
+                    (arg, token.clone())
+                }
+            };
+            values.insert(arg.name.clone(), value);
+        }
+
+        for arg in &self.args {
+            if values.contains_key(&arg.name) {
+                continue;
+            }
+            match &arg.default {
+                Some(default) => {
+                    values.insert(arg.name.clone(), default.clone());
+                }
+                None if arg.required => {
+                    return Err(self.usage_error(format!("missing '{}'", arg.name)));
+                }
+                None => {}
+            }
+        }
+        Ok(values)
+    }
+
+    /// Usage text for `mdv <name> --help`.
+    pub fn usage(&self) -> String {
+        let mut out = String::new();
+        if let Some(description) = &self.description {
+            out.push_str(description);
+            out.push_str("\n\n");
+        }
+        out.push_str(&format!("Usage: mdv {}", self.name));
+        for arg in &self.args {
+            match arg.required && arg.default.is_none() {
+                true => out.push_str(&format!(" <{}>", arg.name)),
+                false => out.push_str(&format!(" [{}]", arg.name)),
+            }
+        }
+        out.push('\n');
+        if !self.args.is_empty() {
+            out.push_str("\nArguments (positional or --name value):\n");
+        }
+        for arg in &self.args {
+            out.push_str(&format!(
+                "  {:<16}{}",
+                arg.name,
+                arg.help.as_deref().unwrap_or("")
+            ));
+            if let Some(default) = &arg.default {
+                out.push_str(&format!(" [default: {default}]"));
+            }
+            out.push('\n');
+        }
+        out.push_str(&format!("\nDefined in {}\n", self.path.display()));
+        out
+    }
+
+    /// Call the command's `run` function with the vault bindings.
+    ///
+    /// `run` receives `ctx` with `args`, `vault_root` and `profile`; returns
+    /// the string it gives back, if any.
+    pub fn run(
+        &self,
+        vault_ctx: VaultContext,
+        args: &BTreeMap<String, String>,
+    ) -> Result<Option<String>, CommandError> {
+        let failed = |e: ScriptingError| CommandError::Failed {
+            name: self.name.clone(),
+            source: e,
+        };
+        let vault_root = vault_ctx.vault_root.to_string_lossy().to_string();
+        let profile = vault_ctx.config.active_profile.clone();
+        let engine = LuaEngine::with_vault_context(
+            SandboxConfig::restricted().with_permissions(self.permissions),
+            vault_ctx,
+        )
+        .map_err(failed)?;
+        let def = eval(&engine, &self.path)?;
+        let lua = engine.lua();
+
+        let ctx = lua.create_table().map_err(|e| failed(e.into()))?;
+        let arg_table = lua.create_table().map_err(|e| failed(e.into()))?;
+        for (name, value) in args {
+            arg_table.set(name.as_str(), value.as_str()).map_err(|e| failed(e.into()))?;
+        }
+        ctx.set("args", arg_table).map_err(|e| failed(e.into()))?;
+        ctx.set("vault_root", vault_root).map_err(|e| failed(e.into()))?;
+        ctx.set("profile", profile).map_err(|e| failed(e.into()))?;
+
+        let run: Function = def.get("run").map_err(|e| failed(e.into()))?;
+        match run.call::<Value>(ctx).map_err(|e| failed(e.into()))? {
+            Value::Nil => Ok(None),
+            Value::String(s) => Ok(Some(s.to_string_lossy())),
+            other => Err(CommandError::Invalid {
+                path: self.path.clone(),
+                message: format!(
+                    "run returned a {}, expected a string or nil",
+                    other.type_name()
+                ),
+            }),
+        }
+    }
+
+    fn usage_error(&self, message: String) -> CommandError {
+        CommandError::Usage(format!("mdv {}: {message}\n\n{}", self.name, self.usage()))
+    }
+}
+
+/// Evaluate a command file, which must return a table.
+fn eval(engine: &LuaEngine, path: &Path) -> Result<Table, CommandError> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| CommandError::Io { path: path.to_path_buf(), source: e })?;
+    let value: Value =
+        engine.lua().load(&source).set_name(path.to_string_lossy()).eval().map_err(
+            |e| CommandError::Lua { path: path.to_path_buf(), source: e.into() },
+        )?;
+    match value {
+        Value::Table(table) => Ok(table),
+        _ => Err(CommandError::Invalid {
+            path: path.to_path_buf(),
+            message: "the file must return a table".into(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, file: &str, source: &str) {
+        std::fs::write(dir.join(file), source).unwrap();
+    }
+
+    const STANDUP: &str = r#"
+        return {
+            name = "standup",
+            description = "Daily standup",
+            args = {
+                { name = "since", help = "Start date", default = "yesterday" },
+                { name = "team", required = true },
+            },
+            run = function(ctx) return ctx.args.team end,
+        }
+    "#;
+
+    #[test]
+    fn discovers_commands_by_name() {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), "standup.lua", STANDUP);
+        write(dir.path(), "weekly.lua", "return { run = function() end }");
+        write(dir.path(), "notes.md", "# not a command");
+
+        let commands = discover(dir.path()).unwrap();
+        let names: Vec<_> = commands.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["standup", "weekly"]);
+        assert_eq!(commands[0].args.len(), 2);
+        assert!(commands[0].args[1].required);
+
+        assert!(matches!(find(dir.path(), "nope"), Err(CommandError::Unknown(_))));
+        assert!(discover(&dir.path().join("missing")).unwrap().is_empty());
+
+        write(dir.path(), "broken.lua", "return { name = 'broken' }");
+        assert!(matches!(discover(dir.path()), Err(CommandError::Invalid { .. })));
+    }
+
+    #[test]
+    fn reports_errors_reading_permissions() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("odd.lua");
+        write(
+            dir.path(),
+            "odd.lua",
+            r#"return setmetatable({ run = function() end }, { __index = function(_, key)
+                if key == "permissions" then error("no permissions here") end
+            end })"#,
+        );
+        let err = UserCommand::load(&path).unwrap_err();
+        assert!(matches!(err, CommandError::Lua { .. }), "{err:?}");
+    }
+
+    #[test]
+    fn parses_flags_positionals_and_defaults() {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), "standup.lua", STANDUP);
+        let command = find(dir.path(), "standup").unwrap();
+        let args = |argv: &[&str]| {
+            command.parse_args(&argv.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+        };
+
+        let values = args(&["--team", "core"]).unwrap();
+        assert_eq!(values["since"], "yesterday");
+        assert_eq!(values["team"], "core");
+
+        let values = args(&["--since=friday", "core"]).unwrap();
+        assert_eq!(values["since"], "friday");
+        assert_eq!(values["team"], "core");
+
+        assert!(matches!(args(&[]), Err(CommandError::Usage(_))));
+        assert!(matches!(args(&["--bogus", "x"]), Err(CommandError::Usage(_))));
+    }
+}
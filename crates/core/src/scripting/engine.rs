@@ -3,13 +3,17 @@
 //! This module provides a sandboxed Lua execution environment for
 //! running user-defined scripts safely.
 
-use mlua::{Lua, Result as LuaResult, StdLib, Value};
+use mlua::{Function, Lua, MultiValue, Result as LuaResult, StdLib, Table, Value};
 
+use super::activity_bindings::register_activity_bindings;
 use super::bindings::register_mdv_table;
 use super::index_bindings::register_index_bindings;
-use super::types::{SandboxConfig, ScriptingError};
+use super::types::{
+    Capability, Permissions, SandboxConfig, ScriptingError, trust_scripts,
+};
 use super::vault_bindings::register_vault_bindings;
 use super::vault_context::VaultContext;
+use crate::config::types::SecurityPolicy;
 
 /// A sandboxed Lua execution environment.
 ///
@@ -37,10 +41,8 @@ impl LuaEngine {
     pub fn new(config: SandboxConfig) -> Result<Self, ScriptingError> {
         // Create Lua with restricted standard library
         // Note: base functions (print, type, tostring, etc.) are always available
-        // We add: table, string, utf8, math
-        let libs = StdLib::TABLE | StdLib::STRING | StdLib::UTF8 | StdLib::MATH;
-
-        let lua = Lua::new_with(libs, mlua::LuaOptions::default())?;
+        // We add: table, string, utf8, math, and os and io for the shell permission
+        let lua = Lua::new_with(Self::libs(), mlua::LuaOptions::default())?;
 
         // Apply memory limit if configured
         if config.memory_limit > 0 {
@@ -52,8 +54,11 @@ impl LuaEngine {
 
         // Register mdv bindings
         register_mdv_table(&lua)?;
+        Self::guard_bindings(&lua)?;
 
-        Ok(Self { lua, config })
+        let engine = Self { lua, config };
+        engine.set_permissions(engine.config.permissions)?;
+        Ok(engine)
     }
 
     /// Create a new engine with default restrictive sandbox.
@@ -89,8 +94,7 @@ impl LuaEngine {
         vault_ctx: VaultContext,
     ) -> Result<Self, ScriptingError> {
         // Create Lua with restricted standard library
-        let libs = StdLib::TABLE | StdLib::STRING | StdLib::UTF8 | StdLib::MATH;
-        let lua = Lua::new_with(libs, mlua::LuaOptions::default())?;
+        let lua = Lua::new_with(Self::libs(), mlua::LuaOptions::default())?;
 
         // Apply memory limit if configured
         if config.memory_limit > 0 {
@@ -109,7 +113,75 @@ impl LuaEngine {
         // Register index query bindings (uses VaultContext from app_data)
         register_index_bindings(&lua)?;
 
-        Ok(Self { lua, config })
+        // Register activity log bindings (uses VaultContext from app_data)
+        register_activity_bindings(&lua)?;
+        Self::guard_bindings(&lua)?;
+
+        let engine = Self { lua, config };
+        engine.set_permissions(engine.config.permissions)?;
+        Ok(engine)
+    }
+
+    /// Change what scripts in this engine may do, e.g. before calling a
+    /// handler that declared its own `permissions`.
+    ///
+    /// `shell` and `net` are only granted if the vault's security policy
+    /// (or `--trust-scripts`) allows them; without a vault context only
+    /// `--trust-scripts` does.
+    pub fn set_permissions(
+        &self,
+        permissions: Permissions,
+    ) -> Result<(), ScriptingError> {
+        let permissions = match self.lua.app_data_ref::<VaultContext>() {
+            Some(ctx) => permissions.within(&ctx.config.security),
+            None => permissions.within(&SecurityPolicy::default()),
+        };
+        self.lua.set_app_data(permissions);
+
+        // The shell permission hands back the libraries the sandbox removed,
+        // but only --trust-scripts lets them start other programs
+        let globals = self.lua.globals();
+        for lib in SHELL_LIBS {
+            let value = if permissions.shell {
+                self.lua
+                    .named_registry_value::<Value>(&stash_key(lib, trust_scripts()))?
+            } else {
+                Value::Nil
+            };
+            globals.set(lib, value)?;
+        }
+        Ok(())
+    }
+
+    /// The permissions scripts currently run with.
+    pub fn permissions(&self) -> Permissions {
+        self.lua.app_data_ref::<Permissions>().map(|p| *p).unwrap_or_default()
+    }
+
+    fn libs() -> StdLib {
+        StdLib::TABLE
+            | StdLib::STRING
+            | StdLib::UTF8
+            | StdLib::MATH
+            | StdLib::OS
+            | StdLib::IO
+    }
+
+    /// Wrap the `mdv` functions that need a permission in a check of the
+    /// engine's current permissions.
+    fn guard_bindings(lua: &Lua) -> LuaResult<()> {
+        let mdv: Table = lua.globals().get("mdv")?;
+        for &(name, capability) in GUARDED {
+            let Some(inner) = mdv.get::<Option<Function>>(name)? else {
+                continue;
+            };
+            let guarded = lua.create_function(move |lua, args: MultiValue| {
+                require(lua, capability, &format!("mdv.{name}"))?;
+                inner.call::<MultiValue>(args)
+            })?;
+            mdv.set(name, guarded)?;
+        }
+        Ok(())
     }
 
     /// Execute a Lua script and return the result.
@@ -166,6 +238,24 @@ impl LuaEngine {
         // - Inspect/modify internals: debug
         // - Cause resource exhaustion: collectgarbage
 
+        // Kept aside for scripts granted the shell permission, whole for
+        // trusted scripts and without PROCESS_FNS for the rest
+        for lib in SHELL_LIBS {
+            let full: Table = globals.get(lib)?;
+            let limited = lua.create_table()?;
+            for pair in full.pairs::<Value, Value>() {
+                let (key, value) = pair?;
+                limited.set(key, value)?;
+            }
+            for &(owner, name) in PROCESS_FNS {
+                if owner == lib {
+                    limited.set(name, Value::Nil)?;
+                }
+            }
+            lua.set_named_registry_value(&stash_key(lib, true), full)?;
+            lua.set_named_registry_value(&stash_key(lib, false), limited)?;
+        }
+
         globals.set("dofile", Value::Nil)?;
         globals.set("loadfile", Value::Nil)?;
         globals.set("load", Value::Nil)?;
@@ -180,6 +270,52 @@ impl LuaEngine {
     }
 }
 
+/// Standard libraries only scripts with the shell permission see.
+const SHELL_LIBS: [&str; 2] = ["os", "io"];
+
+/// Functions in [`SHELL_LIBS`] that start or end processes, which need
+/// `--trust-scripts` on top of the shell permission.
+const PROCESS_FNS: &[(&str, &str)] =
+    &[("os", "execute"), ("os", "exit"), ("io", "popen")];
+
+fn stash_key(lib: &str, trusted: bool) -> String {
+    if trusted {
+        format!("mdvault.stdlib.{lib}")
+    } else {
+        format!("mdvault.stdlib.{lib}.limited")
+    }
+}
+
+/// `mdv` functions and the permission each needs.
+const GUARDED: &[(&str, Capability)] = &[
+    ("read_note", Capability::ReadVault),
+    ("selector", Capability::ReadVault),
+    ("backlinks", Capability::ReadVault),
+    ("links", Capability::ReadVault),
+    ("outlinks", Capability::ReadVault),
+    ("query", Capability::ReadVault),
+    ("find_project", Capability::ReadVault),
+    ("activity_since", Capability::ReadVault),
+    ("write_note", Capability::WriteVault),
+    ("set_frontmatter", Capability::WriteVault),
+    ("capture", Capability::WriteVault),
+    ("macro", Capability::WriteVault),
+    ("log_activity", Capability::WriteVault),
+    ("fetch", Capability::Net),
+    ("shell", Capability::Shell),
+];
+
+/// Fail unless the running script has `capability`; `what` names the
+/// operation in the error.
+pub(crate) fn require(lua: &Lua, capability: Capability, what: &str) -> LuaResult<()> {
+    let permissions = lua.app_data_ref::<Permissions>().map(|p| *p).unwrap_or_default();
+    if permissions.allows(capability) {
+        Ok(())
+    } else {
+        Err(mlua::Error::runtime(capability.denied(what)))
+    }
+}
+
 impl Default for LuaEngine {
     fn default() -> Self {
         Self::sandboxed().expect("failed to create default Lua engine")
@@ -385,4 +521,61 @@ mod tests {
         let result = engine.eval(r#"local x = 1"#).unwrap();
         assert!(result.is_none());
     }
+
+    #[test]
+    fn test_permissions_from_lua() {
+        let engine = LuaEngine::sandboxed().unwrap();
+        let parse = |src: &str| {
+            Permissions::from_lua(engine.lua().load(src).eval::<Value>().unwrap())
+        };
+
+        assert_eq!(parse("nil").unwrap(), Permissions::default());
+        let p = parse("{ write_vault = false, net = true }").unwrap();
+        assert!(p.read_vault && !p.write_vault && !p.shell && p.net);
+        assert!(parse("{ disk = true }").unwrap_err().contains("unknown permission"));
+        assert!(parse("{ net = 'yes' }").unwrap_err().contains("true or false"));
+    }
+
+    #[test]
+    fn test_shell_and_net_need_security_policy() {
+        let wanted = Permissions::all();
+        let masked = wanted.within(&SecurityPolicy::default());
+        assert!(masked.read_vault && masked.write_vault);
+        assert!(!masked.shell && !masked.net);
+
+        let policy =
+            SecurityPolicy { allow_shell: true, allow_http: true, ..Default::default() };
+        assert_eq!(wanted.within(&policy), wanted);
+
+        let preview = wanted.read_only();
+        assert!(preview.read_vault);
+        assert!(!preview.write_vault && !preview.shell && !preview.net);
+    }
+
+    #[test]
+    fn test_sandbox_hides_os_and_denies_fetch() {
+        let engine = LuaEngine::sandboxed().unwrap();
+        assert!(engine.eval_bool("os == nil and io == nil").unwrap());
+        let err = engine.eval(r#"mdv.fetch("https://example.com")"#).unwrap_err();
+        assert!(err.to_string().contains("mdv.fetch is not permitted"), "{err}");
+
+        // Declaring shell is not enough without the security policy
+        engine.set_permissions(Permissions::all()).unwrap();
+        assert!(!engine.permissions().shell);
+        assert!(engine.eval_bool("os == nil").unwrap());
+        let err = engine.eval(r#"mdv.shell("true")"#).unwrap_err();
+        assert!(err.to_string().contains("mdv.shell is not permitted"), "{err}");
+    }
+
+    #[test]
+    fn test_set_permissions_limits_vault_access() {
+        let engine = LuaEngine::sandboxed().unwrap();
+        let denied = Permissions { write_vault: false, ..Permissions::default() };
+        engine.set_permissions(denied).unwrap();
+        assert_eq!(engine.permissions(), denied);
+        let err =
+            require(engine.lua(), Capability::WriteVault, "mdv.write_note").unwrap_err();
+        assert!(err.to_string().contains("write_vault = true"), "{err}");
+        assert!(require(engine.lua(), Capability::ReadVault, "mdv.query").is_ok());
+    }
 }
@@ -0,0 +1,186 @@
+//! Vault event hooks from the hooks directory.
+//!
+//! Each `.lua` file in `hooks_dir` returns a table of handlers:
+//!
+//! ```lua
+//! return {
+//!     on_rename = function(old_path, new_path)
+//!         mdv.capture("log-to-daily", { text = old_path .. " -> " .. new_path })
+//!     end,
+//! }
+//! ```
+//!
+//! Handlers run after the change is on disk and in the index, in file name
+//! order. With a [`VaultContext`] they get the vault bindings (`mdv.query`,
+//! `mdv.write_note`, ...); a failing handler does not undo the change.
+//! A script's `permissions` table applies to its handlers.
+
+use std::path::{Path, PathBuf};
+
+use mlua::{Function, Value};
+use thiserror::Error;
+
+use super::engine::LuaEngine;
+use super::types::{Permissions, SandboxConfig, ScriptingError};
+use super::vault_context::VaultContext;
+
+/// Errors loading or running event hooks.
+#[derive(Debug, Error)]
+pub enum EventHookError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("{event} hook in {path} failed: {source}")]
+    Failed {
+        event: &'static str,
+        path: PathBuf,
+        #[source]
+        source: ScriptingError,
+    },
+}
+
+/// A hook script and its source.
+#[derive(Debug, Clone)]
+struct HookScript {
+    path: PathBuf,
+    source: String,
+}
+
+/// The hook scripts of a vault.
+#[derive(Clone, Default)]
+pub struct EventHooks {
+    scripts: Vec<HookScript>,
+    ctx: Option<VaultContext>,
+}
+
+impl EventHooks {
+    /// Read the `.lua` files in `dir`. A missing directory has no hooks.
+    pub fn load(dir: &Path) -> Result<Self, EventHookError> {
+        let io_err =
+            |path: &Path, e| EventHookError::Io { path: path.to_path_buf(), source: e };
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default());
+            }
+            Err(e) => return Err(io_err(dir, e)),
+        };
+
+        let mut scripts = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(|e| io_err(dir, e))?.path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "lua") {
+                let source =
+                    std::fs::read_to_string(&path).map_err(|e| io_err(&path, e))?;
+                scripts.push(HookScript { path, source });
+            }
+        }
+        scripts.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(Self { scripts, ctx: None })
+    }
+
+    /// Give handlers the vault bindings.
+    pub fn with_context(mut self, ctx: VaultContext) -> Self {
+        self.ctx = Some(ctx);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scripts.is_empty()
+    }
+
+    /// Call every `on_rename(old_path, new_path)` handler.
+    ///
+    /// Paths are relative to the vault root. Returns the handlers that failed.
+    pub fn on_rename(&self, old_path: &Path, new_path: &Path) -> Vec<EventHookError> {
+        let old = old_path.to_string_lossy().to_string();
+        let new = new_path.to_string_lossy().to_string();
+        self.dispatch("on_rename", |handler| {
+            handler.call::<()>((old.as_str(), new.as_str()))
+        })
+    }
+
+    fn dispatch(
+        &self,
+        event: &'static str,
+        call: impl Fn(Function) -> mlua::Result<()>,
+    ) -> Vec<EventHookError> {
+        let mut errors = Vec::new();
+        for script in &self.scripts {
+            let failed = |e: ScriptingError| EventHookError::Failed {
+                event,
+                path: script.path.clone(),
+                source: e,
+            };
+            let engine = match &self.ctx {
+                Some(ctx) => LuaEngine::with_vault_context(
+                    SandboxConfig::restricted(),
+                    ctx.clone(),
+                ),
+                None => LuaEngine::sandboxed(),
+            };
+            let result = engine.and_then(|engine| {
+                let lua = engine.lua();
+                let value: Value = lua
+                    .load(&script.source)
+                    .set_name(script.path.to_string_lossy())
+                    .eval()?;
+                let handler = match value {
+                    Value::Table(table) => {
+                        let permissions = table.get::<Value>("permissions")?;
+                        engine.set_permissions(
+                            Permissions::from_lua(permissions)
+                                .map_err(mlua::Error::runtime)?,
+                        )?;
+                        table.get::<Option<Function>>(event)?
+                    }
+                    _ => None,
+                };
+                match handler {
+                    Some(handler) => call(handler).map_err(Into::into),
+                    None => Ok(()),
+                }
+            });
+            if let Err(e) = result {
+                errors.push(failed(e));
+            }
+        }
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn runs_on_rename_handlers_in_order() {
+        let dir = TempDir::new().unwrap();
+        let write = |name: &str, source: &str| {
+            std::fs::write(dir.path().join(name), source).unwrap()
+        };
+        write(
+            "a.lua",
+            r#"return { on_rename = function(old, new)
+                if old ~= "a.md" or new ~= "b/a.md" then error("bad paths") end
+            end }"#,
+        );
+        write("b.lua", r#"return { on_rename = function() error("boom") end }"#);
+        write("c.lua", "return { on_create = function() end }");
+        write("notes.md", "# not a hook");
+
+        let hooks = EventHooks::load(dir.path()).unwrap();
+        assert!(!hooks.is_empty());
+        let errors = hooks.on_rename(Path::new("a.md"), Path::new("b/a.md"));
+        assert_eq!(errors.len(), 1);
+        let message = errors[0].to_string();
+        assert!(message.contains("b.lua") && message.contains("boom"), "{message}");
+
+        assert!(EventHooks::load(&dir.path().join("missing")).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+//! Output formatters written in Lua.
+//!
+//! A `.lua` file in the formatters directory returns a function that turns
+//! the JSON payload of a query command into text:
+//!
+//! ```lua
+//! -- formatters/task-brief.lua
+//! return function(notes)
+//!     local lines = {}
+//!     for _, note in ipairs(notes) do
+//!         lines[#lines + 1] = "- " .. note.title .. " (" .. note.path .. ")"
+//!     end
+//!     return table.concat(lines, "\n")
+//! end
+//! ```
+//!
+//! and is picked with `--output lua:task-brief`. A file may instead return
+//! a table with a `format` function and a `permissions` table (see
+//! [`Permissions`]). The function has the vault bindings; a string it returns
+//! is printed.
+
+use std::path::{Path, PathBuf};
+
+use mlua::{Function, Value};
+use thiserror::Error;
+
+use super::engine::LuaEngine;
+use super::types::{Permissions, SandboxConfig, ScriptingError};
+use super::vault_context::VaultContext;
+use crate::types::validation::yaml_to_lua_table;
+
+/// Errors loading or running output formatters.
+#[derive(Debug, Error)]
+pub enum FormatterError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to evaluate {path}: {source}")]
+    Lua {
+        path: PathBuf,
+        #[source]
+        source: ScriptingError,
+    },
+
+    #[error("Invalid formatter in {path}: {message}")]
+    Invalid { path: PathBuf, message: String },
+
+    #[error("Unknown formatter '{name}': no {name}.lua in {}", dir.display())]
+    Unknown { name: String, dir: PathBuf },
+
+    #[error("Formatter '{name}' failed: {message}")]
+    Failed { name: String, message: String },
+}
+
+/// A formatter loaded from the formatters directory, ready to format
+/// payloads.
+pub struct Formatter {
+    name: String,
+    path: PathBuf,
+    format: Function,
+    engine: LuaEngine,
+}
+
+impl Formatter {
+    /// Load `<dir>/<name>.lua` into an engine with the vault bindings.
+    pub fn load(
+        dir: &Path,
+        name: &str,
+        vault_ctx: VaultContext,
+    ) -> Result<Self, FormatterError> {
+        let path = dir.join(format!("{name}.lua"));
+        if name.is_empty() || name.contains(['/', '\\']) || !path.is_file() {
+            return Err(FormatterError::Unknown {
+                name: name.to_string(),
+                dir: dir.to_path_buf(),
+            });
+        }
+        let engine =
+            LuaEngine::with_vault_context(SandboxConfig::restricted(), vault_ctx)
+                .map_err(|e| FormatterError::Lua { path: path.clone(), source: e })?;
+        Self::load_into(engine, name, path)
+    }
+
+    fn load_into(
+        engine: LuaEngine,
+        name: &str,
+        path: PathBuf,
+    ) -> Result<Self, FormatterError> {
+        let invalid =
+            |message: String| FormatterError::Invalid { path: path.clone(), message };
+        let lua_err =
+            |e: ScriptingError| FormatterError::Lua { path: path.clone(), source: e };
+
+        let source = std::fs::read_to_string(&path)
+            .map_err(|e| FormatterError::Io { path: path.clone(), source: e })?;
+        let value: Value = engine
+            .lua()
+            .load(&source)
+            .set_name(path.to_string_lossy())
+            .eval()
+            .map_err(|e| lua_err(e.into()))?;
+
+        let format = match value {
+            Value::Function(format) => format,
+            Value::Table(def) => {
+                let permissions =
+                    def.get("permissions").map_err(|e: mlua::Error| lua_err(e.into()))?;
+                let permissions = Permissions::from_lua(permissions)
+                    .map_err(|e| invalid(e.to_string()))?;
+                engine.set_permissions(permissions).map_err(lua_err)?;
+                def.get::<Option<Function>>("format")
+                    .ok()
+                    .flatten()
+                    .ok_or_else(|| invalid("the table has no format function".into()))?
+            }
+            _ => {
+                return Err(invalid("the file must return a function or a table".into()));
+            }
+        };
+
+        Ok(Self { name: name.to_string(), path, format, engine })
+    }
+
+    /// The formatter's name, as given after `lua:`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Call the formatter with `payload`; returns the string it gives back,
+    /// if any.
+    pub fn format(
+        &self,
+        payload: &serde_json::Value,
+    ) -> Result<Option<String>, FormatterError> {
+        let failed =
+            |message: String| FormatterError::Failed { name: self.name.clone(), message };
+        let lua = self.engine.lua();
+        let yaml = serde_yaml::to_value(payload).map_err(|e| failed(e.to_string()))?;
+        let data = yaml_to_lua_table(lua, &yaml).map_err(|e| failed(e.to_string()))?;
+
+        match self.format.call::<Value>(data).map_err(|e| failed(e.to_string()))? {
+            Value::Nil => Ok(None),
+            Value::String(s) => Ok(Some(s.to_string_lossy())),
+            other => Err(FormatterError::Invalid {
+                path: self.path.clone(),
+                message: format!(
+                    "format returned a {}, expected a string or nil",
+                    other.type_name()
+                ),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn load(dir: &Path, name: &str) -> Result<Formatter, FormatterError> {
+        let path = dir.join(format!("{name}.lua"));
+        Formatter::load_into(LuaEngine::sandboxed().unwrap(), name, path)
+    }
+
+    #[test]
+    fn formats_payloads() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("brief.lua"),
+            r#"return function(notes)
+                local out = {}
+                for _, n in ipairs(notes) do out[#out + 1] = n.title .. ":" .. n.words end
+                return table.concat(out, ",")
+            end"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("count.lua"),
+            "return { format = function(notes) return tostring(#notes) end }",
+        )
+        .unwrap();
+
+        let payload = serde_json::json!([
+            { "title": "A", "words": 3 },
+            { "title": "B", "words": 5, "due": null },
+        ]);
+        let brief = load(dir.path(), "brief").unwrap();
+        assert_eq!(brief.format(&payload).unwrap().as_deref(), Some("A:3,B:5"));
+        let count = load(dir.path(), "count").unwrap();
+        assert_eq!(count.format(&payload).unwrap().as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn rejects_invalid_formatters() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("table.lua"), "return { name = 'x' }").unwrap();
+        std::fs::write(dir.path().join("number.lua"), "return function() return 1 end")
+            .unwrap();
+
+        assert!(matches!(load(dir.path(), "missing"), Err(FormatterError::Io { .. })));
+        assert!(matches!(load(dir.path(), "table"), Err(FormatterError::Invalid { .. })));
+        let number = load(dir.path(), "number").unwrap();
+        assert!(matches!(
+            number.format(&serde_json::json!([])),
+            Err(FormatterError::Invalid { .. })
+        ));
+    }
+}
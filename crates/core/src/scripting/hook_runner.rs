@@ -2,10 +2,16 @@
 //!
 //! This module provides functions to run lifecycle hooks defined in type definitions.
 
+use std::collections::HashMap;
+use std::path::Path;
+
 use super::engine::LuaEngine;
 use super::hooks::{HookError, NoteContext};
+use super::pool;
 use super::types::SandboxConfig;
 use super::vault_context::VaultContext;
+use crate::frontmatter::{Frontmatter, ParsedDocument, parse, serialize_with_order};
+use crate::types::TypeRegistry;
 use crate::types::definition::TypeDefinition;
 use crate::types::validation::yaml_to_lua_table;
 use tracing::debug;
@@ -70,8 +76,11 @@ pub fn run_on_create_hook(
     }
 
     // Create engine with vault context
-    let engine = LuaEngine::with_vault_context(SandboxConfig::restricted(), vault_ctx)
-        .map_err(|e| HookError::LuaError(e.to_string()))?;
+    let engine = LuaEngine::with_vault_context(
+        SandboxConfig::restricted().with_permissions(typedef.permissions),
+        vault_ctx,
+    )
+    .map_err(|e| HookError::LuaError(e.to_string()))?;
 
     let lua = engine.lua();
 
@@ -220,8 +229,11 @@ pub fn run_on_update_hook(
     }
 
     // Create engine with vault context
-    let engine = LuaEngine::with_vault_context(SandboxConfig::restricted(), vault_ctx)
-        .map_err(|e| HookError::LuaError(e.to_string()))?;
+    let engine = LuaEngine::with_vault_context(
+        SandboxConfig::restricted().with_permissions(typedef.permissions),
+        vault_ctx,
+    )
+    .map_err(|e| HookError::LuaError(e.to_string()))?;
 
     let lua = engine.lua();
 
@@ -302,8 +314,186 @@ pub fn run_on_update_hook(
     }
 }
 
+/// Hooks that keep a note's frontmatter up to date as it changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteHook {
+    /// `on_save(note)`, after a command writes the note.
+    Save,
+    /// `on_index(note)`, when the note is (re)indexed.
+    Index,
+}
+
+impl NoteHook {
+    /// Name of the hook function in the type definition.
+    pub fn name(self) -> &'static str {
+        match self {
+            NoteHook::Save => "on_save",
+            NoteHook::Index => "on_index",
+        }
+    }
+
+    fn is_defined(self, typedef: &TypeDefinition) -> bool {
+        match self {
+            NoteHook::Save => typedef.has_on_save_hook,
+            NoteHook::Index => typedef.has_on_index_hook,
+        }
+    }
+}
+
+/// Run `hook` of the type of the note at `path` (whose text is `content`),
+/// returning the note's new text if the hook changed its frontmatter.
+///
+/// The hook gets the note table (`path`, `type`, `content`, `frontmatter`)
+/// and returns it, or nil to leave the note alone. Only the returned
+/// frontmatter is applied. These hooks run on every reindex, so they get
+/// a plain sandbox without the vault bindings, reused across notes (see
+/// [`pool`]).
+pub fn apply_note_hook(
+    registry: &TypeRegistry,
+    hook: NoteHook,
+    path: &Path,
+    content: &str,
+) -> Result<Option<String>, HookError> {
+    let Ok(parsed) = parse(content) else {
+        return Ok(None);
+    };
+    let fields =
+        parsed.frontmatter.as_ref().map(|fm| fm.fields.clone()).unwrap_or_default();
+    let Some(typedef) =
+        fields.get("type").and_then(|t| t.as_str()).and_then(|t| registry.get(t))
+    else {
+        return Ok(None);
+    };
+    if !hook.is_defined(&typedef) {
+        return Ok(None);
+    }
+
+    let pooled = pool::checkout(&typedef).map_err(|e| {
+        HookError::LuaError(format!("failed to load type definition: {}", e))
+    })?;
+    let lua = pooled.lua();
+    let lua_err = |e: mlua::Error| HookError::LuaError(e.to_string());
+
+    let frontmatter = serde_yaml::Value::Mapping(
+        fields
+            .iter()
+            .map(|(k, v)| (serde_yaml::Value::String(k.clone()), v.clone()))
+            .collect(),
+    );
+    let note_table = lua.create_table().map_err(lua_err)?;
+    note_table.set("path", path.to_string_lossy().to_string()).map_err(lua_err)?;
+    note_table.set("type", typedef.name.clone()).map_err(lua_err)?;
+    note_table.set("content", content).map_err(lua_err)?;
+    note_table
+        .set("frontmatter", yaml_to_lua_table(lua, &frontmatter).map_err(lua_err)?)
+        .map_err(lua_err)?;
+
+    let hook_fn: mlua::Function = pooled.table().get(hook.name()).map_err(|e| {
+        HookError::LuaError(format!("{} function not found: {}", hook.name(), e))
+    })?;
+    let result: mlua::Value = hook_fn.call(note_table).map_err(|e| {
+        HookError::Execution(format!("{} hook failed: {}", hook.name(), e))
+    })?;
+
+    let mlua::Value::Table(returned_note) = result else {
+        return Ok(None);
+    };
+    let Ok(fm_table) = returned_note.get::<mlua::Table>("frontmatter") else {
+        return Ok(None);
+    };
+    let serde_yaml::Value::Mapping(map) = lua_table_to_yaml(&fm_table)? else {
+        return Ok(None);
+    };
+    let new_fields: HashMap<String, serde_yaml::Value> = map
+        .into_iter()
+        .filter_map(|(k, v)| match k {
+            serde_yaml::Value::String(key) => Some((key, v)),
+            _ => None,
+        })
+        .collect();
+
+    // Leave the text (and its formatting) alone unless a field changed
+    if new_fields == fields {
+        return Ok(None);
+    }
+    let doc = ParsedDocument {
+        frontmatter: Some(Frontmatter { fields: new_fields }),
+        body: parsed.body,
+    };
+    Ok(Some(serialize_with_order(&doc, typedef.frontmatter_order.as_deref())))
+}
+
+/// Run the `migrate(note, from_version)` function of `typedef` on a note
+/// written for schema version `from_version`, returning its new frontmatter.
+///
+/// The note table is the one the other hooks get (`path`, `type`,
+/// `content`, `frontmatter`). `migrate` returns it, or nil after changing
+/// `note.frontmatter` in place. Like [`apply_note_hook`] it runs in a plain
+/// sandbox reused across notes.
+pub fn run_migrate_hook(
+    typedef: &TypeDefinition,
+    path: &Path,
+    content: &str,
+    fields: &HashMap<String, serde_yaml::Value>,
+    from_version: u32,
+) -> Result<HashMap<String, serde_yaml::Value>, HookError> {
+    let pooled = pool::checkout(typedef).map_err(|e| {
+        HookError::LuaError(format!("failed to load type definition: {}", e))
+    })?;
+    let lua = pooled.lua();
+    let lua_err = |e: mlua::Error| HookError::LuaError(e.to_string());
+
+    let frontmatter = serde_yaml::Value::Mapping(
+        fields
+            .iter()
+            .map(|(k, v)| (serde_yaml::Value::String(k.clone()), v.clone()))
+            .collect(),
+    );
+    let note_table = lua.create_table().map_err(lua_err)?;
+    note_table.set("path", path.to_string_lossy().to_string()).map_err(lua_err)?;
+    note_table.set("type", typedef.name.clone()).map_err(lua_err)?;
+    note_table.set("content", content).map_err(lua_err)?;
+    note_table
+        .set("frontmatter", yaml_to_lua_table(lua, &frontmatter).map_err(lua_err)?)
+        .map_err(lua_err)?;
+
+    let migrate_fn: mlua::Function = pooled
+        .table()
+        .get("migrate")
+        .map_err(|e| HookError::LuaError(format!("migrate function not found: {}", e)))?;
+    let result: mlua::Value = migrate_fn
+        .call((note_table.clone(), from_version))
+        .map_err(|e| HookError::Execution(format!("migrate failed: {}", e)))?;
+
+    let returned_note = match result {
+        mlua::Value::Table(t) => t,
+        mlua::Value::Nil => note_table,
+        other => {
+            return Err(HookError::Execution(format!(
+                "migrate returned a {}, expected the note or nil",
+                other.type_name()
+            )));
+        }
+    };
+    let fm_table: mlua::Table = returned_note.get("frontmatter").map_err(|_| {
+        HookError::Execution("migrate returned a note without frontmatter".into())
+    })?;
+    let serde_yaml::Value::Mapping(map) = lua_table_to_yaml(&fm_table)? else {
+        return Ok(HashMap::new());
+    };
+    Ok(map
+        .into_iter()
+        .filter_map(|(k, v)| match k {
+            serde_yaml::Value::String(key) => Some((key, v)),
+            _ => None,
+        })
+        .collect())
+}
+
 /// Convert a Lua table to serde_yaml::Value.
-fn lua_table_to_yaml(table: &mlua::Table) -> Result<serde_yaml::Value, HookError> {
+pub(crate) fn lua_table_to_yaml(
+    table: &mlua::Table,
+) -> Result<serde_yaml::Value, HookError> {
     let mut map = serde_yaml::Mapping::new();
 
     for pair in table.pairs::<mlua::Value, mlua::Value>() {
@@ -327,7 +517,9 @@ fn lua_table_to_yaml(table: &mlua::Table) -> Result<serde_yaml::Value, HookError
 }
 
 /// Convert a single Lua value to serde_yaml::Value.
-fn lua_value_to_yaml(value: mlua::Value) -> Result<serde_yaml::Value, HookError> {
+pub(crate) fn lua_value_to_yaml(
+    value: mlua::Value,
+) -> Result<serde_yaml::Value, HookError> {
     match value {
         mlua::Value::Nil => Ok(serde_yaml::Value::Null),
         mlua::Value::Boolean(b) => Ok(serde_yaml::Value::Bool(b)),
@@ -387,11 +579,18 @@ mod tests {
             schema: HashMap::new(),
             output: None,
             frontmatter_order: None,
+            template: None,
             variables: crate::vars::VarsMap::new(),
             has_validate_fn: false,
             has_on_create_hook: true,
             has_on_update_hook: false,
+            has_on_save_hook: false,
+            has_on_index_hook: false,
+            has_migrate_fn: false,
+            schema_version: None,
             is_builtin_override: false,
+            retention: None,
+            permissions: crate::scripting::Permissions::default(),
             lua_source: lua_source.to_string(),
         }
     }
@@ -415,11 +614,18 @@ mod tests {
             schema: HashMap::new(),
             output: None,
             frontmatter_order: None,
+            template: None,
             variables: crate::vars::VarsMap::new(),
             has_validate_fn: false,
             has_on_create_hook: false, // No hook
             has_on_update_hook: false,
+            has_on_save_hook: false,
+            has_on_index_hook: false,
+            has_migrate_fn: false,
+            schema_version: None,
             is_builtin_override: false,
+            retention: None,
+            permissions: crate::scripting::Permissions::default(),
             lua_source: String::new(),
         };
 
@@ -476,4 +682,44 @@ mod tests {
 
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_apply_note_hook_updates_frontmatter() {
+        let mut typedef = make_typedef_with_hook(
+            r#"
+            return {
+                on_save = function(note)
+                    if note.frontmatter.status == "done" then
+                        return nil
+                    end
+                    note.frontmatter.status = "done"
+                    return note
+                end
+            }
+        "#,
+        );
+        typedef.has_on_create_hook = false;
+        typedef.has_on_save_hook = true;
+        let mut registry = TypeRegistry::new();
+        registry.register(typedef).unwrap();
+        let path = Path::new("test.md");
+
+        let open = "---\ntype: test\nstatus: open\n---\n# Test\n";
+        let saved = apply_note_hook(&registry, NoteHook::Save, path, open).unwrap();
+        let saved = saved.expect("hook changes the note");
+        assert!(saved.contains("status: done"));
+        assert!(saved.contains("# Test"));
+
+        // A nil return, an unchanged note or a missing hook leave it alone
+        assert!(
+            apply_note_hook(&registry, NoteHook::Save, path, &saved).unwrap().is_none()
+        );
+        assert!(
+            apply_note_hook(&registry, NoteHook::Index, path, open).unwrap().is_none()
+        );
+        let untyped = "# No frontmatter\n";
+        assert!(
+            apply_note_hook(&registry, NoteHook::Save, path, untyped).unwrap().is_none()
+        );
+    }
 }
@@ -3,27 +3,31 @@
 //! This module provides Lua bindings for querying the vault index:
 //! - `mdv.current_note()` - Get the current note being processed
 //! - `mdv.backlinks(path)` - Get notes linking to a path
-//! - `mdv.outlinks(path)` - Get notes a path links to
+//! - `mdv.links(path)` - Get notes a path links to (alias `mdv.outlinks`)
 //! - `mdv.query(opts)` - Query the vault index
 
 use std::path::Path;
 
+use chrono::{DateTime, NaiveDate, Utc};
 use mlua::{Function, Lua, Result as LuaResult, Table, Value};
 
 use super::vault_context::VaultContext;
-use crate::index::NoteQuery;
+use crate::index::{FieldFilter, FieldOp, IndexDb, IndexedLink, NoteQuery};
 use crate::types::validation::yaml_to_lua_table;
+use crate::vars::datemath::try_evaluate_date_expr;
 
 /// Register index query bindings on an existing mdv table.
 ///
-/// This adds `mdv.current_note()`, `mdv.backlinks()`, `mdv.outlinks()`, and
-/// `mdv.query()` functions that have access to the vault index.
+/// This adds `mdv.current_note()`, `mdv.backlinks()`, `mdv.links()` (and its
+/// alias `mdv.outlinks()`), `mdv.query()` and `mdv.find_project()` functions
+/// that have access to the vault index.
 pub fn register_index_bindings(lua: &Lua) -> LuaResult<()> {
     let mdv: Table = lua.globals().get("mdv")?;
 
     mdv.set("current_note", create_current_note_fn(lua)?)?;
     mdv.set("backlinks", create_backlinks_fn(lua)?)?;
-    mdv.set("outlinks", create_outlinks_fn(lua)?)?;
+    mdv.set("links", create_links_fn(lua)?)?;
+    mdv.set("outlinks", create_links_fn(lua)?)?;
     mdv.set("query", create_query_fn(lua)?)?;
     mdv.set("find_project", create_find_project_fn(lua)?)?;
 
@@ -75,14 +79,14 @@ fn create_current_note_fn(lua: &Lua) -> LuaResult<Function> {
 
 /// Create the `mdv.backlinks(path)` function.
 ///
-/// Returns a list of notes that link to the specified path.
+/// Returns the links pointing at the note at `path`; see [`note_backlinks`].
 ///
 /// # Examples (in Lua)
 ///
 /// ```lua
 /// local links = mdv.backlinks("projects/my-project.md")
 /// for _, link in ipairs(links) do
-///     print(link.source_path .. " links to this note")
+///     print(link.path .. " links to this note: " .. (link.context or ""))
 /// end
 /// ```
 fn create_backlinks_fn(lua: &Lua) -> LuaResult<Function> {
@@ -90,164 +94,156 @@ fn create_backlinks_fn(lua: &Lua) -> LuaResult<Function> {
         let ctx = lua
             .app_data_ref::<VaultContext>()
             .ok_or_else(|| mlua::Error::runtime("VaultContext not available"))?;
-
-        let db = match &ctx.index_db {
-            Some(db) => db,
-            None => {
-                return Err(mlua::Error::runtime(
-                    "Index database not available. Run 'mdv reindex' first.",
-                ));
-            }
-        };
-
-        // Resolve path
-        let resolved_path = resolve_note_path(&ctx.vault_root, &path);
-
-        // Get note ID
-        let note = match db.get_note_by_path(Path::new(&resolved_path)) {
-            Ok(Some(n)) => n,
-            Ok(None) => {
-                // Return empty table if note not found
-                return Ok(Value::Table(lua.create_table()?));
-            }
-            Err(e) => return Err(mlua::Error::runtime(format!("Index error: {}", e))),
-        };
-
-        let note_id = match note.id {
-            Some(id) => id,
-            None => return Ok(Value::Table(lua.create_table()?)),
-        };
-
-        // Get backlinks
-        let backlinks = db
-            .get_backlinks(note_id)
-            .map_err(|e| mlua::Error::runtime(format!("Index error: {}", e)))?;
-
-        // Convert to Lua table
-        let result = lua.create_table()?;
-        for (i, link) in backlinks.iter().enumerate() {
-            let link_table = lua.create_table()?;
-
-            // Get source note path
-            if let Ok(Some(source_note)) = db.get_note_by_id(link.source_id) {
-                link_table
-                    .set("source_path", source_note.path.to_string_lossy().to_string())?;
-                link_table.set("source_title", source_note.title)?;
-                link_table.set("source_type", source_note.note_type.as_str())?;
-            }
-
-            if let Some(text) = &link.link_text {
-                link_table.set("link_text", text.as_str())?;
-            }
-            if let Some(context) = &link.context {
-                link_table.set("context", context.as_str())?;
-            }
-            link_table.set("link_type", link.link_type.as_str())?;
-
-            result.set(i + 1, link_table)?;
-        }
-
-        Ok(Value::Table(result))
+        note_backlinks(lua, index_db(&ctx)?, &path)
     })
 }
 
-/// Create the `mdv.outlinks(path)` function.
+/// Create the `mdv.links(path)` function, also available as `mdv.outlinks`.
 ///
-/// Returns a list of notes that the specified path links to.
+/// Returns the links in the note at `path`; see [`note_links`].
 ///
 /// # Examples (in Lua)
 ///
 /// ```lua
-/// local links = mdv.outlinks("projects/my-project.md")
+/// local links = mdv.links("projects/my-project.md")
 /// for _, link in ipairs(links) do
-///     print("Links to: " .. link.target_path)
+///     print("Links to: " .. link.path)
 /// end
 /// ```
-fn create_outlinks_fn(lua: &Lua) -> LuaResult<Function> {
+fn create_links_fn(lua: &Lua) -> LuaResult<Function> {
     lua.create_function(|lua, path: String| {
         let ctx = lua
             .app_data_ref::<VaultContext>()
             .ok_or_else(|| mlua::Error::runtime("VaultContext not available"))?;
+        note_links(lua, index_db(&ctx)?, &path)
+    })
+}
 
-        let db = match &ctx.index_db {
-            Some(db) => db,
-            None => {
-                return Err(mlua::Error::runtime(
-                    "Index database not available. Run 'mdv reindex' first.",
-                ));
-            }
-        };
-
-        // Resolve path
-        let resolved_path = resolve_note_path(&ctx.vault_root, &path);
+fn index_db(ctx: &VaultContext) -> LuaResult<&IndexDb> {
+    ctx.index_db.as_deref().ok_or_else(|| {
+        mlua::Error::runtime("Index database not available. Run 'mdv reindex' first.")
+    })
+}
 
-        // Get note ID
-        let note = match db.get_note_by_path(Path::new(&resolved_path)) {
-            Ok(Some(n)) => n,
-            Ok(None) => {
-                // Return empty table if note not found
-                return Ok(Value::Table(lua.create_table()?));
-            }
-            Err(e) => return Err(mlua::Error::runtime(format!("Index error: {}", e))),
-        };
+/// Index ID of the note at `path`, if it is indexed.
+fn note_id(db: &IndexDb, path: &str) -> LuaResult<Option<i64>> {
+    let resolved = resolve_note_path(path);
+    match db.get_note_by_path(Path::new(&resolved)) {
+        Ok(note) => Ok(note.and_then(|n| n.id)),
+        Err(e) => Err(mlua::Error::runtime(format!("Index error: {}", e))),
+    }
+}
 
-        let note_id = match note.id {
-            Some(id) => id,
-            None => return Ok(Value::Table(lua.create_table()?)),
-        };
+/// Fields every link table has: `link_type`, and `link_text`, `context`,
+/// `anchor` and `line` when known.
+fn link_table(lua: &Lua, link: &IndexedLink) -> LuaResult<Table> {
+    let table = lua.create_table()?;
+    table.set("link_type", link.link_type.as_str())?;
+    table.set("link_text", link.link_text.as_deref())?;
+    table.set("context", link.context.as_deref())?;
+    table.set("anchor", link.target_anchor.as_deref())?;
+    table.set("line", link.line_number)?;
+    Ok(table)
+}
 
-        // Get outgoing links
-        let outlinks = db
-            .get_outgoing_links(note_id)
-            .map_err(|e| mlua::Error::runtime(format!("Index error: {}", e)))?;
-
-        // Convert to Lua table
-        let result = lua.create_table()?;
-        for (i, link) in outlinks.iter().enumerate() {
-            let link_table = lua.create_table()?;
-
-            link_table.set("target_path", link.target_path.as_str())?;
-
-            // Get target note info if resolved
-            if let Some(target_id) = link.target_id {
-                if let Ok(Some(target_note)) = db.get_note_by_id(target_id) {
-                    link_table.set("target_title", target_note.title)?;
-                    link_table.set("target_type", target_note.note_type.as_str())?;
-                    link_table.set("resolved", true)?;
-                } else {
-                    link_table.set("resolved", false)?;
-                }
-            } else {
-                link_table.set("resolved", false)?;
+/// Run `mdv.links(path)` against `db`: the links in the note at `path`.
+///
+/// Each link has `path` (the linked note's path, or the link target as
+/// written if it does not resolve), `title` and `type` of the linked note,
+/// `resolved`, and the fields of [`link_table`]. The older `target_path`,
+/// `target_title` and `target_type` keys are kept. A note that is not
+/// indexed has no links.
+pub(crate) fn note_links(lua: &Lua, db: &IndexDb, path: &str) -> LuaResult<Table> {
+    let result = lua.create_table()?;
+    let Some(id) = note_id(db, path)? else {
+        return Ok(result);
+    };
+    let links = db
+        .get_outgoing_links(id)
+        .map_err(|e| mlua::Error::runtime(format!("Index error: {}", e)))?;
+
+    for (i, link) in links.iter().enumerate() {
+        let table = link_table(lua, link)?;
+        table.set("target_path", link.target_path.as_str())?;
+        let target = link.target_id.and_then(|id| db.get_note_by_id(id).ok().flatten());
+        match target {
+            Some(note) => {
+                table.set("path", note.path.to_string_lossy().to_string())?;
+                table.set("title", note.title.as_str())?;
+                table.set("type", note.note_type.as_str())?;
+                table.set("target_title", note.title)?;
+                table.set("target_type", note.note_type.as_str())?;
+                table.set("resolved", true)?;
             }
-
-            if let Some(text) = &link.link_text {
-                link_table.set("link_text", text.as_str())?;
+            None => {
+                table.set("path", link.target_path.as_str())?;
+                table.set("resolved", false)?;
             }
-            link_table.set("link_type", link.link_type.as_str())?;
-
-            result.set(i + 1, link_table)?;
         }
+        result.set(i + 1, table)?;
+    }
+    Ok(result)
+}
 
-        Ok(Value::Table(result))
-    })
+/// Run `mdv.backlinks(path)` against `db`: the links pointing at the note at
+/// `path`.
+///
+/// Each link has `path`, `title` and `type` of the linking note and the
+/// fields of [`link_table`]; `context` is the text around the link in the
+/// linking note. The older `source_path`, `source_title` and `source_type`
+/// keys are kept.
+pub(crate) fn note_backlinks(lua: &Lua, db: &IndexDb, path: &str) -> LuaResult<Table> {
+    let result = lua.create_table()?;
+    let Some(id) = note_id(db, path)? else {
+        return Ok(result);
+    };
+    let links = db
+        .get_backlinks(id)
+        .map_err(|e| mlua::Error::runtime(format!("Index error: {}", e)))?;
+
+    for (i, link) in links.iter().enumerate() {
+        let table = link_table(lua, link)?;
+        if let Ok(Some(source)) = db.get_note_by_id(link.source_id) {
+            let source_path = source.path.to_string_lossy().to_string();
+            table.set("path", source_path.as_str())?;
+            table.set("title", source.title.as_str())?;
+            table.set("type", source.note_type.as_str())?;
+            table.set("source_path", source_path)?;
+            table.set("source_title", source.title)?;
+            table.set("source_type", source.note_type.as_str())?;
+        }
+        result.set(i + 1, table)?;
+    }
+    Ok(result)
 }
 
 /// Create the `mdv.query(opts)` function.
 ///
-/// Query the vault index with filters.
+/// Query the vault index with filters. Options:
+///
+/// - `type`: note type
+/// - `status`: value of the `status` field
+/// - `path_prefix`: folder the notes live under
+/// - `modified_after`, `modified_before`: a date (`2025-01-31`), a datetime,
+///   or a date expression (`today - 7d`)
+/// - `where`: a field filter (`"priority>=3"`) or a list of them, as in
+///   `mdv list --where`
+/// - `limit`, `offset`
+///
+/// Each result has `path`, `type`, `title` and `modified`, plus `created`
+/// and `frontmatter` when the note has them.
 ///
 /// # Examples (in Lua)
 ///
 /// ```lua
 /// -- Find all open tasks
-/// local tasks = mdv.query({ type = "task" })
+/// local tasks = mdv.query({ type = "task", status = "open" })
 /// for _, note in ipairs(tasks) do
 ///     print(note.path .. ": " .. note.title)
 /// end
 ///
-/// -- Find recent notes
-/// local recent = mdv.query({ limit = 10 })
+/// -- Urgent notes touched this week
+/// local recent = mdv.query({ modified_after = "today - 7d", where = { "priority>=3" } })
 /// ```
 fn create_query_fn(lua: &Lua) -> LuaResult<Function> {
     lua.create_function(|lua, opts: Option<Table>| {
@@ -264,62 +260,121 @@ fn create_query_fn(lua: &Lua) -> LuaResult<Function> {
             }
         };
 
-        // Build query from options
-        let mut query = NoteQuery::default();
-
-        if let Some(opts) = opts {
-            // Type filter
-            if let Ok(type_str) = opts.get::<String>("type") {
-                query.note_type = Some(type_str.parse().unwrap_or_default());
-            }
+        query_notes(lua, db, opts).map(Value::Table)
+    })
+}
 
-            // Path prefix filter
-            if let Ok(prefix) = opts.get::<String>("path_prefix") {
-                query.path_prefix = Some(std::path::PathBuf::from(prefix));
-            }
+/// Run `mdv.query(opts)` against `db`.
+///
+/// Shared with callers that have an index but no [`VaultContext`], such as
+/// typedef `validate()` functions.
+pub(crate) fn query_notes(
+    lua: &Lua,
+    db: &IndexDb,
+    opts: Option<Table>,
+) -> LuaResult<Table> {
+    let query = match opts {
+        Some(opts) => note_query_from_opts(&opts)?,
+        None => NoteQuery::default(),
+    };
+
+    let notes = db
+        .query_notes(&query)
+        .map_err(|e| mlua::Error::runtime(format!("Query error: {}", e)))?;
+
+    let result = lua.create_table()?;
+    for (i, note) in notes.iter().enumerate() {
+        let note_table = lua.create_table()?;
+        note_table.set("path", note.path.to_string_lossy().to_string())?;
+        note_table.set("type", note.note_type.as_str())?;
+        note_table.set("title", note.title.clone())?;
+        note_table.set("modified", note.modified.to_rfc3339())?;
 
-            // Limit
-            if let Ok(limit) = opts.get::<i64>("limit") {
-                query.limit = Some(limit as u32);
-            }
+        if let Some(created) = note.created {
+            note_table.set("created", created.to_rfc3339())?;
+        }
 
-            // Offset
-            if let Ok(offset) = opts.get::<i64>("offset") {
-                query.offset = Some(offset as u32);
-            }
+        // Parse and include frontmatter if available
+        if let Some(fm_json) = &note.frontmatter_json
+            && let Ok(fm) = serde_json::from_str::<serde_json::Value>(fm_json)
+        {
+            let fm_yaml = json_to_yaml(&fm);
+            let fm_lua = yaml_to_lua_table(lua, &fm_yaml)?;
+            note_table.set("frontmatter", fm_lua)?;
         }
 
-        // Execute query
-        let notes = db
-            .query_notes(&query)
-            .map_err(|e| mlua::Error::runtime(format!("Query error: {}", e)))?;
+        result.set(i + 1, note_table)?;
+    }
+    Ok(result)
+}
 
-        // Convert to Lua table
-        let result = lua.create_table()?;
-        for (i, note) in notes.iter().enumerate() {
-            let note_table = lua.create_table()?;
-            note_table.set("path", note.path.to_string_lossy().to_string())?;
-            note_table.set("type", note.note_type.as_str())?;
-            note_table.set("title", note.title.clone())?;
-            note_table.set("modified", note.modified.to_rfc3339())?;
-
-            if let Some(created) = note.created {
-                note_table.set("created", created.to_rfc3339())?;
-            }
+/// Build a [`NoteQuery`] from the options table of `mdv.query`.
+fn note_query_from_opts(opts: &Table) -> LuaResult<NoteQuery> {
+    let mut query = NoteQuery::default();
 
-            // Parse and include frontmatter if available
-            if let Some(fm_json) = &note.frontmatter_json
-                && let Ok(fm) = serde_json::from_str::<serde_json::Value>(fm_json)
-            {
-                let fm_yaml = json_to_yaml(&fm);
-                let fm_lua = yaml_to_lua_table(lua, &fm_yaml)?;
-                note_table.set("frontmatter", fm_lua)?;
-            }
+    if let Ok(type_str) = opts.get::<String>("type") {
+        query.note_type = Some(type_str.parse().unwrap_or_default());
+    }
+    if let Ok(prefix) = opts.get::<String>("path_prefix") {
+        query.path_prefix = Some(std::path::PathBuf::from(prefix));
+    }
+    if let Ok(limit) = opts.get::<i64>("limit") {
+        query.limit = Some(limit as u32);
+    }
+    if let Ok(offset) = opts.get::<i64>("offset") {
+        query.offset = Some(offset as u32);
+    }
+    if let Some(date) = opts.get::<Option<String>>("modified_after")? {
+        query.modified_after = Some(parse_query_date("modified_after", &date)?);
+    }
+    if let Some(date) = opts.get::<Option<String>>("modified_before")? {
+        query.modified_before = Some(parse_query_date("modified_before", &date)?);
+    }
+    if let Some(status) = opts.get::<Option<String>>("status")? {
+        query.fields.push(FieldFilter {
+            key: "status".into(),
+            op: FieldOp::Eq,
+            value: status,
+        });
+    }
 
-            result.set(i + 1, note_table)?;
+    let filters = match opts.get::<Value>("where")? {
+        Value::Nil => Vec::new(),
+        Value::String(s) => vec![s.to_str()?.to_string()],
+        Value::Table(list) => {
+            list.sequence_values::<String>().collect::<LuaResult<_>>()?
+        }
+        other => {
+            return Err(mlua::Error::runtime(format!(
+                "where must be a string or a list of strings, got {}",
+                other.type_name()
+            )));
         }
+    };
+    for filter in filters {
+        query.fields.push(
+            filter
+                .parse::<FieldFilter>()
+                .map_err(|e| mlua::Error::runtime(e.to_string()))?,
+        );
+    }
+
+    Ok(query)
+}
 
-        Ok(Value::Table(result))
+/// Parse a date option: a date expression, `YYYY-MM-DD`, or RFC 3339.
+pub(crate) fn parse_query_date(option: &str, value: &str) -> LuaResult<DateTime<Utc>> {
+    let midnight =
+        |date: NaiveDate| DateTime::from_naive_utc_and_offset(date.into(), Utc);
+    let evaluated = try_evaluate_date_expr(value);
+    let day = evaluated.as_deref().unwrap_or(value);
+    if let Ok(date) = NaiveDate::parse_from_str(day, "%Y-%m-%d") {
+        return Ok(midnight(date));
+    }
+    DateTime::parse_from_rfc3339(value).map(|dt| dt.with_timezone(&Utc)).map_err(|_| {
+        mlua::Error::runtime(format!(
+            "{option} expects YYYY-MM-DD or a date expression, got '{value}'"
+        ))
     })
 }
 
@@ -386,7 +441,7 @@ fn create_find_project_fn(lua: &Lua) -> LuaResult<Function> {
 }
 
 /// Resolve a note path relative to vault root.
-fn resolve_note_path(_vault_root: &std::path::Path, path: &str) -> String {
+fn resolve_note_path(path: &str) -> String {
     // If path doesn't end with .md, append it
     if path.ends_with(".md") { path.to_string() } else { format!("{}.md", path) }
 }
@@ -425,15 +480,13 @@ mod tests {
 
     #[test]
     fn test_resolve_note_path_with_extension() {
-        let vault_root = std::path::Path::new("/vault");
-        let result = resolve_note_path(vault_root, "notes/test.md");
+        let result = resolve_note_path("notes/test.md");
         assert_eq!(result, "notes/test.md");
     }
 
     #[test]
     fn test_resolve_note_path_without_extension() {
-        let vault_root = std::path::Path::new("/vault");
-        let result = resolve_note_path(vault_root, "notes/test");
+        let result = resolve_note_path("notes/test");
         assert_eq!(result, "notes/test.md");
     }
 
@@ -455,4 +508,38 @@ mod tests {
             panic!("Expected mapping");
         }
     }
+
+    #[test]
+    fn test_note_query_from_opts() {
+        let lua = Lua::new();
+        let opts: Table = lua
+            .load(
+                r#"{ type = "task", status = "open", modified_after = "2025-01-31",
+                     where = { "priority>=3", "tags=rust" }, limit = 5 }"#,
+            )
+            .eval()
+            .unwrap();
+
+        let query = note_query_from_opts(&opts).unwrap();
+        assert_eq!(query.note_type, Some(crate::index::NoteType::Task));
+        assert_eq!(query.limit, Some(5));
+        assert_eq!(
+            query.modified_after.unwrap().to_rfc3339(),
+            "2025-01-31T00:00:00+00:00"
+        );
+        let fields: Vec<String> = query.fields.iter().map(|f| f.to_string()).collect();
+        assert_eq!(fields, ["status=open", "priority>=3", "tags=rust"]);
+
+        let single: Table = lua.load(r#"{ where = "due<2025-02-01" }"#).eval().unwrap();
+        assert_eq!(note_query_from_opts(&single).unwrap().fields.len(), 1);
+
+        let relative: Table =
+            lua.load(r#"{ modified_before = "today - 7d" }"#).eval().unwrap();
+        assert!(note_query_from_opts(&relative).unwrap().modified_before.is_some());
+
+        for bad in [r#"{ where = "priority" }"#, r#"{ modified_after = "someday" }"#] {
+            let opts: Table = lua.load(bad).eval().unwrap();
+            assert!(note_query_from_opts(&opts).is_err(), "{bad}");
+        }
+    }
 }
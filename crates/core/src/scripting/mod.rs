@@ -34,6 +34,10 @@
 //! - `mdv.date(expr, format?)` - Evaluate date math expressions
 //! - `mdv.render(template, context)` - Render templates with variables
 //! - `mdv.is_date_expr(str)` - Check if a string is a date expression
+//! - `mdv.slug(str)` - Slug a string like the `slugify` template filter
+//! - `mdv.path.join/basename/relative` - Manipulate vault paths
+//! - `mdv.yaml.parse/emit` - Convert between YAML text and Lua values
+//! - `mdv.string.split/trim/starts_with/ends_with/replace` - Plain-text string helpers
 //!
 //! With vault context (via `LuaEngine::with_vault_context`):
 //! - `mdv.template(name, vars?)` - Render a template by name
@@ -45,6 +49,8 @@
 //! - `mdv.outlinks(path)` - Get notes a path links to
 //! - `mdv.query(opts)` - Query the vault index
 //! - `mdv.selector(opts)` - Show interactive fuzzy selector for notes of a type
+//! - `mdv.log_activity(event, details?)` - Append an entry to the activity log
+//! - `mdv.activity_since(since, filter?)` - Read activity log entries since a date
 //!
 //! # Security
 //!
@@ -55,21 +61,36 @@
 //! - Arbitrary code loading (`load`, `loadfile`, `dofile` removed)
 //! - Debug library access (`debug` removed)
 
+pub mod activity_bindings;
 pub mod bindings;
+pub mod commands;
 pub mod engine;
+pub mod event_hooks;
+pub mod formatters;
 pub mod hook_runner;
 pub mod hooks;
 pub mod index_bindings;
+pub mod pool;
+pub mod repl;
 pub mod selector;
+pub mod stdlib_bindings;
+pub mod template_filters;
 pub mod types;
 pub mod vault_bindings;
 pub mod vault_context;
 
 pub use engine::LuaEngine;
+pub use event_hooks::{EventHookError, EventHooks};
+pub use formatters::{Formatter, FormatterError};
 pub use hook_runner::{
-    HookResult, UpdateHookResult, run_on_create_hook, run_on_update_hook,
+    HookResult, NoteHook, UpdateHookResult, apply_note_hook, run_migrate_hook,
+    run_on_create_hook, run_on_update_hook,
 };
 pub use hooks::{HookError, NoteContext};
+pub use repl::{LuaRepl, ReplStep};
 pub use selector::{SelectorCallback, SelectorItem, SelectorOptions};
-pub use types::{SandboxConfig, ScriptingError};
+pub use types::{
+    Capability, Permissions, SandboxConfig, ScriptingError, set_trust_scripts,
+    trust_scripts,
+};
 pub use vault_context::{CurrentNote, VaultContext};
@@ -0,0 +1,244 @@
+//! Per-thread pool of engines for type definitions.
+//!
+//! `validate()` and the `on_save`/`on_index` hooks run once per note.
+//! Building a sandboxed engine for every note dominated `mdv validate` and
+//! `mdv reindex` on large vaults, so each thread keeps one engine per type
+//! definition, with the definition compiled once.
+//!
+//! The definition itself still runs afresh for every note, in a new
+//! environment holding its own copies of the library tables (`mdv`,
+//! `string`, ...). Globals, captured locals and changes to those tables
+//! therefore never carry over from one note to the next.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use mlua::{ChunkMode, Lua, Table, Value};
+
+use super::engine::LuaEngine;
+use super::types::{Permissions, SandboxConfig, ScriptingError};
+use crate::types::TypeDefinition;
+
+thread_local! {
+    static LOADED: RefCell<HashMap<String, Rc<Loaded>>> = RefCell::new(HashMap::new());
+}
+
+/// A type definition compiled in a long-lived engine.
+struct Loaded {
+    source: String,
+    permissions: Permissions,
+    chunk_name: String,
+    bytecode: Vec<u8>,
+    engine: LuaEngine,
+}
+
+impl Loaded {
+    fn load(typedef: &TypeDefinition) -> Result<Self, ScriptingError> {
+        let engine = LuaEngine::new(
+            SandboxConfig::restricted().with_permissions(typedef.permissions),
+        )?;
+        let chunk_name = typedef.source_path.to_string_lossy().into_owned();
+        let bytecode = engine
+            .lua()
+            .load(&typedef.lua_source)
+            .set_name(&chunk_name)
+            .into_function()?
+            .dump(false);
+
+        Ok(Self {
+            source: typedef.lua_source.clone(),
+            permissions: typedef.permissions,
+            chunk_name,
+            bytecode,
+            engine,
+        })
+    }
+
+    fn is_current(&self, typedef: &TypeDefinition) -> bool {
+        self.source == typedef.lua_source && self.permissions == typedef.permissions
+    }
+
+    /// Run the definition in a new environment and return the
+    /// environment and the definition's table.
+    fn instantiate(&self) -> mlua::Result<(Table, Table)> {
+        let lua = self.engine.lua();
+        let env = isolated_env(lua)?;
+        let table = lua
+            .load(&self.bytecode[..])
+            .set_name(&self.chunk_name)
+            .set_mode(ChunkMode::Binary)
+            .set_environment(env.clone())
+            .eval()?;
+        Ok((env, table))
+    }
+}
+
+/// A copy of the engine's globals in which every table reachable from
+/// them (`mdv`, `mdv.path`, `string`, ...) is copied too, so a definition
+/// can change anything it sees without affecting the next note.
+fn isolated_env(lua: &Lua) -> mlua::Result<Table> {
+    fn copy(
+        lua: &Lua,
+        table: &Table,
+        copies: &mut HashMap<*const std::ffi::c_void, Table>,
+    ) -> mlua::Result<Table> {
+        if let Some(done) = copies.get(&table.to_pointer()) {
+            return Ok(done.clone());
+        }
+        let out = lua.create_table()?;
+        copies.insert(table.to_pointer(), out.clone());
+        for pair in table.pairs::<Value, Value>() {
+            let (key, value) = pair?;
+            let value = match value {
+                Value::Table(t) if t.metatable().is_none() => {
+                    Value::Table(copy(lua, &t, copies)?)
+                }
+                other => other,
+            };
+            out.raw_set(key, value)?;
+        }
+        Ok(out)
+    }
+
+    copy(lua, &lua.globals(), &mut HashMap::new())
+}
+
+/// A type definition checked out of the pool, evaluated for one note.
+pub struct PooledTypedef {
+    loaded: Rc<Loaded>,
+    env: Table,
+    table: Table,
+}
+
+impl PooledTypedef {
+    /// The engine's Lua state.
+    pub fn lua(&self) -> &Lua {
+        self.loaded.engine.lua()
+    }
+
+    /// The table the type definition returned.
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    /// The `mdv` table the definition sees, for bindings added per note.
+    pub fn mdv(&self) -> mlua::Result<Table> {
+        self.env.get("mdv")
+    }
+}
+
+/// Check out `typedef`, compiling it in a new engine the first time it is
+/// used on this thread or after its source changed.
+pub fn checkout(typedef: &TypeDefinition) -> Result<PooledTypedef, ScriptingError> {
+    let loaded = LOADED.with(|loaded| {
+        let mut loaded = loaded.borrow_mut();
+        if let Some(entry) = loaded.get(&typedef.name)
+            && entry.is_current(typedef)
+        {
+            return Ok(entry.clone());
+        }
+        let entry = Rc::new(Loaded::load(typedef)?);
+        loaded.insert(typedef.name.clone(), entry.clone());
+        Ok::<_, ScriptingError>(entry)
+    })?;
+    let (env, table) = loaded.instantiate()?;
+    Ok(PooledTypedef { loaded, env, table })
+}
+
+/// Drop every engine this thread has pooled.
+pub fn clear() {
+    LOADED.with(|loaded| loaded.borrow_mut().clear());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typedef(name: &str, source: &str) -> TypeDefinition {
+        TypeDefinition { lua_source: source.to_string(), ..TypeDefinition::empty(name) }
+    }
+
+    #[test]
+    fn reuses_the_engine_until_the_source_changes() {
+        let counter = typedef(
+            "pool-counter",
+            "loads = (loads or 0) + 1; return { loads = function() return loads end }",
+        );
+        let loads = |td: &TypeDefinition| -> i64 {
+            let pooled = checkout(td).unwrap();
+            pooled.table().get::<mlua::Function>("loads").unwrap().call(()).unwrap()
+        };
+        assert_eq!(loads(&counter), 1);
+        assert_eq!(loads(&counter), 1);
+        assert!(Rc::ptr_eq(
+            &checkout(&counter).unwrap().loaded,
+            &checkout(&counter).unwrap().loaded
+        ));
+
+        let changed = typedef("pool-counter", &format!("{}\n", counter.lua_source));
+        assert!(!Rc::ptr_eq(
+            &checkout(&counter).unwrap().loaded,
+            &checkout(&changed).unwrap().loaded
+        ));
+    }
+
+    #[test]
+    fn resets_globals_between_checkouts() {
+        let td = typedef(
+            "pool-globals",
+            r#"
+            prefix = "note:"
+            return {
+                visit = function(path)
+                    local before = seen
+                    seen = path
+                    prefix = prefix .. "!"
+                    return tostring(before) .. " " .. prefix
+                end,
+            }
+            "#,
+        );
+        let visit = |path: &str| -> String {
+            let pooled = checkout(&td).unwrap();
+            pooled.table().get::<mlua::Function>("visit").unwrap().call(path).unwrap()
+        };
+        assert_eq!(visit("a.md"), "nil note:!");
+        assert_eq!(visit("b.md"), "nil note:!");
+
+        // The engine's own globals stay out of reach of the definition
+        let pooled = checkout(&td).unwrap();
+        assert!(pooled.lua().globals().get::<Value>("seen").unwrap().is_nil());
+    }
+
+    #[test]
+    fn captured_locals_and_tables_do_not_carry_over() {
+        let td = typedef(
+            "pool-locals",
+            r#"
+            local count = 0
+            local seen = {}
+            return {
+                visit = function(path)
+                    count = count + 1
+                    table.insert(seen, path)
+                    mdv.visits = (mdv.visits or 0) + 1
+                    string.tag = (string.tag or "") .. "!"
+                    return count .. " " .. #seen .. " " .. mdv.visits .. " " .. string.tag
+                end,
+            }
+            "#,
+        );
+        let visit = |path: &str| -> String {
+            let pooled = checkout(&td).unwrap();
+            pooled.table().get::<mlua::Function>("visit").unwrap().call(path).unwrap()
+        };
+        assert_eq!(visit("a.md"), "1 1 1 !");
+        assert_eq!(visit("b.md"), "1 1 1 !");
+
+        // The shared tables the engine hands out are untouched
+        let pooled = checkout(&td).unwrap();
+        let mdv: Table = pooled.lua().globals().get("mdv").unwrap();
+        assert!(mdv.get::<Value>("visits").unwrap().is_nil());
+    }
+}
@@ -0,0 +1,137 @@
+//! Line-by-line evaluation for the interactive Lua session (`mdv lua`).
+//!
+//! Input is evaluated the way the standalone `lua` interpreter does it: a
+//! line is first tried as an expression (`return <line>`), then as a
+//! statement. A chunk that is not finished yet (an open `function`, table or
+//! string) is kept until the following lines complete it. Globals persist
+//! between lines; `local`s end with their chunk.
+
+use mlua::{MultiValue, Value};
+
+use super::engine::LuaEngine;
+use super::hook_runner::lua_value_to_yaml;
+use super::types::ScriptingError;
+
+/// What a line of input did.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplStep {
+    /// The chunk is incomplete; more lines are needed.
+    Continue,
+    /// The chunk ran; these are its formatted results.
+    Done(Vec<String>),
+}
+
+/// An interactive session on top of a [`LuaEngine`].
+pub struct LuaRepl {
+    engine: LuaEngine,
+    pending: String,
+}
+
+impl LuaRepl {
+    pub fn new(engine: LuaEngine) -> Self {
+        Self { engine, pending: String::new() }
+    }
+
+    /// Whether an incomplete chunk is waiting for more lines.
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Drop an incomplete chunk.
+    pub fn reset(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Feed one line of input.
+    ///
+    /// On an error the pending chunk is dropped, so the next line starts fresh.
+    pub fn eval_line(&mut self, line: &str) -> Result<ReplStep, ScriptingError> {
+        if !self.pending.is_empty() {
+            self.pending.push('\n');
+        }
+        self.pending.push_str(line);
+        if self.pending.trim().is_empty() {
+            self.pending.clear();
+            return Ok(ReplStep::Done(Vec::new()));
+        }
+
+        let lua = self.engine.lua();
+        let chunk = match lua
+            .load(format!("return {}", self.pending))
+            .set_name("=stdin")
+            .into_function()
+        {
+            Ok(function) => function,
+            Err(_) => {
+                match lua.load(self.pending.as_str()).set_name("=stdin").into_function() {
+                    Ok(function) => function,
+                    Err(mlua::Error::SyntaxError { incomplete_input: true, .. }) => {
+                        return Ok(ReplStep::Continue);
+                    }
+                    Err(e) => {
+                        self.pending.clear();
+                        return Err(e.into());
+                    }
+                }
+            }
+        };
+        self.pending.clear();
+
+        let values: MultiValue = chunk.call(())?;
+        Ok(ReplStep::Done(values.iter().map(format_value).collect()))
+    }
+}
+
+/// Show a result: tables as YAML, everything else as `tostring` would.
+pub fn format_value(value: &Value) -> String {
+    if let Value::Table(_) = value
+        && let Ok(yaml) = lua_value_to_yaml(value.clone())
+        && let Ok(text) = serde_yaml::to_string(&yaml)
+    {
+        return text.trim_end().to_string();
+    }
+    if let Value::String(s) = value {
+        return s.to_string_lossy();
+    }
+    value.to_string().unwrap_or_else(|_| value.type_name().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn done(values: &[&str]) -> ReplStep {
+        ReplStep::Done(values.iter().map(|v| v.to_string()).collect())
+    }
+
+    #[test]
+    fn evaluates_expressions_and_statements() {
+        let mut repl = LuaRepl::new(LuaEngine::sandboxed().unwrap());
+        assert_eq!(repl.eval_line("1 + 2").unwrap(), done(&["3"]));
+        assert_eq!(repl.eval_line("x = 'hi'").unwrap(), done(&[]));
+        assert_eq!(repl.eval_line("x, #x").unwrap(), done(&["hi", "2"]));
+        assert_eq!(repl.eval_line("   ").unwrap(), done(&[]));
+        assert_eq!(
+            repl.eval_line("{ tags = { 'x', 'y' } }").unwrap(),
+            done(&["tags:\n- x\n- y"])
+        );
+        assert!(
+            repl.eval_line("error('boom')").unwrap_err().to_string().contains("boom")
+        );
+    }
+
+    #[test]
+    fn keeps_incomplete_chunks() {
+        let mut repl = LuaRepl::new(LuaEngine::sandboxed().unwrap());
+        assert_eq!(repl.eval_line("function double(n)").unwrap(), ReplStep::Continue);
+        assert!(repl.is_pending());
+        assert_eq!(repl.eval_line("  return n * 2").unwrap(), ReplStep::Continue);
+        assert_eq!(repl.eval_line("end").unwrap(), done(&[]));
+        assert!(!repl.is_pending());
+        assert_eq!(repl.eval_line("double(21)").unwrap(), done(&["42"]));
+
+        // A real syntax error is reported and dropped
+        assert!(repl.eval_line("x = = 1").is_err());
+        assert!(!repl.is_pending());
+    }
+}
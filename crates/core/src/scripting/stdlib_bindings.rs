@@ -0,0 +1,262 @@
+//! General-purpose helpers for Lua scripts.
+//!
+//! This module adds helpers that hooks and typedefs would otherwise write
+//! in plain Lua:
+//! - `mdv.slug(str)` - Slug a string the way `{{... | slugify}}` does
+//! - `mdv.path.join/basename/relative` - Vault path manipulation
+//! - `mdv.yaml.parse/emit` - YAML to Lua values and back
+//! - `mdv.string.split/trim/starts_with/ends_with/replace` - Plain-text string helpers
+
+use std::path::{Component, Path, PathBuf};
+
+use mlua::{Lua, MultiValue, Result as LuaResult, Table, Value, Variadic};
+
+use super::hook_runner::lua_value_to_yaml;
+use crate::templates::engine::slugify;
+use crate::types::validation::yaml_to_lua_table;
+
+/// Register the helpers on an existing mdv table.
+pub fn register_stdlib_bindings(lua: &Lua) -> LuaResult<()> {
+    let mdv: Table = lua.globals().get("mdv")?;
+
+    mdv.set("slug", lua.create_function(|_, s: String| Ok(slugify(&s)))?)?;
+    mdv.set("path", create_path_table(lua)?)?;
+    mdv.set("yaml", create_yaml_table(lua)?)?;
+    mdv.set("string", create_string_table(lua)?)?;
+
+    Ok(())
+}
+
+/// Create the `mdv.path` table.
+///
+/// Paths are strings with `/` separators, as in note paths.
+///
+/// # Examples (in Lua)
+///
+/// ```lua
+/// mdv.path.join("Projects", "MCP", "MCP.md")           -- "Projects/MCP/MCP.md"
+/// mdv.path.basename("Projects/MCP/MCP.md")             -- "MCP.md"
+/// mdv.path.basename("Projects/MCP/MCP.md", ".md")      -- "MCP"
+/// mdv.path.relative("Projects/MCP/Tasks/T.md", "Projects/MCP")  -- "Tasks/T.md"
+/// ```
+fn create_path_table(lua: &Lua) -> LuaResult<Table> {
+    let path = lua.create_table()?;
+
+    path.set(
+        "join",
+        lua.create_function(|_, parts: Variadic<String>| {
+            let joined: PathBuf = parts.iter().filter(|p| !p.is_empty()).collect();
+            Ok(to_slash(&joined))
+        })?,
+    )?;
+    path.set(
+        "basename",
+        lua.create_function(|_, (p, suffix): (String, Option<String>)| {
+            let name = Path::new(&p)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            Ok(match suffix.as_deref().and_then(|s| name.strip_suffix(s)) {
+                Some(stem) if !stem.is_empty() => stem.to_string(),
+                _ => name,
+            })
+        })?,
+    )?;
+    path.set(
+        "relative",
+        lua.create_function(|_, (p, base): (String, String)| {
+            Ok(to_slash(&relative_path(Path::new(&p), Path::new(&base))))
+        })?,
+    )?;
+
+    Ok(path)
+}
+
+/// The path of `path` relative to `base`, worked out from the path text.
+///
+/// `.` segments are dropped; `..` is added to climb out of `base` where the
+/// two paths part.
+fn relative_path(path: &Path, base: &Path) -> PathBuf {
+    fn parts(p: &Path) -> Vec<Component<'_>> {
+        p.components().filter(|c| *c != Component::CurDir).collect()
+    }
+    let (path, base) = (parts(path), parts(base));
+    let common = path.iter().zip(&base).take_while(|(a, b)| a == b).count();
+
+    let mut out = PathBuf::new();
+    for _ in common..base.len() {
+        out.push("..");
+    }
+    out.extend(&path[common..]);
+    if out.as_os_str().is_empty() { PathBuf::from(".") } else { out }
+}
+
+fn to_slash(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Create the `mdv.yaml` table.
+///
+/// `parse` returns `nil, error` for invalid YAML. `emit` writes a table as
+/// a YAML document without the leading `---`.
+///
+/// # Examples (in Lua)
+///
+/// ```lua
+/// local data = mdv.yaml.parse("tags: [a, b]\npriority: 2")
+/// print(data.tags[2])                    -- "b"
+/// mdv.yaml.emit({ status = "open" })     -- "status: open\n"
+/// ```
+fn create_yaml_table(lua: &Lua) -> LuaResult<Table> {
+    let yaml = lua.create_table()?;
+
+    yaml.set(
+        "parse",
+        lua.create_function(|lua, text: String| {
+            match serde_yaml::from_str::<serde_yaml::Value>(&text) {
+                Ok(value) => {
+                    Ok(MultiValue::from_vec(vec![yaml_to_lua_table(lua, &value)?]))
+                }
+                Err(e) => Ok(MultiValue::from_vec(vec![
+                    Value::Nil,
+                    Value::String(lua.create_string(e.to_string())?),
+                ])),
+            }
+        })?,
+    )?;
+    yaml.set(
+        "emit",
+        lua.create_function(|_, value: Value| {
+            let value = lua_value_to_yaml(value)
+                .map_err(|e| mlua::Error::runtime(e.to_string()))?;
+            serde_yaml::to_string(&value).map_err(|e| mlua::Error::runtime(e.to_string()))
+        })?,
+    )?;
+
+    Ok(yaml)
+}
+
+/// Create the `mdv.string` table.
+///
+/// Unlike Lua's `string.find` and `string.gsub`, these take plain text,
+/// not patterns.
+///
+/// # Examples (in Lua)
+///
+/// ```lua
+/// mdv.string.split("a, b, c", ", ")            -- { "a", "b", "c" }
+/// mdv.string.trim("  hi  ")                    -- "hi"
+/// mdv.string.starts_with("TASK-1", "TASK-")    -- true
+/// mdv.string.ends_with("note.md", ".md")       -- true
+/// mdv.string.replace("a.b.c", ".", "/")        -- "a/b/c"
+/// ```
+fn create_string_table(lua: &Lua) -> LuaResult<Table> {
+    let string = lua.create_table()?;
+
+    string.set(
+        "split",
+        lua.create_function(|_, (s, sep): (String, Option<String>)| {
+            Ok(match sep.as_deref() {
+                None | Some("") => s.split_whitespace().map(str::to_string).collect(),
+                Some(sep) => s.split(sep).map(str::to_string).collect::<Vec<_>>(),
+            })
+        })?,
+    )?;
+    string.set("trim", lua.create_function(|_, s: String| Ok(s.trim().to_string()))?)?;
+    string.set(
+        "starts_with",
+        lua.create_function(|_, (s, prefix): (String, String)| {
+            Ok(s.starts_with(&prefix))
+        })?,
+    )?;
+    string.set(
+        "ends_with",
+        lua.create_function(|_, (s, suffix): (String, String)| Ok(s.ends_with(&suffix)))?,
+    )?;
+    string.set(
+        "replace",
+        lua.create_function(|_, (s, from, to): (String, String, String)| {
+            Ok(if from.is_empty() { s } else { s.replace(&from, &to) })
+        })?,
+    )?;
+
+    Ok(string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scripting::LuaEngine;
+
+    fn eval(script: &str) -> String {
+        LuaEngine::sandboxed().unwrap().eval_string(script).unwrap()
+    }
+
+    #[test]
+    fn test_slug_matches_template_filter() {
+        assert_eq!(eval(r#"mdv.slug("Team Sync: Q3 Plan")"#), "team-sync-q3-plan");
+    }
+
+    #[test]
+    fn test_path_helpers() {
+        assert_eq!(
+            eval(r#"mdv.path.join("Projects", "", "MCP", "MCP.md")"#),
+            "Projects/MCP/MCP.md"
+        );
+        assert_eq!(eval(r#"mdv.path.basename("Projects/MCP/MCP.md")"#), "MCP.md");
+        assert_eq!(eval(r#"mdv.path.basename("Projects/MCP/MCP.md", ".md")"#), "MCP");
+        assert_eq!(
+            eval(r#"mdv.path.relative("Projects/MCP/Tasks/T.md", "./Projects/MCP")"#),
+            "Tasks/T.md"
+        );
+        assert_eq!(
+            eval(r#"mdv.path.relative("Inbox/a.md", "Projects/MCP")"#),
+            "../../Inbox/a.md"
+        );
+        assert_eq!(eval(r#"mdv.path.relative("Projects", "Projects")"#), ".");
+    }
+
+    #[test]
+    fn test_yaml_round_trip() {
+        assert_eq!(
+            eval(
+                r#"local d = mdv.yaml.parse("tags: [a, b]\npriority: 2")
+                return d.tags[2] .. d.priority"#
+            ),
+            "b2"
+        );
+        assert_eq!(eval(r#"mdv.yaml.emit({ status = "open" })"#), "status: open\n");
+        assert_eq!(
+            eval(
+                r#"local d, err = mdv.yaml.parse("a: [")
+                return tostring(d) .. " " .. tostring(err ~= nil)"#
+            ),
+            "nil true"
+        );
+    }
+
+    #[test]
+    fn test_string_helpers() {
+        assert_eq!(
+            eval(r#"table.concat(mdv.string.split("a, b, c", ", "), "|")"#),
+            "a|b|c"
+        );
+        assert_eq!(eval(r#"table.concat(mdv.string.split(" a  b "), "|")"#), "a|b");
+        assert_eq!(eval(r#"mdv.string.trim("  hi  ")"#), "hi");
+        assert_eq!(
+            eval(r#"tostring(mdv.string.starts_with("TASK-1", "TASK-"))"#),
+            "true"
+        );
+        assert_eq!(eval(r#"tostring(mdv.string.ends_with("note.md", ".txt"))"#), "false");
+        assert_eq!(eval(r#"mdv.string.replace("a.b.c", ".", "/")"#), "a/b/c");
+    }
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(
+            relative_path(Path::new("a/b/c"), Path::new("a")),
+            PathBuf::from("b/c")
+        );
+        assert_eq!(relative_path(Path::new("a"), Path::new("a/b")), PathBuf::from(".."));
+    }
+}
@@ -0,0 +1,181 @@
+//! Template filters written in Lua.
+//!
+//! `.mdvault/filters.lua` returns a table of functions, each taking the
+//! value and the filter's argument:
+//!
+//! ```lua
+//! return {
+//!     initials = function(value)
+//!         return (value:gsub("(%w)%w*%s*", "%1"))
+//!     end,
+//!     prefix = function(value, arg)
+//!         return (arg or "") .. value
+//!     end,
+//! }
+//! ```
+//!
+//! Once loaded, `{{author | initials}}` and `{{title | prefix:"wip-"}}` work
+//! in templates, captures and typedef output paths. A function must return
+//! a string or a number.
+
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use mlua::{Function, Value};
+use thiserror::Error;
+
+use super::engine::LuaEngine;
+use super::types::ScriptingError;
+use crate::templates::filters::{FilterFn, register_filter};
+
+/// Errors loading template filters.
+#[derive(Debug, Error)]
+pub enum TemplateFilterError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to evaluate {path}: {source}")]
+    Lua {
+        path: PathBuf,
+        #[source]
+        source: ScriptingError,
+    },
+
+    #[error("Invalid template filters in {path}: {message}")]
+    Invalid { path: PathBuf, message: String },
+}
+
+/// Evaluate a filters file and register its functions for templates
+/// rendered on this thread. Returns the names registered, sorted.
+///
+/// Does nothing if the file doesn't exist. Names of built-in filters are
+/// rejected, since those cannot be replaced.
+pub fn load_template_filters(path: &Path) -> Result<Vec<String>, TemplateFilterError> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let invalid = |message: String| TemplateFilterError::Invalid {
+        path: path.to_path_buf(),
+        message,
+    };
+    let lua_err = |e: ScriptingError| TemplateFilterError::Lua {
+        path: path.to_path_buf(),
+        source: e,
+    };
+
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| TemplateFilterError::Io { path: path.to_path_buf(), source: e })?;
+    let engine = LuaEngine::sandboxed().map_err(lua_err)?;
+    let value: Value = engine
+        .lua()
+        .load(&source)
+        .set_name(path.to_string_lossy())
+        .eval()
+        .map_err(|e| lua_err(e.into()))?;
+    let Value::Table(table) = value else {
+        return Err(invalid("the file must return a table of functions".into()));
+    };
+
+    let mut filters = Vec::new();
+    for pair in table.pairs::<String, Value>() {
+        let (name, value) = pair.map_err(|e| lua_err(e.into()))?;
+        let Value::Function(function) = value else {
+            return Err(invalid(format!("'{name}' is not a function")));
+        };
+        filters.push((name, function));
+    }
+    filters.sort_by(|a, b| a.0.cmp(&b.0));
+
+    // Lua functions only hold a weak reference to their state, so every
+    // filter keeps the engine alive.
+    let engine = Rc::new(engine);
+    let mut names = Vec::new();
+    for (name, function) in filters {
+        if !register_filter(&name, lua_filter(engine.clone(), function)) {
+            return Err(invalid(format!("'{name}' is a built-in filter")));
+        }
+        names.push(name);
+    }
+    Ok(names)
+}
+
+/// A Lua function and the engine its state lives in.
+struct LuaFilter {
+    function: Function,
+    _engine: Rc<LuaEngine>,
+}
+
+impl LuaFilter {
+    fn apply(&self, value: &str, arg: Option<&str>) -> Result<String, String> {
+        match self.function.call::<Value>((value, arg)).map_err(|e| e.to_string())? {
+            Value::String(s) => Ok(s.to_string_lossy()),
+            Value::Integer(n) => Ok(n.to_string()),
+            Value::Number(n) => Ok(n.to_string()),
+            other => Err(format!("returned a {}, expected a string", other.type_name())),
+        }
+    }
+}
+
+fn lua_filter(engine: Rc<LuaEngine>, function: Function) -> FilterFn {
+    let filter = LuaFilter { function, _engine: engine };
+    Rc::new(move |value, arg| filter.apply(value, arg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::templates::engine::{RenderContext, render_string};
+    use crate::templates::filters::clear_filters;
+    use tempfile::TempDir;
+
+    fn write(dir: &TempDir, source: &str) -> PathBuf {
+        let path = dir.path().join("filters.lua");
+        std::fs::write(&path, source).unwrap();
+        path
+    }
+
+    #[test]
+    fn registers_filters_for_templates() {
+        let dir = TempDir::new().unwrap();
+        let path = write(
+            &dir,
+            r#"return {
+                prefix = function(value, arg) return (arg or "") .. value end,
+                len = function(value) return #value end,
+            }"#,
+        );
+        assert_eq!(load_template_filters(&path).unwrap(), ["len", "prefix"]);
+
+        let mut ctx = RenderContext::new();
+        ctx.insert("title".into(), "Team Sync".into());
+        let rendered =
+            render_string(r#"{{title | slug | prefix:"wip-"}} {{title | len}}"#, &ctx)
+                .unwrap();
+        assert_eq!(rendered, "wip-team-sync 9");
+        clear_filters();
+    }
+
+    #[test]
+    fn rejects_invalid_files() {
+        let dir = TempDir::new().unwrap();
+        let missing = dir.path().join("none.lua");
+        assert!(load_template_filters(&missing).unwrap().is_empty());
+
+        for source in [
+            "return 1",
+            "return { shout = 'loud' }",
+            "return { upper = function(v) return v end }",
+        ] {
+            let path = write(&dir, source);
+            assert!(matches!(
+                load_template_filters(&path),
+                Err(TemplateFilterError::Invalid { .. })
+            ));
+        }
+        clear_filters();
+    }
+}
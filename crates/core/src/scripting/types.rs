@@ -1,7 +1,11 @@
 //! Scripting types and error definitions.
 
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use thiserror::Error;
 
+use crate::config::types::SecurityPolicy;
+
 /// Errors that can occur during Lua script execution.
 #[derive(Debug, Error)]
 pub enum ScriptingError {
@@ -26,6 +30,146 @@ pub enum ScriptingError {
     Other(String),
 }
 
+/// Set once per process by `--trust-scripts`.
+static TRUSTED: AtomicBool = AtomicBool::new(false);
+
+/// Let scripts have the `shell` and `net` permissions they declare even when
+/// `security.allow_shell` / `security.allow_http` are off (`--trust-scripts`).
+pub fn set_trust_scripts(trusted: bool) {
+    TRUSTED.store(trusted, Ordering::Relaxed);
+}
+
+/// Whether `--trust-scripts` is in effect.
+pub fn trust_scripts() -> bool {
+    TRUSTED.load(Ordering::Relaxed)
+}
+
+/// What a script may do beyond computing values, declared in its table:
+///
+/// ```lua
+/// permissions = { read_vault = true, write_vault = false, net = true }
+/// ```
+///
+/// Undeclared permissions keep their defaults: vault access on, shell and
+/// network off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permissions {
+    /// Read notes and query the index (`mdv.read_note`, `mdv.query`, ...).
+    pub read_vault: bool,
+    /// Create and change notes (`mdv.write_note`, `mdv.capture`, `mdv.macro`, ...).
+    pub write_vault: bool,
+    /// The Lua `os` and `io` libraries: run programs, touch any file.
+    pub shell: bool,
+    /// HTTP requests with `mdv.fetch`.
+    pub net: bool,
+}
+
+impl Default for Permissions {
+    fn default() -> Self {
+        Self { read_vault: true, write_vault: true, shell: false, net: false }
+    }
+}
+
+/// One of the [`Permissions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    ReadVault,
+    WriteVault,
+    Shell,
+    Net,
+}
+
+impl Capability {
+    /// The key in a `permissions` table.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ReadVault => "read_vault",
+            Self::WriteVault => "write_vault",
+            Self::Shell => "shell",
+            Self::Net => "net",
+        }
+    }
+
+    /// Why a script may lack this capability and how to grant it.
+    pub(crate) fn denied(self, what: &str) -> String {
+        let declare = format!("declare permissions = {{ {} = true }}", self.name());
+        match self {
+            Self::ReadVault | Self::WriteVault => {
+                format!("{what} is not permitted: {declare}")
+            }
+            Self::Shell => format!(
+                "{what} is not permitted: {declare} and set security.allow_shell \
+                 or pass --trust-scripts"
+            ),
+            Self::Net => format!(
+                "{what} is not permitted: {declare} and set security.allow_http \
+                 or pass --trust-scripts"
+            ),
+        }
+    }
+}
+
+impl Permissions {
+    /// Everything, for code the user types themselves (`mdv lua`).
+    pub fn all() -> Self {
+        Self { read_vault: true, write_vault: true, shell: true, net: true }
+    }
+
+    pub fn allows(&self, capability: Capability) -> bool {
+        match capability {
+            Capability::ReadVault => self.read_vault,
+            Capability::WriteVault => self.write_vault,
+            Capability::Shell => self.shell,
+            Capability::Net => self.net,
+        }
+    }
+
+    /// Drop `shell` and `net` unless the security policy or `--trust-scripts`
+    /// allows them.
+    pub fn within(self, policy: &SecurityPolicy) -> Self {
+        Self {
+            shell: self.shell && (policy.allow_shell || trust_scripts()),
+            net: self.net && (policy.allow_http || trust_scripts()),
+            ..self
+        }
+    }
+
+    /// Drop `write_vault`, `shell` and `net`, for previews that must leave
+    /// the vault, the system and remote services as they are.
+    pub fn read_only(self) -> Self {
+        Self { write_vault: false, shell: false, net: false, ..self }
+    }
+
+    /// Read a script's `permissions` field; `nil` gives the defaults.
+    pub fn from_lua(value: mlua::Value) -> Result<Self, String> {
+        let table = match value {
+            mlua::Value::Nil => return Ok(Self::default()),
+            mlua::Value::Table(table) => table,
+            _ => return Err("permissions must be a table".to_string()),
+        };
+        let mut permissions = Self::default();
+        for pair in table.pairs::<String, mlua::Value>() {
+            let (key, value) = pair.map_err(|e| format!("invalid permissions: {e}"))?;
+            let mlua::Value::Boolean(granted) = value else {
+                return Err(format!("permissions.{key} must be true or false"));
+            };
+            match key.as_str() {
+                "read_vault" => permissions.read_vault = granted,
+                "write_vault" => permissions.write_vault = granted,
+                "shell" => permissions.shell = granted,
+                "net" => permissions.net = granted,
+                _ => {
+                    return Err(format!(
+                        "unknown permission '{key}' (expected read_vault, write_vault, \
+                         shell or net)"
+                    ));
+                }
+            }
+        }
+        Ok(permissions)
+    }
+}
+
 /// Configuration for the Lua sandbox.
 #[derive(Debug, Clone)]
 pub struct SandboxConfig {
@@ -37,6 +181,10 @@ pub struct SandboxConfig {
 
     /// Whether to allow `require` for loading modules.
     pub allow_require: bool,
+
+    /// What scripts in the engine may do. `shell` and `net` are further
+    /// limited by the security policy.
+    pub permissions: Permissions,
 }
 
 impl Default for SandboxConfig {
@@ -52,11 +200,23 @@ impl SandboxConfig {
             memory_limit: 10 * 1024 * 1024, // 10 MB
             instruction_limit: 100_000,
             allow_require: false,
+            permissions: Permissions::default(),
         }
     }
 
     /// An unrestricted configuration (use with caution).
     pub fn unrestricted() -> Self {
-        Self { memory_limit: 0, instruction_limit: 0, allow_require: true }
+        Self {
+            memory_limit: 0,
+            instruction_limit: 0,
+            allow_require: true,
+            permissions: Permissions::all(),
+        }
+    }
+
+    /// Run scripts with `permissions` instead of the defaults.
+    pub fn with_permissions(mut self, permissions: Permissions) -> Self {
+        self.permissions = permissions;
+        self
     }
 }
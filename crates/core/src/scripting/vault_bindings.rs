@@ -5,20 +5,26 @@
 //! - `mdv.capture(name, vars?)` - Execute a capture workflow
 //! - `mdv.macro(name, vars?)` - Execute a macro workflow
 //! - `mdv.read_note(path)` - Read a note's content and frontmatter
+//! - `mdv.write_note(path, note)` - Write a note inside the vault
+//! - `mdv.set_frontmatter(path, fields)` - Update fields of a note's frontmatter
 
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 
 use chrono::Local;
 use mlua::{Function, Lua, MultiValue, Result as LuaResult, Table, Value};
 
+use super::hook_runner::lua_table_to_yaml;
 use super::selector::{SelectorItem, SelectorOptions};
 use super::vault_context::VaultContext;
+use crate::activity::ActivityLogService;
 use crate::captures::CaptureSpec;
 use crate::config::types::ResolvedConfig;
-use crate::frontmatter::{apply_ops, parse, serialize};
-use crate::index::NoteQuery;
+use crate::frontmatter::{
+    Frontmatter, ParsedDocument, apply_ops, parse, serialize, serialize_with_order,
+};
+use crate::index::{IndexBuilder, NoteQuery};
 use crate::macros::runner::{MacroRunError, RunContext, RunOptions, StepExecutor};
 use crate::macros::types::{CaptureStep, ShellStep, StepResult, TemplateStep};
 use crate::markdown_ast::{MarkdownEditor, SectionMatch};
@@ -39,6 +45,8 @@ pub fn register_vault_bindings(lua: &Lua, ctx: VaultContext) -> LuaResult<()> {
     mdv.set("capture", create_capture_fn(lua)?)?;
     mdv.set("macro", create_macro_fn(lua)?)?;
     mdv.set("read_note", create_read_note_fn(lua)?)?;
+    mdv.set("write_note", create_write_note_fn(lua)?)?;
+    mdv.set("set_frontmatter", create_set_frontmatter_fn(lua)?)?;
     mdv.set("selector", create_selector_fn(lua)?)?;
 
     Ok(())
@@ -333,6 +341,192 @@ fn create_read_note_fn(lua: &Lua) -> LuaResult<Function> {
     })
 }
 
+/// Create the `mdv.write_note(path, note)` function.
+///
+/// `note` is either the full text of the note or a table with `frontmatter`
+/// and `body`, which is serialized in the type's field order. The path must
+/// stay inside the vault; missing folders are created. The write is logged
+/// to the activity log and the note reindexed.
+///
+/// Returns: `(true, nil)` on success, `(false, error)` on failure.
+///
+/// # Examples (in Lua)
+///
+/// ```lua
+/// local ok, err = mdv.write_note("Inbox/idea.md", {
+///     frontmatter = { type = "zettel", title = "Idea" },
+///     body = "# Idea\n",
+/// })
+/// ```
+fn create_write_note_fn(lua: &Lua) -> LuaResult<Function> {
+    lua.create_function(|lua, (path, note): (String, Value)| {
+        let ctx = lua
+            .app_data_ref::<VaultContext>()
+            .ok_or_else(|| mlua::Error::runtime("VaultContext not available"))?;
+
+        let result = resolve_writable_path(&ctx.vault_root, &path).and_then(|rel| {
+            let content = match note {
+                Value::String(s) => {
+                    let content = s.to_str()?.to_string();
+                    parse(&content).map_err(|e| {
+                        mlua::Error::runtime(format!("invalid frontmatter: {e}"))
+                    })?;
+                    content
+                }
+                Value::Table(t) => {
+                    let fields = match t.get::<Option<Table>>("frontmatter")? {
+                        Some(fm) => yaml_fields(&fm)?,
+                        None => Default::default(),
+                    };
+                    let body: String =
+                        t.get::<Option<String>>("body")?.unwrap_or_default();
+                    let doc = ParsedDocument {
+                        frontmatter: (!fields.is_empty())
+                            .then_some(Frontmatter { fields }),
+                        body,
+                    };
+                    serialize_with_order(&doc, field_order(&ctx, &doc).as_deref())
+                }
+                other => {
+                    return Err(mlua::Error::runtime(format!(
+                        "note must be a string or a table, got {}",
+                        other.type_name()
+                    )));
+                }
+            };
+            write_vault_note(&ctx, &rel, &content)
+        });
+        write_result(lua, result)
+    })
+}
+
+/// Create the `mdv.set_frontmatter(path, fields)` function.
+///
+/// Sets the given fields in an existing note's frontmatter, keeping the
+/// rest of the note as it is. Like `mdv.write_note`, the write is logged
+/// and the note reindexed.
+///
+/// Returns: `(true, nil)` on success, `(false, error)` on failure.
+///
+/// # Examples (in Lua)
+///
+/// ```lua
+/// local project = mdv.find_project("MCP")
+/// mdv.set_frontmatter(project.path, { task_counter = project.frontmatter.task_counter + 1 })
+/// ```
+fn create_set_frontmatter_fn(lua: &Lua) -> LuaResult<Function> {
+    lua.create_function(|lua, (path, fields): (String, Table)| {
+        let ctx = lua
+            .app_data_ref::<VaultContext>()
+            .ok_or_else(|| mlua::Error::runtime("VaultContext not available"))?;
+
+        let result = resolve_writable_path(&ctx.vault_root, &path).and_then(|rel| {
+            let full_path = ctx.vault_root.join(&rel);
+            let content = fs::read_to_string(&full_path).map_err(|e| {
+                mlua::Error::runtime(format!("failed to read '{}': {e}", rel.display()))
+            })?;
+            let mut doc = parse(&content)
+                .map_err(|e| mlua::Error::runtime(format!("invalid frontmatter: {e}")))?;
+            let mut frontmatter = doc.frontmatter.take().unwrap_or_default();
+            frontmatter.fields.extend(yaml_fields(&fields)?);
+            doc.frontmatter = Some(frontmatter);
+            let updated = serialize_with_order(&doc, field_order(&ctx, &doc).as_deref());
+            write_vault_note(&ctx, &rel, &updated)
+        });
+        write_result(lua, result)
+    })
+}
+
+/// Resolve a note path for writing, relative to the vault root.
+///
+/// Scripts may only write markdown notes inside the vault, and not under
+/// `.mdvault/`.
+fn resolve_writable_path(vault_root: &Path, path: &str) -> LuaResult<PathBuf> {
+    let path =
+        if path.ends_with(".md") { path.to_string() } else { format!("{path}.md") };
+    let path = Path::new(&path);
+    let rel =
+        if path.is_absolute() { path.strip_prefix(vault_root).ok() } else { Some(path) };
+    let escapes = |rel: &Path| {
+        rel.components().any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+            || rel.starts_with(".mdvault")
+    };
+    match rel {
+        Some(rel) if !escapes(rel) => Ok(rel.to_path_buf()),
+        _ => Err(mlua::Error::runtime(format!(
+            "'{}' is outside the vault or in .mdvault/",
+            path.display()
+        ))),
+    }
+}
+
+/// Write a note atomically, then log it and reindex it.
+fn write_vault_note(ctx: &VaultContext, rel: &Path, content: &str) -> LuaResult<()> {
+    let full_path = ctx.vault_root.join(rel);
+    let created = !full_path.exists();
+    let write = || -> std::io::Result<()> {
+        if let Some(parent) = full_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let name = full_path.file_name().unwrap_or_default().to_string_lossy();
+        let tmp = full_path.with_file_name(format!(".{name}.mdv-tmp"));
+        fs::write(&tmp, content)?;
+        fs::rename(&tmp, &full_path)
+    };
+    write().map_err(|e| {
+        mlua::Error::runtime(format!("failed to write '{}': {e}", rel.display()))
+    })?;
+
+    if let Some(activity) = ActivityLogService::try_from_config(&ctx.config) {
+        let note_type = parse(content)
+            .ok()
+            .and_then(|doc| doc.frontmatter)
+            .and_then(|fm| {
+                fm.fields.get("type").and_then(|v| v.as_str()).map(String::from)
+            })
+            .unwrap_or_else(|| "none".to_string());
+        let _ = activity.log_script_write(&note_type, &full_path, created);
+    }
+    if let Some(db) = &ctx.index_db {
+        let builder =
+            IndexBuilder::new(db, &ctx.vault_root).with_types(&ctx.type_registry);
+        if let Err(e) = builder.reindex_file(rel) {
+            tracing::warn!("failed to reindex {}: {e}", rel.display());
+        }
+    }
+    Ok(())
+}
+
+/// Frontmatter fields from a Lua table.
+fn yaml_fields(table: &Table) -> LuaResult<HashMap<String, serde_yaml::Value>> {
+    let serde_yaml::Value::Mapping(map) =
+        lua_table_to_yaml(table).map_err(|e| mlua::Error::runtime(e.to_string()))?
+    else {
+        return Ok(HashMap::new());
+    };
+    Ok(map
+        .into_iter()
+        .filter_map(|(k, v)| k.as_str().map(|k| (k.to_string(), v)))
+        .collect())
+}
+
+/// Field order of the document's type, if its typedef sets one.
+fn field_order(ctx: &VaultContext, doc: &ParsedDocument) -> Option<Vec<String>> {
+    let note_type = doc.frontmatter.as_ref()?.fields.get("type")?.as_str()?;
+    ctx.type_registry.get(note_type)?.frontmatter_order.clone()
+}
+
+/// `(true, nil)` or `(false, error)` for the write bindings.
+fn write_result(lua: &Lua, result: LuaResult<()>) -> LuaResult<MultiValue> {
+    Ok(match result {
+        Ok(()) => MultiValue::from_vec(vec![Value::Boolean(true), Value::Nil]),
+        Err(e) => MultiValue::from_vec(vec![
+            Value::Boolean(false),
+            Value::String(lua.create_string(e.to_string())?),
+        ]),
+    })
+}
+
 /// Create the `mdv.selector(opts)` function.
 ///
 /// Shows an interactive selector for notes of a given type.
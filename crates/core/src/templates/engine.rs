@@ -10,6 +10,7 @@ use crate::config::types::ResolvedConfig;
 use crate::vars::datemath::{evaluate_date_expr_with_ref, is_date_expr, parse_date_expr};
 
 use super::discovery::TemplateInfo;
+use super::filters::{apply_filter, parse_filters};
 use super::repository::LoadedTemplate;
 
 #[derive(Debug, Error)]
@@ -237,7 +238,9 @@ fn filter_template_fields(raw_fm: &str) -> String {
 /// Supports:
 /// - Simple variables: `{{var_name}}`
 /// - Date math expressions: `{{today + 1d}}`, `{{now - 2h}}`, `{{today | %Y-%m-%d}}`
-/// - Filters: `{{var_name | filter}}` (currently supports: slugify)
+/// - Filters: `{{var_name | filter}}`, chained and with arguments:
+///   `{{due | fmt:"%B %d"}}`, `{{status | default:"todo" | upper}}`.
+///   See [`super::filters`] for the built-in filters.
 pub fn render_string(
     template: &str,
     ctx: &RenderContext,
@@ -261,20 +264,25 @@ pub fn render_string_with_ref_date(
     let result = re.replace_all(template, |caps: &regex::Captures<'_>| {
         let expr = caps[1].trim();
 
-        // Check for filter syntax first: "var_name | filter"
-        if let Some((var_name, filter)) = parse_filter_expr(expr) {
-            if let Some(value) = ctx.get(var_name) {
-                return apply_filter(value, filter);
-            }
-            // Variable not found, but might be a date expression with format
-            // (e.g., "today | %Y-%m-%d")
-            if is_date_expr(expr)
-                && let Ok(parsed) = parse_date_expr(expr)
+        // Check for filter syntax first: "var_name | filter | filter:arg"
+        if let Some((var_name, chain)) = parse_filter_expr(expr) {
+            let filters = parse_filters(chain);
+            let value = if let Some(value) = ctx.get(var_name) {
+                value.clone()
+            } else if is_date_expr(var_name)
+                && let Ok(parsed) = parse_date_expr(var_name)
             {
-                return evaluate_date_expr_with_ref(&parsed, ref_date);
-            }
-            debug!("Template variable not found for filter: {}", var_name);
-            return caps[0].to_string();
+                // e.g. "today + 7d | fmt:\"%B %d\""
+                evaluate_date_expr_with_ref(&parsed, ref_date)
+            } else if filters.iter().any(|(name, _)| *name == "default") {
+                String::new()
+            } else {
+                debug!("Template variable not found for filter: {}", var_name);
+                return caps[0].to_string();
+            };
+            return filters
+                .into_iter()
+                .fold(value, |value, (name, arg)| apply_filter(&value, name, arg));
         }
 
         // Check context variable FIRST - if explicitly set, use it
@@ -298,9 +306,9 @@ pub fn render_string_with_ref_date(
     Ok(result.into_owned())
 }
 
-/// Parse a filter expression like "var_name | filter_name".
-/// Returns (var_name, filter_name) if valid, None otherwise.
-fn parse_filter_expr(expr: &str) -> Option<(&str, &str)> {
+/// Parse a filter expression like "var_name | filter_name | ...".
+/// Returns (var_name, filter_chain) if valid, None otherwise.
+pub(crate) fn parse_filter_expr(expr: &str) -> Option<(&str, &str)> {
     let parts: Vec<&str> = expr.splitn(2, '|').collect();
     if parts.len() != 2 {
         return None;
@@ -323,18 +331,6 @@ fn parse_filter_expr(expr: &str) -> Option<(&str, &str)> {
     Some((var_name, filter))
 }
 
-/// Apply a filter to a value.
-fn apply_filter(value: &str, filter: &str) -> String {
-    match filter {
-        "slugify" => slugify(value),
-        "lowercase" | "lower" => value.to_lowercase(),
-        "uppercase" | "upper" => value.to_uppercase(),
-        "trim" => value.trim().to_string(),
-        "year" => value.chars().take(4).collect(),
-        _ => value.to_string(), // Unknown filter, return unchanged
-    }
-}
-
 /// Convert a string to a URL-friendly slug.
 ///
 /// - Converts to lowercase
@@ -342,7 +338,7 @@ fn apply_filter(value: &str, filter: &str) -> String {
 /// - Removes non-alphanumeric characters (except hyphens)
 /// - Collapses multiple hyphens into one
 /// - Trims leading/trailing hyphens
-fn slugify(s: &str) -> String {
+pub(crate) fn slugify(s: &str) -> String {
     let mut result = String::with_capacity(s.len());
 
     for c in s.chars() {
@@ -496,6 +492,38 @@ mod tests {
         assert_eq!(result, "{{missing | slugify}}");
     }
 
+    #[test]
+    fn test_render_string_filter_chain_with_arguments() {
+        let mut ctx = RenderContext::new();
+        ctx.insert("title".into(), "Team Sync".into());
+        ctx.insert("due".into(), "2026-03-05".into());
+        ctx.insert("status".into(), "".into());
+
+        let result = render_string(
+            r#"{{title | slug}}/{{due | fmt:"%B %d"}}/{{status | default:"todo" | upper}}"#,
+            &ctx,
+        )
+        .unwrap();
+        assert_eq!(result, "team-sync/March 05/TODO");
+    }
+
+    #[test]
+    fn test_render_string_default_for_missing_var() {
+        let ctx = RenderContext::new();
+        let result = render_string(r#"{{project | default:"inbox"}}"#, &ctx).unwrap();
+        assert_eq!(result, "inbox");
+    }
+
+    #[test]
+    fn test_render_string_fmt_on_date_expression() {
+        let ctx = RenderContext::new();
+        let ref_date = NaiveDate::from_ymd_opt(2026, 3, 5);
+        let result =
+            render_string_with_ref_date(r#"{{today + 1d | fmt:"%A"}}"#, &ctx, ref_date)
+                .unwrap();
+        assert_eq!(result, "Friday");
+    }
+
     #[test]
     fn test_date_format_not_parsed_as_filter() {
         let ctx = RenderContext::new();
@@ -0,0 +1,227 @@
+//! Filters for template placeholders.
+//!
+//! A placeholder may pipe its value through filters, each optionally taking
+//! an argument after a colon:
+//!
+//! ```text
+//! {{title | slug}}
+//! {{date | fmt:"%B %d"}}
+//! {{status | default:"todo" | upper}}
+//! ```
+//!
+//! Built-in filters cover slugs, case, dates and defaults. Others can be
+//! added with [`register_filter`]; the CLI registers the functions in the
+//! vault's `.mdvault/filters.lua`. Unknown filters leave the value as it is.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::rc::Rc;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use tracing::warn;
+
+/// A custom filter: takes the value and the filter's argument, if any.
+pub type FilterFn = Rc<dyn Fn(&str, Option<&str>) -> Result<String, String>>;
+
+thread_local! {
+    static CUSTOM: RefCell<HashMap<String, FilterFn>> = RefCell::new(HashMap::new());
+}
+
+/// The names of the built-in filters.
+pub const BUILTIN_FILTERS: &[&str] = &[
+    "slug",
+    "slugify",
+    "lower",
+    "lowercase",
+    "upper",
+    "uppercase",
+    "title",
+    "trim",
+    "year",
+    "fmt",
+    "default",
+];
+
+/// Make `filter` available to templates rendered on this thread as `name`.
+///
+/// Built-in filters cannot be replaced; registering one of their names is
+/// ignored and returns false.
+pub fn register_filter(name: &str, filter: FilterFn) -> bool {
+    if BUILTIN_FILTERS.contains(&name) {
+        return false;
+    }
+    CUSTOM.with(|custom| custom.borrow_mut().insert(name.to_string(), filter));
+    true
+}
+
+/// Whether `name` is a built-in filter or one registered on this thread.
+pub fn is_known_filter(name: &str) -> bool {
+    BUILTIN_FILTERS.contains(&name)
+        || CUSTOM.with(|custom| custom.borrow().contains_key(name))
+}
+
+/// Forget every filter registered on this thread.
+pub fn clear_filters() {
+    CUSTOM.with(|custom| custom.borrow_mut().clear());
+}
+
+/// Apply the filter `name` to `value`.
+pub fn apply_filter(value: &str, name: &str, arg: Option<&str>) -> String {
+    match name {
+        "slug" | "slugify" => super::engine::slugify(value),
+        "lower" | "lowercase" => value.to_lowercase(),
+        "upper" | "uppercase" => value.to_uppercase(),
+        "title" => title_case(value),
+        "trim" => value.trim().to_string(),
+        "year" => value.chars().take(4).collect(),
+        "fmt" => format_date(value, arg.unwrap_or("%Y-%m-%d")),
+        "default" if value.is_empty() => arg.unwrap_or_default().to_string(),
+        "default" => value.to_string(),
+        _ => {
+            let custom = CUSTOM.with(|custom| custom.borrow().get(name).cloned());
+            match custom.map(|filter| filter(value, arg)) {
+                Some(Ok(filtered)) => filtered,
+                Some(Err(e)) => {
+                    warn!("Template filter '{}' failed: {}", name, e);
+                    value.to_string()
+                }
+                // Unknown filter, return unchanged
+                None => value.to_string(),
+            }
+        }
+    }
+}
+
+/// Split a filter chain like `default:"a|b" | upper` into filters and
+/// their arguments. Quotes around an argument are removed, and a `|`
+/// inside them does not end the filter.
+pub fn parse_filters(chain: &str) -> Vec<(&str, Option<&str>)> {
+    let mut filters = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in chain.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            ('|', None) => {
+                filters.push(&chain[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    filters.push(&chain[start..]);
+
+    filters
+        .into_iter()
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .map(|f| match f.split_once(':') {
+            Some((name, arg)) => (name.trim(), Some(unquote(arg.trim()))),
+            None => (f, None),
+        })
+        .collect()
+}
+
+fn unquote(arg: &str) -> &str {
+    for q in ['"', '\''] {
+        if let Some(inner) = arg.strip_prefix(q).and_then(|a| a.strip_suffix(q)) {
+            return inner;
+        }
+    }
+    arg
+}
+
+/// Upper-case the first letter of every word.
+fn title_case(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut start = true;
+    for c in value.chars() {
+        if start {
+            out.extend(c.to_uppercase());
+        } else {
+            out.push(c);
+        }
+        start = c.is_whitespace() || c == '-';
+    }
+    out
+}
+
+/// Format a date or datetime value with a strftime format. Values that are
+/// not dates, and invalid formats, leave the value as it is.
+fn format_date(value: &str, fmt: &str) -> String {
+    let value = value.trim();
+    let mut buf = String::new();
+    let written = if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        write!(buf, "{}", date.format(fmt))
+    } else if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        write!(buf, "{}", dt.format(fmt))
+    } else if let Ok(dt) = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M"))
+    {
+        write!(buf, "{}", dt.format(fmt))
+    } else {
+        return value.to_string();
+    };
+    match written {
+        Ok(()) => buf,
+        Err(_) => {
+            warn!("Invalid date format '{}' in fmt filter", fmt);
+            value.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_filters() {
+        assert_eq!(parse_filters("slug"), [("slug", None)]);
+        assert_eq!(
+            parse_filters(r#"fmt:"%B | %d" | upper"#),
+            [("fmt", Some("%B | %d")), ("upper", None)]
+        );
+        assert_eq!(
+            parse_filters("default:'todo'|trim| "),
+            [("default", Some("todo")), ("trim", None)]
+        );
+        assert_eq!(parse_filters("default:none"), [("default", Some("none"))]);
+    }
+
+    #[test]
+    fn test_builtin_filters() {
+        assert_eq!(apply_filter("My New Task!", "slug", None), "my-new-task");
+        assert_eq!(apply_filter("the left hand", "title", None), "The Left Hand");
+        assert_eq!(apply_filter("", "default", Some("todo")), "todo");
+        assert_eq!(apply_filter("done", "default", Some("todo")), "done");
+        assert_eq!(apply_filter("2026-03-05", "fmt", Some("%B %d")), "March 05");
+        assert_eq!(
+            apply_filter("2026-03-05T14:30:00+01:00", "fmt", Some("%H:%M")),
+            "14:30"
+        );
+        assert_eq!(apply_filter("soon", "fmt", Some("%B")), "soon");
+        assert_eq!(apply_filter("2026-03-05", "fmt", Some("%Q")), "2026-03-05");
+    }
+
+    #[test]
+    fn test_custom_filters() {
+        let initials: FilterFn = Rc::new(|value, _| {
+            Ok(value.split_whitespace().filter_map(|w| w.chars().next()).collect())
+        });
+        assert!(register_filter("initials", initials));
+        assert!(register_filter("fail", Rc::new(|_, _| Err("nope".into()))));
+        assert!(!register_filter("upper", Rc::new(|v, _| Ok(v.to_string()))));
+
+        assert!(is_known_filter("initials") && is_known_filter("fmt"));
+        assert_eq!(apply_filter("Ursula Le Guin", "initials", None), "ULG");
+        assert_eq!(apply_filter("kept", "fail", None), "kept");
+        assert_eq!(apply_filter("abc", "upper", None), "ABC");
+
+        clear_filters();
+        assert!(!is_known_filter("initials"));
+        assert_eq!(apply_filter("Ursula Le Guin", "initials", None), "Ursula Le Guin");
+    }
+}
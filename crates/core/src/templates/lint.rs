@@ -0,0 +1,241 @@
+//! Template lint: problems `mdv new` would hit only when a template is used.
+//!
+//! Each template is loaded (resolving `{% extends %}`) and checked for:
+//! - frontmatter that isn't valid YAML once placeholders are filled in
+//! - filters that are neither built in nor registered
+//! - an output path that can't be rendered from the known variables
+//! - variables the template, its typedef and `mdv new` don't declare
+//!
+//! Undeclared variables are warnings, since `--var` can still supply them;
+//! the rest are errors.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::Serialize;
+
+use super::discovery::TemplateInfo;
+use super::engine::{RenderContext, parse_filter_expr, render_string};
+use super::filters::{is_known_filter, parse_filters};
+use super::repository::{LoadedTemplate, TemplateRepository};
+use crate::types::TypeDefinition;
+use crate::vars::VarsMap;
+use crate::vars::datemath::is_date_expr;
+
+/// Variables `mdv new` provides to every template.
+pub const BUILTIN_VARIABLES: &[&str] = &[
+    "title",
+    "type",
+    "date",
+    "time",
+    "datetime",
+    "today",
+    "now",
+    "week",
+    "vault_root",
+    "templates_dir",
+    "captures_dir",
+    "macros_dir",
+    "template_name",
+    "template_path",
+    "output_path",
+    "output_filename",
+    "output_dir",
+];
+
+static PLACEHOLDER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{([^{}]+)\}\}").unwrap());
+
+/// The problems found in one template.
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateLint {
+    /// The template's logical name.
+    pub name: String,
+    /// The template file.
+    pub path: PathBuf,
+    /// Problems that break the template.
+    pub errors: Vec<String>,
+    /// Problems that may be intended.
+    pub warnings: Vec<String>,
+}
+
+impl TemplateLint {
+    fn new(info: &TemplateInfo) -> Self {
+        Self {
+            name: info.logical_name.clone(),
+            path: info.path.clone(),
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Whether no problems were found.
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty() && self.warnings.is_empty()
+    }
+}
+
+/// Lint one template. `typedef_for` gives the type definition a loaded
+/// template uses, if any, for its declared fields and output path; an error
+/// loading it is reported as a problem.
+pub fn lint_template(
+    repo: &TemplateRepository,
+    info: &TemplateInfo,
+    typedef_for: impl FnOnce(&LoadedTemplate) -> Result<Option<TypeDefinition>, String>,
+) -> TemplateLint {
+    let mut lint = TemplateLint::new(info);
+    let loaded = match repo.get_by_name(&info.logical_name) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            lint.errors.push(e.to_string());
+            return lint;
+        }
+    };
+    let typedef = typedef_for(&loaded).unwrap_or_else(|e| {
+        lint.errors.push(e);
+        None
+    });
+
+    let raw_frontmatter = loaded.raw_frontmatter.as_deref().unwrap_or_default();
+    if let Some(message) = check_frontmatter(raw_frontmatter) {
+        lint.errors.push(message);
+    }
+
+    let mut declared: HashSet<String> =
+        BUILTIN_VARIABLES.iter().map(|v| v.to_string()).collect();
+    declared.extend(frontmatter_vars(raw_frontmatter));
+    if let Some(td) = &typedef {
+        declared.extend(td.schema.keys().cloned());
+        declared.extend(td.variables.keys().cloned());
+    }
+
+    let mut reported = HashSet::new();
+    for text in [raw_frontmatter, loaded.body.as_str()] {
+        for caps in PLACEHOLDER_RE.captures_iter(text) {
+            let expr = caps[1].trim();
+            let (var, chain) = parse_filter_expr(expr).unwrap_or((expr, ""));
+            let filters = parse_filters(chain);
+            for (name, _) in &filters {
+                if !is_known_filter(name) && reported.insert(format!("filter:{name}")) {
+                    lint.errors.push(format!("unknown filter '{name}' in {}", &caps[0]));
+                }
+            }
+            let has_default = filters.iter().any(|(name, _)| *name == "default");
+            if !declared.contains(var)
+                && !is_date_expr(expr)
+                && !is_date_expr(var)
+                && !has_default
+                && reported.insert(format!("var:{var}"))
+            {
+                lint.warnings.push(format!("undeclared variable '{var}'"));
+            }
+        }
+    }
+
+    let output = loaded
+        .frontmatter
+        .as_ref()
+        .and_then(|fm| fm.output.clone())
+        .or_else(|| typedef.as_ref().and_then(|td| td.output.clone()));
+    if let Some(output) = output {
+        let ctx: RenderContext =
+            declared.iter().map(|v| (v.clone(), "sample".to_string())).collect();
+        match render_string(&output, &ctx) {
+            Ok(path) if path.contains("{{") || path.trim().is_empty() => {
+                lint.errors.push(format!("output path '{output}' cannot be rendered"));
+            }
+            Ok(_) => {}
+            Err(e) => lint.errors.push(format!("output path '{output}': {e}")),
+        }
+    }
+
+    if loaded.body.contains("{%") {
+        lint.warnings.push("unrecognised '{%' tag in the body".into());
+    }
+    lint
+}
+
+/// The YAML error in raw frontmatter, with placeholders filled in.
+fn check_frontmatter(raw: &str) -> Option<String> {
+    if raw.trim().is_empty() {
+        return None;
+    }
+    let filled = PLACEHOLDER_RE.replace_all(raw, "sample");
+    match serde_yaml::from_str::<serde_yaml::Value>(&filled) {
+        Ok(serde_yaml::Value::Mapping(_)) => None,
+        Ok(_) => Some("frontmatter is not a mapping".into()),
+        Err(e) => Some(format!("invalid frontmatter: {e}")),
+    }
+}
+
+/// The variables a template declares under `vars:` in its frontmatter.
+fn frontmatter_vars(raw: &str) -> Vec<String> {
+    #[derive(serde::Deserialize)]
+    struct Vars {
+        #[serde(default)]
+        vars: VarsMap,
+    }
+    let filled = PLACEHOLDER_RE.replace_all(raw, "sample");
+    serde_yaml::from_str::<Vars>(&filled)
+        .map(|v| v.vars.into_keys().collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn lint(files: &[(&str, &str)], name: &str) -> TemplateLint {
+        let tmp = tempdir().unwrap();
+        for (file, content) in files {
+            fs::write(tmp.path().join(format!("{file}.md")), content).unwrap();
+        }
+        let repo = TemplateRepository::new(tmp.path()).unwrap();
+        let info = repo.list_all().iter().find(|t| t.logical_name == name).unwrap();
+        lint_template(&repo, info, |_| Ok(None))
+    }
+
+    #[test]
+    fn test_clean_template() {
+        let result = lint(
+            &[(
+                "note",
+                "---\noutput: \"notes/{{title | slug}}.md\"\nvars:\n  mood: \"Mood?\"\n---\n# {{title}}\n{{mood}} {{today + 1d}} {{status | default:\"todo\"}}\n",
+            )],
+            "note",
+        );
+        assert!(result.is_clean(), "{result:?}");
+    }
+
+    #[test]
+    fn test_reports_problems() {
+        let result = lint(
+            &[(
+                "bad",
+                "---\noutput: \"notes/{{nope}}.md\"\ntags: [a\n---\n{{title | slugfy}} {{mood}} {{mood}}\n",
+            )],
+            "bad",
+        );
+        assert_eq!(result.errors.len(), 3, "{result:?}");
+        assert!(result.errors[0].starts_with("invalid frontmatter"));
+        assert_eq!(result.errors[1], "unknown filter 'slugfy' in {{title | slugfy}}");
+        assert_eq!(
+            result.errors[2],
+            "output path 'notes/{{nope}}.md' cannot be rendered"
+        );
+        assert_eq!(
+            result.warnings,
+            ["undeclared variable 'nope'", "undeclared variable 'mood'"]
+        );
+    }
+
+    #[test]
+    fn test_reports_load_errors() {
+        let result = lint(&[("child", "{% extends \"ghost\" %}\n")], "child");
+        assert_eq!(result.errors, ["template 'child' extends unknown template 'ghost'"]);
+    }
+}
@@ -1,3 +1,6 @@
 pub mod discovery;
 pub mod engine;
+pub mod filters;
+#[cfg(feature = "native")]
+pub mod lint;
 pub mod repository;
@@ -1,6 +1,34 @@
+//! Template lookup by logical name.
+//!
+//! A template can extend another one and override its named blocks:
+//!
+//! ```text
+//! <!-- templates/base-note.md -->
+//! # {{title}}
+//!
+//! {% block body %}
+//! Write here.
+//! {% endblock %}
+//!
+//! ## Links
+//!
+//! <!-- templates/meeting.md -->
+//! {% extends "base-note" %}
+//! {% block body %}
+//! ## Attendees
+//! {% endblock %}
+//! ```
+//!
+//! Blocks the child doesn't define keep the parent's content, and text
+//! outside the child's blocks is ignored. A child without frontmatter uses
+//! its parent's. Parents may extend further templates.
+
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
 
+use regex::Regex;
 use thiserror::Error;
 
 use crate::frontmatter::{
@@ -31,6 +59,70 @@ pub enum TemplateRepoError {
         #[source]
         source: FrontmatterParseError,
     },
+
+    #[error("template '{template}' extends unknown template '{parent}'")]
+    UnknownParent { template: String, parent: String },
+
+    #[error("template inheritance cycle: {0}")]
+    InheritanceCycle(String),
+}
+
+/// `{% extends "name" %}` at the start of a template body.
+static EXTENDS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^\s*\{%\s*extends\s+["']([^"']+)["']\s*%\}[ \t]*\r?\n?"#).unwrap()
+});
+
+/// `{% block name %}...{% endblock %}`; the newline after each tag belongs
+/// to the tag. Blocks don't nest.
+static BLOCK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?s)\{%\s*block\s+([\w-]+)\s*%\}\r?\n?(.*?)\{%\s*endblock(?:\s+[\w-]+)?\s*%\}\r?\n?",
+    )
+    .unwrap()
+});
+
+/// The template a body extends, if it starts with `{% extends %}`.
+pub fn extends_of(body: &str) -> Option<&str> {
+    EXTENDS_RE.captures(body).and_then(|c| c.get(1)).map(|m| m.as_str())
+}
+
+/// The blocks a body defines, by name. A later block replaces an earlier
+/// one with the same name.
+pub fn blocks_of(body: &str) -> HashMap<&str, &str> {
+    BLOCK_RE
+        .captures_iter(body)
+        .map(|c| (c.get(1).unwrap().as_str(), c.get(2).unwrap().as_str()))
+        .collect()
+}
+
+/// Put the child's blocks into the parent body, keeping the block tags so
+/// a template further down can still override them.
+fn override_blocks(parent: &str, blocks: &HashMap<&str, &str>) -> String {
+    let mut out = String::with_capacity(parent.len());
+    let mut last = 0;
+    for caps in BLOCK_RE.captures_iter(parent) {
+        let content = caps.get(2).unwrap();
+        if let Some(replacement) = blocks.get(&caps[1]) {
+            out.push_str(&parent[last..content.start()]);
+            out.push_str(replacement);
+            last = content.end();
+        }
+    }
+    out.push_str(&parent[last..]);
+    out
+}
+
+/// Template text from raw frontmatter and a body.
+fn join_content(raw_frontmatter: Option<&str>, body: &str) -> String {
+    match raw_frontmatter {
+        Some(raw) => format!("---\n{raw}---\n{body}"),
+        None => body.to_string(),
+    }
+}
+
+/// Drop the block tags, keeping their content.
+fn strip_blocks(body: &str) -> String {
+    BLOCK_RE.replace_all(body, "$2").into_owned()
 }
 
 #[derive(Debug, Clone)]
@@ -65,6 +157,22 @@ impl TemplateRepository {
     }
 
     pub fn get_by_name(&self, name: &str) -> Result<LoadedTemplate, TemplateRepoError> {
+        let mut loaded = self.load_with_blocks(name, &mut Vec::new())?;
+        let body = strip_blocks(&loaded.body);
+        if body != loaded.body {
+            loaded.content = join_content(loaded.raw_frontmatter.as_deref(), &body);
+            loaded.body = body;
+        }
+        Ok(loaded)
+    }
+
+    /// Load a template with its parents resolved, block tags left in place.
+    /// `chain` holds the templates being resolved, to catch cycles.
+    fn load_with_blocks(
+        &self,
+        name: &str,
+        chain: &mut Vec<String>,
+    ) -> Result<LoadedTemplate, TemplateRepoError> {
         let info = self
             .templates
             .iter()
@@ -79,13 +187,120 @@ impl TemplateRepository {
             TemplateRepoError::FrontmatterParse { path: info.path.clone(), source: e }
         })?;
 
+        let Some(parent_name) = extends_of(&body) else {
+            return Ok(LoadedTemplate {
+                logical_name: info.logical_name.clone(),
+                path: info.path.clone(),
+                content,
+                frontmatter,
+                raw_frontmatter,
+                body,
+            });
+        };
+
+        chain.push(info.logical_name.clone());
+        if chain.iter().any(|n| n == parent_name) {
+            chain.push(parent_name.to_string());
+            return Err(TemplateRepoError::InheritanceCycle(chain.join(" -> ")));
+        }
+        let parent = self.load_with_blocks(parent_name, chain).map_err(|e| match e {
+            TemplateRepoError::NotFound(_) if !self.has_template(parent_name) => {
+                TemplateRepoError::UnknownParent {
+                    template: info.logical_name.clone(),
+                    parent: parent_name.to_string(),
+                }
+            }
+            e => e,
+        })?;
+        chain.pop();
+
+        let body = override_blocks(&parent.body, &blocks_of(&body));
+        let (frontmatter, raw_frontmatter) = if raw_frontmatter.is_some() {
+            (frontmatter, raw_frontmatter)
+        } else {
+            (parent.frontmatter, parent.raw_frontmatter)
+        };
         Ok(LoadedTemplate {
             logical_name: info.logical_name.clone(),
             path: info.path.clone(),
-            content,
+            content: join_content(raw_frontmatter.as_deref(), &body),
             frontmatter,
             raw_frontmatter,
             body,
         })
     }
+
+    fn has_template(&self, name: &str) -> bool {
+        self.templates.iter().any(|t| t.logical_name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn repo(files: &[(&str, &str)]) -> (tempfile::TempDir, TemplateRepository) {
+        let tmp = tempdir().unwrap();
+        for (name, content) in files {
+            let path = tmp.path().join(format!("{name}.md"));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        let repo = TemplateRepository::new(tmp.path()).unwrap();
+        (tmp, repo)
+    }
+
+    const BASE: &str = "---\ntype: note\n---\n# {{title}}\n\n{% block body %}\nWrite here.\n{% endblock %}\n\n{% block links %}\n## Links\n{% endblock %}\n";
+
+    #[test]
+    fn test_child_overrides_blocks() {
+        let (_tmp, repo) = repo(&[
+            ("base-note", BASE),
+            (
+                "meeting",
+                "{% extends \"base-note\" %}\nignored\n{% block body %}\n## Attendees\n{% endblock body %}\n",
+            ),
+        ]);
+        let loaded = repo.get_by_name("meeting").unwrap();
+        assert_eq!(loaded.body, "# {{title}}\n\n## Attendees\n\n## Links\n");
+        assert_eq!(loaded.raw_frontmatter.as_deref(), Some("type: note\n"));
+        assert_eq!(loaded.content, format!("---\ntype: note\n---\n{}", loaded.body));
+
+        let base = repo.get_by_name("base-note").unwrap();
+        assert_eq!(base.body, "# {{title}}\n\nWrite here.\n\n## Links\n");
+    }
+
+    #[test]
+    fn test_multi_level_inheritance_and_own_frontmatter() {
+        let (_tmp, repo) = repo(&[
+            ("base-note", BASE),
+            (
+                "work",
+                "{% extends 'base-note' %}\n{% block links %}\n## Tickets\n{% endblock %}\n",
+            ),
+            (
+                "standup",
+                "---\ntype: standup\n---\n{% extends \"work\" %}\n{% block body %}\nYesterday:\n{% endblock %}\n",
+            ),
+        ]);
+        let loaded = repo.get_by_name("standup").unwrap();
+        assert_eq!(loaded.body, "# {{title}}\n\nYesterday:\n\n## Tickets\n");
+        assert_eq!(loaded.raw_frontmatter.as_deref(), Some("type: standup\n"));
+    }
+
+    #[test]
+    fn test_inheritance_errors() {
+        let (_tmp, repo) = repo(&[
+            ("orphan", "{% extends \"missing\" %}\n"),
+            ("a", "{% extends \"b\" %}\n"),
+            ("b", "{% extends \"a\" %}\n"),
+        ]);
+        assert!(matches!(
+            repo.get_by_name("orphan"),
+            Err(TemplateRepoError::UnknownParent { ref parent, .. }) if parent == "missing"
+        ));
+        let err = repo.get_by_name("a").unwrap_err();
+        assert_eq!(err.to_string(), "template inheritance cycle: a -> b -> a");
+    }
 }
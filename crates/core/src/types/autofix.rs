@@ -4,12 +4,13 @@
 //! - Adding missing required fields with default values
 //! - Normalizing enum value case
 //! - Adding missing frontmatter block
+//! - Fixes suggested by the type's `validate()` function
 
 use std::collections::HashMap;
 use std::path::Path;
 
 use super::definition::TypeDefinition;
-use super::errors::ValidationError;
+use super::errors::{FixOp, SuggestedFix, ValidationError, ValidationResult};
 use super::registry::TypeRegistry;
 use crate::frontmatter::{Frontmatter, ParsedDocument, parse as parse_frontmatter};
 
@@ -30,14 +31,15 @@ impl FixResult {
     }
 }
 
-/// Attempt to auto-fix validation errors in a note.
+/// Attempt to auto-fix validation errors in a note, and apply the fixes
+/// its `validate()` function suggested.
 ///
 /// Returns a FixResult with the corrected content if fixes were applied.
 pub fn try_fix_note(
     registry: &TypeRegistry,
     note_type: &str,
     content: &str,
-    errors: &[ValidationError],
+    result: &ValidationResult,
 ) -> FixResult {
     let typedef = match registry.get(note_type) {
         Some(td) => td,
@@ -53,7 +55,7 @@ pub fn try_fix_note(
     let mut frontmatter = parsed.frontmatter.map(|fm| fm.fields).unwrap_or_default();
     let mut fixes = Vec::new();
 
-    for error in errors {
+    for error in &result.errors {
         match error {
             ValidationError::MissingRequired { field } => {
                 if let Some(fix) = fix_missing_required(&typedef, field, &mut frontmatter)
@@ -70,6 +72,11 @@ pub fn try_fix_note(
             _ => {} // Other errors can't be auto-fixed
         }
     }
+    for fix in &result.fixes {
+        if apply_suggested_fix(fix, &mut frontmatter) {
+            fixes.push(fix.to_string());
+        }
+    }
 
     if fixes.is_empty() {
         return FixResult::no_fix();
@@ -129,6 +136,32 @@ fn fix_enum_case(
     Some(format!("Fixed case for '{}': '{}' -> '{}'", field, value, correct_value))
 }
 
+/// Apply a fix suggested by `validate()`; false if it changes nothing.
+fn apply_suggested_fix(
+    fix: &SuggestedFix,
+    frontmatter: &mut HashMap<String, serde_yaml::Value>,
+) -> bool {
+    match &fix.op {
+        FixOp::Set(value) => {
+            frontmatter.insert(fix.field.clone(), value.clone()).as_ref() != Some(value)
+        }
+        FixOp::Remove => frontmatter.remove(&fix.field).is_some(),
+        FixOp::Append(value) => {
+            let mut items = match frontmatter.remove(&fix.field) {
+                Some(serde_yaml::Value::Sequence(items)) => items,
+                Some(serde_yaml::Value::Null) | None => Vec::new(),
+                Some(other) => vec![other],
+            };
+            let changed = !items.contains(value);
+            if changed {
+                items.push(value.clone());
+            }
+            frontmatter.insert(fix.field.clone(), serde_yaml::Value::Sequence(items));
+            changed
+        }
+    }
+}
+
 /// Apply fixes to a note file.
 pub fn apply_fixes(path: &Path, content: &str) -> Result<(), String> {
     std::fs::write(path, content)
@@ -174,11 +207,18 @@ mod tests {
             schema,
             output: None,
             frontmatter_order: None,
+            template: None,
             variables: crate::vars::VarsMap::new(),
             has_validate_fn: false,
             has_on_create_hook: false,
             has_on_update_hook: false,
+            has_on_save_hook: false,
+            has_on_index_hook: false,
+            has_migrate_fn: false,
+            schema_version: None,
             is_builtin_override: false,
+            retention: None,
+            permissions: crate::scripting::Permissions::default(),
             lua_source: String::new(),
         }
     }
@@ -192,7 +232,8 @@ mod tests {
         let errors =
             vec![ValidationError::MissingRequired { field: "status".to_string() }];
 
-        let result = try_fix_note(&registry, "task", content, &errors);
+        let result =
+            try_fix_note(&registry, "task", content, &ValidationResult::failure(errors));
         assert!(result.fixed);
         assert_eq!(result.fixes.len(), 1);
         assert!(result.fixes[0].contains("status"));
@@ -211,7 +252,8 @@ mod tests {
             allowed: vec!["open".to_string(), "done".to_string()],
         }];
 
-        let result = try_fix_note(&registry, "task", content, &errors);
+        let result =
+            try_fix_note(&registry, "task", content, &ValidationResult::failure(errors));
         assert!(result.fixed);
         assert!(result.fixes[0].contains("OPEN"));
         assert!(result.fixes[0].contains("open"));
@@ -237,11 +279,18 @@ mod tests {
             schema,
             output: None,
             frontmatter_order: None,
+            template: None,
             variables: crate::vars::VarsMap::new(),
             has_validate_fn: false,
             has_on_create_hook: false,
             has_on_update_hook: false,
+            has_on_save_hook: false,
+            has_on_index_hook: false,
+            has_migrate_fn: false,
+            schema_version: None,
             is_builtin_override: false,
+            retention: None,
+            permissions: crate::scripting::Permissions::default(),
             lua_source: String::new(),
         };
 
@@ -252,7 +301,35 @@ mod tests {
         let errors =
             vec![ValidationError::MissingRequired { field: "project".to_string() }];
 
-        let result = try_fix_note(&registry, "task", content, &errors);
+        let result =
+            try_fix_note(&registry, "task", content, &ValidationResult::failure(errors));
         assert!(!result.fixed);
     }
+
+    #[test]
+    fn test_apply_suggested_fixes() {
+        let mut registry = TypeRegistry::new();
+        registry.register(make_typedef_with_defaults()).unwrap();
+
+        let content =
+            "---\ntype: task\nstatus: open\nlegacy: x\ntags: rust\n---\n\n# Test\n";
+        let fix = |field: &str, op: FixOp| SuggestedFix { field: field.to_string(), op };
+        let mut result = ValidationResult::success();
+        result.fixes = vec![
+            fix("priority", FixOp::Set("high".into())),
+            fix("legacy", FixOp::Remove),
+            fix("tags", FixOp::Append("cli".into())),
+            fix("status", FixOp::Set("open".into())),
+        ];
+
+        let fixed = try_fix_note(&registry, "task", content, &result);
+        assert_eq!(
+            fixed.fixes,
+            ["Set 'priority' to 'high'", "Removed 'legacy'", "Appended 'cli' to 'tags'"]
+        );
+        let content = fixed.content.unwrap();
+        assert!(content.contains("priority: high"), "{content}");
+        assert!(!content.contains("legacy"), "{content}");
+        assert!(content.contains("- rust\n- cli"), "{content}");
+    }
 }
@@ -0,0 +1,284 @@
+//! Schema checks for frontmatter fields.
+//!
+//! These need neither Lua nor the index, so they are also built without the
+//! `native` feature.
+
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use super::errors::{ValidationError, ValidationResult};
+use super::schema::{FieldSchema, FieldType};
+
+/// Validate frontmatter against a field schema.
+///
+/// # Arguments
+///
+/// * `schema` - Field schemas, keyed by field name
+/// * `frontmatter` - The frontmatter mapping to validate
+/// * `skip_inherited` - If true, skip required-field checks for inherited fields
+pub fn validate_schema(
+    schema: &HashMap<String, FieldSchema>,
+    frontmatter: &serde_yaml::Mapping,
+    skip_inherited: bool,
+) -> ValidationResult {
+    let mut result = ValidationResult::success();
+
+    for (field_name, schema) in schema {
+        let value = frontmatter.get(serde_yaml::Value::String(field_name.clone()));
+
+        // Check required fields
+        // Skip inherited fields during creation (they'll be set by on_create hook)
+        // Skip core fields (they're managed outside frontmatter, e.g., title from filename/heading)
+        // Skip prompted fields (they're template variables, not frontmatter fields)
+        if schema.required && value.is_none() {
+            if skip_inherited && schema.inherited {
+                // Skip - inherited field will be populated by hook
+                continue;
+            }
+            if schema.core {
+                // Skip - core fields are not stored in frontmatter
+                continue;
+            }
+            if schema.prompt.is_some() {
+                // Skip - prompted fields are template variables, not frontmatter fields
+                continue;
+            }
+            result.add_error(ValidationError::MissingRequired {
+                field: field_name.clone(),
+            });
+            continue;
+        }
+
+        // Validate value if present
+        if let Some(val) = value {
+            let field_result = validate_field(field_name, schema, val);
+            result.merge(field_result);
+        }
+    }
+
+    result
+}
+
+/// Validate a single field value against its schema.
+fn validate_field(
+    field: &str,
+    schema: &FieldSchema,
+    value: &serde_yaml::Value,
+) -> ValidationResult {
+    let mut result = ValidationResult::success();
+
+    let expected_type = schema.effective_type();
+
+    // Type checking
+    let type_ok = match (&expected_type, value) {
+        (FieldType::String, serde_yaml::Value::String(_)) => true,
+        (FieldType::Number, serde_yaml::Value::Number(_)) => true,
+        (FieldType::Boolean, serde_yaml::Value::Bool(_)) => true,
+        (FieldType::List, serde_yaml::Value::Sequence(_)) => true,
+        (FieldType::Date, serde_yaml::Value::String(s)) => is_valid_date(s),
+        (FieldType::Datetime, serde_yaml::Value::String(s)) => is_valid_datetime(s),
+        (FieldType::Reference, serde_yaml::Value::String(_)) => true,
+        _ => false,
+    };
+
+    if !type_ok {
+        result.add_error(ValidationError::TypeMismatch {
+            field: field.to_string(),
+            expected: expected_type.to_string(),
+            actual: yaml_type_name(value),
+        });
+        return result;
+    }
+
+    // Enum constraint
+    if let (Some(enum_values), serde_yaml::Value::String(s)) =
+        (&schema.enum_values, value)
+        && !enum_values.contains(s)
+    {
+        result.add_error(ValidationError::EnumViolation {
+            field: field.to_string(),
+            value: s.clone(),
+            allowed: enum_values.clone(),
+        });
+    }
+
+    // Number constraints
+    if let serde_yaml::Value::Number(n) = value
+        && let Some(f) = n.as_f64()
+    {
+        if let Some(min) = schema.min
+            && f < min
+        {
+            result.add_error(ValidationError::InvalidValue {
+                field: field.to_string(),
+                message: format!("value {} is less than minimum {}", f, min),
+            });
+        }
+        if let Some(max) = schema.max
+            && f > max
+        {
+            result.add_error(ValidationError::InvalidValue {
+                field: field.to_string(),
+                message: format!("value {} is greater than maximum {}", f, max),
+            });
+        }
+        if let Some(true) = schema.integer
+            && f.fract() != 0.0
+        {
+            result.add_error(ValidationError::InvalidValue {
+                field: field.to_string(),
+                message: format!("value {} must be an integer", f),
+            });
+        }
+    }
+
+    // String length constraints
+    if let serde_yaml::Value::String(s) = value {
+        if let Some(min) = schema.min_length
+            && s.len() < min
+        {
+            result.add_error(ValidationError::InvalidValue {
+                field: field.to_string(),
+                message: format!(
+                    "string length {} is less than minimum {}",
+                    s.len(),
+                    min
+                ),
+            });
+        }
+        if let Some(max) = schema.max_length
+            && s.len() > max
+        {
+            result.add_error(ValidationError::InvalidValue {
+                field: field.to_string(),
+                message: format!(
+                    "string length {} is greater than maximum {}",
+                    s.len(),
+                    max
+                ),
+            });
+        }
+        if let Some(pattern) = &schema.pattern
+            && let Ok(re) = Regex::new(pattern)
+            && !re.is_match(s)
+        {
+            result.add_error(ValidationError::InvalidValue {
+                field: field.to_string(),
+                message: format!("value '{}' does not match pattern '{}'", s, pattern),
+            });
+        }
+    }
+
+    // List constraints
+    if let serde_yaml::Value::Sequence(seq) = value {
+        if let Some(min) = schema.min_items
+            && seq.len() < min
+        {
+            result.add_error(ValidationError::InvalidValue {
+                field: field.to_string(),
+                message: format!("list has {} items, minimum is {}", seq.len(), min),
+            });
+        }
+        if let Some(max) = schema.max_items
+            && seq.len() > max
+        {
+            result.add_error(ValidationError::InvalidValue {
+                field: field.to_string(),
+                message: format!("list has {} items, maximum is {}", seq.len(), max),
+            });
+        }
+
+        // Validate items if schema provided
+        if let Some(item_schema) = &schema.items {
+            for (i, item) in seq.iter().enumerate() {
+                let item_field = format!("{}[{}]", field, i);
+                let item_result = validate_field(&item_field, item_schema, item);
+                result.merge(item_result);
+            }
+        }
+    }
+
+    result
+}
+
+/// Check if a string is a valid date (YYYY-MM-DD format).
+fn is_valid_date(s: &str) -> bool {
+    // Simple validation: YYYY-MM-DD
+    if s.len() != 10 {
+        return false;
+    }
+    let parts: Vec<&str> = s.split('-').collect();
+    if parts.len() != 3 {
+        return false;
+    }
+    parts[0].len() == 4
+        && parts[1].len() == 2
+        && parts[2].len() == 2
+        && parts[0].chars().all(|c| c.is_ascii_digit())
+        && parts[1].chars().all(|c| c.is_ascii_digit())
+        && parts[2].chars().all(|c| c.is_ascii_digit())
+}
+
+/// Check if a string is a valid datetime (ISO 8601 format).
+fn is_valid_datetime(s: &str) -> bool {
+    // Accept various ISO 8601 formats
+    // YYYY-MM-DDTHH:MM:SS or YYYY-MM-DD HH:MM:SS or YYYY-MM-DDTHH:MM:SSZ
+    chrono::DateTime::parse_from_rfc3339(s).is_ok()
+        || chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").is_ok()
+        || chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").is_ok()
+}
+
+/// Get a human-readable type name for a YAML value.
+fn yaml_type_name(value: &serde_yaml::Value) -> String {
+    match value {
+        serde_yaml::Value::Null => "null".to_string(),
+        serde_yaml::Value::Bool(_) => "boolean".to_string(),
+        serde_yaml::Value::Number(_) => "number".to_string(),
+        serde_yaml::Value::String(_) => "string".to_string(),
+        serde_yaml::Value::Sequence(_) => "list".to_string(),
+        serde_yaml::Value::Mapping(_) => "mapping".to_string(),
+        serde_yaml::Value::Tagged(_) => "tagged".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_schema_checks_required_and_enum() {
+        let schema = HashMap::from([
+            ("title".to_string(), FieldSchema::required_string()),
+            (
+                "status".to_string(),
+                FieldSchema::required_enum(vec!["open".into(), "done".into()]),
+            ),
+        ]);
+        let frontmatter: serde_yaml::Mapping =
+            serde_yaml::from_str("status: closed").unwrap();
+
+        let result = validate_schema(&schema, &frontmatter, false);
+        assert!(!result.valid);
+        assert_eq!(result.errors.len(), 2);
+    }
+
+    #[test]
+    fn test_is_valid_date() {
+        assert!(is_valid_date("2025-12-29"));
+        assert!(is_valid_date("2000-01-01"));
+        assert!(!is_valid_date("2025-1-29")); // Month not zero-padded
+        assert!(!is_valid_date("25-12-29")); // Year not 4 digits
+        assert!(!is_valid_date("2025/12/29")); // Wrong separator
+        assert!(!is_valid_date("not a date"));
+    }
+
+    #[test]
+    fn test_is_valid_datetime() {
+        assert!(is_valid_datetime("2025-12-29T14:30:00Z"));
+        assert!(is_valid_datetime("2025-12-29T14:30:00+00:00"));
+        assert!(is_valid_datetime("2025-12-29T14:30:00"));
+        assert!(!is_valid_datetime("not a datetime"));
+        assert!(!is_valid_datetime("2025-12-29")); // Just a date
+    }
+}
@@ -4,6 +4,8 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 
 use super::schema::FieldSchema;
+use crate::expire::RetentionRule;
+use crate::scripting::Permissions;
 use crate::vars::VarsMap;
 
 /// A loaded type definition from a Lua file.
@@ -27,6 +29,10 @@ pub struct TypeDefinition {
     /// Preferred order of frontmatter fields.
     pub frontmatter_order: Option<Vec<String>>,
 
+    /// Template rendered for new notes of this type instead of the
+    /// generated scaffolding (logical name in the templates directory).
+    pub template: Option<String>,
+
     /// Template variables with optional prompts and defaults.
     /// These are used for template body substitution, not frontmatter fields.
     pub variables: VarsMap,
@@ -40,9 +46,31 @@ pub struct TypeDefinition {
     /// Whether this type has an on_update() hook.
     pub has_on_update_hook: bool,
 
+    /// Whether this type has an on_save() hook, run after a command writes
+    /// one of its notes.
+    pub has_on_save_hook: bool,
+
+    /// Whether this type has an on_index() hook, run when one of its notes
+    /// is (re)indexed.
+    pub has_on_index_hook: bool,
+
+    /// Whether this type has a migrate() function, run by `mdv migrate` on
+    /// notes written for an older `schema_version`.
+    pub has_migrate_fn: bool,
+
+    /// Version of the schema; notes record the version they were written
+    /// for in a `schema_version` field.
+    pub schema_version: Option<u32>,
+
     /// Whether this overrides a built-in type.
     pub is_builtin_override: bool,
 
+    /// When notes of this type expire (see `mdv expire`).
+    pub retention: Option<RetentionRule>,
+
+    /// What this type's hooks and validate() may do.
+    pub permissions: Permissions,
+
     /// Raw Lua source (for re-execution of hooks).
     pub lua_source: String,
 }
@@ -57,18 +85,29 @@ impl TypeDefinition {
             schema: HashMap::new(),
             output: None,
             frontmatter_order: None,
+            template: None,
             variables: VarsMap::new(),
             has_validate_fn: false,
             has_on_create_hook: false,
             has_on_update_hook: false,
+            has_on_save_hook: false,
+            has_on_index_hook: false,
+            has_migrate_fn: false,
+            schema_version: None,
             is_builtin_override: false,
+            retention: None,
+            permissions: Permissions::default(),
             lua_source: String::new(),
         }
     }
 
     /// Check if this type has any hooks.
     pub fn has_hooks(&self) -> bool {
-        self.has_validate_fn || self.has_on_create_hook || self.has_on_update_hook
+        self.has_validate_fn
+            || self.has_on_create_hook
+            || self.has_on_update_hook
+            || self.has_on_save_hook
+            || self.has_on_index_hook
     }
 
     /// Get a list of required fields.
@@ -9,7 +9,9 @@ use walkdir::WalkDir;
 use super::definition::{TypeDefinition, TypedefInfo};
 use super::errors::TypedefError;
 use super::schema::{FieldSchema, FieldType};
-use crate::scripting::LuaEngine;
+use super::workflow::Workflow;
+use crate::expire::RetentionRule;
+use crate::scripting::{LuaEngine, Permissions};
 use crate::vars::{VarMetadata, VarSpec, VarsMap};
 
 /// Built-in type names that can be overridden by Lua definitions.
@@ -173,6 +175,9 @@ fn parse_typedef(
     // Extract frontmatter order
     let frontmatter_order: Option<Vec<String>> = table.get("frontmatter_order").ok();
 
+    // Extract the template new notes are rendered from (optional)
+    let template: Option<String> = table.get("template").ok();
+
     // Extract schema
     let schema = extract_schema(&table, path)?;
 
@@ -183,6 +188,33 @@ fn parse_typedef(
     let has_validate_fn = table.get::<mlua::Function>("validate").is_ok();
     let has_on_create_hook = table.get::<mlua::Function>("on_create").is_ok();
     let has_on_update_hook = table.get::<mlua::Function>("on_update").is_ok();
+    let has_on_save_hook = table.get::<mlua::Function>("on_save").is_ok();
+    let has_on_index_hook = table.get::<mlua::Function>("on_index").is_ok();
+    let has_migrate_fn = table.get::<mlua::Function>("migrate").is_ok();
+
+    // Extract the schema version (optional)
+    let schema_version = match table.get::<mlua::Value>("schema_version") {
+        Ok(mlua::Value::Nil) | Err(_) => None,
+        Ok(mlua::Value::Integer(v)) if v >= 1 => Some(v as u32),
+        Ok(_) => {
+            return Err(TypedefError::InvalidDefinition {
+                path: path.to_path_buf(),
+                message: "schema_version must be a positive integer".to_string(),
+            });
+        }
+    };
+
+    // Extract retention policy (optional)
+    let retention = extract_retention(&table, path)?;
+
+    // Extract declared permissions (optional)
+    let permissions = table.get("permissions").map_err(|e| TypedefError::LuaParse {
+        path: path.to_path_buf(),
+        source: e.into(),
+    })?;
+    let permissions = Permissions::from_lua(permissions).map_err(|message| {
+        TypedefError::InvalidDefinition { path: path.to_path_buf(), message }
+    })?;
 
     // Check if this overrides a built-in
     let is_builtin_override = BUILTIN_TYPES.contains(&name);
@@ -194,15 +226,59 @@ fn parse_typedef(
         schema,
         output,
         frontmatter_order,
+        template,
         variables,
         has_validate_fn,
         has_on_create_hook,
         has_on_update_hook,
+        has_on_save_hook,
+        has_on_index_hook,
+        has_migrate_fn,
+        schema_version,
         is_builtin_override,
+        retention,
+        permissions,
         lua_source: source.to_string(),
     })
 }
 
+/// Extract the retention policy from Lua table.
+///
+/// Format: `retention = { after = "1y", action = "trash", where = { "status=resolved" } }`
+/// (`action` defaults to archive, `where` is optional).
+fn extract_retention(
+    table: &mlua::Table,
+    path: &Path,
+) -> Result<Option<RetentionRule>, TypedefError> {
+    let invalid = |message: String| TypedefError::InvalidDefinition {
+        path: path.to_path_buf(),
+        message,
+    };
+    let retention: mlua::Table = match table.get::<mlua::Value>("retention") {
+        Ok(mlua::Value::Table(t)) => t,
+        Ok(mlua::Value::Nil) | Err(_) => return Ok(None),
+        Ok(_) => return Err(invalid("retention must be a table".to_string())),
+    };
+
+    let after: String = retention
+        .get("after")
+        .map_err(|_| invalid("retention.after is required (e.g. \"1y\")".to_string()))?;
+    let action: Option<String> = retention.get("action").ok();
+    let filters: Vec<String> = match retention.get::<mlua::Value>("where") {
+        Ok(mlua::Value::String(f)) => vec![f.to_string_lossy()],
+        Ok(mlua::Value::Table(t)) => {
+            t.sequence_values::<String>().collect::<Result<_, _>>().map_err(|_| {
+                invalid("retention.where must be a list of filters".to_string())
+            })?
+        }
+        _ => Vec::new(),
+    };
+
+    RetentionRule::parse(&after, action.as_deref(), &filters)
+        .map(Some)
+        .map_err(|e| invalid(format!("retention: {e}")))
+}
+
 /// Extract schema from Lua table.
 fn extract_schema(
     table: &mlua::Table,
@@ -272,8 +348,15 @@ fn extract_variables(table: &mlua::Table, path: &Path) -> Result<VarsMap, Typede
                 let prompt: Option<String> = t.get("prompt").ok();
                 let required: Option<bool> = t.get("required").ok();
                 let description: Option<String> = t.get("description").ok();
-
-                VarSpec::Full(VarMetadata { prompt, description, required, default })
+                let options: Option<Vec<String>> = t.get("options").ok();
+
+                VarSpec::Full(VarMetadata {
+                    prompt,
+                    description,
+                    required,
+                    default,
+                    options,
+                })
             }
             _ => continue, // Skip invalid values
         };
@@ -288,7 +371,7 @@ fn extract_variables(table: &mlua::Table, path: &Path) -> Result<VarsMap, Typede
 fn parse_field_schema(
     table: &mlua::Table,
     field_name: &str,
-    _path: &Path,
+    path: &Path,
 ) -> Result<FieldSchema, TypedefError> {
     // Get field type
     let field_type: Option<FieldType> =
@@ -330,12 +413,13 @@ fn parse_field_schema(
     let items: Option<Box<FieldSchema>> = table
         .get::<mlua::Table>("items")
         .ok()
-        .map(|t| parse_field_schema(&t, &format!("{}[]", field_name), _path))
+        .map(|t| parse_field_schema(&t, &format!("{}[]", field_name), path))
         .transpose()?
         .map(Box::new);
 
     // Get reference constraint
-    let note_type: Option<String> = table.get("note_type").ok();
+    let note_type: Option<String> =
+        table.get("target_type").or_else(|_| table.get("note_type")).ok();
 
     // Get prompt text for interactive input
     let prompt: Option<String> = table.get("prompt").ok();
@@ -349,9 +433,30 @@ fn parse_field_schema(
     // Get inherited flag (field value will be set by on_create hook)
     let inherited: bool = table.get("inherited").unwrap_or(false);
 
+    // Get unique flag (no two notes of the type share the value)
+    let unique: bool = table.get("unique").unwrap_or(false);
+
     // Get selector for interactive note selection
     let selector: Option<String> = table.get("selector").ok();
 
+    // Get allowed transitions, checked against the enum
+    let transitions: Option<Vec<String>> = table.get("transitions").ok();
+    if let Some(chains) = &transitions {
+        let invalid = |message: String| TypedefError::InvalidDefinition {
+            path: path.to_path_buf(),
+            message: format!("field '{field_name}': {message}"),
+        };
+        let workflow = Workflow::parse(chains).map_err(invalid)?;
+        if let Some(values) = &enum_values
+            && let Some(state) =
+                workflow.states().find(|s| !values.iter().any(|v| v == s))
+        {
+            return Err(invalid(format!(
+                "transition state '{state}' is not in the enum"
+            )));
+        }
+    }
+
     Ok(FieldSchema {
         field_type,
         required,
@@ -372,7 +477,9 @@ fn parse_field_schema(
         core,
         multiline,
         inherited,
+        unique,
         selector,
+        transitions,
     })
 }
 
@@ -455,6 +562,7 @@ mod tests {
             r#"
 return {
     description = "Meeting notes",
+    template = "meeting-notes",
     schema = {
         title = { type = "string", required = true },
         date = { type = "date", required = true },
@@ -470,6 +578,7 @@ return {
 
         assert_eq!(typedef.name, "meeting");
         assert_eq!(typedef.description, Some("Meeting notes".to_string()));
+        assert_eq!(typedef.template.as_deref(), Some("meeting-notes"));
         assert_eq!(typedef.schema.len(), 3);
         assert!(typedef.schema.contains_key("title"));
         assert!(typedef.schema.get("title").unwrap().required);
@@ -498,6 +607,9 @@ return {
     end,
     on_update = function(note, previous)
         return note
+    end,
+    on_save = function(note)
+        return note
     end
 }
 "#,
@@ -510,6 +622,8 @@ return {
         assert!(typedef.has_validate_fn);
         assert!(typedef.has_on_create_hook);
         assert!(typedef.has_on_update_hook);
+        assert!(typedef.has_on_save_hook);
+        assert!(!typedef.has_on_index_hook);
         assert!(typedef.is_builtin_override); // "task" is a built-in
     }
 
@@ -607,6 +721,26 @@ return {
         assert!(matches!(result, Err(TypedefError::InvalidDefinition { .. })));
     }
 
+    #[test]
+    fn test_permissions_lookup_error_is_reported() {
+        let temp = TempDir::new().unwrap();
+        let types_dir = temp.path().join("types");
+        fs::create_dir_all(&types_dir).unwrap();
+
+        fs::write(
+            types_dir.join("odd.lua"),
+            r#"return setmetatable({}, { __index = function(_, key)
+                if key == "permissions" then error("no permissions here") end
+            end })"#,
+        )
+        .unwrap();
+
+        let repo = TypedefRepository::new(&types_dir).unwrap();
+        let err = repo.load_typedef("odd").unwrap_err();
+        assert!(matches!(err, TypedefError::LuaParse { .. }), "{err:?}");
+        assert!(err.to_string().contains("no permissions here"), "{err}");
+    }
+
     #[test]
     fn test_load_typedef_with_selector() {
         let temp = TempDir::new().unwrap();
@@ -653,6 +787,96 @@ return {
         assert_eq!(area_field.prompt, Some("Which area?".to_string()));
     }
 
+    #[test]
+    fn test_load_typedef_with_retention() {
+        let temp = TempDir::new().unwrap();
+        let types_dir = temp.path().join("types");
+        fs::create_dir_all(&types_dir).unwrap();
+
+        fs::write(
+            types_dir.join("inbox.lua"),
+            r#"
+return {
+    retention = { after = "30d", action = "trash", where = { "status=resolved" } },
+}
+"#,
+        )
+        .unwrap();
+        fs::write(
+            types_dir.join("bad.lua"),
+            r#"return { retention = { after = "forever" } }"#,
+        )
+        .unwrap();
+
+        let repo = TypedefRepository::new(&types_dir).unwrap();
+        let rule = repo.load_typedef("inbox").unwrap().retention.unwrap();
+        assert_eq!(rule.after, "30d");
+        assert_eq!(rule.action, crate::expire::ExpireAction::Trash);
+        assert_eq!(rule.filters[0].to_string(), "status=resolved");
+
+        assert!(matches!(
+            repo.load_typedef("bad"),
+            Err(TypedefError::InvalidDefinition { .. })
+        ));
+    }
+
+    #[test]
+    fn test_load_typedef_with_schema_version() {
+        let temp = TempDir::new().unwrap();
+        let types_dir = temp.path().join("types");
+        fs::create_dir_all(&types_dir).unwrap();
+
+        fs::write(
+            types_dir.join("task.lua"),
+            r#"return { schema_version = 2, migrate = function(note, from) return note end }"#,
+        )
+        .unwrap();
+        fs::write(types_dir.join("bad.lua"), r#"return { schema_version = "two" }"#)
+            .unwrap();
+
+        let repo = TypedefRepository::new(&types_dir).unwrap();
+        let typedef = repo.load_typedef("task").unwrap();
+        assert_eq!(typedef.schema_version, Some(2));
+        assert!(typedef.has_migrate_fn);
+
+        assert!(matches!(
+            repo.load_typedef("bad"),
+            Err(TypedefError::InvalidDefinition { .. })
+        ));
+    }
+
+    #[test]
+    fn test_load_typedef_with_transitions() {
+        let temp = TempDir::new().unwrap();
+        let types_dir = temp.path().join("types");
+        fs::create_dir_all(&types_dir).unwrap();
+
+        fs::write(
+            types_dir.join("task.lua"),
+            r#"return { schema = { status = {
+                enum = { "todo", "doing", "done" },
+                transitions = { "todo -> doing -> done" },
+            } } }"#,
+        )
+        .unwrap();
+        fs::write(
+            types_dir.join("bad.lua"),
+            r#"return { schema = { status = {
+                enum = { "todo", "done" },
+                transitions = { "todo -> doing" },
+            } } }"#,
+        )
+        .unwrap();
+
+        let repo = TypedefRepository::new(&types_dir).unwrap();
+        let typedef = repo.load_typedef("task").unwrap();
+        let workflow = typedef.schema["status"].workflow().unwrap();
+        assert_eq!(workflow.next("todo"), ["doing"]);
+
+        let err = repo.load_typedef("bad").unwrap_err();
+        assert!(err.to_string().contains("'doing' is not in the enum"), "{err}");
+    }
+
     #[test]
     fn test_with_fallback_merges_directories() {
         let temp = TempDir::new().unwrap();
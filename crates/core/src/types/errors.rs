@@ -23,6 +23,7 @@ pub enum TypedefError {
     },
 
     /// Error parsing a Lua type definition.
+    #[cfg(feature = "native")]
     #[error("failed to parse type definition {path}: {source}")]
     LuaParse {
         path: PathBuf,
@@ -62,6 +63,35 @@ pub enum ValidationError {
     #[error("enum constraint violated for '{field}': '{value}' not in {allowed:?}")]
     EnumViolation { field: String, value: String, allowed: Vec<String> },
 
+    /// A reference field points to a note that is not in the index.
+    #[error("dangling reference in '{field}': no {target_type} note '{value}'")]
+    DanglingReference { field: String, value: String, target_type: String },
+
+    /// A reference field points to a note of another type.
+    #[error(
+        "invalid reference in '{field}': '{value}' is a {actual} note, expected {target_type}"
+    )]
+    WrongReferenceType {
+        field: String,
+        value: String,
+        target_type: String,
+        actual: String,
+    },
+
+    /// A `unique` field holds a value another note of the type already uses.
+    #[error(
+        "duplicate value for unique field '{field}': '{value}' is also used by {}",
+        others.join(", ")
+    )]
+    DuplicateValue { field: String, value: String, others: Vec<String> },
+
+    /// A field changed in a way its type's `transitions` do not allow.
+    #[error(
+        "illegal transition for '{field}': '{from}' -> '{to}' ({})",
+        allowed_from(from, allowed)
+    )]
+    IllegalTransition { field: String, from: String, to: String, allowed: Vec<String> },
+
     /// Custom validation function failed.
     #[error("custom validation failed: {message}")]
     CustomValidation { message: String },
@@ -71,6 +101,52 @@ pub enum ValidationError {
     LuaError(String),
 }
 
+/// The moves an [`ValidationError::IllegalTransition`] message offers instead.
+fn allowed_from(from: &str, allowed: &[String]) -> String {
+    if allowed.is_empty() {
+        format!("'{from}' is a final state")
+    } else {
+        format!("from '{from}' only: {}", allowed.join(", "))
+    }
+}
+
+/// A change to a frontmatter field suggested by a `validate()` function,
+/// applied by `mdv validate --fix`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuggestedFix {
+    pub field: String,
+    pub op: FixOp,
+}
+
+/// What a [`SuggestedFix`] does to its field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FixOp {
+    /// Set the field to a value.
+    Set(serde_yaml::Value),
+    /// Remove the field.
+    Remove,
+    /// Append a value to the field's list, creating the list if needed.
+    Append(serde_yaml::Value),
+}
+
+impl std::fmt::Display for SuggestedFix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let show = |value: &serde_yaml::Value| match value {
+            serde_yaml::Value::String(s) => s.clone(),
+            other => serde_yaml::to_string(other)
+                .map(|s| s.trim_end().to_string())
+                .unwrap_or_default(),
+        };
+        match &self.op {
+            FixOp::Set(value) => write!(f, "Set '{}' to '{}'", self.field, show(value)),
+            FixOp::Remove => write!(f, "Removed '{}'", self.field),
+            FixOp::Append(value) => {
+                write!(f, "Appended '{}' to '{}'", show(value), self.field)
+            }
+        }
+    }
+}
+
 /// Result of validating a note against its type definition.
 #[derive(Debug, Clone, Default)]
 pub struct ValidationResult {
@@ -80,17 +156,19 @@ pub struct ValidationResult {
     pub errors: Vec<ValidationError>,
     /// Non-fatal warnings.
     pub warnings: Vec<String>,
+    /// Fixes suggested by the type's `validate()` function.
+    pub fixes: Vec<SuggestedFix>,
 }
 
 impl ValidationResult {
     /// Create a successful validation result.
     pub fn success() -> Self {
-        Self { valid: true, errors: vec![], warnings: vec![] }
+        Self { valid: true, errors: vec![], warnings: vec![], fixes: vec![] }
     }
 
     /// Create a failed validation result.
     pub fn failure(errors: Vec<ValidationError>) -> Self {
-        Self { valid: false, errors, warnings: vec![] }
+        Self { valid: false, errors, warnings: vec![], fixes: vec![] }
     }
 
     /// Create a failed validation result with a single error.
@@ -113,6 +191,7 @@ impl ValidationResult {
     pub fn merge(&mut self, other: ValidationResult) {
         self.errors.extend(other.errors);
         self.warnings.extend(other.warnings);
+        self.fixes.extend(other.fixes);
         if !other.valid {
             self.valid = false;
         }
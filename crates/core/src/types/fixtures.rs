@@ -0,0 +1,274 @@
+//! Test fixtures for type definitions.
+//!
+//! Typedef authors keep sample notes under `tests/` next to their `.lua`
+//! files, each with the outcome validation should have:
+//!
+//! ```yaml
+//! # types/tests/task/done-without-date.yaml
+//! frontmatter:
+//!   type: task
+//!   status: done
+//! body: "Shipped it."
+//! expect:
+//!   valid: false
+//!   errors: ["completed_at"]
+//! ```
+//!
+//! Fixtures run through [`validate_note`], so a custom `validate()` that
+//! stops behaving as its fixtures say is caught after an upgrade.
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use walkdir::WalkDir;
+
+use super::errors::TypedefError;
+use super::registry::TypeRegistry;
+use super::validation::validate_note;
+
+/// The outcome a fixture expects from validation.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Expectation {
+    /// Whether the note should pass; implied `false` when `errors` is set.
+    #[serde(default)]
+    pub valid: Option<bool>,
+    /// Text each expected error must contain.
+    #[serde(default)]
+    pub errors: Vec<String>,
+    /// Text each expected warning must contain.
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+impl Expectation {
+    /// Whether the note should pass validation.
+    pub fn expects_valid(&self) -> bool {
+        self.valid.unwrap_or(self.errors.is_empty())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct FixtureFile {
+    #[serde(default)]
+    frontmatter: serde_yaml::Mapping,
+    #[serde(default)]
+    body: String,
+    #[serde(default)]
+    expect: Expectation,
+}
+
+/// A sample note and the validation outcome it expects.
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    /// Path below the tests directory, without extension (`task/done`).
+    pub name: String,
+    /// The fixture file.
+    pub path: PathBuf,
+    /// The `type` in the frontmatter, or else the fixture's folder name.
+    pub note_type: String,
+    /// The note's frontmatter.
+    pub frontmatter: serde_yaml::Mapping,
+    /// The note's body.
+    pub body: String,
+    /// The expected outcome.
+    pub expect: Expectation,
+}
+
+impl Fixture {
+    /// Load a fixture file; `root` is the tests directory it is named from.
+    pub fn load(root: &Path, path: &Path) -> Result<Self, TypedefError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|source| TypedefError::Io { path: path.to_path_buf(), source })?;
+        let file: FixtureFile = serde_yaml::from_str(&content).map_err(|e| {
+            TypedefError::InvalidDefinition {
+                path: path.to_path_buf(),
+                message: e.to_string(),
+            }
+        })?;
+
+        let rel = path.strip_prefix(root).unwrap_or(path).with_extension("");
+        let name = rel.to_string_lossy().replace('\\', "/");
+        let note_type = match file.frontmatter.get("type") {
+            Some(serde_yaml::Value::String(t)) => t.clone(),
+            _ => rel
+                .parent()
+                .and_then(|p| p.file_name())
+                .map(|p| p.to_string_lossy().into_owned())
+                .ok_or_else(|| TypedefError::InvalidDefinition {
+                    path: path.to_path_buf(),
+                    message: "fixture has no `type` in its frontmatter and is not \
+                              in a folder named after a type"
+                        .into(),
+                })?,
+        };
+
+        Ok(Self {
+            name,
+            path: path.to_path_buf(),
+            note_type,
+            frontmatter: file.frontmatter,
+            body: file.body,
+            expect: file.expect,
+        })
+    }
+
+    /// The frontmatter, with `type` filled in from the folder name.
+    fn typed_frontmatter(&self) -> serde_yaml::Mapping {
+        let mut frontmatter = self.frontmatter.clone();
+        frontmatter.entry("type".into()).or_insert_with(|| self.note_type.clone().into());
+        frontmatter
+    }
+
+    /// The fixture as note text.
+    pub fn content(&self) -> String {
+        let yaml = serde_yaml::to_string(&self.typed_frontmatter()).unwrap_or_default();
+        format!("---\n{yaml}---\n{}", self.body)
+    }
+
+    /// Validate the fixture and compare with its expectation.
+    ///
+    /// Returns how the outcome differs from the expected one; empty when
+    /// the fixture passes.
+    pub fn run(&self, registry: &TypeRegistry) -> Vec<String> {
+        if !registry.is_known_type(&self.note_type) {
+            return vec![format!("unknown type '{}'", self.note_type)];
+        }
+
+        let result = validate_note(
+            registry,
+            &self.note_type,
+            &self.path.to_string_lossy(),
+            &serde_yaml::Value::Mapping(self.typed_frontmatter()),
+            &self.content(),
+        );
+        let errors: Vec<String> = result.errors.iter().map(ToString::to_string).collect();
+
+        let mut failures = Vec::new();
+        match (self.expect.expects_valid(), errors.is_empty()) {
+            (true, false) => {
+                failures.extend(errors.iter().map(|e| format!("unexpected error: {e}")))
+            }
+            (false, true) => failures.push("expected the note to be invalid".into()),
+            _ => {}
+        }
+        for expected in &self.expect.errors {
+            if !errors.iter().any(|e| e.contains(expected.as_str())) {
+                failures.push(format!("no error containing '{expected}'"));
+            }
+        }
+        for expected in &self.expect.warnings {
+            if !result.warnings.iter().any(|w| w.contains(expected.as_str())) {
+                failures.push(format!("no warning containing '{expected}'"));
+            }
+        }
+        failures
+    }
+}
+
+/// Find and load the fixtures below a tests directory, sorted by name.
+///
+/// Fixtures are `.yaml` or `.yml` files at any depth. Returns an empty list
+/// if the directory doesn't exist.
+pub fn discover_fixtures(root: &Path) -> Result<Vec<Fixture>, TypedefError> {
+    if !root.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut out = Vec::new();
+    for entry in WalkDir::new(root).sort_by_file_name() {
+        let entry =
+            entry.map_err(|e| TypedefError::WalkError(root.display().to_string(), e))?;
+        let path = entry.path();
+        let is_yaml = matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("yaml") | Some("yml")
+        );
+        if path.is_file() && is_yaml {
+            out.push(Fixture::load(root, path)?);
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TypedefRepository;
+    use tempfile::tempdir;
+
+    fn registry() -> TypeRegistry {
+        let tmp = tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join("ticket.lua"),
+            r#"return { schema = { status = { type = "string", enum = { "open", "done" } } } }"#,
+        )
+        .unwrap();
+        let repo = TypedefRepository::new(tmp.path()).unwrap();
+        TypeRegistry::from_repository(&repo).unwrap()
+    }
+
+    fn write(dir: &Path, rel: &str, content: &str) {
+        let path = dir.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_discover_fixtures_names_and_types() {
+        let tmp = tempdir().unwrap();
+        write(tmp.path(), "ticket/open.yaml", "frontmatter:\n  status: open\n");
+        write(tmp.path(), "other.yml", "frontmatter:\n  type: ticket\n");
+        write(tmp.path(), "notes.md", "not a fixture");
+
+        let fixtures = discover_fixtures(tmp.path()).unwrap();
+        let names: Vec<_> =
+            fixtures.iter().map(|f| (f.name.as_str(), f.note_type.as_str())).collect();
+        assert_eq!(names, [("other", "ticket"), ("ticket/open", "ticket")]);
+    }
+
+    #[test]
+    fn test_fixture_without_type_is_rejected() {
+        let tmp = tempdir().unwrap();
+        write(tmp.path(), "loose.yaml", "frontmatter:\n  status: open\n");
+        assert!(matches!(
+            discover_fixtures(tmp.path()),
+            Err(TypedefError::InvalidDefinition { .. })
+        ));
+    }
+
+    #[test]
+    fn test_run_compares_outcome_with_expectation() {
+        let tmp = tempdir().unwrap();
+        write(tmp.path(), "ticket/ok.yaml", "frontmatter:\n  status: open\n");
+        write(
+            tmp.path(),
+            "ticket/bad.yaml",
+            "frontmatter:\n  status: nope\nexpect:\n  errors: [\"not in\"]\n",
+        );
+        write(
+            tmp.path(),
+            "ticket/wrong.yaml",
+            "frontmatter:\n  status: open\nexpect:\n  valid: false\n",
+        );
+        write(tmp.path(), "ghost/a.yaml", "frontmatter: {}\n");
+
+        let registry = registry();
+        let outcomes: Vec<(String, Vec<String>)> = discover_fixtures(tmp.path())
+            .unwrap()
+            .iter()
+            .map(|f| (f.name.clone(), f.run(&registry)))
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                ("ghost/a".to_string(), vec!["unknown type 'ghost'".to_string()]),
+                ("ticket/bad".to_string(), vec![]),
+                ("ticket/ok".to_string(), vec![]),
+                (
+                    "ticket/wrong".to_string(),
+                    vec!["expected the note to be invalid".to_string()]
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,212 @@
+//! JSON Schema export of type schemas.
+//!
+//! Converts the field schemas of a type into a JSON Schema document for the
+//! note's frontmatter, so editors and other tools can check notes as they
+//! are written. The documents accept what [`validate_schema`] accepts.
+//!
+//! [`validate_schema`]: super::check::validate_schema
+
+use std::collections::HashMap;
+
+use serde_json::{Map, Value, json};
+
+use super::schema::{FieldSchema, FieldType};
+
+/// The JSON Schema dialect of exported documents.
+pub const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Build the JSON Schema document for the frontmatter of a type.
+///
+/// `type` is pinned to the type name unless the schema declares it itself.
+/// Fields `validate` does not require in frontmatter (core and prompted
+/// fields) are not listed as required.
+pub fn type_json_schema(
+    type_name: &str,
+    description: Option<&str>,
+    schema: &HashMap<String, FieldSchema>,
+) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    if !schema.contains_key("type") {
+        properties.insert("type".into(), json!({ "const": type_name }));
+        required.push("type".to_string());
+    }
+
+    let mut names: Vec<&String> = schema.keys().collect();
+    names.sort();
+    for name in names {
+        let field = &schema[name];
+        properties.insert(name.clone(), field_json_schema(field));
+        if field.required && !field.core && field.prompt.is_none() {
+            required.push(name.clone());
+        }
+    }
+    required.sort();
+
+    let mut doc = Map::new();
+    doc.insert("$schema".into(), DIALECT.into());
+    doc.insert("title".into(), type_name.into());
+    if let Some(description) = description {
+        doc.insert("description".into(), description.into());
+    }
+    doc.insert("type".into(), "object".into());
+    doc.insert("properties".into(), Value::Object(properties));
+    if !required.is_empty() {
+        doc.insert("required".into(), required.into());
+    }
+    Value::Object(doc)
+}
+
+/// Build the JSON Schema of a single field value.
+pub fn field_json_schema(field: &FieldSchema) -> Value {
+    let mut out = Map::new();
+    if let Some(description) = &field.description {
+        out.insert("description".into(), description.clone().into());
+    }
+
+    match field.effective_type() {
+        FieldType::String | FieldType::Reference => {
+            out.insert("type".into(), "string".into());
+        }
+        FieldType::Number if field.integer == Some(true) => {
+            out.insert("type".into(), "integer".into());
+        }
+        FieldType::Number => {
+            out.insert("type".into(), "number".into());
+        }
+        FieldType::Boolean => {
+            out.insert("type".into(), "boolean".into());
+        }
+        FieldType::Date => {
+            out.insert("type".into(), "string".into());
+            out.insert("format".into(), "date".into());
+        }
+        FieldType::Datetime => {
+            out.insert("type".into(), "string".into());
+            out.insert("format".into(), "date-time".into());
+        }
+        FieldType::List => {
+            out.insert("type".into(), "array".into());
+            if let Some(items) = &field.items {
+                out.insert("items".into(), field_json_schema(items));
+            }
+            if let Some(min) = field.min_items {
+                out.insert("minItems".into(), min.into());
+            }
+            if let Some(max) = field.max_items {
+                out.insert("maxItems".into(), max.into());
+            }
+        }
+    }
+
+    if let Some(values) = &field.enum_values {
+        out.insert("enum".into(), values.clone().into());
+    }
+    if let Some(pattern) = &field.pattern {
+        out.insert("pattern".into(), pattern.clone().into());
+    }
+    if let Some(min) = field.min_length {
+        out.insert("minLength".into(), min.into());
+    }
+    if let Some(max) = field.max_length {
+        out.insert("maxLength".into(), max.into());
+    }
+    if let Some(min) = field.min {
+        out.insert("minimum".into(), min.into());
+    }
+    if let Some(max) = field.max {
+        out.insert("maximum".into(), max.into());
+    }
+    if let Some(default) =
+        field.default.as_ref().and_then(|v| serde_json::to_value(v).ok())
+    {
+        out.insert("default".into(), default);
+    }
+    Value::Object(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(fields: &[(&str, FieldSchema)]) -> HashMap<String, FieldSchema> {
+        fields.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()
+    }
+
+    #[test]
+    fn test_type_json_schema_lists_fields_and_required() {
+        let doc = type_json_schema(
+            "task",
+            Some("A task"),
+            &schema(&[
+                (
+                    "status",
+                    FieldSchema::required_enum(vec!["open".into(), "done".into()]),
+                ),
+                ("title", FieldSchema { core: true, ..FieldSchema::required_string() }),
+                ("notes", FieldSchema::optional_string()),
+            ]),
+        );
+
+        assert_eq!(doc["$schema"], DIALECT);
+        assert_eq!(doc["title"], "task");
+        assert_eq!(doc["description"], "A task");
+        assert_eq!(doc["type"], "object");
+        assert_eq!(doc["properties"]["type"], json!({ "const": "task" }));
+        assert_eq!(doc["properties"]["status"]["enum"], json!(["open", "done"]));
+        assert_eq!(doc["required"], json!(["status", "type"]));
+    }
+
+    #[test]
+    fn test_field_json_schema_constraints() {
+        let priority = FieldSchema {
+            field_type: Some(FieldType::Number),
+            integer: Some(true),
+            min: Some(1.0),
+            max: Some(5.0),
+            default: Some(serde_yaml::Value::Number(3.into())),
+            ..Default::default()
+        };
+        assert_eq!(
+            field_json_schema(&priority),
+            json!({ "type": "integer", "minimum": 1.0, "maximum": 5.0, "default": 3 })
+        );
+
+        let id = FieldSchema {
+            pattern: Some("^[A-Z]+-\\d+$".into()),
+            min_length: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(
+            field_json_schema(&id),
+            json!({ "type": "string", "pattern": "^[A-Z]+-\\d+$", "minLength": 3 })
+        );
+
+        let due = FieldSchema { field_type: Some(FieldType::Date), ..Default::default() };
+        assert_eq!(
+            field_json_schema(&due),
+            json!({ "type": "string", "format": "date" })
+        );
+    }
+
+    #[test]
+    fn test_field_json_schema_list_items() {
+        let tags = FieldSchema {
+            field_type: Some(FieldType::List),
+            items: Some(Box::new(FieldSchema {
+                enum_values: Some(vec!["a".into(), "b".into()]),
+                ..Default::default()
+            })),
+            max_items: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            field_json_schema(&tags),
+            json!({
+                "type": "array",
+                "items": { "type": "string", "enum": ["a", "b"] },
+                "maxItems": 2
+            })
+        );
+    }
+}
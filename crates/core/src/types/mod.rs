@@ -3,9 +3,12 @@
 //! This module provides a system for defining custom note types with:
 //! - Field schemas (required fields, types, constraints)
 //! - Custom validation functions
-//! - Lifecycle hooks (on_create, on_update)
+//! - Allowed field value transitions (status workflows)
+//! - Lifecycle hooks (on_create, on_update, on_save, on_index)
 //!
 //! Type definitions are loaded from Lua files in `~/.config/mdvault/types/`.
+//! Without the `native` feature only schema checking ([`validate_schema`])
+//! is built.
 //!
 //! # Example Type Definition
 //!
@@ -29,24 +32,47 @@
 //! }
 //! ```
 
+#[cfg(feature = "native")]
 pub mod autofix;
+pub mod check;
+#[cfg(feature = "native")]
 pub mod definition;
+#[cfg(feature = "native")]
 pub mod discovery;
 pub mod errors;
+#[cfg(feature = "native")]
+pub mod fixtures;
+pub mod json_schema;
+#[cfg(feature = "native")]
 pub mod registry;
+#[cfg(feature = "native")]
 pub mod scaffolding;
 pub mod schema;
+#[cfg(feature = "native")]
 pub mod validation;
+pub mod workflow;
 
 // Re-export commonly used types
+#[cfg(feature = "native")]
 pub use autofix::{FixResult, apply_fixes, try_fix_note};
+pub use check::validate_schema;
+#[cfg(feature = "native")]
 pub use definition::{TypeDefinition, TypedefInfo};
+#[cfg(feature = "native")]
 pub use discovery::TypedefRepository;
-pub use errors::{TypedefError, ValidationError, ValidationResult};
+pub use errors::{FixOp, SuggestedFix, TypedefError, ValidationError, ValidationResult};
+#[cfg(feature = "native")]
+pub use fixtures::{Expectation, Fixture, discover_fixtures};
+pub use json_schema::{field_json_schema, type_json_schema};
+#[cfg(feature = "native")]
 pub use registry::TypeRegistry;
+#[cfg(feature = "native")]
 pub use scaffolding::{generate_scaffolding, get_missing_required_fields};
 pub use schema::{FieldSchema, FieldType};
+#[cfg(feature = "native")]
 pub use validation::{
     BrokenLink, LinkIntegrityResult, add_link_integrity_warnings, check_link_integrity,
-    validate_note, validate_note_for_creation,
+    check_transition, check_transitions, check_unique, validate_note,
+    validate_note_for_creation, validate_note_with_index,
 };
+pub use workflow::Workflow;
@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::workflow::Workflow;
+
 /// Type of a frontmatter field.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -100,6 +102,11 @@ pub struct FieldSchema {
     #[serde(default)]
     pub inherited: bool,
 
+    /// Whether no two notes of the type may share this field's value.
+    /// Checked against the index by `validate` and note creation.
+    #[serde(default)]
+    pub unique: bool,
+
     // String constraints
     /// Allowed values for enum fields.
     #[serde(default, rename = "enum")]
@@ -144,8 +151,10 @@ pub struct FieldSchema {
     pub max_items: Option<usize>,
 
     // Reference constraints
-    /// Restrict to notes of a specific type.
-    #[serde(default)]
+    /// Restrict to notes of a specific type. Declared as `target_type` (or
+    /// `note_type`); with the index, `validate` checks the referenced note
+    /// exists and has this type.
+    #[serde(default, alias = "target_type")]
     pub note_type: Option<String>,
 
     // Interactive selection
@@ -159,6 +168,12 @@ pub struct FieldSchema {
     /// ```
     #[serde(default)]
     pub selector: Option<String>,
+
+    // Workflow
+    /// Allowed value changes, as chains like `"todo -> doing -> done"`.
+    /// See [`Workflow`].
+    #[serde(default)]
+    pub transitions: Option<Vec<String>>,
 }
 
 impl FieldSchema {
@@ -186,6 +201,11 @@ impl FieldSchema {
         }
     }
 
+    /// The workflow declared by `transitions`, if any.
+    pub fn workflow(&self) -> Option<Workflow> {
+        self.transitions.as_deref().and_then(|chains| Workflow::parse(chains).ok())
+    }
+
     /// Get the effective field type, defaulting to String if not specified.
     pub fn effective_type(&self) -> FieldType {
         self.field_type.unwrap_or(FieldType::String)
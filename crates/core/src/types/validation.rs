@@ -1,15 +1,18 @@
 //! Note validation against type definitions.
 
+use std::collections::HashMap;
 use std::path::Path;
 
-use regex::Regex;
-
+use super::check::validate_schema;
 use super::definition::TypeDefinition;
-use super::errors::{ValidationError, ValidationResult};
+use super::errors::{FixOp, SuggestedFix, ValidationError, ValidationResult};
 use super::registry::TypeRegistry;
 use super::schema::{FieldSchema, FieldType};
-use crate::index::IndexDb;
-use crate::scripting::LuaEngine;
+use crate::index::{IndexDb, IndexedNote};
+use crate::scripting::engine::require;
+use crate::scripting::hook_runner::lua_value_to_yaml;
+use crate::scripting::index_bindings::{note_backlinks, note_links, query_notes};
+use crate::scripting::{Capability, pool};
 
 /// Validate a note's frontmatter against its type definition.
 ///
@@ -29,7 +32,29 @@ pub fn validate_note(
     frontmatter: &serde_yaml::Value,
     content: &str,
 ) -> ValidationResult {
-    validate_note_impl(registry, note_type, note_path, frontmatter, content, false)
+    validate_note_impl(registry, note_type, note_path, frontmatter, content, false, None)
+}
+
+/// Validate a note like [`validate_note`], letting the type's `validate()`
+/// function query the vault with `mdv.query()`, `mdv.links()` and
+/// `mdv.backlinks()`.
+pub fn validate_note_with_index(
+    registry: &TypeRegistry,
+    note_type: &str,
+    note_path: &str,
+    frontmatter: &serde_yaml::Value,
+    content: &str,
+    db: &IndexDb,
+) -> ValidationResult {
+    validate_note_impl(
+        registry,
+        note_type,
+        note_path,
+        frontmatter,
+        content,
+        false,
+        Some(db),
+    )
 }
 
 /// Validate a note during creation, before on_create hooks run.
@@ -43,7 +68,76 @@ pub fn validate_note_for_creation(
     frontmatter: &serde_yaml::Value,
     content: &str,
 ) -> ValidationResult {
-    validate_note_impl(registry, note_type, note_path, frontmatter, content, true)
+    validate_note_impl(registry, note_type, note_path, frontmatter, content, true, None)
+}
+
+/// Check that changing `field` of a `note_type` note from `from` to `to` is
+/// allowed by the field's `transitions`.
+///
+/// Fields without transitions, and types without a definition, allow any
+/// change.
+pub fn check_transition(
+    registry: &TypeRegistry,
+    note_type: &str,
+    field: &str,
+    from: &str,
+    to: &str,
+) -> Result<(), ValidationError> {
+    let Some(workflow) = registry
+        .get(note_type)
+        .and_then(|td| td.schema.get(field).and_then(|f| f.workflow()))
+    else {
+        return Ok(());
+    };
+    if workflow.allows(from, to) {
+        return Ok(());
+    }
+    Err(ValidationError::IllegalTransition {
+        field: field.to_string(),
+        from: from.to_string(),
+        to: to.to_string(),
+        allowed: workflow.next(from).to_vec(),
+    })
+}
+
+/// Check a note's fields against their `transitions`, comparing with the
+/// values last indexed for the note at `rel_path`.
+///
+/// Notes that are not indexed yet have nothing to compare against.
+pub fn check_transitions(
+    registry: &TypeRegistry,
+    note_type: &str,
+    rel_path: &Path,
+    frontmatter: &serde_yaml::Value,
+    db: &IndexDb,
+) -> ValidationResult {
+    let mut result = ValidationResult::success();
+    let Some(typedef) = registry.get(note_type) else {
+        return result;
+    };
+    if typedef.schema.values().all(|f| f.transitions.is_none()) {
+        return result;
+    }
+    let previous: serde_json::Value = match db.get_note_by_path(rel_path) {
+        Ok(Some(note)) => note
+            .frontmatter_json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default(),
+        _ => return result,
+    };
+
+    for field in typedef.schema.keys() {
+        let (Some(from), Some(to)) = (
+            previous.get(field).and_then(|v| v.as_str()),
+            frontmatter.get(field).and_then(|v| v.as_str()),
+        ) else {
+            continue;
+        };
+        if let Err(e) = check_transition(registry, note_type, field, from, to) {
+            result.add_error(e);
+        }
+    }
+    result
 }
 
 /// Internal implementation of note validation.
@@ -54,6 +148,7 @@ fn validate_note_impl(
     frontmatter: &serde_yaml::Value,
     content: &str,
     skip_inherited: bool,
+    index: Option<&IndexDb>,
 ) -> ValidationResult {
     // Get type definition (if any)
     let typedef = match registry.get(note_type) {
@@ -65,277 +160,172 @@ fn validate_note_impl(
 
     // Phase 1: Schema validation
     if let serde_yaml::Value::Mapping(map) = frontmatter {
-        let schema_result = validate_schema(&typedef, map, skip_inherited);
+        let schema_result = validate_schema(&typedef.schema, map, skip_inherited);
         result.merge(schema_result);
     }
 
     // Phase 2: Custom validate() function
     if typedef.has_validate_fn {
-        match run_validate_hook(&typedef, note_type, note_path, frontmatter, content) {
-            Ok((valid, message)) => {
-                if !valid {
-                    result.add_error(ValidationError::CustomValidation {
-                        message: message
-                            .unwrap_or_else(|| "Custom validation failed".to_string()),
-                    });
-                }
-            }
+        match run_validate_hook(
+            &typedef,
+            note_type,
+            note_path,
+            frontmatter,
+            content,
+            index,
+        ) {
+            Ok(hook_result) => result.merge(hook_result),
             Err(e) => result.add_error(e),
         }
     }
 
+    // Phase 3: Reference targets and unique values
+    if let (Some(db), serde_yaml::Value::Mapping(map)) = (index, frontmatter) {
+        result.merge(check_references(&typedef.schema, map, db));
+        result.merge(check_unique(
+            registry,
+            note_type,
+            Path::new(note_path),
+            frontmatter,
+            db,
+        ));
+    }
+
     result
 }
 
-/// Validate frontmatter against schema.
-///
-/// # Arguments
+/// Check a note's `unique` fields against the other indexed notes of its
+/// type.
 ///
-/// * `typedef` - The type definition containing the schema
-/// * `frontmatter` - The frontmatter mapping to validate
-/// * `skip_inherited` - If true, skip required-field checks for inherited fields
-fn validate_schema(
-    typedef: &TypeDefinition,
-    frontmatter: &serde_yaml::Mapping,
-    skip_inherited: bool,
+/// `note_path` may be absolute or relative to the vault root; the note's own
+/// index entry is not counted.
+pub fn check_unique(
+    registry: &TypeRegistry,
+    note_type: &str,
+    note_path: &Path,
+    frontmatter: &serde_yaml::Value,
+    db: &IndexDb,
 ) -> ValidationResult {
     let mut result = ValidationResult::success();
-
-    for (field_name, schema) in &typedef.schema {
-        let value = frontmatter.get(serde_yaml::Value::String(field_name.clone()));
-
-        // Check required fields
-        // Skip inherited fields during creation (they'll be set by on_create hook)
-        // Skip core fields (they're managed outside frontmatter, e.g., title from filename/heading)
-        // Skip prompted fields (they're template variables, not frontmatter fields)
-        if schema.required && value.is_none() {
-            if skip_inherited && schema.inherited {
-                // Skip - inherited field will be populated by hook
-                continue;
-            }
-            if schema.core {
-                // Skip - core fields are not stored in frontmatter
-                continue;
-            }
-            if schema.prompt.is_some() {
-                // Skip - prompted fields are template variables, not frontmatter fields
-                continue;
-            }
-            result.add_error(ValidationError::MissingRequired {
-                field: field_name.clone(),
-            });
+    let Some(typedef) = registry.get(note_type) else {
+        return result;
+    };
+    for field in typedef.schema.iter().filter(|(_, f)| f.unique).map(|(k, _)| k) {
+        let value = match frontmatter.get(field) {
+            Some(serde_yaml::Value::String(s)) if !s.is_empty() => s.clone(),
+            Some(serde_yaml::Value::Number(n)) => n.to_string(),
+            _ => continue,
+        };
+        let Ok(notes) = db.find_notes_by_field(field, &value) else {
             continue;
-        }
-
-        // Validate value if present
-        if let Some(val) = value {
-            let field_result = validate_field(field_name, schema, val);
-            result.merge(field_result);
+        };
+        let others: Vec<String> = notes
+            .iter()
+            .filter(|n| !note_path.ends_with(&n.path) && indexed_type(n) == note_type)
+            .map(|n| n.path.to_string_lossy().into_owned())
+            .collect();
+        if !others.is_empty() {
+            result.add_error(ValidationError::DuplicateValue {
+                field: field.clone(),
+                value,
+                others,
+            });
         }
     }
-
     result
 }
 
-/// Validate a single field value against its schema.
-fn validate_field(
-    field: &str,
-    schema: &FieldSchema,
-    value: &serde_yaml::Value,
+/// The type of an indexed note. Notes of custom types are indexed untyped,
+/// so their `type` field decides.
+fn indexed_type(note: &IndexedNote) -> String {
+    note.frontmatter_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str::<serde_json::Value>(json).ok())
+        .and_then(|fm| fm.get("type").and_then(|t| t.as_str()).map(str::to_string))
+        .unwrap_or_else(|| note.note_type.as_str().to_string())
+}
+
+/// Check that reference fields with a target type point to an indexed note
+/// of that type, including the items of reference lists.
+fn check_references(
+    schema: &HashMap<String, FieldSchema>,
+    frontmatter: &serde_yaml::Mapping,
+    db: &IndexDb,
 ) -> ValidationResult {
     let mut result = ValidationResult::success();
-
-    let expected_type = schema.effective_type();
-
-    // Type checking
-    let type_ok = match (&expected_type, value) {
-        (FieldType::String, serde_yaml::Value::String(_)) => true,
-        (FieldType::Number, serde_yaml::Value::Number(_)) => true,
-        (FieldType::Boolean, serde_yaml::Value::Bool(_)) => true,
-        (FieldType::List, serde_yaml::Value::Sequence(_)) => true,
-        (FieldType::Date, serde_yaml::Value::String(s)) => is_valid_date(s),
-        (FieldType::Datetime, serde_yaml::Value::String(s)) => is_valid_datetime(s),
-        (FieldType::Reference, serde_yaml::Value::String(_)) => true,
-        _ => false,
-    };
-
-    if !type_ok {
-        result.add_error(ValidationError::TypeMismatch {
-            field: field.to_string(),
-            expected: expected_type.to_string(),
-            actual: yaml_type_name(value),
-        });
-        return result;
-    }
-
-    // Enum constraint
-    if let (Some(enum_values), serde_yaml::Value::String(s)) =
-        (&schema.enum_values, value)
-        && !enum_values.contains(s)
-    {
-        result.add_error(ValidationError::EnumViolation {
-            field: field.to_string(),
-            value: s.clone(),
-            allowed: enum_values.clone(),
-        });
-    }
-
-    // Number constraints
-    if let serde_yaml::Value::Number(n) = value
-        && let Some(f) = n.as_f64()
-    {
-        if let Some(min) = schema.min
-            && f < min
-        {
-            result.add_error(ValidationError::InvalidValue {
-                field: field.to_string(),
-                message: format!("value {} is less than minimum {}", f, min),
-            });
-        }
-        if let Some(max) = schema.max
-            && f > max
-        {
-            result.add_error(ValidationError::InvalidValue {
-                field: field.to_string(),
-                message: format!("value {} is greater than maximum {}", f, max),
-            });
-        }
-        if let Some(true) = schema.integer
-            && f.fract() != 0.0
-        {
-            result.add_error(ValidationError::InvalidValue {
-                field: field.to_string(),
-                message: format!("value {} must be an integer", f),
-            });
-        }
-    }
-
-    // String length constraints
-    if let serde_yaml::Value::String(s) = value {
-        if let Some(min) = schema.min_length
-            && s.len() < min
-        {
-            result.add_error(ValidationError::InvalidValue {
-                field: field.to_string(),
-                message: format!(
-                    "string length {} is less than minimum {}",
-                    s.len(),
-                    min
-                ),
-            });
-        }
-        if let Some(max) = schema.max_length
-            && s.len() > max
-        {
-            result.add_error(ValidationError::InvalidValue {
-                field: field.to_string(),
-                message: format!(
-                    "string length {} is greater than maximum {}",
-                    s.len(),
-                    max
-                ),
-            });
-        }
-        if let Some(pattern) = &schema.pattern
-            && let Ok(re) = Regex::new(pattern)
-            && !re.is_match(s)
-        {
-            result.add_error(ValidationError::InvalidValue {
-                field: field.to_string(),
-                message: format!("value '{}' does not match pattern '{}'", s, pattern),
-            });
-        }
-    }
-
-    // List constraints
-    if let serde_yaml::Value::Sequence(seq) = value {
-        if let Some(min) = schema.min_items
-            && seq.len() < min
-        {
-            result.add_error(ValidationError::InvalidValue {
-                field: field.to_string(),
-                message: format!("list has {} items, minimum is {}", seq.len(), min),
-            });
-        }
-        if let Some(max) = schema.max_items
-            && seq.len() > max
-        {
-            result.add_error(ValidationError::InvalidValue {
-                field: field.to_string(),
-                message: format!("list has {} items, maximum is {}", seq.len(), max),
-            });
+    for (field, field_schema) in schema {
+        let Some(value) = frontmatter.get(field.as_str()) else {
+            continue;
+        };
+        let (target_schema, values) = match value {
+            serde_yaml::Value::Sequence(items) => match &field_schema.items {
+                Some(item_schema) => (item_schema.as_ref(), items.iter().collect()),
+                None => continue,
+            },
+            other => (field_schema, vec![other]),
+        };
+        let Some(target_type) = target_schema.note_type.as_deref() else {
+            continue;
+        };
+        if target_schema.effective_type() != FieldType::Reference {
+            continue;
         }
-
-        // Validate items if schema provided
-        if let Some(item_schema) = &schema.items {
-            for (i, item) in seq.iter().enumerate() {
-                let item_field = format!("{}[{}]", field, i);
-                let item_result = validate_field(&item_field, item_schema, item);
-                result.merge(item_result);
+        for reference in values.iter().filter_map(|v| v.as_str()) {
+            if let Some(e) = check_reference(db, field, reference, target_type) {
+                result.add_error(e);
             }
         }
     }
-
     result
 }
 
-/// Check if a string is a valid date (YYYY-MM-DD format).
-fn is_valid_date(s: &str) -> bool {
-    // Simple validation: YYYY-MM-DD
-    if s.len() != 10 {
-        return false;
-    }
-    let parts: Vec<&str> = s.split('-').collect();
-    if parts.len() != 3 {
-        return false;
-    }
-    parts[0].len() == 4
-        && parts[1].len() == 2
-        && parts[2].len() == 2
-        && parts[0].chars().all(|c| c.is_ascii_digit())
-        && parts[1].chars().all(|c| c.is_ascii_digit())
-        && parts[2].chars().all(|c| c.is_ascii_digit())
-}
-
-/// Check if a string is a valid datetime (ISO 8601 format).
-fn is_valid_datetime(s: &str) -> bool {
-    // Accept various ISO 8601 formats
-    // YYYY-MM-DDTHH:MM:SS or YYYY-MM-DD HH:MM:SS or YYYY-MM-DDTHH:MM:SSZ
-    chrono::DateTime::parse_from_rfc3339(s).is_ok()
-        || chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").is_ok()
-        || chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").is_ok()
-}
-
-/// Get a human-readable type name for a YAML value.
-fn yaml_type_name(value: &serde_yaml::Value) -> String {
-    match value {
-        serde_yaml::Value::Null => "null".to_string(),
-        serde_yaml::Value::Bool(_) => "boolean".to_string(),
-        serde_yaml::Value::Number(_) => "number".to_string(),
-        serde_yaml::Value::String(_) => "string".to_string(),
-        serde_yaml::Value::Sequence(_) => "list".to_string(),
-        serde_yaml::Value::Mapping(_) => "mapping".to_string(),
-        serde_yaml::Value::Tagged(_) => "tagged".to_string(),
+/// Check one reference, returning the error if it is dangling or mistyped.
+fn check_reference(
+    db: &IndexDb,
+    field: &str,
+    reference: &str,
+    target_type: &str,
+) -> Option<ValidationError> {
+    if reference.trim().is_empty() {
+        return None;
+    }
+    let targets = db.find_notes_by_reference(reference).ok()?;
+    if targets.iter().any(|note| indexed_type(note) == target_type) {
+        return None;
     }
+    Some(match targets.first() {
+        None => ValidationError::DanglingReference {
+            field: field.to_string(),
+            value: reference.to_string(),
+            target_type: target_type.to_string(),
+        },
+        Some(note) => ValidationError::WrongReferenceType {
+            field: field.to_string(),
+            value: reference.to_string(),
+            target_type: target_type.to_string(),
+            actual: indexed_type(note),
+        },
+    })
 }
 
+/// `mdv` functions a `validate()` function gets when there is an index.
+const INDEX_FUNCTIONS: [&str; 4] = ["query", "links", "outlinks", "backlinks"];
+
 /// Run custom validate() Lua hook.
+///
+/// `validate(note)` returns `true`/`nil` on success, `false` or a message
+/// on failure, or a list of issues (see [`parse_issues`]).
 fn run_validate_hook(
     typedef: &TypeDefinition,
     note_type: &str,
     note_path: &str,
     frontmatter: &serde_yaml::Value,
     content: &str,
-) -> Result<(bool, Option<String>), ValidationError> {
-    let engine =
-        LuaEngine::sandboxed().map_err(|e| ValidationError::LuaError(e.to_string()))?;
-
-    let lua = engine.lua();
-
-    // Load the type definition
-    lua.load(&typedef.lua_source)
-        .exec()
-        .map_err(|e| ValidationError::LuaError(e.to_string()))?;
+    index: Option<&IndexDb>,
+) -> Result<ValidationResult, ValidationError> {
+    let pooled =
+        pool::checkout(typedef).map_err(|e| ValidationError::LuaError(e.to_string()))?;
+    let lua = pooled.lua();
 
     // Build note table for validation
     let note_table =
@@ -358,40 +348,130 @@ fn run_validate_hook(
         .set("frontmatter", fm_table)
         .map_err(|e| ValidationError::LuaError(e.to_string()))?;
 
-    // Get the type definition table by re-evaluating
-    let typedef_table: mlua::Table = lua
-        .load(&typedef.lua_source)
-        .eval()
-        .map_err(|e| ValidationError::LuaError(e.to_string()))?;
-
     // Call validate function
-    let validate_fn: mlua::Function = typedef_table
+    let validate_fn: mlua::Function = pooled
+        .table()
         .get("validate")
         .map_err(|e| ValidationError::LuaError(e.to_string()))?;
 
-    let result = validate_fn
-        .call::<mlua::MultiValue>(note_table)
-        .map_err(|e| ValidationError::LuaError(e.to_string()))?;
+    let result = match index {
+        // Scoped so the index functions can borrow the index for the call
+        Some(db) => lua.scope(|scope| {
+            let mdv = pooled.mdv()?;
+            let query = scope.create_function(|lua, opts: Option<mlua::Table>| {
+                require(lua, Capability::ReadVault, "mdv.query")?;
+                query_notes(lua, db, opts)
+            })?;
+            let links = scope.create_function(|lua, path: String| {
+                require(lua, Capability::ReadVault, "mdv.links")?;
+                note_links(lua, db, &path)
+            })?;
+            let backlinks = scope.create_function(|lua, path: String| {
+                require(lua, Capability::ReadVault, "mdv.backlinks")?;
+                note_backlinks(lua, db, &path)
+            })?;
+            mdv.set("query", query)?;
+            mdv.set("links", links.clone())?;
+            mdv.set("outlinks", links)?;
+            mdv.set("backlinks", backlinks)?;
+            let result = validate_fn.call::<mlua::MultiValue>(note_table);
+            // The definition's table outlives the index borrow
+            for name in INDEX_FUNCTIONS {
+                mdv.set(name, mlua::Value::Nil)?;
+            }
+            result
+        }),
+        None => validate_fn.call::<mlua::MultiValue>(note_table),
+    }
+    .map_err(|e| ValidationError::LuaError(e.to_string()))?;
 
-    // Parse result: (true) or (false, "error message")
+    // Parse result: (true), (false, "error message") or a list of issues
+    let failed = |message: Option<String>| {
+        ValidationResult::single_error(ValidationError::CustomValidation {
+            message: message.unwrap_or_else(|| "Custom validation failed".to_string()),
+        })
+    };
     let values: Vec<mlua::Value> = result.into_iter().collect();
     match values.as_slice() {
         [mlua::Value::Boolean(true)] | [mlua::Value::Boolean(true), _] => {
-            Ok((true, None))
+            Ok(ValidationResult::success())
         }
-        [mlua::Value::Boolean(false)] => Ok((false, None)),
-        [mlua::Value::Boolean(false), mlua::Value::String(msg)] => {
-            let msg_str = msg.to_str().map(|s| s.to_string()).unwrap_or_default();
-            Ok((false, Some(msg_str)))
+        [mlua::Value::Boolean(false)] => Ok(failed(None)),
+        [mlua::Value::Boolean(false), mlua::Value::String(msg)]
+        | [mlua::Value::String(msg)] => {
+            Ok(failed(Some(msg.to_str().map(|s| s.to_string()).unwrap_or_default())))
         }
-        [mlua::Value::String(msg)] => {
-            let msg_str = msg.to_str().map(|s| s.to_string()).unwrap_or_default();
-            Ok((false, Some(msg_str)))
+        [mlua::Value::Table(issues), ..] => parse_issues(issues),
+        [mlua::Value::Nil] => Ok(ValidationResult::success()), // nil treated as success
+        [] => Ok(ValidationResult::success()), // no return treated as success
+        _ => Ok(ValidationResult::success()),
+    }
+}
+
+/// Parse the issues a `validate()` function returned.
+///
+/// Each issue is `{ severity = "error"|"warning", field?, message, fix? }`;
+/// a single issue may be returned without the surrounding list. `fix` is
+/// `{ op = "set"|"remove"|"append", value? }` and applies to `field`.
+fn parse_issues(issues: &mlua::Table) -> Result<ValidationResult, ValidationError> {
+    let invalid = |message: String| {
+        ValidationError::LuaError(format!(
+            "validate() returned an invalid issue: {message}"
+        ))
+    };
+    let list = if issues.contains_key("message").unwrap_or(false) {
+        vec![issues.clone()]
+    } else {
+        issues
+            .sequence_values::<mlua::Table>()
+            .collect::<mlua::Result<Vec<_>>>()
+            .map_err(|_| invalid("each issue must be a table".into()))?
+    };
+
+    let mut result = ValidationResult::success();
+    for issue in list {
+        let text = |key: &str| -> Result<Option<String>, ValidationError> {
+            issue.get(key).map_err(|_| invalid(format!("{key} must be a string")))
+        };
+        let message =
+            text("message")?.ok_or_else(|| invalid("missing message".into()))?;
+        let field = text("field")?;
+
+        if let Some(fix) = issue
+            .get::<Option<mlua::Table>>("fix")
+            .map_err(|_| invalid("fix must be a table".into()))?
+        {
+            let field = field
+                .clone()
+                .ok_or_else(|| invalid("a fix needs the issue's field".into()))?;
+            let value = || {
+                lua_value_to_yaml(fix.get("value").unwrap_or(mlua::Value::Nil))
+                    .map_err(|e| invalid(e.to_string()))
+            };
+            let op: Option<String> =
+                fix.get("op").map_err(|_| invalid("fix.op must be a string".into()))?;
+            let op = match op.as_deref().unwrap_or("set") {
+                "set" => FixOp::Set(value()?),
+                "remove" => FixOp::Remove,
+                "append" => FixOp::Append(value()?),
+                other => return Err(invalid(format!("unknown fix op '{other}'"))),
+            };
+            result.fixes.push(SuggestedFix { field, op });
+        }
+
+        match text("severity")?.as_deref().unwrap_or("error") {
+            "error" => result.add_error(match field {
+                Some(field) => ValidationError::InvalidValue { field, message },
+                None => ValidationError::CustomValidation { message },
+            }),
+            "warning" => result.add_warning(match field {
+                Some(field) => format!("{field}: {message}"),
+                None => message,
+            }),
+            other => return Err(invalid(format!("unknown severity '{other}'"))),
         }
-        [mlua::Value::Nil] => Ok((true, None)), // nil treated as success
-        [] => Ok((true, None)),                 // no return treated as success
-        _ => Ok((true, None)),
     }
+    Ok(result)
 }
 
 /// Convert a serde_yaml::Value to a Lua value.
@@ -540,7 +620,6 @@ pub fn add_link_integrity_warnings(
 mod tests {
     use super::*;
     use crate::types::definition::TypeDefinition;
-    use std::collections::HashMap;
 
     fn make_frontmatter(fields: &[(&str, serde_yaml::Value)]) -> serde_yaml::Value {
         let mut map = serde_yaml::Mapping::new();
@@ -558,11 +637,18 @@ mod tests {
             schema,
             output: None,
             frontmatter_order: None,
+            template: None,
             variables: crate::vars::VarsMap::new(),
             has_validate_fn: false,
             has_on_create_hook: false,
             has_on_update_hook: false,
+            has_on_save_hook: false,
+            has_on_index_hook: false,
+            has_migrate_fn: false,
+            schema_version: None,
             is_builtin_override: false,
+            retention: None,
+            permissions: crate::scripting::Permissions::default(),
             lua_source: String::new(),
         }
     }
@@ -811,21 +897,361 @@ mod tests {
     }
 
     #[test]
-    fn test_is_valid_date() {
-        assert!(is_valid_date("2025-12-29"));
-        assert!(is_valid_date("2000-01-01"));
-        assert!(!is_valid_date("2025-1-29")); // Month not zero-padded
-        assert!(!is_valid_date("25-12-29")); // Year not 4 digits
-        assert!(!is_valid_date("2025/12/29")); // Wrong separator
-        assert!(!is_valid_date("not a date"));
+    fn test_validate_fn_can_query_index() {
+        let mut typedef = make_typedef_with_schema(HashMap::new());
+        typedef.has_validate_fn = true;
+        typedef.lua_source = r#"
+            return {
+                validate = function(note)
+                    local open = mdv.query({ type = "project", status = "open" })
+                    if #open > 0 then
+                        return false, "close " .. open[1].title .. " first"
+                    end
+                    return true
+                end,
+            }
+        "#
+        .to_string();
+        let mut registry = TypeRegistry::new();
+        registry.register(typedef).unwrap();
+
+        let db = IndexDb::open_in_memory().unwrap();
+        let frontmatter = make_frontmatter(&[]);
+        let result =
+            validate_note_with_index(&registry, "test", "/t.md", &frontmatter, "", &db);
+        assert!(result.valid);
+
+        let id = db
+            .insert_note(&crate::index::IndexedNote {
+                id: None,
+                path: "Projects/mcp.md".into(),
+                note_type: crate::index::NoteType::Project,
+                title: "MCP".to_string(),
+                created: None,
+                modified: chrono::Utc::now(),
+                frontmatter_json: Some(r#"{"status":"open"}"#.to_string()),
+                content_hash: String::new(),
+                word_count: 0,
+                heading_json: None,
+            })
+            .unwrap();
+        db.replace_note_fields(id, Some(r#"{"status":"open"}"#)).unwrap();
+        let result =
+            validate_note_with_index(&registry, "test", "/t.md", &frontmatter, "", &db);
+        assert!(!result.valid);
+        assert!(result.errors[0].to_string().contains("close MCP first"));
+    }
+
+    #[test]
+    fn test_validate_fn_can_follow_links() {
+        use crate::index::{IndexedLink, IndexedNote, LinkType, NoteType};
+
+        let mut typedef = make_typedef_with_schema(HashMap::new());
+        typedef.has_validate_fn = true;
+        typedef.lua_source = r#"
+            return {
+                validate = function(note)
+                    for _, link in ipairs(mdv.links(note.path)) do
+                        if link.title and link.title:match("MOC$") then
+                            return true
+                        end
+                    end
+                    local cited = mdv.backlinks(note.path)[1]
+                    return false, "link to a MOC (cited by " .. cited.path
+                        .. ": " .. cited.context .. ")"
+                end,
+            }
+        "#
+        .to_string();
+        let mut registry = TypeRegistry::new();
+        registry.register(typedef).unwrap();
+
+        let db = IndexDb::open_in_memory().unwrap();
+        let note = |path: &str, title: &str| IndexedNote {
+            id: None,
+            path: path.into(),
+            note_type: NoteType::Zettel,
+            title: title.to_string(),
+            created: None,
+            modified: chrono::Utc::now(),
+            frontmatter_json: None,
+            content_hash: String::new(),
+            word_count: 0,
+            heading_json: None,
+        };
+        let link = |source_id, target_id, context: &str| IndexedLink {
+            id: None,
+            source_id,
+            target_id: Some(target_id),
+            target_path: String::new(),
+            target_anchor: None,
+            link_text: None,
+            link_type: LinkType::Wikilink,
+            context: Some(context.to_string()),
+            line_number: Some(3),
+        };
+        let zettel = db.insert_note(&note("z.md", "Idea")).unwrap();
+        let other = db.insert_note(&note("other.md", "Other")).unwrap();
+        let moc = db.insert_note(&note("moc.md", "Rust MOC")).unwrap();
+        db.insert_link(&link(other, zettel, "see [[z]]")).unwrap();
+
+        let frontmatter = make_frontmatter(&[]);
+        let result =
+            validate_note_with_index(&registry, "test", "z.md", &frontmatter, "", &db);
+        assert!(!result.valid);
+        assert!(
+            result.errors[0].to_string().contains("cited by other.md: see [[z]]"),
+            "{}",
+            result.errors[0]
+        );
+
+        db.insert_link(&link(zettel, moc, "part of [[moc]]")).unwrap();
+        let result =
+            validate_note_with_index(&registry, "test", "z.md", &frontmatter, "", &db);
+        assert!(result.valid, "{:?}", result.errors);
+    }
+
+    #[test]
+    fn test_validate_fn_returns_issues() {
+        let mut typedef = make_typedef_with_schema(HashMap::new());
+        typedef.has_validate_fn = true;
+        typedef.lua_source = r#"
+            return {
+                validate = function(note)
+                    local issues = {
+                        { severity = "warning", field = "tags", message = "no tags",
+                          fix = { op = "append", value = "inbox" } },
+                        { message = "needs a review date" },
+                    }
+                    if note.frontmatter.status ~= "open" then
+                        issues[#issues + 1] = { field = "status", message = "must be open",
+                                                fix = { value = "open" } }
+                    end
+                    return issues
+                end,
+            }
+        "#
+        .to_string();
+        let mut registry = TypeRegistry::new();
+        registry.register(typedef).unwrap();
+
+        let frontmatter = make_frontmatter(&[("status", "OPEN".into())]);
+        let result = validate_note(&registry, "test", "t.md", &frontmatter, "");
+        assert!(!result.valid);
+        assert_eq!(result.warnings, ["tags: no tags"]);
+        let errors: Vec<_> = result.errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            [
+                "custom validation failed: needs a review date",
+                "invalid value for field 'status': must be open",
+            ]
+        );
+        assert_eq!(
+            result.fixes,
+            [
+                SuggestedFix { field: "tags".into(), op: FixOp::Append("inbox".into()) },
+                SuggestedFix { field: "status".into(), op: FixOp::Set("open".into()) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_fn_rejects_malformed_issues() {
+        let validate = |issue: &str| {
+            let mut typedef = make_typedef_with_schema(HashMap::new());
+            typedef.has_validate_fn = true;
+            typedef.lua_source =
+                format!("return {{ validate = function() return {{ {issue} }} end }}");
+            let mut registry = TypeRegistry::new();
+            registry.register(typedef).unwrap();
+            let result =
+                validate_note(&registry, "test", "t.md", &make_frontmatter(&[]), "");
+            result.errors[0].to_string()
+        };
+
+        assert!(
+            validate(r#"{ severity = "fatal", message = "x" }"#)
+                .contains("unknown severity")
+        );
+        assert!(
+            validate(r#"{ message = "x", fix = { value = 1 } }"#)
+                .contains("needs the issue's field")
+        );
+        assert!(
+            validate(r#"{ field = "a", message = "x", fix = { op = "rename" } }"#)
+                .contains("unknown fix op")
+        );
+        assert!(validate(r#"{ field = "a" }"#).contains("missing message"));
+    }
+
+    #[test]
+    fn test_check_transitions_against_indexed_value() {
+        let mut schema = HashMap::new();
+        schema.insert(
+            "status".to_string(),
+            FieldSchema {
+                enum_values: Some(vec!["todo".into(), "doing".into(), "done".into()]),
+                transitions: Some(vec!["todo -> doing -> done".into()]),
+                ..Default::default()
+            },
+        );
+        let mut registry = TypeRegistry::new();
+        registry.register(make_typedef_with_schema(schema)).unwrap();
+
+        let db = IndexDb::open_in_memory().unwrap();
+        let path = Path::new("tasks/t.md");
+        let done = make_frontmatter(&[("status", "done".into())]);
+        // Not indexed yet: nothing to compare against
+        assert!(check_transitions(&registry, "test", path, &done, &db).valid);
+
+        db.insert_note(&crate::index::IndexedNote {
+            id: None,
+            path: path.into(),
+            note_type: crate::index::NoteType::None,
+            title: "t".to_string(),
+            created: None,
+            modified: chrono::Utc::now(),
+            frontmatter_json: Some(r#"{"status":"todo"}"#.to_string()),
+            content_hash: String::new(),
+            word_count: 0,
+            heading_json: None,
+        })
+        .unwrap();
+
+        let result = check_transitions(&registry, "test", path, &done, &db);
+        assert!(!result.valid);
+        assert_eq!(
+            result.errors[0].to_string(),
+            "illegal transition for 'status': 'todo' -> 'done' (from 'todo' only: doing)"
+        );
+        let doing = make_frontmatter(&[("status", "doing".into())]);
+        assert!(check_transitions(&registry, "test", path, &doing, &db).valid);
+
+        let err =
+            check_transition(&registry, "test", "status", "done", "todo").unwrap_err();
+        assert!(err.to_string().ends_with("('done' is a final state)"), "{err}");
+    }
+
+    #[test]
+    fn test_reference_target_type() {
+        use crate::index::NoteType;
+
+        let reference = |note_type: &str| FieldSchema {
+            field_type: Some(FieldType::Reference),
+            note_type: Some(note_type.to_string()),
+            ..Default::default()
+        };
+        let mut schema = HashMap::new();
+        schema.insert("project".to_string(), reference("project"));
+        schema.insert(
+            "related".to_string(),
+            FieldSchema {
+                field_type: Some(FieldType::List),
+                items: Some(Box::new(reference("book"))),
+                ..Default::default()
+            },
+        );
+        let mut registry = TypeRegistry::new();
+        registry.register(make_typedef_with_schema(schema)).unwrap();
+
+        let db = IndexDb::open_in_memory().unwrap();
+        let insert = |path: &str, note_type: NoteType, fm: &str| {
+            db.insert_note(&crate::index::IndexedNote {
+                id: None,
+                path: path.into(),
+                note_type,
+                title: String::new(),
+                created: None,
+                modified: chrono::Utc::now(),
+                frontmatter_json: Some(fm.to_string()),
+                content_hash: String::new(),
+                word_count: 0,
+                heading_json: None,
+            })
+            .unwrap();
+        };
+        insert("Projects/MCP/MCP.md", NoteType::Project, r#"{"type":"project"}"#);
+        insert("zettel/idea.md", NoteType::Zettel, r#"{"type":"zettel"}"#);
+        insert("books/dune.md", NoteType::None, r#"{"type":"book"}"#);
+
+        let validate = |fm: serde_yaml::Value| {
+            validate_note_with_index(&registry, "test", "t.md", &fm, "", &db)
+        };
+        let fm = |project: &str, related: &[&str]| {
+            make_frontmatter(&[
+                ("project", project.into()),
+                (
+                    "related",
+                    serde_yaml::Value::Sequence(
+                        related.iter().map(|r| (*r).into()).collect(),
+                    ),
+                ),
+            ])
+        };
+
+        assert!(validate(fm("MCP", &["[[dune]]"])).valid);
+
+        let result = validate(fm("XYZ", &["idea"]));
+        let errors: Vec<String> = result.errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert!(errors.contains(
+            &"dangling reference in 'project': no project note 'XYZ'".to_string()
+        ));
+        assert!(
+            errors.contains(
+                &"invalid reference in 'related': 'idea' is a zettel note, expected book"
+                    .to_string()
+            )
+        );
+
+        // Without the index, references are not checked
+        assert!(validate_note(&registry, "test", "t.md", &fm("XYZ", &[]), "").valid);
     }
 
     #[test]
-    fn test_is_valid_datetime() {
-        assert!(is_valid_datetime("2025-12-29T14:30:00Z"));
-        assert!(is_valid_datetime("2025-12-29T14:30:00+00:00"));
-        assert!(is_valid_datetime("2025-12-29T14:30:00"));
-        assert!(!is_valid_datetime("not a datetime"));
-        assert!(!is_valid_datetime("2025-12-29")); // Just a date
+    fn test_unique_field_checked_against_index() {
+        let mut schema = HashMap::new();
+        schema.insert(
+            "zettel-id".to_string(),
+            FieldSchema { unique: true, ..Default::default() },
+        );
+        let mut registry = TypeRegistry::new();
+        registry.register(make_typedef_with_schema(schema)).unwrap();
+
+        let db = IndexDb::open_in_memory().unwrap();
+        for (path, note_type) in [("a.md", "test"), ("b.md", "other")] {
+            let fm = format!(r#"{{"type":"{note_type}","zettel-id":"42"}}"#);
+            let id = db
+                .insert_note(&crate::index::IndexedNote {
+                    id: None,
+                    path: path.into(),
+                    note_type: crate::index::NoteType::None,
+                    title: String::new(),
+                    created: None,
+                    modified: chrono::Utc::now(),
+                    frontmatter_json: Some(fm.clone()),
+                    content_hash: String::new(),
+                    word_count: 0,
+                    heading_json: None,
+                })
+                .unwrap();
+            db.replace_note_fields(id, Some(&fm)).unwrap();
+        }
+
+        let fm = make_frontmatter(&[("zettel-id", "42".into())]);
+        // The note's own entry does not count, nor do notes of other types
+        assert!(
+            check_unique(&registry, "test", Path::new("/vault/a.md"), &fm, &db).valid
+        );
+
+        let result =
+            validate_note_with_index(&registry, "test", "/vault/c.md", &fm, "", &db);
+        assert_eq!(
+            result.errors[0].to_string(),
+            "duplicate value for unique field 'zettel-id': '42' is also used by a.md"
+        );
+        let fm = make_frontmatter(&[("zettel-id", "43".into())]);
+        assert!(check_unique(&registry, "test", Path::new("c.md"), &fm, &db).valid);
     }
 }
@@ -0,0 +1,86 @@
+//! Allowed value transitions of a field.
+//!
+//! A field's `transitions` lists chains of states such as
+//! `"todo -> doing -> done"`; each arrow is a move the field's value may
+//! make. Moves from a state the workflow does not mention are not checked.
+
+use std::collections::BTreeMap;
+
+/// The moves a field's value may make, parsed from its `transitions`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Workflow {
+    edges: BTreeMap<String, Vec<String>>,
+}
+
+impl Workflow {
+    /// Parse transition chains like `"todo -> doing -> done"`.
+    pub fn parse<S: AsRef<str>>(chains: &[S]) -> Result<Self, String> {
+        let mut workflow = Self::default();
+        for chain in chains {
+            let chain = chain.as_ref();
+            let states: Vec<&str> = chain.split("->").map(str::trim).collect();
+            if states.len() < 2 || states.iter().any(|s| s.is_empty()) {
+                return Err(format!(
+                    "invalid transition '{chain}': expected 'from -> to' states"
+                ));
+            }
+            for pair in states.windows(2) {
+                let next = workflow.edges.entry(pair[0].to_string()).or_default();
+                if !next.iter().any(|s| s == pair[1]) {
+                    next.push(pair[1].to_string());
+                }
+                workflow.edges.entry(pair[1].to_string()).or_default();
+            }
+        }
+        Ok(workflow)
+    }
+
+    /// Every state named in the workflow, sorted.
+    pub fn states(&self) -> impl Iterator<Item = &str> {
+        self.edges.keys().map(String::as_str)
+    }
+
+    /// States reachable from `from` in one move, in declaration order.
+    pub fn next(&self, from: &str) -> &[String] {
+        self.edges.get(from).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Whether the value may change from `from` to `to`.
+    pub fn allows(&self, from: &str, to: &str) -> bool {
+        from == to
+            || !self.edges.contains_key(from)
+            || self.next(from).iter().any(|s| s == to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chains() {
+        let wf = Workflow::parse(&["todo -> doing -> done", "doing -> blocked -> doing"])
+            .unwrap();
+        assert_eq!(wf.states().collect::<Vec<_>>(), ["blocked", "doing", "done", "todo"]);
+        assert_eq!(wf.next("doing"), ["done", "blocked"]);
+        assert!(wf.next("done").is_empty());
+    }
+
+    #[test]
+    fn test_allows() {
+        let wf = Workflow::parse(&["todo -> doing -> done"]).unwrap();
+        assert!(wf.allows("todo", "doing"));
+        assert!(wf.allows("doing", "doing"));
+        assert!(!wf.allows("todo", "done"));
+        assert!(!wf.allows("done", "todo"));
+        // Values outside the workflow are not checked
+        assert!(wf.allows("open", "done"));
+    }
+
+    #[test]
+    fn test_parse_rejects_incomplete_chains() {
+        assert!(Workflow::parse(&["todo"]).is_err());
+        assert!(Workflow::parse(&["todo -> "]).is_err());
+        assert!(Workflow::parse(&["-> done"]).is_err());
+    }
+}
@@ -0,0 +1,267 @@
+//! Operation journal for undoing the last write.
+//!
+//! An operation (running a template, capture, or macro) calls
+//! [`Recorder::before_write`] for every file before it writes it, then
+//! [`Recorder::commit`]. The previous contents are kept under
+//! `.mdvault/undo/{id}/` and the files written are journaled in
+//! `.mdvault/undo.jsonl`, so [`undo_last`] can put them back, removing
+//! files the operation created. Files edited since are left alone.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::paths::PathResolver;
+use crate::vault::content_hash;
+
+/// Operations kept in the journal; older ones can no longer be undone.
+const KEEP_OPERATIONS: usize = 20;
+
+#[derive(Debug, Error)]
+pub enum UndoError {
+    #[error("failed to access {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("undo journal is corrupt at line {line}: {message}")]
+    Journal { line: usize, message: String },
+
+    #[error("{0} has changed since; not undoing")]
+    Changed(PathBuf),
+
+    #[error("nothing to undo")]
+    NothingToUndo,
+}
+
+/// A file an operation wrote. Paths are vault-relative.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileChange {
+    pub path: PathBuf,
+    /// Copy of what the file held before; `None` when the operation
+    /// created it.
+    pub backup: Option<PathBuf>,
+    /// Content hash once the operation was done (`None` if it left no
+    /// file), to notice later edits.
+    pub hash: Option<String>,
+}
+
+/// One journaled operation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperationRecord {
+    pub id: String,
+    pub at: DateTime<Utc>,
+    /// What ran, e.g. `capture 'inbox'`.
+    pub label: String,
+    pub files: Vec<FileChange>,
+}
+
+/// Collects the files an operation is about to write.
+#[derive(Debug)]
+pub struct Recorder {
+    vault_root: PathBuf,
+    id: String,
+    files: Vec<(PathBuf, Option<PathBuf>)>,
+}
+
+impl Recorder {
+    pub fn new(vault_root: &Path) -> Self {
+        Self {
+            vault_root: vault_root.to_path_buf(),
+            id: Utc::now().format("%Y%m%d-%H%M%S%.6f").to_string(),
+            files: Vec::new(),
+        }
+    }
+
+    /// Identifier the operation is journaled under.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Keep what `path` holds now, before the operation writes it. Only the
+    /// first call per file counts, so undo restores the state before the
+    /// whole operation.
+    pub fn before_write(&mut self, path: &Path) -> Result<(), UndoError> {
+        let rel = path.strip_prefix(&self.vault_root).unwrap_or(path).to_path_buf();
+        if self.files.iter().any(|(p, _)| *p == rel) {
+            return Ok(());
+        }
+        let full = self.vault_root.join(&rel);
+        let backup = if full.is_file() {
+            let resolver = PathResolver::new(&self.vault_root);
+            let backup = resolver.undo_dir(&self.id).join(self.files.len().to_string());
+            let io_err = |source| UndoError::Io { path: full.clone(), source };
+            if let Some(parent) = backup.parent() {
+                fs::create_dir_all(parent).map_err(io_err)?;
+            }
+            fs::copy(&full, &backup).map_err(io_err)?;
+            Some(backup.strip_prefix(&self.vault_root).unwrap_or(&backup).to_path_buf())
+        } else {
+            None
+        };
+        self.files.push((rel, backup));
+        Ok(())
+    }
+
+    /// Journal the operation once it is done (also when it failed part
+    /// way, so what it did write can be undone). Returns `None` if it was
+    /// not about to write anything.
+    pub fn commit(self, label: &str) -> Result<Option<OperationRecord>, UndoError> {
+        if self.files.is_empty() {
+            return Ok(None);
+        }
+        let files = self
+            .files
+            .into_iter()
+            .map(|(path, backup)| {
+                let hash = content_hash(&self.vault_root.join(&path)).ok();
+                FileChange { path, backup, hash }
+            })
+            .collect();
+        let record = OperationRecord {
+            id: self.id,
+            at: Utc::now(),
+            label: label.to_string(),
+            files,
+        };
+
+        let resolver = PathResolver::new(&self.vault_root);
+        let mut records = read_journal(&resolver.undo_journal())?;
+        records.push(record.clone());
+        let dropped = records.len().saturating_sub(KEEP_OPERATIONS);
+        for old in records.drain(..dropped) {
+            let _ = fs::remove_dir_all(resolver.undo_dir(&old.id));
+        }
+        write_journal(&resolver.undo_journal(), &records)?;
+        Ok(Some(record))
+    }
+}
+
+/// The operation [`undo_last`] would undo, if any.
+pub fn last_operation(vault_root: &Path) -> Result<Option<OperationRecord>, UndoError> {
+    let journal = PathResolver::new(vault_root).undo_journal();
+    Ok(read_journal(&journal)?.pop())
+}
+
+/// Put back the files of the most recent operation and drop it from the
+/// journal.
+///
+/// Nothing is touched if any of its files was edited since.
+pub fn undo_last(vault_root: &Path) -> Result<OperationRecord, UndoError> {
+    let resolver = PathResolver::new(vault_root);
+    let journal = resolver.undo_journal();
+    let mut records = read_journal(&journal)?;
+    let record = records.pop().ok_or(UndoError::NothingToUndo)?;
+
+    for file in &record.files {
+        if content_hash(&vault_root.join(&file.path)).ok() != file.hash {
+            return Err(UndoError::Changed(file.path.clone()));
+        }
+    }
+    for file in record.files.iter().rev() {
+        let full = vault_root.join(&file.path);
+        let io_err = |source| UndoError::Io { path: full.clone(), source };
+        match &file.backup {
+            Some(backup) => {
+                fs::copy(vault_root.join(backup), &full).map_err(io_err)?;
+            }
+            None if full.exists() => fs::remove_file(&full).map_err(io_err)?,
+            None => {}
+        }
+    }
+
+    write_journal(&journal, &records)?;
+    let _ = fs::remove_dir_all(resolver.undo_dir(&record.id));
+    Ok(record)
+}
+
+/// Read every operation in the journal (empty if there is none).
+pub fn read_journal(path: &Path) -> Result<Vec<OperationRecord>, UndoError> {
+    let content = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(source) => return Err(UndoError::Io { path: path.to_path_buf(), source }),
+    };
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| UndoError::Journal { line: i + 1, message: e.to_string() })
+        })
+        .collect()
+}
+
+fn write_journal(path: &Path, records: &[OperationRecord]) -> Result<(), UndoError> {
+    let io_err = |source| UndoError::Io { path: path.to_path_buf(), source };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(io_err)?;
+    }
+    let mut file = fs::File::create(path).map_err(io_err)?;
+    for record in records {
+        let line = serde_json::to_string(record).expect("journal entry serializes");
+        writeln!(file, "{line}").map_err(io_err)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn undoes_the_last_operation() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::write(root.join("inbox.md"), "# Inbox\n").unwrap();
+
+        let mut recorder = Recorder::new(root);
+        recorder.before_write(&root.join("inbox.md")).unwrap();
+        recorder.before_write(Path::new("new.md")).unwrap();
+        fs::write(root.join("inbox.md"), "# Inbox\n- idea\n").unwrap();
+        fs::write(root.join("new.md"), "# New\n").unwrap();
+        // A second write to the same file keeps the first backup
+        recorder.before_write(&root.join("inbox.md")).unwrap();
+        fs::write(root.join("inbox.md"), "# Inbox\n- idea\n- more\n").unwrap();
+        let id = recorder.id().to_string();
+        let record = recorder.commit("capture 'inbox'").unwrap().unwrap();
+        assert_eq!(record.files.len(), 2);
+        assert_eq!(last_operation(root).unwrap().map(|r| r.id), Some(id));
+
+        let undone = undo_last(root).unwrap();
+        assert_eq!(undone.label, "capture 'inbox'");
+        assert_eq!(fs::read_to_string(root.join("inbox.md")).unwrap(), "# Inbox\n");
+        assert!(!root.join("new.md").exists());
+        assert!(!PathResolver::new(root).undo_dir(&undone.id).exists());
+        assert!(matches!(undo_last(root), Err(UndoError::NothingToUndo)));
+    }
+
+    #[test]
+    fn leaves_files_edited_since() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+
+        // Nothing written, nothing journaled
+        assert!(Recorder::new(root).commit("noop").unwrap().is_none());
+
+        let mut recorder = Recorder::new(root);
+        recorder.before_write(Path::new("note.md")).unwrap();
+        fs::write(root.join("note.md"), "generated\n").unwrap();
+        recorder.commit("template 'note'").unwrap();
+
+        fs::write(root.join("note.md"), "generated\nand edited\n").unwrap();
+        assert!(
+            matches!(undo_last(root), Err(UndoError::Changed(p)) if p == Path::new("note.md"))
+        );
+        assert!(root.join("note.md").exists());
+        assert!(last_operation(root).unwrap().is_some());
+    }
+}
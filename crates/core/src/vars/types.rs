@@ -75,6 +75,15 @@ impl VarSpec {
             VarSpec::Full(m) => m.description.as_deref(),
         }
     }
+
+    /// Get the allowed values, if the variable is a selection.
+    #[must_use]
+    pub fn options(&self) -> Option<&[String]> {
+        match self {
+            VarSpec::Simple(_) => None,
+            VarSpec::Full(m) => m.options.as_deref(),
+        }
+    }
 }
 
 /// Full metadata for a variable specification.
@@ -92,8 +101,10 @@ pub struct VarMetadata {
 
     /// Default value (static string or computed expression like "{{today}}").
     pub default: Option<String>,
+
+    /// Allowed values, prompted for as a selection.
+    pub options: Option<Vec<String>>,
     // Future extensions:
-    // pub options: Option<Vec<String>>,  // Selection/dropdown prompt
     // pub validate: Option<String>,       // Regex validation pattern
     // pub var_type: Option<VarType>,      // Type hints (string, date, number)
 }
@@ -240,6 +251,22 @@ description: "The meeting date"
         assert_eq!(spec.prompt(), "Enter date");
         assert_eq!(spec.default(), Some("{{today}}"));
         assert_eq!(spec.description(), Some("The meeting date"));
+        assert!(spec.options().is_none());
+    }
+
+    #[test]
+    fn test_varspec_deserialize_options() {
+        let yaml = r#"
+prompt: "Priority"
+options: [low, medium, high]
+default: medium
+"#;
+        let spec: VarSpec = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            spec.options(),
+            Some(&["low".to_string(), "medium".to_string(), "high".to_string()][..])
+        );
+        assert_eq!(spec.default(), Some("medium"));
     }
 
     #[test]
@@ -1,4 +1,4 @@
-//! Note content extraction: links, title, type, frontmatter.
+//! Note content extraction: links, title, type, frontmatter, checkboxes, anchors.
 
 use std::path::Path;
 use std::sync::LazyLock;
@@ -6,7 +6,7 @@ use std::sync::LazyLock;
 use regex::Regex;
 
 use crate::frontmatter::{self, Frontmatter};
-use crate::index::types::{LinkType, NoteType};
+use crate::index::types::{AnchorKind, LinkType, NoteHeading, NoteType, TaskStatus};
 
 /// Extracted information from a note file.
 #[derive(Debug, Clone)]
@@ -19,23 +19,62 @@ pub struct ExtractedNote {
     pub frontmatter_json: Option<String>,
     /// All links found in the document.
     pub links: Vec<ExtractedLink>,
+    /// Markdown body with frontmatter stripped (for full-text indexing).
+    pub body: String,
+    /// Checkbox list items (`- [ ] ...`) found in the body.
+    pub checkboxes: Vec<ExtractedCheckbox>,
+    /// Headings and block IDs that links can point to.
+    pub anchors: Vec<ExtractedAnchor>,
+    /// Words of prose in the body, outside code blocks.
+    pub word_count: u32,
+    /// Heading outline, in document order.
+    pub headings: Vec<NoteHeading>,
+}
+
+/// A heading or block ID extracted from a note.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedAnchor {
+    /// Heading or block.
+    pub kind: AnchorKind,
+    /// Heading text, or block ID without the `^`.
+    pub name: String,
+    /// Line number in the body (1-based).
+    pub line_number: u32,
+}
+
+/// A checkbox list item extracted from a note.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedCheckbox {
+    /// Item text after the checkbox.
+    pub text: String,
+    /// Status derived from the box marker (` `, `x`, `/`, `-`).
+    pub status: TaskStatus,
+    /// Line number in the body where the item appears (1-based).
+    pub line_number: u32,
+    /// Text of the nearest heading above the item, if any.
+    pub heading: Option<String>,
 }
 
 /// A link extracted from a note.
 #[derive(Debug, Clone)]
 pub struct ExtractedLink {
-    /// Target path/name (raw, as written in the link).
+    /// Target path/name as written, without the fragment (empty for `[[#Section]]`).
     pub target: String,
+    /// Fragment after the target: heading text, or `^id` for a block.
+    pub anchor: Option<String>,
     /// Display text (alias for wikilinks, text for markdown links).
     pub text: Option<String>,
     /// Type of link.
     pub link_type: LinkType,
     /// Line number where link appears (1-based).
     pub line_number: u32,
-    /// Context text (surrounding content).
+    /// The sentence, list item or frontmatter field containing the link.
     pub context: Option<String>,
 }
 
+/// Longest context kept for a link, in characters.
+const MAX_CONTEXT_CHARS: usize = 160;
+
 // Regex patterns for link extraction
 static WIKILINK_RE: LazyLock<Regex> = LazyLock::new(|| {
     // Matches [[target]] or [[target|alias]]
@@ -46,7 +85,17 @@ static WIKILINK_RE: LazyLock<Regex> = LazyLock::new(|| {
 static MARKDOWN_LINK_RE: LazyLock<Regex> = LazyLock::new(|| {
     // Matches [text](url) - captures .md files and relative paths
     // Excludes http:// and https:// URLs
-    Regex::new(r"\[([^\]]+)\]\(([^)]+)\)").unwrap()
+    Regex::new(r"\[([^\]]*)\]\(([^)]+)\)").unwrap()
+});
+
+static BLOCK_ID_RE: LazyLock<Regex> = LazyLock::new(|| {
+    // Matches a trailing " ^block-id" (or a line that is only "^block-id")
+    Regex::new(r"(?:^|\s)\^([A-Za-z0-9-]+)\s*$").unwrap()
+});
+
+static CHECKBOX_RE: LazyLock<Regex> = LazyLock::new(|| {
+    // Matches "- [ ] text", "* [x] text", "1. [/] text" (any indentation)
+    Regex::new(r"^\s*(?:[-*+]|\d+[.)])\s+\[([ xX/-])\]\s+(.+?)\s*$").unwrap()
 });
 
 /// Extract note information from file content.
@@ -84,7 +133,22 @@ pub fn extract_note(content: &str, file_path: &Path) -> ExtractedNote {
     let fm_links = extract_frontmatter_links(&parsed.frontmatter);
     links.extend(fm_links);
 
-    ExtractedNote { title, note_type, frontmatter_json, links }
+    // Extract checkbox items and link anchors from body
+    let checkboxes = extract_checkboxes(&parsed.body);
+    let anchors = extract_anchors(&parsed.body);
+    let (word_count, headings) = extract_outline(&parsed.body);
+
+    ExtractedNote {
+        title,
+        note_type,
+        frontmatter_json,
+        links,
+        body: parsed.body,
+        checkboxes,
+        anchors,
+        word_count,
+        headings,
+    }
 }
 
 fn extract_title(fm: &Option<Frontmatter>, body: &str, file_path: &Path) -> String {
@@ -118,39 +182,60 @@ fn extract_links(body: &str) -> Vec<ExtractedLink> {
 
         // Extract wikilinks
         for cap in WIKILINK_RE.captures_iter(line) {
-            let target = cap.get(1).map(|m| m.as_str()).unwrap_or("");
+            let m = cap.get(0).unwrap();
+            let raw = cap.get(1).map(|m| m.as_str()).unwrap_or("");
             let alias = cap.get(2).map(|m| m.as_str().to_string());
+            let (target, anchor) = split_link_target(raw);
+            let link_type = if is_embed(line, m.start()) {
+                LinkType::Embed
+            } else {
+                LinkType::Wikilink
+            };
 
             links.push(ExtractedLink {
                 target: target.to_string(),
+                anchor: anchor.map(String::from),
                 text: alias,
-                link_type: LinkType::Wikilink,
+                link_type,
                 line_number,
-                context: Some(truncate_context(line, 100)),
+                context: link_context(line, m.start(), m.end()),
             });
         }
 
         // Extract markdown links to local files
         for cap in MARKDOWN_LINK_RE.captures_iter(line) {
+            let m = cap.get(0).unwrap();
             let text = cap.get(1).map(|m| m.as_str()).unwrap_or("");
-            let url = cap.get(2).map(|m| m.as_str()).unwrap_or("");
+            let raw_url = cap.get(2).map(|m| m.as_str()).unwrap_or("");
 
             // Skip external URLs
-            if url.starts_with("http://") || url.starts_with("https://") {
+            if raw_url.starts_with("http://") || raw_url.starts_with("https://") {
                 continue;
             }
 
+            let (url, anchor) = match raw_url.split_once('#') {
+                Some((url, fragment)) => (url, Some(fragment)),
+                None => (raw_url, None),
+            };
+
             // Skip non-markdown links (images, etc.) unless they're relative paths
             if !url.ends_with(".md") && !is_likely_note_reference(url) {
                 continue;
             }
 
+            let link_type = if is_embed(line, m.start()) {
+                LinkType::Embed
+            } else {
+                LinkType::Markdown
+            };
+
             links.push(ExtractedLink {
                 target: url.to_string(),
+                anchor: anchor.filter(|a| !a.is_empty()).map(String::from),
                 text: Some(text.to_string()),
-                link_type: LinkType::Markdown,
+                link_type,
                 line_number,
-                context: Some(truncate_context(line, 100)),
+                context: link_context(line, m.start(), m.end()),
             });
         }
     }
@@ -158,6 +243,139 @@ fn extract_links(body: &str) -> Vec<ExtractedLink> {
     links
 }
 
+/// Whether the link starting at byte `start` of `line` is an embed (`![[...]]`
+/// or `![...](...)`).
+pub fn is_embed(line: &str, start: usize) -> bool {
+    start > 0 && line.as_bytes()[start - 1] == b'!'
+}
+
+/// Split a wikilink target into the note part and its fragment.
+///
+/// Handles `note#Heading`, `note#^block-id`, the shorthand `note^block-id`,
+/// and fragment-only links like `#Heading` (empty note part). The fragment
+/// is returned without `#`; block fragments keep their leading `^`.
+pub fn split_link_target(target: &str) -> (&str, Option<&str>) {
+    if let Some((note, fragment)) = target.split_once('#') {
+        let fragment = fragment.trim();
+        return (note.trim(), (!fragment.is_empty()).then_some(fragment));
+    }
+    if let Some(pos) = target.rfind('^') {
+        let id = &target[pos + 1..];
+        if !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return (target[..pos].trim(), Some(&target[pos..]));
+        }
+    }
+    (target.trim(), None)
+}
+
+/// Body lines outside fenced code blocks, with 1-based line numbers.
+fn prose_lines(body: &str) -> impl Iterator<Item = (u32, &str)> {
+    let mut fence: Option<&str> = None;
+    body.lines().enumerate().filter_map(move |(i, line)| {
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            return None;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            return None;
+        }
+        Some(((i + 1) as u32, line))
+    })
+}
+
+/// Level and text of an ATX heading line (`## Title`), or `None` for other
+/// lines.
+///
+/// `#tag` is not a heading; a bare `#` is a heading with empty text. A closing
+/// sequence (`## Title ##`) is not part of the text.
+fn parse_heading(line: &str) -> Option<(u8, &str)> {
+    let trimmed = line.trim_start();
+    let rest = trimmed.strip_prefix('#')?.trim_start_matches('#');
+    let level = (trimmed.len() - rest.len()).min(u8::MAX as usize) as u8;
+    (rest.is_empty() || rest.starts_with(' '))
+        .then(|| (level, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+fn extract_checkboxes(body: &str) -> Vec<ExtractedCheckbox> {
+    let mut items = Vec::new();
+    let mut heading: Option<String> = None;
+
+    for (line_number, line) in prose_lines(body) {
+        if let Some((_, text)) = parse_heading(line) {
+            heading = (!text.is_empty()).then(|| text.to_string());
+            continue;
+        }
+
+        if let Some(cap) = CHECKBOX_RE.captures(line) {
+            let status = match &cap[1] {
+                "x" | "X" => TaskStatus::Done,
+                "/" => TaskStatus::InProgress,
+                "-" => TaskStatus::Cancelled,
+                _ => TaskStatus::Open,
+            };
+            items.push(ExtractedCheckbox {
+                text: cap[2].to_string(),
+                status,
+                line_number,
+                heading: heading.clone(),
+            });
+        }
+    }
+
+    items
+}
+
+/// Prose word count and heading outline. A word is any whitespace-separated
+/// token with a letter or digit in it, so list markers and `#`s do not count.
+fn extract_outline(body: &str) -> (u32, Vec<NoteHeading>) {
+    let mut words = 0;
+    let mut headings = Vec::new();
+
+    for (_, line) in prose_lines(body) {
+        if let Some((level, text)) = parse_heading(line)
+            && !text.is_empty()
+        {
+            headings.push(NoteHeading { level, title: text.to_string() });
+        }
+        words += line
+            .split_whitespace()
+            .filter(|w| w.chars().any(char::is_alphanumeric))
+            .count() as u32;
+    }
+
+    (words, headings)
+}
+
+fn extract_anchors(body: &str) -> Vec<ExtractedAnchor> {
+    let mut anchors = Vec::new();
+
+    for (line_number, line) in prose_lines(body) {
+        if let Some((_, text)) = parse_heading(line)
+            && !text.is_empty()
+        {
+            anchors.push(ExtractedAnchor {
+                kind: AnchorKind::Heading,
+                name: text.to_string(),
+                line_number,
+            });
+        }
+
+        if let Some(cap) = BLOCK_ID_RE.captures(line) {
+            anchors.push(ExtractedAnchor {
+                kind: AnchorKind::Block,
+                name: cap[1].to_string(),
+                line_number,
+            });
+        }
+    }
+
+    anchors
+}
+
 fn is_likely_note_reference(url: &str) -> bool {
     // Consider it a note reference if it:
     // - Doesn't have a file extension (might be a note name)
@@ -198,9 +416,10 @@ fn extract_frontmatter_links(fm: &Option<Frontmatter>) -> Vec<ExtractedLink> {
                 links.push(ExtractedLink {
                     target: s.to_string(),
                     text: Some(format!("{}: {}", field, s)),
+                    anchor: None,
                     link_type: LinkType::Frontmatter,
                     line_number: 0, // Frontmatter doesn't have meaningful line numbers
-                    context: None,
+                    context: Some(format!("{}: {}", field, s)),
                 });
             }
             // Handle array of strings
@@ -210,9 +429,10 @@ fn extract_frontmatter_links(fm: &Option<Frontmatter>) -> Vec<ExtractedLink> {
                         links.push(ExtractedLink {
                             target: s.to_string(),
                             text: Some(format!("{}: {}", field, s)),
+                            anchor: None,
                             link_type: LinkType::Frontmatter,
                             line_number: 0,
-                            context: None,
+                            context: Some(format!("{}: {}", field, s)),
                         });
                     }
                 }
@@ -223,18 +443,109 @@ fn extract_frontmatter_links(fm: &Option<Frontmatter>) -> Vec<ExtractedLink> {
     links
 }
 
-fn truncate_context(line: &str, max_len: usize) -> String {
-    if line.len() <= max_len {
-        line.to_string()
+/// The text around a link at bytes `start..end` of `line`: the whole item
+/// for list items, otherwise the sentence containing it. Markers for lists,
+/// checkboxes, quotes and headings are dropped, and long contexts are cut
+/// to a window around the link.
+fn link_context(line: &str, start: usize, end: usize) -> Option<String> {
+    let item_start = line.len() - strip_line_markers(line).len();
+    let (from, to) = if is_list_item(line) {
+        (item_start, line.len())
     } else {
-        // Find the last char boundary at or before max_len to avoid
-        // panicking on multi-byte characters (e.g. em-dash '—').
-        let mut end = max_len;
-        while end > 0 && !line.is_char_boundary(end) {
-            end -= 1;
+        sentence_bounds(line, item_start, start, end)
+    };
+    let text = line[from..to].trim();
+    if text.is_empty() {
+        return None;
+    }
+    let offset = from + (line[from..to].len() - line[from..to].trim_start().len());
+    Some(window(text, start.saturating_sub(offset), end.saturating_sub(offset)))
+}
+
+/// `line` without leading list, checkbox, quote or heading markers.
+fn strip_line_markers(line: &str) -> &str {
+    let mut rest = line.trim_start();
+    loop {
+        let before = rest;
+        rest = rest.strip_prefix('>').unwrap_or(rest).trim_start();
+        for marker in ["- ", "* ", "+ "] {
+            rest = rest.strip_prefix(marker).unwrap_or(rest);
+        }
+        let digits =
+            rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits > 0
+            && let Some(after) = rest[digits..]
+                .strip_prefix(". ")
+                .or_else(|| rest[digits..].strip_prefix(") "))
+        {
+            rest = after;
+        }
+        // `[ ] `, `[x] `: a checkbox
+        if let Some(after) = rest
+            .strip_prefix('[')
+            .and_then(|r| r.get(1..))
+            .and_then(|r| r.strip_prefix("] "))
+        {
+            rest = after;
         }
-        format!("{}...", &line[..end])
+        if parse_heading(rest).is_some() {
+            rest = rest.trim_start_matches('#');
+        }
+        rest = rest.trim_start();
+        if rest.len() == before.len() {
+            return rest;
+        }
+    }
+}
+
+/// Whether `line` is a bullet or numbered list item.
+fn is_list_item(line: &str) -> bool {
+    let trimmed = line.trim_start().trim_start_matches('>').trim_start();
+    if ["- ", "* ", "+ "].iter().any(|m| trimmed.starts_with(m)) {
+        return true;
     }
+    let rest = trimmed.trim_start_matches(|c: char| c.is_ascii_digit());
+    rest.len() < trimmed.len() && (rest.starts_with(". ") || rest.starts_with(") "))
+}
+
+/// Byte range of the sentence in `line[from..]` containing `start..end`.
+///
+/// A sentence ends at `.`, `!` or `?` followed by whitespace.
+fn sentence_bounds(line: &str, from: usize, start: usize, end: usize) -> (usize, usize) {
+    let is_end = |i: usize| {
+        matches!(line.as_bytes()[i], b'.' | b'!' | b'?')
+            && line.as_bytes().get(i + 1).is_none_or(|b| b.is_ascii_whitespace())
+    };
+    let begin =
+        (from..start.max(from)).rev().find(|&i| is_end(i)).map_or(from, |i| i + 1);
+    let finish = (end..line.len()).find(|&i| is_end(i)).map_or(line.len(), |i| i + 1);
+    (begin, finish)
+}
+
+/// `text` cut to [`MAX_CONTEXT_CHARS`] around the link at bytes
+/// `start..end`, with `...` where it was cut.
+fn window(text: &str, start: usize, end: usize) -> String {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    if chars.len() <= MAX_CONTEXT_CHARS {
+        return text.to_string();
+    }
+    let link_start = chars.iter().position(|(i, _)| *i >= start).unwrap_or(0);
+    let link_end = chars.iter().position(|(i, _)| *i >= end).unwrap_or(chars.len());
+    let spare = MAX_CONTEXT_CHARS.saturating_sub(link_end - link_start);
+    let mut first = link_start.saturating_sub(spare / 2);
+    let last = (first + MAX_CONTEXT_CHARS).min(chars.len());
+    first = last.saturating_sub(MAX_CONTEXT_CHARS);
+
+    let byte = |n: usize| chars.get(n).map_or(text.len(), |(i, _)| *i);
+    let mut out = String::new();
+    if first > 0 {
+        out.push_str("...");
+    }
+    out.push_str(text[byte(first)..byte(last)].trim());
+    if last < chars.len() {
+        out.push_str("...");
+    }
+    out
 }
 
 #[cfg(test)]
@@ -280,6 +591,26 @@ And [image](./pic.png) should be skipped too.
         assert_eq!(note.links[0].link_type, LinkType::Markdown);
     }
 
+    #[test]
+    fn test_extract_embeds() {
+        let content = "Summary: ![[meeting#Decisions]] and ![[diagram.png|300]].\n\
+                       Inline ![the spec](./spec.md), but [[plain]] stays a link.\n";
+        let note = extract_note(content, Path::new("test.md"));
+
+        let links: Vec<_> =
+            note.links.iter().map(|l| (l.target.as_str(), l.link_type)).collect();
+        assert_eq!(
+            links,
+            vec![
+                ("meeting", LinkType::Embed),
+                ("diagram.png", LinkType::Embed),
+                ("plain", LinkType::Wikilink),
+                ("./spec.md", LinkType::Embed),
+            ]
+        );
+        assert_eq!(note.links[0].anchor.as_deref(), Some("Decisions"));
+    }
+
     #[test]
     fn test_extract_frontmatter_links() {
         let content = r#"---
@@ -303,6 +634,43 @@ related:
         assert!(fm_links.iter().any(|l| l.target == "note-b"));
     }
 
+    #[test]
+    fn test_link_context_is_the_sentence_or_item() {
+        let content = "---\nproject: garden\n---\n\
+            # Notes on [[soil]]\n\
+            Compost helps. Mix it into [[beds.md]] before spring! Then wait.\n\
+            - [ ] Order seeds from [[supplier]]. Soon.\n\
+            > 2. Quoted [text](peat.md) item\n";
+        let note = extract_note(content, Path::new("garden.md"));
+        let context = |target: &str| {
+            note.links
+                .iter()
+                .find(|l| l.target == target)
+                .unwrap()
+                .context
+                .clone()
+                .unwrap()
+        };
+
+        assert_eq!(context("soil"), "Notes on [[soil]]");
+        assert_eq!(context("beds.md"), "Mix it into [[beds.md]] before spring!");
+        assert_eq!(context("supplier"), "Order seeds from [[supplier]]. Soon.");
+        assert_eq!(context("peat.md"), "Quoted [text](peat.md) item");
+        assert_eq!(context("garden"), "project: garden");
+    }
+
+    #[test]
+    fn test_long_context_is_cut_around_the_link() {
+        let filler = "word ".repeat(60);
+        let line = format!("{filler}the [[target]] is here {filler}");
+        let note = extract_note(&line, Path::new("long.md"));
+        let context = note.links[0].context.as_deref().unwrap();
+
+        assert!(context.starts_with("...") && context.ends_with("..."), "{context}");
+        assert!(context.contains("the [[target]] is here"));
+        assert!(context.chars().count() <= MAX_CONTEXT_CHARS + 6);
+    }
+
     #[test]
     fn test_extract_title_from_frontmatter() {
         let content = r#"---
@@ -366,6 +734,151 @@ Line 4 with [[link2]]
         let note = extract_note(content, Path::new("test.md"));
 
         assert_eq!(note.links.len(), 1);
-        assert_eq!(note.links[0].target, "note#section");
+        assert_eq!(note.links[0].target, "note");
+        assert_eq!(note.links[0].anchor.as_deref(), Some("section"));
+    }
+
+    #[test]
+    fn test_link_fragments() {
+        let content = "[[note#^abc-1]] [[note^def]] [[#Local|here]] [[a^b c]]\n\
+                       [md](./other.md#next-steps) [bare](other#intro)\n";
+        let note = extract_note(content, Path::new("test.md"));
+
+        let parts: Vec<(&str, Option<&str>)> =
+            note.links.iter().map(|l| (l.target.as_str(), l.anchor.as_deref())).collect();
+        assert_eq!(
+            parts,
+            vec![
+                ("note", Some("^abc-1")),
+                ("note", Some("^def")),
+                ("", Some("Local")),
+                ("a^b c", None),
+                ("./other.md", Some("next-steps")),
+                ("other", Some("intro")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_anchors() {
+        let content = r#"---
+title: Anchors
+---
+# Title
+
+A paragraph. ^intro-para
+
+## Next Steps ##
+#tag is not a heading
+- item ^item-1
+
+```
+## not a heading ^not-a-block
+```
+"#;
+        let note = extract_note(content, Path::new("anchors.md"));
+
+        let anchors: Vec<(AnchorKind, &str, u32)> = note
+            .anchors
+            .iter()
+            .map(|a| (a.kind, a.name.as_str(), a.line_number))
+            .collect();
+        assert_eq!(
+            anchors,
+            vec![
+                (AnchorKind::Heading, "Title", 1),
+                (AnchorKind::Block, "intro-para", 3),
+                (AnchorKind::Heading, "Next Steps", 5),
+                (AnchorKind::Block, "item-1", 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_outline() {
+        let content = r#"---
+title: Outline
+---
+# Plan
+
+Ship the first draft - then review it.
+
+## Steps ##
+- [ ] write tests
+
+```
+## not counted either
+```
+"#;
+        let note = extract_note(content, Path::new("plan.md"));
+
+        assert_eq!(note.word_count, 11);
+        assert_eq!(
+            note.headings,
+            vec![
+                NoteHeading { level: 1, title: "Plan".into() },
+                NoteHeading { level: 2, title: "Steps".into() },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_checkboxes() {
+        let content = r#"---
+type: daily
+---
+# 2025-01-06
+
+- [ ] Call the bank
+- [x] Review PR
+Some prose with [ ] brackets.
+
+## Work
+  * [/] Draft proposal
+1. [-] Old idea
+- [?] Unknown marker is not a checkbox
+"#;
+        let note = extract_note(content, Path::new("daily.md"));
+
+        assert_eq!(
+            note.checkboxes,
+            vec![
+                ExtractedCheckbox {
+                    text: "Call the bank".into(),
+                    status: TaskStatus::Open,
+                    line_number: 3,
+                    heading: Some("2025-01-06".into()),
+                },
+                ExtractedCheckbox {
+                    text: "Review PR".into(),
+                    status: TaskStatus::Done,
+                    line_number: 4,
+                    heading: Some("2025-01-06".into()),
+                },
+                ExtractedCheckbox {
+                    text: "Draft proposal".into(),
+                    status: TaskStatus::InProgress,
+                    line_number: 8,
+                    heading: Some("Work".into()),
+                },
+                ExtractedCheckbox {
+                    text: "Old idea".into(),
+                    status: TaskStatus::Cancelled,
+                    line_number: 9,
+                    heading: Some("Work".into()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_checkboxes_in_code_blocks_are_ignored() {
+        let content = "```md\n- [ ] not a task\n# not a heading\n```\n- [ ] real task\n";
+        let note = extract_note(content, Path::new("note.md"));
+
+        assert_eq!(note.checkboxes.len(), 1);
+        assert_eq!(note.checkboxes[0].text, "real task");
+        assert_eq!(note.checkboxes[0].line_number, 5);
+        assert_eq!(note.checkboxes[0].heading, None);
     }
 }
@@ -0,0 +1,275 @@
+//! Gitignore-style exclusion patterns.
+//!
+//! Used for the vault's `.mdvignore` and for `--exclude` flags. The syntax
+//! follows `.gitignore`:
+//!
+//! - blank lines and lines starting with `#` are skipped
+//! - `*` matches within one path segment, `?` one character, `[a-z]` a class
+//! - `**` matches any number of segments (`**/drafts`, `archive/**`)
+//! - a pattern containing `/` other than at the end is anchored to the vault
+//!   root; otherwise it matches at any depth
+//! - a trailing `/` only matches directories
+//! - a leading `!` re-includes what an earlier pattern excluded
+//!
+//! The last matching pattern wins. As in git, a file inside an excluded
+//! directory cannot be re-included.
+
+use std::path::{Component, Path, PathBuf};
+
+use regex::Regex;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum IgnoreError {
+    #[error("failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("invalid exclude pattern '{pattern}': {message}")]
+    InvalidPattern { pattern: String, message: String },
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    regex: Regex,
+    negated: bool,
+    dir_only: bool,
+}
+
+/// A list of exclusion patterns.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreRules {
+    rules: Vec<Rule>,
+}
+
+impl IgnoreRules {
+    /// Parse the contents of an ignore file.
+    pub fn parse(source: &str) -> Result<Self, IgnoreError> {
+        let mut rules = Self::default();
+        for line in source.lines() {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            rules.add(line)?;
+        }
+        Ok(rules)
+    }
+
+    /// The vault's `.mdvignore`; empty when the file does not exist.
+    pub fn load(vault_root: &Path) -> Result<Self, IgnoreError> {
+        let path = crate::paths::PathResolver::new(vault_root).ignore_file();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let source = std::fs::read_to_string(&path)
+            .map_err(|e| IgnoreError::Io { path: path.clone(), source: e })?;
+        Self::parse(&source)
+    }
+
+    /// Add one pattern, e.g. from `--exclude`.
+    pub fn add(&mut self, pattern: &str) -> Result<(), IgnoreError> {
+        let invalid = |message: &str| IgnoreError::InvalidPattern {
+            pattern: pattern.to_string(),
+            message: message.to_string(),
+        };
+
+        let (negated, rest) = match pattern.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            // `\!` and `\#` start patterns with a literal `!` or `#`
+            None if pattern.starts_with("\\!") || pattern.starts_with("\\#") => {
+                (false, &pattern[1..])
+            }
+            None => (false, pattern),
+        };
+        let (dir_only, rest) = match rest.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, rest),
+        };
+        let anchored = rest.contains('/');
+        let rest = rest.strip_prefix('/').unwrap_or(rest);
+        if rest.is_empty() {
+            return Err(invalid("pattern is empty"));
+        }
+
+        let mut regex = String::from("^");
+        if !anchored {
+            regex.push_str("(?:.*/)?");
+        }
+        regex.push_str(&glob_to_regex(rest).map_err(invalid)?);
+        regex.push('$');
+
+        let regex = Regex::new(&regex).map_err(|e| invalid(&e.to_string()))?;
+        self.rules.push(Rule { regex, negated, dir_only });
+        Ok(())
+    }
+
+    /// Whether there are no patterns.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether a vault-relative path is excluded, either itself or through
+    /// one of its parent directories.
+    pub fn is_ignored(&self, relative: &Path, is_dir: bool) -> bool {
+        if self.rules.is_empty() {
+            return false;
+        }
+        let segments: Vec<String> = relative
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(s) => Some(s.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect();
+        for end in 1..segments.len() {
+            if self.matches(&segments[..end].join("/"), true) {
+                return true;
+            }
+        }
+        !segments.is_empty() && self.matches(&segments.join("/"), is_dir)
+    }
+
+    /// Whether the last rule matching `path` excludes it.
+    fn matches(&self, path: &str, is_dir: bool) -> bool {
+        self.rules
+            .iter()
+            .rev()
+            .find(|r| (is_dir || !r.dir_only) && r.regex.is_match(path))
+            .is_some_and(|r| !r.negated)
+    }
+}
+
+/// Translate a glob into a regex body (without anchors).
+fn glob_to_regex(glob: &str) -> Result<String, &'static str> {
+    let chars: Vec<char> = glob.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                let at_start = i == 0 || chars[i - 1] == '/';
+                let at_end = i + 2 == chars.len();
+                if at_start && chars.get(i + 2) == Some(&'/') {
+                    // `**/` : zero or more leading directories
+                    out.push_str("(?:.*/)?");
+                    i += 3;
+                    continue;
+                }
+                if at_start && at_end {
+                    // trailing `/**` : everything inside
+                    out.push_str(".*");
+                } else {
+                    out.push_str("[^/]*");
+                }
+                i += 2;
+                continue;
+            }
+            '*' => out.push_str("[^/]*"),
+            '?' => out.push_str("[^/]"),
+            '[' => {
+                let close = chars[i + 1..]
+                    .iter()
+                    .enumerate()
+                    .skip(1)
+                    .find(|(_, c)| **c == ']')
+                    .map(|(n, _)| i + 1 + n)
+                    .ok_or("unclosed '['")?;
+                out.push('[');
+                let mut class = &chars[i + 1..close];
+                if let Some(('!' | '^', rest)) = class.split_first() {
+                    out.push('^');
+                    class = rest;
+                }
+                for c in class {
+                    if matches!(c, '\\' | '[' | ']' | '&' | '~') {
+                        out.push('\\');
+                    }
+                    out.push(*c);
+                }
+                out.push(']');
+                i = close;
+            }
+            '\\' if i + 1 < chars.len() => {
+                i += 1;
+                out.push_str(&regex::escape(&chars[i].to_string()));
+            }
+            c => out.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(source: &str) -> IgnoreRules {
+        IgnoreRules::parse(source).unwrap()
+    }
+
+    fn ignored(rules: &IgnoreRules, path: &str) -> bool {
+        rules.is_ignored(Path::new(path), false)
+    }
+
+    #[test]
+    fn unanchored_patterns_match_at_any_depth() {
+        let r = rules("# generated\n\n*.tmp.md\ndrafts/\n");
+        assert!(ignored(&r, "a.tmp.md"));
+        assert!(ignored(&r, "deep/down/b.tmp.md"));
+        assert!(ignored(&r, "drafts/idea.md"));
+        assert!(ignored(&r, "projects/drafts/idea.md"));
+        assert!(!ignored(&r, "drafts.md"));
+        assert!(!ignored(&r, "notes/a.md"));
+    }
+
+    #[test]
+    fn slashes_anchor_to_the_root() {
+        let r = rules("/Archive\nJournal/2019/*.md\n");
+        assert!(ignored(&r, "Archive/old.md"));
+        assert!(!ignored(&r, "Projects/Archive/x.md"));
+        assert!(ignored(&r, "Journal/2019/2019-01-01.md"));
+        assert!(!ignored(&r, "Journal/2020/2020-01-01.md"));
+    }
+
+    #[test]
+    fn double_star_spans_directories() {
+        let r = rules("**/generated/**\nlogs/**/*.md\n");
+        assert!(ignored(&r, "generated/a.md"));
+        assert!(ignored(&r, "x/y/generated/z/a.md"));
+        assert!(ignored(&r, "logs/a.md"));
+        assert!(ignored(&r, "logs/2024/05/a.md"));
+        assert!(!ignored(&r, "blogs/a.md"));
+    }
+
+    #[test]
+    fn negation_and_classes() {
+        let r = rules("scratch-*.md\n!scratch-keep.md\n[0-9][0-9].md\n");
+        assert!(ignored(&r, "scratch-1.md"));
+        assert!(!ignored(&r, "scratch-keep.md"));
+        assert!(ignored(&r, "42.md"));
+        assert!(!ignored(&r, "4x.md"));
+    }
+
+    #[test]
+    fn files_under_ignored_directories_stay_ignored() {
+        let r = rules("build/\n!build/keep.md\n");
+        assert!(ignored(&r, "build/keep.md"));
+        assert!(r.is_ignored(Path::new("build"), true));
+        assert!(!r.is_ignored(Path::new("build"), false));
+    }
+
+    #[test]
+    fn invalid_patterns_are_errors() {
+        assert!(matches!(
+            IgnoreRules::parse("[abc"),
+            Err(IgnoreError::InvalidPattern { .. })
+        ));
+        assert!(IgnoreRules::default().add("/").is_err());
+        assert!(ignored(&rules("\\!important.md"), "!important.md"));
+    }
+}
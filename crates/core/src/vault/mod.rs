@@ -5,8 +5,15 @@
 
 pub mod extractor;
 pub mod hasher;
+pub mod ignore;
+pub mod usage;
 pub mod walker;
 
-pub use extractor::{ExtractedLink, ExtractedNote, extract_note};
+pub use extractor::{
+    ExtractedAnchor, ExtractedCheckbox, ExtractedLink, ExtractedNote, extract_note,
+    is_embed, split_link_target,
+};
 pub use hasher::{content_hash, content_hash_str};
+pub use ignore::{IgnoreError, IgnoreRules};
+pub use usage::{ByteSize, QuotaConfig, QuotaExceeded, VaultUsage};
 pub use walker::{VaultWalker, VaultWalkerError, WalkedFile};
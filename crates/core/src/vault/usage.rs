@@ -0,0 +1,248 @@
+//! Disk usage of a vault: largest files, per-folder totals, and quotas.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer};
+
+use super::walker::{VaultWalker, VaultWalkerError, WalkedFile};
+
+/// A size in bytes, written in config as `500MB`, `1.5 GB`, or a plain
+/// number of bytes. Units are binary (1 KB = 1024 bytes).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ByteSize(pub u64);
+
+impl FromStr for ByteSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split =
+            s.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(s.len());
+        let (number, unit) = s.split_at(split);
+        let number: f64 =
+            number.parse().map_err(|_| format!("invalid size '{s}' (e.g. 500MB)"))?;
+        let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+            "" | "B" => 1,
+            "K" | "KB" | "KIB" => 1 << 10,
+            "M" | "MB" | "MIB" => 1 << 20,
+            "G" | "GB" | "GIB" => 1 << 30,
+            "T" | "TB" | "TIB" => 1 << 40,
+            other => return Err(format!("unknown size unit '{other}' in '{s}'")),
+        };
+        Ok(Self((number * multiplier as f64) as u64))
+    }
+}
+
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+        if self.0 < 1024 {
+            return write!(f, "{} B", self.0);
+        }
+        let mut value = self.0 as f64;
+        let mut unit = "B";
+        for next in UNITS {
+            if value < 1024.0 {
+                break;
+            }
+            value /= 1024.0;
+            unit = next;
+        }
+        write!(f, "{value:.1} {unit}")
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteSize {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Bytes(u64),
+            Text(String),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Bytes(n) => Ok(Self(n)),
+            Raw::Text(s) => s.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// Size of every file in a vault, attachments included.
+#[derive(Debug, Default)]
+pub struct VaultUsage {
+    /// Files by size, largest first.
+    pub files: Vec<WalkedFile>,
+    pub total: u64,
+}
+
+impl VaultUsage {
+    /// Measure the vault, honouring the same exclusions as indexing.
+    pub fn scan(root: &Path, excluded: Vec<PathBuf>) -> Result<Self, VaultWalkerError> {
+        let mut files = VaultWalker::with_exclusions(root, excluded)?.walk_all()?;
+        files.sort_by(|a, b| {
+            b.size.cmp(&a.size).then(a.relative_path.cmp(&b.relative_path))
+        });
+        let total = files.iter().map(|f| f.size).sum();
+        Ok(Self { files, total })
+    }
+
+    /// The `n` largest files.
+    pub fn largest(&self, n: usize) -> &[WalkedFile] {
+        &self.files[..n.min(self.files.len())]
+    }
+
+    /// Number of markdown notes; the rest are attachments.
+    pub fn note_count(&self) -> usize {
+        self.files.iter().filter(|f| is_note(&f.relative_path)).count()
+    }
+
+    /// Total size of each top-level folder, largest first. Files at the vault
+    /// root are grouped under an empty path.
+    pub fn top_folders(&self) -> Vec<(PathBuf, u64)> {
+        let mut folders: BTreeMap<PathBuf, u64> = BTreeMap::new();
+        for file in &self.files {
+            let folder = match file.relative_path.components().next() {
+                Some(first) if file.relative_path.components().count() > 1 => {
+                    PathBuf::from(first.as_os_str())
+                }
+                _ => PathBuf::new(),
+            };
+            *folders.entry(folder).or_default() += file.size;
+        }
+        let mut folders: Vec<_> = folders.into_iter().collect();
+        folders.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        folders
+    }
+
+    /// Total size of everything under `folder` (relative to the vault root).
+    pub fn folder_size(&self, folder: &Path) -> u64 {
+        self.files
+            .iter()
+            .filter(|f| f.relative_path.starts_with(folder))
+            .map(|f| f.size)
+            .sum()
+    }
+
+    /// Quotas that are exceeded, the whole vault first.
+    pub fn check_quota(&self, quota: &QuotaConfig) -> Vec<QuotaExceeded> {
+        let vault = quota.vault.filter(|limit| self.total > limit.0).map(|limit| {
+            QuotaExceeded { folder: None, used: ByteSize(self.total), limit }
+        });
+        let folders = quota.folders.iter().filter_map(|(folder, limit)| {
+            let used = self.folder_size(Path::new(folder));
+            (used > limit.0).then(|| QuotaExceeded {
+                folder: Some(PathBuf::from(folder)),
+                used: ByteSize(used),
+                limit: *limit,
+            })
+        });
+        vault.into_iter().chain(folders).collect()
+    }
+}
+
+fn is_note(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "md")
+}
+
+/// Size limits for the vault and individual folders, from `[quota]`.
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct QuotaConfig {
+    /// Limit for the whole vault
+    #[serde(default)]
+    pub vault: Option<ByteSize>,
+    /// Limits for folders, keyed by path relative to the vault root
+    #[serde(default)]
+    pub folders: BTreeMap<String, ByteSize>,
+}
+
+impl QuotaConfig {
+    pub fn is_empty(&self) -> bool {
+        self.vault.is_none() && self.folders.is_empty()
+    }
+}
+
+/// A vault or folder that has grown past its quota.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotaExceeded {
+    /// `None` for the vault as a whole.
+    pub folder: Option<PathBuf>,
+    pub used: ByteSize,
+    pub limit: ByteSize,
+}
+
+impl fmt::Display for QuotaExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.folder {
+            Some(folder) => write!(f, "{}/ is {}", folder.display(), self.used)?,
+            None => write!(f, "vault is {}", self.used)?,
+        }
+        write!(f, ", over its {} quota", self.limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn parses_and_formats_sizes() {
+        assert_eq!("512".parse::<ByteSize>().unwrap(), ByteSize(512));
+        assert_eq!("2KB".parse::<ByteSize>().unwrap(), ByteSize(2048));
+        assert_eq!("1.5 GB".parse::<ByteSize>().unwrap(), ByteSize(3 << 29));
+        assert_eq!("10mib".parse::<ByteSize>().unwrap(), ByteSize(10 << 20));
+        assert!("lots".parse::<ByteSize>().is_err());
+        assert!("5 PB".parse::<ByteSize>().is_err());
+
+        assert_eq!(ByteSize(900).to_string(), "900 B");
+        assert_eq!(ByteSize(1536).to_string(), "1.5 KB");
+        assert_eq!(ByteSize(3 << 29).to_string(), "1.5 GB");
+    }
+
+    #[test]
+    fn measures_files_folders_and_quotas() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("Logs")).unwrap();
+        fs::create_dir_all(root.join("Projects/assets")).unwrap();
+        fs::create_dir_all(root.join(".mdvault")).unwrap();
+        fs::write(root.join("Logs/server.md"), vec![b'x'; 3000]).unwrap();
+        fs::write(root.join("Projects/plan.md"), vec![b'x'; 100]).unwrap();
+        fs::write(root.join("Projects/assets/diagram.png"), vec![0; 1000]).unwrap();
+        fs::write(root.join("inbox.md"), vec![b'x'; 10]).unwrap();
+        fs::write(root.join(".mdvault/index.db"), vec![0; 9000]).unwrap();
+
+        let usage = VaultUsage::scan(root, Vec::new()).unwrap();
+        assert_eq!(usage.total, 4110);
+        assert_eq!(usage.note_count(), 3);
+        let largest: Vec<_> = usage.largest(2).iter().map(|f| &f.relative_path).collect();
+        assert_eq!(
+            largest,
+            [Path::new("Logs/server.md"), Path::new("Projects/assets/diagram.png")]
+        );
+        assert_eq!(
+            usage.top_folders(),
+            vec![
+                (PathBuf::from("Logs"), 3000),
+                (PathBuf::from("Projects"), 1100),
+                (PathBuf::new(), 10),
+            ]
+        );
+
+        let quota = QuotaConfig {
+            vault: Some(ByteSize(4000)),
+            folders: BTreeMap::from([
+                ("Logs".to_string(), ByteSize(2048)),
+                ("Projects".to_string(), ByteSize(2048)),
+            ]),
+        };
+        let exceeded = usage.check_quota(&quota);
+        assert_eq!(exceeded.len(), 2);
+        assert_eq!(exceeded[0].to_string(), "vault is 4.0 KB, over its 3.9 KB quota");
+        assert_eq!(exceeded[1].to_string(), "Logs/ is 2.9 KB, over its 2.0 KB quota");
+    }
+}
@@ -5,6 +5,8 @@ use std::time::SystemTime;
 use thiserror::Error;
 use walkdir::WalkDir;
 
+use super::ignore::{IgnoreError, IgnoreRules};
+
 #[derive(Debug, Error)]
 pub enum VaultWalkerError {
     #[error("vault root does not exist: {0}")]
@@ -15,6 +17,9 @@ pub enum VaultWalkerError {
 
     #[error("failed to read file metadata {0}: {1}")]
     MetadataError(String, #[source] std::io::Error),
+
+    #[error(transparent)]
+    Ignore(#[from] IgnoreError),
 }
 
 /// Information about a discovered markdown file.
@@ -36,6 +41,8 @@ pub struct VaultWalker {
     root: PathBuf,
     /// Folders to exclude from walking (relative paths from vault root).
     excluded_folders: Vec<PathBuf>,
+    /// Patterns from `.mdvignore` and [`exclude`](Self::exclude).
+    ignore: IgnoreRules,
 }
 
 impl VaultWalker {
@@ -49,6 +56,8 @@ impl VaultWalker {
     /// Excluded folders can be specified as:
     /// - Relative paths from vault root (e.g., "automations/templates")
     /// - Absolute paths (will be converted to relative)
+    ///
+    /// The vault's `.mdvignore` patterns, if any, also apply.
     pub fn with_exclusions(
         root: &Path,
         excluded_folders: Vec<PathBuf>,
@@ -73,12 +82,34 @@ impl VaultWalker {
             })
             .collect();
 
-        Ok(Self { root, excluded_folders })
+        let ignore = IgnoreRules::load(&root)?;
+        Ok(Self { root, excluded_folders, ignore })
+    }
+
+    /// Also skip paths matching these gitignore-style patterns.
+    pub fn exclude(mut self, patterns: &[String]) -> Result<Self, VaultWalkerError> {
+        for pattern in patterns {
+            self.ignore.add(pattern)?;
+        }
+        Ok(self)
     }
 
     /// Walk the vault and return all markdown files.
     /// Excludes hidden directories, common non-vault directories, and configured exclusions.
     pub fn walk(&self) -> Result<Vec<WalkedFile>, VaultWalkerError> {
+        self.collect(is_markdown_file)
+    }
+
+    /// Walk the vault and return every file, attachments included, with the
+    /// same exclusions as [`walk`](Self::walk).
+    pub fn walk_all(&self) -> Result<Vec<WalkedFile>, VaultWalkerError> {
+        self.collect(|_| true)
+    }
+
+    fn collect(
+        &self,
+        include: impl Fn(&Path) -> bool,
+    ) -> Result<Vec<WalkedFile>, VaultWalkerError> {
         let mut files = Vec::new();
 
         for entry in WalkDir::new(&self.root)
@@ -91,7 +122,7 @@ impl VaultWalker {
             })?;
 
             let path = entry.path();
-            if !path.is_file() || !is_markdown_file(path) {
+            if !path.is_file() || !include(path) {
                 continue;
             }
 
@@ -133,19 +164,21 @@ impl VaultWalker {
             return true;
         }
 
+        let Ok(relative) = entry.path().strip_prefix(&self.root) else {
+            return false;
+        };
+
         // Check against configured exclusions
-        if !self.excluded_folders.is_empty()
-            && let Ok(relative) = entry.path().strip_prefix(&self.root)
-        {
-            for excluded in &self.excluded_folders {
-                // Check if the entry's path starts with the excluded folder
-                if relative.starts_with(excluded) {
-                    return true;
-                }
+        for excluded in &self.excluded_folders {
+            // Check if the entry's path starts with the excluded folder
+            if relative.starts_with(excluded) {
+                return true;
             }
         }
 
-        false
+        // Parents were checked on the way down, so only the entry itself
+        // needs matching
+        self.ignore.is_ignored(relative, entry.file_type().is_dir())
     }
 
     /// Get the vault root path.
@@ -224,6 +257,18 @@ mod tests {
         assert!(!paths.iter().any(|p| p.contains("readme.txt")));
     }
 
+    #[test]
+    fn test_walk_all_includes_attachments() {
+        let vault = create_test_vault();
+        let walker = VaultWalker::new(vault.path()).unwrap();
+        let files = walker.walk_all().unwrap();
+
+        let paths: Vec<_> = files.iter().map(|f| f.relative_path.clone()).collect();
+        assert_eq!(files.len(), 4);
+        assert!(paths.contains(&PathBuf::from("readme.txt")));
+        assert!(!paths.iter().any(|p| p.starts_with(".hidden")));
+    }
+
     #[test]
     fn test_walk_results_sorted() {
         let vault = create_test_vault();
@@ -308,4 +353,40 @@ mod tests {
         assert!(paths.contains(&"docs/readme.md".to_string()));
         assert!(!paths.iter().any(|p| p.contains("internal")));
     }
+
+    #[test]
+    fn test_walk_respects_mdvignore_and_patterns() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+
+        fs::write(
+            root.join(".mdvignore"),
+            "# generated
+*.gen.md
+Archive/
+",
+        )
+        .unwrap();
+        fs::write(root.join("note.md"), "# Note").unwrap();
+        fs::write(root.join("index.gen.md"), "# Generated").unwrap();
+        fs::create_dir_all(root.join("Archive/2020")).unwrap();
+        fs::write(root.join("Archive/2020/old.md"), "# Old").unwrap();
+        fs::create_dir_all(root.join("drafts")).unwrap();
+        fs::write(root.join("drafts/wip.md"), "# WIP").unwrap();
+
+        let files = VaultWalker::new(root).unwrap().walk().unwrap();
+        let paths: Vec<_> = files.iter().map(|f| f.relative_path.clone()).collect();
+        assert_eq!(paths, [PathBuf::from("drafts/wip.md"), PathBuf::from("note.md")]);
+
+        let files = VaultWalker::new(root)
+            .unwrap()
+            .exclude(&["drafts/".to_string()])
+            .unwrap()
+            .walk()
+            .unwrap();
+        assert_eq!(files.len(), 1);
+
+        fs::write(root.join(".mdvignore"), "[oops\n").unwrap();
+        assert!(matches!(VaultWalker::new(root), Err(VaultWalkerError::Ignore(_))));
+    }
 }
@@ -0,0 +1,243 @@
+//! Webhooks fired on vault events.
+//!
+//! Each `[[webhooks.hook]]` in the config maps an event to a URL. When the
+//! event happens, the payload is POSTed to every matching URL through
+//! `curl`, so HTTPS, proxies and certificates work the way they do in the
+//! user's shell. Failed deliveries are retried with exponential backoff;
+//! a target that still fails is reported, never fatal to the command that
+//! raised the event.
+//!
+//! A payload template is JSON with `{{var}}` placeholders. Values are
+//! escaped for use inside JSON strings, so a template reads
+//! `{"text": "Done: {{title}}"}`. Every event has `event`, `vault` and
+//! `timestamp`; the rest depend on the event.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use chrono::Utc;
+use regex::Regex;
+use thiserror::Error;
+
+use crate::config::types::{ResolvedConfig, WebhookEvent, WebhookTarget, WebhooksConfig};
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("webhooks are configured but security.allow_http is false")]
+    HttpNotAllowed,
+
+    #[error("payload template for {url} is not valid JSON: {message}")]
+    Payload { url: String, message: String },
+
+    #[error("failed to run curl: {0}")]
+    Spawn(#[source] std::io::Error),
+
+    #[error("delivery to {url} failed after {attempts} attempt(s): {message}")]
+    Delivery { url: String, attempts: u32, message: String },
+}
+
+/// Something that happened in the vault, with the values a payload can use.
+#[derive(Debug, Clone)]
+pub struct VaultEvent {
+    pub kind: WebhookEvent,
+    vars: BTreeMap<String, String>,
+}
+
+impl VaultEvent {
+    /// A new event in `vault` (the profile name), stamped with the current time.
+    pub fn new(kind: WebhookEvent, vault: &str) -> Self {
+        let mut vars = BTreeMap::new();
+        vars.insert("event".to_string(), kind.as_str().to_string());
+        vars.insert("vault".to_string(), vault.to_string());
+        vars.insert("timestamp".to_string(), Utc::now().to_rfc3339());
+        Self { kind, vars }
+    }
+
+    /// Add a payload variable.
+    pub fn with(mut self, key: &str, value: impl Into<String>) -> Self {
+        self.vars.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Value of a payload variable.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.vars.get(key).map(String::as_str)
+    }
+
+    /// The payload sent when a target has no template: every variable as a
+    /// JSON object.
+    pub fn default_payload(&self) -> String {
+        serde_json::to_string(&self.vars).unwrap_or_default()
+    }
+
+    /// Fill in a payload template and check that the result is JSON.
+    ///
+    /// Unknown placeholders become empty strings.
+    pub fn render(&self, template: &str) -> Result<String, String> {
+        let re = Regex::new(r"\{\{\s*([A-Za-z0-9_]+)\s*\}\}").expect("valid regex");
+        let body = re.replace_all(template, |caps: &regex::Captures<'_>| {
+            let value = self.get(&caps[1]).unwrap_or_default();
+            let quoted = serde_json::to_string(value).unwrap_or_default();
+            quoted[1..quoted.len() - 1].to_string()
+        });
+        serde_json::from_str::<serde_json::Value>(&body).map_err(|e| e.to_string())?;
+        Ok(body.into_owned())
+    }
+}
+
+/// Delivers events to the webhooks in the config.
+pub struct WebhookService {
+    config: WebhooksConfig,
+    allow_http: bool,
+    vault: String,
+}
+
+impl WebhookService {
+    /// Create from ResolvedConfig.
+    /// Returns None if no webhooks are configured.
+    pub fn try_from_config(config: &ResolvedConfig) -> Option<Self> {
+        if config.webhooks.hooks.is_empty() {
+            return None;
+        }
+        Some(Self {
+            config: config.webhooks.clone(),
+            allow_http: config.security.allow_http,
+            vault: config.active_profile.clone(),
+        })
+    }
+
+    /// Send an event from this service's vault, with `vars` for the payload,
+    /// to every target subscribed to it.
+    ///
+    /// Returns the failures; an empty list means every delivery succeeded
+    /// (or nothing was subscribed).
+    pub fn emit(&self, kind: WebhookEvent, vars: &[(&str, &str)]) -> Vec<WebhookError> {
+        let event = vars
+            .iter()
+            .fold(VaultEvent::new(kind, &self.vault), |event, (k, v)| event.with(k, *v));
+        self.emit_event(&event)
+    }
+
+    /// Send `event` to every target subscribed to it.
+    pub fn emit_event(&self, event: &VaultEvent) -> Vec<WebhookError> {
+        let targets: Vec<&WebhookTarget> =
+            self.config.hooks.iter().filter(|h| h.event == event.kind).collect();
+        if targets.is_empty() {
+            return Vec::new();
+        }
+        if !self.allow_http {
+            return vec![WebhookError::HttpNotAllowed];
+        }
+
+        let mut failures = Vec::new();
+        for target in targets {
+            if let Err(e) = self.deliver(target, event) {
+                failures.push(e);
+            }
+        }
+        failures
+    }
+
+    /// POST to one target, retrying with exponential backoff.
+    fn deliver(
+        &self,
+        target: &WebhookTarget,
+        event: &VaultEvent,
+    ) -> Result<(), WebhookError> {
+        let body = match &target.payload {
+            Some(template) => event.render(template).map_err(|message| {
+                WebhookError::Payload { url: target.url.clone(), message }
+            })?,
+            None => event.default_payload(),
+        };
+
+        let attempts = self.config.retries + 1;
+        let mut delay = Duration::from_millis(self.config.backoff_ms);
+        let mut message = String::new();
+        for attempt in 1..=attempts {
+            match self.post(target, &body)? {
+                None => return Ok(()),
+                Some(error) => message = error,
+            }
+            if attempt < attempts {
+                thread::sleep(delay);
+                delay *= 2;
+            }
+        }
+        Err(WebhookError::Delivery { url: target.url.clone(), attempts, message })
+    }
+
+    /// One delivery attempt. Returns curl's error on failure, including
+    /// non-2xx responses.
+    fn post(
+        &self,
+        target: &WebhookTarget,
+        body: &str,
+    ) -> Result<Option<String>, WebhookError> {
+        let mut cmd = Command::new("curl");
+        cmd.args(["--silent", "--show-error", "--fail", "--request", "POST"])
+            .arg("--max-time")
+            .arg(self.config.timeout_secs.to_string())
+            .args(["--header", "Content-Type: application/json"]);
+        for (name, value) in &target.headers {
+            cmd.arg("--header").arg(format!("{name}: {value}"));
+        }
+        // Only http(s), also across redirects, and never read as an option
+        cmd.args(["--proto", "=http,https", "--proto-redir", "=http,https"])
+            .args(["--data-binary", "@-"])
+            .arg("--url")
+            .arg(&target.url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+
+        let mut child = cmd.spawn().map_err(WebhookError::Spawn)?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // A failed write shows up as a curl error below
+            let _ = stdin.write_all(body.as_bytes());
+        }
+        let output = child.wait_with_output().map_err(WebhookError::Spawn)?;
+        if output.status.success() {
+            Ok(None)
+        } else {
+            Ok(Some(String::from_utf8_lossy(&output.stderr).trim().to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event() -> VaultEvent {
+        VaultEvent::new(WebhookEvent::TaskCompleted, "work")
+            .with("title", "Ship \"beta\"")
+            .with("path", "Projects/app/tasks/APP-001.md")
+    }
+
+    #[test]
+    fn renders_templates_with_escaped_values() {
+        let body = event()
+            .render(r#"{"text": "Done: {{title}}", "where": "{{ vault }}/{{path}}", "x": "{{nope}}"}"#)
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["text"], r#"Done: Ship "beta""#);
+        assert_eq!(json["where"], "work/Projects/app/tasks/APP-001.md");
+        assert_eq!(json["x"], "");
+
+        assert!(event().render(r#"{"text": {{title}}}"#).is_err());
+    }
+
+    #[test]
+    fn default_payload_carries_every_variable() {
+        let json: serde_json::Value =
+            serde_json::from_str(&event().default_payload()).unwrap();
+        assert_eq!(json["event"], "task.completed");
+        assert_eq!(json["vault"], "work");
+        assert_eq!(json["path"], "Projects/app/tasks/APP-001.md");
+        assert!(json["timestamp"].is_string());
+    }
+}
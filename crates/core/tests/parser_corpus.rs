@@ -0,0 +1,181 @@
+//! Corpus tests for the parsers every feature depends on.
+//!
+//! Each file in `fixtures/corpus/` is run through the frontmatter parser,
+//! the link extractor, and the markdown editor, both as written and with
+//! CRLF line endings. Add a fixture here whenever a real-world note breaks
+//! one of them.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use mdvault_core::frontmatter::{self, ParsedDocument};
+use mdvault_core::markdown_ast::{InsertPosition, MarkdownEditor, SectionMatch};
+use mdvault_core::vault::extract_note;
+
+fn corpus() -> Vec<(PathBuf, String)> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/corpus");
+    let mut files: Vec<(PathBuf, String)> = fs::read_dir(&dir)
+        .expect("corpus directory")
+        .map(|entry| entry.unwrap().path())
+        .filter(|p| p.extension().is_some_and(|e| e == "md"))
+        .map(|p| {
+            let content = fs::read_to_string(&p).unwrap();
+            (p, content)
+        })
+        .collect();
+    files.sort();
+    assert!(!files.is_empty(), "corpus is empty");
+    files
+}
+
+/// Every corpus document, plus a CRLF rendition of each.
+fn corpus_with_variants() -> Vec<(String, String)> {
+    let mut docs = Vec::new();
+    for (path, content) in corpus() {
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        let crlf = content.replace("\r\n", "\n").replace('\n', "\r\n");
+        docs.push((name.clone(), content));
+        docs.push((format!("{name} (crlf)"), crlf));
+    }
+    docs
+}
+
+fn corpus_file(name: &str) -> String {
+    corpus()
+        .into_iter()
+        .find(|(p, _)| p.file_name().unwrap() == name)
+        .map(|(_, c)| c)
+        .unwrap_or_else(|| panic!("missing corpus file {name}"))
+}
+
+fn fields(
+    doc: &ParsedDocument,
+) -> Option<&std::collections::HashMap<String, serde_yaml::Value>> {
+    doc.frontmatter.as_ref().map(|fm| &fm.fields).filter(|f| !f.is_empty())
+}
+
+#[test]
+fn frontmatter_round_trip_is_stable() {
+    for (name, content) in corpus_with_variants() {
+        let Ok(first) = frontmatter::parse(&content) else {
+            continue;
+        };
+        let serialized = frontmatter::serialize(&first);
+
+        let second = frontmatter::parse(&serialized)
+            .unwrap_or_else(|e| panic!("{name}: re-parse failed: {e}"));
+        assert_eq!(fields(&first), fields(&second), "{name}: fields changed");
+
+        let reserialized = frontmatter::serialize(&second);
+        assert_eq!(serialized, reserialized, "{name}: serialize is not stable");
+    }
+}
+
+#[test]
+fn line_endings_do_not_change_frontmatter() {
+    for (path, content) in corpus() {
+        let lf = content.replace("\r\n", "\n");
+        let crlf = lf.replace('\n', "\r\n");
+
+        let from_lf = frontmatter::parse(&lf).ok();
+        let from_crlf = frontmatter::parse(&crlf).ok();
+        assert_eq!(
+            from_lf.as_ref().and_then(fields),
+            from_crlf.as_ref().and_then(fields),
+            "{}",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn extracted_links_point_into_the_body() {
+    for (name, content) in corpus_with_variants() {
+        let note = extract_note(&content, Path::new(&name));
+        let body_lines = note.body.lines().count() as u32;
+
+        assert!(!note.title.trim().is_empty(), "{name}: empty title");
+        for link in note.links.iter().filter(|l| l.line_number > 0) {
+            assert!(
+                link.line_number <= body_lines,
+                "{name}: link '{}' on line {} of {}",
+                link.target,
+                link.line_number,
+                body_lines
+            );
+            assert!(!link.target.contains('\r'), "{name}: CR in link target");
+        }
+    }
+}
+
+#[test]
+fn editor_can_insert_into_every_heading() {
+    for (name, content) in corpus_with_variants() {
+        let body = frontmatter::parse(&content).map(|d| d.body).unwrap_or(content);
+
+        for heading in MarkdownEditor::find_headings(&body) {
+            let result = MarkdownEditor::insert_into_section(
+                &body,
+                &SectionMatch::new(&heading.title).case_sensitive(true),
+                "- corpus marker\n",
+                InsertPosition::End,
+            )
+            .unwrap_or_else(|e| panic!("{name}: insert into '{}': {e}", heading.title));
+
+            assert!(result.content.contains("corpus marker"), "{name}");
+            assert!(
+                MarkdownEditor::section_exists(
+                    &result.content,
+                    &SectionMatch::new(&heading.title)
+                ),
+                "{name}: heading '{}' lost after insert",
+                heading.title
+            );
+        }
+    }
+}
+
+#[test]
+fn fenced_dashes_are_not_frontmatter_or_headings() {
+    let content = corpus_file("nested_fences_with_dashes.md");
+    let doc = frontmatter::parse(&content).unwrap();
+
+    assert_eq!(fields(&doc).unwrap().len(), 1);
+    assert!(doc.body.contains("key: value"));
+
+    let titles: Vec<String> =
+        MarkdownEditor::find_headings(&doc.body).into_iter().map(|h| h.title).collect();
+    assert_eq!(titles, vec!["Fences", "After fences"]);
+
+    let note = extract_note(&content, Path::new("nested_fences_with_dashes.md"));
+    assert!(note.links.iter().any(|l| l.target == "fenced-link-is-real"));
+}
+
+#[test]
+fn block_scalars_may_contain_dashes() {
+    let content = corpus_file("block_scalar_with_dashes.md");
+    let doc = frontmatter::parse(&content).unwrap();
+    let summary = fields(&doc).unwrap()["summary"].as_str().unwrap();
+
+    assert!(summary.contains("--- not a delimiter"));
+    assert!(doc.body.starts_with("# Block scalar"));
+}
+
+#[test]
+fn unicode_titles_survive_extraction() {
+    let content = corpus_file("unicode_title.md");
+    let note = extract_note(&content, Path::new("unicode_title.md"));
+
+    assert_eq!(note.title, "Café — naïve 日本語 🚀");
+    assert!(note.links.iter().any(|l| l.target == "日本語ノート"));
+    assert!(MarkdownEditor::section_exists(&note.body, &SectionMatch::new("sección ñ")));
+}
+
+#[test]
+fn mixed_line_endings_are_parsed() {
+    let content = corpus_file("mixed_line_endings.md");
+    let doc = frontmatter::parse(&content).unwrap();
+
+    assert_eq!(fields(&doc).unwrap()["title"].as_str(), Some("Mixed endings"));
+    assert!(doc.body.starts_with("# Mixed endings"));
+}
@@ -0,0 +1,164 @@
+//! Property tests for the frontmatter parser, link extractor, and markdown editor.
+//!
+//! Complements `parser_corpus.rs`: the corpus pins known-awkward documents,
+//! these generate many small ones built from the same awkward pieces.
+//! Shrunk failures are recorded in `parser_props.proptest-regressions` and
+//! replayed on every run; commit that file along with the fix.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use mdvault_core::frontmatter::{self, Frontmatter, ParsedDocument};
+use mdvault_core::markdown_ast::{InsertPosition, MarkdownEditor, SectionMatch};
+use mdvault_core::vault::extract_note;
+use proptest::prelude::*;
+use serde_yaml::Value;
+
+/// Line fragments that tend to confuse line-based parsers.
+fn awkward_line() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just("---".to_string()),
+        Just("--- ".to_string()),
+        Just("```".to_string()),
+        Just("~~~".to_string()),
+        Just("# ".to_string()),
+        Just("[[".to_string()),
+        Just("]]".to_string()),
+        Just("key: value".to_string()),
+        Just("|".to_string()),
+        "[a-zA-Z0-9 ]{0,12}",
+        "\\PC{0,8}",
+    ]
+}
+
+/// Documents assembled from awkward lines with random line endings.
+fn awkward_document() -> impl Strategy<Value = String> {
+    prop::collection::vec(
+        (awkward_line(), prop_oneof![Just("\n"), Just("\r\n"), Just("")]),
+        0..24,
+    )
+    .prop_map(|parts| parts.into_iter().map(|(line, end)| line + end).collect())
+}
+
+fn yaml_scalar() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        "[a-zA-Z][a-zA-Z0-9 _-]{0,15}".prop_map(Value::from),
+        "\\PC{1,10}".prop_map(Value::from),
+        any::<i32>().prop_map(|n| Value::from(n as i64)),
+        any::<bool>().prop_map(Value::from),
+        Just(Value::from("---")),
+        Just(Value::from("a: b # c")),
+        Just(Value::from("line one\n--- line two\n")),
+    ]
+}
+
+fn yaml_value() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        3 => yaml_scalar(),
+        1 => prop::collection::vec(yaml_scalar(), 0..4).prop_map(Value::Sequence),
+    ]
+}
+
+fn frontmatter_fields() -> impl Strategy<Value = HashMap<String, Value>> {
+    prop::collection::hash_map("[a-z][a-z0-9_]{0,10}", yaml_value(), 1..6)
+}
+
+/// Body text: plain lines, headings, and wikilinks, never starting a line with `---`.
+fn body_lines() -> impl Strategy<Value = Vec<String>> {
+    prop::collection::vec(
+        prop_oneof![
+            "[a-zA-Z0-9 .,]{0,30}",
+            "[a-z]{1,10}".prop_map(|t| format!("## {t}")),
+            "[a-z][a-z-]{0,10}".prop_map(|t| format!("see [[{t}]]")),
+            "\\PC{0,10}".prop_map(|s| format!("> {s}")),
+        ],
+        0..16,
+    )
+}
+
+proptest! {
+    #[test]
+    fn parse_never_panics(doc in awkward_document()) {
+        let _ = frontmatter::parse(&doc);
+        let _ = frontmatter::parse_template_frontmatter(&doc);
+    }
+
+    #[test]
+    fn extract_never_panics(doc in awkward_document()) {
+        let note = extract_note(&doc, Path::new("prop.md"));
+        prop_assert!(!note.title.is_empty());
+    }
+
+    #[test]
+    fn editor_never_panics(doc in awkward_document(), title in "[a-z ]{0,8}") {
+        let _ = MarkdownEditor::find_headings(&doc);
+        let _ = MarkdownEditor::insert_into_section(
+            &doc,
+            &SectionMatch::new(title),
+            "- item\n",
+            InsertPosition::Begin,
+        );
+    }
+
+    #[test]
+    fn parse_serialize_round_trip(
+        fields in frontmatter_fields(),
+        lines in body_lines(),
+        crlf in any::<bool>(),
+    ) {
+        let body = lines.join("\n") + "\n";
+        let doc = ParsedDocument {
+            frontmatter: Some(Frontmatter { fields: fields.clone() }),
+            body: body.clone(),
+        };
+        let mut text = frontmatter::serialize(&doc);
+        if crlf {
+            text = text.replace('\n', "\r\n");
+        }
+
+        let parsed = frontmatter::parse(&text).expect("serialized output parses");
+        prop_assert_eq!(&parsed.frontmatter.as_ref().unwrap().fields, &fields);
+        let parsed_body = parsed.body.replace("\r\n", "\n");
+        prop_assert_eq!(parsed_body.trim_start_matches('\n'), body.trim_start_matches('\n'));
+
+        let again = frontmatter::serialize(&parsed);
+        prop_assert_eq!(frontmatter::serialize(&frontmatter::parse(&again).unwrap()), again);
+    }
+
+    #[test]
+    fn wikilinks_found_on_their_lines(lines in body_lines()) {
+        let body = lines.join("\n");
+        let note = extract_note(&body, Path::new("prop.md"));
+
+        for (i, line) in lines.iter().enumerate() {
+            if let Some(target) = line.strip_prefix("see [[").and_then(|l| l.strip_suffix("]]")) {
+                prop_assert!(
+                    note.links.iter().any(|l| l.target == target && l.line_number == i as u32 + 1),
+                    "missing [[{}]] on line {}", target, i + 1
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn insert_keeps_existing_headings(lines in body_lines(), pick in any::<prop::sample::Index>()) {
+        let body = lines.join("\n") + "\n";
+        let headings = MarkdownEditor::find_headings(&body);
+        prop_assume!(!headings.is_empty());
+
+        let target = &headings[pick.index(headings.len())];
+        let result = MarkdownEditor::insert_into_section(
+            &body,
+            &SectionMatch::new(&target.title).case_sensitive(true),
+            "- inserted item\n",
+            InsertPosition::End,
+        )
+        .unwrap();
+
+        prop_assert!(result.content.contains("inserted item"));
+        let after: Vec<String> =
+            MarkdownEditor::find_headings(&result.content).into_iter().map(|h| h.title).collect();
+        let before: Vec<String> = headings.into_iter().map(|h| h.title).collect();
+        prop_assert_eq!(after, before);
+    }
+}
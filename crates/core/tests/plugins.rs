@@ -0,0 +1,120 @@
+//! Integration tests for compiled-in plugins registered with `register_plugin!`.
+
+use std::fs;
+use std::path::Path;
+
+use mdvault_core::config::types::ResolvedConfig;
+use mdvault_core::index::{IndexBuilder, IndexDb, IndexedNote, NoteQuery};
+use mdvault_core::lint::{CategoryReport, LintIssue, run_lint};
+use mdvault_core::plugins::{self, PluginContext, PluginResult, VaultPlugin};
+use mdvault_core::types::TypeRegistry;
+use serde_json::{Map, Value};
+use tempfile::tempdir;
+
+#[derive(Default)]
+struct Shouty {
+    min_bangs: usize,
+}
+
+impl VaultPlugin for Shouty {
+    fn name(&self) -> &'static str {
+        "shouty"
+    }
+
+    fn init(&mut self, ctx: &PluginContext<'_>) -> PluginResult<()> {
+        self.min_bangs =
+            ctx.settings.get("min_bangs").and_then(|v| v.as_integer()).unwrap_or(1)
+                as usize;
+        Ok(())
+    }
+
+    fn enrich(&self, _note: &IndexedNote, body: &str) -> Map<String, Value> {
+        let mut fields = Map::new();
+        fields.insert("bangs".into(), body.matches('!').count().into());
+        fields
+    }
+
+    fn lint(
+        &self,
+        _ctx: &PluginContext<'_>,
+        db: &IndexDb,
+    ) -> PluginResult<Vec<CategoryReport>> {
+        let mut report = CategoryReport::new("shouting", "Shouting");
+        let filter = format!("shouty.bangs>={}", self.min_bangs);
+        let query = NoteQuery { fields: vec![filter.parse()?], ..Default::default() };
+        for note in db.query_notes(&query)? {
+            report.warnings.push(LintIssue {
+                path: note.path.display().to_string(),
+                line: None,
+                message: "too many exclamation marks".into(),
+                suggestion: None,
+                fixable: false,
+            });
+        }
+        Ok(vec![report])
+    }
+}
+
+mdvault_core::register_plugin!(Shouty::default());
+
+fn config(vault: &Path) -> ResolvedConfig {
+    let mut plugins = std::collections::BTreeMap::new();
+    plugins.insert("shouty".to_string(), toml::from_str("min_bangs = 3").unwrap());
+    ResolvedConfig {
+        active_profile: "test".into(),
+        vault_root: vault.to_path_buf(),
+        templates_dir: vault.join(".mdvault/templates"),
+        captures_dir: vault.join(".mdvault/captures"),
+        macros_dir: vault.join(".mdvault/macros"),
+        typedefs_dir: vault.join(".mdvault/typedefs"),
+        typedefs_fallback_dir: None,
+        commands_dir: vault.join(".mdvault/commands"),
+        hooks_dir: vault.join(".mdvault/hooks"),
+        formatters_dir: vault.join(".mdvault/formatters"),
+        excluded_folders: vec![],
+        security: Default::default(),
+        logging: Default::default(),
+        activity: Default::default(),
+        display: Default::default(),
+        ingest: Default::default(),
+        capture: Default::default(),
+        quota: Default::default(),
+        webhooks: Default::default(),
+        index: Default::default(),
+        plugins,
+        searches: Default::default(),
+        embeddings: Default::default(),
+        staleness: Default::default(),
+        orphans: Default::default(),
+        tui: Default::default(),
+        serve: Default::default(),
+    }
+}
+
+#[test]
+fn registered_plugin_enriches_index_and_adds_lint_rules() {
+    let tmp = tempdir().unwrap();
+    let vault = tmp.path();
+    fs::write(vault.join("calm.md"), "# Calm\n\nQuiet words.\n").unwrap();
+    fs::write(vault.join("loud.md"), "# Loud\n\nWow! Really! Yes!\n").unwrap();
+
+    let registry = plugins::activate(&config(vault)).unwrap();
+    let names: Vec<_> = registry.plugins().map(|p| p.name()).collect();
+    assert_eq!(names, ["shouty"]);
+
+    let db = IndexDb::open_in_memory().unwrap();
+    IndexBuilder::new(&db, vault).full_reindex(None).unwrap();
+
+    let query = NoteQuery {
+        fields: vec!["shouty.bangs=3".parse().unwrap()],
+        ..Default::default()
+    };
+    let loud = db.query_notes(&query).unwrap();
+    assert_eq!(loud.len(), 1);
+    assert_eq!(loud[0].path, Path::new("loud.md"));
+
+    let report = run_lint(&db, &TypeRegistry::new(), vault, Some("shouting"), true);
+    assert_eq!(report.categories.len(), 1);
+    assert_eq!(report.categories[0].warnings.len(), 1);
+    assert_eq!(report.categories[0].warnings[0].path, "loud.md");
+}
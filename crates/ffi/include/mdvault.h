@@ -0,0 +1,90 @@
+/*
+ * mdvault C API.
+ *
+ * All strings are NUL-terminated UTF-8. Strings returned by this library
+ * belong to the caller and must be released with mdv_string_free().
+ *
+ * Every function except mdv_open() returns a JSON response:
+ *
+ *   {"ok": true, "data": ...}
+ *   {"ok": false, "error": {"code": "not_found", "message": "..."}}
+ *
+ * Error codes: config, not_found, already_exists, invalid_input, validation,
+ * index, scripting, template, io, internal.
+ *
+ * A vault handle may move between threads but must not be used from two
+ * threads at once.
+ */
+
+#ifndef MDVAULT_H
+#define MDVAULT_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct MdvVault MdvVault;
+
+/*
+ * Open a vault from its config file (NULL for the default location) and
+ * profile (NULL for the default profile), updating the index. Returns NULL
+ * on failure; see mdv_last_error().
+ */
+MdvVault *mdv_open(const char *config_path, const char *profile);
+
+/*
+ * The error from the last failed mdv_open() on this thread, as
+ * {"code": ..., "message": ...}, or NULL.
+ */
+char *mdv_last_error(void);
+
+/* Close a vault. NULL is ignored. */
+void mdv_close(MdvVault *vault);
+
+/*
+ * Search the index.
+ *
+ * Request (all fields optional):
+ *   {"query": "launch", "type": "project", "path_prefix": "Projects/",
+ *    "where": ["status=open"], "mode": "direct", "limit": 20}
+ *
+ * mode is one of direct, neighbourhood, temporal, cooccurrence, full.
+ * Data: [{"path", "title", "score", "snippet"}]
+ */
+char *mdv_search(const MdvVault *vault, const char *request);
+
+/*
+ * Read a note by its path relative to the vault root.
+ *
+ * Data: {"path", "title", "frontmatter", "body"}
+ */
+char *mdv_read(const MdvVault *vault, const char *path);
+
+/*
+ * Create a note from a template, or from literal content.
+ *
+ * Request:
+ *   {"path": "Meetings/sync.md", "template": "meeting",
+ *    "vars": {"title": "Weekly sync"}}
+ *   {"path": "scratch.md", "content": "# Scratch\n"}
+ *
+ * Fails with already_exists if the note exists. Data: {"path"}
+ */
+char *mdv_create(const MdvVault *vault, const char *request);
+
+/*
+ * Run a capture by name. Lua hooks are not run.
+ *
+ * Request: {"name": "inbox", "vars": {"text": "Call Bo"}}
+ * Data: {"target", "section", "frontmatter_updated"}
+ */
+char *mdv_capture(const MdvVault *vault, const char *request);
+
+/* Free a string returned by this library. NULL is ignored. */
+void mdv_string_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* MDVAULT_H */
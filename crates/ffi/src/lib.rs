@@ -0,0 +1,361 @@
+//! C ABI for embedding the mdvault engine.
+//!
+//! Requests and responses are JSON, so bindings only need to pass strings:
+//!
+//! - Every string crossing the boundary is NUL-terminated UTF-8.
+//! - Strings returned by this library are owned by the caller and must be
+//!   released with [`mdv_string_free`].
+//! - Every call other than [`mdv_open`] returns either
+//!   `{"ok": true, "data": ...}` or
+//!   `{"ok": false, "error": {"code": "not_found", "message": "..."}}`, where
+//!   `code` is one of the stable codes of `mdvault_core::ErrorCode`.
+//! - A vault handle may be moved between threads but must not be used from
+//!   two threads at once.
+//!
+//! See `include/mdvault.h` for the request shapes.
+
+mod ops;
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::path::Path;
+use std::ptr;
+
+use mdvault_core::error::ErrorCode;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+use ops::{FfiError, FfiResult, Vault};
+
+/// An open vault. Opaque to C.
+pub struct MdvVault(Vault);
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<FfiError>> = const { RefCell::new(None) };
+}
+
+/// Open the vault described by the config file at `config_path` (NULL for
+/// the default location), using `profile` (NULL for the default profile).
+///
+/// Brings the index up to date before returning. Returns NULL on failure;
+/// [`mdv_last_error`] then describes why.
+///
+/// # Safety
+///
+/// `config_path` and `profile` must each be NULL or a valid C string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mdv_open(
+    config_path: *const c_char,
+    profile: *const c_char,
+) -> *mut MdvVault {
+    let result = guard(|| {
+        let config_path = unsafe { opt_str(config_path) }?;
+        let profile = unsafe { opt_str(profile) }?;
+        Vault::open(config_path.map(Path::new), profile)
+    });
+    match result {
+        Ok(vault) => Box::into_raw(Box::new(MdvVault(vault))),
+        Err(e) => {
+            LAST_ERROR.with(|last| *last.borrow_mut() = Some(e));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// The error from the last failed [`mdv_open`] on this thread, as
+/// `{"code": ..., "message": ...}`, or NULL if there is none.
+#[unsafe(no_mangle)]
+pub extern "C" fn mdv_last_error() -> *mut c_char {
+    LAST_ERROR.with(|last| match &*last.borrow() {
+        Some(e) => into_c_string(e.to_json().to_string()),
+        None => ptr::null_mut(),
+    })
+}
+
+/// Close a vault opened with [`mdv_open`]. NULL is ignored.
+///
+/// # Safety
+///
+/// `vault` must be NULL or a handle from [`mdv_open`] that has not been
+/// closed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mdv_close(vault: *mut MdvVault) {
+    if !vault.is_null() {
+        drop(unsafe { Box::from_raw(vault) });
+    }
+}
+
+/// Search the index.
+///
+/// # Safety
+///
+/// `vault` must be a live handle and `request` a valid C string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mdv_search(
+    vault: *const MdvVault,
+    request: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let vault = unsafe { handle(vault) }?;
+        vault.search(unsafe { parse_request(request) }?)
+    })
+}
+
+/// Read a note by its path relative to the vault root.
+///
+/// # Safety
+///
+/// `vault` must be a live handle and `path` a valid C string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mdv_read(
+    vault: *const MdvVault,
+    path: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let vault = unsafe { handle(vault) }?;
+        let path =
+            unsafe { opt_str(path) }?.ok_or_else(|| FfiError::invalid("path is NULL"))?;
+        vault.read(path)
+    })
+}
+
+/// Create a note, from a template or literal content.
+///
+/// # Safety
+///
+/// `vault` must be a live handle and `request` a valid C string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mdv_create(
+    vault: *const MdvVault,
+    request: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let vault = unsafe { handle(vault) }?;
+        vault.create(unsafe { parse_request(request) }?)
+    })
+}
+
+/// Run a capture. Lua hooks are not run.
+///
+/// # Safety
+///
+/// `vault` must be a live handle and `request` a valid C string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mdv_capture(
+    vault: *const MdvVault,
+    request: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let vault = unsafe { handle(vault) }?;
+        vault.capture(unsafe { parse_request(request) }?)
+    })
+}
+
+/// Free a string returned by this library. NULL is ignored.
+///
+/// # Safety
+///
+/// `s` must be NULL or a string returned by this library that has not been
+/// freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mdv_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// Run `f`, turning panics into errors so they never unwind into C.
+fn guard<T>(f: impl FnOnce() -> FfiResult<T>) -> FfiResult<T> {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|panic| {
+        let message = panic
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panic".to_string());
+        Err(FfiError { code: ErrorCode::Internal, message })
+    })
+}
+
+fn respond(f: impl FnOnce() -> FfiResult<Value>) -> *mut c_char {
+    let response = match guard(f) {
+        Ok(data) => json!({ "ok": true, "data": data }),
+        Err(e) => json!({ "ok": false, "error": e.to_json() }),
+    };
+    into_c_string(response.to_string())
+}
+
+fn into_c_string(s: String) -> *mut c_char {
+    // serde_json escapes NUL, so this only fails on a bug
+    CString::new(s).map(CString::into_raw).unwrap_or(ptr::null_mut())
+}
+
+unsafe fn opt_str<'a>(s: *const c_char) -> FfiResult<Option<&'a str>> {
+    if s.is_null() {
+        return Ok(None);
+    }
+    unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map(Some)
+        .map_err(|_| FfiError::invalid("string is not valid UTF-8"))
+}
+
+unsafe fn handle<'a>(vault: *const MdvVault) -> FfiResult<&'a Vault> {
+    unsafe { vault.as_ref() }
+        .map(|v| &v.0)
+        .ok_or_else(|| FfiError::invalid("vault handle is NULL"))
+}
+
+unsafe fn parse_request<T: DeserializeOwned>(request: *const c_char) -> FfiResult<T> {
+    let request = unsafe { opt_str(request) }?
+        .ok_or_else(|| FfiError::invalid("request is NULL"))?;
+    serde_json::from_str(request)
+        .map_err(|e| FfiError::invalid(format!("invalid request: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    /// Take ownership of a returned string and parse it.
+    fn take(s: *mut c_char) -> Value {
+        assert!(!s.is_null());
+        let text = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string();
+        unsafe { mdv_string_free(s) };
+        serde_json::from_str(&text).unwrap()
+    }
+
+    fn setup() -> (TempDir, CString) {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        let vault = root.join("vault");
+        fs::create_dir_all(vault.join("Projects")).unwrap();
+        fs::create_dir_all(root.join("templates")).unwrap();
+        fs::create_dir_all(root.join("captures")).unwrap();
+        fs::write(
+            vault.join("Projects/alpha.md"),
+            "---\ntype: project\nstatus: open\n---\n# Alpha\n\nLaunch plan.\n",
+        )
+        .unwrap();
+        fs::write(vault.join("Inbox.md"), "# Inbox\n\n## Items\n").unwrap();
+        fs::write(root.join("templates/meeting.md"), "# {{title}}\n\nAttendees:\n")
+            .unwrap();
+        fs::write(
+            root.join("captures/inbox.lua"),
+            r#"return {
+    name = "inbox",
+    target = { file = "Inbox.md", section = "Items", position = "end" },
+    content = "- {{text}}",
+}"#,
+        )
+        .unwrap();
+
+        let config = root.join("config.toml");
+        fs::write(
+            &config,
+            format!(
+                "version = 1\nprofile = \"default\"\n\n[profiles.default]\n\
+                 vault_root = \"{}\"\ntemplates_dir = \"{}\"\ncaptures_dir = \"{}\"\n\
+                 macros_dir = \"{}\"\n",
+                vault.display(),
+                root.join("templates").display(),
+                root.join("captures").display(),
+                root.join("macros").display(),
+            ),
+        )
+        .unwrap();
+        let config = c(config.to_str().unwrap());
+        (dir, config)
+    }
+
+    #[test]
+    fn round_trips_through_the_c_api() {
+        let (dir, config) = setup();
+        let vault = unsafe { mdv_open(config.as_ptr(), ptr::null()) };
+        assert!(!vault.is_null(), "{:?}", take(mdv_last_error()));
+
+        let found = take(unsafe {
+            mdv_search(vault, c(r#"{"query": "alpha", "type": "project"}"#).as_ptr())
+        });
+        assert_eq!(found["ok"], true);
+        assert_eq!(found["data"][0]["path"], "Projects/alpha.md");
+        assert_eq!(found["data"][0]["title"], "Alpha");
+
+        let note = take(unsafe { mdv_read(vault, c("Projects/alpha.md").as_ptr()) });
+        assert_eq!(note["data"]["frontmatter"]["status"], "open");
+        assert!(note["data"]["body"].as_str().unwrap().contains("Launch plan."));
+
+        let created = take(unsafe {
+            mdv_create(
+                vault,
+                c(r#"{"path": "Meetings/sync.md", "template": "meeting",
+                      "vars": {"title": "Weekly sync"}}"#)
+                .as_ptr(),
+            )
+        });
+        assert_eq!(created["data"]["path"], "Meetings/sync.md");
+        let written =
+            fs::read_to_string(dir.path().join("vault/Meetings/sync.md")).unwrap();
+        assert!(written.starts_with("# Weekly sync"));
+        let found =
+            take(unsafe { mdv_search(vault, c(r#"{"query": "sync"}"#).as_ptr()) });
+        assert_eq!(found["data"][0]["path"], "Meetings/sync.md");
+
+        let captured = take(unsafe {
+            mdv_capture(
+                vault,
+                c(r#"{"name": "inbox", "vars": {"text": "Call Bo"}}"#).as_ptr(),
+            )
+        });
+        assert_eq!(captured["data"]["target"], "Inbox.md");
+        assert_eq!(captured["data"]["section"], "Items");
+        let inbox = fs::read_to_string(dir.path().join("vault/Inbox.md")).unwrap();
+        assert!(inbox.contains("- Call Bo"));
+
+        unsafe { mdv_close(vault) };
+    }
+
+    #[test]
+    fn reports_errors_with_stable_codes() {
+        let (_dir, config) = setup();
+        let vault = unsafe { mdv_open(config.as_ptr(), ptr::null()) };
+
+        let code = |response: Value| {
+            assert_eq!(response["ok"], false);
+            response["error"]["code"].as_str().unwrap().to_string()
+        };
+        assert_eq!(
+            code(take(unsafe { mdv_read(vault, c("missing.md").as_ptr()) })),
+            "not_found"
+        );
+        assert_eq!(
+            code(take(unsafe { mdv_read(vault, c("../config.toml").as_ptr()) })),
+            "invalid_input"
+        );
+        assert_eq!(
+            code(take(unsafe {
+                mdv_create(vault, c(r#"{"path": "Inbox.md"}"#).as_ptr())
+            })),
+            "already_exists"
+        );
+        assert_eq!(
+            code(take(unsafe { mdv_search(vault, c(r#"{"colour": 1}"#).as_ptr()) })),
+            "invalid_input"
+        );
+        assert_eq!(
+            code(take(unsafe { mdv_read(ptr::null(), ptr::null()) })),
+            "invalid_input"
+        );
+        unsafe { mdv_close(vault) };
+
+        let missing = c("/nonexistent/config.toml");
+        assert!(unsafe { mdv_open(missing.as_ptr(), ptr::null()) }.is_null());
+        assert_eq!(take(mdv_last_error())["code"], "config");
+    }
+}
@@ -0,0 +1,253 @@
+//! The operations behind the C functions, in safe Rust.
+//!
+//! Each takes a parsed request and returns the JSON `data` of a successful
+//! response, or an [`FfiError`] carrying a stable [`ErrorCode`].
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use mdvault_core::captures::{CaptureRepoError, CaptureRepository, run_capture};
+use mdvault_core::config::loader::ConfigLoader;
+use mdvault_core::config::types::ResolvedConfig;
+use mdvault_core::error::ErrorCode;
+use mdvault_core::frontmatter;
+use mdvault_core::index::{
+    FieldFilter, FieldOp, IndexBuilder, IndexDb, SearchEngine, SearchMode, SearchQuery,
+};
+use mdvault_core::paths::PathResolver;
+use mdvault_core::templates::discovery::TemplateInfo;
+use mdvault_core::templates::engine::{build_render_context, render};
+use mdvault_core::templates::repository::{TemplateRepoError, TemplateRepository};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+/// A failure reported to the caller as `{"code": ..., "message": ...}`.
+#[derive(Debug)]
+pub struct FfiError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl FfiError {
+    pub fn invalid(message: impl Into<String>) -> Self {
+        Self { code: ErrorCode::InvalidInput, message: message.into() }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({ "code": self.code.as_str(), "message": self.message })
+    }
+}
+
+impl<E: Into<mdvault_core::Error>> From<E> for FfiError {
+    fn from(e: E) -> Self {
+        let e = e.into();
+        Self { code: e.code(), message: e.to_string() }
+    }
+}
+
+pub type FfiResult<T> = Result<T, FfiError>;
+
+/// An open vault: its configuration and index.
+pub struct Vault {
+    config: ResolvedConfig,
+    db: IndexDb,
+}
+
+impl Vault {
+    /// Load the configuration (default location when `config_path` is
+    /// `None`) and bring the index up to date.
+    pub fn open(config_path: Option<&Path>, profile: Option<&str>) -> FfiResult<Self> {
+        let config = ConfigLoader::load(config_path, profile)?;
+        let index_path = PathResolver::new(&config.vault_root).index_db();
+        if let Some(dir) = index_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let db = IndexDb::open(&index_path)?;
+        let vault = Self { config, db };
+        vault.builder().incremental_reindex(None)?;
+        Ok(vault)
+    }
+
+    fn builder(&self) -> IndexBuilder<'_> {
+        IndexBuilder::with_exclusions(
+            &self.db,
+            &self.config.vault_root,
+            self.config.excluded_folders.clone(),
+        )
+    }
+
+    pub fn search(&self, req: SearchRequest) -> FfiResult<Value> {
+        let mut fields = req
+            .filters
+            .iter()
+            .map(|f| f.parse::<FieldFilter>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| FfiError::invalid(e.to_string()))?;
+        if let Some(note_type) = req.note_type {
+            fields.push(FieldFilter {
+                key: "type".into(),
+                op: FieldOp::Eq,
+                value: note_type,
+            });
+        }
+        let mode = match req.mode.as_deref().unwrap_or("direct") {
+            "direct" => SearchMode::Direct,
+            "neighbourhood" => SearchMode::Neighbourhood { hops: 2 },
+            "temporal" => SearchMode::Temporal { days: 30 },
+            "cooccurrence" => SearchMode::Cooccurrence { min_shared: 2 },
+            "full" => SearchMode::Full,
+            other => {
+                return Err(FfiError::invalid(format!("unknown search mode '{other}'")));
+            }
+        };
+        let query = SearchQuery {
+            text: req.query,
+            path_prefix: req.path_prefix,
+            fields,
+            mode,
+            limit: req.limit,
+            ..Default::default()
+        };
+
+        let results = SearchEngine::new(&self.db).search(&query)?;
+        let results: Vec<Value> = results
+            .into_iter()
+            .map(|r| {
+                json!({
+                    "path": r.note.path,
+                    "title": r.note.title,
+                    "score": r.score,
+                    "snippet": r.snippet.map(|s| s.marked("[", "]")),
+                })
+            })
+            .collect();
+        Ok(Value::Array(results))
+    }
+
+    pub fn read(&self, path: &str) -> FfiResult<Value> {
+        let rel = vault_relative(path)?;
+        let content = fs::read_to_string(self.config.vault_root.join(&rel))?;
+        let doc = frontmatter::parse(&content)?;
+        let fields = doc.frontmatter.map(|fm| fm.fields).unwrap_or_default();
+        let title =
+            self.db.get_note_by_path(&rel)?.map(|note| note.title).unwrap_or_else(|| {
+                rel.file_stem().unwrap_or_default().to_string_lossy().into()
+            });
+
+        Ok(json!({
+            "path": rel,
+            "title": title,
+            "frontmatter": fields,
+            "body": doc.body,
+        }))
+    }
+
+    pub fn create(&self, req: CreateRequest) -> FfiResult<Value> {
+        let rel = vault_relative(&req.path)?;
+        if rel.extension().is_none_or(|e| e != "md") {
+            return Err(FfiError::invalid("path must end in .md"));
+        }
+        let output = self.config.vault_root.join(&rel);
+        if output.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("note already exists: {}", rel.display()),
+            )
+            .into());
+        }
+
+        let content = match &req.template {
+            Some(name) => {
+                let loaded = TemplateRepository::new(&self.config.templates_dir)
+                    .map_err(TemplateRepoError::from)?
+                    .get_by_name(name)?;
+                let info = TemplateInfo {
+                    logical_name: loaded.logical_name.clone(),
+                    path: loaded.path.clone(),
+                };
+                let mut ctx = build_render_context(&self.config, &info, &output);
+                ctx.extend(req.vars);
+                render(&loaded, &ctx)?
+            }
+            None => req.content.unwrap_or_default(),
+        };
+
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&output, content)?;
+        self.builder().reindex_file(&rel)?;
+        Ok(json!({ "path": rel }))
+    }
+
+    pub fn capture(&self, req: CaptureRequest) -> FfiResult<Value> {
+        let loaded = CaptureRepository::new(&self.config.captures_dir)
+            .map_err(CaptureRepoError::from)?
+            .get_by_name(&req.name)?;
+        let outcome = run_capture(&self.config, &loaded.spec, &req.vars)?;
+
+        // Targets outside the vault are written but not indexed
+        let target = match outcome.target.strip_prefix(&self.config.vault_root) {
+            Ok(rel) => {
+                self.builder().reindex_file(rel)?;
+                rel.to_path_buf()
+            }
+            Err(_) => outcome.target.clone(),
+        };
+        Ok(json!({
+            "target": target,
+            "section": outcome.section.map(|(title, _)| title),
+            "frontmatter_updated": outcome.frontmatter_updated,
+        }))
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SearchRequest {
+    pub query: Option<String>,
+    #[serde(rename = "type")]
+    pub note_type: Option<String>,
+    pub path_prefix: Option<String>,
+    /// `key<op>value` frontmatter filters, as for `mdv list --where`
+    #[serde(rename = "where")]
+    pub filters: Vec<String>,
+    pub mode: Option<String>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateRequest {
+    pub path: String,
+    #[serde(default)]
+    pub template: Option<String>,
+    #[serde(default)]
+    pub vars: HashMap<String, String>,
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CaptureRequest {
+    pub name: String,
+    #[serde(default)]
+    pub vars: HashMap<String, String>,
+}
+
+/// Accept only paths that stay inside the vault.
+fn vault_relative(path: &str) -> FfiResult<PathBuf> {
+    let path = Path::new(path);
+    let inside =
+        path.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if !inside || path.as_os_str().is_empty() {
+        return Err(FfiError::invalid(format!(
+            "path must be relative to the vault root: {}",
+            path.display()
+        )));
+    }
+    Ok(path.components().collect())
+}
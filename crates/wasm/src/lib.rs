@@ -0,0 +1,200 @@
+//! WebAssembly bindings for the pure parts of mdvault.
+//!
+//! Built on `mdvault-core` without its `native` feature, so there is no
+//! index, Lua, or file access: callers pass note text in and get text or
+//! JSON back. This is enough for a browser preview that parses, validates
+//! and renders notes exactly as the CLI does.
+//!
+//! Structured values cross the boundary as JSON strings. Failures throw a
+//! JavaScript `Error` carrying the message.
+//!
+//! ```js
+//! import init, { validateNote } from "./mdvault_wasm.js";
+//! await init();
+//! const schema = { status: { type: "string", enum: ["open", "done"] } };
+//! JSON.parse(validateNote(JSON.stringify(schema), noteText));
+//! // { valid: false, errors: ["enum constraint violated for 'status': ..."], warnings: [] }
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+
+use chrono::NaiveDate;
+use mdvault_core::frontmatter::{self, ParsedDocument};
+use mdvault_core::markdown_ast::{InsertPosition, MarkdownEditor, SectionMatch};
+use mdvault_core::templates::engine::render_string_with_ref_date;
+use mdvault_core::types::{FieldSchema, validate_schema};
+use mdvault_core::vars::datemath::{evaluate_date_expr_with_ref, parse_date_expr};
+use serde_json::json;
+use wasm_bindgen::prelude::*;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// Split a note into `{"frontmatter": {...} | null, "body": "..."}`.
+#[wasm_bindgen(js_name = parseNote)]
+pub fn parse_note(content: &str) -> std::result::Result<String, JsError> {
+    to_js(parse_note_json(content))
+}
+
+/// Check a note's frontmatter against a field schema, given as JSON in the
+/// same shape as the `schema` table of a Lua type definition. Returns
+/// `{"valid": bool, "errors": [...], "warnings": [...]}`.
+///
+/// Custom `validate()` functions need Lua and are not run.
+#[wasm_bindgen(js_name = validateNote)]
+pub fn validate_note(
+    schema: &str,
+    content: &str,
+) -> std::result::Result<String, JsError> {
+    to_js(validate_note_json(schema, content))
+}
+
+/// Evaluate a date expression such as `today + 7d` or `monday`, relative to
+/// `today` (`YYYY-MM-DD`) when given.
+#[wasm_bindgen(js_name = evaluateDate)]
+pub fn evaluate_date(
+    expr: &str,
+    today: Option<String>,
+) -> std::result::Result<String, JsError> {
+    to_js(evaluate_date_at(expr, today.as_deref()))
+}
+
+/// Render a template string with variables given as a JSON object of
+/// strings. Date expressions use `today` (`YYYY-MM-DD`) when given.
+#[wasm_bindgen(js_name = renderTemplate)]
+pub fn render_template(
+    template: &str,
+    vars: &str,
+    today: Option<String>,
+) -> std::result::Result<String, JsError> {
+    to_js(render_template_at(template, vars, today.as_deref()))
+}
+
+/// Insert `fragment` under the heading `section`, at its start or (with
+/// `at_end`) its end. Frontmatter is left in place.
+#[wasm_bindgen(js_name = insertIntoSection)]
+pub fn insert_into_section(
+    content: &str,
+    section: &str,
+    fragment: &str,
+    at_end: bool,
+) -> std::result::Result<String, JsError> {
+    to_js(insert_into_body(content, section, fragment, at_end))
+}
+
+fn to_js(result: Result<String>) -> std::result::Result<String, JsError> {
+    result.map_err(|e| JsError::new(&e.to_string()))
+}
+
+fn parse_note_json(content: &str) -> Result<String> {
+    let doc = frontmatter::parse(content)?;
+    Ok(json!({ "frontmatter": doc.frontmatter, "body": doc.body }).to_string())
+}
+
+fn validate_note_json(schema: &str, content: &str) -> Result<String> {
+    let schema: HashMap<String, FieldSchema> = serde_json::from_str(schema)?;
+    let doc = frontmatter::parse(content)?;
+    let fields = doc
+        .frontmatter
+        .map(|fm| fm.fields.into_iter().map(|(k, v)| (k.into(), v)).collect())
+        .unwrap_or_default();
+
+    let result = validate_schema(&schema, &fields, false);
+    let errors: Vec<String> = result.errors.iter().map(|e| e.to_string()).collect();
+    Ok(json!({
+        "valid": result.valid,
+        "errors": errors,
+        "warnings": result.warnings,
+    })
+    .to_string())
+}
+
+fn evaluate_date_at(expr: &str, today: Option<&str>) -> Result<String> {
+    let expr = parse_date_expr(expr)?;
+    Ok(evaluate_date_expr_with_ref(&expr, parse_today(today)?))
+}
+
+fn render_template_at(template: &str, vars: &str, today: Option<&str>) -> Result<String> {
+    let ctx: HashMap<String, String> = serde_json::from_str(vars)?;
+    Ok(render_string_with_ref_date(template, &ctx, parse_today(today)?)?)
+}
+
+fn insert_into_body(
+    content: &str,
+    section: &str,
+    fragment: &str,
+    at_end: bool,
+) -> Result<String> {
+    let mut doc: ParsedDocument = frontmatter::parse(content)?;
+    let position = if at_end { InsertPosition::End } else { InsertPosition::Begin };
+    let result = MarkdownEditor::insert_into_section(
+        &doc.body,
+        &SectionMatch::new(section),
+        fragment,
+        position,
+    )?;
+    // Only re-serialize when there is frontmatter, so plain notes are untouched
+    if doc.frontmatter.is_none() {
+        return Ok(result.content);
+    }
+    doc.body = result.content;
+    Ok(frontmatter::serialize(&doc))
+}
+
+fn parse_today(today: Option<&str>) -> Result<Option<NaiveDate>> {
+    Ok(today.map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d")).transpose()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    const NOTE: &str = "---\ntype: task\nstatus: blocked\n---\n# Task\n\n## Log\n";
+
+    #[test]
+    fn parses_and_validates_notes() {
+        let parsed: Value =
+            serde_json::from_str(&parse_note_json(NOTE).unwrap()).unwrap();
+        assert_eq!(parsed["frontmatter"]["status"], "blocked");
+        assert_eq!(parsed["body"], "# Task\n\n## Log\n");
+
+        let schema = r#"{
+            "status": {"type": "string", "enum": ["todo", "done"]},
+            "due": {"type": "date", "required": true}
+        }"#;
+        let result: Value =
+            serde_json::from_str(&validate_note_json(schema, NOTE).unwrap()).unwrap();
+        assert_eq!(result["valid"], false);
+        assert_eq!(result["errors"].as_array().unwrap().len(), 2);
+
+        assert!(validate_note_json("{", NOTE).is_err());
+    }
+
+    #[test]
+    fn evaluates_dates_and_renders_templates() {
+        assert_eq!(
+            evaluate_date_at("today + 7d", Some("2025-01-15")).unwrap(),
+            "2025-01-22"
+        );
+        assert!(evaluate_date_at("today", Some("15/01/2025")).is_err());
+
+        let rendered = render_template_at(
+            "# {{title}}\nDue: {{today + 1d}}",
+            r#"{"title": "Plan"}"#,
+            Some("2025-01-15"),
+        )
+        .unwrap();
+        assert_eq!(rendered, "# Plan\nDue: 2025-01-16");
+    }
+
+    #[test]
+    fn inserts_under_a_section() {
+        let updated = insert_into_body(NOTE, "Log", "- started\n", true).unwrap();
+        assert!(updated.starts_with("---\n"));
+        assert!(updated.ends_with("## Log\n- started\n"), "{updated}");
+
+        let err = insert_into_body(NOTE, "Missing", "x", false).unwrap_err();
+        assert!(err.to_string().contains("Missing"));
+    }
+}